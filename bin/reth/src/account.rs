@@ -0,0 +1,145 @@
+//! `reth account` command to manage the keystore of the auth server accounts.
+use crate::{
+    args::utils::genesis_value_parser,
+    dirs::{DataDirPath, MaybePlatformPath},
+};
+use clap::{Args, Parser, Subcommand};
+use reth_primitives::{Address, ChainSpec};
+use reth_rpc::eth::keystore::Keystore;
+use secp256k1::SecretKey;
+use std::{
+    fs,
+    io::{self, BufRead, Write},
+    path::PathBuf,
+    str::FromStr,
+    sync::Arc,
+};
+
+/// `reth account` command
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// The path to the data dir for all reth files and subdirectories.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/` or `$HOME/.local/share/reth/`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/`
+    /// - macOS: `$HOME/Library/Application Support/reth/`
+    #[arg(long, value_name = "DATA_DIR", verbatim_doc_comment, default_value_t, global = true)]
+    datadir: MaybePlatformPath<DataDirPath>,
+
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    ///
+    /// Built-in chains:
+    /// - mainnet
+    /// - goerli
+    /// - sepolia
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        verbatim_doc_comment,
+        default_value = "mainnet",
+        value_parser = genesis_value_parser,
+        global = true
+    )]
+    chain: Arc<ChainSpec>,
+
+    /// Path to the keystore directory.
+    ///
+    /// Defaults to `<DATADIR>/keystore`.
+    #[arg(long, value_name = "PATH", global = true)]
+    keystore: Option<PathBuf>,
+
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+/// `reth account` subcommands
+#[derive(Subcommand, Debug)]
+pub enum Subcommands {
+    /// List the addresses of all accounts in the keystore.
+    List,
+    /// Create a new account with a random key.
+    New {
+        #[clap(flatten)]
+        password: PasswordArgs,
+    },
+    /// Import a hex encoded secret key from a file.
+    Import {
+        /// The file that contains the hex encoded secret key.
+        #[arg(value_name = "KEY_FILE")]
+        key_file: PathBuf,
+
+        #[clap(flatten)]
+        password: PasswordArgs,
+    },
+    /// Print the hex encoded secret key of an account.
+    Export {
+        /// The address of the account.
+        address: Address,
+
+        #[clap(flatten)]
+        password: PasswordArgs,
+    },
+}
+
+/// The password of a keystore account.
+#[derive(Debug, Args)]
+pub struct PasswordArgs {
+    /// Read the password from the first line of the given file instead of stdin.
+    #[arg(long, value_name = "PATH")]
+    password_file: Option<PathBuf>,
+}
+
+impl PasswordArgs {
+    fn password(&self) -> eyre::Result<String> {
+        let line = match &self.password_file {
+            Some(path) => fs::read_to_string(path)?.lines().next().unwrap_or_default().to_string(),
+            None => {
+                eprint!("Password: ");
+                io::stderr().flush()?;
+                let mut line = String::new();
+                io::stdin().lock().read_line(&mut line)?;
+                line
+            }
+        };
+        Ok(line.trim_end_matches(['\r', '\n']).to_string())
+    }
+}
+
+impl Command {
+    /// Execute `account` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        let path = match self.keystore {
+            Some(path) => path,
+            None => self.datadir.unwrap_or_chain_default(self.chain.chain).keystore_path(),
+        };
+        let keystore = Keystore::open(path)?;
+
+        match self.command {
+            Subcommands::List => {
+                for address in keystore.accounts()? {
+                    println!("{address:?}");
+                }
+            }
+            Subcommands::New { password } => {
+                let address = keystore.new_account(&password.password()?)?;
+                println!("{address:?}");
+            }
+            Subcommands::Import { key_file, password } => {
+                let key = fs::read_to_string(key_file)?;
+                let key = SecretKey::from_str(key.trim().trim_start_matches("0x"))?;
+                let address = keystore.import(&key, &password.password()?)?;
+                println!("{address:?}");
+            }
+            Subcommands::Export { address, password } => {
+                let key = keystore.export(address, &password.password()?)?;
+                println!("{}", hex::encode(key.secret_bytes()));
+            }
+        }
+
+        Ok(())
+    }
+}
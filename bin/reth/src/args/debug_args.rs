@@ -1,6 +1,6 @@
 //! clap [Args](clap::Args) for debugging purposes
 
-use clap::Args;
+use clap::{value_parser, Args};
 use reth_primitives::{TxHash, H256};
 
 /// Parameters for debugging purposes
@@ -57,4 +57,37 @@ pub struct DebugArgs {
         conflicts_with = "hook_transaction"
     )]
     pub hook_all: bool,
+
+    /// Periodically verify a sample of storage roots against the stored trie, every given number
+    /// of seconds.
+    ///
+    /// Mismatches are logged and reported via metrics, to detect silent database corruption.
+    #[arg(
+        long = "debug.verify-state-root-interval",
+        help_heading = "Debug",
+        value_name = "SECONDS",
+        value_parser = value_parser!(u64).range(1..)
+    )]
+    pub verify_state_root_interval: Option<u64>,
+
+    /// The number of storage roots sampled per state root verification.
+    #[arg(long = "debug.verify-state-root-samples", help_heading = "Debug", default_value_t = 16)]
+    pub verify_state_root_samples: usize,
+
+    /// Also recompute the full state root from the hashed state and verify it against the header
+    /// every given number of state root verifications.
+    #[arg(
+        long = "debug.verify-state-root-full-every",
+        help_heading = "Debug",
+        value_name = "RUNS",
+        value_parser = value_parser!(u64).range(1..)
+    )]
+    pub verify_state_root_full_every: Option<u64>,
+
+    /// Report the consensus engine or the network as stalled if they make no progress for the
+    /// given number of seconds while busy, or while messages are queued for them.
+    ///
+    /// Stalls are logged with diagnostics and reported via metrics.
+    #[arg(long = "debug.stall-threshold", help_heading = "Debug", value_name = "SECONDS")]
+    pub stall_threshold: Option<u64>,
 }
@@ -14,7 +14,8 @@ pub struct DevArgs {
     /// or automatically mined blocks.
     /// Disables network discovery and enables local http server.
     /// Prefunds 20 accounts derived by mnemonic "test test test test test test test test test test
-    /// test junk" with 10 000 ETH each.
+    /// test junk" with 10 000 ETH each, which can sign via `eth_sign`, `eth_signTypedData_v4`,
+    /// `eth_signTransaction` and `eth_sendTransaction`.
     #[arg(long = "dev", alias = "auto-mine", help_heading = "Dev testnet", verbatim_doc_comment)]
     pub dev: bool,
 
@@ -1,4 +1,5 @@
 use clap::Args;
+use reth_rpc::eth::gas_oracle::GasPriceOracleStrategy;
 
 /// Parameters to configure Gas Price Oracle
 #[derive(Debug, Args, PartialEq, Eq, Default)]
@@ -19,6 +20,19 @@ pub struct GasPriceOracleArgs {
     /// The percentile of gas prices to use for the estimate
     #[arg(long = "gpo.percentile", default_value = "60")]
     pub percentile: Option<u32>,
+
+    /// Minimum transaction priority fee to be recommended by gpo
+    #[arg(long = "gpo.minprice")]
+    pub min_price: Option<u64>,
+
+    /// Priority fee recommended by gpo if there are no recent transactions to base the estimate
+    /// on, or always with the `fixed` strategy
+    #[arg(long = "gpo.default")]
+    pub default: Option<u64>,
+
+    /// The strategy gpo uses to recommend a priority fee: `percentile` or `fixed`
+    #[arg(long = "gpo.strategy", default_value = "percentile")]
+    pub strategy: GasPriceOracleStrategy,
 }
 
 #[cfg(test)]
@@ -43,7 +57,27 @@ mod tests {
                 ignore_price: Some(2),
                 max_price: Some(500000000000),
                 percentile: Some(60),
+                min_price: None,
+                default: None,
+                strategy: GasPriceOracleStrategy::Percentile,
             }
         );
     }
+
+    #[test]
+    fn test_parse_gpo_strategy_args() {
+        let args = CommandParser::<GasPriceOracleArgs>::parse_from([
+            "reth",
+            "--gpo.strategy",
+            "fixed",
+            "--gpo.default",
+            "1000000000",
+            "--gpo.minprice",
+            "1",
+        ])
+        .args;
+        assert_eq!(args.strategy, GasPriceOracleStrategy::Fixed);
+        assert_eq!(args.default, Some(1_000_000_000));
+        assert_eq!(args.min_price, Some(1));
+    }
 }
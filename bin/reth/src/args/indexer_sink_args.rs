@@ -0,0 +1,80 @@
+//! clap [Args](clap::Args) for publishing the canonical chain to external indexers
+
+use clap::Args;
+use reth_primitives::BlockNumber;
+
+/// Parameters for publishing the canonical chain to a message broker
+#[derive(Debug, Clone, Args, PartialEq)]
+#[command(next_help_heading = "Indexer sink")]
+pub struct IndexerSinkArgs {
+    /// Publish the canonical blocks, receipts and state diffs to the given comma-separated list
+    /// of Kafka brokers.
+    ///
+    /// Requires reth to be built with the `kafka` feature.
+    #[arg(long = "sink.kafka", value_name = "BROKERS", conflicts_with = "nats")]
+    pub kafka: Option<String>,
+
+    /// Publish the canonical blocks, receipts and state diffs to the NATS JetStream server at the
+    /// given URL.
+    ///
+    /// Requires reth to be built with the `nats` feature.
+    #[arg(long = "sink.nats", value_name = "URL")]
+    pub nats: Option<String>,
+
+    /// The prefix of the topics the messages are published to.
+    #[arg(long = "sink.topic-prefix", value_name = "PREFIX", default_value = "reth")]
+    pub topic_prefix: String,
+
+    /// The first block to publish if the sink has not published any blocks yet.
+    ///
+    /// Defaults to the first block after the current tip.
+    #[arg(long = "sink.from-block", value_name = "BLOCK_NUMBER")]
+    pub from_block: Option<BlockNumber>,
+}
+
+impl IndexerSinkArgs {
+    /// Returns `true` if a broker is configured.
+    pub fn is_enabled(&self) -> bool {
+        self.kafka.is_some() || self.nats.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[clap(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn test_parse_indexer_sink_args() {
+        let args = CommandParser::<IndexerSinkArgs>::parse_from(["reth"]).args;
+        assert!(!args.is_enabled());
+        assert_eq!(args.topic_prefix, "reth");
+
+        let args = CommandParser::<IndexerSinkArgs>::parse_from([
+            "reth",
+            "--sink.kafka",
+            "localhost:9092",
+            "--sink.topic-prefix",
+            "mainnet",
+        ])
+        .args;
+        assert_eq!(args.kafka.as_deref(), Some("localhost:9092"));
+        assert_eq!(args.topic_prefix, "mainnet");
+
+        assert!(CommandParser::<IndexerSinkArgs>::try_parse_from([
+            "reth",
+            "--sink.kafka",
+            "localhost:9092",
+            "--sink.nats",
+            "nats://localhost:4222"
+        ])
+        .is_err());
+    }
+}
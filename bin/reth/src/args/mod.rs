@@ -43,4 +43,20 @@ pub use dev_args::DevArgs;
 mod pruning_args;
 pub use pruning_args::PruningArgs;
 
+/// OtlpMetricsArgs for pushing metrics via OpenTelemetry
+mod otlp_args;
+pub use otlp_args::OtlpMetricsArgs;
+
+/// SyncArgs for configuring the initial sync
+mod sync_args;
+pub use sync_args::SyncArgs;
+
+/// IndexerSinkArgs for publishing the canonical chain to external indexers
+mod indexer_sink_args;
+pub use indexer_sink_args::IndexerSinkArgs;
+
+/// RuntimeArgs for running subsystems on dedicated tokio runtimes
+mod runtime_args;
+pub use runtime_args::RuntimeArgs;
+
 pub mod utils;
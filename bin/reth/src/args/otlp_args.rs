@@ -0,0 +1,73 @@
+//! clap [Args](clap::Args) for pushing metrics via OpenTelemetry
+
+use crate::args::utils::parse_duration_from_secs;
+use clap::Args;
+use std::time::Duration;
+
+/// Parameters for pushing metrics to an OpenTelemetry collector
+#[derive(Debug, Clone, Args, PartialEq)]
+#[command(next_help_heading = "Metrics")]
+pub struct OtlpMetricsArgs {
+    /// Push metrics to the given OpenTelemetry collector via OTLP/gRPC.
+    ///
+    /// The metrics are pushed in addition to being served at the Prometheus endpoint, which must
+    /// be enabled with `--metrics`.
+    #[arg(long = "metrics.otlp", value_name = "URL", requires = "metrics")]
+    pub endpoint: Option<String>,
+
+    /// The interval in seconds at which metrics are pushed to the collector.
+    #[arg(
+        long = "metrics.otlp.interval",
+        value_name = "SECONDS",
+        value_parser = parse_duration_from_secs,
+        default_value = "10"
+    )]
+    pub interval: Duration,
+}
+
+impl Default for OtlpMetricsArgs {
+    fn default() -> Self {
+        Self { endpoint: None, interval: Duration::from_secs(10) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[clap(long)]
+        metrics: Option<String>,
+        #[clap(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn test_parse_otlp_args() {
+        let args = CommandParser::<OtlpMetricsArgs>::parse_from(["reth"]).args;
+        assert_eq!(args, OtlpMetricsArgs::default());
+
+        let args = CommandParser::<OtlpMetricsArgs>::parse_from([
+            "reth",
+            "--metrics",
+            "127.0.0.1:9001",
+            "--metrics.otlp",
+            "http://localhost:4317",
+            "--metrics.otlp.interval",
+            "5",
+        ])
+        .args;
+        assert_eq!(args.endpoint.as_deref(), Some("http://localhost:4317"));
+        assert_eq!(args.interval, Duration::from_secs(5));
+
+        assert!(CommandParser::<OtlpMetricsArgs>::try_parse_from([
+            "reth",
+            "--metrics.otlp",
+            "http://localhost:4317"
+        ])
+        .is_err());
+    }
+}
@@ -6,8 +6,12 @@ use clap::{
     builder::{RangedU64ValueParser, TypedValueParser},
     Arg, Args, Command,
 };
-use reth_primitives::constants::MAXIMUM_EXTRA_DATA_SIZE;
-use std::{borrow::Cow, ffi::OsStr, time::Duration};
+use eyre::eyre;
+use reth_payload_relay::{
+    BeaconChainParams, BuilderSigner, RelayConfig, RelayEncoding, RelaySubmitter,
+};
+use reth_primitives::{constants::MAXIMUM_EXTRA_DATA_SIZE, fs, hex, ChainSpec};
+use std::{borrow::Cow, ffi::OsStr, path::PathBuf, time::Duration};
 
 /// Parameters for configuring the Payload Builder
 #[derive(Debug, Args, PartialEq, Default)]
@@ -36,6 +40,54 @@ pub struct PayloadBuilderArgs {
     /// Maximum number of tasks to spawn for building a payload.
     #[arg(long = "builder.max-tasks", help_heading = "Builder", default_value = "3", value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
     pub max_payload_tasks: usize,
+
+    /// Urls of builder relays locally built payloads are submitted to.
+    ///
+    /// Requires `--builder.relay-secret-key`.
+    #[arg(
+        long = "builder.relays",
+        help_heading = "Builder",
+        value_delimiter = ',',
+        value_name = "URL",
+        requires = "relay_secret_key"
+    )]
+    pub relays: Vec<String>,
+
+    /// Path to a file containing the hex encoded BLS secret key relay submissions are signed with.
+    #[arg(long = "builder.relay-secret-key", help_heading = "Builder", value_name = "PATH")]
+    pub relay_secret_key: Option<PathBuf>,
+
+    /// Encoding of relay submissions, either `json` or `ssz`.
+    #[arg(long = "builder.relay-encoding", help_heading = "Builder", default_value_t = RelayEncoding::Json)]
+    pub relay_encoding: RelayEncoding,
+
+    /// Submit payloads to relays even if they don't pay the fee recipient registered by the
+    /// proposer.
+    #[arg(long = "builder.relay-skip-fee-recipient-check", help_heading = "Builder")]
+    pub relay_skip_fee_recipient_check: bool,
+}
+
+impl PayloadBuilderArgs {
+    /// Returns the submitter of payloads to the configured relays, if any.
+    pub fn relay_submitter(&self, chain: &ChainSpec) -> eyre::Result<Option<RelaySubmitter>> {
+        if self.relays.is_empty() {
+            return Ok(None)
+        }
+
+        let params = BeaconChainParams::from_chain(chain.chain)
+            .ok_or_else(|| eyre!("relay submissions are not supported on chain {}", chain.chain))?;
+        let path = self
+            .relay_secret_key
+            .as_ref()
+            .ok_or_else(|| eyre!("relay submissions require --builder.relay-secret-key"))?;
+        let secret_key = hex::decode(fs::read_to_string(path)?.trim().trim_start_matches("0x"))?;
+        let signer = BuilderSigner::new(&secret_key, params.genesis_fork_version)?;
+
+        let config = RelayConfig::new(self.relays.clone(), params)
+            .with_encoding(self.relay_encoding)
+            .with_fee_recipient_validation(!self.relay_skip_fee_recipient_check);
+        Ok(Some(RelaySubmitter::new(config, signer)?))
+    }
 }
 
 impl PayloadBuilderConfig for PayloadBuilderArgs {
@@ -117,6 +169,31 @@ mod tests {
         .is_err());
     }
 
+    #[test]
+    fn test_args_with_relays() {
+        let args = CommandParser::<PayloadBuilderArgs>::parse_from([
+            "reth",
+            "--builder.relays",
+            "https://relay-a.example,https://relay-b.example",
+            "--builder.relay-secret-key",
+            "bls.key",
+            "--builder.relay-encoding",
+            "ssz",
+        ])
+        .args;
+        assert_eq!(args.relays.len(), 2);
+        assert_eq!(args.relay_encoding, RelayEncoding::Ssz);
+        assert!(!args.relay_skip_fee_recipient_check);
+
+        // relays require a signing key
+        assert!(CommandParser::<PayloadBuilderArgs>::try_parse_from([
+            "reth",
+            "--builder.relays",
+            "https://relay-a.example",
+        ])
+        .is_err());
+    }
+
     #[test]
     fn test_default_extradata() {
         let extradata = default_extradata();
@@ -6,28 +6,41 @@ use crate::{
 };
 use clap::{
     builder::{PossibleValue, RangedU64ValueParser, TypedValueParser},
-    Arg, Args, Command,
+    Arg, Args, Command, ValueEnum,
 };
 use futures::TryFutureExt;
+use reth_db::database::Database;
 use reth_network_api::{NetworkInfo, Peers};
+use reth_primitives::Chain;
 use reth_provider::{
     BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader, EvmEnvProvider,
-    HeaderProvider, StateProviderFactory,
+    HeaderProvider, PreimageReader, StageCheckpointReader, StateProviderFactory,
 };
 use reth_rpc::{
+    block_trace_cache::{
+        BlockTraceCacheConfig, DEFAULT_TRACE_CACHE_MAX_BLOCKS, DEFAULT_TRACE_CACHE_MAX_SIZE_MB,
+    },
     eth::{
         cache::{
-            DEFAULT_BLOCK_CACHE_MAX_LEN, DEFAULT_ENV_CACHE_MAX_LEN, DEFAULT_RECEIPT_CACHE_MAX_LEN,
+            EthStateCacheConfig, DEFAULT_BLOCK_CACHE_MAX_LEN, DEFAULT_ENV_CACHE_MAX_LEN,
+            DEFAULT_LATEST_BLOCK_CACHE_MAX_LEN, DEFAULT_RECEIPT_CACHE_MAX_LEN,
         },
         gas_oracle::GasPriceOracleConfig,
+        keystore::{Keystore, KeystoreError},
         RPC_DEFAULT_GAS_CAP,
     },
-    JwtError, JwtSecret,
+    JwtError, JwtSecret, PersonalApi, ProviderApi,
+};
+use reth_rpc_api::{
+    AdminRpcNamespacesApiServer, EthSigningApiServer, PersonalApiServer, ProviderApiServer,
 };
 use reth_rpc_builder::{
     auth::{AuthServerConfig, AuthServerHandle},
-    constants,
+    constants::{self, DEFAULT_MAX_BLOCKS_PER_FILTER, DEFAULT_MAX_LOGS_PER_RESPONSE},
     error::RpcError,
+    namespace_gate::NamespaceGate,
+    rate_limit::RateLimitConfig,
+    response_cache::{response_cache_head_task, ResponseCache, DEFAULT_RESPONSE_CACHE_MAX_LEN},
     EthConfig, IpcServerBuilder, RethRpcModule, RpcModuleBuilder, RpcModuleConfig,
     RpcModuleSelection, RpcServerConfig, RpcServerHandle, ServerBuilder, TransportRpcModuleConfig,
 };
@@ -39,7 +52,8 @@ use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr},
     path::PathBuf,
 };
-use tracing::{debug, info};
+use tokio::runtime::Handle;
+use tracing::{debug, info, warn};
 
 /// Default max number of subscriptions per connection.
 pub(crate) const RPC_DEFAULT_MAX_SUBS_PER_CONN: u32 = 1024;
@@ -54,6 +68,114 @@ pub(crate) const RPC_DEFAULT_MAX_CONNECTIONS: u32 = 100;
 /// Default number of incoming connections.
 pub(crate) const RPC_DEFAULT_MAX_TRACING_REQUESTS: u32 = 25;
 
+/// Methods served over http and ws by the [RpcProfile::Public] profile.
+pub(crate) const RPC_PUBLIC_ALLOWED_METHODS: &[&str] = &[
+    "eth_blockNumber",
+    "eth_call",
+    "eth_chainId",
+    "eth_estimateGas",
+    "eth_feeHistory",
+    "eth_gasPrice",
+    "eth_getBalance",
+    "eth_getBlockByHash",
+    "eth_getBlockByNumber",
+    "eth_getBlockTransactionCountByHash",
+    "eth_getBlockTransactionCountByNumber",
+    "eth_getCode",
+    "eth_getFilterChanges",
+    "eth_getFilterLogs",
+    "eth_getLogs",
+    "eth_getLogsPage",
+    "eth_getStorageAt",
+    "eth_getTransactionByBlockHashAndIndex",
+    "eth_getTransactionByBlockNumberAndIndex",
+    "eth_getTransactionByHash",
+    "eth_getTransactionCount",
+    "eth_getTransactionReceipt",
+    "eth_getUncleByBlockHashAndIndex",
+    "eth_getUncleByBlockNumberAndIndex",
+    "eth_getUncleCountByBlockHash",
+    "eth_getUncleCountByBlockNumber",
+    "eth_maxPriorityFeePerGas",
+    "eth_newBlockFilter",
+    "eth_newFilter",
+    "eth_newPendingTransactionFilter",
+    "eth_protocolVersion",
+    "eth_sendRawTransaction",
+    "eth_subscribe",
+    "eth_syncing",
+    "eth_uninstallFilter",
+    "eth_unsubscribe",
+    "net_listening",
+    "net_version",
+    "web3_clientVersion",
+    "web3_sha3",
+];
+/// Gas cap of the [RpcProfile::Public] profile.
+pub(crate) const RPC_PUBLIC_GAS_CAP: u64 = 30_000_000;
+/// Max number of concurrent tracing requests of the [RpcProfile::Public] profile.
+pub(crate) const RPC_PUBLIC_MAX_TRACING_REQUESTS: u32 = 2;
+/// Max number of logs in a single response of the [RpcProfile::Public] profile.
+pub(crate) const RPC_PUBLIC_MAX_LOGS_PER_RESPONSE: usize = 10_000;
+/// Max number of blocks a single `eth_getLogs` query may span in the [RpcProfile::Public] profile.
+pub(crate) const RPC_PUBLIC_MAX_BLOCKS_PER_FILTER: u64 = 10_000;
+/// Max number of incoming connections of the [RpcProfile::Public] profile.
+pub(crate) const RPC_PUBLIC_MAX_CONNECTIONS: u32 = 50;
+/// Max number of subscriptions per connection of the [RpcProfile::Public] profile.
+pub(crate) const RPC_PUBLIC_MAX_SUBS_PER_CONN: u32 = 64;
+/// Max response size in MB of the [RpcProfile::Public] profile.
+pub(crate) const RPC_PUBLIC_MAX_RESPONSE_SIZE_MB: u32 = 10;
+/// Requests per second allowed for a single client IP by the [RpcProfile::Public] profile.
+pub(crate) const RPC_PUBLIC_RATE_LIMIT: u32 = 50;
+
+/// A preset of defaults for the RPC servers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum RpcProfile {
+    /// The regular defaults.
+    #[default]
+    Default,
+    /// Conservative limits for RPC servers that are exposed to the public.
+    Public,
+}
+
+impl RpcProfile {
+    /// Applies the profile to all options of the given args that are unchanged from their
+    /// defaults.
+    pub fn apply(self, args: &mut RpcServerArgs) {
+        match self {
+            RpcProfile::Default => {}
+            RpcProfile::Public => {
+                args.rpc_allowed_methods.get_or_insert_with(|| {
+                    RPC_PUBLIC_ALLOWED_METHODS.iter().map(|method| method.to_string()).collect()
+                });
+                if args.rpc_gas_cap == u64::from(RPC_DEFAULT_GAS_CAP) {
+                    args.rpc_gas_cap = RPC_PUBLIC_GAS_CAP;
+                }
+                if args.rpc_max_tracing_requests == RPC_DEFAULT_MAX_TRACING_REQUESTS {
+                    args.rpc_max_tracing_requests = RPC_PUBLIC_MAX_TRACING_REQUESTS;
+                }
+                if args.rpc_max_logs_per_response == DEFAULT_MAX_LOGS_PER_RESPONSE {
+                    args.rpc_max_logs_per_response = RPC_PUBLIC_MAX_LOGS_PER_RESPONSE;
+                }
+                if args.rpc_max_blocks_per_filter == DEFAULT_MAX_BLOCKS_PER_FILTER {
+                    args.rpc_max_blocks_per_filter = RPC_PUBLIC_MAX_BLOCKS_PER_FILTER;
+                }
+                if args.rpc_max_connections == RPC_DEFAULT_MAX_CONNECTIONS {
+                    args.rpc_max_connections = RPC_PUBLIC_MAX_CONNECTIONS;
+                }
+                if args.rpc_max_subscriptions_per_connection == RPC_DEFAULT_MAX_SUBS_PER_CONN {
+                    args.rpc_max_subscriptions_per_connection = RPC_PUBLIC_MAX_SUBS_PER_CONN;
+                }
+                if args.rpc_max_response_size == RPC_DEFAULT_MAX_RESPONSE_SIZE_MB {
+                    args.rpc_max_response_size = RPC_PUBLIC_MAX_RESPONSE_SIZE_MB;
+                }
+                args.rpc_rate_limit.get_or_insert(RPC_PUBLIC_RATE_LIMIT);
+                args.rpc_response_cache_len.get_or_insert(DEFAULT_RESPONSE_CACHE_MAX_LEN);
+            }
+        }
+    }
+}
+
 /// Parameters for configuring the rpc more granularity via CLI
 #[derive(Debug, Args)]
 #[command(next_help_heading = "RPC")]
@@ -118,6 +240,24 @@ pub struct RpcServerArgs {
     #[arg(long = "authrpc.jwtsecret", value_name = "PATH", global = true, required = false)]
     pub auth_jwtsecret: Option<PathBuf>,
 
+    /// Enable the `personal` namespace and the keystore backed `eth_accounts`, `eth_sign` and
+    /// `eth_signTransaction` methods on the auth server.
+    #[arg(long = "authrpc.accounts")]
+    pub auth_accounts: bool,
+
+    /// Path to the keystore directory of the auth server accounts.
+    ///
+    /// Defaults to `<DATADIR>/keystore`.
+    #[arg(long = "authrpc.keystore", value_name = "PATH")]
+    pub auth_keystore: Option<PathBuf>,
+
+    /// Enable the `provider` namespace on the RPC servers, which serves raw read access to all
+    /// database tables for stateless RPC replicas.
+    ///
+    /// This exposes the entire database and must only be used on trusted networks.
+    #[arg(long = "rpc.provider")]
+    pub rpc_provider: bool,
+
     /// Set the maximum RPC request payload size for both HTTP and WS in megabytes.
     #[arg(long, default_value_t = RPC_DEFAULT_MAX_REQUEST_SIZE_MB)]
     pub rpc_max_request_size: u32,
@@ -138,6 +278,19 @@ pub struct RpcServerArgs {
     #[arg(long, value_name = "COUNT", default_value_t = RPC_DEFAULT_MAX_TRACING_REQUESTS)]
     pub rpc_max_tracing_requests: u32,
 
+    /// Maximum number of logs that can be returned in a single `eth_getLogs` response.
+    ///
+    /// Queries that match more logs are rejected with the block range that fits into a response,
+    /// `0` disables the limit.
+    #[arg(long, value_name = "COUNT", default_value_t = DEFAULT_MAX_LOGS_PER_RESPONSE)]
+    pub rpc_max_logs_per_response: usize,
+
+    /// Maximum number of blocks that a single `eth_getLogs` query may span.
+    ///
+    /// Queries over larger ranges are rejected with the allowed range, `0` disables the limit.
+    #[arg(long, value_name = "COUNT", default_value_t = DEFAULT_MAX_BLOCKS_PER_FILTER)]
+    pub rpc_max_blocks_per_filter: u64,
+
     /// Maximum gas limit for `eth_call` and call tracing RPC methods.
     #[arg(
         long,
@@ -163,17 +316,118 @@ pub struct RpcServerArgs {
     /// Maximum number of env cache entries.
     #[arg(long, default_value_t = DEFAULT_ENV_CACHE_MAX_LEN)]
     pub env_cache_len: u32,
+
+    /// Number of latest canonical blocks, with their receipts, that are always kept in memory.
+    ///
+    /// Reads of older blocks don't evict these blocks, `0` disables this cache.
+    #[arg(long, default_value_t = DEFAULT_LATEST_BLOCK_CACHE_MAX_LEN)]
+    pub latest_block_cache_len: u32,
+
+    /// HTTP endpoint of an archive node that serves the blocks and receipts that are not in the
+    /// database, for example because the receipts were pruned.
+    ///
+    /// The fetched data is verified against the local headers.
+    #[arg(long = "rpc.archive-url", value_name = "URL")]
+    pub rpc_archive_url: Option<String>,
+
+    /// Maximum number of cached `debug_traceBlock*` results, `0` disables the cache.
+    #[arg(long, default_value_t = DEFAULT_TRACE_CACHE_MAX_BLOCKS)]
+    pub trace_cache_len: u32,
+
+    /// Maximum size of the cached `debug_traceBlock*` results in MB.
+    #[arg(long, value_name = "MB", default_value_t = DEFAULT_TRACE_CACHE_MAX_SIZE_MB)]
+    pub trace_cache_size: usize,
+
+    /// Preset of defaults for the RPC servers.
+    ///
+    /// The `public` profile only serves an allowlist of read-only `eth`, `net` and `web3` methods
+    /// over http and ws, lowers the gas cap, log query, tracing, connection, subscription and
+    /// response size limits, and enables per-IP rate limiting and the response cache. Options that
+    /// are changed from their defaults take precedence over the profile.
+    #[arg(long = "rpc.profile", value_enum, default_value_t = RpcProfile::Default)]
+    pub rpc_profile: RpcProfile,
+
+    /// Comma separated list of methods that are served over http and ws.
+    ///
+    /// Entries ending in `*` match all methods with the given prefix, e.g. `eth_*`.
+    #[arg(long = "rpc.allowed-methods", value_name = "METHODS", value_delimiter = ',')]
+    pub rpc_allowed_methods: Option<Vec<String>>,
+
+    /// Maximum number of requests per second a single client IP can send to the http and ws
+    /// servers.
+    ///
+    /// Clients are identified by the `X-Forwarded-For` or `X-Real-IP` header set by a reverse
+    /// proxy, requests without these headers share a single limit.
+    #[arg(long = "rpc.ratelimit", value_name = "REQUESTS_PER_SECOND")]
+    pub rpc_rate_limit: Option<u32>,
+
+    /// Maximum number of requests a single client IP can send at once. Defaults to twice the rate
+    /// limit.
+    #[arg(long = "rpc.ratelimit-burst", value_name = "COUNT")]
+    pub rpc_rate_limit_burst: Option<u32>,
+
+    /// Number of responses of idempotent read methods, like blocks and receipts, that are cached
+    /// by the http server.
+    ///
+    /// Only calls with an explicit block number or hash are cached, not block tags like `latest`.
+    /// Responses that depend on the head of the chain are only served until the next block, blocks
+    /// at or below the finalized block are cached independently of the head. Disabled by default.
+    #[arg(long = "rpc.response-cache-len", value_name = "COUNT")]
+    pub rpc_response_cache_len: Option<u32>,
 }
 
 impl RpcServerArgs {
+    /// Applies the configured [RpcProfile] to these args.
+    pub fn apply_profile(&mut self) {
+        let profile = self.rpc_profile;
+        profile.apply(self)
+    }
+
+    /// Returns true if the given method is served over http and ws.
+    pub fn is_method_allowed(&self, method: &str) -> bool {
+        let Some(allowed) = &self.rpc_allowed_methods else { return true };
+        allowed.iter().map(|entry| entry.trim()).any(|entry| match entry.strip_suffix('*') {
+            Some(prefix) => method.starts_with(prefix),
+            None => method == entry,
+        })
+    }
+
+    /// Returns the per-IP rate limit of the http and ws servers, if configured.
+    pub fn rate_limit_config(&self) -> Option<RateLimitConfig> {
+        self.rpc_rate_limit.map(|requests_per_second| {
+            let config = RateLimitConfig::new(requests_per_second);
+            match self.rpc_rate_limit_burst {
+                Some(burst) => config.with_burst(burst),
+                None => config,
+            }
+        })
+    }
+
+    /// Opens the keystore of the auth server accounts if they are enabled.
+    pub fn keystore(
+        &self,
+        default_keystore_path: PathBuf,
+    ) -> Result<Option<Keystore>, KeystoreError> {
+        if !self.auth_accounts {
+            return Ok(None)
+        }
+        let path = self.auth_keystore.clone().unwrap_or(default_keystore_path);
+        debug!(target: "reth::cli", ?path, "Opening keystore");
+        Keystore::open(path).map(Some)
+    }
+
     /// Configures and launches _all_ servers.
     ///
     /// Returns the handles for the launched regular RPC server(s) (if any) and the server handle
     /// for the auth server that handles the `engine_` API that's accessed by the consensus
     /// layer.
+    ///
+    /// If `rpc_runtime` is set, the regular RPC server(s) run on that runtime, the auth server
+    /// always runs on the current runtime.
     #[allow(clippy::too_many_arguments)]
-    pub async fn start_servers<Provider, Pool, Network, Tasks, Events, Engine, Conf>(
+    pub async fn start_servers<DB, Provider, Pool, Network, Tasks, Events, Engine, Conf>(
         &self,
+        db: DB,
         provider: Provider,
         pool: Pool,
         network: Network,
@@ -181,15 +435,20 @@ impl RpcServerArgs {
         events: Events,
         engine_api: Engine,
         jwt_secret: JwtSecret,
+        keystore: Option<Keystore>,
+        rpc_runtime: Option<Handle>,
         conf: &mut Conf,
     ) -> eyre::Result<(RpcServerHandle, AuthServerHandle)>
     where
+        DB: Database + 'static,
         Provider: BlockReaderIdExt
             + HeaderProvider
             + StateProviderFactory
             + EvmEnvProvider
             + ChainSpecProvider
             + ChangeSetReader
+            + StageCheckpointReader
+            + PreimageReader
             + Clone
             + Unpin
             + 'static,
@@ -201,11 +460,30 @@ impl RpcServerArgs {
         Conf: RethNodeCommandConfig,
     {
         let auth_config = self.auth_server_config(jwt_secret)?;
+        let chain = provider.chain_spec().chain;
+        let chain_id = chain.id();
+        let personal = keystore
+            .map(|keystore| PersonalApi::new(keystore, chain_id, Box::new(executor.clone())));
+        let provider_api =
+            self.rpc_provider.then(|| ProviderApi::new(db, Box::new(executor.clone())));
 
         let module_config = self.transport_rpc_module_config();
         debug!(target: "reth::cli", http=?module_config.http(), ws=?module_config.ws(), "Using RPC module config");
 
-        let (mut rpc_modules, auth_module, mut registry) = RpcModuleBuilder::default()
+        let response_cache = self.rpc_response_cache_len.map(|max_len| {
+            let cache = ResponseCache::new(max_len);
+            executor.spawn_critical(
+                "rpc response cache head task",
+                Box::pin(response_cache_head_task(
+                    cache.clone(),
+                    provider.clone(),
+                    events.canonical_state_stream(),
+                )),
+            );
+            cache
+        });
+
+        let (mut rpc_modules, mut auth_module, mut registry) = RpcModuleBuilder::default()
             .with_provider(provider)
             .with_pool(pool)
             .with_network(network)
@@ -213,10 +491,41 @@ impl RpcServerArgs {
             .with_executor(executor)
             .build_with_auth_server(module_config, engine_api);
 
+        // the keys of the prefunded dev accounts are public, so they can sign on the dev chain
+        if chain == Chain::dev() {
+            registry.eth_api().with_dev_accounts();
+        }
+
         // apply configured customization
         conf.extend_rpc_modules(self, &mut registry, &mut rpc_modules)?;
 
-        let server_config = self.rpc_server_config();
+        if let Some(provider_api) = provider_api {
+            warn!(target: "reth::cli", "Serving raw database tables over the provider namespace");
+            rpc_modules.merge_configured(provider_api.into_rpc())?;
+        }
+
+        if self.rpc_allowed_methods.is_some() {
+            rpc_modules.retain_http_ws_methods(|method| self.is_method_allowed(method));
+        }
+
+        // namespaces of the http and ws servers can be toggled via the authenticated admin methods
+        let namespace_gate = NamespaceGate::default();
+        auth_module.module_mut().merge(namespace_gate.clone().into_rpc())?;
+
+        // keystore accounts are only served on the auth server
+        if let Some(personal) = personal {
+            info!(target: "reth::cli", path=?personal.keystore().dir(), "Serving keystore accounts on the auth server");
+            auth_module.module_mut().merge(PersonalApiServer::into_rpc(personal.clone()))?;
+            auth_module.module_mut().merge(EthSigningApiServer::into_rpc(personal))?;
+        }
+
+        let mut server_config = self
+            .rpc_server_config()
+            .with_namespace_gate(Some(namespace_gate))
+            .with_response_cache(response_cache);
+        if let Some(rpc_runtime) = rpc_runtime {
+            server_config = server_config.with_tokio_runtime(rpc_runtime);
+        }
         let launch_rpc = rpc_modules.start_server(server_config).map_ok(|handle| {
             if let Some(url) = handle.ipc_endpoint() {
                 info!(target: "reth::cli", url=%url, "RPC IPC server started");
@@ -256,6 +565,8 @@ impl RpcServerArgs {
             + EvmEnvProvider
             + ChainSpecProvider
             + ChangeSetReader
+            + StageCheckpointReader
+            + PreimageReader
             + Clone
             + Unpin
             + 'static,
@@ -323,8 +634,26 @@ impl RethRpcConfig for RpcServerArgs {
     fn eth_config(&self) -> EthConfig {
         EthConfig::default()
             .max_tracing_requests(self.rpc_max_tracing_requests)
+            .max_logs_per_response(self.rpc_max_logs_per_response)
+            .max_blocks_per_filter(self.rpc_max_blocks_per_filter)
             .rpc_gas_cap(self.rpc_gas_cap)
             .gpo_config(self.gas_price_oracle_config())
+            .state_cache(self.state_cache_config())
+            .trace_cache(BlockTraceCacheConfig {
+                max_blocks: self.trace_cache_len,
+                max_size_mb: self.trace_cache_size,
+            })
+    }
+
+    fn state_cache_config(&self) -> EthStateCacheConfig {
+        EthStateCacheConfig {
+            max_blocks: self.block_cache_len,
+            max_receipts: self.receipt_cache_len,
+            max_envs: self.env_cache_len,
+            max_latest_blocks: self.latest_block_cache_len,
+            archive_url: self.rpc_archive_url.clone(),
+            ..Default::default()
+        }
     }
 
     fn rpc_max_request_size_bytes(&self) -> u32 {
@@ -342,6 +671,9 @@ impl RethRpcConfig for RpcServerArgs {
             self.gas_price_oracle.max_price,
             self.gas_price_oracle.percentile,
         )
+        .with_strategy(self.gas_price_oracle.strategy)
+        .with_default(self.gas_price_oracle.default)
+        .with_min_price(self.gas_price_oracle.min_price)
     }
 
     fn transport_rpc_module_config(&self) -> TransportRpcModuleConfig {
@@ -388,7 +720,7 @@ impl RethRpcConfig for RpcServerArgs {
     }
 
     fn rpc_server_config(&self) -> RpcServerConfig {
-        let mut config = RpcServerConfig::default();
+        let mut config = RpcServerConfig::default().with_rate_limit(self.rate_limit_config());
 
         if self.http {
             let socket_address = SocketAddr::new(self.http_addr, self.http_port);
@@ -497,6 +829,40 @@ mod tests {
         assert!(args.is_err());
     }
 
+    #[test]
+    fn test_rpc_public_profile() {
+        let mut args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--rpc.profile",
+            "public",
+            "--rpc.gascap",
+            "1000",
+        ])
+        .args;
+        args.apply_profile();
+
+        // explicitly set options take precedence
+        assert_eq!(args.rpc_gas_cap, 1000);
+        assert_eq!(args.rpc_max_tracing_requests, RPC_PUBLIC_MAX_TRACING_REQUESTS);
+        assert_eq!(args.eth_config().max_blocks_per_filter, RPC_PUBLIC_MAX_BLOCKS_PER_FILTER);
+        assert_eq!(args.rate_limit_config(), Some(RateLimitConfig::new(RPC_PUBLIC_RATE_LIMIT)));
+        assert_eq!(args.rpc_response_cache_len, Some(DEFAULT_RESPONSE_CACHE_MAX_LEN));
+        assert!(args.is_method_allowed("eth_getLogs"));
+        assert!(!args.is_method_allowed("debug_traceTransaction"));
+        assert!(!args.is_method_allowed("eth_sendTransaction"));
+
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--rpc.allowed-methods",
+            "eth_*,net_version",
+        ])
+        .args;
+        assert!(args.is_method_allowed("eth_sendTransaction"));
+        assert!(args.is_method_allowed("net_version"));
+        assert!(!args.is_method_allowed("net_peerCount"));
+        assert_eq!(args.rate_limit_config(), None);
+    }
+
     #[test]
     fn test_rpc_server_args_parser() {
         let args =
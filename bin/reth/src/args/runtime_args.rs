@@ -0,0 +1,128 @@
+//! clap [Args](clap::Args) for the tokio runtimes of the node.
+
+use crate::runner::SubsystemRuntime;
+use clap::{builder::RangedU64ValueParser, Args};
+
+/// Parameters for running subsystems of the node on dedicated tokio runtimes.
+///
+/// By default all subsystems share a single runtime, so a subsystem under heavy load, e.g. the RPC
+/// servers serving tracing requests, can delay the others, like the consensus engine. Subsystems
+/// with a dedicated runtime only compete with the others for the CPU cores they are pinned to.
+#[derive(Debug, Clone, Args, PartialEq, Eq, Default)]
+#[command(next_help_heading = "Runtime")]
+pub struct RuntimeArgs {
+    /// Number of worker threads of a dedicated runtime for the network.
+    ///
+    /// If not set, the network runs on the shared runtime.
+    #[arg(long = "runtime.network-threads", value_name = "THREADS", value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    pub network_threads: Option<usize>,
+
+    /// Comma separated CPU cores to pin the threads of the network runtime to.
+    ///
+    /// Only supported on Linux.
+    #[arg(
+        long = "runtime.network-cores",
+        value_name = "CORES",
+        value_delimiter = ',',
+        requires = "network_threads"
+    )]
+    pub network_cores: Vec<usize>,
+
+    /// Number of worker threads of a dedicated runtime for the HTTP, WS and IPC RPC servers.
+    ///
+    /// The auth server serving the engine API always runs on the shared runtime. If not set, the
+    /// RPC servers run on the shared runtime.
+    #[arg(long = "runtime.rpc-threads", value_name = "THREADS", value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    pub rpc_threads: Option<usize>,
+
+    /// Maximum number of threads of the blocking pool of the RPC runtime.
+    ///
+    /// RPC requests that read from the database run on the blocking pool. Defaults to tokio's
+    /// default of 512 threads.
+    #[arg(long = "runtime.rpc-blocking-threads", value_name = "THREADS", requires = "rpc_threads", value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    pub rpc_blocking_threads: Option<usize>,
+
+    /// Comma separated CPU cores to pin the threads of the RPC runtime to.
+    ///
+    /// Only supported on Linux.
+    #[arg(
+        long = "runtime.rpc-cores",
+        value_name = "CORES",
+        value_delimiter = ',',
+        requires = "rpc_threads"
+    )]
+    pub rpc_cores: Vec<usize>,
+}
+
+impl RuntimeArgs {
+    /// Builds the dedicated runtime for the network, if configured.
+    pub fn network_runtime(&self) -> Result<Option<SubsystemRuntime>, std::io::Error> {
+        self.network_threads
+            .map(|threads| {
+                SubsystemRuntime::new("reth-network", threads, None, self.network_cores.clone())
+            })
+            .transpose()
+    }
+
+    /// Builds the dedicated runtime for the RPC servers, if configured.
+    pub fn rpc_runtime(&self) -> Result<Option<SubsystemRuntime>, std::io::Error> {
+        self.rpc_threads
+            .map(|threads| {
+                SubsystemRuntime::new(
+                    "reth-rpc",
+                    threads,
+                    self.rpc_blocking_threads,
+                    self.rpc_cores.clone(),
+                )
+            })
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[clap(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn test_parse_runtime_args() {
+        let args = CommandParser::<RuntimeArgs>::parse_from(["reth"]).args;
+        assert_eq!(args, RuntimeArgs::default());
+
+        let args = CommandParser::<RuntimeArgs>::parse_from([
+            "reth",
+            "--runtime.rpc-threads",
+            "4",
+            "--runtime.rpc-blocking-threads",
+            "16",
+            "--runtime.rpc-cores",
+            "4,5,6,7",
+        ])
+        .args;
+        assert_eq!(args.rpc_threads, Some(4));
+        assert_eq!(args.rpc_blocking_threads, Some(16));
+        assert_eq!(args.rpc_cores, vec![4, 5, 6, 7]);
+        assert_eq!(args.network_threads, None);
+
+        // pinning requires a dedicated runtime
+        assert!(CommandParser::<RuntimeArgs>::try_parse_from([
+            "reth",
+            "--runtime.network-cores",
+            "1"
+        ])
+        .is_err());
+        assert!(CommandParser::<RuntimeArgs>::try_parse_from([
+            "reth",
+            "--runtime.network-threads",
+            "0"
+        ])
+        .is_err());
+    }
+}
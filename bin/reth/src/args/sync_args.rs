@@ -0,0 +1,69 @@
+//! clap [Args](clap::Args) for the initial sync
+
+use clap::{builder::RangedU64ValueParser, Args};
+use reth_blockchain_tree::DEFAULT_MAX_UNCONNECTED_BLOCKS;
+use reth_primitives::H256;
+
+/// Parameters for the initial sync
+#[derive(Debug, Args, PartialEq)]
+#[command(next_help_heading = "Sync")]
+pub struct SyncArgs {
+    /// Start executing from a trusted recent block instead of genesis.
+    ///
+    /// The state of the checkpoint block must already be present in the database, for example by
+    /// importing it. It is verified against the state root of the checkpoint header before the
+    /// node starts. The headers below the checkpoint are downloaded and verified in the
+    /// background.
+    ///
+    /// The checkpoint must be a post-merge block.
+    #[arg(long = "sync.checkpoint", value_name = "HASH", help_heading = "Sync")]
+    pub checkpoint: Option<H256>,
+
+    /// The number of payloads with unknown parents that are buffered until their parents are
+    /// downloaded.
+    ///
+    /// Buffered payloads are validated and attached as soon as their missing ancestors arrive, the
+    /// least recently buffered payloads are evicted once the buffer is full.
+    #[arg(
+        long = "sync.max-buffered-blocks",
+        value_name = "COUNT",
+        value_parser = RangedU64ValueParser::<usize>::new().range(1..),
+        default_value_t = DEFAULT_MAX_UNCONNECTED_BLOCKS,
+        help_heading = "Sync"
+    )]
+    pub max_buffered_blocks: usize,
+}
+
+impl Default for SyncArgs {
+    fn default() -> Self {
+        Self { checkpoint: None, max_buffered_blocks: DEFAULT_MAX_UNCONNECTED_BLOCKS }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[clap(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn parse_sync_checkpoint() {
+        let hash = H256::repeat_byte(0xab);
+        let args = CommandParser::<SyncArgs>::parse_from([
+            "reth",
+            "--sync.checkpoint",
+            &format!("{hash:?}"),
+        ])
+        .args;
+        assert_eq!(args.checkpoint, Some(hash));
+
+        let args = CommandParser::<SyncArgs>::parse_from(["reth"]).args;
+        assert_eq!(args, SyncArgs::default());
+    }
+}
@@ -2,9 +2,10 @@
 
 use clap::Args;
 use reth_transaction_pool::{
-    PoolConfig, PriceBumpConfig, SubPoolLimit, DEFAULT_PRICE_BUMP, REPLACE_BLOB_PRICE_BUMP,
-    TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER, TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT,
-    TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
+    validate::{DEFAULT_FEE_FLOOR_STEP, DEFAULT_MAX_FEE_FLOOR},
+    DynamicFeeFloorConfig, PoolConfig, PriceBumpConfig, SubPoolLimit, DEFAULT_PRICE_BUMP,
+    REPLACE_BLOB_PRICE_BUMP, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
+    TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT, TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
 };
 
 /// Parameters for debugging purposes
@@ -42,6 +43,23 @@ pub struct TxPoolArgs {
     /// Price bump percentage to replace an already existing blob transaction
     #[arg(long = "blobpool.pricebump", help_heading = "TxPool", default_value_t = REPLACE_BLOB_PRICE_BUMP)]
     pub blob_transaction_price_bump: u128,
+
+    /// Rate of external transactions per second above which a dynamic minimum priority fee is
+    /// enforced.
+    ///
+    /// The fee floor doubles for every second the rate is exceeded and decays once the rate
+    /// drops. Transactions below the floor are dropped before their signature is recovered.
+    /// Disabled if not set.
+    #[arg(long = "txpool.spam_rate_threshold", help_heading = "TxPool")]
+    pub spam_rate_threshold: Option<u64>,
+
+    /// The fee floor in wei that is enforced once the spam rate threshold is exceeded.
+    #[arg(long = "txpool.fee_floor_step", help_heading = "TxPool", default_value_t = DEFAULT_FEE_FLOOR_STEP)]
+    pub fee_floor_step: u128,
+
+    /// The maximum fee floor in wei.
+    #[arg(long = "txpool.max_fee_floor", help_heading = "TxPool", default_value_t = DEFAULT_MAX_FEE_FLOOR)]
+    pub max_fee_floor: u128,
 }
 
 impl TxPoolArgs {
@@ -67,4 +85,14 @@ impl TxPoolArgs {
             },
         }
     }
+
+    /// Returns the configuration of the dynamic fee floor, if enabled.
+    pub fn fee_floor_config(&self) -> Option<DynamicFeeFloorConfig> {
+        self.spam_rate_threshold.map(|rate_threshold| DynamicFeeFloorConfig {
+            rate_threshold,
+            step: self.fee_floor_step,
+            max_floor: self.max_fee_floor,
+            ..Default::default()
+        })
+    }
 }
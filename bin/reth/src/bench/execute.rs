@@ -0,0 +1,242 @@
+//! Command for benchmarking the re-execution of historical blocks.
+use crate::{
+    args::{utils::genesis_value_parser, DatabaseArgs},
+    dirs::{DataDirPath, MaybePlatformPath},
+    utils::db::open_db_read_only,
+};
+use clap::Parser;
+use comfy_table::{presets::ASCII_MARKDOWN, Table};
+use reth_interfaces::Result;
+use reth_primitives::{
+    stage::StageId, Account, Address, BlockNumber, Bytecode, Bytes, ChainSpec, StorageKey,
+    StorageValue, H256,
+};
+use reth_provider::{
+    AccountReader, BlockExecutor, BlockHashReader, BlockReader, ExecutorFactory, HeaderProvider,
+    PostState, ProviderFactory, StageCheckpointReader, StateProvider, StateRootProvider,
+};
+use reth_revm::stack::{Hook, InspectorStackConfig};
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+use tracing::*;
+
+/// `reth bench execute` command
+///
+/// Re-executes a range of historical blocks on top of the state before the first block, without
+/// writing anything to the database, and reports the execution throughput.
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// The path to the data dir for all reth files and subdirectories.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/` or `$HOME/.local/share/reth/`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/`
+    /// - macOS: `$HOME/Library/Application Support/reth/`
+    #[arg(long, value_name = "DATA_DIR", verbatim_doc_comment, default_value_t)]
+    datadir: MaybePlatformPath<DataDirPath>,
+
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    ///
+    /// Built-in chains:
+    /// - mainnet
+    /// - goerli
+    /// - sepolia
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        verbatim_doc_comment,
+        default_value = "mainnet",
+        value_parser = genesis_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    #[clap(flatten)]
+    db: DatabaseArgs,
+
+    /// The first block to execute.
+    #[arg(long)]
+    from: BlockNumber,
+
+    /// The last block to execute (inclusive).
+    #[arg(long)]
+    to: BlockNumber,
+
+    /// Count the executed opcodes and report the most frequent ones.
+    ///
+    /// NOTE: This slows down execution.
+    #[arg(long)]
+    opcodes: bool,
+
+    /// The number of opcodes to report.
+    #[arg(long, default_value = "20", requires = "opcodes")]
+    top_opcodes: usize,
+}
+
+impl Command {
+    /// Execute `bench execute` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        eyre::ensure!(self.from > 0, "cannot execute the genesis block");
+        eyre::ensure!(self.from <= self.to, "invalid block range {}..={}", self.from, self.to);
+
+        // add network name to data dir
+        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+        let db = open_db_read_only(&data_dir.db_path(), self.db.log_level)?;
+        let factory = ProviderFactory::new(&db, self.chain.clone());
+        let provider = factory.provider()?;
+
+        let executed = provider
+            .get_stage_checkpoint(StageId::Execution)?
+            .map(|checkpoint| checkpoint.block_number)
+            .unwrap_or_default();
+        eyre::ensure!(
+            self.to <= executed,
+            "block {} is not executed yet, the execution checkpoint is at block {executed}",
+            self.to
+        );
+
+        let stats = Arc::<StateReadStats>::default();
+        let state = StatsStateProvider {
+            inner: factory.history_by_block_number(self.from - 1)?,
+            stats: Arc::clone(&stats),
+        };
+
+        let executor_factory =
+            reth_revm::Factory::new(self.chain.clone()).with_stack_config(InspectorStackConfig {
+                use_printer_tracer: false,
+                use_opcode_counter: self.opcodes,
+                hook: if self.opcodes { Hook::All } else { Hook::None },
+            });
+        let mut executor = executor_factory.with_sp(state);
+
+        let mut td = provider.header_td_by_number(self.from - 1)?.unwrap_or_default();
+        let mut elapsed = Duration::ZERO;
+        let mut gas_used = 0u64;
+        let mut transactions = 0usize;
+
+        info!(target: "reth::cli", from = self.from, to = self.to, "Executing blocks");
+        for number in self.from..=self.to {
+            let block = provider
+                .block_with_senders(number)?
+                .ok_or_else(|| eyre::eyre!("block {number} not found"))?;
+            td += block.block.difficulty;
+
+            let start = Instant::now();
+            executor.execute_and_verify_receipt(&block.block, td, Some(block.senders))?;
+            elapsed += start.elapsed();
+
+            gas_used += block.block.gas_used;
+            transactions += block.block.body.len();
+            debug!(target: "reth::cli", number, elapsed = ?start.elapsed(), "Executed block");
+        }
+
+        let blocks = self.to - self.from + 1;
+        let seconds = elapsed.as_secs_f64();
+        println!("Executed {blocks} blocks with {transactions} transactions in {elapsed:?}");
+        println!("Gas used: {gas_used}");
+        println!("Throughput: {:.2} Mgas/s", gas_used as f64 / seconds / 1_000_000.);
+        println!("Blocks per second: {:.2}", blocks as f64 / seconds);
+
+        let mut table = Table::new();
+        table.load_preset(ASCII_MARKDOWN);
+        table.set_header(["State read", "Count"]);
+        table.add_row(["Accounts".to_string(), stats.accounts.load(Ordering::Relaxed).to_string()]);
+        table.add_row([
+            "Storage slots".to_string(),
+            stats.storage.load(Ordering::Relaxed).to_string(),
+        ]);
+        table.add_row([
+            "Bytecodes".to_string(),
+            stats.bytecodes.load(Ordering::Relaxed).to_string(),
+        ]);
+        table.add_row([
+            "Block hashes".to_string(),
+            stats.block_hashes.load(Ordering::Relaxed).to_string(),
+        ]);
+        println!("\n{table}");
+
+        if let Some(counter) = &executor.stack().opcode_counter {
+            let mut table = Table::new();
+            table.load_preset(ASCII_MARKDOWN);
+            table.set_header(["Opcode", "Count"]);
+            for (opcode, count) in counter.hotspots().into_iter().take(self.top_opcodes) {
+                table.add_row([opcode.to_string(), count.to_string()]);
+            }
+            println!("\n{table}");
+        }
+
+        Ok(())
+    }
+}
+
+/// Counters for the state reads of the executor.
+///
+/// Note: the executor caches all state it reads, so each value is read at most once.
+#[derive(Debug, Default)]
+struct StateReadStats {
+    accounts: AtomicU64,
+    storage: AtomicU64,
+    bytecodes: AtomicU64,
+    block_hashes: AtomicU64,
+}
+
+/// A [StateProvider] that counts the state reads of the wrapped provider.
+struct StatsStateProvider<SP> {
+    inner: SP,
+    stats: Arc<StateReadStats>,
+}
+
+impl<SP: StateProvider> BlockHashReader for StatsStateProvider<SP> {
+    fn block_hash(&self, number: BlockNumber) -> Result<Option<H256>> {
+        self.stats.block_hashes.fetch_add(1, Ordering::Relaxed);
+        self.inner.block_hash(number)
+    }
+
+    fn canonical_hashes_range(&self, start: BlockNumber, end: BlockNumber) -> Result<Vec<H256>> {
+        self.inner.canonical_hashes_range(start, end)
+    }
+}
+
+impl<SP: StateProvider> AccountReader for StatsStateProvider<SP> {
+    fn basic_account(&self, address: Address) -> Result<Option<Account>> {
+        self.stats.accounts.fetch_add(1, Ordering::Relaxed);
+        self.inner.basic_account(address)
+    }
+}
+
+impl<SP: StateProvider> StateRootProvider for StatsStateProvider<SP> {
+    fn state_root(&self, post_state: PostState) -> Result<H256> {
+        self.inner.state_root(post_state)
+    }
+
+    fn storage_root(&self, address: Address, post_state: PostState) -> Result<H256> {
+        self.inner.storage_root(address, post_state)
+    }
+}
+
+impl<SP: StateProvider> StateProvider for StatsStateProvider<SP> {
+    fn storage(&self, account: Address, storage_key: StorageKey) -> Result<Option<StorageValue>> {
+        self.stats.storage.fetch_add(1, Ordering::Relaxed);
+        self.inner.storage(account, storage_key)
+    }
+
+    fn bytecode_by_hash(&self, code_hash: H256) -> Result<Option<Bytecode>> {
+        self.stats.bytecodes.fetch_add(1, Ordering::Relaxed);
+        self.inner.bytecode_by_hash(code_hash)
+    }
+
+    fn proof(
+        &self,
+        address: Address,
+        keys: &[H256],
+    ) -> Result<(Vec<Bytes>, H256, Vec<Vec<Bytes>>)> {
+        self.inner.proof(address, keys)
+    }
+}
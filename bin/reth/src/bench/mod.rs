@@ -0,0 +1,31 @@
+//! `reth bench` command. Collection of various benchmarking routines.
+use clap::{Parser, Subcommand};
+
+mod execute;
+mod rpc;
+
+/// `reth bench` command
+#[derive(Debug, Parser)]
+pub struct Command {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+/// `reth bench` subcommands
+#[derive(Subcommand, Debug)]
+pub enum Subcommands {
+    /// Benchmark the re-execution of historical blocks.
+    Execute(execute::Command),
+    /// Benchmark the RPC server of a running node with a recorded or synthetic workload.
+    Rpc(rpc::Command),
+}
+
+impl Command {
+    /// Execute `bench` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        match self.command {
+            Subcommands::Execute(command) => command.execute().await,
+            Subcommands::Rpc(command) => command.execute().await,
+        }
+    }
+}
@@ -0,0 +1,238 @@
+//! Command for benchmarking the RPC server of a running node.
+use clap::Parser;
+use comfy_table::{presets::ASCII_MARKDOWN, Table};
+use futures::{stream, StreamExt};
+use jsonrpsee::{
+    core::{client::ClientT, params::ArrayParams},
+    http_client::{HttpClient, HttpClientBuilder},
+    rpc_params,
+};
+use reth_primitives::U64;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+use tracing::*;
+
+/// `reth bench rpc` command
+///
+/// Sends a workload of RPC requests to a node and reports the latency percentiles per method.
+///
+/// The workload is either read from a file, or generated from the most recent blocks of the node:
+/// for every block, `eth_getLogs` and `trace_block` requests for the block are generated, as well
+/// as `eth_call` requests that replay the transactions of the block on top of its parent.
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// The HTTP RPC endpoint of the node.
+    #[arg(long, value_name = "URL", default_value = "http://localhost:8545")]
+    rpc_url: String,
+
+    /// A recorded workload, with one JSON request per line, for example
+    /// `{"method": "eth_blockNumber", "params": []}`.
+    ///
+    /// If not set, a synthetic workload is generated from the most recent blocks.
+    #[arg(long, value_name = "PATH")]
+    workload: Option<PathBuf>,
+
+    /// The number of recent blocks used to generate the synthetic workload.
+    #[arg(long, default_value = "10", conflicts_with = "workload")]
+    blocks: u64,
+
+    /// The maximum number of `eth_call` requests generated per block.
+    #[arg(long, default_value = "10", conflicts_with = "workload")]
+    calls_per_block: usize,
+
+    /// The total number of requests to send. The workload is repeated as often as required.
+    ///
+    /// Defaults to the size of the workload.
+    #[arg(long)]
+    requests: Option<usize>,
+
+    /// The number of concurrent requests.
+    #[arg(long, default_value = "8")]
+    concurrency: usize,
+}
+
+/// A single request of the workload.
+#[derive(Debug, Clone, Deserialize)]
+struct WorkloadRequest {
+    method: String,
+    #[serde(default)]
+    params: Vec<Value>,
+}
+
+impl WorkloadRequest {
+    fn new(method: &str, params: Vec<Value>) -> Self {
+        Self { method: method.to_string(), params }
+    }
+
+    fn params(&self) -> ArrayParams {
+        let mut params = ArrayParams::new();
+        for param in &self.params {
+            params.insert(param).expect("value serializes");
+        }
+        params
+    }
+}
+
+impl Command {
+    /// Execute `bench rpc` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        let client = HttpClientBuilder::default()
+            .request_timeout(Duration::from_secs(300))
+            .build(&self.rpc_url)?;
+
+        let workload = match &self.workload {
+            Some(path) => read_workload(path)?,
+            None => self.synthetic_workload(&client).await?,
+        };
+        eyre::ensure!(!workload.is_empty(), "the workload is empty");
+
+        let requests = self.requests.unwrap_or(workload.len());
+        info!(target: "reth::cli", requests, concurrency = self.concurrency, "Sending requests");
+
+        let start = Instant::now();
+        let results = stream::iter(workload.iter().cycle().take(requests))
+            .map(|request| {
+                let client = &client;
+                async move {
+                    let start = Instant::now();
+                    let result =
+                        client.request::<Value, _>(&request.method, request.params()).await;
+                    if let Err(err) = &result {
+                        debug!(target: "reth::cli", method = %request.method, %err, "Request failed");
+                    }
+                    (request.method.as_str(), start.elapsed(), result.is_ok())
+                }
+            })
+            .buffer_unordered(self.concurrency.max(1))
+            .collect::<Vec<_>>()
+            .await;
+        let elapsed = start.elapsed();
+
+        let mut latencies = BTreeMap::<&str, MethodLatencies>::new();
+        for (method, latency, ok) in results {
+            let entry = latencies.entry(method).or_default();
+            if ok {
+                entry.latencies.push(latency);
+            } else {
+                entry.errors += 1;
+            }
+        }
+
+        let mut table = Table::new();
+        table.load_preset(ASCII_MARKDOWN);
+        table.set_header(["Method", "Requests", "Errors", "p50", "p90", "p99", "Max"]);
+        for (method, mut entry) in latencies {
+            entry.latencies.sort_unstable();
+            let percentile =
+                |p| percentile(&entry.latencies, p).map(|d| format!("{d:?}")).unwrap_or_default();
+            table.add_row([
+                method.to_string(),
+                (entry.latencies.len() + entry.errors).to_string(),
+                entry.errors.to_string(),
+                percentile(50.),
+                percentile(90.),
+                percentile(99.),
+                percentile(100.),
+            ]);
+        }
+
+        println!("Sent {requests} requests in {elapsed:?}");
+        println!("Requests per second: {:.2}", requests as f64 / elapsed.as_secs_f64());
+        println!("\n{table}");
+
+        Ok(())
+    }
+
+    /// Generates a workload from the most recent blocks of the node.
+    async fn synthetic_workload(&self, client: &HttpClient) -> eyre::Result<Vec<WorkloadRequest>> {
+        let latest: U64 = client.request("eth_blockNumber", rpc_params![]).await?;
+        let latest = latest.as_u64();
+
+        let mut workload = Vec::new();
+        for number in latest.saturating_sub(self.blocks.saturating_sub(1))..=latest {
+            let block_number = json!(U64::from(number));
+            workload.push(WorkloadRequest::new(
+                "eth_getLogs",
+                vec![json!({ "fromBlock": block_number, "toBlock": block_number })],
+            ));
+            workload.push(WorkloadRequest::new("trace_block", vec![block_number.clone()]));
+
+            if number == 0 {
+                continue
+            }
+            let block: Value =
+                client.request("eth_getBlockByNumber", rpc_params![block_number, true]).await?;
+            let transactions = block["transactions"].as_array().cloned().unwrap_or_default();
+            for tx in
+                transactions.iter().filter(|tx| !tx["to"].is_null()).take(self.calls_per_block)
+            {
+                let call = json!({
+                    "from": tx["from"],
+                    "to": tx["to"],
+                    "data": tx["input"],
+                    "value": tx["value"],
+                    "gas": tx["gas"],
+                });
+                workload.push(WorkloadRequest::new(
+                    "eth_call",
+                    vec![call, json!(U64::from(number - 1))],
+                ));
+            }
+        }
+
+        info!(target: "reth::cli", requests = workload.len(), "Generated synthetic workload");
+        Ok(workload)
+    }
+}
+
+/// Reads a workload with one JSON request per line.
+fn read_workload(path: &PathBuf) -> eyre::Result<Vec<WorkloadRequest>> {
+    let content = reth_primitives::fs::read_to_string(path)?;
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| Ok(serde_json::from_str(line)?))
+        .collect()
+}
+
+#[derive(Debug, Default)]
+struct MethodLatencies {
+    latencies: Vec<Duration>,
+    errors: usize,
+}
+
+/// Returns the given percentile of the sorted latencies, using the nearest-rank method.
+fn percentile(sorted: &[Duration], percentile: f64) -> Option<Duration> {
+    if sorted.is_empty() {
+        return None
+    }
+    let rank = (percentile / 100. * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearest_rank_percentile() {
+        let latencies = (1..=10).map(Duration::from_millis).collect::<Vec<_>>();
+        assert_eq!(percentile(&latencies, 50.), Some(Duration::from_millis(5)));
+        assert_eq!(percentile(&latencies, 99.), Some(Duration::from_millis(10)));
+        assert_eq!(percentile(&latencies, 100.), Some(Duration::from_millis(10)));
+        assert_eq!(percentile(&[], 50.), None);
+    }
+
+    #[test]
+    fn parse_workload_request() {
+        let request: WorkloadRequest =
+            serde_json::from_str(r#"{"method": "eth_blockNumber"}"#).unwrap();
+        assert_eq!(request.method, "eth_blockNumber");
+        assert!(request.params.is_empty());
+    }
+}
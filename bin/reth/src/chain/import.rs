@@ -148,9 +148,10 @@ impl ImportCommand {
             eyre::bail!("unable to import non canonical blocks");
         }
 
-        let header_downloader = ReverseHeadersDownloaderBuilder::from(config.stages.headers)
-            .build(file_client.clone(), consensus.clone())
-            .into_task();
+        let header_downloader =
+            ReverseHeadersDownloaderBuilder::from(config.stages.headers.clone())
+                .build(file_client.clone(), consensus.clone())
+                .into_task();
 
         let body_downloader = BodiesDownloaderBuilder::from(config.stages.bodies)
             .build(file_client.clone(), consensus.clone(), db.clone())
@@ -172,13 +173,19 @@ impl ImportCommand {
                     body_downloader,
                     factory.clone(),
                 )
-                .set(
-                    TotalDifficultyStage::new(consensus.clone())
-                        .with_commit_threshold(config.stages.total_difficulty.commit_threshold),
-                )
-                .set(SenderRecoveryStage {
-                    commit_threshold: config.stages.sender_recovery.commit_threshold,
+                .set({
+                    let stage = TotalDifficultyStage::new(consensus.clone())
+                        .with_commit_threshold(config.stages.total_difficulty.commit_threshold);
+                    if config.stages.total_difficulty.elide_post_merge {
+                        stage.with_post_merge_elision(&self.chain)
+                    } else {
+                        stage
+                    }
                 })
+                .set(SenderRecoveryStage::new(
+                    config.stages.sender_recovery.commit_threshold,
+                    config.prune.as_ref().map(|prune| prune.parts.clone()).unwrap_or_default(),
+                ))
                 .set(ExecutionStage::new(
                     factory,
                     ExecutionStageThresholds {
@@ -8,9 +8,10 @@ use reth_payload_builder::{PayloadBuilderHandle, PayloadBuilderService};
 use reth_primitives::ChainSpec;
 use reth_provider::{
     BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader, EvmEnvProvider,
-    StateProviderFactory,
+    PreimageReader, StageCheckpointReader, StateProviderFactory,
 };
 use reth_rpc_builder::{RethModuleRegistry, TransportRpcModules};
+use reth_stages::StageHook;
 use reth_tasks::TaskSpawner;
 use reth_transaction_pool::TransactionPool;
 use std::{fmt, sync::Arc};
@@ -53,6 +54,8 @@ pub trait RethNodeCommandConfig: fmt::Debug {
             + EvmEnvProvider
             + ChainSpecProvider
             + ChangeSetReader
+            + StageCheckpointReader
+            + PreimageReader
             + Clone
             + Unpin
             + 'static,
@@ -69,6 +72,10 @@ pub trait RethNodeCommandConfig: fmt::Debug {
     ///
     /// By default this spawns a [BasicPayloadJobGenerator] with the default configuration
     /// [BasicPayloadJobGeneratorConfig].
+    ///
+    /// Custom transaction ordering or inclusion rules can be installed by overriding this and
+    /// creating the generator with [BasicPayloadJobGenerator::with_builder], for example with an
+    /// [OrderedPayloadBuilder](reth_basic_payload_builder::OrderedPayloadBuilder).
     fn spawn_payload_builder_service<Conf, Provider, Pool, Tasks>(
         &mut self,
         conf: &Conf,
@@ -101,6 +108,16 @@ pub trait RethNodeCommandConfig: fmt::Debug {
 
         Ok(payload_builder)
     }
+
+    /// Returns the hooks that should be invoked around every stage execution of the sync
+    /// pipeline.
+    ///
+    /// This can be used to coordinate external systems (e.g. indexers) with pipeline progress.
+    ///
+    /// By default no hooks are installed.
+    fn stage_hooks(&self) -> Vec<Arc<dyn StageHook>> {
+        Vec::new()
+    }
 }
 
 /// A trait that allows for extending parts of the CLI with additional functionality.
@@ -176,6 +193,8 @@ impl<T: RethNodeCommandConfig> RethNodeCommandConfig for NoArgs<T> {
             + EvmEnvProvider
             + ChainSpecProvider
             + ChangeSetReader
+            + StageCheckpointReader
+            + PreimageReader
             + Clone
             + Unpin
             + 'static,
@@ -209,6 +228,10 @@ impl<T: RethNodeCommandConfig> RethNodeCommandConfig for NoArgs<T> {
             .ok_or_else(|| eyre::eyre!("config value must be set"))?
             .spawn_payload_builder_service(conf, provider, pool, executor, chain_spec)
     }
+
+    fn stage_hooks(&self) -> Vec<Arc<dyn StageHook>> {
+        self.inner().map(|inner| inner.stage_hooks()).unwrap_or_default()
+    }
 }
 
 #[cfg(test)]
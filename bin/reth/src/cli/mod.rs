@@ -1,11 +1,11 @@
 //! CLI definition and entrypoint to executable
 use crate::{
     args::utils::genesis_value_parser,
-    chain,
+    bench, chain,
     cli::ext::RethCliExt,
     db, debug_cmd,
     dirs::{LogsDir, PlatformPath},
-    node, p2p, recover,
+    estimate, node, p2p, recover, replica,
     runner::CliRunner,
     stage, test_vectors,
     version::{LONG_VERSION, SHORT_VERSION},
@@ -15,7 +15,7 @@ use reth_primitives::ChainSpec;
 use reth_tracing::{
     tracing::{metadata::LevelFilter, Level, Subscriber},
     tracing_subscriber::{filter::Directive, registry::LookupSpan, EnvFilter},
-    BoxedLayer, FileWorkerGuard,
+    BoxedLayer, FileWorkerGuard, OtlpGuard,
 };
 use std::{fmt, fmt::Display, sync::Arc};
 
@@ -91,28 +91,50 @@ impl<Ext: RethCliExt> Cli<Ext> {
             Commands::P2P(command) => runner.run_until_ctrl_c(command.execute()),
             Commands::TestVectors(command) => runner.run_until_ctrl_c(command.execute()),
             Commands::Config(command) => runner.run_until_ctrl_c(command.execute()),
+            Commands::Estimate(command) => runner.run_until_ctrl_c(command.execute()),
             Commands::Debug(command) => runner.run_command_until_exit(|ctx| command.execute(ctx)),
             Commands::Recover(command) => runner.run_command_until_exit(|ctx| command.execute(ctx)),
+            Commands::Replica(command) => runner.run_command_until_exit(|ctx| command.execute(ctx)),
+            Commands::Bench(command) => runner.run_blocking_until_ctrl_c(command.execute()),
+            Commands::Account(command) => runner.run_blocking_until_ctrl_c(command.execute()),
         }
     }
 
     /// Initializes tracing with the configured options.
     ///
-    /// If file logging is enabled, this function returns a guard that must be kept alive to ensure
-    /// that all logs are flushed to disk.
-    pub fn init_tracing(&self) -> eyre::Result<Option<FileWorkerGuard>> {
-        let mut layers =
-            vec![reth_tracing::stdout(self.verbosity.directive(), &self.logs.color.to_string())];
-        let guard = self.logs.layer()?.map(|(layer, guard)| {
+    /// If file logging or OTLP export is enabled, this function returns guards that must be kept
+    /// alive to ensure that all logs are flushed to disk and all spans are exported.
+    pub fn init_tracing(&self) -> eyre::Result<TracingGuards> {
+        // the terminal dashboard takes over stdout
+        let stdout_directive = if matches!(&self.command, Commands::Node(command) if command.tui) {
+            LevelFilter::OFF.into()
+        } else {
+            self.verbosity.directive()
+        };
+        let mut layers = vec![reth_tracing::stdout(stdout_directive, &self.logs.color.to_string())];
+        let file = self.logs.layer()?.and_then(|(layer, guard)| {
+            layers.push(layer);
+            guard
+        });
+        let otlp = self.logs.otlp_layer()?.map(|(layer, guard)| {
             layers.push(layer);
             guard
         });
 
         reth_tracing::init(layers);
-        Ok(guard.flatten())
+        Ok(TracingGuards { file, otlp })
     }
 }
 
+/// The guards returned by [Cli::init_tracing].
+#[derive(Debug)]
+pub struct TracingGuards {
+    /// Flushes the log file when dropped.
+    pub file: Option<FileWorkerGuard>,
+    /// Exports all pending spans when dropped.
+    pub otlp: Option<OtlpGuard>,
+}
+
 /// Convenience function for parsing CLI options, set up logging and run the chosen command.
 #[inline]
 pub fn run() -> eyre::Result<()> {
@@ -146,12 +168,24 @@ pub enum Commands<Ext: RethCliExt = ()> {
     /// Write config to stdout
     #[command(name = "config")]
     Config(crate::config::Command),
+    /// Estimate the disk usage for the configured chain and prune mode
+    #[command(name = "estimate")]
+    Estimate(estimate::Command),
     /// Various debug routines
     #[command(name = "debug")]
     Debug(debug_cmd::Command),
     /// Scripts for node recovery
     #[command(name = "recover")]
     Recover(recover::Command),
+    /// Serve RPC from the database of a node running in another process
+    #[command(name = "replica")]
+    Replica(replica::Command),
+    /// Benchmarking routines
+    #[command(name = "bench")]
+    Bench(bench::Command),
+    /// Manage the keystore of the auth server accounts
+    #[command(name = "account")]
+    Account(account::Command),
 }
 
 /// The log configuration.
@@ -189,6 +223,19 @@ pub struct Logs {
         default_value_t = ColorMode::Always
     )]
     color: ColorMode,
+
+    /// Export spans to the given OpenTelemetry collector via OTLP/gRPC.
+    #[arg(long = "tracing.otlp", value_name = "URL", global = true)]
+    otlp: Option<String>,
+
+    /// The filter to use for spans exported via OTLP.
+    #[arg(
+        long = "tracing.otlp.filter",
+        value_name = "FILTER",
+        global = true,
+        default_value = "info,rpc::engine=debug,blockchain_tree=debug,sync::pipeline=debug"
+    )]
+    otlp_filter: String,
 }
 
 impl Logs {
@@ -209,6 +256,17 @@ impl Logs {
             Ok(None)
         }
     }
+
+    /// Builds a tracing layer that exports spans via OTLP, if configured.
+    pub fn otlp_layer<S>(&self) -> eyre::Result<Option<(BoxedLayer<S>, OtlpGuard)>>
+    where
+        S: Subscriber,
+        for<'a> S: LookupSpan<'a>,
+    {
+        let Some(endpoint) = &self.otlp else { return Ok(None) };
+        let filter = EnvFilter::builder().parse(&self.otlp_filter)?;
+        Ok(Some(reth_tracing::otlp(filter, endpoint.clone())?))
+    }
 }
 
 /// The verbosity settings for the cli.
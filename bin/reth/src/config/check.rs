@@ -0,0 +1,341 @@
+//! Command that validates the config file together with the node flags.
+
+use crate::{
+    args::{utils::genesis_value_parser, NetworkArgs, PruningArgs, RpcServerArgs},
+    dirs::{DataDirPath, MaybePlatformPath},
+};
+use clap::Parser;
+use eyre::{bail, WrapErr};
+use reth_config::Config;
+use reth_primitives::ChainSpec;
+use reth_rpc_builder::{RethRpcModule, RpcModuleSelection};
+use serde::Serialize;
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// `reth config check` command
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// The path to the configuration file to use.
+    ///
+    /// Defaults to `reth.toml` in the data dir of the chain.
+    #[arg(long, value_name = "FILE", verbatim_doc_comment)]
+    config: Option<PathBuf>,
+
+    /// The path to the data dir for all reth files and subdirectories.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/` or `$HOME/.local/share/reth/`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/`
+    /// - macOS: `$HOME/Library/Application Support/reth/`
+    #[arg(long, value_name = "DATA_DIR", verbatim_doc_comment, default_value_t)]
+    datadir: MaybePlatformPath<DataDirPath>,
+
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    ///
+    /// Built-in chains:
+    /// - mainnet
+    /// - goerli
+    /// - sepolia
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        verbatim_doc_comment,
+        default_value = "mainnet",
+        value_parser = genesis_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    /// Print the effective config and the issues as JSON instead of TOML.
+    #[arg(long)]
+    json: bool,
+
+    #[clap(flatten)]
+    network: NetworkArgs,
+
+    #[clap(flatten)]
+    rpc: RpcServerArgs,
+
+    #[clap(flatten)]
+    pruning: PruningArgs,
+}
+
+impl Command {
+    /// Execute `config check` command
+    pub fn execute(&self) -> eyre::Result<()> {
+        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+        let config_path = self.config.clone().unwrap_or_else(|| data_dir.config_path());
+
+        // unlike the node, the config file is not created if it doesn't exist
+        let mut config = if config_path.exists() {
+            confy::load_path::<Config>(&config_path).wrap_err_with(|| {
+                format!("Could not load config file: {}", config_path.display())
+            })?
+        } else {
+            Config::default()
+        };
+        self.apply_flags(&mut config)?;
+
+        let mut issues = Vec::new();
+        if !config_path.exists() {
+            issues.push(Issue::warning(format!(
+                "config file {} does not exist, the defaults are used",
+                config_path.display()
+            )));
+        }
+        issues.extend(check_prune_rpc(&config, &self.rpc_modules()));
+        issues.extend(check_peer_limits(&config));
+        issues.extend(check_datadir(data_dir.as_ref()));
+
+        if self.json {
+            let report = serde_json::json!({ "config": config, "issues": issues });
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            println!("{}", toml::to_string_pretty(&config)?);
+            for issue in &issues {
+                eprintln!("{issue}");
+            }
+        }
+
+        let errors = issues.iter().filter(|issue| issue.severity == Severity::Error).count();
+        if errors > 0 {
+            bail!("The configuration has {errors} error(s)")
+        }
+        Ok(())
+    }
+
+    /// Applies the flags that override the config file, the same way the node does.
+    fn apply_flags(&self, config: &mut Config) -> eyre::Result<()> {
+        config.peers.connect_trusted_nodes_only = self.network.trusted_only;
+        config.peers.trusted_nodes.extend(self.network.trusted_peers.iter().copied());
+        config.peers = config
+            .peers
+            .clone()
+            .with_max_inbound_opt(self.network.max_inbound_peers)
+            .with_max_outbound_opt(self.network.max_outbound_peers)
+            .with_banned_subnets(self.network.banned_ips.iter().copied());
+        config.prune = self.pruning.prune_config(Arc::clone(&self.chain))?.or(config.prune.take());
+        Ok(())
+    }
+
+    /// Returns the enabled RPC modules by transport.
+    fn rpc_modules(&self) -> Vec<(&'static str, Vec<RethRpcModule>)> {
+        let mut modules = Vec::new();
+        if self.rpc.http {
+            let selection = self.rpc.http_api.clone().unwrap_or(RpcModuleSelection::Standard);
+            modules.push(("http", selection.into_selection()));
+        }
+        if self.rpc.ws {
+            let selection = self.rpc.ws_api.clone().unwrap_or(RpcModuleSelection::Standard);
+            modules.push(("ws", selection.into_selection()));
+        }
+        modules
+    }
+}
+
+/// How severe an [Issue] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Severity {
+    /// The node starts, but likely doesn't behave as intended.
+    Warning,
+    /// The node fails to start.
+    Error,
+}
+
+/// A problem found in the configuration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct Issue {
+    severity: Severity,
+    message: String,
+}
+
+impl Issue {
+    fn warning(message: impl Into<String>) -> Self {
+        Self { severity: Severity::Warning, message: message.into() }
+    }
+
+    fn error(message: impl Into<String>) -> Self {
+        Self { severity: Severity::Error, message: message.into() }
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.severity {
+            Severity::Warning => write!(f, "warning: {}", self.message),
+            Severity::Error => write!(f, "error: {}", self.message),
+        }
+    }
+}
+
+/// Checks that the enabled RPC namespaces don't rely on pruned data.
+fn check_prune_rpc(config: &Config, modules: &[(&str, Vec<RethRpcModule>)]) -> Vec<Issue> {
+    let Some(prune) = &config.prune else { return Vec::new() };
+    let parts = &prune.parts;
+
+    let mut pruned = Vec::new();
+    if parts.account_history.is_some() {
+        pruned.push("account history");
+    }
+    if parts.storage_history.is_some() {
+        pruned.push("storage history");
+    }
+    if parts.receipts.is_some() || !parts.receipts_log_filter.0.is_empty() {
+        pruned.push("receipts");
+    }
+
+    let mut issues = Vec::new();
+    for (transport, modules) in modules {
+        for module in modules {
+            if matches!(module, RethRpcModule::Trace | RethRpcModule::Debug | RethRpcModule::Ots) &&
+                !pruned.is_empty()
+            {
+                issues.push(Issue::warning(format!(
+                    "the `{module}` namespace is enabled on {transport}, but {} is pruned: \
+                     requests for pruned blocks fail",
+                    pruned.join(", ")
+                )));
+            }
+            if *module == RethRpcModule::Eth && parts.transaction_lookup.is_some() {
+                issues.push(Issue::warning(format!(
+                    "the `eth` namespace is enabled on {transport}, but the transaction lookup \
+                     is pruned: pruned transactions can't be found by hash"
+                )));
+            }
+        }
+    }
+    issues
+}
+
+/// Checks that the peer limits are consistent with the session limits.
+fn check_peer_limits(config: &Config) -> Vec<Issue> {
+    let connection_info = &config.peers.connection_info;
+    let limits = &config.sessions.limits;
+    let mut issues = Vec::new();
+
+    if let Some(max) = limits.max_established_outbound() {
+        if (max as usize) < connection_info.max_outbound() {
+            issues.push(Issue::warning(format!(
+                "peers.connection_info.max_outbound is {}, but at most {max} outbound sessions \
+                 are allowed by sessions.limits",
+                connection_info.max_outbound()
+            )));
+        }
+    }
+    if let Some(max) = limits.max_established_inbound() {
+        if (max as usize) < connection_info.max_inbound() {
+            issues.push(Issue::warning(format!(
+                "peers.connection_info.max_inbound is {}, but at most {max} inbound sessions \
+                 are allowed by sessions.limits",
+                connection_info.max_inbound()
+            )));
+        }
+    }
+    if connection_info.max_outbound() == 0 && connection_info.max_inbound() == 0 {
+        issues.push(Issue::error("no inbound or outbound peers are allowed"));
+    }
+    if config.peers.connect_trusted_nodes_only && config.peers.trusted_nodes.is_empty() {
+        issues.push(Issue::error("only trusted peers are allowed, but there are no trusted peers"));
+    }
+    issues
+}
+
+/// Checks that the data dir exists or can be created, and is writable.
+fn check_datadir(path: &Path) -> Vec<Issue> {
+    // the data dir is created on startup, so its closest existing ancestor must be writable
+    let Some(existing) = path.ancestors().find(|dir| dir.exists()) else {
+        return vec![Issue::error(format!("data dir {} can't be created", path.display()))]
+    };
+    if !existing.is_dir() {
+        return vec![Issue::error(format!("{} is not a directory", existing.display()))]
+    }
+    match tempfile::tempfile_in(existing) {
+        Ok(_) => Vec::new(),
+        Err(err) if existing == path => {
+            vec![Issue::error(format!("data dir {} is not writable: {err}", path.display()))]
+        }
+        Err(err) => vec![Issue::error(format!(
+            "data dir {} can't be created, {} is not writable: {err}",
+            path.display(),
+            existing.display()
+        ))],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_config::config::PruneConfig;
+    use reth_network::SessionLimits;
+    use reth_primitives::{PruneMode, PruneModes};
+
+    #[test]
+    fn parse_config_check() {
+        let cmd = Command::try_parse_from([
+            "reth",
+            "--http",
+            "--http.api",
+            "eth,trace",
+            "--full",
+            "--json",
+        ])
+        .unwrap();
+        assert!(cmd.json);
+        assert!(cmd.pruning.full);
+        assert_eq!(
+            cmd.rpc_modules(),
+            vec![("http", vec![RethRpcModule::Eth, RethRpcModule::Trace])]
+        );
+    }
+
+    #[test]
+    fn prune_rpc_conflicts() {
+        let modules = vec![("http", vec![RethRpcModule::Eth, RethRpcModule::Trace])];
+        let mut config = Config::default();
+        assert!(check_prune_rpc(&config, &modules).is_empty());
+
+        config.prune = Some(PruneConfig {
+            block_interval: 5,
+            parts: PruneModes {
+                account_history: Some(PruneMode::Distance(128)),
+                transaction_lookup: Some(PruneMode::Full),
+                ..Default::default()
+            },
+        });
+        let issues = check_prune_rpc(&config, &modules);
+        assert_eq!(issues.len(), 2);
+        assert!(issues.iter().all(|issue| issue.severity == Severity::Warning));
+        assert!(issues[1].message.contains("`trace`"));
+    }
+
+    #[test]
+    fn peer_limit_conflicts() {
+        let mut config = Config::default();
+        assert!(check_peer_limits(&config).is_empty());
+
+        config.sessions.limits = SessionLimits::default().with_max_established_outbound(10);
+        config.peers.connect_trusted_nodes_only = true;
+        let issues = check_peer_limits(&config);
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].severity, Severity::Warning);
+        assert_eq!(issues[1].severity, Severity::Error);
+    }
+
+    #[test]
+    fn datadir_permissions() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(check_datadir(dir.path()).is_empty());
+        assert!(check_datadir(&dir.path().join("mainnet")).is_empty());
+
+        let file = dir.path().join("file");
+        std::fs::write(&file, []).unwrap();
+        assert_eq!(check_datadir(&file.join("mainnet"))[0].severity, Severity::Error);
+    }
+}
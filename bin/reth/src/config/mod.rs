@@ -1,13 +1,19 @@
 //! CLI command to show configs
 use std::path::PathBuf;
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use eyre::{bail, WrapErr};
 use reth_config::Config;
 
+mod check;
+
 /// `reth config` command
 #[derive(Debug, Parser)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct Command {
+    #[command(subcommand)]
+    command: Option<Subcommands>,
+
     /// The path to the configuration file to use.
     #[arg(long, value_name = "FILE", verbatim_doc_comment)]
     config: Option<PathBuf>,
@@ -17,9 +23,21 @@ pub struct Command {
     default: bool,
 }
 
+/// `reth config` subcommands
+#[derive(Debug, Subcommand)]
+pub enum Subcommands {
+    /// Validate the config file together with the node flags and print the effective config
+    #[command(name = "check")]
+    Check(check::Command),
+}
+
 impl Command {
     /// Execute `config` command
     pub async fn execute(&self) -> eyre::Result<()> {
+        if let Some(Subcommands::Check(command)) = &self.command {
+            return command.execute()
+        }
+
         let config = if self.default {
             Config::default()
         } else {
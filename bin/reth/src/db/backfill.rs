@@ -0,0 +1,290 @@
+//! Command that backfills pruned receipts from a remote archive node or from peers.
+use crate::{
+    args::{get_secret_key, NetworkArgs},
+    dirs::{ChainPath, DataDirPath},
+};
+use clap::Parser;
+use futures::{stream, StreamExt, TryStreamExt};
+use reth_config::Config;
+use reth_db::{
+    database::Database,
+    models::StoredBlockBodyIndices,
+    tables,
+    transaction::{DbTx, DbTxMut},
+};
+use reth_downloaders::receipts::receipts::ReceiptsDownloaderBuilder;
+use reth_network::FetchClient;
+use reth_primitives::{BlockNumber, ChainSpec, Header, PruneCheckpoint, PrunePart, Receipt, H256};
+use reth_provider::ProviderFactory;
+use reth_rpc::eth::archive::ArchiveClient;
+use std::{ops::RangeInclusive, sync::Arc};
+use tracing::*;
+
+/// The arguments for the `reth db backfill` command
+///
+/// Fetches the receipts of a range of blocks from a remote archive node over JSON-RPC, or from
+/// peers over p2p with `--p2p`, and writes them to the database, for example to turn a pruned node
+/// into a node with a deeper history without resyncing. The receipts of every block are verified
+/// against the canonical block hash and the receipts root of the local header before they are
+/// written.
+///
+/// If the backfilled range reaches the receipts prune checkpoint, the checkpoint is moved below
+/// the range. The pruning configuration must keep the backfilled receipts, otherwise the pruner
+/// removes them again.
+///
+/// This command runs while the node is stopped. A running node fetches the blocks and receipts
+/// that are missing in its database from an archive node on demand when they are requested over
+/// RPC, see `--rpc.archive-url`.
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The HTTP RPC endpoint of the archive node.
+    #[arg(long, value_name = "URL", required_unless_present = "p2p")]
+    rpc_url: Option<String>,
+
+    /// Download the receipts from peers with `GetReceipts` instead of an archive node.
+    ///
+    /// Receipts of blocks before the Byzantium hardfork can not be verified and are rejected.
+    #[arg(long, conflicts_with = "rpc_url")]
+    p2p: bool,
+
+    /// The first block to backfill.
+    #[arg(long)]
+    from: BlockNumber,
+
+    /// The last block to backfill, inclusive.
+    ///
+    /// Defaults to the receipts prune checkpoint.
+    #[arg(long)]
+    to: Option<BlockNumber>,
+
+    /// The number of blocks that are requested concurrently.
+    #[arg(long, default_value = "8")]
+    concurrency: usize,
+
+    /// The number of blocks written per database transaction.
+    #[arg(long, default_value = "1000")]
+    commit_threshold: u64,
+
+    #[clap(flatten)]
+    network: NetworkArgs,
+}
+
+/// Where the receipts are fetched from.
+enum ReceiptsSource {
+    /// A remote archive node.
+    Rpc(ArchiveClient),
+    /// The peers of the network.
+    P2p(FetchClient),
+}
+
+/// The verified receipts of a block.
+#[derive(Debug)]
+struct BlockReceipts {
+    number: BlockNumber,
+    body: StoredBlockBodyIndices,
+    receipts: Vec<Receipt>,
+}
+
+impl Command {
+    /// Execute `db backfill` command
+    pub async fn execute<DB: Database + 'static>(
+        self,
+        db: Arc<DB>,
+        chain: Arc<ChainSpec>,
+        data_dir: ChainPath<DataDirPath>,
+    ) -> eyre::Result<()> {
+        let db = db.as_ref();
+        let checkpoint =
+            db.view(|tx| tx.get::<tables::PruneCheckpoints>(PrunePart::Receipts))??;
+        let to = match self.to.or(checkpoint.and_then(|checkpoint| checkpoint.block_number)) {
+            Some(to) => to,
+            None => eyre::bail!("no receipts are pruned, the last block must be set with --to"),
+        };
+        eyre::ensure!(self.from <= to, "the range {}..={to} is empty", self.from);
+
+        let source = match &self.rpc_url {
+            Some(rpc_url) => {
+                info!(target: "reth::cli", from = self.from, to, %rpc_url, "Backfilling receipts from archive node");
+                ReceiptsSource::Rpc(ArchiveClient::new(rpc_url)?)
+            }
+            None => {
+                info!(target: "reth::cli", from = self.from, to, "Backfilling receipts from peers");
+                ReceiptsSource::P2p(self.start_network(db, &chain, &data_dir).await?)
+            }
+        };
+
+        let mut start = self.from;
+        while start <= to {
+            let end = to.min(start.saturating_add(self.commit_threshold.max(1) - 1));
+            let blocks = match &source {
+                ReceiptsSource::Rpc(client) => self.fetch_range(db, client, start..=end).await?,
+                ReceiptsSource::P2p(client) => {
+                    self.download_range(db, client, &chain, start..=end).await?
+                }
+            };
+            write_receipts(db, blocks)?;
+            info!(target: "reth::cli", block = end, to, "Backfilled receipts");
+            start = end + 1;
+        }
+
+        // the receipts are complete from the start of the range up to the checkpoint
+        if let Some(checkpoint) = checkpoint {
+            if checkpoint.block_number.map_or(false, |number| self.from <= number && number <= to) {
+                let first_tx = db
+                    .view(|tx| tx.get::<tables::BlockBodyIndices>(self.from))??
+                    .map(|body| body.first_tx_num)
+                    .unwrap_or_default();
+                let checkpoint = PruneCheckpoint {
+                    block_number: self.from.checked_sub(1),
+                    tx_number: first_tx.checked_sub(1),
+                    prune_mode: checkpoint.prune_mode,
+                };
+                db.update(|tx| {
+                    tx.put::<tables::PruneCheckpoints>(PrunePart::Receipts, checkpoint)
+                })??;
+                info!(target: "reth::cli", ?checkpoint, "Receipts prune checkpoint updated");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Starts the network and returns a client to request receipts from peers.
+    async fn start_network<DB: Database + 'static>(
+        &self,
+        db: &Arc<DB>,
+        chain: &Arc<ChainSpec>,
+        data_dir: &ChainPath<DataDirPath>,
+    ) -> eyre::Result<FetchClient> {
+        let mut config = Config::default();
+        config.peers.connect_trusted_nodes_only = self.network.trusted_only;
+        config.peers.trusted_nodes.extend(self.network.trusted_peers.iter().copied());
+
+        let p2p_secret_key = get_secret_key(
+            &self.network.p2p_secret_key.clone().unwrap_or_else(|| data_dir.p2p_secret_path()),
+        )?;
+        let network = self
+            .network
+            .network_config(&config, chain.clone(), p2p_secret_key, data_dir.known_peers_path())
+            .build(Arc::new(ProviderFactory::new(Arc::clone(db), chain.clone())))
+            .start_network()
+            .await?;
+        info!(target: "reth::cli", peer_id = %network.peer_id(), "Connected to P2P network");
+
+        Ok(network.fetch_client().await?)
+    }
+
+    /// Fetches and verifies the receipts of the given range from the archive node.
+    async fn fetch_range<DB: Database>(
+        &self,
+        db: &DB,
+        client: &ArchiveClient,
+        range: RangeInclusive<BlockNumber>,
+    ) -> eyre::Result<Vec<BlockReceipts>> {
+        let blocks = stream::iter(range)
+            .map(|number| fetch_block_receipts(db, client, number))
+            .buffered(self.concurrency.max(1))
+            .try_collect::<Vec<_>>()
+            .await?;
+        Ok(blocks.into_iter().flatten().collect())
+    }
+
+    /// Downloads the receipts of the given range from peers.
+    ///
+    /// The downloader verifies the receipts against the receipts root of the local headers.
+    async fn download_range<DB: Database>(
+        &self,
+        db: &DB,
+        client: &FetchClient,
+        chain: &ChainSpec,
+        range: RangeInclusive<BlockNumber>,
+    ) -> eyre::Result<Vec<BlockReceipts>> {
+        let mut headers = Vec::new();
+        let mut bodies = Vec::new();
+        for number in range {
+            let (hash, header, body) = read_block(db, number)?;
+            headers.push(header.seal(hash));
+            bodies.push(body);
+        }
+
+        let downloaded = ReceiptsDownloaderBuilder::default()
+            .with_max_concurrent_requests(self.concurrency)
+            .build(client.clone(), headers, chain)
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        let mut blocks = Vec::new();
+        for (block, body) in downloaded.into_iter().flatten().zip(bodies) {
+            let number = block.block.number;
+            eyre::ensure!(
+                block.receipts.len() as u64 == body.tx_count,
+                "downloaded {} receipts for block {number} with {} transactions",
+                block.receipts.len(),
+                body.tx_count
+            );
+            if body.tx_count == 0 {
+                continue
+            }
+            let receipts = block.receipts.into_iter().map(|receipt| receipt.receipt).collect();
+            blocks.push(BlockReceipts { number, body, receipts });
+        }
+        Ok(blocks)
+    }
+}
+
+/// Writes the receipts of the given blocks in a single transaction.
+fn write_receipts<DB: Database>(db: &DB, blocks: Vec<BlockReceipts>) -> eyre::Result<()> {
+    let tx = db.tx_mut()?;
+    for block in blocks {
+        for (tx_num, receipt) in block.body.tx_num_range().zip(block.receipts) {
+            tx.put::<tables::Receipts>(tx_num, receipt)?;
+        }
+        trace!(target: "reth::cli", block = block.number, "Wrote receipts");
+    }
+    tx.commit()?;
+
+    Ok(())
+}
+
+/// Reads the canonical hash, header and body indices of a block from the database.
+fn read_block<DB: Database>(
+    db: &DB,
+    number: BlockNumber,
+) -> eyre::Result<(H256, Header, StoredBlockBodyIndices)> {
+    let (hash, header, body) = db.view(|tx| {
+        Ok::<_, eyre::Report>((
+            tx.get::<tables::CanonicalHeaders>(number)?,
+            tx.get::<tables::Headers>(number)?,
+            tx.get::<tables::BlockBodyIndices>(number)?,
+        ))
+    })??;
+    let (Some(hash), Some(header), Some(body)) = (hash, header, body) else {
+        eyre::bail!("block {number} is not in the database")
+    };
+    Ok((hash, header, body))
+}
+
+/// Fetches the receipts of a block from the archive node.
+///
+/// The archive client verifies the receipts against the receipts root of the local header.
+/// Returns `None` if the block has no transactions.
+async fn fetch_block_receipts<DB: Database>(
+    db: &DB,
+    client: &ArchiveClient,
+    number: BlockNumber,
+) -> eyre::Result<Option<BlockReceipts>> {
+    let (hash, header, body) = read_block(db, number)?;
+    if body.tx_count == 0 {
+        return Ok(None)
+    }
+
+    let receipts = client.receipts(&header.seal(hash)).await?;
+    eyre::ensure!(
+        receipts.len() as u64 == body.tx_count,
+        "archive node returned {} receipts for block {number} with {} transactions",
+        receipts.len(),
+        body.tx_count
+    );
+
+    Ok(Some(BlockReceipts { number, body, receipts }))
+}
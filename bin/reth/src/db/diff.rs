@@ -17,10 +17,11 @@ use clap::Parser;
 use reth_db::{
     cursor::DbCursorRO, database::Database, open_db_read_only, table::Table, transaction::DbTx,
     AccountChangeSet, AccountHistory, AccountsTrie, BlockBodyIndices, BlockOmmers,
-    BlockWithdrawals, Bytecodes, CanonicalHeaders, DatabaseEnvRO, HashedAccount, HashedStorage,
-    HeaderNumbers, HeaderTD, Headers, PlainAccountState, PlainStorageState, PruneCheckpoints,
-    Receipts, StorageChangeSet, StorageHistory, StoragesTrie, SyncStage, SyncStageProgress, Tables,
-    TransactionBlock, Transactions, TxHashNumber, TxSenders,
+    BlockWithdrawals, Bytecodes, CanonicalHeaders, ChainState, CumulativeGasUsed, DatabaseEnvRO,
+    HashedAccount, HashedStorage, HeaderNumbers, HeaderTD, Headers, PlainAccountState,
+    PlainStorageState, PreimageChangeSet, Preimages, PruneCheckpoints, Receipts, StorageChangeSet,
+    StorageHistory, StoragesTrie, SyncStage, SyncStageProgress, Tables, TransactionBlock,
+    Transactions, TxHashNumber, TxSenders,
 };
 use tracing::info;
 
@@ -140,6 +141,16 @@ impl Command {
                 Tables::PruneCheckpoints => {
                     find_diffs::<PruneCheckpoints>(primary_tx, secondary_tx, output_dir)?
                 }
+                Tables::ChainState => {
+                    find_diffs::<ChainState>(primary_tx, secondary_tx, output_dir)?
+                }
+                Tables::Preimages => find_diffs::<Preimages>(primary_tx, secondary_tx, output_dir)?,
+                Tables::PreimageChangeSet => {
+                    find_diffs::<PreimageChangeSet>(primary_tx, secondary_tx, output_dir)?
+                }
+                Tables::CumulativeGasUsed => {
+                    find_diffs::<CumulativeGasUsed>(primary_tx, secondary_tx, output_dir)?
+                }
             };
         }
 
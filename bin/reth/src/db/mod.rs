@@ -20,12 +20,16 @@ use std::{
     sync::Arc,
 };
 
+mod backfill;
 mod clear;
 mod diff;
 mod get;
 mod list;
+mod snapshot;
+mod trie_stats;
 /// DB List TUI
 mod tui;
+mod verify_root;
 
 /// `reth db` command
 #[derive(Debug, Parser)]
@@ -84,6 +88,14 @@ pub enum Subcommands {
     },
     /// Deletes all table entries
     Clear(clear::Command),
+    /// Reports node count and depth statistics of the account and storage tries
+    TrieStats(trie_stats::Command),
+    /// Backfills pruned receipts from a remote archive node or from peers
+    Backfill(backfill::Command),
+    /// Recomputes the state root of a block and compares it against the header
+    VerifyRoot(verify_root::Command),
+    /// Creates, restores and downloads consistent copies of the database
+    Snapshot(snapshot::Command),
     /// Lists current and local database versions
     Version,
     /// Returns the full database path
@@ -209,6 +221,21 @@ impl Command {
                 let db = open_db(&db_path, self.db.log_level)?;
                 command.execute(&db)?;
             }
+            Subcommands::TrieStats(command) => {
+                let db = open_db_read_only(&db_path, self.db.log_level)?;
+                command.execute(&db)?;
+            }
+            Subcommands::Backfill(command) => {
+                let db = Arc::new(open_db(&db_path, self.db.log_level)?);
+                command.execute(db, self.chain.clone(), data_dir).await?;
+            }
+            Subcommands::VerifyRoot(command) => {
+                let db = open_db_read_only(&db_path, self.db.log_level)?;
+                command.execute(&db)?;
+            }
+            Subcommands::Snapshot(command) => {
+                command.execute(&db_path, self.db.log_level).await?;
+            }
             Subcommands::Version => {
                 let local_db_version = match get_db_version(&db_path) {
                     Ok(version) => Some(version),
@@ -0,0 +1,277 @@
+//! Download of published database snapshots over HTTP(S).
+
+use eyre::WrapErr;
+use hyper::{
+    body::HttpBody,
+    client::HttpConnector,
+    header::{CONTENT_LENGTH, RANGE},
+    Body, Client, Request, StatusCode, Uri,
+};
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+use reth_primitives::{
+    keccak256, recover_signer,
+    tiny_keccak::{Hasher, Keccak},
+    Address, H256,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+use tracing::info;
+
+/// The name of the manifest of a published snapshot.
+pub(crate) const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// The name of the signature of the manifest of a published snapshot.
+pub(crate) const MANIFEST_SIGNATURE_FILE_NAME: &str = "manifest.json.sig";
+
+/// The interval of the download progress logs.
+const PROGRESS_LOG_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The manifest of a published snapshot, listing its files and their content hashes.
+///
+/// The manifest is published next to the files of the snapshot, together with a signature over
+/// the `keccak256` hash of the manifest as a 65 byte `r || s || v` hex string.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct SnapshotManifest {
+    /// The files of the snapshot.
+    pub(crate) files: Vec<SnapshotFile>,
+}
+
+/// A file of a published snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct SnapshotFile {
+    /// The name of the file in the snapshot directory.
+    pub(crate) name: String,
+    /// The size of the file in bytes.
+    pub(crate) size: u64,
+    /// The `keccak256` hash of the content of the file.
+    pub(crate) hash: H256,
+}
+
+impl SnapshotManifest {
+    /// Decodes the manifest and checks that it was signed by `signer`.
+    pub(crate) fn verify(manifest: &[u8], signature: &str, signer: Address) -> eyre::Result<Self> {
+        let signature = hex::decode(signature.trim().trim_start_matches("0x"))
+            .wrap_err("Invalid manifest signature")?;
+        let mut signature: [u8; 65] =
+            signature.try_into().map_err(|_| eyre::eyre!("Manifest signature must be 65 bytes"))?;
+        // accept both the recovery id and the legacy `v` value
+        if signature[64] >= 27 {
+            signature[64] -= 27;
+        }
+
+        let recovered = recover_signer(&signature, keccak256(manifest).as_fixed_bytes())
+            .map_err(|err| eyre::eyre!("Invalid manifest signature: {err}"))?;
+        eyre::ensure!(
+            recovered == signer,
+            "Manifest is signed by {recovered:?}, expected {signer:?}"
+        );
+
+        let manifest: Self = serde_json::from_slice(manifest).wrap_err("Invalid manifest")?;
+        for file in &manifest.files {
+            // the files are written to the download directory by name
+            eyre::ensure!(
+                Path::new(&file.name).file_name().and_then(|name| name.to_str()) ==
+                    Some(file.name.as_str()),
+                "Invalid file name in manifest: {}",
+                file.name
+            );
+        }
+        Ok(manifest)
+    }
+
+    /// Returns the file with the given name.
+    pub(crate) fn file(&self, name: &str) -> Option<&SnapshotFile> {
+        self.files.iter().find(|file| file.name == name)
+    }
+}
+
+/// Downloads the files of a snapshot published at a base URL.
+///
+/// Downloads are resumed with HTTP range requests, so an interrupted download continues where
+/// it stopped when it is started again with the same download directory.
+#[derive(Debug)]
+pub(crate) struct SnapshotDownloader {
+    /// The base URL of the snapshot, without a trailing slash.
+    url: String,
+    client: Client<HttpsConnector<HttpConnector>>,
+}
+
+impl SnapshotDownloader {
+    /// Creates a downloader for the snapshot published at the `http` or `https` `url`.
+    pub(crate) fn new(url: &str) -> eyre::Result<Self> {
+        let uri: Uri = url.parse().wrap_err_with(|| format!("Invalid snapshot URL {url}"))?;
+        eyre::ensure!(
+            matches!(uri.scheme_str(), Some("http" | "https")) && uri.host().is_some(),
+            "Invalid snapshot URL {url}: expected an http or https URL"
+        );
+
+        let connector =
+            HttpsConnectorBuilder::new().with_native_roots().https_or_http().enable_http1().build();
+        Ok(Self {
+            url: url.trim_end_matches('/').to_string(),
+            client: Client::builder().build(connector),
+        })
+    }
+
+    /// Fetches the manifest and its signature.
+    pub(crate) async fn fetch_manifest(&self) -> eyre::Result<(Vec<u8>, String)> {
+        let manifest = self.fetch(MANIFEST_FILE_NAME).await?;
+        let signature = String::from_utf8(self.fetch(MANIFEST_SIGNATURE_FILE_NAME).await?)
+            .wrap_err("Invalid manifest signature")?;
+        Ok((manifest, signature))
+    }
+
+    /// Downloads the file into `dir` and verifies its content hash.
+    ///
+    /// A file that was partially downloaded before is resumed, a file that was fully downloaded
+    /// is only verified. A file that does not match its hash is removed.
+    pub(crate) async fn download(&self, file: &SnapshotFile, dir: &Path) -> eyre::Result<PathBuf> {
+        let path = dir.join(&file.name);
+        let mut len = fs::metadata(&path).map(|metadata| metadata.len()).unwrap_or_default();
+        if len > file.size {
+            fs::remove_file(&path)?;
+            len = 0;
+        }
+
+        if len < file.size {
+            info!(target: "reth::cli", file = %file.name, offset = len, size = file.size, "Downloading snapshot file");
+            let request = Request::get(self.uri(&file.name)?)
+                .header(RANGE, format!("bytes={len}-"))
+                .body(Body::empty())
+                .expect("valid request");
+            let mut response = self.client.request(request).await?;
+            let mut out = match response.status() {
+                StatusCode::PARTIAL_CONTENT => {
+                    OpenOptions::new().append(true).create(true).open(&path)?
+                }
+                // the server does not support ranges, start over
+                StatusCode::OK => {
+                    len = 0;
+                    File::create(&path)?
+                }
+                status => eyre::bail!("Failed to download {}: {status}", file.name),
+            };
+
+            let mut last_log = Instant::now();
+            while let Some(chunk) = response.body_mut().data().await {
+                let chunk = chunk?;
+                out.write_all(&chunk)?;
+                len += chunk.len() as u64;
+                if last_log.elapsed() >= PROGRESS_LOG_INTERVAL {
+                    info!(target: "reth::cli", file = %file.name, progress = %format!("{:.2}%", len as f64 / file.size as f64 * 100.0), "Downloading snapshot file");
+                    last_log = Instant::now();
+                }
+            }
+            out.sync_all()?;
+        }
+
+        info!(target: "reth::cli", file = %file.name, "Verifying snapshot file");
+        let hash = hash_file(&path)?;
+        if hash != file.hash {
+            fs::remove_file(&path)?;
+            eyre::bail!(
+                "Content hash mismatch of {}: expected {:?}, got {hash:?}",
+                file.name,
+                file.hash
+            );
+        }
+        Ok(path)
+    }
+
+    /// Fetches a small file of the snapshot into memory.
+    async fn fetch(&self, name: &str) -> eyre::Result<Vec<u8>> {
+        let response = self.client.get(self.uri(name)?).await?;
+        eyre::ensure!(
+            response.status().is_success(),
+            "Failed to fetch {name}: {}",
+            response.status()
+        );
+        if let Some(len) = response.headers().get(CONTENT_LENGTH) {
+            eyre::ensure!(
+                len.to_str().ok().and_then(|len| len.parse::<u64>().ok()).unwrap_or_default() <=
+                    1024 * 1024,
+                "{name} is too large"
+            );
+        }
+        Ok(hyper::body::to_bytes(response.into_body()).await?.to_vec())
+    }
+
+    fn uri(&self, name: &str) -> eyre::Result<Uri> {
+        let url = format!("{}/{name}", self.url);
+        url.parse().wrap_err_with(|| format!("Invalid snapshot URL {url}"))
+    }
+}
+
+/// Returns the `keccak256` hash of the content of the file.
+pub(crate) fn hash_file(path: &Path) -> io::Result<H256> {
+    let mut file = File::open(path)?;
+    let mut hasher = Keccak::v256();
+    let mut buf = vec![0; 1024 * 1024];
+    loop {
+        let read = file.read(&mut buf)?;
+        if read == 0 {
+            break
+        }
+        hasher.update(&buf[..read]);
+    }
+    let mut hash = H256::zero();
+    hasher.finalize(hash.as_mut());
+    Ok(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{public_key_to_address, sign_message};
+    use secp256k1::{rand::thread_rng, SecretKey, SECP256K1};
+
+    #[test]
+    fn verify_manifest() {
+        let secret = SecretKey::new(&mut thread_rng());
+        let signer = public_key_to_address(secret.public_key(SECP256K1));
+        let manifest = SnapshotManifest {
+            files: vec![SnapshotFile {
+                name: "mdbx.dat".to_string(),
+                size: 3,
+                hash: keccak256(b"abc"),
+            }],
+        };
+        let encoded = serde_json::to_vec(&manifest).unwrap();
+        let signature =
+            sign_message(H256::from_slice(&secret.secret_bytes()[..]), keccak256(&encoded))
+                .unwrap();
+        let signature = hex::encode(signature.to_bytes());
+
+        assert_eq!(SnapshotManifest::verify(&encoded, &signature, signer).unwrap(), manifest);
+        assert!(SnapshotManifest::verify(&encoded, &signature, Address::random()).is_err());
+
+        // a manifest that was changed after signing is rejected
+        let mut tampered = manifest.clone();
+        tampered.files[0].size = 4;
+        let tampered = serde_json::to_vec(&tampered).unwrap();
+        assert!(SnapshotManifest::verify(&tampered, &signature, signer).is_err());
+
+        // files outside of the download directory are rejected
+        let mut escaping = manifest;
+        escaping.files[0].name = "../mdbx.dat".to_string();
+        let escaping = serde_json::to_vec(&escaping).unwrap();
+        let signature =
+            sign_message(H256::from_slice(&secret.secret_bytes()[..]), keccak256(&escaping))
+                .unwrap();
+        let signature = hex::encode(signature.to_bytes());
+        assert!(SnapshotManifest::verify(&escaping, &signature, signer).is_err());
+    }
+
+    #[test]
+    fn hash_snapshot_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mdbx.dat");
+        fs::write(&path, b"abc").unwrap();
+        assert_eq!(hash_file(&path).unwrap(), keccak256(b"abc"));
+    }
+}
@@ -0,0 +1,316 @@
+use clap::{Parser, Subcommand};
+use download::{SnapshotDownloader, SnapshotManifest};
+use eyre::WrapErr;
+use reth_db::{
+    mdbx::{Environment, EnvironmentFlags, Error as MdbxError, Mode, NoWriteMap},
+    open_db_read_only,
+    version::{check_db_version_file, db_version_file_path, DB_VERSION_FILE_NAME},
+    Tables,
+};
+use reth_interfaces::db::LogLevel;
+use reth_primitives::{
+    tiny_keccak::{Hasher, Keccak},
+    Address, H256,
+};
+use std::{
+    fs::{self, File},
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+};
+use tracing::info;
+
+mod download;
+
+/// The name of the MDBX data file in the database directory.
+const MDBX_DATA_FILE_NAME: &str = "mdbx.dat";
+
+/// The name of the MDBX lock file in the database directory.
+const MDBX_LOCK_FILE_NAME: &str = "mdbx.lck";
+
+/// The arguments for the `reth db snapshot` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Subcommand, Debug)]
+/// `reth db snapshot` subcommands
+pub enum Subcommands {
+    /// Copies the database into a new directory.
+    ///
+    /// The copy is made from a single read transaction, so it is consistent and can be made while
+    /// the node is running.
+    Create {
+        /// The directory to create the snapshot in. It must not contain a database yet.
+        dest: PathBuf,
+
+        /// Omits free pages from the snapshot, which makes it smaller but takes longer.
+        #[arg(long)]
+        compact: bool,
+    },
+    /// Replaces the database with a snapshot.
+    ///
+    /// The node must be stopped, the restore fails if the database is in use.
+    Restore {
+        /// The directory of the snapshot.
+        src: PathBuf,
+
+        /// Bypasses the interactive confirmation and replaces the database directly
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Downloads a published snapshot over HTTP(S) and replaces the database with it.
+    ///
+    /// The snapshot is published as a `manifest.json` that lists the `keccak256` hash of every
+    /// file, a `manifest.json.sig` with the signature of the manifest, and the files next to
+    /// them. The hashes are verified after the download and again while the files are copied
+    /// into the database directory. Interrupted downloads are resumed when the command is run
+    /// again. The node continues syncing from the block of the snapshot when it is started.
+    ///
+    /// The node must be stopped, the restore fails if the database is in use.
+    Download {
+        /// The base URL of the snapshot.
+        url: String,
+
+        /// The address that must have signed the manifest of the snapshot.
+        #[arg(long)]
+        signer: Address,
+
+        /// The directory to download the snapshot into.
+        ///
+        /// Defaults to `snapshot-download` next to the database directory.
+        #[arg(long, value_name = "PATH")]
+        dir: Option<PathBuf>,
+
+        /// Bypasses the interactive confirmation and replaces the database directly
+        #[arg(short, long)]
+        force: bool,
+    },
+}
+
+impl Command {
+    /// Execute `db snapshot` command
+    pub async fn execute(self, db_path: &Path, log_level: Option<LogLevel>) -> eyre::Result<()> {
+        match self.command {
+            Subcommands::Create { dest, compact } => {
+                let db = open_db_read_only(db_path, log_level)?;
+                let data_file = dest.join(MDBX_DATA_FILE_NAME);
+                eyre::ensure!(
+                    !data_file.exists(),
+                    "{} already contains a database",
+                    dest.display()
+                );
+                fs::create_dir_all(&dest)
+                    .wrap_err_with(|| format!("Could not create directory {}", dest.display()))?;
+
+                info!(target: "reth::cli", dest = %dest.display(), compact, "Creating database snapshot");
+                db.inner.copy(&data_file, compact).wrap_err("Could not copy the database")?;
+                fs::copy(db_version_file_path(db_path), db_version_file_path(&dest))
+                    .wrap_err("Could not copy the database version file")?;
+                info!(target: "reth::cli", dest = %dest.display(), "Database snapshot created");
+            }
+            Subcommands::Restore { src, force } => {
+                let data_file = src.join(MDBX_DATA_FILE_NAME);
+                eyre::ensure!(data_file.exists(), "{} is not a database snapshot", src.display());
+                check_db_version_file(&src)?;
+
+                if force || confirm_restore(db_path, &src.display().to_string()) {
+                    restore(&src, db_path, None)?;
+                }
+            }
+            Subcommands::Download { url, signer, dir, force } => {
+                let dir = dir.unwrap_or_else(|| db_path.with_file_name("snapshot-download"));
+                let downloader = SnapshotDownloader::new(&url)?;
+
+                let (manifest, signature) = downloader.fetch_manifest().await?;
+                let manifest = SnapshotManifest::verify(&manifest, &signature, signer)?;
+                for name in [MDBX_DATA_FILE_NAME, DB_VERSION_FILE_NAME] {
+                    eyre::ensure!(
+                        manifest.file(name).is_some(),
+                        "The manifest does not contain {name}"
+                    );
+                }
+                fs::create_dir_all(&dir)
+                    .wrap_err_with(|| format!("Could not create directory {}", dir.display()))?;
+
+                for file in &manifest.files {
+                    downloader.download(file, &dir).await?;
+                }
+                check_db_version_file(&dir)?;
+                info!(target: "reth::cli", dir = %dir.display(), "Database snapshot downloaded");
+
+                if force || confirm_restore(db_path, &url) {
+                    restore(&dir, db_path, Some(&manifest))?;
+                    fs::remove_dir_all(&dir).wrap_err_with(|| {
+                        format!("Could not remove download directory {}", dir.display())
+                    })?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Asks for confirmation to replace the database with a snapshot.
+fn confirm_restore(db_path: &Path, snapshot: &str) -> bool {
+    print!("Are you sure you want to replace the database at {db_path:?} with the snapshot at {snapshot:?}? The node must be stopped. (y/N): ");
+    // Flush the buffer to ensure the message is printed immediately
+    io::stdout().flush().unwrap();
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).expect("Failed to read line");
+
+    if !input.trim().eq_ignore_ascii_case("y") {
+        println!("Database restore aborted!");
+        return false
+    }
+    true
+}
+
+/// Replaces the database with the snapshot in `src`.
+///
+/// If a manifest is given, the copied files must match its hashes, otherwise the database is left
+/// intact.
+///
+/// The database is locked while it is replaced, so this fails if the database is used by another
+/// process.
+fn restore(src: &Path, db_path: &Path, manifest: Option<&SnapshotManifest>) -> eyre::Result<()> {
+    fs::create_dir_all(db_path)
+        .wrap_err_with(|| format!("Could not create database directory {}", db_path.display()))?;
+    let lock = lock_database(db_path)?;
+
+    info!(target: "reth::cli", src = %src.display(), "Restoring database snapshot");
+    // copy next to the database first, so a failed copy leaves the database intact
+    let (data_file, data_hash) =
+        copy_to_temp(&src.join(MDBX_DATA_FILE_NAME), &db_path.join(MDBX_DATA_FILE_NAME))
+            .wrap_err("Could not copy the snapshot")?;
+    let (version_file, version_hash) =
+        copy_to_temp(&db_version_file_path(src), &db_version_file_path(db_path))
+            .wrap_err("Could not copy the database version file")?;
+    if let Some(manifest) = manifest {
+        for (name, copy, hash) in [
+            (MDBX_DATA_FILE_NAME, &data_file, data_hash),
+            (DB_VERSION_FILE_NAME, &version_file, version_hash),
+        ] {
+            let expected = manifest.file(name).map(|file| file.hash);
+            if expected != Some(hash) {
+                let _ = fs::remove_file(&data_file);
+                let _ = fs::remove_file(&version_file);
+                eyre::bail!(
+                    "Content hash mismatch of {}: expected {expected:?}, got {hash:?}",
+                    copy.display()
+                );
+            }
+        }
+    }
+
+    // the lock file is recreated when the database is opened
+    if let Err(err) = fs::remove_file(db_path.join(MDBX_LOCK_FILE_NAME)) {
+        if err.kind() != io::ErrorKind::NotFound {
+            return Err(err).wrap_err("Could not remove the database lock file")
+        }
+    }
+    fs::rename(&data_file, db_path.join(MDBX_DATA_FILE_NAME))
+        .wrap_err("Could not replace the database")?;
+    fs::rename(&version_file, db_version_file_path(db_path))
+        .wrap_err("Could not replace the database version file")?;
+    sync_dir(db_path).wrap_err("Could not sync the database directory")?;
+    drop(lock);
+
+    info!(target: "reth::cli", "Database snapshot restored");
+    Ok(())
+}
+
+/// Opens the database exclusively, which fails if it is used by another process.
+///
+/// The database stays locked until the returned environment is dropped. Returns `None` if there
+/// is no database yet.
+fn lock_database(db_path: &Path) -> eyre::Result<Option<Environment<NoWriteMap>>> {
+    if !db_path.join(MDBX_DATA_FILE_NAME).exists() {
+        return Ok(None)
+    }
+
+    let mut env = Environment::new();
+    env.set_max_dbs(Tables::ALL.len());
+    env.set_flags(EnvironmentFlags { mode: Mode::ReadOnly, exclusive: true, ..Default::default() });
+    match env.open(db_path) {
+        Ok(env) => Ok(Some(env)),
+        Err(MdbxError::Busy) => {
+            eyre::bail!("The database at {} is in use, the node must be stopped", db_path.display())
+        }
+        Err(err) => Err(err).wrap_err("Could not lock the database"),
+    }
+}
+
+/// Copies `src` to a temporary file next to `dest` and syncs it to disk.
+///
+/// Returns the path of the temporary file, which can then be renamed to `dest`, and the
+/// `keccak256` hash of the copied content.
+fn copy_to_temp(src: &Path, dest: &Path) -> io::Result<(PathBuf, H256)> {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".restore");
+    let tmp_file = dest.with_file_name(name);
+
+    let mut reader = File::open(src)?;
+    let mut writer = File::create(&tmp_file)?;
+    let mut hasher = Keccak::v256();
+    let mut buf = vec![0; 1024 * 1024];
+    loop {
+        let read = reader.read(&mut buf)?;
+        if read == 0 {
+            break
+        }
+        hasher.update(&buf[..read]);
+        writer.write_all(&buf[..read])?;
+    }
+    writer.sync_all()?;
+
+    let mut hash = H256::zero();
+    hasher.finalize(hash.as_mut());
+    Ok((tmp_file, hash))
+}
+
+/// Syncs the entries of the directory to disk, so renames in the directory are durable.
+fn sync_dir(dir: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    File::open(dir)?.sync_all()?;
+    #[cfg(not(unix))]
+    let _ = dir;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use download::SnapshotFile;
+    use reth_primitives::keccak256;
+
+    #[test]
+    fn restore_verifies_manifest() {
+        let src = tempfile::tempdir().unwrap();
+        let db = tempfile::tempdir().unwrap();
+        fs::write(src.path().join(MDBX_DATA_FILE_NAME), b"abc").unwrap();
+        fs::write(db_version_file_path(src.path()), b"1").unwrap();
+
+        let file = |name: &str, content: &[u8]| SnapshotFile {
+            name: name.to_string(),
+            size: content.len() as u64,
+            hash: keccak256(content),
+        };
+        let tampered = SnapshotManifest {
+            files: vec![file(MDBX_DATA_FILE_NAME, b"abd"), file(DB_VERSION_FILE_NAME, b"1")],
+        };
+        assert!(restore(src.path(), db.path(), Some(&tampered)).is_err());
+        assert!(!db.path().join(MDBX_DATA_FILE_NAME).exists());
+        assert_eq!(fs::read_dir(db.path()).unwrap().count(), 0);
+
+        let manifest = SnapshotManifest {
+            files: vec![file(MDBX_DATA_FILE_NAME, b"abc"), file(DB_VERSION_FILE_NAME, b"1")],
+        };
+        restore(src.path(), db.path(), Some(&manifest)).unwrap();
+        assert_eq!(fs::read(db.path().join(MDBX_DATA_FILE_NAME)).unwrap(), b"abc");
+        assert_eq!(fs::read(db_version_file_path(db.path())).unwrap(), b"1");
+    }
+}
@@ -0,0 +1,131 @@
+use clap::Parser;
+use comfy_table::{presets::ASCII_MARKDOWN, Table};
+use reth_db::{cursor::DbCursorRO, database::Database, tables, transaction::DbTx};
+use reth_primitives::{trie::BranchNodeCompact, H256};
+use std::collections::BTreeMap;
+
+/// The arguments for the `reth db trie-stats` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The number of largest storage tries to list.
+    #[arg(long, default_value = "10")]
+    top: usize,
+}
+
+impl Command {
+    /// Execute `db trie-stats` command
+    pub fn execute<DB: Database>(self, db: &DB) -> eyre::Result<()> {
+        let tx = db.tx()?;
+
+        let mut accounts = TrieStats::default();
+        for entry in tx.cursor_read::<tables::AccountsTrie>()?.walk(None)? {
+            let (nibbles, node) = entry?;
+            accounts.record(nibbles.inner.len(), &node);
+        }
+
+        let mut storages = TrieStats::default();
+        let mut storage_tries = BTreeMap::<H256, usize>::new();
+        for entry in tx.cursor_read::<tables::StoragesTrie>()?.walk(None)? {
+            let (hashed_address, entry) = entry?;
+            storages.record(entry.nibbles.inner.len(), &entry.node);
+            *storage_tries.entry(hashed_address).or_default() += 1;
+        }
+
+        let mut table = Table::new();
+        table.load_preset(ASCII_MARKDOWN);
+        table.set_header(["", "Accounts trie", "Storage tries"]);
+        table.add_row([
+            "Hashed entries".to_string(),
+            tx.entries::<tables::HashedAccount>()?.to_string(),
+            tx.entries::<tables::HashedStorage>()?.to_string(),
+        ]);
+        table.add_row(["Tries".to_string(), "1".to_string(), storage_tries.len().to_string()]);
+        table.add_row([
+            "Branch nodes".to_string(),
+            accounts.nodes.to_string(),
+            storages.nodes.to_string(),
+        ]);
+        table.add_row([
+            "Children".to_string(),
+            accounts.children.to_string(),
+            storages.children.to_string(),
+        ]);
+        table.add_row([
+            "Stored hashes".to_string(),
+            accounts.hashes.to_string(),
+            storages.hashes.to_string(),
+        ]);
+        table.add_row([
+            "Max depth".to_string(),
+            accounts.max_depth().to_string(),
+            storages.max_depth().to_string(),
+        ]);
+        table.add_row([
+            "Average depth".to_string(),
+            format!("{:.2}", accounts.average_depth()),
+            format!("{:.2}", storages.average_depth()),
+        ]);
+        println!("{table}");
+
+        let mut depths = Table::new();
+        depths.load_preset(ASCII_MARKDOWN);
+        depths.set_header(["Depth", "Account nodes", "Storage nodes"]);
+        for depth in 0..=accounts.max_depth().max(storages.max_depth()) {
+            depths.add_row([
+                depth.to_string(),
+                accounts.depths.get(&depth).copied().unwrap_or_default().to_string(),
+                storages.depths.get(&depth).copied().unwrap_or_default().to_string(),
+            ]);
+        }
+        println!("\n{depths}");
+
+        if self.top > 0 && !storage_tries.is_empty() {
+            let mut largest = storage_tries.into_iter().collect::<Vec<_>>();
+            largest.sort_unstable_by(|a, b| b.1.cmp(&a.1));
+
+            let mut top = Table::new();
+            top.load_preset(ASCII_MARKDOWN);
+            top.set_header(["Hashed address", "Branch nodes"]);
+            for (hashed_address, nodes) in largest.into_iter().take(self.top) {
+                top.add_row([format!("{hashed_address:?}"), nodes.to_string()]);
+            }
+            println!("\n{top}");
+        }
+
+        Ok(())
+    }
+}
+
+/// Statistics of the branch nodes of one or more tries.
+#[derive(Debug, Default)]
+struct TrieStats {
+    /// The number of branch nodes.
+    nodes: usize,
+    /// The total number of children of all branch nodes.
+    children: usize,
+    /// The total number of stored child hashes.
+    hashes: usize,
+    /// The number of branch nodes per depth, in nibbles.
+    depths: BTreeMap<usize, usize>,
+}
+
+impl TrieStats {
+    fn record(&mut self, depth: usize, node: &BranchNodeCompact) {
+        self.nodes += 1;
+        self.children += node.state_mask.count_ones() as usize;
+        self.hashes += node.hashes.len();
+        *self.depths.entry(depth).or_default() += 1;
+    }
+
+    fn max_depth(&self) -> usize {
+        self.depths.keys().next_back().copied().unwrap_or_default()
+    }
+
+    fn average_depth(&self) -> f64 {
+        if self.nodes == 0 {
+            return 0.
+        }
+        let total = self.depths.iter().map(|(depth, nodes)| depth * nodes).sum::<usize>();
+        total as f64 / self.nodes as f64
+    }
+}
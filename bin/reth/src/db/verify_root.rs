@@ -0,0 +1,81 @@
+use clap::Parser;
+use reth_db::{database::Database, tables, transaction::DbTx};
+use reth_primitives::{stage::StageId, BlockNumber};
+use reth_provider::providers::revert_hashed_state;
+use reth_trie::{hashed_cursor::HashedPostStateCursorFactory, StateRoot};
+use std::time::Instant;
+use tracing::*;
+
+/// The arguments for the `reth db verify-root` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The block to verify the state root of.
+    ///
+    /// Defaults to the block of the last merkle stage checkpoint.
+    #[arg(long)]
+    block: Option<BlockNumber>,
+
+    /// Recompute the root from the hashed state only, ignoring the stored intermediate trie
+    /// nodes.
+    ///
+    /// This is much slower, since the whole account and storage tries are rebuilt.
+    #[arg(long)]
+    full: bool,
+}
+
+impl Command {
+    /// Execute `db verify-root` command
+    pub fn execute<DB: Database>(self, db: &DB) -> eyre::Result<()> {
+        self.verify(&db.tx()?)
+    }
+
+    fn verify<'a, TX: DbTx<'a>>(&self, tx: &TX) -> eyre::Result<()> {
+        let tip = tx
+            .get::<tables::SyncStage>(StageId::MerkleExecute.to_string())?
+            .map(|checkpoint| checkpoint.block_number)
+            .unwrap_or_default();
+        let block = self.block.unwrap_or(tip);
+        eyre::ensure!(
+            block <= tip,
+            "block {block} is above the merkle stage checkpoint {tip}, the hashed state is not available yet"
+        );
+
+        let header = tx
+            .get::<tables::Headers>(block)?
+            .ok_or_else(|| eyre::eyre!("header of block {block} not found"))?;
+
+        info!(target: "reth::cli", block, tip, full = self.full, "Computing state root");
+        let start = Instant::now();
+        let root = if block == tip {
+            if self.full {
+                StateRoot::new(tx).with_stored_trie_ignored().root()?
+            } else {
+                StateRoot::new(tx).root()?
+            }
+        } else {
+            let reverts = revert_hashed_state(tx, block + 1..=tip)?;
+            let (account_prefix_set, storage_prefix_set) = reverts.construct_prefix_sets();
+            let hashed_cursor_factory = HashedPostStateCursorFactory::new(tx, &reverts);
+            let state_root = StateRoot::new(tx)
+                .with_hashed_cursor_factory(&hashed_cursor_factory)
+                .with_changed_account_prefixes(account_prefix_set)
+                .with_changed_storage_prefixes(storage_prefix_set);
+            if self.full {
+                state_root.with_stored_trie_ignored().root()?
+            } else {
+                state_root.root()?
+            }
+        };
+        let elapsed = start.elapsed();
+
+        if root == header.state_root {
+            println!("State root of block {block} matches the header: {root:?} ({elapsed:?})");
+            Ok(())
+        } else {
+            eyre::bail!(
+                "state root mismatch at block {block}: computed {root:?}, header {:?}",
+                header.state_root
+            )
+        }
+    }
+}
@@ -0,0 +1,252 @@
+//! Command for building a payload without submitting it.
+use crate::{
+    args::{utils::genesis_value_parser, DatabaseArgs},
+    dirs::{DataDirPath, MaybePlatformPath},
+    runner::CliContext,
+};
+use clap::Parser;
+use futures::{stream, StreamExt};
+use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
+use reth_basic_payload_builder::{
+    BuildArguments, BuildOutcome, Cancelled, PayloadBuilder, PayloadConfig,
+};
+use reth_blockchain_tree::noop::NoopBlockchainTree;
+use reth_db::open_db_read_only;
+use reth_payload_builder::{database::CachedReads, PayloadBuilderAttributes};
+use reth_primitives::{
+    Address, BlockHashOrNumber, Bytes, ChainSpec, TransactionSigned, H256, U256, U64,
+};
+use reth_provider::{BlockNumReader, BlockReader, BlockchainProvider, ProviderFactory};
+use reth_rlp::Decodable;
+use reth_rpc_api::{DebugApiClient, TxPoolApiClient};
+use reth_rpc_types::engine::PayloadAttributes;
+use reth_transaction_pool::{
+    blobstore::InMemoryBlobStore, EthPooledTransaction, PoolConfig, TransactionPool,
+    TransactionValidationTaskExecutor,
+};
+use std::{sync::Arc, time::Duration};
+use tokio::sync::broadcast;
+use tracing::*;
+
+/// The number of transactions that are fetched from the node concurrently.
+const FETCH_CONCURRENCY: usize = 16;
+
+/// `reth debug build-block` command
+///
+/// Builds a payload on top of a parent block with the transactions of a node's pool and prints a
+/// summary of the block, without submitting it. The block is built with the same payload builder
+/// as the node, which makes this useful for debugging the builder and the health of the pool.
+///
+/// The pending transactions are fetched from a running node over JSON-RPC, which must have the
+/// `txpool` and `debug` namespaces enabled. They are validated against the latest state of the
+/// local database, so the parent should be the tip of the database.
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// The path to the data dir for all reth files and subdirectories.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/` or `$HOME/.local/share/reth/`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/`
+    /// - macOS: `$HOME/Library/Application Support/reth/`
+    #[arg(long, value_name = "DATA_DIR", verbatim_doc_comment, default_value_t)]
+    datadir: MaybePlatformPath<DataDirPath>,
+
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    ///
+    /// Built-in chains:
+    /// - mainnet
+    /// - goerli
+    /// - sepolia
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        verbatim_doc_comment,
+        default_value = "mainnet",
+        value_parser = genesis_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    #[clap(flatten)]
+    db: DatabaseArgs,
+
+    /// The HTTP RPC endpoint of the node whose pending transactions are included.
+    #[arg(long, value_name = "URL", default_value = "http://localhost:8545")]
+    rpc_url: String,
+
+    /// The hash or number of the parent block.
+    ///
+    /// Defaults to the tip of the database.
+    #[arg(long)]
+    parent: Option<BlockHashOrNumber>,
+
+    /// The timestamp of the block.
+    ///
+    /// Defaults to 12 seconds after the parent.
+    #[arg(long)]
+    timestamp: Option<u64>,
+
+    /// The recipient of the priority fees.
+    #[arg(long, default_value_t)]
+    fee_recipient: Address,
+
+    /// The `prevRandao` value of the block.
+    #[arg(long, default_value_t)]
+    prev_randao: H256,
+
+    /// The extra data of the block.
+    #[arg(long, default_value_t)]
+    extra_data: Bytes,
+
+    /// Print every included transaction.
+    #[arg(long)]
+    verbose: bool,
+}
+
+impl Command {
+    /// Execute `debug build-block` command
+    pub async fn execute(self, ctx: CliContext) -> eyre::Result<()> {
+        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+        let db_path = data_dir.db_path();
+        let db = Arc::new(open_db_read_only(&db_path, self.db.log_level)?);
+
+        let factory = ProviderFactory::new(db, Arc::clone(&self.chain));
+        let (canon_state_notification_sender, _receiver) = broadcast::channel(1);
+        let provider = BlockchainProvider::new(
+            factory,
+            NoopBlockchainTree::new(canon_state_notification_sender),
+        )?;
+
+        let parent = match self.parent {
+            Some(parent) => provider.block(parent)?,
+            None => provider.block(provider.best_block_number()?.into())?,
+        }
+        .ok_or_else(|| eyre::eyre!("parent block not found"))?
+        .seal_slow();
+        let timestamp = self.timestamp.unwrap_or(parent.timestamp + 12);
+
+        // collect the pending transactions of the node into a local pool
+        let blob_store = InMemoryBlobStore::default();
+        let validator = TransactionValidationTaskExecutor::eth_builder(Arc::clone(&self.chain))
+            .build_with_tasks(provider.clone(), ctx.task_executor.clone(), blob_store.clone());
+        let pool =
+            reth_transaction_pool::Pool::eth_pool(validator, blob_store, PoolConfig::default());
+
+        let client = HttpClientBuilder::default()
+            .request_timeout(Duration::from_secs(60))
+            .build(&self.rpc_url)?;
+        let transactions = fetch_pending_transactions(&client).await?;
+        let fetched = transactions.len();
+        let results = pool.add_external_transactions(transactions).await?;
+        let mut rejected = 0;
+        for err in results.into_iter().filter_map(Result::err) {
+            rejected += 1;
+            debug!(target: "reth::cli", hash = ?err.hash(), %err, "Transaction rejected by the pool");
+        }
+        let pool_size = pool.pool_size();
+        info!(
+            target: "reth::cli",
+            fetched,
+            rejected,
+            pending = pool_size.pending,
+            queued = pool_size.queued + pool_size.basefee,
+            "Collected pending transactions"
+        );
+
+        // build the payload like the payload builder service of the node
+        let attributes = PayloadAttributes {
+            timestamp: U64::from(timestamp),
+            prev_randao: self.prev_randao,
+            suggested_fee_recipient: self.fee_recipient,
+            withdrawals: self.chain.is_shanghai_activated_at_timestamp(timestamp).then(Vec::new),
+            parent_beacon_block_root: self
+                .chain
+                .is_cancun_activated_at_timestamp(timestamp)
+                .then(H256::zero),
+        };
+        let attributes = PayloadBuilderAttributes::new(parent.hash, attributes);
+        let (initialized_cfg, initialized_block_env) =
+            attributes.cfg_and_block_env(&self.chain, &parent);
+        let config = PayloadConfig {
+            initialized_block_env,
+            initialized_cfg,
+            parent_block: Arc::new(parent),
+            extra_data: self.extra_data.clone(),
+            attributes,
+            chain_spec: Arc::clone(&self.chain),
+        };
+        let args = BuildArguments {
+            client: provider,
+            pool,
+            cached_reads: CachedReads::default(),
+            config,
+            cancel: Cancelled::default(),
+            best_payload: None,
+        };
+        let payload = match ().try_build(args)? {
+            BuildOutcome::Better { payload, .. } => payload,
+            outcome => eyre::bail!("payload was not built: {outcome:?}"),
+        };
+
+        let block = payload.block();
+        let base_fee = block.base_fee_per_gas.unwrap_or_default();
+        let burnt_fees = U256::from(block.gas_used) * U256::from(base_fee);
+        println!("Block:          #{} {:?}", block.number, block.hash);
+        println!("Parent:         {:?}", block.parent_hash);
+        println!("Transactions:   {} of {fetched} fetched", block.body.len());
+        println!("Gas used:       {} / {}", block.gas_used, block.gas_limit);
+        println!("Base fee:       {base_fee}");
+        println!("Priority fees:  {}", payload.fees());
+        println!("Burnt fees:     {burnt_fees}");
+        if let Some(blob_gas_used) = block.blob_gas_used {
+            println!("Blob gas used:  {blob_gas_used}");
+        }
+        println!("State root:     {:?}", block.state_root);
+        if self.verbose {
+            for (idx, tx) in block.body.iter().enumerate() {
+                println!(
+                    "{idx:>5} {:?} type={} nonce={} gas_limit={} tip={}",
+                    tx.hash,
+                    u8::from(tx.tx_type()),
+                    tx.nonce(),
+                    tx.gas_limit(),
+                    tx.effective_gas_tip(Some(base_fee)).unwrap_or_default(),
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Fetches the pending transactions of the node's pool.
+async fn fetch_pending_transactions(
+    client: &HttpClient,
+) -> eyre::Result<Vec<EthPooledTransaction>> {
+    let content = TxPoolApiClient::txpool_content(client).await?;
+    let hashes = content
+        .pending
+        .into_values()
+        .flat_map(|txs| txs.into_values().map(|tx| tx.hash))
+        .collect::<Vec<_>>();
+    info!(target: "reth::cli", count = hashes.len(), "Fetching pending transactions");
+
+    let transactions = stream::iter(hashes)
+        .map(|hash| async move {
+            let raw = DebugApiClient::raw_transaction(client, hash).await?;
+            // the transaction left the pool in the meantime
+            if raw.is_empty() {
+                return Ok(None)
+            }
+            let tx = TransactionSigned::decode(&mut raw.as_ref())?
+                .into_ecrecovered()
+                .ok_or_else(|| eyre::eyre!("invalid signature of transaction {hash:?}"))?;
+            eyre::Ok(Some(EthPooledTransaction::new(tx)))
+        })
+        .buffered(FETCH_CONCURRENCY)
+        .collect::<Vec<_>>()
+        .await;
+    transactions.into_iter().filter_map(Result::transpose).collect()
+}
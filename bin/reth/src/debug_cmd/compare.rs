@@ -0,0 +1,307 @@
+//! Command for comparing the local execution of blocks with a remote client.
+use crate::{
+    args::{utils::genesis_value_parser, DatabaseArgs},
+    dirs::{DataDirPath, MaybePlatformPath},
+    utils::db::open_db_read_only,
+};
+use clap::Parser;
+use jsonrpsee::http_client::HttpClientBuilder;
+use reth_db::{
+    cursor::DbCursorRO,
+    models::{AccountBeforeTx, BlockNumberAddress},
+    tables,
+    transaction::DbTx,
+};
+use reth_primitives::{
+    proofs::calculate_receipt_root, stage::StageId, Address, BlockNumber, BlockNumberOrTag,
+    ChainSpec, Receipt, ReceiptWithBloom, SealedBlock, H256, U256,
+};
+use reth_provider::{
+    post_state::StorageChangeset, BlockReader, ExecutorFactory, HeaderProvider, PostState,
+    ProviderFactory, StageCheckpointReader,
+};
+use reth_rpc_api::EthApiClient;
+use reth_rpc_types::{RichBlock, TransactionReceipt};
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt,
+    sync::Arc,
+    time::Duration,
+};
+use tracing::*;
+
+/// `reth debug compare` command
+///
+/// Re-executes a range of historical blocks on top of the state before the first block and
+/// compares the results with the responses of a remote client, stopping at the first divergence.
+///
+/// For every block, the block hash, the gas used, the status and the logs of every transaction, the
+/// receipts root and the state root of the local execution are compared with the remote block and
+/// its receipts. The remote client must support `eth_getBlockReceipts`.
+///
+/// The state roots are computed on top of the hashed state of the database, reverted to the state
+/// before the first block with the changesets. Comparing blocks far behind the tip therefore reads
+/// all changesets after them.
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// The path to the data dir for all reth files and subdirectories.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/` or `$HOME/.local/share/reth/`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/`
+    /// - macOS: `$HOME/Library/Application Support/reth/`
+    #[arg(long, value_name = "DATA_DIR", verbatim_doc_comment, default_value_t)]
+    datadir: MaybePlatformPath<DataDirPath>,
+
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    ///
+    /// Built-in chains:
+    /// - mainnet
+    /// - goerli
+    /// - sepolia
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        verbatim_doc_comment,
+        default_value = "mainnet",
+        value_parser = genesis_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    #[clap(flatten)]
+    db: DatabaseArgs,
+
+    /// The HTTP RPC endpoint of the client to compare with.
+    #[arg(long, value_name = "URL")]
+    remote: String,
+
+    /// The first block to compare.
+    #[arg(long)]
+    from: BlockNumber,
+
+    /// The last block to compare (inclusive).
+    #[arg(long)]
+    to: BlockNumber,
+}
+
+impl Command {
+    /// Execute `debug compare` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        eyre::ensure!(self.from > 0, "cannot execute the genesis block");
+        eyre::ensure!(self.from <= self.to, "invalid block range {}..={}", self.from, self.to);
+
+        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+        let db = open_db_read_only(&data_dir.db_path(), self.db.log_level)?;
+        let factory = ProviderFactory::new(&db, self.chain.clone());
+        let provider = factory.provider()?;
+
+        let executed = provider
+            .get_stage_checkpoint(StageId::Execution)?
+            .map(|checkpoint| checkpoint.block_number)
+            .unwrap_or_default();
+        eyre::ensure!(
+            self.to <= executed,
+            "block {} is not executed yet, the execution checkpoint is at block {executed}",
+            self.to
+        );
+        // the hashed state and the tries are at the merkle checkpoint
+        let merkle = provider
+            .get_stage_checkpoint(StageId::MerkleExecute)?
+            .map(|checkpoint| checkpoint.block_number)
+            .unwrap_or_default();
+        eyre::ensure!(
+            self.from - 1 <= merkle,
+            "the state tries are not computed yet, the merkle checkpoint is at block {merkle}"
+        );
+
+        let client = HttpClientBuilder::default()
+            .request_timeout(Duration::from_secs(60))
+            .build(&self.remote)?;
+
+        let state = factory.history_by_block_number(self.from - 1)?;
+        let mut executor = reth_revm::Factory::new(self.chain.clone()).with_sp(state);
+        let mut td = provider.header_td_by_number(self.from - 1)?.unwrap_or_default();
+        // the state after the last compared block on top of the hashed state of the database
+        let mut overlay = revert_state(provider.tx_ref(), self.from - 1, merkle)?;
+
+        info!(target: "reth::cli", from = self.from, to = self.to, remote = %self.remote, "Comparing blocks");
+        for number in self.from..=self.to {
+            let block = provider
+                .block_with_senders(number)?
+                .ok_or_else(|| eyre::eyre!("block {number} not found"))?;
+            td += block.block.difficulty;
+
+            let (post_state, _) =
+                executor.execute_transactions(&block.block, td, Some(block.senders))?;
+            let post_state = executor.apply_post_block_changes(&block.block, td, post_state)?;
+            let receipts = post_state.receipts(number).to_vec();
+            overlay.extend(post_state);
+            let state_root = overlay.state_root_slow(provider.tx_ref())?;
+
+            let remote_block =
+                EthApiClient::block_by_number(&client, BlockNumberOrTag::Number(number), false)
+                    .await?
+                    .ok_or_else(|| eyre::eyre!("block {number} not found on the remote"))?;
+            let remote_receipts =
+                EthApiClient::block_receipts(&client, BlockNumberOrTag::Number(number))
+                    .await?
+                    .ok_or_else(|| {
+                        eyre::eyre!("receipts of block {number} not found on the remote")
+                    })?;
+
+            let block = block.block.seal_slow();
+            if let Some(divergence) =
+                compare_block(&block, &receipts, state_root, &remote_block, &remote_receipts)
+            {
+                println!("First divergence at block #{number} {:?}", block.hash);
+                println!("{divergence}");
+                eyre::bail!("block {number} diverges from the remote")
+            }
+            debug!(target: "reth::cli", number, "Block matches the remote");
+        }
+
+        println!("Blocks {}..={} match the remote", self.from, self.to);
+        Ok(())
+    }
+}
+
+/// A difference between the local execution of a block and the remote client.
+#[derive(Debug)]
+struct Divergence {
+    /// What diverges.
+    what: String,
+    /// The local value.
+    local: String,
+    /// The remote value.
+    remote: String,
+}
+
+impl Divergence {
+    fn new(what: impl Into<String>, local: impl fmt::Debug, remote: impl fmt::Debug) -> Self {
+        Self { what: what.into(), local: format!("{local:?}"), remote: format!("{remote:?}") }
+    }
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.what)?;
+        writeln!(f, "  local:  {}", self.local)?;
+        write!(f, "  remote: {}", self.remote)
+    }
+}
+
+/// Returns a [PostState] that reverts the state of the database at block `tip` to the state after
+/// block `block`, using the values before the changes of blocks `block + 1..=tip`.
+fn revert_state<'tx, TX: DbTx<'tx>>(
+    tx: &TX,
+    block: BlockNumber,
+    tip: BlockNumber,
+) -> eyre::Result<PostState> {
+    let mut state = PostState::new();
+    if block >= tip {
+        return Ok(state)
+    }
+
+    // the first change of every account and slot holds its value after `block`
+    let mut accounts = HashSet::new();
+    for entry in tx.cursor_read::<tables::AccountChangeSet>()?.walk_range(block + 1..=tip)? {
+        let (_, AccountBeforeTx { address, info }) = entry?;
+        if !accounts.insert(address) {
+            continue
+        }
+        match info {
+            Some(account) => state.create_account(block, address, account),
+            // the account did not exist, neither did its storage
+            None => state.destroy_account(block, address, Default::default()),
+        }
+    }
+
+    let mut storage: BTreeMap<Address, StorageChangeset> = BTreeMap::new();
+    let range = BlockNumberAddress::range(block + 1..=tip);
+    for entry in tx.cursor_read::<tables::StorageChangeSet>()?.walk_range(range)? {
+        let (BlockNumberAddress((_, address)), slot) = entry?;
+        storage
+            .entry(address)
+            .or_default()
+            .entry(U256::from_be_bytes(slot.key.0))
+            .or_insert((U256::ZERO, slot.value));
+    }
+    for (address, changeset) in storage {
+        state.change_storage(block, address, changeset);
+    }
+
+    Ok(state)
+}
+
+/// Compares the locally executed block with the remote block, returning the first divergence.
+///
+/// Transactions are compared before the roots, so a diverging root is reported with the first
+/// diverging transaction.
+fn compare_block(
+    block: &SealedBlock,
+    receipts: &[Receipt],
+    state_root: H256,
+    remote_block: &RichBlock,
+    remote_receipts: &[TransactionReceipt],
+) -> Option<Divergence> {
+    if remote_block.header.hash != Some(block.hash) {
+        return Some(Divergence::new("block hash", block.hash, remote_block.header.hash))
+    }
+    if receipts.len() != remote_receipts.len() {
+        return Some(Divergence::new("receipt count", receipts.len(), remote_receipts.len()))
+    }
+
+    let mut cumulative_gas_used = 0;
+    for (idx, (receipt, remote)) in receipts.iter().zip(remote_receipts).enumerate() {
+        let gas_used = receipt.cumulative_gas_used - cumulative_gas_used;
+        cumulative_gas_used = receipt.cumulative_gas_used;
+
+        let hash = block.body[idx].hash;
+        let what = if remote.gas_used.map(|gas| gas.to::<u64>()) != Some(gas_used) {
+            "gas used"
+        } else if remote.status_code.map(|status| status.to::<u64>() == 1) != Some(receipt.success)
+        {
+            "status"
+        } else if receipt.logs.len() != remote.logs.len() ||
+            receipt.logs.iter().zip(&remote.logs).any(|(log, remote)| {
+                log.address != remote.address ||
+                    log.topics != remote.topics ||
+                    log.data != remote.data
+            })
+        {
+            "logs"
+        } else {
+            continue
+        };
+        let local = serde_json::to_string_pretty(receipt).ok();
+        let remote = serde_json::to_string_pretty(remote).ok();
+        return Some(Divergence {
+            what: format!("{what} of transaction {idx} {hash:?} (local gas used: {gas_used})"),
+            local: local.unwrap_or_default(),
+            remote: remote.unwrap_or_default(),
+        })
+    }
+
+    let receipts_with_bloom =
+        receipts.iter().cloned().map(Into::into).collect::<Vec<ReceiptWithBloom>>();
+    let receipts_root = calculate_receipt_root(&receipts_with_bloom);
+    if receipts_root != remote_block.header.receipts_root {
+        return Some(Divergence::new(
+            "receipts root",
+            receipts_root,
+            remote_block.header.receipts_root,
+        ))
+    }
+    if state_root != remote_block.header.state_root {
+        return Some(Divergence::new("state root", state_root, remote_block.header.state_root))
+    }
+    let local_gas_used = receipts.last().map(|receipt| receipt.cumulative_gas_used);
+    let remote_gas_used = remote_block.header.gas_used.to::<u64>();
+    if local_gas_used.unwrap_or_default() != remote_gas_used {
+        return Some(Divergence::new("block gas used", local_gas_used, remote_gas_used))
+    }
+    None
+}
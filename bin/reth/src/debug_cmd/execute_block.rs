@@ -0,0 +1,238 @@
+//! Command for re-executing a single block and dumping the execution results.
+use crate::{
+    args::{utils::genesis_value_parser, DatabaseArgs},
+    dirs::{DataDirPath, MaybePlatformPath},
+    utils::db::open_db_read_only,
+};
+use clap::Parser;
+use reth_indexer_sink::message::{ReceiptMessage, StateDiffMessage};
+use reth_primitives::{
+    fs, stage::StageId, Block, BlockHashOrNumber, ChainSpec, SealedBlockWithSenders,
+    TransactionSignedEcRecovered, U256,
+};
+use reth_provider::{
+    BlockNumReader, BlockReader, ExecutorFactory, HeaderProvider, ProviderFactory,
+    StageCheckpointReader, StateProvider,
+};
+use reth_revm::{
+    database::{State, SubState},
+    env::{fill_cfg_and_block_env, tx_env_with_recovered},
+    executor::verify_receipt,
+    revm::{
+        primitives::{BlockEnv, CfgEnv, Env, ResultAndState},
+        DatabaseCommit, EVM,
+    },
+    system_calls::pre_block_system_calls,
+    tracing::{TracingInspector, TracingInspectorConfig},
+};
+use reth_rlp::Decodable;
+use reth_rpc_types::{trace::parity::LocalizedTransactionTrace, TransactionInfo};
+use serde::Serialize;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tracing::*;
+
+/// `reth debug execute-block` command
+///
+/// Re-executes a single block on top of the state of its parent, without writing anything to the
+/// database, and dumps the receipts, the state diff and optionally the traces of the block into
+/// JSON files.
+///
+/// The block is either read from the database or decoded from a file with the hex encoded RLP of
+/// the block, for example a block that was rejected by the node. Mismatches with the receipts root,
+/// the logs bloom and the gas used of the header are reported, the results are dumped regardless.
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// The path to the data dir for all reth files and subdirectories.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/` or `$HOME/.local/share/reth/`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/`
+    /// - macOS: `$HOME/Library/Application Support/reth/`
+    #[arg(long, value_name = "DATA_DIR", verbatim_doc_comment, default_value_t)]
+    datadir: MaybePlatformPath<DataDirPath>,
+
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    ///
+    /// Built-in chains:
+    /// - mainnet
+    /// - goerli
+    /// - sepolia
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        verbatim_doc_comment,
+        default_value = "mainnet",
+        value_parser = genesis_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    #[clap(flatten)]
+    db: DatabaseArgs,
+
+    /// The hash or number of the canonical block to execute.
+    #[arg(long, required_unless_present = "rlp", conflicts_with = "rlp")]
+    block: Option<BlockHashOrNumber>,
+
+    /// The path to a file with the hex encoded RLP of the block to execute.
+    #[arg(long, value_name = "FILE")]
+    rlp: Option<PathBuf>,
+
+    /// The directory the results are written to.
+    ///
+    /// Defaults to `block-<number>` in the current directory.
+    #[arg(long, value_name = "DIR")]
+    output: Option<PathBuf>,
+
+    /// Also trace the transactions and write the parity style traces.
+    ///
+    /// NOTE: This executes the transactions of the block a second time.
+    #[arg(long)]
+    traces: bool,
+}
+
+impl Command {
+    /// Execute `debug execute-block` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+        let db = open_db_read_only(&data_dir.db_path(), self.db.log_level)?;
+        let factory = ProviderFactory::new(&db, self.chain.clone());
+        let provider = factory.provider()?;
+
+        let block = match (&self.block, &self.rlp) {
+            (Some(id), _) => {
+                let number = match id {
+                    BlockHashOrNumber::Hash(hash) => provider
+                        .block_number(*hash)?
+                        .ok_or_else(|| eyre::eyre!("block {hash:?} not found"))?,
+                    BlockHashOrNumber::Number(number) => *number,
+                };
+                let block = provider
+                    .block_with_senders(number)?
+                    .ok_or_else(|| eyre::eyre!("block {number} not found"))?;
+                SealedBlockWithSenders { block: block.block.seal_slow(), senders: block.senders }
+            }
+            (None, Some(path)) => {
+                let raw = fs::read_to_string(path)?;
+                let raw = hex::decode(raw.trim().trim_start_matches("0x"))?;
+                let block = Block::decode(&mut raw.as_slice())?;
+                let senders = block
+                    .senders()
+                    .ok_or_else(|| eyre::eyre!("invalid transaction signature in block"))?;
+                SealedBlockWithSenders { block: block.seal_slow(), senders }
+            }
+            (None, None) => eyre::bail!("either --block or --rlp is required"),
+        };
+        eyre::ensure!(block.number > 0, "cannot execute the genesis block");
+
+        let parent_number = provider
+            .block_number(block.parent_hash)?
+            .ok_or_else(|| eyre::eyre!("parent block {:?} not found", block.parent_hash))?;
+        let executed = provider
+            .get_stage_checkpoint(StageId::Execution)?
+            .map(|checkpoint| checkpoint.block_number)
+            .unwrap_or_default();
+        eyre::ensure!(
+            parent_number <= executed,
+            "parent block {parent_number} is not executed yet, the execution checkpoint is at block {executed}"
+        );
+        let td =
+            provider.header_td_by_number(parent_number)?.unwrap_or_default() + block.difficulty;
+        let state = factory.history_by_block_hash(block.parent_hash)?;
+
+        info!(target: "reth::cli", number = block.number, hash = ?block.hash, "Executing block");
+        let mut executor = reth_revm::Factory::new(self.chain.clone()).with_sp(&state);
+        let unsealed = block.block.clone().unseal();
+        let (post_state, gas_used) =
+            executor.execute_transactions(&unsealed, td, Some(block.senders.clone()))?;
+        let post_state = executor.apply_post_block_changes(&unsealed, td, post_state)?;
+
+        // report all mismatches with the header instead of failing on the first one
+        let mut mismatches = 0;
+        if gas_used != block.gas_used {
+            mismatches += 1;
+            warn!(target: "reth::cli", got = gas_used, expected = block.gas_used, "Gas used mismatch");
+        }
+        if let Err(err) = verify_receipt(
+            block.receipts_root,
+            block.logs_bloom,
+            post_state.receipts(block.number).iter(),
+        ) {
+            mismatches += 1;
+            warn!(target: "reth::cli", %err, "Receipts mismatch");
+        }
+
+        let output =
+            self.output.clone().unwrap_or_else(|| PathBuf::from(format!("block-{}", block.number)));
+        fs::create_dir_all(&output)?;
+
+        let receipts =
+            post_state.receipts(block.number).iter().map(ReceiptMessage::from).collect::<Vec<_>>();
+        write_json(&output.join("receipts.json"), &receipts)?;
+        write_json(&output.join("state-diff.json"), &StateDiffMessage::new(&block, &post_state))?;
+        if self.traces {
+            let traces = self.trace_block(&block, td, &state)?;
+            write_json(&output.join("traces.json"), &traces)?;
+        }
+
+        println!("Block:         #{} {:?}", block.number, block.hash);
+        println!("Transactions:  {}", block.body.len());
+        println!("Gas used:      {gas_used} (header: {})", block.gas_used);
+        println!("Mismatches:    {mismatches}");
+        println!("Output:        {}", output.display());
+
+        Ok(())
+    }
+
+    /// Replays the transactions of the block with a tracer on top of the parent state.
+    fn trace_block(
+        &self,
+        block: &SealedBlockWithSenders,
+        td: U256,
+        state: impl StateProvider,
+    ) -> eyre::Result<Vec<LocalizedTransactionTrace>> {
+        let mut cfg = CfgEnv::default();
+        let mut block_env = BlockEnv::default();
+        fill_cfg_and_block_env(&mut cfg, &mut block_env, &self.chain, &block.header, td);
+
+        let mut db = SubState::new(State::new(state));
+        let changes = pre_block_system_calls(&mut db, &self.chain, &block.header)?;
+        db.commit(changes);
+
+        let mut traces = Vec::new();
+        for (idx, (tx, sender)) in block.body.iter().zip(&block.senders).enumerate() {
+            let tx = TransactionSignedEcRecovered::from_signed_transaction(tx.clone(), *sender);
+            let info = TransactionInfo {
+                hash: Some(tx.hash()),
+                index: Some(idx as u64),
+                block_hash: Some(block.hash),
+                block_number: Some(block.number),
+                base_fee: block.base_fee_per_gas,
+            };
+
+            let env =
+                Env { cfg: cfg.clone(), block: block_env.clone(), tx: tx_env_with_recovered(&tx) };
+            let mut inspector = TracingInspector::new(TracingInspectorConfig::default_parity());
+            let mut evm = EVM::with_env(env);
+            evm.database(&mut db);
+            let ResultAndState { state, .. } = evm
+                .inspect(&mut inspector)
+                .map_err(|err| eyre::eyre!("failed to trace transaction {:?}: {err:?}", tx.hash))?;
+            db.commit(state);
+
+            traces.extend(inspector.into_parity_builder().into_localized_transaction_traces(info));
+        }
+        Ok(traces)
+    }
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> eyre::Result<()> {
+    fs::write(path, serde_json::to_vec_pretty(value)?)?;
+    debug!(target: "reth::cli", path = %path.display(), "Wrote file");
+    Ok(())
+}
@@ -102,9 +102,10 @@ impl Command {
         Client: HeadersClient + BodiesClient + Clone + 'static,
     {
         // building network downloaders using the fetch client
-        let header_downloader = ReverseHeadersDownloaderBuilder::from(config.stages.headers)
-            .build(client.clone(), Arc::clone(&consensus))
-            .into_task_with(task_executor);
+        let header_downloader =
+            ReverseHeadersDownloaderBuilder::from(config.stages.headers.clone())
+                .build(client.clone(), Arc::clone(&consensus))
+                .into_task_with(task_executor);
 
         let body_downloader = BodiesDownloaderBuilder::from(config.stages.bodies)
             .build(client, Arc::clone(&consensus), db.clone())
@@ -126,13 +127,19 @@ impl Command {
                     body_downloader,
                     factory.clone(),
                 )
-                .set(
-                    TotalDifficultyStage::new(consensus)
-                        .with_commit_threshold(stage_conf.total_difficulty.commit_threshold),
-                )
-                .set(SenderRecoveryStage {
-                    commit_threshold: stage_conf.sender_recovery.commit_threshold,
+                .set({
+                    let stage = TotalDifficultyStage::new(consensus)
+                        .with_commit_threshold(stage_conf.total_difficulty.commit_threshold);
+                    if stage_conf.total_difficulty.elide_post_merge {
+                        stage.with_post_merge_elision(&self.chain)
+                    } else {
+                        stage
+                    }
                 })
+                .set(SenderRecoveryStage::new(
+                    stage_conf.sender_recovery.commit_threshold,
+                    config.prune.as_ref().map(|prune| prune.parts.clone()).unwrap_or_default(),
+                ))
                 .set(ExecutionStage::new(
                     factory,
                     ExecutionStageThresholds { max_blocks: None, max_changes: None },
@@ -3,6 +3,9 @@ use clap::{Parser, Subcommand};
 
 use crate::runner::CliContext;
 
+mod build_block;
+mod compare;
+mod execute_block;
 mod execution;
 mod in_memory_merkle;
 mod merkle;
@@ -17,6 +20,12 @@ pub struct Command {
 /// `reth debug` subcommands
 #[derive(Subcommand, Debug)]
 pub enum Subcommands {
+    /// Build a payload on top of a block with the pending transactions of a node.
+    BuildBlock(build_block::Command),
+    /// Re-execute a range of blocks and compare the results with a remote client.
+    Compare(compare::Command),
+    /// Re-execute a single block and dump its receipts, state diff and traces.
+    ExecuteBlock(execute_block::Command),
     /// Debug the roundtrip execution of blocks as well as the generated data.
     Execution(execution::Command),
     /// Debug the clean & incremental state root calculations.
@@ -29,6 +38,9 @@ impl Command {
     /// Execute `debug` command
     pub async fn execute(self, ctx: CliContext) -> eyre::Result<()> {
         match self.command {
+            Subcommands::BuildBlock(command) => command.execute(ctx).await,
+            Subcommands::Compare(command) => command.execute().await,
+            Subcommands::ExecuteBlock(command) => command.execute().await,
             Subcommands::Execution(command) => command.execute(ctx).await,
             Subcommands::Merkle(command) => command.execute(ctx).await,
             Subcommands::InMemoryMerkle(command) => command.execute(ctx).await,
@@ -288,6 +288,11 @@ impl<D> ChainPath<D> {
     pub fn jwt_path(&self) -> PathBuf {
         self.0.join("jwt.hex").into()
     }
+
+    /// Returns the path to the keystore directory for this chain.
+    pub fn keystore_path(&self) -> PathBuf {
+        self.0.join("keystore").into()
+    }
 }
 
 impl<D> AsRef<Path> for ChainPath<D> {
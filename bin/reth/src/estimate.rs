@@ -0,0 +1,418 @@
+//! Disk usage estimation for the configured chain and prune mode.
+//!
+//! The estimates are based on built-in table size models for the known chains and are only meant
+//! to catch volumes that clearly cannot hold the database. They are not exact.
+use crate::{
+    args::{utils::genesis_value_parser, PruningArgs},
+    dirs::{DataDirPath, MaybePlatformPath},
+};
+use clap::Parser;
+use comfy_table::{Cell, Row, Table as ComfyTable};
+use human_bytes::human_bytes;
+use reth_config::{config::PruneConfig, Config};
+use reth_primitives::{BlockNumber, Chain, ChainSpec, PruneMode, PruneModes};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tracing::{info, warn};
+
+const GIGABYTE: u64 = 1024 * 1024 * 1024;
+
+/// If the available space is below this fraction of the remaining required space, the node
+/// refuses to start.
+const REFUSE_THRESHOLD: f64 = 0.5;
+
+/// `reth estimate` command
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// The path to the data dir for all reth files and subdirectories.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/` or `$HOME/.local/share/reth/`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/`
+    /// - macOS: `$HOME/Library/Application Support/reth/`
+    #[arg(long, value_name = "DATA_DIR", verbatim_doc_comment, default_value_t)]
+    datadir: MaybePlatformPath<DataDirPath>,
+
+    /// The path to the configuration file to use.
+    #[arg(long, value_name = "FILE", verbatim_doc_comment)]
+    config: Option<PathBuf>,
+
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    ///
+    /// Built-in chains:
+    /// - mainnet
+    /// - goerli
+    /// - sepolia
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        verbatim_doc_comment,
+        default_value = "mainnet",
+        value_parser = genesis_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    /// All pruning related arguments
+    #[clap(flatten)]
+    pruning: PruningArgs,
+}
+
+impl Command {
+    /// Execute `estimate` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+        let config_path = self.config.clone().unwrap_or(data_dir.config_path());
+        let config: Config = confy::load_path(&config_path).unwrap_or_default();
+
+        let prune_config =
+            self.pruning.prune_config(Arc::clone(&self.chain))?.or(config.prune.clone());
+
+        let Some(estimate) = DiskUsageEstimate::new(self.chain.chain, prune_config.as_ref()) else {
+            eyre::bail!("No disk usage model available for chain {}", self.chain.chain)
+        };
+
+        let mut table = ComfyTable::new();
+        table.load_preset(comfy_table::presets::ASCII_MARKDOWN);
+        table.set_header(["Segment", "Estimated Size"]);
+        for (segment, size) in estimate.segments() {
+            let mut row = Row::new();
+            row.add_cell(Cell::new(segment)).add_cell(Cell::new(human_bytes(size as f64)));
+            table.add_row(row);
+        }
+        let mut row = Row::new();
+        row.add_cell(Cell::new("Total")).add_cell(Cell::new(human_bytes(estimate.total() as f64)));
+        table.add_row(row);
+        println!("Estimated disk usage at block {}:", estimate.reference_block);
+        println!("{table}");
+
+        let db_path = data_dir.db_path();
+        let used = directory_size(&db_path).unwrap_or_default();
+        match available_space(data_dir.as_ref()) {
+            Some(available) => {
+                println!(
+                    "Database size: {}, available space: {}",
+                    human_bytes(used as f64),
+                    human_bytes(available as f64)
+                );
+                match estimate.check(used, available) {
+                    DiskSpaceCheck::Sufficient => println!("The volume can hold the database."),
+                    DiskSpaceCheck::Low { missing } | DiskSpaceCheck::Insufficient { missing } => {
+                        println!(
+                            "The volume is {} short of the estimated size.",
+                            human_bytes(missing as f64)
+                        )
+                    }
+                }
+            }
+            None => println!("Unable to determine available space on this platform."),
+        }
+
+        Ok(())
+    }
+}
+
+/// Built-in table size model of a chain at a reference block.
+///
+/// All sizes are in bytes for an archive node synced up to `reference_block`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainSizeModel {
+    /// The block number the sizes were sampled at.
+    pub reference_block: BlockNumber,
+    /// Headers, canonical hashes and total difficulties.
+    pub headers: u64,
+    /// Block bodies, ommers, withdrawals and transactions.
+    pub transactions: u64,
+    /// Transaction senders.
+    pub senders: u64,
+    /// Transaction hash to number lookup.
+    pub transaction_lookup: u64,
+    /// Receipts.
+    pub receipts: u64,
+    /// Account changesets and history indices.
+    pub account_history: u64,
+    /// Storage changesets and history indices.
+    pub storage_history: u64,
+    /// Plain and hashed state, bytecodes and the trie.
+    pub state: u64,
+}
+
+impl ChainSizeModel {
+    /// Returns the built-in model for the given chain, if there is one.
+    pub fn for_chain(chain: Chain) -> Option<Self> {
+        if chain == Chain::mainnet() {
+            Some(Self {
+                reference_block: 18_000_000,
+                headers: 9 * GIGABYTE,
+                transactions: 270 * GIGABYTE,
+                senders: 46 * GIGABYTE,
+                transaction_lookup: 95 * GIGABYTE,
+                receipts: 240 * GIGABYTE,
+                account_history: 230 * GIGABYTE,
+                storage_history: 760 * GIGABYTE,
+                state: 420 * GIGABYTE,
+            })
+        } else if chain == Chain::goerli() {
+            Some(Self {
+                reference_block: 9_600_000,
+                headers: 4 * GIGABYTE,
+                transactions: 60 * GIGABYTE,
+                senders: 8 * GIGABYTE,
+                transaction_lookup: 18 * GIGABYTE,
+                receipts: 55 * GIGABYTE,
+                account_history: 45 * GIGABYTE,
+                storage_history: 160 * GIGABYTE,
+                state: 90 * GIGABYTE,
+            })
+        } else if chain == Chain::sepolia() {
+            Some(Self {
+                reference_block: 4_200_000,
+                headers: 2 * GIGABYTE,
+                transactions: 25 * GIGABYTE,
+                senders: 3 * GIGABYTE,
+                transaction_lookup: 7 * GIGABYTE,
+                receipts: 20 * GIGABYTE,
+                account_history: 15 * GIGABYTE,
+                storage_history: 50 * GIGABYTE,
+                state: 25 * GIGABYTE,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// Estimated disk usage for a chain with the configured prune modes applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiskUsageEstimate {
+    /// The block number the estimate applies to.
+    pub reference_block: BlockNumber,
+    /// The model with the sizes of all prunable segments scaled down.
+    pub sizes: ChainSizeModel,
+}
+
+impl DiskUsageEstimate {
+    /// Creates the estimate for the given chain and prune configuration.
+    ///
+    /// Returns `None` if there is no built-in model for the chain.
+    pub fn new(chain: Chain, prune_config: Option<&PruneConfig>) -> Option<Self> {
+        let model = ChainSizeModel::for_chain(chain)?;
+        Some(Self::from_model(model, prune_config.map(|config| &config.parts)))
+    }
+
+    /// Applies the prune modes to the given model.
+    pub fn from_model(model: ChainSizeModel, prune_modes: Option<&PruneModes>) -> Self {
+        let tip = model.reference_block;
+        let mut sizes = model;
+        if let Some(modes) = prune_modes {
+            sizes.senders = retained(model.senders, modes.sender_recovery, tip);
+            sizes.transaction_lookup =
+                retained(model.transaction_lookup, modes.transaction_lookup, tip);
+            sizes.receipts = retained(model.receipts, modes.receipts, tip);
+            sizes.account_history = retained(model.account_history, modes.account_history, tip);
+            sizes.storage_history = retained(model.storage_history, modes.storage_history, tip);
+        }
+        Self { reference_block: tip, sizes }
+    }
+
+    /// Returns the estimated size of every segment.
+    pub fn segments(&self) -> [(&'static str, u64); 8] {
+        [
+            ("Headers", self.sizes.headers),
+            ("Transactions", self.sizes.transactions),
+            ("Senders", self.sizes.senders),
+            ("Transaction Lookup", self.sizes.transaction_lookup),
+            ("Receipts", self.sizes.receipts),
+            ("Account History", self.sizes.account_history),
+            ("Storage History", self.sizes.storage_history),
+            ("State", self.sizes.state),
+        ]
+    }
+
+    /// Returns the estimated total size.
+    pub fn total(&self) -> u64 {
+        self.segments().iter().map(|(_, size)| size).sum()
+    }
+
+    /// Checks whether a volume with `available` bytes left can hold the database, given that
+    /// `used` bytes are already occupied by it.
+    pub fn check(&self, used: u64, available: u64) -> DiskSpaceCheck {
+        let required = self.total().saturating_sub(used);
+        if available >= required {
+            DiskSpaceCheck::Sufficient
+        } else if (available as f64) >= required as f64 * REFUSE_THRESHOLD {
+            DiskSpaceCheck::Low { missing: required - available }
+        } else {
+            DiskSpaceCheck::Insufficient { missing: required - available }
+        }
+    }
+}
+
+/// Outcome of [DiskUsageEstimate::check].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiskSpaceCheck {
+    /// The volume can hold the estimated database size.
+    Sufficient,
+    /// The volume is short of the estimate, but within the margin of error.
+    Low {
+        /// Number of bytes missing.
+        missing: u64,
+    },
+    /// The volume clearly cannot hold the database.
+    Insufficient {
+        /// Number of bytes missing.
+        missing: u64,
+    },
+}
+
+/// Runs the disk space preflight for the node.
+///
+/// Logs a warning if the available space is low and returns an error if the volume clearly cannot
+/// hold the database.
+pub fn preflight(
+    chain: Chain,
+    prune_config: Option<&PruneConfig>,
+    data_dir: &Path,
+    db_path: &Path,
+) -> eyre::Result<()> {
+    let Some(estimate) = DiskUsageEstimate::new(chain, prune_config) else { return Ok(()) };
+    let Some(available) = available_space(data_dir) else { return Ok(()) };
+    let used = directory_size(db_path).unwrap_or_default();
+
+    match estimate.check(used, available) {
+        DiskSpaceCheck::Sufficient => {
+            info!(
+                target: "reth::cli",
+                estimated = %human_bytes(estimate.total() as f64),
+                available = %human_bytes(available as f64),
+                "Disk space check passed"
+            );
+            Ok(())
+        }
+        DiskSpaceCheck::Low { missing } => {
+            warn!(
+                target: "reth::cli",
+                estimated = %human_bytes(estimate.total() as f64),
+                available = %human_bytes(available as f64),
+                missing = %human_bytes(missing as f64),
+                "Available disk space is lower than the estimated database size"
+            );
+            Ok(())
+        }
+        DiskSpaceCheck::Insufficient { missing } => eyre::bail!(
+            "Not enough disk space: estimated database size is {}, but only {} are available ({} missing). Use --db.skip-disk-check to start anyway.",
+            human_bytes(estimate.total() as f64),
+            human_bytes(available as f64),
+            human_bytes(missing as f64)
+        ),
+    }
+}
+
+/// Returns the share of `size` that is retained with the given prune mode at the `tip`.
+fn retained(size: u64, mode: Option<PruneMode>, tip: BlockNumber) -> u64 {
+    let kept_blocks = match mode {
+        None => return size,
+        Some(PruneMode::Full) => 0,
+        Some(PruneMode::Distance(distance)) => distance.min(tip),
+        Some(PruneMode::Before(block)) => tip.saturating_sub(block),
+    };
+    if tip == 0 {
+        return size
+    }
+    (size as u128 * kept_blocks as u128 / tip as u128) as u64
+}
+
+/// Returns the total size of all files in the directory, recursively.
+fn directory_size(path: &Path) -> io::Result<u64> {
+    if !path.exists() {
+        return Ok(0)
+    }
+    let mut size = 0;
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            size += directory_size(&entry.path())?;
+        } else {
+            size += metadata.len();
+        }
+    }
+    Ok(size)
+}
+
+/// Returns the space available to unprivileged users on the volume containing `path`.
+///
+/// Walks up to the first existing ancestor, since the data dir may not have been created yet.
+#[cfg(unix)]
+fn available_space(path: &Path) -> Option<u64> {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    let existing = path.ancestors().find(|p| p.exists())?;
+    let c_path = CString::new(existing.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: `c_path` is a valid nul-terminated string and `stat` is a valid out pointer.
+    let res = unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) };
+    if res != 0 {
+        return None
+    }
+    #[allow(clippy::unnecessary_cast)]
+    Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+#[cfg(not(unix))]
+fn available_space(_path: &Path) -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::MINIMUM_PRUNING_DISTANCE;
+
+    #[test]
+    fn full_node_estimate_is_smaller() {
+        let archive = DiskUsageEstimate::new(Chain::mainnet(), None).unwrap();
+        let full = DiskUsageEstimate::new(
+            Chain::mainnet(),
+            Some(&PruneConfig {
+                block_interval: 5,
+                parts: PruneModes {
+                    sender_recovery: Some(PruneMode::Full),
+                    account_history: Some(PruneMode::Distance(MINIMUM_PRUNING_DISTANCE)),
+                    storage_history: Some(PruneMode::Distance(MINIMUM_PRUNING_DISTANCE)),
+                    ..Default::default()
+                },
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(full.sizes.senders, 0);
+        assert!(full.sizes.storage_history < GIGABYTE);
+        assert_eq!(full.sizes.state, archive.sizes.state);
+        assert!(full.total() < archive.total());
+    }
+
+    #[test]
+    fn no_model_for_custom_chain() {
+        assert!(DiskUsageEstimate::new(Chain::Id(1337), None).is_none());
+    }
+
+    #[test]
+    fn check_thresholds() {
+        let estimate = DiskUsageEstimate::new(Chain::sepolia(), None).unwrap();
+        let total = estimate.total();
+
+        assert_eq!(estimate.check(0, total), DiskSpaceCheck::Sufficient);
+        assert_eq!(estimate.check(total / 2, total / 2), DiskSpaceCheck::Sufficient);
+        assert_eq!(estimate.check(0, total - GIGABYTE), DiskSpaceCheck::Low { missing: GIGABYTE });
+        assert_eq!(
+            estimate.check(0, GIGABYTE),
+            DiskSpaceCheck::Insufficient { missing: total - GIGABYTE }
+        );
+    }
+}
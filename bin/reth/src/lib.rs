@@ -23,18 +23,23 @@
 //! - `min-debug-logs`: Disables all logs below `debug` level.
 //! - `min-trace-logs`: Disables all logs below `trace` level.
 
+pub mod account;
 pub mod args;
+pub mod bench;
 pub mod chain;
 pub mod cli;
 pub mod config;
 pub mod db;
 pub mod debug_cmd;
 pub mod dirs;
+pub mod estimate;
 pub mod init;
 pub mod node;
+pub mod otlp_exporter;
 pub mod p2p;
 pub mod prometheus_exporter;
 pub mod recover;
+pub mod replica;
 pub mod runner;
 pub mod stage;
 pub mod test_vectors;
@@ -0,0 +1,525 @@
+//! Sync from a trusted checkpoint.
+//!
+//! Instead of executing all blocks since genesis, the node starts executing from a trusted recent
+//! block whose state was imported into the database. The database is anchored at the checkpoint:
+//! its header is written as the canonical head and all stage checkpoints are moved to it, so the
+//! pipeline only syncs the blocks after the checkpoint.
+//!
+//! The headers below the checkpoint are then downloaded in reverse by the [HeaderBackfill] and
+//! verified against the hash chain of the trusted checkpoint.
+
+use futures::StreamExt;
+use reth_db::{
+    cursor::{DbCursorRO, DbCursorRW},
+    database::Database,
+    models::StoredBlockBodyIndices,
+    tables,
+    transaction::{DbTx, DbTxMut},
+    DatabaseError,
+};
+use reth_interfaces::p2p::headers::downloader::{HeaderDownloader, SyncTarget};
+use reth_primitives::{
+    stage::{StageCheckpoint, StageId},
+    BlockNumHash, BlockNumber, ChainSpec, SealedHeader, H256, U256,
+};
+use reth_trie::{StateRoot, StateRootError};
+use std::{collections::BTreeMap, sync::Arc};
+use tracing::{error, info, warn};
+
+/// The number of headers below the checkpoint that are needed to execute the blocks after it, for
+/// the `BLOCKHASH` opcode.
+pub const CHECKPOINT_ANCESTORS: u64 = 256;
+
+/// The number of total difficulties written per transaction after the backfill.
+const TD_COMMIT_THRESHOLD: u64 = 100_000;
+
+/// Errors that can occur when anchoring the database at a checkpoint.
+#[derive(Debug, thiserror::Error)]
+pub enum CheckpointError {
+    /// The checkpoint is not a post-merge block.
+    #[error("checkpoint block #{0} is not a post-merge block")]
+    PreMerge(BlockNumber),
+    /// The database was already synced past genesis, but not to the checkpoint.
+    #[error("database is already synced to block #{0}, checkpoint sync requires a database that is not synced past genesis")]
+    AlreadySynced(BlockNumber),
+    /// The database was anchored at a different block with the same number.
+    #[error("database contains block {database} at the height of the checkpoint {checkpoint}")]
+    HashMismatch {
+        /// The hash of the checkpoint.
+        checkpoint: H256,
+        /// The hash of the canonical block in the database.
+        database: H256,
+    },
+    /// The state in the database is not the state of the checkpoint block.
+    #[error("state root of the database {got} does not match the state root of the checkpoint {expected}, the state of the checkpoint block must be imported first")]
+    StateRootMismatch {
+        /// The state root of the checkpoint header.
+        expected: H256,
+        /// The state root computed from the database.
+        got: H256,
+    },
+    /// Failed to compute the state root.
+    #[error(transparent)]
+    StateRoot(#[from] StateRootError),
+    /// Low-level database error.
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+}
+
+/// Anchors the database at the trusted checkpoint header.
+///
+/// This verifies that the state in the database is the state of the checkpoint block by
+/// rebuilding the state trie, then writes the checkpoint header as the canonical head and moves
+/// the checkpoints of all stages to it.
+///
+/// Returns `false` if the database was already anchored at the checkpoint.
+pub fn anchor_checkpoint<DB: Database>(
+    db: &DB,
+    chain: &ChainSpec,
+    checkpoint: &SealedHeader,
+) -> Result<bool, CheckpointError> {
+    let total_difficulty = chain
+        .final_paris_total_difficulty(checkpoint.number)
+        .ok_or(CheckpointError::PreMerge(checkpoint.number))?;
+
+    let tx = db.tx_mut()?;
+
+    let synced =
+        tx.get::<tables::SyncStage>(StageId::Finish.to_string())?.unwrap_or_default().block_number;
+    if synced >= checkpoint.number {
+        let database = tx.get::<tables::CanonicalHeaders>(checkpoint.number)?.unwrap_or_default();
+        if database != checkpoint.hash() {
+            return Err(CheckpointError::HashMismatch { checkpoint: checkpoint.hash(), database })
+        }
+        return Ok(false)
+    }
+    if synced > 0 {
+        return Err(CheckpointError::AlreadySynced(synced))
+    }
+
+    info!(target: "reth::cli", number = checkpoint.number, hash = ?checkpoint.hash(), "Verifying the state of the checkpoint");
+    tx.clear::<tables::AccountsTrie>()?;
+    tx.clear::<tables::StoragesTrie>()?;
+    let (state_root, updates) = StateRoot::new(&tx).root_with_updates()?;
+    if state_root != checkpoint.state_root {
+        return Err(CheckpointError::StateRootMismatch {
+            expected: checkpoint.state_root,
+            got: state_root,
+        })
+    }
+    updates.flush(&tx)?;
+
+    tx.put::<tables::CanonicalHeaders>(checkpoint.number, checkpoint.hash())?;
+    tx.put::<tables::HeaderNumbers>(checkpoint.hash(), checkpoint.number)?;
+    tx.put::<tables::HeaderTD>(checkpoint.number, total_difficulty.into())?;
+    tx.put::<tables::Headers>(checkpoint.number, checkpoint.header.clone())?;
+    // the transactions after the checkpoint are numbered from zero
+    tx.put::<tables::BlockBodyIndices>(checkpoint.number, StoredBlockBodyIndices::default())?;
+
+    for stage in StageId::ALL {
+        tx.put::<tables::SyncStage>(stage.to_string(), StageCheckpoint::new(checkpoint.number))?;
+    }
+
+    tx.commit()?;
+    info!(target: "reth::cli", number = checkpoint.number, hash = ?checkpoint.hash(), "Database anchored at checkpoint");
+    Ok(true)
+}
+
+/// Downloads the headers below the checkpoint in reverse and writes them to the database.
+///
+/// The downloader verifies that every header is the parent of the previously downloaded one, so
+/// all headers are verified against the hash chain of the trusted checkpoint. Additionally, the
+/// backfilled headers are checked against the configured trusted header checkpoints, so a fake
+/// chain is rejected as soon as it diverges from one. Once the backfill reached genesis, the total
+/// difficulties of the backfilled headers are computed.
+#[derive(Debug)]
+pub struct HeaderBackfill<DB, D> {
+    db: Arc<DB>,
+    downloader: D,
+    checkpoint: SealedHeader,
+    /// The lowest header that is connected to the checkpoint.
+    lowest: Option<SealedHeader>,
+    /// Trusted block hashes by number that the backfilled chain must pass through.
+    trusted_checkpoints: BTreeMap<BlockNumber, H256>,
+}
+
+impl<DB, D> HeaderBackfill<DB, D>
+where
+    DB: Database + 'static,
+    D: HeaderDownloader,
+{
+    /// Creates a new backfill for the headers below the checkpoint.
+    pub fn new(db: Arc<DB>, downloader: D, checkpoint: SealedHeader) -> Self {
+        Self { db, downloader, checkpoint, lowest: None, trusted_checkpoints: BTreeMap::new() }
+    }
+
+    /// Sets the trusted checkpoints the backfilled header chain must pass through.
+    pub fn with_trusted_checkpoints(
+        mut self,
+        checkpoints: impl IntoIterator<Item = BlockNumHash>,
+    ) -> Self {
+        self.trusted_checkpoints =
+            checkpoints.into_iter().map(|checkpoint| checkpoint.into_components()).collect();
+        self
+    }
+
+    /// Checks the downloaded headers against the trusted checkpoints in their range.
+    ///
+    /// The headers are expected in descending order and connected, as returned by the downloader.
+    /// Returns the first mismatching checkpoint.
+    fn verify_trusted_checkpoints(&self, headers: &[SealedHeader]) -> Option<(BlockNumber, H256)> {
+        let (Some(highest), Some(lowest)) = (headers.first(), headers.last()) else { return None };
+        self.trusted_checkpoints.range(lowest.number..=highest.number).find_map(
+            |(&number, &expected)| {
+                let header = &headers[(highest.number - number) as usize];
+                (header.hash() != expected).then_some((number, expected))
+            },
+        )
+    }
+
+    /// Finds the lowest header that is connected to the checkpoint and configures the downloader
+    /// to fill the gap between genesis and that header.
+    ///
+    /// Returns `false` if there is no gap.
+    fn init(&mut self) -> Result<bool, DatabaseError> {
+        if self.lowest.is_some() {
+            return Ok(true)
+        }
+
+        let tx = self.db.tx()?;
+        let genesis = tx.get::<tables::Headers>(0)?.unwrap_or_default();
+        let genesis_hash = tx.get::<tables::CanonicalHeaders>(0)?.unwrap_or_default();
+
+        // headers are written with descending numbers, so everything between the lowest header
+        // above genesis and the checkpoint is present
+        let mut lowest = self.checkpoint.clone();
+        if let Some((number, header)) = tx.cursor_read::<tables::Headers>()?.seek(1)? {
+            if number < self.checkpoint.number {
+                let hash = tx.get::<tables::CanonicalHeaders>(number)?.unwrap_or_default();
+                lowest = header.seal(hash);
+            }
+        }
+
+        if lowest.number <= 1 {
+            return Ok(false)
+        }
+
+        self.downloader
+            .update_sync_gap(genesis.seal(genesis_hash), SyncTarget::Gap(lowest.clone()));
+        self.lowest = Some(lowest);
+        Ok(true)
+    }
+
+    /// Backfills headers until the header with the given number is written.
+    ///
+    /// Returns `false` if the downloader failed.
+    pub async fn backfill_to(&mut self, target: BlockNumber) -> Result<bool, DatabaseError> {
+        if !self.init()? {
+            return Ok(true)
+        }
+
+        while self.lowest.as_ref().map_or(false, |lowest| lowest.number > target.max(1)) {
+            match self.downloader.next().await {
+                Some(Ok(headers)) => {
+                    if let Some((number, expected)) = self.verify_trusted_checkpoints(&headers) {
+                        error!(target: "reth::cli", number, ?expected, "Backfilled header does not match trusted checkpoint");
+                        return Ok(false)
+                    }
+                    self.write_headers(headers)?
+                }
+                Some(Err(err)) => {
+                    error!(target: "reth::cli", %err, "Failed to backfill headers below the checkpoint");
+                    return Ok(false)
+                }
+                None => return Ok(false),
+            }
+        }
+        Ok(true)
+    }
+
+    /// Backfills all headers below the checkpoint and computes their total difficulties.
+    pub async fn run(mut self) {
+        match self.backfill_to(1).await {
+            Ok(true) => {}
+            Ok(false) => return,
+            Err(err) => {
+                error!(target: "reth::cli", %err, "Failed to write backfilled headers");
+                return
+            }
+        }
+
+        info!(target: "reth::cli", checkpoint = self.checkpoint.number, "Headers below the checkpoint backfilled");
+        if let Err(err) = self.write_total_difficulties() {
+            error!(target: "reth::cli", %err, "Failed to write total difficulties of backfilled headers");
+        }
+    }
+
+    /// Writes a batch of headers, which are returned by the downloader with descending numbers.
+    fn write_headers(&mut self, headers: Vec<SealedHeader>) -> Result<(), DatabaseError> {
+        let tx = self.db.tx_mut()?;
+        let mut lowest = None;
+        for header in headers {
+            if header.number == 0 {
+                continue
+            }
+            tx.put::<tables::HeaderNumbers>(header.hash(), header.number)?;
+            tx.put::<tables::CanonicalHeaders>(header.number, header.hash())?;
+            tx.put::<tables::Headers>(header.number, header.header.clone())?;
+            lowest = Some(header);
+        }
+        tx.commit()?;
+
+        if let Some(lowest) = lowest {
+            info!(target: "reth::cli", number = lowest.number, checkpoint = self.checkpoint.number, "Backfilled headers");
+            self.lowest = Some(lowest);
+        }
+        Ok(())
+    }
+
+    /// Computes the total difficulties of the backfilled headers.
+    ///
+    /// The total difficulties are written in ascending order and committed in batches, so after a
+    /// restart the computation resumes from the highest total difficulty below the checkpoint that
+    /// is stored in the database.
+    fn write_total_difficulties(&self) -> Result<(), DatabaseError> {
+        let (mut from, mut td) = {
+            let tx = self.db.tx()?;
+            let mut cursor_td = tx.cursor_read::<tables::HeaderTD>()?;
+            let last = match cursor_td.seek(self.checkpoint.number)? {
+                Some(_) => cursor_td.prev()?,
+                None => cursor_td.last()?,
+            };
+            match last {
+                Some((number, td)) => (number + 1, Into::<U256>::into(td)),
+                None => (1, U256::ZERO),
+            }
+        };
+        if from > 1 && from < self.checkpoint.number {
+            info!(target: "reth::cli", from, checkpoint = self.checkpoint.number, "Resuming total difficulties of backfilled headers");
+        }
+
+        while from < self.checkpoint.number {
+            let to = (from + TD_COMMIT_THRESHOLD).min(self.checkpoint.number);
+            let tx = self.db.tx_mut()?;
+            let mut cursor_td = tx.cursor_write::<tables::HeaderTD>()?;
+            for entry in tx.cursor_read::<tables::Headers>()?.walk_range(from..to)? {
+                let (number, header) = entry?;
+                td += header.difficulty;
+                cursor_td.upsert(number, td.into())?;
+            }
+            drop(cursor_td);
+            tx.commit()?;
+            from = to;
+        }
+
+        let expected = td + self.checkpoint.difficulty;
+        let stored = self.db.tx()?.get::<tables::HeaderTD>(self.checkpoint.number)?;
+        if stored.map(Into::<U256>::into) != Some(expected) {
+            warn!(target: "reth::cli", ?expected, ?stored, "Total difficulty of the checkpoint does not match the backfilled headers");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::Stream;
+    use reth_db::{test_utils::create_test_rw_db, DatabaseEnv};
+    use reth_interfaces::{
+        p2p::headers::error::HeadersDownloaderResult,
+        test_utils::generators::{self, random_header_range},
+    };
+    use reth_primitives::ChainSpecBuilder;
+    use std::{
+        collections::VecDeque,
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    const CHECKPOINT: BlockNumber = 10;
+
+    /// A downloader that returns the given batches of headers.
+    struct TestDownloader(VecDeque<Vec<SealedHeader>>);
+
+    impl HeaderDownloader for TestDownloader {
+        fn update_local_head(&mut self, _head: SealedHeader) {}
+
+        fn update_sync_target(&mut self, _target: SyncTarget) {}
+
+        fn set_batch_size(&mut self, _limit: usize) {}
+    }
+
+    impl Stream for TestDownloader {
+        type Item = HeadersDownloaderResult<Vec<SealedHeader>>;
+
+        fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            Poll::Ready(self.0.pop_front().map(Ok))
+        }
+    }
+
+    /// Returns a post-merge chain, a database with its genesis and the headers up to the
+    /// checkpoint, which have the empty state root of the database.
+    fn setup() -> (ChainSpec, Arc<DatabaseEnv>, Vec<SealedHeader>) {
+        let chain = ChainSpec {
+            paris_block_and_final_difficulty: Some((0, U256::from(1_000_000))),
+            ..ChainSpecBuilder::mainnet().build()
+        };
+        let headers = random_header_range(&mut generators::rng(), 0..CHECKPOINT + 1, H256::zero());
+
+        let db = create_test_rw_db();
+        let genesis = &headers[0];
+        let tx = db.tx_mut().unwrap();
+        tx.put::<tables::Headers>(0, genesis.header.clone()).unwrap();
+        tx.put::<tables::CanonicalHeaders>(0, genesis.hash()).unwrap();
+        tx.put::<tables::HeaderTD>(0, genesis.difficulty.into()).unwrap();
+        tx.commit().unwrap();
+
+        (chain, db, headers)
+    }
+
+    /// Returns the headers between genesis and the checkpoint in descending batches.
+    fn batches(headers: &[SealedHeader], batch_size: usize) -> VecDeque<Vec<SealedHeader>> {
+        let mut below = headers[1..CHECKPOINT as usize].to_vec();
+        below.reverse();
+        below.chunks(batch_size).map(<[SealedHeader]>::to_vec).collect()
+    }
+
+    #[test]
+    fn anchor_at_trusted_checkpoint() {
+        let (chain, db, headers) = setup();
+        let checkpoint = &headers[CHECKPOINT as usize];
+
+        assert!(matches!(anchor_checkpoint(db.as_ref(), &chain, checkpoint), Ok(true)));
+
+        let tx = db.tx().unwrap();
+        assert_eq!(
+            tx.get::<tables::CanonicalHeaders>(CHECKPOINT).unwrap(),
+            Some(checkpoint.hash())
+        );
+        assert_eq!(tx.get::<tables::Headers>(CHECKPOINT).unwrap(), Some(checkpoint.header.clone()));
+        assert_eq!(
+            tx.get::<tables::HeaderTD>(CHECKPOINT).unwrap().map(Into::<U256>::into),
+            chain.final_paris_total_difficulty(CHECKPOINT)
+        );
+        for stage in StageId::ALL {
+            assert_eq!(
+                tx.get::<tables::SyncStage>(stage.to_string()).unwrap(),
+                Some(StageCheckpoint::new(CHECKPOINT))
+            );
+        }
+        drop(tx);
+
+        // anchoring at the same checkpoint again is a no-op
+        assert!(matches!(anchor_checkpoint(db.as_ref(), &chain, checkpoint), Ok(false)));
+
+        // a different block at the height of the checkpoint is rejected
+        let mut other = checkpoint.header.clone();
+        other.gas_limit += 1;
+        assert!(matches!(
+            anchor_checkpoint(db.as_ref(), &chain, &other.seal_slow()),
+            Err(CheckpointError::HashMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn anchor_rejects_state_root_mismatch() {
+        let (chain, db, headers) = setup();
+        let mut checkpoint = headers[CHECKPOINT as usize].header.clone();
+        checkpoint.state_root = H256::random();
+
+        assert!(matches!(
+            anchor_checkpoint(db.as_ref(), &chain, &checkpoint.seal_slow()),
+            Err(CheckpointError::StateRootMismatch { .. })
+        ));
+
+        // nothing was written
+        let tx = db.tx().unwrap();
+        assert_eq!(tx.get::<tables::CanonicalHeaders>(CHECKPOINT).unwrap(), None);
+        assert_eq!(tx.get::<tables::SyncStage>(StageId::Finish.to_string()).unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn backfill_headers_to_anchor() {
+        let (chain, db, headers) = setup();
+        let checkpoint = headers[CHECKPOINT as usize].clone();
+        anchor_checkpoint(db.as_ref(), &chain, &checkpoint).unwrap();
+
+        // the headers are backfilled down to the target
+        let downloader = TestDownloader(batches(&headers, 4));
+        let mut backfill = HeaderBackfill::new(db.clone(), downloader, checkpoint.clone())
+            .with_trusted_checkpoints([BlockNumHash::new(5, headers[5].hash())]);
+        assert!(matches!(backfill.backfill_to(6).await, Ok(true)));
+        assert_eq!(backfill.lowest.as_ref().map(|header| header.number), Some(6));
+        assert!(matches!(backfill.backfill_to(1).await, Ok(true)));
+
+        let tx = db.tx().unwrap();
+        for header in &headers[1..CHECKPOINT as usize] {
+            assert_eq!(
+                tx.get::<tables::CanonicalHeaders>(header.number).unwrap(),
+                Some(header.hash())
+            );
+            assert_eq!(
+                tx.get::<tables::HeaderNumbers>(header.hash()).unwrap(),
+                Some(header.number)
+            );
+            assert_eq!(
+                tx.get::<tables::Headers>(header.number).unwrap(),
+                Some(header.header.clone())
+            );
+        }
+        drop(tx);
+
+        // nothing is left to backfill after a restart
+        let mut backfill = HeaderBackfill::new(db, TestDownloader(VecDeque::new()), checkpoint);
+        assert!(matches!(backfill.backfill_to(1).await, Ok(true)));
+    }
+
+    #[tokio::test]
+    async fn backfill_rejects_trusted_checkpoint_mismatch() {
+        let (chain, db, headers) = setup();
+        let checkpoint = headers[CHECKPOINT as usize].clone();
+        anchor_checkpoint(db.as_ref(), &chain, &checkpoint).unwrap();
+
+        let downloader = TestDownloader(batches(&headers, 4));
+        let mut backfill = HeaderBackfill::new(db.clone(), downloader, checkpoint)
+            .with_trusted_checkpoints([BlockNumHash::new(5, H256::random())]);
+        assert!(matches!(backfill.backfill_to(1).await, Ok(false)));
+
+        // the batch with the mismatching header was not written
+        let tx = db.tx().unwrap();
+        assert_eq!(tx.get::<tables::CanonicalHeaders>(6).unwrap(), Some(headers[6].hash()));
+        assert_eq!(tx.get::<tables::CanonicalHeaders>(5).unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn resume_total_difficulties() {
+        let (chain, db, headers) = setup();
+        let checkpoint = headers[CHECKPOINT as usize].clone();
+        anchor_checkpoint(db.as_ref(), &chain, &checkpoint).unwrap();
+
+        let mut backfill =
+            HeaderBackfill::new(db.clone(), TestDownloader(batches(&headers, 4)), checkpoint);
+        assert!(matches!(backfill.backfill_to(1).await, Ok(true)));
+
+        // a previous run stored the total difficulties up to block 4
+        let partial = U256::from(123_456);
+        db.update(|tx| tx.put::<tables::HeaderTD>(4, partial.into())).unwrap().unwrap();
+
+        backfill.write_total_difficulties().unwrap();
+
+        let tx = db.tx().unwrap();
+        // the entries below the last stored one are not recomputed
+        for number in 1..4 {
+            assert_eq!(tx.get::<tables::HeaderTD>(number).unwrap(), None);
+        }
+        let mut td = partial;
+        for header in &headers[5..CHECKPOINT as usize] {
+            td += header.difficulty;
+            assert_eq!(
+                tx.get::<tables::HeaderTD>(header.number).unwrap().map(Into::<U256>::into),
+                Some(td)
+            );
+        }
+    }
+}
@@ -6,6 +6,7 @@ use reth_beacon_consensus::BeaconConsensusEngineEvent;
 use reth_interfaces::consensus::ForkchoiceState;
 use reth_network::{NetworkEvent, NetworkHandle};
 use reth_network_api::PeersInfo;
+use reth_payload_builder::PayloadBuilderEvent;
 use reth_primitives::{
     stage::{EntitiesCheckpoint, StageCheckpoint, StageId},
     BlockNumber,
@@ -18,7 +19,7 @@ use std::{
     time::{Duration, Instant},
 };
 use tokio::time::Interval;
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
 
 /// Interval of reporting node state.
 const INFO_MESSAGE_INTERVAL: Duration = Duration::from_secs(30);
@@ -133,6 +134,20 @@ impl NodeState {
         }
     }
 
+    fn handle_payload_builder_event(&self, event: PayloadBuilderEvent) {
+        if let PayloadBuilderEvent::Resolved(payload) = event {
+            let block = payload.block();
+            debug!(
+                id = %payload.id(),
+                number = block.number,
+                txs = block.body.len(),
+                gas_used = block.gas_used,
+                fees = %payload.fees(),
+                "Payload resolved"
+            );
+        }
+    }
+
     fn handle_consensus_layer_health_event(&self, event: ConsensusLayerHealthEvent) {
         match event {
             ConsensusLayerHealthEvent::NeverSeen => {
@@ -162,6 +177,8 @@ pub enum NodeEvent {
     ConsensusEngine(BeaconConsensusEngineEvent),
     /// A Consensus Layer health event.
     ConsensusLayerHealth(ConsensusLayerHealthEvent),
+    /// A payload builder event.
+    PayloadBuilder(PayloadBuilderEvent),
 }
 
 impl From<NetworkEvent> for NodeEvent {
@@ -188,6 +205,12 @@ impl From<ConsensusLayerHealthEvent> for NodeEvent {
     }
 }
 
+impl From<PayloadBuilderEvent> for NodeEvent {
+    fn from(event: PayloadBuilderEvent) -> Self {
+        NodeEvent::PayloadBuilder(event)
+    }
+}
+
 /// Displays relevant information to the user from components of the node, and periodically
 /// displays the high-level status of the node.
 pub async fn handle_events<E>(
@@ -259,6 +282,7 @@ where
                 NodeEvent::ConsensusLayerHealth(event) => {
                     this.state.handle_consensus_layer_health_event(event)
                 }
+                NodeEvent::PayloadBuilder(event) => this.state.handle_payload_builder_event(event),
             }
         }
 
@@ -5,16 +5,24 @@ use crate::{
     args::{
         get_secret_key,
         utils::{genesis_value_parser, parse_socket_address},
-        DatabaseArgs, DebugArgs, DevArgs, NetworkArgs, PayloadBuilderArgs, PruningArgs,
-        RpcServerArgs, TxPoolArgs,
+        DatabaseArgs, DebugArgs, DevArgs, IndexerSinkArgs, NetworkArgs, OtlpMetricsArgs,
+        PayloadBuilderArgs, PruningArgs, RpcServerArgs, RuntimeArgs, SyncArgs, TxPoolArgs,
     },
     cli::{
         config::RethRpcConfig,
         ext::{RethCliExt, RethNodeCommandConfig},
     },
     dirs::{DataDirPath, MaybePlatformPath},
+    estimate,
     init::init_genesis,
-    node::cl_events::ConsensusLayerHealthEvents,
+    node::{
+        checkpoint::{anchor_checkpoint, HeaderBackfill, CHECKPOINT_ANCESTORS},
+        cl_events::ConsensusLayerHealthEvents,
+        reorg_alert::WebhookReorgHook,
+        state_root_verifier::StateRootVerifier,
+        status_server::{StatusEvents, DEFAULT_STATUS_SERVER_ADDR},
+        tui::NodeTui,
+    },
     prometheus_exporter,
     runner::CliContext,
     utils::get_single_header,
@@ -27,7 +35,8 @@ use futures::{future::Either, pin_mut, stream, stream_select, StreamExt};
 use reth_auto_seal_consensus::{AutoSealBuilder, AutoSealConsensus, MiningMode};
 use reth_beacon_consensus::{BeaconConsensus, BeaconConsensusEngine, MIN_BLOCKS_FOR_PIPELINE_RUN};
 use reth_blockchain_tree::{
-    config::BlockchainTreeConfig, externals::TreeExternals, BlockchainTree, ShareableBlockchainTree,
+    config::BlockchainTreeConfig, externals::TreeExternals, BlockchainTree, ReorgAlert,
+    ShareableBlockchainTree,
 };
 use reth_config::{config::PruneConfig, Config};
 use reth_db::{database::Database, init_db, DatabaseEnv};
@@ -36,7 +45,9 @@ use reth_downloaders::{
     bodies::bodies::BodiesDownloaderBuilder,
     headers::reverse_headers::ReverseHeadersDownloaderBuilder,
 };
+use reth_indexer_sink::{IndexerSink, SinkBackend, Topics};
 use reth_interfaces::{
+    blockchain_tree::BlockchainTreeEngine,
     consensus::Consensus,
     p2p::{
         bodies::{client::BodiesClient, downloader::BodyDownloader},
@@ -44,17 +55,26 @@ use reth_interfaces::{
         headers::{client::HeadersClient, downloader::HeaderDownloader},
     },
 };
-use reth_network::{error::NetworkError, NetworkConfig, NetworkHandle, NetworkManager};
+use reth_network::{
+    error::NetworkError,
+    eth_requests::EthRequestHandlerConfig,
+    peers::write_peers_file,
+    snap_requests::SnapRequestHandler,
+    transactions::{TransactionsManager, TransactionsManagerConfig},
+    NetworkConfig, NetworkHandle, NetworkManager,
+};
 use reth_network_api::NetworkInfo;
 use reth_primitives::{
     constants::eip4844::{LoadKzgSettingsError, MAINNET_KZG_TRUSTED_SETUP},
     kzg::KzgSettings,
     stage::StageId,
-    BlockHashOrNumber, BlockNumber, ChainSpec, DisplayHardforks, Head, SealedHeader, H256,
+    BlockHashOrNumber, BlockNumber, ChainSpec, DisplayHardforks, Head, SealedHeader,
+    SenderRecoveryCache, H256,
 };
 use reth_provider::{
-    providers::BlockchainProvider, BlockHashReader, BlockReader, CanonStateSubscriptions,
-    HeaderProvider, ProviderFactory, StageCheckpointReader,
+    providers::{BlockchainProvider, BytecodeCache},
+    BlockHashReader, BlockReader, CanonStateSubscriptions, HeaderProvider, ProviderFactory,
+    SnapStateProviderFactory, StageCheckpointReader, StateProviderFactory,
 };
 use reth_revm::Factory;
 use reth_revm_inspectors::stack::Hook;
@@ -62,27 +82,38 @@ use reth_rpc_engine_api::EngineApi;
 use reth_stages::{
     prelude::*,
     stages::{
-        AccountHashingStage, ExecutionStage, ExecutionStageThresholds, HeaderSyncMode,
-        IndexAccountHistoryStage, IndexStorageHistoryStage, MerkleStage, SenderRecoveryStage,
-        StorageHashingStage, TotalDifficultyStage, TransactionLookupStage,
+        AccountHashingStage, ChainStatsStage, ExecutionStage, ExecutionStageThresholds,
+        HeaderSyncMode, IndexAccountHistoryStage, IndexStorageHistoryStage, MerkleStage,
+        SenderRecoveryStage, StorageHashingStage, TotalDifficultyStage, TransactionLookupStage,
     },
     MetricEventsSender, MetricsListener,
 };
-use reth_tasks::TaskExecutor;
+use reth_tasks::{
+    watchdog::{Heartbeat, StallDetector},
+    RestartPolicy, TaskExecutor,
+};
 use reth_transaction_pool::{
-    blobstore::InMemoryBlobStore, TransactionPool, TransactionValidationTaskExecutor,
+    blobstore::InMemoryBlobStore, DynamicFeeFloor, TransactionPool,
+    TransactionValidationTaskExecutor,
 };
 use secp256k1::SecretKey;
 use std::{
     net::{Ipv4Addr, SocketAddr, SocketAddrV4},
     path::PathBuf,
     sync::Arc,
+    time::Duration,
 };
 use tokio::sync::{mpsc::unbounded_channel, oneshot, watch};
 use tracing::*;
 
+pub mod checkpoint;
 pub mod cl_events;
 pub mod events;
+pub mod peer_stats;
+pub mod reorg_alert;
+pub mod state_root_verifier;
+pub mod status_server;
+mod tui;
 
 /// Start the node
 #[derive(Debug, Parser)]
@@ -127,6 +158,31 @@ pub struct NodeCommand<Ext: RethCliExt = ()> {
     #[arg(long, value_name = "SOCKET", value_parser = parse_socket_address, help_heading = "Metrics")]
     pub metrics: Option<SocketAddr>,
 
+    /// All OTLP metrics related arguments
+    #[clap(flatten)]
+    pub otlp: OtlpMetricsArgs,
+
+    /// Enable the status server, which streams structured node events for dashboards and UIs.
+    ///
+    /// The events will be served at the given interface and port, or at
+    /// `127.0.0.1:9545` if none is given.
+    #[arg(
+        long = "with-status-server",
+        value_name = "SOCKET",
+        value_parser = parse_socket_address,
+        num_args = 0..=1,
+        default_missing_value = DEFAULT_STATUS_SERVER_ADDR,
+        help_heading = "Status"
+    )]
+    pub status_server: Option<SocketAddr>,
+
+    /// Render an interactive terminal dashboard instead of logging to stdout.
+    ///
+    /// The dashboard shows the sync progress, connected peers, the transaction pool and recent
+    /// blocks. Pressing `q` closes the dashboard and shuts down the node.
+    #[arg(long, help_heading = "Status")]
+    pub tui: bool,
+
     /// Add a new instance of a node.
     ///
     /// Configures the ports of the node to avoid conflicts with the defaults.
@@ -147,6 +203,13 @@ pub struct NodeCommand<Ext: RethCliExt = ()> {
     #[arg(long, value_name = "PATH")]
     pub trusted_setup_file: Option<PathBuf>,
 
+    /// Skip the disk space preflight check on startup.
+    ///
+    /// By default the node refuses to start if the volume clearly cannot hold the estimated
+    /// database size for the configured chain and prune mode.
+    #[arg(long = "db.skip-disk-check", help_heading = "Database")]
+    pub skip_disk_check: bool,
+
     /// All networking related arguments
     #[clap(flatten)]
     pub network: NetworkArgs,
@@ -179,6 +242,18 @@ pub struct NodeCommand<Ext: RethCliExt = ()> {
     #[clap(flatten)]
     pub pruning: PruningArgs,
 
+    /// All sync related arguments with --sync prefix
+    #[clap(flatten)]
+    pub sync: SyncArgs,
+
+    /// All indexer sink related arguments with --sink prefix
+    #[clap(flatten)]
+    pub sink: IndexerSinkArgs,
+
+    /// All runtime related arguments with --runtime prefix
+    #[clap(flatten)]
+    pub runtime: RuntimeArgs,
+
     /// Additional cli arguments
     #[clap(flatten)]
     pub ext: Ext::Node,
@@ -192,7 +267,11 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
             config,
             chain,
             metrics,
+            otlp,
+            status_server,
+            tui,
             trusted_setup_file,
+            skip_disk_check,
             instance,
             network,
             rpc,
@@ -202,6 +281,9 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
             db,
             dev,
             pruning,
+            sync,
+            sink,
+            runtime,
             ..
         } = self;
         NodeCommand {
@@ -209,8 +291,12 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
             config,
             chain,
             metrics,
+            otlp,
+            status_server,
+            tui,
             instance,
             trusted_setup_file,
+            skip_disk_check,
             network,
             rpc,
             txpool,
@@ -219,6 +305,9 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
             db,
             dev,
             pruning,
+            sync,
+            sink,
+            runtime,
             ext,
         }
     }
@@ -231,6 +320,23 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
         // Does not do anything on windows.
         raise_fd_limit();
 
+        self.rpc.apply_profile();
+
+        // the runtimes are owned by the runner, which shuts them down after the tasks spawned onto
+        // them received the shutdown signal
+        let network_runtime =
+            self.runtime.network_runtime()?.map(|runtime| ctx.subsystem_runtimes.register(runtime));
+        let rpc_runtime =
+            self.runtime.rpc_runtime()?.map(|runtime| ctx.subsystem_runtimes.register(runtime));
+        let network_executor = match &network_runtime {
+            Some(handle) => ctx.task_executor.with_handle(handle.clone()),
+            None => ctx.task_executor.clone(),
+        };
+        let rpc_executor = match &rpc_runtime {
+            Some(handle) => ctx.task_executor.with_handle(handle.clone()),
+            None => ctx.task_executor.clone(),
+        };
+
         // add network name to data dir
         let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
         let config_path = self.config.clone().unwrap_or(data_dir.config_path());
@@ -240,7 +346,19 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
         // always store reth.toml in the data dir, not the chain specific data dir
         info!(target: "reth::cli", path = ?config_path, "Configuration loaded");
 
+        let prune_config =
+            self.pruning.prune_config(Arc::clone(&self.chain))?.or(config.prune.clone());
+
         let db_path = data_dir.db_path();
+        if !self.skip_disk_check {
+            estimate::preflight(
+                self.chain.chain,
+                prune_config.as_ref(),
+                data_dir.as_ref(),
+                &db_path,
+            )?;
+        }
+
         info!(target: "reth::cli", path = ?db_path, "Opening database");
         let db = Arc::new(init_db(&db_path, self.db.log_level)?);
         info!(target: "reth::cli", "Database opened");
@@ -267,89 +385,175 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
         let metrics_listener = MetricsListener::new(metrics_rx);
         ctx.task_executor.spawn_critical("metrics listener task", metrics_listener);
 
-        let prune_config =
-            self.pruning.prune_config(Arc::clone(&self.chain))?.or(config.prune.clone());
+        // contract bytecode is cached once and shared by the executor and the RPC
+        let bytecode_cache = BytecodeCache::default();
+        // senders recovered by the transaction pool are reused when a block is executed
+        let sender_cache = SenderRecoveryCache::default();
 
         // configure blockchain tree
-        let tree_externals = TreeExternals::new(
+        let mut tree_externals = TreeExternals::new(
             db.clone(),
             Arc::clone(&consensus),
             Factory::new(self.chain.clone()),
             Arc::clone(&self.chain),
-        );
-        let tree_config = BlockchainTreeConfig::default();
+        )
+        .with_bytecode_cache(bytecode_cache.clone())
+        .with_sender_cache(sender_cache.clone());
+        if config.stages.total_difficulty.elide_post_merge {
+            tree_externals = tree_externals.with_post_merge_td_elision();
+        }
+        let tree_config = BlockchainTreeConfig::default()
+            .with_preimages(config.stages.execution.preimages)
+            .with_max_unconnected_blocks(self.sync.max_buffered_blocks);
         // The size of the broadcast is twice the maximum reorg depth, because at maximum reorg
         // depth at least N blocks must be sent at once.
         let (canon_state_notification_sender, _receiver) =
             tokio::sync::broadcast::channel(tree_config.max_reorg_depth() as usize * 2);
-        let blockchain_tree = ShareableBlockchainTree::new(
-            BlockchainTree::new(
-                tree_externals,
-                canon_state_notification_sender.clone(),
-                tree_config,
-                prune_config.clone().map(|config| config.parts),
-            )?
-            .with_sync_metrics_tx(metrics_tx.clone()),
-        );
+        let mut tree = BlockchainTree::new(
+            tree_externals,
+            canon_state_notification_sender.clone(),
+            tree_config,
+            prune_config.clone().map(|config| config.parts),
+        )?
+        .with_sync_metrics_tx(metrics_tx.clone());
+        if let Some(alert) = &config.reorg_alert {
+            info!(target: "reth::cli", webhook = %alert.webhook, min_depth = alert.min_depth, "Posting deep reorgs to webhook");
+            let hook = WebhookReorgHook::new(&alert.webhook, ctx.task_executor.clone())?;
+            tree = tree.with_reorg_alert(ReorgAlert::new(alert.min_depth, Box::new(hook)));
+        }
+        let blockchain_tree = ShareableBlockchainTree::new(tree);
 
         // setup the blockchain provider
-        let factory = ProviderFactory::new(Arc::clone(&db), Arc::clone(&self.chain));
+        let factory = ProviderFactory::new(Arc::clone(&db), Arc::clone(&self.chain))
+            .with_bytecode_cache(bytecode_cache);
         let blockchain_db = BlockchainProvider::new(factory, blockchain_tree.clone())?;
+        let head = self.lookup_head(Arc::clone(&db)).expect("the head block is missing");
         let blob_store = InMemoryBlobStore::default();
-        let validator = TransactionValidationTaskExecutor::eth_builder(Arc::clone(&self.chain))
+        let fee_floor =
+            self.txpool.fee_floor_config().map(|config| Arc::new(DynamicFeeFloor::new(config)));
+        let mut validator = TransactionValidationTaskExecutor::eth_builder(Arc::clone(&self.chain))
             .kzg_settings(self.kzg_settings()?)
-            .with_additional_tasks(1)
-            .build_with_tasks(blockchain_db.clone(), ctx.task_executor.clone(), blob_store.clone());
+            .with_head_timestamp(head.timestamp)
+            .with_sender_cache(sender_cache)
+            .with_additional_tasks(1);
+        if let Some(fee_floor) = fee_floor.clone() {
+            validator = validator.with_dynamic_fee_floor(fee_floor);
+        }
+        let validator = validator.build_with_tasks(
+            blockchain_db.clone(),
+            ctx.task_executor.clone(),
+            blob_store.clone(),
+        );
 
         let transaction_pool =
             reth_transaction_pool::Pool::eth_pool(validator, blob_store, self.txpool.pool_config());
         info!(target: "reth::cli", "Transaction pool initialized");
 
-        // spawn txpool maintenance task
+        // spawn txpool maintenance task, the task only tracks the canonical chain so it can be
+        // restarted from scratch if it panics
         {
             let pool = transaction_pool.clone();
-            let chain_events = blockchain_db.canonical_state_stream();
             let client = blockchain_db.clone();
-            ctx.task_executor.spawn_critical(
+            let executor = ctx.task_executor.clone();
+            ctx.task_executor.spawn_critical_with_restart(
                 "txpool maintenance task",
-                reth_transaction_pool::maintain::maintain_transaction_pool_future(
-                    client,
-                    pool,
-                    chain_events,
-                    ctx.task_executor.clone(),
-                    Default::default(),
-                ),
+                RestartPolicy::Restart { max_restarts: 3, backoff: Duration::from_secs(1) },
+                move || {
+                    reth_transaction_pool::maintain::maintain_transaction_pool_future(
+                        client.clone(),
+                        pool.clone(),
+                        client.canonical_state_stream(),
+                        executor.clone(),
+                        Default::default(),
+                    )
+                },
             );
             debug!(target: "reth::cli", "Spawned txpool maintenance task");
         }
 
+        // spawn indexer sink
+        if self.sink.is_enabled() {
+            let sink = IndexerSink::new(
+                ProviderFactory::new(Arc::clone(&db), Arc::clone(&self.chain)),
+                self.indexer_sink_backend().await?,
+                Topics::with_prefix(&self.sink.topic_prefix),
+                self.sink.from_block,
+            )?;
+            info!(target: "reth::cli", next = sink.next_block(), "Publishing canonical chain to indexer sink");
+            ctx.task_executor.spawn_critical(
+                "indexer sink",
+                sink.run(blockchain_db.subscribe_to_canonical_state()),
+            );
+        }
+
         info!(target: "reth::cli", "Connecting to P2P network");
         let network_secret_path =
             self.network.p2p_secret_key.clone().unwrap_or_else(|| data_dir.p2p_secret_path());
         debug!(target: "reth::cli", ?network_secret_path, "Loading p2p key file");
         let secret_key = get_secret_key(&network_secret_path)?;
         let default_peers_path = data_dir.known_peers_path();
-        let head = self.lookup_head(Arc::clone(&db)).expect("the head block is missing");
         let network_config = self.load_network_config(
             &config,
             Arc::clone(&db),
-            ctx.task_executor.clone(),
+            network_executor.clone(),
             head,
             secret_key,
             default_peers_path.clone(),
         );
+        let mut stall_detector = self
+            .debug
+            .stall_threshold
+            .map(|threshold| StallDetector::new(Duration::from_secs(threshold)));
+        let network_heartbeat =
+            stall_detector.as_mut().map(|detector| detector.heartbeat("p2p network task"));
         let network = self
             .start_network(
                 network_config,
-                &ctx.task_executor,
+                &network_executor,
                 transaction_pool.clone(),
+                blockchain_db.clone(),
+                config.transactions.clone(),
+                config.eth_requests,
+                fee_floor,
                 default_peers_path,
+                network_heartbeat,
             )
             .await?;
         info!(target: "reth::cli", peer_id = %network.peer_id(), local_addr = %network.local_addr(), "Connected to P2P network");
         debug!(target: "reth::cli", peer_id = ?network.peer_id(), "Full peer ID");
         let network_client = network.fetch_client().await?;
 
+        if let Some(checkpoint) = self.sync.checkpoint {
+            let checkpoint =
+                self.fetch_tip(&db, &network_client, BlockHashOrNumber::Hash(checkpoint)).await?;
+            if anchor_checkpoint(&db, &self.chain, &checkpoint)? {
+                blockchain_tree.restore_canonical_hashes_and_finalize(checkpoint.number)?;
+            }
+
+            let downloader = ReverseHeadersDownloaderBuilder::from(config.stages.headers.clone())
+                .build(network_client.clone(), Arc::clone(&consensus));
+            let mut backfill = HeaderBackfill::new(Arc::clone(&db), downloader, checkpoint.clone())
+                .with_trusted_checkpoints(
+                    config.stages.headers.trusted_checkpoints.iter().copied().map(Into::into),
+                );
+
+            // the blocks after the checkpoint can only be executed with the hashes of their
+            // ancestors
+            info!(target: "reth::cli", number = checkpoint.number, "Backfilling the ancestors of the checkpoint");
+            if !backfill.backfill_to(checkpoint.number.saturating_sub(CHECKPOINT_ANCESTORS)).await?
+            {
+                eyre::bail!("failed to download the ancestors of the checkpoint");
+            }
+            ctx.task_executor.spawn(backfill.run());
+        }
+
+        // keep the fork id of the network in sync with the canonical head
+        ctx.task_executor.spawn(reth_network::status::track_canonical_head_future(
+            network.clone(),
+            blockchain_db.clone(),
+            blockchain_db.canonical_state_stream(),
+        ));
+
         let (consensus_engine_tx, consensus_engine_rx) = unbounded_channel();
 
         debug!(target: "reth::cli", "Spawning payload builder service");
@@ -361,6 +565,11 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
             Arc::clone(&self.chain),
         )?;
 
+        if let Some(submitter) = self.builder.relay_submitter(&self.chain)? {
+            info!(target: "reth::cli", relays = ?self.builder.relays, "Submitting built payloads to relays");
+            ctx.task_executor.spawn(submitter.run(payload_builder.event_listener()));
+        }
+
         let max_block = if let Some(block) = self.debug.max_block {
             Some(block)
         } else if let Some(tip) = self.debug.tip {
@@ -432,6 +641,8 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
         };
 
         let pipeline_events = pipeline.events();
+        let status_pipeline_events =
+            (self.status_server.is_some() || self.tui).then(|| pipeline.events());
 
         let initial_target = if let Some(tip) = self.debug.tip {
             // Set the provided tip as the initial pipeline target.
@@ -458,23 +669,29 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
         });
 
         // Configure the consensus engine
-        let (beacon_consensus_engine, beacon_engine_handle) = BeaconConsensusEngine::with_channel(
-            client,
-            pipeline,
-            blockchain_db.clone(),
-            Box::new(ctx.task_executor.clone()),
-            Box::new(network.clone()),
-            max_block,
-            self.debug.continuous,
-            payload_builder.clone(),
-            initial_target,
-            MIN_BLOCKS_FOR_PIPELINE_RUN,
-            consensus_engine_tx,
-            consensus_engine_rx,
-            pruner,
-        )?;
+        let (mut beacon_consensus_engine, beacon_engine_handle) =
+            BeaconConsensusEngine::with_channel(
+                client,
+                pipeline,
+                blockchain_db.clone(),
+                Box::new(ctx.task_executor.clone()),
+                Box::new(network.clone()),
+                max_block,
+                self.debug.continuous,
+                payload_builder.clone(),
+                initial_target,
+                MIN_BLOCKS_FOR_PIPELINE_RUN,
+                consensus_engine_tx,
+                consensus_engine_rx,
+                pruner,
+            )?;
         info!(target: "reth::cli", "Consensus engine initialized");
 
+        if let Some(mut detector) = stall_detector {
+            beacon_consensus_engine.set_heartbeat(detector.heartbeat("consensus engine"));
+            ctx.task_executor.spawn(detector.run());
+        }
+
         let events = stream_select!(
             network.event_listener().map(Into::into),
             beacon_engine_handle.event_listener().map(Into::into),
@@ -493,6 +710,57 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
             events::handle_events(Some(network.clone()), Some(head.number), events),
         );
 
+        if let Some(peer_stats) = config.peer_stats.clone() {
+            info!(target: "reth::cli", file = ?peer_stats.file, endpoint = ?peer_stats.endpoint, interval = peer_stats.interval, "Exporting peer stats");
+            ctx.task_executor
+                .spawn(peer_stats::export_peer_stats(peer_stats, network.event_listener()));
+        }
+
+        if let Some(interval) = self.debug.verify_state_root_interval {
+            info!(target: "reth::cli", interval, "Spawning state root verifier");
+            let verifier = StateRootVerifier::new(
+                Arc::clone(&db),
+                Duration::from_secs(interval),
+                self.debug.verify_state_root_samples,
+                self.debug.verify_state_root_full_every,
+            );
+            ctx.task_executor.spawn(verifier.run());
+        }
+
+        let mut tui_exit = None;
+        if let Some(pipeline_events) = status_pipeline_events {
+            let status = StatusEvents::new();
+            if let Some(listen_addr) = self.status_server {
+                info!(target: "reth::cli", addr = %listen_addr, "Starting status server");
+                status_server::start_status_server(listen_addr, status.clone()).await?;
+            }
+            if self.tui {
+                let (tx, rx) = oneshot::channel();
+                let dashboard = NodeTui::new(status.subscribe());
+                std::thread::Builder::new().name("reth-tui".to_string()).spawn(move || {
+                    if let Err(err) = dashboard.run() {
+                        error!(target: "reth::cli", %err, "Terminal dashboard failed");
+                    }
+                    let _ = tx.send(());
+                })?;
+                tui_exit = Some(rx);
+            }
+
+            let events = stream_select!(
+                network.event_listener().map(Into::into),
+                beacon_engine_handle.event_listener().map(Into::into),
+                pipeline_events.map(Into::into),
+                payload_builder.event_listener().map(Into::into)
+            );
+            ctx.task_executor.spawn(status_server::publish_events(
+                network.clone(),
+                events,
+                status.clone(),
+            ));
+            ctx.task_executor
+                .spawn(status_server::publish_pool_stats(transaction_pool.clone(), status));
+        }
+
         let engine_api = EngineApi::new(
             blockchain_db.clone(),
             self.chain.clone(),
@@ -505,6 +773,7 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
         // extract the jwt secret from the args if possible
         let default_jwt_path = data_dir.jwt_path();
         let jwt_secret = self.rpc.jwt_secret(default_jwt_path)?;
+        let keystore = self.rpc.keystore(data_dir.keystore_path())?;
 
         // adjust rpc port numbers based on instance number
         self.adjust_instance_ports();
@@ -513,13 +782,16 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
         let (_rpc_server, _auth_server) = self
             .rpc
             .start_servers(
+                Arc::clone(&db),
                 blockchain_db.clone(),
                 transaction_pool.clone(),
                 network.clone(),
-                ctx.task_executor.clone(),
+                rpc_executor,
                 blockchain_tree,
                 engine_api,
                 jwt_secret,
+                keystore,
+                rpc_runtime,
                 &mut self.ext,
             )
             .await?;
@@ -532,16 +804,32 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
             let _ = tx.send(res);
         });
 
-        rx.await??;
+        let terminate = self.debug.terminate;
+        let node = async move {
+            rx.await??;
 
-        info!(target: "reth::cli", "Consensus engine has exited.");
+            info!(target: "reth::cli", "Consensus engine has exited.");
 
-        if self.debug.terminate {
-            Ok(())
-        } else {
-            // The pipeline has finished downloading blocks up to `--debug.tip` or
-            // `--debug.max-block`. Keep other node components alive for further usage.
-            futures::future::pending().await
+            if terminate {
+                Ok(())
+            } else {
+                // The pipeline has finished downloading blocks up to `--debug.tip` or
+                // `--debug.max-block`. Keep other node components alive for further usage.
+                futures::future::pending().await
+            }
+        };
+
+        match tui_exit {
+            Some(tui_exit) => {
+                tokio::select! {
+                    res = node => res,
+                    _ = tui_exit => {
+                        info!(target: "reth::cli", "Terminal dashboard closed, shutting down");
+                        Ok(())
+                    }
+                }
+            }
+            None => node.await,
         }
     }
 
@@ -563,9 +851,10 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
         Client: HeadersClient + BodiesClient + Clone + 'static,
     {
         // building network downloaders using the fetch client
-        let header_downloader = ReverseHeadersDownloaderBuilder::from(config.stages.headers)
-            .build(client.clone(), Arc::clone(&consensus))
-            .into_task_with(task_executor);
+        let header_downloader =
+            ReverseHeadersDownloaderBuilder::from(config.stages.headers.clone())
+                .build(client.clone(), Arc::clone(&consensus))
+                .into_task_with(task_executor);
 
         let body_downloader = BodiesDownloaderBuilder::from(config.stages.bodies)
             .build(client, Arc::clone(&consensus), db.clone())
@@ -594,6 +883,29 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
             .wrap_err_with(|| format!("Could not load config file {:?}", config_path))
     }
 
+    /// Connects to the message broker of the indexer sink.
+    async fn indexer_sink_backend(&self) -> eyre::Result<Box<dyn SinkBackend>> {
+        match (&self.sink.kafka, &self.sink.nats) {
+            #[cfg(feature = "kafka")]
+            (Some(brokers), _) => {
+                Ok(Box::new(reth_indexer_sink::backend::KafkaBackend::new(brokers)?))
+            }
+            #[cfg(not(feature = "kafka"))]
+            (Some(_), _) => {
+                eyre::bail!("--sink.kafka requires reth to be built with the kafka feature")
+            }
+            #[cfg(feature = "nats")]
+            (_, Some(url)) => {
+                Ok(Box::new(reth_indexer_sink::backend::NatsBackend::connect(url).await?))
+            }
+            #[cfg(not(feature = "nats"))]
+            (_, Some(_)) => {
+                eyre::bail!("--sink.nats requires reth to be built with the nats feature")
+            }
+            (None, None) => eyre::bail!("no indexer sink broker configured"),
+        }
+    }
+
     /// Loads the trusted setup params from a given file path or falls back to
     /// `MAINNET_KZG_TRUSTED_SETUP`.
     fn kzg_settings(&self) -> eyre::Result<Arc<KzgSettings>> {
@@ -620,8 +932,16 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
     async fn start_metrics_endpoint(&self, db: Arc<DatabaseEnv>) -> eyre::Result<()> {
         if let Some(listen_addr) = self.metrics {
             info!(target: "reth::cli", addr = %listen_addr, "Starting metrics endpoint");
-            prometheus_exporter::initialize(listen_addr, db, metrics_process::Collector::default())
-                .await?;
+            if let Some(endpoint) = &self.otlp.endpoint {
+                info!(target: "reth::cli", %endpoint, "Pushing metrics via OTLP");
+            }
+            prometheus_exporter::initialize(
+                listen_addr,
+                &self.otlp,
+                db,
+                metrics_process::Collector::default(),
+            )
+            .await?;
         }
 
         Ok(())
@@ -629,27 +949,65 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
 
     /// Spawns the configured network and associated tasks and returns the [NetworkHandle] connected
     /// to that network.
-    async fn start_network<C, Pool>(
+    #[allow(clippy::too_many_arguments)]
+    async fn start_network<C, Pool, State>(
         &self,
         config: NetworkConfig<C>,
         task_executor: &TaskExecutor,
         pool: Pool,
+        state: State,
+        transactions_config: TransactionsManagerConfig,
+        eth_requests_config: EthRequestHandlerConfig,
+        fee_floor: Option<Arc<DynamicFeeFloor>>,
         default_peers_path: PathBuf,
+        heartbeat: Option<Heartbeat>,
     ) -> Result<NetworkHandle, NetworkError>
     where
         C: BlockReader + HeaderProvider + Clone + Unpin + 'static,
         Pool: TransactionPool + Unpin + 'static,
+        State: SnapStateProviderFactory + StateProviderFactory + Unpin + 'static,
     {
         let client = config.client.clone();
-        let (handle, network, txpool, eth) = NetworkManager::builder(config)
-            .await?
-            .transactions(pool)
-            .request_handler(client)
-            .split_with_handle();
+        let mut builder = NetworkManager::builder(config).await?;
+        if self.network.snap {
+            let (snap, snap_protocol) = SnapRequestHandler::new(state);
+            builder = builder.add_protocol(snap_protocol);
+            task_executor.spawn_critical("p2p snap request handler", snap);
+        }
+        let (handle, mut network, _, eth) =
+            builder.request_handler_with_config(client, eth_requests_config).split_with_handle();
+
+        if let Some(heartbeat) = heartbeat {
+            network.set_heartbeat(heartbeat);
+        }
 
-        task_executor.spawn_critical("p2p txpool", txpool);
+        // the transactions manager is recreated with a new channel to the network if it panics,
+        // it only tracks the sessions that are established after it was (re)started
+        {
+            let handle = handle.clone();
+            task_executor.spawn_critical_with_restart(
+                "p2p txpool",
+                RestartPolicy::Restart { max_restarts: 3, backoff: Duration::from_secs(1) },
+                move || {
+                    let (tx, rx) = unbounded_channel();
+                    handle.set_transactions(tx);
+                    let txpool = TransactionsManager::with_config(
+                        handle.clone(),
+                        pool.clone(),
+                        rx,
+                        transactions_config.clone(),
+                    );
+                    match &fee_floor {
+                        Some(fee_floor) => txpool.with_fee_floor(Arc::clone(fee_floor)),
+                        None => txpool,
+                    }
+                },
+            );
+        }
         task_executor.spawn_critical("p2p eth request handler", eth);
 
+        // the network manager owns the listener and the state shared by all network handles, so it
+        // can't be recreated and a panic shuts down the node
         let known_peers_file = self.network.persistent_peers_file(default_peers_path);
         task_executor.spawn_critical_with_signal("p2p network task", |shutdown| {
             run_network_until_shutdown(shutdown, network, known_peers_file)
@@ -794,6 +1152,10 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
 
         let mut builder = Pipeline::builder();
 
+        for hook in self.ext.stage_hooks() {
+            builder = builder.with_stage_hook(hook);
+        }
+
         if let Some(max_block) = max_block {
             debug!(target: "reth::cli", max_block, "Configuring builder to use max block");
             builder = builder.with_max_block(max_block)
@@ -805,6 +1167,7 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
 
         let stack_config = InspectorStackConfig {
             use_printer_tracer: self.debug.print_inspector,
+            use_opcode_counter: false,
             hook: if let Some(hook_block) = self.debug.hook_block {
                 Hook::Block(hook_block)
             } else if let Some(tx) = self.debug.hook_transaction {
@@ -833,13 +1196,22 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
                     body_downloader,
                     factory.clone(),
                 )
-                .set(
-                    TotalDifficultyStage::new(consensus)
-                        .with_commit_threshold(stage_config.total_difficulty.commit_threshold),
+                .with_trusted_header_checkpoints(
+                    stage_config.headers.trusted_checkpoints.iter().copied().map(Into::into),
                 )
-                .set(SenderRecoveryStage {
-                    commit_threshold: stage_config.sender_recovery.commit_threshold,
+                .set({
+                    let stage = TotalDifficultyStage::new(consensus)
+                        .with_commit_threshold(stage_config.total_difficulty.commit_threshold);
+                    if stage_config.total_difficulty.elide_post_merge {
+                        stage.with_post_merge_elision(&self.chain)
+                    } else {
+                        stage
+                    }
                 })
+                .set(SenderRecoveryStage::new(
+                    stage_config.sender_recovery.commit_threshold,
+                    prune_modes.clone(),
+                ))
                 .set(
                     ExecutionStage::new(
                         factory,
@@ -854,7 +1226,8 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
                             .max(stage_config.storage_hashing.clean_threshold),
                         prune_modes.clone(),
                     )
-                    .with_metrics_tx(metrics_tx),
+                    .with_metrics_tx(metrics_tx)
+                    .with_preimages(stage_config.execution.preimages),
                 )
                 .set(AccountHashingStage::new(
                     stage_config.account_hashing.clean_threshold,
@@ -876,7 +1249,12 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
                 .set(IndexStorageHistoryStage::new(
                     stage_config.index_storage_history.commit_threshold,
                     prune_modes,
-                )),
+                ))
+                .add_after(
+                    ChainStatsStage::new(stage_config.chain_stats.commit_threshold),
+                    StageId::TotalDifficulty,
+                )
+                .disable_if(ChainStatsStage::ID, || !stage_config.chain_stats.enabled),
             )
             .build(db, self.chain.clone());
 
@@ -895,7 +1273,8 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
 }
 
 /// Drives the [NetworkManager] future until a [Shutdown](reth_tasks::shutdown::Shutdown) signal is
-/// received. If configured, this writes known peers to `persistent_peers_file` afterwards.
+/// received. Afterwards, this removes the mapped ports from the gateway and, if configured, writes
+/// known peers to `persistent_peers_file`.
 async fn run_network_until_shutdown<C>(
     shutdown: reth_tasks::shutdown::Shutdown,
     network: NetworkManager<C>,
@@ -910,18 +1289,17 @@ async fn run_network_until_shutdown<C>(
         _ = shutdown => {},
     }
 
+    network.remove_port_mappings().await;
+
     if let Some(file_path) = persistent_peers_file {
-        let known_peers = network.all_peers().collect::<Vec<_>>();
-        if let Ok(known_peers) = serde_json::to_string_pretty(&known_peers) {
-            trace!(target : "reth::cli", peers_file =?file_path, num_peers=%known_peers.len(), "Saving current peers");
-            let parent_dir = file_path.parent().map(std::fs::create_dir_all).transpose();
-            match parent_dir.and_then(|_| std::fs::write(&file_path, known_peers)) {
-                Ok(_) => {
-                    info!(target: "reth::cli", peers_file=?file_path, "Wrote network peers to file");
-                }
-                Err(err) => {
-                    warn!(target: "reth::cli", ?err, peers_file=?file_path, "Failed to write network peers to file");
-                }
+        let known_peers = network.persisted_peers();
+        trace!(target : "reth::cli", peers_file =?file_path, num_peers=%known_peers.len(), "Saving current peers");
+        match write_peers_file(&file_path, &known_peers) {
+            Ok(_) => {
+                info!(target: "reth::cli", peers_file=?file_path, "Wrote network peers to file");
+            }
+            Err(err) => {
+                warn!(target: "reth::cli", ?err, peers_file=?file_path, "Failed to write network peers to file");
             }
         }
     }
@@ -962,6 +1340,19 @@ mod tests {
         assert_eq!(cmd.network.port, Some(99));
     }
 
+    #[test]
+    fn parse_status_server() {
+        let cmd = NodeCommand::<()>::try_parse_from(["reth"]).unwrap();
+        assert_eq!(cmd.status_server, None);
+
+        let cmd = NodeCommand::<()>::try_parse_from(["reth", "--with-status-server"]).unwrap();
+        assert_eq!(cmd.status_server, Some(DEFAULT_STATUS_SERVER_ADDR.parse().unwrap()));
+
+        let cmd =
+            NodeCommand::<()>::try_parse_from(["reth", "--with-status-server", ":9600"]).unwrap();
+        assert_eq!(cmd.status_server, Some(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 9600)));
+    }
+
     #[test]
     fn parse_metrics_port() {
         let cmd = NodeCommand::<()>::try_parse_from(["reth", "--metrics", "9001"]).unwrap();
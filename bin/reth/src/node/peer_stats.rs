@@ -0,0 +1,289 @@
+//! Exports anonymized statistics of the sessions with peers for network research.
+
+use futures::{Stream, StreamExt};
+use hyper::{client::HttpConnector, header::CONTENT_TYPE, Body, Client, Method, Request, Uri};
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+use reth_config::config::PeerStatsConfig;
+use reth_network::NetworkEvent;
+use reth_primitives::PeerId;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::OpenOptions,
+    io::Write,
+    path::PathBuf,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use tracing::{debug, warn};
+
+/// The statistics of the sessions with peers over an interval.
+///
+/// Reports don't contain peer ids or addresses, only the name and version of the client of a
+/// peer, so they can be shared without identifying the peers.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerStatsReport {
+    /// The unix timestamp of the end of the interval in seconds.
+    pub timestamp: u64,
+    /// The length of the interval in seconds.
+    pub interval: u64,
+    /// The number of sessions that were active at the end of the interval.
+    pub active_sessions: u64,
+    /// The statistics of the sessions by client name and version.
+    pub clients: BTreeMap<String, ClientStats>,
+    /// The number of closed sessions by disconnect reason.
+    pub disconnect_reasons: BTreeMap<String, u64>,
+}
+
+/// The statistics of the sessions with peers that run the same client.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClientStats {
+    /// The number of established sessions.
+    pub established: u64,
+    /// The number of closed sessions.
+    pub closed: u64,
+    /// The total duration of the closed sessions in seconds.
+    pub session_secs: u64,
+}
+
+impl PeerStatsReport {
+    /// Adds the statistics of another report to this report.
+    ///
+    /// The merged report ends at the later timestamp and spans the sum of both intervals.
+    pub fn merge(&mut self, other: &PeerStatsReport) {
+        if other.timestamp >= self.timestamp {
+            self.active_sessions = other.active_sessions;
+            self.timestamp = other.timestamp;
+        }
+        self.interval += other.interval;
+        for (client, stats) in &other.clients {
+            let merged = self.clients.entry(client.clone()).or_default();
+            merged.established += stats.established;
+            merged.closed += stats.closed;
+            merged.session_secs += stats.session_secs;
+        }
+        for (reason, count) in &other.disconnect_reasons {
+            *self.disconnect_reasons.entry(reason.clone()).or_default() += count;
+        }
+    }
+}
+
+/// Collects the statistics of sessions from [NetworkEvent]s.
+#[derive(Debug)]
+pub struct PeerStatsCollector {
+    /// The client and start of the active sessions.
+    sessions: HashMap<PeerId, (String, Instant)>,
+    /// The statistics of the current interval.
+    report: PeerStatsReport,
+    /// The start of the current interval.
+    interval_start: Instant,
+}
+
+impl PeerStatsCollector {
+    /// Creates a new collector.
+    pub fn new() -> Self {
+        Self {
+            sessions: HashMap::new(),
+            report: PeerStatsReport::default(),
+            interval_start: Instant::now(),
+        }
+    }
+
+    /// Records a network event.
+    pub fn on_network_event(&mut self, event: &NetworkEvent) {
+        match event {
+            NetworkEvent::SessionEstablished { peer_id, client_version, .. } => {
+                let client = anonymize_client_version(client_version);
+                self.report.clients.entry(client.clone()).or_default().established += 1;
+                self.sessions.insert(*peer_id, (client, Instant::now()));
+            }
+            NetworkEvent::SessionClosed { peer_id, reason } => {
+                let Some((client, start)) = self.sessions.remove(peer_id) else { return };
+                let stats = self.report.clients.entry(client).or_default();
+                stats.closed += 1;
+                stats.session_secs += start.elapsed().as_secs();
+
+                let reason =
+                    reason.as_ref().map_or_else(|| "Unknown".to_string(), ToString::to_string);
+                *self.report.disconnect_reasons.entry(reason).or_default() += 1;
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the statistics of the current interval and starts a new interval.
+    pub fn take_report(&mut self) -> PeerStatsReport {
+        let mut report = std::mem::take(&mut self.report);
+        report.timestamp =
+            SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        report.interval = self.interval_start.elapsed().as_secs();
+        report.active_sessions = self.sessions.len() as u64;
+        self.interval_start = Instant::now();
+        report
+    }
+}
+
+impl Default for PeerStatsCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the name and version of the client, e.g. `Geth/v1.12.0-stable` for
+/// `Geth/mynode/v1.12.0-stable/linux-amd64/go1.20.5`.
+///
+/// Custom node names, platforms and compiler versions can identify a peer and are removed.
+fn anonymize_client_version(client_version: &str) -> String {
+    let mut parts = client_version.split('/');
+    let name = parts.next().unwrap_or_default();
+    let version = parts.find(|part| {
+        part.strip_prefix('v')
+            .and_then(|version| version.chars().next())
+            .is_some_and(|c| c.is_ascii_digit())
+    });
+    match version {
+        Some(version) => format!("{name}/{version}"),
+        None => name.to_string(),
+    }
+}
+
+/// Periodically writes the statistics of the sessions to the file and posts them to the endpoint
+/// of the config.
+pub async fn export_peer_stats<St>(config: PeerStatsConfig, mut events: St)
+where
+    St: Stream<Item = NetworkEvent> + Unpin,
+{
+    let endpoint = match config.endpoint.as_deref().map(str::parse::<Uri>).transpose() {
+        Ok(endpoint) => endpoint,
+        Err(err) => {
+            warn!(target: "reth::cli", %err, "Invalid peer stats endpoint");
+            None
+        }
+    };
+    let connector =
+        HttpsConnectorBuilder::new().with_native_roots().https_or_http().enable_http1().build();
+    let exporter = PeerStatsExporter {
+        file: config.file,
+        endpoint,
+        client: Client::builder().build(connector),
+    };
+
+    let mut collector = PeerStatsCollector::new();
+    let mut interval = tokio::time::interval(Duration::from_secs(config.interval.max(1)));
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    // the first tick completes immediately
+    interval.tick().await;
+    loop {
+        tokio::select! {
+            event = events.next() => match event {
+                Some(event) => collector.on_network_event(&event),
+                None => return,
+            },
+            _ = interval.tick() => exporter.export(&collector.take_report()).await,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct PeerStatsExporter {
+    file: Option<PathBuf>,
+    endpoint: Option<Uri>,
+    client: Client<HttpsConnector<HttpConnector>>,
+}
+
+impl PeerStatsExporter {
+    async fn export(&self, report: &PeerStatsReport) {
+        let body = match serde_json::to_vec(report) {
+            Ok(body) => body,
+            Err(err) => {
+                warn!(target: "reth::cli", %err, "Failed to serialize peer stats");
+                return
+            }
+        };
+
+        if let Some(path) = &self.file {
+            let written =
+                OpenOptions::new().create(true).append(true).open(path).and_then(|mut file| {
+                    file.write_all(&body)?;
+                    file.write_all(b"\n")
+                });
+            if let Err(err) = written {
+                warn!(target: "reth::cli", %err, path = %path.display(), "Failed to write peer stats");
+            }
+        }
+
+        if let Some(endpoint) = &self.endpoint {
+            let request = Request::builder()
+                .method(Method::POST)
+                .uri(endpoint.clone())
+                .header(CONTENT_TYPE, "application/json")
+                .body(Body::from(body))
+                .expect("valid request");
+            match self.client.request(request).await {
+                Ok(response) if response.status().is_success() => {
+                    debug!(target: "reth::cli", "Posted peer stats");
+                }
+                Ok(response) => {
+                    warn!(target: "reth::cli", status = %response.status(), "Peer stats endpoint rejected report");
+                }
+                Err(err) => {
+                    warn!(target: "reth::cli", %err, "Failed to post peer stats");
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn anonymize_client_versions() {
+        assert_eq!(
+            anonymize_client_version("Geth/mynode/v1.12.0-stable/linux-amd64/go1.20.5"),
+            "Geth/v1.12.0-stable"
+        );
+        assert_eq!(
+            anonymize_client_version("reth/v0.1.0-alpha.8/x86_64-unknown-linux-gnu"),
+            "reth/v0.1.0-alpha.8"
+        );
+        assert_eq!(anonymize_client_version("erigon/vendor"), "erigon");
+    }
+
+    #[test]
+    fn merge_reports() {
+        let mut report = PeerStatsReport {
+            timestamp: 10,
+            interval: 5,
+            active_sessions: 3,
+            clients: BTreeMap::from([(
+                "Geth/v1.12.0".to_string(),
+                ClientStats { established: 2, closed: 1, session_secs: 60 },
+            )]),
+            disconnect_reasons: BTreeMap::from([("Too many peers".to_string(), 1)]),
+        };
+        report.merge(&PeerStatsReport {
+            timestamp: 15,
+            interval: 5,
+            active_sessions: 4,
+            clients: BTreeMap::from([
+                (
+                    "Geth/v1.12.0".to_string(),
+                    ClientStats { established: 1, closed: 2, session_secs: 30 },
+                ),
+                ("reth/v0.1.0".to_string(), ClientStats { established: 1, ..Default::default() }),
+            ]),
+            disconnect_reasons: BTreeMap::from([("Too many peers".to_string(), 2)]),
+        });
+
+        assert_eq!(report.timestamp, 15);
+        assert_eq!(report.interval, 10);
+        assert_eq!(report.active_sessions, 4);
+        assert_eq!(
+            report.clients["Geth/v1.12.0"],
+            ClientStats { established: 3, closed: 3, session_secs: 90 }
+        );
+        assert_eq!(report.clients["reth/v0.1.0"].established, 1);
+        assert_eq!(report.disconnect_reasons["Too many peers"], 3);
+    }
+}
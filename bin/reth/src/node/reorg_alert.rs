@@ -0,0 +1,109 @@
+//! Posts deep reorgs of the canonical chain to a webhook.
+
+use hyper::{client::HttpConnector, header::CONTENT_TYPE, Body, Client, Method, Request, Uri};
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+use reth_blockchain_tree::{ReorgEvent, ReorgHook};
+use reth_primitives::{BlockNumber, H256};
+use reth_tasks::TaskExecutor;
+use serde::Serialize;
+use tracing::{debug, warn};
+
+/// A [ReorgHook] that posts every reorg as JSON to a webhook.
+///
+/// The requests are sent in the background, failed requests are logged and not retried.
+#[derive(Debug)]
+pub struct WebhookReorgHook {
+    url: Uri,
+    client: Client<HttpsConnector<HttpConnector>>,
+    executor: TaskExecutor,
+}
+
+impl WebhookReorgHook {
+    /// Creates a hook that posts to the given `http` or `https` URL.
+    pub fn new(url: &str, executor: TaskExecutor) -> eyre::Result<Self> {
+        let url: Uri =
+            url.parse().map_err(|err| eyre::eyre!("invalid reorg webhook {url}: {err}"))?;
+        if !matches!(url.scheme_str(), Some("http" | "https")) || url.host().is_none() {
+            eyre::bail!("invalid reorg webhook {url}: expected an http or https URL")
+        }
+
+        let connector =
+            HttpsConnectorBuilder::new().with_native_roots().https_or_http().enable_http1().build();
+        Ok(Self { url, client: Client::builder().build(connector), executor })
+    }
+}
+
+impl ReorgHook for WebhookReorgHook {
+    fn on_reorg(&self, event: &ReorgEvent) {
+        let body = match serde_json::to_vec(&ReorgPayload::from(event)) {
+            Ok(body) => body,
+            Err(err) => {
+                warn!(target: "reth::cli", %err, "Failed to serialize reorg alert");
+                return
+            }
+        };
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(self.url.clone())
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .expect("valid request");
+
+        let client = self.client.clone();
+        let depth = event.depth;
+        self.executor.spawn(async move {
+            match client.request(request).await {
+                Ok(response) if response.status().is_success() => {
+                    debug!(target: "reth::cli", depth, "Posted reorg alert");
+                }
+                Ok(response) => {
+                    warn!(target: "reth::cli", depth, status = %response.status(), "Reorg webhook rejected alert");
+                }
+                Err(err) => {
+                    warn!(target: "reth::cli", depth, %err, "Failed to post reorg alert");
+                }
+            }
+        });
+    }
+}
+
+/// The JSON body that is posted to the webhook.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReorgPayload {
+    depth: u64,
+    fork_block_number: BlockNumber,
+    fork_block_hash: H256,
+    old_tip_number: BlockNumber,
+    old_tip_hash: H256,
+    new_tip_number: BlockNumber,
+    new_tip_hash: H256,
+}
+
+impl From<&ReorgEvent> for ReorgPayload {
+    fn from(event: &ReorgEvent) -> Self {
+        Self {
+            depth: event.depth,
+            fork_block_number: event.fork_block.number,
+            fork_block_hash: event.fork_block.hash,
+            old_tip_number: event.old_tip.number,
+            old_tip_hash: event.old_tip.hash,
+            new_tip_number: event.new_tip.number,
+            new_tip_hash: event.new_tip.hash,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_tasks::TaskManager;
+
+    #[tokio::test]
+    async fn rejects_non_http_webhooks() {
+        let manager = TaskManager::new(tokio::runtime::Handle::current());
+        assert!(WebhookReorgHook::new("ftp://alerts.example.com/reth", manager.executor()).is_err());
+        assert!(WebhookReorgHook::new("alerts.example.com/reth", manager.executor()).is_err());
+        assert!(WebhookReorgHook::new("/reth", manager.executor()).is_err());
+    }
+}
@@ -0,0 +1,199 @@
+//! Background verification of the stored trie against the hashed state.
+
+use reth_db::{
+    cursor::{DbCursorRO, DbDupCursorRO},
+    database::Database,
+    tables,
+    transaction::DbTx,
+};
+use reth_metrics::{metrics::Counter, Metrics};
+use reth_primitives::{
+    stage::{StageId, StageUnitCheckpoint},
+    trie::Nibbles,
+    BlockNumber, H256,
+};
+use reth_trie::{prefix_set::PrefixSetMut, StateRoot, StorageRoot};
+use std::{sync::Arc, time::Duration};
+use tracing::{debug, error, info};
+
+/// Storage tries with more slots than this are skipped when sampled, to keep each run cheap.
+const MAX_SAMPLED_STORAGE_SLOTS: usize = 10_000;
+
+/// Periodically checks that the stored trie is consistent with the hashed state, to detect silent
+/// disk corruption early.
+///
+/// Each run recomputes the storage roots of a random sample of accounts from their hashed storage
+/// and compares them against the roots derived from the stored storage trie nodes. Optionally,
+/// every n-th run also recomputes the state root from the hashed state only and compares it against
+/// the state root of the header at the checkpoint.
+///
+/// The hashing and merkle stages commit their progress separately, so the hashed state and the
+/// trie only match while the pipeline is idle. Runs are skipped while the pipeline is running.
+///
+/// Mismatches are reported via logs and metrics, the verifier never modifies the database.
+#[derive(Debug)]
+pub struct StateRootVerifier<DB> {
+    db: Arc<DB>,
+    /// The interval between runs.
+    interval: Duration,
+    /// The number of storage tries sampled per run.
+    samples: usize,
+    /// Also verify the state root every n-th run.
+    full_every: Option<u64>,
+    metrics: StateRootVerifierMetrics,
+}
+
+impl<DB: Database + 'static> StateRootVerifier<DB> {
+    /// Creates a new verifier.
+    pub fn new(db: Arc<DB>, interval: Duration, samples: usize, full_every: Option<u64>) -> Self {
+        Self { db, interval, samples, full_every, metrics: Default::default() }
+    }
+
+    /// Runs the verifier until the node shuts down.
+    pub async fn run(self) {
+        let this = Arc::new(self);
+        let mut interval = tokio::time::interval(this.interval);
+        // the first tick completes immediately, skip it to not slow down the startup
+        interval.tick().await;
+
+        let mut run = 0u64;
+        loop {
+            interval.tick().await;
+            run += 1;
+            let full = this.full_every.map_or(false, |every| every > 0 && run % every == 0);
+
+            let verifier = Arc::clone(&this);
+            match tokio::task::spawn_blocking(move || verifier.verify(full)).await {
+                Ok(Ok(())) => {}
+                Ok(Err(err)) => {
+                    debug!(target: "reth::cli", %err, "Failed to verify state root")
+                }
+                Err(err) => error!(target: "reth::cli", %err, "State root verification panicked"),
+            }
+        }
+    }
+
+    fn verify(&self, full: bool) -> eyre::Result<()> {
+        let tx = self.db.tx()?;
+
+        let Some(block) = idle_checkpoint(&tx)? else {
+            debug!(target: "reth::cli", "Pipeline is running, skipping state root verification");
+            self.metrics.skipped_runs.increment(1);
+            return Ok(())
+        };
+
+        for _ in 0..self.samples {
+            self.verify_random_storage_root(&tx)?;
+        }
+
+        if full {
+            let Some(header) = tx.get::<tables::Headers>(block)? else { return Ok(()) };
+
+            let root = StateRoot::new(&tx).with_stored_trie_ignored().root()?;
+            self.metrics.state_roots_verified.increment(1);
+            if root == header.state_root {
+                info!(target: "reth::cli", block, ?root, "Verified state root");
+            } else {
+                self.metrics.state_root_mismatches.increment(1);
+                error!(
+                    target: "reth::cli",
+                    block,
+                    computed = ?root,
+                    expected = ?header.state_root,
+                    "State root mismatch, the database may be corrupted"
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Recomputes the storage root of a random account from its hashed storage and compares it
+    /// against the root derived from the stored storage trie.
+    fn verify_random_storage_root<'a, TX: DbTx<'a>>(&self, tx: &TX) -> eyre::Result<()> {
+        let mut cursor = tx.cursor_dup_read::<tables::HashedStorage>()?;
+        let entry = match cursor.seek(H256::random())? {
+            Some(entry) => Some(entry),
+            // wrap around to the first account
+            None => cursor.first()?,
+        };
+        let Some((hashed_address, _)) = entry else { return Ok(()) };
+
+        let mut prefixes = PrefixSetMut::default();
+        for entry in cursor.walk_dup(Some(hashed_address), None)? {
+            if prefixes.len() >= MAX_SAMPLED_STORAGE_SLOTS {
+                debug!(target: "reth::cli", ?hashed_address, "Skipping large storage trie");
+                return Ok(())
+            }
+            let (_, entry) = entry?;
+            prefixes.insert(Nibbles::unpack(entry.key));
+        }
+
+        let stored = StorageRoot::new_hashed(tx, hashed_address).root()?;
+        let recomputed = StorageRoot::new_hashed(tx, hashed_address)
+            .with_changed_prefixes(prefixes.freeze())
+            .root()?;
+
+        self.metrics.storage_roots_verified.increment(1);
+        if stored != recomputed {
+            self.metrics.storage_root_mismatches.increment(1);
+            error!(
+                target: "reth::cli",
+                ?hashed_address,
+                ?stored,
+                ?recomputed,
+                "Storage root mismatch, the database may be corrupted"
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the checkpoint of the idle pipeline, or `None` if the pipeline is running.
+///
+/// The pipeline is idle if the hashing and merkle stages are at the checkpoint of the finish stage
+/// and none of them has committed intermediate progress.
+fn idle_checkpoint<'a, TX: DbTx<'a>>(tx: &TX) -> eyre::Result<Option<BlockNumber>> {
+    let checkpoint = |stage_id: StageId| -> eyre::Result<_> {
+        Ok(tx.get::<tables::SyncStage>(stage_id.to_string())?.unwrap_or_default())
+    };
+
+    let finish = checkpoint(StageId::Finish)?.block_number;
+    for stage_id in [StageId::AccountHashing, StageId::StorageHashing, StageId::MerkleExecute] {
+        let checkpoint = checkpoint(stage_id)?;
+        let in_progress = match checkpoint.stage_checkpoint {
+            Some(StageUnitCheckpoint::Account(checkpoint)) => checkpoint.address.is_some(),
+            Some(StageUnitCheckpoint::Storage(checkpoint)) => checkpoint.address.is_some(),
+            _ => false,
+        };
+        if checkpoint.block_number != finish || in_progress {
+            return Ok(None)
+        }
+    }
+
+    // the merkle stage stores its intermediate progress separately
+    let merkle_progress =
+        tx.get::<tables::SyncStageProgress>(StageId::MerkleExecute.to_string())?;
+    if merkle_progress.map_or(false, |progress| !progress.is_empty()) {
+        return Ok(None)
+    }
+
+    Ok(Some(finish))
+}
+
+/// Metrics for the state root verifier.
+#[derive(Metrics)]
+#[metrics(scope = "state_root_verifier")]
+struct StateRootVerifierMetrics {
+    /// The number of verified storage roots.
+    storage_roots_verified: Counter,
+    /// The number of storage roots that did not match the stored storage trie.
+    storage_root_mismatches: Counter,
+    /// The number of verified state roots.
+    state_roots_verified: Counter,
+    /// The number of state roots that did not match the header.
+    state_root_mismatches: Counter,
+    /// The number of runs skipped because the pipeline was running.
+    skipped_runs: Counter,
+}
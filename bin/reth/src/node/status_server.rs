@@ -0,0 +1,523 @@
+//! A server that streams structured node lifecycle events, intended for dashboards and terminal
+//! UIs.
+//!
+//! The server exposes two endpoints:
+//!
+//! - `/events`: A stream of [server-sent events](https://html.spec.whatwg.org/multipage/server-sent-events.html)
+//!   where every event is a JSON encoded [StatusEvent]. The latest known state of the node is sent
+//!   first, so clients don't have to wait for the next update.
+//! - `/status`: The latest known state of the node as a JSON array of [StatusEvent]s.
+//!
+//! Unlike the tracing logs, the format of these events is stable and machine readable.
+
+use crate::node::events::NodeEvent;
+use eyre::WrapErr;
+use futures::{Stream, StreamExt};
+use hyper::{
+    body::Bytes,
+    header::{CACHE_CONTROL, CONTENT_TYPE},
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use reth_beacon_consensus::{BeaconConsensusEngineEvent, ForkchoiceStatus};
+use reth_network::{NetworkEvent, NetworkHandle};
+use reth_network_api::PeersInfo;
+use reth_payload_builder::{PayloadBuilderEvent, PayloadId};
+use reth_primitives::{BlockNumber, PeerId, H256, U256};
+use reth_stages::{ExecOutput, PipelineEvent};
+use reth_transaction_pool::{PoolSize, TransactionPool};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    convert::Infallible,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::sync::broadcast;
+use tracing::{debug, error};
+
+/// The default address of the status server.
+pub const DEFAULT_STATUS_SERVER_ADDR: &str = "127.0.0.1:9545";
+
+/// The number of events buffered for slow clients before they start missing events.
+const STATUS_EVENT_BUFFER: usize = 1024;
+
+/// Interval of publishing the size of the transaction pool.
+const TXPOOL_STATS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A structured node lifecycle event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StatusEvent {
+    /// A pipeline stage is about to be run.
+    StageStarted {
+        /// The stage that is about to be run.
+        stage: String,
+        /// 1-indexed position of the stage in the pipeline.
+        pipeline_position: usize,
+        /// Total number of stages in the pipeline.
+        pipeline_total: usize,
+        /// The block the stage starts from.
+        block_number: BlockNumber,
+    },
+    /// A pipeline stage made progress.
+    StageProgress {
+        /// The stage that was run.
+        stage: String,
+        /// 1-indexed position of the stage in the pipeline.
+        pipeline_position: usize,
+        /// Total number of stages in the pipeline.
+        pipeline_total: usize,
+        /// The block the stage reached.
+        block_number: BlockNumber,
+        /// The number of entities the stage processed, if the stage reports it.
+        processed: Option<u64>,
+        /// The total number of entities the stage has to process, if the stage reports it.
+        total: Option<u64>,
+        /// Whether the stage is done.
+        done: bool,
+    },
+    /// A pipeline stage was unwound.
+    StageUnwound {
+        /// The stage that was unwound.
+        stage: String,
+        /// The block the stage was unwound to.
+        block_number: BlockNumber,
+    },
+    /// The number of connected peers changed.
+    Peers {
+        /// The number of connected peers.
+        connected: usize,
+    },
+    /// A session with a peer was established.
+    PeerConnected {
+        /// The identifier of the peer.
+        peer_id: PeerId,
+        /// The client version the peer announced.
+        client_version: String,
+        /// The remote address of the peer.
+        remote_addr: SocketAddr,
+        /// The negotiated `eth` protocol version.
+        eth_version: u8,
+    },
+    /// A session with a peer was closed.
+    PeerDisconnected {
+        /// The identifier of the peer.
+        peer_id: PeerId,
+    },
+    /// A block was added to the canonical chain.
+    Head {
+        /// The number of the block.
+        number: BlockNumber,
+        /// The hash of the block.
+        hash: H256,
+        /// The timestamp of the block.
+        timestamp: u64,
+        /// The gas used by the block.
+        gas_used: u64,
+        /// The number of transactions in the block.
+        transactions: usize,
+    },
+    /// The size of the transaction pool.
+    TxPool {
+        /// The number of transactions in the pending sub-pool.
+        pending: usize,
+        /// The number of transactions in the basefee sub-pool.
+        basefee: usize,
+        /// The number of transactions in the queued sub-pool.
+        queued: usize,
+    },
+    /// The forkchoice state was updated.
+    Forkchoice {
+        /// The head block hash.
+        head: H256,
+        /// The safe block hash.
+        safe: H256,
+        /// The finalized block hash.
+        finalized: H256,
+        /// The status of the forkchoice update.
+        status: &'static str,
+    },
+    /// A payload job was started.
+    PayloadJobStarted {
+        /// The identifier of the payload.
+        id: PayloadId,
+        /// The parent block the payload is built on.
+        parent: H256,
+    },
+    /// A payload job failed.
+    PayloadJobFailed {
+        /// The identifier of the payload.
+        id: PayloadId,
+    },
+    /// A built payload was handed to the consensus layer.
+    PayloadBuilt {
+        /// The identifier of the payload.
+        id: PayloadId,
+        /// The number of the built block.
+        number: BlockNumber,
+        /// The hash of the built block.
+        hash: H256,
+        /// The gas used by the built block.
+        gas_used: u64,
+        /// The number of transactions in the built block.
+        transactions: usize,
+        /// The fees collected by the built block.
+        fees: U256,
+    },
+}
+
+impl StatusEvent {
+    /// Converts a [NodeEvent] into the corresponding [StatusEvent]s.
+    fn from_node_event(event: NodeEvent, network: &NetworkHandle) -> Vec<Self> {
+        let event = match event {
+            NodeEvent::Network(event) => {
+                let peer = match event {
+                    NetworkEvent::SessionEstablished {
+                        peer_id,
+                        remote_addr,
+                        client_version,
+                        version,
+                        ..
+                    } => StatusEvent::PeerConnected {
+                        peer_id,
+                        client_version: client_version.to_string(),
+                        remote_addr,
+                        eth_version: version.into(),
+                    },
+                    NetworkEvent::SessionClosed { peer_id, .. } => {
+                        StatusEvent::PeerDisconnected { peer_id }
+                    }
+                    _ => return Vec::new(),
+                };
+                return vec![peer, StatusEvent::Peers { connected: network.num_connected_peers() }]
+            }
+            NodeEvent::Pipeline(event) => match event {
+                PipelineEvent::Running {
+                    pipeline_position,
+                    pipeline_total,
+                    stage_id,
+                    checkpoint,
+                } => StatusEvent::StageStarted {
+                    stage: stage_id.to_string(),
+                    pipeline_position,
+                    pipeline_total,
+                    block_number: checkpoint.unwrap_or_default().block_number,
+                },
+                PipelineEvent::Ran {
+                    pipeline_position,
+                    pipeline_total,
+                    stage_id,
+                    result: ExecOutput { checkpoint, done },
+                } => {
+                    let entities = checkpoint.entities();
+                    StatusEvent::StageProgress {
+                        stage: stage_id.to_string(),
+                        pipeline_position,
+                        pipeline_total,
+                        block_number: checkpoint.block_number,
+                        processed: entities.map(|entities| entities.processed),
+                        total: entities.map(|entities| entities.total),
+                        done,
+                    }
+                }
+                PipelineEvent::Unwound { stage_id, result } => StatusEvent::StageUnwound {
+                    stage: stage_id.to_string(),
+                    block_number: result.checkpoint.block_number,
+                },
+                _ => return Vec::new(),
+            },
+            NodeEvent::ConsensusEngine(event) => match event {
+                BeaconConsensusEngineEvent::ForkchoiceUpdated(state, status) => {
+                    StatusEvent::Forkchoice {
+                        head: state.head_block_hash,
+                        safe: state.safe_block_hash,
+                        finalized: state.finalized_block_hash,
+                        status: match status {
+                            ForkchoiceStatus::Valid => "valid",
+                            ForkchoiceStatus::Invalid => "invalid",
+                            ForkchoiceStatus::Syncing => "syncing",
+                        },
+                    }
+                }
+                BeaconConsensusEngineEvent::CanonicalBlockAdded(block) => StatusEvent::Head {
+                    number: block.number,
+                    hash: block.hash,
+                    timestamp: block.timestamp,
+                    gas_used: block.gas_used,
+                    transactions: block.body.len(),
+                },
+                BeaconConsensusEngineEvent::ForkBlockAdded(_) => return Vec::new(),
+            },
+            NodeEvent::PayloadBuilder(event) => match event {
+                PayloadBuilderEvent::JobStarted { id, parent } => {
+                    StatusEvent::PayloadJobStarted { id, parent }
+                }
+                PayloadBuilderEvent::JobFailed { id } => StatusEvent::PayloadJobFailed { id },
+                PayloadBuilderEvent::Resolved(payload) => {
+                    let block = payload.block();
+                    StatusEvent::PayloadBuilt {
+                        id: payload.id(),
+                        number: block.number,
+                        hash: block.hash,
+                        gas_used: block.gas_used,
+                        transactions: block.body.len(),
+                        fees: payload.fees(),
+                    }
+                }
+            },
+            NodeEvent::ConsensusLayerHealth(_) => return Vec::new(),
+        };
+        vec![event]
+    }
+}
+
+/// The latest known state of the node, sent to new clients.
+#[derive(Debug, Default)]
+struct StatusSnapshot {
+    stage: Option<StatusEvent>,
+    peers: Option<StatusEvent>,
+    connected_peers: BTreeMap<PeerId, StatusEvent>,
+    head: Option<StatusEvent>,
+    forkchoice: Option<StatusEvent>,
+    payload: Option<StatusEvent>,
+    txpool: Option<StatusEvent>,
+}
+
+impl StatusSnapshot {
+    /// Records the event if it describes the current state of the node.
+    fn update(&mut self, event: &StatusEvent) {
+        let slot = match event {
+            StatusEvent::StageStarted { .. } |
+            StatusEvent::StageProgress { .. } |
+            StatusEvent::StageUnwound { .. } => &mut self.stage,
+            StatusEvent::Peers { .. } => &mut self.peers,
+            StatusEvent::PeerConnected { peer_id, .. } => {
+                self.connected_peers.insert(*peer_id, event.clone());
+                return
+            }
+            StatusEvent::PeerDisconnected { peer_id } => {
+                self.connected_peers.remove(peer_id);
+                return
+            }
+            StatusEvent::Head { .. } => &mut self.head,
+            StatusEvent::TxPool { .. } => &mut self.txpool,
+            StatusEvent::Forkchoice { .. } => &mut self.forkchoice,
+            StatusEvent::PayloadBuilt { .. } => &mut self.payload,
+            StatusEvent::PayloadJobStarted { .. } | StatusEvent::PayloadJobFailed { .. } => return,
+        };
+        *slot = Some(event.clone());
+    }
+
+    /// Returns all recorded events.
+    fn events(&self) -> Vec<StatusEvent> {
+        [&self.stage, &self.peers]
+            .into_iter()
+            .flatten()
+            .chain(self.connected_peers.values())
+            .chain(
+                [&self.head, &self.forkchoice, &self.payload, &self.txpool].into_iter().flatten(),
+            )
+            .cloned()
+            .collect()
+    }
+}
+
+/// Shared handle that publishes [StatusEvent]s to all connected clients.
+#[derive(Debug, Clone)]
+pub struct StatusEvents {
+    sender: broadcast::Sender<StatusEvent>,
+    snapshot: Arc<Mutex<StatusSnapshot>>,
+}
+
+impl StatusEvents {
+    /// Creates a new, empty publisher.
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(STATUS_EVENT_BUFFER);
+        Self { sender, snapshot: Default::default() }
+    }
+
+    /// Publishes the event to all clients.
+    pub fn publish(&self, event: StatusEvent) {
+        self.snapshot.lock().expect("not poisoned").update(&event);
+        let _ = self.sender.send(event);
+    }
+
+    /// Returns the latest known state of the node and a receiver for all following events.
+    pub(crate) fn subscribe(&self) -> (Vec<StatusEvent>, broadcast::Receiver<StatusEvent>) {
+        // subscribe while holding the lock, so no event is missed or sent twice
+        let snapshot = self.snapshot.lock().expect("not poisoned");
+        (snapshot.events(), self.sender.subscribe())
+    }
+
+    /// Returns the latest known state of the node.
+    fn snapshot(&self) -> Vec<StatusEvent> {
+        self.snapshot.lock().expect("not poisoned").events()
+    }
+}
+
+impl Default for StatusEvents {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Publishes the relevant node events until the stream is exhausted.
+pub async fn publish_events<E>(network: NetworkHandle, mut events: E, status: StatusEvents)
+where
+    E: Stream<Item = NodeEvent> + Unpin,
+{
+    while let Some(event) = events.next().await {
+        for event in StatusEvent::from_node_event(event, &network) {
+            status.publish(event);
+        }
+    }
+}
+
+/// Periodically publishes the size of the transaction pool.
+pub async fn publish_pool_stats<Pool>(pool: Pool, status: StatusEvents)
+where
+    Pool: TransactionPool,
+{
+    let mut interval = tokio::time::interval(TXPOOL_STATS_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+        let PoolSize { pending, basefee, queued, .. } = pool.pool_size();
+        status.publish(StatusEvent::TxPool { pending, basefee, queued });
+    }
+}
+
+/// Starts the status server at the given address.
+pub async fn start_status_server(
+    listen_addr: SocketAddr,
+    status: StatusEvents,
+) -> eyre::Result<()> {
+    let make_svc = make_service_fn(move |_| {
+        let status = status.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req: Request<Body>| {
+                let response = handle_request(req, &status);
+                async move { Ok::<_, Infallible>(response) }
+            }))
+        }
+    });
+    let server =
+        Server::try_bind(&listen_addr).wrap_err("Could not bind to address")?.serve(make_svc);
+
+    tokio::spawn(async move {
+        if let Err(err) = server.await {
+            error!(target: "reth::cli", %err, "Status server crashed");
+        }
+    });
+
+    Ok(())
+}
+
+fn handle_request(req: Request<Body>, status: &StatusEvents) -> Response<Body> {
+    if req.method() != Method::GET {
+        return empty_response(StatusCode::METHOD_NOT_ALLOWED)
+    }
+    match req.uri().path() {
+        "/events" => event_stream_response(status),
+        "/status" => match serde_json::to_vec(&status.snapshot()) {
+            Ok(body) => Response::builder()
+                .header(CONTENT_TYPE, "application/json")
+                .body(Body::from(body))
+                .expect("valid response"),
+            Err(_) => empty_response(StatusCode::INTERNAL_SERVER_ERROR),
+        },
+        _ => empty_response(StatusCode::NOT_FOUND),
+    }
+}
+
+fn empty_response(status: StatusCode) -> Response<Body> {
+    Response::builder().status(status).body(Body::empty()).expect("valid response")
+}
+
+/// Returns a response that streams all events to the client.
+fn event_stream_response(status: &StatusEvents) -> Response<Body> {
+    let (snapshot, mut events) = status.subscribe();
+    let (mut sender, body) = Body::channel();
+
+    tokio::spawn(async move {
+        for event in snapshot {
+            if sender.send_data(encode_event(&event)).await.is_err() {
+                return
+            }
+        }
+        loop {
+            let event = match events.recv().await {
+                Ok(event) => event,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    debug!(target: "reth::cli", skipped, "Status client is lagging behind");
+                    continue
+                }
+                Err(broadcast::error::RecvError::Closed) => return,
+            };
+            if sender.send_data(encode_event(&event)).await.is_err() {
+                // client disconnected
+                return
+            }
+        }
+    });
+
+    Response::builder()
+        .header(CONTENT_TYPE, "text/event-stream")
+        .header(CACHE_CONTROL, "no-cache")
+        .body(body)
+        .expect("valid response")
+}
+
+/// Encodes the event as a server-sent event.
+fn encode_event(event: &StatusEvent) -> Bytes {
+    let json = serde_json::to_string(event).expect("status events are serializable");
+    Bytes::from(format!("data: {json}\n\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_stage_progress() {
+        let event = StatusEvent::StageProgress {
+            stage: "Execution".to_string(),
+            pipeline_position: 4,
+            pipeline_total: 12,
+            block_number: 100,
+            processed: Some(10),
+            total: None,
+            done: false,
+        };
+        assert_eq!(
+            encode_event(&event),
+            Bytes::from(
+                "data: {\"type\":\"stage_progress\",\"stage\":\"Execution\",\"pipeline_position\":4,\"pipeline_total\":12,\"block_number\":100,\"processed\":10,\"total\":null,\"done\":false}\n\n"
+            )
+        );
+    }
+
+    #[test]
+    fn snapshot_keeps_latest_state() {
+        let head = StatusEvent::Head {
+            number: 1,
+            hash: H256::zero(),
+            timestamp: 0,
+            gas_used: 0,
+            transactions: 0,
+        };
+        let status = StatusEvents::new();
+        status.publish(StatusEvent::Peers { connected: 1 });
+        status.publish(head.clone());
+        status.publish(StatusEvent::Peers { connected: 2 });
+        status.publish(StatusEvent::PayloadJobFailed { id: PayloadId::new([0; 8]) });
+
+        let (snapshot, mut events) = status.subscribe();
+        assert_eq!(snapshot, vec![StatusEvent::Peers { connected: 2 }, head]);
+
+        status.publish(StatusEvent::Peers { connected: 3 });
+        assert_eq!(events.try_recv().unwrap(), StatusEvent::Peers { connected: 3 });
+    }
+}
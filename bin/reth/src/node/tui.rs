@@ -0,0 +1,430 @@
+//! Interactive terminal dashboard of the node.
+//!
+//! The dashboard renders the [StatusEvent]s published by the node, see
+//! [status_server](crate::node::status_server).
+
+use crate::node::status_server::StatusEvent;
+use crossterm::{
+    event::{self, Event, KeyCode, KeyModifiers},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use reth_primitives::{BlockNumber, PeerId, H256};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    io,
+    net::SocketAddr,
+    time::Duration,
+};
+use tokio::sync::broadcast::{self, error::TryRecvError};
+use tui::{
+    backend::{Backend, CrosstermBackend},
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Paragraph, Row, Table},
+    Frame, Terminal,
+};
+
+/// The number of recent blocks shown in the dashboard.
+const RECENT_BLOCKS: usize = 16;
+
+/// Interval of redrawing the dashboard.
+const TICK_RATE: Duration = Duration::from_millis(250);
+
+/// Available keybindings for the [NodeTui]
+static CMDS: [(&str, &str); 1] = [("q", "Quit")];
+
+/// Progress of a single pipeline stage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct StageRow {
+    stage: String,
+    block_number: BlockNumber,
+    processed: Option<u64>,
+    total: Option<u64>,
+    done: bool,
+}
+
+impl StageRow {
+    /// Formats the progress of the stage, if the stage reports it.
+    fn progress(&self) -> String {
+        match (self.processed, self.total) {
+            (Some(processed), Some(total)) if total > 0 => {
+                format!("{:.2}%", processed as f64 / total as f64 * 100.0)
+            }
+            _ => String::from("-"),
+        }
+    }
+}
+
+/// A connected peer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PeerRow {
+    client_version: String,
+    remote_addr: SocketAddr,
+    eth_version: u8,
+}
+
+/// A block of the canonical chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BlockRow {
+    number: BlockNumber,
+    hash: H256,
+    timestamp: u64,
+    gas_used: u64,
+    transactions: usize,
+}
+
+/// The state rendered by the dashboard.
+#[derive(Debug, Default)]
+struct Dashboard {
+    /// Progress of the pipeline stages, keyed by their position in the pipeline.
+    stages: BTreeMap<usize, StageRow>,
+    /// All connected peers.
+    peers: BTreeMap<PeerId, PeerRow>,
+    /// The most recent canonical blocks, newest first.
+    recent_blocks: VecDeque<BlockRow>,
+    /// The size of the pending, basefee and queued sub-pools.
+    txpool: Option<(usize, usize, usize)>,
+    /// The status of the latest forkchoice update.
+    forkchoice: Option<&'static str>,
+    /// The number of the latest payload built by the node.
+    last_payload: Option<BlockNumber>,
+}
+
+impl Dashboard {
+    /// Applies the event to the dashboard.
+    fn on_event(&mut self, event: StatusEvent) {
+        match event {
+            StatusEvent::StageStarted { stage, pipeline_position, block_number, .. } => {
+                let row = self.stages.entry(pipeline_position).or_insert_with(|| StageRow {
+                    stage: stage.clone(),
+                    block_number,
+                    processed: None,
+                    total: None,
+                    done: false,
+                });
+                row.stage = stage;
+                row.block_number = block_number;
+                row.done = false;
+            }
+            StatusEvent::StageProgress {
+                stage,
+                pipeline_position,
+                block_number,
+                processed,
+                total,
+                done,
+                ..
+            } => {
+                self.stages.insert(
+                    pipeline_position,
+                    StageRow { stage, block_number, processed, total, done },
+                );
+            }
+            StatusEvent::StageUnwound { stage, block_number } => {
+                if let Some(row) = self.stages.values_mut().find(|row| row.stage == stage) {
+                    row.block_number = block_number;
+                }
+            }
+            StatusEvent::PeerConnected { peer_id, client_version, remote_addr, eth_version } => {
+                self.peers.insert(peer_id, PeerRow { client_version, remote_addr, eth_version });
+            }
+            StatusEvent::PeerDisconnected { peer_id } => {
+                self.peers.remove(&peer_id);
+            }
+            StatusEvent::Head { number, hash, timestamp, gas_used, transactions } => {
+                // a reorg replaces the blocks at or above the new head
+                self.recent_blocks.retain(|block| block.number < number);
+                self.recent_blocks.push_front(BlockRow {
+                    number,
+                    hash,
+                    timestamp,
+                    gas_used,
+                    transactions,
+                });
+                self.recent_blocks.truncate(RECENT_BLOCKS);
+            }
+            StatusEvent::TxPool { pending, basefee, queued } => {
+                self.txpool = Some((pending, basefee, queued));
+            }
+            StatusEvent::Forkchoice { status, .. } => self.forkchoice = Some(status),
+            StatusEvent::PayloadBuilt { number, .. } => self.last_payload = Some(number),
+            StatusEvent::Peers { .. } |
+            StatusEvent::PayloadJobStarted { .. } |
+            StatusEvent::PayloadJobFailed { .. } => {}
+        }
+    }
+}
+
+/// Interactive terminal dashboard of the node.
+pub(crate) struct NodeTui {
+    /// Receiver of node events.
+    events: broadcast::Receiver<StatusEvent>,
+    /// The state of the dashboard.
+    dashboard: Dashboard,
+}
+
+impl NodeTui {
+    /// Creates a new dashboard from the latest known state of the node and the receiver of all
+    /// following events.
+    pub(crate) fn new(
+        (snapshot, events): (Vec<StatusEvent>, broadcast::Receiver<StatusEvent>),
+    ) -> Self {
+        let mut dashboard = Dashboard::default();
+        snapshot.into_iter().for_each(|event| dashboard.on_event(event));
+        Self { events, dashboard }
+    }
+
+    /// Applies all pending events to the dashboard.
+    fn drain_events(&mut self) {
+        loop {
+            match self.events.try_recv() {
+                Ok(event) => self.dashboard.on_event(event),
+                // skipped events are superseded by the following ones
+                Err(TryRecvError::Lagged(_)) => continue,
+                Err(TryRecvError::Empty) | Err(TryRecvError::Closed) => return,
+            }
+        }
+    }
+
+    /// Shows the [NodeTui] in the terminal until the user quits.
+    ///
+    /// This blocks the current thread.
+    pub(crate) fn run(mut self) -> eyre::Result<()> {
+        // Setup backend
+        enable_raw_mode()?;
+        let mut stdout = io::stdout();
+        execute!(stdout, EnterAlternateScreen)?;
+        let backend = CrosstermBackend::new(stdout);
+        let mut terminal = Terminal::new(backend)?;
+
+        let res = self.event_loop(&mut terminal);
+
+        // Restore terminal
+        disable_raw_mode()?;
+        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+        terminal.show_cursor()?;
+
+        Ok(res?)
+    }
+
+    /// Run the event loop
+    fn event_loop<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> io::Result<()> {
+        loop {
+            self.drain_events();
+            terminal.draw(|f| ui(f, &self.dashboard))?;
+
+            if crossterm::event::poll(TICK_RATE)? {
+                if let Event::Key(key) = event::read()? {
+                    // raw mode swallows the interrupt signal, so ctrl-c is handled here as well
+                    let ctrl_c = key.code == KeyCode::Char('c') &&
+                        key.modifiers.contains(KeyModifiers::CONTROL);
+                    if ctrl_c || matches!(key.code, KeyCode::Char('q') | KeyCode::Char('Q')) {
+                        return Ok(())
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Render the UI
+fn ui<B: Backend>(f: &mut Frame<'_, B>, dashboard: &Dashboard) {
+    let outer_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Percentage(40),
+                Constraint::Percentage(28),
+                Constraint::Min(5),
+                Constraint::Length(3),
+            ]
+            .as_ref(),
+        )
+        .split(f.size());
+
+    let header_style = Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD);
+
+    // Stages and summary
+    {
+        let inner_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+            .split(outer_chunks[0]);
+
+        let rows = dashboard.stages.iter().map(|(position, row)| {
+            Row::new(vec![
+                position.to_string(),
+                row.stage.clone(),
+                row.block_number.to_string(),
+                row.progress(),
+                if row.done { "done" } else { "running" }.to_string(),
+            ])
+        });
+        let stages = Table::new(rows)
+            .header(Row::new(vec!["#", "Stage", "Block", "Progress", "Status"]).style(header_style))
+            .block(Block::default().borders(Borders::ALL).title("Stages"))
+            .widths(&[
+                Constraint::Length(3),
+                Constraint::Percentage(35),
+                Constraint::Percentage(20),
+                Constraint::Percentage(20),
+                Constraint::Percentage(15),
+            ]);
+        f.render_widget(stages, inner_chunks[0]);
+
+        let fmt_opt = |value: Option<String>| value.unwrap_or_else(|| String::from("-"));
+        let (pending, basefee, queued) = match dashboard.txpool {
+            Some((pending, basefee, queued)) => {
+                (Some(pending.to_string()), Some(basefee.to_string()), Some(queued.to_string()))
+            }
+            None => (None, None, None),
+        };
+        let summary = [
+            format!("Peers: {}", dashboard.peers.len()),
+            format!(
+                "Head: {}",
+                fmt_opt(dashboard.recent_blocks.front().map(|block| block.number.to_string()))
+            ),
+            format!("Forkchoice: {}", dashboard.forkchoice.unwrap_or("-")),
+            format!(
+                "Last built payload: {}",
+                fmt_opt(dashboard.last_payload.map(|n| n.to_string()))
+            ),
+            String::new(),
+            String::from("Transaction pool"),
+            format!("  Pending: {}", fmt_opt(pending)),
+            format!("  Basefee: {}", fmt_opt(basefee)),
+            format!("  Queued:  {}", fmt_opt(queued)),
+        ]
+        .join("\n");
+        let summary = Paragraph::new(summary)
+            .block(Block::default().borders(Borders::ALL).title("Node"))
+            .alignment(Alignment::Left);
+        f.render_widget(summary, inner_chunks[1]);
+    }
+
+    // Peers
+    {
+        let rows = dashboard.peers.iter().map(|(peer_id, peer)| {
+            Row::new(vec![
+                format!("{peer_id:?}"),
+                peer.remote_addr.to_string(),
+                format!("eth/{}", peer.eth_version),
+                peer.client_version.clone(),
+            ])
+        });
+        let peers = Table::new(rows)
+            .header(Row::new(vec!["Peer", "Address", "Version", "Client"]).style(header_style))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("Peers ({})", dashboard.peers.len())),
+            )
+            .widths(&[
+                Constraint::Percentage(30),
+                Constraint::Percentage(20),
+                Constraint::Percentage(10),
+                Constraint::Percentage(40),
+            ]);
+        f.render_widget(peers, outer_chunks[1]);
+    }
+
+    // Recent blocks
+    {
+        let rows = dashboard.recent_blocks.iter().map(|block| {
+            Row::new(vec![
+                block.number.to_string(),
+                format!("{:?}", block.hash),
+                block.timestamp.to_string(),
+                block.transactions.to_string(),
+                block.gas_used.to_string(),
+            ])
+        });
+        let blocks = Table::new(rows)
+            .header(
+                Row::new(vec!["Number", "Hash", "Timestamp", "Transactions", "Gas used"])
+                    .style(header_style),
+            )
+            .block(Block::default().borders(Borders::ALL).title("Recent blocks"))
+            .widths(&[
+                Constraint::Percentage(12),
+                Constraint::Percentage(48),
+                Constraint::Percentage(14),
+                Constraint::Percentage(12),
+                Constraint::Percentage(14),
+            ]);
+        f.render_widget(blocks, outer_chunks[2]);
+    }
+
+    // Footer
+    let footer = Paragraph::new(
+        CMDS.iter().map(|(k, v)| format!("[{k}] {v}")).collect::<Vec<_>>().join(" | "),
+    )
+    .block(Block::default().borders(Borders::ALL))
+    .alignment(Alignment::Center)
+    .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
+    f.render_widget(footer, outer_chunks[3]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn head(number: BlockNumber) -> StatusEvent {
+        StatusEvent::Head {
+            number,
+            hash: H256::from_low_u64_be(number),
+            timestamp: number * 12,
+            gas_used: 0,
+            transactions: 0,
+        }
+    }
+
+    #[test]
+    fn keeps_recent_blocks() {
+        let mut dashboard = Dashboard::default();
+        for number in 0..RECENT_BLOCKS as u64 + 4 {
+            dashboard.on_event(head(number));
+        }
+        assert_eq!(dashboard.recent_blocks.len(), RECENT_BLOCKS);
+        assert_eq!(dashboard.recent_blocks.front().unwrap().number, RECENT_BLOCKS as u64 + 3);
+
+        // reorg to a lower head drops the replaced blocks
+        dashboard.on_event(head(10));
+        assert_eq!(dashboard.recent_blocks.front().unwrap().number, 10);
+        assert!(dashboard.recent_blocks.iter().all(|block| block.number <= 10));
+    }
+
+    #[test]
+    fn tracks_stages_and_peers() {
+        let mut dashboard = Dashboard::default();
+        dashboard.on_event(StatusEvent::StageStarted {
+            stage: "Headers".to_string(),
+            pipeline_position: 1,
+            pipeline_total: 2,
+            block_number: 0,
+        });
+        dashboard.on_event(StatusEvent::StageProgress {
+            stage: "Headers".to_string(),
+            pipeline_position: 1,
+            pipeline_total: 2,
+            block_number: 50,
+            processed: Some(50),
+            total: Some(200),
+            done: false,
+        });
+        assert_eq!(dashboard.stages[&1].progress(), "25.00%");
+
+        let peer_id = PeerId::random();
+        dashboard.on_event(StatusEvent::PeerConnected {
+            peer_id,
+            client_version: "reth".to_string(),
+            remote_addr: "127.0.0.1:30303".parse().unwrap(),
+            eth_version: 68,
+        });
+        assert_eq!(dashboard.peers.len(), 1);
+        dashboard.on_event(StatusEvent::PeerDisconnected { peer_id });
+        assert!(dashboard.peers.is_empty());
+    }
+}
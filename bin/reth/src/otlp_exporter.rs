@@ -0,0 +1,224 @@
+//! OpenTelemetry metrics exporter
+//!
+//! Bridges the metrics recorded via the [metrics] facade to OpenTelemetry instruments, which are
+//! periodically pushed to a collector via OTLP.
+use eyre::WrapErr;
+use metrics::{
+    Counter, CounterFn, Gauge, GaugeFn, Histogram, HistogramFn, Key, KeyName, Recorder,
+    SharedString, Unit,
+};
+use opentelemetry::{
+    metrics::{Meter, MeterProvider as _},
+    KeyValue,
+};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{metrics::MeterProvider, runtime, Resource};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+/// A [Recorder] that records all metrics into OpenTelemetry instruments.
+///
+/// Metric names map to instruments and metric labels to instrument attributes:
+///  - counters are recorded as monotonic counters
+///  - gauges are recorded as up-down counters, so that the exported sum is the gauge value
+///  - histograms are recorded as histograms
+pub(crate) struct OtlpRecorder {
+    meter: Meter,
+    /// The provider owns the periodic exporter, which stops once the provider is dropped.
+    _provider: MeterProvider,
+    descriptions: Mutex<HashMap<String, SharedString>>,
+    counters: Mutex<HashMap<Key, Arc<OtlpCounter>>>,
+    gauges: Mutex<HashMap<Key, Arc<OtlpGauge>>>,
+    histograms: Mutex<HashMap<Key, Arc<OtlpHistogram>>>,
+}
+
+impl OtlpRecorder {
+    /// Creates a recorder that pushes its metrics to the given OTLP endpoint at the given
+    /// interval.
+    ///
+    /// Must be called from within a tokio runtime.
+    pub(crate) fn new(endpoint: &str, interval: Duration) -> eyre::Result<Self> {
+        let provider = opentelemetry_otlp::new_pipeline()
+            .metrics(runtime::Tokio)
+            .with_exporter(opentelemetry_otlp::new_exporter().tonic().with_endpoint(endpoint))
+            .with_period(interval)
+            .with_resource(Resource::new([KeyValue::new("service.name", "reth")]))
+            .build()
+            .wrap_err("Could not build OTLP metrics pipeline")?;
+
+        Ok(Self {
+            meter: provider.meter("reth"),
+            _provider: provider,
+            descriptions: Default::default(),
+            counters: Default::default(),
+            gauges: Default::default(),
+            histograms: Default::default(),
+        })
+    }
+
+    fn describe(&self, key: KeyName, description: SharedString) {
+        self.descriptions.lock().unwrap().insert(key.as_str().to_owned(), description);
+    }
+
+    fn description(&self, key: &Key) -> SharedString {
+        self.descriptions.lock().unwrap().get(key.name()).cloned().unwrap_or_default()
+    }
+}
+
+impl std::fmt::Debug for OtlpRecorder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OtlpRecorder").finish_non_exhaustive()
+    }
+}
+
+impl Recorder for OtlpRecorder {
+    fn describe_counter(&self, key: KeyName, _unit: Option<Unit>, description: SharedString) {
+        self.describe(key, description)
+    }
+
+    fn describe_gauge(&self, key: KeyName, _unit: Option<Unit>, description: SharedString) {
+        self.describe(key, description)
+    }
+
+    fn describe_histogram(&self, key: KeyName, _unit: Option<Unit>, description: SharedString) {
+        self.describe(key, description)
+    }
+
+    fn register_counter(&self, key: &Key) -> Counter {
+        let counter = self
+            .counters
+            .lock()
+            .unwrap()
+            .entry(key.clone())
+            .or_insert_with(|| {
+                Arc::new(OtlpCounter {
+                    counter: self
+                        .meter
+                        .u64_counter(key.name().to_owned())
+                        .with_description(self.description(key))
+                        .init(),
+                    attributes: attributes(key),
+                    value: AtomicU64::new(0),
+                })
+            })
+            .clone();
+        Counter::from_arc(counter)
+    }
+
+    fn register_gauge(&self, key: &Key) -> Gauge {
+        let gauge = self
+            .gauges
+            .lock()
+            .unwrap()
+            .entry(key.clone())
+            .or_insert_with(|| {
+                Arc::new(OtlpGauge {
+                    counter: self
+                        .meter
+                        .f64_up_down_counter(key.name().to_owned())
+                        .with_description(self.description(key))
+                        .init(),
+                    attributes: attributes(key),
+                    value: AtomicU64::new(0f64.to_bits()),
+                })
+            })
+            .clone();
+        Gauge::from_arc(gauge)
+    }
+
+    fn register_histogram(&self, key: &Key) -> Histogram {
+        let histogram = self
+            .histograms
+            .lock()
+            .unwrap()
+            .entry(key.clone())
+            .or_insert_with(|| {
+                Arc::new(OtlpHistogram {
+                    histogram: self
+                        .meter
+                        .f64_histogram(key.name().to_owned())
+                        .with_description(self.description(key))
+                        .init(),
+                    attributes: attributes(key),
+                })
+            })
+            .clone();
+        Histogram::from_arc(histogram)
+    }
+}
+
+/// Converts the labels of the key to instrument attributes.
+fn attributes(key: &Key) -> Vec<KeyValue> {
+    key.labels()
+        .map(|label| KeyValue::new(label.key().to_owned(), label.value().to_owned()))
+        .collect()
+}
+
+struct OtlpCounter {
+    counter: opentelemetry::metrics::Counter<u64>,
+    attributes: Vec<KeyValue>,
+    /// The total of the counter, required to convert absolute values to increments.
+    value: AtomicU64,
+}
+
+impl CounterFn for OtlpCounter {
+    fn increment(&self, value: u64) {
+        self.value.fetch_add(value, Ordering::Relaxed);
+        self.counter.add(value, &self.attributes);
+    }
+
+    fn absolute(&self, value: u64) {
+        let previous = self.value.fetch_max(value, Ordering::Relaxed);
+        if value > previous {
+            self.counter.add(value - previous, &self.attributes);
+        }
+    }
+}
+
+struct OtlpGauge {
+    counter: opentelemetry::metrics::UpDownCounter<f64>,
+    attributes: Vec<KeyValue>,
+    /// The bits of the current gauge value, required to convert absolute values to deltas.
+    value: AtomicU64,
+}
+
+impl OtlpGauge {
+    fn add(&self, delta: f64) {
+        let _ = self.value.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+            Some((f64::from_bits(bits) + delta).to_bits())
+        });
+        self.counter.add(delta, &self.attributes);
+    }
+}
+
+impl GaugeFn for OtlpGauge {
+    fn increment(&self, value: f64) {
+        self.add(value)
+    }
+
+    fn decrement(&self, value: f64) {
+        self.add(-value)
+    }
+
+    fn set(&self, value: f64) {
+        let previous = f64::from_bits(self.value.swap(value.to_bits(), Ordering::Relaxed));
+        self.counter.add(value - previous, &self.attributes);
+    }
+}
+
+struct OtlpHistogram {
+    histogram: opentelemetry::metrics::Histogram<f64>,
+    attributes: Vec<KeyValue>,
+}
+
+impl HistogramFn for OtlpHistogram {
+    fn record(&self, value: f64) {
+        self.histogram.record(value, &self.attributes);
+    }
+}
@@ -14,10 +14,13 @@ use reth_config::Config;
 use reth_db::open_db;
 use reth_discv4::NatResolver;
 use reth_interfaces::p2p::bodies::client::BodiesClient;
+use reth_network::FetchClient;
 use reth_primitives::{BlockHashOrNumber, ChainSpec, NodeRecord};
 use reth_provider::ProviderFactory;
 use std::{path::PathBuf, sync::Arc};
 
+mod stats;
+
 /// `reth p2p` command
 #[derive(Debug, Parser)]
 pub struct Command {
@@ -66,7 +69,7 @@ pub struct Command {
     #[arg(long)]
     trusted_peer: Option<NodeRecord>,
 
-    /// Connect only to trusted peers
+    /// Connect only to trusted peers and reject sessions with other peers
     #[arg(long)]
     trusted_only: bool,
 
@@ -99,49 +102,23 @@ pub enum Subcommands {
         #[arg(value_parser = hash_or_num_value_parser)]
         id: BlockHashOrNumber,
     },
+    /// Aggregate the peer stats reports exported by the node
+    Stats {
+        /// The files of the reports
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
 }
 impl Command {
     /// Execute `p2p` command
     pub async fn execute(&self) -> eyre::Result<()> {
-        let tempdir = tempfile::TempDir::new()?;
-        let noop_db = Arc::new(open_db(&tempdir.into_path(), self.db.log_level)?);
-
-        // add network name to data dir
-        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
-        let config_path = self.config.clone().unwrap_or(data_dir.config_path());
-
-        let mut config: Config = confy::load_path(&config_path).unwrap_or_default();
-
-        if let Some(peer) = self.trusted_peer {
-            config.peers.trusted_nodes.insert(peer);
-        }
-
-        if config.peers.trusted_nodes.is_empty() && self.trusted_only {
-            eyre::bail!("No trusted nodes. Set trusted peer with `--trusted-peer <enode record>` or set `--trusted-only` to `false`")
-        }
-
-        config.peers.connect_trusted_nodes_only = self.trusted_only;
-
-        let default_secret_key_path = data_dir.p2p_secret_path();
-        let secret_key_path = self.p2p_secret_key.clone().unwrap_or(default_secret_key_path);
-        let p2p_secret_key = get_secret_key(&secret_key_path)?;
-
-        let mut network_config_builder =
-            config.network_config(self.nat, None, p2p_secret_key).chain_spec(self.chain.clone());
-
-        network_config_builder = self.discovery.apply_to_builder(network_config_builder);
-
-        let network = network_config_builder
-            .build(Arc::new(ProviderFactory::new(noop_db, self.chain.clone())))
-            .start_network()
-            .await?;
-
-        let fetch_client = network.fetch_client().await?;
         let retries = self.retries.max(1);
         let backoff = ConstantBuilder::default().with_max_times(retries);
 
-        match self.command {
+        match &self.command {
             Subcommands::Header { id } => {
+                let id = *id;
+                let fetch_client = self.fetch_client().await?;
                 let header = (move || get_single_header(fetch_client.clone(), id))
                     .retry(&backoff)
                     .notify(|err, _| println!("Error requesting header: {err}. Retrying..."))
@@ -149,7 +126,8 @@ impl Command {
                 println!("Successfully downloaded header: {header:?}");
             }
             Subcommands::Body { id } => {
-                let hash = match id {
+                let fetch_client = self.fetch_client().await?;
+                let hash = match *id {
                     BlockHashOrNumber::Hash(hash) => hash,
                     BlockHashOrNumber::Number(number) => {
                         println!("Block number provided. Downloading header first...");
@@ -180,8 +158,48 @@ impl Command {
                 let body = result.into_iter().next().unwrap();
                 println!("Successfully downloaded body: {body:?}")
             }
+            Subcommands::Stats { files } => stats::aggregate(files)?,
         }
 
         Ok(())
     }
+
+    /// Starts the network and returns a client to fetch data from peers.
+    async fn fetch_client(&self) -> eyre::Result<FetchClient> {
+        let tempdir = tempfile::TempDir::new()?;
+        let noop_db = Arc::new(open_db(&tempdir.into_path(), self.db.log_level)?);
+
+        // add network name to data dir
+        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+        let config_path = self.config.clone().unwrap_or(data_dir.config_path());
+
+        let mut config: Config = confy::load_path(&config_path).unwrap_or_default();
+
+        if let Some(peer) = self.trusted_peer {
+            config.peers.trusted_nodes.insert(peer);
+        }
+
+        if config.peers.trusted_nodes.is_empty() && self.trusted_only {
+            eyre::bail!("No trusted nodes. Set trusted peer with `--trusted-peer <enode record>` or set `--trusted-only` to `false`")
+        }
+
+        config.peers.connect_trusted_nodes_only = self.trusted_only;
+
+        let default_secret_key_path = data_dir.p2p_secret_path();
+        let secret_key_path = self.p2p_secret_key.clone().unwrap_or(default_secret_key_path);
+        let p2p_secret_key = get_secret_key(&secret_key_path)?;
+
+        let mut network_config_builder = config
+            .network_config(self.nat.clone(), None, p2p_secret_key)
+            .chain_spec(self.chain.clone());
+
+        network_config_builder = self.discovery.apply_to_builder(network_config_builder);
+
+        let network = network_config_builder
+            .build(Arc::new(ProviderFactory::new(noop_db, self.chain.clone())))
+            .start_network()
+            .await?;
+
+        Ok(network.fetch_client().await?)
+    }
 }
@@ -0,0 +1,65 @@
+use crate::node::peer_stats::PeerStatsReport;
+use comfy_table::{presets::ASCII_MARKDOWN, Table};
+use eyre::WrapErr;
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    path::PathBuf,
+};
+
+/// Aggregates the peer stats reports in the files and prints the result.
+///
+/// Every line of the files is a report, as written by the peer stats export of the node.
+pub(crate) fn aggregate(files: &[PathBuf]) -> eyre::Result<()> {
+    let mut total = PeerStatsReport::default();
+    let mut reports = 0;
+    for path in files {
+        let file =
+            File::open(path).wrap_err_with(|| format!("Could not open {}", path.display()))?;
+        for (idx, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue
+            }
+            let report: PeerStatsReport = serde_json::from_str(&line).wrap_err_with(|| {
+                format!("Invalid peer stats report in {}:{}", path.display(), idx + 1)
+            })?;
+            total.merge(&report);
+            reports += 1;
+        }
+    }
+
+    println!(
+        "Aggregated {reports} reports over {}, {} active sessions at the end",
+        humantime::format_duration(std::time::Duration::from_secs(total.interval)),
+        total.active_sessions
+    );
+
+    let mut clients = total.clients.into_iter().collect::<Vec<_>>();
+    clients.sort_by(|(_, a), (_, b)| b.established.cmp(&a.established));
+    let mut table = Table::new();
+    table.load_preset(ASCII_MARKDOWN);
+    table.set_header(["Client", "Established", "Closed", "Average session (s)"]);
+    for (client, stats) in clients {
+        let average = if stats.closed == 0 { 0 } else { stats.session_secs / stats.closed };
+        table.add_row([
+            client,
+            stats.established.to_string(),
+            stats.closed.to_string(),
+            average.to_string(),
+        ]);
+    }
+    println!("\n{table}");
+
+    let mut reasons = total.disconnect_reasons.into_iter().collect::<Vec<_>>();
+    reasons.sort_by(|(_, a), (_, b)| b.cmp(a));
+    let mut table = Table::new();
+    table.load_preset(ASCII_MARKDOWN);
+    table.set_header(["Disconnect reason", "Sessions"]);
+    for (reason, count) in reasons {
+        table.add_row([reason, count.to_string()]);
+    }
+    println!("\n{table}");
+
+    Ok(())
+}
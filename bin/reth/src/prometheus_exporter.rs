@@ -1,4 +1,5 @@
 //! Prometheus exporter
+use crate::{args::OtlpMetricsArgs, otlp_exporter::OtlpRecorder};
 use eyre::WrapErr;
 use hyper::{
     service::{make_service_fn, service_fn},
@@ -6,10 +7,10 @@ use hyper::{
 };
 use metrics::{describe_gauge, gauge};
 use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
-use metrics_util::layers::{PrefixLayer, Stack};
+use metrics_util::layers::{FanoutBuilder, PrefixLayer, Stack};
 use reth_db::{database::Database, tables, DatabaseEnv};
 use reth_metrics::metrics::Unit;
-use std::{convert::Infallible, net::SocketAddr, sync::Arc};
+use std::{convert::Infallible, net::SocketAddr, sync::Arc, time::Duration};
 use tracing::error;
 
 pub(crate) trait Hook: Fn() + Send + Sync {}
@@ -19,29 +20,58 @@ impl<T: Fn() + Send + Sync> Hook for T {}
 ///
 /// The hooks are called every time the metrics are requested at the given endpoint, and can be used
 /// to record values for pull-style metrics, i.e. metrics that are not automatically updated.
+///
+/// If an OTLP endpoint is configured, all metrics are additionally pushed to it. In that case the
+/// hooks are also called before every push.
 pub(crate) async fn initialize_with_hooks<F: Hook + 'static>(
     listen_addr: SocketAddr,
+    otlp: &OtlpMetricsArgs,
     hooks: impl IntoIterator<Item = F>,
 ) -> eyre::Result<()> {
     let recorder = PrometheusBuilder::new().build_recorder();
     let handle = recorder.handle();
 
     let hooks: Vec<_> = hooks.into_iter().collect();
+    let hook = Arc::new(move || hooks.iter().for_each(|hook| hook()));
 
     // Start endpoint
-    start_endpoint(listen_addr, handle, Arc::new(move || hooks.iter().for_each(|hook| hook())))
+    start_endpoint(listen_addr, handle, Arc::clone(&hook))
         .await
         .wrap_err("Could not start Prometheus endpoint")?;
 
     // Build metrics stack
-    Stack::new(recorder)
-        .push(PrefixLayer::new("reth"))
-        .install()
-        .wrap_err("Couldn't set metrics recorder.")?;
+    if let Some(endpoint) = &otlp.endpoint {
+        let otlp_recorder = OtlpRecorder::new(endpoint, otlp.interval)?;
+        spawn_hook_task(otlp.interval, hook);
+
+        let fanout =
+            FanoutBuilder::default().add_recorder(recorder).add_recorder(otlp_recorder).build();
+        Stack::new(fanout)
+            .push(PrefixLayer::new("reth"))
+            .install()
+            .wrap_err("Couldn't set metrics recorder.")?;
+    } else {
+        Stack::new(recorder)
+            .push(PrefixLayer::new("reth"))
+            .install()
+            .wrap_err("Couldn't set metrics recorder.")?;
+    }
 
     Ok(())
 }
 
+/// Spawns a task that calls the hook at the given interval, so that pull-style metrics are also
+/// up to date when they are pushed.
+fn spawn_hook_task<F: Hook + 'static>(interval: Duration, hook: Arc<F>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(interval);
+        loop {
+            interval.tick().await;
+            (hook)();
+        }
+    });
+}
+
 /// Starts an endpoint at the given address to serve Prometheus metrics.
 async fn start_endpoint<F: Hook + 'static>(
     listen_addr: SocketAddr,
@@ -71,6 +101,7 @@ async fn start_endpoint<F: Hook + 'static>(
 /// metrics.
 pub(crate) async fn initialize(
     listen_addr: SocketAddr,
+    otlp: &OtlpMetricsArgs,
     db: Arc<DatabaseEnv>,
     process: metrics_process::Collector,
 ) -> eyre::Result<()> {
@@ -119,7 +150,7 @@ pub(crate) async fn initialize(
         Box::new(move || cloned_process.collect()),
         Box::new(collect_memory_stats),
     ];
-    initialize_with_hooks(listen_addr, hooks).await?;
+    initialize_with_hooks(listen_addr, otlp, hooks).await?;
 
     // We describe the metrics after the recorder is installed, otherwise this information is not
     // registered
@@ -0,0 +1,350 @@
+//! Read-replica that serves RPC traffic from a database synced by another process.
+//!
+//! The replica opens the database of a running node read-only and serves the regular RPC
+//! namespaces from it. It does not sync, execute or accept transactions. New canonical blocks that
+//! are committed by the primary node are picked up by the [ReplicaTailer], which polls the
+//! database and emits canonical state notifications for subscriptions and caches.
+//!
+//! Instead of a local datadir, the replica can read the tables of a node that serves the
+//! `provider` namespace, so it does not need access to the disk of the primary node.
+use crate::{
+    args::{utils::genesis_value_parser, DatabaseArgs, RpcServerArgs},
+    dirs::{DataDirPath, MaybePlatformPath},
+    runner::CliContext,
+};
+use clap::Parser;
+use humantime::parse_duration;
+use jsonrpsee::http_client::HttpClientBuilder;
+use reth_blockchain_tree::NoopBlockchainTree;
+use reth_db::{database::Database, open_db_read_only};
+use reth_interfaces::blockchain_tree::BlockchainTreeViewer;
+use reth_network_api::noop::NoopNetwork;
+use reth_primitives::{BlockNumber, ChainSpec, SealedBlockWithSenders, SealedHeader};
+use reth_provider::{
+    providers::BlockchainProvider, BlockHashReader, BlockNumReader, BlockReader, CanonChainTracker,
+    CanonStateNotification, CanonStateNotificationSender, Chain, PostState, ProviderError,
+    ProviderFactory,
+};
+use reth_rpc::RemoteDatabase;
+use reth_transaction_pool::noop::NoopTransactionPool;
+use std::{collections::VecDeque, sync::Arc, time::Duration};
+use tokio::sync::broadcast;
+use tracing::*;
+
+/// The capacity of the canonical state notification channel.
+const CANON_STATE_NOTIFICATION_BUFFER: usize = 128;
+
+/// The number of recently committed blocks that are kept to construct reorg notifications.
+const MAX_CACHED_BLOCKS: usize = 64;
+
+/// The maximum number of new blocks that are read for a single notification.
+///
+/// If the primary node advanced further since the last poll, for example during the pipeline
+/// sync, the head is moved without a notification.
+const MAX_NOTIFIED_BLOCKS: u64 = 64;
+
+/// `reth replica` command
+///
+/// Serves RPC traffic from the database of a node that runs in another process. The database is
+/// opened read-only, so the replica can run alongside the primary node on the same datadir.
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// The path to the data dir of the primary node.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/` or `$HOME/.local/share/reth/`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/`
+    /// - macOS: `$HOME/Library/Application Support/reth/`
+    #[arg(long, value_name = "DATA_DIR", verbatim_doc_comment, default_value_t)]
+    datadir: MaybePlatformPath<DataDirPath>,
+
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    ///
+    /// Built-in chains:
+    /// - mainnet
+    /// - goerli
+    /// - sepolia
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        verbatim_doc_comment,
+        default_value = "mainnet",
+        value_parser = genesis_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    /// The interval in which the database is polled for new canonical blocks.
+    ///
+    /// Parses strings using [humantime::parse_duration]
+    #[arg(
+        long = "replica.poll-interval",
+        value_name = "DURATION",
+        default_value = "1s",
+        value_parser = parse_duration,
+        verbatim_doc_comment
+    )]
+    poll_interval: Duration,
+
+    /// The HTTP RPC endpoint of a node that serves the `provider` namespace.
+    ///
+    /// If set, the tables are read from the remote node instead of the local datadir.
+    #[arg(long = "replica.remote", value_name = "URL")]
+    remote: Option<String>,
+
+    #[clap(flatten)]
+    db: DatabaseArgs,
+
+    #[clap(flatten)]
+    rpc: RpcServerArgs,
+}
+
+impl Command {
+    /// Execute `replica` command
+    pub async fn execute(self, ctx: CliContext) -> eyre::Result<()> {
+        if let Some(url) = self.remote.clone() {
+            // the database reads block on the requests, so they run on a dedicated runtime
+            let runtime = tokio::runtime::Builder::new_multi_thread()
+                .worker_threads(2)
+                .thread_name("replica-remote")
+                .enable_all()
+                .build()?;
+            let client = HttpClientBuilder::default().build(&url)?;
+            info!(target: "reth::cli", %url, "Reading tables from remote node");
+            let db = RemoteDatabase::new(client, runtime.handle().clone());
+            let res = self.serve(ctx, db).await;
+            runtime.shutdown_background();
+            return res
+        }
+
+        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+        let db_path = data_dir.db_path();
+        info!(target: "reth::cli", path = ?db_path, "Opening database read-only");
+        let db = Arc::new(open_db_read_only(&db_path, self.db.log_level)?);
+        self.serve(ctx, db).await
+    }
+
+    /// Serves RPC traffic from the given database until the process exits.
+    async fn serve<DB: Database + 'static>(self, ctx: CliContext, db: DB) -> eyre::Result<()> {
+        let factory = ProviderFactory::new(db, Arc::clone(&self.chain));
+
+        let (canon_state_notification_sender, _receiver) =
+            broadcast::channel(CANON_STATE_NOTIFICATION_BUFFER);
+        let tree = NoopBlockchainTree::new(canon_state_notification_sender.clone());
+        let provider = BlockchainProvider::new(factory.clone(), tree.clone())?;
+
+        let tailer =
+            ReplicaTailer::new(factory, provider.clone(), canon_state_notification_sender)?;
+        info!(target: "reth::cli", head = tailer.head().number, "Tailing canonical blocks of the primary node");
+        ctx.task_executor.spawn_critical("replica tailer", tailer.run(self.poll_interval));
+
+        let _rpc_server = self
+            .rpc
+            .start_rpc_server(
+                provider,
+                NoopTransactionPool::default(),
+                NoopNetwork,
+                ctx.task_executor.clone(),
+                tree,
+            )
+            .await?;
+        info!(target: "reth::cli", "RPC server started");
+
+        futures::future::pending().await
+    }
+}
+
+/// Keeps the provider view of a read-only database in sync with the primary node.
+///
+/// Every poll opens a new read transaction, which sees the latest snapshot committed by the
+/// primary node. If the canonical chain advanced, the canonical head of the provider is updated
+/// and a [CanonStateNotification] with the new blocks and their receipts is sent. Blocks that were
+/// unwound by the primary node are reported as a reorg, as long as they are still cached.
+///
+/// The notifications do not contain the state changes of the blocks.
+pub struct ReplicaTailer<DB, Tree> {
+    factory: ProviderFactory<DB>,
+    provider: BlockchainProvider<DB, Tree>,
+    sender: CanonStateNotificationSender,
+    /// The current canonical head of the provider.
+    head: SealedHeader,
+    /// The most recent canonical blocks, with the head as the last entry.
+    blocks: VecDeque<(SealedBlockWithSenders, PostState)>,
+}
+
+impl<DB, Tree> ReplicaTailer<DB, Tree>
+where
+    DB: Database,
+    Tree: BlockchainTreeViewer + Send + Sync,
+{
+    /// Creates a new tailer that starts at the current head of the database.
+    pub fn new(
+        factory: ProviderFactory<DB>,
+        provider: BlockchainProvider<DB, Tree>,
+        sender: CanonStateNotificationSender,
+    ) -> eyre::Result<Self> {
+        let head = {
+            let provider = factory.provider()?;
+            read_block(&provider, provider.best_block_number()?)?
+        };
+        Ok(Self {
+            factory,
+            provider,
+            sender,
+            head: head.0.header.clone(),
+            blocks: VecDeque::from([head]),
+        })
+    }
+
+    /// Returns the current canonical head.
+    pub fn head(&self) -> &SealedHeader {
+        &self.head
+    }
+
+    /// Polls the database in the given interval.
+    pub async fn run(mut self, poll_interval: Duration) {
+        let mut interval = tokio::time::interval(poll_interval);
+        loop {
+            interval.tick().await;
+            if let Err(err) = self.poll() {
+                warn!(target: "reth::cli", %err, "Failed to read canonical blocks of the primary node");
+            }
+        }
+    }
+
+    /// Reads the canonical chain of the database and notifies about the changes since the last
+    /// poll.
+    pub fn poll(&mut self) -> eyre::Result<()> {
+        let provider = self.factory.provider()?;
+        let tip = provider.best_block_number()?;
+        if tip == self.head.number && provider.block_hash(tip)? == Some(self.head.hash()) {
+            return Ok(())
+        }
+
+        // unwind the cached blocks that are no longer canonical
+        let mut reverted = Vec::new();
+        while let Some((block, _)) = self.blocks.back() {
+            if block.number <= tip && provider.block_hash(block.number)? == Some(block.hash()) {
+                break
+            }
+            reverted.extend(self.blocks.pop_back());
+        }
+
+        let Some(ancestor) = self.blocks.back().map(|(block, _)| block.number) else {
+            warn!(target: "reth::cli", depth = reverted.len(), "Reorg is deeper than the cached blocks, resetting the head");
+            let head = read_block(&provider, tip)?;
+            drop(provider);
+            self.reset(head);
+            return Ok(())
+        };
+        if tip - ancestor > MAX_NOTIFIED_BLOCKS {
+            debug!(target: "reth::cli", ancestor, tip, "Primary node advanced too far, resetting the head");
+            let head = read_block(&provider, tip)?;
+            drop(provider);
+            self.reset(head);
+            return Ok(())
+        }
+
+        let mut committed = Vec::new();
+        for number in ancestor + 1..=tip {
+            committed.push(read_block(&provider, number)?);
+        }
+        drop(provider);
+
+        self.blocks.extend(committed.iter().cloned());
+        while self.blocks.len() > MAX_CACHED_BLOCKS {
+            self.blocks.pop_front();
+        }
+        self.head = self.blocks.back().expect("contains the ancestor").0.header.clone();
+        self.provider.set_canonical_head(self.head.clone());
+
+        let new = Arc::new(Chain::new(committed));
+        let notification = if reverted.is_empty() {
+            CanonStateNotification::Commit { new }
+        } else {
+            info!(target: "reth::cli", depth = reverted.len(), head = self.head.number, "Primary node reorged");
+            CanonStateNotification::Reorg { old: Arc::new(Chain::new(reverted)), new }
+        };
+        trace!(target: "reth::cli", head = self.head.number, "Canonical head updated");
+        // there might be no subscribers
+        let _ = self.sender.send(notification);
+
+        Ok(())
+    }
+
+    /// Moves the head to the given block without a notification.
+    fn reset(&mut self, head: (SealedBlockWithSenders, PostState)) {
+        self.head = head.0.header.clone();
+        self.blocks = VecDeque::from([head]);
+        self.provider.set_canonical_head(self.head.clone());
+    }
+}
+
+/// Reads a canonical block with its senders and receipts.
+fn read_block(
+    provider: &impl BlockReader,
+    number: BlockNumber,
+) -> eyre::Result<(SealedBlockWithSenders, PostState)> {
+    let hash = provider.block_hash(number)?.ok_or(ProviderError::HeaderNotFound(number.into()))?;
+    let (block, senders) = provider
+        .block_with_senders(number)?
+        .ok_or(ProviderError::HeaderNotFound(number.into()))?
+        .into_components();
+
+    // receipts might be pruned
+    let mut state = PostState::new();
+    for receipt in provider.receipts_by_block(number.into())?.unwrap_or_default() {
+        state.add_receipt(number, receipt);
+    }
+
+    Ok((SealedBlockWithSenders { block: block.seal(hash), senders }, state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::test_utils::create_test_rw_db;
+    use reth_primitives::{
+        stage::{StageCheckpoint, StageId},
+        MAINNET,
+    };
+    use reth_provider::{
+        test_utils::blocks::BlockChainTestData, BlockWriter, StageCheckpointWriter,
+    };
+
+    #[test]
+    fn tail_canonical_blocks() {
+        let factory = ProviderFactory::new(create_test_rw_db(), MAINNET.clone());
+        let data = BlockChainTestData::default();
+        let provider = factory.provider_rw().unwrap();
+        provider.insert_block(data.genesis, None, None).unwrap();
+        provider.commit().unwrap();
+
+        let (sender, mut notifications) = broadcast::channel(CANON_STATE_NOTIFICATION_BUFFER);
+        let tree = NoopBlockchainTree::new(sender.clone());
+        let blockchain = BlockchainProvider::new(factory.clone(), tree).unwrap();
+        let mut tailer = ReplicaTailer::new(factory.clone(), blockchain.clone(), sender).unwrap();
+        assert_eq!(tailer.head().number, 0);
+
+        // nothing changed
+        tailer.poll().unwrap();
+        assert!(notifications.try_recv().is_err());
+
+        // the primary node commits a block
+        let (block, _) = data.blocks[0].clone();
+        let provider = factory.provider_rw().unwrap();
+        provider.insert_block(block.block.clone(), Some(block.senders.clone()), None).unwrap();
+        provider.save_stage_checkpoint(StageId::Finish, StageCheckpoint::new(1)).unwrap();
+        provider.commit().unwrap();
+
+        tailer.poll().unwrap();
+        assert_eq!(tailer.head(), &block.header);
+        assert_eq!(blockchain.best_block_number().unwrap(), 1);
+        match notifications.try_recv().unwrap() {
+            CanonStateNotification::Commit { new } => assert_eq!(new.tip(), &block),
+            notification => panic!("unexpected notification {notification:?}"),
+        }
+    }
+}
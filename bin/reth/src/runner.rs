@@ -2,8 +2,16 @@
 
 use futures::pin_mut;
 use reth_tasks::{TaskExecutor, TaskManager};
-use std::future::Future;
-use tracing::trace;
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+use tokio::runtime::Handle;
+use tracing::{trace, warn};
 
 /// Used to execute cli commands
 #[derive(Default, Debug)]
@@ -27,6 +35,7 @@ impl CliRunner {
         E: Send + Sync + From<std::io::Error> + From<reth_tasks::PanickedTaskError> + 'static,
     {
         let AsyncCliRunner { context, task_manager, tokio_runtime } = AsyncCliRunner::new()?;
+        let subsystem_runtimes = context.subsystem_runtimes.clone();
 
         // Executes the command until it finished or ctrl-c was fired
         let task_manager = tokio_runtime.block_on(run_to_completion_or_panic(
@@ -37,6 +46,10 @@ impl CliRunner {
         // fires the shutdown signal to all tasks spawned via the task executor
         drop(task_manager);
 
+        // the tasks on the dedicated runtimes of subsystems received the shutdown signal, give
+        // them some time to finish
+        subsystem_runtimes.shutdown(SUBSYSTEM_RUNTIME_SHUTDOWN_TIMEOUT);
+
         // drop the tokio runtime on a separate thread because drop blocks until its pools
         // (including blocking pool) are shutdown. In other words `drop(tokio_runtime)` would block
         // the current thread but we want to exit right away.
@@ -101,7 +114,11 @@ impl AsyncCliRunner {
         let tokio_runtime = tokio_runtime()?;
         let task_manager = TaskManager::new(tokio_runtime.handle().clone());
         let task_executor = task_manager.executor();
-        Ok(Self { context: CliContext { task_executor }, task_manager, tokio_runtime })
+        Ok(Self {
+            context: CliContext { task_executor, subsystem_runtimes: Default::default() },
+            task_manager,
+            tokio_runtime,
+        })
     }
 }
 
@@ -109,8 +126,14 @@ impl AsyncCliRunner {
 pub struct CliContext {
     /// Used to execute/spawn tasks
     pub task_executor: TaskExecutor,
+    /// Owns the dedicated runtimes of subsystems
+    pub subsystem_runtimes: SubsystemRuntimes,
 }
 
+/// How long the [CliRunner] waits for the tasks of a [SubsystemRuntime] to finish after the
+/// shutdown signal was fired.
+const SUBSYSTEM_RUNTIME_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Creates a new default tokio multi-thread [Runtime](tokio::runtime::Runtime) with all features
 /// enabled
 pub fn tokio_runtime() -> Result<tokio::runtime::Runtime, std::io::Error> {
@@ -121,6 +144,125 @@ pub fn tokio_runtime() -> Result<tokio::runtime::Runtime, std::io::Error> {
         .build()
 }
 
+/// A dedicated tokio runtime for a subsystem of the node, see
+/// [RuntimeArgs](crate::args::RuntimeArgs).
+///
+/// The runtime is shut down in the background on drop, so it can be dropped from within another
+/// runtime.
+#[derive(Debug)]
+pub struct SubsystemRuntime {
+    runtime: Option<tokio::runtime::Runtime>,
+}
+
+// === impl SubsystemRuntime ===
+
+impl SubsystemRuntime {
+    /// Creates a new multi-thread runtime with the given number of worker threads.
+    ///
+    /// If `cores` is not empty, the threads of the runtime, including the threads of its blocking
+    /// pool, are pinned round-robin to the given CPU cores.
+    pub fn new(
+        name: &'static str,
+        worker_threads: usize,
+        max_blocking_threads: Option<usize>,
+        cores: Vec<usize>,
+    ) -> Result<Self, std::io::Error> {
+        let mut builder = tokio::runtime::Builder::new_multi_thread();
+        builder
+            .enable_all()
+            .thread_name(name)
+            .worker_threads(worker_threads)
+            // increase stack size, mostly for RPC calls that use the evm: <https://github.com/paradigmxyz/reth/issues/3056> and  <https://github.com/bluealloy/revm/issues/305>
+            .thread_stack_size(8 * 1024 * 1024);
+        if let Some(max_blocking_threads) = max_blocking_threads {
+            builder.max_blocking_threads(max_blocking_threads);
+        }
+        if !cores.is_empty() {
+            if cfg!(target_os = "linux") {
+                let next = AtomicUsize::new(0);
+                builder.on_thread_start(move || {
+                    let core = cores[next.fetch_add(1, Ordering::Relaxed) % cores.len()];
+                    pin_current_thread(core);
+                });
+            } else {
+                warn!(target: "reth::cli", runtime = name, "Pinning threads to CPU cores is only supported on Linux");
+            }
+        }
+        Ok(Self { runtime: Some(builder.build()?) })
+    }
+
+    /// Returns the [Handle] to the runtime.
+    pub fn handle(&self) -> &Handle {
+        self.runtime.as_ref().expect("runtime is only taken on shutdown").handle()
+    }
+
+    /// Shuts down the runtime, waiting up to `timeout` for its tasks to finish.
+    pub fn shutdown_timeout(mut self, timeout: Duration) {
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_timeout(timeout);
+        }
+    }
+}
+
+/// The dedicated runtimes of the subsystems of a command.
+///
+/// Commands register their [SubsystemRuntime]s here instead of owning them, so the runtimes outlive
+/// the command future. The [CliRunner] shuts them down after the shutdown signal was fired, so the
+/// tasks spawned onto them can shut down gracefully.
+#[derive(Debug, Clone, Default)]
+pub struct SubsystemRuntimes {
+    runtimes: Arc<Mutex<Vec<SubsystemRuntime>>>,
+}
+
+// === impl SubsystemRuntimes ===
+
+impl SubsystemRuntimes {
+    /// Takes ownership of the runtime and returns its [Handle].
+    pub fn register(&self, runtime: SubsystemRuntime) -> Handle {
+        let handle = runtime.handle().clone();
+        self.runtimes.lock().expect("not poisoned").push(runtime);
+        handle
+    }
+
+    /// Shuts down all registered runtimes, waiting up to `timeout` for the tasks of each runtime.
+    fn shutdown(&self, timeout: Duration) {
+        let runtimes = std::mem::take(&mut *self.runtimes.lock().expect("not poisoned"));
+        for runtime in runtimes {
+            runtime.shutdown_timeout(timeout);
+        }
+    }
+}
+
+impl Drop for SubsystemRuntime {
+    fn drop(&mut self) {
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+/// Pins the current thread to the given CPU core.
+#[cfg(target_os = "linux")]
+fn pin_current_thread(core: usize) {
+    if core >= libc::CPU_SETSIZE as usize {
+        warn!(target: "reth::cli", core, "Failed to pin thread to CPU core: invalid core");
+        return
+    }
+    // SAFETY: the set is zero initialized and the core is within its bounds
+    let res = unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_SET(core, &mut set);
+        libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set)
+    };
+    if res != 0 {
+        let err = std::io::Error::last_os_error();
+        warn!(target: "reth::cli", core, %err, "Failed to pin thread to CPU core");
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn pin_current_thread(_core: usize) {}
+
 /// Runs the given future to completion or until a critical task panicked
 async fn run_to_completion_or_panic<F, E>(mut tasks: TaskManager, fut: F) -> Result<TaskManager, E>
 where
@@ -132,6 +132,7 @@ impl Command {
             info!(target: "reth::cli", "Starting metrics endpoint at {}", listen_addr);
             prometheus_exporter::initialize(
                 listen_addr,
+                &Default::default(),
                 Arc::clone(&db),
                 metrics_process::Collector::default(),
             )
@@ -182,6 +183,9 @@ impl Command {
                             .with_max_buffered_blocks_size_bytes(
                                 config.stages.bodies.downloader_max_buffered_blocks_size_bytes,
                             )
+                            .with_max_spilled_blocks_size_bytes(
+                                config.stages.bodies.downloader_max_spilled_blocks_size_bytes,
+                            )
                             .with_concurrent_requests_range(
                                 config.stages.bodies.downloader_min_concurrent_requests..=
                                     config.stages.bodies.downloader_max_concurrent_requests,
@@ -192,7 +196,9 @@ impl Command {
 
                     (Box::new(stage), None)
                 }
-                StageEnum::Senders => (Box::new(SenderRecoveryStage::new(batch_size)), None),
+                StageEnum::Senders => {
+                    (Box::new(SenderRecoveryStage::new(batch_size, PruneModes::none())), None)
+                }
                 StageEnum::Execution => {
                     let factory = reth_revm::Factory::new(self.chain.clone());
                     (
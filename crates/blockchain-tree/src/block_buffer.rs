@@ -58,7 +58,9 @@ impl BlockBuffer {
         let num_hash = block.num_hash();
 
         self.parent_to_child.entry(block.parent_hash).or_default().insert(block.num_hash());
-        self.hash_to_num.insert(block.hash, block.number);
+        if self.hash_to_num.insert(block.hash, block.number).is_none() {
+            self.metrics.buffered_blocks.increment(1);
+        }
         self.blocks.entry(block.number).or_default().insert(block.hash, block);
 
         if let Some((evicted_num_hash, _)) =
@@ -68,6 +70,7 @@ impl BlockBuffer {
             if let Some(evicted_block) = self.remove_from_blocks(&evicted_num_hash) {
                 // evict the block if limit is hit
                 self.remove_from_parent(evicted_block.parent_hash, &evicted_num_hash);
+                self.metrics.evicted_blocks.increment(1);
             }
         }
         self.metrics.blocks.set(self.len() as f64);
@@ -87,6 +90,7 @@ impl BlockBuffer {
         }
 
         taken.extend(self.remove_children(vec![parent]));
+        self.metrics.connected_blocks.increment(taken.len() as u64);
         self.metrics.blocks.set(self.len() as f64);
         taken
     }
@@ -108,10 +112,13 @@ impl BlockBuffer {
         }
         // remove from lru
         for block in remove_parent_children.iter() {
+            self.hash_to_num.remove(&block.hash);
             self.lru.pop(block);
         }
 
-        self.remove_children(remove_parent_children);
+        let expired =
+            remove_parent_children.len() + self.remove_children(remove_parent_children).len();
+        self.metrics.expired_blocks.increment(expired as u64);
         self.metrics.blocks.set(self.len() as f64);
     }
 
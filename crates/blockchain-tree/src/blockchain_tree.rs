@@ -3,6 +3,7 @@ use crate::{
     canonical_chain::CanonicalChain,
     chain::{BlockChainId, BlockKind},
     metrics::TreeMetrics,
+    reorg::{ReorgAlert, ReorgEvent},
     AppendableChain, BlockBuffer, BlockIndices, BlockchainTreeConfig, PostStateData, TreeExternals,
 };
 use reth_db::{cursor::DbCursorRO, database::Database, tables, transaction::DbTx};
@@ -79,6 +80,9 @@ pub struct BlockchainTree<DB: Database, C: Consensus, EF: ExecutorFactory> {
     chains: HashMap<BlockChainId, AppendableChain>,
     /// Unconnected block buffer.
     buffered_blocks: BlockBuffer,
+    /// Headers of buffered blocks that failed validation when they were connected, until they
+    /// are taken by the consensus engine.
+    invalid_buffered_blocks: Vec<SealedHeader>,
     /// Static blockchain ID generator
     block_chain_id_generator: u64,
     /// Indices to block and their connection to the canonical chain.
@@ -93,6 +97,8 @@ pub struct BlockchainTree<DB: Database, C: Consensus, EF: ExecutorFactory> {
     metrics: TreeMetrics,
     /// Metrics for sync stages.
     sync_metrics_tx: Option<MetricEventsSender>,
+    /// Alert for deep reorgs.
+    reorg_alert: Option<ReorgAlert>,
     prune_modes: Option<PruneModes>,
 }
 
@@ -137,6 +143,7 @@ impl<DB: Database, C: Consensus, EF: ExecutorFactory> BlockchainTree<DB, C, EF>
         Ok(Self {
             externals,
             buffered_blocks: BlockBuffer::new(config.max_unconnected_blocks()),
+            invalid_buffered_blocks: Vec::new(),
             block_chain_id_generator: 0,
             chains: Default::default(),
             block_indices: BlockIndices::new(
@@ -147,6 +154,7 @@ impl<DB: Database, C: Consensus, EF: ExecutorFactory> BlockchainTree<DB, C, EF>
             canon_state_notification_sender,
             metrics: Default::default(),
             sync_metrics_tx: None,
+            reorg_alert: None,
             prune_modes,
         })
     }
@@ -157,6 +165,12 @@ impl<DB: Database, C: Consensus, EF: ExecutorFactory> BlockchainTree<DB, C, EF>
         self
     }
 
+    /// Set the alert that is fired for deep reorgs of the canonical chain.
+    pub fn with_reorg_alert(mut self, reorg_alert: ReorgAlert) -> Self {
+        self.reorg_alert = Some(reorg_alert);
+        self
+    }
+
     /// Check if then block is known to blockchain tree or database and return its status.
     ///
     /// Function will check:
@@ -618,12 +632,17 @@ impl<DB: Database, C: Consensus, EF: ExecutorFactory> BlockchainTree<DB, C, EF>
     ///
     /// # Note
     ///
-    /// This recovers transaction signers (unlike [`BlockchainTree::insert_block`]).
+    /// This recovers transaction signers (unlike [`BlockchainTree::insert_block`]), signers that
+    /// are in the configured sender cache are not recovered again.
     pub fn insert_block_without_senders(
         &mut self,
         block: SealedBlock,
     ) -> Result<InsertPayloadOk, InsertBlockError> {
-        match block.try_seal_with_senders() {
+        let sealed = match &self.externals.sender_cache {
+            Some(cache) => block.try_seal_with_senders_cached(cache),
+            None => block.try_seal_with_senders(),
+        };
+        match sealed {
             Ok(block) => self.insert_block(block),
             Err(block) => Err(InsertBlockError::sender_recovery_error(block)),
         }
@@ -822,14 +841,32 @@ impl<DB: Database, C: Consensus, EF: ExecutorFactory> BlockchainTree<DB, C, EF>
         // insert block children
         for block in include_blocks.into_iter() {
             // dont fail on error, just ignore the block.
-            let _ = self.try_insert_validated_block(block).map_err(|err| {
+            if let Err(err) = self.try_insert_validated_block(block) {
                 debug!(
                     target: "blockchain_tree", ?err,
                     "Failed to insert buffered block",
                 );
-                err
-            });
+                if err.kind().is_invalid_block() {
+                    self.record_invalid_buffered_block(err.into_block().header);
+                }
+            }
+        }
+    }
+
+    /// Keeps the header of a buffered block that failed validation, so that the consensus engine
+    /// can reject its descendants without executing it again.
+    ///
+    /// At most `max_unconnected_blocks` headers are kept, the oldest are dropped first.
+    fn record_invalid_buffered_block(&mut self, header: SealedHeader) {
+        if self.invalid_buffered_blocks.len() >= self.config.max_unconnected_blocks() {
+            self.invalid_buffered_blocks.remove(0);
         }
+        self.invalid_buffered_blocks.push(header);
+    }
+
+    /// Returns the headers of the buffered blocks that failed validation since the last call.
+    pub fn take_invalid_buffered_blocks(&mut self) -> Vec<SealedHeader> {
+        std::mem::take(&mut self.invalid_buffered_blocks)
     }
 
     /// Split a sidechain at the given point, and return the canonical part of it.
@@ -1010,12 +1047,17 @@ impl<DB: Database, C: Consensus, EF: ExecutorFactory> BlockchainTree<DB, C, EF>
                     old: Arc::new(old_canon_chain.clone()),
                     new: Arc::new(new_canon_chain.clone()),
                 };
-                let reorg_depth = old_canon_chain.len();
+                let reorg = ReorgEvent {
+                    depth: old_canon_chain.len() as u64,
+                    fork_block: old_canon_chain.fork_block(),
+                    old_tip: old_canon_chain.tip().num_hash(),
+                    new_tip: new_canon_chain.tip().num_hash(),
+                };
 
                 // insert old canon chain
                 self.insert_chain(AppendableChain::new(old_canon_chain));
 
-                self.update_reorg_metrics(reorg_depth as f64);
+                self.on_reorg(&reorg);
             } else {
                 // error here to confirm that we are reverting nothing from db.
                 error!(target: "blockchain_tree", "Reverting nothing from db on block: #{:?}", block_hash);
@@ -1042,13 +1084,17 @@ impl<DB: Database, C: Consensus, EF: ExecutorFactory> BlockchainTree<DB, C, EF>
     }
 
     /// Canonicalize the given chain and commit it to the database.
+    #[instrument(level = "debug", skip_all, fields(block_hash = ?chain.tip().hash, block_number = chain.tip().number), target = "blockchain_tree")]
     fn commit_canonical(&self, chain: Chain) -> Result<(), Error> {
         let provider = DatabaseProvider::new_rw(
             self.externals.db.tx_mut()?,
             self.externals.chain_spec.clone(),
         );
 
-        let (blocks, state) = chain.into_inner();
+        let (blocks, mut state) = chain.into_inner();
+        if self.config.record_preimages() {
+            state.record_preimages();
+        }
 
         provider
             .append_blocks_with_post_state(
@@ -1110,9 +1156,15 @@ impl<DB: Database, C: Consensus, EF: ExecutorFactory> BlockchainTree<DB, C, EF>
         }
     }
 
-    fn update_reorg_metrics(&mut self, reorg_depth: f64) {
+    /// Updates the reorg metrics and fires the reorg alert if the reorg is deep enough.
+    fn on_reorg(&mut self, reorg: &ReorgEvent) {
         self.metrics.reorgs.increment(1);
-        self.metrics.latest_reorg_depth.set(reorg_depth);
+        self.metrics.latest_reorg_depth.set(reorg.depth as f64);
+        self.metrics.reorg_depth.record(reorg.depth as f64);
+
+        if let Some(alert) = &self.reorg_alert {
+            alert.on_reorg(reorg);
+        }
     }
 
     /// Update blockchain tree chains (canonical and sidechains) and sync metrics.
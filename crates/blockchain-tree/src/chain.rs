@@ -21,6 +21,7 @@ use std::{
     collections::BTreeMap,
     ops::{Deref, DerefMut},
 };
+use tracing::instrument;
 
 /// The ID of a sidechain internally in a [`BlockchainTree`][super::BlockchainTree].
 pub(crate) type BlockChainId = u64;
@@ -177,6 +178,7 @@ impl AppendableChain {
 
     /// Validate and execute the given block that _extends the canonical chain_, validating its
     /// state root after execution.
+    #[instrument(level = "debug", skip_all, fields(block_hash = ?block.hash, block_number = block.number), target = "blockchain_tree")]
     fn validate_and_execute<PSDP, DB, C, EF>(
         block: SealedBlockWithSenders,
         parent_block: &SealedHeader,
@@ -1,5 +1,8 @@
 //! Blockchain tree configuration
 
+/// The default number of unconnected blocks that are buffered.
+pub const DEFAULT_MAX_UNCONNECTED_BLOCKS: usize = 200;
+
 /// The configuration for the blockchain tree.
 #[derive(Clone, Copy, Debug)]
 pub struct BlockchainTreeConfig {
@@ -15,6 +18,9 @@ pub struct BlockchainTreeConfig {
     /// at least `additional_canonical_block_hashes`+`max_reorg_depth`, for eth that would be
     /// 256+64.
     num_of_additional_canonical_block_hashes: u64,
+    /// Whether to record the preimages of hashed addresses and storage keys when committing
+    /// blocks to the database.
+    record_preimages: bool,
 }
 
 impl Default for BlockchainTreeConfig {
@@ -28,7 +34,8 @@ impl Default for BlockchainTreeConfig {
             // EVM requires that last 256 block hashes are available.
             num_of_additional_canonical_block_hashes: 256,
             // max unconnected blocks.
-            max_unconnected_blocks: 200,
+            max_unconnected_blocks: DEFAULT_MAX_UNCONNECTED_BLOCKS,
+            record_preimages: false,
         }
     }
 }
@@ -49,9 +56,26 @@ impl BlockchainTreeConfig {
             max_reorg_depth,
             num_of_additional_canonical_block_hashes,
             max_unconnected_blocks,
+            record_preimages: false,
         }
     }
 
+    /// Set the max number of unconnected blocks that are buffered until their missing ancestors
+    /// are inserted.
+    ///
+    /// The buffer must be able to hold at least one block.
+    pub fn with_max_unconnected_blocks(mut self, max_unconnected_blocks: usize) -> Self {
+        assert!(max_unconnected_blocks > 0, "the block buffer must hold at least one block");
+        self.max_unconnected_blocks = max_unconnected_blocks;
+        self
+    }
+
+    /// Record the preimages of hashed addresses and storage keys of committed blocks.
+    pub fn with_preimages(mut self, record_preimages: bool) -> Self {
+        self.record_preimages = record_preimages;
+        self
+    }
+
     /// Return the maximum reorg depth.
     pub fn max_reorg_depth(&self) -> u64 {
         self.max_reorg_depth
@@ -72,4 +96,9 @@ impl BlockchainTreeConfig {
     pub fn max_unconnected_blocks(&self) -> usize {
         self.max_unconnected_blocks
     }
+
+    /// Return whether preimages are recorded when committing blocks.
+    pub fn record_preimages(&self) -> bool {
+        self.record_preimages
+    }
 }
@@ -1,8 +1,8 @@
 //! Blockchain tree externals.
 
 use reth_db::database::Database;
-use reth_primitives::ChainSpec;
-use reth_provider::ProviderFactory;
+use reth_primitives::{ChainSpec, SenderRecoveryCache};
+use reth_provider::{providers::BytecodeCache, ProviderFactory};
 use std::sync::Arc;
 
 /// A container for external components.
@@ -24,18 +24,59 @@ pub struct TreeExternals<DB, C, EF> {
     pub(crate) executor_factory: EF,
     /// The chain spec.
     pub(crate) chain_spec: Arc<ChainSpec>,
+    /// The cache for contract bytecode used by the state providers of the tree.
+    pub(crate) bytecode_cache: Option<BytecodeCache>,
+    /// The cache of senders that were recovered before the blocks are inserted.
+    pub(crate) sender_cache: Option<SenderRecoveryCache>,
+    /// Whether total difficulty entries are not written for blocks after the merge.
+    pub(crate) elide_post_merge_td: bool,
 }
 
 impl<DB, C, EF> TreeExternals<DB, C, EF> {
     /// Create new tree externals.
     pub fn new(db: DB, consensus: C, executor_factory: EF, chain_spec: Arc<ChainSpec>) -> Self {
-        Self { db, consensus, executor_factory, chain_spec }
+        Self {
+            db,
+            consensus,
+            executor_factory,
+            chain_spec,
+            bytecode_cache: None,
+            sender_cache: None,
+            elide_post_merge_td: false,
+        }
+    }
+
+    /// Sets the cache for contract bytecode, which may be shared with other providers.
+    pub fn with_bytecode_cache(mut self, bytecode_cache: BytecodeCache) -> Self {
+        self.bytecode_cache = Some(bytecode_cache);
+        self
+    }
+
+    /// Sets the cache of recovered senders, e.g. the senders of the transactions accepted by the
+    /// transaction pool.
+    pub fn with_sender_cache(mut self, sender_cache: SenderRecoveryCache) -> Self {
+        self.sender_cache = Some(sender_cache);
+        self
+    }
+
+    /// Don't write total difficulty entries for committed blocks at or above the merge block, in
+    /// line with the post-merge elision of the total difficulty stage.
+    pub fn with_post_merge_td_elision(mut self) -> Self {
+        self.elide_post_merge_td = true;
+        self
     }
 }
 
 impl<DB: Database, C, EF> TreeExternals<DB, C, EF> {
     /// Return shareable database helper structure.
     pub fn database(&self) -> ProviderFactory<&DB> {
-        ProviderFactory::new(&self.db, self.chain_spec.clone())
+        let mut factory = ProviderFactory::new(&self.db, self.chain_spec.clone());
+        if let Some(cache) = &self.bytecode_cache {
+            factory = factory.with_bytecode_cache(cache.clone());
+        }
+        if self.elide_post_merge_td {
+            factory = factory.with_post_merge_td_elision();
+        }
+        factory
     }
 }
@@ -35,7 +35,7 @@ pub mod chain;
 pub use chain::AppendableChain;
 
 pub mod config;
-pub use config::BlockchainTreeConfig;
+pub use config::{BlockchainTreeConfig, DEFAULT_MAX_UNCONNECTED_BLOCKS};
 
 pub mod externals;
 pub use externals::TreeExternals;
@@ -43,9 +43,15 @@ pub use externals::TreeExternals;
 pub mod shareable;
 pub use shareable::ShareableBlockchainTree;
 
+pub mod noop;
+pub use noop::NoopBlockchainTree;
+
 pub mod post_state_data;
 pub use post_state_data::{PostStateData, PostStateDataRef};
 
+pub mod reorg;
+pub use reorg::{ReorgAlert, ReorgEvent, ReorgHook};
+
 /// Buffer of not executed blocks.
 pub mod block_buffer;
 mod canonical_chain;
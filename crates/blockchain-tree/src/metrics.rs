@@ -1,5 +1,5 @@
 use reth_metrics::{
-    metrics::{Counter, Gauge},
+    metrics::{Counter, Gauge, Histogram},
     Metrics,
 };
 
@@ -15,6 +15,8 @@ pub struct TreeMetrics {
     pub reorgs: Counter,
     /// The latest reorg depth
     pub latest_reorg_depth: Gauge,
+    /// The depth of reorgs
+    pub reorg_depth: Histogram,
     /// Longest sidechain height
     pub longest_sidechain_height: Gauge,
 }
@@ -25,4 +27,12 @@ pub struct TreeMetrics {
 pub struct BlockBufferMetrics {
     /// Total blocks in the block buffer
     pub blocks: Gauge,
+    /// The number of blocks that were buffered because their parent was unknown
+    pub buffered_blocks: Counter,
+    /// The number of blocks that were evicted because the buffer was full
+    pub evicted_blocks: Counter,
+    /// The number of blocks that were discarded because they were at or below the finalized block
+    pub expired_blocks: Counter,
+    /// The number of blocks that were taken out of the buffer once their parent was inserted
+    pub connected_blocks: Counter,
 }
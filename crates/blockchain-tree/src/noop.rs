@@ -0,0 +1,153 @@
+//! A blockchain tree that does not hold any blocks.
+use reth_interfaces::{
+    blockchain_tree::{
+        error::{BlockchainTreeError, InsertBlockError},
+        BlockchainTreeEngine, BlockchainTreeViewer, CanonicalOutcome, InsertPayloadOk,
+    },
+    provider::ProviderError,
+    Error,
+};
+use reth_primitives::{
+    BlockHash, BlockNumHash, BlockNumber, Receipt, SealedBlock, SealedBlockWithSenders,
+    SealedHeader,
+};
+use reth_provider::{
+    BlockchainTreePendingStateProvider, CanonStateNotificationSender, CanonStateNotifications,
+    CanonStateSubscriptions, PostStateDataProvider,
+};
+use std::collections::{BTreeMap, HashSet};
+
+/// A [BlockchainTreeEngine] that does not hold any blocks and rejects all insertions.
+///
+/// This is used by processes that only read a database that is synced by another process, so the
+/// [BlockchainProvider](reth_provider::providers::BlockchainProvider) serves all blocks from the
+/// database. The canonical state notifications are sent by the owner of the
+/// [CanonStateNotificationSender] instead of the tree.
+#[derive(Debug, Clone)]
+pub struct NoopBlockchainTree {
+    canon_state_notification_sender: CanonStateNotificationSender,
+}
+
+impl NoopBlockchainTree {
+    /// Creates a new tree whose subscribers receive the notifications of the given sender.
+    pub fn new(canon_state_notification_sender: CanonStateNotificationSender) -> Self {
+        Self { canon_state_notification_sender }
+    }
+}
+
+impl BlockchainTreeEngine for NoopBlockchainTree {
+    fn buffer_block(&self, block: SealedBlockWithSenders) -> Result<(), InsertBlockError> {
+        Err(InsertBlockError::tree_error(
+            BlockchainTreeError::BlockBufferingFailed { block_hash: block.hash },
+            block.block,
+        ))
+    }
+
+    fn insert_block(
+        &self,
+        block: SealedBlockWithSenders,
+    ) -> Result<InsertPayloadOk, InsertBlockError> {
+        Err(InsertBlockError::tree_error(
+            BlockchainTreeError::BlockHashNotFoundInChain { block_hash: block.hash },
+            block.block,
+        ))
+    }
+
+    fn finalize_block(&self, _finalized_block: BlockNumber) {}
+
+    fn restore_canonical_hashes_and_finalize(
+        &self,
+        _last_finalized_block: BlockNumber,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn restore_canonical_hashes(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn make_canonical(&self, block_hash: &BlockHash) -> Result<CanonicalOutcome, Error> {
+        Err(ProviderError::BlockHashNotFound(*block_hash).into())
+    }
+
+    fn unwind(&self, _unwind_to: BlockNumber) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn take_invalid_buffered_blocks(&self) -> Vec<SealedHeader> {
+        Vec::new()
+    }
+}
+
+impl BlockchainTreeViewer for NoopBlockchainTree {
+    fn blocks(&self) -> BTreeMap<BlockNumber, HashSet<BlockHash>> {
+        Default::default()
+    }
+
+    fn header_by_hash(&self, _hash: BlockHash) -> Option<SealedHeader> {
+        None
+    }
+
+    fn block_by_hash(&self, _hash: BlockHash) -> Option<SealedBlock> {
+        None
+    }
+
+    fn buffered_block_by_hash(&self, _block_hash: BlockHash) -> Option<SealedBlock> {
+        None
+    }
+
+    fn buffered_header_by_hash(&self, _block_hash: BlockHash) -> Option<SealedHeader> {
+        None
+    }
+
+    fn canonical_blocks(&self) -> BTreeMap<BlockNumber, BlockHash> {
+        Default::default()
+    }
+
+    fn find_canonical_ancestor(&self, _parent_hash: BlockHash) -> Option<BlockHash> {
+        None
+    }
+
+    fn is_canonical(&self, _hash: BlockHash) -> Result<bool, Error> {
+        Ok(false)
+    }
+
+    fn lowest_buffered_ancestor(&self, _hash: BlockHash) -> Option<SealedBlockWithSenders> {
+        None
+    }
+
+    fn canonical_tip(&self) -> BlockNumHash {
+        Default::default()
+    }
+
+    fn pending_blocks(&self) -> (BlockNumber, Vec<BlockHash>) {
+        (0, vec![])
+    }
+
+    fn pending_block_num_hash(&self) -> Option<BlockNumHash> {
+        None
+    }
+
+    fn pending_block_and_receipts(&self) -> Option<(SealedBlock, Vec<Receipt>)> {
+        None
+    }
+
+    fn receipts_by_block_hash(&self, _block_hash: BlockHash) -> Option<Vec<Receipt>> {
+        None
+    }
+}
+
+impl BlockchainTreePendingStateProvider for NoopBlockchainTree {
+    fn find_pending_state_provider(
+        &self,
+        _block_hash: BlockHash,
+    ) -> Option<Box<dyn PostStateDataProvider>> {
+        None
+    }
+}
+
+impl CanonStateSubscriptions for NoopBlockchainTree {
+    fn subscribe_to_canonical_state(&self) -> CanonStateNotifications {
+        self.canon_state_notification_sender.subscribe()
+    }
+}
@@ -0,0 +1,79 @@
+//! Hooks that are notified about reorgs of the canonical chain.
+use reth_primitives::{BlockNumHash, ForkBlock};
+use std::fmt::Debug;
+
+/// A reorg of the canonical chain, as performed by the [BlockchainTree](crate::BlockchainTree).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReorgEvent {
+    /// The number of canonical blocks that were reverted.
+    pub depth: u64,
+    /// The last block that both the old and the new canonical chain have in common.
+    pub fork_block: ForkBlock,
+    /// The tip of the canonical chain before the reorg.
+    pub old_tip: BlockNumHash,
+    /// The tip of the canonical chain after the reorg.
+    pub new_tip: BlockNumHash,
+}
+
+/// A hook that is called for reorgs of the canonical chain, for example to alert an operator.
+///
+/// The hook is called while the tree is locked, so it must not block.
+pub trait ReorgHook: Debug + Send + Sync {
+    /// Called after the reorg was committed to the database.
+    fn on_reorg(&self, event: &ReorgEvent);
+}
+
+/// Calls a [ReorgHook] for reorgs that are at least a minimum depth deep.
+#[derive(Debug)]
+pub struct ReorgAlert {
+    /// The minimum depth of reorgs that the hook is called for.
+    min_depth: u64,
+    hook: Box<dyn ReorgHook>,
+}
+
+impl ReorgAlert {
+    /// Creates an alert that calls the hook for reorgs of at least `min_depth` blocks.
+    pub fn new(min_depth: u64, hook: Box<dyn ReorgHook>) -> Self {
+        Self { min_depth, hook }
+    }
+
+    /// Calls the hook if the reorg is deep enough.
+    pub fn on_reorg(&self, event: &ReorgEvent) {
+        if event.depth >= self.min_depth {
+            self.hook.on_reorg(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug, Default)]
+    struct RecordingHook(Mutex<Vec<ReorgEvent>>);
+
+    impl ReorgHook for Arc<RecordingHook> {
+        fn on_reorg(&self, event: &ReorgEvent) {
+            self.0.lock().unwrap().push(*event);
+        }
+    }
+
+    #[test]
+    fn fires_for_deep_reorgs() {
+        let hook = Arc::new(RecordingHook::default());
+        let alert = ReorgAlert::new(2, Box::new(hook.clone()));
+        let reorg = |depth| ReorgEvent {
+            depth,
+            fork_block: ForkBlock::default(),
+            old_tip: BlockNumHash::default(),
+            new_tip: BlockNumHash::default(),
+        };
+
+        alert.on_reorg(&reorg(1));
+        assert!(hook.0.lock().unwrap().is_empty());
+        alert.on_reorg(&reorg(2));
+        alert.on_reorg(&reorg(5));
+        assert_eq!(*hook.0.lock().unwrap(), vec![reorg(2), reorg(5)]);
+    }
+}
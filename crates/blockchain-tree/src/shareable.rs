@@ -100,6 +100,10 @@ impl<DB: Database, C: Consensus, EF: ExecutorFactory> BlockchainTreeEngine
         tree.update_chains_metrics();
         res
     }
+
+    fn take_invalid_buffered_blocks(&self) -> Vec<SealedHeader> {
+        self.tree.write().take_invalid_buffered_blocks()
+    }
 }
 
 impl<DB: Database, C: Consensus, EF: ExecutorFactory> BlockchainTreeViewer
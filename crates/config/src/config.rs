@@ -4,8 +4,11 @@ use reth_downloaders::{
     bodies::bodies::BodiesDownloaderBuilder,
     headers::reverse_headers::ReverseHeadersDownloaderBuilder,
 };
-use reth_network::{NetworkConfigBuilder, PeersConfig, SessionsConfig};
-use reth_primitives::PruneModes;
+use reth_network::{
+    eth_requests::EthRequestHandlerConfig, transactions::TransactionsManagerConfig,
+    NetworkConfigBuilder, PeersConfig, SessionsConfig, SessionsPreset,
+};
+use reth_primitives::{BlockNumHash, BlockNumber, PruneModes, H256};
 use secp256k1::SecretKey;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -14,6 +17,10 @@ use std::path::PathBuf;
 #[derive(Debug, Clone, Default, Deserialize, PartialEq, Serialize)]
 #[serde(default)]
 pub struct Config {
+    /// Preset for peer sessions, only used if the `[sessions]` section is not configured.
+    // plain values have to be serialized before all tables
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sessions_preset: Option<SessionsPreset>,
     /// Configuration for each stage in the pipeline.
     // TODO(onbjerg): Can we make this easier to maintain when we add/remove stages?
     pub stages: StageConfig,
@@ -24,6 +31,16 @@ pub struct Config {
     pub peers: PeersConfig,
     /// Configuration for peer sessions.
     pub sessions: SessionsConfig,
+    /// Configuration for transaction gossip.
+    pub transactions: TransactionsManagerConfig,
+    /// Configuration for the rate limits of requests from peers.
+    pub eth_requests: EthRequestHandlerConfig,
+    /// Configuration for alerts about deep reorgs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reorg_alert: Option<ReorgAlertConfig>,
+    /// Configuration for the export of anonymized peer statistics.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peer_stats: Option<PeerStatsConfig>,
 }
 
 impl Config {
@@ -34,18 +51,20 @@ impl Config {
         peers_file: Option<PathBuf>,
         secret_key: SecretKey,
     ) -> NetworkConfigBuilder {
-        let peer_config = self
-            .peers
-            .clone()
-            .with_basic_nodes_from_file(peers_file)
-            .unwrap_or_else(|_| self.peers.clone());
+        let peer_config =
+            self.peers.clone().with_peers_file(peers_file).unwrap_or_else(|_| self.peers.clone());
 
         let discv4 =
             Discv4Config::builder().external_ip_resolver(Some(nat_resolution_method)).clone();
-        NetworkConfigBuilder::new(secret_key)
-            .sessions_config(self.sessions.clone())
-            .peer_config(peer_config)
-            .discovery(discv4)
+        let mut builder =
+            NetworkConfigBuilder::new(secret_key).peer_config(peer_config).discovery(discv4);
+        // an explicitly configured `[sessions]` section takes precedence over the preset
+        if self.sessions != SessionsConfig::default() {
+            builder = builder.sessions_config(self.sessions.clone());
+        } else if let Some(preset) = self.sessions_preset {
+            builder = builder.sessions_preset(preset);
+        }
+        builder
     }
 }
 
@@ -75,10 +94,12 @@ pub struct StageConfig {
     pub index_account_history: IndexHistoryConfig,
     /// Index Storage History stage configuration.
     pub index_storage_history: IndexHistoryConfig,
+    /// Chain Stats stage configuration.
+    pub chain_stats: ChainStatsConfig,
 }
 
 /// Header stage configuration.
-#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
 #[serde(default)]
 pub struct HeadersConfig {
     /// The maximum number of requests to send concurrently.
@@ -96,6 +117,11 @@ pub struct HeadersConfig {
     pub downloader_request_limit: u64,
     /// The maximum number of headers to download before committing progress to the database.
     pub commit_threshold: u64,
+    /// Trusted block hashes that the downloaded header chain must pass through.
+    ///
+    /// Default: none
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub trusted_checkpoints: Vec<TrustedCheckpoint>,
 }
 
 impl Default for HeadersConfig {
@@ -106,10 +132,26 @@ impl Default for HeadersConfig {
             downloader_max_concurrent_requests: 100,
             downloader_min_concurrent_requests: 5,
             downloader_max_buffered_responses: 100,
+            trusted_checkpoints: Vec::new(),
         }
     }
 }
 
+/// A trusted block of the canonical chain, e.g. obtained from the consensus layer.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Serialize)]
+pub struct TrustedCheckpoint {
+    /// The number of the block.
+    pub number: BlockNumber,
+    /// The hash of the block.
+    pub hash: H256,
+}
+
+impl From<TrustedCheckpoint> for BlockNumHash {
+    fn from(checkpoint: TrustedCheckpoint) -> Self {
+        BlockNumHash::new(checkpoint.number, checkpoint.hash)
+    }
+}
+
 impl From<HeadersConfig> for ReverseHeadersDownloaderBuilder {
     fn from(config: HeadersConfig) -> Self {
         ReverseHeadersDownloaderBuilder::default()
@@ -128,11 +170,17 @@ pub struct TotalDifficultyConfig {
     /// The maximum number of total difficulty entries to sum up before committing progress to the
     /// database.
     pub commit_threshold: u64,
+    /// Whether to skip storing total difficulty entries for blocks after the merge.
+    ///
+    /// The total difficulty is constant after the merge, so these entries are redundant. This
+    /// applies to the blocks synced by the pipeline and the blocks committed by the blockchain
+    /// tree.
+    pub elide_post_merge: bool,
 }
 
 impl Default for TotalDifficultyConfig {
     fn default() -> Self {
-        Self { commit_threshold: 100_000 }
+        Self { commit_threshold: 100_000, elide_post_merge: false }
     }
 }
 
@@ -152,6 +200,11 @@ pub struct BodiesConfig {
     ///
     /// Default: 2GB
     pub downloader_max_buffered_blocks_size_bytes: usize,
+    /// The size of the blocks that are spilled to a temporary directory on disk once the internal
+    /// block buffer is full, in bytes. `0` disables spilling.
+    ///
+    /// Default: 0
+    pub downloader_max_spilled_blocks_size_bytes: usize,
     /// The minimum number of requests to send concurrently.
     ///
     /// Default: 5
@@ -169,6 +222,7 @@ impl Default for BodiesConfig {
             downloader_request_limit: 200,
             downloader_stream_batch_size: 1_000,
             downloader_max_buffered_blocks_size_bytes: 2 * 1024 * 1024 * 1024, // ~2GB
+            downloader_max_spilled_blocks_size_bytes: 0,
             downloader_min_concurrent_requests: 5,
             downloader_max_concurrent_requests: 100,
         }
@@ -181,6 +235,7 @@ impl From<BodiesConfig> for BodiesDownloaderBuilder {
             .with_stream_batch_size(config.downloader_stream_batch_size)
             .with_request_limit(config.downloader_request_limit)
             .with_max_buffered_blocks_size_bytes(config.downloader_max_buffered_blocks_size_bytes)
+            .with_max_spilled_blocks_size_bytes(config.downloader_max_spilled_blocks_size_bytes)
             .with_concurrent_requests_range(
                 config.downloader_min_concurrent_requests..=
                     config.downloader_max_concurrent_requests,
@@ -210,11 +265,13 @@ pub struct ExecutionConfig {
     pub max_blocks: Option<u64>,
     /// The maximum amount of state changes to keep in memory before the execution stage commits.
     pub max_changes: Option<u64>,
+    /// Whether to store the preimages of hashed addresses and storage keys of executed blocks.
+    pub preimages: bool,
 }
 
 impl Default for ExecutionConfig {
     fn default() -> Self {
-        Self { max_blocks: Some(500_000), max_changes: Some(5_000_000) }
+        Self { max_blocks: Some(500_000), max_changes: Some(5_000_000), preimages: false }
     }
 }
 
@@ -278,6 +335,22 @@ impl Default for IndexHistoryConfig {
     }
 }
 
+/// Chain Stats stage configuration.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct ChainStatsConfig {
+    /// Whether to maintain the cumulative gas used table. Disabled by default.
+    pub enabled: bool,
+    /// The maximum number of blocks to process before committing progress to the database.
+    pub commit_threshold: u64,
+}
+
+impl Default for ChainStatsConfig {
+    fn default() -> Self {
+        Self { enabled: false, commit_threshold: 100_000 }
+    }
+}
+
 /// Pruning configuration.
 #[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
 #[serde(default)]
@@ -294,9 +367,46 @@ impl Default for PruneConfig {
     }
 }
 
+/// Reorg alert configuration.
+#[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct ReorgAlertConfig {
+    /// The URL that a JSON description of every deep reorg is posted to.
+    pub webhook: String,
+    /// The minimum number of reverted canonical blocks for a reorg to be posted.
+    pub min_depth: u64,
+}
+
+impl Default for ReorgAlertConfig {
+    fn default() -> Self {
+        Self { webhook: String::new(), min_depth: 3 }
+    }
+}
+
+/// Peer statistics export configuration.
+#[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct PeerStatsConfig {
+    /// The file that every report is appended to as a line of JSON.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<PathBuf>,
+    /// The URL that every report is posted to as JSON.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    /// The interval of the reports in seconds.
+    pub interval: u64,
+}
+
+impl Default for PeerStatsConfig {
+    fn default() -> Self {
+        Self { file: None, endpoint: None, interval: 300 }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::Config;
+    use reth_network::SessionsPreset;
 
     const EXTENSION: &str = "toml";
 
@@ -317,6 +427,18 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_sessions_preset() {
+        with_tempdir("config-sessions-preset-test", |config_path| {
+            let config: Config = toml::from_str("sessions_preset = 'low-resource'").unwrap();
+            assert_eq!(config.sessions_preset, Some(SessionsPreset::LowResource));
+            confy::store_path(config_path, &config).unwrap();
+
+            let loaded_config: Config = confy::load_path(config_path).unwrap();
+            assert_eq!(config, loaded_config);
+        })
+    }
+
     #[test]
     fn test_load_config() {
         with_tempdir("config-load-test", |config_path| {
@@ -9,6 +9,7 @@ use reth_rpc_types::engine::{
     CancunPayloadFields, ExecutionPayload, ForkchoiceState, ForkchoiceUpdated, PayloadAttributes,
     PayloadStatus,
 };
+use reth_tasks::watchdog::QueuedMessages;
 use tokio::sync::{mpsc, mpsc::UnboundedSender, oneshot};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
@@ -19,6 +20,8 @@ use tokio_stream::wrappers::UnboundedReceiverStream;
 #[derive(Clone, Debug)]
 pub struct BeaconConsensusEngineHandle {
     pub(crate) to_engine: UnboundedSender<BeaconEngineMessage>,
+    /// The messages sent to the engine that it hasn't received yet.
+    pub(crate) queue: QueuedMessages,
 }
 
 // === impl BeaconConsensusEngineHandle ===
@@ -26,7 +29,15 @@ pub struct BeaconConsensusEngineHandle {
 impl BeaconConsensusEngineHandle {
     /// Creates a new beacon consensus engine handle.
     pub fn new(to_engine: UnboundedSender<BeaconEngineMessage>) -> Self {
-        Self { to_engine }
+        Self { to_engine, queue: QueuedMessages::default() }
+    }
+
+    /// Sends a message to the beacon consensus engine.
+    fn send(&self, msg: BeaconEngineMessage) {
+        self.queue.on_send();
+        if self.to_engine.send(msg).is_err() {
+            self.queue.on_recv();
+        }
     }
 
     /// Sends a new payload message to the beacon consensus engine and waits for a response.
@@ -38,7 +49,7 @@ impl BeaconConsensusEngineHandle {
         cancun_fields: Option<CancunPayloadFields>,
     ) -> Result<PayloadStatus, BeaconOnNewPayloadError> {
         let (tx, rx) = oneshot::channel();
-        let _ = self.to_engine.send(BeaconEngineMessage::NewPayload { payload, cancun_fields, tx });
+        self.send(BeaconEngineMessage::NewPayload { payload, cancun_fields, tx });
         rx.await.map_err(|_| BeaconOnNewPayloadError::EngineUnavailable)?
     }
 
@@ -65,11 +76,7 @@ impl BeaconConsensusEngineHandle {
         payload_attrs: Option<PayloadAttributes>,
     ) -> oneshot::Receiver<Result<OnForkChoiceUpdated, reth_interfaces::Error>> {
         let (tx, rx) = oneshot::channel();
-        let _ = self.to_engine.send(BeaconEngineMessage::ForkchoiceUpdated {
-            state,
-            payload_attrs,
-            tx,
-        });
+        self.send(BeaconEngineMessage::ForkchoiceUpdated { state, payload_attrs, tx });
         rx
     }
 
@@ -77,13 +84,13 @@ impl BeaconConsensusEngineHandle {
     ///
     /// See also <https://github.com/ethereum/execution-apis/blob/3d627c95a4d3510a8187dd02e0250ecb4331d27e/src/engine/paris.md#engine_exchangetransitionconfigurationv1>
     pub async fn transition_configuration_exchanged(&self) {
-        let _ = self.to_engine.send(BeaconEngineMessage::TransitionConfigurationExchanged);
+        self.send(BeaconEngineMessage::TransitionConfigurationExchanged);
     }
 
     /// Creates a new [`BeaconConsensusEngineEvent`] listener stream.
     pub fn event_listener(&self) -> UnboundedReceiverStream<BeaconConsensusEngineEvent> {
         let (tx, rx) = mpsc::unbounded_channel();
-        let _ = self.to_engine.send(BeaconEngineMessage::EventListener(tx));
+        self.send(BeaconEngineMessage::EventListener(tx));
         UnboundedReceiverStream::new(rx)
     }
 }
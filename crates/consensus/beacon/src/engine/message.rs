@@ -165,3 +165,19 @@ pub enum BeaconEngineMessage {
     /// Add a new listener for [`BeaconEngineMessage`].
     EventListener(UnboundedSender<BeaconConsensusEngineEvent>),
 }
+
+// === impl BeaconEngineMessage ===
+
+impl BeaconEngineMessage {
+    /// Returns the kind of the message, used for diagnostics.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            BeaconEngineMessage::NewPayload { .. } => "new_payload",
+            BeaconEngineMessage::ForkchoiceUpdated { .. } => "forkchoice_updated",
+            BeaconEngineMessage::TransitionConfigurationExchanged => {
+                "transition_configuration_exchanged"
+            }
+            BeaconEngineMessage::EventListener(_) => "event_listener",
+        }
+    }
+}
@@ -8,7 +8,7 @@ use crate::{
     sync::{EngineSyncController, EngineSyncEvent},
 };
 use futures::{Future, StreamExt};
-use reth_db::database::Database;
+use reth_db::{database::Database, models::ChainStateKey};
 use reth_interfaces::{
     blockchain_tree::{
         error::{InsertBlockError, InsertBlockErrorKind},
@@ -26,16 +26,16 @@ use reth_primitives::{
     Head, Header, SealedBlock, SealedHeader, H256, U256,
 };
 use reth_provider::{
-    BlockIdReader, BlockReader, BlockSource, CanonChainTracker, ChainSpecProvider, ProviderError,
-    StageCheckpointReader,
+    BlockIdReader, BlockReader, BlockSource, CanonChainTracker, ChainSpecProvider,
+    ChainStateReader, ChainStateWriter, ProviderError, StageCheckpointReader,
 };
 use reth_prune::Pruner;
 use reth_rpc_types::engine::{
-    CancunPayloadFields, ExecutionPayload, PayloadAttributes, PayloadError, PayloadStatus,
-    PayloadStatusEnum, PayloadValidationError,
+    CancunPayloadFields, ExecutionPayload, PayloadAttributes, PayloadStatus, PayloadStatusEnum,
+    PayloadValidationError,
 };
 use reth_stages::{ControlFlow, Pipeline, PipelineError};
-use reth_tasks::TaskSpawner;
+use reth_tasks::{watchdog::Heartbeat, TaskSpawner};
 use std::{
     pin::Pin,
     sync::Arc,
@@ -162,7 +162,9 @@ where
         + BlockReader
         + BlockIdReader
         + CanonChainTracker
-        + StageCheckpointReader,
+        + StageCheckpointReader
+        + ChainStateReader
+        + ChainStateWriter,
 {
     /// Controls syncing triggered by engine updates.
     sync: EngineSyncController<DB, Client>,
@@ -199,6 +201,8 @@ where
     pipeline_run_threshold: u64,
     /// Controls pruning triggered by engine updates.
     prune: Option<EnginePruneController<DB>>,
+    /// Reports the progress of the message loop, if monitored.
+    heartbeat: Option<Heartbeat>,
 }
 
 impl<DB, BT, Client> BeaconConsensusEngine<DB, BT, Client>
@@ -209,6 +213,8 @@ where
         + BlockIdReader
         + CanonChainTracker
         + StageCheckpointReader
+        + ChainStateReader
+        + ChainStateWriter
         + ChainSpecProvider
         + 'static,
     Client: HeadersClient + BodiesClient + Clone + Unpin + 'static,
@@ -256,6 +262,9 @@ where
     /// - The process was previously interrupted amidst the pipeline run. This is checked by
     ///   comparing the checkpoints of the first ([StageId::Headers]) and last ([StageId::Finish])
     ///   stages. In this case, the latest available header in the database is used as the target.
+    /// - The blocks of the last valid forkchoice state are not canonical in the database, see
+    ///   [Self::check_forkchoice_consistency]. In this case, the last head block is used as the
+    ///   target.
     ///
     /// Propagates any database related error.
     #[allow(clippy::too_many_arguments)]
@@ -274,7 +283,7 @@ where
         rx: UnboundedReceiver<BeaconEngineMessage>,
         pruner: Option<Pruner<DB>>,
     ) -> Result<(Self, BeaconConsensusEngineHandle), Error> {
-        let handle = BeaconConsensusEngineHandle { to_engine };
+        let handle = BeaconConsensusEngineHandle::new(to_engine);
         let sync = EngineSyncController::new(
             pipeline,
             client,
@@ -297,12 +306,16 @@ where
             metrics: EngineMetrics::default(),
             pipeline_run_threshold,
             prune,
+            heartbeat: None,
         };
 
         let maybe_pipeline_target = match target {
             // Provided target always takes precedence.
             target @ Some(_) => target,
-            None => this.check_pipeline_consistency()?,
+            None => match this.check_pipeline_consistency()? {
+                target @ Some(_) => target,
+                None => this.check_forkchoice_consistency()?,
+            },
         };
 
         if let Some(target) = maybe_pipeline_target {
@@ -355,6 +368,83 @@ where
         Ok(None)
     }
 
+    /// Check if the blocks of the last valid forkchoice state, persisted before the node was shut
+    /// down, are canonical in the database.
+    ///
+    /// If they are, the tracked safe and finalized blocks are restored, so they're available before
+    /// the consensus layer sends the next forkchoice update.
+    ///
+    /// Otherwise the database is inconsistent with the last state the consensus layer considered
+    /// valid, for example because the node was interrupted while the pipeline unwound or the
+    /// database was modified manually. Instead of rejecting the next forkchoice update of the
+    /// consensus layer, the pipeline is run to the last head block, which downloads missing blocks
+    /// and unwinds blocks that are not part of its chain.
+    ///
+    /// # Returns
+    ///
+    /// The hash of the last head block if the database is inconsistent, otherwise `None`.
+    fn check_forkchoice_consistency(&self) -> Result<Option<H256>, Error> {
+        let Some(head) = self.blockchain.get_chain_state(ChainStateKey::LastHeadBlock)? else {
+            return Ok(None)
+        };
+        let safe = self.blockchain.get_chain_state(ChainStateKey::LastSafeBlock)?;
+        let finalized = self.blockchain.get_chain_state(ChainStateKey::LastFinalizedBlock)?;
+
+        let mut headers = [None, None, None];
+        for (header, hash) in headers.iter_mut().zip([Some(head), safe, finalized]) {
+            // the safe and finalized hashes are zero until the first finalized checkpoint
+            let Some(hash) = hash.filter(|hash| !hash.is_zero()) else { continue };
+            match self.canonical_header_by_hash(hash)? {
+                Some(canonical) => *header = Some(canonical),
+                None => {
+                    warn!(
+                        target: "consensus::engine",
+                        ?head,
+                        missing = ?hash,
+                        "Last forkchoice state is inconsistent with the database"
+                    );
+                    return Ok(Some(head))
+                }
+            }
+        }
+
+        let [_, safe, finalized] = headers;
+        if let Some(safe) = safe {
+            self.blockchain.set_safe(safe);
+        }
+        if let Some(finalized) = finalized {
+            self.blockchain.set_finalized(finalized);
+        }
+        debug!(target: "consensus::engine", ?head, "Last forkchoice state is consistent with the database");
+
+        Ok(None)
+    }
+
+    /// Returns the header of the block with the given hash if it's part of the canonical chain in
+    /// the database.
+    fn canonical_header_by_hash(&self, hash: H256) -> Result<Option<SealedHeader>, Error> {
+        let Some(number) = self.blockchain.block_number(hash)? else { return Ok(None) };
+        if self.blockchain.block_hash(number)? != Some(hash) {
+            return Ok(None)
+        }
+        Ok(self.blockchain.sealed_header(number)?)
+    }
+
+    /// Persists the forkchoice state, so it can be checked against the database on the next
+    /// startup.
+    ///
+    /// This should only be called for states that are `VALID`.
+    fn persist_forkchoice_state(&self, state: &ForkchoiceState) {
+        let entries = [
+            (ChainStateKey::LastHeadBlock, state.head_block_hash),
+            (ChainStateKey::LastSafeBlock, state.safe_block_hash),
+            (ChainStateKey::LastFinalizedBlock, state.finalized_block_hash),
+        ];
+        if let Err(error) = self.blockchain.save_chain_state(&entries) {
+            warn!(target: "consensus::engine", ?state, ?error, "Failed to persist forkchoice state");
+        }
+    }
+
     /// Returns a new [`BeaconConsensusEngineHandle`] that can be cloned and shared.
     ///
     /// The [`BeaconConsensusEngineHandle`] can be used to interact with this
@@ -363,6 +453,14 @@ where
         self.handle.clone()
     }
 
+    /// Reports the progress of the message loop and the messages queued by the
+    /// [`BeaconConsensusEngineHandle`] to the given [`Heartbeat`], so that a stalled engine can be
+    /// detected.
+    pub fn set_heartbeat(&mut self, heartbeat: Heartbeat) {
+        heartbeat.track_queue(self.handle.queue.clone());
+        self.heartbeat = Some(heartbeat);
+    }
+
     /// Returns true if the distance from the local tip to the block is greater than the configured
     /// threshold.
     ///
@@ -528,6 +626,18 @@ where
         Some(status)
     }
 
+    /// Marks the buffered blocks that failed validation when they were connected by the last
+    /// insertion as invalid.
+    ///
+    /// Their descendants are still buffered and link to the invalid block, so they are rejected
+    /// without executing the invalid block again.
+    fn on_invalid_buffered_blocks(&mut self) {
+        for header in self.blockchain.take_invalid_buffered_blocks() {
+            warn!(target: "consensus::engine", invalid_hash=?header.hash, invalid_number=?header.number, "Marking buffered block as invalid");
+            self.invalid_headers.insert(header);
+        }
+    }
+
     /// Checks if the given `head` points to an invalid header, which requires a specific response
     /// to a forkchoice update.
     fn check_invalid_ancestor(&mut self, head: H256) -> Option<PayloadStatus> {
@@ -583,6 +693,7 @@ where
         match fcu_status {
             ForkchoiceStatus::Invalid => {}
             ForkchoiceStatus::Valid => {
+                self.persist_forkchoice_state(&state);
                 // FCU head is valid, we're no longer syncing
                 self.sync_state_updater.update_sync_state(SyncState::Idle);
                 // node's fully synced, clear active download requests
@@ -1128,16 +1239,16 @@ where
     ///    - incorrect hash
     ///    - the versioned hashes passed with the payload do not exactly match transaction
     ///    versioned hashes
+    ///
+    /// See [ExecutionPayload::try_into_sealed_block_with_cancun_fields].
     fn ensure_well_formed_payload(
         &self,
         payload: ExecutionPayload,
         cancun_fields: Option<CancunPayloadFields>,
     ) -> Result<SealedBlock, PayloadStatus> {
         let parent_hash = payload.parent_hash();
-        let block = match payload.try_into_sealed_block(
-            cancun_fields.as_ref().map(|fields| fields.parent_beacon_block_root),
-        ) {
-            Ok(block) => block,
+        match payload.try_into_sealed_block_with_cancun_fields(cancun_fields.as_ref()) {
+            Ok(block) => Ok(block),
             Err(error) => {
                 error!(target: "consensus::engine", ?error, "Invalid payload");
 
@@ -1150,80 +1261,9 @@ where
                 }
                 let status = PayloadStatusEnum::from(error);
 
-                return Err(PayloadStatus::new(status, latest_valid_hash))
-            }
-        };
-
-        let block_versioned_hashes = block
-            .blob_transactions()
-            .iter()
-            .filter_map(|tx| tx.as_eip4844().map(|blob_tx| &blob_tx.blob_versioned_hashes))
-            .flatten()
-            .collect::<Vec<_>>();
-
-        self.validate_versioned_hashes(parent_hash, block_versioned_hashes, cancun_fields)?;
-
-        Ok(block)
-    }
-
-    /// Validates that the versioned hashes in the block match the versioned hashes passed in the
-    /// [CancunPayloadFields], if the cancun payload fields are provided. If the payload fields are
-    /// not provided, but versioned hashes exist in the block, this returns a [PayloadStatus] with
-    /// the [PayloadError::InvalidVersionedHashes] error.
-    ///
-    /// This validates versioned hashes according to the Engine API Cancun spec:
-    /// <https://github.com/ethereum/execution-apis/blob/fe8e13c288c592ec154ce25c534e26cb7ce0530d/src/engine/cancun.md#specification>
-    fn validate_versioned_hashes(
-        &self,
-        parent_hash: H256,
-        block_versioned_hashes: Vec<&H256>,
-        cancun_fields: Option<CancunPayloadFields>,
-    ) -> Result<(), PayloadStatus> {
-        // This validates the following engine API rule:
-        //
-        // 3. Given the expected array of blob versioned hashes client software **MUST** run its
-        //    validation by taking the following steps:
-        //
-        //   1. Obtain the actual array by concatenating blob versioned hashes lists
-        //      (`tx.blob_versioned_hashes`) of each [blob
-        //      transaction](https://eips.ethereum.org/EIPS/eip-4844#new-transaction-type) included
-        //      in the payload, respecting the order of inclusion. If the payload has no blob
-        //      transactions the expected array **MUST** be `[]`.
-        //
-        //   2. Return `{status: INVALID, latestValidHash: null, validationError: errorMessage |
-        //      null}` if the expected and the actual arrays don't match.
-        //
-        // This validation **MUST** be instantly run in all cases even during active sync process.
-        if let Some(fields) = cancun_fields {
-            if block_versioned_hashes.len() != fields.versioned_hashes.len() {
-                // if the lengths don't match then we know that the payload is invalid
-                let latest_valid_hash =
-                    self.latest_valid_hash_for_invalid_payload(parent_hash, None);
-                let status = PayloadStatusEnum::from(PayloadError::InvalidVersionedHashes);
-                return Err(PayloadStatus::new(status, latest_valid_hash))
-            }
-
-            // we can use `zip` safely here because we already compared their length
-            let zipped_versioned_hashes =
-                fields.versioned_hashes.iter().zip(block_versioned_hashes);
-            for (payload_versioned_hash, block_versioned_hash) in zipped_versioned_hashes {
-                if payload_versioned_hash != block_versioned_hash {
-                    // One of the hashes does not match - return invalid
-                    let latest_valid_hash =
-                        self.latest_valid_hash_for_invalid_payload(parent_hash, None);
-                    let status = PayloadStatusEnum::from(PayloadError::InvalidVersionedHashes);
-                    return Err(PayloadStatus::new(status, latest_valid_hash))
-                }
+                Err(PayloadStatus::new(status, latest_valid_hash))
             }
-        } else if !block_versioned_hashes.is_empty() {
-            // there are versioned hashes in the block but no expected versioned hashes were
-            // provided in the new payload call, so the payload is invalid
-            let latest_valid_hash = self.latest_valid_hash_for_invalid_payload(parent_hash, None);
-            let status = PayloadStatusEnum::from(PayloadError::InvalidVersionedHashes);
-            return Err(PayloadStatus::new(status, latest_valid_hash))
         }
-
-        Ok(())
     }
 
     /// When the pipeline or the pruner is active, the tree is unable to commit any additional
@@ -1255,7 +1295,9 @@ where
         debug_assert!(self.sync.is_pipeline_idle(), "pipeline must be idle");
 
         let block_hash = block.hash;
-        let status = self.blockchain.insert_block_without_senders(block.clone())?;
+        let status = self.blockchain.insert_block_without_senders(block.clone());
+        self.on_invalid_buffered_blocks();
+        let status = status?;
         let mut latest_valid_hash = None;
         let block = Arc::new(block);
         let status = match status {
@@ -1268,7 +1310,20 @@ where
                 self.listeners.notify(BeaconConsensusEngineEvent::ForkBlockAdded(block));
                 PayloadStatusEnum::Accepted
             }
-            InsertPayloadOk::Inserted(BlockStatus::Disconnected { .. }) |
+            InsertPayloadOk::Inserted(BlockStatus::Disconnected { missing_ancestor }) => {
+                // check if the block's parent is already marked as invalid
+                if let Some(status) =
+                    self.check_invalid_ancestor_with_head(block.parent_hash, block.hash)
+                {
+                    return Ok(status)
+                }
+
+                // the block is buffered until its missing ancestor is inserted
+                self.download_missing_ancestor(missing_ancestor);
+
+                // not known to be invalid, but we don't know anything else
+                PayloadStatusEnum::Syncing
+            }
             InsertPayloadOk::AlreadySeen(BlockStatus::Disconnected { .. }) => {
                 // check if the block's parent is already marked as invalid
                 if let Some(status) =
@@ -1384,7 +1439,9 @@ where
             return
         }
 
-        match self.blockchain.insert_block_without_senders(block) {
+        let res = self.blockchain.insert_block_without_senders(block);
+        self.on_invalid_buffered_blocks();
+        match res {
             Ok(status) => {
                 match status {
                     InsertPayloadOk::Inserted(BlockStatus::Valid) => {
@@ -1417,6 +1474,19 @@ where
         }
     }
 
+    /// Starts downloading the missing ancestor of a buffered payload.
+    ///
+    /// Once the ancestor is downloaded and inserted, the buffered payload is attached and validated
+    /// without waiting for the CL to resend it. Ancestors that are too far ahead of the canonical
+    /// tip are left to the pipeline, which is started by the next forkchoice update.
+    fn download_missing_ancestor(&mut self, missing_ancestor: BlockNumHash) {
+        let canonical_tip_num = self.blockchain.canonical_tip().number;
+        if self.exceeds_pipeline_run_threshold(canonical_tip_num, missing_ancestor.number) {
+            return
+        }
+        self.sync.download_full_block(missing_ancestor.hash);
+    }
+
     /// This handles downloaded blocks that are shown to be disconnected from the canonical chain.
     ///
     /// This mainly compares the missing parent of the downloaded block with the current canonical
@@ -1703,6 +1773,9 @@ where
                 // sending us unneeded updates, we need to respond `true` on `eth_syncing` request.
                 self.sync_state_updater.update_sync_state(SyncState::Syncing);
             }
+            EnginePruneEvent::Restarted { restarts } => {
+                warn!(target: "consensus::engine", restarts, "Pruner panicked, retrying on the next block");
+            }
             EnginePruneEvent::TaskDropped => {
                 error!(target: "consensus::engine", "Failed to receive spawned pruner");
                 return Some(Err(BeaconConsensusEngineError::PrunerChannelClosed))
@@ -1771,6 +1844,8 @@ where
         + BlockIdReader
         + CanonChainTracker
         + StageCheckpointReader
+        + ChainStateReader
+        + ChainStateWriter
         + ChainSpecProvider
         + Unpin
         + 'static,
@@ -1779,6 +1854,7 @@ where
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let this = self.get_mut();
+        let _heartbeat = this.heartbeat.as_ref().map(Heartbeat::enter);
 
         // Process all incoming messages from the CL, these can affect the state of the
         // SyncController, hence they are polled first, and they're also time sensitive.
@@ -1796,32 +1872,38 @@ where
 
             // handle next engine message
             match this.engine_message_rx.poll_next_unpin(cx) {
-                Poll::Ready(Some(msg)) => match msg {
-                    BeaconEngineMessage::ForkchoiceUpdated { state, payload_attrs, tx } => {
-                        match this.on_forkchoice_updated(state, payload_attrs, tx) {
-                            OnForkchoiceUpdateOutcome::Processed => {}
-                            OnForkchoiceUpdateOutcome::ReachedMaxBlock => {
-                                // reached the max block, we can terminate the future
-                                return Poll::Ready(Ok(()))
-                            }
-                            OnForkchoiceUpdateOutcome::Fatal(err) => {
-                                // fatal error, we can terminate the future
-                                return Poll::Ready(Err(Error::Execution(err).into()))
+                Poll::Ready(Some(msg)) => {
+                    this.handle.queue.on_recv();
+                    if let Some(heartbeat) = &this.heartbeat {
+                        heartbeat.on_message(msg.kind());
+                    }
+                    match msg {
+                        BeaconEngineMessage::ForkchoiceUpdated { state, payload_attrs, tx } => {
+                            match this.on_forkchoice_updated(state, payload_attrs, tx) {
+                                OnForkchoiceUpdateOutcome::Processed => {}
+                                OnForkchoiceUpdateOutcome::ReachedMaxBlock => {
+                                    // reached the max block, we can terminate the future
+                                    return Poll::Ready(Ok(()))
+                                }
+                                OnForkchoiceUpdateOutcome::Fatal(err) => {
+                                    // fatal error, we can terminate the future
+                                    return Poll::Ready(Err(Error::Execution(err).into()))
+                                }
                             }
                         }
+                        BeaconEngineMessage::NewPayload { payload, cancun_fields, tx } => {
+                            this.metrics.new_payload_messages.increment(1);
+                            let res = this.on_new_payload(payload, cancun_fields);
+                            let _ = tx.send(res);
+                        }
+                        BeaconEngineMessage::TransitionConfigurationExchanged => {
+                            this.blockchain.on_transition_configuration_exchanged();
+                        }
+                        BeaconEngineMessage::EventListener(tx) => {
+                            this.listeners.push_listener(tx);
+                        }
                     }
-                    BeaconEngineMessage::NewPayload { payload, cancun_fields, tx } => {
-                        this.metrics.new_payload_messages.increment(1);
-                        let res = this.on_new_payload(payload, cancun_fields);
-                        let _ = tx.send(res);
-                    }
-                    BeaconEngineMessage::TransitionConfigurationExchanged => {
-                        this.blockchain.on_transition_configuration_exchanged();
-                    }
-                    BeaconEngineMessage::EventListener(tx) => {
-                        this.listeners.push_listener(tx);
-                    }
-                },
+                }
                 Poll::Ready(None) => {
                     unreachable!("Engine holds the a sender to the message channel")
                 }
@@ -2151,6 +2233,23 @@ mod tests {
             ));
             assert_eq!(result, expected_result);
             assert_matches!(engine_rx.try_recv(), Err(TryRecvError::Empty));
+
+            // the valid forkchoice state is persisted after the response is sent, so it's available
+            // once the next message was processed
+            let _ = env.send_forkchoice_updated(forkchoice).await.unwrap();
+            let provider = ProviderFactory::new(env.db.as_ref(), chain_spec.clone());
+            assert_eq!(
+                provider.get_chain_state(ChainStateKey::LastHeadBlock).unwrap(),
+                Some(block1.hash)
+            );
+            assert_eq!(
+                provider.get_chain_state(ChainStateKey::LastFinalizedBlock).unwrap(),
+                Some(block1.hash)
+            );
+            assert_eq!(
+                provider.get_chain_state(ChainStateKey::LastSafeBlock).unwrap(),
+                Some(H256::zero())
+            );
         }
 
         #[tokio::test]
@@ -3,10 +3,22 @@
 use futures::FutureExt;
 use reth_db::database::Database;
 use reth_primitives::BlockNumber;
-use reth_prune::{Pruner, PrunerResult, PrunerWithResult};
-use reth_tasks::TaskSpawner;
-use std::task::{ready, Context, Poll};
+use reth_prune::{Pruner, PrunerResult};
+use reth_tasks::{RestartPolicy, TaskSpawner};
+use std::{
+    panic::AssertUnwindSafe,
+    task::{ready, Context, Poll},
+    time::Duration,
+};
 use tokio::sync::oneshot;
+use tracing::error;
+
+/// How a panicked pruner run is handled.
+///
+/// The database transaction of a panicked run is rolled back, so the pruner is run again once the
+/// chain advances.
+const PRUNER_RESTART_POLICY: RestartPolicy =
+    RestartPolicy::Restart { max_restarts: 3, backoff: Duration::from_secs(1) };
 
 /// Manages pruning under the control of the engine.
 ///
@@ -16,12 +28,25 @@ pub(crate) struct EnginePruneController<DB> {
     pruner_state: PrunerState<DB>,
     /// The type that can spawn the pruner task.
     pruner_task_spawner: Box<dyn TaskSpawner>,
+    /// Number of consecutive pruner runs that panicked.
+    restarts: usize,
 }
 
 impl<DB: Database + 'static> EnginePruneController<DB> {
     /// Create a new instance
     pub(crate) fn new(pruner: Pruner<DB>, pruner_task_spawner: Box<dyn TaskSpawner>) -> Self {
-        Self { pruner_state: PrunerState::Idle(Some(pruner)), pruner_task_spawner }
+        Self { pruner_state: PrunerState::Idle(Some(pruner)), pruner_task_spawner, restarts: 0 }
+    }
+
+    /// Returns the backoff after which a panicked pruner is run again, or `None` if the
+    /// [PRUNER_RESTART_POLICY] gives up on the pruner.
+    fn restart_backoff(&self) -> Option<Duration> {
+        match PRUNER_RESTART_POLICY {
+            RestartPolicy::Restart { max_restarts, backoff } if self.restarts < max_restarts => {
+                Some(backoff)
+            }
+            _ => None,
+        }
     }
 
     /// Returns `true` if the pruner is idle.
@@ -40,10 +65,16 @@ impl<DB: Database + 'static> EnginePruneController<DB> {
             }
         };
         let ev = match res {
-            Ok((pruner, result)) => {
+            Ok((pruner, PrunerRun::Finished(result))) => {
+                self.restarts = 0;
                 self.pruner_state = PrunerState::Idle(Some(pruner));
                 EnginePruneEvent::Finished { result }
             }
+            Ok((pruner, PrunerRun::Panicked)) => {
+                self.restarts += 1;
+                self.pruner_state = PrunerState::Idle(Some(pruner));
+                EnginePruneEvent::Restarted { restarts: self.restarts }
+            }
             Err(_) => {
                 // failed to receive the pruner
                 EnginePruneEvent::TaskDropped
@@ -67,11 +98,27 @@ impl<DB: Database + 'static> EnginePruneController<DB> {
                 // Check tip for pruning
                 if pruner.is_pruning_needed(tip_block_number) {
                     let (tx, rx) = oneshot::channel();
+                    let restart_backoff = self.restart_backoff();
                     self.pruner_task_spawner.spawn_critical_blocking(
                         "pruner task",
                         Box::pin(async move {
-                            let result = pruner.run(tip_block_number);
-                            let _ = tx.send((pruner, result));
+                            let run = match restart_backoff {
+                                Some(backoff) => {
+                                    match std::panic::catch_unwind(AssertUnwindSafe(|| {
+                                        pruner.run(tip_block_number)
+                                    })) {
+                                        Ok(result) => PrunerRun::Finished(result),
+                                        Err(_) => {
+                                            error!(target: "consensus::engine", ?backoff, "Pruner panicked, restarting");
+                                            std::thread::sleep(backoff);
+                                            PrunerRun::Panicked
+                                        }
+                                    }
+                                }
+                                // the panic notifies the task manager
+                                None => PrunerRun::Finished(pruner.run(tip_block_number)),
+                            };
+                            let _ = tx.send((pruner, run));
                         }),
                     );
                     self.pruner_state = PrunerState::Running(rx);
@@ -118,11 +165,27 @@ pub(crate) enum EnginePruneEvent {
         /// Final result of the pruner run.
         result: PrunerResult,
     },
+    /// Pruner panicked and is run again once the chain advances, according to the
+    /// [RestartPolicy].
+    ///
+    /// If this is returned, the pruner is idle.
+    Restarted {
+        /// Number of consecutive pruner runs that panicked.
+        restarts: usize,
+    },
     /// Pruner task was dropped after it was started, unable to receive it because channel
     /// closed. This would indicate a panicked pruner task
     TaskDropped,
 }
 
+/// The outcome of a pruner run in the spawned task.
+enum PrunerRun {
+    /// The pruner finished with the result.
+    Finished(PrunerResult),
+    /// The pruner panicked and may be run again.
+    Panicked,
+}
+
 /// The possible pruner states within the sync controller.
 ///
 /// [PrunerState::Idle] means that the pruner is currently idle.
@@ -136,7 +199,7 @@ enum PrunerState<DB> {
     /// Pruner is idle.
     Idle(Option<Pruner<DB>>),
     /// Pruner is running and waiting for a response
-    Running(oneshot::Receiver<PrunerWithResult<DB>>),
+    Running(oneshot::Receiver<(Pruner<DB>, PrunerRun)>),
 }
 
 impl<DB> PrunerState<DB> {
@@ -0,0 +1,119 @@
+//! Message brokers the sink publishes to.
+use crate::SinkError;
+use async_trait::async_trait;
+
+/// A message broker that the [IndexerSink](crate::IndexerSink) publishes to.
+#[async_trait]
+pub trait SinkBackend: Send + Sync {
+    /// Publishes the payload with the given key to the topic.
+    ///
+    /// Returns once the broker acknowledged the message.
+    async fn publish(&self, topic: &str, key: &str, payload: Vec<u8>) -> Result<(), SinkError>;
+}
+
+#[async_trait]
+impl<T: SinkBackend + ?Sized> SinkBackend for Box<T> {
+    async fn publish(&self, topic: &str, key: &str, payload: Vec<u8>) -> Result<(), SinkError> {
+        (**self).publish(topic, key, payload).await
+    }
+}
+
+#[cfg(feature = "kafka")]
+pub use kafka::KafkaBackend;
+
+#[cfg(feature = "kafka")]
+mod kafka {
+    use super::*;
+    use rdkafka::{
+        producer::{FutureProducer, FutureRecord},
+        util::Timeout,
+        ClientConfig,
+    };
+
+    /// Publishes to Kafka topics with an idempotent producer.
+    ///
+    /// The block hash is the key of all messages.
+    pub struct KafkaBackend {
+        producer: FutureProducer,
+    }
+
+    impl KafkaBackend {
+        /// Creates a producer for the comma-separated list of bootstrap brokers.
+        pub fn new(brokers: &str) -> Result<Self, SinkError> {
+            let producer = ClientConfig::new()
+                .set("bootstrap.servers", brokers)
+                .set("enable.idempotence", "true")
+                .set("acks", "all")
+                .create()
+                .map_err(|err| SinkError::Connect(err.to_string()))?;
+            Ok(Self { producer })
+        }
+    }
+
+    #[async_trait]
+    impl SinkBackend for KafkaBackend {
+        async fn publish(&self, topic: &str, key: &str, payload: Vec<u8>) -> Result<(), SinkError> {
+            let record = FutureRecord::to(topic).key(key).payload(&payload);
+            self.producer.send(record, Timeout::Never).await.map_err(|(err, _)| {
+                SinkError::Publish { topic: topic.to_string(), message: err.to_string() }
+            })?;
+            Ok(())
+        }
+    }
+
+    impl std::fmt::Debug for KafkaBackend {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("KafkaBackend").finish_non_exhaustive()
+        }
+    }
+}
+
+#[cfg(feature = "nats")]
+pub use nats::NatsBackend;
+
+#[cfg(feature = "nats")]
+mod nats {
+    use super::*;
+    use async_nats::jetstream;
+
+    /// Publishes to NATS JetStream subjects.
+    ///
+    /// The subjects must be bound to a JetStream stream, otherwise the messages are not
+    /// acknowledged.
+    #[derive(Debug)]
+    pub struct NatsBackend {
+        jetstream: jetstream::Context,
+    }
+
+    impl NatsBackend {
+        /// Connects to the NATS server at the given URL.
+        pub async fn connect(url: &str) -> Result<Self, SinkError> {
+            let client = async_nats::connect(url)
+                .await
+                .map_err(|err| SinkError::Connect(err.to_string()))?;
+            Ok(Self { jetstream: jetstream::new(client) })
+        }
+    }
+
+    #[async_trait]
+    impl SinkBackend for NatsBackend {
+        async fn publish(
+            &self,
+            topic: &str,
+            _key: &str,
+            payload: Vec<u8>,
+        ) -> Result<(), SinkError> {
+            let err = |err: jetstream::context::PublishError| SinkError::Publish {
+                topic: topic.to_string(),
+                message: err.to_string(),
+            };
+            self.jetstream
+                .publish(topic.to_string(), payload.into())
+                .await
+                .map_err(err)?
+                .await
+                .map_err(err)?;
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use reth_db::DatabaseError;
+use thiserror::Error;
+
+/// Errors of the [IndexerSink](crate::IndexerSink).
+#[derive(Error, Debug)]
+pub enum SinkError {
+    /// The broker did not accept a message.
+    #[error("failed to publish to {topic}: {message}")]
+    Publish {
+        /// The topic of the message.
+        topic: String,
+        /// The error of the broker.
+        message: String,
+    },
+
+    /// The connection to the broker could not be established.
+    #[error("failed to connect to the broker: {0}")]
+    Connect(String),
+
+    /// An interface error occurred.
+    #[error("An interface error occurred.")]
+    Interface(#[from] reth_interfaces::Error),
+
+    /// A database error occurred.
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+
+    /// A message could not be serialized.
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
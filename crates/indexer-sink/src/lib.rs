@@ -0,0 +1,35 @@
+#![cfg_attr(docsrs, feature(doc_cfg))]
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxzy/reth/issues/"
+)]
+#![warn(missing_docs, unreachable_pub)]
+#![deny(unused_must_use, rust_2018_idioms)]
+#![doc(test(
+    no_crate_inject,
+    attr(deny(warnings, rust_2018_idioms), allow(dead_code, unused_variables))
+))]
+//! Publishes the canonical chain to external indexers.
+//!
+//! The [IndexerSink] is driven by the canonical state notifications of the node and publishes the
+//! blocks, receipts and state diffs of every canonical block as JSON messages to a message broker.
+//! Blocks that are reorged out are announced with a revert message.
+//!
+//! Delivery is at-least-once: the next block to publish is stored in the database after the broker
+//! acknowledged all messages of a block, and publishing resumes from there after a restart or a
+//! broker outage. Consumers should deduplicate messages by block hash.
+//!
+//! ## Feature Flags
+//!
+//! - `kafka`: Enables the [KafkaBackend](backend::KafkaBackend).
+//! - `nats`: Enables the [NatsBackend](backend::NatsBackend), which publishes to NATS JetStream.
+
+pub mod backend;
+mod error;
+pub mod message;
+mod sink;
+
+pub use backend::SinkBackend;
+pub use error::SinkError;
+pub use sink::{IndexerSink, Topics, INDEXER_SINK_STAGE_ID};
@@ -0,0 +1,203 @@
+//! The JSON messages that are published for canonical blocks.
+use reth_primitives::{
+    Account, Address, BlockHash, BlockNumber, Bytes, Log, Receipt, SealedBlock,
+    SealedBlockWithSenders, H256, U256,
+};
+use reth_provider::PostState;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A block that was added to the canonical chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockMessage {
+    /// The block with its header and hash.
+    pub block: SealedBlock,
+    /// The senders of the transactions.
+    pub senders: Vec<Address>,
+}
+
+impl From<&SealedBlockWithSenders> for BlockMessage {
+    fn from(block: &SealedBlockWithSenders) -> Self {
+        Self { block: block.block.clone(), senders: block.senders.clone() }
+    }
+}
+
+/// The receipts of a canonical block.
+///
+/// The receipts are empty if they are pruned.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceiptsMessage {
+    /// The number of the block.
+    pub block_number: BlockNumber,
+    /// The hash of the block.
+    pub block_hash: BlockHash,
+    /// The receipts in transaction order.
+    pub receipts: Vec<ReceiptMessage>,
+}
+
+/// A transaction receipt.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceiptMessage {
+    /// The type of the transaction.
+    pub tx_type: u8,
+    /// Whether the transaction was successful.
+    pub success: bool,
+    /// The gas used in the block up to and including this transaction.
+    pub cumulative_gas_used: u64,
+    /// The logs of the transaction.
+    pub logs: Vec<LogMessage>,
+}
+
+impl From<&Receipt> for ReceiptMessage {
+    fn from(receipt: &Receipt) -> Self {
+        Self {
+            tx_type: receipt.tx_type.into(),
+            success: receipt.success,
+            cumulative_gas_used: receipt.cumulative_gas_used,
+            logs: receipt.logs.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// A log of a transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogMessage {
+    /// The contract that emitted the log.
+    pub address: Address,
+    /// The topics of the log.
+    pub topics: Vec<H256>,
+    /// The data of the log.
+    pub data: Bytes,
+}
+
+impl From<&Log> for LogMessage {
+    fn from(log: &Log) -> Self {
+        Self { address: log.address, topics: log.topics.clone(), data: log.data.clone() }
+    }
+}
+
+/// The accounts and storage slots that were changed by a canonical block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateDiffMessage {
+    /// The number of the block.
+    pub block_number: BlockNumber,
+    /// The hash of the block.
+    pub block_hash: BlockHash,
+    /// The changed accounts.
+    pub accounts: BTreeMap<Address, AccountDiff>,
+    /// The changed storage slots by account.
+    pub storage: BTreeMap<Address, StorageDiff>,
+}
+
+impl StateDiffMessage {
+    /// Creates the state diff of the block.
+    ///
+    /// The state must contain the changes of the block, and the accounts and storage after the
+    /// execution of the block.
+    pub fn new(block: &SealedBlockWithSenders, state: &PostState) -> Self {
+        let number = block.number;
+        let accounts = state
+            .account_changes()
+            .inner
+            .get(&number)
+            .into_iter()
+            .flatten()
+            .map(|(address, before)| {
+                let after = state.account(address).copied().flatten();
+                (
+                    *address,
+                    AccountDiff { before: before.map(Into::into), after: after.map(Into::into) },
+                )
+            })
+            .collect();
+
+        let storage = state
+            .storage_changes()
+            .inner
+            .get(&number)
+            .into_iter()
+            .flatten()
+            .map(|(address, transition)| {
+                let after = state.account_storage(address);
+                let slots = transition
+                    .storage
+                    .iter()
+                    .map(|(slot, before)| {
+                        let after = after
+                            .and_then(|storage| storage.storage.get(slot))
+                            .copied()
+                            .unwrap_or_default();
+                        (*slot, SlotDiff { before: *before, after })
+                    })
+                    .collect();
+                (*address, StorageDiff { wiped: transition.wipe.is_wiped(), slots })
+            })
+            .collect();
+
+        Self { block_number: number, block_hash: block.hash, accounts, storage }
+    }
+}
+
+/// The state of an account before and after a block.
+///
+/// The state is `None` if the account does not exist.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountDiff {
+    /// The account before the block.
+    pub before: Option<AccountInfo>,
+    /// The account after the block.
+    pub after: Option<AccountInfo>,
+}
+
+/// The basic information of an account.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountInfo {
+    /// The nonce of the account.
+    pub nonce: u64,
+    /// The balance of the account.
+    pub balance: U256,
+    /// The hash of the bytecode, if the account is a contract.
+    pub code_hash: Option<H256>,
+}
+
+impl From<Account> for AccountInfo {
+    fn from(account: Account) -> Self {
+        Self { nonce: account.nonce, balance: account.balance, code_hash: account.bytecode_hash }
+    }
+}
+
+/// The changed storage slots of an account.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageDiff {
+    /// Whether the storage was wiped, for example by a selfdestruct.
+    pub wiped: bool,
+    /// The changed slots.
+    pub slots: BTreeMap<U256, SlotDiff>,
+}
+
+/// The value of a storage slot before and after a block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlotDiff {
+    /// The value before the block.
+    pub before: U256,
+    /// The value after the block.
+    pub after: U256,
+}
+
+/// A block that was removed from the canonical chain.
+///
+/// Reverts are published from the highest block down, so consumers can undo the messages of the
+/// block in order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevertMessage {
+    /// The number of the block.
+    pub block_number: BlockNumber,
+    /// The hash of the block.
+    pub block_hash: BlockHash,
+}
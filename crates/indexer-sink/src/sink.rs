@@ -0,0 +1,360 @@
+use crate::{
+    message::{BlockMessage, ReceiptsMessage, RevertMessage, StateDiffMessage},
+    SinkBackend, SinkError,
+};
+use reth_db::database::Database;
+use reth_interfaces::provider::ProviderError;
+use reth_primitives::{
+    stage::{StageCheckpoint, StageId},
+    BlockNumHash, BlockNumber, SealedBlockWithSenders,
+};
+use reth_provider::{
+    BlockHashReader, BlockNumReader, BlockReader, CanonStateNotification, CanonStateNotifications,
+    PostState, ProviderFactory, StageCheckpointReader, StageCheckpointWriter,
+};
+use serde::Serialize;
+use std::{collections::VecDeque, time::Duration};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::*;
+
+/// The stage id under which the next block to publish is stored.
+pub const INDEXER_SINK_STAGE_ID: StageId = StageId::Other("IndexerSink");
+
+/// The number of blocks that are read from the database at once while catching up.
+const CATCH_UP_BATCH_SIZE: u64 = 100;
+
+/// The number of published blocks that are kept to detect reorgs that were not notified.
+const MAX_PUBLISHED_BLOCKS: usize = 64;
+
+/// The interval in which publishing is retried after an error.
+const RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The topics the messages are published to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Topics {
+    /// The topic of the [BlockMessage]s.
+    pub blocks: String,
+    /// The topic of the [ReceiptsMessage]s.
+    pub receipts: String,
+    /// The topic of the [StateDiffMessage]s.
+    pub state_diffs: String,
+    /// The topic of the [RevertMessage]s.
+    pub reverts: String,
+}
+
+impl Topics {
+    /// Creates the topics `<prefix>.blocks`, `<prefix>.receipts`, `<prefix>.state-diffs` and
+    /// `<prefix>.reverts`.
+    pub fn with_prefix(prefix: &str) -> Self {
+        Self {
+            blocks: format!("{prefix}.blocks"),
+            receipts: format!("{prefix}.receipts"),
+            state_diffs: format!("{prefix}.state-diffs"),
+            reverts: format!("{prefix}.reverts"),
+        }
+    }
+}
+
+impl Default for Topics {
+    fn default() -> Self {
+        Self::with_prefix("reth")
+    }
+}
+
+/// Publishes the canonical chain to a [SinkBackend].
+///
+/// For every canonical block, a [BlockMessage], a [ReceiptsMessage] and a [StateDiffMessage] are
+/// published, in this order. The next block to publish is stored in the database under
+/// [INDEXER_SINK_STAGE_ID] once the backend acknowledged all messages of a block.
+///
+/// Blocks that were missed, because the sink started behind the tip, lagged behind the
+/// notifications or the backend was unavailable, are read from the database. Reorgs are detected
+/// for the most recently published blocks, older blocks that were reorged while the sink was not
+/// running are not reverted.
+pub struct IndexerSink<DB, B> {
+    factory: ProviderFactory<DB>,
+    backend: B,
+    topics: Topics,
+    /// The next block to publish.
+    next: BlockNumber,
+    /// The most recently published blocks, with the last published block as the last entry.
+    published: VecDeque<BlockNumHash>,
+}
+
+impl<DB, B> IndexerSink<DB, B>
+where
+    DB: Database + Clone + Send + Sync + 'static,
+    B: SinkBackend,
+{
+    /// Creates a new sink that resumes at the stored offset.
+    ///
+    /// If no offset is stored, publishing starts at `start_block`, or at the block after the
+    /// current tip if `None`.
+    pub fn new(
+        factory: ProviderFactory<DB>,
+        backend: B,
+        topics: Topics,
+        start_block: Option<BlockNumber>,
+    ) -> Result<Self, SinkError> {
+        let provider = factory.provider()?;
+        let next = match provider.get_stage_checkpoint(INDEXER_SINK_STAGE_ID)? {
+            Some(checkpoint) => checkpoint.block_number,
+            None => match start_block {
+                Some(block) => block,
+                None => provider.best_block_number()? + 1,
+            },
+        };
+        drop(provider);
+
+        Ok(Self { factory, backend, topics, next, published: VecDeque::new() })
+    }
+
+    /// Returns the next block to publish.
+    pub fn next_block(&self) -> BlockNumber {
+        self.next
+    }
+
+    /// Publishes the blocks of the canonical state notifications.
+    ///
+    /// Errors of the backend are retried until the blocks are published.
+    pub async fn run(mut self, mut notifications: CanonStateNotifications) {
+        info!(target: "indexer_sink", next = self.next, "Publishing canonical blocks");
+        self.recover().await;
+        loop {
+            let res = match notifications.recv().await {
+                Ok(notification) => self.on_notification(notification).await,
+                Err(RecvError::Lagged(skipped)) => {
+                    debug!(target: "indexer_sink", skipped, "Lagged behind canonical state notifications");
+                    self.catch_up().await
+                }
+                Err(RecvError::Closed) => return,
+            };
+            if let Err(err) = res {
+                warn!(target: "indexer_sink", %err, "Failed to publish canonical blocks");
+                self.recover().await;
+            }
+        }
+    }
+
+    /// Catches up with the database until it succeeds.
+    async fn recover(&mut self) {
+        while let Err(err) = self.catch_up().await {
+            warn!(target: "indexer_sink", %err, "Failed to publish canonical blocks, retrying in {RETRY_INTERVAL:?}");
+            tokio::time::sleep(RETRY_INTERVAL).await;
+        }
+    }
+
+    /// Publishes the changes of a canonical state notification.
+    pub async fn on_notification(
+        &mut self,
+        notification: CanonStateNotification,
+    ) -> Result<(), SinkError> {
+        if let Some(old) = notification.reverted() {
+            for block in old.blocks().values().rev() {
+                if self.published.back().map_or(false, |last| last.hash == block.hash) {
+                    self.revert().await?;
+                }
+            }
+        }
+
+        let Some(new) = notification.committed() else { return Ok(()) };
+        for (number, block) in new.blocks() {
+            if *number < self.next {
+                continue
+            }
+            if *number > self.next || !self.extends_published(block) {
+                // the notification does not connect to the published blocks
+                return self.catch_up().await
+            }
+            let state = new.state_at_block(*number).unwrap_or_default();
+            self.publish(block, &state).await?;
+        }
+        Ok(())
+    }
+
+    /// Reverts the published blocks that are no longer canonical and publishes all canonical
+    /// blocks of the database that were not published yet.
+    pub async fn catch_up(&mut self) -> Result<(), SinkError> {
+        while let Some(last) = self.published.back().copied() {
+            if self.factory.provider()?.block_hash(last.number)? == Some(last.hash) {
+                break
+            }
+            self.revert().await?;
+        }
+
+        loop {
+            let tip = self.factory.provider()?.best_block_number()?;
+            if self.next > tip {
+                return Ok(())
+            }
+            let range = self.next..=tip.min(self.next + CATCH_UP_BATCH_SIZE - 1);
+            trace!(target: "indexer_sink", ?range, "Reading blocks from the database");
+            let blocks = {
+                let provider = self.factory.provider()?;
+                let states = provider.block_execution_result_range(range.clone())?;
+                let blocks = range
+                    .map(|number| {
+                        let not_found = || {
+                            reth_interfaces::Error::from(ProviderError::BlockNotFound(
+                                number.into(),
+                            ))
+                        };
+                        let (block, senders) = provider
+                            .block_with_senders(number)?
+                            .ok_or_else(not_found)?
+                            .into_components();
+                        let hash = provider.block_hash(number)?.ok_or_else(not_found)?;
+                        Ok(SealedBlockWithSenders { block: block.seal(hash), senders })
+                    })
+                    .collect::<Result<Vec<_>, SinkError>>()?;
+                blocks.into_iter().zip(states)
+            };
+            for (block, state) in blocks {
+                self.publish(&block, &state).await?;
+            }
+        }
+    }
+
+    /// Returns `true` if the block is the child of the last published block.
+    fn extends_published(&self, block: &SealedBlockWithSenders) -> bool {
+        self.published.back().map_or(true, |last| last.hash == block.parent_hash)
+    }
+
+    /// Publishes the messages of a block and stores the next block to publish.
+    async fn publish(
+        &mut self,
+        block: &SealedBlockWithSenders,
+        state: &PostState,
+    ) -> Result<(), SinkError> {
+        let key = format!("{:?}", block.hash);
+        self.send(&self.topics.blocks, &key, &BlockMessage::from(block)).await?;
+        let receipts = ReceiptsMessage {
+            block_number: block.number,
+            block_hash: block.hash,
+            receipts: state.receipts(block.number).iter().map(Into::into).collect(),
+        };
+        self.send(&self.topics.receipts, &key, &receipts).await?;
+        self.send(&self.topics.state_diffs, &key, &StateDiffMessage::new(block, state)).await?;
+
+        self.published.push_back(block.num_hash());
+        if self.published.len() > MAX_PUBLISHED_BLOCKS {
+            self.published.pop_front();
+        }
+        self.save_next(block.number + 1).await?;
+        trace!(target: "indexer_sink", number = block.number, hash = ?block.hash, "Published block");
+        Ok(())
+    }
+
+    /// Publishes the revert of the last published block.
+    async fn revert(&mut self) -> Result<(), SinkError> {
+        let Some(last) = self.published.back().copied() else { return Ok(()) };
+        let message = RevertMessage { block_number: last.number, block_hash: last.hash };
+        self.send(&self.topics.reverts, &format!("{:?}", last.hash), &message).await?;
+
+        self.published.pop_back();
+        self.save_next(last.number).await?;
+        debug!(target: "indexer_sink", number = last.number, hash = ?last.hash, "Published revert");
+        Ok(())
+    }
+
+    async fn send<T: Serialize>(
+        &self,
+        topic: &str,
+        key: &str,
+        message: &T,
+    ) -> Result<(), SinkError> {
+        self.backend.publish(topic, key, serde_json::to_vec(message)?).await
+    }
+
+    /// Stores the next block to publish.
+    ///
+    /// The write transaction is committed on a blocking thread, so it doesn't stall the runtime.
+    async fn save_next(&mut self, next: BlockNumber) -> Result<(), SinkError> {
+        let factory = self.factory.clone();
+        tokio::task::spawn_blocking(move || {
+            let provider = factory.provider_rw()?;
+            provider.save_stage_checkpoint(INDEXER_SINK_STAGE_ID, StageCheckpoint::new(next))?;
+            provider.commit()?;
+            Ok::<_, SinkError>(())
+        })
+        .await
+        .expect("offset write does not panic")?;
+        self.next = next;
+        Ok(())
+    }
+}
+
+impl<DB, B> std::fmt::Debug for IndexerSink<DB, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IndexerSink")
+            .field("topics", &self.topics)
+            .field("next", &self.next)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use reth_db::test_utils::create_test_rw_db;
+    use reth_primitives::MAINNET;
+    use reth_provider::{test_utils::blocks::BlockChainTestData, BlockWriter, Chain};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct TestBackend {
+        messages: Mutex<Vec<(String, String)>>,
+    }
+
+    #[async_trait]
+    impl SinkBackend for Arc<TestBackend> {
+        async fn publish(
+            &self,
+            topic: &str,
+            key: &str,
+            _payload: Vec<u8>,
+        ) -> Result<(), SinkError> {
+            self.messages.lock().unwrap().push((topic.to_string(), key.to_string()));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn publish_and_resume() {
+        let factory = ProviderFactory::new(create_test_rw_db(), MAINNET.clone());
+        let data = BlockChainTestData::default();
+        let provider = factory.provider_rw().unwrap();
+        provider.insert_block(data.genesis, None, None).unwrap();
+        provider.commit().unwrap();
+
+        let backend = Arc::new(TestBackend::default());
+        let topics = Topics::default();
+        let mut sink =
+            IndexerSink::new(factory.clone(), backend.clone(), topics.clone(), None).unwrap();
+        assert_eq!(sink.next_block(), 1);
+
+        // the tree commits a block
+        let (block, state) = data.blocks[0].clone();
+        let provider = factory.provider_rw().unwrap();
+        provider.insert_block(block.block.clone(), Some(block.senders.clone()), None).unwrap();
+        provider.commit().unwrap();
+        let new = Arc::new(Chain::new(vec![(block.clone(), state)]));
+        sink.on_notification(CanonStateNotification::Commit { new }).await.unwrap();
+
+        let key = format!("{:?}", block.hash);
+        assert_eq!(
+            *backend.messages.lock().unwrap(),
+            vec![
+                (topics.blocks.clone(), key.clone()),
+                (topics.receipts.clone(), key.clone()),
+                (topics.state_diffs.clone(), key.clone()),
+            ]
+        );
+        assert_eq!(sink.next_block(), 2);
+
+        // a restarted sink resumes after the published block
+        let sink = IndexerSink::new(factory, backend, topics, None).unwrap();
+        assert_eq!(sink.next_block(), 2);
+    }
+}
@@ -89,6 +89,13 @@ pub trait BlockchainTreeEngine: BlockchainTreeViewer + Send + Sync {
 
     /// Unwind tables and put it inside state
     fn unwind(&self, unwind_to: BlockNumber) -> Result<(), Error>;
+
+    /// Returns the headers of the buffered blocks that failed validation when they were connected
+    /// to the tree, since the last call.
+    ///
+    /// Buffered blocks are executed when their missing ancestor is inserted, so an invalid
+    /// buffered block is not reported by the insertion that triggered its execution.
+    fn take_invalid_buffered_blocks(&self) -> Vec<SealedHeader>;
 }
 
 /// All possible outcomes of a canonicalization attempt of [BlockchainTreeEngine::make_canonical].
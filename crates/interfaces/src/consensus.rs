@@ -93,6 +93,8 @@ pub enum ConsensusError {
     BodyWithdrawalsRootDiff { got: H256, expected: H256 },
     #[error("Block with [hash:{hash:?},number: {number}] is already known.")]
     BlockKnown { hash: BlockHash, number: BlockNumber },
+    #[error("Block hash ({got:?}) does not match the trusted checkpoint for block {number} ({expected:?})")]
+    TrustedCheckpointMismatch { number: BlockNumber, got: BlockHash, expected: BlockHash },
     #[error("Block parent [hash:{hash:?}] is not known.")]
     ParentUnknown { hash: BlockHash },
     #[error(
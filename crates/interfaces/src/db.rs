@@ -1,3 +1,5 @@
+use crate::ErrorKind;
+
 /// Database error type. It uses i32 to represent an error code.
 #[derive(Debug, thiserror::Error, PartialEq, Eq, Clone)]
 pub enum DatabaseError {
@@ -43,6 +45,57 @@ pub enum DatabaseError {
     /// Failed to use the specified log level, as it's not available.
     #[error("Log level is not available: {0:?}")]
     LogLevelUnavailable(LogLevel),
+    /// A request to a remote database failed.
+    #[error("Remote database request failed: {0}")]
+    Remote(String),
+    /// Failed to write to a read-only database.
+    #[error("Database is read-only")]
+    ReadOnly,
+}
+
+impl DatabaseError {
+    /// Returns the [ErrorKind] of this error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            DatabaseError::DecodeError => ErrorKind::Corruption,
+            DatabaseError::LogLevelUnavailable(_) | DatabaseError::ReadOnly => ErrorKind::Other,
+            DatabaseError::Remote(_) => ErrorKind::Transient,
+            DatabaseError::FailedToOpen(code) |
+            DatabaseError::TableCreation(code) |
+            DatabaseError::Write { code, .. } |
+            DatabaseError::Read(code) |
+            DatabaseError::Delete(code) |
+            DatabaseError::Commit(code) |
+            DatabaseError::InitTransaction(code) |
+            DatabaseError::InitCursor(code) |
+            DatabaseError::Stats(code) => error_code_kind(*code),
+        }
+    }
+
+    /// Returns `true` if the operation that caused this error may succeed if retried.
+    pub fn is_retryable(&self) -> bool {
+        self.kind().is_retryable()
+    }
+}
+
+/// Classifies a raw MDBX error code, see `mdbx.h`.
+fn error_code_kind(code: i32) -> ErrorKind {
+    const MDBX_PAGE_NOTFOUND: i32 = -30797;
+    const MDBX_CORRUPTED: i32 = -30796;
+    const MDBX_PANIC: i32 = -30795;
+    const MDBX_INVALID: i32 = -30793;
+    const MDBX_READERS_FULL: i32 = -30790;
+    const MDBX_UNABLE_EXTEND_MAPSIZE: i32 = -30785;
+    const MDBX_BUSY: i32 = -30778;
+    const MDBX_EBADSIGN: i32 = -30420;
+    const MDBX_WANNA_RECOVERY: i32 = -30419;
+
+    match code {
+        MDBX_PAGE_NOTFOUND | MDBX_CORRUPTED | MDBX_PANIC | MDBX_INVALID | MDBX_EBADSIGN |
+        MDBX_WANNA_RECOVERY => ErrorKind::Corruption,
+        MDBX_READERS_FULL | MDBX_UNABLE_EXTEND_MAPSIZE | MDBX_BUSY => ErrorKind::Transient,
+        _ => ErrorKind::Other,
+    }
 }
 
 /// Database write operation type
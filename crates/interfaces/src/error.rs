@@ -23,3 +23,84 @@ pub enum Error {
     #[error("{0}")]
     Custom(std::string::String),
 }
+
+impl Error {
+    /// Returns the [ErrorKind] of this error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Database(err) => err.kind(),
+            Error::Provider(err) => err.kind(),
+            Error::Network(_) => ErrorKind::Transient,
+            Error::Execution(_) | Error::Consensus(_) | Error::Custom(_) => ErrorKind::Other,
+        }
+    }
+
+    /// Returns `true` if the operation that caused this error may succeed if retried.
+    pub fn is_retryable(&self) -> bool {
+        self.kind().is_retryable()
+    }
+}
+
+/// Broad classification of errors, so callers can handle them without matching on individual
+/// variants or error messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// The requested data does not exist.
+    NotFound,
+    /// The requested data existed at some point but was removed by pruning.
+    Pruned,
+    /// The database is in an inconsistent or invalid state.
+    ///
+    /// Retrying will not help and continuing may cause further damage.
+    Corruption,
+    /// A temporary failure, for example a busy database or a dropped channel.
+    ///
+    /// The operation may succeed if retried.
+    Transient,
+    /// Any other error.
+    Other,
+}
+
+impl ErrorKind {
+    /// Returns `true` if the operation may succeed if retried.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, ErrorKind::Transient)
+    }
+
+    /// Returns `true` if the requested data does not exist or was pruned.
+    pub fn is_missing_data(&self) -> bool {
+        matches!(self, ErrorKind::NotFound | ErrorKind::Pruned)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{db::DatabaseError, provider::ProviderError};
+
+    #[test]
+    fn error_kinds() {
+        let err: Error = ProviderError::BlockHashNotFound(Default::default()).into();
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+
+        let err: Error = ProviderError::StateAtBlockPruned(1).into();
+        assert_eq!(err.kind(), ErrorKind::Pruned);
+        assert!(err.kind().is_missing_data());
+
+        let err: Error = ProviderError::BlockBodyTransactionCount.into();
+        assert_eq!(err.kind(), ErrorKind::Corruption);
+
+        let err: Error = DatabaseError::DecodeError.into();
+        assert_eq!(err.kind(), ErrorKind::Corruption);
+
+        // `MDBX_BUSY`
+        let err: Error = ProviderError::Database(DatabaseError::InitTransaction(-30778)).into();
+        assert_eq!(err.kind(), ErrorKind::Transient);
+        assert!(err.is_retryable());
+
+        // `MDBX_CORRUPTED`
+        let err: Error = DatabaseError::Read(-30796).into();
+        assert_eq!(err.kind(), ErrorKind::Corruption);
+        assert!(!err.is_retryable());
+    }
+}
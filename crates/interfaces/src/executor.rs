@@ -1,4 +1,4 @@
-use reth_primitives::{BlockHash, BlockNumHash, Bloom, H256};
+use reth_primitives::{BlockHash, BlockNumHash, Bloom, H256, U256};
 use thiserror::Error;
 
 /// Transaction validation errors
@@ -20,6 +20,12 @@ pub enum BlockValidationError {
     },
     #[error("Block gas used {got} is different from expected gas used {expected}.")]
     BlockGasUsed { got: u64, expected: u64 },
+    #[error("Block blob gas used {got} is different from expected blob gas used {expected}.")]
+    BlockBlobGasUsed { got: u64, expected: u64 },
+    #[error("Max fee per blob gas {max_fee_per_blob_gas} of transaction {hash:?} is less than the blob gas price {blob_gas_price}")]
+    BlobFeeCapTooLow { hash: H256, max_fee_per_blob_gas: u128, blob_gas_price: u128 },
+    #[error("Sender of transaction {hash:?} can't pay the max cost {max_cost} including the max blob fee")]
+    InsufficientFundsForBlobFee { hash: H256, max_cost: U256 },
     #[error("Block {hash:?} is pre merge")]
     BlockPreMerge { hash: H256 },
     #[error("Missing total difficulty")]
@@ -6,3 +6,6 @@ pub mod downloader;
 
 /// Block response
 pub mod response;
+
+/// Validation of block bodies against their headers.
+pub mod validation;
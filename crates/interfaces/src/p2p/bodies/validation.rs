@@ -0,0 +1,50 @@
+use crate::consensus::ConsensusError;
+use reth_primitives::{BlockBody, SealedHeader};
+
+/// Ensures the block response data matches the header.
+///
+/// This only depends on the header, so it can be checked as soon as a body is received and the
+/// peer that served an invalid body can be penalized right away.
+///
+/// This ensures the body response items match the header's hashes:
+///   - ommer hash
+///   - transaction root
+///   - withdrawals root
+pub fn ensure_valid_body_response(
+    header: &SealedHeader,
+    block: &BlockBody,
+) -> Result<(), ConsensusError> {
+    let body_roots = block.calculate_roots();
+
+    if header.ommers_hash != body_roots.ommers_hash {
+        return Err(ConsensusError::BodyOmmersHashDiff {
+            got: body_roots.ommers_hash,
+            expected: header.ommers_hash,
+        })
+    }
+
+    if header.transactions_root != body_roots.tx_root {
+        return Err(ConsensusError::BodyTransactionRootDiff {
+            got: body_roots.tx_root,
+            expected: header.transactions_root,
+        })
+    }
+
+    let withdrawals = block.withdrawals.as_deref().unwrap_or(&[]);
+    if let Some(header_withdrawals_root) = header.withdrawals_root {
+        let withdrawals_root = reth_primitives::proofs::calculate_withdrawals_root(withdrawals);
+        if withdrawals_root != header_withdrawals_root {
+            return Err(ConsensusError::BodyWithdrawalsRootDiff {
+                got: withdrawals_root,
+                expected: header_withdrawals_root,
+            })
+        }
+        return Ok(())
+    }
+
+    if !withdrawals.is_empty() {
+        return Err(ConsensusError::WithdrawalsRootUnexpected)
+    }
+
+    Ok(())
+}
@@ -62,6 +62,7 @@ impl EthResponseValidator for RequestResult<Vec<Header>> {
                 RequestError::UnsupportedCapability => None,
                 RequestError::Timeout => Some(ReputationChangeKind::Timeout),
                 RequestError::BadResponse => None,
+                RequestError::RateLimited => None,
             }
         } else {
             None
@@ -83,6 +84,8 @@ pub enum RequestError {
     Timeout,
     #[error("Received bad response.")]
     BadResponse,
+    #[error("Request rate limit exceeded.")]
+    RateLimited,
 }
 
 // === impl RequestError ===
@@ -189,6 +192,31 @@ pub enum DownloadError {
         /// Invalid block number range.
         range: RangeInclusive<BlockNumber>,
     },
+    /* ==================== RECEIPTS ERRORS ==================== */
+    /// The receipts root of the received receipts does not match the header.
+    #[error("Receipts root mismatch for block {hash}. Received: {received}. Expected: {expected}")]
+    ReceiptsRootMismatch {
+        /// Hash of the block the receipts belong to.
+        hash: H256,
+        /// The root of the received receipts.
+        received: H256,
+        /// The receipts root of the header.
+        expected: H256,
+    },
+    /// Received receipts for more blocks than requested.
+    #[error("Received more receipts than requested. Expected: {expected}. Received: {received}")]
+    TooManyReceipts {
+        /// For how many blocks we received receipts.
+        received: usize,
+        /// For how many blocks we expected receipts.
+        expected: usize,
+    },
+    /// The receipts of a pre-Byzantium block can not be verified against its receipts root.
+    #[error("Receipts of pre-Byzantium block {number} can not be verified")]
+    PreByzantiumReceipts {
+        /// The number of the block.
+        number: BlockNumber,
+    },
     /* ==================== COMMON ERRORS ==================== */
     /// Timed out while waiting for request id response.
     #[error("Timed out while waiting for response.")]
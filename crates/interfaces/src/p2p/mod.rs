@@ -24,3 +24,9 @@ pub mod error;
 
 /// Priority enum for BlockHeader and BlockBody requests
 pub mod priority;
+
+/// Traits for requesting transactions from the pools of connected peers.
+pub mod transactions;
+
+/// Traits for requesting historical block receipts from connected peers.
+pub mod receipts;
@@ -0,0 +1,26 @@
+use crate::p2p::{download::DownloadClient, error::PeerRequestResult, priority::Priority};
+use futures::Future;
+use reth_primitives::{ReceiptWithBloom, H256};
+use std::pin::Pin;
+
+/// The receipts future type
+pub type ReceiptsFut =
+    Pin<Box<dyn Future<Output = PeerRequestResult<Vec<Vec<ReceiptWithBloom>>>> + Send + Sync>>;
+
+/// A client capable of requesting the receipts of historical blocks from connected peers.
+#[auto_impl::auto_impl(&, Arc, Box)]
+pub trait ReceiptsClient: DownloadClient {
+    /// The output of the request future for querying block receipts.
+    type Output: Future<Output = PeerRequestResult<Vec<Vec<ReceiptWithBloom>>>>
+        + Sync
+        + Send
+        + Unpin;
+
+    /// Fetches the receipts of the blocks with the requested hashes.
+    fn get_receipts(&self, hashes: Vec<H256>) -> Self::Output {
+        self.get_receipts_with_priority(hashes, Priority::Normal)
+    }
+
+    /// Fetches the receipts of the blocks with the requested hashes with priority.
+    fn get_receipts_with_priority(&self, hashes: Vec<H256>, priority: Priority) -> Self::Output;
+}
@@ -0,0 +1,30 @@
+use crate::p2p::{download::DownloadClient, error::PeerRequestResult, priority::Priority};
+use futures::Future;
+use reth_primitives::{PooledTransactionsElement, H256};
+use std::pin::Pin;
+
+/// The pooled transactions future type
+pub type PooledTransactionsFut =
+    Pin<Box<dyn Future<Output = PeerRequestResult<Vec<PooledTransactionsElement>>> + Send + Sync>>;
+
+/// A client capable of requesting transactions from the pools of connected peers.
+#[auto_impl::auto_impl(&, Arc, Box)]
+pub trait PooledTransactionsClient: DownloadClient {
+    /// The output of the request future for querying pooled transactions.
+    type Output: Future<Output = PeerRequestResult<Vec<PooledTransactionsElement>>>
+        + Sync
+        + Send
+        + Unpin;
+
+    /// Fetches the pooled transactions for the requested hashes.
+    fn get_pooled_transactions(&self, hashes: Vec<H256>) -> Self::Output {
+        self.get_pooled_transactions_with_priority(hashes, Priority::Normal)
+    }
+
+    /// Fetches the pooled transactions for the requested hashes with priority.
+    fn get_pooled_transactions_with_priority(
+        &self,
+        hashes: Vec<H256>,
+        priority: Priority,
+    ) -> Self::Output;
+}
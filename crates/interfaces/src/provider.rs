@@ -1,3 +1,4 @@
+use crate::ErrorKind;
 use reth_primitives::{Address, BlockHash, BlockHashOrNumber, BlockNumber, TxNumber, H256};
 
 /// Bundled errors variants thrown by various providers.
@@ -55,6 +56,9 @@ pub enum ProviderError {
     /// Block body wrong transaction count
     #[error("Stored block indices does not match transaction count")]
     BlockBodyTransactionCount,
+    /// Thrown when the senders of a block are pruned and can't be recovered from the signatures
+    #[error("Failed to recover the transaction senders of block {0}")]
+    SenderRecoveryError(BlockNumber),
     /// Thrown when the cache service task dropped
     #[error("cache service task stopped")]
     CacheServiceUnavailable,
@@ -97,3 +101,40 @@ pub enum ProviderError {
     #[error("State at block #{0} is pruned")]
     StateAtBlockPruned(BlockNumber),
 }
+
+impl ProviderError {
+    /// Returns the [ErrorKind] of this error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            ProviderError::Database(err) => err.kind(),
+            ProviderError::BlockHashNotFound(_) |
+            ProviderError::BlockBodyIndicesNotFound(_) |
+            ProviderError::TotalDifficultyNotFound { .. } |
+            ProviderError::HeaderNotFound(_) |
+            ProviderError::BlockNotFound(_) |
+            ProviderError::BestBlockNotFound |
+            ProviderError::FinalizedBlockNotFound |
+            ProviderError::SafeBlockNotFound |
+            ProviderError::UnknownBlockHash(_) |
+            ProviderError::StateForHashNotFound(_) |
+            ProviderError::BlockNumberForTransactionIndexNotFound => ErrorKind::NotFound,
+            ProviderError::StateAtBlockPruned(_) => ErrorKind::Pruned,
+            // changesets and senders are written together with the blocks they belong to, if
+            // they are missing the tables are out of sync
+            ProviderError::StorageChangesetNotFound { .. } |
+            ProviderError::AccountChangesetNotFound { .. } |
+            ProviderError::MismatchOfTransactionAndSenderId { .. } |
+            ProviderError::BlockBodyTransactionCount |
+            ProviderError::SenderRecoveryError(_) |
+            ProviderError::StateRootMismatch { .. } |
+            ProviderError::UnwindStateRootMismatch { .. } => ErrorKind::Corruption,
+            ProviderError::CacheServiceUnavailable => ErrorKind::Transient,
+            ProviderError::StateRootNotAvailableForHistoricalBlock => ErrorKind::Other,
+        }
+    }
+
+    /// Returns `true` if the operation that caused this error may succeed if retried.
+    pub fn is_retryable(&self) -> bool {
+        self.kind().is_retryable()
+    }
+}
@@ -1,6 +1,12 @@
 //! Support for banning peers.
 use reth_primitives::PeerId;
-use std::{collections::HashMap, net::IpAddr, time::Instant};
+use std::{
+    collections::HashMap,
+    fmt,
+    net::{AddrParseError, IpAddr},
+    str::FromStr,
+    time::Instant,
+};
 
 /// Determines whether or not the IP is globally routable.
 /// Should be replaced with [`IpAddr::is_global`](std::net::IpAddr::is_global) once it is stable.
@@ -15,12 +21,135 @@ pub fn is_global(ip: &IpAddr) -> bool {
     }
 }
 
+/// A range of IP addresses in CIDR notation, e.g. `192.168.0.0/16`.
+///
+/// A single IP address is a subnet with the full prefix length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IpSubnet {
+    /// The first address of the subnet.
+    addr: IpAddr,
+    /// Number of leading bits of the address that are fixed.
+    prefix_len: u8,
+}
+
+impl IpSubnet {
+    /// Creates the subnet of the given address and prefix length.
+    ///
+    /// The host bits of the address are cleared. Returns `None` if the prefix length exceeds the
+    /// length of the address.
+    pub fn new(addr: IpAddr, prefix_len: u8) -> Option<Self> {
+        let addr = match addr {
+            IpAddr::V4(ip) => {
+                let host_bits = 32u32.checked_sub(prefix_len as u32)?;
+                let mask = u32::MAX.checked_shl(host_bits).unwrap_or(0);
+                IpAddr::V4((u32::from(ip) & mask).into())
+            }
+            IpAddr::V6(ip) => {
+                let host_bits = 128u32.checked_sub(prefix_len as u32)?;
+                let mask = u128::MAX.checked_shl(host_bits).unwrap_or(0);
+                IpAddr::V6((u128::from(ip) & mask).into())
+            }
+        };
+        Some(Self { addr, prefix_len })
+    }
+
+    /// The first address of the subnet.
+    pub fn addr(&self) -> IpAddr {
+        self.addr
+    }
+
+    /// Number of leading bits of the address that are fixed.
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    /// Returns true if the subnet is a single address.
+    pub fn is_single_addr(&self) -> bool {
+        match self.addr {
+            IpAddr::V4(_) => self.prefix_len == 32,
+            IpAddr::V6(_) => self.prefix_len == 128,
+        }
+    }
+
+    /// Returns true if the address is part of the subnet.
+    ///
+    /// IPv4-mapped IPv6 addresses are treated as IPv4 addresses.
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        let ip = match ip {
+            IpAddr::V6(ip) => ip.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(IpAddr::V6(*ip)),
+            ip => *ip,
+        };
+        IpSubnet::new(ip, self.prefix_len).map_or(false, |subnet| subnet == *self)
+    }
+}
+
+impl From<IpAddr> for IpSubnet {
+    fn from(addr: IpAddr) -> Self {
+        let prefix_len = if addr.is_ipv4() { 32 } else { 128 };
+        Self { addr, prefix_len }
+    }
+}
+
+impl fmt::Display for IpSubnet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_single_addr() {
+            write!(f, "{}", self.addr)
+        } else {
+            write!(f, "{}/{}", self.addr, self.prefix_len)
+        }
+    }
+}
+
+impl FromStr for IpSubnet {
+    type Err = IpSubnetParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((addr, prefix_len)) = s.split_once('/') else {
+            return Ok(IpAddr::from_str(s)?.into())
+        };
+        let addr = IpAddr::from_str(addr)?;
+        prefix_len
+            .parse()
+            .ok()
+            .and_then(|prefix_len| IpSubnet::new(addr, prefix_len))
+            .ok_or_else(|| IpSubnetParseError::InvalidPrefixLen(prefix_len.to_string()))
+    }
+}
+
+/// Error returned when parsing an [`IpSubnet`] fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IpSubnetParseError {
+    /// The address is invalid.
+    InvalidAddr(AddrParseError),
+    /// The prefix length is not a number or exceeds the length of the address.
+    InvalidPrefixLen(String),
+}
+
+impl From<AddrParseError> for IpSubnetParseError {
+    fn from(err: AddrParseError) -> Self {
+        IpSubnetParseError::InvalidAddr(err)
+    }
+}
+
+impl fmt::Display for IpSubnetParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IpSubnetParseError::InvalidAddr(err) => write!(f, "{err}"),
+            IpSubnetParseError::InvalidPrefixLen(len) => write!(f, "invalid prefix length {len}"),
+        }
+    }
+}
+
+impl std::error::Error for IpSubnetParseError {}
+
 /// Stores peers that should be taken out of circulation either indefinitely or until a certain
 /// timestamp
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct BanList {
     /// A set of IPs whose packets get dropped instantly.
     banned_ips: HashMap<IpAddr, Option<Instant>>,
+    /// A set of subnets whose packets get dropped instantly.
+    banned_subnets: HashMap<IpSubnet, Option<Instant>>,
     /// A set of [`PeerId`] whose packets get dropped instantly.
     banned_peers: HashMap<PeerId, Option<Instant>>,
 }
@@ -42,7 +171,7 @@ impl BanList {
         banned_peers: HashMap<PeerId, Option<Instant>>,
         banned_ips: HashMap<IpAddr, Option<Instant>>,
     ) -> Self {
-        Self { banned_ips, banned_peers }
+        Self { banned_ips, banned_subnets: HashMap::new(), banned_peers }
     }
 
     /// Removes all peers that are no longer banned.
@@ -75,10 +204,26 @@ impl BanList {
         evicted
     }
 
+    /// Removes all subnets that are no longer banned.
+    pub fn evict_subnets(&mut self, now: Instant) -> Vec<IpSubnet> {
+        let mut evicted = Vec::new();
+        self.banned_subnets.retain(|subnet, until| {
+            if let Some(until) = until {
+                if now > *until {
+                    evicted.push(*subnet);
+                    return false
+                }
+            }
+            true
+        });
+        evicted
+    }
+
     /// Removes all entries that should no longer be banned.
     ///
-    /// Returns the evicted entries.
+    /// Returns the evicted ip addresses and peers.
     pub fn evict(&mut self, now: Instant) -> (Vec<IpAddr>, Vec<PeerId>) {
+        self.evict_subnets(now);
         let ips = self.evict_ips(now);
         let peers = self.evict_peers(now);
         (ips, peers)
@@ -90,10 +235,12 @@ impl BanList {
         self.is_banned_peer(peer_id) || self.is_banned_ip(ip)
     }
 
-    /// checks the ban list to see if it contains the given ip
+    /// checks the ban list to see if it contains the given ip, either directly or as part of a
+    /// banned subnet
     #[inline]
     pub fn is_banned_ip(&self, ip: &IpAddr) -> bool {
-        self.banned_ips.contains_key(ip)
+        self.banned_ips.contains_key(ip) ||
+            self.banned_subnets.keys().any(|subnet| subnet.contains(ip))
     }
 
     /// checks the ban list to see if it contains the given ip
@@ -107,6 +254,16 @@ impl BanList {
         self.banned_ips.remove(ip);
     }
 
+    /// Unbans the subnet.
+    ///
+    /// If the subnet is a single address, the address is unbanned as well.
+    pub fn unban_subnet(&mut self, subnet: &IpSubnet) {
+        self.banned_subnets.remove(subnet);
+        if subnet.is_single_addr() {
+            self.banned_ips.remove(&subnet.addr());
+        }
+    }
+
     /// Unbans the ip address
     pub fn unban_peer(&mut self, peer_id: &PeerId) {
         self.banned_peers.remove(peer_id);
@@ -149,6 +306,20 @@ impl BanList {
             self.banned_ips.insert(ip, until);
         }
     }
+
+    /// Bans the subnet indefinitely.
+    ///
+    /// Unlike [`Self::ban_ip`], this also bans non-global IPs.
+    pub fn ban_subnet(&mut self, subnet: IpSubnet) {
+        self.ban_subnet_with(subnet, None);
+    }
+
+    /// Bans the subnet indefinitely or until the given timeout.
+    ///
+    /// Unlike [`Self::ban_ip_with`], this also bans non-global IPs.
+    pub fn ban_subnet_with(&mut self, subnet: IpSubnet, until: Option<Instant>) {
+        self.banned_subnets.insert(subnet, until);
+    }
 }
 
 #[cfg(test)]
@@ -175,6 +346,53 @@ mod tests {
         assert!(!banlist.is_banned_ip(&ip));
     }
 
+    #[test]
+    fn parse_subnet() {
+        let subnet: IpSubnet = "1.2.3.4/16".parse().unwrap();
+        assert_eq!(subnet.addr(), IpAddr::from([1, 2, 0, 0]));
+        assert_eq!(subnet.to_string(), "1.2.0.0/16");
+
+        let subnet: IpSubnet = "1.2.3.4".parse().unwrap();
+        assert!(subnet.is_single_addr());
+        assert_eq!(subnet.to_string(), "1.2.3.4");
+
+        let subnet: IpSubnet = "2001:db8::1/32".parse().unwrap();
+        assert_eq!(subnet.to_string(), "2001:db8::/32");
+
+        assert!("1.2.3.4/33".parse::<IpSubnet>().is_err());
+        assert!("1.2.3.4/x".parse::<IpSubnet>().is_err());
+        assert!("1.2.3/24".parse::<IpSubnet>().is_err());
+    }
+
+    #[test]
+    fn subnet_contains() {
+        let subnet: IpSubnet = "1.2.0.0/16".parse().unwrap();
+        assert!(subnet.contains(&IpAddr::from([1, 2, 255, 1])));
+        assert!(!subnet.contains(&IpAddr::from([1, 3, 0, 1])));
+        assert!(subnet.contains(&"::ffff:1.2.3.4".parse().unwrap()));
+        assert!(!subnet.contains(&"2001:db8::1".parse().unwrap()));
+
+        let all: IpSubnet = "0.0.0.0/0".parse().unwrap();
+        assert!(all.contains(&IpAddr::from([8, 8, 8, 8])));
+    }
+
+    #[test]
+    fn can_ban_unban_subnet() {
+        let subnet: IpSubnet = "10.0.0.0/8".parse().unwrap();
+        let ip = IpAddr::from([10, 1, 2, 3]);
+        let mut banlist = BanList::default();
+        banlist.ban_subnet(subnet);
+        assert!(banlist.is_banned_ip(&ip));
+        assert!(!banlist.is_banned_ip(&IpAddr::from([11, 1, 2, 3])));
+        banlist.unban_subnet(&subnet);
+        assert!(!banlist.is_banned_ip(&ip));
+
+        let ip = IpAddr::from([1, 1, 1, 1]);
+        banlist.ban_ip(ip);
+        banlist.unban_subnet(&ip.into());
+        assert!(!banlist.is_banned_ip(&ip));
+    }
+
     #[test]
     fn cannot_ban_non_global() {
         let mut ip = IpAddr::from([0, 0, 0, 0]);
@@ -94,19 +94,21 @@ pub struct MeteredStream<S> {
     inner: S,
     /// The [`BandwidthMeter`] struct this uses to meter bandwidth
     meter: BandwidthMeter,
+    /// Meters the bandwidth of this stream only, even if `meter` is shared with other streams
+    stream_meter: BandwidthMeter,
 }
 
 impl<S> MeteredStream<S> {
     /// Creates a new [`MeteredStream`] wrapping around the provided stream,
     /// along with a new [`BandwidthMeter`]
     pub fn new(inner: S) -> Self {
-        Self { inner, meter: BandwidthMeter::default() }
+        Self::new_with_meter(inner, BandwidthMeter::default())
     }
 
     /// Creates a new [`MeteredStream`] wrapping around the provided stream,
     /// attaching the provided [`BandwidthMeter`]
     pub fn new_with_meter(inner: S, meter: BandwidthMeter) -> Self {
-        Self { inner, meter }
+        Self { inner, meter, stream_meter: BandwidthMeter::default() }
     }
 
     /// Provides a reference to the [`BandwidthMeter`] attached to this [`MeteredStream`]
@@ -114,6 +116,11 @@ impl<S> MeteredStream<S> {
         &self.meter
     }
 
+    /// Provides a reference to the [`BandwidthMeter`] that only meters this [`MeteredStream`]
+    pub fn stream_meter(&self) -> &BandwidthMeter {
+        &self.stream_meter
+    }
+
     /// Returns the wrapped stream
     pub fn inner(&self) -> &S {
         &self.inner
@@ -132,10 +139,9 @@ impl<Stream: AsyncRead> AsyncRead for MeteredStream<Stream> {
             ready!(this.inner.poll_read(cx, buf))?;
             buf.filled().len() - init_num_bytes
         };
-        this.meter
-            .inner
-            .inbound
-            .fetch_add(u64::try_from(num_bytes).unwrap_or(u64::max_value()), Ordering::Relaxed);
+        let num_bytes = u64::try_from(num_bytes).unwrap_or(u64::max_value());
+        this.meter.inner.inbound.fetch_add(num_bytes, Ordering::Relaxed);
+        this.stream_meter.inner.inbound.fetch_add(num_bytes, Ordering::Relaxed);
         Poll::Ready(Ok(()))
     }
 }
@@ -148,10 +154,9 @@ impl<Stream: AsyncWrite> AsyncWrite for MeteredStream<Stream> {
     ) -> Poll<io::Result<usize>> {
         let this = self.project();
         let num_bytes = ready!(this.inner.poll_write(cx, buf))?;
-        this.meter
-            .inner
-            .outbound
-            .fetch_add(u64::try_from(num_bytes).unwrap_or(u64::max_value()), Ordering::Relaxed);
+        let metered = u64::try_from(num_bytes).unwrap_or(u64::max_value());
+        this.meter.inner.outbound.fetch_add(metered, Ordering::Relaxed);
+        this.stream_meter.inner.outbound.fetch_add(metered, Ordering::Relaxed);
         Poll::Ready(Ok(num_bytes))
     }
 
@@ -274,5 +279,9 @@ mod tests {
 
         assert_bandwidth_counts(&shared_client_bandwidth_meter, 8, 8);
         assert_bandwidth_counts(&shared_server_bandwidth_meter, 8, 8);
+
+        // the stream meters only count the traffic of their own stream
+        assert_bandwidth_counts(metered_client_1.stream_meter(), 4, 4);
+        assert_bandwidth_counts(metered_server_2.stream_meter(), 4, 4);
     }
 }
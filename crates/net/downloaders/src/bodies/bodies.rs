@@ -1,4 +1,4 @@
-use super::queue::BodiesRequestQueue;
+use super::{queue::BodiesRequestQueue, spill::SpilledResponses};
 use crate::{bodies::task::TaskDownloader, metrics::BodyDownloaderMetrics};
 use futures::Stream;
 use futures_util::StreamExt;
@@ -21,11 +21,12 @@ use std::{
     collections::BinaryHeap,
     mem,
     ops::RangeInclusive,
+    path::PathBuf,
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
 };
-use tracing::info;
+use tracing::{info, warn};
 
 /// The scope for headers downloader metrics.
 pub const BODIES_DOWNLOADER_SCOPE: &str = "downloaders.bodies";
@@ -61,6 +62,8 @@ pub struct BodiesDownloader<B: BodiesClient, DB> {
     in_progress_queue: BodiesRequestQueue<B>,
     /// Buffered responses
     buffered_responses: BinaryHeap<OrderedBodiesResponse>,
+    /// Responses that were spilled to disk because the buffer was full, if enabled.
+    spilled_responses: Option<SpilledResponses>,
     /// Queued body responses that can be returned for insertion into the database.
     queued_bodies: Vec<BlockResponse>,
     /// The bodies downloader metrics.
@@ -175,6 +178,13 @@ where
 
     /// Returns true if the size of buffered blocks is lower than the configured maximum
     fn has_buffer_capacity(&self) -> bool {
+        self.has_memory_buffer_capacity() ||
+            self.spilled_responses.as_ref().map_or(false, SpilledResponses::has_capacity)
+    }
+
+    /// Returns true if the size of the blocks buffered in memory is lower than the configured
+    /// maximum
+    fn has_memory_buffer_capacity(&self) -> bool {
         self.buffered_blocks_size_bytes < self.max_buffered_blocks_size_bytes
     }
 
@@ -191,6 +201,7 @@ where
         nothing_to_request &&
             self.in_progress_queue.is_empty() &&
             self.buffered_responses.is_empty() &&
+            self.spilled_responses.as_ref().map_or(true, SpilledResponses::is_empty) &&
             self.queued_bodies.is_empty()
     }
 
@@ -204,12 +215,17 @@ where
         self.queued_bodies = Vec::new();
         self.buffered_responses = BinaryHeap::new();
         self.buffered_blocks_size_bytes = 0;
+        if let Some(spilled) = self.spilled_responses.as_mut() {
+            spilled.clear();
+        }
 
         // reset metrics
         self.metrics.in_flight_requests.set(0.);
         self.metrics.buffered_responses.set(0.);
         self.metrics.buffered_blocks.set(0.);
         self.metrics.buffered_blocks_size_bytes.set(0.);
+        self.metrics.spilled_responses.set(0.);
+        self.metrics.spilled_blocks_size_bytes.set(0.);
         self.metrics.queued_blocks.set(0.);
     }
 
@@ -231,7 +247,29 @@ where
     }
 
     /// Adds a new response to the internal buffer
+    ///
+    /// If the buffer is full and spilling is enabled, the response is written to disk instead,
+    /// unless it can be queued right away.
     fn buffer_bodies_response(&mut self, response: Vec<BlockResponse>) {
+        if !self.has_memory_buffer_capacity() &&
+            response
+                .first()
+                .map_or(false, |b| b.block_number() > self.next_expected_block_number())
+        {
+            if let Some(spilled) = self.spilled_responses.as_mut().filter(|s| s.has_capacity()) {
+                match spilled.push(&response) {
+                    Ok(()) => {
+                        self.metrics.spilled_responses.set(spilled.len() as f64);
+                        self.metrics.spilled_blocks_size_bytes.set(spilled.size_bytes() as f64);
+                        return
+                    }
+                    Err(error) => {
+                        warn!(target: "downloaders::bodies", ?error, "Failed to spill bodies response, buffering in memory");
+                    }
+                }
+            }
+        }
+
         // take into account capacity
         let size = response.iter().map(BlockResponse::size).sum::<usize>() +
             response.capacity() * mem::size_of::<BlockResponse>();
@@ -269,7 +307,39 @@ where
                 self.pop_buffered_response();
             }
         }
-        None
+        self.try_next_spilled()
+    }
+
+    /// Returns a spilled response if it's first block number matches the next expected.
+    fn try_next_spilled(&mut self) -> Option<Vec<BlockResponse>> {
+        let expected = self.next_expected_block_number();
+        let spilled = self.spilled_responses.as_mut()?;
+        let next_block_range = spilled.first_block_range()?;
+        if *next_block_range.start() > expected {
+            return None
+        }
+
+        let response = spilled.pop_first();
+        self.metrics.spilled_responses.set(spilled.len() as f64);
+        self.metrics.spilled_blocks_size_bytes.set(spilled.size_bytes() as f64);
+        match response {
+            // Drop spilled response since we passed that range
+            Ok(_) if *next_block_range.end() < expected => None,
+            Ok(response) => Some(
+                response?
+                    .into_iter()
+                    .skip_while(|b| b.block_number() < expected)
+                    .take_while(|b| self.download_range.contains(&b.block_number()))
+                    .collect(),
+            ),
+            Err(error) => {
+                // request the lost blocks again, responses that overlap with the re-requested
+                // range are skipped when they are queued
+                warn!(target: "downloaders::bodies", ?error, range = ?next_block_range, "Failed to read spilled bodies response");
+                self.in_progress_queue.last_requested_block_number = expected.checked_sub(1);
+                None
+            }
+        }
     }
 
     /// Returns the next batch of block bodies that can be returned if we have enough buffered
@@ -513,6 +583,13 @@ pub struct BodiesDownloaderBuilder {
     pub max_buffered_blocks_size_bytes: usize,
     /// The maximum number of requests to send concurrently.
     pub concurrent_requests_range: RangeInclusive<usize>,
+    /// Maximum number of bytes of received bodies to spill to disk once the internal buffer is
+    /// full, `0` disables spilling.
+    pub max_spilled_blocks_size_bytes: usize,
+    /// The directory the received bodies are spilled to.
+    ///
+    /// Defaults to the temporary directory of the OS.
+    pub spill_dir: Option<PathBuf>,
 }
 
 impl Default for BodiesDownloaderBuilder {
@@ -522,6 +599,8 @@ impl Default for BodiesDownloaderBuilder {
             stream_batch_size: 10_000,
             max_buffered_blocks_size_bytes: 2 * 1024 * 1024 * 1024, // ~2GB
             concurrent_requests_range: 5..=100,
+            max_spilled_blocks_size_bytes: 0,
+            spill_dir: None,
         }
     }
 }
@@ -557,6 +636,21 @@ impl BodiesDownloaderBuilder {
         self
     }
 
+    /// Set max spilled block bytes on the downloader, `0` disables spilling.
+    pub fn with_max_spilled_blocks_size_bytes(
+        mut self,
+        max_spilled_blocks_size_bytes: usize,
+    ) -> Self {
+        self.max_spilled_blocks_size_bytes = max_spilled_blocks_size_bytes;
+        self
+    }
+
+    /// Set the directory the downloader spills bodies to.
+    pub fn with_spill_dir(mut self, spill_dir: impl Into<PathBuf>) -> Self {
+        self.spill_dir = Some(spill_dir.into());
+        self
+    }
+
     /// Consume self and return the concurrent downloader.
     pub fn build<B, DB>(
         self,
@@ -573,9 +667,15 @@ impl BodiesDownloaderBuilder {
             stream_batch_size,
             concurrent_requests_range,
             max_buffered_blocks_size_bytes,
+            max_spilled_blocks_size_bytes,
+            spill_dir,
         } = self;
         let metrics = BodyDownloaderMetrics::default();
         let in_progress_queue = BodiesRequestQueue::new(metrics.clone());
+        let spilled_responses = (max_spilled_blocks_size_bytes > 0).then(|| {
+            let spill_dir = spill_dir.unwrap_or_else(std::env::temp_dir);
+            SpilledResponses::new(&spill_dir, max_spilled_blocks_size_bytes)
+        });
         BodiesDownloader {
             client: Arc::new(client),
             consensus,
@@ -589,6 +689,7 @@ impl BodiesDownloaderBuilder {
             download_range: RangeInclusive::new(1, 0),
             latest_queued_block_number: None,
             buffered_responses: Default::default(),
+            spilled_responses,
             queued_bodies: Default::default(),
             buffered_blocks_size_bytes: 0,
         }
@@ -702,6 +803,36 @@ mod tests {
         }
     }
 
+    // Check that bodies are returned in correct order if the responses are spilled to disk.
+    #[tokio::test]
+    async fn streams_bodies_in_order_with_spilled_responses() {
+        // Generate some random blocks
+        let db = create_test_rw_db();
+        let (headers, mut bodies) = generate_bodies(0..=99);
+
+        insert_headers(&db, &headers);
+
+        let spill_dir = tempfile::tempdir().unwrap();
+        let client = Arc::new(
+            TestBodiesClient::default().with_bodies(bodies.clone()).with_should_delay(true),
+        );
+        let mut downloader = BodiesDownloaderBuilder::default()
+            .with_request_limit(10)
+            .with_stream_batch_size(100)
+            .with_max_buffered_blocks_size_bytes(1)
+            .with_max_spilled_blocks_size_bytes(usize::MAX)
+            .with_spill_dir(spill_dir.path())
+            .build(client.clone(), Arc::new(TestConsensus::default()), db);
+        downloader.set_download_range(0..=99).expect("failed to set download range");
+
+        assert_matches!(
+            downloader.next().await,
+            Some(Ok(res)) => assert_eq!(res, zip_blocks(headers.iter(), &mut bodies))
+        );
+        assert!(downloader.spilled_responses.as_ref().unwrap().is_empty());
+        assert_eq!(client.times_requested(), 10);
+    }
+
     // Check that the downloader picks up the new range and downloads bodies after previous range
     // was completed.
     #[tokio::test]
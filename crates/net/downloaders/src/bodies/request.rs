@@ -3,7 +3,9 @@ use futures::{Future, FutureExt};
 use reth_interfaces::{
     consensus::{Consensus as ConsensusTrait, Consensus},
     p2p::{
-        bodies::{client::BodiesClient, response::BlockResponse},
+        bodies::{
+            client::BodiesClient, response::BlockResponse, validation::ensure_valid_body_response,
+        },
         error::{DownloadError, DownloadResult},
         priority::Priority,
     },
@@ -23,8 +25,9 @@ use std::{
 /// If the response arrived with insufficient number of bodies, the future
 /// will issue another request until all bodies are collected.
 ///
-/// It then proceeds to verify the downloaded bodies. In case of an validation error,
-/// the future will start over.
+/// It then proceeds to verify the downloaded bodies. Every body is checked against the roots of its
+/// header as soon as it is received, before it is validated by the consensus engine. In case of an
+/// validation error, the peer that served the body is penalized and the future will start over.
 ///
 /// The future will filter out any empty headers (see [reth_primitives::Header::is_empty]) from the
 /// request. If [BodiesRequestFuture] was initialized with all empty headers, no request will be
@@ -140,7 +143,10 @@ where
         }
 
         // Buffer block responses
-        self.try_buffer_blocks(bodies)?;
+        if let Err(error) = self.try_buffer_blocks(bodies) {
+            tracing::debug!(target: "downloaders::bodies", ?peer_id, %error, "Received invalid body");
+            return Err(error)
+        }
 
         // Submit next request if any
         if let Some(req) = self.next_request() {
@@ -179,6 +185,13 @@ where
                 // increment full block body metric
                 total_size += next_body.size();
 
+                // Ensure the body matches the header, regardless of the consensus engine in use
+                if let Err(error) = ensure_valid_body_response(&next_header, &next_body) {
+                    let hash = next_header.hash();
+                    self.pending_headers.push_front(next_header);
+                    return Err(DownloadError::BodyValidation { hash, error })
+                }
+
                 let block = SealedBlock::new(next_header, next_body);
 
                 if let Err(error) = self.consensus.validate_block(&block) {
@@ -250,9 +263,15 @@ mod tests {
         bodies::test_utils::zip_blocks,
         test_utils::{generate_bodies, TestBodiesClient},
     };
+    use assert_matches::assert_matches;
     use reth_interfaces::{
+        consensus::ConsensusError,
         p2p::bodies::response::BlockResponse,
-        test_utils::{generators, generators::random_header_range, TestConsensus},
+        test_utils::{
+            generators,
+            generators::{random_header_range, random_signed_tx},
+            TestConsensus,
+        },
     };
     use reth_primitives::H256;
     use std::sync::Arc;
@@ -302,4 +321,32 @@ mod tests {
             (headers.into_iter().filter(|h| !h.is_empty()).count() as u64 + 1) / 2
         );
     }
+
+    /// Check that bodies that don't match their header are rejected before consensus validation.
+    #[tokio::test]
+    async fn request_rejects_body_with_invalid_root() {
+        let mut rng = generators::rng();
+        let (headers, bodies) = generate_bodies(0..=19);
+        let header = headers.iter().find(|h| !h.is_empty()).cloned().unwrap();
+
+        let mut body = bodies[&header.hash()].clone();
+        body.transactions.push(random_signed_tx(&mut rng));
+
+        let mut fut = BodiesRequestFuture::new(
+            Arc::new(TestBodiesClient::default()),
+            Arc::new(TestConsensus::default()),
+            BodyDownloaderMetrics::default(),
+        )
+        .with_headers(vec![header.clone()]);
+
+        assert_matches!(
+            fut.try_buffer_blocks(vec![body]),
+            Err(DownloadError::BodyValidation {
+                hash,
+                error: ConsensusError::BodyTransactionRootDiff { .. }
+            }) if hash == header.hash()
+        );
+        assert!(fut.buffer.is_empty());
+        assert_eq!(fut.pending_headers.front(), Some(&header));
+    }
 }
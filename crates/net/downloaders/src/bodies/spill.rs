@@ -0,0 +1,206 @@
+use reth_interfaces::p2p::bodies::response::BlockResponse;
+use reth_primitives::{BlockNumber, SealedBlock, SealedHeader};
+use reth_rlp::{Decodable, DecodeError, Encodable};
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
+use tracing::warn;
+
+/// Tag of an encoded [BlockResponse::Empty].
+const EMPTY_RESPONSE_TAG: u8 = 0;
+
+/// Tag of an encoded [BlockResponse::Full].
+const FULL_RESPONSE_TAG: u8 = 1;
+
+/// An on-disk queue of body responses that were received while the in-memory buffer of the
+/// [BodiesDownloader](super::bodies::BodiesDownloader) was full.
+///
+/// Every response is written to its own file in the spill directory and read back once the
+/// downloader reaches its block range, so requests can still be sent to peers while the
+/// responses of a slow peer hold back the queue. The files are removed when they are read back,
+/// when the queue is cleared and when it is dropped.
+#[derive(Debug)]
+pub(crate) struct SpilledResponses {
+    /// The directory of the spilled responses, created on the first spill.
+    dir: PathBuf,
+    /// The maximum number of bytes of spilled responses.
+    max_size_bytes: usize,
+    /// The number of bytes of the spilled responses on disk.
+    size_bytes: usize,
+    /// The spilled responses by the number of their first block.
+    responses: BTreeMap<BlockNumber, SpilledResponse>,
+}
+
+#[derive(Debug)]
+struct SpilledResponse {
+    /// The number of the last block of the response.
+    last_block: BlockNumber,
+    /// The number of blocks in the response.
+    len: usize,
+    /// The number of bytes of the response on disk.
+    size: usize,
+}
+
+impl SpilledResponses {
+    /// Creates a new queue that spills up to `max_size_bytes` of responses into a directory in
+    /// `parent_dir`.
+    ///
+    /// The directory is unique to the process, so multiple nodes can share the parent directory.
+    pub(crate) fn new(parent_dir: &Path, max_size_bytes: usize) -> Self {
+        Self {
+            dir: parent_dir.join(format!("reth-bodies-{}", std::process::id())),
+            max_size_bytes,
+            size_bytes: 0,
+            responses: BTreeMap::new(),
+        }
+    }
+
+    /// Returns `true` if more responses can be spilled.
+    pub(crate) fn has_capacity(&self) -> bool {
+        self.size_bytes < self.max_size_bytes
+    }
+
+    /// Returns `true` if no responses are spilled.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.responses.is_empty()
+    }
+
+    /// Returns the number of spilled responses.
+    pub(crate) fn len(&self) -> usize {
+        self.responses.len()
+    }
+
+    /// Returns the number of bytes of the spilled responses on disk.
+    pub(crate) fn size_bytes(&self) -> usize {
+        self.size_bytes
+    }
+
+    /// Returns the block range of the spilled response with the lowest block numbers.
+    pub(crate) fn first_block_range(&self) -> Option<RangeInclusive<BlockNumber>> {
+        self.responses.first_key_value().map(|(first, resp)| *first..=resp.last_block)
+    }
+
+    /// Writes the response to disk.
+    ///
+    /// # Panics
+    /// If the response is empty.
+    pub(crate) fn push(&mut self, response: &[BlockResponse]) -> io::Result<()> {
+        let first_block = response.first().expect("is not empty").block_number();
+        let last_block = response.last().expect("is not empty").block_number();
+
+        let mut buf = Vec::new();
+        for block in response {
+            match block {
+                BlockResponse::Empty(header) => {
+                    buf.push(EMPTY_RESPONSE_TAG);
+                    header.encode(&mut buf);
+                }
+                BlockResponse::Full(block) => {
+                    buf.push(FULL_RESPONSE_TAG);
+                    block.encode(&mut buf);
+                }
+            }
+        }
+
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.path(first_block), &buf)?;
+
+        let spilled = SpilledResponse { last_block, len: response.len(), size: buf.len() };
+        if let Some(replaced) = self.responses.insert(first_block, spilled) {
+            self.size_bytes -= replaced.size;
+        }
+        self.size_bytes += buf.len();
+        Ok(())
+    }
+
+    /// Reads back and removes the spilled response with the lowest block numbers.
+    pub(crate) fn pop_first(&mut self) -> io::Result<Option<Vec<BlockResponse>>> {
+        let Some((first_block, spilled)) = self.responses.pop_first() else { return Ok(None) };
+        self.size_bytes -= spilled.size;
+
+        let path = self.path(first_block);
+        let buf = fs::read(&path);
+        let _ = fs::remove_file(&path);
+        let response = decode_response(&buf?, spilled.len)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        Ok(Some(response))
+    }
+
+    /// Removes all spilled responses.
+    pub(crate) fn clear(&mut self) {
+        self.responses.clear();
+        self.size_bytes = 0;
+        if let Err(err) = fs::remove_dir_all(&self.dir) {
+            if err.kind() != io::ErrorKind::NotFound {
+                warn!(target: "downloaders::bodies", ?err, dir = ?self.dir, "Failed to remove spilled bodies");
+            }
+        }
+    }
+
+    fn path(&self, first_block: BlockNumber) -> PathBuf {
+        self.dir.join(format!("{first_block}.rlp"))
+    }
+}
+
+/// Decodes the blocks of a spilled response.
+fn decode_response(mut buf: &[u8], len: usize) -> Result<Vec<BlockResponse>, DecodeError> {
+    let mut response = Vec::with_capacity(len);
+    while let Some((tag, rest)) = buf.split_first() {
+        buf = rest;
+        let block = match *tag {
+            EMPTY_RESPONSE_TAG => BlockResponse::Empty(SealedHeader::decode(&mut buf)?),
+            FULL_RESPONSE_TAG => BlockResponse::Full(SealedBlock::decode(&mut buf)?),
+            _ => return Err(DecodeError::Custom("unknown block response tag")),
+        };
+        response.push(block);
+    }
+    Ok(response)
+}
+
+impl Drop for SpilledResponses {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_interfaces::test_utils::{generators, generators::random_block_range};
+    use reth_primitives::H256;
+
+    #[test]
+    fn spill_and_read_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut rng = generators::rng();
+        let blocks = random_block_range(&mut rng, 0..=9, H256::zero(), 0..3)
+            .into_iter()
+            .map(|block| {
+                if block.body.is_empty() {
+                    BlockResponse::Empty(block.header)
+                } else {
+                    BlockResponse::Full(block)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut spilled = SpilledResponses::new(dir.path(), 1024 * 1024);
+        spilled.push(&blocks[5..]).unwrap();
+        spilled.push(&blocks[..5]).unwrap();
+        assert_eq!(spilled.len(), 2);
+        assert_eq!(spilled.first_block_range(), Some(0..=4));
+
+        assert_eq!(spilled.pop_first().unwrap().unwrap(), blocks[..5]);
+        assert_eq!(spilled.pop_first().unwrap().unwrap(), blocks[5..]);
+        assert!(spilled.is_empty());
+        assert_eq!(spilled.size_bytes(), 0);
+
+        // the files are removed
+        spilled.push(&blocks).unwrap();
+        drop(spilled);
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+}
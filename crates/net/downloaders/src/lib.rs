@@ -27,5 +27,8 @@ pub mod headers;
 /// Common downloader metrics.
 pub mod metrics;
 
+/// A downloader for historical block receipts that verifies them against the receipts root.
+pub mod receipts;
+
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils;
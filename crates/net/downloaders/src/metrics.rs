@@ -35,6 +35,10 @@ pub struct BodyDownloaderMetrics {
     pub buffered_blocks: Gauge,
     /// Total amount of memory used by the buffered blocks in bytes
     pub buffered_blocks_size_bytes: Gauge,
+    /// The number of responses that were spilled to disk because the internal buffer was full.
+    pub spilled_responses: Gauge,
+    /// Total amount of disk space used by the spilled blocks in bytes
+    pub spilled_blocks_size_bytes: Gauge,
     /// The number blocks that are contiguous and are queued for insertion into the db.
     pub queued_blocks: Gauge,
     /// The number of out-of-order requests sent by the downloader.
@@ -132,3 +136,43 @@ impl HeaderDownloaderMetrics {
         }
     }
 }
+
+/// Common receipts downloader metrics.
+///
+/// These metrics will be initialized with the `downloaders.receipts` scope.
+/// ```
+/// use reth_downloaders::metrics::ReceiptsDownloaderMetrics;
+/// use reth_interfaces::p2p::error::DownloadError;
+///
+/// // Initialize metrics.
+/// let metrics = ReceiptsDownloaderMetrics::default();
+/// // Increment `downloaders.receipts.timeout_errors` counter by 1.
+/// metrics.increment_errors(&DownloadError::Timeout);
+/// ```
+#[derive(Clone, Metrics)]
+#[metrics(scope = "downloaders.receipts")]
+pub struct ReceiptsDownloaderMetrics {
+    /// The number of blocks whose receipts were successfully sent to the poller (stage)
+    pub total_flushed: Counter,
+    /// Number of blocks whose receipts were successfully downloaded and verified
+    pub total_downloaded: Counter,
+    /// The number of requests (can contain more than 1 block) currently in-flight.
+    pub in_flight_requests: Gauge,
+    /// Number of timeout errors while requesting items
+    pub timeout_errors: Counter,
+    /// Number of receipts root mismatches while requesting items
+    pub validation_errors: Counter,
+    /// Number of unexpected errors while requesting items
+    pub unexpected_errors: Counter,
+}
+
+impl ReceiptsDownloaderMetrics {
+    /// Increment errors counter.
+    pub fn increment_errors(&self, error: &DownloadError) {
+        match error {
+            DownloadError::Timeout => self.timeout_errors.increment(1),
+            DownloadError::ReceiptsRootMismatch { .. } => self.validation_errors.increment(1),
+            _error => self.unexpected_errors.increment(1),
+        }
+    }
+}
@@ -0,0 +1,5 @@
+/// A concurrent downloader that verifies receipts against the receipts root of their header.
+#[allow(clippy::module_inception)]
+pub mod receipts;
+
+mod request;
@@ -0,0 +1,298 @@
+use super::request::{has_receipts, ReceiptsRequestFuture};
+use crate::metrics::ReceiptsDownloaderMetrics;
+use futures::{stream::FuturesOrdered, Stream, StreamExt};
+use reth_interfaces::p2p::{
+    error::{DownloadError, DownloadResult},
+    receipts::ReceiptsClient,
+};
+use reth_primitives::{
+    BlockNumHash, BlockNumber, ChainSpec, ForkCondition, Hardfork, ReceiptWithBloom, SealedHeader,
+};
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+/// The verified receipts of a single block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockReceipts {
+    /// The number and hash of the block.
+    pub block: BlockNumHash,
+    /// The receipts of all transactions in the block, in order.
+    pub receipts: Vec<ReceiptWithBloom>,
+}
+
+/// Downloads the receipts of a range of historical blocks from connected peers.
+///
+/// This is an alternative to re-executing the blocks, e.g. for pruned nodes that want to be able to
+/// serve receipts quickly. The receipts of every block are verified against the receipts root of
+/// its header, so the headers passed to the downloader must be trusted, i.e. already validated by
+/// the pipeline.
+///
+/// Receipts of pre-Byzantium blocks commit to an intermediate state root instead of the status
+/// code, which [ReceiptWithBloom] does not represent, so they can not be verified. If any of the
+/// headers before the Byzantium block of the chain has receipts, the downloader yields
+/// [DownloadError::PreByzantiumReceipts] without sending any request.
+///
+/// The downloader sends up to `max_concurrent_requests` requests for `request_limit` blocks with
+/// receipts each, and yields the verified receipts in the order of the headers.
+#[must_use = "Stream does nothing unless polled"]
+pub struct ReceiptsDownloader<C: ReceiptsClient> {
+    /// The receipts client
+    client: Arc<C>,
+    /// The maximum number of blocks with receipts to request at once.
+    request_limit: u64,
+    /// The maximum number of requests to send concurrently.
+    max_concurrent_requests: usize,
+    /// The maximum number of consecutive failed attempts of a single request.
+    max_retries: usize,
+    /// An error to yield before any request is sent.
+    error: Option<DownloadError>,
+    /// The headers of the blocks whose receipts have not been requested yet.
+    pending_headers: VecDeque<SealedHeader>,
+    /// Requests in progress, yielded in the order they were sent.
+    in_progress_queue: FuturesOrdered<ReceiptsRequestFuture<C>>,
+    /// The metrics of the downloader.
+    metrics: ReceiptsDownloaderMetrics,
+}
+
+impl<C> ReceiptsDownloader<C>
+where
+    C: ReceiptsClient + 'static,
+{
+    /// Returns `true` if the receipts of all headers were downloaded.
+    pub fn is_terminated(&self) -> bool {
+        self.pending_headers.is_empty() && self.in_progress_queue.is_empty()
+    }
+
+    /// Takes the headers of the next request, up to `request_limit` headers with receipts and all
+    /// headers without receipts in between.
+    fn next_headers(&mut self) -> Option<Vec<SealedHeader>> {
+        if self.pending_headers.is_empty() {
+            return None
+        }
+
+        let mut headers = Vec::new();
+        let mut non_empty = 0;
+        while let Some(header) = self.pending_headers.front() {
+            if has_receipts(header) {
+                if non_empty == self.request_limit {
+                    break
+                }
+                non_empty += 1;
+            }
+            headers.push(self.pending_headers.pop_front().expect("exists"));
+        }
+        Some(headers)
+    }
+}
+
+impl<C> Stream for ReceiptsDownloader<C>
+where
+    C: ReceiptsClient + 'static,
+{
+    type Item = DownloadResult<Vec<BlockReceipts>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        if let Some(error) = this.error.take() {
+            this.pending_headers.clear();
+            return Poll::Ready(Some(Err(error)))
+        }
+
+        // Fill the in-progress queue up to the limit
+        while this.in_progress_queue.len() < this.max_concurrent_requests {
+            let Some(headers) = this.next_headers() else { break };
+            this.in_progress_queue.push_back(
+                ReceiptsRequestFuture::new(
+                    Arc::clone(&this.client),
+                    this.metrics.clone(),
+                    this.max_retries,
+                )
+                .with_headers(headers),
+            );
+        }
+        this.metrics.in_flight_requests.set(this.in_progress_queue.len() as f64);
+
+        let res = this.in_progress_queue.poll_next_unpin(cx);
+        if let Poll::Ready(Some(Ok(receipts))) = &res {
+            this.metrics.total_flushed.increment(receipts.len() as u64);
+        }
+        res
+    }
+}
+
+/// Builder for [ReceiptsDownloader].
+#[derive(Debug, Clone)]
+pub struct ReceiptsDownloaderBuilder {
+    /// The maximum number of blocks with receipts to request at once.
+    pub request_limit: u64,
+    /// The maximum number of requests to send concurrently.
+    pub max_concurrent_requests: usize,
+    /// The maximum number of consecutive failed attempts of a single request.
+    pub max_retries: usize,
+}
+
+impl Default for ReceiptsDownloaderBuilder {
+    fn default() -> Self {
+        Self { request_limit: 64, max_concurrent_requests: 8, max_retries: 5 }
+    }
+}
+
+impl ReceiptsDownloaderBuilder {
+    /// Set the maximum number of blocks with receipts to request at once.
+    pub fn with_request_limit(mut self, request_limit: u64) -> Self {
+        self.request_limit = request_limit;
+        self
+    }
+
+    /// Set the maximum number of requests to send concurrently.
+    pub fn with_max_concurrent_requests(mut self, max_concurrent_requests: usize) -> Self {
+        self.max_concurrent_requests = max_concurrent_requests;
+        self
+    }
+
+    /// Set the maximum number of consecutive failed attempts of a single request before the
+    /// downloader yields an error.
+    pub fn with_max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Consume self and return the receipts downloader for the given headers of the chain.
+    pub fn build<C>(
+        self,
+        client: C,
+        headers: Vec<SealedHeader>,
+        chain_spec: &ChainSpec,
+    ) -> ReceiptsDownloader<C>
+    where
+        C: ReceiptsClient + 'static,
+    {
+        let Self { request_limit, max_concurrent_requests, max_retries } = self;
+
+        // the first block whose receipts contain the status code
+        let byzantium_block = match chain_spec.fork(Hardfork::Byzantium) {
+            ForkCondition::Block(block) | ForkCondition::TTD { fork_block: Some(block), .. } => {
+                block
+            }
+            _ => BlockNumber::MAX,
+        };
+        let error = headers
+            .iter()
+            .find(|header| header.number < byzantium_block && has_receipts(header))
+            .map(|header| DownloadError::PreByzantiumReceipts { number: header.number });
+
+        ReceiptsDownloader {
+            client: Arc::new(client),
+            request_limit: request_limit.max(1),
+            max_concurrent_requests: max_concurrent_requests.max(1),
+            max_retries,
+            error,
+            pending_headers: headers.into(),
+            in_progress_queue: Default::default(),
+            metrics: ReceiptsDownloaderMetrics::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{generate_receipts, TestReceiptsClient};
+    use assert_matches::assert_matches;
+    use futures::TryStreamExt;
+    use reth_primitives::{ChainSpecBuilder, MAINNET};
+
+    fn byzantium_chain_spec() -> ChainSpec {
+        ChainSpecBuilder::mainnet().byzantium_activated().build()
+    }
+
+    #[tokio::test]
+    async fn download_receipts() {
+        let (headers, receipts) = generate_receipts(0..=99);
+        let expected = headers
+            .iter()
+            .map(|header| BlockReceipts {
+                block: header.num_hash(),
+                receipts: receipts.get(&header.hash()).cloned().unwrap_or_default(),
+            })
+            .collect::<Vec<_>>();
+
+        let client =
+            Arc::new(TestReceiptsClient::default().with_receipts(receipts).with_max_batch_size(7));
+        let downloaded = ReceiptsDownloaderBuilder::default()
+            .with_request_limit(10)
+            .with_max_concurrent_requests(3)
+            .build(Arc::clone(&client), headers, &byzantium_chain_spec())
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap()
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+
+        assert_eq!(downloaded, expected);
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_receipts() {
+        let (headers, receipts) = generate_receipts(0..=19);
+        let expected = headers
+            .iter()
+            .map(|header| BlockReceipts {
+                block: header.num_hash(),
+                receipts: receipts.get(&header.hash()).cloned().unwrap_or_default(),
+            })
+            .collect::<Vec<_>>();
+
+        let client = Arc::new(
+            TestReceiptsClient::default().with_receipts(receipts).with_invalid_responses(2),
+        );
+        let downloaded = ReceiptsDownloaderBuilder::default()
+            .build(Arc::clone(&client), headers, &byzantium_chain_spec())
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap()
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+
+        assert_eq!(downloaded, expected);
+        assert_eq!(client.bad_messages(), 2);
+    }
+
+    #[tokio::test]
+    async fn fails_after_max_retries() {
+        let (headers, receipts) = generate_receipts(0..=19);
+
+        let client = Arc::new(
+            TestReceiptsClient::default().with_receipts(receipts).with_invalid_responses(10),
+        );
+        let res = ReceiptsDownloaderBuilder::default()
+            .with_max_concurrent_requests(1)
+            .with_max_retries(2)
+            .build(Arc::clone(&client), headers, &byzantium_chain_spec())
+            .try_collect::<Vec<_>>()
+            .await;
+
+        assert_matches!(res, Err(DownloadError::ReceiptsRootMismatch { .. }));
+        assert_eq!(client.bad_messages(), 3);
+    }
+
+    #[tokio::test]
+    async fn rejects_pre_byzantium_receipts() {
+        let (headers, receipts) = generate_receipts(0..=19);
+
+        let client = Arc::new(TestReceiptsClient::default().with_receipts(receipts));
+        let res = ReceiptsDownloaderBuilder::default()
+            .build(Arc::clone(&client), headers, &MAINNET)
+            .try_collect::<Vec<_>>()
+            .await;
+
+        assert_matches!(res, Err(DownloadError::PreByzantiumReceipts { number: 1 }));
+    }
+}
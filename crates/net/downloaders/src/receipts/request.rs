@@ -0,0 +1,245 @@
+use super::receipts::BlockReceipts;
+use crate::metrics::ReceiptsDownloaderMetrics;
+use futures::{Future, FutureExt};
+use reth_interfaces::p2p::{
+    error::{DownloadError, DownloadResult},
+    priority::Priority,
+    receipts::ReceiptsClient,
+};
+use reth_primitives::{
+    constants::EMPTY_RECEIPTS, proofs::calculate_receipt_root, PeerId, ReceiptWithBloom,
+    SealedHeader, WithPeerId, H256,
+};
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    sync::Arc,
+    task::{ready, Context, Poll},
+};
+
+/// Receipts request implemented as a [Future].
+///
+/// The future will poll the underlying request until fulfilled.
+/// If the response arrived with receipts for fewer blocks than requested, the future will issue
+/// another request for the remaining blocks until all receipts are collected.
+///
+/// The receipts of every block are checked against the receipts root of its header as soon as they
+/// are received. In case of a mismatch, the peer that served the receipts is penalized and the
+/// remaining blocks are requested again.
+///
+/// Failed requests are retried up to `max_retries` times in a row. Every response that makes
+/// progress resets the counter. Once the retries are exhausted, the future resolves with the last
+/// error.
+///
+/// Headers with an empty receipts root are not requested and are immediately resolved with no
+/// receipts.
+///
+/// NB: This assumes that peers respond with receipts in the order that the blocks were requested.
+pub(crate) struct ReceiptsRequestFuture<C: ReceiptsClient> {
+    client: Arc<C>,
+    metrics: ReceiptsDownloaderMetrics,
+    // Headers to download receipts for. The collection is shrunk as responses are buffered.
+    pending_headers: VecDeque<SealedHeader>,
+    /// Internal buffer for all verified receipts
+    buffer: Vec<BlockReceipts>,
+    fut: Option<C::Output>,
+    /// Tracks for how many blocks we requested receipts in the last request.
+    last_request_len: Option<usize>,
+    /// The maximum number of consecutive failed requests before the future resolves with an
+    /// error.
+    max_retries: usize,
+    /// The number of consecutive failed requests.
+    retries: usize,
+}
+
+impl<C> ReceiptsRequestFuture<C>
+where
+    C: ReceiptsClient + 'static,
+{
+    /// Returns an empty future. Use [ReceiptsRequestFuture::with_headers] to set the request.
+    pub(crate) fn new(
+        client: Arc<C>,
+        metrics: ReceiptsDownloaderMetrics,
+        max_retries: usize,
+    ) -> Self {
+        Self {
+            client,
+            metrics,
+            pending_headers: Default::default(),
+            buffer: Default::default(),
+            fut: None,
+            last_request_len: None,
+            max_retries,
+            retries: 0,
+        }
+    }
+
+    pub(crate) fn with_headers(mut self, headers: Vec<SealedHeader>) -> Self {
+        self.buffer.reserve_exact(headers.len());
+        self.pending_headers = VecDeque::from(headers);
+        // Submit the request only if there are any receipts to download.
+        // Otherwise, the future will immediately be resolved.
+        if let Some(req) = self.next_request() {
+            self.submit_request(req, Priority::Normal);
+        }
+        self
+    }
+
+    /// Handles a failed request. Returns the error if the retries are exhausted, otherwise the
+    /// remaining blocks are requested again.
+    fn on_error(&mut self, error: DownloadError, peer_id: Option<PeerId>) -> DownloadResult<()> {
+        self.metrics.increment_errors(&error);
+        tracing::debug!(target: "downloaders::receipts", ?peer_id, %error, retries = self.retries, "Error requesting receipts");
+        if let Some(peer_id) = peer_id {
+            self.client.report_bad_message(peer_id);
+        }
+
+        if self.retries >= self.max_retries {
+            self.fut = None;
+            return Err(error)
+        }
+        self.retries += 1;
+
+        self.submit_request(
+            self.next_request().expect("existing hashes to resubmit"),
+            Priority::High,
+        );
+        Ok(())
+    }
+
+    /// Retrieve the block hashes for the next request.
+    fn next_request(&self) -> Option<Vec<H256>> {
+        let mut hashes =
+            self.pending_headers.iter().filter(|h| has_receipts(h)).map(|h| h.hash()).peekable();
+        hashes.peek().is_some().then(|| hashes.collect())
+    }
+
+    /// Submit the request with the given priority.
+    fn submit_request(&mut self, req: Vec<H256>, priority: Priority) {
+        tracing::trace!(target: "downloaders::receipts", request_len = req.len(), "Requesting receipts");
+        let client = Arc::clone(&self.client);
+        self.last_request_len = Some(req.len());
+        self.fut = Some(client.get_receipts_with_priority(req, priority));
+    }
+
+    /// Process receipts response.
+    /// Returns an error if the response is invalid.
+    fn on_receipts_response(
+        &mut self,
+        response: WithPeerId<Vec<Vec<ReceiptWithBloom>>>,
+    ) -> DownloadResult<()> {
+        let (peer_id, receipts) = response.split();
+        let request_len = self.last_request_len.unwrap_or_default();
+        let response_len = receipts.len();
+
+        tracing::trace!(target: "downloaders::receipts", request_len, response_len, ?peer_id, "Received receipts");
+
+        if receipts.is_empty() {
+            return Err(DownloadError::EmptyResponse)
+        }
+
+        if response_len > request_len {
+            return Err(DownloadError::TooManyReceipts {
+                expected: request_len,
+                received: response_len,
+            })
+        }
+
+        if let Err(error) = self.try_buffer_receipts(receipts) {
+            tracing::debug!(target: "downloaders::receipts", ?peer_id, %error, "Received invalid receipts");
+            return Err(error)
+        }
+        self.retries = 0;
+
+        // Submit next request if any
+        if let Some(req) = self.next_request() {
+            self.submit_request(req, Priority::High);
+        } else {
+            self.fut = None;
+        }
+
+        Ok(())
+    }
+
+    /// Attempt to buffer the received receipts. Returns an error if the receipts of a block do not
+    /// match its receipts root. The receipts of every block preceding the failed one will be
+    /// buffered.
+    ///
+    /// This method removes headers from the internal collection.
+    /// If the receipts fail verification, then the header will be put back.
+    fn try_buffer_receipts(&mut self, receipts: Vec<Vec<ReceiptWithBloom>>) -> DownloadResult<()> {
+        for block_receipts in receipts {
+            self.buffer_empty_headers();
+            let Some(header) = self.pending_headers.pop_front() else { return Ok(()) };
+
+            let root = calculate_receipt_root(&block_receipts);
+            if root != header.receipts_root {
+                let error = DownloadError::ReceiptsRootMismatch {
+                    hash: header.hash(),
+                    received: root,
+                    expected: header.receipts_root,
+                };
+                self.pending_headers.push_front(header);
+                return Err(error)
+            }
+
+            self.metrics.total_downloaded.increment(1);
+            self.buffer.push(BlockReceipts { block: header.num_hash(), receipts: block_receipts });
+        }
+        Ok(())
+    }
+
+    /// Buffers all headers at the front of the queue that have no receipts.
+    fn buffer_empty_headers(&mut self) {
+        while self.pending_headers.front().map(|h| !has_receipts(h)).unwrap_or_default() {
+            let header = self.pending_headers.pop_front().unwrap();
+            self.buffer.push(BlockReceipts { block: header.num_hash(), receipts: Vec::new() });
+        }
+    }
+}
+
+impl<C> Future for ReceiptsRequestFuture<C>
+where
+    C: ReceiptsClient + 'static,
+{
+    type Output = DownloadResult<Vec<BlockReceipts>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        loop {
+            // Buffer any headers without receipts
+            this.buffer_empty_headers();
+
+            if this.pending_headers.is_empty() {
+                return Poll::Ready(Ok(std::mem::take(&mut this.buffer)))
+            }
+
+            let fut = this.fut.as_mut().expect("pending headers with receipts are requested");
+            match ready!(fut.poll_unpin(cx)) {
+                Ok(response) => {
+                    let peer_id = response.peer_id();
+                    if let Err(error) = this.on_receipts_response(response) {
+                        if let Err(error) = this.on_error(error, Some(peer_id)) {
+                            return Poll::Ready(Err(error))
+                        }
+                    }
+                }
+                Err(error) => {
+                    if error.is_channel_closed() {
+                        return Poll::Ready(Err(error.into()))
+                    }
+
+                    if let Err(error) = this.on_error(error.into(), None) {
+                        return Poll::Ready(Err(error))
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns `true` if the block of the header has any receipts to download.
+pub(super) fn has_receipts(header: &SealedHeader) -> bool {
+    header.receipts_root != EMPTY_RECEIPTS
+}
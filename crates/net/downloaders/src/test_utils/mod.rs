@@ -3,7 +3,10 @@
 use crate::bodies::test_utils::create_raw_bodies;
 use futures::SinkExt;
 use reth_interfaces::test_utils::generators::random_block_range;
-use reth_primitives::{BlockBody, SealedHeader, H256};
+use reth_primitives::{
+    proofs::calculate_receipt_root, BlockBody, Header, Receipt, ReceiptWithBloom, SealedHeader,
+    TxType, H256,
+};
 use std::{collections::HashMap, io::SeekFrom, ops::RangeInclusive};
 use tokio::{
     fs::File,
@@ -14,10 +17,12 @@ use tokio_util::codec::FramedWrite;
 mod bodies_client;
 mod file_client;
 mod file_codec;
+mod receipts_client;
 
 pub use bodies_client::TestBodiesClient;
 pub use file_client::{FileClient, FileClientError};
 pub(crate) use file_codec::BlockFileCodec;
+pub use receipts_client::TestReceiptsClient;
 use reth_interfaces::test_utils::generators;
 
 /// Metrics scope used for testing.
@@ -48,6 +53,40 @@ pub(crate) fn generate_bodies(
     (headers, bodies)
 }
 
+/// Generate a set of headers and the receipts of the blocks, keyed by block hash.
+///
+/// Every third block has no transactions and therefore no receipts.
+pub(crate) fn generate_receipts(
+    range: RangeInclusive<u64>,
+) -> (Vec<SealedHeader>, HashMap<H256, Vec<ReceiptWithBloom>>) {
+    let mut headers = Vec::new();
+    let mut receipts = HashMap::new();
+    for number in range {
+        let block_receipts = (0..number % 3 * 2)
+            .map(|idx| {
+                Receipt {
+                    tx_type: TxType::EIP1559,
+                    success: (number + idx) % 2 == 0,
+                    cumulative_gas_used: 21_000 * (idx + 1),
+                    logs: vec![],
+                }
+                .with_bloom()
+            })
+            .collect::<Vec<_>>();
+        let header = Header {
+            number,
+            receipts_root: calculate_receipt_root(&block_receipts),
+            ..Default::default()
+        }
+        .seal_slow();
+        if !block_receipts.is_empty() {
+            receipts.insert(header.hash(), block_receipts);
+        }
+        headers.push(header);
+    }
+    (headers, receipts)
+}
+
 /// Generate a set of bodies, write them to a temporary file, and return the file along with the
 /// bodies and corresponding block hashes
 pub(crate) async fn generate_bodies_file(
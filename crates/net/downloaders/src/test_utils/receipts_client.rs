@@ -0,0 +1,82 @@
+use reth_interfaces::p2p::{
+    download::DownloadClient,
+    priority::Priority,
+    receipts::{ReceiptsClient, ReceiptsFut},
+};
+use reth_primitives::{PeerId, ReceiptWithBloom, H256};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+/// A [ReceiptsClient] for testing.
+#[derive(Debug, Default)]
+pub struct TestReceiptsClient {
+    receipts: Arc<HashMap<H256, Vec<ReceiptWithBloom>>>,
+    max_batch_size: Option<usize>,
+    /// The number of responses that are served with invalid receipts.
+    invalid_responses: AtomicU64,
+    bad_messages: AtomicU64,
+}
+
+impl TestReceiptsClient {
+    pub(crate) fn with_receipts(mut self, receipts: HashMap<H256, Vec<ReceiptWithBloom>>) -> Self {
+        self.receipts = Arc::new(receipts);
+        self
+    }
+
+    pub(crate) fn with_max_batch_size(mut self, max_batch_size: usize) -> Self {
+        self.max_batch_size = Some(max_batch_size);
+        self
+    }
+
+    pub(crate) fn with_invalid_responses(self, invalid_responses: u64) -> Self {
+        self.invalid_responses.store(invalid_responses, Ordering::Relaxed);
+        self
+    }
+
+    pub(crate) fn bad_messages(&self) -> u64 {
+        self.bad_messages.load(Ordering::Relaxed)
+    }
+}
+
+impl DownloadClient for TestReceiptsClient {
+    fn report_bad_message(&self, _peer_id: PeerId) {
+        self.bad_messages.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn num_connected_peers(&self) -> usize {
+        0
+    }
+}
+
+impl ReceiptsClient for TestReceiptsClient {
+    type Output = ReceiptsFut;
+
+    fn get_receipts_with_priority(&self, hashes: Vec<H256>, _priority: Priority) -> Self::Output {
+        let invalid = self
+            .invalid_responses
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1))
+            .is_ok();
+
+        let mut receipts = hashes
+            .into_iter()
+            .take(self.max_batch_size.unwrap_or(usize::MAX))
+            .map(|hash| {
+                self.receipts
+                    .get(&hash)
+                    .cloned()
+                    .expect("Downloader asked for receipts it should not ask for")
+            })
+            .collect::<Vec<_>>();
+        if invalid {
+            // drop a receipt so the receipts root does not match
+            receipts[0].pop();
+        }
+
+        Box::pin(futures::future::ready(Ok((PeerId::default(), receipts).into())))
+    }
+}
@@ -103,6 +103,26 @@ impl proptest::arbitrary::Arbitrary for Capability {
     type Strategy = BoxedStrategy<Capability>;
 }
 
+/// A subprotocol other than `eth` that is multiplexed over the same connection.
+///
+/// Subprotocols are announced with their capability in the `Hello` message. If the peer shares the
+/// capability, the protocol reserves `messages` message ids after the ids of the shared
+/// capabilities that precede it in alphabetical order.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Protocol {
+    /// The capability the protocol is announced with.
+    pub cap: Capability,
+    /// The number of messages of the protocol.
+    pub messages: u8,
+}
+
+impl Protocol {
+    /// Create a new `Protocol` with the given capability and number of messages.
+    pub fn new(cap: Capability, messages: u8) -> Self {
+        Self { cap, messages }
+    }
+}
+
 /// Represents all capabilities of a node.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Capabilities {
@@ -188,6 +208,9 @@ pub enum SharedCapability {
     /// The `eth` capability.
     Eth { version: EthVersion, offset: u8 },
 
+    /// The capability of a [Protocol] other than `eth`.
+    Other { name: SmolStr, version: u8, offset: u8, messages: u8 },
+
     /// An unknown capability.
     UnknownCapability { name: SmolStr, version: u8, offset: u8 },
 }
@@ -205,6 +228,7 @@ impl SharedCapability {
     pub fn name(&self) -> &str {
         match self {
             SharedCapability::Eth { .. } => "eth",
+            SharedCapability::Other { name, .. } => name,
             SharedCapability::UnknownCapability { name, .. } => name,
         }
     }
@@ -213,6 +237,7 @@ impl SharedCapability {
     pub fn version(&self) -> u8 {
         match self {
             SharedCapability::Eth { version, .. } => *version as u8,
+            SharedCapability::Other { version, .. } => *version,
             SharedCapability::UnknownCapability { version, .. } => *version,
         }
     }
@@ -221,6 +246,7 @@ impl SharedCapability {
     pub fn offset(&self) -> u8 {
         match self {
             SharedCapability::Eth { offset, .. } => *offset,
+            SharedCapability::Other { offset, .. } => *offset,
             SharedCapability::UnknownCapability { offset, .. } => *offset,
         }
     }
@@ -229,6 +255,7 @@ impl SharedCapability {
     pub fn num_messages(&self) -> Result<u8, SharedCapabilityError> {
         match self {
             SharedCapability::Eth { version, .. } => Ok(version.total_messages()),
+            SharedCapability::Other { messages, .. } => Ok(*messages),
             _ => Err(SharedCapabilityError::UnknownCapability),
         }
     }
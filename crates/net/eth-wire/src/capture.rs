@@ -0,0 +1,86 @@
+//! Recording of the raw frames exchanged over an [`EthStream`](crate::EthStream).
+
+use bytes::Bytes;
+use std::{collections::VecDeque, time::SystemTime};
+
+/// The direction of a captured frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameDirection {
+    /// Received from the peer.
+    Inbound,
+    /// Sent to the peer.
+    Outbound,
+}
+
+/// A single captured frame.
+#[derive(Debug, Clone)]
+pub struct CapturedFrame {
+    /// When the frame was captured.
+    pub timestamp: SystemTime,
+    /// Whether the frame was received or sent.
+    pub direction: FrameDirection,
+    /// The uncompressed frame as it is exchanged on the wire: the message id followed by the RLP
+    /// encoded message.
+    ///
+    /// Inbound frames are recorded before they are decoded, so this also includes frames that
+    /// failed to decode.
+    pub payload: Bytes,
+}
+
+/// A ring buffer of the most recent frames exchanged with a peer.
+#[derive(Debug)]
+pub struct FrameRecorder {
+    limit: usize,
+    frames: VecDeque<CapturedFrame>,
+}
+
+impl FrameRecorder {
+    /// Creates a new recorder that keeps the `limit` most recent frames, returns `None` if `limit`
+    /// is zero.
+    pub fn new(limit: usize) -> Option<Self> {
+        (limit > 0).then(|| Self { limit, frames: VecDeque::with_capacity(limit) })
+    }
+
+    /// Records a frame, evicting the oldest one if the buffer is full.
+    pub fn record(&mut self, direction: FrameDirection, payload: Bytes) {
+        if self.frames.len() == self.limit {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(CapturedFrame { timestamp: SystemTime::now(), direction, payload });
+    }
+
+    /// Returns the recorded frames, oldest first.
+    pub fn frames(&self) -> impl Iterator<Item = &CapturedFrame> + '_ {
+        self.frames.iter()
+    }
+
+    /// Returns the number of recorded frames.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns `true` if no frames were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_most_recent_frames() {
+        let mut recorder = FrameRecorder::new(2).unwrap();
+        for id in 0u8..3 {
+            recorder.record(FrameDirection::Inbound, Bytes::from(vec![id]));
+        }
+        let payloads = recorder.frames().map(|frame| frame.payload.to_vec()).collect::<Vec<_>>();
+        assert_eq!(payloads, vec![vec![1], vec![2]]);
+    }
+
+    #[test]
+    fn disabled_without_frames() {
+        assert!(FrameRecorder::new(0).is_none());
+    }
+}
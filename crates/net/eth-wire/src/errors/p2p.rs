@@ -1,4 +1,5 @@
 //! Error handling for [`P2PStream`](crate::P2PStream)
+use smol_str::SmolStr;
 use std::io;
 
 use crate::{
@@ -35,6 +36,10 @@ pub enum P2PStreamError {
     PingBeforeHandshake,
     #[error("too many messages buffered before sending")]
     SendBufferFull,
+    #[error("subprotocol {0} is not shared with the peer")]
+    UnknownSubprotocol(SmolStr),
+    #[error("message id {id} is not reserved by subprotocol {name}")]
+    InvalidSubprotocolMessageId { name: SmolStr, id: usize },
     #[error("disconnected")]
     Disconnected(DisconnectReason),
     #[error("unknown disconnect reason: {0}")]
@@ -1,4 +1,5 @@
 use crate::{
+    capture::{FrameDirection, FrameRecorder},
     errors::{EthHandshakeError, EthStreamError},
     message::{EthBroadcastMessage, ProtocolBroadcastMessage},
     types::{EthMessage, ProtocolMessage, Status},
@@ -168,13 +169,29 @@ pub struct EthStream<S> {
     version: EthVersion,
     #[pin]
     inner: S,
+    /// Records the most recent raw frames, if enabled.
+    capture: Option<FrameRecorder>,
 }
 
 impl<S> EthStream<S> {
     /// Creates a new unauthed [`EthStream`] from a provided stream. You will need
     /// to manually handshake a peer.
     pub fn new(version: EthVersion, inner: S) -> Self {
-        Self { version, inner }
+        Self { version, inner, capture: None }
+    }
+
+    /// Keeps the `frames` most recent raw frames exchanged over this stream, see
+    /// [`FrameRecorder`].
+    ///
+    /// Setting `frames` to zero disables the capture.
+    pub fn with_capture(mut self, frames: usize) -> Self {
+        self.capture = FrameRecorder::new(frames);
+        self
+    }
+
+    /// Returns the recorded frames, if the capture is enabled.
+    pub fn captured_frames(&self) -> Option<&FrameRecorder> {
+        self.capture.as_ref()
     }
 
     /// Returns the eth version.
@@ -211,6 +228,9 @@ where
         let mut bytes = BytesMut::new();
         ProtocolBroadcastMessage::from(item).encode(&mut bytes);
         let bytes = bytes.freeze();
+        if let Some(capture) = &mut self.capture {
+            capture.record(FrameDirection::Outbound, bytes.clone());
+        }
 
         self.inner.start_send_unpin(bytes)?;
 
@@ -234,6 +254,11 @@ where
             None => return Poll::Ready(None),
         };
 
+        if let Some(capture) = this.capture {
+            // record the frame before decoding so that malformed messages are captured as well
+            capture.record(FrameDirection::Inbound, Bytes::copy_from_slice(&bytes));
+        }
+
         if bytes.len() > MAX_MESSAGE_SIZE {
             return Poll::Ready(Some(Err(EthStreamError::MessageTooBig(bytes.len()))))
         }
@@ -285,7 +310,11 @@ where
         ProtocolMessage::from(item).encode(&mut bytes);
         let bytes = bytes.freeze();
 
-        self.project().inner.start_send(bytes)?;
+        let this = self.project();
+        if let Some(capture) = this.capture {
+            capture.record(FrameDirection::Outbound, bytes.clone());
+        }
+        this.inner.start_send(bytes)?;
 
         Ok(())
     }
@@ -19,6 +19,7 @@
 
 pub mod builder;
 pub mod capability;
+mod capture;
 mod disconnect;
 pub mod errors;
 mod ethstream;
@@ -35,6 +36,7 @@ pub use tokio_util::codec::{
 };
 
 pub use crate::{
+    capture::{CapturedFrame, FrameDirection, FrameRecorder},
     disconnect::{CanDisconnect, DisconnectReason},
     ethstream::{EthStream, UnauthedEthStream, MAX_MESSAGE_SIZE},
     hello::HelloMessage,
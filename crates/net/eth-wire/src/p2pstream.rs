@@ -1,6 +1,6 @@
 #![allow(dead_code, unreachable_pub, missing_docs, unused_variables)]
 use crate::{
-    capability::{Capability, SharedCapability},
+    capability::{Capability, Protocol, RawCapabilityMessage, SharedCapability},
     disconnect::CanDisconnect,
     errors::{P2PHandshakeError, P2PStreamError},
     pinger::{Pinger, PingerEvent},
@@ -15,6 +15,7 @@ use reth_primitives::{
     hex,
 };
 use reth_rlp::{Decodable, DecodeError, Encodable, EMPTY_LIST_CODE};
+use smol_str::SmolStr;
 use std::{
     collections::{BTreeSet, HashMap, HashSet, VecDeque},
     io,
@@ -62,18 +63,36 @@ const GRACE_PERIOD: Duration = Duration::from_secs(2);
 /// encoded data.
 const MAX_P2P_CAPACITY: usize = 2;
 
+/// [`MAX_SUBPROTOCOL_MESSAGES`] is the maximum number of received subprotocol messages that are
+/// buffered until they are taken with [`P2PStream::take_subprotocol_message`].
+///
+/// Once the buffer is full, the stream stops reading from the underlying stream until the buffer
+/// is drained.
+const MAX_SUBPROTOCOL_MESSAGES: usize = 32;
+
 /// An un-authenticated [`P2PStream`]. This is consumed and returns a [`P2PStream`] after the
 /// `Hello` handshake is completed.
 #[pin_project]
 pub struct UnauthedP2PStream<S> {
     #[pin]
     inner: S,
+    /// The subprotocols other than `eth` that are announced in the `Hello` message.
+    protocols: Vec<Protocol>,
 }
 
 impl<S> UnauthedP2PStream<S> {
     /// Create a new `UnauthedP2PStream` from a type `S` which implements `Stream` and `Sink`.
     pub fn new(inner: S) -> Self {
-        Self { inner }
+        Self { inner, protocols: Vec::new() }
+    }
+
+    /// Sets the subprotocols other than `eth` that are multiplexed over the stream if the peer
+    /// shares them.
+    ///
+    /// The capabilities of the protocols must be included in the `Hello` message of the handshake.
+    pub fn with_protocols(mut self, protocols: Vec<Protocol>) -> Self {
+        self.protocols = protocols;
+        self
     }
 
     /// Returns a reference to the inner stream.
@@ -150,20 +169,24 @@ where
             })
         }
 
-        // determine shared capabilities (currently returns only one capability)
-        let capability_res =
-            set_capability_offsets(hello.capabilities, their_hello.capabilities.clone());
+        // determine shared capabilities
+        let capability_res = set_capability_offsets(
+            hello.capabilities,
+            their_hello.capabilities.clone(),
+            &self.protocols,
+        );
 
-        let shared_capability = match capability_res {
+        let (shared_capability, subprotocols) = match capability_res {
             Err(err) => {
                 // we don't share any capabilities, send a disconnect message
                 self.send_disconnect(DisconnectReason::UselessPeer).await?;
                 Err(err)
             }
-            Ok(cap) => Ok(cap),
+            Ok(caps) => Ok(caps),
         }?;
 
-        let stream = P2PStream::new(self.inner, shared_capability);
+        let mut stream = P2PStream::new(self.inner, shared_capability);
+        stream.subprotocols = subprotocols;
 
         Ok((stream, their_hello))
     }
@@ -218,6 +241,16 @@ pub struct P2PStream<S> {
     /// The supported capability for this stream.
     shared_capability: SharedCapability,
 
+    /// The shared subprotocols other than `eth`.
+    subprotocols: Vec<SharedCapability>,
+
+    /// Received messages of the subprotocols, with the name of their subprotocol.
+    subprotocol_messages: VecDeque<(SmolStr, RawCapabilityMessage)>,
+
+    /// Maximum number of subprotocol messages that we buffer here before the [Stream] impl stops
+    /// reading from the underlying stream.
+    subprotocol_message_buffer_capacity: usize,
+
     /// Outgoing messages buffered for sending to the underlying stream.
     outgoing_messages: VecDeque<Bytes>,
 
@@ -241,6 +274,9 @@ impl<S> P2PStream<S> {
             decoder: snap::raw::Decoder::new(),
             pinger: Pinger::new(PING_INTERVAL, PING_TIMEOUT),
             shared_capability: capability,
+            subprotocols: Vec::new(),
+            subprotocol_messages: VecDeque::new(),
+            subprotocol_message_buffer_capacity: MAX_SUBPROTOCOL_MESSAGES,
             outgoing_messages: VecDeque::new(),
             outgoing_message_buffer_capacity: MAX_P2P_CAPACITY,
             disconnecting: false,
@@ -261,11 +297,76 @@ impl<S> P2PStream<S> {
         self.outgoing_message_buffer_capacity = capacity;
     }
 
+    /// Sets a custom capacity of the buffer for received subprotocol messages.
+    pub fn set_subprotocol_message_buffer_capacity(&mut self, capacity: usize) {
+        self.subprotocol_message_buffer_capacity = capacity;
+    }
+
     /// Returns the shared capability for this stream.
     pub fn shared_capability(&self) -> &SharedCapability {
         &self.shared_capability
     }
 
+    /// Returns the shared subprotocols other than `eth`.
+    pub fn shared_subprotocols(&self) -> &[SharedCapability] {
+        &self.subprotocols
+    }
+
+    /// Takes the next received message of a subprotocol other than `eth`, with the name of its
+    /// subprotocol.
+    ///
+    /// These messages are buffered while the stream is polled for `eth` messages. The id of the
+    /// message is relative to the message id offset of its subprotocol.
+    ///
+    /// If the buffer is full, the stream yields [Poll::Pending] without reading further messages,
+    /// so the buffered messages must be taken after every poll.
+    pub fn take_subprotocol_message(&mut self) -> Option<(SmolStr, RawCapabilityMessage)> {
+        self.subprotocol_messages.pop_front()
+    }
+
+    /// Queues a message of a subprotocol other than `eth`, like [Sink::start_send].
+    ///
+    /// The id of the message is relative to the message id offset of the subprotocol.
+    pub fn start_send_subprotocol(
+        &mut self,
+        name: &str,
+        msg: RawCapabilityMessage,
+    ) -> Result<(), P2PStreamError> {
+        // ensure we have free capacity
+        if !self.has_outgoing_capacity() {
+            return Err(P2PStreamError::SendBufferFull)
+        }
+
+        let cap = self
+            .subprotocols
+            .iter()
+            .find(|cap| cap.name() == name)
+            .ok_or_else(|| P2PStreamError::UnknownSubprotocol(name.into()))?;
+        if msg.id >= cap.num_messages()? as usize {
+            return Err(P2PStreamError::InvalidSubprotocolMessageId {
+                name: name.into(),
+                id: msg.id,
+            })
+        }
+        let id = cap.offset() + msg.id as u8;
+
+        let mut compressed = BytesMut::zeroed(1 + snap::raw::max_compress_len(msg.payload.len()));
+        let compressed_size =
+            self.encoder.compress(&msg.payload, &mut compressed[1..]).map_err(|err| {
+                tracing::debug!(
+                    ?err,
+                    msg=%hex::encode(&msg.payload),
+                    "error compressing subprotocol message"
+                );
+                err
+            })?;
+        compressed.truncate(compressed_size + 1);
+        compressed[0] = id;
+        self.outgoing_messages.push_back(compressed.freeze());
+
+        Ok(())
+    }
+
     /// Returns `true` if the connection is about to disconnect.
     pub fn is_disconnecting(&self) -> bool {
         self.disconnecting
@@ -364,7 +465,14 @@ where
 
         // we should loop here to ensure we don't return Poll::Pending if we have a message to
         // return behind any pings we need to respond to
-        while let Poll::Ready(res) = this.inner.poll_next_unpin(cx) {
+        loop {
+            if this.subprotocol_messages.len() >= this.subprotocol_message_buffer_capacity {
+                // the subprotocol messages must be taken first, stop reading until then
+                cx.waker().wake_by_ref();
+                return Poll::Pending
+            }
+
+            let Poll::Ready(res) = this.inner.poll_next_unpin(cx) else { break };
             let bytes = match res {
                 Some(Ok(bytes)) => bytes,
                 Some(Err(err)) => return Poll::Ready(Some(Err(err.into()))),
@@ -453,6 +561,21 @@ where
                     //  * `eth/67` is reserved message IDs 0x10 - 0x19.
                     //  * `qrs/65` is reserved message IDs 0x1a - 0x21.
                     //
+                    let subprotocol = this.subprotocols.iter().find(|cap| {
+                        id >= cap.offset() &&
+                            cap.num_messages()
+                                .map_or(false, |messages| id - cap.offset() < messages)
+                    });
+                    if let Some(cap) = subprotocol {
+                        // buffer the message for the subprotocol and continue reading
+                        let msg = RawCapabilityMessage {
+                            id: (id - cap.offset()) as usize,
+                            payload: decompress_buf.split_off(1).freeze(),
+                        };
+                        this.subprotocol_messages.push_back((cap.name().into(), msg));
+                        continue
+                    }
+
                     decompress_buf[0] = bytes[0] - this.shared_capability.offset();
 
                     return Poll::Ready(Some(Ok(decompress_buf)))
@@ -567,13 +690,17 @@ where
 /// Determines the offsets for each shared capability between the input list of peer
 /// capabilities and the input list of locally supported capabilities.
 ///
-/// Currently only `eth` versions 66 and 67 are supported.
+/// Returns the shared `eth` capability and the shared capabilities of the given `protocols`.
+/// Shared capabilities that are neither `eth` nor one of the `protocols` are ignored.
+///
+/// Currently only `eth` versions 66, 67 and 68 are supported.
 /// Additionally, the `p2p` capability version 5 is supported, but is
 /// expected _not_ to be in neither `local_capabilities` or `peer_capabilities`.
 pub fn set_capability_offsets(
     local_capabilities: Vec<Capability>,
     peer_capabilities: Vec<Capability>,
-) -> Result<SharedCapability, P2PStreamError> {
+    protocols: &[Protocol],
+) -> Result<(SharedCapability, Vec<SharedCapability>), P2PStreamError> {
     // find intersection of capabilities
     let our_capabilities = local_capabilities.into_iter().collect::<HashSet<_>>();
 
@@ -625,16 +752,27 @@ pub fn set_capability_offsets(
     // alphabetic order.
     let mut offset = MAX_RESERVED_MESSAGE_ID + 1;
     for name in shared_capability_names {
-        let version = shared_capabilities.get(&name).unwrap();
-
-        let shared_capability = SharedCapability::new(&name, *version as u8, offset)?;
+        let version = *shared_capabilities.get(&name).unwrap();
+
+        let protocol = protocols
+            .iter()
+            .find(|protocol| protocol.cap.name == name && protocol.cap.version == version);
+        let shared_capability = match protocol {
+            Some(protocol) if name != "eth" => SharedCapability::Other {
+                name: name.clone(),
+                version: version as u8,
+                offset,
+                messages: protocol.messages,
+            },
+            _ => SharedCapability::new(&name, version as u8, offset)?,
+        };
 
         match shared_capability {
             SharedCapability::UnknownCapability { .. } => {
                 // Capabilities which are not shared are ignored
                 tracing::debug!("unknown capability: name={:?}, version={}", name, version,);
             }
-            SharedCapability::Eth { .. } => {
+            SharedCapability::Eth { .. } | SharedCapability::Other { .. } => {
                 // increment the offset if the capability is known
                 offset += shared_capability.num_messages()?;
 
@@ -643,17 +781,15 @@ pub fn set_capability_offsets(
         }
     }
 
-    // TODO: support multiple capabilities - we would need a new Stream type to go on top of
-    // `P2PStream` containing its capability. `P2PStream` would still send pings and handle
-    // pongs, but instead contain a map of capabilities to their respective stream / channel.
-    // Each channel would be responsible for containing the offset for that stream and would
-    // only increment / decrement message IDs.
-    // NOTE: since the `P2PStream` currently only supports one capability, we set the
-    // capability with the lowest offset.
-    Ok(shared_with_offsets
-        .first()
-        .ok_or(P2PStreamError::HandshakeError(P2PHandshakeError::NoSharedCapabilities))?
-        .clone())
+    // The `P2PStream` yields the messages of the `eth` capability, the messages of the other
+    // subprotocols are buffered separately.
+    let eth = shared_with_offsets
+        .iter()
+        .position(|cap| matches!(cap, SharedCapability::Eth { .. }))
+        .ok_or(P2PStreamError::HandshakeError(P2PHandshakeError::NoSharedCapabilities))?;
+    let eth = shared_with_offsets.remove(eth);
+
+    Ok((eth, shared_with_offsets))
 }
 
 /// This represents only the reserved `p2p` subprotocol messages.
@@ -1004,8 +1140,8 @@ mod tests {
             vec![EthVersion::Eth66.into(), EthVersion::Eth67.into(), EthVersion::Eth68.into()];
         let peer_capabilities: Vec<Capability> = vec![EthVersion::Eth66.into()];
 
-        let shared_capability =
-            set_capability_offsets(local_capabilities, peer_capabilities).unwrap();
+        let (shared_capability, _) =
+            set_capability_offsets(local_capabilities, peer_capabilities, &[]).unwrap();
 
         assert_eq!(
             shared_capability,
@@ -1016,12 +1152,144 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_subprotocol_offsets() {
+        let protocol = Protocol::new(Capability::new("aaa".into(), 1), 2);
+        let capabilities: Vec<Capability> = vec![EthVersion::Eth67.into(), protocol.cap.clone()];
+
+        let (eth, subprotocols) =
+            set_capability_offsets(capabilities.clone(), capabilities, &[protocol]).unwrap();
+
+        // `aaa` precedes `eth` in alphabetical order
+        assert_eq!(
+            subprotocols,
+            vec![SharedCapability::Other {
+                name: "aaa".into(),
+                version: 1,
+                offset: MAX_RESERVED_MESSAGE_ID + 1,
+                messages: 2
+            }]
+        );
+        assert_eq!(
+            eth,
+            SharedCapability::Eth {
+                version: EthVersion::Eth67,
+                offset: MAX_RESERVED_MESSAGE_ID + 3
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_subprotocol_messages() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+        let protocol = Protocol::new(Capability::new("aaa".into(), 1), 2);
+        let msg = RawCapabilityMessage { id: 1, payload: Bytes::from_static(b"hello") };
+
+        let server_protocol = protocol.clone();
+        let server_msg = msg.clone();
+        let handle = tokio::spawn(async move {
+            let (incoming, _) = listener.accept().await.unwrap();
+            let stream = crate::PassthroughCodec::default().framed(incoming);
+
+            let (mut server_hello, _) = eth_hello();
+            server_hello.capabilities.push(server_protocol.cap.clone());
+
+            let (mut p2p_stream, _) = UnauthedP2PStream::new(stream)
+                .with_protocols(vec![server_protocol])
+                .handshake(server_hello)
+                .await
+                .unwrap();
+
+            p2p_stream.start_send_subprotocol("aaa", server_msg).unwrap();
+            p2p_stream.flush().await.unwrap();
+            p2p_stream.disconnect(DisconnectReason::UselessPeer).await.unwrap();
+        });
+
+        let outgoing = TcpStream::connect(local_addr).await.unwrap();
+        let sink = crate::PassthroughCodec::default().framed(outgoing);
+
+        let (mut client_hello, _) = eth_hello();
+        client_hello.capabilities.push(protocol.cap.clone());
+
+        let (mut p2p_stream, _) = UnauthedP2PStream::new(sink)
+            .with_protocols(vec![protocol])
+            .handshake(client_hello)
+            .await
+            .unwrap();
+
+        // the subprotocol message is buffered and not yielded as an `eth` message
+        let err = p2p_stream.next().await.unwrap().unwrap_err();
+        assert!(matches!(err, P2PStreamError::Disconnected(DisconnectReason::UselessPeer)));
+        assert_eq!(p2p_stream.take_subprotocol_message(), Some(("aaa".into(), msg)));
+        assert_eq!(p2p_stream.take_subprotocol_message(), None);
+
+        handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_subprotocol_messages_backpressure() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+        let protocol = Protocol::new(Capability::new("aaa".into(), 1), 2);
+        let first = RawCapabilityMessage { id: 0, payload: Bytes::from_static(b"first") };
+        let second = RawCapabilityMessage { id: 1, payload: Bytes::from_static(b"second") };
+
+        let server_protocol = protocol.clone();
+        let server_msgs = [first.clone(), second.clone()];
+        let handle = tokio::spawn(async move {
+            let (incoming, _) = listener.accept().await.unwrap();
+            let stream = crate::PassthroughCodec::default().framed(incoming);
+
+            let (mut server_hello, _) = eth_hello();
+            server_hello.capabilities.push(server_protocol.cap.clone());
+
+            let (mut p2p_stream, _) = UnauthedP2PStream::new(stream)
+                .with_protocols(vec![server_protocol])
+                .handshake(server_hello)
+                .await
+                .unwrap();
+
+            for msg in server_msgs {
+                p2p_stream.start_send_subprotocol("aaa", msg).unwrap();
+                p2p_stream.flush().await.unwrap();
+            }
+            p2p_stream.disconnect(DisconnectReason::UselessPeer).await.unwrap();
+        });
+
+        let outgoing = TcpStream::connect(local_addr).await.unwrap();
+        let sink = crate::PassthroughCodec::default().framed(outgoing);
+
+        let (mut client_hello, _) = eth_hello();
+        client_hello.capabilities.push(protocol.cap.clone());
+
+        let (mut p2p_stream, _) = UnauthedP2PStream::new(sink)
+            .with_protocols(vec![protocol])
+            .handshake(client_hello)
+            .await
+            .unwrap();
+        p2p_stream.set_subprotocol_message_buffer_capacity(1);
+
+        // the stream stops reading while the buffer is full
+        for msg in [first, second] {
+            let res = tokio::time::timeout(Duration::from_millis(100), p2p_stream.next()).await;
+            assert!(res.is_err());
+            assert_eq!(p2p_stream.take_subprotocol_message(), Some(("aaa".into(), msg)));
+            assert_eq!(p2p_stream.take_subprotocol_message(), None);
+        }
+
+        let err = p2p_stream.next().await.unwrap().unwrap_err();
+        assert!(matches!(err, P2PStreamError::Disconnected(DisconnectReason::UselessPeer)));
+
+        handle.await.unwrap();
+    }
+
     #[test]
     fn test_peer_capability_version_too_low() {
         let local_capabilities: Vec<Capability> = vec![EthVersion::Eth67.into()];
         let peer_capabilities: Vec<Capability> = vec![EthVersion::Eth66.into()];
 
-        let shared_capability = set_capability_offsets(local_capabilities, peer_capabilities);
+        let shared_capability = set_capability_offsets(local_capabilities, peer_capabilities, &[]);
 
         assert!(matches!(
             shared_capability,
@@ -1034,7 +1302,7 @@ mod tests {
         let local_capabilities: Vec<Capability> = vec![EthVersion::Eth66.into()];
         let peer_capabilities: Vec<Capability> = vec![EthVersion::Eth67.into()];
 
-        let shared_capability = set_capability_offsets(local_capabilities, peer_capabilities);
+        let shared_capability = set_capability_offsets(local_capabilities, peer_capabilities, &[]);
 
         assert!(matches!(
             shared_capability,
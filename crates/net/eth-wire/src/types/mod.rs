@@ -23,3 +23,6 @@ pub use state::*;
 
 pub mod receipts;
 pub use receipts::*;
+
+pub mod snap;
+pub use snap::{snap_protocol, SnapMessage, SnapMessageId};
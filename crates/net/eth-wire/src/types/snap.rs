@@ -0,0 +1,323 @@
+//! Implements the messages of the `snap/1` protocol.
+//!
+//! See also <https://github.com/ethereum/devp2p/blob/master/caps/snap.md>
+use crate::capability::{Capability, Protocol, RawCapabilityMessage};
+use reth_codecs::derive_arbitrary;
+use reth_primitives::{Bytes, H256};
+use reth_rlp::{Decodable, DecodeError, Encodable, RlpDecodable, RlpEncodable};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The number of messages of the `snap/1` protocol.
+pub const SNAP_MESSAGES: u8 = 8;
+
+/// Returns the `snap/1` protocol.
+pub fn snap_protocol() -> Protocol {
+    Protocol::new(Capability::new("snap".into(), 1), SNAP_MESSAGES)
+}
+
+/// A request for the accounts of a range of hashed addresses in the state trie with the given
+/// root.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GetAccountRange {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The root of the state trie to serve.
+    pub root_hash: H256,
+    /// The hashed address of the first account to serve.
+    pub starting_hash: H256,
+    /// The hashed address after which to stop serving accounts.
+    pub limit_hash: H256,
+    /// The soft limit of the size of the response in bytes.
+    pub response_bytes: u64,
+}
+
+/// An account in the slim format of the `snap` protocol, keyed by its hashed address.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AccountData {
+    /// The hashed address of the account.
+    pub hash: H256,
+    /// The RLP encoding of the account, with an empty storage root and code hash if the account
+    /// has no storage or code.
+    pub body: Bytes,
+}
+
+/// The response to [`GetAccountRange`], containing consecutive accounts and the merkle proofs of
+/// the first and last account of the range.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AccountRange {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The accounts, ordered by hashed address.
+    pub accounts: Vec<AccountData>,
+    /// The trie nodes that prove the boundaries of the range.
+    pub proof: Vec<Bytes>,
+}
+
+/// A request for the storage slots of a range of hashed keys in the storage tries of the given
+/// accounts.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GetStorageRanges {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The root of the state trie to serve.
+    pub root_hash: H256,
+    /// The hashed addresses of the accounts to serve the storage of.
+    pub account_hashes: Vec<H256>,
+    /// The hashed key of the first slot to serve of the first account, empty for the first slot.
+    pub starting_hash: Bytes,
+    /// The hashed key after which to stop serving slots of the first account, empty for no
+    /// limit.
+    pub limit_hash: Bytes,
+    /// The soft limit of the size of the response in bytes.
+    pub response_bytes: u64,
+}
+
+/// A storage slot, keyed by its hashed key.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StorageData {
+    /// The hashed key of the slot.
+    pub hash: H256,
+    /// The RLP encoding of the value of the slot.
+    pub data: Bytes,
+}
+
+/// The response to [`GetStorageRanges`], containing the slots of consecutive accounts of the
+/// request.
+///
+/// The proof is only set if the slots of the last account are incomplete.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StorageRanges {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The slots of every account, ordered by hashed key.
+    pub slots: Vec<Vec<StorageData>>,
+    /// The trie nodes that prove the boundaries of the slots of the last account.
+    pub proof: Vec<Bytes>,
+}
+
+/// A request for the bytecodes with the given hashes.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GetByteCodes {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The hashes of the bytecodes.
+    pub hashes: Vec<H256>,
+    /// The soft limit of the size of the response in bytes.
+    pub response_bytes: u64,
+}
+
+/// The response to [`GetByteCodes`], containing the bytecodes in the order of the request.
+///
+/// Bytecodes that are not available are skipped.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ByteCodes {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The bytecodes.
+    pub codes: Vec<Bytes>,
+}
+
+/// A request for trie nodes by their path in the state trie with the given root.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GetTrieNodes {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The root of the state trie to serve.
+    pub root_hash: H256,
+    /// The paths of the nodes, grouped by account.
+    ///
+    /// The first element of a group is the path in the account trie, the remaining elements are
+    /// paths in the storage trie of the account.
+    pub paths: Vec<Vec<Bytes>>,
+    /// The soft limit of the size of the response in bytes.
+    pub response_bytes: u64,
+}
+
+/// The response to [`GetTrieNodes`], containing the nodes in the order of the request.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TrieNodes {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The RLP encoded trie nodes.
+    pub nodes: Vec<Bytes>,
+}
+
+/// Represents message IDs for `snap` protocol messages, relative to the offset of the protocol.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[allow(missing_docs)]
+pub enum SnapMessageId {
+    GetAccountRange = 0x00,
+    AccountRange = 0x01,
+    GetStorageRanges = 0x02,
+    StorageRanges = 0x03,
+    GetByteCodes = 0x04,
+    ByteCodes = 0x05,
+    GetTrieNodes = 0x06,
+    TrieNodes = 0x07,
+}
+
+impl TryFrom<usize> for SnapMessageId {
+    type Error = DecodeError;
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        match value {
+            0x00 => Ok(SnapMessageId::GetAccountRange),
+            0x01 => Ok(SnapMessageId::AccountRange),
+            0x02 => Ok(SnapMessageId::GetStorageRanges),
+            0x03 => Ok(SnapMessageId::StorageRanges),
+            0x04 => Ok(SnapMessageId::GetByteCodes),
+            0x05 => Ok(SnapMessageId::ByteCodes),
+            0x06 => Ok(SnapMessageId::GetTrieNodes),
+            0x07 => Ok(SnapMessageId::TrieNodes),
+            _ => Err(DecodeError::Custom("Invalid message ID")),
+        }
+    }
+}
+
+/// A message of the `snap` protocol.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[allow(missing_docs)]
+pub enum SnapMessage {
+    GetAccountRange(GetAccountRange),
+    AccountRange(AccountRange),
+    GetStorageRanges(GetStorageRanges),
+    StorageRanges(StorageRanges),
+    GetByteCodes(GetByteCodes),
+    ByteCodes(ByteCodes),
+    GetTrieNodes(GetTrieNodes),
+    TrieNodes(TrieNodes),
+}
+
+impl SnapMessage {
+    /// Returns the message's ID.
+    pub fn message_id(&self) -> SnapMessageId {
+        match self {
+            SnapMessage::GetAccountRange(_) => SnapMessageId::GetAccountRange,
+            SnapMessage::AccountRange(_) => SnapMessageId::AccountRange,
+            SnapMessage::GetStorageRanges(_) => SnapMessageId::GetStorageRanges,
+            SnapMessage::StorageRanges(_) => SnapMessageId::StorageRanges,
+            SnapMessage::GetByteCodes(_) => SnapMessageId::GetByteCodes,
+            SnapMessage::ByteCodes(_) => SnapMessageId::ByteCodes,
+            SnapMessage::GetTrieNodes(_) => SnapMessageId::GetTrieNodes,
+            SnapMessage::TrieNodes(_) => SnapMessageId::TrieNodes,
+        }
+    }
+
+    /// Returns the id of the request or response.
+    pub fn request_id(&self) -> u64 {
+        match self {
+            SnapMessage::GetAccountRange(msg) => msg.request_id,
+            SnapMessage::AccountRange(msg) => msg.request_id,
+            SnapMessage::GetStorageRanges(msg) => msg.request_id,
+            SnapMessage::StorageRanges(msg) => msg.request_id,
+            SnapMessage::GetByteCodes(msg) => msg.request_id,
+            SnapMessage::ByteCodes(msg) => msg.request_id,
+            SnapMessage::GetTrieNodes(msg) => msg.request_id,
+            SnapMessage::TrieNodes(msg) => msg.request_id,
+        }
+    }
+
+    /// Decodes a message of the subprotocol.
+    pub fn decode_message(msg: &RawCapabilityMessage) -> Result<Self, DecodeError> {
+        let buf = &mut msg.payload.as_ref();
+        let message = match SnapMessageId::try_from(msg.id)? {
+            SnapMessageId::GetAccountRange => {
+                SnapMessage::GetAccountRange(GetAccountRange::decode(buf)?)
+            }
+            SnapMessageId::AccountRange => SnapMessage::AccountRange(AccountRange::decode(buf)?),
+            SnapMessageId::GetStorageRanges => {
+                SnapMessage::GetStorageRanges(GetStorageRanges::decode(buf)?)
+            }
+            SnapMessageId::StorageRanges => SnapMessage::StorageRanges(StorageRanges::decode(buf)?),
+            SnapMessageId::GetByteCodes => SnapMessage::GetByteCodes(GetByteCodes::decode(buf)?),
+            SnapMessageId::ByteCodes => SnapMessage::ByteCodes(ByteCodes::decode(buf)?),
+            SnapMessageId::GetTrieNodes => SnapMessage::GetTrieNodes(GetTrieNodes::decode(buf)?),
+            SnapMessageId::TrieNodes => SnapMessage::TrieNodes(TrieNodes::decode(buf)?),
+        };
+        Ok(message)
+    }
+
+    /// Encodes the message as a message of the subprotocol.
+    pub fn encode_message(&self) -> RawCapabilityMessage {
+        let mut payload = Vec::new();
+        match self {
+            SnapMessage::GetAccountRange(msg) => msg.encode(&mut payload),
+            SnapMessage::AccountRange(msg) => msg.encode(&mut payload),
+            SnapMessage::GetStorageRanges(msg) => msg.encode(&mut payload),
+            SnapMessage::StorageRanges(msg) => msg.encode(&mut payload),
+            SnapMessage::GetByteCodes(msg) => msg.encode(&mut payload),
+            SnapMessage::ByteCodes(msg) => msg.encode(&mut payload),
+            SnapMessage::GetTrieNodes(msg) => msg.encode(&mut payload),
+            SnapMessage::TrieNodes(msg) => msg.encode(&mut payload),
+        }
+        RawCapabilityMessage { id: self.message_id() as usize, payload: payload.into() }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn snap_message_roundtrip() {
+        let messages = [
+            SnapMessage::GetAccountRange(GetAccountRange {
+                request_id: 1,
+                root_hash: H256::repeat_byte(1),
+                starting_hash: H256::zero(),
+                limit_hash: H256::repeat_byte(0xff),
+                response_bytes: 512 * 1024,
+            }),
+            SnapMessage::StorageRanges(StorageRanges {
+                request_id: 2,
+                slots: vec![
+                    vec![StorageData { hash: H256::repeat_byte(2), data: vec![0x01].into() }],
+                    vec![],
+                ],
+                proof: vec![],
+            }),
+            SnapMessage::GetTrieNodes(GetTrieNodes {
+                request_id: 3,
+                root_hash: H256::repeat_byte(3),
+                paths: vec![vec![vec![0x12].into()], vec![vec![0x34].into(), vec![0x56].into()]],
+                response_bytes: 1024,
+            }),
+        ];
+        for message in messages {
+            let raw = message.encode_message();
+            assert_eq!(raw.id, message.message_id() as usize);
+            assert_eq!(SnapMessage::decode_message(&raw).unwrap(), message);
+        }
+    }
+
+    #[test]
+    fn reject_invalid_snap_message_id() {
+        let raw = RawCapabilityMessage { id: SNAP_MESSAGES as usize, payload: Bytes::default() };
+        assert!(SnapMessage::decode_message(&raw).is_err());
+    }
+}
@@ -39,9 +39,16 @@ pub struct PooledTransactions(
     pub Vec<PooledTransactionsElement>,
 );
 
-impl From<Vec<TransactionSigned>> for PooledTransactions {
-    fn from(txs: Vec<TransactionSigned>) -> Self {
-        PooledTransactions(txs.into_iter().map(Into::into).collect())
+impl TryFrom<Vec<TransactionSigned>> for PooledTransactions {
+    type Error = TransactionSigned;
+
+    /// Converts non-4844 transactions, blob transactions require their sidecar, see
+    /// [PooledTransactionsElement::try_from_blob_transaction].
+    fn try_from(txs: Vec<TransactionSigned>) -> Result<Self, Self::Error> {
+        txs.into_iter()
+            .map(PooledTransactionsElement::try_from_broadcast)
+            .collect::<Result<_, _>>()
+            .map(PooledTransactions)
     }
 }
 
@@ -145,7 +152,8 @@ mod test {
                     },
                 ),
             ]
-            .into(),
+            .try_into()
+            .unwrap(),
         };
         request.encode(&mut data);
         assert_eq!(data, expected);
@@ -207,7 +215,8 @@ mod test {
                     },
                 ),
             ]
-            .into(),
+            .try_into()
+            .unwrap(),
         };
 
         let request = RequestPair::<PooledTransactions>::decode(&mut &data[..]).unwrap();
@@ -346,7 +355,8 @@ mod test {
                     },
                 ),
             ]
-            .into(),
+            .try_into()
+            .unwrap(),
         };
 
         // checking tx by tx for easier debugging if there are any regressions
@@ -489,7 +499,8 @@ mod test {
                     },
                 ),
             ]
-            .into(),
+            .try_into()
+            .unwrap(),
         };
 
         let mut encoded = vec![];
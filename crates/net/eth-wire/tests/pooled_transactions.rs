@@ -1,6 +1,6 @@
 //! Decoding tests for [`PooledTransactions`]
 use reth_eth_wire::PooledTransactions;
-use reth_primitives::{hex, Bytes, PooledTransactionsElement};
+use reth_primitives::{hex, Bytes, PooledTransactionsElement, TransactionSigned};
 use reth_rlp::Decodable;
 use std::{fs, path::PathBuf};
 
@@ -31,3 +31,27 @@ fn decode_blob_rpc_transaction() {
     let hex_data = Bytes::from(hex::decode(data.trim()).unwrap());
     let _txs = PooledTransactionsElement::decode_enveloped(hex_data).unwrap();
 }
+
+#[test]
+fn blob_rpc_transaction_pooled_and_consensus_encoding() {
+    let network_data_path =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("testdata/rpc_blob_transaction");
+    let data = fs::read_to_string(network_data_path).expect("Unable to read file");
+    let hex_data = Bytes::from(hex::decode(data.trim()).unwrap());
+    let pooled = PooledTransactionsElement::decode_enveloped(hex_data.clone()).unwrap();
+    assert!(pooled.is_eip4844());
+
+    // the pooled form round trips with the sidecar
+    assert_eq!(pooled.envelope_encoded()[..], hex_data[..]);
+
+    // the consensus form drops the sidecar but keeps the hash
+    let hash = *pooled.hash();
+    let consensus = pooled.into_transaction();
+    let consensus_encoded = consensus.envelope_encoded();
+    assert!(consensus_encoded.len() < hex_data.len());
+    let decoded = TransactionSigned::decode_enveloped(consensus_encoded.into()).unwrap();
+    assert_eq!(decoded.hash(), hash);
+
+    // the consensus form can't be turned back into the pooled form without a sidecar
+    assert!(PooledTransactionsElement::try_from_broadcast(decoded).is_err());
+}
@@ -0,0 +1,129 @@
+//! Resolve the external IP via plain-text HTTP endpoints.
+
+use std::{net::IpAddr, time::Duration};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+use tracing::debug;
+
+/// HTTP endpoints that respond with the caller's IP address as plain text.
+pub const DEFAULT_HTTP_ENDPOINTS: &[&str] =
+    &["http://ifconfig.me/ip", "http://icanhazip.com", "http://api.ipify.org"];
+
+/// Timeout for a single HTTP request.
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Upper bound for the response size, an IP address fits comfortably.
+const MAX_RESPONSE_SIZE: u64 = 4 * 1024;
+
+/// Resolves the external IP via the given endpoint or via the [DEFAULT_HTTP_ENDPOINTS].
+pub(crate) async fn resolve_external_ip_http(url: Option<&str>) -> Option<IpAddr> {
+    let endpoints = match url {
+        Some(url) => vec![url],
+        None => DEFAULT_HTTP_ENDPOINTS.to_vec(),
+    };
+    for url in endpoints {
+        match tokio::time::timeout(HTTP_TIMEOUT, get_ip(url)).await {
+            Ok(Some(ip)) => return Some(ip),
+            Ok(None) => {}
+            Err(_) => {
+                debug!(target: "net::nat", url, "Timed out resolving external IP via HTTP");
+            }
+        }
+    }
+    None
+}
+
+async fn get_ip(url: &str) -> Option<IpAddr> {
+    let Some((host, path)) = split_url(url) else {
+        debug!(target: "net::nat", url, "Unsupported HTTP resolver url");
+        return None
+    };
+    let authority = if host.contains(':') { host.to_string() } else { format!("{host}:80") };
+    let mut stream = TcpStream::connect(&authority)
+        .await
+        .map_err(|err| {
+            debug!(target: "net::nat", ?err, url, "Failed to connect to HTTP resolver");
+            err
+        })
+        .ok()?;
+
+    let request = format!(
+        "GET {path} HTTP/1.1\r\nHost: {host}\r\nUser-Agent: reth\r\nAccept: text/plain\r\nConnection: close\r\n\r\n"
+    );
+    stream.write_all(request.as_bytes()).await.ok()?;
+
+    let mut response = Vec::new();
+    stream.take(MAX_RESPONSE_SIZE).read_to_end(&mut response).await.ok()?;
+
+    let ip = parse_response(&response);
+    if ip.is_none() {
+        debug!(target: "net::nat", url, "Invalid response from HTTP resolver");
+    }
+    ip
+}
+
+/// Splits a `http://host[:port][/path]` url into host and path.
+fn split_url(url: &str) -> Option<(&str, &str)> {
+    let rest = url.strip_prefix("http://")?;
+    let (host, path) = match rest.find('/') {
+        Some(idx) => rest.split_at(idx),
+        None => (rest, "/"),
+    };
+    (!host.is_empty()).then_some((host, path))
+}
+
+/// Parses the IP from a successful HTTP response with a plain-text body.
+fn parse_response(response: &[u8]) -> Option<IpAddr> {
+    let response = std::str::from_utf8(response).ok()?;
+    let (head, body) = response.split_once("\r\n\r\n")?;
+    let status = head.lines().next()?.split_whitespace().nth(1)?;
+    if status != "200" {
+        return None
+    }
+    body.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn split_urls() {
+        assert_eq!(split_url("http://ifconfig.me/ip"), Some(("ifconfig.me", "/ip")));
+        assert_eq!(split_url("http://127.0.0.1:8080"), Some(("127.0.0.1:8080", "/")));
+        assert_eq!(split_url("https://ifconfig.me/ip"), None);
+        assert_eq!(split_url("http:///ip"), None);
+    }
+
+    #[test]
+    fn parse_responses() {
+        let ok = b"HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\n\r\n1.2.3.4\n";
+        assert_eq!(parse_response(ok), Some("1.2.3.4".parse().unwrap()));
+
+        let not_found = b"HTTP/1.1 404 Not Found\r\n\r\n1.2.3.4";
+        assert_eq!(parse_response(not_found), None);
+
+        let garbage = b"HTTP/1.1 200 OK\r\n\r\n<html></html>";
+        assert_eq!(parse_response(garbage), None);
+    }
+
+    #[tokio::test]
+    async fn resolve_via_local_endpoint() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let n = stream.read(&mut buf).await.unwrap();
+            assert!(buf[..n].starts_with(b"GET /ip HTTP/1.1\r\n"));
+            stream.write_all(b"HTTP/1.1 200 OK\r\n\r\n203.0.113.7\n").await.unwrap();
+        });
+
+        let url = format!("http://{addr}/ip");
+        let ip = resolve_external_ip_http(Some(&url)).await;
+        assert_eq!(ip, Some("203.0.113.7".parse().unwrap()));
+    }
+}
@@ -0,0 +1,93 @@
+//! Resolve the external IP from the local network interfaces.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use tokio::net::UdpSocket;
+use tracing::debug;
+
+/// Well known public addresses used to select the interface with the default route.
+///
+/// Connecting a UDP socket does not send any packets, it only makes the OS pick the outgoing
+/// interface.
+const ROUTE_PROBES: [SocketAddr; 2] = [
+    SocketAddr::new(IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)), 53),
+    SocketAddr::new(IpAddr::V6(Ipv6Addr::new(0x2606, 0x4700, 0x4700, 0, 0, 0, 0, 0x1111)), 53),
+];
+
+/// Returns the address of the interface that routes to the public internet if that address is
+/// publicly routable, which is the case if the node is not behind a NAT.
+pub(crate) async fn resolve_external_ip_interface() -> Option<IpAddr> {
+    for probe in ROUTE_PROBES {
+        let bind_addr: SocketAddr = if probe.is_ipv4() {
+            (Ipv4Addr::UNSPECIFIED, 0).into()
+        } else {
+            (Ipv6Addr::UNSPECIFIED, 0).into()
+        };
+        let Ok(socket) = UdpSocket::bind(bind_addr).await else { continue };
+        if let Err(err) = socket.connect(probe).await {
+            debug!(target: "net::nat", ?err, ?probe, "No route for interface lookup");
+            continue
+        }
+        let Ok(local) = socket.local_addr() else { continue };
+        let ip = local.ip();
+        if is_global(&ip) {
+            return Some(ip)
+        }
+        debug!(target: "net::nat", ?ip, "Interface address is not publicly routable");
+    }
+    None
+}
+
+/// Returns true if the address is publicly routable.
+pub(crate) fn is_global(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_unspecified() ||
+                ip.is_loopback() ||
+                ip.is_private() ||
+                ip.is_link_local() ||
+                ip.is_broadcast() ||
+                ip.is_documentation() ||
+                // shared address space, see RFC 6598
+                (a == 100 && (b & 0b1100_0000) == 64) ||
+                // reserved for future use
+                a >= 240)
+        }
+        IpAddr::V6(ip) => {
+            let first = ip.segments()[0];
+            !(ip.is_unspecified() ||
+                ip.is_loopback() ||
+                ip.is_multicast() ||
+                // unique local
+                (first & 0xfe00) == 0xfc00 ||
+                // unicast link local
+                (first & 0xffc0) == 0xfe80 ||
+                // documentation
+                (first == 0x2001 && ip.segments()[1] == 0xdb8))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn global_addresses() {
+        for ip in [
+            "10.0.0.1",
+            "192.168.1.1",
+            "172.16.0.1",
+            "127.0.0.1",
+            "100.64.0.1",
+            "fe80::1",
+            "fd00::1",
+            "::1",
+        ] {
+            assert!(!is_global(&ip.parse().unwrap()), "{ip}");
+        }
+        for ip in ["8.8.8.8", "100.128.0.1", "2606:4700:4700::1111"] {
+            assert!(is_global(&ip.parse().unwrap()), "{ip}");
+        }
+    }
+}
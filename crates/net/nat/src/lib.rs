@@ -13,10 +13,29 @@
 
 //! Helpers for resolving the external IP.
 //!
+//! The external IP can be resolved via UPnP, the local interfaces, plain-text HTTP endpoints, STUN
+//! servers, or be configured statically, see [NatResolver]. [ResolveNatInterval] re-checks the
+//! external IP periodically so that the advertised node record follows address changes.
+//!
+//! Listener ports can be mapped on the gateway via UPnP or NAT-PMP, see [PortMapper].
+//! [PortMappingInterval] renews the mappings before they expire, and removes them on shutdown.
+//!
 //! ## Feature Flags
 //!
 //! - `serde` (default): Enable serde support
 
+mod http;
+mod interface;
+mod port_mapping;
+mod stun;
+
+pub use http::DEFAULT_HTTP_ENDPOINTS;
+pub use port_mapping::{
+    map_port_with, remove_port_mapping, ParsePortMapperError, PortMapper, PortMapping,
+    PortMappingInterval, PortMappingProtocol, DEFAULT_PORT_MAPPING_LIFETIME,
+};
+pub use stun::DEFAULT_STUN_SERVERS;
+
 use igd::aio::search_gateway;
 use pin_project_lite::pin_project;
 use std::{
@@ -34,10 +53,13 @@ use tracing::debug;
 use serde_with::{DeserializeFromStr, SerializeDisplay};
 
 /// All builtin resolvers.
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Default, Hash)]
+#[derive(Debug, Clone, Eq, PartialEq, Default, Hash)]
 #[cfg_attr(feature = "serde", derive(SerializeDisplay, DeserializeFromStr))]
 pub enum NatResolver {
     /// Resolve with any available resolver.
+    ///
+    /// This doesn't include [NatResolver::Stun], which contacts third-party STUN servers and
+    /// has to be selected explicitly.
     #[default]
     Any,
     /// Resolve via Upnp
@@ -46,6 +68,17 @@ pub enum NatResolver {
     PublicIp,
     /// Use the given [IpAddr]
     ExternalIp(IpAddr),
+    /// Use the address of the local interface that routes to the public internet, if that
+    /// address is publicly routable itself.
+    Interface,
+    /// Resolve external IP via a plain-text HTTP endpoint.
+    ///
+    /// If no endpoint is given, the [DEFAULT_HTTP_ENDPOINTS] are tried in order.
+    Http(Option<String>),
+    /// Resolve external IP by sending a STUN binding request to the given `host:port`.
+    ///
+    /// If no server is given, the [DEFAULT_STUN_SERVERS] are tried in order.
+    Stun(Option<String>),
     /// Resolve nothing
     None,
 }
@@ -66,6 +99,11 @@ impl fmt::Display for NatResolver {
             NatResolver::Upnp => f.write_str("upnp"),
             NatResolver::PublicIp => f.write_str("publicip"),
             NatResolver::ExternalIp(ip) => write!(f, "extip:{ip}"),
+            NatResolver::Interface => f.write_str("interface"),
+            NatResolver::Http(None) => f.write_str("http"),
+            NatResolver::Http(Some(url)) => f.write_str(url),
+            NatResolver::Stun(None) => f.write_str("stun"),
+            NatResolver::Stun(Some(server)) => write!(f, "stun:{server}"),
             NatResolver::None => f.write_str("none"),
         }
     }
@@ -91,6 +129,11 @@ impl FromStr for NatResolver {
             "upnp" => NatResolver::Upnp,
             "none" => NatResolver::None,
             "publicip" | "public-ip" => NatResolver::PublicIp,
+            "interface" => NatResolver::Interface,
+            "http" => NatResolver::Http(None),
+            "stun" => NatResolver::Stun(None),
+            s if s.starts_with("http://") => NatResolver::Http(Some(s.to_string())),
+            s if s.starts_with("stun:") => NatResolver::Stun(Some(s["stun:".len()..].to_string())),
             s => {
                 let Some(ip) = s.strip_prefix("extip:") else {
                     return Err(ParseNatResolverError::UnknownVariant(format!(
@@ -154,7 +197,7 @@ impl ResolveNatInterval {
     ///    if the attempt was unsuccessful.
     pub fn poll_tick(&mut self, cx: &mut Context<'_>) -> Poll<Option<IpAddr>> {
         if self.interval.poll_tick(cx).is_ready() {
-            self.future = Some(Box::pin(self.resolver.external_addr()));
+            self.future = Some(Box::pin(self.resolver.clone().external_addr()));
         }
 
         if let Some(mut fut) = self.future.take() {
@@ -179,6 +222,10 @@ pub async fn external_ip() -> Option<IpAddr> {
 pub async fn external_addr_with(resolver: NatResolver) -> Option<IpAddr> {
     match resolver {
         NatResolver::Any => {
+            // a publicly routable interface address needs no further lookups
+            if let Some(ip) = interface::resolve_external_ip_interface().await {
+                return Some(ip)
+            }
             ResolveAny {
                 upnp: Some(Box::pin(resolve_external_ip_upnp())),
                 external: Some(Box::pin(resolve_external_ip())),
@@ -188,6 +235,9 @@ pub async fn external_addr_with(resolver: NatResolver) -> Option<IpAddr> {
         NatResolver::Upnp => resolve_external_ip_upnp().await,
         NatResolver::PublicIp => resolve_external_ip().await,
         NatResolver::ExternalIp(ip) => Some(ip),
+        NatResolver::Interface => interface::resolve_external_ip_interface().await,
+        NatResolver::Http(url) => http::resolve_external_ip_http(url.as_deref()).await,
+        NatResolver::Stun(server) => stun::resolve_external_ip_stun(server.as_deref()).await,
         NatResolver::None => None,
     }
 }
@@ -292,4 +342,21 @@ mod tests {
         assert_eq!(ip, s.parse().unwrap());
         assert_eq!(ip.to_string().as_str(), s);
     }
+
+    #[test]
+    fn test_from_str_resolvers() {
+        for (s, resolver) in [
+            ("interface", NatResolver::Interface),
+            ("http", NatResolver::Http(None)),
+            ("http://ifconfig.me/ip", NatResolver::Http(Some("http://ifconfig.me/ip".to_string()))),
+            ("stun", NatResolver::Stun(None)),
+            (
+                "stun:stun.example.com:3478",
+                NatResolver::Stun(Some("stun.example.com:3478".to_string())),
+            ),
+        ] {
+            assert_eq!(resolver, s.parse().unwrap());
+            assert_eq!(resolver.to_string().as_str(), s);
+        }
+    }
 }
@@ -0,0 +1,633 @@
+//! Map the listener ports on the gateway via UPnP or NAT-PMP, see
+//! [RFC 6886](https://www.rfc-editor.org/rfc/rfc6886).
+
+use igd::aio::search_gateway;
+use std::{
+    fmt,
+    future::{poll_fn, Future},
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    pin::Pin,
+    str::FromStr,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::net::UdpSocket;
+use tracing::debug;
+
+#[cfg(feature = "serde")]
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+
+/// How long a port mapping is requested for, mappings are renewed after half of the lifetime.
+pub const DEFAULT_PORT_MAPPING_LIFETIME: Duration = Duration::from_secs(60 * 60);
+
+/// Description of the mapping shown in the gateway's UPnP table.
+const UPNP_DESCRIPTION: &str = "reth";
+
+const NATPMP_PORT: u16 = 5351;
+const NATPMP_VERSION: u8 = 0;
+const NATPMP_OP_EXTERNAL_ADDRESS: u8 = 0;
+const NATPMP_OP_MAP_UDP: u8 = 1;
+const NATPMP_OP_MAP_TCP: u8 = 2;
+/// Responses have the opcode of the request plus 128.
+const NATPMP_OP_RESPONSE: u8 = 128;
+/// Initial retransmission timeout, doubled for each attempt.
+const NATPMP_INITIAL_TIMEOUT: Duration = Duration::from_millis(250);
+const NATPMP_ATTEMPTS: u32 = 4;
+
+/// All builtin port mapping protocols.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default, Hash)]
+#[cfg_attr(feature = "serde", derive(SerializeDisplay, DeserializeFromStr))]
+pub enum PortMapper {
+    /// Try NAT-PMP first and fall back to UPnP.
+    #[default]
+    Any,
+    /// Map ports via UPnP
+    Upnp,
+    /// Map ports via NAT-PMP
+    NatPmp,
+    /// Map nothing
+    None,
+}
+
+// === impl PortMapper ===
+
+impl PortMapper {
+    /// Attempts to map the local port on the gateway (best effort).
+    pub async fn map_port(
+        self,
+        protocol: PortMappingProtocol,
+        local_port: u16,
+        lifetime: Duration,
+    ) -> Option<PortMapping> {
+        map_port_with(self, protocol, local_port, lifetime).await
+    }
+}
+
+impl fmt::Display for PortMapper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PortMapper::Any => f.write_str("any"),
+            PortMapper::Upnp => f.write_str("upnp"),
+            PortMapper::NatPmp => f.write_str("natpmp"),
+            PortMapper::None => f.write_str("none"),
+        }
+    }
+}
+
+/// Error when parsing a [PortMapper]
+#[derive(Debug, thiserror::Error)]
+#[error("Unknown port mapper: {0}")]
+pub struct ParsePortMapperError(String);
+
+impl FromStr for PortMapper {
+    type Err = ParsePortMapperError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let r = match s {
+            "any" => PortMapper::Any,
+            "upnp" => PortMapper::Upnp,
+            "natpmp" | "nat-pmp" => PortMapper::NatPmp,
+            "none" => PortMapper::None,
+            s => return Err(ParsePortMapperError(s.to_string())),
+        };
+        Ok(r)
+    }
+}
+
+/// The transport protocol of a port mapping.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum PortMappingProtocol {
+    /// The RLPx listener
+    Tcp,
+    /// The discovery socket
+    Udp,
+}
+
+/// A port that has been mapped on the gateway.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct PortMapping {
+    /// The transport protocol of the mapping.
+    pub protocol: PortMappingProtocol,
+    /// The local port that is reachable via the external address.
+    pub local_port: u16,
+    /// The external address of the gateway and the mapped port.
+    pub external: SocketAddr,
+    /// How long the gateway keeps the mapping.
+    pub lifetime: Duration,
+    /// The protocol that created the mapping, either [PortMapper::Upnp] or [PortMapper::NatPmp].
+    pub mapper: PortMapper,
+}
+
+// === impl PortMapping ===
+
+impl PortMapping {
+    /// Removes the mapping from the gateway (best effort).
+    pub async fn remove(self) {
+        remove_port_mapping(self).await
+    }
+}
+
+/// With this type you can map local ports on the gateway and renew the mappings before they
+/// expire.
+#[must_use = "Does nothing unless polled"]
+pub struct PortMappingInterval {
+    mapper: PortMapper,
+    ports: Vec<(PortMappingProtocol, u16)>,
+    lifetime: Duration,
+    future: Option<PortMappingFut>,
+    interval: tokio::time::Interval,
+    /// The ports that were mapped in the last round.
+    mappings: Vec<PortMapping>,
+}
+
+// === impl PortMappingInterval ===
+
+impl PortMappingInterval {
+    /// Creates a new [PortMappingInterval] that maps the given ports with the given lifetime.
+    ///
+    /// The first attempt starts immediately, mappings are renewed after half of the lifetime.
+    #[track_caller]
+    pub fn new(
+        mapper: PortMapper,
+        ports: impl IntoIterator<Item = (PortMappingProtocol, u16)>,
+        lifetime: Duration,
+    ) -> Self {
+        let interval = tokio::time::interval(lifetime / 2);
+        Self {
+            mapper,
+            ports: ports.into_iter().collect(),
+            lifetime,
+            future: None,
+            interval,
+            mappings: Vec::new(),
+        }
+    }
+
+    /// Returns the ports that were mapped in the last round.
+    pub fn mappings(&self) -> &[PortMapping] {
+        &self.mappings
+    }
+
+    /// Removes the ports that were mapped in the last round from the gateway (best effort).
+    ///
+    /// This should be called on shutdown, so the ports are not forwarded until the mappings
+    /// expire.
+    pub async fn remove(self) {
+        for mapping in self.mappings {
+            mapping.remove().await;
+        }
+    }
+
+    /// Completes when the ports have been (re-)mapped.
+    pub async fn tick(&mut self) -> Vec<PortMapping> {
+        poll_fn(|cx| self.poll_tick(cx)).await
+    }
+
+    /// Polls for the next round of mappings.
+    ///
+    /// This method can return the following values:
+    ///
+    ///  * `Poll::Pending` if the next round has not yet completed.
+    ///  * `Poll::Ready(Vec<PortMapping>)` with all ports that were mapped successfully, which is
+    ///    empty if no port could be mapped.
+    pub fn poll_tick(&mut self, cx: &mut Context<'_>) -> Poll<Vec<PortMapping>> {
+        if self.interval.poll_tick(cx).is_ready() {
+            let (mapper, ports, lifetime) = (self.mapper, self.ports.clone(), self.lifetime);
+            self.future = Some(Box::pin(async move {
+                let mut mappings = Vec::with_capacity(ports.len());
+                for (protocol, port) in ports {
+                    if let Some(mapping) = mapper.map_port(protocol, port, lifetime).await {
+                        mappings.push(mapping);
+                    }
+                }
+                mappings
+            }));
+        }
+
+        if let Some(mut fut) = self.future.take() {
+            match fut.as_mut().poll(cx) {
+                Poll::Ready(mappings) => {
+                    self.mappings = mappings.clone();
+                    return Poll::Ready(mappings)
+                }
+                Poll::Pending => {
+                    self.future = Some(fut);
+                }
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+impl fmt::Debug for PortMappingInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PortMappingInterval")
+            .field("mapper", &self.mapper)
+            .field("ports", &self.ports)
+            .field("lifetime", &self.lifetime)
+            .field("mappings", &self.mappings)
+            .finish_non_exhaustive()
+    }
+}
+
+type PortMappingFut = Pin<Box<dyn Future<Output = Vec<PortMapping>> + Send>>;
+
+/// Given a [`PortMapper`] attempts to map the local port on the gateway (best effort).
+pub async fn map_port_with(
+    mapper: PortMapper,
+    protocol: PortMappingProtocol,
+    local_port: u16,
+    lifetime: Duration,
+) -> Option<PortMapping> {
+    match mapper {
+        PortMapper::Any => {
+            // NAT-PMP is a single datagram exchange, UPnP needs a multicast search first
+            match map_port_natpmp(protocol, local_port, lifetime).await {
+                Some(mapping) => Some(mapping),
+                None => map_port_upnp(protocol, local_port, lifetime).await,
+            }
+        }
+        PortMapper::Upnp => map_port_upnp(protocol, local_port, lifetime).await,
+        PortMapper::NatPmp => map_port_natpmp(protocol, local_port, lifetime).await,
+        PortMapper::None => None,
+    }
+}
+
+/// Removes the mapping from the gateway with the protocol that created it (best effort).
+pub async fn remove_port_mapping(mapping: PortMapping) {
+    match mapping.mapper {
+        PortMapper::Upnp => remove_port_upnp(mapping).await,
+        PortMapper::NatPmp => remove_port_natpmp(mapping).await,
+        PortMapper::Any | PortMapper::None => {}
+    }
+}
+
+async fn map_port_upnp(
+    protocol: PortMappingProtocol,
+    local_port: u16,
+    lifetime: Duration,
+) -> Option<PortMapping> {
+    let gateway = search_gateway(Default::default())
+        .await
+        .map_err(|err| {
+            debug!(target: "net::nat", ?err, "Failed to map port via UPnP: failed to find gateway");
+            err
+        })
+        .ok()?;
+
+    // the gateway forwards to the address of the interface that routes to it
+    let local_ip = local_ip_towards(gateway.addr).await?;
+    let local_addr = SocketAddr::new(local_ip, local_port);
+    let upnp_protocol = upnp_protocol(protocol);
+    let lease = lifetime.as_secs().min(u32::MAX as u64) as u32;
+
+    let external_port = match gateway
+        .add_port(upnp_protocol, local_port, local_addr, lease, UPNP_DESCRIPTION)
+        .await
+    {
+        Ok(()) => local_port,
+        Err(err) => {
+            debug!(target: "net::nat", ?err, local_port, "Same external port unavailable via UPnP");
+            gateway
+                .add_any_port(upnp_protocol, local_addr, lease, UPNP_DESCRIPTION)
+                .await
+                .map_err(|err| {
+                    debug!(target: "net::nat", ?err, local_port, "Failed to map port via UPnP");
+                    err
+                })
+                .ok()?
+        }
+    };
+
+    let external_ip = gateway
+        .get_external_ip()
+        .await
+        .map_err(|err| {
+            debug!(target: "net::nat", ?err, "Failed to resolve external IP via UPnP");
+            err
+        })
+        .ok()?;
+
+    Some(PortMapping {
+        protocol,
+        local_port,
+        external: SocketAddr::new(external_ip, external_port),
+        lifetime,
+        mapper: PortMapper::Upnp,
+    })
+}
+
+fn upnp_protocol(protocol: PortMappingProtocol) -> igd::PortMappingProtocol {
+    match protocol {
+        PortMappingProtocol::Tcp => igd::PortMappingProtocol::TCP,
+        PortMappingProtocol::Udp => igd::PortMappingProtocol::UDP,
+    }
+}
+
+async fn remove_port_upnp(mapping: PortMapping) {
+    let gateway = match search_gateway(Default::default()).await {
+        Ok(gateway) => gateway,
+        Err(err) => {
+            debug!(target: "net::nat", ?err, "Failed to remove port mapping via UPnP: failed to find gateway");
+            return
+        }
+    };
+    if let Err(err) =
+        gateway.remove_port(upnp_protocol(mapping.protocol), mapping.external.port()).await
+    {
+        debug!(target: "net::nat", ?err, external=%mapping.external, "Failed to remove port mapping via UPnP");
+    }
+}
+
+/// Returns the address of the local interface that routes to the given address.
+async fn local_ip_towards(addr: SocketAddr) -> Option<IpAddr> {
+    let bind_addr: SocketAddr = if addr.is_ipv4() {
+        (Ipv4Addr::UNSPECIFIED, 0).into()
+    } else {
+        (std::net::Ipv6Addr::UNSPECIFIED, 0).into()
+    };
+    let socket = UdpSocket::bind(bind_addr).await.ok()?;
+    socket.connect(addr).await.ok()?;
+    Some(socket.local_addr().ok()?.ip())
+}
+
+async fn map_port_natpmp(
+    protocol: PortMappingProtocol,
+    local_port: u16,
+    lifetime: Duration,
+) -> Option<PortMapping> {
+    let Some(gateway) = default_gateway() else {
+        debug!(target: "net::nat", "Failed to map port via NAT-PMP: no default gateway");
+        return None
+    };
+    map_port_natpmp_with(
+        SocketAddr::new(gateway.into(), NATPMP_PORT),
+        protocol,
+        local_port,
+        lifetime,
+    )
+    .await
+}
+
+async fn remove_port_natpmp(mapping: PortMapping) {
+    let Some(gateway) = default_gateway() else {
+        debug!(target: "net::nat", "Failed to remove port mapping via NAT-PMP: no default gateway");
+        return
+    };
+    remove_port_natpmp_with(SocketAddr::new(gateway.into(), NATPMP_PORT), mapping).await
+}
+
+/// Requests a lifetime of `0`, which deletes the mapping, see RFC 6886 section 3.4.
+async fn remove_port_natpmp_with(gateway: SocketAddr, mapping: PortMapping) -> Option<()> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await.ok()?;
+    socket.connect(gateway).await.ok()?;
+
+    let request = encode_map_request(mapping.protocol, mapping.local_port, 0, Duration::ZERO);
+    natpmp_request(&socket, &request)
+        .await
+        .and_then(|msg| decode_map_response(&msg, mapping.protocol, mapping.local_port))
+        .map(|_| ())
+}
+
+async fn map_port_natpmp_with(
+    gateway: SocketAddr,
+    protocol: PortMappingProtocol,
+    local_port: u16,
+    lifetime: Duration,
+) -> Option<PortMapping> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await.ok()?;
+    socket.connect(gateway).await.ok()?;
+
+    let external_ip = natpmp_request(&socket, &[NATPMP_VERSION, NATPMP_OP_EXTERNAL_ADDRESS])
+        .await
+        .and_then(|msg| decode_external_address_response(&msg))?;
+
+    // suggest the same external port
+    let request = encode_map_request(protocol, local_port, local_port, lifetime);
+    let (external_port, lifetime) = natpmp_request(&socket, &request)
+        .await
+        .and_then(|msg| decode_map_response(&msg, protocol, local_port))?;
+
+    Some(PortMapping {
+        protocol,
+        local_port,
+        external: SocketAddr::new(external_ip.into(), external_port),
+        lifetime,
+        mapper: PortMapper::NatPmp,
+    })
+}
+
+/// Sends the request to the gateway and waits for the response, retransmitting with exponential
+/// backoff.
+async fn natpmp_request(socket: &UdpSocket, request: &[u8]) -> Option<Vec<u8>> {
+    let mut timeout = NATPMP_INITIAL_TIMEOUT;
+    for _ in 0..NATPMP_ATTEMPTS {
+        socket.send(request).await.ok()?;
+        let mut buf = [0u8; 16];
+        match tokio::time::timeout(timeout, socket.recv(&mut buf)).await {
+            Ok(Ok(n)) => return Some(buf[..n].to_vec()),
+            Ok(Err(err)) => {
+                // the gateway does not speak NAT-PMP
+                debug!(target: "net::nat", ?err, "Failed to receive NAT-PMP response");
+                return None
+            }
+            Err(_) => timeout *= 2,
+        }
+    }
+    debug!(target: "net::nat", "Timed out waiting for NAT-PMP response");
+    None
+}
+
+fn encode_map_request(
+    protocol: PortMappingProtocol,
+    local_port: u16,
+    suggested_external_port: u16,
+    lifetime: Duration,
+) -> [u8; 12] {
+    let mut msg = [0u8; 12];
+    msg[0] = NATPMP_VERSION;
+    msg[1] = natpmp_opcode(protocol);
+    // bytes 2..4 are reserved
+    msg[4..6].copy_from_slice(&local_port.to_be_bytes());
+    msg[6..8].copy_from_slice(&suggested_external_port.to_be_bytes());
+    msg[8..12].copy_from_slice(&(lifetime.as_secs().min(u32::MAX as u64) as u32).to_be_bytes());
+    msg
+}
+
+fn natpmp_opcode(protocol: PortMappingProtocol) -> u8 {
+    match protocol {
+        PortMappingProtocol::Tcp => NATPMP_OP_MAP_TCP,
+        PortMappingProtocol::Udp => NATPMP_OP_MAP_UDP,
+    }
+}
+
+/// Checks the common response header and returns the remaining payload after the seconds since
+/// epoch.
+fn decode_response_header(msg: &[u8], opcode: u8) -> Option<&[u8]> {
+    if msg.len() < 8 || msg[0] != NATPMP_VERSION || msg[1] != NATPMP_OP_RESPONSE + opcode {
+        return None
+    }
+    let result = u16::from_be_bytes([msg[2], msg[3]]);
+    if result != 0 {
+        debug!(target: "net::nat", result, opcode, "NAT-PMP request failed");
+        return None
+    }
+    Some(&msg[8..])
+}
+
+fn decode_external_address_response(msg: &[u8]) -> Option<Ipv4Addr> {
+    let payload = decode_response_header(msg, NATPMP_OP_EXTERNAL_ADDRESS)?;
+    let octets: [u8; 4] = payload.get(..4)?.try_into().ok()?;
+    Some(Ipv4Addr::from(octets))
+}
+
+/// Returns the mapped external port and the lifetime granted by the gateway.
+fn decode_map_response(
+    msg: &[u8],
+    protocol: PortMappingProtocol,
+    local_port: u16,
+) -> Option<(u16, Duration)> {
+    let payload = decode_response_header(msg, natpmp_opcode(protocol))?;
+    let payload = payload.get(..8)?;
+    if u16::from_be_bytes([payload[0], payload[1]]) != local_port {
+        return None
+    }
+    let external_port = u16::from_be_bytes([payload[2], payload[3]]);
+    let lifetime = u32::from_be_bytes([payload[4], payload[5], payload[6], payload[7]]);
+    Some((external_port, Duration::from_secs(lifetime as u64)))
+}
+
+/// Returns the IPv4 default gateway.
+#[cfg(target_os = "linux")]
+fn default_gateway() -> Option<Ipv4Addr> {
+    let routes = std::fs::read_to_string("/proc/net/route").ok()?;
+    parse_default_gateway(&routes)
+}
+
+/// Returns the IPv4 default gateway.
+#[cfg(not(target_os = "linux"))]
+fn default_gateway() -> Option<Ipv4Addr> {
+    None
+}
+
+/// Parses the default gateway from the kernel routing table, which lists destination and gateway
+/// as little endian hex.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_default_gateway(routes: &str) -> Option<Ipv4Addr> {
+    routes.lines().skip(1).find_map(|line| {
+        let mut fields = line.split_whitespace().skip(1);
+        let destination = fields.next()?;
+        let gateway = u32::from_str_radix(fields.next()?, 16).ok()?;
+        (destination == "00000000" && gateway != 0).then(|| Ipv4Addr::from(gateway.to_le_bytes()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() {
+        for (s, mapper) in [
+            ("any", PortMapper::Any),
+            ("upnp", PortMapper::Upnp),
+            ("natpmp", PortMapper::NatPmp),
+            ("none", PortMapper::None),
+        ] {
+            assert_eq!(mapper, s.parse().unwrap());
+            assert_eq!(mapper.to_string().as_str(), s);
+        }
+        assert!("pcp".parse::<PortMapper>().is_err());
+    }
+
+    #[test]
+    fn parse_route_table() {
+        let routes = "\
+Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT
+eth0\t0000A8C0\t00000000\t0001\t0\t0\t0\t00FFFFFF\t0\t0\t0
+eth0\t00000000\t0100A8C0\t0003\t0\t0\t0\t00000000\t0\t0\t0
+";
+        assert_eq!(parse_default_gateway(routes), Some(Ipv4Addr::new(192, 168, 0, 1)));
+        assert_eq!(
+            parse_default_gateway(&routes.lines().take(2).collect::<Vec<_>>().join("\n")),
+            None
+        );
+    }
+
+    #[test]
+    fn encode_decode_map() {
+        let msg =
+            encode_map_request(PortMappingProtocol::Tcp, 30303, 30303, Duration::from_secs(3600));
+        assert_eq!(msg, [0, 2, 0, 0, 0x76, 0x5f, 0x76, 0x5f, 0, 0, 0x0e, 0x10]);
+
+        let mut resp = vec![0, 130, 0, 0, 0, 0, 0, 1, 0x76, 0x5f, 0x76, 0x60, 0, 0, 0x07, 0x08];
+        assert_eq!(
+            decode_map_response(&resp, PortMappingProtocol::Tcp, 30303),
+            Some((30304, Duration::from_secs(1800)))
+        );
+        assert_eq!(decode_map_response(&resp, PortMappingProtocol::Udp, 30303), None);
+
+        // not authorized
+        resp[3] = 2;
+        assert_eq!(decode_map_response(&resp, PortMappingProtocol::Tcp, 30303), None);
+    }
+
+    #[tokio::test]
+    async fn map_via_local_gateway() {
+        let gateway = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let gateway_addr = gateway.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 16];
+            let (n, from) = gateway.recv_from(&mut buf).await.unwrap();
+            assert_eq!(&buf[..n], &[NATPMP_VERSION, NATPMP_OP_EXTERNAL_ADDRESS]);
+            let resp = [0, 128, 0, 0, 0, 0, 0, 1, 203, 0, 113, 7];
+            gateway.send_to(&resp, from).await.unwrap();
+
+            let (n, from) = gateway.recv_from(&mut buf).await.unwrap();
+            assert_eq!(n, 12);
+            let mut resp = [0u8; 16];
+            resp[1] = 128 + buf[1];
+            resp[8..12].copy_from_slice(&buf[4..8]);
+            resp[12..16].copy_from_slice(&buf[8..12]);
+            gateway.send_to(&resp, from).await.unwrap();
+        });
+
+        let mapping = map_port_natpmp_with(
+            gateway_addr,
+            PortMappingProtocol::Udp,
+            30303,
+            DEFAULT_PORT_MAPPING_LIFETIME,
+        )
+        .await
+        .unwrap();
+        assert_eq!(mapping.external, "203.0.113.7:30303".parse().unwrap());
+        assert_eq!(mapping.lifetime, DEFAULT_PORT_MAPPING_LIFETIME);
+        assert_eq!(mapping.mapper, PortMapper::NatPmp);
+    }
+
+    #[tokio::test]
+    async fn remove_via_local_gateway() {
+        let gateway = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let gateway_addr = gateway.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 16];
+            let (n, from) = gateway.recv_from(&mut buf).await.unwrap();
+            // lifetime and suggested external port are zero
+            assert_eq!(&buf[..n], &[0, 1, 0, 0, 0x76, 0x5f, 0, 0, 0, 0, 0, 0]);
+            let mut resp = [0u8; 16];
+            resp[1] = 128 + buf[1];
+            resp[8..10].copy_from_slice(&buf[4..6]);
+            gateway.send_to(&resp, from).await.unwrap();
+        });
+
+        let mapping = PortMapping {
+            protocol: PortMappingProtocol::Udp,
+            local_port: 30303,
+            external: "203.0.113.7:30303".parse().unwrap(),
+            lifetime: DEFAULT_PORT_MAPPING_LIFETIME,
+            mapper: PortMapper::NatPmp,
+        };
+        assert_eq!(remove_port_natpmp_with(gateway_addr, mapping).await, Some(()));
+    }
+}
@@ -0,0 +1,224 @@
+//! Resolve the external IP via STUN binding requests, see [RFC 5389](https://www.rfc-editor.org/rfc/rfc5389).
+
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::Duration,
+};
+use tokio::net::{lookup_host, UdpSocket};
+use tracing::debug;
+
+/// Public STUN servers.
+pub const DEFAULT_STUN_SERVERS: &[&str] = &["stun.l.google.com:19302", "stun.cloudflare.com:3478"];
+
+/// Timeout for a single binding request.
+const STUN_TIMEOUT: Duration = Duration::from_secs(3);
+
+const MAGIC_COOKIE: u32 = 0x2112_A442;
+const BINDING_REQUEST: u16 = 0x0001;
+const BINDING_SUCCESS: u16 = 0x0101;
+const ATTR_MAPPED_ADDRESS: u16 = 0x0001;
+const ATTR_XOR_MAPPED_ADDRESS: u16 = 0x0020;
+const HEADER_LEN: usize = 20;
+
+/// Resolves the external IP via the given server or via the [DEFAULT_STUN_SERVERS].
+pub(crate) async fn resolve_external_ip_stun(server: Option<&str>) -> Option<IpAddr> {
+    let servers = match server {
+        Some(server) => vec![server],
+        None => DEFAULT_STUN_SERVERS.to_vec(),
+    };
+    for server in servers {
+        match tokio::time::timeout(STUN_TIMEOUT, binding_request(server)).await {
+            Ok(Some(ip)) => return Some(ip),
+            Ok(None) => {}
+            Err(_) => {
+                debug!(target: "net::nat", server, "Timed out resolving external IP via STUN");
+            }
+        }
+    }
+    None
+}
+
+async fn binding_request(server: &str) -> Option<IpAddr> {
+    let server_addr = lookup_host(server)
+        .await
+        .map_err(|err| {
+            debug!(target: "net::nat", ?err, server, "Failed to resolve STUN server");
+            err
+        })
+        .ok()?
+        .next()?;
+    let bind_addr: SocketAddr = if server_addr.is_ipv4() {
+        (Ipv4Addr::UNSPECIFIED, 0).into()
+    } else {
+        (Ipv6Addr::UNSPECIFIED, 0).into()
+    };
+    let socket = UdpSocket::bind(bind_addr).await.ok()?;
+    socket.connect(server_addr).await.ok()?;
+
+    // the transaction id must be random so spoofed responses can't be matched to the request
+    let transaction_id = rand::random::<[u8; 12]>();
+    socket.send(&encode_binding_request(transaction_id)).await.ok()?;
+
+    let mut buf = [0u8; 512];
+    loop {
+        let n = socket.recv(&mut buf).await.ok()?;
+        match decode_binding_response(&buf[..n], transaction_id) {
+            Ok(addr) => return addr.map(|addr| addr.ip()),
+            // unrelated datagram, keep waiting for the response
+            Err(()) => continue,
+        }
+    }
+}
+
+fn encode_binding_request(transaction_id: [u8; 12]) -> [u8; HEADER_LEN] {
+    let mut msg = [0u8; HEADER_LEN];
+    msg[0..2].copy_from_slice(&BINDING_REQUEST.to_be_bytes());
+    // message length is zero, no attributes
+    msg[4..8].copy_from_slice(&MAGIC_COOKIE.to_be_bytes());
+    msg[8..].copy_from_slice(&transaction_id);
+    msg
+}
+
+/// Decodes a binding response.
+///
+/// Returns `Err` if the message is not a response to the request with the given transaction id
+/// and `Ok(None)` if the response does not contain a mapped address.
+fn decode_binding_response(msg: &[u8], transaction_id: [u8; 12]) -> Result<Option<SocketAddr>, ()> {
+    if msg.len() < HEADER_LEN ||
+        msg[4..8] != MAGIC_COOKIE.to_be_bytes() ||
+        msg[8..HEADER_LEN] != transaction_id
+    {
+        return Err(())
+    }
+    if u16::from_be_bytes([msg[0], msg[1]]) != BINDING_SUCCESS {
+        return Ok(None)
+    }
+
+    let len = u16::from_be_bytes([msg[2], msg[3]]) as usize;
+    let mut attrs = msg.get(HEADER_LEN..HEADER_LEN + len).ok_or(())?;
+    let mut mapped = None;
+    while attrs.len() >= 4 {
+        let ty = u16::from_be_bytes([attrs[0], attrs[1]]);
+        let attr_len = u16::from_be_bytes([attrs[2], attrs[3]]) as usize;
+        let Some(value) = attrs.get(4..4 + attr_len) else { break };
+        match ty {
+            ATTR_XOR_MAPPED_ADDRESS => {
+                if let Some(addr) = decode_address(value, Some(&msg[4..HEADER_LEN])) {
+                    return Ok(Some(addr))
+                }
+            }
+            ATTR_MAPPED_ADDRESS => mapped = decode_address(value, None),
+            _ => {}
+        }
+        // attributes are padded to a multiple of 4 bytes
+        let padded = (4 + attr_len + 3) & !3;
+        attrs = attrs.get(padded..).unwrap_or_default();
+    }
+    Ok(mapped)
+}
+
+/// Decodes a (XOR-)MAPPED-ADDRESS attribute value, `xor` is the magic cookie followed by the
+/// transaction id.
+fn decode_address(value: &[u8], xor: Option<&[u8]>) -> Option<SocketAddr> {
+    if value.len() < 4 {
+        return None
+    }
+    let mut port = u16::from_be_bytes([value[2], value[3]]);
+    let mut addr = value[4..].to_vec();
+    if let Some(xor) = xor {
+        port ^= (MAGIC_COOKIE >> 16) as u16;
+        for (byte, key) in addr.iter_mut().zip(xor) {
+            *byte ^= key;
+        }
+    }
+    let ip = match (value[1], addr.len()) {
+        (0x01, 4) => IpAddr::V4(Ipv4Addr::new(addr[0], addr[1], addr[2], addr[3])),
+        (0x02, 16) => {
+            let octets: [u8; 16] = addr.try_into().ok()?;
+            IpAddr::V6(Ipv6Addr::from(octets))
+        }
+        _ => return None,
+    };
+    Some(SocketAddr::new(ip, port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(transaction_id: [u8; 12], attrs: &[u8]) -> Vec<u8> {
+        let mut msg = Vec::new();
+        msg.extend_from_slice(&BINDING_SUCCESS.to_be_bytes());
+        msg.extend_from_slice(&(attrs.len() as u16).to_be_bytes());
+        msg.extend_from_slice(&MAGIC_COOKIE.to_be_bytes());
+        msg.extend_from_slice(&transaction_id);
+        msg.extend_from_slice(attrs);
+        msg
+    }
+
+    #[test]
+    fn encode_request() {
+        let id = [7u8; 12];
+        let msg = encode_binding_request(id);
+        assert_eq!(&msg[..4], &[0x00, 0x01, 0x00, 0x00]);
+        assert_eq!(&msg[4..8], &[0x21, 0x12, 0xA4, 0x42]);
+        assert_eq!(&msg[8..], &id);
+    }
+
+    #[test]
+    fn decode_xor_mapped_address() {
+        let id = [1u8; 12];
+        let ip = Ipv4Addr::new(203, 0, 113, 7);
+        let port = 30303u16;
+        let xport = port ^ (MAGIC_COOKIE >> 16) as u16;
+        let xip = u32::from(ip) ^ MAGIC_COOKIE;
+
+        let mut attrs = vec![0x00, 0x20, 0x00, 0x08, 0x00, 0x01];
+        attrs.extend_from_slice(&xport.to_be_bytes());
+        attrs.extend_from_slice(&xip.to_be_bytes());
+
+        let addr = decode_binding_response(&response(id, &attrs), id).unwrap();
+        assert_eq!(addr, Some(SocketAddr::new(ip.into(), port)));
+    }
+
+    #[test]
+    fn decode_mapped_address_after_unknown_attribute() {
+        let id = [2u8; 12];
+        // unknown attribute with 1 byte of value and 3 bytes of padding
+        let mut attrs = vec![0x80, 0x22, 0x00, 0x01, b'x', 0, 0, 0];
+        attrs.extend_from_slice(&[0x00, 0x01, 0x00, 0x08, 0x00, 0x01, 0x76, 0x5f, 1, 2, 3, 4]);
+
+        let addr = decode_binding_response(&response(id, &attrs), id).unwrap();
+        assert_eq!(addr, Some("1.2.3.4:30303".parse().unwrap()));
+    }
+
+    #[test]
+    fn reject_foreign_transaction() {
+        let msg = response([3u8; 12], &[]);
+        assert!(decode_binding_response(&msg, [4u8; 12]).is_err());
+        assert_eq!(decode_binding_response(&msg, [3u8; 12]), Ok(None));
+    }
+
+    #[tokio::test]
+    async fn resolve_via_local_server() {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let server_addr = server.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 512];
+            let (n, from) = server.recv_from(&mut buf).await.unwrap();
+            assert_eq!(n, HEADER_LEN);
+            let id: [u8; 12] = buf[8..HEADER_LEN].try_into().unwrap();
+
+            let SocketAddr::V4(from) = from else { unreachable!() };
+            let xport = from.port() ^ (MAGIC_COOKIE >> 16) as u16;
+            let xip = u32::from(*from.ip()) ^ MAGIC_COOKIE;
+            let mut attrs = vec![0x00, 0x20, 0x00, 0x08, 0x00, 0x01];
+            attrs.extend_from_slice(&xport.to_be_bytes());
+            attrs.extend_from_slice(&xip.to_be_bytes());
+            server.send_to(&response(id, &attrs), from).await.unwrap();
+        });
+
+        let ip = resolve_external_ip_stun(Some(&server_addr.to_string())).await;
+        assert_eq!(ip, Some(IpAddr::V4(Ipv4Addr::LOCALHOST)));
+    }
+}
@@ -20,9 +20,9 @@
 //! - `serde` (default): Enable serde support
 use async_trait::async_trait;
 use reth_eth_wire::{DisconnectReason, EthVersion, Status};
-use reth_primitives::{NodeRecord, PeerId};
+use reth_primitives::{NodeRecord, PeerId, PooledTransactionsElement, H256};
 use reth_rpc_types::NetworkStatus;
-use std::{net::SocketAddr, sync::Arc};
+use std::{net::SocketAddr, sync::Arc, time::Duration};
 
 pub use error::NetworkError;
 pub use reputation::{Reputation, ReputationChangeKind};
@@ -53,6 +53,17 @@ pub trait NetworkInfo: Send + Sync {
 
     /// Returns `true` when the node is undergoing the very first Pipeline sync.
     fn is_initially_syncing(&self) -> bool;
+
+    /// Requests the transactions with the given hashes from the pool of a connected peer.
+    ///
+    /// Returns an empty list if no peer could serve the request, or if the network does not
+    /// support requesting transactions.
+    async fn get_pooled_transactions(
+        &self,
+        _hashes: Vec<H256>,
+    ) -> Result<Vec<PooledTransactionsElement>, NetworkError> {
+        Ok(Vec::new())
+    }
 }
 
 /// Provides general purpose information about Peers in the network.
@@ -79,6 +90,13 @@ pub trait Peers: PeersInfo {
         self.add_peer_kind(peer, PeerKind::Trusted, addr);
     }
 
+    /// Removes a peer from the trusted set.
+    ///
+    /// The peer is kept as a basic peer.
+    fn remove_trusted_peer(&self, peer: PeerId) {
+        self.remove_peer(peer, PeerKind::Trusted);
+    }
+
     /// Adds a peer to the known peer set, with the given kind.
     fn add_peer_kind(&self, peer: PeerId, kind: PeerKind, addr: SocketAddr);
 
@@ -130,6 +148,12 @@ pub struct PeerInfo {
     pub eth_version: EthVersion,
     /// The Status message the peer sent for the `eth` handshake
     pub status: Status,
+    /// The average round-trip time of the requests the peer served, if any.
+    pub request_latency: Option<Duration>,
+    /// The number of bytes received from the peer during the session.
+    pub inbound_bytes: u64,
+    /// The number of bytes sent to the peer during the session.
+    pub outbound_bytes: u64,
 }
 
 /// The direction of the connection.
@@ -30,10 +30,18 @@ pub enum ReputationChangeKind {
     FailedToConnect,
     /// Connection dropped by peer.
     Dropped,
+    /// Peer repeatedly exceeded its request rate limit.
+    ExceededRateLimit,
     /// Reset the reputation to the default value.
     Reset,
     /// Apply a reputation change by value
     Other(Reputation),
+    /// A reputation change of a custom kind, for example emitted by the handler of an additional
+    /// protocol.
+    ///
+    /// The change is weighted by the configured reputation policy, by default custom changes have
+    /// no weight.
+    Custom(&'static str),
 }
 
 impl ReputationChangeKind {
@@ -1,8 +1,9 @@
 //! Builder support for configuring the entire setup.
 
 use crate::{
-    eth_requests::EthRequestHandler, transactions::TransactionsManager, NetworkHandle,
-    NetworkManager,
+    eth_requests::{EthRequestHandler, EthRequestHandlerConfig},
+    transactions::{TransactionsManager, TransactionsManagerConfig},
+    NetworkHandle, NetworkManager, ProtocolHandler,
 };
 use reth_transaction_pool::TransactionPool;
 use tokio::sync::mpsc;
@@ -34,16 +35,32 @@ impl<C, Tx, Eth> NetworkBuilder<C, Tx, Eth> {
         (handle, network, transactions, request_handler)
     }
 
+    /// Registers the handler of a custom RLPx subprotocol, see [`NetworkManager::add_protocol`].
+    pub fn add_protocol(mut self, handler: impl ProtocolHandler) -> Self {
+        self.network.add_protocol(handler);
+        self
+    }
+
     /// Creates a new [`TransactionsManager`] and wires it to the network.
     pub fn transactions<Pool: TransactionPool>(
         self,
         pool: Pool,
+    ) -> NetworkBuilder<C, TransactionsManager<Pool>, Eth> {
+        self.transactions_with_config(pool, Default::default())
+    }
+
+    /// Creates a new [`TransactionsManager`] with the given [`TransactionsManagerConfig`] and
+    /// wires it to the network.
+    pub fn transactions_with_config<Pool: TransactionPool>(
+        self,
+        pool: Pool,
+        config: TransactionsManagerConfig,
     ) -> NetworkBuilder<C, TransactionsManager<Pool>, Eth> {
         let NetworkBuilder { mut network, request_handler, .. } = self;
         let (tx, rx) = mpsc::unbounded_channel();
         network.set_transactions(tx);
         let handle = network.handle().clone();
-        let transactions = TransactionsManager::new(handle, pool, rx);
+        let transactions = TransactionsManager::with_config(handle, pool, rx, config);
         NetworkBuilder { network, request_handler, transactions }
     }
 
@@ -51,12 +68,22 @@ impl<C, Tx, Eth> NetworkBuilder<C, Tx, Eth> {
     pub fn request_handler<Client>(
         self,
         client: Client,
+    ) -> NetworkBuilder<C, Tx, EthRequestHandler<Client>> {
+        self.request_handler_with_config(client, Default::default())
+    }
+
+    /// Creates a new [`EthRequestHandler`] with the given [`EthRequestHandlerConfig`] and wires it
+    /// to the network.
+    pub fn request_handler_with_config<Client>(
+        self,
+        client: Client,
+        config: EthRequestHandlerConfig,
     ) -> NetworkBuilder<C, Tx, EthRequestHandler<Client>> {
         let NetworkBuilder { mut network, transactions, .. } = self;
         let (tx, rx) = mpsc::channel(ETH_REQUEST_CHANNEL_CAPACITY);
         network.set_eth_request_handler(tx);
         let peers = network.handle().peers_handle().clone();
-        let request_handler = EthRequestHandler::new(client, peers, rx);
+        let request_handler = EthRequestHandler::with_config(client, peers, rx, config);
         NetworkBuilder { network, request_handler, transactions }
     }
 }
@@ -1,5 +1,12 @@
+use linked_hash_map::LinkedHashMap;
 use linked_hash_set::LinkedHashSet;
-use std::{borrow::Borrow, hash::Hash, num::NonZeroUsize};
+use std::{
+    borrow::Borrow,
+    hash::Hash,
+    mem,
+    num::NonZeroUsize,
+    time::{Duration, Instant},
+};
 
 /// A minimal LRU cache based on a `LinkedHashSet` with limited capacity.
 ///
@@ -65,6 +72,113 @@ where
     }
 }
 
+/// An LRU cache like [`LruCache`] whose entries additionally expire after a time-to-live.
+///
+/// Entries are kept in the order they were last inserted, so the least recently used and expired
+/// entries are always at the front and are evicted lazily on insert.
+#[derive(Debug, Clone)]
+pub struct TimedLruCache<T: Hash + Eq> {
+    limit: NonZeroUsize,
+    ttl: Option<Duration>,
+    inner: LinkedHashMap<T, Instant>,
+    /// Number of entries that were evicted, either because they expired or the limit was reached.
+    evicted: u64,
+}
+
+impl<T: Hash + Eq> TimedLruCache<T> {
+    /// Estimated heap memory used by a single entry in bytes.
+    ///
+    /// This includes the key, the timestamp of the last insert, the links of the list node and the
+    /// pointer in the table of the map.
+    pub const ENTRY_SIZE: usize =
+        mem::size_of::<T>() + mem::size_of::<Instant>() + 3 * mem::size_of::<usize>();
+
+    /// Creates a new `TimedLruCache` with the given limit and an optional time-to-live for entries.
+    pub fn new(limit: NonZeroUsize, ttl: Option<Duration>) -> Self {
+        Self { limit, ttl, inner: LinkedHashMap::new(), evicted: 0 }
+    }
+
+    /// Insert an element into the cache.
+    ///
+    /// Returns `true` if the element is new or the existing entry expired. Returns `false` if the
+    /// element is present and not expired.
+    ///
+    /// In both cases the entry becomes the most recently used one and its time-to-live restarts.
+    pub fn insert(&mut self, entry: T) -> bool {
+        self.insert_at(entry, Instant::now())
+    }
+
+    fn insert_at(&mut self, entry: T, now: Instant) -> bool {
+        self.evict_expired(now);
+        if let Some(inserted) = self.inner.get_refresh(&entry) {
+            *inserted = now;
+            return false
+        }
+        self.inner.insert(entry, now);
+        while self.inner.len() > self.limit.get() {
+            self.inner.pop_front();
+            self.evicted += 1;
+        }
+        true
+    }
+
+    /// Removes all expired entries from the front of the cache.
+    fn evict_expired(&mut self, now: Instant) {
+        let Some(ttl) = self.ttl else { return };
+        while let Some((_, inserted)) = self.inner.front() {
+            if now.saturating_duration_since(*inserted) < ttl {
+                break
+            }
+            self.inner.pop_front();
+            self.evicted += 1;
+        }
+    }
+
+    /// Returns `true` if the cache contains an unexpired value.
+    pub fn contains<Q: ?Sized>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq,
+    {
+        match (self.inner.get(value), self.ttl) {
+            (Some(inserted), Some(ttl)) => inserted.elapsed() < ttl,
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+    }
+
+    /// Returns the number of entries, including expired entries that were not evicted yet.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Returns the estimated heap memory used by the entries in bytes.
+    pub fn memory_usage(&self) -> usize {
+        self.len() * Self::ENTRY_SIZE
+    }
+
+    /// Returns the number of evicted entries and resets the counter.
+    pub fn take_evicted(&mut self) -> u64 {
+        mem::take(&mut self.evicted)
+    }
+}
+
+impl<T> Extend<T> for TimedLruCache<T>
+where
+    T: Eq + Hash,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter.into_iter() {
+            self.insert(item);
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -110,4 +224,64 @@ mod test {
             assert!(cache.contains(e));
         }
     }
+
+    #[test]
+    fn test_timed_cache_enforces_limit() {
+        let limit = NonZeroUsize::new(2).unwrap();
+        let mut cache = TimedLruCache::new(limit, None);
+        cache.extend(["a", "b", "c"]);
+        assert!(!cache.contains("a"));
+        assert!(cache.contains("b"));
+        assert!(cache.contains("c"));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.take_evicted(), 1);
+        assert_eq!(cache.take_evicted(), 0);
+        assert_eq!(cache.memory_usage(), 2 * TimedLruCache::<&str>::ENTRY_SIZE);
+    }
+
+    #[test]
+    fn test_timed_cache_expires_entries() {
+        let limit = NonZeroUsize::new(5).unwrap();
+        let ttl = Duration::from_secs(10);
+        let mut cache = TimedLruCache::new(limit, Some(ttl));
+        let start = Instant::now();
+        assert!(cache.insert_at("old", start));
+        assert!(!cache.insert_at("old", start + Duration::from_secs(5)));
+        assert!(cache.insert_at("new", start + Duration::from_secs(5)));
+
+        // `old` expired and is re-inserted
+        assert!(cache.insert_at("old", start + ttl));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.take_evicted(), 1);
+
+        // `new` expired and is evicted on the next insert
+        assert!(cache.insert_at("other", start + Duration::from_secs(15)));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.take_evicted(), 1);
+
+        // re-inserting `old` restarts its time-to-live
+        assert!(!cache.insert_at("old", start + Duration::from_secs(19)));
+        assert!(cache.insert_at("another", start + Duration::from_secs(25)));
+        assert!(!cache.insert_at("old", start + Duration::from_secs(25)));
+        // only `other` expired
+        assert_eq!(cache.take_evicted(), 1);
+    }
+
+    #[test]
+    fn test_timed_cache_evicts_least_recently_used() {
+        let limit = NonZeroUsize::new(3).unwrap();
+        let mut cache = TimedLruCache::new(limit, None);
+        cache.extend(["a", "b", "c"]);
+
+        // `a` is seen again and becomes the most recently used entry
+        assert!(!cache.insert("a"));
+        assert!(cache.insert("d"));
+        assert!(!cache.contains("b"));
+        assert!(cache.insert("e"));
+        assert!(!cache.contains("c"));
+        assert!(cache.contains("a"));
+        assert!(cache.contains("d"));
+        assert!(cache.contains("e"));
+        assert_eq!(cache.take_evicted(), 2);
+    }
 }
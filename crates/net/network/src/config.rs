@@ -1,16 +1,20 @@
 //! Network config support
 
 use crate::{
+    discv5::Discv5Config,
     error::NetworkError,
     import::{BlockImport, ProofOfStakeBlockImport},
+    mdns::MdnsConfig,
     peers::PeersConfig,
-    session::SessionsConfig,
+    proxy::ProxyConfig,
+    session::{SessionsConfig, SessionsPreset},
     NetworkHandle, NetworkManager,
 };
 use reth_discv4::{Discv4Config, Discv4ConfigBuilder, DEFAULT_DISCOVERY_ADDRESS};
 use reth_dns_discovery::DnsDiscoveryConfig;
 use reth_ecies::util::pk2id;
 use reth_eth_wire::{HelloMessage, Status};
+use reth_net_nat::PortMapper;
 use reth_primitives::{
     mainnet_nodes, sepolia_nodes, ChainSpec, ForkFilter, Head, NodeRecord, PeerId, MAINNET,
 };
@@ -41,6 +45,10 @@ pub struct NetworkConfig<C> {
     pub dns_discovery_config: Option<DnsDiscoveryConfig>,
     /// How to set up discovery.
     pub discovery_v4_config: Option<Discv4Config>,
+    /// How to set up discovery over discv5.
+    pub discovery_v5_config: Option<Discv5Config>,
+    /// How to set up discovery on the local network via mDNS.
+    pub mdns_config: Option<MdnsConfig>,
     /// Address to use for discovery
     pub discovery_addr: SocketAddr,
     /// Address to listen for incoming connections
@@ -68,6 +76,10 @@ pub struct NetworkConfig<C> {
     pub status: Status,
     /// Sets the hello message for the p2p handshake in RLPx
     pub hello_message: HelloMessage,
+    /// The proxy to tunnel outbound connections through, if any.
+    pub proxy: Option<ProxyConfig>,
+    /// How to map the listener and discovery ports on the gateway, if at all.
+    pub port_mapper: Option<PortMapper>,
 }
 
 // === impl NetworkConfig ===
@@ -126,6 +138,10 @@ pub struct NetworkConfigBuilder {
     dns_discovery_config: Option<DnsDiscoveryConfig>,
     /// How to set up discovery.
     discovery_v4_builder: Option<Discv4ConfigBuilder>,
+    /// How to set up discovery over discv5.
+    discovery_v5_config: Option<Discv5Config>,
+    /// How to set up discovery on the local network via mDNS.
+    mdns_config: Option<MdnsConfig>,
     /// All boot nodes to start network discovery with.
     boot_nodes: HashSet<NodeRecord>,
     /// Address to use for discovery
@@ -136,6 +152,8 @@ pub struct NetworkConfigBuilder {
     peers_config: Option<PeersConfig>,
     /// How to configure the sessions manager
     sessions_config: Option<SessionsConfig>,
+    /// The preset that is used if no sessions config is set.
+    sessions_preset: SessionsPreset,
     /// The network's chain spec
     chain_spec: Arc<ChainSpec>,
     /// The default mode of the network.
@@ -147,6 +165,10 @@ pub struct NetworkConfigBuilder {
     hello_message: Option<HelloMessage>,
     /// Head used to start set for the fork filter and status.
     head: Option<Head>,
+    /// The proxy to tunnel outbound connections through.
+    proxy: Option<ProxyConfig>,
+    /// How to map the listener and discovery ports on the gateway.
+    port_mapper: Option<PortMapper>,
 }
 
 // === impl NetworkConfigBuilder ===
@@ -158,16 +180,21 @@ impl NetworkConfigBuilder {
             secret_key,
             dns_discovery_config: Some(Default::default()),
             discovery_v4_builder: Some(Default::default()),
+            discovery_v5_config: None,
+            mdns_config: None,
             boot_nodes: Default::default(),
             discovery_addr: None,
             listener_addr: None,
             peers_config: None,
             sessions_config: None,
+            sessions_preset: Default::default(),
             chain_spec: MAINNET.clone(),
             network_mode: Default::default(),
             executor: None,
             hello_message: None,
             head: None,
+            proxy: None,
+            port_mapper: None,
         }
     }
 
@@ -215,12 +242,38 @@ impl NetworkConfigBuilder {
         self
     }
 
+    /// Tunnels all outbound connections through the given proxy.
+    ///
+    /// Inbound connections are not affected.
+    pub fn proxy(mut self, proxy: ProxyConfig) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Maps the listener and discovery ports on the gateway with the given [PortMapper] so that
+    /// the node can accept inbound connections from behind a NAT.
+    ///
+    /// This is disabled by default.
+    pub fn port_mapping(mut self, mapper: PortMapper) -> Self {
+        self.port_mapper = (mapper != PortMapper::None).then_some(mapper);
+        self
+    }
+
     /// Set a custom peer config for how peers are handled
     pub fn peer_config(mut self, config: PeersConfig) -> Self {
         self.peers_config = Some(config);
         self
     }
 
+    /// Only connect to and accept sessions with trusted peers.
+    ///
+    /// See also [PeersConfig::connect_trusted_nodes_only].
+    pub fn trusted_nodes_only(mut self, trusted_only: bool) -> Self {
+        let config = self.peers_config.take().unwrap_or_default();
+        self.peers_config = Some(config.with_connect_trusted_nodes_only(trusted_only));
+        self
+    }
+
     /// Sets the executor to use for spawning tasks.
     ///
     /// If `None`, then [tokio::spawn] is used for spawning tasks.
@@ -230,11 +283,24 @@ impl NetworkConfigBuilder {
     }
 
     /// Sets a custom config for how sessions are handled.
+    ///
+    /// See [SessionsConfig::low_resource] and [SessionsConfig::high_throughput] for presets. The
+    /// config is validated when the [NetworkManager](crate::NetworkManager) is created.
+    ///
+    /// By default, the [SessionsPreset] is used.
     pub fn sessions_config(mut self, config: SessionsConfig) -> Self {
         self.sessions_config = Some(config);
         self
     }
 
+    /// Sets the [SessionsPreset] that is used if no [SessionsConfig] is set.
+    ///
+    /// By default, the session event buffer is sized for the configured number of peers.
+    pub fn sessions_preset(mut self, preset: SessionsPreset) -> Self {
+        self.sessions_preset = preset;
+        self
+    }
+
     /// Sets the discovery and listener address
     ///
     /// This is a convenience function for both [NetworkConfigBuilder::listener_addr] and
@@ -282,12 +348,28 @@ impl NetworkConfigBuilder {
         self
     }
 
+    /// Enables discovery over discv5 with the given config.
+    ///
+    /// This is disabled by default.
+    pub fn discovery_v5(mut self, config: Discv5Config) -> Self {
+        self.discovery_v5_config = Some(config);
+        self
+    }
+
     /// Sets the dns discovery config to use.
     pub fn dns_discovery(mut self, config: DnsDiscoveryConfig) -> Self {
         self.dns_discovery_config = Some(config);
         self
     }
 
+    /// Enables discovery on the local network via mDNS with the given config.
+    ///
+    /// This is disabled by default.
+    pub fn mdns(mut self, config: MdnsConfig) -> Self {
+        self.mdns_config = Some(config);
+        self
+    }
+
     /// Convenience function for setting [Self::boot_nodes] to the mainnet boot nodes.
     pub fn mainnet_boot_nodes(self) -> Self {
         self.boot_nodes(mainnet_nodes())
@@ -310,9 +392,24 @@ impl NetworkConfigBuilder {
         self
     }
 
+    /// Disable the mDNS discovery.
+    pub fn disable_mdns(mut self) -> Self {
+        self.mdns_config = None;
+        self
+    }
+
+    /// Disable the discv5 discovery.
+    pub fn disable_discv5_discovery(mut self) -> Self {
+        self.discovery_v5_config = None;
+        self
+    }
+
     /// Disables all discovery.
     pub fn disable_discovery(self) -> Self {
-        self.disable_discv4_discovery().disable_dns_discovery()
+        self.disable_discv4_discovery()
+            .disable_discv5_discovery()
+            .disable_dns_discovery()
+            .disable_mdns()
     }
 
     /// Disables all discovery if the given condition is true.
@@ -360,20 +457,32 @@ impl NetworkConfigBuilder {
             secret_key,
             mut dns_discovery_config,
             discovery_v4_builder,
+            discovery_v5_config,
+            mdns_config,
             boot_nodes,
             discovery_addr,
             listener_addr,
             peers_config,
             sessions_config,
+            sessions_preset,
             chain_spec,
             network_mode,
             executor,
             hello_message,
             head,
+            proxy,
+            port_mapper,
         } = self;
 
         let listener_addr = listener_addr.unwrap_or(DEFAULT_DISCOVERY_ADDRESS);
 
+        let peers_config = peers_config.unwrap_or_default();
+        let sessions_config = sessions_config.unwrap_or_else(|| {
+            let num_peers = peers_config.connection_info.max_inbound() +
+                peers_config.connection_info.max_outbound();
+            sessions_preset.config(num_peers)
+        });
+
         let mut hello_message =
             hello_message.unwrap_or_else(|| HelloMessage::builder(peer_id).build());
         hello_message.port = listener_addr.port();
@@ -409,10 +518,12 @@ impl NetworkConfigBuilder {
             boot_nodes,
             dns_discovery_config,
             discovery_v4_config: discovery_v4_builder.map(|builder| builder.build()),
+            discovery_v5_config,
+            mdns_config,
             discovery_addr: discovery_addr.unwrap_or(DEFAULT_DISCOVERY_ADDRESS),
             listener_addr,
-            peers_config: peers_config.unwrap_or_default(),
-            sessions_config: sessions_config.unwrap_or_default(),
+            peers_config,
+            sessions_config,
             chain_spec,
             block_import: Box::<ProofOfStakeBlockImport>::default(),
             network_mode,
@@ -420,6 +531,8 @@ impl NetworkConfigBuilder {
             status,
             hello_message,
             fork_filter,
+            proxy,
+            port_mapper,
         }
     }
 }
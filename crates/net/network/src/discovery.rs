@@ -1,19 +1,23 @@
 //! Discovery support for the network.
 
 use crate::{
+    discv5::{update_fork_id, Discv5Config, Discv5Service},
     error::{NetworkError, ServiceKind},
     manager::DiscoveredEvent,
+    mdns::{MdnsConfig, MdnsDiscoveryService, MDNS_PORT},
 };
+use discv5::Discv5;
 use futures::StreamExt;
 use reth_discv4::{DiscoveryUpdate, Discv4, Discv4Config, EnrForkIdEntry};
 use reth_dns_discovery::{
     DnsDiscoveryConfig, DnsDiscoveryHandle, DnsDiscoveryService, DnsNodeRecordUpdate, DnsResolver,
 };
+use reth_net_nat::{PortMapping, PortMappingProtocol};
 use reth_primitives::{ForkId, NodeRecord, PeerId};
 use secp256k1::SecretKey;
 use std::{
     collections::{hash_map::Entry, HashMap, VecDeque},
-    net::{IpAddr, SocketAddr},
+    net::{IpAddr, Ipv4Addr, SocketAddr},
     sync::Arc,
     task::{Context, Poll},
 };
@@ -38,12 +42,22 @@ pub struct Discovery {
     discv4_updates: Option<ReceiverStream<DiscoveryUpdate>>,
     /// The handle to the spawned discv4 service
     _discv4_service: Option<JoinHandle<()>>,
+    /// The running discv5 server.
+    discv5: Option<Arc<Discv5>>,
+    /// Nodes discovered via discv5.
+    discv5_updates: Option<ReceiverStream<(NodeRecord, Option<ForkId>)>>,
+    /// The handle to the spawned discv5 service
+    _discv5_service: Option<JoinHandle<()>>,
     /// Handler to interact with the DNS discovery service
     _dns_discovery: Option<DnsDiscoveryHandle>,
     /// Updates from the DNS discovery service.
     dns_discovery_updates: Option<ReceiverStream<DnsNodeRecordUpdate>>,
     /// The handle to the spawned DNS discovery service
     _dns_disc_service: Option<JoinHandle<()>>,
+    /// Nodes discovered on the local network via mDNS.
+    mdns_updates: Option<ReceiverStream<NodeRecord>>,
+    /// The handle to the spawned mDNS discovery service
+    _mdns_service: Option<JoinHandle<()>>,
     /// Events buffered until polled.
     queued_events: VecDeque<DiscoveryEvent>,
     /// List of listeners subscribed to discovery events.
@@ -55,11 +69,15 @@ impl Discovery {
     ///
     /// This will spawn the [`reth_discv4::Discv4Service`] onto a new task and establish a listener
     /// channel to receive all discovered nodes.
+    ///
+    /// Nodes discovered via discv5 are merged into the same [DiscoveryEvent]s.
     pub async fn new(
         discovery_addr: SocketAddr,
         sk: SecretKey,
         discv4_config: Option<Discv4Config>,
+        discv5_config: Option<Discv5Config>,
         dns_discovery_config: Option<DnsDiscoveryConfig>,
+        mdns_config: Option<MdnsConfig>,
     ) -> Result<Self, NetworkError> {
         // setup discv4
         let local_enr = NodeRecord::from_secret_key(discovery_addr, &sk);
@@ -76,6 +94,17 @@ impl Discovery {
             (None, None, None)
         };
 
+        // setup discv5
+        let (discv5, discv5_updates, _discv5_service) = if let Some(config) = discv5_config {
+            let addr = config.addr;
+            let (service, updates) = Discv5Service::start(&sk, local_enr.tcp_port, config)
+                .await
+                .map_err(|err| NetworkError::from_io_error(err, ServiceKind::Discovery(addr)))?;
+            (Some(service.discv5()), Some(updates), Some(service.spawn()))
+        } else {
+            (None, None, None)
+        };
+
         // setup DNS discovery
         let (_dns_discovery, dns_discovery_updates, _dns_disc_service) =
             if let Some(dns_config) = dns_discovery_config {
@@ -90,17 +119,36 @@ impl Discovery {
                 (None, None, None)
             };
 
+        // setup local network discovery
+        let (mdns_updates, _mdns_service) = if let Some(mdns_config) = mdns_config {
+            let (service, updates) =
+                MdnsDiscoveryService::bind(local_enr, mdns_config).map_err(|err| {
+                    NetworkError::from_io_error(
+                        err,
+                        ServiceKind::Discovery((Ipv4Addr::UNSPECIFIED, MDNS_PORT).into()),
+                    )
+                })?;
+            (Some(updates), Some(service.spawn()))
+        } else {
+            (None, None)
+        };
+
         Ok(Self {
             discovery_listeners: Default::default(),
             local_enr,
             discv4,
             discv4_updates,
             _discv4_service,
+            discv5,
+            discv5_updates,
+            _discv5_service,
             discovered_nodes: Default::default(),
             queued_events: Default::default(),
             _dns_disc_service,
             _dns_discovery,
             dns_discovery_updates,
+            mdns_updates,
+            _mdns_service,
         })
     }
 
@@ -115,13 +163,16 @@ impl Discovery {
         self.discovery_listeners.retain_mut(|listener| listener.send(event.clone()).is_ok());
     }
 
-    /// Updates the `eth:ForkId` field in discv4.
+    /// Updates the `eth:ForkId` field in discv4 and discv5.
     #[allow(unused)]
     pub(crate) fn update_fork_id(&self, fork_id: ForkId) {
         if let Some(discv4) = &self.discv4 {
             // use forward-compatible forkid entry
             discv4.set_eip868_rlp("eth".as_bytes().to_vec(), EnrForkIdEntry::from(fork_id))
         }
+        if let Some(discv5) = &self.discv5 {
+            update_fork_id(discv5, fork_id)
+        }
     }
 
     /// Bans the [`IpAddr`] in the discovery service.
@@ -138,6 +189,22 @@ impl Discovery {
         }
     }
 
+    /// Announces the external address of a port mapping in the discovery service.
+    pub(crate) fn on_port_mapping(&self, mapping: PortMapping) {
+        if let Some(discv4) = &self.discv4 {
+            match mapping.protocol {
+                PortMappingProtocol::Tcp => discv4.set_tcp_port(mapping.external.port()),
+                PortMappingProtocol::Udp => discv4.set_udp_port(mapping.external.port()),
+            }
+            discv4.set_external_ip_addr(mapping.external.ip());
+        }
+    }
+
+    /// Returns the address of the discv4 socket, if discv4 is enabled.
+    pub(crate) fn discv4_local_addr(&self) -> Option<SocketAddr> {
+        self.discv4.as_ref().map(|discv4| discv4.local_addr())
+    }
+
     /// Returns the id with which the local identifies itself in the network
     pub(crate) fn local_id(&self) -> PeerId {
         self.local_enr.id
@@ -202,6 +269,12 @@ impl Discovery {
                 self.on_discv4_update(update)
             }
 
+            while let Some(Poll::Ready(Some((record, fork_id)))) =
+                self.discv5_updates.as_mut().map(|updates| updates.poll_next_unpin(cx))
+            {
+                self.on_node_record_update(record, fork_id);
+            }
+
             while let Some(Poll::Ready(Some(update))) =
                 self.dns_discovery_updates.as_mut().map(|updates| updates.poll_next_unpin(cx))
             {
@@ -209,6 +282,13 @@ impl Discovery {
                 self.on_node_record_update(update.node_record, update.fork_id);
             }
 
+            while let Some(Poll::Ready(Some(record))) =
+                self.mdns_updates.as_mut().map(|updates| updates.poll_next_unpin(cx))
+            {
+                self.add_discv4_node(record);
+                self.on_node_record_update(record, None);
+            }
+
             if self.queued_events.is_empty() {
                 return Poll::Pending
             }
@@ -228,7 +308,7 @@ impl Discovery {
         Self {
             discovered_nodes: Default::default(),
             local_enr: NodeRecord {
-                address: IpAddr::V4(std::net::Ipv4Addr::UNSPECIFIED),
+                address: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
                 tcp_port: 0,
                 udp_port: 0,
                 id: PeerId::random(),
@@ -237,9 +317,14 @@ impl Discovery {
             discv4_updates: Default::default(),
             queued_events: Default::default(),
             _discv4_service: Default::default(),
+            discv5: None,
+            discv5_updates: None,
+            _discv5_service: None,
             _dns_discovery: None,
             dns_discovery_updates: None,
             _dns_disc_service: None,
+            mdns_updates: None,
+            _mdns_service: None,
             discovery_listeners: Default::default(),
         }
     }
@@ -259,16 +344,22 @@ mod tests {
     use super::*;
     use rand::thread_rng;
     use secp256k1::SECP256K1;
-    use std::net::{Ipv4Addr, SocketAddrV4};
+    use std::net::SocketAddrV4;
 
     #[tokio::test(flavor = "multi_thread")]
     async fn test_discovery_setup() {
         let mut rng = thread_rng();
         let (secret_key, _) = SECP256K1.generate_keypair(&mut rng);
         let discovery_addr = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0));
-        let _discovery =
-            Discovery::new(discovery_addr, secret_key, Default::default(), Default::default())
-                .await
-                .unwrap();
+        let _discovery = Discovery::new(
+            discovery_addr,
+            secret_key,
+            Default::default(),
+            None,
+            Default::default(),
+            None,
+        )
+        .await
+        .unwrap();
     }
 }
@@ -0,0 +1,272 @@
+//! Discovery over the [discv5](https://github.com/ethereum/devp2p/blob/master/discv5/discv5.md)
+//! protocol.
+//!
+//! The local ENR advertises the TCP port of the RLPx listener and the `eth` fork id, see
+//! [EIP-2124](https://eips.ethereum.org/EIPS/eip-2124). Discovered nodes without a TCP port are
+//! not execution layer nodes and are ignored.
+
+use discv5::{
+    enr::{CombinedKey, EnrBuilder, EnrPublicKey, NodeId},
+    Discv5, Discv5Event, ListenConfig,
+};
+use reth_discv4::EnrForkIdEntry;
+use reth_primitives::{ForkId, NodeRecord, PeerId};
+use reth_rlp::{Decodable, Encodable};
+use secp256k1::SecretKey;
+use std::{
+    io,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::{sync::mpsc, task::JoinHandle};
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::{debug, trace};
+
+pub use discv5::Enr;
+
+/// The default port of the discv5 service.
+///
+/// The port must differ from the discv4 port, because both protocols are served on their own
+/// socket.
+pub const DEFAULT_DISCV5_PORT: u16 = 9000;
+
+/// Settings for the [Discv5Service].
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Discv5Config {
+    /// The UDP address the service listens on.
+    ///
+    /// Default: `0.0.0.0:9000`
+    pub addr: SocketAddr,
+    /// The nodes to bootstrap the routing table with.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub bootstrap_nodes: Vec<Enr>,
+    /// How often a lookup for random nodes is started.
+    ///
+    /// Default: 20s
+    #[cfg_attr(feature = "serde", serde(with = "humantime_serde"))]
+    pub lookup_interval: Duration,
+    /// The fork id advertised in the local ENR under the `eth` key.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub fork_id: Option<ForkId>,
+}
+
+impl Discv5Config {
+    /// Sets the nodes to bootstrap the routing table with.
+    pub fn with_bootstrap_nodes(mut self, nodes: impl IntoIterator<Item = Enr>) -> Self {
+        self.bootstrap_nodes = nodes.into_iter().collect();
+        self
+    }
+
+    /// Sets the port of the address the service listens on.
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.addr.set_port(port);
+        self
+    }
+
+    /// Sets the fork id advertised in the local ENR.
+    pub fn with_fork_id(mut self, fork_id: ForkId) -> Self {
+        self.fork_id = Some(fork_id);
+        self
+    }
+}
+
+impl Default for Discv5Config {
+    fn default() -> Self {
+        Self {
+            addr: SocketAddr::from((Ipv4Addr::UNSPECIFIED, DEFAULT_DISCV5_PORT)),
+            bootstrap_nodes: Vec::new(),
+            lookup_interval: Duration::from_secs(20),
+            fork_id: None,
+        }
+    }
+}
+
+/// A service that runs random lookups over discv5 and reports the discovered nodes.
+pub struct Discv5Service {
+    /// The running discv5 server.
+    discv5: Arc<Discv5>,
+    /// Events of the discv5 server.
+    events: mpsc::Receiver<Discv5Event>,
+    /// How often a lookup for random nodes is started.
+    lookup_interval: Duration,
+    /// Sender half of the discovered nodes channel.
+    updates: mpsc::Sender<(NodeRecord, Option<ForkId>)>,
+}
+
+impl Discv5Service {
+    /// Starts the discv5 server and returns the service and a stream of discovered nodes with
+    /// the fork id of their ENR.
+    ///
+    /// The local ENR advertises the given TCP port of the RLPx listener.
+    pub async fn start(
+        secret_key: &SecretKey,
+        tcp_port: u16,
+        config: Discv5Config,
+    ) -> io::Result<(Self, ReceiverStream<(NodeRecord, Option<ForkId>)>)> {
+        let Discv5Config { addr, bootstrap_nodes, lookup_interval, fork_id } = config;
+
+        let key = CombinedKey::secp256k1_from_bytes(&mut secret_key.secret_bytes())
+            .map_err(|err| other_err(format!("invalid secret key: {err:?}")))?;
+        let mut builder = EnrBuilder::new("v4");
+        match addr.ip() {
+            IpAddr::V4(ip) if !ip.is_unspecified() => {
+                builder.ip4(ip).tcp4(tcp_port);
+            }
+            IpAddr::V6(ip) if !ip.is_unspecified() => {
+                builder.ip6(ip).tcp6(tcp_port);
+            }
+            IpAddr::V4(_) => {
+                // the address is learned from the PONG responses of other nodes
+                builder.tcp4(tcp_port);
+            }
+            IpAddr::V6(_) => {
+                builder.tcp6(tcp_port);
+            }
+        }
+        if let Some(fork_id) = fork_id {
+            builder.add_value_rlp("eth", encode_fork_id(fork_id).into());
+        }
+        let local_enr = builder.build(&key).map_err(|err| other_err(format!("{err:?}")))?;
+
+        let discv5_config =
+            discv5::Discv5ConfigBuilder::new(ListenConfig::from_ip(addr.ip(), addr.port())).build();
+        let mut discv5 = Discv5::new(local_enr, key, discv5_config).map_err(other_err)?;
+        for node in bootstrap_nodes {
+            if let Err(err) = discv5.add_enr(node) {
+                debug!(target: "net::discv5", %err, "Failed to add bootstrap node");
+            }
+        }
+        discv5.start().await.map_err(|err| match err {
+            discv5::Discv5Error::Io(err) => err,
+            err => other_err(format!("{err:?}")),
+        })?;
+        let events = discv5.event_stream().await.map_err(|err| other_err(format!("{err:?}")))?;
+
+        let (updates, rx) = mpsc::channel(256);
+        let service = Self { discv5: Arc::new(discv5), events, lookup_interval, updates };
+        Ok((service, ReceiverStream::new(rx)))
+    }
+
+    /// Returns the running discv5 server.
+    pub fn discv5(&self) -> Arc<Discv5> {
+        Arc::clone(&self.discv5)
+    }
+
+    /// Spawns this service onto a new task.
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::task::spawn(async move { self.run().await })
+    }
+
+    /// Runs the service until the receiver of the updates is dropped.
+    async fn run(mut self) {
+        let mut interval = tokio::time::interval(self.lookup_interval);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    let discv5 = Arc::clone(&self.discv5);
+                    // the nodes found by the lookup are reported as events
+                    tokio::task::spawn(async move {
+                        if let Err(err) = discv5.find_node(NodeId::random()).await {
+                            trace!(target: "net::discv5", ?err, "Lookup failed");
+                        }
+                    });
+                }
+                event = self.events.recv() => {
+                    let enr = match event {
+                        Some(Discv5Event::Discovered(enr)) => enr,
+                        Some(Discv5Event::SessionEstablished(enr, _)) => enr,
+                        Some(_) => continue,
+                        None => return,
+                    };
+                    let Some(node) = node_record(&enr) else { continue };
+                    trace!(target: "net::discv5", ?node, "Discovered node");
+                    if self.updates.send((node, fork_id(&enr))).await.is_err() {
+                        return
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for Discv5Service {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Discv5Service")
+            .field("local_enr", &self.discv5.local_enr())
+            .field("lookup_interval", &self.lookup_interval)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Updates the fork id advertised in the local ENR.
+pub(crate) fn update_fork_id(discv5: &Discv5, fork_id: ForkId) {
+    if let Err(err) = discv5.enr_insert("eth", &encode_fork_id(fork_id)) {
+        debug!(target: "net::discv5", ?err, "Failed to update fork id");
+    }
+}
+
+/// Returns the node record of an ENR that advertises a TCP port.
+///
+/// IPv4 addresses are preferred.
+fn node_record(enr: &Enr) -> Option<NodeRecord> {
+    let (address, tcp_port, udp_port) = match (enr.ip4(), enr.tcp4()) {
+        (Some(ip), Some(tcp)) => (IpAddr::V4(ip), tcp, enr.udp4().unwrap_or(tcp)),
+        _ => {
+            let (ip, tcp) = enr.ip6().zip(enr.tcp6())?;
+            (IpAddr::V6(ip), tcp, enr.udp6().unwrap_or(tcp))
+        }
+    };
+    let id = PeerId::from_slice(&enr.public_key().encode_uncompressed());
+    Some(NodeRecord { address, tcp_port, udp_port, id })
+}
+
+/// Returns the fork id of the `eth` entry of the ENR.
+fn fork_id(enr: &Enr) -> Option<ForkId> {
+    let mut entry = enr.get("eth")?;
+    EnrForkIdEntry::decode(&mut entry).ok().map(|entry| entry.fork_id)
+}
+
+fn encode_fork_id(fork_id: ForkId) -> Vec<u8> {
+    let mut buf = Vec::new();
+    EnrForkIdEntry::from(fork_id).encode(&mut buf);
+    buf
+}
+
+fn other_err(err: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, err.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::ForkHash;
+    use secp256k1::SECP256K1;
+
+    #[test]
+    fn node_record_from_enr() {
+        let (secret_key, public_key) = SECP256K1.generate_keypair(&mut rand::thread_rng());
+        let key = CombinedKey::secp256k1_from_bytes(&mut secret_key.secret_bytes()).unwrap();
+        let fork_id = ForkId { hash: ForkHash([0xdc, 0xe9, 0x6c, 0x2d]), next: 0 };
+        let enr = EnrBuilder::new("v4")
+            .ip4(Ipv4Addr::new(10, 0, 0, 1))
+            .tcp4(30303)
+            .udp4(9000)
+            .add_value_rlp("eth", encode_fork_id(fork_id).into())
+            .build(&key)
+            .unwrap();
+
+        let node = node_record(&enr).unwrap();
+        assert_eq!(node.address, IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)));
+        assert_eq!(node.tcp_port, 30303);
+        assert_eq!(node.udp_port, 9000);
+        assert_eq!(node.id, PeerId::from_slice(&public_key.serialize_uncompressed()[1..]));
+        assert_eq!(super::fork_id(&enr), Some(fork_id));
+
+        // nodes without a TCP port are not execution layer nodes
+        let enr = EnrBuilder::new("v4").ip4(Ipv4Addr::new(10, 0, 0, 1)).build(&key).unwrap();
+        assert!(node_record(&enr).is_none());
+        assert_eq!(super::fork_id(&enr), None);
+    }
+}
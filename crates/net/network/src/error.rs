@@ -1,6 +1,6 @@
 //! Possible errors when interacting with the network.
 
-use crate::session::PendingSessionHandshakeError;
+use crate::session::{PendingSessionHandshakeError, SessionsConfigError};
 use reth_dns_discovery::resolver::ResolveError;
 use reth_eth_wire::{
     errors::{EthHandshakeError, EthStreamError, P2PHandshakeError, P2PStreamError},
@@ -48,6 +48,9 @@ pub enum NetworkError {
     /// See also [DnsResolver](reth_dns_discovery::DnsResolver::from_system_conf)
     #[error("Failed to configure DNS resolver: {0}")]
     DnsResolver(#[from] ResolveError),
+    /// The configuration of the sessions is invalid.
+    #[error("Invalid sessions config: {0}")]
+    InvalidSessionsConfig(#[from] SessionsConfigError),
 }
 
 impl NetworkError {
@@ -223,6 +226,7 @@ impl SessionError for PendingSessionHandshakeError {
         match self {
             PendingSessionHandshakeError::Eth(eth) => eth.merits_discovery_ban(),
             PendingSessionHandshakeError::Ecies(_) => true,
+            PendingSessionHandshakeError::ClientVersion { .. } => false,
         }
     }
 
@@ -230,6 +234,8 @@ impl SessionError for PendingSessionHandshakeError {
         match self {
             PendingSessionHandshakeError::Eth(eth) => eth.is_fatal_protocol_error(),
             PendingSessionHandshakeError::Ecies(_) => true,
+            // the peer is removed and banned
+            PendingSessionHandshakeError::ClientVersion { .. } => true,
         }
     }
 
@@ -237,6 +243,7 @@ impl SessionError for PendingSessionHandshakeError {
         match self {
             PendingSessionHandshakeError::Eth(eth) => eth.should_backoff(),
             PendingSessionHandshakeError::Ecies(_) => Some(BackoffKind::Low),
+            PendingSessionHandshakeError::ClientVersion { .. } => None,
         }
     }
 }
@@ -6,18 +6,25 @@ use reth_eth_wire::{
     BlockBodies, BlockHeaders, GetBlockBodies, GetBlockHeaders, GetNodeData, GetReceipts, NodeData,
     Receipts,
 };
-use reth_interfaces::p2p::error::RequestResult;
+use reth_interfaces::p2p::error::{RequestError, RequestResult};
+use reth_network_api::ReputationChangeKind;
 use reth_primitives::{BlockBody, BlockHashOrNumber, Header, HeadersDirection, PeerId};
 use reth_provider::{BlockReader, HeaderProvider, ReceiptProvider};
 use std::{
     borrow::Borrow,
+    collections::HashMap,
     future::Future,
     hash::Hash,
     pin::Pin,
     task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use tokio::{
+    sync::{mpsc::Receiver, oneshot},
+    time::Interval,
 };
-use tokio::sync::{mpsc::Receiver, oneshot};
 use tokio_stream::wrappers::ReceiverStream;
+use tracing::trace;
 
 // Limits: <https://github.com/ethereum/go-ethereum/blob/b0d44338bbcefee044f1f635a84487cbbd8f0538/eth/protocols/eth/handler.go#L34-L56>
 
@@ -50,6 +57,105 @@ const SOFT_RESPONSE_LIMIT: usize = 2 * 1024 * 1024;
 /// Estimated size in bytes of an RLP encoded header.
 const APPROX_HEADER_SIZE: usize = 500;
 
+/// Interval at which the quotas of idle peers are dropped.
+const QUOTA_CLEANUP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Per peer rate limits of the [`EthRequestHandler`].
+///
+/// Every peer has a token bucket for requests and one for the bytes of the responses, both refill
+/// at the configured rate and hold at most one second worth of tokens. Requests of a peer that
+/// exceeds either are dropped without a response. A limit of `0` disables it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct EthRequestHandlerConfig {
+    /// Requests per second a peer may send, `0` is unlimited.
+    pub max_requests_per_sec: u32,
+    /// Bytes of responses per second a peer may request, `0` is unlimited.
+    ///
+    /// The size of responses is estimated.
+    pub max_bytes_per_sec: u64,
+    /// Number of rejected requests after which the reputation of the peer is reduced, `0` never
+    /// reduces the reputation.
+    pub max_rejected_requests: u32,
+}
+
+impl EthRequestHandlerConfig {
+    /// Sets the requests per second a peer may send.
+    pub fn with_max_requests_per_sec(mut self, max_requests_per_sec: u32) -> Self {
+        self.max_requests_per_sec = max_requests_per_sec;
+        self
+    }
+
+    /// Sets the bytes of responses per second a peer may request.
+    pub fn with_max_bytes_per_sec(mut self, max_bytes_per_sec: u64) -> Self {
+        self.max_bytes_per_sec = max_bytes_per_sec;
+        self
+    }
+
+    /// Sets the number of rejected requests after which the reputation of the peer is reduced.
+    pub fn with_max_rejected_requests(mut self, max_rejected_requests: u32) -> Self {
+        self.max_rejected_requests = max_rejected_requests;
+        self
+    }
+
+    /// Returns true if neither the requests nor the bytes of a peer are limited.
+    fn is_unlimited(&self) -> bool {
+        self.max_requests_per_sec == 0 && self.max_bytes_per_sec == 0
+    }
+}
+
+impl Default for EthRequestHandlerConfig {
+    fn default() -> Self {
+        Self {
+            max_requests_per_sec: 50,
+            // 5 full responses per second
+            max_bytes_per_sec: 5 * SOFT_RESPONSE_LIMIT as u64,
+            max_rejected_requests: 10,
+        }
+    }
+}
+
+/// Token buckets of a single peer.
+#[derive(Debug)]
+struct PeerQuota {
+    /// Available requests.
+    requests: f64,
+    /// Available bytes, negative if the last responses exceeded the quota.
+    bytes: f64,
+    /// When the buckets were last refilled.
+    last_refill: Instant,
+    /// Number of rejected requests since the last reputation change.
+    rejected: u32,
+}
+
+impl PeerQuota {
+    fn new(config: &EthRequestHandlerConfig, now: Instant) -> Self {
+        Self {
+            requests: config.max_requests_per_sec as f64,
+            bytes: config.max_bytes_per_sec as f64,
+            last_refill: now,
+            rejected: 0,
+        }
+    }
+
+    fn refill(&mut self, config: &EthRequestHandlerConfig, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        let max_requests = config.max_requests_per_sec as f64;
+        let max_bytes = config.max_bytes_per_sec as f64;
+        self.requests = (self.requests + elapsed * max_requests).min(max_requests);
+        self.bytes = (self.bytes + elapsed * max_bytes).min(max_bytes);
+        self.last_refill = now;
+    }
+
+    /// Returns true if both buckets are full, i.e. the peer didn't send requests for a while.
+    fn is_idle(&self, config: &EthRequestHandlerConfig, now: Instant) -> bool {
+        now.saturating_duration_since(self.last_refill) >= Duration::from_secs(1) ||
+            (self.requests >= config.max_requests_per_sec as f64 &&
+                self.bytes >= config.max_bytes_per_sec as f64)
+    }
+}
+
 /// Manages eth related requests on top of the p2p network.
 ///
 /// This can be spawned to another task and is supposed to be run as background service.
@@ -57,22 +163,80 @@ const APPROX_HEADER_SIZE: usize = 500;
 pub struct EthRequestHandler<C> {
     /// The client type that can interact with the chain.
     client: C,
-    /// Used for reporting peers.
-    #[allow(unused)]
-    // TODO use to report spammers
+    /// Used for reporting peers that exceed their rate limit.
     peers: PeersHandle,
     /// Incoming request from the [NetworkManager](crate::NetworkManager).
     incoming_requests: ReceiverStream<IncomingEthRequest>,
     /// Metrics for the eth request handler.
     metrics: EthRequestHandlerMetrics,
+    /// Per peer rate limits.
+    config: EthRequestHandlerConfig,
+    /// Token buckets of the peers that recently sent requests.
+    quotas: HashMap<PeerId, PeerQuota>,
+    /// Interval at which the quotas of idle peers are dropped.
+    quota_cleanup_interval: Interval,
 }
 
 // === impl EthRequestHandler ===
 impl<C> EthRequestHandler<C> {
     /// Create a new instance
     pub fn new(client: C, peers: PeersHandle, incoming: Receiver<IncomingEthRequest>) -> Self {
-        let metrics = Default::default();
-        Self { client, peers, incoming_requests: ReceiverStream::new(incoming), metrics }
+        Self::with_config(client, peers, incoming, Default::default())
+    }
+
+    /// Create a new instance with the given rate limits.
+    pub fn with_config(
+        client: C,
+        peers: PeersHandle,
+        incoming: Receiver<IncomingEthRequest>,
+        config: EthRequestHandlerConfig,
+    ) -> Self {
+        Self {
+            client,
+            peers,
+            incoming_requests: ReceiverStream::new(incoming),
+            metrics: Default::default(),
+            config,
+            quotas: Default::default(),
+            quota_cleanup_interval: tokio::time::interval(QUOTA_CLEANUP_INTERVAL),
+        }
+    }
+
+    /// Takes a request of the peer from its quota and returns false if the quota is exceeded.
+    ///
+    /// If the peer exceeded its quota too often, its reputation is reduced.
+    fn acquire(&mut self, peer_id: PeerId, now: Instant) -> bool {
+        let config = &self.config;
+        if config.is_unlimited() {
+            return true
+        }
+        let quota = self.quotas.entry(peer_id).or_insert_with(|| PeerQuota::new(config, now));
+        quota.refill(config, now);
+
+        let has_request = config.max_requests_per_sec == 0 || quota.requests >= 1.0;
+        let has_bytes = config.max_bytes_per_sec == 0 || quota.bytes > 0.0;
+        if has_request && has_bytes {
+            if config.max_requests_per_sec > 0 {
+                quota.requests -= 1.0;
+            }
+            return true
+        }
+
+        trace!(target: "net::eth", ?peer_id, "Peer exceeded request rate limit");
+        self.metrics.rejected_requests.increment(1);
+        quota.rejected += 1;
+        if config.max_rejected_requests > 0 && quota.rejected >= config.max_rejected_requests {
+            quota.rejected = 0;
+            self.peers.reputation_change(peer_id, ReputationChangeKind::ExceededRateLimit);
+        }
+        false
+    }
+
+    /// Takes the estimated size of a response from the quota of the peer.
+    fn consume_bytes(&mut self, peer_id: &PeerId, bytes: usize) {
+        if let Some(quota) = self.quotas.get_mut(peer_id) {
+            quota.bytes -= bytes as f64;
+        }
     }
 }
 
@@ -147,22 +311,31 @@ where
 
     fn on_headers_request(
         &mut self,
-        _peer_id: PeerId,
+        peer_id: PeerId,
         request: GetBlockHeaders,
         response: oneshot::Sender<RequestResult<BlockHeaders>>,
     ) {
         self.metrics.received_headers_requests.increment(1);
+        if !self.acquire(peer_id, Instant::now()) {
+            let _ = response.send(Err(RequestError::RateLimited));
+            return
+        }
         let headers = self.get_headers_response(request);
+        self.consume_bytes(&peer_id, headers.len() * APPROX_HEADER_SIZE);
         let _ = response.send(Ok(BlockHeaders(headers)));
     }
 
     fn on_bodies_request(
         &mut self,
-        _peer_id: PeerId,
+        peer_id: PeerId,
         request: GetBlockBodies,
         response: oneshot::Sender<RequestResult<BlockBodies>>,
     ) {
         self.metrics.received_bodies_requests.increment(1);
+        if !self.acquire(peer_id, Instant::now()) {
+            let _ = response.send(Err(RequestError::RateLimited));
+            return
+        }
         let mut bodies = Vec::new();
 
         let mut total_bytes = APPROX_BODY_SIZE;
@@ -191,15 +364,20 @@ where
             }
         }
 
+        self.consume_bytes(&peer_id, bodies.len() * APPROX_BODY_SIZE);
         let _ = response.send(Ok(BlockBodies(bodies)));
     }
 
     fn on_receipts_request(
         &mut self,
-        _peer_id: PeerId,
+        peer_id: PeerId,
         request: GetReceipts,
         response: oneshot::Sender<RequestResult<Receipts>>,
     ) {
+        if !self.acquire(peer_id, Instant::now()) {
+            let _ = response.send(Err(RequestError::RateLimited));
+            return
+        }
         let mut receipts = Vec::new();
 
         let mut total_bytes = APPROX_RECEIPT_SIZE;
@@ -229,6 +407,7 @@ where
             }
         }
 
+        self.consume_bytes(&peer_id, receipts.len() * APPROX_RECEIPT_SIZE);
         let _ = response.send(Ok(Receipts(receipts)));
     }
 }
@@ -245,6 +424,12 @@ where
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let this = self.get_mut();
 
+        if this.quota_cleanup_interval.poll_tick(cx).is_ready() {
+            let now = Instant::now();
+            let config = this.config;
+            this.quotas.retain(|_, quota| !quota.is_idle(&config, now));
+        }
+
         loop {
             match this.incoming_requests.poll_next_unpin(cx) {
                 Poll::Pending => return Poll::Pending,
@@ -319,3 +504,64 @@ pub enum IncomingEthRequest {
         response: oneshot::Sender<RequestResult<Receipts>>,
     },
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::peers::PeersManager;
+
+    #[test]
+    fn test_peer_quota_refill() {
+        let config = EthRequestHandlerConfig::default()
+            .with_max_requests_per_sec(2)
+            .with_max_bytes_per_sec(1000);
+        let now = Instant::now();
+        let mut quota = PeerQuota::new(&config, now);
+        assert!(quota.is_idle(&config, now));
+
+        quota.requests -= 2.0;
+        quota.bytes -= 1500.0;
+        assert!(!quota.is_idle(&config, now));
+
+        // half a second refills one request and half of the bytes
+        let now = now + Duration::from_millis(500);
+        quota.refill(&config, now);
+        assert_eq!(quota.requests, 1.0);
+        assert_eq!(quota.bytes, 0.0);
+
+        // the buckets never hold more than one second worth of tokens
+        let now = now + Duration::from_secs(10);
+        quota.refill(&config, now);
+        assert_eq!(quota.requests, 2.0);
+        assert_eq!(quota.bytes, 1000.0);
+        assert!(quota.is_idle(&config, now));
+    }
+
+    #[tokio::test]
+    async fn test_acquire_limits() {
+        let (_tx, rx) = tokio::sync::mpsc::channel(1);
+        let peers = PeersManager::new(Default::default()).handle();
+        let config = EthRequestHandlerConfig::default()
+            .with_max_requests_per_sec(2)
+            .with_max_bytes_per_sec(0);
+        let mut handler = EthRequestHandler::with_config((), peers.clone(), rx, config);
+        let peer_id = PeerId::random();
+        let now = Instant::now();
+
+        // the bytes are not limited
+        handler.consume_bytes(&peer_id, SOFT_RESPONSE_LIMIT);
+        assert!(handler.acquire(peer_id, now));
+        handler.consume_bytes(&peer_id, SOFT_RESPONSE_LIMIT);
+        assert!(handler.acquire(peer_id, now));
+        assert!(!handler.acquire(peer_id, now));
+
+        // a limit of 0 disables rate limiting
+        let (_tx, rx) = tokio::sync::mpsc::channel(1);
+        let config = config.with_max_requests_per_sec(0);
+        let mut handler = EthRequestHandler::with_config((), peers, rx, config);
+        for _ in 0..100 {
+            assert!(handler.acquire(peer_id, now));
+        }
+        assert!(handler.quotas.is_empty());
+    }
+}
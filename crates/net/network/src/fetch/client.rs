@@ -1,6 +1,10 @@
 //! A client implementation that can interact with the network and download data.
 
-use crate::{fetch::DownloadRequest, flattened_response::FlattenedResponse, peers::PeersHandle};
+use crate::{
+    fetch::{DownloadRequest, PeerFilter},
+    flattened_response::FlattenedResponse,
+    peers::PeersHandle,
+};
 use futures::{future, future::Either};
 
 use reth_interfaces::p2p::{
@@ -9,6 +13,8 @@ use reth_interfaces::p2p::{
     error::{PeerRequestResult, RequestError},
     headers::client::{HeadersClient, HeadersRequest},
     priority::Priority,
+    receipts::{ReceiptsClient, ReceiptsFut},
+    transactions::{PooledTransactionsClient, PooledTransactionsFut},
 };
 use reth_network_api::ReputationChangeKind;
 use reth_primitives::{Header, PeerId, H256};
@@ -23,8 +29,10 @@ use tokio::sync::{
 
 /// Front-end API for fetching data from the network.
 ///
-/// Following diagram illustrates how a request, See [`HeadersClient::get_headers`] and
-/// [`BodiesClient::get_block_bodies`] is handled internally.
+/// Following diagram illustrates how a request, See [`HeadersClient::get_headers`],
+/// [`BodiesClient::get_block_bodies`], [`PooledTransactionsClient::get_pooled_transactions`] and
+/// [`ReceiptsClient::get_receipts`]
+/// is handled internally.
 #[cfg_attr(doc, aquamarine::aquamarine)]
 /// ```mermaid
 /// sequenceDiagram
@@ -67,6 +75,24 @@ pub struct FetchClient {
     pub(crate) peers_handle: PeersHandle,
     /// Number of active peer sessions the node's currently handling.
     pub(crate) num_active_peers: Arc<AtomicUsize>,
+    /// Criteria for the peers that requests are sent to.
+    pub(crate) peer_filter: PeerFilter,
+}
+
+impl FetchClient {
+    /// Only sends the requests of this client to peers that match the given [PeerFilter].
+    ///
+    /// Requests are queued until a matching peer is available. If none of the connected peers
+    /// match, requests fail with [RequestError::UnsupportedCapability].
+    pub fn with_peer_filter(mut self, filter: PeerFilter) -> Self {
+        self.peer_filter = filter;
+        self
+    }
+
+    /// Returns the criteria for the peers that requests are sent to.
+    pub fn peer_filter(&self) -> &PeerFilter {
+        &self.peer_filter
+    }
 }
 
 impl DownloadClient for FetchClient {
@@ -95,7 +121,12 @@ impl HeadersClient for FetchClient {
         let (response, rx) = oneshot::channel();
         if self
             .request_tx
-            .send(DownloadRequest::GetBlockHeaders { request, response, priority })
+            .send(DownloadRequest::GetBlockHeaders {
+                request,
+                response,
+                priority,
+                filter: self.peer_filter.clone(),
+            })
             .is_ok()
         {
             Either::Left(FlattenedResponse::from(rx))
@@ -117,7 +148,62 @@ impl BodiesClient for FetchClient {
         let (response, rx) = oneshot::channel();
         if self
             .request_tx
-            .send(DownloadRequest::GetBlockBodies { request, response, priority })
+            .send(DownloadRequest::GetBlockBodies {
+                request,
+                response,
+                priority,
+                filter: self.peer_filter.clone(),
+            })
+            .is_ok()
+        {
+            Box::pin(FlattenedResponse::from(rx))
+        } else {
+            Box::pin(future::err(RequestError::ChannelClosed))
+        }
+    }
+}
+
+impl PooledTransactionsClient for FetchClient {
+    type Output = PooledTransactionsFut;
+
+    /// Sends a `GetPooledTransactions` request to an available peer.
+    fn get_pooled_transactions_with_priority(
+        &self,
+        request: Vec<H256>,
+        priority: Priority,
+    ) -> Self::Output {
+        let (response, rx) = oneshot::channel();
+        if self
+            .request_tx
+            .send(DownloadRequest::GetPooledTransactions {
+                request,
+                response,
+                priority,
+                filter: self.peer_filter.clone(),
+            })
+            .is_ok()
+        {
+            Box::pin(FlattenedResponse::from(rx))
+        } else {
+            Box::pin(future::err(RequestError::ChannelClosed))
+        }
+    }
+}
+
+impl ReceiptsClient for FetchClient {
+    type Output = ReceiptsFut;
+
+    /// Sends a `GetReceipts` request to an available peer.
+    fn get_receipts_with_priority(&self, request: Vec<H256>, priority: Priority) -> Self::Output {
+        let (response, rx) = oneshot::channel();
+        if self
+            .request_tx
+            .send(DownloadRequest::GetReceipts {
+                request,
+                response,
+                priority,
+                filter: self.peer_filter.clone(),
+            })
             .is_ok()
         {
             Box::pin(FlattenedResponse::from(rx))
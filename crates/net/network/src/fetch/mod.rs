@@ -1,15 +1,23 @@
 //! Fetch data from the network.
 
-use crate::{message::BlockRequest, peers::PeersHandle};
+use crate::{
+    message::BlockRequest,
+    peers::{PeersHandle, PeersManager},
+};
 use futures::StreamExt;
-use reth_eth_wire::{GetBlockBodies, GetBlockHeaders};
+use reth_eth_wire::{
+    capability::{Capabilities, Capability},
+    EthVersion, GetBlockBodies, GetBlockHeaders, GetPooledTransactions, GetReceipts,
+};
 use reth_interfaces::p2p::{
     error::{EthResponseValidator, PeerRequestResult, RequestError, RequestResult},
     headers::client::HeadersRequest,
     priority::Priority,
 };
 use reth_network_api::ReputationChangeKind;
-use reth_primitives::{BlockBody, Header, PeerId, H256};
+use reth_primitives::{
+    BlockBody, Header, PeerId, PooledTransactionsElement, ReceiptWithBloom, H256,
+};
 use std::{
     collections::{HashMap, VecDeque},
     sync::{
@@ -17,13 +25,21 @@ use std::{
         Arc,
     },
     task::{Context, Poll},
+    time::{Duration, Instant},
 };
 use tokio::sync::{mpsc, mpsc::UnboundedSender, oneshot};
 use tokio_stream::wrappers::UnboundedReceiverStream;
+use tracing::trace;
 
 mod client;
 pub use client::FetchClient;
 
+/// Weight of a new sample in the moving averages of a peer's [`PeerStats`].
+const PEER_STATS_SMOOTHING: f64 = 0.25;
+
+/// Minimum time recorded in a peer's [`PeerStats`] for an empty or failed response.
+const PEER_STATS_FAILED_RESPONSE_PENALTY: Duration = Duration::from_secs(5);
+
 /// Manages data fetching operations.
 ///
 /// This type is hooked into the staged sync pipeline and delegates download request to available
@@ -37,6 +53,12 @@ pub struct StateFetcher {
     /// Currently active [`GetBlockBodies`] requests
     inflight_bodies_requests:
         HashMap<PeerId, Request<Vec<H256>, PeerRequestResult<Vec<BlockBody>>>>,
+    /// Currently active [`GetPooledTransactions`] requests
+    inflight_pooled_transactions_requests:
+        HashMap<PeerId, Request<Vec<H256>, PeerRequestResult<Vec<PooledTransactionsElement>>>>,
+    /// Currently active [`GetReceipts`] requests
+    inflight_receipts_requests:
+        HashMap<PeerId, Request<Vec<H256>, PeerRequestResult<Vec<Vec<ReceiptWithBloom>>>>>,
     /// The list of _available_ peers for requests.
     peers: HashMap<PeerId, Peer>,
     /// The handle to the peers manager
@@ -59,6 +81,8 @@ impl StateFetcher {
         Self {
             inflight_headers_requests: Default::default(),
             inflight_bodies_requests: Default::default(),
+            inflight_pooled_transactions_requests: Default::default(),
+            inflight_receipts_requests: Default::default(),
             peers: Default::default(),
             peers_handle,
             num_active_peers,
@@ -75,9 +99,26 @@ impl StateFetcher {
         best_hash: H256,
         best_number: u64,
         timeout: Arc<AtomicU64>,
+        eth_version: EthVersion,
+        capabilities: Arc<Capabilities>,
     ) {
-        self.peers
-            .insert(peer_id, Peer { state: PeerState::Idle, best_hash, best_number, timeout });
+        self.peers.insert(
+            peer_id,
+            Peer {
+                state: PeerState::Idle,
+                best_hash,
+                best_number,
+                timeout,
+                stats: Default::default(),
+                eth_version,
+                capabilities,
+            },
+        );
+    }
+
+    /// Returns the average round-trip time of the requests the peer served, if any.
+    pub(crate) fn peer_latency(&self, peer_id: &PeerId) -> Option<Duration> {
+        self.peers.get(peer_id).and_then(|peer| peer.stats.latency)
     }
 
     /// Removes the peer from the peer list, after which it is no longer available for future
@@ -94,6 +135,12 @@ impl StateFetcher {
         if let Some(req) = self.inflight_bodies_requests.remove(peer) {
             let _ = req.response.send(Err(RequestError::ConnectionDropped));
         }
+        if let Some(req) = self.inflight_pooled_transactions_requests.remove(peer) {
+            let _ = req.response.send(Err(RequestError::ConnectionDropped));
+        }
+        if let Some(req) = self.inflight_receipts_requests.remove(peer) {
+            let _ = req.response.send(Err(RequestError::ConnectionDropped));
+        }
     }
 
     /// Updates the block information for the peer.
@@ -117,37 +164,87 @@ impl StateFetcher {
         }
     }
 
-    /// Returns the _next_ idle peer that's ready to accept a request,
-    /// prioritizing those with the lowest timeout/latency.
-    /// Once a peer has been yielded, it will be moved to the end of the map
-    fn next_peer(&mut self) -> Option<PeerId> {
+    /// Returns the _next_ idle peer that's ready to accept the given request and matches its
+    /// [PeerFilter].
+    ///
+    /// Peers are prioritized by the time they took to serve a single item of this kind of request,
+    /// which accounts for both latency and throughput, and then by their timeout. Peers that have
+    /// not served such a request yet come first, so that every peer gets measured.
+    fn next_peer(&self, request: Option<&DownloadRequest>, peers: &PeersManager) -> Option<PeerId> {
+        let kind = request.map(DownloadRequest::peer_state);
         self.peers
             .iter()
             .filter(|(_, peer)| peer.state.is_idle())
-            .min_by_key(|(_, peer)| peer.timeout())
+            .filter(|(id, peer)| {
+                request.map_or(true, |request| {
+                    request.filter().matches(peer, peers.get_reputation(id))
+                })
+            })
+            .min_by_key(|(_, peer)| {
+                (peer.stats.expected_time_per_item(kind.as_ref()), peer.timeout())
+            })
             .map(|(id, _)| *id)
     }
 
+    /// Fails the queued requests that none of the connected peers can serve, because no peer
+    /// matches their [PeerFilter], with [RequestError::UnsupportedCapability].
+    ///
+    /// Without any connected peers, requests stay queued until a peer connects.
+    fn fail_unservable_requests(&mut self, peers: &PeersManager) {
+        if self.queued_requests.is_empty() ||
+            self.peers.values().all(|peer| matches!(peer.state, PeerState::Closing))
+        {
+            return
+        }
+
+        let (servable, unservable): (VecDeque<_>, Vec<_>) =
+            std::mem::take(&mut self.queued_requests).into_iter().partition(|request| {
+                self.peers.iter().any(|(id, peer)| {
+                    !matches!(peer.state, PeerState::Closing) &&
+                        request.filter().matches(peer, peers.get_reputation(id))
+                })
+            });
+        self.queued_requests = servable;
+        for request in unservable {
+            trace!(target: "net::fetch", filter = ?request.filter(), "No connected peer matches the request filter");
+            request.send_err(RequestError::UnsupportedCapability);
+        }
+    }
+
     /// Returns the next action to return
-    fn poll_action(&mut self) -> PollAction {
+    fn poll_action(&mut self, peers: &PeersManager) -> PollAction {
+        self.fail_unservable_requests(peers);
+
         // we only check and not pop here since we don't know yet whether a peer is available.
         if self.queued_requests.is_empty() {
             return PollAction::NoRequests
         }
+        if !self.peers.values().any(|peer| peer.state.is_idle()) {
+            return PollAction::NoPeersAvailable
+        }
 
-        let Some(peer_id) = self.next_peer() else { return PollAction::NoPeersAvailable };
+        // requests that no idle peer matches don't hold up the requests queued behind them
+        let Some((idx, peer_id)) =
+            self.queued_requests.iter().enumerate().find_map(|(idx, request)| {
+                self.next_peer(Some(request), peers).map(|peer_id| (idx, peer_id))
+            })
+        else {
+            return PollAction::NoPeersAvailable
+        };
 
-        let request = self.queued_requests.pop_front().expect("not empty; qed");
+        let request = self.queued_requests.remove(idx).expect("exists; qed");
         let request = self.prepare_block_request(peer_id, request);
 
         PollAction::Ready(FetchAction::BlockRequest { peer_id, request })
     }
 
     /// Advance the state the syncer
-    pub(crate) fn poll(&mut self, cx: &mut Context<'_>) -> Poll<FetchAction> {
+    ///
+    /// The [PeersManager] provides the reputation of peers for [PeerFilter]s.
+    pub(crate) fn poll(&mut self, cx: &mut Context<'_>, peers: &PeersManager) -> Poll<FetchAction> {
         // drain buffered actions first
         loop {
-            let no_peers_available = match self.poll_action() {
+            let no_peers_available = match self.poll_action(peers) {
                 PollAction::Ready(action) => return Poll::Ready(action),
                 PollAction::NoRequests => false,
                 PollAction::NoPeersAvailable => true,
@@ -195,7 +292,8 @@ impl StateFetcher {
 
         match req {
             DownloadRequest::GetBlockHeaders { request, response, .. } => {
-                let inflight = Request { request: request.clone(), response };
+                let inflight =
+                    Request { request: request.clone(), response, sent_at: Instant::now() };
                 self.inflight_headers_requests.insert(peer_id, inflight);
                 let HeadersRequest { start, limit, direction } = request;
                 BlockRequest::GetBlockHeaders(GetBlockHeaders {
@@ -206,18 +304,40 @@ impl StateFetcher {
                 })
             }
             DownloadRequest::GetBlockBodies { request, response, .. } => {
-                let inflight = Request { request: request.clone(), response };
+                let inflight =
+                    Request { request: request.clone(), response, sent_at: Instant::now() };
                 self.inflight_bodies_requests.insert(peer_id, inflight);
                 BlockRequest::GetBlockBodies(GetBlockBodies(request))
             }
+            DownloadRequest::GetPooledTransactions { request, response, .. } => {
+                let inflight =
+                    Request { request: request.clone(), response, sent_at: Instant::now() };
+                self.inflight_pooled_transactions_requests.insert(peer_id, inflight);
+                BlockRequest::GetPooledTransactions(GetPooledTransactions(request))
+            }
+            DownloadRequest::GetReceipts { request, response, .. } => {
+                let inflight =
+                    Request { request: request.clone(), response, sent_at: Instant::now() };
+                self.inflight_receipts_requests.insert(peer_id, inflight);
+                BlockRequest::GetReceipts(GetReceipts(request))
+            }
         }
     }
 
-    /// Returns a new followup request for the peer.
+    /// Returns a new followup request for the peer, the first queued request that matches the
+    /// peer.
     ///
     /// Caution: this expects that the peer is _not_ closed.
-    fn followup_request(&mut self, peer_id: PeerId) -> Option<BlockResponseOutcome> {
-        let req = self.queued_requests.pop_front()?;
+    fn followup_request(
+        &mut self,
+        peer_id: PeerId,
+        peers: &PeersManager,
+    ) -> Option<BlockResponseOutcome> {
+        let peer = self.peers.get(&peer_id)?;
+        let reputation = peers.get_reputation(&peer_id);
+        let idx =
+            self.queued_requests.iter().position(|req| req.filter().matches(peer, reputation))?;
+        let req = self.queued_requests.remove(idx).expect("exists; qed");
         let req = self.prepare_block_request(peer_id, req);
         Some(BlockResponseOutcome::Request(peer_id, req))
     }
@@ -231,11 +351,14 @@ impl StateFetcher {
         &mut self,
         peer_id: PeerId,
         res: RequestResult<Vec<Header>>,
+        peers: &PeersManager,
     ) -> Option<BlockResponseOutcome> {
         let is_error = res.is_err();
         let maybe_reputation_change = res.reputation_change_err();
+        let items = res.as_ref().map(Vec::len).unwrap_or_default();
 
         let resp = self.inflight_headers_requests.remove(&peer_id);
+        let elapsed = resp.as_ref().map(|r| r.sent_at.elapsed());
 
         let is_likely_bad_response = resp
             .as_ref()
@@ -248,10 +371,13 @@ impl StateFetcher {
         }
 
         if let Some(peer) = self.peers.get_mut(&peer_id) {
+            if let Some(elapsed) = elapsed {
+                peer.stats.on_response(&PeerState::GetBlockHeaders, elapsed, items);
+            }
             // If the peer is still ready to accept new requests, we try to send a followup
             // request immediately.
             if peer.state.on_request_finished() && !is_error && !is_likely_bad_response {
-                return self.followup_request(peer_id)
+                return self.followup_request(peer_id, peers)
             }
         }
 
@@ -266,13 +392,68 @@ impl StateFetcher {
         &mut self,
         peer_id: PeerId,
         res: RequestResult<Vec<BlockBody>>,
+        peers: &PeersManager,
     ) -> Option<BlockResponseOutcome> {
+        let items = res.as_ref().map(Vec::len).unwrap_or_default();
+        let mut elapsed = None;
         if let Some(resp) = self.inflight_bodies_requests.remove(&peer_id) {
+            elapsed = Some(resp.sent_at.elapsed());
             let _ = resp.response.send(res.map(|b| (peer_id, b).into()));
         }
         if let Some(peer) = self.peers.get_mut(&peer_id) {
+            if let Some(elapsed) = elapsed {
+                peer.stats.on_response(&PeerState::GetBlockBodies, elapsed, items);
+            }
+            if peer.state.on_request_finished() {
+                return self.followup_request(peer_id, peers)
+            }
+        }
+        None
+    }
+
+    /// Called on a `GetPooledTransactions` response from a peer
+    pub(crate) fn on_pooled_transactions_response(
+        &mut self,
+        peer_id: PeerId,
+        res: RequestResult<Vec<PooledTransactionsElement>>,
+        peers: &PeersManager,
+    ) -> Option<BlockResponseOutcome> {
+        let items = res.as_ref().map(Vec::len).unwrap_or_default();
+        let mut elapsed = None;
+        if let Some(resp) = self.inflight_pooled_transactions_requests.remove(&peer_id) {
+            elapsed = Some(resp.sent_at.elapsed());
+            let _ = resp.response.send(res.map(|txs| (peer_id, txs).into()));
+        }
+        if let Some(peer) = self.peers.get_mut(&peer_id) {
+            if let Some(elapsed) = elapsed {
+                peer.stats.on_response(&PeerState::GetPooledTransactions, elapsed, items);
+            }
             if peer.state.on_request_finished() {
-                return self.followup_request(peer_id)
+                return self.followup_request(peer_id, peers)
+            }
+        }
+        None
+    }
+
+    /// Called on a `GetReceipts` response from a peer
+    pub(crate) fn on_receipts_response(
+        &mut self,
+        peer_id: PeerId,
+        res: RequestResult<Vec<Vec<ReceiptWithBloom>>>,
+        peers: &PeersManager,
+    ) -> Option<BlockResponseOutcome> {
+        let items = res.as_ref().map(Vec::len).unwrap_or_default();
+        let mut elapsed = None;
+        if let Some(resp) = self.inflight_receipts_requests.remove(&peer_id) {
+            elapsed = Some(resp.sent_at.elapsed());
+            let _ = resp.response.send(res.map(|receipts| (peer_id, receipts).into()));
+        }
+        if let Some(peer) = self.peers.get_mut(&peer_id) {
+            if let Some(elapsed) = elapsed {
+                peer.stats.on_response(&PeerState::GetReceipts, elapsed, items);
+            }
+            if peer.state.on_request_finished() {
+                return self.followup_request(peer_id, peers)
             }
         }
         None
@@ -284,6 +465,7 @@ impl StateFetcher {
             request_tx: self.download_requests_tx.clone(),
             peers_handle: self.peers_handle.clone(),
             num_active_peers: Arc::clone(&self.num_active_peers),
+            peer_filter: Default::default(),
         }
     }
 }
@@ -305,6 +487,12 @@ struct Peer {
     best_number: u64,
     /// Tracks the current timeout value we use for the peer.
     timeout: Arc<AtomicU64>,
+    /// Round-trip times and throughput of the requests the peer served.
+    stats: PeerStats,
+    /// The negotiated eth version.
+    eth_version: EthVersion,
+    /// The capabilities the peer announced.
+    capabilities: Arc<Capabilities>,
 }
 
 impl Peer {
@@ -313,6 +501,118 @@ impl Peer {
     }
 }
 
+/// Criteria a peer must meet to be sent a request, see [FetchClient::with_peer_filter].
+///
+/// By default, all peers match.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PeerFilter {
+    /// The minimum negotiated eth version.
+    min_eth_version: Option<EthVersion>,
+    /// Capabilities the peer must announce, in at least the given version.
+    required_capabilities: Vec<Capability>,
+    /// The minimum reputation.
+    min_reputation: Option<i32>,
+}
+
+// === impl PeerFilter ===
+
+impl PeerFilter {
+    /// Only matches peers that negotiated at least the given eth version.
+    pub fn with_min_eth_version(mut self, version: EthVersion) -> Self {
+        self.min_eth_version = Some(version);
+        self
+    }
+
+    /// Only matches peers that announced the capability in at least the given version.
+    pub fn with_required_capability(mut self, capability: Capability) -> Self {
+        self.required_capabilities.push(capability);
+        self
+    }
+
+    /// Only matches peers with at least the given reputation.
+    pub fn with_min_reputation(mut self, reputation: i32) -> Self {
+        self.min_reputation = Some(reputation);
+        self
+    }
+
+    /// Returns `true` if the peer with the given reputation meets all criteria.
+    fn matches(&self, peer: &Peer, reputation: Option<i32>) -> bool {
+        if self.min_eth_version.map_or(false, |version| peer.eth_version < version) {
+            return false
+        }
+        if let Some(min_reputation) = self.min_reputation {
+            if reputation.map_or(true, |reputation| reputation < min_reputation) {
+                return false
+            }
+        }
+        self.required_capabilities.iter().all(|required| {
+            peer.capabilities
+                .capabilities()
+                .iter()
+                .any(|cap| cap.name == required.name && cap.version >= required.version)
+        })
+    }
+}
+
+/// Moving averages of the round-trip times and throughput of the requests a peer served.
+#[derive(Debug, Default)]
+struct PeerStats {
+    /// Round-trip time of all requests.
+    latency: Option<Duration>,
+    /// Time it took to serve a single header.
+    time_per_header: Option<Duration>,
+    /// Time it took to serve a single block body.
+    time_per_body: Option<Duration>,
+}
+
+// === impl PeerStats ===
+
+impl PeerStats {
+    /// Records a response with the given number of items to a request of the given kind that
+    /// arrived `elapsed` after the request was sent.
+    ///
+    /// Empty and failed responses, which have no items, are fast to serve. Instead of the elapsed
+    /// time they record at least [`PEER_STATS_FAILED_RESPONSE_PENALTY`], so peers that lack the
+    /// data or fail requests are not preferred.
+    fn on_response(&mut self, kind: &PeerState, elapsed: Duration, items: usize) {
+        let (elapsed, per_item) = if items == 0 {
+            let penalty = elapsed.max(PEER_STATS_FAILED_RESPONSE_PENALTY);
+            (penalty, penalty)
+        } else {
+            (elapsed, elapsed / items.min(u32::MAX as usize) as u32)
+        };
+        self.latency = Some(moving_average(self.latency, elapsed));
+        match kind {
+            PeerState::GetBlockHeaders => {
+                self.time_per_header = Some(moving_average(self.time_per_header, per_item))
+            }
+            PeerState::GetBlockBodies => {
+                self.time_per_body = Some(moving_average(self.time_per_body, per_item))
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the expected time to serve a single item of a request of the given kind.
+    fn expected_time_per_item(&self, kind: Option<&PeerState>) -> Option<Duration> {
+        match kind {
+            Some(PeerState::GetBlockHeaders) => self.time_per_header,
+            Some(PeerState::GetBlockBodies) => self.time_per_body,
+            _ => self.latency,
+        }
+    }
+}
+
+/// Returns the exponential moving average with the new sample.
+fn moving_average(average: Option<Duration>, sample: Duration) -> Duration {
+    match average {
+        Some(average) => {
+            average.mul_f64(1.0 - PEER_STATS_SMOOTHING) + sample.mul_f64(PEER_STATS_SMOOTHING)
+        }
+        None => sample,
+    }
+}
+
 /// Tracks the state of an individual peer
 enum PeerState {
     /// Peer is currently not handling requests and is available.
@@ -321,6 +621,10 @@ enum PeerState {
     GetBlockHeaders,
     /// Peer is handling a `GetBlockBodies` request.
     GetBlockBodies,
+    /// Peer is handling a `GetPooledTransactions` request.
+    GetPooledTransactions,
+    /// Peer is handling a `GetReceipts` request.
+    GetReceipts,
     /// Peer session is about to close
     Closing,
 }
@@ -355,6 +659,8 @@ struct Request<Req, Resp> {
     #[allow(unused)]
     request: Req,
     response: oneshot::Sender<Resp>,
+    /// When the request was sent to the peer.
+    sent_at: Instant,
 }
 
 /// Requests that can be sent to the Syncer from a [`FetchClient`]
@@ -364,12 +670,28 @@ pub(crate) enum DownloadRequest {
         request: HeadersRequest,
         response: oneshot::Sender<PeerRequestResult<Vec<Header>>>,
         priority: Priority,
+        filter: PeerFilter,
     },
     /// Download the requested headers and send response through channel
     GetBlockBodies {
         request: Vec<H256>,
         response: oneshot::Sender<PeerRequestResult<Vec<BlockBody>>>,
         priority: Priority,
+        filter: PeerFilter,
+    },
+    /// Download the requested pooled transactions and send response through channel
+    GetPooledTransactions {
+        request: Vec<H256>,
+        response: oneshot::Sender<PeerRequestResult<Vec<PooledTransactionsElement>>>,
+        priority: Priority,
+        filter: PeerFilter,
+    },
+    /// Download the receipts of the requested blocks and send response through channel
+    GetReceipts {
+        request: Vec<H256>,
+        response: oneshot::Sender<PeerRequestResult<Vec<Vec<ReceiptWithBloom>>>>,
+        priority: Priority,
+        filter: PeerFilter,
     },
 }
 
@@ -381,6 +703,8 @@ impl DownloadRequest {
         match self {
             DownloadRequest::GetBlockHeaders { .. } => PeerState::GetBlockHeaders,
             DownloadRequest::GetBlockBodies { .. } => PeerState::GetBlockBodies,
+            DownloadRequest::GetPooledTransactions { .. } => PeerState::GetPooledTransactions,
+            DownloadRequest::GetReceipts { .. } => PeerState::GetReceipts,
         }
     }
 
@@ -389,6 +713,18 @@ impl DownloadRequest {
         match self {
             DownloadRequest::GetBlockHeaders { priority, .. } => priority,
             DownloadRequest::GetBlockBodies { priority, .. } => priority,
+            DownloadRequest::GetPooledTransactions { priority, .. } => priority,
+            DownloadRequest::GetReceipts { priority, .. } => priority,
+        }
+    }
+
+    /// Returns the criteria for the peer that handles the request.
+    fn filter(&self) -> &PeerFilter {
+        match self {
+            DownloadRequest::GetBlockHeaders { filter, .. } => filter,
+            DownloadRequest::GetBlockBodies { filter, .. } => filter,
+            DownloadRequest::GetPooledTransactions { filter, .. } => filter,
+            DownloadRequest::GetReceipts { filter, .. } => filter,
         }
     }
 
@@ -396,6 +732,24 @@ impl DownloadRequest {
     fn is_normal_priority(&self) -> bool {
         self.get_priority().is_normal()
     }
+
+    /// Fails the request with the given error.
+    fn send_err(self, err: RequestError) {
+        match self {
+            DownloadRequest::GetBlockHeaders { response, .. } => {
+                let _ = response.send(Err(err));
+            }
+            DownloadRequest::GetBlockBodies { response, .. } => {
+                let _ = response.send(Err(err));
+            }
+            DownloadRequest::GetPooledTransactions { response, .. } => {
+                let _ = response.send(Err(err));
+            }
+            DownloadRequest::GetReceipts { response, .. } => {
+                let _ = response.send(Err(err));
+            }
+        }
+    }
 }
 
 /// An action the syncer can emit.
@@ -427,20 +781,25 @@ mod tests {
     use reth_primitives::{SealedHeader, H256, H512};
     use std::future::poll_fn;
 
+    fn eth_capabilities() -> Arc<Capabilities> {
+        Arc::new(vec![Capability::from(EthVersion::Eth67)].into())
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_poll_fetcher() {
         let manager = PeersManager::new(PeersConfig::default());
         let mut fetcher = StateFetcher::new(manager.handle(), Default::default());
 
         poll_fn(move |cx| {
-            assert!(fetcher.poll(cx).is_pending());
+            assert!(fetcher.poll(cx, &manager).is_pending());
             let (tx, _rx) = oneshot::channel();
             fetcher.queued_requests.push_back(DownloadRequest::GetBlockBodies {
                 request: vec![],
                 response: tx,
                 priority: Priority::default(),
+                filter: Default::default(),
             });
-            assert!(fetcher.poll(cx).is_pending());
+            assert!(fetcher.poll(cx, &manager).is_pending());
 
             Poll::Ready(())
         })
@@ -454,20 +813,34 @@ mod tests {
         // Add a few random peers
         let peer1 = H512::random();
         let peer2 = H512::random();
-        fetcher.new_active_peer(peer1, H256::random(), 1, Arc::new(AtomicU64::new(1)));
-        fetcher.new_active_peer(peer2, H256::random(), 2, Arc::new(AtomicU64::new(1)));
+        fetcher.new_active_peer(
+            peer1,
+            H256::random(),
+            1,
+            Arc::new(AtomicU64::new(1)),
+            EthVersion::Eth67,
+            eth_capabilities(),
+        );
+        fetcher.new_active_peer(
+            peer2,
+            H256::random(),
+            2,
+            Arc::new(AtomicU64::new(1)),
+            EthVersion::Eth67,
+            eth_capabilities(),
+        );
 
-        let first_peer = fetcher.next_peer().unwrap();
+        let first_peer = fetcher.next_peer(None, &manager).unwrap();
         assert!(first_peer == peer1 || first_peer == peer2);
         // Pending disconnect for first_peer
         fetcher.on_pending_disconnect(&first_peer);
         // first_peer now isn't idle, so we should get other peer
-        let second_peer = fetcher.next_peer().unwrap();
+        let second_peer = fetcher.next_peer(None, &manager).unwrap();
         assert!(first_peer == peer1 || first_peer == peer2);
         assert_ne!(first_peer, second_peer);
         // without idle peers, returns None
         fetcher.on_pending_disconnect(&second_peer);
-        assert_eq!(fetcher.next_peer(), None);
+        assert_eq!(fetcher.next_peer(None, &manager), None);
     }
 
     #[tokio::test]
@@ -481,18 +854,210 @@ mod tests {
 
         let peer2_timeout = Arc::new(AtomicU64::new(300));
 
-        fetcher.new_active_peer(peer1, H256::random(), 1, Arc::new(AtomicU64::new(30)));
-        fetcher.new_active_peer(peer2, H256::random(), 2, Arc::clone(&peer2_timeout));
-        fetcher.new_active_peer(peer3, H256::random(), 3, Arc::new(AtomicU64::new(50)));
+        fetcher.new_active_peer(
+            peer1,
+            H256::random(),
+            1,
+            Arc::new(AtomicU64::new(30)),
+            EthVersion::Eth67,
+            eth_capabilities(),
+        );
+        fetcher.new_active_peer(
+            peer2,
+            H256::random(),
+            2,
+            Arc::clone(&peer2_timeout),
+            EthVersion::Eth67,
+            eth_capabilities(),
+        );
+        fetcher.new_active_peer(
+            peer3,
+            H256::random(),
+            3,
+            Arc::new(AtomicU64::new(50)),
+            EthVersion::Eth67,
+            eth_capabilities(),
+        );
 
         // Must always get peer1 (lowest timeout)
-        assert_eq!(fetcher.next_peer(), Some(peer1));
-        assert_eq!(fetcher.next_peer(), Some(peer1));
+        assert_eq!(fetcher.next_peer(None, &manager), Some(peer1));
+        assert_eq!(fetcher.next_peer(None, &manager), Some(peer1));
         // peer2's timeout changes below peer1's
         peer2_timeout.store(10, Ordering::Relaxed);
         // Then we get peer 2 always (now lowest)
-        assert_eq!(fetcher.next_peer(), Some(peer2));
-        assert_eq!(fetcher.next_peer(), Some(peer2));
+        assert_eq!(fetcher.next_peer(None, &manager), Some(peer2));
+        assert_eq!(fetcher.next_peer(None, &manager), Some(peer2));
+    }
+
+    #[tokio::test]
+    async fn test_latency_prioritization() {
+        let manager = PeersManager::new(PeersConfig::default());
+        let mut fetcher = StateFetcher::new(manager.handle(), Default::default());
+        let fast = H512::random();
+        let slow = H512::random();
+        let new = H512::random();
+        fetcher.new_active_peer(
+            fast,
+            H256::random(),
+            1,
+            Arc::new(AtomicU64::new(100)),
+            EthVersion::Eth67,
+            eth_capabilities(),
+        );
+        fetcher.new_active_peer(
+            slow,
+            H256::random(),
+            1,
+            Arc::new(AtomicU64::new(10)),
+            EthVersion::Eth67,
+            eth_capabilities(),
+        );
+
+        let queue_bodies_request = |fetcher: &mut StateFetcher| {
+            let (tx, _rx) = oneshot::channel();
+            fetcher.queued_requests.push_back(DownloadRequest::GetBlockBodies {
+                request: vec![],
+                response: tx,
+                priority: Priority::default(),
+                filter: Default::default(),
+            });
+        };
+
+        // 10 bodies in 100ms vs 1 body in 50ms
+        fetcher.peers.get_mut(&fast).unwrap().stats.on_response(
+            &PeerState::GetBlockBodies,
+            Duration::from_millis(100),
+            10,
+        );
+        fetcher.peers.get_mut(&slow).unwrap().stats.on_response(
+            &PeerState::GetBlockBodies,
+            Duration::from_millis(50),
+            1,
+        );
+        queue_bodies_request(&mut fetcher);
+        assert_eq!(fetcher.next_peer(fetcher.queued_requests.front(), &manager), Some(fast));
+        assert_eq!(fetcher.peer_latency(&slow), Some(Duration::from_millis(50)));
+
+        // headers have not been measured yet, so the timeout decides
+        fetcher.queued_requests.clear();
+        let (tx, _rx) = oneshot::channel();
+        fetcher.queued_requests.push_back(DownloadRequest::GetBlockHeaders {
+            request: HeadersRequest { start: 0u64.into(), limit: 1, direction: Default::default() },
+            response: tx,
+            priority: Priority::default(),
+            filter: Default::default(),
+        });
+        assert_eq!(fetcher.next_peer(fetcher.queued_requests.front(), &manager), Some(slow));
+
+        // unmeasured peers are tried first
+        fetcher.new_active_peer(
+            new,
+            H256::random(),
+            1,
+            Arc::new(AtomicU64::new(1000)),
+            EthVersion::Eth67,
+            eth_capabilities(),
+        );
+        fetcher.queued_requests.clear();
+        queue_bodies_request(&mut fetcher);
+        assert_eq!(fetcher.next_peer(fetcher.queued_requests.front(), &manager), Some(new));
+
+        // an empty response is penalized instead of counting as a fast response
+        fetcher.peers.get_mut(&new).unwrap().stats.on_response(
+            &PeerState::GetBlockBodies,
+            Duration::from_millis(1),
+            0,
+        );
+        fetcher.queued_requests.clear();
+        queue_bodies_request(&mut fetcher);
+        assert_eq!(fetcher.next_peer(fetcher.queued_requests.front(), &manager), Some(fast));
+        assert_eq!(fetcher.peer_latency(&new), Some(PEER_STATS_FAILED_RESPONSE_PENALTY));
+    }
+
+    #[tokio::test]
+    async fn test_peer_filter() {
+        let mut manager = PeersManager::new(PeersConfig::default());
+        let mut fetcher = StateFetcher::new(manager.handle(), Default::default());
+        let eth66 = H512::random();
+        let eth68 = H512::random();
+        fetcher.new_active_peer(
+            eth66,
+            H256::random(),
+            1,
+            Arc::new(AtomicU64::new(1)),
+            EthVersion::Eth66,
+            Arc::new(vec![Capability::from(EthVersion::Eth66)].into()),
+        );
+        fetcher.new_active_peer(
+            eth68,
+            H256::random(),
+            1,
+            Arc::new(AtomicU64::new(100)),
+            EthVersion::Eth68,
+            Arc::new(
+                vec![Capability::from(EthVersion::Eth68), Capability::new("snap".into(), 1)].into(),
+            ),
+        );
+
+        let queue_request = |fetcher: &mut StateFetcher, filter: PeerFilter| {
+            let (tx, _rx) = oneshot::channel();
+            fetcher.queued_requests.push_back(DownloadRequest::GetBlockBodies {
+                request: vec![],
+                response: tx,
+                priority: Priority::default(),
+                filter,
+            });
+        };
+
+        queue_request(&mut fetcher, PeerFilter::default());
+        assert_eq!(fetcher.next_peer(fetcher.queued_requests.front(), &manager), Some(eth66));
+
+        fetcher.queued_requests.clear();
+        queue_request(&mut fetcher, PeerFilter::default().with_min_eth_version(EthVersion::Eth67));
+        assert_eq!(fetcher.next_peer(fetcher.queued_requests.front(), &manager), Some(eth68));
+
+        fetcher.queued_requests.clear();
+        queue_request(
+            &mut fetcher,
+            PeerFilter::default().with_required_capability(Capability::new("snap".into(), 2)),
+        );
+        assert_eq!(fetcher.next_peer(fetcher.queued_requests.front(), &manager), None);
+
+        // peers unknown to the peers manager have no reputation
+        fetcher.queued_requests.clear();
+        queue_request(&mut fetcher, PeerFilter::default().with_min_reputation(0));
+        assert_eq!(fetcher.next_peer(fetcher.queued_requests.front(), &manager), None);
+        manager.add_peer(eth68, "127.0.0.1:30303".parse().unwrap(), None);
+        assert_eq!(fetcher.next_peer(fetcher.queued_requests.front(), &manager), Some(eth68));
+
+        // a request no idle peer matches doesn't block the requests behind it
+        fetcher.queued_requests.clear();
+        queue_request(&mut fetcher, PeerFilter::default().with_min_eth_version(EthVersion::Eth68));
+        queue_request(&mut fetcher, PeerFilter::default());
+        fetcher.peers.get_mut(&eth68).unwrap().state = PeerState::GetBlockHeaders;
+        let action = poll_fn(|cx| fetcher.poll(cx, &manager)).await;
+        let FetchAction::BlockRequest { peer_id, .. } = action;
+        assert_eq!(peer_id, eth66);
+        assert_eq!(fetcher.queued_requests.len(), 1);
+
+        // a request no connected peer matches fails
+        fetcher.queued_requests.clear();
+        fetcher.on_pending_disconnect(&eth68);
+        let (tx, rx) = oneshot::channel();
+        fetcher.queued_requests.push_back(DownloadRequest::GetBlockBodies {
+            request: vec![],
+            response: tx,
+            priority: Priority::default(),
+            filter: PeerFilter::default()
+                .with_required_capability(Capability::new("snap".into(), 1)),
+        });
+        poll_fn(|cx| {
+            assert!(fetcher.poll(cx, &manager).is_pending());
+            Poll::Ready(())
+        })
+        .await;
+        assert_eq!(rx.await.unwrap(), Err(RequestError::UnsupportedCapability));
+        assert!(fetcher.queued_requests.is_empty());
     }
 
     #[tokio::test]
@@ -501,26 +1066,37 @@ mod tests {
         let mut fetcher = StateFetcher::new(manager.handle(), Default::default());
         let peer_id = H512::random();
 
-        assert_eq!(fetcher.on_block_headers_response(peer_id, Ok(vec![Header::default()])), None);
+        assert_eq!(
+            fetcher.on_block_headers_response(peer_id, Ok(vec![Header::default()]), &manager),
+            None
+        );
 
         assert_eq!(
-            fetcher.on_block_headers_response(peer_id, Err(RequestError::Timeout)),
+            fetcher.on_block_headers_response(peer_id, Err(RequestError::Timeout), &manager),
             Some(BlockResponseOutcome::BadResponse(peer_id, ReputationChangeKind::Timeout))
         );
         assert_eq!(
-            fetcher.on_block_headers_response(peer_id, Err(RequestError::BadResponse)),
+            fetcher.on_block_headers_response(peer_id, Err(RequestError::BadResponse), &manager),
             None
         );
         assert_eq!(
-            fetcher.on_block_headers_response(peer_id, Err(RequestError::ChannelClosed)),
+            fetcher.on_block_headers_response(peer_id, Err(RequestError::ChannelClosed), &manager),
             None
         );
         assert_eq!(
-            fetcher.on_block_headers_response(peer_id, Err(RequestError::ConnectionDropped)),
+            fetcher.on_block_headers_response(
+                peer_id,
+                Err(RequestError::ConnectionDropped),
+                &manager
+            ),
             None
         );
         assert_eq!(
-            fetcher.on_block_headers_response(peer_id, Err(RequestError::UnsupportedCapability)),
+            fetcher.on_block_headers_response(
+                peer_id,
+                Err(RequestError::UnsupportedCapability),
+                &manager
+            ),
             None
         );
     }
@@ -540,6 +1116,7 @@ mod tests {
                     direction: Default::default(),
                 },
                 response: tx,
+                sent_at: Instant::now(),
             };
             let mut header = SealedHeader::default().unseal();
             header.number = 0u64;
@@ -551,17 +1128,20 @@ mod tests {
             Default::default(),
             Default::default(),
             Default::default(),
+            EthVersion::Eth67,
+            eth_capabilities(),
         );
 
         let (req, header) = request_pair();
         fetcher.inflight_headers_requests.insert(peer_id, req);
 
-        let outcome = fetcher.on_block_headers_response(peer_id, Ok(vec![header]));
+        let outcome = fetcher.on_block_headers_response(peer_id, Ok(vec![header]), &manager);
         assert!(outcome.is_none());
         assert!(fetcher.peers[&peer_id].state.is_idle());
 
-        let outcome =
-            fetcher.on_block_headers_response(peer_id, Err(RequestError::Timeout)).unwrap();
+        let outcome = fetcher
+            .on_block_headers_response(peer_id, Err(RequestError::Timeout), &manager)
+            .unwrap();
 
         assert!(EthResponseValidator::reputation_change_err(&Err(RequestError::Timeout)).is_some());
 
@@ -576,4 +1156,40 @@ mod tests {
 
         assert!(fetcher.peers[&peer_id].state.is_idle());
     }
+
+    #[tokio::test]
+    async fn test_pooled_transactions_request() {
+        let manager = PeersManager::new(PeersConfig::default());
+        let mut fetcher = StateFetcher::new(manager.handle(), Default::default());
+        let peer_id = H512::random();
+        fetcher.new_active_peer(
+            peer_id,
+            H256::random(),
+            1,
+            Arc::new(AtomicU64::new(1)),
+            EthVersion::Eth67,
+            eth_capabilities(),
+        );
+
+        let hash = H256::random();
+        let (tx, rx) = oneshot::channel();
+        fetcher.queued_requests.push_back(DownloadRequest::GetPooledTransactions {
+            request: vec![hash],
+            response: tx,
+            priority: Priority::default(),
+            filter: Default::default(),
+        });
+
+        let action = poll_fn(|cx| fetcher.poll(cx, &manager)).await;
+        let FetchAction::BlockRequest { peer_id: target, request } = action;
+        assert_eq!(target, peer_id);
+        assert_eq!(request, BlockRequest::GetPooledTransactions(GetPooledTransactions(vec![hash])));
+
+        assert!(fetcher.on_pooled_transactions_response(peer_id, Ok(vec![]), &manager).is_none());
+        assert!(fetcher.peers[&peer_id].state.is_idle());
+
+        let response = rx.await.unwrap().unwrap();
+        assert_eq!(response.peer_id(), peer_id);
+        assert!(response.into_data().is_empty());
+    }
 }
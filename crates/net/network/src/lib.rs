@@ -127,6 +127,7 @@ mod builder;
 mod cache;
 pub mod config;
 mod discovery;
+pub mod discv5;
 pub mod error;
 pub mod eth_requests;
 mod fetch;
@@ -134,27 +135,38 @@ mod flattened_response;
 mod import;
 mod listener;
 mod manager;
+pub mod mdns;
 mod message;
 mod metrics;
 mod network;
 pub mod peers;
+pub mod protocol;
+pub mod proxy;
 mod session;
+pub mod snap_requests;
 mod state;
+pub mod status;
 mod swarm;
 pub mod transactions;
 
 pub use builder::NetworkBuilder;
 pub use config::{NetworkConfig, NetworkConfigBuilder};
 pub use discovery::Discovery;
-pub use fetch::FetchClient;
+pub use fetch::{FetchClient, PeerFilter};
 pub use manager::{NetworkEvent, NetworkManager};
+pub use mdns::MdnsConfig;
 pub use message::PeerRequest;
 pub use network::NetworkHandle;
-pub use peers::PeersConfig;
+pub use peers::{PeersConfig, ReputationPolicy, SharedReputationPolicy};
+pub use protocol::{ProtocolConnection, ProtocolHandler};
+pub use proxy::ProxyConfig;
 pub use session::{
-    ActiveSessionHandle, ActiveSessionMessage, Direction, PeerInfo, PendingSessionEvent,
-    PendingSessionHandle, PendingSessionHandshakeError, SessionCommand, SessionEvent, SessionId,
-    SessionLimits, SessionManager, SessionsConfig,
+    ActiveSessionHandle, ActiveSessionMessage, BandwidthLimitAction, Direction, HelloHook,
+    HelloHooks, PeerBandwidthLimit, PeerInfo, PendingSessionEvent, PendingSessionHandle,
+    PendingSessionHandshakeError, SessionCaptureConfig, SessionCommand, SessionEvent, SessionId,
+    SessionLimits, SessionManager, SessionsConfig, SessionsConfigBuilder, SessionsConfigError,
+    SessionsPreset,
 };
 
 pub use reth_eth_wire::{DisconnectReason, HelloBuilder, HelloMessage};
+pub use reth_net_common::ban_list::IpSubnet;
@@ -25,7 +25,8 @@ use crate::{
     message::{NewBlockMessage, PeerMessage, PeerRequest, PeerRequestSender},
     metrics::{DisconnectMetrics, NetworkMetrics, NETWORK_POOL_TRANSACTIONS_SCOPE},
     network::{NetworkHandle, NetworkHandleMessage},
-    peers::{PeersHandle, PeersManager},
+    peers::{PeersHandle, PeersManager, PersistedPeer},
+    protocol::ProtocolHandler,
     session::SessionManager,
     state::NetworkState,
     swarm::{NetworkConnectionState, Swarm, SwarmEvent},
@@ -40,10 +41,14 @@ use reth_eth_wire::{
 };
 use reth_metrics::common::mpsc::UnboundedMeteredSender;
 use reth_net_common::bandwidth_meter::BandwidthMeter;
-use reth_network_api::ReputationChangeKind;
+use reth_net_nat::{
+    PortMapping, PortMappingInterval, PortMappingProtocol, DEFAULT_PORT_MAPPING_LIFETIME,
+};
+use reth_network_api::{NetworkInfo, ReputationChangeKind};
 use reth_primitives::{listener::EventListeners, ForkId, NodeRecord, PeerId, H256};
 use reth_provider::{BlockNumReader, BlockReader};
 use reth_rpc_types::{EthProtocolInfo, NetworkStatus};
+use reth_tasks::watchdog::Heartbeat;
 use std::{
     net::SocketAddr,
     pin::Pin,
@@ -123,6 +128,10 @@ pub struct NetworkManager<C> {
     metrics: NetworkMetrics,
     /// Disconnect metrics for the Network
     disconnect_metrics: DisconnectMetrics,
+    /// Maps the listener and discovery ports on the gateway, if configured.
+    port_mapping: Option<PortMappingInterval>,
+    /// Reports the progress of the manager, if monitored.
+    heartbeat: Option<Heartbeat>,
 }
 
 // === impl NetworkManager ===
@@ -140,6 +149,15 @@ impl<C> NetworkManager<C> {
         self.to_eth_request_handler = Some(tx);
     }
 
+    /// Registers the handler of a custom RLPx subprotocol.
+    ///
+    /// The capability of the protocol is announced in the `Hello` message, and every session with a
+    /// peer that shares it hands a [`ProtocolConnection`](crate::ProtocolConnection) to the
+    /// handler. Handlers must be added before the manager is spawned.
+    pub fn add_protocol(&mut self, handler: impl ProtocolHandler) {
+        self.swarm.sessions_mut().add_protocol(handler);
+    }
+
     /// Returns the [`NetworkHandle`] that can be cloned and shared.
     ///
     /// The [`NetworkHandle`] can be used to interact with this [`NetworkManager`]
@@ -179,10 +197,16 @@ where
             hello_message,
             status,
             fork_filter,
+            discovery_v5_config,
             dns_discovery_config,
+            mdns_config,
+            proxy,
+            port_mapper,
             ..
         } = config;
 
+        sessions_config.validate()?;
+
         let peers_manager = PeersManager::new(peers_config);
         let peers_handle = peers_manager.handle();
 
@@ -198,16 +222,33 @@ where
             disc_config
         });
 
-        let discovery =
-            Discovery::new(discovery_addr, secret_key, discovery_v4_config, dns_discovery_config)
-                .await?;
+        let discovery_v5_config =
+            discovery_v5_config.map(|disc_config| disc_config.with_fork_id(status.forkid));
+
+        let discovery = Discovery::new(
+            discovery_addr,
+            secret_key,
+            discovery_v4_config,
+            discovery_v5_config,
+            dns_discovery_config,
+            mdns_config,
+        )
+        .await?;
         // need to retrieve the addr here since provided port could be `0`
         let local_peer_id = discovery.local_id();
 
+        let port_mapping = port_mapper.map(|mapper| {
+            let mut ports = vec![(PortMappingProtocol::Tcp, incoming.local_address().port())];
+            if let Some(discovery_addr) = discovery.discv4_local_addr() {
+                ports.push((PortMappingProtocol::Udp, discovery_addr.port()));
+            }
+            PortMappingInterval::new(mapper, ports, DEFAULT_PORT_MAPPING_LIFETIME)
+        });
+
         let num_active_peers = Arc::new(AtomicUsize::new(0));
         let bandwidth_meter: BandwidthMeter = BandwidthMeter::default();
 
-        let sessions = SessionManager::new(
+        let mut sessions = SessionManager::new(
             secret_key,
             sessions_config,
             executor,
@@ -215,7 +256,9 @@ where
             hello_message,
             fork_filter,
             bandwidth_meter.clone(),
+            proxy,
         );
+        sessions.set_client_version_filter(peers_manager.client_version_filter());
 
         let state = NetworkState::new(
             client,
@@ -251,9 +294,18 @@ where
             num_active_peers,
             metrics: Default::default(),
             disconnect_metrics: Default::default(),
+            port_mapping,
+            heartbeat: None,
         })
     }
 
+    /// Reports the progress of the manager and the messages queued by the [`NetworkHandle`] to the
+    /// given [`Heartbeat`], so that a stalled network can be detected.
+    pub fn set_heartbeat(&mut self, heartbeat: Heartbeat) {
+        heartbeat.track_queue(self.handle.queued_messages().clone());
+        self.heartbeat = Some(heartbeat);
+    }
+
     /// Create a new [`NetworkManager`] instance and start a [`NetworkBuilder`] to configure all
     /// components of the network
     ///
@@ -319,6 +371,12 @@ where
         self.swarm.state().peers().iter_peers()
     }
 
+    /// Returns all peers in the peer set with their reputation and backoff, as written to the
+    /// peers file.
+    pub fn persisted_peers(&self) -> Vec<PersistedPeer> {
+        self.swarm.state().peers().persisted_peers()
+    }
+
     /// Returns a new [`PeersHandle`] that can be cloned and shared.
     ///
     /// The [`PeersHandle`] can be used to interact with the network's peer set.
@@ -504,11 +562,50 @@ where
                 unreachable!("Not emitted by session")
             }
             PeerMessage::Other(other) => {
+                // messages of registered subprotocols are handled by their `ProtocolHandler`
                 debug!(target : "net", message_id=%other.id, "Ignoring unsupported message");
             }
         }
     }
 
+    /// Announces the external addresses of the mapped ports.
+    ///
+    /// Ports that could not be mapped are announced with their local port.
+    fn on_port_mappings(&mut self, mappings: Vec<PortMapping>) {
+        if mappings.is_empty() {
+            debug!(target : "net", "Failed to map any port on the gateway");
+            return
+        }
+        let discovery = self.swarm.state_mut().discovery_mut();
+        let tcp_port = self.handle.local_addr().port();
+        let udp_port = discovery.discv4_local_addr().map_or(tcp_port, |addr| addr.port());
+        let mut record = NodeRecord {
+            address: mappings[0].external.ip(),
+            tcp_port,
+            udp_port,
+            id: *self.handle.peer_id(),
+        };
+        for mapping in mappings {
+            info!(target : "net", protocol=?mapping.protocol, local_port=mapping.local_port, external=%mapping.external, "Mapped port on the gateway");
+            match mapping.protocol {
+                PortMappingProtocol::Tcp => record.tcp_port = mapping.external.port(),
+                PortMappingProtocol::Udp => record.udp_port = mapping.external.port(),
+            }
+            discovery.on_port_mapping(mapping);
+        }
+        self.handle.set_external_node_record(record);
+    }
+
+    /// Removes the ports that were mapped on the gateway, if any.
+    ///
+    /// This should be called on shutdown, so the gateway stops forwarding the ports before the
+    /// mappings expire.
+    pub async fn remove_port_mappings(&mut self) {
+        if let Some(port_mapping) = self.port_mapping.take() {
+            port_mapping.remove().await;
+        }
+    }
+
     /// Handler for received messages from a handle
     fn on_handle_message(&mut self, msg: NetworkHandleMessage) {
         match msg {
@@ -518,6 +615,7 @@ where
             NetworkHandleMessage::DiscoveryListener(tx) => {
                 self.swarm.state_mut().discovery_mut().add_listener(tx);
             }
+            NetworkHandleMessage::SetTransactions(tx) => self.set_transactions(tx),
             NetworkHandleMessage::AnnounceBlock(block, hash) => {
                 if self.handle.mode().is_stake() {
                     // See [EIP-3675](https://eips.ethereum.org/EIPS/eip-3675#devp2p)
@@ -563,6 +661,12 @@ where
             NetworkHandleMessage::ReputationChange(peer_id, kind) => {
                 self.swarm.state_mut().peers_mut().apply_reputation_change(&peer_id, kind);
             }
+            NetworkHandleMessage::BanIp(subnet) => {
+                self.swarm.state_mut().peers_mut().ban_subnet(subnet);
+            }
+            NetworkHandleMessage::UnbanIp(subnet) => {
+                self.swarm.state_mut().peers_mut().unban_subnet(subnet);
+            }
             NetworkHandleMessage::GetReputationById(peer_id, tx) => {
                 let _ = tx.send(self.swarm.state_mut().peers().get_reputation(&peer_id));
             }
@@ -575,13 +679,28 @@ where
             NetworkHandleMessage::StatusUpdate { head } => {
                 if let Some(transition) = self.swarm.sessions_mut().on_status_update(head) {
                     self.swarm.state_mut().update_fork_id(transition.current);
+                    if transition.current != transition.past {
+                        info!(target: "net", past=?transition.past, current=?transition.current, "Fork id changed");
+                        self.event_listeners.notify(NetworkEvent::ForkIdChanged {
+                            past: transition.past,
+                            current: transition.current,
+                        });
+                    }
                 }
             }
             NetworkHandleMessage::GetPeerInfo(tx) => {
-                let _ = tx.send(self.swarm.sessions_mut().get_peer_info());
+                let mut peers = self.swarm.sessions_mut().get_peer_info();
+                for peer in &mut peers {
+                    peer.request_latency = self.swarm.state().peer_latency(&peer.remote_id);
+                }
+                let _ = tx.send(peers);
             }
             NetworkHandleMessage::GetPeerInfoById(peer_id, tx) => {
-                let _ = tx.send(self.swarm.sessions_mut().get_peer_info_by_id(peer_id));
+                let mut peer = self.swarm.sessions_mut().get_peer_info_by_id(peer_id);
+                if let Some(peer) = &mut peer {
+                    peer.request_latency = self.swarm.state().peer_latency(&peer.remote_id);
+                }
+                let _ = tx.send(peer);
             }
         }
     }
@@ -595,6 +714,14 @@ where
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let this = self.get_mut();
+        let _heartbeat = this.heartbeat.as_ref().map(Heartbeat::enter);
+
+        // (re-)map the ports on the gateway
+        if let Some(Poll::Ready(mappings)) =
+            this.port_mapping.as_mut().map(|port_mapping| port_mapping.poll_tick(cx))
+        {
+            this.on_port_mappings(mappings);
+        }
 
         // poll new block imports
         while let Poll::Ready(outcome) = this.block_import.poll(cx) {
@@ -611,7 +738,13 @@ where
                     error!("Network message channel closed.");
                     return Poll::Ready(())
                 }
-                Poll::Ready(Some(msg)) => this.on_handle_message(msg),
+                Poll::Ready(Some(msg)) => {
+                    this.handle.queued_messages().on_recv();
+                    if let Some(heartbeat) = &this.heartbeat {
+                        heartbeat.on_message("handle message");
+                    }
+                    this.on_handle_message(msg)
+                }
             };
         }
 
@@ -634,6 +767,9 @@ where
             match this.swarm.poll_next_unpin(cx) {
                 Poll::Pending | Poll::Ready(None) => break,
                 Poll::Ready(Some(event)) => {
+                    if let Some(heartbeat) = &this.heartbeat {
+                        heartbeat.on_message("swarm event");
+                    }
                     // handle event
                     match event {
                         SwarmEvent::ValidMessage { peer_id, message } => {
@@ -691,7 +827,20 @@ where
                                     .state_mut()
                                     .peers_mut()
                                     .on_incoming_session_established(peer_id, remote_addr);
+                            } else {
+                                this.swarm
+                                    .state_mut()
+                                    .peers_mut()
+                                    .on_outgoing_session_established(peer_id);
                             }
+                            this.swarm
+                                .state_mut()
+                                .peers_mut()
+                                .on_session_client_version(peer_id, &client_version);
+                            this.metrics
+                                .unreachable_trusted_peers
+                                .set(this.swarm.state().peers().num_unreachable_trusted_peers()
+                                    as f64);
                             this.event_listeners.notify(NetworkEvent::SessionEstablished {
                                 peer_id,
                                 remote_addr,
@@ -833,6 +982,10 @@ where
                                 this.swarm.state().peers().num_backed_off_peers().saturating_sub(1)
                                     as f64,
                             );
+                            this.metrics
+                                .unreachable_trusted_peers
+                                .set(this.swarm.state().peers().num_unreachable_trusted_peers()
+                                    as f64);
                         }
                         SwarmEvent::OutgoingConnectionError { remote_addr, peer_id, error } => {
                             trace!(
@@ -856,6 +1009,10 @@ where
                                 this.swarm.state().peers().num_backed_off_peers().saturating_sub(1)
                                     as f64,
                             );
+                            this.metrics
+                                .unreachable_trusted_peers
+                                .set(this.swarm.state().peers().num_unreachable_trusted_peers()
+                                    as f64);
                         }
                         SwarmEvent::BadMessage { peer_id } => {
                             this.swarm.state_mut().peers_mut().apply_reputation_change(
@@ -921,6 +1078,16 @@ pub enum NetworkEvent {
     PeerAdded(PeerId),
     /// Event emitted when a new peer is removed
     PeerRemoved(PeerId),
+    /// Event emitted when the local [ForkId] changed, because the head moved across a fork
+    /// boundary.
+    ///
+    /// Peers that were compatible with the `past` fork id may no longer be compatible.
+    ForkIdChanged {
+        /// The previously active fork id.
+        past: ForkId,
+        /// The new active fork id.
+        current: ForkId,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -0,0 +1,373 @@
+//! Local network discovery over multicast DNS.
+//!
+//! Nodes on the same LAN periodically announce their enode URL as a `TXT` record for the
+//! configured service name and answer queries for it. This allows local devnets to find each
+//! other without any bootnodes.
+//!
+//! Only the small subset of [RFC 6762](https://datatracker.ietf.org/doc/html/rfc6762) that is
+//! required for this exchange is implemented.
+
+use reth_primitives::{NodeRecord, PeerId};
+use socket2::{Domain, Protocol, Socket, Type};
+use std::{
+    io,
+    net::{IpAddr, Ipv4Addr, SocketAddr, SocketAddrV4},
+    time::Duration,
+};
+use tokio::{net::UdpSocket, sync::mpsc, task::JoinHandle};
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::{debug, trace};
+
+/// The multicast address used by mDNS.
+pub const MDNS_MULTICAST_ADDR: Ipv4Addr = Ipv4Addr::new(224, 0, 0, 251);
+
+/// The port used by mDNS.
+pub const MDNS_PORT: u16 = 5353;
+
+/// The default service name that is announced.
+pub const DEFAULT_MDNS_SERVICE_NAME: &str = "_ethereum._udp.local";
+
+/// DNS record type `TXT`.
+const TYPE_TXT: u16 = 16;
+/// DNS class `IN`.
+const CLASS_IN: u16 = 1;
+/// The TTL of announced records, in seconds.
+const RECORD_TTL: u32 = 120;
+/// The maximum size of an mDNS packet.
+const MAX_PACKET_SIZE: usize = 9000;
+
+/// Settings for the [MdnsDiscoveryService].
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MdnsConfig {
+    /// The service name that is announced and queried.
+    ///
+    /// Default: `_ethereum._udp.local`
+    pub service_name: String,
+    /// How often the local node is announced.
+    ///
+    /// Default: 30s
+    #[cfg_attr(feature = "serde", serde(with = "humantime_serde"))]
+    pub announce_interval: Duration,
+}
+
+impl Default for MdnsConfig {
+    fn default() -> Self {
+        Self {
+            service_name: DEFAULT_MDNS_SERVICE_NAME.to_string(),
+            announce_interval: Duration::from_secs(30),
+        }
+    }
+}
+
+/// A service that announces the local node on the LAN and reports other nodes that announce
+/// themselves.
+#[derive(Debug)]
+pub struct MdnsDiscoveryService {
+    /// The multicast socket.
+    socket: UdpSocket,
+    /// The local node that is announced.
+    local_node: NodeRecord,
+    /// Settings.
+    config: MdnsConfig,
+    /// Sender half of the discovered nodes channel.
+    updates: mpsc::Sender<NodeRecord>,
+}
+
+impl MdnsDiscoveryService {
+    /// Binds the multicast socket and returns the service and a stream of discovered nodes.
+    pub fn bind(
+        local_node: NodeRecord,
+        config: MdnsConfig,
+    ) -> io::Result<(Self, ReceiverStream<NodeRecord>)> {
+        let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+        socket.set_reuse_address(true)?;
+        #[cfg(unix)]
+        socket.set_reuse_port(true)?;
+        socket.bind(&SocketAddr::from((Ipv4Addr::UNSPECIFIED, MDNS_PORT)).into())?;
+        socket.set_multicast_loop_v4(true)?;
+        socket.join_multicast_v4(&MDNS_MULTICAST_ADDR, &Ipv4Addr::UNSPECIFIED)?;
+        socket.set_nonblocking(true)?;
+        let socket = UdpSocket::from_std(socket.into())?;
+
+        let (updates, rx) = mpsc::channel(256);
+        Ok((Self { socket, local_node, config, updates }, ReceiverStream::new(rx)))
+    }
+
+    /// Spawns this service onto a new task.
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::task::spawn(async move { self.run().await })
+    }
+
+    /// Runs the service until the receiver of the updates is dropped.
+    async fn run(self) {
+        let target = SocketAddr::V4(SocketAddrV4::new(MDNS_MULTICAST_ADDR, MDNS_PORT));
+        let announcement =
+            encode_announcement(&self.config.service_name, &self.local_node.to_string());
+
+        // ask for other nodes right away, so we don't have to wait for their next announcement
+        let _ = self.socket.send_to(&encode_query(&self.config.service_name), target).await;
+
+        let mut interval = tokio::time::interval(self.config.announce_interval);
+        let mut buf = vec![0u8; MAX_PACKET_SIZE];
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if let Err(err) = self.socket.send_to(&announcement, target).await {
+                        debug!(target: "net::mdns", ?err, "Failed to send announcement");
+                    }
+                }
+                res = self.socket.recv_from(&mut buf) => {
+                    let (len, remote) = match res {
+                        Ok(res) => res,
+                        Err(err) => {
+                            debug!(target: "net::mdns", ?err, "Failed to read packet");
+                            continue
+                        }
+                    };
+                    let Some(packet) = Packet::decode(&buf[..len]) else {
+                        trace!(target: "net::mdns", ?remote, "Ignoring malformed packet");
+                        continue
+                    };
+
+                    if packet.queries(&self.config.service_name) {
+                        let _ = self.socket.send_to(&announcement, target).await;
+                    }
+
+                    for record in packet.records(&self.config.service_name) {
+                        let Some(node) = parse_node(&record, remote.ip(), self.local_node.id)
+                        else {
+                            continue
+                        };
+                        trace!(target: "net::mdns", ?node, "Discovered node");
+                        if self.updates.send(node).await.is_err() {
+                            return
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Parses an announced enode URL.
+///
+/// If the node announced an unspecified address, the address the packet was received from is
+/// used instead.
+fn parse_node(record: &str, remote: IpAddr, local_id: PeerId) -> Option<NodeRecord> {
+    let mut node: NodeRecord = record.parse().ok()?;
+    if node.id == local_id {
+        return None
+    }
+    if node.address.is_unspecified() {
+        node.address = remote;
+    }
+    Some(node)
+}
+
+/// Encodes a query for the `TXT` record of the service name.
+fn encode_query(service_name: &str) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(64);
+    encode_header(&mut buf, 0, 1, 0);
+    encode_name(&mut buf, service_name);
+    buf.extend_from_slice(&TYPE_TXT.to_be_bytes());
+    buf.extend_from_slice(&CLASS_IN.to_be_bytes());
+    buf
+}
+
+/// Encodes a response with a single `TXT` record that contains the `value`.
+fn encode_announcement(service_name: &str, value: &str) -> Vec<u8> {
+    let value = &value.as_bytes()[..value.len().min(u8::MAX as usize)];
+    let mut buf = Vec::with_capacity(64 + value.len());
+    // response, authoritative answer
+    encode_header(&mut buf, 0x8400, 0, 1);
+    encode_name(&mut buf, service_name);
+    buf.extend_from_slice(&TYPE_TXT.to_be_bytes());
+    buf.extend_from_slice(&CLASS_IN.to_be_bytes());
+    buf.extend_from_slice(&RECORD_TTL.to_be_bytes());
+    buf.extend_from_slice(&(value.len() as u16 + 1).to_be_bytes());
+    buf.push(value.len() as u8);
+    buf.extend_from_slice(value);
+    buf
+}
+
+fn encode_header(buf: &mut Vec<u8>, flags: u16, questions: u16, answers: u16) {
+    buf.extend_from_slice(&0u16.to_be_bytes());
+    buf.extend_from_slice(&flags.to_be_bytes());
+    buf.extend_from_slice(&questions.to_be_bytes());
+    buf.extend_from_slice(&answers.to_be_bytes());
+    buf.extend_from_slice(&0u16.to_be_bytes());
+    buf.extend_from_slice(&0u16.to_be_bytes());
+}
+
+fn encode_name(buf: &mut Vec<u8>, name: &str) {
+    for label in name.split('.').filter(|label| !label.is_empty()) {
+        buf.push(label.len() as u8);
+        buf.extend_from_slice(label.as_bytes());
+    }
+    buf.push(0);
+}
+
+/// The relevant parts of a decoded mDNS packet.
+#[derive(Debug, Default)]
+struct Packet {
+    /// Names of all `TXT` questions.
+    questions: Vec<String>,
+    /// Name and strings of all `TXT` answers.
+    answers: Vec<(String, Vec<String>)>,
+}
+
+impl Packet {
+    fn decode(buf: &[u8]) -> Option<Self> {
+        let read_u16 = |pos: usize| -> Option<u16> {
+            Some(u16::from_be_bytes(buf.get(pos..pos + 2)?.try_into().ok()?))
+        };
+        let flags = read_u16(2)?;
+        let questions = read_u16(4)?;
+        let records = read_u16(6)? as usize + read_u16(8)? as usize + read_u16(10)? as usize;
+        let is_response = flags & 0x8000 != 0;
+
+        let mut packet = Packet::default();
+        let mut pos = 12;
+        for _ in 0..questions {
+            let (name, next) = decode_name(buf, pos)?;
+            let ty = read_u16(next)?;
+            pos = next + 4;
+            if !is_response && ty == TYPE_TXT {
+                packet.questions.push(name);
+            }
+        }
+
+        for _ in 0..records {
+            let (name, next) = decode_name(buf, pos)?;
+            let ty = read_u16(next)?;
+            let len = read_u16(next + 8)? as usize;
+            let data = buf.get(next + 10..next + 10 + len)?;
+            pos = next + 10 + len;
+            if is_response && ty == TYPE_TXT {
+                packet.answers.push((name, decode_txt(data)));
+            }
+        }
+
+        Some(packet)
+    }
+
+    /// Returns true if the packet asks for the service name.
+    fn queries(&self, service_name: &str) -> bool {
+        self.questions.iter().any(|name| name.eq_ignore_ascii_case(service_name))
+    }
+
+    /// Returns all `TXT` strings that were announced for the service name.
+    fn records(self, service_name: &str) -> impl Iterator<Item = String> + '_ {
+        self.answers
+            .into_iter()
+            .filter(move |(name, _)| name.eq_ignore_ascii_case(service_name))
+            .flat_map(|(_, strings)| strings)
+    }
+}
+
+/// Decodes the (possibly compressed) name at `pos`.
+///
+/// Returns the name and the position right after it.
+fn decode_name(buf: &[u8], mut pos: usize) -> Option<(String, usize)> {
+    let mut labels = Vec::new();
+    let mut end = None;
+    // bound the number of jumps to guard against pointer loops
+    let mut jumps = 0;
+    loop {
+        let len = *buf.get(pos)? as usize;
+        if len == 0 {
+            end.get_or_insert(pos + 1);
+            break
+        }
+        if len & 0xc0 == 0xc0 {
+            let offset = ((len & 0x3f) << 8) | *buf.get(pos + 1)? as usize;
+            end.get_or_insert(pos + 2);
+            jumps += 1;
+            if jumps > 16 {
+                return None
+            }
+            pos = offset;
+            continue
+        }
+        let label = buf.get(pos + 1..pos + 1 + len)?;
+        labels.push(String::from_utf8_lossy(label).into_owned());
+        pos += 1 + len;
+    }
+    Some((labels.join("."), end?))
+}
+
+/// Decodes the character strings of a `TXT` record.
+fn decode_txt(mut data: &[u8]) -> Vec<String> {
+    let mut strings = Vec::new();
+    while let Some((&len, rest)) = data.split_first() {
+        let Some(s) = rest.get(..len as usize) else { break };
+        strings.push(String::from_utf8_lossy(s).into_owned());
+        data = &rest[len as usize..];
+    }
+    strings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::thread_rng;
+    use secp256k1::SECP256K1;
+
+    fn local_node() -> NodeRecord {
+        let (sk, _) = SECP256K1.generate_keypair(&mut thread_rng());
+        NodeRecord::from_secret_key("0.0.0.0:30303".parse().unwrap(), &sk)
+    }
+
+    #[test]
+    fn announcement_roundtrip() {
+        let node = local_node();
+        let encoded = encode_announcement(DEFAULT_MDNS_SERVICE_NAME, &node.to_string());
+        let packet = Packet::decode(&encoded).unwrap();
+        assert!(!packet.queries(DEFAULT_MDNS_SERVICE_NAME));
+
+        let records = packet.records(DEFAULT_MDNS_SERVICE_NAME).collect::<Vec<_>>();
+        assert_eq!(records, vec![node.to_string()]);
+
+        let remote: IpAddr = "192.168.1.2".parse().unwrap();
+        let discovered = parse_node(&records[0], remote, PeerId::random()).unwrap();
+        assert_eq!(discovered.id, node.id);
+        assert_eq!(discovered.address, remote);
+
+        // our own announcements are ignored
+        assert!(parse_node(&records[0], remote, node.id).is_none());
+    }
+
+    #[test]
+    fn query_roundtrip() {
+        let packet = Packet::decode(&encode_query(DEFAULT_MDNS_SERVICE_NAME)).unwrap();
+        assert!(packet.queries(DEFAULT_MDNS_SERVICE_NAME));
+        assert!(!packet.queries("_other._udp.local"));
+        assert_eq!(packet.records(DEFAULT_MDNS_SERVICE_NAME).count(), 0);
+    }
+
+    #[test]
+    fn decode_compressed_name() {
+        // second answer refers to the name of the first answer at offset 12
+        let mut buf = encode_announcement(DEFAULT_MDNS_SERVICE_NAME, "first");
+        buf[7] = 2;
+        buf.extend_from_slice(&[0xc0, 12]);
+        buf.extend_from_slice(&TYPE_TXT.to_be_bytes());
+        buf.extend_from_slice(&CLASS_IN.to_be_bytes());
+        buf.extend_from_slice(&RECORD_TTL.to_be_bytes());
+        buf.extend_from_slice(&4u16.to_be_bytes());
+        buf.extend_from_slice(&[3, b'a', b'b', b'c']);
+
+        let packet = Packet::decode(&buf).unwrap();
+        assert_eq!(
+            packet.records(DEFAULT_MDNS_SERVICE_NAME).collect::<Vec<_>>(),
+            vec!["first", "abc"]
+        );
+    }
+
+    #[test]
+    fn reject_truncated() {
+        let encoded = encode_announcement(DEFAULT_MDNS_SERVICE_NAME, "enode://");
+        assert!(Packet::decode(&encoded[..encoded.len() - 2]).is_none());
+    }
+}
@@ -67,6 +67,8 @@ pub enum PeerMessage {
 pub enum BlockRequest {
     GetBlockHeaders(GetBlockHeaders),
     GetBlockBodies(GetBlockBodies),
+    GetPooledTransactions(GetPooledTransactions),
+    GetReceipts(GetReceipts),
 }
 
 /// Protocol related request messages that expect a response
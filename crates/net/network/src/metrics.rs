@@ -20,6 +20,9 @@ pub struct NetworkMetrics {
     /// Number of peers known to the node
     pub(crate) tracked_peers: Gauge,
 
+    /// Number of trusted peers that could not be reached on the last connection attempt
+    pub(crate) unreachable_trusted_peers: Gauge,
+
     /// Cumulative number of failures of pending sessions
     pub(crate) pending_session_failures: Counter,
 
@@ -71,6 +74,20 @@ pub struct TransactionsManagerMetrics {
     pub(crate) inflight_transaction_requests: Gauge,
     /// How often we failed to send a request to the peer because the channel was full.
     pub(crate) egress_peer_channel_full: Counter,
+    /// Number of transactions tracked as seen across all peers.
+    pub(crate) seen_transactions_cache_entries: Gauge,
+    /// Estimated memory in bytes used to track transactions seen by peers.
+    pub(crate) seen_transactions_cache_memory: Gauge,
+    /// Number of transactions evicted from the caches of transactions seen by peers, either
+    /// because they expired or the cache was full.
+    pub(crate) seen_transactions_cache_evictions: Counter,
+    /// Number of received transactions dropped because they were below the dynamic fee floor.
+    pub(crate) transactions_below_fee_floor: Counter,
+    /// Number of received transactions with an invalid signature.
+    pub(crate) invalid_transaction_signatures: Counter,
+    /// Number of announced transactions that were not fetched because their announced type is
+    /// unsupported or their announced size is too large.
+    pub(crate) skipped_announced_transactions: Counter,
 }
 
 /// Metrics for Disconnection types
@@ -151,4 +168,24 @@ pub struct EthRequestHandlerMetrics {
 
     /// Number of received bodies requests
     pub(crate) received_bodies_requests: Counter,
+
+    /// Number of requests rejected because the peer exceeded its rate limit
+    pub(crate) rejected_requests: Counter,
+}
+
+/// Metrics for the SnapRequestHandler
+#[derive(Metrics)]
+#[metrics(scope = "network")]
+pub struct SnapRequestHandlerMetrics {
+    /// Number of received account range requests
+    pub(crate) received_account_range_requests: Counter,
+
+    /// Number of received storage ranges requests
+    pub(crate) received_storage_ranges_requests: Counter,
+
+    /// Number of received bytecodes requests
+    pub(crate) received_bytecodes_requests: Counter,
+
+    /// Number of received trie nodes requests
+    pub(crate) received_trie_nodes_requests: Counter,
 }
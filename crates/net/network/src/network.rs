@@ -1,18 +1,24 @@
 use crate::{
     config::NetworkMode, discovery::DiscoveryEvent, manager::NetworkEvent, message::PeerRequest,
-    peers::PeersHandle, FetchClient,
+    peers::PeersHandle, transactions::NetworkTransactionEvent, FetchClient,
 };
 use async_trait::async_trait;
 use parking_lot::Mutex;
 use reth_eth_wire::{DisconnectReason, NewBlock, NewPooledTransactionHashes, SharedTransactions};
-use reth_interfaces::sync::{NetworkSyncUpdater, SyncState, SyncStateProvider};
-use reth_net_common::bandwidth_meter::BandwidthMeter;
+use reth_interfaces::{
+    p2p::transactions::PooledTransactionsClient,
+    sync::{NetworkSyncUpdater, SyncState, SyncStateProvider},
+};
+use reth_net_common::{ban_list::IpSubnet, bandwidth_meter::BandwidthMeter};
 use reth_network_api::{
     NetworkError, NetworkInfo, PeerInfo, PeerKind, Peers, PeersInfo, Reputation,
     ReputationChangeKind,
 };
-use reth_primitives::{Head, NodeRecord, PeerId, TransactionSigned, H256};
+use reth_primitives::{
+    Head, NodeRecord, PeerId, PooledTransactionsElement, TransactionSigned, H256,
+};
 use reth_rpc_types::NetworkStatus;
+use reth_tasks::watchdog::QueuedMessages;
 use std::{
     net::SocketAddr,
     sync::{
@@ -22,6 +28,7 @@ use std::{
 };
 use tokio::sync::{mpsc, mpsc::UnboundedSender, oneshot};
 use tokio_stream::wrappers::UnboundedReceiverStream;
+use tracing::trace;
 
 /// A _shareable_ network frontend. Used to interact with the network.
 ///
@@ -50,7 +57,9 @@ impl NetworkHandle {
         let inner = NetworkInner {
             num_active_peers,
             to_manager_tx,
+            queued_messages: QueuedMessages::default(),
             listener_address,
+            external_node_record: Mutex::new(None),
             local_peer_id,
             peers,
             network_mode,
@@ -74,14 +83,16 @@ impl NetworkHandle {
         &self.inner.peers
     }
 
-    fn manager(&self) -> &UnboundedSender<NetworkHandleMessage> {
-        &self.inner.to_manager_tx
+    /// Sets the [`NodeRecord`] with the addresses on the gateway that are mapped to the listener
+    /// and discovery ports, which is then announced instead of the local address.
+    pub(crate) fn set_external_node_record(&self, record: NodeRecord) {
+        *self.inner.external_node_record.lock() = Some(record);
     }
 
     /// Creates a new [`NetworkEvent`] listener channel.
     pub fn event_listener(&self) -> UnboundedReceiverStream<NetworkEvent> {
         let (tx, rx) = mpsc::unbounded_channel();
-        let _ = self.manager().send(NetworkHandleMessage::EventListener(tx));
+        self.send_message(NetworkHandleMessage::EventListener(tx));
         UnboundedReceiverStream::new(rx)
     }
 
@@ -90,7 +101,7 @@ impl NetworkHandle {
     /// This stream yields [`DiscoveryEvent`]s for each peer that is discovered.
     pub fn discovery_listener(&self) -> UnboundedReceiverStream<DiscoveryEvent> {
         let (tx, rx) = mpsc::unbounded_channel();
-        let _ = self.manager().send(NetworkHandleMessage::DiscoveryListener(tx));
+        self.send_message(NetworkHandleMessage::DiscoveryListener(tx));
         UnboundedReceiverStream::new(rx)
     }
 
@@ -99,7 +110,7 @@ impl NetworkHandle {
     /// The [`FetchClient`] is the entrypoint for sending requests to the network.
     pub async fn fetch_client(&self) -> Result<FetchClient, oneshot::error::RecvError> {
         let (tx, rx) = oneshot::channel();
-        let _ = self.manager().send(NetworkHandleMessage::FetchClient(tx));
+        self.send_message(NetworkHandleMessage::FetchClient(tx));
         rx.await
     }
 
@@ -111,7 +122,7 @@ impl NetworkHandle {
         peer_id: PeerId,
     ) -> Result<Option<PeerInfo>, oneshot::error::RecvError> {
         let (tx, rx) = oneshot::channel();
-        let _ = self.manager().send(NetworkHandleMessage::GetPeerInfoById(peer_id, tx));
+        self.send_message(NetworkHandleMessage::GetPeerInfoById(peer_id, tx));
         rx.await
     }
 
@@ -122,7 +133,15 @@ impl NetworkHandle {
 
     /// Sends a [`NetworkHandleMessage`] to the manager
     pub(crate) fn send_message(&self, msg: NetworkHandleMessage) {
-        let _ = self.inner.to_manager_tx.send(msg);
+        self.inner.queued_messages.on_send();
+        if self.inner.to_manager_tx.send(msg).is_err() {
+            self.inner.queued_messages.on_recv();
+        }
+    }
+
+    /// Returns the messages sent to the manager that it hasn't received yet.
+    pub(crate) fn queued_messages(&self) -> &QueuedMessages {
+        &self.inner.queued_messages
     }
 
     /// Update the status of the node.
@@ -157,11 +176,32 @@ impl NetworkHandle {
         })
     }
 
+    /// Bans the IP address or subnet until it's unbanned.
+    ///
+    /// Connected peers with an address in the subnet are disconnected and new connections from it
+    /// are rejected before the RLPx handshake.
+    pub fn ban_ip(&self, subnet: impl Into<IpSubnet>) {
+        self.send_message(NetworkHandleMessage::BanIp(subnet.into()))
+    }
+
+    /// Unbans the IP address or subnet, see [`Self::ban_ip`].
+    pub fn unban_ip(&self, subnet: impl Into<IpSubnet>) {
+        self.send_message(NetworkHandleMessage::UnbanIp(subnet.into()))
+    }
+
     /// Provides a shareable reference to the [`BandwidthMeter`] stored on the [`NetworkInner`]
     pub fn bandwidth_meter(&self) -> &BandwidthMeter {
         &self.inner.bandwidth_meter
     }
 
+    /// Sets the channel the transaction events of the network are sent to, replacing the channel
+    /// of the previous [`TransactionsManager`](crate::transactions::TransactionsManager).
+    ///
+    /// This is used to restart the transactions manager.
+    pub fn set_transactions(&self, tx: mpsc::UnboundedSender<NetworkTransactionEvent>) {
+        self.send_message(NetworkHandleMessage::SetTransactions(tx))
+    }
+
     /// Send message to gracefully shutdown node.
     ///
     /// This will disconnect all active and pending sessions and prevent
@@ -182,6 +222,10 @@ impl PeersInfo for NetworkHandle {
 
     fn local_node_record(&self) -> NodeRecord {
         let id = *self.peer_id();
+        if let Some(record) = *self.inner.external_node_record.lock() {
+            return record
+        }
+
         let mut socket_addr = *self.inner.listener_address.lock();
 
         if socket_addr.ip().is_unspecified() {
@@ -207,7 +251,7 @@ impl Peers for NetworkHandle {
 
     async fn get_peers(&self) -> Result<Vec<PeerInfo>, NetworkError> {
         let (tx, rx) = oneshot::channel();
-        let _ = self.manager().send(NetworkHandleMessage::GetPeerInfo(tx));
+        self.send_message(NetworkHandleMessage::GetPeerInfo(tx));
         Ok(rx.await?)
     }
 
@@ -236,7 +280,7 @@ impl Peers for NetworkHandle {
 
     async fn reputation_by_id(&self, peer_id: PeerId) -> Result<Option<Reputation>, NetworkError> {
         let (tx, rx) = oneshot::channel();
-        let _ = self.manager().send(NetworkHandleMessage::GetReputationById(peer_id, tx));
+        self.send_message(NetworkHandleMessage::GetReputationById(peer_id, tx));
         Ok(rx.await?)
     }
 }
@@ -249,7 +293,7 @@ impl NetworkInfo for NetworkHandle {
 
     async fn network_status(&self) -> Result<NetworkStatus, NetworkError> {
         let (tx, rx) = oneshot::channel();
-        let _ = self.manager().send(NetworkHandleMessage::GetStatus(tx));
+        self.send_message(NetworkHandleMessage::GetStatus(tx));
         rx.await.map_err(Into::into)
     }
 
@@ -264,6 +308,20 @@ impl NetworkInfo for NetworkHandle {
     fn is_initially_syncing(&self) -> bool {
         SyncStateProvider::is_initially_syncing(self)
     }
+
+    async fn get_pooled_transactions(
+        &self,
+        hashes: Vec<H256>,
+    ) -> Result<Vec<PooledTransactionsElement>, NetworkError> {
+        let client = self.fetch_client().await?;
+        match client.get_pooled_transactions(hashes).await {
+            Ok(response) => Ok(response.into_data()),
+            Err(err) => {
+                trace!(target: "net", ?err, "Failed to request pooled transactions from peers");
+                Ok(Vec::new())
+            }
+        }
+    }
 }
 
 impl SyncStateProvider for NetworkHandle {
@@ -301,8 +359,13 @@ struct NetworkInner {
     num_active_peers: Arc<AtomicUsize>,
     /// Sender half of the message channel to the [`crate::NetworkManager`].
     to_manager_tx: UnboundedSender<NetworkHandleMessage>,
+    /// The messages sent to the [`crate::NetworkManager`] that it hasn't received yet.
+    queued_messages: QueuedMessages,
     /// The local address that accepts incoming connections.
     listener_address: Arc<Mutex<SocketAddr>>,
+    /// The record with the addresses on the gateway that are mapped to the listener and discovery
+    /// ports, if any.
+    external_node_record: Mutex<Option<NodeRecord>>,
     /// The identifier used by this node.
     local_peer_id: PeerId,
     /// Access to the all the nodes.
@@ -345,6 +408,10 @@ pub(crate) enum NetworkHandleMessage {
     },
     /// Apply a reputation change to the given peer.
     ReputationChange(PeerId, ReputationChangeKind),
+    /// Bans the subnet and disconnects all peers with an address in it.
+    BanIp(IpSubnet),
+    /// Unbans the subnet.
+    UnbanIp(IpSubnet),
     /// Returns the client that can be used to interact with the network.
     FetchClient(oneshot::Sender<FetchClient>),
     /// Apply a status update.
@@ -361,4 +428,6 @@ pub(crate) enum NetworkHandleMessage {
     Shutdown(oneshot::Sender<()>),
     /// Add a new listener for `DiscoveryEvent`.
     DiscoveryListener(UnboundedSender<DiscoveryEvent>),
+    /// Replaces the channel to the transactions manager.
+    SetTransactions(UnboundedSender<NetworkTransactionEvent>),
 }
@@ -1,23 +1,26 @@
 use crate::{
     error::{BackoffKind, SessionError},
     peers::{
+        persist::{read_peers_file, unix_timestamp, write_peers_file, PersistedPeer},
         reputation::{is_banned_reputation, DEFAULT_REPUTATION},
-        ReputationChangeWeights, DEFAULT_MAX_CONCURRENT_DIALS, DEFAULT_MAX_PEERS_INBOUND,
-        DEFAULT_MAX_PEERS_OUTBOUND,
+        ReputationChangeWeights, ReputationPolicy, SharedReputationPolicy,
+        DEFAULT_MAX_CONCURRENT_DIALS, DEFAULT_MAX_PEERS_INBOUND, DEFAULT_MAX_PEERS_OUTBOUND,
     },
     session::{Direction, PendingSessionHandshakeError},
 };
 use futures::StreamExt;
+use parking_lot::RwLock;
 use reth_eth_wire::{errors::EthStreamError, DisconnectReason};
-use reth_net_common::ban_list::BanList;
+use reth_net_common::ban_list::{BanList, IpSubnet};
 use reth_network_api::{PeerKind, ReputationChangeKind};
 use reth_primitives::{ForkId, NodeRecord, PeerId};
 use std::{
     collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
     fmt::Display,
-    io::{self, ErrorKind},
+    io,
     net::{IpAddr, SocketAddr},
-    path::Path,
+    path::{Path, PathBuf},
+    sync::Arc,
     task::{Context, Poll},
     time::Duration,
 };
@@ -93,7 +96,7 @@ pub(crate) struct PeersManager {
     /// Interval for triggering connections if there are free slots.
     refill_slots_interval: Interval,
     /// How to weigh reputation changes
-    reputation_weights: ReputationChangeWeights,
+    reputation_policy: Arc<dyn ReputationPolicy>,
     /// Tracks current slot stats.
     connection_info: ConnectionInfo,
     /// Tracks unwanted ips/peer ids.
@@ -113,6 +116,14 @@ pub(crate) struct PeersManager {
     last_tick: Instant,
     /// Maximum number of backoff attempts before we give up on a peer and dropping.
     max_backoff_count: u32,
+    /// How to back off trusted peers that we failed to connect to.
+    trusted_peer_backoff: TrustedPeerBackoff,
+    /// Connection policies for peers by client version, shared with the sessions.
+    client_version_filter: ClientVersionFilter,
+    /// The file the known peers are periodically written to, if any.
+    peers_file: Option<PathBuf>,
+    /// Interval at which the known peers are written to the peers file.
+    persist_interval: Interval,
 }
 
 impl PeersManager {
@@ -122,6 +133,7 @@ impl PeersManager {
             refill_slots_interval,
             connection_info,
             reputation_weights,
+            reputation_policy,
             ban_list,
             ban_duration,
             backoff_durations,
@@ -129,6 +141,11 @@ impl PeersManager {
             connect_trusted_nodes_only,
             basic_nodes,
             max_backoff_count,
+            trusted_peer_backoff,
+            client_version_policies,
+            persisted_peers,
+            peers_file,
+            persist_interval,
         } = config;
         let (manager_tx, handle_rx) = mpsc::unbounded_channel();
         let now = Instant::now();
@@ -136,6 +153,11 @@ impl PeersManager {
         // We use half of the interval to decrease the max duration to `150%` in worst case
         let unban_interval = ban_duration.min(backoff_durations.low) / 2;
 
+        let reputation_policy = match reputation_policy {
+            Some(policy) => policy.0,
+            None => Arc::new(reputation_weights),
+        };
+
         let mut peers = HashMap::with_capacity(trusted_nodes.len() + basic_nodes.len());
 
         for NodeRecord { address, tcp_port, udp_port: _, id } in trusted_nodes {
@@ -146,12 +168,32 @@ impl PeersManager {
             peers.entry(id).or_insert_with(|| Peer::new(SocketAddr::from((address, tcp_port))));
         }
 
+        // restore the state of the peers known before the restart, whether a peer is trusted is
+        // only determined by the config
+        let mut backed_off_peers = HashMap::new();
+        for persisted in persisted_peers {
+            let NodeRecord { address, tcp_port, udp_port: _, id } = persisted.record;
+            let addr = SocketAddr::from((address, tcp_port));
+            let peer = peers.entry(id).or_insert_with(|| Peer::new(addr));
+            peer.reputation = persisted.reputation;
+            peer.severe_backoff_counter = persisted.severe_backoff_counter;
+            if let Some(remaining) = persisted.remaining_backoff() {
+                peer.backed_off = true;
+                backed_off_peers.insert(id, std::time::Instant::now() + remaining);
+            }
+        }
+
+        let client_version_filter = ClientVersionFilter::new(
+            client_version_policies,
+            peers.iter().filter(|(_, peer)| peer.is_trusted()).map(|(id, _)| *id),
+        );
+
         Self {
             peers,
             manager_tx,
             handle_rx: UnboundedReceiverStream::new(handle_rx),
             queued_actions: Default::default(),
-            reputation_weights,
+            reputation_policy,
             refill_slots_interval: tokio::time::interval_at(
                 now + refill_slots_interval,
                 refill_slots_interval,
@@ -159,12 +201,16 @@ impl PeersManager {
             release_interval: tokio::time::interval_at(now + unban_interval, unban_interval),
             connection_info,
             ban_list,
-            backed_off_peers: Default::default(),
+            backed_off_peers,
             ban_duration,
             backoff_durations,
             connect_trusted_nodes_only,
             last_tick: Instant::now(),
             max_backoff_count,
+            trusted_peer_backoff,
+            client_version_filter,
+            peers_file,
+            persist_interval: tokio::time::interval_at(now + persist_interval, persist_interval),
         }
     }
 
@@ -184,6 +230,36 @@ impl PeersManager {
         self.peers.iter().map(|(peer_id, v)| NodeRecord::new(v.addr, *peer_id))
     }
 
+    /// Returns the known peers with their reputation and backoff, as written to the peers file.
+    ///
+    /// Banned peers and peers that are removed once their session is closed are skipped.
+    pub(crate) fn persisted_peers(&self) -> Vec<PersistedPeer> {
+        self.peers
+            .iter()
+            .filter(|(_, peer)| !peer.remove_after_disconnect && !peer.is_banned())
+            .map(|(peer_id, peer)| PersistedPeer {
+                record: NodeRecord::new(peer.addr, *peer_id),
+                reputation: peer.reputation,
+                backed_off_until: self.backed_off_peers.get(peer_id).copied().map(unix_timestamp),
+                severe_backoff_counter: peer.severe_backoff_counter,
+            })
+            .collect()
+    }
+
+    /// Writes the known peers to the peers file on a blocking task, if one is configured.
+    fn persist_peers(&self) {
+        let Some(path) = self.peers_file.clone() else { return };
+        let peers = self.persisted_peers();
+        tokio::task::spawn_blocking(move || match write_peers_file(&path, &peers) {
+            Ok(()) => {
+                trace!(target: "net::peers", file = %path.display(), num_peers = peers.len(), "Persisted known peers")
+            }
+            Err(err) => {
+                debug!(target: "net::peers", %err, file = %path.display(), "Failed to persist known peers")
+            }
+        });
+    }
+
     /// Returns the number of currently active inbound connections.
     #[inline]
     pub(crate) fn num_inbound_connections(&self) -> usize {
@@ -202,6 +278,17 @@ impl PeersManager {
         self.backed_off_peers.len()
     }
 
+    /// Returns the number of trusted peers that are currently not connected because the last
+    /// connection attempt failed.
+    pub(crate) fn num_unreachable_trusted_peers(&self) -> usize {
+        self.peers
+            .values()
+            .filter(|peer| {
+                peer.is_trusted() && peer.reconnect_attempts > 0 && !peer.state.is_connected()
+            })
+            .count()
+    }
+
     /// Invoked when a new _incoming_ tcp connection is accepted.
     ///
     /// returns an error if the inbound ip address is on the ban list or
@@ -238,7 +325,14 @@ impl PeersManager {
         remote_addr: SocketAddr,
         err: &PendingSessionHandshakeError,
     ) {
-        if err.is_fatal_protocol_error() {
+        if let PendingSessionHandshakeError::ClientVersion { peer_id, .. } = err {
+            // the client is banned, not the IP
+            if let Some(peer) = self.peers.remove(peer_id) {
+                self.connection_info.decr_state(peer.state);
+                self.queued_actions.push_back(PeerAction::PeerRemoved(*peer_id));
+            }
+            self.ban_peer(*peer_id);
+        } else if err.is_fatal_protocol_error() {
             self.ban_ip(remote_addr.ip());
 
             if err.merits_discovery_ban() {
@@ -275,6 +369,7 @@ impl PeersManager {
                     return
                 }
                 value.state = PeerConnectionState::In;
+                value.reconnect_attempts = 0;
             }
             Entry::Vacant(entry) => {
                 // peer is missing in the table, we add it but mark it as to be removed after
@@ -285,6 +380,50 @@ impl PeersManager {
                 self.queued_actions.push_back(PeerAction::PeerAdded(peer_id));
             }
         }
+
+        if self.connect_trusted_nodes_only {
+            if let Some(peer) = self.peers.get_mut(&peer_id).filter(|peer| !peer.is_trusted()) {
+                // only sessions with trusted peers are accepted
+                peer.state.disconnect();
+                self.queued_actions.push_back(PeerAction::Disconnect {
+                    peer_id,
+                    reason: Some(DisconnectReason::UselessPeer),
+                });
+            }
+        }
+    }
+
+    /// Called when a new _outgoing_ active session was established to the given peer.
+    pub(crate) fn on_outgoing_session_established(&mut self, peer_id: PeerId) {
+        if let Some(peer) = self.peers.get_mut(&peer_id) {
+            peer.reconnect_attempts = 0;
+        }
+    }
+
+    /// Returns the [ClientVersionFilter] that rejects banned clients during the `Hello` handshake
+    /// of sessions.
+    pub(crate) fn client_version_filter(&self) -> ClientVersionFilter {
+        self.client_version_filter.clone()
+    }
+
+    /// Called with the client version a peer announced in the `Hello` message of an established
+    /// session.
+    ///
+    /// Applies the first [ClientVersionPolicy] that matches the client version. Trusted peers are
+    /// exempt from policies. Banned clients are already rejected during the handshake, see
+    /// [ClientVersionFilter].
+    pub(crate) fn on_session_client_version(&mut self, peer_id: PeerId, client_version: &str) {
+        let Some(policy) = self.client_version_filter.policy(client_version) else { return };
+        if policy.action != ClientVersionAction::Deprioritize {
+            return
+        }
+        let Some(peer) = self.peers.get_mut(&peer_id) else { return };
+        if peer.is_trusted() {
+            return
+        }
+
+        debug!(target: "net::peers", ?peer_id, %client_version, pattern=%policy.pattern, "deprioritizing peer by client version");
+        peer.deprioritized = true;
     }
 
     /// Bans the peer temporarily with the configured ban timeout
@@ -298,6 +437,27 @@ impl PeersManager {
         self.ban_list.ban_ip_until(ip, std::time::Instant::now() + self.ban_duration);
     }
 
+    /// Bans the subnet until it's unbanned and disconnects all peers with an address in it.
+    pub(crate) fn ban_subnet(&mut self, subnet: IpSubnet) {
+        debug!(target : "net::peers", %subnet, "banning subnet");
+        self.ban_list.ban_subnet(subnet);
+
+        for (peer_id, peer) in self.peers.iter_mut() {
+            if peer.state.is_connected() && subnet.contains(&peer.addr.ip()) {
+                peer.state.disconnect();
+                self.queued_actions.push_back(PeerAction::Disconnect {
+                    peer_id: *peer_id,
+                    reason: Some(DisconnectReason::DisconnectRequested),
+                });
+            }
+        }
+    }
+
+    /// Unbans the subnet.
+    pub(crate) fn unban_subnet(&mut self, subnet: IpSubnet) {
+        self.ban_list.unban_subnet(&subnet);
+    }
+
     /// Temporarily puts the peer in timeout by inserting it into the backedoff peers set
     fn backoff_peer_until(&mut self, peer_id: PeerId, until: std::time::Instant) {
         trace!(target: "net::peers", ?peer_id, "backing off");
@@ -349,8 +509,8 @@ impl PeersManager {
             if rep.is_reset() {
                 peer.reset_reputation()
             } else {
-                let reputation_change = self.reputation_weights.change(rep);
-                peer.apply_reputation(reputation_change.as_i32())
+                let reputation_change = self.reputation_policy.reputation_change(rep);
+                peer.apply_reputation(reputation_change)
             }
         } else {
             return
@@ -452,7 +612,10 @@ impl PeersManager {
     ) {
         trace!(target: "net::peers", ?remote_addr, ?peer_id, ?err, "handling failed connection");
 
-        if err.is_fatal_protocol_error() {
+        if self.peers.get(peer_id).map_or(false, |peer| peer.is_trusted()) {
+            // trusted peers are never removed, instead we keep trying to reconnect
+            self.on_trusted_connection_failure(peer_id);
+        } else if err.is_fatal_protocol_error() {
             trace!(target: "net::peers", ?remote_addr, ?peer_id, ?err, "fatal connection error");
             // remove the peer to which we can't establish a connection due to protocol related
             // issues.
@@ -491,8 +654,9 @@ impl PeersManager {
                     backoff_until = Some(backoff_time);
                 } else {
                     // If the error was not a backoff error, we reduce the peer's reputation
-                    let reputation_change = self.reputation_weights.change(reputation_change);
-                    peer.reputation = peer.reputation.saturating_add(reputation_change.as_i32());
+                    let reputation_change =
+                        self.reputation_policy.reputation_change(reputation_change);
+                    peer.reputation = peer.reputation.saturating_add(reputation_change);
                 };
 
                 self.connection_info.decr_state(peer.state);
@@ -518,6 +682,21 @@ impl PeersManager {
         self.fill_outbound_slots();
     }
 
+    /// Backs off a trusted peer after a failed connection attempt.
+    ///
+    /// The backoff grows exponentially with the number of consecutive failed attempts and is
+    /// randomized by [TrustedPeerBackoff::jitter] to avoid reconnecting in lockstep.
+    fn on_trusted_connection_failure(&mut self, peer_id: &PeerId) {
+        let Some(peer) = self.peers.get_mut(peer_id) else { return };
+        self.connection_info.decr_state(peer.state);
+        peer.state = PeerConnectionState::Idle;
+        peer.reconnect_attempts = peer.reconnect_attempts.saturating_add(1);
+
+        let backoff = self.trusted_peer_backoff.backoff(peer.reconnect_attempts);
+        debug!(target: "net::peers", ?peer_id, attempts=peer.reconnect_attempts, ?backoff, "failed to reach trusted peer");
+        self.backoff_peer_until(*peer_id, std::time::Instant::now() + backoff);
+    }
+
     /// Invoked if a pending session was disconnected because there's already a connection to the
     /// peer.
     ///
@@ -573,6 +752,8 @@ impl PeersManager {
             return
         }
 
+        self.client_version_filter.set_trusted(peer_id, kind == PeerKind::Trusted);
+
         match self.peers.entry(peer_id) {
             Entry::Occupied(mut entry) => {
                 let peer = entry.get_mut();
@@ -634,6 +815,16 @@ impl PeersManager {
         let peer = entry.get_mut();
 
         peer.kind = PeerKind::Basic;
+        self.client_version_filter.set_trusted(peer_id, false);
+
+        if self.connect_trusted_nodes_only && peer.state.is_connected() {
+            // only sessions with trusted peers are kept
+            peer.state.disconnect();
+            self.queued_actions.push_back(PeerAction::Disconnect {
+                peer_id,
+                reason: Some(DisconnectReason::DisconnectRequested),
+            });
+        }
     }
 
     /// Returns the idle peer with the highest reputation.
@@ -650,6 +841,7 @@ impl PeersManager {
             !peer.is_backed_off() &&
                 !peer.is_banned() &&
                 peer.state.is_unconnected() &&
+                !self.ban_list.is_banned_ip(&peer.addr.ip()) &&
                 (!self.connect_trusted_nodes_only || peer.is_trusted())
         });
 
@@ -666,8 +858,11 @@ impl PeersManager {
                 return Some((*maybe_better.0, maybe_better.1))
             }
 
-            // otherwise we keep track of the best peer using the reputation
-            if maybe_better.1.reputation > best_peer.1.reputation {
+            // otherwise we keep track of the best peer using the reputation, deprioritized peers
+            // are only picked if there are no other peers
+            if (!maybe_better.1.deprioritized, maybe_better.1.reputation) >
+                (!best_peer.1.deprioritized, best_peer.1.reputation)
+            {
                 best_peer = maybe_better;
             }
         }
@@ -682,6 +877,9 @@ impl PeersManager {
     fn fill_outbound_slots(&mut self) {
         self.tick();
 
+        // trusted peers are always dialed, regardless of free slots
+        self.dial_trusted_peers();
+
         // as long as there a slots available try to fill them with the best peers
         let mut new_outbound_dials = 1;
         while self.connection_info.has_out_capacity() {
@@ -713,6 +911,28 @@ impl PeersManager {
         }
     }
 
+    /// Queues [`PeerAction::Connect`] actions for all trusted peers that are currently not
+    /// connected, unless they are backed off.
+    fn dial_trusted_peers(&mut self) {
+        let mut dials = Vec::new();
+        for (peer_id, peer) in self.peers.iter_mut() {
+            if peer.is_trusted() &&
+                peer.state.is_unconnected() &&
+                !peer.is_backed_off() &&
+                !peer.is_banned()
+            {
+                trace!(target : "net::peers",  ?peer_id, addr=?peer.addr, "schedule outbound connection to trusted peer");
+                peer.state = PeerConnectionState::Out;
+                dials.push(PeerAction::Connect { peer_id: *peer_id, remote_addr: peer.addr });
+            }
+        }
+
+        for action in dials {
+            self.connection_info.inc_out();
+            self.queued_actions.push_back(action);
+        }
+    }
+
     /// Advances the state.
     ///
     /// Event hooks invoked externally may trigger a new [`PeerAction`] that are buffered until
@@ -772,6 +992,10 @@ impl PeersManager {
                 self.fill_outbound_slots();
             }
 
+            if self.persist_interval.poll_tick(cx).is_ready() {
+                self.persist_peers();
+            }
+
             if self.queued_actions.is_empty() {
                 return Poll::Pending
             }
@@ -807,6 +1031,16 @@ pub struct ConnectionInfo {
 // === impl ConnectionInfo ===
 
 impl ConnectionInfo {
+    /// Returns the maximum allowed outbound connections.
+    pub fn max_outbound(&self) -> usize {
+        self.max_outbound
+    }
+
+    /// Returns the maximum allowed inbound connections.
+    pub fn max_inbound(&self) -> usize {
+        self.max_inbound
+    }
+
     ///  Returns `true` if there's still capacity for a new outgoing connection.
     fn has_out_capacity(&self) -> bool {
         self.num_outbound < self.max_outbound
@@ -873,6 +1107,10 @@ pub struct Peer {
     backed_off: bool,
     /// Counts number of times the peer was backed off due to a severe [BackoffKind].
     severe_backoff_counter: u32,
+    /// Number of consecutive failed connection attempts to a trusted peer.
+    reconnect_attempts: u32,
+    /// Whether the client of the peer matched a [ClientVersionAction::Deprioritize] policy.
+    deprioritized: bool,
 }
 
 // === impl Peer ===
@@ -896,6 +1134,8 @@ impl Peer {
             kind: Default::default(),
             backed_off: false,
             severe_backoff_counter: 0,
+            reconnect_attempts: 0,
+            deprioritized: false,
         }
     }
 
@@ -1078,6 +1318,9 @@ pub struct PeersConfig {
     /// Trusted nodes to connect to.
     pub trusted_nodes: HashSet<NodeRecord>,
     /// Connect to trusted nodes only?
+    ///
+    /// If enabled, sessions with peers that are not trusted are rejected as well. Trusted peers
+    /// can be added and removed at runtime, e.g. with `admin_addTrustedPeer`.
     pub connect_trusted_nodes_only: bool,
     /// Maximum number of backoff attempts before we give up on a peer and dropping.
     ///
@@ -1102,11 +1345,31 @@ pub struct PeersConfig {
     pub connection_info: ConnectionInfo,
     /// How to weigh reputation changes.
     pub reputation_weights: ReputationChangeWeights,
+    /// Replaces the [`reputation_weights`](Self::reputation_weights) if set.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub reputation_policy: Option<SharedReputationPolicy>,
     /// How long to backoff peers that are we failed to connect to for non-fatal reasons, such as
     /// [`DisconnectReason::TooManyPeers`].
     ///
     /// The backoff duration increases with number of backoff attempts.
     pub backoff_durations: PeerBackoffDurations,
+    /// How to back off trusted peers that we failed to connect to.
+    ///
+    /// Trusted peers are never dropped from the set, instead we keep trying to reconnect.
+    pub trusted_peer_backoff: TrustedPeerBackoff,
+    /// Connection policies for peers by the client version of their `Hello` message.
+    ///
+    /// The first matching policy applies, trusted peers are exempt.
+    pub client_version_policies: Vec<ClientVersionPolicy>,
+    /// Peers known before the restart, with their reputation and backoff.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub persisted_peers: Vec<PersistedPeer>,
+    /// The file the known peers are periodically written to, if any.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub peers_file: Option<PathBuf>,
+    /// How often the known peers are written to the peers file.
+    #[cfg_attr(feature = "serde", serde(with = "humantime_serde"))]
+    pub persist_interval: Duration,
 }
 
 impl Default for PeersConfig {
@@ -1115,6 +1378,7 @@ impl Default for PeersConfig {
             refill_slots_interval: Duration::from_millis(5_000),
             connection_info: Default::default(),
             reputation_weights: Default::default(),
+            reputation_policy: None,
             ban_list: Default::default(),
             // Ban peers for 12h
             ban_duration: Duration::from_secs(60 * 60 * 12),
@@ -1123,6 +1387,12 @@ impl Default for PeersConfig {
             connect_trusted_nodes_only: false,
             basic_nodes: Default::default(),
             max_backoff_count: 5,
+            trusted_peer_backoff: Default::default(),
+            client_version_policies: Default::default(),
+            persisted_peers: Default::default(),
+            peers_file: None,
+            // 5min
+            persist_interval: Duration::from_secs(60 * 5),
         }
     }
 }
@@ -1134,6 +1404,14 @@ impl PeersConfig {
         self
     }
 
+    /// IP addresses and subnets that we want to never connect to, see [`BanList::ban_subnet`].
+    pub fn with_banned_subnets(mut self, subnets: impl IntoIterator<Item = IpSubnet>) -> Self {
+        for subnet in subnets {
+            self.ban_list.ban_subnet(subnet);
+        }
+        self
+    }
+
     /// Maximum occupied slots for outbound connections.
     pub fn with_max_pending_outbound(mut self, num_outbound: usize) -> Self {
         self.connection_info.num_outbound = num_outbound;
@@ -1198,20 +1476,51 @@ impl PeersConfig {
         self
     }
 
+    /// Connection policies for peers by client version.
+    pub fn with_client_version_policies(mut self, policies: Vec<ClientVersionPolicy>) -> Self {
+        self.client_version_policies = policies;
+        self
+    }
+
+    /// Sets the [`ReputationPolicy`] that replaces the configured reputation weights.
+    pub fn with_reputation_policy(mut self, policy: impl ReputationPolicy) -> Self {
+        self.reputation_policy = Some(SharedReputationPolicy::new(policy));
+        self
+    }
+
     /// Read from file nodes available at launch. Ignored if None.
+    ///
+    /// The saved reputation and backoff of the peers is discarded, see also
+    /// [Self::with_peers_file].
     pub fn with_basic_nodes_from_file(
         self,
         optional_file: Option<impl AsRef<Path>>,
     ) -> Result<Self, io::Error> {
         let Some(file_path) = optional_file else { return Ok(self) };
-        let reader = match std::fs::File::open(file_path.as_ref()) {
-            Ok(file) => io::BufReader::new(file),
-            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(self),
-            Err(e) => Err(e)?,
-        };
         info!(target: "net::peers", file = %file_path.as_ref().display(), "Loading saved peers");
-        let nodes: HashSet<NodeRecord> = serde_json::from_reader(reader)?;
-        Ok(self.with_basic_nodes(nodes))
+        let nodes = read_peers_file(file_path.as_ref())?.into_iter().map(|peer| peer.record);
+        Ok(self.with_basic_nodes(nodes.collect()))
+    }
+
+    /// Peers known before the restart, with their reputation and backoff.
+    pub fn with_persisted_peers(mut self, peers: Vec<PersistedPeer>) -> Self {
+        self.persisted_peers = peers;
+        self
+    }
+
+    /// Restores the known peers from the file and periodically writes them back to it. Ignored if
+    /// None.
+    pub fn with_peers_file(self, optional_file: Option<PathBuf>) -> Result<Self, io::Error> {
+        let Some(file_path) = optional_file else { return Ok(self) };
+        info!(target: "net::peers", file = %file_path.display(), "Loading saved peers");
+        let peers = read_peers_file(&file_path)?;
+        Ok(Self { peers_file: Some(file_path), ..self.with_persisted_peers(peers) })
+    }
+
+    /// How often the known peers are written to the peers file.
+    pub fn with_persist_interval(mut self, interval: Duration) -> Self {
+        self.persist_interval = interval;
+        self
     }
 }
 
@@ -1272,6 +1581,152 @@ impl Default for PeerBackoffDurations {
     }
 }
 
+/// Exponential backoff for reconnecting to trusted peers.
+///
+/// See also [PeersConfig::trusted_peer_backoff].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TrustedPeerBackoff {
+    /// Backoff after the first failed attempt, doubled with every consecutive failed attempt.
+    #[cfg_attr(feature = "serde", serde(with = "humantime_serde"))]
+    pub initial: Duration,
+    /// Maximum backoff duration.
+    #[cfg_attr(feature = "serde", serde(with = "humantime_serde"))]
+    pub max: Duration,
+    /// Maximum fraction of the backoff that is randomly added on top of it.
+    pub jitter: f64,
+}
+
+impl TrustedPeerBackoff {
+    /// Returns the backoff duration after `attempts` consecutive failed attempts, without jitter.
+    pub fn base_backoff(&self, attempts: u32) -> Duration {
+        let factor = 1u32.checked_shl(attempts.saturating_sub(1)).unwrap_or(u32::MAX);
+        self.initial.saturating_mul(factor).min(self.max)
+    }
+
+    /// Returns the backoff duration after `attempts` consecutive failed attempts, with jitter.
+    pub fn backoff(&self, attempts: u32) -> Duration {
+        let base = self.base_backoff(attempts);
+        let jitter = base.mul_f64(self.jitter.clamp(0.0, 1.0) * rand::random::<f64>());
+        base + jitter
+    }
+}
+
+impl Default for TrustedPeerBackoff {
+    fn default() -> Self {
+        Self { initial: Duration::from_secs(5), max: Duration::from_secs(60 * 5), jitter: 0.25 }
+    }
+}
+
+/// A connection policy for peers whose client version matches a pattern.
+///
+/// See also [PeersConfig::client_version_policies].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClientVersionPolicy {
+    /// The pattern to match the client version against, e.g. `Geth/v1.10.*`.
+    ///
+    /// `*` matches any sequence of characters, the match is case-insensitive.
+    pub pattern: String,
+    /// What to do with peers whose client version matches.
+    pub action: ClientVersionAction,
+}
+
+impl ClientVersionPolicy {
+    /// Returns true if the client version matches the pattern of the policy.
+    pub fn matches(&self, client_version: &str) -> bool {
+        glob_match(
+            self.pattern.to_ascii_lowercase().as_bytes(),
+            client_version.to_ascii_lowercase().as_bytes(),
+        )
+    }
+}
+
+/// Rejects peers whose client version matches a [ClientVersionAction::Ban] policy during the
+/// `Hello` handshake, before the session is established.
+///
+/// This is shared by the [PeersManager] and the sessions. The [PeersManager] keeps the set of
+/// trusted peers, which are exempt from policies, up to date.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ClientVersionFilter {
+    policies: Arc<[ClientVersionPolicy]>,
+    trusted: Arc<RwLock<HashSet<PeerId>>>,
+}
+
+impl ClientVersionFilter {
+    fn new(policies: Vec<ClientVersionPolicy>, trusted: impl IntoIterator<Item = PeerId>) -> Self {
+        Self {
+            policies: policies.into(),
+            trusted: Arc::new(RwLock::new(trusted.into_iter().collect())),
+        }
+    }
+
+    /// Returns the first policy that matches the client version.
+    fn policy(&self, client_version: &str) -> Option<&ClientVersionPolicy> {
+        self.policies.iter().find(|policy| policy.matches(client_version))
+    }
+
+    /// Returns the policy that bans the peer with the given client version, if any.
+    pub(crate) fn banned_by(
+        &self,
+        peer_id: &PeerId,
+        client_version: &str,
+    ) -> Option<&ClientVersionPolicy> {
+        self.policy(client_version)
+            .filter(|policy| policy.action == ClientVersionAction::Ban)
+            .filter(|_| !self.trusted.read().contains(peer_id))
+    }
+
+    fn set_trusted(&self, peer_id: PeerId, trusted: bool) {
+        if self.policies.is_empty() {
+            return
+        }
+        if trusted {
+            self.trusted.write().insert(peer_id);
+        } else {
+            self.trusted.write().remove(&peer_id);
+        }
+    }
+}
+
+/// What to do with peers that match a [ClientVersionPolicy].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ClientVersionAction {
+    /// Reject the peer during the `Hello` handshake and ban it for the configured ban duration.
+    Ban,
+    /// Only dial the peer if there are no other peers to dial.
+    Deprioritize,
+}
+
+/// Matches the input against a pattern in which `*` matches any sequence of bytes.
+fn glob_match(pattern: &[u8], input: &[u8]) -> bool {
+    // position of the last `*` in the pattern and of the input it was matched at
+    let mut backtrack = None;
+    let (mut p, mut i) = (0, 0);
+    while i < input.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                backtrack = Some((p, i));
+                p += 1;
+            }
+            Some(c) if *c == input[i] => {
+                p += 1;
+                i += 1;
+            }
+            _ => {
+                // let the last `*` match one more byte
+                let Some((star, matched)) = backtrack else { return false };
+                backtrack = Some((star, matched + 1));
+                p = star + 1;
+                i = matched + 1;
+            }
+        }
+    }
+    pattern[p..].iter().all(|c| *c == b'*')
+}
+
 #[derive(Debug, Error)]
 pub enum InboundConnectionError {
     ExceedsLimit(usize),
@@ -1286,12 +1741,15 @@ impl Display for InboundConnectionError {
 
 #[cfg(test)]
 mod test {
-    use super::PeersManager;
+    use super::{Peer, PeersManager};
     use crate::{
         error::BackoffKind,
         peers::{
-            manager::{ConnectionInfo, PeerBackoffDurations, PeerConnectionState},
-            reputation::DEFAULT_REPUTATION,
+            manager::{
+                ClientVersionAction, ClientVersionPolicy, ConnectionInfo, PeerBackoffDurations,
+                PeerConnectionState, TrustedPeerBackoff,
+            },
+            reputation::{BANNED_REPUTATION, DEFAULT_REPUTATION},
             PeerAction,
         },
         session::PendingSessionHandshakeError,
@@ -1863,6 +2321,41 @@ mod test {
 
         peers.apply_reputation_change(&peer, ReputationChangeKind::Reset);
         assert_eq!(peers.get_reputation(&peer), Some(0));
+
+        // custom kinds have no weight by default
+        peers.apply_reputation_change(&peer, ReputationChangeKind::Custom("spam"));
+        assert_eq!(peers.get_reputation(&peer), Some(0));
+    }
+
+    #[tokio::test]
+    async fn test_reputation_policy() {
+        #[derive(Debug)]
+        struct StrictPolicy;
+
+        impl ReputationPolicy for StrictPolicy {
+            fn reputation_change(&self, kind: ReputationChangeKind) -> i32 {
+                match kind {
+                    ReputationChangeKind::Timeout => 0,
+                    ReputationChangeKind::Custom("spam") => -100,
+                    _ => i32::MIN,
+                }
+            }
+        }
+
+        let peer = PeerId::random();
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let mut peers =
+            PeersManager::new(PeersConfig::default().with_reputation_policy(StrictPolicy));
+        peers.add_peer(peer, socket_addr, None);
+
+        peers.apply_reputation_change(&peer, ReputationChangeKind::Timeout);
+        assert_eq!(peers.get_reputation(&peer), Some(0));
+
+        peers.apply_reputation_change(&peer, ReputationChangeKind::Custom("spam"));
+        assert_eq!(peers.get_reputation(&peer), Some(-100));
+
+        peers.apply_reputation_change(&peer, ReputationChangeKind::BadMessage);
+        assert!(peers.peers.get(&peer).unwrap().is_banned());
     }
 
     #[tokio::test]
@@ -1982,6 +2475,33 @@ mod test {
         }
     }
 
+    #[tokio::test]
+    async fn test_ban_subnet() {
+        let subnet: IpSubnet = "1.2.0.0/16".parse().unwrap();
+        let connected = PeerId::random();
+        let connected_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4)), 8008);
+        let other_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(1, 3, 3, 4)), 8008);
+
+        let mut peers = PeersManager::default();
+        peers.on_incoming_session_established(connected, connected_addr);
+        peers.queued_actions.clear();
+
+        peers.ban_subnet(subnet);
+        match peers.queued_actions.pop_front() {
+            Some(PeerAction::Disconnect { peer_id, .. }) => assert_eq!(peer_id, connected),
+            _ => unreachable!(),
+        }
+        assert!(peers.on_incoming_pending_session(connected_addr.ip()).is_err());
+        assert!(peers.on_incoming_pending_session(other_addr.ip()).is_ok());
+
+        // discovered peers in the subnet are ignored
+        peers.add_peer(PeerId::random(), SocketAddr::new([1, 2, 9, 9].into(), 30303), None);
+        assert_eq!(peers.peers.len(), 1);
+
+        peers.unban_subnet(subnet);
+        assert!(peers.on_incoming_pending_session(connected_addr.ip()).is_ok());
+    }
+
     #[tokio::test]
     async fn test_on_active_inbound_ban_list() {
         let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2));
@@ -2115,6 +2635,146 @@ mod test {
         .await;
     }
 
+    #[tokio::test]
+    async fn test_trusted_nodes_only_rejects_untrusted_sessions() {
+        let trusted_peer = PeerId::random();
+        let untrusted_peer = PeerId::random();
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let mut peers =
+            PeersManager::new(PeersConfig::default().with_connect_trusted_nodes_only(true));
+        peers.add_trusted_peer(trusted_peer, socket_addr);
+        peers.queued_actions.clear();
+
+        for peer_id in [trusted_peer, untrusted_peer] {
+            assert!(peers.on_incoming_pending_session(socket_addr.ip()).is_ok());
+            peers.on_incoming_session_established(peer_id, socket_addr);
+        }
+        match peers.queued_actions.pop_back() {
+            Some(PeerAction::Disconnect { peer_id, reason }) => {
+                assert_eq!(peer_id, untrusted_peer);
+                assert_eq!(reason, Some(DisconnectReason::UselessPeer));
+            }
+            _ => unreachable!(),
+        }
+        assert!(peers.peers[&trusted_peer].state.is_connected());
+
+        // the untrusted peer is removed once the session is closed
+        peers.on_active_session_gracefully_closed(untrusted_peer);
+        assert!(!peers.peers.contains_key(&untrusted_peer));
+        assert_eq!(peers.connection_info.num_inbound, 1);
+
+        // peers removed from the trusted set at runtime are disconnected
+        peers.queued_actions.clear();
+        peers.remove_peer_from_trusted_set(trusted_peer);
+        match peers.queued_actions.pop_front() {
+            Some(PeerAction::Disconnect { peer_id, reason }) => {
+                assert_eq!(peer_id, trusted_peer);
+                assert_eq!(reason, Some(DisconnectReason::DisconnectRequested));
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_trusted_peer_with_backoff() {
+        let trusted_peer = PeerId::random();
+        let trusted_sock = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let config = PeersConfig {
+            trusted_peer_backoff: TrustedPeerBackoff {
+                initial: Duration::from_millis(100),
+                max: Duration::from_secs(1),
+                jitter: 0.0,
+            },
+            backoff_durations: PeerBackoffDurations {
+                low: Duration::from_millis(100),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+        .with_trusted_nodes(HashSet::from([NodeRecord::new(trusted_sock, trusted_peer)]));
+        let mut peers = PeersManager::new(config);
+
+        match event!(peers) {
+            PeerAction::Connect { peer_id, .. } => {
+                assert_eq!(peer_id, trusted_peer);
+            }
+            _ => unreachable!(),
+        }
+
+        // fatal errors would remove and ban basic peers
+        peers.on_pending_session_dropped(
+            &trusted_sock,
+            &trusted_peer,
+            &PendingSessionHandshakeError::Eth(EthStreamError::P2PStreamError(
+                P2PStreamError::HandshakeError(P2PHandshakeError::HelloNotInHandshake),
+            )),
+        );
+
+        let peer = peers.peers.get(&trusted_peer).unwrap();
+        assert!(peer.is_backed_off());
+        assert_eq!(peer.reconnect_attempts, 1);
+        assert_eq!(peers.num_unreachable_trusted_peers(), 1);
+        assert_eq!(peers.connection_info.num_outbound, 0);
+        assert!(!peers.queued_actions.iter().any(|a| matches!(a, PeerAction::BanPeer { .. })));
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        match event!(peers) {
+            PeerAction::Connect { peer_id, .. } => {
+                assert_eq!(peer_id, trusted_peer);
+            }
+            _ => unreachable!(),
+        }
+
+        peers.on_outgoing_session_established(trusted_peer);
+        assert_eq!(peers.peers.get(&trusted_peer).unwrap().reconnect_attempts, 0);
+        assert_eq!(peers.num_unreachable_trusted_peers(), 0);
+    }
+
+    #[test]
+    fn test_trusted_peer_backoff() {
+        let backoff = TrustedPeerBackoff {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(10),
+            jitter: 0.5,
+        };
+        assert_eq!(backoff.base_backoff(1), Duration::from_secs(1));
+        assert_eq!(backoff.base_backoff(2), Duration::from_secs(2));
+        assert_eq!(backoff.base_backoff(4), Duration::from_secs(8));
+        assert_eq!(backoff.base_backoff(5), Duration::from_secs(10));
+        assert_eq!(backoff.base_backoff(u32::MAX), Duration::from_secs(10));
+
+        for attempts in 1..10 {
+            let base = backoff.base_backoff(attempts);
+            let with_jitter = backoff.backoff(attempts);
+            assert!(with_jitter >= base && with_jitter <= base.mul_f64(1.5));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dial_trusted_peers_without_capacity() {
+        let trusted_peer = PeerId::random();
+        let trusted_sock = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let config = PeersConfig::default()
+            .with_max_outbound(0)
+            .with_trusted_nodes(HashSet::from([NodeRecord::new(trusted_sock, trusted_peer)]));
+        let mut peers = PeersManager::new(config);
+
+        let basic_peer = PeerId::random();
+        peers.add_peer(basic_peer, trusted_sock, None);
+
+        peers.fill_outbound_slots();
+        let dials = peers
+            .queued_actions
+            .iter()
+            .filter_map(|ev| match ev {
+                PeerAction::Connect { peer_id, .. } => Some(*peer_id),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(dials, vec![trusted_peer]);
+    }
+
     #[tokio::test]
     async fn test_tick() {
         let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2));
@@ -2204,4 +2864,119 @@ mod test {
             .count();
         assert_eq!(dials, peer_manager.connection_info.max_concurrent_outbound_dials);
     }
+
+    #[test]
+    fn test_client_version_policy_matches() {
+        let policy = ClientVersionPolicy {
+            pattern: "Geth/v1.10.*".to_string(),
+            action: ClientVersionAction::Ban,
+        };
+        assert!(policy.matches("Geth/v1.10.26-stable-e5eb32ac/linux-amd64/go1.18.5"));
+        assert!(policy.matches("geth/V1.10.0"));
+        assert!(!policy.matches("Geth/v1.11.0"));
+        assert!(!policy.matches("Geth"));
+
+        let policy = ClientVersionPolicy { pattern: "*/v1.2.3/*".to_string(), ..policy };
+        assert!(policy.matches("Nethermind/v1.2.3/linux-x64"));
+        assert!(!policy.matches("Nethermind/v1.2.30"));
+    }
+
+    #[tokio::test]
+    async fn test_client_version_policies() {
+        let banned = PeerId::random();
+        let deprioritized = PeerId::random();
+        let other = PeerId::random();
+        let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let config = PeersConfig::default().with_client_version_policies(vec![
+            ClientVersionPolicy {
+                pattern: "buggy/*".to_string(),
+                action: ClientVersionAction::Ban,
+            },
+            ClientVersionPolicy {
+                pattern: "old/*".to_string(),
+                action: ClientVersionAction::Deprioritize,
+            },
+        ]);
+        let mut peers = PeersManager::new(config);
+        let trusted = PeerId::random();
+        peers.add_trusted_peer(trusted, addr);
+        for peer_id in [banned, deprioritized, other] {
+            peers.add_peer(peer_id, addr, None);
+        }
+        peers.queued_actions.clear();
+
+        // banned clients are rejected during the handshake, unless they're trusted
+        let filter = peers.client_version_filter();
+        assert!(filter.banned_by(&banned, "buggy/v1.0.0/linux").is_some());
+        assert!(filter.banned_by(&trusted, "buggy/v1.0.0/linux").is_none());
+        assert!(filter.banned_by(&other, "reth/v0.1.0").is_none());
+        assert!(filter.banned_by(&deprioritized, "old/v0.1.0").is_none());
+        peers.remove_peer_from_trusted_set(trusted);
+        assert!(filter.banned_by(&trusted, "buggy/v1.0.0/linux").is_some());
+
+        // the rejected peer is removed and banned, its IP isn't
+        peers.on_incoming_pending_session(addr.ip()).unwrap();
+        peers.on_incoming_pending_session_dropped(
+            addr,
+            &PendingSessionHandshakeError::ClientVersion {
+                peer_id: banned,
+                client_version: "buggy/v1.0.0/linux".to_string(),
+            },
+        );
+        assert!(peers.ban_list.is_banned_peer(&banned));
+        assert!(!peers.ban_list.is_banned_ip(&addr.ip()));
+        assert!(!peers.peers.contains_key(&banned));
+
+        peers.on_session_client_version(deprioritized, "old/v0.1.0");
+        peers.on_session_client_version(other, "reth/v0.1.0");
+        assert!(peers.peers[&deprioritized].deprioritized);
+        assert!(!peers.peers[&other].deprioritized);
+
+        // deprioritized peers are only dialed if there are no other peers
+        peers.peers.get_mut(&other).unwrap().reputation = DEFAULT_REPUTATION - 100;
+        assert_eq!(peers.best_unconnected().unwrap().0, other);
+        peers.remove_peer(other);
+        assert_eq!(peers.best_unconnected().unwrap().0, deprioritized);
+    }
+
+    #[tokio::test]
+    async fn test_persisted_peers() {
+        let backed_off = PeerId::random();
+        let trusted = PeerId::random();
+        let banned = PeerId::random();
+        let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let mut peers = PeersManager::default();
+        for peer_id in [backed_off, banned] {
+            peers.add_peer(peer_id, addr, None);
+        }
+        peers.peers.insert(trusted, Peer::trusted(addr));
+        peers.peers.get_mut(&backed_off).unwrap().reputation = DEFAULT_REPUTATION - 100;
+        peers.peers.get_mut(&backed_off).unwrap().severe_backoff_counter = 2;
+        peers.backoff_peer_until(backed_off, std::time::Instant::now() + Duration::from_secs(3600));
+        peers.peers.get_mut(&banned).unwrap().reputation = BANNED_REPUTATION;
+
+        let persisted = peers.persisted_peers();
+        assert_eq!(persisted.len(), 2);
+        assert!(!persisted.iter().any(|peer| peer.record.id == banned));
+
+        // the state of the peers is restored after a restart
+        let peers = PeersManager::new(PeersConfig::default().with_persisted_peers(persisted));
+        assert_eq!(peers.peers.len(), 2);
+        let peer = &peers.peers[&backed_off];
+        assert_eq!(peer.reputation, DEFAULT_REPUTATION - 100);
+        assert_eq!(peer.severe_backoff_counter, 2);
+        assert!(peer.backed_off);
+        assert!(peers.backed_off_peers.contains_key(&backed_off));
+        // peers that are no longer trusted in the config aren't trusted after a restart
+        assert!(!peers.peers[&trusted].is_trusted());
+        assert!(!peers.peers[&trusted].backed_off);
+
+        // while peers that are trusted in the config keep their state
+        let config = PeersConfig::default()
+            .with_trusted_nodes(HashSet::from([NodeRecord::new(addr, backed_off)]))
+            .with_persisted_peers(peers.persisted_peers());
+        let peers = PeersManager::new(config);
+        assert!(peers.peers[&backed_off].is_trusted());
+        assert_eq!(peers.peers[&backed_off].severe_backoff_counter, 2);
+    }
 }
@@ -1,11 +1,15 @@
 //! Peer related implementations
 
 mod manager;
+mod persist;
 mod reputation;
 
-pub(crate) use manager::{InboundConnectionError, PeerAction, PeersManager};
-pub use manager::{Peer, PeersConfig, PeersHandle};
-pub use reputation::ReputationChangeWeights;
+pub use manager::{
+    ClientVersionAction, ClientVersionPolicy, Peer, PeersConfig, PeersHandle, TrustedPeerBackoff,
+};
+pub(crate) use manager::{ClientVersionFilter, InboundConnectionError, PeerAction, PeersManager};
+pub use persist::{read_peers_file, write_peers_file, PersistedPeer};
+pub use reputation::{ReputationChangeWeights, ReputationPolicy, SharedReputationPolicy};
 pub use reth_network_api::PeerKind;
 
 /// Maximum number of available slots for outbound sessions.
@@ -0,0 +1,115 @@
+//! Persistence of the known peers across restarts.
+
+use reth_primitives::NodeRecord;
+use std::{
+    collections::HashSet,
+    io::{self, ErrorKind},
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// A known peer as written to the peers file.
+///
+/// Besides the address of the peer, its reputation and backoff are kept, so peers that behaved
+/// well are preferred after a restart and peers that are backed off are not dialed right away.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PersistedPeer {
+    /// The address and id of the peer.
+    pub record: NodeRecord,
+    /// The reputation of the peer.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub reputation: i32,
+    /// The unix timestamp in seconds until which the peer is backed off, if any.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub backed_off_until: Option<u64>,
+    /// The number of times the peer was backed off due to a severe backoff.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub severe_backoff_counter: u32,
+}
+
+impl PersistedPeer {
+    /// Returns the remaining backoff of the peer, if it's still backed off.
+    pub fn remaining_backoff(&self) -> Option<Duration> {
+        let until = UNIX_EPOCH + Duration::from_secs(self.backed_off_until?);
+        until.duration_since(SystemTime::now()).ok()
+    }
+}
+
+/// Returns the unix timestamp in seconds of the instant.
+pub(crate) fn unix_timestamp(instant: std::time::Instant) -> u64 {
+    let remaining = instant.saturating_duration_since(std::time::Instant::now());
+    (SystemTime::now() + remaining).duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+/// Reads the peers file.
+///
+/// Files of older versions that only contain the node records of the peers are supported. A
+/// missing file is treated as empty.
+pub fn read_peers_file(path: &Path) -> io::Result<Vec<PersistedPeer>> {
+    let content = match std::fs::read(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    if let Ok(peers) = serde_json::from_slice::<Vec<PersistedPeer>>(&content) {
+        return Ok(peers)
+    }
+    let records: HashSet<NodeRecord> = serde_json::from_slice(&content)?;
+    Ok(records
+        .into_iter()
+        .map(|record| PersistedPeer {
+            record,
+            reputation: 0,
+            backed_off_until: None,
+            severe_backoff_counter: 0,
+        })
+        .collect())
+}
+
+/// Writes the peers to the peers file.
+///
+/// The file is replaced atomically, so a crash while writing doesn't lose the known peers.
+pub fn write_peers_file(path: &Path, peers: &[PersistedPeer]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_vec_pretty(peers)?)?;
+    std::fs::rename(tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::PeerId;
+    use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+    #[test]
+    fn peers_file_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("known-peers.json");
+        assert!(read_peers_file(&path).unwrap().is_empty());
+
+        let record = NodeRecord::new(
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 30303),
+            PeerId::random(),
+        );
+        let peers = vec![PersistedPeer {
+            record,
+            reputation: -1024,
+            backed_off_until: Some(unix_timestamp(std::time::Instant::now()) + 3600),
+            severe_backoff_counter: 2,
+        }];
+        write_peers_file(&path, &peers).unwrap();
+        assert_eq!(read_peers_file(&path).unwrap(), peers);
+        assert!(peers[0].remaining_backoff().is_some());
+
+        // files that only contain the node records are still supported
+        std::fs::write(&path, serde_json::to_vec(&vec![record]).unwrap()).unwrap();
+        let peers = read_peers_file(&path).unwrap();
+        assert_eq!(peers.len(), 1);
+        assert_eq!(peers[0].record, record);
+        assert!(peers[0].remaining_backoff().is_none());
+    }
+}
@@ -1,6 +1,7 @@
 //! Peer reputation management
 
 use reth_network_api::{Reputation, ReputationChangeKind};
+use std::{collections::HashMap, fmt, sync::Arc};
 
 /// The default reputation of a peer
 pub(crate) const DEFAULT_REPUTATION: Reputation = 0;
@@ -23,6 +24,9 @@ const TIMEOUT_REPUTATION_CHANGE: i32 = 4 * REPUTATION_UNIT;
 /// The reputation change to apply to a peer that sent a bad message.
 const BAD_MESSAGE_REPUTATION_CHANGE: i32 = 16 * REPUTATION_UNIT;
 
+/// The reputation change to apply to a peer that repeatedly exceeded its request rate limit.
+const EXCEEDED_RATE_LIMIT_REPUTATION_CHANGE: i32 = 4 * REPUTATION_UNIT;
+
 /// The reputation change applies to a peer that has sent a transaction (full or hash) that we
 /// already know about and have already previously received from that peer.
 ///
@@ -42,6 +46,7 @@ pub(crate) fn is_banned_reputation(reputation: i32) -> bool {
 /// How the [`ReputationChangeKind`] are weighted.
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct ReputationChangeWeights {
     /// Weight for [`ReputationChangeKind::BadMessage`]
     pub bad_message: Reputation,
@@ -59,6 +64,12 @@ pub struct ReputationChangeWeights {
     pub failed_to_connect: Reputation,
     /// Weight for [`ReputationChangeKind::Dropped`]
     pub dropped: Reputation,
+    /// Weight for [`ReputationChangeKind::ExceededRateLimit`]
+    pub exceeded_rate_limit: Reputation,
+    /// Weights for [`ReputationChangeKind::Custom`] by name.
+    ///
+    /// Custom kinds without a weight don't change the reputation.
+    pub custom: HashMap<String, Reputation>,
 }
 
 // === impl ReputationChangeWeights ===
@@ -76,8 +87,12 @@ impl ReputationChangeWeights {
             ReputationChangeKind::BadProtocol => self.bad_protocol.into(),
             ReputationChangeKind::FailedToConnect => self.failed_to_connect.into(),
             ReputationChangeKind::Dropped => self.dropped.into(),
+            ReputationChangeKind::ExceededRateLimit => self.exceeded_rate_limit.into(),
             ReputationChangeKind::Reset => DEFAULT_REPUTATION.into(),
             ReputationChangeKind::Other(val) => val.into(),
+            ReputationChangeKind::Custom(name) => {
+                self.custom.get(name).copied().unwrap_or_default().into()
+            }
         }
     }
 }
@@ -93,10 +108,52 @@ impl Default for ReputationChangeWeights {
             bad_protocol: BAD_PROTOCOL_REPUTATION_CHANGE,
             failed_to_connect: FAILED_TO_CONNECT_REPUTATION_CHANGE,
             dropped: REMOTE_DISCONNECT_REPUTATION_CHANGE,
+            exceeded_rate_limit: EXCEEDED_RATE_LIMIT_REPUTATION_CHANGE,
+            custom: Default::default(),
         }
     }
 }
 
+/// Decides by how much a [`ReputationChangeKind`] changes the reputation of a peer.
+///
+/// The default policy is [`ReputationChangeWeights`], a custom policy can be set via
+/// [`PeersConfig::with_reputation_policy`](crate::PeersConfig::with_reputation_policy).
+///
+/// Peers are banned once their reputation drops below `-51200`, a change of `-1024` is the
+/// smallest penalty of the default policy.
+pub trait ReputationPolicy: fmt::Debug + Send + Sync + 'static {
+    /// Returns the change to apply to the reputation of a peer for the given kind.
+    ///
+    /// This is never invoked with [`ReputationChangeKind::Reset`], which always restores the
+    /// default reputation.
+    fn reputation_change(&self, kind: ReputationChangeKind) -> Reputation;
+}
+
+impl ReputationPolicy for ReputationChangeWeights {
+    fn reputation_change(&self, kind: ReputationChangeKind) -> Reputation {
+        self.change(kind).as_i32()
+    }
+}
+
+/// A [`ReputationPolicy`] that can be shared between configs.
+///
+/// Two instances are equal if they share the same policy.
+#[derive(Debug, Clone)]
+pub struct SharedReputationPolicy(pub(crate) Arc<dyn ReputationPolicy>);
+
+impl SharedReputationPolicy {
+    /// Creates a new instance with the given policy.
+    pub fn new(policy: impl ReputationPolicy) -> Self {
+        Self(Arc::new(policy))
+    }
+}
+
+impl PartialEq for SharedReputationPolicy {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::as_ptr(&self.0) as *const () == Arc::as_ptr(&other.0) as *const ()
+    }
+}
+
 /// Represents a change in a peer's reputation.
 #[derive(Debug, Copy, Clone, Default)]
 pub(crate) struct ReputationChange(Reputation);
@@ -0,0 +1,75 @@
+//! Support for custom RLPx subprotocols.
+//!
+//! A [ProtocolHandler] registered with the [NetworkManager](crate::NetworkManager) announces an
+//! additional capability in the `Hello` message. Every session with a peer that shares the
+//! capability hands a [ProtocolConnection] to the handler, over which the messages of the
+//! subprotocol are exchanged next to the `eth` messages of the session.
+
+use futures::Stream;
+use reth_eth_wire::capability::{Protocol, RawCapabilityMessage};
+use reth_network_api::Direction;
+use reth_primitives::PeerId;
+use std::{
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tokio_stream::wrappers::ReceiverStream;
+
+/// A handler of a custom RLPx subprotocol.
+pub trait ProtocolHandler: fmt::Debug + Send + Sync + 'static {
+    /// Returns the protocol, its capability and the number of messages it reserves.
+    fn protocol(&self) -> Protocol;
+
+    /// Invoked when a session is established with a peer that shares the protocol.
+    ///
+    /// The connection yields the messages the peer sends over the protocol and ends when the
+    /// session is closed.
+    fn on_connection(&self, peer_id: PeerId, direction: Direction, conn: ProtocolConnection);
+}
+
+/// The messages of a subprotocol exchanged with a peer.
+///
+/// Message ids are relative to the subprotocol, starting at 0.
+#[derive(Debug)]
+pub struct ProtocolConnection {
+    /// Messages received from the peer.
+    incoming: ReceiverStream<RawCapabilityMessage>,
+    /// Messages to send to the peer.
+    outgoing: mpsc::Sender<RawCapabilityMessage>,
+}
+
+impl ProtocolConnection {
+    /// Creates a new connection from the channels of the session.
+    pub(crate) fn new(
+        incoming: mpsc::Receiver<RawCapabilityMessage>,
+        outgoing: mpsc::Sender<RawCapabilityMessage>,
+    ) -> Self {
+        Self { incoming: ReceiverStream::new(incoming), outgoing }
+    }
+
+    /// Queues a message to send to the peer.
+    ///
+    /// Messages with an id outside of the messages of the protocol are dropped by the session.
+    pub fn try_send(
+        &self,
+        msg: RawCapabilityMessage,
+    ) -> Result<(), TrySendError<RawCapabilityMessage>> {
+        self.outgoing.try_send(msg)
+    }
+
+    /// Returns a sender for messages to the peer that can be used independently of the
+    /// connection.
+    pub fn sender(&self) -> mpsc::Sender<RawCapabilityMessage> {
+        self.outgoing.clone()
+    }
+}
+
+impl Stream for ProtocolConnection {
+    type Item = RawCapabilityMessage;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.incoming).poll_next(cx)
+    }
+}
@@ -3,6 +3,8 @@
 use crate::{
     message::{NewBlockMessage, PeerMessage, PeerRequest, PeerResponse, PeerResponseResult},
     session::{
+        bandwidth::{BandwidthLimitAction, BandwidthLimiter},
+        capture::FrameCapture,
         config::INITIAL_REQUEST_TIMEOUT,
         handle::{ActiveSessionMessage, SessionCommand},
         SessionId,
@@ -13,7 +15,7 @@ use fnv::FnvHashMap;
 use futures::{stream::Fuse, SinkExt, StreamExt};
 use reth_ecies::stream::ECIESStream;
 use reth_eth_wire::{
-    capability::Capabilities,
+    capability::{Capabilities, RawCapabilityMessage},
     errors::{EthHandshakeError, EthStreamError, P2PStreamError},
     message::{EthBroadcastMessage, RequestPair},
     DisconnectReason, EthMessage, EthStream, P2PStream,
@@ -33,7 +35,10 @@ use std::{
 };
 use tokio::{
     net::TcpStream,
-    sync::{mpsc::error::TrySendError, oneshot},
+    sync::{
+        mpsc::{self, error::TrySendError},
+        oneshot,
+    },
     time::Interval,
 };
 use tokio_stream::wrappers::ReceiverStream;
@@ -97,6 +102,12 @@ pub(crate) struct ActiveSession {
     pub(crate) protocol_breach_request_timeout: Duration,
     /// Used to reserve a slot to guarantee that the termination message is delivered
     pub(crate) terminate_message: Option<(PollSender<ActiveSessionMessage>, ActiveSessionMessage)>,
+    /// Keeps the most recent messages exchanged with the peer, if enabled.
+    pub(crate) capture: Option<FrameCapture>,
+    /// The channels of the custom subprotocols shared with the peer.
+    pub(crate) subprotocols: Vec<SubprotocolSession>,
+    /// Enforces the bandwidth limit of the peer, if limited.
+    pub(crate) bandwidth_limiter: Option<BandwidthLimiter>,
 }
 
 impl ActiveSession {
@@ -352,6 +363,27 @@ impl ActiveSession {
         }
     }
 
+    /// Forwards the received messages of the custom subprotocols to their handlers.
+    ///
+    /// Messages are dropped if the handler doesn't keep up.
+    fn on_subprotocol_messages(&mut self) {
+        while let Some((name, msg)) = self.conn.inner_mut().take_subprotocol_message() {
+            let Some(subprotocol) = self.subprotocols.iter().find(|s| s.name == name.as_str())
+            else {
+                continue
+            };
+            if let Err(err) = subprotocol.to_handler.try_send(msg) {
+                trace!(
+                    target : "net::session",
+                    %err,
+                    protocol=%name,
+                    remote_peer_id=?self.remote_peer_id,
+                    "no capacity for incoming subprotocol message",
+                );
+            }
+        }
+    }
+
     /// Notify the manager that the peer sent a bad message
     fn on_bad_message(&self) {
         let _ = self
@@ -374,6 +406,9 @@ impl ActiveSession {
 
     /// Report back that this session has been closed due to an error
     fn close_on_error(&mut self, error: EthStreamError, cx: &mut Context<'_>) -> Poll<()> {
+        if let (Some(capture), Some(frames)) = (&self.capture, self.conn.captured_frames()) {
+            capture.dump_on_error(frames, self.remote_peer_id, self.remote_addr, &error);
+        }
         let msg = ActiveSessionMessage::ClosedOnConnectionError {
             peer_id: self.remote_peer_id,
             remote_addr: self.remote_addr,
@@ -530,6 +565,18 @@ impl Future for ActiveSession {
                 this.on_internal_peer_request(req, deadline);
             }
 
+            for subprotocol in &mut this.subprotocols {
+                while let Poll::Ready(Some(msg)) = subprotocol.from_handler.poll_next_unpin(cx) {
+                    progress = true;
+                    if msg.id < subprotocol.messages as usize {
+                        this.queued_outgoing
+                            .push_back(OutgoingMessage::Subprotocol(subprotocol.name.clone(), msg));
+                    } else {
+                        debug!(target: "net::session", protocol=%subprotocol.name, id=msg.id, "dropping subprotocol message with unknown id");
+                    }
+                }
+            }
+
             // Advance all active requests.
             // We remove each request one by one and add them back.
             for idx in (0..this.received_requests_from_remote.len()).rev() {
@@ -552,6 +599,11 @@ impl Future for ActiveSession {
                     let res = match msg {
                         OutgoingMessage::Eth(msg) => this.conn.start_send_unpin(msg),
                         OutgoingMessage::Broadcast(msg) => this.conn.start_send_broadcast(msg),
+                        OutgoingMessage::Subprotocol(name, msg) => this
+                            .conn
+                            .inner_mut()
+                            .start_send_subprotocol(&name, msg)
+                            .map_err(Into::into),
                     };
                     if let Err(err) = res {
                         debug!(target: "net::session", ?err,  remote_peer_id=?this.remote_peer_id, "failed to send message");
@@ -564,6 +616,22 @@ impl Future for ActiveSession {
                 }
             }
 
+            // stop reading from a peer that exceeds its bandwidth limit
+            if let Some(limiter) = &mut this.bandwidth_limiter {
+                if limiter.poll_exceeded(cx) {
+                    match limiter.action() {
+                        BandwidthLimitAction::Throttle => {
+                            trace!(target: "net::session", remote_peer_id=?this.remote_peer_id, "throttling peer that exceeds bandwidth limit");
+                            break 'main
+                        }
+                        BandwidthLimitAction::Disconnect => {
+                            debug!(target: "net::session", remote_peer_id=?this.remote_peer_id, "disconnecting peer that exceeds bandwidth limit");
+                            return this.try_disconnect(DisconnectReason::UselessPeer, cx)
+                        }
+                    }
+                }
+            }
+
             // read incoming messages from the wire
             'receive: loop {
                 // ensure we still have enough budget for another iteration
@@ -593,7 +661,9 @@ impl Future for ActiveSession {
                     }
                 }
 
-                match this.conn.poll_next_unpin(cx) {
+                let res = this.conn.poll_next_unpin(cx);
+                this.on_subprotocol_messages();
+                match res {
                     Poll::Pending => break,
                     Poll::Ready(None) => {
                         if this.is_disconnecting() {
@@ -726,12 +796,27 @@ enum RequestState {
     TimedOut,
 }
 
+/// The channels of a custom subprotocol shared with the peer.
+pub(crate) struct SubprotocolSession {
+    /// The name of the subprotocol.
+    pub(crate) name: String,
+    /// The number of messages of the subprotocol.
+    pub(crate) messages: u8,
+    /// Sends the messages received from the peer to the
+    /// [ProtocolHandler](crate::protocol::ProtocolHandler).
+    pub(crate) to_handler: mpsc::Sender<RawCapabilityMessage>,
+    /// Messages of the [ProtocolHandler](crate::protocol::ProtocolHandler) to send to the peer.
+    pub(crate) from_handler: Fuse<ReceiverStream<RawCapabilityMessage>>,
+}
+
 /// Outgoing messages that can be sent over the wire.
 pub(crate) enum OutgoingMessage {
     /// A message that is owned.
     Eth(EthMessage),
     /// A message that may be shared by multiple sessions.
     Broadcast(EthBroadcastMessage),
+    /// A message of a custom subprotocol, with the name of the subprotocol.
+    Subprotocol(String, RawCapabilityMessage),
 }
 
 impl From<EthMessage> for OutgoingMessage {
@@ -850,6 +935,8 @@ mod tests {
                 self.hello.clone(),
                 self.status,
                 self.fork_filter.clone(),
+                Default::default(),
+                Vec::new(),
             ));
 
             let mut stream = ReceiverStream::new(pending_sessions_rx);
@@ -893,6 +980,9 @@ mod tests {
                         )),
                         protocol_breach_request_timeout: PROTOCOL_BREACH_REQUEST_TIMEOUT,
                         terminate_message: None,
+                        capture: None,
+                        subprotocols: Vec::new(),
+                        bandwidth_limiter: None,
                     }
                 }
                 ev => {
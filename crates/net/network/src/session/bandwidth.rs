@@ -0,0 +1,123 @@
+//! Per-peer bandwidth limits.
+//!
+//! If enabled via
+//! [`SessionsConfig::peer_bandwidth_limit`](crate::SessionsConfig::peer_bandwidth_limit), every
+//! session meters the bytes received from the peer over fixed windows of one second. If a peer
+//! exceeds the limit within a window, the session either stops reading from the peer until the next
+//! window or disconnects the peer, depending on the configured [BandwidthLimitAction].
+//!
+//! Only inbound bytes count towards the limit: they are the ones the peer controls and the ones
+//! throttling, which only stops reading, can slow down. Bytes sent to the peer are mostly responses
+//! to its requests, which are already bounded by the request limits.
+
+use reth_net_common::bandwidth_meter::BandwidthMeter;
+use std::{task::Context, time::Duration};
+use tokio::time::{interval, Interval, MissedTickBehavior};
+
+/// The window over which the bandwidth of a peer is measured.
+const BANDWIDTH_LIMIT_WINDOW: Duration = Duration::from_secs(1);
+
+/// What a session does with a peer that exceeds its [PeerBandwidthLimit].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BandwidthLimitAction {
+    /// Stop reading from the peer until the next window, which deprioritizes its requests and
+    /// broadcasts in favor of other peers.
+    #[default]
+    Throttle,
+    /// Disconnect the peer.
+    Disconnect,
+}
+
+/// The maximum bandwidth a single peer may use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PeerBandwidthLimit {
+    /// The maximum number of bytes per second received from the peer.
+    pub max_bytes_per_sec: u64,
+    /// What to do with a peer that exceeds the limit.
+    pub action: BandwidthLimitAction,
+}
+
+impl PeerBandwidthLimit {
+    /// Creates a new limit that throttles peers that exceed the given number of bytes per second.
+    pub fn new(max_bytes_per_sec: u64) -> Self {
+        Self { max_bytes_per_sec, action: BandwidthLimitAction::Throttle }
+    }
+
+    /// Sets the action for peers that exceed the limit.
+    pub fn with_action(mut self, action: BandwidthLimitAction) -> Self {
+        self.action = action;
+        self
+    }
+}
+
+/// Enforces the [PeerBandwidthLimit] of a session.
+#[derive(Debug)]
+pub(crate) struct BandwidthLimiter {
+    limit: PeerBandwidthLimit,
+    /// Meters the bandwidth of the session's connection.
+    meter: BandwidthMeter,
+    /// Marks the start of a new window.
+    interval: Interval,
+    /// The number of bytes received at the start of the current window.
+    window_start: u64,
+}
+
+impl BandwidthLimiter {
+    pub(crate) fn new(limit: PeerBandwidthLimit, meter: BandwidthMeter) -> Self {
+        let mut interval = interval(BANDWIDTH_LIMIT_WINDOW);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let window_start = meter.total_inbound();
+        Self { limit, meter, interval, window_start }
+    }
+
+    /// Returns the action for the peer.
+    pub(crate) fn action(&self) -> BandwidthLimitAction {
+        self.limit.action
+    }
+
+    /// Returns `true` if the peer exceeded the limit in the current window.
+    ///
+    /// This registers the waker for the start of the next window.
+    pub(crate) fn poll_exceeded(&mut self, cx: &mut Context<'_>) -> bool {
+        while self.interval.poll_tick(cx).is_ready() {
+            self.window_start = self.meter.total_inbound();
+        }
+        self.meter.total_inbound().saturating_sub(self.window_start) > self.limit.max_bytes_per_sec
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::task::noop_waker_ref;
+    use reth_net_common::bandwidth_meter::MeteredStream;
+    use tokio::io::{duplex, AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn test_bandwidth_limiter() {
+        let (client, server) = duplex(64);
+        let mut client = MeteredStream::new(client);
+        let mut server = MeteredStream::new(server);
+        let mut cx = Context::from_waker(noop_waker_ref());
+
+        let mut limiter =
+            BandwidthLimiter::new(PeerBandwidthLimit::new(10), server.stream_meter().clone());
+        assert!(!limiter.poll_exceeded(&mut cx));
+
+        let mut buf = [0u8; 8];
+        client.write_all(b"ping").await.unwrap();
+        server.read_exact(&mut buf[..4]).await.unwrap();
+        assert!(!limiter.poll_exceeded(&mut cx));
+
+        // outbound bytes don't count
+        server.write_all(b"pongpongpong").await.unwrap();
+        client.read_exact(&mut [0u8; 12]).await.unwrap();
+        assert!(!limiter.poll_exceeded(&mut cx));
+
+        client.write_all(b"pingping").await.unwrap();
+        server.read_exact(&mut buf).await.unwrap();
+        assert!(limiter.poll_exceeded(&mut cx));
+    }
+}
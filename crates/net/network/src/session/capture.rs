@@ -0,0 +1,159 @@
+//! Forensic capture of the messages exchanged with a peer.
+//!
+//! If enabled via [`SessionsConfig::capture`](crate::SessionsConfig::capture), the
+//! [`EthStream`](reth_eth_wire::EthStream) of every session keeps the last raw frames exchanged
+//! with the peer in a ring buffer. If the session is closed because of a protocol error, the buffer
+//! is written to disk, which helps debugging interop issues with other clients.
+
+use reth_eth_wire::{errors::EthStreamError, EthMessageID, FrameDirection, FrameRecorder};
+use reth_primitives::{hex, PeerId};
+use std::{
+    fmt::Write as _,
+    fs,
+    net::SocketAddr,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tracing::{debug, info};
+
+/// Default number of messages kept per session.
+pub const DEFAULT_CAPTURE_FRAMES: usize = 64;
+
+/// Configures the capture of the last messages exchanged with a peer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SessionCaptureConfig {
+    /// The number of most recent messages to keep per session.
+    pub frames: usize,
+    /// The directory the captured messages are written to.
+    pub dir: PathBuf,
+}
+
+impl SessionCaptureConfig {
+    /// Creates a new config that writes captures to the given directory and keeps the
+    /// [DEFAULT_CAPTURE_FRAMES] most recent messages.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { frames: DEFAULT_CAPTURE_FRAMES, dir: dir.into() }
+    }
+
+    /// Sets the number of most recent messages to keep per session.
+    pub fn with_frames(mut self, frames: usize) -> Self {
+        self.frames = frames;
+        self
+    }
+}
+
+/// Writes the frames captured by a session's stream to disk.
+#[derive(Debug)]
+pub(crate) struct FrameCapture {
+    dir: PathBuf,
+}
+
+impl FrameCapture {
+    /// Creates a new capture from the config, returns `None` if no frames should be kept.
+    pub(crate) fn new(config: &SessionCaptureConfig) -> Option<Self> {
+        (config.frames > 0).then(|| Self { dir: config.dir.clone() })
+    }
+
+    /// Renders the captured frames of a session that failed with the given error.
+    fn render(
+        frames: &FrameRecorder,
+        peer_id: &PeerId,
+        remote_addr: &SocketAddr,
+        error: &EthStreamError,
+    ) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "peer_id: {peer_id:?}");
+        let _ = writeln!(out, "remote_addr: {remote_addr}");
+        let _ = writeln!(out, "error: {error}");
+        let _ = writeln!(out, "frames: {}", frames.len());
+        for frame in frames.frames() {
+            let millis = frame
+                .timestamp
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis())
+                .unwrap_or_default();
+            let direction = match frame.direction {
+                FrameDirection::Inbound => "in",
+                FrameDirection::Outbound => "out",
+            };
+            // the frame may be malformed, so the message id is only decoded for display
+            let message_id = frame
+                .payload
+                .first()
+                .and_then(|id| EthMessageID::try_from(*id as usize).ok())
+                .map(|id| format!("{id:?}"))
+                .unwrap_or_else(|| "Unknown".to_string());
+            let _ = writeln!(
+                out,
+                "{millis} {direction} {message_id} 0x{}",
+                hex::encode(&frame.payload)
+            );
+        }
+        out
+    }
+
+    /// Writes the captured frames to disk if the session was closed because of a protocol error.
+    ///
+    /// IO errors are not considered protocol errors, since they are caused by the connection.
+    pub(crate) fn dump_on_error(
+        &self,
+        frames: &FrameRecorder,
+        peer_id: PeerId,
+        remote_addr: SocketAddr,
+        error: &EthStreamError,
+    ) {
+        if error.as_io().is_some() {
+            return
+        }
+
+        let contents = Self::render(frames, &peer_id, &remote_addr, error);
+        let secs =
+            SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default();
+        let path = self.dir.join(format!("{peer_id:x}-{secs}.log"));
+        let dir = self.dir.clone();
+
+        // don't block the session task on disk IO
+        tokio::task::spawn_blocking(move || {
+            match fs::create_dir_all(&dir).and_then(|_| fs::write(&path, contents)) {
+                Ok(()) => {
+                    info!(target: "net::session", ?peer_id, path=%path.display(), "Wrote capture of session closed on protocol error")
+                }
+                Err(err) => {
+                    debug!(target: "net::session", ?err, ?peer_id, "Failed to write session capture")
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_eth_wire::errors::EthHandshakeError;
+    use reth_primitives::bytes::Bytes;
+
+    #[test]
+    fn renders_undecodable_frames() {
+        let mut frames = FrameRecorder::new(2).unwrap();
+        // GetBlockBodies with an empty request pair, and a frame with an unknown message id
+        frames.record(FrameDirection::Inbound, Bytes::from_static(&[0x05, 0xc2, 0x01, 0xc0]));
+        frames.record(FrameDirection::Outbound, Bytes::from_static(&[0xff, 0x01]));
+
+        let error = EthStreamError::EthHandshakeError(EthHandshakeError::StatusNotInHandshake);
+        let rendered = FrameCapture::render(
+            &frames,
+            &PeerId::random(),
+            &"127.0.0.1:30303".parse().unwrap(),
+            &error,
+        );
+        assert!(rendered.lines().any(|line| line.ends_with(" in GetBlockBodies 0x05c201c0")));
+        assert!(rendered.lines().any(|line| line.ends_with(" out Unknown 0xff01")));
+    }
+
+    #[test]
+    fn disabled_without_frames() {
+        let config = SessionCaptureConfig::new("/tmp").with_frames(0);
+        assert!(FrameCapture::new(&config).is_none());
+    }
+}
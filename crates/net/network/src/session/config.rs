@@ -2,9 +2,11 @@
 
 use crate::{
     peers::{DEFAULT_MAX_PEERS_INBOUND, DEFAULT_MAX_PEERS_OUTBOUND},
-    session::{Direction, ExceedsSessionLimit},
+    session::{Direction, ExceedsSessionLimit, PeerBandwidthLimit, SessionCaptureConfig},
 };
-use std::time::Duration;
+use reth_eth_wire::HelloMessage;
+use reth_primitives::PeerId;
+use std::{fmt, net::SocketAddr, sync::Arc, time::Duration};
 
 /// Default request timeout for a single request.
 ///
@@ -38,6 +40,22 @@ pub struct SessionsConfig {
     /// `PROTOCOL_BREACH_REQUEST_TIMEOUT`) this is considered a protocol violation and results in a
     /// dropped session.
     pub protocol_breach_request_timeout: Duration,
+    /// Hooks for observing and adjusting the `Hello` handshake of sessions.
+    ///
+    /// Hooks only exist at runtime and are therefore not (de)serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub hello_hooks: HelloHooks,
+    /// If set, the most recent messages exchanged with a peer are written to disk when the
+    /// session is closed because of a protocol error.
+    ///
+    /// By default, nothing is captured.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub capture: Option<SessionCaptureConfig>,
+    /// The maximum bandwidth a single peer may use.
+    ///
+    /// By default, peers are not limited.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub peer_bandwidth_limit: Option<PeerBandwidthLimit>,
 }
 
 impl Default for SessionsConfig {
@@ -56,11 +74,83 @@ impl Default for SessionsConfig {
             limits: Default::default(),
             initial_internal_request_timeout: INITIAL_REQUEST_TIMEOUT,
             protocol_breach_request_timeout: PROTOCOL_BREACH_REQUEST_TIMEOUT,
+            hello_hooks: Default::default(),
+            capture: None,
+            peer_bandwidth_limit: None,
         }
     }
 }
 
 impl SessionsConfig {
+    /// Returns a [SessionsConfigBuilder] that starts from the default configuration.
+    pub fn builder() -> SessionsConfigBuilder {
+        SessionsConfigBuilder::default()
+    }
+
+    /// Returns a [SessionsConfigBuilder] that starts from this configuration.
+    pub fn into_builder(self) -> SessionsConfigBuilder {
+        SessionsConfigBuilder { config: self }
+    }
+
+    /// A configuration for nodes with little memory and bandwidth.
+    ///
+    /// Uses small channel buffers and limits the number of pending sessions, so that fewer
+    /// handshakes run concurrently.
+    pub fn low_resource() -> Self {
+        SessionsConfig {
+            session_command_buffer: 8,
+            session_event_buffer: 64,
+            limits: SessionLimits::default()
+                .with_max_pending_inbound(10)
+                .with_max_pending_outbound(10),
+            ..Default::default()
+        }
+    }
+
+    /// A configuration for nodes that serve and download a lot of data from many peers.
+    ///
+    /// Uses large channel buffers, so that sessions are rarely throttled by the manager, and a
+    /// shorter request timeout, so that requests to slow peers are retried sooner.
+    pub fn high_throughput() -> Self {
+        SessionsConfig {
+            session_command_buffer: 128,
+            session_event_buffer: 1024,
+            initial_internal_request_timeout: Duration::from_secs(10),
+            ..Default::default()
+        }
+    }
+
+    /// Checks that the configuration can be used to create a
+    /// [SessionManager](crate::session::SessionManager).
+    pub fn validate(&self) -> Result<(), SessionsConfigError> {
+        if self.session_command_buffer == 0 {
+            return Err(SessionsConfigError::ZeroBuffer("session_command_buffer"))
+        }
+        if self.session_event_buffer == 0 {
+            return Err(SessionsConfigError::ZeroBuffer("session_event_buffer"))
+        }
+        if self.initial_internal_request_timeout.is_zero() {
+            return Err(SessionsConfigError::ZeroRequestTimeout)
+        }
+        if self.initial_internal_request_timeout > self.protocol_breach_request_timeout {
+            return Err(SessionsConfigError::RequestTimeoutExceedsProtocolBreach {
+                initial: self.initial_internal_request_timeout,
+                protocol_breach: self.protocol_breach_request_timeout,
+            })
+        }
+        if self.peer_bandwidth_limit.map_or(false, |limit| limit.max_bytes_per_sec == 0) {
+            return Err(SessionsConfigError::ZeroBandwidthLimit)
+        }
+        Ok(())
+    }
+
+    /// Increases the session event buffer so that it can hold at least two events per session of
+    /// the given number of peers.
+    pub fn with_upscaled_event_buffer(mut self, num_peers: usize) -> Self {
+        self.session_event_buffer = self.session_event_buffer.max(num_peers * 2);
+        self
+    }
+
     /// Sets the buffer size for the bounded communication channel between the manager and its
     /// sessions for events emitted by the sessions.
     ///
@@ -70,8 +160,249 @@ impl SessionsConfig {
         self.session_event_buffer = n;
         self
     }
+
+    /// Enables the capture of the messages exchanged with peers, see [SessionCaptureConfig].
+    pub fn with_capture(mut self, capture: SessionCaptureConfig) -> Self {
+        self.capture = Some(capture);
+        self
+    }
+
+    /// Sets the [HelloHook] that is invoked during the `Hello` handshake of every session.
+    pub fn with_hello_hook(mut self, hook: impl HelloHook) -> Self {
+        self.hello_hooks = HelloHooks::new(hook);
+        self
+    }
+
+    /// Limits the bandwidth of every peer, see [PeerBandwidthLimit].
+    pub fn with_peer_bandwidth_limit(mut self, limit: PeerBandwidthLimit) -> Self {
+        self.peer_bandwidth_limit = Some(limit);
+        self
+    }
+}
+
+/// Named presets of the [SessionsConfig].
+///
+/// A preset is only used if no [SessionsConfig] is set, see
+/// [NetworkConfigBuilder::sessions_preset](crate::NetworkConfigBuilder::sessions_preset).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum SessionsPreset {
+    /// The default configuration, with the session event buffer sized for the configured number
+    /// of peers.
+    #[default]
+    Default,
+    /// See [SessionsConfig::low_resource].
+    LowResource,
+    /// See [SessionsConfig::high_throughput].
+    HighThroughput,
+}
+
+// === impl SessionsPreset ===
+
+impl SessionsPreset {
+    /// Returns the [SessionsConfig] of the preset for the given number of peers.
+    pub fn config(self, num_peers: usize) -> SessionsConfig {
+        match self {
+            SessionsPreset::Default => {
+                SessionsConfig::default().with_upscaled_event_buffer(num_peers)
+            }
+            SessionsPreset::LowResource => SessionsConfig::low_resource(),
+            SessionsPreset::HighThroughput => SessionsConfig::high_throughput(),
+        }
+    }
+}
+
+/// Builds a [SessionsConfig] and validates it, see [SessionsConfig::validate].
+///
+/// ```
+/// use reth_network::{SessionLimits, SessionsConfig};
+/// use std::time::Duration;
+///
+/// let config = SessionsConfig::low_resource()
+///     .into_builder()
+///     .limits(SessionLimits::default().with_max_pending_inbound(5))
+///     .initial_internal_request_timeout(Duration::from_secs(30))
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SessionsConfigBuilder {
+    config: SessionsConfig,
 }
 
+// === impl SessionsConfigBuilder ===
+
+impl SessionsConfigBuilder {
+    /// Sets the size of the command buffer of every session task.
+    pub fn session_command_buffer(mut self, n: usize) -> Self {
+        self.config.session_command_buffer = n;
+        self
+    }
+
+    /// Sets the size of the buffer for events emitted by the sessions, see
+    /// [SessionsConfig::with_session_event_buffer].
+    pub fn session_event_buffer(mut self, n: usize) -> Self {
+        self.config.session_event_buffer = n;
+        self
+    }
+
+    /// Sets the [SessionLimits] to enforce.
+    pub fn limits(mut self, limits: SessionLimits) -> Self {
+        self.config.limits = limits;
+        self
+    }
+
+    /// Sets the time after which a request is timed out internally.
+    pub fn initial_internal_request_timeout(mut self, timeout: Duration) -> Self {
+        self.config.initial_internal_request_timeout = timeout;
+        self
+    }
+
+    /// Sets the time after which an unanswered request is considered a protocol violation.
+    pub fn protocol_breach_request_timeout(mut self, timeout: Duration) -> Self {
+        self.config.protocol_breach_request_timeout = timeout;
+        self
+    }
+
+    /// Sets the [HelloHook] that is invoked during the `Hello` handshake of every session.
+    pub fn hello_hook(mut self, hook: impl HelloHook) -> Self {
+        self.config.hello_hooks = HelloHooks::new(hook);
+        self
+    }
+
+    /// Enables the capture of the messages exchanged with peers, see [SessionCaptureConfig].
+    pub fn capture(mut self, capture: SessionCaptureConfig) -> Self {
+        self.config.capture = Some(capture);
+        self
+    }
+
+    /// Limits the bandwidth of every peer, see [PeerBandwidthLimit].
+    pub fn peer_bandwidth_limit(mut self, limit: PeerBandwidthLimit) -> Self {
+        self.config.peer_bandwidth_limit = Some(limit);
+        self
+    }
+
+    /// Returns the configured [SessionsConfig] if it is valid.
+    pub fn build(self) -> Result<SessionsConfig, SessionsConfigError> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
+/// Error returned when a [SessionsConfig] is invalid.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SessionsConfigError {
+    /// A channel buffer has size zero.
+    #[error("{0} must be greater than zero")]
+    ZeroBuffer(&'static str),
+    /// The internal request timeout is zero.
+    #[error("initial_internal_request_timeout must be greater than zero")]
+    ZeroRequestTimeout,
+    /// Requests would be considered a protocol violation before they time out internally.
+    #[error(
+        "initial_internal_request_timeout ({initial:?}) exceeds protocol_breach_request_timeout ({protocol_breach:?})"
+    )]
+    RequestTimeoutExceedsProtocolBreach {
+        /// The internal request timeout.
+        initial: Duration,
+        /// The protocol breach timeout.
+        protocol_breach: Duration,
+    },
+    /// The per-peer bandwidth limit is zero.
+    #[error("peer_bandwidth_limit must be greater than zero")]
+    ZeroBandwidthLimit,
+}
+
+/// Observes and adjusts the `Hello` handshake of sessions.
+///
+/// This can be used to experiment with the advertised client identity and capabilities per
+/// connection, or to record the `Hello` of all peers for analytics.
+///
+/// Hooks are invoked synchronously on the task of the pending session they belong to and should
+/// not block.
+pub trait HelloHook: fmt::Debug + Send + Sync + 'static {
+    /// Invoked on the task of the pending outbound session, before the connection to
+    /// `remote_peer_id` is established.
+    ///
+    /// The given [HelloMessage] is sent on this connection only, so the client version and
+    /// capabilities can be adjusted per connection. Changes to the `id` are discarded, since the
+    /// remote verifies it against the node key.
+    fn on_outbound_hello(
+        &self,
+        _remote_addr: SocketAddr,
+        _remote_peer_id: PeerId,
+        _hello: &mut HelloMessage,
+    ) {
+    }
+
+    /// Invoked with the peer's [HelloMessage] once the p2p handshake of a session succeeded.
+    fn on_peer_hello(
+        &self,
+        _remote_addr: SocketAddr,
+        _direction: Direction,
+        _hello: &HelloMessage,
+    ) {
+    }
+}
+
+/// An optional [HelloHook] shared by all sessions.
+///
+/// Two instances are equal if they share the same hook.
+#[derive(Debug, Clone, Default)]
+pub struct HelloHooks(Option<Arc<dyn HelloHook>>);
+
+impl HelloHooks {
+    /// Creates a new instance with the given hook.
+    pub fn new(hook: impl HelloHook) -> Self {
+        Self(Some(Arc::new(hook)))
+    }
+
+    /// Returns true if no hook is configured.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_none()
+    }
+
+    /// See [HelloHook::on_outbound_hello]
+    pub(crate) fn on_outbound_hello(
+        &self,
+        remote_addr: SocketAddr,
+        remote_peer_id: PeerId,
+        hello: &mut HelloMessage,
+    ) {
+        if let Some(hook) = &self.0 {
+            // the remote verifies the id against the key used in the ECIES handshake
+            let id = hello.id;
+            hook.on_outbound_hello(remote_addr, remote_peer_id, hello);
+            hello.id = id;
+        }
+    }
+
+    /// See [HelloHook::on_peer_hello]
+    pub(crate) fn on_peer_hello(
+        &self,
+        remote_addr: SocketAddr,
+        direction: Direction,
+        hello: &HelloMessage,
+    ) {
+        if let Some(hook) = &self.0 {
+            hook.on_peer_hello(remote_addr, direction, hello);
+        }
+    }
+}
+
+impl PartialEq for HelloHooks {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (None, None) => true,
+            (Some(a), Some(b)) => Arc::as_ptr(a) as *const () == Arc::as_ptr(b) as *const (),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for HelloHooks {}
+
 /// Limits for sessions.
 ///
 /// By default, no session limits will be enforced
@@ -108,6 +439,16 @@ impl SessionLimits {
         self.max_established_outbound = Some(limit);
         self
     }
+
+    /// Returns the maximum number of active inbound sessions, if limited.
+    pub fn max_established_inbound(&self) -> Option<u32> {
+        self.max_established_inbound
+    }
+
+    /// Returns the maximum number of active outbound sessions, if limited.
+    pub fn max_established_outbound(&self) -> Option<u32> {
+        self.max_established_outbound
+    }
 }
 
 /// Keeps track of all sessions.
@@ -211,4 +552,41 @@ mod tests {
         limits.inc_pending_inbound();
         assert!(limits.ensure_pending_inbound().is_err());
     }
+
+    #[test]
+    fn test_presets_are_valid() {
+        SessionsConfig::default().validate().unwrap();
+        SessionsConfig::low_resource().validate().unwrap();
+        SessionsConfig::high_throughput().validate().unwrap();
+    }
+
+    #[test]
+    fn test_builder_validation() {
+        assert_eq!(
+            SessionsConfig::builder().session_event_buffer(0).build(),
+            Err(SessionsConfigError::ZeroBuffer("session_event_buffer"))
+        );
+        assert!(matches!(
+            SessionsConfig::builder()
+                .initial_internal_request_timeout(Duration::from_secs(60))
+                .protocol_breach_request_timeout(Duration::from_secs(30))
+                .build(),
+            Err(SessionsConfigError::RequestTimeoutExceedsProtocolBreach { .. })
+        ));
+        assert_eq!(
+            SessionsConfig::builder().peer_bandwidth_limit(PeerBandwidthLimit::new(0)).build(),
+            Err(SessionsConfigError::ZeroBandwidthLimit)
+        );
+
+        let config = SessionsConfig::high_throughput().into_builder().build().unwrap();
+        assert_eq!(config, SessionsConfig::high_throughput());
+    }
+
+    #[test]
+    fn test_presets() {
+        assert_eq!(SessionsPreset::LowResource.config(200), SessionsConfig::low_resource());
+        assert_eq!(SessionsPreset::HighThroughput.config(200), SessionsConfig::high_throughput());
+        assert_eq!(SessionsPreset::Default.config(1000).session_event_buffer, 2000);
+        assert_eq!(SessionsPreset::Default.config(1), SessionsConfig::default());
+    }
 }
@@ -9,7 +9,7 @@ use reth_eth_wire::{
     errors::EthStreamError,
     DisconnectReason, EthStream, EthVersion, P2PStream, Status,
 };
-use reth_net_common::bandwidth_meter::MeteredStream;
+use reth_net_common::bandwidth_meter::{BandwidthMeter, MeteredStream};
 use reth_network_api::PeerInfo;
 use reth_primitives::PeerId;
 use std::{io, net::SocketAddr, sync::Arc, time::Instant};
@@ -77,6 +77,8 @@ pub struct ActiveSessionHandle {
     pub(crate) local_addr: Option<SocketAddr>,
     /// The Status message the peer sent for the `eth` handshake
     pub(crate) status: Status,
+    /// Meters the bandwidth of the session.
+    pub(crate) bandwidth_meter: BandwidthMeter,
 }
 
 // === impl ActiveSessionHandle ===
@@ -148,6 +150,9 @@ impl ActiveSessionHandle {
             client_version: self.client_version.clone(),
             eth_version: self.version,
             status: self.status,
+            request_latency: None,
+            inbound_bytes: self.bandwidth_meter.total_inbound(),
+            outbound_bytes: self.bandwidth_meter.total_outbound(),
         }
     }
 }
@@ -193,6 +198,20 @@ pub enum PendingSessionEvent {
         error: Option<EthStreamError>,
     },
 
+    /// The peer's client version is banned, the session was disconnected after the `Hello`
+    /// handshake.
+    ClientVersionRejected {
+        /// The remote node's socket address
+        remote_addr: SocketAddr,
+        /// The internal identifier for the disconnected session
+        session_id: SessionId,
+        /// The direction of the session, either `Inbound` or `Outgoing`
+        direction: Direction,
+        /// The remote node's public key
+        peer_id: PeerId,
+        /// The client version the peer announced
+        client_version: String,
+    },
     /// Thrown when unable to establish a [`TcpStream`].
     OutgoingConnectionError {
         /// The remote node's socket address
@@ -2,13 +2,21 @@
 use crate::{
     message::PeerMessage,
     metrics::SessionManagerMetrics,
-    session::{active::ActiveSession, config::SessionCounter},
+    peers::ClientVersionFilter,
+    protocol::{ProtocolConnection, ProtocolHandler},
+    proxy::{self, ProxyConfig},
+    session::{
+        active::{ActiveSession, SubprotocolSession},
+        bandwidth::BandwidthLimiter,
+        capture::FrameCapture,
+        config::SessionCounter,
+    },
 };
 use fnv::FnvHashMap;
 use futures::{future::Either, io, FutureExt, StreamExt};
 use reth_ecies::{stream::ECIESStream, ECIESError};
 use reth_eth_wire::{
-    capability::{Capabilities, CapabilityMessage},
+    capability::{Capabilities, CapabilityMessage, Protocol, SharedCapability},
     errors::EthStreamError,
     DisconnectReason, EthVersion, HelloMessage, Status, UnauthedEthStream, UnauthedP2PStream,
 };
@@ -34,13 +42,20 @@ use tokio::{
     sync::{mpsc, oneshot},
 };
 use tokio_stream::wrappers::ReceiverStream;
-use tracing::{instrument, trace};
+use tracing::{debug, instrument, trace};
 
 mod active;
+mod bandwidth;
+mod capture;
 mod config;
 mod handle;
 pub use crate::message::PeerRequestSender;
-pub use config::{SessionLimits, SessionsConfig};
+pub use bandwidth::{BandwidthLimitAction, PeerBandwidthLimit};
+pub use capture::{SessionCaptureConfig, DEFAULT_CAPTURE_FRAMES};
+pub use config::{
+    HelloHook, HelloHooks, SessionLimits, SessionsConfig, SessionsConfigBuilder,
+    SessionsConfigError, SessionsPreset,
+};
 pub use handle::{
     ActiveSessionHandle, ActiveSessionMessage, PendingSessionEvent, PendingSessionHandle,
     SessionCommand,
@@ -102,6 +117,18 @@ pub struct SessionManager {
     bandwidth_meter: BandwidthMeter,
     /// Metrics for the session manager.
     metrics: SessionManagerMetrics,
+    /// The proxy outbound connections are tunneled through, if any.
+    proxy: Option<ProxyConfig>,
+    /// Hooks invoked during the `Hello` handshake.
+    hello_hooks: HelloHooks,
+    /// Rejects banned clients during the `Hello` handshake.
+    client_version_filter: ClientVersionFilter,
+    /// Handlers of the custom subprotocols announced to peers.
+    protocols: Vec<Arc<dyn ProtocolHandler>>,
+    /// Configures the capture of the messages exchanged with peers, if enabled.
+    capture: Option<SessionCaptureConfig>,
+    /// The maximum bandwidth of a single peer, if limited.
+    peer_bandwidth_limit: Option<PeerBandwidthLimit>,
 }
 
 // === impl SessionManager ===
@@ -116,6 +143,7 @@ impl SessionManager {
         hello_message: HelloMessage,
         fork_filter: ForkFilter,
         bandwidth_meter: BandwidthMeter,
+        proxy: Option<ProxyConfig>,
     ) -> Self {
         let (pending_sessions_tx, pending_sessions_rx) = mpsc::channel(config.session_event_buffer);
         let (active_session_tx, active_session_rx) = mpsc::channel(config.session_event_buffer);
@@ -139,6 +167,12 @@ impl SessionManager {
             active_session_rx: ReceiverStream::new(active_session_rx),
             bandwidth_meter,
             metrics: Default::default(),
+            proxy,
+            hello_hooks: config.hello_hooks,
+            client_version_filter: Default::default(),
+            protocols: Vec::new(),
+            capture: config.capture,
+            peer_bandwidth_limit: config.peer_bandwidth_limit,
         }
     }
 
@@ -165,6 +199,53 @@ impl SessionManager {
         self.hello_message.clone()
     }
 
+    /// Registers the handler of a custom subprotocol and announces its capability to peers.
+    ///
+    /// Only sessions that are established after the handler was added share the subprotocol.
+    pub(crate) fn add_protocol(&mut self, handler: impl ProtocolHandler) {
+        self.hello_message.capabilities.push(handler.protocol().cap);
+        self.protocols.push(Arc::new(handler));
+    }
+
+    /// Sets the [ClientVersionFilter] that rejects banned clients during the `Hello` handshake.
+    pub(crate) fn set_client_version_filter(&mut self, filter: ClientVersionFilter) {
+        self.client_version_filter = filter;
+    }
+
+    /// Returns the custom subprotocols announced to peers.
+    fn protocols(&self) -> Vec<Protocol> {
+        self.protocols.iter().map(|handler| handler.protocol()).collect()
+    }
+
+    /// Hands the connections of the shared custom subprotocols to their handlers and returns the
+    /// channels of the session.
+    fn subprotocol_sessions(
+        &self,
+        peer_id: PeerId,
+        direction: Direction,
+        shared: &[SharedCapability],
+    ) -> Vec<SubprotocolSession> {
+        let mut sessions = Vec::with_capacity(shared.len());
+        for cap in shared {
+            let Ok(messages) = cap.num_messages() else { continue };
+            let Some(handler) =
+                self.protocols.iter().find(|handler| handler.protocol().cap.name == cap.name())
+            else {
+                continue
+            };
+            let (to_handler, incoming) = mpsc::channel(self.session_command_buffer);
+            let (outgoing, from_handler) = mpsc::channel(self.session_command_buffer);
+            handler.on_connection(peer_id, direction, ProtocolConnection::new(incoming, outgoing));
+            sessions.push(SubprotocolSession {
+                name: cap.name().into(),
+                messages,
+                to_handler,
+                from_handler: ReceiverStream::new(from_handler).fuse(),
+            });
+        }
+        sessions
+    }
+
     /// Spawns the given future onto a new task that is tracked in the `spawned_tasks`
     /// [`JoinSet`](tokio::task::JoinSet).
     fn spawn<F>(&self, f: F)
@@ -213,6 +294,9 @@ impl SessionManager {
         let hello_message = self.hello_message.clone();
         let status = self.status;
         let fork_filter = self.fork_filter.clone();
+        let hello_hooks = self.hello_hooks.clone();
+        let client_version_filter = self.client_version_filter.clone();
+        let protocols = self.protocols();
         self.spawn(start_pending_incoming_session(
             disconnect_rx,
             session_id,
@@ -223,6 +307,9 @@ impl SessionManager {
             hello_message,
             status,
             fork_filter,
+            hello_hooks,
+            client_version_filter,
+            protocols,
         ));
 
         let handle = PendingSessionHandle {
@@ -246,6 +333,10 @@ impl SessionManager {
             let fork_filter = self.fork_filter.clone();
             let status = self.status;
             let band_with_meter = self.bandwidth_meter.clone();
+            let proxy = self.proxy.clone();
+            let hello_hooks = self.hello_hooks.clone();
+            let client_version_filter = self.client_version_filter.clone();
+            let protocols = self.protocols();
             self.spawn(start_pending_outbound_session(
                 disconnect_rx,
                 pending_events,
@@ -257,6 +348,10 @@ impl SessionManager {
                 status,
                 fork_filter,
                 band_with_meter,
+                proxy,
+                hello_hooks,
+                client_version_filter,
+                protocols,
             ));
 
             let handle = PendingSessionHandle {
@@ -439,6 +534,21 @@ impl SessionManager {
                 // negotiated version
                 let version = conn.version();
 
+                let subprotocols = self.subprotocol_sessions(
+                    peer_id,
+                    direction,
+                    conn.inner().shared_subprotocols(),
+                );
+
+                // meters the bandwidth of this session only
+                let bandwidth_meter = conn.inner().inner().inner().stream_meter().clone();
+
+                // the stream records the raw frames so that undecodable messages are captured too
+                let conn = match &self.capture {
+                    Some(capture) => conn.with_capture(capture.frames),
+                    None => conn,
+                };
+
                 let session = ActiveSession {
                     next_id: 0,
                     remote_peer_id: peer_id,
@@ -459,6 +569,11 @@ impl SessionManager {
                     internal_request_timeout: Arc::clone(&timeout),
                     protocol_breach_request_timeout: self.protocol_breach_request_timeout,
                     terminate_message: None,
+                    capture: self.capture.as_ref().and_then(FrameCapture::new),
+                    subprotocols,
+                    bandwidth_limiter: self
+                        .peer_bandwidth_limit
+                        .map(|limit| BandwidthLimiter::new(limit, bandwidth_meter.clone())),
                 };
 
                 self.spawn(session);
@@ -476,6 +591,7 @@ impl SessionManager {
                     client_version: Arc::clone(&client_version),
                     remote_addr,
                     local_addr,
+                    bandwidth_meter,
                 };
 
                 self.active_sessions.insert(peer_id, handle);
@@ -522,6 +638,32 @@ impl SessionManager {
                     }
                 }
             }
+            PendingSessionEvent::ClientVersionRejected {
+                remote_addr,
+                session_id,
+                direction,
+                peer_id,
+                client_version,
+            } => {
+                self.remove_pending_session(&session_id);
+                let error =
+                    Some(PendingSessionHandshakeError::ClientVersion { peer_id, client_version });
+                match direction {
+                    Direction::Incoming => {
+                        Poll::Ready(SessionEvent::IncomingPendingSessionClosed {
+                            remote_addr,
+                            error,
+                        })
+                    }
+                    Direction::Outgoing(peer_id) => {
+                        Poll::Ready(SessionEvent::OutgoingPendingSessionClosed {
+                            remote_addr,
+                            peer_id,
+                            error,
+                        })
+                    }
+                }
+            }
             PendingSessionEvent::OutgoingConnectionError {
                 remote_addr,
                 session_id,
@@ -693,6 +835,14 @@ pub enum PendingSessionHandshakeError {
     Eth(EthStreamError),
     /// The pending session failed due to an error while establishing the ECIES stream
     Ecies(ECIESError),
+    /// The peer was rejected during the `Hello` handshake, because its client version is banned
+    /// by a [ClientVersionPolicy](crate::ClientVersionPolicy).
+    ClientVersion {
+        /// The id of the rejected peer.
+        peer_id: PeerId,
+        /// The client version the peer announced.
+        client_version: String,
+    },
 }
 
 impl PendingSessionHandshakeError {
@@ -725,6 +875,9 @@ pub(crate) async fn start_pending_incoming_session(
     hello: HelloMessage,
     status: Status,
     fork_filter: ForkFilter,
+    hello_hooks: HelloHooks,
+    client_version_filter: ClientVersionFilter,
+    protocols: Vec<Protocol>,
 ) {
     authenticate(
         disconnect_rx,
@@ -737,6 +890,9 @@ pub(crate) async fn start_pending_incoming_session(
         hello,
         status,
         fork_filter,
+        hello_hooks,
+        client_version_filter,
+        protocols,
     )
     .await
 }
@@ -751,12 +907,18 @@ async fn start_pending_outbound_session(
     remote_addr: SocketAddr,
     remote_peer_id: PeerId,
     secret_key: SecretKey,
-    hello: HelloMessage,
+    mut hello: HelloMessage,
     status: Status,
     fork_filter: ForkFilter,
     bandwidth_meter: BandwidthMeter,
+    proxy: Option<ProxyConfig>,
+    hello_hooks: HelloHooks,
+    client_version_filter: ClientVersionFilter,
+    protocols: Vec<Protocol>,
 ) {
-    let stream = match TcpStream::connect(remote_addr).await {
+    hello_hooks.on_outbound_hello(remote_addr, remote_peer_id, &mut hello);
+
+    let stream = match proxy::connect(proxy.as_ref(), remote_addr).await {
         Ok(stream) => MeteredStream::new_with_meter(stream, bandwidth_meter),
         Err(error) => {
             let _ = events
@@ -781,6 +943,9 @@ async fn start_pending_outbound_session(
         hello,
         status,
         fork_filter,
+        hello_hooks,
+        client_version_filter,
+        protocols,
     )
     .await
 }
@@ -798,6 +963,9 @@ async fn authenticate(
     hello: HelloMessage,
     status: Status,
     fork_filter: ForkFilter,
+    hello_hooks: HelloHooks,
+    client_version_filter: ClientVersionFilter,
+    protocols: Vec<Protocol>,
 ) {
     let local_addr = stream.inner().local_addr().ok();
     let stream = match get_eciess_stream(stream, secret_key, direction).await {
@@ -815,7 +983,7 @@ async fn authenticate(
         }
     };
 
-    let unauthed = UnauthedP2PStream::new(stream);
+    let unauthed = UnauthedP2PStream::new(stream).with_protocols(protocols);
 
     let auth = authenticate_stream(
         unauthed,
@@ -826,6 +994,8 @@ async fn authenticate(
         hello,
         status,
         fork_filter,
+        hello_hooks,
+        client_version_filter,
     )
     .boxed();
 
@@ -874,9 +1044,11 @@ async fn authenticate_stream(
     hello: HelloMessage,
     status: Status,
     fork_filter: ForkFilter,
+    hello_hooks: HelloHooks,
+    client_version_filter: ClientVersionFilter,
 ) -> PendingSessionEvent {
     // conduct the p2p handshake and return the authenticated stream
-    let (p2p_stream, their_hello) = match stream.handshake(hello).await {
+    let (mut p2p_stream, their_hello) = match stream.handshake(hello).await {
         Ok(stream_res) => stream_res,
         Err(err) => {
             return PendingSessionEvent::Disconnected {
@@ -888,6 +1060,23 @@ async fn authenticate_stream(
         }
     };
 
+    hello_hooks.on_peer_hello(remote_addr, direction, &their_hello);
+
+    // reject banned clients before the `eth` handshake
+    if let Some(policy) =
+        client_version_filter.banned_by(&their_hello.id, &their_hello.client_version)
+    {
+        debug!(target: "net::session", ?remote_addr, peer_id=?their_hello.id, client_version=%their_hello.client_version, pattern=%policy.pattern, "rejecting banned client");
+        let _ = p2p_stream.disconnect(DisconnectReason::UselessPeer).await;
+        return PendingSessionEvent::ClientVersionRejected {
+            remote_addr,
+            session_id,
+            direction,
+            peer_id: their_hello.id,
+            client_version: their_hello.client_version,
+        }
+    }
+
     // if the hello handshake was successful we can try status handshake
     //
     // Before trying status handshake, set up the version to shared_capability
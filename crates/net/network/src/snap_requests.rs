@@ -0,0 +1,523 @@
+//! State requests of the `snap` protocol.
+
+use crate::{metrics::SnapRequestHandlerMetrics, ProtocolConnection, ProtocolHandler};
+use futures::{stream::SelectAll, Stream, StreamExt};
+use reth_eth_wire::{
+    capability::{Protocol, RawCapabilityMessage},
+    snap::{
+        AccountData, AccountRange, ByteCodes, GetAccountRange, GetByteCodes, GetStorageRanges,
+        GetTrieNodes, StorageData, StorageRanges, TrieNodes,
+    },
+    snap_protocol, SnapMessage,
+};
+use reth_interfaces::Result;
+use reth_network_api::Direction;
+use reth_primitives::{
+    proofs::EMPTY_ROOT, trie::Nibbles, Account, Bytes, PeerId, H256, KECCAK_EMPTY, U256,
+};
+use reth_provider::{SnapStateProviderFactory, StateProviderFactory};
+use reth_rlp::Encodable;
+use reth_rlp_derive::RlpEncodable;
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::sync::mpsc::{self, UnboundedSender};
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tracing::trace;
+
+// Limits: <https://github.com/ethereum/go-ethereum/blob/master/eth/protocols/snap/handler.go>
+
+/// Maximum size of replies to state retrievals.
+const SOFT_RESPONSE_LIMIT: usize = 2 * 1024 * 1024;
+
+/// Maximum number of bytecodes to serve.
+const MAX_CODES_SERVE: usize = 1024;
+
+/// Maximum number of trie nodes to look up.
+const MAX_TRIE_NODE_LOOKUPS: usize = 1024;
+
+/// Number of accounts read from the database at once.
+const ACCOUNT_BATCH_SIZE: usize = 128;
+
+/// Number of storage slots read from the database at once.
+const STORAGE_BATCH_SIZE: usize = 1024;
+
+/// Registers the `snap` protocol with the network and hands the connections to the
+/// [SnapRequestHandler].
+#[derive(Debug)]
+pub struct SnapProtocolHandler {
+    to_request_handler: UnboundedSender<(PeerId, ProtocolConnection)>,
+}
+
+impl ProtocolHandler for SnapProtocolHandler {
+    fn protocol(&self) -> Protocol {
+        snap_protocol()
+    }
+
+    fn on_connection(&self, peer_id: PeerId, _direction: Direction, conn: ProtocolConnection) {
+        let _ = self.to_request_handler.send((peer_id, conn));
+    }
+}
+
+/// Serves the state requests of the `snap` protocol from the states of the recent blocks.
+///
+/// Each request is served from a single database transaction. Account and storage ranges are
+/// served with the proofs of the boundaries of the range, requests for a state that is not
+/// available are answered with an empty response.
+///
+/// Responses to requests of this node are not consumed by the handler.
+///
+/// This can be spawned to another task and is supposed to be run as background service.
+#[must_use = "Handler does nothing unless polled."]
+pub struct SnapRequestHandler<C> {
+    /// The client type that can interact with the chain.
+    client: C,
+    /// Connections of new sessions with peers that share the `snap` protocol.
+    incoming_connections: UnboundedReceiverStream<(PeerId, ProtocolConnection)>,
+    /// The connections of all sessions.
+    connections: SelectAll<SnapConnection>,
+    /// Metrics for the snap request handler.
+    metrics: SnapRequestHandlerMetrics,
+}
+
+// === impl SnapRequestHandler ===
+impl<C> SnapRequestHandler<C> {
+    /// Creates a new handler and the [SnapProtocolHandler] to register with the network.
+    pub fn new(client: C) -> (Self, SnapProtocolHandler) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let handler = Self {
+            client,
+            incoming_connections: UnboundedReceiverStream::new(rx),
+            connections: SelectAll::new(),
+            metrics: Default::default(),
+        };
+        (handler, SnapProtocolHandler { to_request_handler: tx })
+    }
+}
+
+impl<C> SnapRequestHandler<C>
+where
+    C: SnapStateProviderFactory + StateProviderFactory,
+{
+    fn get_account_range(&self, request: GetAccountRange) -> Result<AccountRange> {
+        let GetAccountRange { request_id, root_hash, starting_hash, limit_hash, response_bytes } =
+            request;
+        let mut response = AccountRange { request_id, accounts: Vec::new(), proof: Vec::new() };
+        let Some(state) = self.client.snap_state_by_root(root_hash)? else { return Ok(response) };
+
+        let max_bytes = (response_bytes as usize).min(SOFT_RESPONSE_LIMIT);
+        let mut total_bytes = 0;
+        let mut start = Some(starting_hash);
+        'accounts: while let Some(next) = start {
+            let accounts = state.hashed_accounts(next, ACCOUNT_BATCH_SIZE)?;
+            start = if accounts.len() < ACCOUNT_BATCH_SIZE {
+                None
+            } else {
+                accounts.last().and_then(|(hash, _)| increment_hash(*hash))
+            };
+
+            for (hash, account) in accounts {
+                let body = slim_account_body(account, state.storage_root(hash)?);
+                total_bytes += H256::len_bytes() + body.len();
+                response.accounts.push(AccountData { hash, body });
+
+                if hash >= limit_hash || total_bytes >= max_bytes {
+                    break 'accounts
+                }
+            }
+        }
+
+        // prove the origin and the last account of the range
+        let mut proved = vec![starting_hash];
+        proved.extend(response.accounts.last().map(|account| account.hash));
+        response.proof = state.account_proof(proved)?;
+
+        Ok(response)
+    }
+
+    fn get_storage_ranges(&self, request: GetStorageRanges) -> Result<StorageRanges> {
+        let GetStorageRanges {
+            request_id,
+            root_hash,
+            account_hashes,
+            starting_hash,
+            limit_hash,
+            response_bytes,
+        } = request;
+        let mut response = StorageRanges { request_id, slots: Vec::new(), proof: Vec::new() };
+        let Some(state) = self.client.snap_state_by_root(root_hash)? else { return Ok(response) };
+
+        let max_bytes = (response_bytes as usize).min(SOFT_RESPONSE_LIMIT);
+        let mut total_bytes = 0;
+        for (index, hashed_address) in account_hashes.into_iter().enumerate() {
+            if total_bytes >= max_bytes {
+                break
+            }
+
+            // only the range of the first account can start and end at other slots
+            let (origin, limit) = if index == 0 {
+                let origin = if starting_hash.is_empty() {
+                    H256::zero()
+                } else {
+                    hash_from_bytes(&starting_hash)
+                };
+                let limit = if limit_hash.is_empty() {
+                    H256::repeat_byte(0xff)
+                } else {
+                    hash_from_bytes(&limit_hash)
+                };
+                (origin, limit)
+            } else {
+                (H256::zero(), H256::repeat_byte(0xff))
+            };
+
+            let mut slots = Vec::new();
+            let mut capped = false;
+            let mut start = Some(origin);
+            'slots: while let Some(next) = start {
+                let entries = state.hashed_storage(hashed_address, next, STORAGE_BATCH_SIZE)?;
+                start = if entries.len() < STORAGE_BATCH_SIZE {
+                    None
+                } else {
+                    entries.last().and_then(|entry| increment_hash(entry.key))
+                };
+
+                for entry in entries {
+                    if total_bytes >= max_bytes {
+                        capped = true;
+                        break 'slots
+                    }
+
+                    let mut data = Vec::new();
+                    entry.value.encode(&mut data);
+                    total_bytes += H256::len_bytes() + data.len();
+                    slots.push(StorageData { hash: entry.key, data: data.into() });
+
+                    if entry.key >= limit {
+                        break 'slots
+                    }
+                }
+            }
+
+            let last = slots.last().map(|slot| slot.hash);
+            if !slots.is_empty() {
+                response.slots.push(slots);
+            }
+
+            // a partial storage range needs the proofs of its boundaries and ends the response
+            if !origin.is_zero() || (capped && last.is_some()) {
+                let mut proved = vec![origin];
+                proved.extend(last);
+                response.proof = state.storage_proof(hashed_address, proved)?;
+                break
+            }
+        }
+
+        Ok(response)
+    }
+
+    fn get_byte_codes(&self, request: GetByteCodes) -> Result<ByteCodes> {
+        let GetByteCodes { request_id, hashes, response_bytes } = request;
+        let mut response = ByteCodes { request_id, codes: Vec::new() };
+        let state = self.client.latest()?;
+
+        let max_bytes = (response_bytes as usize).min(SOFT_RESPONSE_LIMIT);
+        let mut total_bytes = 0;
+        for hash in hashes.into_iter().take(MAX_CODES_SERVE) {
+            let code: Bytes = if hash == KECCAK_EMPTY {
+                Bytes::default()
+            } else {
+                match state.bytecode_by_hash(hash)? {
+                    Some(code) => code.original_bytes().into(),
+                    None => continue,
+                }
+            };
+
+            total_bytes += code.len();
+            response.codes.push(code);
+            if total_bytes >= max_bytes {
+                break
+            }
+        }
+
+        Ok(response)
+    }
+
+    fn get_trie_nodes(&self, request: GetTrieNodes) -> Result<TrieNodes> {
+        let GetTrieNodes { request_id, root_hash, paths, response_bytes } = request;
+        let mut response = TrieNodes { request_id, nodes: Vec::new() };
+        let Some(state) = self.client.snap_state_by_root(root_hash)? else { return Ok(response) };
+
+        let max_bytes = (response_bytes as usize).min(SOFT_RESPONSE_LIMIT);
+        let mut total_bytes = 0;
+        let mut lookups = 0;
+        for path_set in paths {
+            if total_bytes >= max_bytes || lookups >= MAX_TRIE_NODE_LOOKUPS {
+                break
+            }
+
+            let nodes = match path_set.as_slice() {
+                [] => break,
+                // a single path is the path of a node of the state trie
+                [path] => {
+                    lookups += 1;
+                    lookup_trie_nodes(std::slice::from_ref(path), |paths| {
+                        state.account_trie_nodes(paths)
+                    })?
+                }
+                // the hashed address of an account followed by paths of its storage trie
+                [hashed_address, paths @ ..] => {
+                    let hashed_address = hash_from_bytes(hashed_address);
+                    let paths = &paths[..paths.len().min(MAX_TRIE_NODE_LOOKUPS - lookups)];
+                    lookups += paths.len();
+                    lookup_trie_nodes(paths, |paths| {
+                        state.storage_trie_nodes(hashed_address, paths)
+                    })?
+                }
+            };
+
+            for node in nodes {
+                total_bytes += node.len();
+                response.nodes.push(node);
+                if total_bytes >= max_bytes {
+                    break
+                }
+            }
+        }
+
+        Ok(response)
+    }
+
+    fn on_message(&self, peer_id: PeerId, msg: RawCapabilityMessage) -> Option<SnapMessage> {
+        let request = match SnapMessage::decode_message(&msg) {
+            Ok(request) => request,
+            Err(err) => {
+                trace!(target: "net::snap", ?peer_id, %err, "invalid snap message");
+                return None
+            }
+        };
+
+        let response = match request {
+            SnapMessage::GetAccountRange(request) => {
+                self.metrics.received_account_range_requests.increment(1);
+                let request_id = request.request_id;
+                SnapMessage::AccountRange(self.get_account_range(request).unwrap_or_else(|err| {
+                    trace!(target: "net::snap", ?peer_id, %err, "failed to serve account range");
+                    AccountRange { request_id, accounts: Vec::new(), proof: Vec::new() }
+                }))
+            }
+            SnapMessage::GetStorageRanges(request) => {
+                self.metrics.received_storage_ranges_requests.increment(1);
+                let request_id = request.request_id;
+                SnapMessage::StorageRanges(self.get_storage_ranges(request).unwrap_or_else(|err| {
+                    trace!(target: "net::snap", ?peer_id, %err, "failed to serve storage ranges");
+                    StorageRanges { request_id, slots: Vec::new(), proof: Vec::new() }
+                }))
+            }
+            SnapMessage::GetByteCodes(request) => {
+                self.metrics.received_bytecodes_requests.increment(1);
+                let request_id = request.request_id;
+                SnapMessage::ByteCodes(self.get_byte_codes(request).unwrap_or_else(|err| {
+                    trace!(target: "net::snap", ?peer_id, %err, "failed to serve bytecodes");
+                    ByteCodes { request_id, codes: Vec::new() }
+                }))
+            }
+            SnapMessage::GetTrieNodes(request) => {
+                self.metrics.received_trie_nodes_requests.increment(1);
+                let request_id = request.request_id;
+                SnapMessage::TrieNodes(self.get_trie_nodes(request).unwrap_or_else(|err| {
+                    trace!(target: "net::snap", ?peer_id, %err, "failed to serve trie nodes");
+                    TrieNodes { request_id, nodes: Vec::new() }
+                }))
+            }
+            SnapMessage::AccountRange(_) |
+            SnapMessage::StorageRanges(_) |
+            SnapMessage::ByteCodes(_) |
+            SnapMessage::TrieNodes(_) => return None,
+        };
+        Some(response)
+    }
+}
+
+/// An endless future.
+///
+/// This should be spawned or used as part of `tokio::select!`.
+impl<C> Future for SnapRequestHandler<C>
+where
+    C: SnapStateProviderFactory + StateProviderFactory + Unpin,
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        loop {
+            while let Poll::Ready(Some((peer_id, conn))) =
+                this.incoming_connections.poll_next_unpin(cx)
+            {
+                this.connections.push(SnapConnection { peer_id, conn });
+            }
+
+            match this.connections.poll_next_unpin(cx) {
+                Poll::Ready(Some((peer_id, to_peer, msg))) => {
+                    if let Some(response) = this.on_message(peer_id, msg) {
+                        let _ = to_peer.try_send(response.encode_message());
+                    }
+                }
+                // all sessions are closed, wait for new connections
+                Poll::Ready(None) | Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// The `snap` messages of a session with a peer.
+struct SnapConnection {
+    peer_id: PeerId,
+    conn: ProtocolConnection,
+}
+
+impl Stream for SnapConnection {
+    type Item = (PeerId, mpsc::Sender<RawCapabilityMessage>, RawCapabilityMessage);
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        this.conn
+            .poll_next_unpin(cx)
+            .map(|msg| msg.map(|msg| (this.peer_id, this.conn.sender(), msg)))
+    }
+}
+
+/// An account in the slim format of the `snap` protocol.
+#[derive(RlpEncodable)]
+struct SlimAccount {
+    nonce: u64,
+    balance: U256,
+    /// Empty if the account has no storage.
+    storage_root: Bytes,
+    /// Empty if the account has no code.
+    code_hash: Bytes,
+}
+
+/// Returns the RLP encoding of the account in the slim format of the `snap` protocol.
+fn slim_account_body(account: Account, storage_root: H256) -> Bytes {
+    let empty_or = |hash: H256, empty: H256| {
+        if hash == empty {
+            Bytes::default()
+        } else {
+            Bytes::from(hash.as_bytes().to_vec())
+        }
+    };
+    let account = SlimAccount {
+        nonce: account.nonce,
+        balance: account.balance,
+        storage_root: empty_or(storage_root, EMPTY_ROOT),
+        code_hash: empty_or(account.bytecode_hash.unwrap_or(KECCAK_EMPTY), KECCAK_EMPTY),
+    };
+    let mut body = Vec::new();
+    account.encode(&mut body);
+    body.into()
+}
+
+/// Looks up the trie nodes at the compact encoded paths, paths that can't be decoded or that have
+/// no node get an empty node.
+fn lookup_trie_nodes(
+    paths: &[Bytes],
+    lookup: impl FnOnce(Vec<Nibbles>) -> Result<Vec<Option<Bytes>>>,
+) -> Result<Vec<Bytes>> {
+    let decoded = paths.iter().map(|path| decode_compact_path(path)).collect::<Vec<_>>();
+    let mut found = lookup(decoded.iter().flatten().cloned().collect())?.into_iter();
+    Ok(decoded
+        .into_iter()
+        .map(|path| path.and_then(|_| found.next().flatten()).unwrap_or_default())
+        .collect())
+}
+
+/// Decodes a path of a trie node in the compact (hex-prefix) encoding.
+///
+/// Returns `None` if the path is not a valid compact encoding.
+fn decode_compact_path(path: &[u8]) -> Option<Nibbles> {
+    let (first, rest) = path.split_first()?;
+    let flag = first >> 4;
+    if flag > 3 {
+        return None
+    }
+
+    let mut nibbles = Vec::with_capacity(rest.len() * 2 + 1);
+    // an odd number of nibbles stores the first nibble with the flag
+    if flag & 1 == 1 {
+        nibbles.push(first & 0x0f);
+    } else if first & 0x0f != 0 {
+        return None
+    }
+    for byte in rest {
+        nibbles.push(byte >> 4);
+        nibbles.push(byte & 0x0f);
+    }
+    Some(Nibbles::from_hex(nibbles))
+}
+
+/// Returns the hash of the bytes, left padded with zeros or truncated to the last 32 bytes.
+fn hash_from_bytes(bytes: &[u8]) -> H256 {
+    let mut hash = H256::zero();
+    let len = bytes.len().min(H256::len_bytes());
+    hash.0[H256::len_bytes() - len..].copy_from_slice(&bytes[bytes.len() - len..]);
+    hash
+}
+
+/// Returns the hash that follows the given hash, `None` for the largest hash.
+fn increment_hash(hash: H256) -> Option<H256> {
+    U256::from_be_bytes(hash.0).checked_add(U256::from(1)).map(|next| H256(next.to_be_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_rlp::Decodable;
+
+    #[test]
+    fn encode_slim_account() {
+        let account = Account { nonce: 1, balance: U256::from(2), bytecode_hash: None };
+        let body = slim_account_body(account, EMPTY_ROOT);
+        // [1, 2, "", ""]
+        assert_eq!(&body[..], &[0xc4, 0x01, 0x02, 0x80, 0x80]);
+
+        let code_hash = H256::repeat_byte(1);
+        let storage_root = H256::repeat_byte(2);
+        let account = Account { bytecode_hash: Some(code_hash), ..account };
+        let body = slim_account_body(account, storage_root);
+        let mut buf = &body[..];
+        let header = reth_rlp::Header::decode(&mut buf).unwrap();
+        assert!(header.list);
+        assert_eq!(u64::decode(&mut buf).unwrap(), 1);
+        assert_eq!(U256::decode(&mut buf).unwrap(), U256::from(2));
+        assert_eq!(H256::decode(&mut buf).unwrap(), storage_root);
+        assert_eq!(H256::decode(&mut buf).unwrap(), code_hash);
+    }
+
+    #[test]
+    fn decode_compact_paths() {
+        assert_eq!(decode_compact_path(&[0x00]), Some(Nibbles::default()));
+        assert_eq!(decode_compact_path(&[0x00, 0x12]), Some(Nibbles::from_hex(vec![1, 2])));
+        assert_eq!(decode_compact_path(&[0x11, 0x23]), Some(Nibbles::from_hex(vec![1, 2, 3])));
+        assert_eq!(decode_compact_path(&[0x3f]), Some(Nibbles::from_hex(vec![0xf])));
+        assert_eq!(decode_compact_path(&[0x01]), None);
+        assert_eq!(decode_compact_path(&[0x40]), None);
+        assert_eq!(decode_compact_path(&[]), None);
+    }
+
+    #[test]
+    fn hashes_from_bytes() {
+        assert_eq!(hash_from_bytes(&[]), H256::zero());
+        assert_eq!(hash_from_bytes(&[1]), H256::from_low_u64_be(1));
+        assert_eq!(hash_from_bytes(H256::repeat_byte(2).as_bytes()), H256::repeat_byte(2));
+    }
+
+    #[test]
+    fn increment_hashes() {
+        assert_eq!(increment_hash(H256::zero()), Some(H256::from_low_u64_be(1)));
+        assert_eq!(increment_hash(H256::repeat_byte(0xff)), None);
+    }
+}
@@ -13,7 +13,7 @@ use crate::{
     FetchClient,
 };
 use reth_eth_wire::{
-    capability::Capabilities, BlockHashNumber, DisconnectReason, NewBlockHashes, Status,
+    capability::Capabilities, BlockHashNumber, DisconnectReason, EthVersion, NewBlockHashes, Status,
 };
 use reth_network_api::PeerKind;
 use reth_primitives::{ForkId, PeerId, H256};
@@ -27,6 +27,7 @@ use std::{
         Arc,
     },
     task::{Context, Poll},
+    time::Duration,
 };
 use tokio::sync::oneshot;
 use tracing::debug;
@@ -111,6 +112,11 @@ where
         self.state_fetcher.client()
     }
 
+    /// Returns the average round-trip time of the requests the peer served, if any.
+    pub(crate) fn peer_latency(&self, peer_id: &PeerId) -> Option<Duration> {
+        self.state_fetcher.peer_latency(peer_id)
+    }
+
     /// Configured genesis hash.
     pub fn genesis_hash(&self) -> H256 {
         self.genesis_hash
@@ -130,6 +136,7 @@ where
         peer: PeerId,
         capabilities: Arc<Capabilities>,
         status: Status,
+        version: EthVersion,
         request_tx: PeerRequestSender,
         timeout: Arc<AtomicU64>,
     ) {
@@ -138,7 +145,14 @@ where
         // find the corresponding block number
         let block_number =
             self.client.block_number(status.blockhash).ok().flatten().unwrap_or_default();
-        self.state_fetcher.new_active_peer(peer, status.blockhash, block_number, timeout);
+        self.state_fetcher.new_active_peer(
+            peer,
+            status.blockhash,
+            block_number,
+            timeout,
+            version,
+            capabilities.clone(),
+        );
 
         self.active_peers.insert(
             peer,
@@ -349,6 +363,18 @@ where
                     let response = PeerResponse::BlockBodies { response: rx };
                     (request, response)
                 }
+                BlockRequest::GetPooledTransactions(request) => {
+                    let (response, rx) = oneshot::channel();
+                    let request = PeerRequest::GetPooledTransactions { request, response };
+                    let response = PeerResponse::PooledTransactions { response: rx };
+                    (request, response)
+                }
+                BlockRequest::GetReceipts(request) => {
+                    let (response, rx) = oneshot::channel();
+                    let request = PeerRequest::GetReceipts { request, response };
+                    let response = PeerResponse::Receipts { response: rx };
+                    (request, response)
+                }
             };
             let _ = peer.request_tx.to_session_tx.try_send(request);
             peer.pending_response = Some(response);
@@ -376,11 +402,26 @@ where
     fn on_eth_response(&mut self, peer: PeerId, resp: PeerResponseResult) -> Option<StateAction> {
         match resp {
             PeerResponseResult::BlockHeaders(res) => {
-                let outcome = self.state_fetcher.on_block_headers_response(peer, res)?;
+                let outcome =
+                    self.state_fetcher.on_block_headers_response(peer, res, &self.peers_manager)?;
                 self.on_block_response_outcome(outcome)
             }
             PeerResponseResult::BlockBodies(res) => {
-                let outcome = self.state_fetcher.on_block_bodies_response(peer, res)?;
+                let outcome =
+                    self.state_fetcher.on_block_bodies_response(peer, res, &self.peers_manager)?;
+                self.on_block_response_outcome(outcome)
+            }
+            PeerResponseResult::PooledTransactions(res) => {
+                let outcome = self.state_fetcher.on_pooled_transactions_response(
+                    peer,
+                    res,
+                    &self.peers_manager,
+                )?;
+                self.on_block_response_outcome(outcome)
+            }
+            PeerResponseResult::Receipts(res) => {
+                let outcome =
+                    self.state_fetcher.on_receipts_response(peer, res, &self.peers_manager)?;
                 self.on_block_response_outcome(outcome)
             }
             _ => None,
@@ -399,7 +440,7 @@ where
                 self.on_discovery_event(discovery);
             }
 
-            while let Poll::Ready(action) = self.state_fetcher.poll(cx) {
+            while let Poll::Ready(action) = self.state_fetcher.poll(cx, &self.peers_manager) {
                 match action {
                     FetchAction::BlockRequest { peer_id, request } => {
                         self.handle_block_request(peer_id, request)
@@ -570,6 +611,7 @@ mod tests {
             peer_id,
             capabilities(),
             Status::default(),
+            EthVersion::Eth67,
             peer_tx,
             Arc::new(AtomicU64::new(1)),
         );
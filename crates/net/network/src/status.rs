@@ -0,0 +1,62 @@
+//! Keeps the network's [`Status`](reth_eth_wire::Status) in sync with the canonical chain.
+
+use crate::NetworkHandle;
+use futures::{future::BoxFuture, FutureExt, Stream, StreamExt};
+use reth_primitives::Head;
+use reth_provider::{CanonStateNotification, HeaderProvider};
+use tracing::{debug, trace};
+
+/// Returns a spawnable future that keeps the head of the network in sync with the canonical chain.
+///
+/// See [track_canonical_head].
+pub fn track_canonical_head_future<Client, St>(
+    network: NetworkHandle,
+    client: Client,
+    events: St,
+) -> BoxFuture<'static, ()>
+where
+    Client: HeaderProvider + Send + 'static,
+    St: Stream<Item = CanonStateNotification> + Send + Unpin + 'static,
+{
+    track_canonical_head(network, client, events).boxed()
+}
+
+/// Updates the head of the network whenever the canonical chain changes.
+///
+/// The head determines the local [`ForkId`](reth_primitives::ForkId) that is advertised to peers
+/// and used to validate theirs. Tracking the canonical head directly ensures block and timestamp
+/// based hardforks are picked up as soon as the canonical chain crosses them, independent of
+/// explicit status updates.
+pub async fn track_canonical_head<Client, St>(
+    network: NetworkHandle,
+    client: Client,
+    mut events: St,
+) where
+    Client: HeaderProvider,
+    St: Stream<Item = CanonStateNotification> + Unpin,
+{
+    while let Some(notification) = events.next().await {
+        let tip = &notification.tip().header;
+        let total_difficulty = match client.header_td_by_number(tip.number) {
+            Ok(Some(td)) => td,
+            Ok(None) => {
+                debug!(target: "net", number=tip.number, "Missing total difficulty of canonical head");
+                continue
+            }
+            Err(err) => {
+                debug!(target: "net", %err, number=tip.number, "Failed to read total difficulty of canonical head");
+                continue
+            }
+        };
+
+        let head = Head {
+            number: tip.number,
+            hash: tip.hash(),
+            difficulty: tip.difficulty,
+            total_difficulty,
+            timestamp: tip.timestamp,
+        };
+        trace!(target: "net", ?head, "Updating head from canonical chain");
+        network.update_status(head);
+    }
+}
@@ -137,6 +137,7 @@ where
                     peer_id,
                     capabilities.clone(),
                     status,
+                    version,
                     messages.clone(),
                     timeout,
                 );
@@ -3,6 +3,7 @@
 use crate::{
     builder::ETH_REQUEST_CHANNEL_CAPACITY, error::NetworkError, eth_requests::EthRequestHandler,
     NetworkConfig, NetworkConfigBuilder, NetworkEvent, NetworkHandle, NetworkManager,
+    ProtocolHandler, SessionsConfig,
 };
 use futures::{FutureExt, StreamExt};
 use pin_project::pin_project;
@@ -234,6 +235,11 @@ where
         self.network.handle().clone()
     }
 
+    /// Registers the handler of a custom subprotocol with the peer's network.
+    pub fn add_protocol(&mut self, handler: impl ProtocolHandler) {
+        self.network.add_protocol(handler);
+    }
+
     /// Set a new request handler that's connected to the peer's network
     pub fn install_request_handler(&mut self) {
         let (tx, rx) = channel(ETH_REQUEST_CHANNEL_CAPACITY);
@@ -309,6 +315,15 @@ where
         Self { config, client, secret_key }
     }
 
+    /// Initialize the network with the given sessions config.
+    pub fn with_sessions_config(client: C, sessions_config: SessionsConfig) -> Self {
+        let secret_key = SecretKey::new(&mut rand::thread_rng());
+        let config = Self::network_config_builder(secret_key)
+            .sessions_config(sessions_config)
+            .build(client.clone());
+        Self { config, client, secret_key }
+    }
+
     fn network_config_builder(secret_key: SecretKey) -> NetworkConfigBuilder {
         NetworkConfigBuilder::new(secret_key)
             .listener_addr(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0)))
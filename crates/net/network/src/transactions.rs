@@ -1,7 +1,7 @@
 //! Transactions management for the p2p network.
 
 use crate::{
-    cache::LruCache,
+    cache::TimedLruCache,
     manager::NetworkEvent,
     message::{PeerRequest, PeerRequestSender},
     metrics::{TransactionsManagerMetrics, NETWORK_POOL_TRANSACTIONS_SCOPE},
@@ -20,26 +20,42 @@ use reth_metrics::common::mpsc::UnboundedMeteredReceiver;
 use reth_network_api::{Peers, ReputationChangeKind};
 use reth_primitives::{
     FromRecoveredPooledTransaction, IntoRecoveredTransaction, PeerId, PooledTransactionsElement,
-    TransactionSigned, TxHash, H256,
+    PooledTransactionsElementEcRecovered, TransactionSigned, TxHash, EIP1559_TX_TYPE_ID,
+    EIP2930_TX_TYPE_ID, EIP4844_TX_TYPE_ID, H256, LEGACY_TX_TYPE_ID,
 };
 use reth_transaction_pool::{
-    error::PoolResult, GetPooledTransactionLimit, PoolTransaction, PropagateKind,
+    error::PoolResult, DynamicFeeFloor, GetPooledTransactionLimit, PoolTransaction, PropagateKind,
     PropagatedTransactions, TransactionPool, ValidPoolTransaction,
 };
 use std::{
-    collections::{hash_map::Entry, HashMap},
+    collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
     num::NonZeroUsize,
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
+    time::Duration,
+};
+use tokio::{
+    sync::{mpsc, oneshot, oneshot::error::RecvError},
+    time::Interval,
 };
-use tokio::sync::{mpsc, oneshot, oneshot::error::RecvError};
 use tokio_stream::wrappers::{ReceiverStream, UnboundedReceiverStream};
 use tracing::{debug, trace};
 
-/// Cache limit of transactions to keep track of for a single peer.
+/// Default cache limit of transactions to keep track of for a single peer.
 const PEER_TRANSACTION_CACHE_LIMIT: usize = 1024 * 10;
 
+/// Default memory budget of the cache of transactions seen by a single peer (720KiB on 64-bit
+/// platforms).
+pub const DEFAULT_PEER_SEEN_TRANSACTIONS_CACHE_SIZE: usize =
+    PEER_TRANSACTION_CACHE_LIMIT * TimedLruCache::<H256>::ENTRY_SIZE;
+
+/// Default time after which a transaction seen by a peer is forgotten.
+pub const DEFAULT_PEER_SEEN_TRANSACTIONS_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Interval at which the metrics of the caches of transactions seen by peers are updated.
+const SEEN_CACHE_METRICS_INTERVAL: Duration = Duration::from_secs(10);
+
 /// Soft limit for NewPooledTransactions
 const NEW_POOLED_TRANSACTION_HASHES_SOFT_LIMIT: usize = 4096;
 
@@ -51,13 +67,86 @@ const MAX_FULL_TRANSACTIONS_PACKET_SIZE: usize = 100 * 1024;
 /// <https://github.com/ethereum/devp2p/blob/master/caps/eth.md#newpooledtransactionhashes-0x08>
 const GET_POOLED_TRANSACTION_SOFT_LIMIT_NUM_HASHES: usize = 256;
 
+/// Softlimit for the response size of a GetPooledTransactions message in bytes (2MB)
+const POOLED_TRANSACTIONS_RESPONSE_SOFT_LIMIT: usize = 2 * 1024 * 1024;
+
 /// Softlimit for the response size of a GetPooledTransactions message (2MB)
 const GET_POOLED_TRANSACTION_SOFT_LIMIT_SIZE: GetPooledTransactionLimit =
-    GetPooledTransactionLimit::SizeSoftLimit(2 * 1024 * 1024);
+    GetPooledTransactionLimit::SizeSoftLimit(POOLED_TRANSACTIONS_RESPONSE_SOFT_LIMIT);
+
+/// Maximum number of concurrent `GetPooledTransactions` requests to a single peer, further
+/// requests are queued until a response arrives.
+const MAX_INFLIGHT_POOLED_TRANSACTIONS_REQUESTS_PER_PEER: usize = 2;
+
+/// Maximum number of queued `GetPooledTransactions` requests to a single peer, further requests
+/// are dropped.
+const MAX_QUEUED_POOLED_TRANSACTIONS_REQUESTS_PER_PEER: usize = 16;
+
+/// Maximum announced size of a non-blob transaction that is fetched (128KB), larger transactions
+/// are rejected by the pool.
+const MAX_ANNOUNCED_TRANSACTION_SIZE: usize = 128 * 1024;
+
+/// Maximum announced size of a blob transaction that is fetched (1MB).
+const MAX_ANNOUNCED_BLOB_TRANSACTION_SIZE: usize = 1024 * 1024;
+
+/// Configuration for the [`TransactionsManager`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct TransactionsManagerConfig {
+    /// Memory budget in bytes for the cache of transactions seen by a single peer.
+    ///
+    /// Transactions in this cache are not propagated to the peer again and the peer is penalized
+    /// for sending them again. A small cache causes re-gossip of transactions the peer already
+    /// knows, a large one costs memory for every connected peer.
+    pub peer_seen_transactions_cache_size: usize,
+    /// How long a transaction seen by a peer is remembered.
+    ///
+    /// If `None`, transactions are only forgotten when the cache is full.
+    #[cfg_attr(feature = "serde", serde(with = "humantime_serde"))]
+    pub peer_seen_transactions_ttl: Option<Duration>,
+}
+
+impl TransactionsManagerConfig {
+    /// Sets the memory budget in bytes for the cache of transactions seen by a single peer.
+    pub fn with_peer_seen_transactions_cache_size(mut self, size: usize) -> Self {
+        self.peer_seen_transactions_cache_size = size;
+        self
+    }
+
+    /// Sets how long a transaction seen by a peer is remembered.
+    pub fn with_peer_seen_transactions_ttl(mut self, ttl: Option<Duration>) -> Self {
+        self.peer_seen_transactions_ttl = ttl;
+        self
+    }
+
+    /// Returns the maximum number of transactions tracked per peer, at least one.
+    pub fn peer_seen_transactions_limit(&self) -> NonZeroUsize {
+        let limit = self.peer_seen_transactions_cache_size / TimedLruCache::<H256>::ENTRY_SIZE;
+        NonZeroUsize::new(limit).unwrap_or(NonZeroUsize::MIN)
+    }
+
+    /// Creates a new cache for transactions seen by a peer.
+    fn new_peer_cache(&self) -> TimedLruCache<H256> {
+        TimedLruCache::new(self.peer_seen_transactions_limit(), self.peer_seen_transactions_ttl)
+    }
+}
+
+impl Default for TransactionsManagerConfig {
+    fn default() -> Self {
+        Self {
+            peer_seen_transactions_cache_size: DEFAULT_PEER_SEEN_TRANSACTIONS_CACHE_SIZE,
+            peer_seen_transactions_ttl: Some(DEFAULT_PEER_SEEN_TRANSACTIONS_TTL),
+        }
+    }
+}
 
 /// The future for inserting a function into the pool
 pub type PoolImportFuture = Pin<Box<dyn Future<Output = PoolResult<TxHash>> + Send + 'static>>;
 
+/// The future that resolves once the senders of a batch of received transactions are recovered.
+type SenderRecoveryFuture = Pin<Box<dyn Future<Output = RecoveredTransactions> + Send + 'static>>;
+
 /// Api to interact with [`TransactionsManager`] task.
 pub struct TransactionsHandle {
     /// Command channel to the [`TransactionsManager`]
@@ -110,6 +199,10 @@ pub struct TransactionsManager<Pool> {
     /// This way we can track incoming transactions and prevent multiple pool imports for the same
     /// transaction
     transactions_by_peers: HashMap<TxHash, Vec<PeerId>>,
+    /// Batches of received transactions whose senders are currently recovered.
+    ///
+    /// Recovery runs on the blocking pool, so that large batches don't stall this task.
+    sender_recoveries: FuturesUnordered<SenderRecoveryFuture>,
     /// Transactions that are currently imported into the `Pool`
     pool_imports: FuturesUnordered<PoolImportFuture>,
     /// All the connected peers.
@@ -122,8 +215,14 @@ pub struct TransactionsManager<Pool> {
     pending_transactions: ReceiverStream<TxHash>,
     /// Incoming events from the [`NetworkManager`](crate::NetworkManager).
     transaction_events: UnboundedMeteredReceiver<NetworkTransactionEvent>,
+    /// Configuration of the manager.
+    config: TransactionsManagerConfig,
+    /// Dynamic minimum fee checked before the signatures of received transactions are recovered.
+    fee_floor: Option<Arc<DynamicFeeFloor>>,
     /// TransactionsManager metrics
     metrics: TransactionsManagerMetrics,
+    /// Interval at which the metrics of the seen transactions caches are updated.
+    seen_cache_metrics_interval: Interval,
 }
 
 impl<Pool: TransactionPool> TransactionsManager<Pool> {
@@ -134,6 +233,18 @@ impl<Pool: TransactionPool> TransactionsManager<Pool> {
         network: NetworkHandle,
         pool: Pool,
         from_network: mpsc::UnboundedReceiver<NetworkTransactionEvent>,
+    ) -> Self {
+        Self::with_config(network, pool, from_network, Default::default())
+    }
+
+    /// Sets up a new instance with the given [`TransactionsManagerConfig`].
+    ///
+    /// Note: This expects an existing [`NetworkManager`](crate::NetworkManager) instance.
+    pub fn with_config(
+        network: NetworkHandle,
+        pool: Pool,
+        from_network: mpsc::UnboundedReceiver<NetworkTransactionEvent>,
+        config: TransactionsManagerConfig,
     ) -> Self {
         let network_events = network.event_listener();
         let (command_tx, command_rx) = mpsc::unbounded_channel();
@@ -148,6 +259,7 @@ impl<Pool: TransactionPool> TransactionsManager<Pool> {
             network_events,
             inflight_requests: Default::default(),
             transactions_by_peers: Default::default(),
+            sender_recoveries: Default::default(),
             pool_imports: Default::default(),
             peers: Default::default(),
             command_tx,
@@ -157,9 +269,20 @@ impl<Pool: TransactionPool> TransactionsManager<Pool> {
                 from_network,
                 NETWORK_POOL_TRANSACTIONS_SCOPE,
             ),
+            config,
+            fee_floor: None,
             metrics: Default::default(),
+            seen_cache_metrics_interval: tokio::time::interval(SEEN_CACHE_METRICS_INTERVAL),
         }
     }
+
+    /// Sets the [DynamicFeeFloor] of the pool's validator.
+    ///
+    /// Received transactions below the floor are dropped before their signature is recovered.
+    pub fn with_fee_floor(mut self, fee_floor: Arc<DynamicFeeFloor>) -> Self {
+        self.fee_floor = Some(fee_floor);
+        self
+    }
 }
 
 // === impl TransactionsManager ===
@@ -183,6 +306,21 @@ where
         self.metrics.inflight_transaction_requests.set(self.inflight_requests.len() as f64);
     }
 
+    /// Updates the metrics of the caches of transactions seen by peers.
+    fn update_seen_cache_metrics(&mut self) {
+        let mut entries = 0;
+        let mut memory = 0;
+        let mut evicted = 0;
+        for peer in self.peers.values_mut() {
+            entries += peer.transactions.len();
+            memory += peer.transactions.memory_usage();
+            evicted += peer.transactions.take_evicted();
+        }
+        self.metrics.seen_transactions_cache_entries.set(entries as f64);
+        self.metrics.seen_transactions_cache_memory.set(memory as f64);
+        self.metrics.seen_transactions_cache_evictions.increment(evicted);
+    }
+
     /// Request handler for an incoming request for transactions
     fn on_get_pooled_transactions(
         &mut self,
@@ -325,14 +463,24 @@ where
         let mut num_already_seen = 0;
 
         if let Some(peer) = self.peers.get_mut(&peer_id) {
-            let mut hashes = msg.into_hashes();
+            let mut announced = AnnouncedTransaction::from_announcement(msg);
             // keep track of the transactions the peer knows
-            for tx in hashes.iter().copied() {
-                if !peer.transactions.insert(tx) {
+            for tx in announced.iter() {
+                if !peer.transactions.insert(tx.hash) {
                     num_already_seen += 1;
                 }
             }
 
+            // skip transactions that would be rejected anyway, before requesting them
+            let num_announced = announced.len();
+            announced.retain(AnnouncedTransaction::is_fetchable);
+            let num_skipped = num_announced - announced.len();
+            if num_skipped > 0 {
+                self.metrics.skipped_announced_transactions.increment(num_skipped as u64);
+                trace!(target: "net::tx", num_skipped, ?peer_id, "Skipping announced transactions");
+            }
+
+            let mut hashes = announced.iter().map(|tx| tx.hash).collect::<Vec<_>>();
             self.pool.retain_unknown(&mut hashes);
 
             if hashes.is_empty() {
@@ -340,23 +488,24 @@ where
                 return
             }
 
-            // enforce recommended soft limit, however the peer may enforce an arbitrary limit on
-            // the response (2MB)
-            hashes.truncate(GET_POOLED_TRANSACTION_SOFT_LIMIT_NUM_HASHES);
+            let unknown = hashes.into_iter().collect::<HashSet<_>>();
+            announced.retain(|tx| unknown.contains(&tx.hash));
 
-            // request the missing transactions
-            let (response, rx) = oneshot::channel();
-            let req = PeerRequest::GetPooledTransactions {
-                request: GetPooledTransactions(hashes),
-                response,
-            };
+            let mut requests = pooled_transactions_requests(announced);
+            if peer.version < EthVersion::Eth68 {
+                // without announced sizes, we only request the recommended soft limit of hashes,
+                // however the peer may enforce an arbitrary limit on the response (2MB)
+                requests.truncate(1);
+            }
 
-            if peer.request_tx.try_send(req).is_ok() {
-                self.inflight_requests.push(GetPooledTxRequestFut::new(peer_id, rx))
-            } else {
-                // peer channel is saturated, drop the request
+            // request the missing transactions, exceeding requests are queued
+            let num_dropped = peer.queue_requests(requests);
+            if num_dropped > 0 {
+                trace!(target: "net::tx", num_dropped, ?peer_id, "Dropping queued transaction requests");
+            }
+            if !peer.send_queued_requests(peer_id, &mut self.inflight_requests) {
+                // peer channel is saturated, the requests stay queued
                 self.metrics.egress_peer_channel_full.increment(1);
-                return
             }
 
             if num_already_seen > 0 {
@@ -421,12 +570,12 @@ where
                 self.peers.insert(
                     peer_id,
                     Peer {
-                        transactions: LruCache::new(
-                            NonZeroUsize::new(PEER_TRANSACTION_CACHE_LIMIT).unwrap(),
-                        ),
+                        transactions: self.config.new_peer_cache(),
                         request_tx: messages,
                         version,
                         client_version,
+                        inflight_requests: 0,
+                        queued_requests: VecDeque::new(),
                     },
                 );
 
@@ -459,6 +608,9 @@ where
     }
 
     /// Starts the import process for the given transactions.
+    ///
+    /// The senders of the transactions are recovered on the blocking pool, the transactions are
+    /// imported once the recovery completes, see [Self::on_recovered_transactions].
     fn import_transactions(
         &mut self,
         peer_id: PeerId,
@@ -470,18 +622,60 @@ where
             return
         }
 
+        if !self.peers.contains_key(&peer_id) {
+            return
+        }
+
+        // drop transactions below the fee floor before spending any work on them
+        let transactions = transactions
+            .into_iter()
+            .filter(|tx| match &self.fee_floor {
+                Some(fee_floor) if !fee_floor.precheck(tx.priority_fee_or_price()) => {
+                    self.metrics.transactions_below_fee_floor.increment(1);
+                    false
+                }
+                _ => true,
+            })
+            .collect::<Vec<_>>();
+        if transactions.is_empty() {
+            return
+        }
+
+        // recover the senders of the whole batch at once, which is done in parallel for large
+        // batches
+        let (tx, rx) = oneshot::channel();
+        tokio::task::spawn_blocking(move || {
+            let _ = tx.send(PooledTransactionsElement::try_recover_batch(transactions));
+        });
+        self.sender_recoveries.push(Box::pin(async move {
+            // the recovery task only fails to respond if it panicked, in which case the batch is
+            // dropped
+            let transactions = rx.await.unwrap_or_default();
+            RecoveredTransactions { peer_id, source, transactions }
+        }));
+    }
+
+    /// Imports a batch of transactions whose senders were recovered.
+    fn on_recovered_transactions(&mut self, recovered: RecoveredTransactions) {
+        let RecoveredTransactions { peer_id, source, transactions } = recovered;
+
+        // the node may have started syncing while the senders were recovered
+        if self.network.is_initially_syncing() {
+            return
+        }
+
         // tracks the quality of the given transactions
-        let mut has_bad_transactions = false;
+        let mut num_bad_transactions = 0;
         let mut num_already_seen = 0;
 
         if let Some(peer) = self.peers.get_mut(&peer_id) {
             for tx in transactions {
-                // recover transaction
-                let tx = if let Ok(tx) = tx.try_into_ecrecovered() {
-                    tx
-                } else {
-                    has_bad_transactions = true;
-                    continue
+                let tx = match tx {
+                    Ok(tx) => tx,
+                    Err(_) => {
+                        num_bad_transactions += 1;
+                        continue
+                    }
                 };
 
                 // track that the peer knows this transaction, but only if this is a new broadcast.
@@ -519,7 +713,12 @@ where
             }
         }
 
-        if has_bad_transactions || num_already_seen > 0 {
+        if num_bad_transactions > 0 {
+            // the peer is responsible for relaying transactions with invalid signatures
+            debug!(target: "net::tx", num_txs=%num_bad_transactions, ?peer_id, "Peer sent transactions with invalid signatures");
+            self.metrics.invalid_transaction_signatures.increment(num_bad_transactions);
+            self.report_peer(peer_id, ReputationChangeKind::BadTransactions);
+        } else if num_already_seen > 0 {
             self.report_already_seen(peer_id);
         }
     }
@@ -539,6 +738,8 @@ where
                 return
             }
             RequestError::BadResponse => ReputationChangeKind::BadTransactions,
+            // only used for requests received from peers
+            RequestError::RateLimited => return,
         };
         self.report_peer(peer_id, kind);
     }
@@ -597,6 +798,14 @@ where
         while let Poll::Ready(Some(GetPooledTxResponse { peer_id, result })) =
             this.inflight_requests.poll_next_unpin(cx)
         {
+            // the request slot is free, send the next queued request to the peer
+            if let Some(peer) = this.peers.get_mut(&peer_id) {
+                peer.inflight_requests = peer.inflight_requests.saturating_sub(1);
+                if !peer.send_queued_requests(peer_id, &mut this.inflight_requests) {
+                    this.metrics.egress_peer_channel_full.increment(1);
+                }
+            }
+
             match result {
                 Ok(Ok(txs)) => {
                     this.import_transactions(peer_id, txs.0, TransactionSource::Response)
@@ -612,6 +821,12 @@ where
         }
 
         this.update_request_metrics();
+
+        // Import all batches whose senders were recovered
+        while let Poll::Ready(Some(recovered)) = this.sender_recoveries.poll_next_unpin(cx) {
+            this.on_recovered_transactions(recovered);
+        }
+
         this.update_import_metrics();
 
         // Advance all imports
@@ -647,6 +862,10 @@ where
             this.on_new_transactions(new_txs);
         }
 
+        if this.seen_cache_metrics_interval.poll_tick(cx).is_ready() {
+            this.update_seen_cache_metrics();
+        }
+
         // all channels are fully drained and import futures pending
 
         Poll::Pending
@@ -753,6 +972,16 @@ impl PooledTransactionsHashesBuilder {
     }
 }
 
+/// A batch of received transactions whose senders were recovered.
+struct RecoveredTransactions {
+    /// The peer that sent the transactions.
+    peer_id: PeerId,
+    /// How the transactions were received.
+    source: TransactionSource,
+    /// The recovered transactions, or the transactions whose signature is invalid.
+    transactions: Vec<Result<PooledTransactionsElementEcRecovered, PooledTransactionsElement>>,
+}
+
 /// How we received the transactions.
 enum TransactionSource {
     /// Transactions were broadcast to us via [`Transactions`] message.
@@ -770,6 +999,79 @@ impl TransactionSource {
     }
 }
 
+/// A transaction announced by a peer via `NewPooledTransactionHashes`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct AnnouncedTransaction {
+    hash: TxHash,
+    /// The announced type, only available for eth/68 peers.
+    ty: Option<u8>,
+    /// The announced size, only available for eth/68 peers.
+    size: Option<usize>,
+}
+
+impl AnnouncedTransaction {
+    /// Returns all transactions of the announcement, in the order in which they should be fetched.
+    ///
+    /// Blob transactions are fetched after all other transactions, since they are larger and
+    /// can't be included in a block without their sidecar.
+    fn from_announcement(msg: NewPooledTransactionHashes) -> Vec<Self> {
+        let mut announced: Vec<Self> = match msg {
+            NewPooledTransactionHashes::Eth66(msg) => {
+                msg.0.into_iter().map(|hash| Self { hash, ty: None, size: None }).collect()
+            }
+            NewPooledTransactionHashes::Eth68(msg) => msg
+                .hashes
+                .into_iter()
+                .zip(msg.types)
+                .zip(msg.sizes)
+                .map(|((hash, ty), size)| Self { hash, ty: Some(ty), size: Some(size) })
+                .collect(),
+        };
+        // stable, so the announced order is kept otherwise
+        announced.sort_by_key(|tx| tx.ty == Some(EIP4844_TX_TYPE_ID));
+        announced
+    }
+
+    /// Returns `false` if the announced type is not supported or the announced size exceeds the
+    /// size limit of its type.
+    fn is_fetchable(&self) -> bool {
+        let (Some(ty), Some(size)) = (self.ty, self.size) else { return true };
+        match ty {
+            LEGACY_TX_TYPE_ID | EIP2930_TX_TYPE_ID | EIP1559_TX_TYPE_ID => {
+                size <= MAX_ANNOUNCED_TRANSACTION_SIZE
+            }
+            EIP4844_TX_TYPE_ID => size <= MAX_ANNOUNCED_BLOB_TRANSACTION_SIZE,
+            _ => false,
+        }
+    }
+}
+
+/// Splits the announced transactions into the hashes of `GetPooledTransactions` requests.
+///
+/// Every request stays within the recommended soft limit on the number of hashes and, if the
+/// sizes were announced, on the size of the response.
+fn pooled_transactions_requests(announced: Vec<AnnouncedTransaction>) -> Vec<Vec<TxHash>> {
+    let mut requests = Vec::new();
+    let mut hashes = Vec::new();
+    let mut size = 0;
+    for tx in announced {
+        let tx_size = tx.size.unwrap_or_default();
+        if !hashes.is_empty() &&
+            (hashes.len() == GET_POOLED_TRANSACTION_SOFT_LIMIT_NUM_HASHES ||
+                size + tx_size > POOLED_TRANSACTIONS_RESPONSE_SOFT_LIMIT)
+        {
+            requests.push(std::mem::take(&mut hashes));
+            size = 0;
+        }
+        hashes.push(tx.hash);
+        size += tx_size;
+    }
+    if !hashes.is_empty() {
+        requests.push(hashes);
+    }
+    requests
+}
+
 /// An inflight request for `PooledTransactions` from a peer
 struct GetPooledTxRequest {
     peer_id: PeerId,
@@ -817,7 +1119,7 @@ impl Future for GetPooledTxRequestFut {
 /// Tracks a single peer
 struct Peer {
     /// Keeps track of transactions that we know the peer has seen.
-    transactions: LruCache<H256>,
+    transactions: TimedLruCache<H256>,
     /// A communication channel directly to the peer's session task.
     request_tx: PeerRequestSender,
     /// negotiated version of the session.
@@ -825,6 +1127,55 @@ struct Peer {
     /// The peer's client version.
     #[allow(unused)]
     client_version: Arc<String>,
+    /// The number of `GetPooledTransactions` requests currently sent to the peer.
+    inflight_requests: usize,
+    /// The hashes of `GetPooledTransactions` requests waiting for a free request slot.
+    queued_requests: VecDeque<Vec<TxHash>>,
+}
+
+impl Peer {
+    /// Queues the given `GetPooledTransactions` requests.
+    ///
+    /// Returns the number of requests that were dropped because the queue is full.
+    fn queue_requests(&mut self, requests: Vec<Vec<TxHash>>) -> usize {
+        let num_requests = requests.len();
+        let capacity = MAX_QUEUED_POOLED_TRANSACTIONS_REQUESTS_PER_PEER
+            .saturating_sub(self.queued_requests.len());
+        self.queued_requests.extend(requests.into_iter().take(capacity));
+        num_requests.saturating_sub(capacity)
+    }
+
+    /// Sends queued requests to the peer until the inflight limit is reached.
+    ///
+    /// Returns `false` if the peer's channel is saturated.
+    fn send_queued_requests(
+        &mut self,
+        peer_id: PeerId,
+        inflight_requests: &mut FuturesUnordered<GetPooledTxRequestFut>,
+    ) -> bool {
+        while self.inflight_requests < MAX_INFLIGHT_POOLED_TRANSACTIONS_REQUESTS_PER_PEER {
+            let Some(hashes) = self.queued_requests.pop_front() else { break };
+            let (response, rx) = oneshot::channel();
+            let req = PeerRequest::GetPooledTransactions {
+                request: GetPooledTransactions(hashes),
+                response,
+            };
+            match self.request_tx.try_send(req) {
+                Ok(()) => {
+                    self.inflight_requests += 1;
+                    inflight_requests.push(GetPooledTxRequestFut::new(peer_id, rx));
+                }
+                Err(err) => {
+                    // keep the request queued until the channel has capacity again
+                    if let PeerRequest::GetPooledTransactions { request, .. } = err.into_inner() {
+                        self.queued_requests.push_front(request.0);
+                    }
+                    return false
+                }
+            }
+        }
+        true
+    }
 }
 
 /// Commands to send to the [`TransactionsManager`](crate::transactions::TransactionsManager)
@@ -862,6 +1213,94 @@ mod tests {
     use secp256k1::SecretKey;
     use std::future::poll_fn;
 
+    #[test]
+    fn test_peer_seen_transactions_limit() {
+        let config = TransactionsManagerConfig::default();
+        assert_eq!(config.peer_seen_transactions_limit().get(), PEER_TRANSACTION_CACHE_LIMIT);
+
+        let config = config.with_peer_seen_transactions_cache_size(0);
+        assert_eq!(config.peer_seen_transactions_limit().get(), 1);
+    }
+
+    #[test]
+    fn test_announced_transactions_requests() {
+        let blob = H256::random();
+        let oversized = H256::random();
+        let unsupported = H256::random();
+        let small = (0..300).map(|_| H256::random()).collect::<Vec<_>>();
+        let large = (0..20).map(|_| H256::random()).collect::<Vec<_>>();
+
+        let mut msg = NewPooledTransactionHashes68::default();
+        let mut announce = |hash, ty, size| {
+            msg.hashes.push(hash);
+            msg.types.push(ty);
+            msg.sizes.push(size);
+        };
+        announce(blob, EIP4844_TX_TYPE_ID, 131_200);
+        announce(oversized, EIP1559_TX_TYPE_ID, 200 * 1024);
+        announce(unsupported, 0x7f, 100);
+        small.iter().for_each(|hash| announce(*hash, EIP1559_TX_TYPE_ID, 200));
+        large.iter().for_each(|hash| announce(*hash, LEGACY_TX_TYPE_ID, 120 * 1024));
+
+        let mut announced = AnnouncedTransaction::from_announcement(msg.into());
+        announced.retain(AnnouncedTransaction::is_fetchable);
+        // blob transactions are fetched last
+        assert_eq!(announced.len(), 321);
+        assert_eq!(announced.last().unwrap().hash, blob);
+
+        let requests = pooled_transactions_requests(announced);
+        // limited by the number of hashes
+        assert_eq!(requests[0], small[..256]);
+        // limited by the size of the response
+        assert_eq!(requests[1].len(), 44 + 16);
+        assert_eq!(requests[2].len(), 4 + 1);
+        assert_eq!(requests.len(), 3);
+        assert_eq!(*requests[2].last().unwrap(), blob);
+    }
+
+    #[test]
+    fn test_peer_queued_requests() {
+        let peer_id = PeerId::random();
+        let (tx, mut rx) = mpsc::channel(3);
+        let mut peer = Peer {
+            transactions: TransactionsManagerConfig::default().new_peer_cache(),
+            request_tx: PeerRequestSender::new(peer_id, tx),
+            version: EthVersion::Eth68,
+            client_version: Default::default(),
+            inflight_requests: 0,
+            queued_requests: VecDeque::new(),
+        };
+        let mut inflight = FuturesUnordered::new();
+
+        let requests = (0..20).map(|_| vec![H256::random()]).collect::<Vec<_>>();
+        let dropped = peer.queue_requests(requests.clone());
+        assert_eq!(dropped, 20 - MAX_QUEUED_POOLED_TRANSACTIONS_REQUESTS_PER_PEER);
+
+        // only up to the inflight limit is sent
+        assert!(peer.send_queued_requests(peer_id, &mut inflight));
+        assert_eq!(peer.inflight_requests, MAX_INFLIGHT_POOLED_TRANSACTIONS_REQUESTS_PER_PEER);
+        assert_eq!(inflight.len(), MAX_INFLIGHT_POOLED_TRANSACTIONS_REQUESTS_PER_PEER);
+        for expected in &requests[..MAX_INFLIGHT_POOLED_TRANSACTIONS_REQUESTS_PER_PEER] {
+            match rx.try_recv() {
+                Ok(PeerRequest::GetPooledTransactions { request, .. }) => {
+                    assert_eq!(request.0, *expected)
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        // a saturated channel keeps the request queued
+        let (tx, _rx) = mpsc::channel(1);
+        peer.request_tx = PeerRequestSender::new(peer_id, tx);
+        peer.inflight_requests = 0;
+        assert!(!peer.send_queued_requests(peer_id, &mut inflight));
+        assert_eq!(peer.inflight_requests, 1);
+        assert_eq!(
+            peer.queued_requests.front(),
+            Some(&requests[MAX_INFLIGHT_POOLED_TRANSACTIONS_REQUESTS_PER_PEER + 1])
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     #[cfg_attr(not(feature = "geth-tests"), ignore)]
     async fn test_ignored_tx_broadcasts_while_initially_syncing() {
@@ -1022,9 +1461,15 @@ mod tests {
             peer_id: *handle1.peer_id(),
             msg: Transactions(vec![signed_tx.clone()]),
         });
+        // senders are recovered on the blocking pool, advance the manager until the transaction is
+        // imported
         poll_fn(|cx| {
             let _ = transactions.poll_unpin(cx);
-            Poll::Ready(())
+            if pool.is_empty() {
+                Poll::Pending
+            } else {
+                Poll::Ready(())
+            }
         })
         .await;
         assert!(!NetworkInfo::is_initially_syncing(&network_handle));
@@ -1105,6 +1550,10 @@ mod tests {
             peer_id: *handle1.peer_id(),
             msg: Transactions(vec![signed_tx.clone()]),
         });
+
+        // wait until the senders are recovered
+        let recovered = transactions.sender_recoveries.next().await.unwrap();
+        transactions.on_recovered_transactions(recovered);
         assert_eq!(
             *handle1.peer_id(),
             transactions.transactions_by_peers.get(&signed_tx.hash()).unwrap()[0]
@@ -1113,7 +1562,11 @@ mod tests {
         // advance the transaction manager future
         poll_fn(|cx| {
             let _ = transactions.poll_unpin(cx);
-            Poll::Ready(())
+            if pool.is_empty() {
+                Poll::Pending
+            } else {
+                Poll::Ready(())
+            }
         })
         .await;
 
@@ -17,7 +17,7 @@ use reth_network::{
     NetworkConfigBuilder, NetworkEvent, NetworkManager, PeersConfig,
 };
 use reth_network_api::{NetworkInfo, Peers, PeersInfo};
-use reth_primitives::{mainnet_nodes, HeadersDirection, NodeRecord, PeerId};
+use reth_primitives::{mainnet_nodes, Head, HeadersDirection, NodeRecord, PeerId, MAINNET};
 use reth_provider::test_utils::NoopProvider;
 use reth_transaction_pool::test_utils::testing_pool;
 use secp256k1::SecretKey;
@@ -65,7 +65,7 @@ async fn test_establish_connections() {
                 NetworkEvent::PeerAdded(peer_id) => {
                     assert!(expected_peers.remove(&peer_id))
                 }
-                NetworkEvent::PeerRemoved(_) => {
+                NetworkEvent::PeerRemoved(_) | NetworkEvent::ForkIdChanged { .. } => {
                     panic!("unexpected event")
                 }
             }
@@ -586,3 +586,34 @@ async fn test_disconnect_incoming_when_exceeded_incoming_connections() {
 
     assert_eq!(handle.num_connected_peers(), 0);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fork_id_changed_event() {
+    reth_tracing::init_test_tracing();
+    let net = Testnet::create(1).await;
+    let handle = net.handles().next().unwrap();
+    let _net = net.spawn();
+
+    let mut events = handle.event_listener();
+
+    // a head past the Shanghai timestamp
+    let head = Head {
+        number: 17_034_870,
+        timestamp: MAINNET.fork_timestamps.shanghai.unwrap(),
+        ..Default::default()
+    };
+    handle.update_status(head);
+
+    let event = tokio::time::timeout(Duration::from_secs(5), events.next()).await.unwrap();
+    match event {
+        Some(NetworkEvent::ForkIdChanged { past, current }) => {
+            assert_ne!(past, current);
+            assert_eq!(current, MAINNET.fork_id(&head));
+        }
+        ev => panic!("unexpected event {ev:?}"),
+    }
+
+    // no transition within the same fork
+    handle.update_status(Head { timestamp: head.timestamp + 12, ..head });
+    assert!(tokio::time::timeout(Duration::from_secs(1), events.next()).await.is_err());
+}
@@ -1,13 +1,22 @@
 //! Session tests
 
 use futures::StreamExt;
-use reth_eth_wire::{capability::Capability, EthVersion};
+use reth_eth_wire::{
+    capability::{Capability, Protocol, RawCapabilityMessage},
+    EthVersion, HelloMessage,
+};
 use reth_network::{
-    test_utils::{PeerConfig, Testnet},
-    NetworkEvent,
+    test_utils::{NetworkEventStream, PeerConfig, Testnet},
+    Direction, HelloHook, NetworkEvent, ProtocolConnection, ProtocolHandler, SessionsConfig,
 };
 use reth_network_api::{NetworkInfo, Peers};
+use reth_primitives::{bytes::Bytes, PeerId};
 use reth_provider::test_utils::NoopProvider;
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::mpsc;
 
 #[tokio::test(flavor = "multi_thread")]
 async fn test_session_established_with_highest_version() {
@@ -82,3 +91,125 @@ async fn test_session_established_with_different_capability() {
 
     handle.terminate().await;
 }
+
+/// Records all peer `Hello`s and advertises a custom client version on outbound connections.
+#[derive(Debug, Default, Clone)]
+struct RecordingHelloHook {
+    client_version: Option<String>,
+    peer_hellos: Arc<Mutex<Vec<(Direction, HelloMessage)>>>,
+}
+
+impl HelloHook for RecordingHelloHook {
+    fn on_outbound_hello(
+        &self,
+        _remote_addr: SocketAddr,
+        _remote_peer_id: PeerId,
+        hello: &mut HelloMessage,
+    ) {
+        if let Some(client_version) = &self.client_version {
+            hello.client_version = client_version.clone();
+        }
+    }
+
+    fn on_peer_hello(&self, _remote_addr: SocketAddr, direction: Direction, hello: &HelloMessage) {
+        self.peer_hellos.lock().unwrap().push((direction, hello.clone()));
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_session_hello_hooks() {
+    reth_tracing::init_test_tracing();
+
+    let mut net = Testnet::default();
+
+    let dialer_hook = RecordingHelloHook {
+        client_version: Some("reth/experiment".to_string()),
+        ..Default::default()
+    };
+    let listener_hook = RecordingHelloHook::default();
+    let p0 = PeerConfig::with_sessions_config(
+        NoopProvider::default(),
+        SessionsConfig::default().with_hello_hook(dialer_hook.clone()),
+    );
+    let p1 = PeerConfig::with_sessions_config(
+        NoopProvider::default(),
+        SessionsConfig::default().with_hello_hook(listener_hook.clone()),
+    );
+    net.extend_peer_with_config(vec![p0, p1]).await.unwrap();
+
+    let mut handles = net.handles();
+    let handle0 = handles.next().unwrap();
+    let handle1 = handles.next().unwrap();
+    drop(handles);
+
+    let handle = net.spawn();
+
+    let mut listener1 = NetworkEventStream::new(handle1.event_listener());
+    handle0.add_peer(*handle1.peer_id(), handle1.local_addr());
+    let peer_id = listener1.next_session_established().await.unwrap();
+    assert_eq!(peer_id, *handle0.peer_id());
+
+    // the listener sees the client version advertised for this connection
+    let peer = handle1.get_peer_by_id(*handle0.peer_id()).await.unwrap().unwrap();
+    assert_eq!(peer.client_version.as_str(), "reth/experiment");
+
+    let hellos = listener_hook.peer_hellos.lock().unwrap().clone();
+    assert_eq!(hellos.len(), 1);
+    assert_eq!(hellos[0].0, Direction::Incoming);
+    assert_eq!(hellos[0].1.id, *handle0.peer_id());
+
+    let hellos = dialer_hook.peer_hellos.lock().unwrap().clone();
+    assert_eq!(hellos.len(), 1);
+    assert_eq!(hellos[0].0, Direction::Outgoing(*handle1.peer_id()));
+    assert_eq!(hellos[0].1.id, *handle1.peer_id());
+
+    handle.terminate().await;
+}
+
+/// A subprotocol with a `ping` and a `pong` message that hands its connections to the test.
+#[derive(Debug)]
+struct PingProtocol {
+    connections: mpsc::UnboundedSender<ProtocolConnection>,
+}
+
+impl ProtocolHandler for PingProtocol {
+    fn protocol(&self) -> Protocol {
+        Protocol::new(Capability::new("ping".into(), 1), 2)
+    }
+
+    fn on_connection(&self, _peer_id: PeerId, _direction: Direction, conn: ProtocolConnection) {
+        let _ = self.connections.send(conn);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_session_custom_protocol() {
+    reth_tracing::init_test_tracing();
+
+    let mut net = Testnet::create(2).await;
+    let (tx0, mut connections0) = mpsc::unbounded_channel();
+    let (tx1, mut connections1) = mpsc::unbounded_channel();
+    net.peers_mut()[0].add_protocol(PingProtocol { connections: tx0 });
+    net.peers_mut()[1].add_protocol(PingProtocol { connections: tx1 });
+
+    let mut handles = net.handles();
+    let handle0 = handles.next().unwrap();
+    let handle1 = handles.next().unwrap();
+    drop(handles);
+
+    let handle = net.spawn();
+
+    handle0.add_peer(*handle1.peer_id(), handle1.local_addr());
+    let mut conn0 = connections0.recv().await.unwrap();
+    let mut conn1 = connections1.recv().await.unwrap();
+
+    let ping = RawCapabilityMessage { id: 0, payload: Bytes::from_static(b"ping") };
+    conn0.try_send(ping.clone()).unwrap();
+    assert_eq!(conn1.next().await.unwrap(), ping);
+
+    let pong = RawCapabilityMessage { id: 1, payload: Bytes::from_static(b"pong") };
+    conn1.try_send(pong.clone()).unwrap();
+    assert_eq!(conn0.next().await.unwrap(), pong);
+
+    handle.terminate().await;
+}
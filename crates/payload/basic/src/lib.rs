@@ -24,8 +24,9 @@ use reth_primitives::{
     bytes::{Bytes, BytesMut},
     calculate_excess_blob_gas,
     constants::{
-        eip4844::MAX_DATA_GAS_PER_BLOCK, BEACON_NONCE, EMPTY_RECEIPTS, EMPTY_TRANSACTIONS,
-        EMPTY_WITHDRAWALS, ETHEREUM_BLOCK_GAS_LIMIT, RETH_CLIENT_VERSION, SLOT_DURATION,
+        eip4844::{blob_fee, MAX_DATA_GAS_PER_BLOCK},
+        BEACON_NONCE, EMPTY_RECEIPTS, EMPTY_TRANSACTIONS, EMPTY_WITHDRAWALS,
+        ETHEREUM_BLOCK_GAS_LIMIT, RETH_CLIENT_VERSION, SLOT_DURATION,
     },
     proofs, Block, BlockNumberOrTag, ChainSpec, Header, IntoRecoveredTransaction, Receipt,
     SealedBlock, Withdrawal, EMPTY_OMMER_ROOT, H256, U256,
@@ -35,9 +36,11 @@ use reth_revm::{
     database::{State, SubState},
     env::tx_env_with_recovered,
     executor::{
-        commit_state_changes, increment_account_balance, post_block_withdrawals_balance_increments,
+        blob_transaction_max_cost, charge_blob_fee, commit_state_changes,
+        increment_account_balance, post_block_withdrawals_balance_increments, refund_blob_fee,
     },
     into_reth_log,
+    system_calls::pre_block_system_calls_with,
 };
 use reth_rlp::Encodable;
 use reth_tasks::TaskSpawner;
@@ -60,6 +63,12 @@ use tokio::{
 use tracing::{debug, trace};
 
 mod metrics;
+mod ordering;
+
+pub use ordering::{
+    BestFeeTransactions, BestPayloadTransactions, FilteredTransactions, OrderedPayloadBuilder,
+    PayloadTransactions,
+};
 
 /// The [`PayloadJobGenerator`] that creates [`BasicPayloadJob`]s.
 pub struct BasicPayloadJobGenerator<Client, Pool, Tasks, Builder = ()> {
@@ -515,13 +524,13 @@ impl Future for PendingPayload {
 ///
 /// If dropped, it will set the `cancelled` flag to true.
 #[derive(Default, Clone, Debug)]
-struct Cancelled(Arc<AtomicBool>);
+pub struct Cancelled(Arc<AtomicBool>);
 
 // === impl Cancelled ===
 
 impl Cancelled {
     /// Returns true if the job was cancelled.
-    fn is_cancelled(&self) -> bool {
+    pub fn is_cancelled(&self) -> bool {
         self.0.load(std::sync::atomic::Ordering::Relaxed)
     }
 }
@@ -534,19 +543,19 @@ impl Drop for Cancelled {
 
 /// Static config for how to build a payload.
 #[derive(Clone)]
-struct PayloadConfig {
+pub struct PayloadConfig {
     /// Pre-configured block environment.
-    initialized_block_env: BlockEnv,
+    pub initialized_block_env: BlockEnv,
     /// Configuration for the environment.
-    initialized_cfg: CfgEnv,
+    pub initialized_cfg: CfgEnv,
     /// The parent block.
-    parent_block: Arc<SealedBlock>,
+    pub parent_block: Arc<SealedBlock>,
     /// Block extra data.
-    extra_data: Bytes,
+    pub extra_data: Bytes,
     /// Requested attributes for the payload.
-    attributes: PayloadBuilderAttributes,
+    pub attributes: PayloadBuilderAttributes,
     /// The chain spec.
-    chain_spec: Arc<ChainSpec>,
+    pub chain_spec: Arc<ChainSpec>,
 }
 
 /// The possible outcomes of a payload building attempt.
@@ -576,12 +585,18 @@ pub enum BuildOutcome {
 /// building process. It holds references to the Ethereum client, transaction pool, cached reads,
 /// payload configuration, cancellation status, and the best payload achieved so far.
 pub struct BuildArguments<Pool, Client> {
-    client: Client,
-    pool: Pool,
-    cached_reads: CachedReads,
-    config: PayloadConfig,
-    cancel: Cancelled,
-    best_payload: Option<Arc<BuiltPayload>>,
+    /// How to interact with the chain.
+    pub client: Client,
+    /// The transaction pool.
+    pub pool: Pool,
+    /// Previously cached disk reads
+    pub cached_reads: CachedReads,
+    /// How to configure the payload.
+    pub config: PayloadConfig,
+    /// A marker that can be used to cancel the job.
+    pub cancel: Cancelled,
+    /// The best payload achieved so far.
+    pub best_payload: Option<Arc<BuiltPayload>>,
 }
 
 /// A trait for building payloads that encapsulate Ethereum transactions.
@@ -634,6 +649,22 @@ where
 fn default_payload_builder<Pool, Client>(
     args: BuildArguments<Pool, Client>,
 ) -> Result<BuildOutcome, PayloadBuilderError>
+where
+    Client: StateProviderFactory,
+    Pool: TransactionPool,
+{
+    let best_txs = BestFeeTransactions.best_transactions(&args.pool, &args.config);
+    build_payload_with_transactions(args, best_txs)
+}
+
+/// Constructs an Ethereum transaction payload that includes the given transactions.
+///
+/// Transactions are executed in the order of the given [BestPayloadTransactions], those that don't
+/// fit into the block or fail to execute are skipped.
+fn build_payload_with_transactions<Pool, Client>(
+    args: BuildArguments<Pool, Client>,
+    mut best_txs: BestPayloadTransactions<Pool>,
+) -> Result<BuildOutcome, PayloadBuilderError>
 where
     Client: StateProviderFactory,
     Pool: TransactionPool,
@@ -661,12 +692,24 @@ where
     let base_fee = initialized_block_env.basefee.to::<u64>();
 
     let mut executed_txs = Vec::new();
-    let mut best_txs = pool.best_transactions_with_base_fee(base_fee);
 
     let mut total_fees = U256::ZERO;
 
     let block_number = initialized_block_env.number.to::<u64>();
 
+    let excess_blob_gas = next_excess_blob_gas(&chain_spec, &parent_block, attributes.timestamp);
+    let blob_gas_price = excess_blob_gas.map(|excess| blob_fee(excess).to::<u128>());
+
+    commit_pre_block_system_calls(
+        &mut db,
+        &mut post_state,
+        &chain_spec,
+        block_number,
+        attributes.timestamp,
+        parent_block.hash,
+        attributes.parent_beacon_block_root,
+    )?;
+
     while let Some(pool_tx) = best_txs.next() {
         // ensure we still have capacity for this transaction
         if cumulative_gas_used + pool_tx.gas_limit() > block_gas_limit {
@@ -687,6 +730,7 @@ where
 
         // There's only limited amount of blob space available per block, so we need to check if the
         // EIP-4844 can still fit in the block
+        let mut tx_blob_fee = None;
         if let Some(blob_tx) = tx.transaction.as_eip4844() {
             let tx_blob_gas = blob_tx.blob_gas();
             if sum_blob_gas_used + tx_blob_gas > MAX_DATA_GAS_PER_BLOCK {
@@ -695,15 +739,33 @@ where
                 // the gas limit condition for regular transactions above.
                 best_txs.mark_invalid(&pool_tx);
                 continue
-            } else {
-                // add to the data gas if we're going to execute the transaction
-                sum_blob_gas_used += tx_blob_gas;
+            }
 
-                // if we've reached the max data gas per block, we can skip blob txs entirely
-                if sum_blob_gas_used == MAX_DATA_GAS_PER_BLOCK {
-                    best_txs.skip_blobs();
-                }
+            // the transaction must pay at least the blob gas price of the block
+            let blob_gas_price = blob_gas_price.unwrap_or_default();
+            if blob_tx.max_fee_per_blob_gas < blob_gas_price {
+                best_txs.mark_invalid(&pool_tx);
+                continue
             }
+
+            // the blob fee is charged before the transaction is executed and refunded if it can't
+            // be executed
+            let blob_fee = U256::from(tx_blob_gas) * U256::from(blob_gas_price);
+            let max_cost = blob_transaction_max_cost(&tx);
+            if !charge_blob_fee(
+                &mut db,
+                &mut post_state,
+                block_number,
+                tx.signer(),
+                max_cost,
+                blob_fee,
+            )? {
+                // the sender can't pay the blob fee
+                trace!(?tx, "skipping blob transaction that can't pay the blob fee");
+                best_txs.mark_invalid(&pool_tx);
+                continue
+            }
+            tx_blob_fee = Some(blob_fee);
         }
 
         // Configure the environment for the block.
@@ -719,6 +781,9 @@ where
         let ResultAndState { result, state } = match evm.transact() {
             Ok(res) => res,
             Err(err) => {
+                if let Some(blob_fee) = tx_blob_fee {
+                    refund_blob_fee(&mut db, &mut post_state, block_number, tx.signer(), blob_fee)?;
+                }
                 match err {
                     EVMError::Transaction(err) => {
                         if matches!(err, InvalidTransaction::NonceTooLow { .. }) {
@@ -740,6 +805,16 @@ where
             }
         };
 
+        if tx_blob_fee.is_some() {
+            // add to the data gas since the transaction is included
+            sum_blob_gas_used += tx.transaction.blob_gas_used().unwrap_or_default();
+
+            // if we've reached the max data gas per block, we can skip blob txs entirely
+            if sum_blob_gas_used == MAX_DATA_GAS_PER_BLOCK {
+                best_txs.skip_blobs();
+            }
+        }
+
         let gas_used = result.gas_used();
 
         // commit changes
@@ -794,26 +869,14 @@ where
 
     // initialize empty blob sidecars at first. If cancun is active then this will
     let mut blob_sidecars = Vec::new();
-    let mut excess_blob_gas = None;
     let mut blob_gas_used = None;
 
-    // only determine cancun fields when active
-    if chain_spec.is_cancun_activated_at_timestamp(attributes.timestamp) {
+    if excess_blob_gas.is_some() {
         // grab the blob sidecars from the executed txs
         blob_sidecars = pool.get_all_blobs_exact(
             executed_txs.iter().filter(|tx| tx.is_eip4844()).map(|tx| tx.hash).collect(),
         )?;
 
-        excess_blob_gas = if chain_spec.is_cancun_activated_at_timestamp(parent_block.timestamp) {
-            let parent_excess_blob_gas = parent_block.excess_blob_gas.unwrap_or_default();
-            let parent_blob_gas_used = parent_block.blob_gas_used.unwrap_or_default();
-            Some(calculate_excess_blob_gas(parent_excess_blob_gas, parent_blob_gas_used))
-        } else {
-            // for the first post-fork block, both parent.blob_gas_used and parent.excess_blob_gas
-            // are evaluated as 0
-            Some(calculate_excess_blob_gas(0, 0))
-        };
-
         blob_gas_used = Some(sum_blob_gas_used);
     }
 
@@ -881,6 +944,16 @@ where
     let block_number = initialized_block_env.number.to::<u64>();
     let block_gas_limit: u64 = initialized_block_env.gas_limit.try_into().unwrap_or(u64::MAX);
 
+    commit_pre_block_system_calls(
+        &mut db,
+        &mut post_state,
+        &chain_spec,
+        block_number,
+        attributes.timestamp,
+        parent_block.hash,
+        attributes.parent_beacon_block_root,
+    )?;
+
     let WithdrawalsOutcome { withdrawals_root, withdrawals } = commit_withdrawals(
         &mut db,
         &mut post_state,
@@ -893,6 +966,8 @@ where
     // calculate the state root
     let state_root = db.db.0.state_root(post_state)?;
 
+    let excess_blob_gas = next_excess_blob_gas(&chain_spec, &parent_block, attributes.timestamp);
+
     let header = Header {
         parent_hash: parent_block.hash,
         ommers_hash: EMPTY_OMMER_ROOT,
@@ -910,8 +985,8 @@ where
         gas_limit: block_gas_limit,
         difficulty: U256::ZERO,
         gas_used: 0,
-        blob_gas_used: None,
-        excess_blob_gas: None,
+        blob_gas_used: excess_blob_gas.map(|_| 0),
+        excess_blob_gas,
         extra_data: extra_data.into(),
         parent_beacon_block_root: attributes.parent_beacon_block_root,
     };
@@ -922,6 +997,54 @@ where
     Ok(BuiltPayload::new(attributes.id, sealed_block, U256::ZERO))
 }
 
+/// Returns the excess blob gas of a block on top of the parent block, if Cancun is active at the
+/// timestamp of the block.
+fn next_excess_blob_gas(
+    chain_spec: &ChainSpec,
+    parent_block: &SealedBlock,
+    timestamp: u64,
+) -> Option<u64> {
+    if !chain_spec.is_cancun_activated_at_timestamp(timestamp) {
+        return None
+    }
+
+    if chain_spec.is_cancun_activated_at_timestamp(parent_block.timestamp) {
+        let parent_excess_blob_gas = parent_block.excess_blob_gas.unwrap_or_default();
+        let parent_blob_gas_used = parent_block.blob_gas_used.unwrap_or_default();
+        Some(calculate_excess_blob_gas(parent_excess_blob_gas, parent_blob_gas_used))
+    } else {
+        // for the first post-fork block, both parent.blob_gas_used and parent.excess_blob_gas
+        // are evaluated as 0
+        Some(calculate_excess_blob_gas(0, 0))
+    }
+}
+
+/// Applies the pre-block system calls and commits them to the _runtime_ Database and PostState.
+#[allow(clippy::too_many_arguments)]
+fn commit_pre_block_system_calls<DB>(
+    db: &mut CacheDB<DB>,
+    post_state: &mut PostState,
+    chain_spec: &ChainSpec,
+    block_number: u64,
+    timestamp: u64,
+    parent_hash: H256,
+    parent_beacon_block_root: Option<H256>,
+) -> Result<(), <DB as DatabaseRef>::Error>
+where
+    DB: DatabaseRef,
+{
+    let changes = pre_block_system_calls_with(
+        db,
+        chain_spec,
+        block_number,
+        timestamp,
+        parent_hash,
+        parent_beacon_block_root,
+    )?;
+    commit_state_changes(db, post_state, block_number, changes, true);
+    Ok(())
+}
+
 /// Represents the outcome of committing withdrawals to the runtime database and post state.
 /// Pre-shanghai these are `None` values.
 struct WithdrawalsOutcome {
@@ -0,0 +1,248 @@
+//! Extension point for the transactions a payload includes and their order.
+
+use crate::{BuildArguments, BuildOutcome, PayloadBuilder, PayloadConfig};
+use reth_payload_builder::error::PayloadBuilderError;
+use reth_provider::StateProviderFactory;
+use reth_transaction_pool::{BestTransactions, TransactionPool, ValidPoolTransaction};
+use std::sync::Arc;
+
+/// The transactions a payload builder tries to include, in the order they should be included.
+pub type BestPayloadTransactions<Pool> = Box<
+    dyn BestTransactions<Item = Arc<ValidPoolTransaction<<Pool as TransactionPool>::Transaction>>>,
+>;
+
+/// Determines which transactions are included in a payload and in which order.
+///
+/// The payload builder executes the returned transactions in order and includes those that fit
+/// into the block. Transactions that can't be included are marked as invalid via
+/// [BestTransactions::mark_invalid], which must also exclude all transactions that depend on them.
+///
+/// This can be used to implement custom ordering, to merge bundles into the pool's transactions or
+/// to enforce inclusion policies, while reusing the default execution and sealing logic, see
+/// [OrderedPayloadBuilder].
+pub trait PayloadTransactions<Pool: TransactionPool>: Send + Sync + Clone {
+    /// Returns the transactions to include in the payload with the given config.
+    fn best_transactions(
+        &self,
+        pool: &Pool,
+        config: &PayloadConfig,
+    ) -> BestPayloadTransactions<Pool>;
+}
+
+/// Includes the transactions of the pool ordered by their priority, which is the tip for the
+/// default pool.
+///
+/// This is the ordering of the default payload builder.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BestFeeTransactions;
+
+impl<Pool: TransactionPool> PayloadTransactions<Pool> for BestFeeTransactions {
+    fn best_transactions(
+        &self,
+        pool: &Pool,
+        config: &PayloadConfig,
+    ) -> BestPayloadTransactions<Pool> {
+        pool.best_transactions_with_base_fee(config.initialized_block_env.basefee.to::<u64>())
+    }
+}
+
+/// Excludes all transactions rejected by the filter, and the transactions that depend on them,
+/// from the transactions of the wrapped [PayloadTransactions].
+#[derive(Debug, Clone)]
+pub struct FilteredTransactions<T, F> {
+    inner: T,
+    filter: F,
+}
+
+impl<T, F> FilteredTransactions<T, F> {
+    /// Creates a new filter over the given transactions.
+    ///
+    /// Only transactions for which the filter returns `true` are included.
+    pub fn new(inner: T, filter: F) -> Self {
+        Self { inner, filter }
+    }
+}
+
+impl<Pool, T, F> PayloadTransactions<Pool> for FilteredTransactions<T, F>
+where
+    Pool: TransactionPool,
+    T: PayloadTransactions<Pool>,
+    F: Fn(&ValidPoolTransaction<Pool::Transaction>) -> bool + Send + Sync + Clone + 'static,
+{
+    fn best_transactions(
+        &self,
+        pool: &Pool,
+        config: &PayloadConfig,
+    ) -> BestPayloadTransactions<Pool> {
+        Box::new(FilterBestTransactions {
+            inner: self.inner.best_transactions(pool, config),
+            filter: self.filter.clone(),
+        })
+    }
+}
+
+/// A [BestTransactions] iterator that skips the transactions rejected by the filter.
+struct FilterBestTransactions<I, F> {
+    inner: I,
+    filter: F,
+}
+
+impl<I, F, T> Iterator for FilterBestTransactions<I, F>
+where
+    I: BestTransactions<Item = Arc<ValidPoolTransaction<T>>>,
+    F: Fn(&ValidPoolTransaction<T>) -> bool,
+{
+    type Item = Arc<ValidPoolTransaction<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let tx = self.inner.next()?;
+            if (self.filter)(&tx) {
+                return Some(tx)
+            }
+            // descendants of excluded transactions can't be included either
+            self.inner.mark_invalid(&tx);
+        }
+    }
+}
+
+impl<I, F, T> BestTransactions for FilterBestTransactions<I, F>
+where
+    I: BestTransactions<Item = Arc<ValidPoolTransaction<T>>>,
+    F: Fn(&ValidPoolTransaction<T>) -> bool + Send,
+{
+    fn mark_invalid(&mut self, transaction: &Self::Item) {
+        self.inner.mark_invalid(transaction)
+    }
+
+    fn no_updates(&mut self) {
+        self.inner.no_updates()
+    }
+
+    fn skip_blobs(&mut self) {
+        self.inner.skip_blobs()
+    }
+
+    fn set_skip_blobs(&mut self, skip_blobs: bool) {
+        self.inner.set_skip_blobs(skip_blobs)
+    }
+}
+
+/// A [PayloadBuilder] that builds payloads like the default builder, but includes the transactions
+/// of the given [PayloadTransactions].
+///
+/// `OrderedPayloadBuilder::default()` behaves exactly like the default builder.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OrderedPayloadBuilder<T = BestFeeTransactions> {
+    transactions: T,
+}
+
+impl<T> OrderedPayloadBuilder<T> {
+    /// Creates a new builder that includes the given transactions.
+    pub fn new(transactions: T) -> Self {
+        Self { transactions }
+    }
+}
+
+impl<Pool, Client, T> PayloadBuilder<Pool, Client> for OrderedPayloadBuilder<T>
+where
+    Client: StateProviderFactory,
+    Pool: TransactionPool,
+    T: PayloadTransactions<Pool>,
+{
+    fn try_build(
+        &self,
+        args: BuildArguments<Pool, Client>,
+    ) -> Result<BuildOutcome, PayloadBuilderError> {
+        let best_txs = self.transactions.best_transactions(&args.pool, &args.config);
+        crate::build_payload_with_transactions(args, best_txs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::TxHash;
+    use reth_transaction_pool::test_utils::{MockTransaction, MockTransactionFactory, MockValidTx};
+    use std::collections::VecDeque;
+
+    /// Yields the given transactions in order and drops the descendants of invalid transactions.
+    #[derive(Default)]
+    struct TestBestTransactions {
+        txs: VecDeque<Arc<MockValidTx>>,
+        invalid: Vec<TxHash>,
+    }
+
+    impl Iterator for TestBestTransactions {
+        type Item = Arc<MockValidTx>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            self.txs.pop_front()
+        }
+    }
+
+    impl BestTransactions for TestBestTransactions {
+        fn mark_invalid(&mut self, transaction: &Self::Item) {
+            self.invalid.push(*transaction.hash());
+            self.txs.retain(|tx| {
+                tx.sender() != transaction.sender() || tx.nonce() < transaction.nonce()
+            });
+        }
+
+        fn no_updates(&mut self) {}
+
+        fn skip_blobs(&mut self) {}
+
+        fn set_skip_blobs(&mut self, _skip_blobs: bool) {}
+    }
+
+    fn hashes(txs: impl IntoIterator<Item = Arc<MockValidTx>>) -> Vec<TxHash> {
+        txs.into_iter().map(|tx| *tx.hash()).collect()
+    }
+
+    #[test]
+    fn filter_excludes_descendants() {
+        let mut factory = MockTransactionFactory::default();
+        let a0 = MockTransaction::eip1559();
+        let a1 = a0.next();
+        let a2 = a1.next();
+        let b0 = MockTransaction::eip1559();
+        let rejected = *a1.get_hash();
+
+        let txs = [a0.clone(), a1, b0.clone(), a2]
+            .into_iter()
+            .map(|tx| factory.validated_arc(tx))
+            .collect();
+        let mut best = FilterBestTransactions {
+            inner: TestBestTransactions { txs, invalid: Vec::new() },
+            filter: |tx: &MockValidTx| *tx.hash() != rejected,
+        };
+
+        let included = hashes(best.by_ref());
+        assert_eq!(included, vec![*a0.get_hash(), *b0.get_hash()]);
+        assert_eq!(best.inner.invalid, vec![rejected]);
+    }
+
+    #[test]
+    fn filter_forwards_invalid_transactions() {
+        let mut factory = MockTransactionFactory::default();
+        let a0 = MockTransaction::eip1559();
+        let a1 = a0.next();
+        let b0 = MockTransaction::eip1559();
+
+        let txs =
+            [a0.clone(), a1, b0.clone()].into_iter().map(|tx| factory.validated_arc(tx)).collect();
+        let mut best = FilterBestTransactions {
+            inner: TestBestTransactions { txs, invalid: Vec::new() },
+            filter: |_: &MockValidTx| true,
+        };
+
+        let first = best.next().unwrap();
+        assert_eq!(first.hash(), a0.get_hash());
+
+        // the payload builder couldn't include the transaction, so its descendants are skipped
+        best.mark_invalid(&first);
+        assert_eq!(hashes(best.by_ref()), vec![*b0.get_hash()]);
+        assert_eq!(best.inner.invalid, vec![*a0.get_hash()]);
+    }
+}
@@ -0,0 +1,23 @@
+use crate::BuiltPayload;
+use reth_primitives::H256;
+use reth_rpc_types::engine::PayloadId;
+use std::sync::Arc;
+
+/// Events emitted by the [PayloadBuilderService](crate::PayloadBuilderService).
+#[derive(Clone, Debug)]
+pub enum PayloadBuilderEvent {
+    /// A new payload job was started.
+    JobStarted {
+        /// The identifier of the payload.
+        id: PayloadId,
+        /// The parent block the payload is built on.
+        parent: H256,
+    },
+    /// Creating or running a payload job failed.
+    JobFailed {
+        /// The identifier of the payload.
+        id: PayloadId,
+    },
+    /// A payload was resolved, this is the payload that is returned to the consensus layer.
+    Resolved(Arc<BuiltPayload>),
+}
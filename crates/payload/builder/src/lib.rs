@@ -112,6 +112,7 @@
 
 pub mod database;
 pub mod error;
+mod events;
 mod metrics;
 mod payload;
 mod service;
@@ -120,6 +121,7 @@ mod traits;
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils;
 
+pub use events::PayloadBuilderEvent;
 pub use payload::{BuiltPayload, PayloadBuilderAttributes};
 pub use reth_rpc_types::engine::PayloadId;
 pub use service::{PayloadBuilderHandle, PayloadBuilderService, PayloadStore};
@@ -4,10 +4,12 @@
 //! Once a new payload is created, it is continuously updated.
 
 use crate::{
-    error::PayloadBuilderError, metrics::PayloadBuilderServiceMetrics, traits::PayloadJobGenerator,
-    BuiltPayload, KeepPayloadJobAlive, PayloadBuilderAttributes, PayloadJob,
+    error::PayloadBuilderError, events::PayloadBuilderEvent, metrics::PayloadBuilderServiceMetrics,
+    traits::PayloadJobGenerator, BuiltPayload, KeepPayloadJobAlive, PayloadBuilderAttributes,
+    PayloadJob,
 };
 use futures_util::{future::FutureExt, StreamExt};
+use reth_primitives::listener::EventListeners;
 use reth_rpc_types::engine::PayloadId;
 use std::{
     future::Future,
@@ -140,6 +142,13 @@ impl PayloadBuilderHandle {
     ) -> Result<PayloadId, PayloadBuilderError> {
         self.send_new_payload(attr).await?
     }
+
+    /// Creates a new payload builder event listener.
+    pub fn event_listener(&self) -> UnboundedReceiverStream<PayloadBuilderEvent> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let _ = self.to_service.send(PayloadServiceCommand::EventListener(tx));
+        UnboundedReceiverStream::new(rx)
+    }
 }
 
 /// A service that manages payload building tasks.
@@ -160,11 +169,15 @@ where
     /// All active payload jobs.
     payload_jobs: Vec<(Gen::Job, PayloadId)>,
     /// Copy of the sender half, so new [`PayloadBuilderHandle`] can be created on demand.
-    _service_tx: mpsc::UnboundedSender<PayloadServiceCommand>,
+    ///
+    /// Also used by resolved payload futures to report the resolved payload back to the service.
+    service_tx: mpsc::UnboundedSender<PayloadServiceCommand>,
     /// Receiver half of the command channel.
     command_rx: UnboundedReceiverStream<PayloadServiceCommand>,
     /// Metrics for the payload builder service
     metrics: PayloadBuilderServiceMetrics,
+    /// Listeners for payload builder events.
+    listeners: EventListeners<PayloadBuilderEvent>,
 }
 
 // === impl PayloadBuilderService ===
@@ -179,9 +192,10 @@ where
         let service = Self {
             generator,
             payload_jobs: Vec::new(),
-            _service_tx: service_tx.clone(),
+            service_tx: service_tx.clone(),
             command_rx: UnboundedReceiverStream::new(command_rx),
             metrics: Default::default(),
+            listeners: Default::default(),
         };
         let handle = PayloadBuilderHandle { to_service: service_tx };
         (service, handle)
@@ -222,6 +236,14 @@ where
             trace!(%id, "terminated resolved job");
         }
 
+        // report the resolved payload back to the service so listeners can be notified
+        let service_tx = self.service_tx.clone();
+        let fut = fut.inspect(move |res| {
+            if let Ok(payload) = res {
+                let _ = service_tx.send(PayloadServiceCommand::Resolved(Arc::clone(payload)));
+            }
+        });
+
         Some(Box::pin(fut))
     }
 }
@@ -253,6 +275,7 @@ where
                         warn!(?err, ?id, "Payload builder job failed; resolving payload");
                         this.metrics.inc_failed_jobs();
                         this.metrics.set_active_jobs(this.payload_jobs.len());
+                        this.listeners.notify(PayloadBuilderEvent::JobFailed { id });
                     }
                     Poll::Pending => {
                         // still pending, put it back
@@ -275,15 +298,19 @@ where
                             warn!(%id, parent = ?attr.parent, "Payload job already in progress, ignoring.");
                         } else {
                             // no job for this payload yet, create one
+                            let parent = attr.parent;
                             match this.generator.new_payload_job(attr) {
                                 Ok(job) => {
                                     this.metrics.inc_initiated_jobs();
                                     new_job = true;
                                     this.payload_jobs.push((job, id));
+                                    this.listeners
+                                        .notify(PayloadBuilderEvent::JobStarted { id, parent });
                                 }
                                 Err(err) => {
                                     this.metrics.inc_failed_jobs();
                                     warn!(?err, %id, "Failed to create payload builder job");
+                                    this.listeners.notify(PayloadBuilderEvent::JobFailed { id });
                                     res = Err(err);
                                 }
                             }
@@ -301,6 +328,12 @@ where
                     PayloadServiceCommand::Resolve(id, tx) => {
                         let _ = tx.send(this.resolve(id));
                     }
+                    PayloadServiceCommand::Resolved(payload) => {
+                        this.listeners.notify(PayloadBuilderEvent::Resolved(payload));
+                    }
+                    PayloadServiceCommand::EventListener(tx) => {
+                        this.listeners.push_listener(tx);
+                    }
                 }
             }
 
@@ -330,4 +363,8 @@ enum PayloadServiceCommand {
     ),
     /// Resolve the payload and return the payload
     Resolve(PayloadId, oneshot::Sender<Option<PayloadFuture>>),
+    /// A payload was resolved
+    Resolved(Arc<BuiltPayload>),
+    /// Add a new event listener
+    EventListener(mpsc::UnboundedSender<PayloadBuilderEvent>),
 }
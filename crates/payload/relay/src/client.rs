@@ -0,0 +1,110 @@
+use crate::{
+    types::{ProposerDuty, SignedBidSubmission},
+    RelayEncoding, RelayError,
+};
+use hyper::{
+    body, client::HttpConnector, header::CONTENT_TYPE, Body, Client, Method, Request, Uri,
+};
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+use reth_primitives::ssz::Encode;
+use std::{future::Future, time::Duration};
+
+/// The path of the proposer duties endpoint.
+const VALIDATORS_PATH: &str = "/relay/v1/builder/validators";
+
+/// The path of the block submission endpoint.
+const BLOCKS_PATH: &str = "/relay/v1/builder/blocks";
+
+/// A client for the builder endpoints of a relay.
+#[derive(Debug, Clone)]
+pub struct RelayClient {
+    /// The url of the relay, without a trailing slash.
+    url: String,
+    client: Client<HttpsConnector<HttpConnector>>,
+    timeout: Duration,
+}
+
+impl RelayClient {
+    /// Creates a new client for the relay at the given url.
+    pub fn new(url: &str, timeout: Duration) -> Result<Self, RelayError> {
+        let url = url.trim_end_matches('/').to_string();
+        let uri = url.parse::<Uri>().map_err(|_| RelayError::InvalidUrl(url.clone()))?;
+        if uri.scheme().is_none() || uri.host().is_none() {
+            return Err(RelayError::InvalidUrl(url))
+        }
+
+        let connector =
+            HttpsConnectorBuilder::new().with_native_roots().https_or_http().enable_http1().build();
+        Ok(Self { url, client: Client::builder().build(connector), timeout })
+    }
+
+    /// Returns the url of the relay.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Returns the proposers of the current and next epoch that are registered with the relay.
+    pub async fn proposer_duties(&self) -> Result<Vec<ProposerDuty>, RelayError> {
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri(format!("{}{VALIDATORS_PATH}", self.url))
+            .body(Body::empty())
+            .expect("valid request");
+        let body = self.send(request).await?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    /// Submits a signed bid and its payload to the relay.
+    pub async fn submit_block(
+        &self,
+        submission: &SignedBidSubmission,
+        encoding: RelayEncoding,
+    ) -> Result<(), RelayError> {
+        let body = match encoding {
+            RelayEncoding::Json => serde_json::to_vec(submission)?,
+            RelayEncoding::Ssz => submission.as_ssz_bytes(),
+        };
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(format!("{}{BLOCKS_PATH}", self.url))
+            .header(CONTENT_TYPE, encoding.content_type())
+            .body(Body::from(body))
+            .expect("valid request");
+        self.send(request).await?;
+        Ok(())
+    }
+
+    /// Sends the request and returns the body of a successful response.
+    async fn send(&self, request: Request<Body>) -> Result<body::Bytes, RelayError> {
+        let response = with_timeout(self.timeout, self.client.request(request)).await??;
+        let status = response.status();
+        let body = with_timeout(self.timeout, body::to_bytes(response.into_body())).await??;
+        if !status.is_success() {
+            return Err(RelayError::Rejected {
+                status: status.as_u16(),
+                message: String::from_utf8_lossy(&body).into_owned(),
+            })
+        }
+        Ok(body)
+    }
+}
+
+async fn with_timeout<F: Future>(timeout: Duration, fut: F) -> Result<F::Output, RelayError> {
+    tokio::time::timeout(timeout, fut).await.map_err(|_| RelayError::Timeout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_relay_url() {
+        let client = RelayClient::new(
+            "https://0xac6e77dfe25ecd6110b8e780608cce0dab71fdd5ebea22a16c0205200f2f8e2e3ad3b71d3499c54ad14d6c21b41a37ae@boost-relay.flashbots.net/",
+            Duration::from_secs(1),
+        )
+        .unwrap();
+        assert!(client.url().ends_with("boost-relay.flashbots.net"));
+        assert!(RelayClient::new("boost-relay.flashbots.net", Duration::from_secs(1)).is_err());
+    }
+}
@@ -0,0 +1,175 @@
+use reth_primitives::Chain;
+use std::{fmt, str::FromStr, time::Duration};
+
+/// The default timeout for requests to a relay.
+pub const DEFAULT_RELAY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Parameters of the consensus layer chain the relays serve.
+///
+/// These are required to derive the slot of a payload from its timestamp and the signing domain
+/// of bids.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BeaconChainParams {
+    /// The timestamp of the beacon chain genesis.
+    pub genesis_time: u64,
+    /// The fork version at genesis, the builder domain is derived from it.
+    pub genesis_fork_version: [u8; 4],
+    /// The duration of a slot in seconds.
+    pub seconds_per_slot: u64,
+}
+
+impl BeaconChainParams {
+    /// The mainnet beacon chain.
+    pub const MAINNET: Self = Self {
+        genesis_time: 1606824023,
+        genesis_fork_version: [0x00, 0x00, 0x00, 0x00],
+        seconds_per_slot: 12,
+    };
+
+    /// The goerli (prater) beacon chain.
+    pub const GOERLI: Self = Self {
+        genesis_time: 1616508000,
+        genesis_fork_version: [0x00, 0x00, 0x10, 0x20],
+        seconds_per_slot: 12,
+    };
+
+    /// The sepolia beacon chain.
+    pub const SEPOLIA: Self = Self {
+        genesis_time: 1655733600,
+        genesis_fork_version: [0x90, 0x00, 0x00, 0x69],
+        seconds_per_slot: 12,
+    };
+
+    /// The holesky beacon chain.
+    pub const HOLESKY: Self = Self {
+        genesis_time: 1695902400,
+        genesis_fork_version: [0x01, 0x01, 0x70, 0x00],
+        seconds_per_slot: 12,
+    };
+
+    /// Returns the parameters of the beacon chain of a known execution chain.
+    pub fn from_chain(chain: Chain) -> Option<Self> {
+        match chain.id() {
+            1 => Some(Self::MAINNET),
+            5 => Some(Self::GOERLI),
+            11155111 => Some(Self::SEPOLIA),
+            17000 => Some(Self::HOLESKY),
+            _ => None,
+        }
+    }
+
+    /// Returns the slot of a payload with the given timestamp.
+    ///
+    /// Returns `None` if the timestamp is before genesis.
+    pub fn slot_at(&self, timestamp: u64) -> Option<u64> {
+        timestamp.checked_sub(self.genesis_time).map(|elapsed| elapsed / self.seconds_per_slot)
+    }
+}
+
+/// How submissions are encoded when sent to a relay.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RelayEncoding {
+    /// JSON encoded submissions, supported by all relays.
+    #[default]
+    Json,
+    /// SSZ encoded submissions, which are faster to decode but not supported by all relays.
+    Ssz,
+}
+
+impl RelayEncoding {
+    /// Returns the content type of submissions with this encoding.
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            RelayEncoding::Json => "application/json",
+            RelayEncoding::Ssz => "application/octet-stream",
+        }
+    }
+}
+
+impl fmt::Display for RelayEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RelayEncoding::Json => f.write_str("json"),
+            RelayEncoding::Ssz => f.write_str("ssz"),
+        }
+    }
+}
+
+impl FromStr for RelayEncoding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(RelayEncoding::Json),
+            "ssz" => Ok(RelayEncoding::Ssz),
+            _ => Err(format!("invalid relay encoding: {s}, expected `json` or `ssz`")),
+        }
+    }
+}
+
+/// Configures the submission of payloads to relays.
+#[derive(Debug, Clone)]
+pub struct RelayConfig {
+    /// The urls of the relays payloads are submitted to.
+    pub relays: Vec<String>,
+    /// The beacon chain the relays serve.
+    pub chain: BeaconChainParams,
+    /// How submissions are encoded.
+    pub encoding: RelayEncoding,
+    /// The timeout of requests to a relay.
+    pub timeout: Duration,
+    /// Whether payloads are only submitted if they pay the fee recipient registered by the
+    /// proposer.
+    pub validate_fee_recipient: bool,
+}
+
+impl RelayConfig {
+    /// Creates a new config for the given relays of the beacon chain.
+    pub fn new(relays: Vec<String>, chain: BeaconChainParams) -> Self {
+        Self {
+            relays,
+            chain,
+            encoding: RelayEncoding::default(),
+            timeout: DEFAULT_RELAY_TIMEOUT,
+            validate_fee_recipient: true,
+        }
+    }
+
+    /// Sets the encoding of submissions.
+    pub fn with_encoding(mut self, encoding: RelayEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Sets the timeout of requests to a relay.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets whether the fee recipient of payloads is checked before they are submitted.
+    pub fn with_fee_recipient_validation(mut self, validate: bool) -> Self {
+        self.validate_fee_recipient = validate;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slot_from_timestamp() {
+        let params = BeaconChainParams::MAINNET;
+        assert_eq!(params.slot_at(params.genesis_time - 1), None);
+        assert_eq!(params.slot_at(params.genesis_time), Some(0));
+        assert_eq!(params.slot_at(params.genesis_time + 12 * 100 + 11), Some(100));
+    }
+
+    #[test]
+    fn parse_encoding() {
+        assert_eq!("json".parse::<RelayEncoding>().unwrap(), RelayEncoding::Json);
+        assert_eq!("SSZ".parse::<RelayEncoding>().unwrap(), RelayEncoding::Ssz);
+        assert!("rlp".parse::<RelayEncoding>().is_err());
+    }
+}
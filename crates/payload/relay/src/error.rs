@@ -0,0 +1,48 @@
+use reth_primitives::Address;
+
+/// Errors that can occur when submitting payloads to a relay.
+#[derive(Debug, thiserror::Error)]
+pub enum RelayError {
+    /// The url of a relay is invalid.
+    #[error("invalid relay url: {0}")]
+    InvalidUrl(String),
+    /// The BLS secret key of the builder is invalid.
+    #[error("invalid builder secret key")]
+    InvalidSecretKey,
+    /// A request to the relay failed.
+    #[error(transparent)]
+    Http(#[from] hyper::Error),
+    /// A request to the relay timed out.
+    #[error("relay request timed out")]
+    Timeout,
+    /// The relay rejected a request.
+    #[error("relay responded with status {status}: {message}")]
+    Rejected {
+        /// The status code of the response.
+        status: u16,
+        /// The body of the response.
+        message: String,
+    },
+    /// A response of the relay could not be decoded.
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    /// The payload's timestamp is before the beacon chain genesis.
+    #[error("payload timestamp {0} is before the beacon chain genesis")]
+    BeforeGenesis(u64),
+    /// No validator registered with the relay is the proposer of the slot.
+    #[error("no registered proposer for slot {0}")]
+    UnknownProposer(u64),
+    /// The payload doesn't pay the fee recipient registered by the proposer.
+    #[error(
+        "payload fee recipient {payload:?} does not match registered fee recipient {registered:?}"
+    )]
+    FeeRecipientMismatch {
+        /// The fee recipient of the payload.
+        payload: Address,
+        /// The fee recipient registered by the proposer.
+        registered: Address,
+    },
+    /// Submissions of payloads with blob transactions are not supported.
+    #[error("payloads with blob gas can't be submitted to relays")]
+    UnsupportedPayload,
+}
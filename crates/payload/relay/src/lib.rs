@@ -0,0 +1,51 @@
+#![cfg_attr(docsrs, feature(doc_cfg))]
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxzy/reth/issues/"
+)]
+#![warn(missing_docs)]
+#![deny(
+    unused_must_use,
+    rust_2018_idioms,
+    rustdoc::broken_intra_doc_links,
+    unused_crate_dependencies
+)]
+#![doc(test(
+    no_crate_inject,
+    attr(deny(warnings, rust_2018_idioms), allow(dead_code, unused_variables))
+))]
+
+//! Submission of locally built payloads to builder relays.
+//!
+//! This implements the builder side of the
+//! [relay API](https://flashbots.github.io/relay-specs/): every payload resolved by the
+//! [PayloadBuilderService](reth_payload_builder::PayloadBuilderService) is signed with the
+//! builder's BLS key and submitted to the configured relays, either JSON or SSZ encoded.
+//!
+//! Since the consensus layer only requests payloads from the node if one of its validators is the
+//! proposer, this allows validators that are connected to the same relays via MEV-Boost to compare
+//! locally built blocks with the blocks of external builders.
+//!
+//! Before a payload is submitted, its fee recipient is checked against the fee recipient the
+//! proposer registered with the relay, relays reject bids that don't pay the proposer.
+
+mod client;
+mod config;
+mod error;
+mod signing;
+mod ssz;
+mod submitter;
+mod types;
+
+pub use client::RelayClient;
+pub use config::{BeaconChainParams, RelayConfig, RelayEncoding, DEFAULT_RELAY_TIMEOUT};
+pub use error::RelayError;
+pub use signing::{
+    compute_builder_domain, compute_signing_root, BuilderSigner, DOMAIN_APPLICATION_BUILDER,
+};
+pub use submitter::RelaySubmitter;
+pub use types::{
+    BidTrace, BlsPublicKey, BlsSignature, ProposerDuty, SignedBidSubmission,
+    SignedValidatorRegistration, ValidatorRegistration,
+};
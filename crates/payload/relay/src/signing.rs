@@ -0,0 +1,155 @@
+//! BLS signing of bids.
+
+use crate::{
+    types::{BidTrace, BlsPublicKey, BlsSignature},
+    RelayError,
+};
+use blst::min_pk::SecretKey;
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+/// The domain type of builder messages, see
+/// <https://github.com/ethereum/builder-specs/blob/main/specs/bellatrix/builder.md#domain-types>.
+pub const DOMAIN_APPLICATION_BUILDER: [u8; 4] = [0x00, 0x00, 0x00, 0x01];
+
+/// The ciphersuite of BLS signatures on the beacon chain.
+const BLS_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+/// Returns the domain bids are signed with.
+///
+/// Builder messages are not bound to a fork, so the domain is derived from the genesis fork version
+/// and an empty genesis validators root.
+pub fn compute_builder_domain(genesis_fork_version: [u8; 4]) -> [u8; 32] {
+    // hash tree root of `ForkData(current_version, genesis_validators_root)`
+    let fork_data_root = hash_pair(&pad_to_chunk(&genesis_fork_version), &[0u8; 32]);
+    let mut domain = [0u8; 32];
+    domain[..4].copy_from_slice(&DOMAIN_APPLICATION_BUILDER);
+    domain[4..].copy_from_slice(&fork_data_root[..28]);
+    domain
+}
+
+/// Returns the root that is signed for an object with the given hash tree root.
+pub fn compute_signing_root(object_root: [u8; 32], domain: [u8; 32]) -> [u8; 32] {
+    // hash tree root of `SigningData(object_root, domain)`
+    hash_pair(&object_root, &domain)
+}
+
+/// Signs bids with the builder's BLS key.
+#[derive(Clone)]
+pub struct BuilderSigner {
+    key: SecretKey,
+    public_key: BlsPublicKey,
+    domain: [u8; 32],
+}
+
+impl BuilderSigner {
+    /// Creates a new signer from the 32 byte secret key for the chain with the given genesis fork
+    /// version.
+    pub fn new(secret_key: &[u8], genesis_fork_version: [u8; 4]) -> Result<Self, RelayError> {
+        let key = SecretKey::from_bytes(secret_key).map_err(|_| RelayError::InvalidSecretKey)?;
+        let public_key = BlsPublicKey(key.sk_to_pk().to_bytes());
+        Ok(Self { key, public_key, domain: compute_builder_domain(genesis_fork_version) })
+    }
+
+    /// Returns the public key of the builder.
+    pub fn public_key(&self) -> BlsPublicKey {
+        self.public_key
+    }
+
+    /// Signs the bid.
+    pub fn sign_bid(&self, bid: &BidTrace) -> BlsSignature {
+        let signing_root = compute_signing_root(bid_trace_root(bid), self.domain);
+        BlsSignature(self.key.sign(&signing_root, BLS_DST, &[]).to_bytes())
+    }
+}
+
+impl fmt::Debug for BuilderSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BuilderSigner")
+            .field("public_key", &self.public_key)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns the hash tree root of the bid.
+fn bid_trace_root(bid: &BidTrace) -> [u8; 32] {
+    merkleize(vec![
+        pad_to_chunk(&bid.slot.to_le_bytes()),
+        bid.parent_hash.0,
+        bid.block_hash.0,
+        pubkey_root(&bid.builder_pubkey),
+        pubkey_root(&bid.proposer_pubkey),
+        pad_to_chunk(bid.proposer_fee_recipient.as_bytes()),
+        pad_to_chunk(&bid.gas_limit.to_le_bytes()),
+        pad_to_chunk(&bid.gas_used.to_le_bytes()),
+        bid.value.to_le_bytes::<32>(),
+    ])
+}
+
+/// Returns the hash tree root of a public key, which spans two chunks.
+fn pubkey_root(pubkey: &BlsPublicKey) -> [u8; 32] {
+    hash_pair(&pad_to_chunk(&pubkey.0[..32]), &pad_to_chunk(&pubkey.0[32..]))
+}
+
+/// Merkleizes the chunks, padding them with zero chunks to the next power of two.
+fn merkleize(mut chunks: Vec<[u8; 32]>) -> [u8; 32] {
+    chunks.resize(chunks.len().next_power_of_two(), [0u8; 32]);
+    while chunks.len() > 1 {
+        chunks = chunks.chunks(2).map(|pair| hash_pair(&pair[0], &pair[1])).collect();
+    }
+    chunks[0]
+}
+
+fn pad_to_chunk(bytes: &[u8]) -> [u8; 32] {
+    let mut chunk = [0u8; 32];
+    chunk[..bytes.len()].copy_from_slice(bytes);
+    chunk
+}
+
+fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blst::{min_pk::Signature, BLST_ERROR};
+    use reth_primitives::{hex_literal::hex, Address, H256, U256};
+
+    #[test]
+    fn mainnet_builder_domain() {
+        assert_eq!(
+            compute_builder_domain([0, 0, 0, 0]),
+            hex!("00000001f5a5fd42d16a20302798ef6ed309979b43003d2320d9f0e8ea9831a9")
+        );
+    }
+
+    #[test]
+    fn sign_and_verify_bid() {
+        let signer = BuilderSigner::new(&[7u8; 32], [0, 0, 0, 0]).unwrap();
+        let bid = BidTrace {
+            slot: 42,
+            parent_hash: H256::random(),
+            block_hash: H256::random(),
+            builder_pubkey: signer.public_key(),
+            proposer_pubkey: BlsPublicKey([2; 48]),
+            proposer_fee_recipient: Address::random(),
+            gas_limit: 30_000_000,
+            gas_used: 21_000,
+            value: U256::from(1u64),
+        };
+        let signature = Signature::from_bytes(&signer.sign_bid(&bid).0).unwrap();
+        let public_key = blst::min_pk::PublicKey::from_bytes(&signer.public_key().0).unwrap();
+        let signing_root = compute_signing_root(bid_trace_root(&bid), signer.domain);
+        assert_eq!(
+            signature.verify(true, &signing_root, BLS_DST, &[], &public_key, true),
+            BLST_ERROR::BLST_SUCCESS
+        );
+
+        let root = bid_trace_root(&bid);
+        assert_ne!(root, bid_trace_root(&BidTrace { slot: 43, ..bid }));
+    }
+}
@@ -0,0 +1,144 @@
+//! SSZ encoding of bid submissions.
+
+use crate::types::{BidTrace, BlsPublicKey, BlsSignature, SignedBidSubmission};
+use reth_primitives::ssz::{DecodeError, Encode, SszEncoder, SszFixed};
+
+/// The length of an SSZ encoded [BidTrace].
+const BID_TRACE_LEN: usize = 8 + 32 + 32 + 48 + 48 + 20 + 8 + 8 + 32;
+
+/// The length of the fixed size part of an SSZ encoded [SignedBidSubmission], the execution
+/// payload is variable size and encoded as a 4 byte offset.
+const SIGNED_BID_SUBMISSION_FIXED_LEN: usize = BID_TRACE_LEN + 4 + 96;
+
+macro_rules! impl_ssz_fixed_bytes {
+    ($name:ident, $len:expr) => {
+        impl SszFixed for $name {
+            const SSZ_LEN: usize = $len;
+
+            fn ssz_append_fixed(&self, buf: &mut Vec<u8>) {
+                buf.extend_from_slice(&self.0);
+            }
+
+            fn from_ssz_fixed(bytes: &[u8]) -> Result<Self, DecodeError> {
+                let bytes = bytes.try_into().map_err(|_| DecodeError::InvalidByteLength {
+                    len: bytes.len(),
+                    expected: Self::SSZ_LEN,
+                })?;
+                Ok(Self(bytes))
+            }
+        }
+    };
+}
+
+impl_ssz_fixed_bytes!(BlsPublicKey, 48);
+impl_ssz_fixed_bytes!(BlsSignature, 96);
+
+impl Encode for BidTrace {
+    fn is_ssz_fixed_len() -> bool {
+        true
+    }
+
+    fn ssz_fixed_len() -> usize {
+        BID_TRACE_LEN
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        let mut encoder = SszEncoder::container(buf, BID_TRACE_LEN);
+        encoder.append(&self.slot);
+        self.parent_hash.ssz_append_field(&mut encoder);
+        self.block_hash.ssz_append_field(&mut encoder);
+        self.builder_pubkey.ssz_append_field(&mut encoder);
+        self.proposer_pubkey.ssz_append_field(&mut encoder);
+        self.proposer_fee_recipient.ssz_append_field(&mut encoder);
+        encoder.append(&self.gas_limit);
+        encoder.append(&self.gas_used);
+        self.value.ssz_append_field(&mut encoder);
+        encoder.finalize();
+    }
+
+    fn ssz_bytes_len(&self) -> usize {
+        BID_TRACE_LEN
+    }
+}
+
+impl Encode for SignedBidSubmission {
+    fn is_ssz_fixed_len() -> bool {
+        false
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        let mut encoder = SszEncoder::container(buf, SIGNED_BID_SUBMISSION_FIXED_LEN);
+        encoder.append(&self.message);
+        encoder.append(&self.execution_payload);
+        self.signature.ssz_append_field(&mut encoder);
+        encoder.finalize();
+    }
+
+    fn ssz_bytes_len(&self) -> usize {
+        SIGNED_BID_SUBMISSION_FIXED_LEN + self.execution_payload.ssz_bytes_len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{Address, Bloom, Bytes, Withdrawal, H256, U256, U64};
+    use reth_rpc_types::engine::{ExecutionPayloadV1, ExecutionPayloadV2};
+
+    fn read_offset(buf: &[u8], at: usize) -> usize {
+        u32::from_le_bytes(buf[at..at + 4].try_into().unwrap()) as usize
+    }
+
+    #[test]
+    fn encode_submission() {
+        let payload = ExecutionPayloadV2 {
+            payload_inner: ExecutionPayloadV1 {
+                parent_hash: H256::random(),
+                fee_recipient: Address::random(),
+                state_root: H256::random(),
+                receipts_root: H256::random(),
+                logs_bloom: Bloom::zero(),
+                prev_randao: H256::random(),
+                block_number: U64::from(1),
+                gas_limit: U64::from(30_000_000),
+                gas_used: U64::from(42_000),
+                timestamp: U64::from(1_700_000_000),
+                extra_data: Bytes::from(b"reth".to_vec()),
+                base_fee_per_gas: U256::from(7u64),
+                block_hash: H256::random(),
+                transactions: vec![Bytes::from(vec![1u8; 10]), Bytes::from(vec![2u8; 20])],
+            },
+            withdrawals: vec![Withdrawal {
+                index: 1,
+                validator_index: 2,
+                address: Address::random(),
+                amount: 3,
+            }],
+        };
+        let submission = SignedBidSubmission {
+            message: BidTrace {
+                slot: 1,
+                parent_hash: payload.payload_inner.parent_hash,
+                block_hash: payload.payload_inner.block_hash,
+                builder_pubkey: BlsPublicKey([1; 48]),
+                proposer_pubkey: BlsPublicKey([2; 48]),
+                proposer_fee_recipient: payload.payload_inner.fee_recipient,
+                gas_limit: 30_000_000,
+                gas_used: 42_000,
+                value: U256::from(1u64),
+            },
+            execution_payload: payload,
+            signature: BlsSignature([3; 96]),
+        };
+
+        let encoded = submission.as_ssz_bytes();
+        assert_eq!(encoded.len(), submission.ssz_bytes_len());
+        assert_eq!(&encoded[..8], &1u64.to_le_bytes());
+        assert_eq!(read_offset(&encoded, BID_TRACE_LEN), SIGNED_BID_SUBMISSION_FIXED_LEN);
+        assert_eq!(&encoded[BID_TRACE_LEN + 4..SIGNED_BID_SUBMISSION_FIXED_LEN], &[3; 96]);
+        assert_eq!(
+            &encoded[SIGNED_BID_SUBMISSION_FIXED_LEN..],
+            &submission.execution_payload.as_ssz_bytes()[..]
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use crate::{
+    types::{BidTrace, SignedBidSubmission},
+    BuilderSigner, RelayClient, RelayConfig, RelayError,
+};
+use futures_util::{future::join_all, Stream, StreamExt};
+use reth_payload_builder::{BuiltPayload, PayloadBuilderEvent};
+use reth_rpc_types::engine::ExecutionPayloadV2;
+use tracing::{debug, info, warn};
+
+/// Submits locally built payloads to the configured relays.
+#[derive(Debug)]
+pub struct RelaySubmitter {
+    config: RelayConfig,
+    signer: BuilderSigner,
+    relays: Vec<RelayClient>,
+}
+
+impl RelaySubmitter {
+    /// Creates a new submitter that signs bids with the given signer.
+    pub fn new(config: RelayConfig, signer: BuilderSigner) -> Result<Self, RelayError> {
+        let relays = config
+            .relays
+            .iter()
+            .map(|url| RelayClient::new(url, config.timeout))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { config, signer, relays })
+    }
+
+    /// Returns the relays payloads are submitted to.
+    pub fn relays(&self) -> &[RelayClient] {
+        &self.relays
+    }
+
+    /// Submits every payload resolved by the payload builder service until the stream of events
+    /// ends.
+    ///
+    /// The events can be obtained from
+    /// [PayloadBuilderHandle::event_listener](reth_payload_builder::PayloadBuilderHandle::event_listener).
+    pub async fn run<St>(self, mut events: St)
+    where
+        St: Stream<Item = PayloadBuilderEvent> + Unpin,
+    {
+        while let Some(event) = events.next().await {
+            if let PayloadBuilderEvent::Resolved(payload) = event {
+                self.submit(&payload).await;
+            }
+        }
+    }
+
+    /// Submits the payload to all relays and returns the result of every submission, in the order
+    /// of the configured relays.
+    pub async fn submit(&self, payload: &BuiltPayload) -> Vec<Result<(), RelayError>> {
+        let block = payload.block();
+        let results =
+            join_all(self.relays.iter().map(|relay| self.submit_to(relay, payload))).await;
+
+        for (relay, result) in self.relays.iter().zip(results.iter()) {
+            match result {
+                Ok(()) => {
+                    info!(target: "payload_builder::relay", relay = relay.url(), number = block.number, hash = ?block.hash(), value = %payload.fees(), "Submitted payload to relay")
+                }
+                Err(err @ RelayError::FeeRecipientMismatch { .. }) => {
+                    warn!(target: "payload_builder::relay", relay = relay.url(), number = block.number, hash = ?block.hash(), %err, "Payload not submitted to relay")
+                }
+                Err(err) => {
+                    debug!(target: "payload_builder::relay", relay = relay.url(), number = block.number, hash = ?block.hash(), %err, "Failed to submit payload to relay")
+                }
+            }
+        }
+
+        results
+    }
+
+    async fn submit_to(
+        &self,
+        relay: &RelayClient,
+        payload: &BuiltPayload,
+    ) -> Result<(), RelayError> {
+        let block = payload.block();
+        if block.blob_gas_used.is_some() {
+            return Err(RelayError::UnsupportedPayload)
+        }
+
+        let slot = self
+            .config
+            .chain
+            .slot_at(block.timestamp)
+            .ok_or(RelayError::BeforeGenesis(block.timestamp))?;
+        let duty = relay
+            .proposer_duties()
+            .await?
+            .into_iter()
+            .find(|duty| duty.slot == slot)
+            .ok_or(RelayError::UnknownProposer(slot))?;
+        let registration = duty.entry.message;
+
+        if self.config.validate_fee_recipient && registration.fee_recipient != block.beneficiary {
+            return Err(RelayError::FeeRecipientMismatch {
+                payload: block.beneficiary,
+                registered: registration.fee_recipient,
+            })
+        }
+
+        let message = BidTrace {
+            slot,
+            parent_hash: block.parent_hash,
+            block_hash: block.hash(),
+            builder_pubkey: self.signer.public_key(),
+            proposer_pubkey: registration.pubkey,
+            proposer_fee_recipient: registration.fee_recipient,
+            gas_limit: block.gas_limit,
+            gas_used: block.gas_used,
+            value: payload.fees(),
+        };
+        let submission = SignedBidSubmission {
+            signature: self.signer.sign_bid(&message),
+            message,
+            execution_payload: ExecutionPayloadV2::from(block.clone()),
+        };
+        relay.submit_block(&submission, self.config.encoding).await
+    }
+}
@@ -0,0 +1,306 @@
+//! Types of the relay API.
+//!
+//! Unlike the engine API, the relay API uses `snake_case` field names and encodes all integers as
+//! decimal strings.
+
+use reth_primitives::{hex, Address, H256, U256};
+use reth_rpc_types::engine::ExecutionPayloadV2;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// A BLS public key.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BlsPublicKey(pub [u8; 48]);
+
+/// A BLS signature.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BlsSignature(pub [u8; 96]);
+
+macro_rules! impl_hex_bytes {
+    ($name:ident, $len:expr) => {
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "0x{}", hex::encode(self.0))
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(&format!("0x{}", hex::encode(self.0)))
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let s = String::deserialize(deserializer)?;
+                let mut bytes = [0u8; $len];
+                hex::decode_to_slice(s.trim_start_matches("0x"), &mut bytes)
+                    .map_err(de::Error::custom)?;
+                Ok(Self(bytes))
+            }
+        }
+    };
+}
+
+impl_hex_bytes!(BlsPublicKey, 48);
+impl_hex_bytes!(BlsSignature, 96);
+
+/// (De)serializes integers as decimal strings.
+mod quantity {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+    use std::{fmt::Display, str::FromStr};
+
+    pub(super) fn serialize<T: Display, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    pub(super) fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?.parse().map_err(de::Error::custom)
+    }
+}
+
+/// (De)serializes an [ExecutionPayloadV2] in the format of the relay API.
+mod payload {
+    use super::quantity;
+    use reth_primitives::{Address, Bloom, Bytes, Withdrawal, H256, U256, U64};
+    use reth_rpc_types::engine::{ExecutionPayloadV1, ExecutionPayloadV2};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(remote = "ExecutionPayloadV1")]
+    struct ExecutionPayloadV1Def {
+        parent_hash: H256,
+        fee_recipient: Address,
+        state_root: H256,
+        receipts_root: H256,
+        logs_bloom: Bloom,
+        prev_randao: H256,
+        #[serde(with = "u64_quantity")]
+        block_number: U64,
+        #[serde(with = "u64_quantity")]
+        gas_limit: U64,
+        #[serde(with = "u64_quantity")]
+        gas_used: U64,
+        #[serde(with = "u64_quantity")]
+        timestamp: U64,
+        extra_data: Bytes,
+        #[serde(with = "quantity")]
+        base_fee_per_gas: U256,
+        block_hash: H256,
+        transactions: Vec<Bytes>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(remote = "ExecutionPayloadV2")]
+    pub(super) struct ExecutionPayloadV2Def {
+        #[serde(flatten, with = "ExecutionPayloadV1Def")]
+        payload_inner: ExecutionPayloadV1,
+        #[serde(with = "withdrawals")]
+        withdrawals: Vec<Withdrawal>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(remote = "Withdrawal")]
+    struct WithdrawalDef {
+        #[serde(with = "quantity")]
+        index: u64,
+        #[serde(with = "quantity")]
+        validator_index: u64,
+        address: Address,
+        #[serde(with = "quantity")]
+        amount: u64,
+    }
+
+    mod withdrawals {
+        use super::*;
+
+        pub(super) fn serialize<S: Serializer>(
+            withdrawals: &[Withdrawal],
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            #[derive(Serialize)]
+            struct Relay<'a>(#[serde(with = "WithdrawalDef")] &'a Withdrawal);
+            serializer.collect_seq(withdrawals.iter().map(Relay))
+        }
+
+        pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Vec<Withdrawal>, D::Error> {
+            #[derive(Deserialize)]
+            struct Relay(#[serde(with = "WithdrawalDef")] Withdrawal);
+            Ok(Vec::<Relay>::deserialize(deserializer)?.into_iter().map(|w| w.0).collect())
+        }
+    }
+
+    /// [U64] parses hex strings, so the decimal strings are converted from [u64].
+    mod u64_quantity {
+        use super::{quantity, U64};
+        use serde::{Deserializer, Serializer};
+
+        pub(super) fn serialize<S: Serializer>(
+            value: &U64,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            quantity::serialize(&value.as_u64(), serializer)
+        }
+
+        pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<U64, D::Error> {
+            quantity::deserialize::<u64, _>(deserializer).map(U64::from)
+        }
+    }
+}
+
+/// The bid of a builder for a slot, this is the message that is signed by the builder.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BidTrace {
+    /// The slot the payload is built for.
+    #[serde(with = "quantity")]
+    pub slot: u64,
+    /// The hash of the parent block.
+    pub parent_hash: H256,
+    /// The hash of the payload.
+    pub block_hash: H256,
+    /// The public key of the builder.
+    pub builder_pubkey: BlsPublicKey,
+    /// The public key of the proposer of the slot.
+    pub proposer_pubkey: BlsPublicKey,
+    /// The fee recipient registered by the proposer.
+    pub proposer_fee_recipient: Address,
+    /// The gas limit of the payload.
+    #[serde(with = "quantity")]
+    pub gas_limit: u64,
+    /// The gas used by the payload.
+    #[serde(with = "quantity")]
+    pub gas_used: u64,
+    /// The value paid to the proposer, in wei.
+    #[serde(with = "quantity")]
+    pub value: U256,
+}
+
+/// A signed bid and the payload it commits to, submitted to `/relay/v1/builder/blocks`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedBidSubmission {
+    /// The bid.
+    pub message: BidTrace,
+    /// The payload.
+    #[serde(with = "payload::ExecutionPayloadV2Def")]
+    pub execution_payload: ExecutionPayloadV2,
+    /// The builder's signature of the bid.
+    pub signature: BlsSignature,
+}
+
+/// A validator's registration with a relay.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidatorRegistration {
+    /// The fee recipient payloads must pay.
+    pub fee_recipient: Address,
+    /// The preferred gas limit of the validator.
+    #[serde(with = "quantity")]
+    pub gas_limit: u64,
+    /// The timestamp of the registration.
+    #[serde(with = "quantity")]
+    pub timestamp: u64,
+    /// The public key of the validator.
+    pub pubkey: BlsPublicKey,
+}
+
+/// A validator registration signed by the validator.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedValidatorRegistration {
+    /// The registration.
+    pub message: ValidatorRegistration,
+    /// The validator's signature of the registration.
+    pub signature: BlsSignature,
+}
+
+/// An upcoming proposer registered with a relay, as returned by `/relay/v1/builder/validators`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProposerDuty {
+    /// The slot of the proposal.
+    #[serde(with = "quantity")]
+    pub slot: u64,
+    /// The index of the proposer.
+    #[serde(with = "quantity")]
+    pub validator_index: u64,
+    /// The registration of the proposer.
+    pub entry: SignedValidatorRegistration,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{SealedBlock, Withdrawal};
+
+    #[test]
+    fn deserialize_proposer_duty() {
+        let s = r#"{"slot":"7151217","validator_index":"492361","entry":{"message":{"fee_recipient":"0x388c818ca8b9251b393131c08a736a67ccb19297","gas_limit":"30000000","timestamp":"1684336740","pubkey":"0x8a1d7b8dd64e0aafe7ea7b6c95065c9364cf99d38470c12ee807d55f7de1529ad29ce2c422e0b65e3d5a05c02caca249"},"signature":"0xabababababababababababababababababababababababababababababababababababababababababababababababababababababababababababababababababababababababababababababababababababababababababababababababab"}}"#;
+        let duty: ProposerDuty = serde_json::from_str(s).unwrap();
+        assert_eq!(duty.slot, 7151217);
+        assert_eq!(duty.entry.message.gas_limit, 30_000_000);
+        let json = serde_json::to_string(&duty).unwrap();
+        assert_eq!(serde_json::from_str::<ProposerDuty>(&json).unwrap(), duty);
+    }
+
+    #[test]
+    fn serialize_bid_trace_quantities() {
+        let bid = BidTrace {
+            slot: 1,
+            parent_hash: H256::zero(),
+            block_hash: H256::zero(),
+            builder_pubkey: BlsPublicKey([1; 48]),
+            proposer_pubkey: BlsPublicKey([2; 48]),
+            proposer_fee_recipient: Address::zero(),
+            gas_limit: 30_000_000,
+            gas_used: 21_000,
+            value: U256::from(1_000_000_000_000_000_000u128),
+        };
+        let value = serde_json::to_value(&bid).unwrap();
+        assert_eq!(value["slot"], "1");
+        assert_eq!(value["value"], "1000000000000000000");
+        assert_eq!(serde_json::from_value::<BidTrace>(value).unwrap(), bid);
+    }
+
+    #[test]
+    fn serialize_submission_payload() {
+        let mut payload = ExecutionPayloadV2::from(SealedBlock::default());
+        payload.payload_inner.block_number = 17_000_000u64.into();
+        payload.withdrawals.push(Withdrawal {
+            index: 1,
+            validator_index: 2,
+            address: Address::zero(),
+            amount: 3,
+        });
+        let submission = SignedBidSubmission {
+            message: BidTrace {
+                slot: 1,
+                parent_hash: H256::zero(),
+                block_hash: H256::zero(),
+                builder_pubkey: BlsPublicKey([1; 48]),
+                proposer_pubkey: BlsPublicKey([2; 48]),
+                proposer_fee_recipient: Address::zero(),
+                gas_limit: 30_000_000,
+                gas_used: 21_000,
+                value: U256::from(1),
+            },
+            execution_payload: payload,
+            signature: BlsSignature([3; 96]),
+        };
+
+        let value = serde_json::to_value(&submission).unwrap();
+        let payload = &value["execution_payload"];
+        assert_eq!(payload["block_number"], "17000000");
+        assert_eq!(payload["base_fee_per_gas"], "0");
+        assert_eq!(payload["withdrawals"][0]["validator_index"], "2");
+        assert_eq!(serde_json::from_value::<SignedBidSubmission>(value).unwrap(), submission);
+    }
+}
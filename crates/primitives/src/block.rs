@@ -1,5 +1,6 @@
 use crate::{
-    Address, BlockHash, BlockNumber, Header, SealedHeader, TransactionSigned, Withdrawal, H256, U64,
+    Address, BlockHash, BlockNumber, Header, SealedHeader, SenderRecoveryCache, TransactionSigned,
+    Withdrawal, H256, U64,
 };
 use fixed_hash::rustc_hex::FromHexError;
 use reth_codecs::derive_arbitrary;
@@ -184,6 +185,18 @@ impl SealedBlock {
         }
     }
 
+    /// Like [Self::try_seal_with_senders], but takes the senders that are in the
+    /// [SenderRecoveryCache] from the cache.
+    pub fn try_seal_with_senders_cached(
+        self,
+        cache: &SenderRecoveryCache,
+    ) -> Result<SealedBlockWithSenders, Self> {
+        match TransactionSigned::recover_signers_cached(&self.body, self.body.len(), cache) {
+            Some(senders) => Ok(SealedBlockWithSenders { block: self, senders }),
+            None => Err(self),
+        }
+    }
+
     /// Unseal the block
     pub fn unseal(self) -> Block {
         Block {
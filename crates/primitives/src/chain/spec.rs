@@ -463,6 +463,12 @@ impl ChainSpec {
             .unwrap_or_else(|| self.is_fork_active_at_timestamp(Hardfork::Cancun, timestamp))
     }
 
+    /// Convenience method to check if [Hardfork::Prague] is active at a given timestamp.
+    #[inline]
+    pub fn is_prague_activated_at_timestamp(&self, timestamp: u64) -> bool {
+        self.is_fork_active_at_timestamp(Hardfork::Prague, timestamp)
+    }
+
     /// Creates a [`ForkFilter`](crate::ForkFilter) for the block described by [Head].
     pub fn fork_filter(&self, head: Head) -> ForkFilter {
         let forks = self.forks_iter().filter_map(|(_, condition)| {
@@ -527,6 +533,41 @@ impl ChainSpec {
         ForkId { hash: forkhash, next: 0 }
     }
 
+    /// Returns all [`ForkId`]s of the chain in the order of activation, starting with the fork id
+    /// of the genesis block.
+    ///
+    /// Like [Self::fork_id], block based forks are applied before timestamp based forks.
+    pub fn fork_ids(&self) -> Vec<ForkId> {
+        let mut activations: Vec<u64> = Vec::new();
+        for (_, cond) in self.forks_iter() {
+            if let ForkCondition::Block(block) |
+            ForkCondition::TTD { fork_block: Some(block), .. } = cond
+            {
+                if block != 0 && activations.last() != Some(&block) {
+                    activations.push(block);
+                }
+            }
+        }
+        for (_, cond) in self.forks_iter() {
+            if let Some(timestamp) =
+                cond.as_timestamp().filter(|time| time > &self.genesis.timestamp)
+            {
+                if activations.last() != Some(&timestamp) {
+                    activations.push(timestamp);
+                }
+            }
+        }
+
+        let mut forkhash = ForkHash::from(self.genesis_hash());
+        let mut fork_ids = Vec::with_capacity(activations.len() + 1);
+        for next in activations {
+            fork_ids.push(ForkId { hash: forkhash, next });
+            forkhash += next;
+        }
+        fork_ids.push(ForkId { hash: forkhash, next: 0 });
+        fork_ids
+    }
+
     /// Build a chainspec using [`ChainSpecBuilder`]
     pub fn builder() -> ChainSpecBuilder {
         ChainSpecBuilder::default()
@@ -571,6 +612,7 @@ impl From<Genesis> for ChainSpec {
         let time_hardfork_opts = [
             (Hardfork::Shanghai, genesis.config.shanghai_time),
             (Hardfork::Cancun, genesis.config.cancun_time),
+            (Hardfork::Prague, genesis.config.prague_time),
         ];
 
         let time_hardforks = time_hardfork_opts
@@ -799,6 +841,13 @@ impl ChainSpecBuilder {
         self
     }
 
+    /// Enable Prague at genesis.
+    pub fn prague_activated(mut self) -> Self {
+        self = self.cancun_activated();
+        self.hardforks.insert(Hardfork::Prague, ForkCondition::Timestamp(0));
+        self
+    }
+
     /// Build the resulting [`ChainSpec`].
     ///
     /// # Panics
@@ -1355,6 +1404,26 @@ Post-merge hard forks (timestamp based):
         );
     }
 
+    #[test]
+    fn mainnet_fork_id_sequence() {
+        let fork_ids = MAINNET.fork_ids();
+        assert_eq!(fork_ids.len(), 14);
+        assert_eq!(fork_ids[0], ForkId { hash: ForkHash([0xfc, 0x64, 0xec, 0x04]), next: 1150000 });
+        assert_eq!(
+            fork_ids[12],
+            ForkId { hash: ForkHash([0xf0, 0xaf, 0xd0, 0xe3]), next: 1681338455 }
+        );
+        assert_eq!(fork_ids[13], ForkId { hash: ForkHash([0xdc, 0xe9, 0x6c, 0x2d]), next: 0 });
+        assert_eq!(
+            fork_ids[13],
+            MAINNET.fork_id(&Head {
+                number: 20000000,
+                timestamp: 1681338455,
+                ..Default::default()
+            })
+        );
+    }
+
     #[test]
     fn goerli_forkids() {
         test_fork_ids(
@@ -381,6 +381,13 @@ pub struct ChainConfig {
     )]
     pub cancun_time: Option<u64>,
 
+    /// Prague switch time.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_stringified_u64_opt"
+    )]
+    pub prague_time: Option<u64>,
+
     /// Total difficulty reached that triggers the merge consensus upgrade.
     #[serde(
         skip_serializing_if = "Option::is_none",
@@ -527,6 +534,7 @@ mod ethers_compat {
                 merge_netsplit_block,
                 shanghai_time,
                 cancun_time,
+                prague_time: None,
                 terminal_total_difficulty: terminal_total_difficulty.map(Into::into),
                 terminal_total_difficulty_passed,
                 ethash: ethash.map(Into::into),
@@ -41,6 +41,8 @@ pub enum Hardfork {
     Shanghai,
     /// Cancun.
     Cancun,
+    /// Prague.
+    Prague,
 }
 
 impl Hardfork {
@@ -85,6 +87,7 @@ impl FromStr for Hardfork {
             "paris" => Hardfork::Paris,
             "shanghai" => Hardfork::Shanghai,
             "cancun" => Hardfork::Cancun,
+            "prague" => Hardfork::Prague,
             _ => return Err(format!("Unknown hardfork: {s}")),
         };
         Ok(hardfork)
@@ -123,6 +126,7 @@ mod tests {
             "PARIS",
             "ShAnGhAI",
             "CaNcUn",
+            "pRaGuE",
         ];
         let expected_hardforks = [
             Hardfork::Frontier,
@@ -142,6 +146,7 @@ mod tests {
             Hardfork::Paris,
             Hardfork::Shanghai,
             Hardfork::Cancun,
+            Hardfork::Prague,
         ];
 
         let hardforks: Vec<Hardfork> =
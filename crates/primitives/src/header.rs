@@ -718,11 +718,18 @@ mod ethers_compat {
 
 #[cfg(test)]
 mod tests {
-    use super::{Bytes, Decodable, Encodable, Header, H256};
+    use super::{Bytes, Decodable, Encodable, Header, HeaderFlags, H256};
     use crate::{Address, HeadersDirection, U256};
     use ethers_core::utils::hex::{self, FromHex};
     use std::str::FromStr;
 
+    #[test]
+    fn header_bitflag_unused_bits() {
+        // one bit is left for a new optional field
+        assert_eq!(HeaderFlags::bitflag_encoded_bytes(), 4);
+        assert_eq!(HeaderFlags::bitflag_unused_bits(), 1);
+    }
+
     // Test vector from: https://eips.ethereum.org/EIPS/eip-2481
     #[test]
     fn test_encode_block_header() {
@@ -95,10 +95,11 @@ pub use transaction::{
     AccessList, AccessListItem, AccessListWithGasUsed, BlobTransaction, BlobTransactionSidecar,
     BlobTransactionValidationError, FromRecoveredPooledTransaction, FromRecoveredTransaction,
     IntoRecoveredTransaction, InvalidTransactionError, PooledTransactionsElement,
-    PooledTransactionsElementEcRecovered, Signature, Transaction, TransactionKind, TransactionMeta,
-    TransactionSigned, TransactionSignedEcRecovered, TransactionSignedNoHash, TxEip1559, TxEip2930,
-    TxEip4844, TxLegacy, TxType, EIP1559_TX_TYPE_ID, EIP2930_TX_TYPE_ID, EIP4844_TX_TYPE_ID,
-    LEGACY_TX_TYPE_ID,
+    PooledTransactionsElementEcRecovered, SenderRecoveryCache, Signature, Transaction,
+    TransactionDecodeError, TransactionKind, TransactionMeta, TransactionSigned,
+    TransactionSignedEcRecovered, TransactionSignedNoHash, TxEip1559, TxEip2930, TxEip4844,
+    TxLegacy, TxType, DEFAULT_SENDER_CACHE_MAX_ENTRIES, EIP1559_TX_TYPE_ID, EIP2930_TX_TYPE_ID,
+    EIP4844_TX_TYPE_ID, LEGACY_TX_TYPE_ID,
 };
 pub use withdrawal::Withdrawal;
 
@@ -158,6 +159,9 @@ pub mod kzg {
 /// Helpers for working with serde
 pub mod serde_helper;
 
+#[cfg(feature = "ssz")]
+pub mod ssz;
+
 /// Returns the keccak256 hash for the given data.
 #[inline]
 pub fn keccak256(data: impl AsRef<[u8]>) -> H256 {
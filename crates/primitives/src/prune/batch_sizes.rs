@@ -19,6 +19,9 @@ pub struct PruneBatchSizes {
     /// Maximum number of storage history entries to prune, per block.
     /// Measured in the number of `StorageChangeSet` table rows.
     storage_history: usize,
+    /// Maximum number of preimages to prune, per block.
+    /// Measured in the number of `PreimageChangeSet` table rows.
+    preimages: usize,
 }
 
 macro_rules! impl_prune_batch_size_methods {
@@ -47,7 +50,8 @@ impl_prune_batch_size_methods!(
     ("transaction lookup entries", transaction_lookup),
     ("transaction senders", transaction_senders),
     ("account history entries", account_history),
-    ("storage history entries", storage_history)
+    ("storage history entries", storage_history),
+    ("preimages", preimages)
 );
 
 impl PruneBatchSizes {
@@ -60,6 +64,7 @@ impl PruneBatchSizes {
             transaction_senders: 1000,
             account_history: 1000,
             storage_history: 1000,
+            preimages: 1000,
         }
     }
 
@@ -72,6 +77,7 @@ impl PruneBatchSizes {
             transaction_senders: 500,
             account_history: 500,
             storage_history: 500,
+            preimages: 500,
         }
     }
 }
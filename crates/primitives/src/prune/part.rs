@@ -18,6 +18,8 @@ pub enum PrunePart {
     AccountHistory,
     /// Prune part responsible for the `StorageChangeSet` and `StorageHistory` tables.
     StorageHistory,
+    /// Prune part responsible for the `PreimageChangeSet` and `Preimages` tables.
+    Preimages,
 }
 
 /// PrunePart error type.
@@ -37,6 +37,10 @@ pub struct PruneModes {
         deserialize_with = "deserialize_opt_prune_mode_with_min_blocks::<64, _>"
     )]
     pub storage_history: Option<PruneMode>,
+    /// Preimages pruning configuration. Only preimages of addresses and storage keys that
+    /// weren't changed after the target block are pruned.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preimages: Option<PruneMode>,
     /// Receipts pruning configuration by retaining only those receipts that contain logs emitted
     /// by the specified addresses, discarding others. This setting is overridden by `receipts`.
     ///
@@ -103,6 +107,7 @@ impl PruneModes {
         (transaction_lookup, TransactionLookup, None),
         (receipts, Receipts, Some(64)),
         (account_history, AccountHistory, Some(64)),
-        (storage_history, StorageHistory, Some(64))
+        (storage_history, StorageHistory, Some(64)),
+        (preimages, Preimages, None)
     );
 }
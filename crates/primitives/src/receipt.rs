@@ -294,6 +294,14 @@ mod tests {
     use reth_rlp::{Decodable, Encodable};
     use std::str::FromStr;
 
+    #[test]
+    fn receipt_bitflag_unused_bits() {
+        // there is no room left for new fields, they require a new version of the encoding, see
+        // `reth_codecs::VersionedCompact`
+        assert_eq!(ReceiptFlags::bitflag_encoded_bytes(), 1);
+        assert_eq!(ReceiptFlags::bitflag_unused_bits(), 0);
+    }
+
     #[test]
     // Test vector from: https://eips.ethereum.org/EIPS/eip-2481
     fn encode_legacy_receipt() {
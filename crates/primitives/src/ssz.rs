@@ -0,0 +1,232 @@
+//! [SSZ](https://github.com/ethereum/consensus-specs/blob/dev/ssz/simple-serialize.md) encoding
+//! of the primitives that are shared with the consensus layer.
+//!
+//! The [Encode] and [Decode] traits are the ones of the `ethereum_ssz` crate, so the types can be
+//! used directly by consensus layer tooling. Fixed size types that are defined in other crates,
+//! like [H256] or [U256], can't implement these traits and are encoded as fields of containers
+//! with the [SszFixed] helpers instead.
+use crate::{Address, Bloom, Bytes, Withdrawal, H160, H256, U256, U64};
+pub use ::ssz::{Decode, DecodeError, Encode, SszDecoder, SszDecoderBuilder, SszEncoder};
+
+/// A fixed size type that is encoded as a field of an SSZ container.
+pub trait SszFixed: Sized {
+    /// The length of the encoding.
+    const SSZ_LEN: usize;
+
+    /// Appends the encoding to the buffer.
+    fn ssz_append_fixed(&self, buf: &mut Vec<u8>);
+
+    /// Decodes the value from exactly [SszFixed::SSZ_LEN] bytes.
+    fn from_ssz_fixed(bytes: &[u8]) -> Result<Self, DecodeError>;
+
+    /// Appends the value as the next field of the container.
+    fn ssz_append_field(&self, encoder: &mut SszEncoder<'_>) {
+        encoder.append_parameterized(true, |buf| self.ssz_append_fixed(buf))
+    }
+
+    /// Registers the value as the next field of the container.
+    fn ssz_register_field(builder: &mut SszDecoderBuilder<'_>) -> Result<(), DecodeError> {
+        builder.register_type_parameterized(true, Self::SSZ_LEN)
+    }
+
+    /// Decodes the next field of the container.
+    fn ssz_decode_field(decoder: &mut SszDecoder<'_>) -> Result<Self, DecodeError> {
+        decoder.decode_next_with(Self::from_ssz_fixed)
+    }
+}
+
+fn check_len(bytes: &[u8], expected: usize) -> Result<(), DecodeError> {
+    if bytes.len() != expected {
+        return Err(DecodeError::InvalidByteLength { len: bytes.len(), expected })
+    }
+    Ok(())
+}
+
+macro_rules! impl_ssz_fixed_hash {
+    ($($name:ty),+) => {
+        $(
+            impl SszFixed for $name {
+                const SSZ_LEN: usize = <$name>::len_bytes();
+
+                fn ssz_append_fixed(&self, buf: &mut Vec<u8>) {
+                    buf.extend_from_slice(self.as_bytes());
+                }
+
+                fn from_ssz_fixed(bytes: &[u8]) -> Result<Self, DecodeError> {
+                    check_len(bytes, Self::SSZ_LEN)?;
+                    Ok(<$name>::from_slice(bytes))
+                }
+            }
+        )+
+    };
+}
+
+impl_ssz_fixed_hash!(H160, H256, Bloom);
+
+impl SszFixed for U256 {
+    const SSZ_LEN: usize = 32;
+
+    /// Integers are encoded in little endian.
+    fn ssz_append_fixed(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.to_le_bytes::<32>());
+    }
+
+    fn from_ssz_fixed(bytes: &[u8]) -> Result<Self, DecodeError> {
+        check_len(bytes, Self::SSZ_LEN)?;
+        Ok(U256::from_le_slice(bytes))
+    }
+}
+
+impl SszFixed for U64 {
+    const SSZ_LEN: usize = 8;
+
+    /// Integers are encoded in little endian.
+    fn ssz_append_fixed(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.as_u64().to_le_bytes());
+    }
+
+    fn from_ssz_fixed(bytes: &[u8]) -> Result<Self, DecodeError> {
+        Ok(u64::from_ssz_bytes(bytes)?.into())
+    }
+}
+
+impl Encode for Bloom {
+    fn is_ssz_fixed_len() -> bool {
+        true
+    }
+
+    fn ssz_fixed_len() -> usize {
+        Self::SSZ_LEN
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        self.ssz_append_fixed(buf)
+    }
+
+    fn ssz_bytes_len(&self) -> usize {
+        Self::SSZ_LEN
+    }
+}
+
+impl Decode for Bloom {
+    fn is_ssz_fixed_len() -> bool {
+        true
+    }
+
+    fn ssz_fixed_len() -> usize {
+        Self::SSZ_LEN
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        Self::from_ssz_fixed(bytes)
+    }
+}
+
+/// Encoded as `ByteList`, the maximum length is not enforced.
+impl Encode for Bytes {
+    fn is_ssz_fixed_len() -> bool {
+        false
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(self)
+    }
+
+    fn ssz_bytes_len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl Decode for Bytes {
+    fn is_ssz_fixed_len() -> bool {
+        false
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        Ok(Bytes::from(bytes.to_vec()))
+    }
+}
+
+/// The length of an encoded [Withdrawal]: `index`, `validator_index`, `address` and `amount`.
+const WITHDRAWAL_SSZ_LEN: usize = 8 + 8 + 20 + 8;
+
+impl Encode for Withdrawal {
+    fn is_ssz_fixed_len() -> bool {
+        true
+    }
+
+    fn ssz_fixed_len() -> usize {
+        WITHDRAWAL_SSZ_LEN
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        let mut encoder = SszEncoder::container(buf, WITHDRAWAL_SSZ_LEN);
+        encoder.append(&self.index);
+        encoder.append(&self.validator_index);
+        self.address.ssz_append_field(&mut encoder);
+        encoder.append(&self.amount);
+        encoder.finalize();
+    }
+
+    fn ssz_bytes_len(&self) -> usize {
+        WITHDRAWAL_SSZ_LEN
+    }
+}
+
+impl Decode for Withdrawal {
+    fn is_ssz_fixed_len() -> bool {
+        true
+    }
+
+    fn ssz_fixed_len() -> usize {
+        WITHDRAWAL_SSZ_LEN
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let mut builder = SszDecoderBuilder::new(bytes);
+        builder.register_type::<u64>()?;
+        builder.register_type::<u64>()?;
+        Address::ssz_register_field(&mut builder)?;
+        builder.register_type::<u64>()?;
+
+        let mut decoder = builder.build()?;
+        Ok(Withdrawal {
+            index: decoder.decode_next()?,
+            validator_index: decoder.decode_next()?,
+            address: Address::ssz_decode_field(&mut decoder)?,
+            amount: decoder.decode_next()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex_literal::hex;
+
+    #[test]
+    fn withdrawal_ssz() {
+        let withdrawal = Withdrawal {
+            index: 1,
+            validator_index: 2,
+            address: Address::repeat_byte(0xaa),
+            amount: 0x0102,
+        };
+
+        let encoded = withdrawal.as_ssz_bytes();
+        assert_eq!(
+            encoded,
+            hex!("01000000000000000200000000000000aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa0201000000000000")
+        );
+        assert_eq!(Withdrawal::from_ssz_bytes(&encoded).unwrap(), withdrawal);
+        assert!(Withdrawal::from_ssz_bytes(&encoded[1..]).is_err());
+    }
+
+    #[test]
+    fn u256_ssz_little_endian() {
+        let mut buf = Vec::new();
+        U256::from(0x0102).ssz_append_fixed(&mut buf);
+        assert_eq!(buf[..2], [0x02, 0x01]);
+        assert_eq!(U256::from_ssz_fixed(&buf).unwrap(), U256::from(0x0102));
+    }
+}
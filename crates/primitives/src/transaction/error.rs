@@ -1,4 +1,5 @@
 use crate::U256;
+use reth_rlp::DecodeError;
 
 /// Represents error variants that can happen when trying to validate a
 /// [Transaction](crate::Transaction)
@@ -52,3 +53,34 @@ pub enum InvalidTransactionError {
     #[error("Transaction signer has bytecode set.")]
     SignerAccountHasBytecode,
 }
+
+/// Represents error variants that can happen when decoding an enveloped
+/// [TransactionSigned](crate::TransactionSigned) from bytes.
+///
+/// See [TransactionSigned::try_decode_enveloped](crate::TransactionSigned::try_decode_enveloped).
+#[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
+pub enum TransactionDecodeError {
+    /// The input is empty.
+    #[error("Empty transaction input.")]
+    Empty,
+    /// The input starts with a type byte of an unknown EIP-2718 transaction type.
+    #[error("Unsupported transaction type {0}.")]
+    UnsupportedType(u8),
+    /// The transaction fields are not encoded as an RLP list.
+    #[error("Transaction fields must be encoded as a list.")]
+    ExpectedList,
+    /// The fields of the transaction don't fill the payload length of the list header.
+    #[error("Transaction payload length mismatch: expected {expected} bytes, decoded {decoded}.")]
+    PayloadLengthMismatch {
+        /// The length of the transaction according to its list header.
+        expected: usize,
+        /// The number of bytes consumed by decoding the transaction fields.
+        decoded: usize,
+    },
+    /// The input has bytes left after the transaction.
+    #[error("{0} trailing bytes after the transaction.")]
+    TrailingBytes(usize),
+    /// A transaction field is not valid RLP.
+    #[error("Invalid transaction RLP: {0}")]
+    Rlp(#[from] DecodeError),
+}
@@ -10,7 +10,7 @@ pub use eip2930::TxEip2930;
 pub use eip4844::{
     BlobTransaction, BlobTransactionSidecar, BlobTransactionValidationError, TxEip4844,
 };
-pub use error::InvalidTransactionError;
+pub use error::{InvalidTransactionError, TransactionDecodeError};
 pub use legacy::TxLegacy;
 pub use meta::TransactionMeta;
 use once_cell::sync::Lazy;
@@ -18,6 +18,7 @@ pub use pooled::{PooledTransactionsElement, PooledTransactionsElementEcRecovered
 use rayon::prelude::{IntoParallelIterator, ParallelIterator};
 use reth_codecs::{add_arbitrary_tests, derive_arbitrary, Compact};
 use reth_rlp::{Decodable, DecodeError, Encodable, Header, EMPTY_LIST_CODE, EMPTY_STRING_CODE};
+pub use sender_cache::{SenderRecoveryCache, DEFAULT_SENDER_CACHE_MAX_ENTRIES};
 use serde::{Deserialize, Serialize};
 pub use signature::Signature;
 use std::mem;
@@ -33,6 +34,7 @@ mod error;
 mod legacy;
 mod meta;
 mod pooled;
+mod sender_cache;
 mod signature;
 mod tx_type;
 pub(crate) mod util;
@@ -333,6 +335,16 @@ impl Transaction {
         Encodable::encode(self, out);
     }
 
+    /// Returns the encoding of the transaction _without_ the signature, this is the preimage of
+    /// the [signature hash](Self::signature_hash).
+    ///
+    /// See also [Transaction::encode_without_signature]
+    pub fn encoded_for_signing(&self) -> Bytes {
+        let mut buf = BytesMut::new();
+        self.encode_without_signature(&mut buf);
+        buf.freeze().into()
+    }
+
     /// Inner encoding function that is used for both rlp [`Encodable`] trait and for calculating
     /// hash that for eip2718 does not require rlp header
     pub fn encode_with_signature(
@@ -793,6 +805,13 @@ impl TransactionSigned {
         self.signature.recover_signer(signature_hash)
     }
 
+    /// Returns the signer from the [SenderRecoveryCache] or recovers it from signature and hash.
+    ///
+    /// Returns `None` if the transaction's signature is invalid, see also [Self::recover_signer].
+    pub fn recover_signer_cached(&self, cache: &SenderRecoveryCache) -> Option<Address> {
+        cache.get_or_recover(&self.hash, || self.recover_signer())
+    }
+
     /// Recovers a list of signers from a transaction list iterator
     ///
     /// Returns `None`, if some transaction's signature is invalid, see also
@@ -808,6 +827,23 @@ impl TransactionSigned {
         }
     }
 
+    /// Like [Self::recover_signers], but takes the signers that are in the [SenderRecoveryCache]
+    /// from the cache.
+    pub fn recover_signers_cached<'a, T>(
+        txes: T,
+        num_txes: usize,
+        cache: &SenderRecoveryCache,
+    ) -> Option<Vec<Address>>
+    where
+        T: IntoParallelIterator<Item = &'a Self> + IntoIterator<Item = &'a Self> + Send,
+    {
+        if num_txes < *PARALLEL_SENDER_RECOVERY_THRESHOLD {
+            txes.into_iter().map(|tx| tx.recover_signer_cached(cache)).collect()
+        } else {
+            txes.into_par_iter().map(|tx| tx.recover_signer_cached(cache)).collect()
+        }
+    }
+
     /// Consumes the type, recover signer and return [`TransactionSignedEcRecovered`]
     ///
     /// Returns `None` if the transaction's signature is invalid, see also [Self::recover_signer].
@@ -997,6 +1033,49 @@ impl TransactionSigned {
             TransactionSigned::decode_enveloped_typed_transaction(&mut data)
         }
     }
+
+    /// Decodes the "raw" format of transaction, like [TransactionSigned::decode_enveloped], but
+    /// requires the input to be exactly one well-formed transaction.
+    ///
+    /// In addition to the RLP errors of the fields, this rejects unknown transaction types,
+    /// transactions whose fields don't match the length of their list header and trailing bytes
+    /// with a dedicated [TransactionDecodeError] variant.
+    pub fn try_decode_enveloped(data: &[u8]) -> Result<Self, TransactionDecodeError> {
+        let first = *data.first().ok_or(TransactionDecodeError::Empty)?;
+        let is_legacy = first >= EMPTY_LIST_CODE;
+        if !is_legacy &&
+            !matches!(first, EIP2930_TX_TYPE_ID | EIP1559_TX_TYPE_ID | EIP4844_TX_TYPE_ID)
+        {
+            return Err(TransactionDecodeError::UnsupportedType(first))
+        }
+
+        // check the list header up front, so the fields are never decoded past its end
+        let mut fields = if is_legacy { data } else { &data[1..] };
+        let header = Header::decode(&mut fields)?;
+        if !header.list {
+            return Err(TransactionDecodeError::ExpectedList)
+        }
+        let expected = data.len() - fields.len() + header.payload_length;
+        if expected > data.len() {
+            return Err(DecodeError::InputTooShort.into())
+        }
+
+        let mut buf = data;
+        let transaction = if is_legacy {
+            TransactionSigned::decode_rlp_legacy_transaction(&mut buf)?
+        } else {
+            TransactionSigned::decode_enveloped_typed_transaction(&mut buf)?
+        };
+
+        let decoded = data.len() - buf.len();
+        if decoded != expected {
+            return Err(TransactionDecodeError::PayloadLengthMismatch { expected, decoded })
+        }
+        if !buf.is_empty() {
+            return Err(TransactionDecodeError::TrailingBytes(buf.len()))
+        }
+        Ok(transaction)
+    }
 }
 
 impl From<TransactionSignedEcRecovered> for TransactionSigned {
@@ -1189,7 +1268,8 @@ mod tests {
             signature::Signature, TransactionKind, TxEip1559, TxLegacy,
             PARALLEL_SENDER_RECOVERY_THRESHOLD,
         },
-        Address, Bytes, Transaction, TransactionSigned, TransactionSignedEcRecovered, H256, U256,
+        Address, Bytes, Transaction, TransactionDecodeError, TransactionSigned,
+        TransactionSignedEcRecovered, H256, U256,
     };
     use bytes::BytesMut;
     use ethers_core::utils::hex;
@@ -1458,4 +1538,83 @@ mod tests {
             assert_eq!(parallel_senders, seq_senders);
         }
     }
+
+    #[test]
+    fn transaction_bitflag_unused_bits() {
+        use crate::transaction::{
+            eip1559::TxEip1559Flags, eip2930::TxEip2930Flags, eip4844::TxEip4844Flags,
+            legacy::TxLegacyFlags,
+        };
+
+        assert_eq!(TxLegacyFlags::bitflag_unused_bits(), 4);
+        assert_eq!(TxEip2930Flags::bitflag_unused_bits(), 1);
+        assert_eq!(TxEip1559Flags::bitflag_unused_bits(), 4);
+        assert_eq!(TxEip4844Flags::bitflag_unused_bits(), 7);
+    }
+
+    #[test]
+    fn try_decode_enveloped_errors() {
+        assert_eq!(
+            TransactionSigned::try_decode_enveloped(&[]),
+            Err(TransactionDecodeError::Empty)
+        );
+        assert_eq!(
+            TransactionSigned::try_decode_enveloped(&[0x05, 0xc0]),
+            Err(TransactionDecodeError::UnsupportedType(0x05))
+        );
+        assert_eq!(
+            TransactionSigned::try_decode_enveloped(&[0x02, 0x80]),
+            Err(TransactionDecodeError::ExpectedList)
+        );
+
+        let raw = hex::decode("02f872041a8459682f008459682f0d8252089461815774383099e24810ab832a5b2a5425c154d58829a2241af62c000080c001a059e6b67f48fb32e7e570dfb11e042b5ad2e55e3ce3ce9cd989c7e06e07feeafda0016b83f4f980694ed2eee4d10667242b1f40dc406901b34125b008d334d47469").unwrap();
+        let tx = TransactionSigned::try_decode_enveloped(&raw).unwrap();
+        assert_eq!(tx.envelope_encoded()[..], raw[..]);
+
+        let mut trailing = raw.clone();
+        trailing.push(0x00);
+        assert_eq!(
+            TransactionSigned::try_decode_enveloped(&trailing),
+            Err(TransactionDecodeError::TrailingBytes(1))
+        );
+
+        // list header claims one more byte than the fields use
+        let mut padded = raw.clone();
+        padded[2] += 1;
+        padded.push(0x80);
+        assert_eq!(
+            TransactionSigned::try_decode_enveloped(&padded),
+            Err(TransactionDecodeError::PayloadLengthMismatch {
+                expected: raw.len() + 1,
+                decoded: raw.len()
+            })
+        );
+
+        // list header claims more bytes than the input has
+        let mut truncated = raw;
+        truncated[2] += 1;
+        assert!(matches!(
+            TransactionSigned::try_decode_enveloped(&truncated),
+            Err(TransactionDecodeError::Rlp(_))
+        ));
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn enveloped_roundtrip(tx in proptest::prelude::any::<TransactionSigned>()) {
+            let encoded = tx.envelope_encoded();
+            let decoded = TransactionSigned::try_decode_enveloped(&encoded).unwrap();
+            proptest::prop_assert_eq!(&decoded, &tx);
+            proptest::prop_assert_eq!(decoded.envelope_encoded(), encoded);
+            proptest::prop_assert_eq!(
+                decoded.transaction.encoded_for_signing(),
+                tx.transaction.encoded_for_signing()
+            );
+        }
+
+        #[test]
+        fn try_decode_enveloped_arbitrary_bytes(data in proptest::collection::vec(proptest::prelude::any::<u8>(), 0..512)) {
+            let _ = TransactionSigned::try_decode_enveloped(&data);
+        }
+    }
 }
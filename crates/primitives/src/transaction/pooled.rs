@@ -1,16 +1,31 @@
 //! Defines the types for blob transactions, legacy, and other EIP-2718 transactions included in a
 //! response to `GetPooledTransactions`.
 use crate::{
-    Address, BlobTransaction, Bytes, Signature, Transaction, TransactionSigned,
+    transaction::PARALLEL_SENDER_RECOVERY_THRESHOLD, Address, BlobTransaction,
+    BlobTransactionSidecar, Bytes, Signature, Transaction, TransactionSigned,
     TransactionSignedEcRecovered, TxEip1559, TxEip2930, TxHash, TxLegacy, EIP4844_TX_TYPE_ID, H256,
 };
-use bytes::Buf;
+use bytes::{Buf, BytesMut};
 use derive_more::{AsRef, Deref};
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
 use reth_rlp::{Decodable, DecodeError, Encodable, Header, EMPTY_LIST_CODE};
 use serde::{Deserialize, Serialize};
 
 /// A response to `GetPooledTransactions`. This can include either a blob transaction, or a
 /// non-4844 signed transaction.
+///
+/// This is the _pooled_ form of a transaction that is used by the network and the transaction
+/// pool, as opposed to the _consensus_ form [TransactionSigned] that is included in blocks and
+/// stored in the database. The two forms only differ for EIP-4844 transactions, whose pooled form
+/// also carries the [BlobTransactionSidecar].
+///
+/// Conversions are explicit, so a blob transaction can't lose or be given an empty sidecar by
+/// accident:
+///  - [TransactionSigned] -> pooled: [PooledTransactionsElement::try_from_broadcast] for non-4844
+///    transactions and [PooledTransactionsElement::try_from_blob_transaction] for EIP-4844
+///    transactions.
+///  - pooled -> [TransactionSigned]: [PooledTransactionsElement::into_transaction], which drops the
+///    sidecar.
 // TODO: redo arbitrary for this encoding - the previous encoding was incorrect
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PooledTransactionsElement {
@@ -51,10 +66,28 @@ impl PooledTransactionsElement {
     /// [BlobTransaction] are disallowed from being propagated, hence this returns an error if the
     /// `tx` is [Transaction::Eip4844]
     pub fn try_from_broadcast(tx: TransactionSigned) -> Result<Self, TransactionSigned> {
-        if tx.is_eip4844() {
-            return Err(tx)
+        let TransactionSigned { transaction, signature, hash } = tx;
+        match transaction {
+            Transaction::Legacy(tx) => Ok(Self::Legacy { transaction: tx, signature, hash }),
+            Transaction::Eip2930(tx) => Ok(Self::Eip2930 { transaction: tx, signature, hash }),
+            Transaction::Eip1559(tx) => Ok(Self::Eip1559 { transaction: tx, signature, hash }),
+            tx @ Transaction::Eip4844(_) => {
+                Err(TransactionSigned { transaction: tx, signature, hash })
+            }
         }
-        Ok(tx.into())
+    }
+
+    /// Tries to convert an EIP-4844 [TransactionSigned] and its [BlobTransactionSidecar] into a
+    /// [PooledTransactionsElement::BlobTransaction].
+    ///
+    /// Returns an error if the `tx` is not [Transaction::Eip4844].
+    pub fn try_from_blob_transaction(
+        tx: TransactionSigned,
+        sidecar: BlobTransactionSidecar,
+    ) -> Result<Self, TransactionSigned> {
+        BlobTransaction::try_from_signed(tx, sidecar)
+            .map(Self::BlobTransaction)
+            .map_err(|(tx, _)| tx)
     }
 
     /// Heavy operation that return signature hash over rlp encoded transaction.
@@ -88,6 +121,19 @@ impl PooledTransactionsElement {
         }
     }
 
+    /// Returns the max priority fee per gas if the transaction is an EIP-1559 or EIP-4844
+    /// transaction, and otherwise the gas price.
+    ///
+    /// This is cheap and can be checked before the signer is recovered.
+    pub fn priority_fee_or_price(&self) -> u128 {
+        match self {
+            Self::Legacy { transaction, .. } => transaction.gas_price,
+            Self::Eip2930 { transaction, .. } => transaction.gas_price,
+            Self::Eip1559 { transaction, .. } => transaction.max_priority_fee_per_gas,
+            Self::BlobTransaction(blob_tx) => blob_tx.transaction.max_priority_fee_per_gas,
+        }
+    }
+
     /// Recover signer from signature and hash.
     ///
     /// Returns `None` if the transaction's signature is invalid, see also [Self::recover_signer].
@@ -107,6 +153,20 @@ impl PooledTransactionsElement {
         }
     }
 
+    /// Recovers the signers of a batch of transactions, in parallel if the batch is large enough.
+    ///
+    /// Returns one result per transaction, in the same order, see also
+    /// [Self::try_into_ecrecovered].
+    pub fn try_recover_batch(
+        transactions: Vec<Self>,
+    ) -> Vec<Result<PooledTransactionsElementEcRecovered, Self>> {
+        if transactions.len() < *PARALLEL_SENDER_RECOVERY_THRESHOLD {
+            transactions.into_iter().map(Self::try_into_ecrecovered).collect()
+        } else {
+            transactions.into_par_iter().map(Self::try_into_ecrecovered).collect()
+        }
+    }
+
     /// Decodes the "raw" format of transaction (e.g. `eth_sendRawTransaction`).
     ///
     /// The raw transaction is either a legacy transaction or EIP-2718 typed transaction
@@ -179,13 +239,49 @@ impl PooledTransactionsElement {
         }
     }
 
+    /// Returns the enveloped encoded pooled transaction.
+    ///
+    /// See also [PooledTransactionsElement::encode_enveloped]
+    pub fn envelope_encoded(&self) -> bytes::Bytes {
+        let mut buf = BytesMut::new();
+        self.encode_enveloped(&mut buf);
+        buf.freeze()
+    }
+
+    /// Encodes the transaction into the "raw" format (e.g. `eth_sendRawTransaction`), this is the
+    /// inverse of [PooledTransactionsElement::decode_enveloped].
+    ///
+    /// This is the same as [TransactionSigned::encode_enveloped] except for EIP-4844 transactions,
+    /// which are encoded with their sidecar:
+    /// `tx_type (0x03) || rlp([transaction_payload_body, blobs, commitments, proofs])`
+    pub fn encode_enveloped(&self, out: &mut dyn bytes::BufMut) {
+        match self {
+            Self::Legacy { transaction, signature, .. } => {
+                transaction.encode_with_signature(signature, out)
+            }
+            Self::Eip2930 { transaction, signature, .. } => {
+                transaction.encode_with_signature(signature, out, false)
+            }
+            Self::Eip1559 { transaction, signature, .. } => {
+                transaction.encode_with_signature(signature, out, false)
+            }
+            Self::BlobTransaction(blob_tx) => blob_tx.encode_with_type_inner(out, false),
+        }
+    }
+
+    /// Returns `true` if the transaction is an EIP-4844 blob transaction.
+    pub fn is_eip4844(&self) -> bool {
+        matches!(self, Self::BlobTransaction(_))
+    }
+
     /// Create [`TransactionSignedEcRecovered`] by converting this transaction into
     /// [`TransactionSigned`] and [`Address`] of the signer.
     pub fn into_ecrecovered_transaction(self, signer: Address) -> TransactionSignedEcRecovered {
         TransactionSignedEcRecovered::from_signed_transaction(self.into_transaction(), signer)
     }
 
-    /// Returns the inner [TransactionSigned].
+    /// Returns the consensus form of the transaction, for EIP-4844 transactions the sidecar is
+    /// dropped.
     pub fn into_transaction(self) -> TransactionSigned {
         match self {
             Self::Legacy { transaction, signature, hash } => {
@@ -341,35 +437,6 @@ impl Decodable for PooledTransactionsElement {
     }
 }
 
-impl From<TransactionSigned> for PooledTransactionsElement {
-    /// Converts from a [TransactionSigned] to a [PooledTransactionsElement].
-    ///
-    /// NOTE: For EIP-4844 transactions, this will return an empty sidecar.
-    fn from(tx: TransactionSigned) -> Self {
-        let TransactionSigned { transaction, signature, hash } = tx;
-        match transaction {
-            Transaction::Legacy(tx) => {
-                PooledTransactionsElement::Legacy { transaction: tx, signature, hash }
-            }
-            Transaction::Eip2930(tx) => {
-                PooledTransactionsElement::Eip2930 { transaction: tx, signature, hash }
-            }
-            Transaction::Eip1559(tx) => {
-                PooledTransactionsElement::Eip1559 { transaction: tx, signature, hash }
-            }
-            Transaction::Eip4844(tx) => {
-                PooledTransactionsElement::BlobTransaction(BlobTransaction {
-                    transaction: tx,
-                    signature,
-                    hash,
-                    // This is empty - just for the conversion!
-                    sidecar: Default::default(),
-                })
-            }
-        }
-    }
-}
-
 /// A signed pooled transaction with recovered signer.
 #[derive(Debug, Clone, PartialEq, Eq, AsRef, Deref)]
 pub struct PooledTransactionsElementEcRecovered {
@@ -415,10 +482,15 @@ impl PooledTransactionsElementEcRecovered {
     }
 }
 
-impl From<TransactionSignedEcRecovered> for PooledTransactionsElementEcRecovered {
-    fn from(tx: TransactionSignedEcRecovered) -> Self {
+impl TryFrom<TransactionSignedEcRecovered> for PooledTransactionsElementEcRecovered {
+    type Error = TransactionSignedEcRecovered;
+
+    /// Converts a non-4844 transaction, see [PooledTransactionsElement::try_from_broadcast].
+    fn try_from(tx: TransactionSignedEcRecovered) -> Result<Self, Self::Error> {
         let signer = tx.signer;
-        let transaction = tx.signed_transaction.into();
-        Self { transaction, signer }
+        match PooledTransactionsElement::try_from_broadcast(tx.signed_transaction) {
+            Ok(transaction) => Ok(Self { transaction, signer }),
+            Err(tx) => Err(TransactionSignedEcRecovered::from_signed_transaction(tx, signer)),
+        }
     }
 }
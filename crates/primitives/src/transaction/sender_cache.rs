@@ -0,0 +1,117 @@
+//! A shared cache of recovered transaction senders.
+
+use crate::{Address, TxHash};
+use parking_lot::Mutex;
+use schnellru::{ByLength, LruMap};
+use std::sync::Arc;
+
+/// The default number of senders kept in the [SenderRecoveryCache].
+pub const DEFAULT_SENDER_CACHE_MAX_ENTRIES: u32 = 100_000;
+
+/// The number of shards of the [SenderRecoveryCache].
+///
+/// Senders are recovered in parallel, sharding the cache keeps the threads from contending for a
+/// single lock.
+const SENDER_CACHE_SHARDS: usize = 16;
+
+/// An LRU cache of recovered senders, keyed by transaction hash.
+///
+/// The transaction pool inserts the senders of the transactions it accepts, so the signature of a
+/// transaction isn't recovered again when a block that includes it is executed. The cache is
+/// cheap to clone, clones share the same entries.
+///
+/// The hash of a transaction commits to its signature, so an entry never becomes stale.
+#[derive(Clone)]
+pub struct SenderRecoveryCache {
+    shards: Arc<[Mutex<LruMap<TxHash, Address, ByLength>>]>,
+}
+
+impl SenderRecoveryCache {
+    /// Creates a new cache that keeps at most `max_entries` senders.
+    pub fn new(max_entries: u32) -> Self {
+        let shard_entries = (max_entries as usize + SENDER_CACHE_SHARDS - 1) / SENDER_CACHE_SHARDS;
+        let shards = (0..SENDER_CACHE_SHARDS)
+            .map(|_| Mutex::new(LruMap::new(ByLength::new(shard_entries as u32))))
+            .collect();
+        Self { shards }
+    }
+
+    /// Returns the shard that holds the sender of the given transaction.
+    fn shard(&self, hash: &TxHash) -> &Mutex<LruMap<TxHash, Address, ByLength>> {
+        &self.shards[hash[0] as usize % SENDER_CACHE_SHARDS]
+    }
+
+    /// Returns the cached sender of the given transaction.
+    pub fn get(&self, hash: &TxHash) -> Option<Address> {
+        self.shard(hash).lock().get(hash).copied()
+    }
+
+    /// Inserts the recovered sender of the given transaction.
+    pub fn insert(&self, hash: TxHash, sender: Address) {
+        self.shard(&hash).lock().insert(hash, sender);
+    }
+
+    /// Returns the cached sender of the given transaction, or recovers it with the given closure.
+    ///
+    /// Recovered senders are not inserted, only transactions accepted by the pool are cached so
+    /// that invalid transactions can't evict useful entries.
+    pub fn get_or_recover(
+        &self,
+        hash: &TxHash,
+        recover: impl FnOnce() -> Option<Address>,
+    ) -> Option<Address> {
+        self.get(hash).or_else(recover)
+    }
+
+    /// Returns the number of cached senders.
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|shard| shard.lock().len()).sum()
+    }
+
+    /// Returns `true` if the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for SenderRecoveryCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_SENDER_CACHE_MAX_ENTRIES)
+    }
+}
+
+impl std::fmt::Debug for SenderRecoveryCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SenderRecoveryCache").field("len", &self.len()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounded_sender_cache() {
+        let cache = SenderRecoveryCache::new(2 * SENDER_CACHE_SHARDS as u32);
+        // the hashes are in the same shard
+        let (a, b, c) =
+            (TxHash::from_low_u64_be(1), TxHash::from_low_u64_be(2), TxHash::from_low_u64_be(3));
+        cache.insert(a, Address::repeat_byte(1));
+        cache.insert(b, Address::repeat_byte(2));
+        assert_eq!(cache.get(&a), Some(Address::repeat_byte(1)));
+
+        // `b` is the least recently used entry
+        cache.insert(c, Address::repeat_byte(3));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(&b), None);
+        assert_eq!(
+            cache.get_or_recover(&b, || Some(Address::repeat_byte(4))),
+            Some(Address::repeat_byte(4))
+        );
+        assert_eq!(cache.get_or_recover(&c, || None), Some(Address::repeat_byte(3)));
+
+        // clones share the entries
+        cache.clone().insert(b, Address::repeat_byte(2));
+        assert_eq!(cache.get(&b), Some(Address::repeat_byte(2)));
+    }
+}
@@ -0,0 +1,39 @@
+use crate::{trie::Nibbles, Bytes};
+use std::collections::BTreeMap;
+
+/// Retains the RLP encoded trie nodes on the paths to the target keys while the
+/// [HashBuilder](super::HashBuilder) computes the root.
+///
+/// The retained nodes are keyed by their path in the trie. A target can be a full key to prove
+/// its inclusion or exclusion, or the path of a single node.
+#[derive(Debug, Default)]
+pub struct ProofRetainer {
+    /// The keys to retain the nodes on the path to.
+    targets: Vec<Nibbles>,
+    /// The retained nodes by path.
+    proofs: BTreeMap<Nibbles, Bytes>,
+}
+
+impl ProofRetainer {
+    /// Creates a new retainer for the given targets.
+    pub fn new(targets: Vec<Nibbles>) -> Self {
+        Self { targets, proofs: BTreeMap::default() }
+    }
+
+    /// Returns `true` if the node at the given path is on the path to any of the targets.
+    pub fn matches(&self, path: &Nibbles) -> bool {
+        path.is_empty() || self.targets.iter().any(|target| target.has_prefix(path))
+    }
+
+    /// Retains the RLP encoded node at the given path if it matches any of the targets.
+    pub fn retain(&mut self, path: &Nibbles, rlp: &[u8]) {
+        if self.matches(path) {
+            self.proofs.insert(path.clone(), Bytes::from(rlp.to_vec()));
+        }
+    }
+
+    /// Returns the retained nodes by path.
+    pub fn into_proofs(self) -> BTreeMap<Nibbles, Bytes> {
+        self.proofs
+    }
+}
@@ -219,6 +219,32 @@ impl<DB: Database> Pruner<DB> {
             );
         }
 
+        if let Some((to_block, prune_mode)) =
+            self.modes.prune_target_block_preimages(tip_block_number)?
+        {
+            trace!(
+                target: "pruner",
+                prune_part = ?PrunePart::Preimages,
+                %to_block,
+                ?prune_mode,
+                "Got target block to prune"
+            );
+
+            let part_start = Instant::now();
+            let part_done = self.prune_preimages(&provider, to_block, prune_mode)?;
+            done = done && part_done;
+            self.metrics
+                .get_prune_part_metrics(PrunePart::Preimages)
+                .duration_seconds
+                .record(part_start.elapsed())
+        } else {
+            trace!(
+                target: "pruner",
+                prune_part = ?PrunePart::Preimages,
+                "No target block to prune"
+            );
+        }
+
         provider.commit()?;
         self.last_pruned_block_number = Some(tip_block_number);
 
@@ -787,6 +813,72 @@ impl<DB: Database> Pruner<DB> {
         Ok(done)
     }
 
+    /// Prune preimages up to the provided block, inclusive.
+    ///
+    /// Preimages that were changed again after the provided block are kept.
+    #[instrument(level = "trace", skip(self, provider), target = "pruner")]
+    fn prune_preimages(
+        &self,
+        provider: &DatabaseProviderRW<'_, DB>,
+        to_block: BlockNumber,
+        prune_mode: PruneMode,
+    ) -> PrunerResult {
+        let range = match self.get_next_block_range_from_checkpoint(
+            provider,
+            PrunePart::Preimages,
+            to_block,
+        )? {
+            Some(range) => range,
+            None => {
+                trace!(target: "pruner", "No preimages to prune");
+                return Ok(true)
+            }
+        };
+        let range_end = *range.end();
+
+        let mut last_changeset_pruned_block = None;
+        let mut hashes = Vec::new();
+        let (rows, done) = provider.prune_table_with_range::<tables::PreimageChangeSet>(
+            range,
+            self.batch_sizes.preimages(self.min_block_interval),
+            |_| false,
+            |(block_number, hash)| {
+                last_changeset_pruned_block = Some(block_number);
+                hashes.push(hash);
+            },
+        )?;
+        trace!(target: "pruner", %rows, %done, "Pruned preimages (changesets)");
+
+        let mut cursor = provider.tx_ref().cursor_write::<tables::Preimages>()?;
+        let mut deleted = 0;
+        for hash in hashes {
+            if let Some((_, preimage)) = cursor.seek_exact(hash)? {
+                if preimage.block_number <= to_block {
+                    cursor.delete_current()?;
+                    deleted += 1;
+                }
+            }
+        }
+        trace!(target: "pruner", %deleted, %done, "Pruned preimages");
+
+        let last_changeset_pruned_block = last_changeset_pruned_block
+            // If there's more preimage changesets to prune, set the checkpoint block number to
+            // previous, so we could finish pruning its preimage changesets on the next run.
+            .map(|block_number| if done { block_number } else { block_number.saturating_sub(1) })
+            .unwrap_or(range_end);
+
+        provider.save_prune_checkpoint(
+            PrunePart::Preimages,
+            PruneCheckpoint {
+                block_number: Some(last_changeset_pruned_block),
+                tx_number: None,
+                prune_mode,
+            },
+        )?;
+
+        Ok(done)
+    }
+
     /// Prune history indices up to the provided block, inclusive.
     ///
     /// Returns total number of processed (walked) and deleted entities.
@@ -900,7 +992,11 @@ mod tests {
         Itertools,
     };
     use reth_db::{
-        cursor::DbCursorRO, tables, test_utils::create_test_rw_db, transaction::DbTx,
+        cursor::DbCursorRO,
+        models::StoredPreimage,
+        tables,
+        test_utils::create_test_rw_db,
+        transaction::{DbTx, DbTxMut},
         BlockNumberList,
     };
     use reth_interfaces::test_utils::{
@@ -911,7 +1007,7 @@ mod tests {
         },
     };
     use reth_primitives::{
-        BlockNumber, PruneBatchSizes, PruneCheckpoint, PruneMode, PruneModes, PrunePart,
+        keccak256, BlockNumber, PruneBatchSizes, PruneCheckpoint, PruneMode, PruneModes, PrunePart,
         ReceiptsLogPruneConfig, TxNumber, H256, MAINNET,
     };
     use reth_provider::{PruneCheckpointReader, TransactionsProvider};
@@ -1579,4 +1675,57 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn prune_preimages() {
+        let tx = TestTransaction::default();
+
+        let preimage = |byte: u8, block_number: BlockNumber| {
+            let preimage = H256::repeat_byte(byte);
+            (
+                keccak256(preimage),
+                StoredPreimage { block_number, preimage: preimage.as_bytes().to_vec().into() },
+            )
+        };
+        // Changed only before the prune target
+        let (hash_a, preimage_a) = preimage(0xaa, 1);
+        // Changed before and after the prune target
+        let (hash_b, preimage_b) = preimage(0xbb, 5);
+        // Changed only at the prune target
+        let (hash_c, preimage_c) = preimage(0xcc, 3);
+
+        tx.commit(|tx| {
+            tx.put::<tables::Preimages>(hash_a, preimage_a)?;
+            tx.put::<tables::Preimages>(hash_b, preimage_b.clone())?;
+            tx.put::<tables::Preimages>(hash_c, preimage_c)?;
+            tx.put::<tables::PreimageChangeSet>(1, hash_a)?;
+            tx.put::<tables::PreimageChangeSet>(1, hash_b)?;
+            tx.put::<tables::PreimageChangeSet>(3, hash_c)?;
+            tx.put::<tables::PreimageChangeSet>(5, hash_b)?;
+            Ok(())
+        })
+        .expect("insert preimages");
+
+        let to_block = 3;
+        let prune_mode = PruneMode::Before(to_block + 1);
+        let pruner = Pruner::new(
+            tx.inner_raw(),
+            MAINNET.clone(),
+            1,
+            PruneModes { preimages: Some(prune_mode), ..Default::default() },
+            PruneBatchSizes::default(),
+        );
+
+        let provider = tx.inner_rw();
+        let result = pruner.prune_preimages(&provider, to_block, prune_mode);
+        assert_matches!(result, Ok(true));
+        provider.commit().expect("commit");
+
+        assert_eq!(tx.table::<tables::Preimages>().unwrap(), vec![(hash_b, preimage_b)]);
+        assert_eq!(tx.table::<tables::PreimageChangeSet>().unwrap(), vec![(5, hash_b)]);
+        assert_eq!(
+            tx.inner().get_prune_checkpoint(PrunePart::Preimages).unwrap(),
+            Some(PruneCheckpoint { block_number: Some(to_block), tx_number: None, prune_mode })
+        );
+    }
 }
@@ -21,6 +21,9 @@
 /// An inspector implementation for an EIP2930 Accesslist
 pub mod access_list;
 
+/// An inspector for counting executed opcodes
+pub mod opcode;
+
 /// An inspector stack abstracting the implementation details of
 /// each inspector and allowing to hook on block/transaction execution,
 /// used in the main RETH executor.
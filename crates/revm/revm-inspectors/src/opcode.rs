@@ -0,0 +1,78 @@
+use revm::{
+    interpreter::{opcode::OPCODE_JUMPMAP, InstructionResult, Interpreter},
+    Database, EVMData, Inspector,
+};
+
+/// An [Inspector] that counts how often each opcode is executed.
+#[derive(Clone, Debug)]
+pub struct OpcodeCounter {
+    counts: [u64; 256],
+}
+
+impl OpcodeCounter {
+    /// Returns how often the given opcode was executed.
+    pub fn count(&self, opcode: u8) -> u64 {
+        self.counts[opcode as usize]
+    }
+
+    /// Returns the name and count of all executed opcodes, sorted by count in descending order.
+    pub fn hotspots(&self) -> Vec<(&'static str, u64)> {
+        let mut hotspots = self
+            .counts
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(opcode, count)| (OPCODE_JUMPMAP[opcode].unwrap_or("UNKNOWN"), *count))
+            .collect::<Vec<_>>();
+        hotspots.sort_unstable_by(|a, b| b.1.cmp(&a.1));
+        hotspots
+    }
+
+    /// Adds the counts of the other counter to this counter.
+    pub fn merge(&mut self, other: &Self) {
+        for (count, other) in self.counts.iter_mut().zip(other.counts.iter()) {
+            *count += other;
+        }
+    }
+}
+
+impl Default for OpcodeCounter {
+    fn default() -> Self {
+        Self { counts: [0; 256] }
+    }
+}
+
+impl<DB> Inspector<DB> for OpcodeCounter
+where
+    DB: Database,
+{
+    fn step(
+        &mut self,
+        interp: &mut Interpreter,
+        _data: &mut EVMData<'_, DB>,
+        _is_static: bool,
+    ) -> InstructionResult {
+        self.counts[interp.current_opcode() as usize] += 1;
+        InstructionResult::Continue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::interpreter::opcode;
+
+    #[test]
+    fn hotspots_are_sorted() {
+        let mut counter = OpcodeCounter::default();
+        counter.counts[opcode::ADD as usize] = 1;
+        counter.counts[opcode::SLOAD as usize] = 3;
+
+        let mut other = OpcodeCounter::default();
+        other.counts[opcode::ADD as usize] = 5;
+        counter.merge(&other);
+
+        assert_eq!(counter.count(opcode::ADD), 6);
+        assert_eq!(counter.hotspots(), vec![("ADD", 6), ("SLOAD", 3)]);
+    }
+}
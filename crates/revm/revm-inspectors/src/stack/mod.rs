@@ -1,5 +1,6 @@
 use std::fmt::Debug;
 
+use crate::opcode::OpcodeCounter;
 use reth_primitives::{bytes::Bytes, Address, TxHash, H256};
 use revm::{
     inspectors::CustomPrintTracer,
@@ -37,6 +38,8 @@ pub enum Hook {
 pub struct InspectorStack {
     /// An inspector that prints the opcode traces to the console.
     pub custom_print_tracer: Option<CustomPrintTracer>,
+    /// An inspector that counts the executed opcodes.
+    pub opcode_counter: Option<OpcodeCounter>,
     /// The provided hook
     pub hook: Hook,
 }
@@ -45,6 +48,7 @@ impl Debug for InspectorStack {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("InspectorStack")
             .field("custom_print_tracer", &self.custom_print_tracer.is_some())
+            .field("opcode_counter", &self.opcode_counter.is_some())
             .field("hook", &self.hook)
             .finish()
     }
@@ -59,6 +63,10 @@ impl InspectorStack {
             stack.custom_print_tracer = Some(CustomPrintTracer::default());
         }
 
+        if config.use_opcode_counter {
+            stack.opcode_counter = Some(OpcodeCounter::default());
+        }
+
         stack
     }
 
@@ -80,6 +88,9 @@ pub struct InspectorStackConfig {
     /// In execution this will print opcode level traces directly to console.
     pub use_printer_tracer: bool,
 
+    /// Enable counting the executed opcodes.
+    pub use_opcode_counter: bool,
+
     /// Hook on a specific block or transaction.
     pub hook: Hook,
 }
@@ -125,7 +136,7 @@ where
         data: &mut EVMData<'_, DB>,
         is_static: bool,
     ) -> InstructionResult {
-        call_inspectors!(inspector, [&mut self.custom_print_tracer], {
+        call_inspectors!(inspector, [&mut self.custom_print_tracer, &mut self.opcode_counter], {
             let status = inspector.step(interpreter, data, is_static);
 
             // Allow inspectors to exit early
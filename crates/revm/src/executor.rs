@@ -4,13 +4,14 @@ use crate::{
     eth_dao_fork::{DAO_HARDFORK_BENEFICIARY, DAO_HARDKFORK_ACCOUNTS},
     into_reth_log,
     stack::{InspectorStack, InspectorStackConfig},
+    system_calls::pre_block_system_calls,
     to_reth_acc,
 };
 use reth_consensus_common::calc;
 use reth_interfaces::executor::{BlockExecutionError, BlockValidationError};
 use reth_primitives::{
     Account, Address, Block, BlockNumber, Bloom, Bytecode, ChainSpec, Hardfork, Header, Receipt,
-    ReceiptWithBloom, TransactionSigned, Withdrawal, H256, U256,
+    ReceiptWithBloom, Transaction, TransactionSigned, Withdrawal, H256, U256,
 };
 use reth_provider::{BlockExecutor, PostState, StateProvider};
 use revm::{
@@ -67,6 +68,11 @@ where
         self
     }
 
+    /// Returns the configured inspectors.
+    pub fn stack(&self) -> &InspectorStack {
+        &self.stack
+    }
+
     /// Gives a reference to the database
     pub fn db(&mut self) -> &mut SubState<DB> {
         self.evm.db().expect("db to not be moved")
@@ -130,6 +136,22 @@ where
         )
     }
 
+    /// Applies the system calls that update system contracts before the transactions of the block
+    /// are executed.
+    fn apply_pre_block_system_calls(
+        &mut self,
+        block: &Block,
+        post_state: &mut PostState,
+    ) -> Result<(), BlockExecutionError> {
+        let chain_spec = self.chain_spec.clone();
+        let changes = pre_block_system_calls(self.db(), &chain_spec, &block.header)
+            .map_err(|_| BlockExecutionError::ProviderError)?;
+        if !changes.is_empty() {
+            self.commit_changes(block.number, changes, true, post_state);
+        }
+        Ok(())
+    }
+
     /// Irregular state change at Ethereum DAO hardfork
     fn apply_dao_fork_changes(
         &mut self,
@@ -171,6 +193,30 @@ where
             .map_err(|_| BlockExecutionError::ProviderError)
     }
 
+    /// Charges the blob fee of an EIP-4844 transaction before it is executed, see
+    /// [charge_blob_fee].
+    fn charge_blob_fee(
+        &mut self,
+        block_number: BlockNumber,
+        transaction: &TransactionSigned,
+        sender: Address,
+        blob_fee: U256,
+        post_state: &mut PostState,
+    ) -> Result<(), BlockExecutionError> {
+        let max_cost = blob_transaction_max_cost(transaction);
+        let charged =
+            charge_blob_fee(self.db(), post_state, block_number, sender, max_cost, blob_fee)
+                .map_err(|_| BlockExecutionError::ProviderError)?;
+        if !charged {
+            return Err(BlockValidationError::InsufficientFundsForBlobFee {
+                hash: transaction.hash,
+                max_cost,
+            }
+            .into())
+        }
+        Ok(())
+    }
+
     /// Runs a single transaction in the configured environment and proceeds
     /// to return the result and state diff (without applying it).
     ///
@@ -209,6 +255,8 @@ where
     /// transition ID for each transaction (with the first executed tx having transition ID 0, and
     /// so on).
     ///
+    /// The state changes of the pre-block system calls are included in the [PostState].
+    ///
     /// The second returned value represents the total gas used by this block of transactions.
     pub fn execute_transactions(
         &mut self,
@@ -216,16 +264,21 @@ where
         total_difficulty: U256,
         senders: Option<Vec<Address>>,
     ) -> Result<(PostState, u64), BlockExecutionError> {
+        let mut post_state = PostState::with_tx_capacity(block.number, block.body.len());
+        self.apply_pre_block_system_calls(block, &mut post_state)?;
+
         // perf: do not execute empty blocks
         if block.body.is_empty() {
-            return Ok((PostState::default(), 0))
+            return Ok((post_state, 0))
         }
         let senders = self.recover_senders(&block.body, senders)?;
 
         self.init_env(&block.header, total_difficulty);
 
+        // the blob gas price is only set for post-Cancun blocks
+        let blob_gas_price = block.header.blob_fee().map(|price| price.to::<u128>());
+
         let mut cumulative_gas_used = 0;
-        let mut post_state = PostState::with_tx_capacity(block.number, block.body.len());
         for (transaction, sender) in block.body.iter().zip(senders) {
             // The sum of the transaction’s gas limit, Tg, and the gas utilised in this block prior,
             // must be no greater than the block’s gasLimit.
@@ -237,6 +290,23 @@ where
                 }
                 .into())
             }
+            // EIP-4844: the blob fee is burned and can't be lower than the blob gas price, it is
+            // charged before the transaction is executed
+            if let Some(blob_gas_used) = transaction.blob_gas_used() {
+                let blob_gas_price = blob_gas_price.unwrap_or_default();
+                let max_fee_per_blob_gas = transaction.max_fee_per_blob_gas().unwrap_or_default();
+                if max_fee_per_blob_gas < blob_gas_price {
+                    return Err(BlockValidationError::BlobFeeCapTooLow {
+                        hash: transaction.hash,
+                        max_fee_per_blob_gas,
+                        blob_gas_price,
+                    }
+                    .into())
+                }
+                let blob_fee = U256::from(blob_gas_used) * U256::from(blob_gas_price);
+                self.charge_blob_fee(block.number, transaction, sender, blob_fee, &mut post_state)?;
+            }
+
             // Execute transaction.
             let ResultAndState { result, state } = self.transact(transaction, sender)?;
 
@@ -320,6 +390,14 @@ where
             .into())
         }
 
+        // Check if blob gas used matches the value set in header.
+        if let Some(expected) = block.blob_gas_used {
+            let got = block.body.iter().filter_map(|tx| tx.blob_gas_used()).sum::<u64>();
+            if expected != got {
+                return Err(BlockValidationError::BlockBlobGasUsed { got, expected }.into())
+            }
+        }
+
         self.apply_post_block_changes(block, total_difficulty, post_state)
     }
 
@@ -394,6 +472,66 @@ where
     Ok(())
 }
 
+/// Returns the balance the sender of an EIP-4844 transaction needs: the gas limit at the max fee
+/// per gas, the value and the blob gas at the max fee per blob gas.
+pub fn blob_transaction_max_cost(transaction: &Transaction) -> U256 {
+    U256::from(transaction.gas_limit()) * U256::from(transaction.max_fee_per_gas()) +
+        U256::from(transaction.value()) +
+        U256::from(transaction.blob_gas_used().unwrap_or_default()) *
+            U256::from(transaction.max_fee_per_blob_gas().unwrap_or_default())
+}
+
+/// Charges the blob fee of an EIP-4844 transaction to its sender before the transaction is
+/// executed.
+///
+/// revm doesn't know about blob gas, so this checks that the balance of the sender covers the
+/// `max_cost` of the transaction, see [blob_transaction_max_cost], and deducts the `blob_fee` by
+/// mutating the db entry in place. The change is recorded in the given [PostState].
+///
+/// Returns `false` without changing the balance if the sender can't pay the `max_cost`.
+pub fn charge_blob_fee<DB>(
+    db: &mut CacheDB<DB>,
+    post_state: &mut PostState,
+    block_number: BlockNumber,
+    sender: Address,
+    max_cost: U256,
+    blob_fee: U256,
+) -> Result<bool, <DB as DatabaseRef>::Error>
+where
+    DB: DatabaseRef,
+{
+    let account = db.load_account(sender)?;
+    if account.info.balance < max_cost {
+        return Ok(false)
+    }
+
+    let old = to_reth_acc(&account.info);
+    account.info.balance -= blob_fee;
+    if account.account_state == AccountState::None {
+        account.account_state = AccountState::Touched;
+    }
+    post_state.change_account(block_number, sender, old, to_reth_acc(&account.info));
+    Ok(true)
+}
+
+/// Refunds a blob fee charged with [charge_blob_fee] if the transaction could not be executed.
+pub fn refund_blob_fee<DB>(
+    db: &mut CacheDB<DB>,
+    post_state: &mut PostState,
+    block_number: BlockNumber,
+    sender: Address,
+    blob_fee: U256,
+) -> Result<(), <DB as DatabaseRef>::Error>
+where
+    DB: DatabaseRef,
+{
+    let account = db.load_account(sender)?;
+    let old = to_reth_acc(&account.info);
+    account.info.balance += blob_fee;
+    post_state.change_account(block_number, sender, old, to_reth_acc(&account.info));
+    Ok(())
+}
+
 /// Commit change to the _run-time_ database [CacheDB], and update the given [PostState] with the
 /// changes made in the transaction, which can be persisted to the database.
 ///
@@ -649,12 +787,17 @@ pub fn insert_post_block_withdrawals_balance_increments(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::database::State;
+    use crate::{
+        database::State,
+        system_calls::{BEACON_ROOTS_ADDRESS, HISTORY_BUFFER_LENGTH, HISTORY_STORAGE_ADDRESS},
+    };
     use once_cell::sync::Lazy;
     use reth_consensus_common::calc;
     use reth_primitives::{
-        constants::ETH_TO_WEI, hex_literal::hex, keccak256, Account, Address, BlockNumber,
-        Bytecode, Bytes, ChainSpecBuilder, ForkCondition, StorageKey, H256, MAINNET, U256,
+        constants::{eip4844::DATA_GAS_PER_BLOB, ETH_TO_WEI},
+        hex_literal::hex,
+        keccak256, Account, Address, BlockNumber, Bytecode, Bytes, ChainSpecBuilder, ForkCondition,
+        Signature, StorageKey, Transaction, TransactionKind, TxEip4844, H256, MAINNET, U256,
     };
     use reth_provider::{
         post_state::{AccountChanges, Storage, StorageTransition, StorageWipe},
@@ -727,6 +870,14 @@ mod tests {
         fn state_root(&self, _post_state: PostState) -> reth_interfaces::Result<H256> {
             todo!()
         }
+
+        fn storage_root(
+            &self,
+            _address: Address,
+            _post_state: PostState,
+        ) -> reth_interfaces::Result<H256> {
+            todo!()
+        }
     }
 
     impl StateProvider for StateProviderTest {
@@ -990,6 +1141,182 @@ mod tests {
         }
     }
 
+    #[test]
+    fn beacon_root_system_call() {
+        let root = H256::random();
+        let header = Header {
+            number: 1,
+            timestamp: 12,
+            parent_beacon_block_root: Some(root),
+            ..Header::default()
+        };
+
+        let mut db = StateProviderTest::default();
+        db.insert_account(
+            BEACON_ROOTS_ADDRESS,
+            Account::default(),
+            Some(hex!("00").into()),
+            HashMap::new(),
+        );
+
+        let chain_spec = Arc::new(ChainSpecBuilder::mainnet().cancun_activated().build());
+        let db = SubState::new(State::new(db));
+
+        let mut executor = Executor::new(chain_spec, db);
+        let out = executor
+            .execute(
+                &Block { header, body: vec![], ommers: vec![], withdrawals: None },
+                U256::ZERO,
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(
+            out.storage().get(&BEACON_ROOTS_ADDRESS).unwrap().storage,
+            BTreeMap::from([
+                (U256::from(12), U256::from(12)),
+                (U256::from(12 + HISTORY_BUFFER_LENGTH), U256::from_be_bytes(root.0)),
+            ])
+        );
+        assert!(out.accounts().get(&BEACON_ROOTS_ADDRESS).is_none());
+    }
+
+    #[test]
+    fn system_calls_without_contract() {
+        let header = Header {
+            number: 1,
+            parent_beacon_block_root: Some(H256::random()),
+            ..Header::default()
+        };
+
+        let chain_spec = Arc::new(ChainSpecBuilder::mainnet().prague_activated().build());
+        let db = SubState::new(State::new(StateProviderTest::default()));
+
+        let mut executor = Executor::new(chain_spec, db);
+        let out = executor
+            .execute(
+                &Block { header, body: vec![], ommers: vec![], withdrawals: None },
+                U256::ZERO,
+                None,
+            )
+            .unwrap();
+
+        assert!(out.storage().is_empty());
+        assert!(out.accounts().is_empty());
+    }
+
+    #[test]
+    fn block_hash_history_system_call() {
+        let parent_hash = H256::random();
+        let header = Header { number: 8192, parent_hash, ..Header::default() };
+
+        let mut db = StateProviderTest::default();
+        db.insert_account(
+            HISTORY_STORAGE_ADDRESS,
+            Account::default(),
+            Some(hex!("00").into()),
+            HashMap::new(),
+        );
+
+        let block = Block { header, body: vec![], ommers: vec![], withdrawals: None };
+
+        // not active before prague
+        let chain_spec = Arc::new(ChainSpecBuilder::mainnet().cancun_activated().build());
+        let mut executor = Executor::new(chain_spec, SubState::new(State::new(db.clone())));
+        let out = executor.execute(&block, U256::ZERO, None).unwrap();
+        assert!(out.storage().is_empty());
+
+        let chain_spec = Arc::new(ChainSpecBuilder::mainnet().prague_activated().build());
+        let mut executor = Executor::new(chain_spec, SubState::new(State::new(db)));
+        let out = executor.execute(&block, U256::ZERO, None).unwrap();
+        assert_eq!(
+            out.storage().get(&HISTORY_STORAGE_ADDRESS).unwrap().storage,
+            BTreeMap::from([(U256::ZERO, U256::from_be_bytes(parent_hash.0))])
+        );
+    }
+
+    #[test]
+    fn blob_fee_is_charged() {
+        let sender = Address::random();
+        let create_block = |max_fee_per_blob_gas: u128| {
+            let blob_tx = TransactionSigned::from_transaction_and_signature(
+                Transaction::Eip4844(TxEip4844 {
+                    chain_id: 1,
+                    gas_limit: 21_000,
+                    to: TransactionKind::Call(Address::random()),
+                    blob_versioned_hashes: vec![H256::random()],
+                    max_fee_per_blob_gas,
+                    ..Default::default()
+                }),
+                Signature::default(),
+            );
+            let header = Header {
+                number: 1,
+                gas_limit: 30_000_000,
+                gas_used: 21_000,
+                base_fee_per_gas: Some(0),
+                blob_gas_used: Some(DATA_GAS_PER_BLOB),
+                excess_blob_gas: Some(0),
+                ..Header::default()
+            };
+            Block { header, body: vec![blob_tx], ommers: vec![], withdrawals: None }
+        };
+        let block = create_block(1);
+
+        let chain_spec = Arc::new(ChainSpecBuilder::mainnet().cancun_activated().build());
+        let balance = U256::from(DATA_GAS_PER_BLOB);
+        let create_db = |balance: U256| {
+            let mut db = StateProviderTest::default();
+            db.insert_account(
+                sender,
+                Account { balance, nonce: 0, bytecode_hash: None },
+                None,
+                HashMap::new(),
+            );
+            SubState::new(State::new(db))
+        };
+
+        // the blob gas price is 1, so the blob fee is the blob gas used
+        let mut executor = Executor::new(chain_spec.clone(), create_db(balance));
+        let out = executor.execute(&block, U256::ZERO, Some(vec![sender])).unwrap();
+        assert_eq!(out.accounts().get(&sender).unwrap().unwrap().balance, U256::ZERO);
+
+        let mut executor = Executor::new(chain_spec.clone(), create_db(balance - U256::from(1)));
+        assert_eq!(
+            executor.execute(&block, U256::ZERO, Some(vec![sender])).unwrap_err(),
+            BlockValidationError::InsufficientFundsForBlobFee {
+                hash: block.body[0].hash,
+                max_cost: balance
+            }
+            .into()
+        );
+
+        // the balance must cover the blob gas at the max fee per blob gas, even though only the
+        // blob gas price is charged
+        let expensive_block = create_block(2);
+        let mut executor = Executor::new(chain_spec.clone(), create_db(balance));
+        assert_eq!(
+            executor.execute(&expensive_block, U256::ZERO, Some(vec![sender])).unwrap_err(),
+            BlockValidationError::InsufficientFundsForBlobFee {
+                hash: expensive_block.body[0].hash,
+                max_cost: balance * U256::from(2)
+            }
+            .into()
+        );
+        let mut executor = Executor::new(chain_spec.clone(), create_db(balance * U256::from(2)));
+        let out = executor.execute(&expensive_block, U256::ZERO, Some(vec![sender])).unwrap();
+        assert_eq!(out.accounts().get(&sender).unwrap().unwrap().balance, balance);
+
+        // the blob gas used in the header must match the transactions
+        let mut block = block;
+        block.header.blob_gas_used = Some(0);
+        let mut executor = Executor::new(chain_spec, create_db(balance));
+        assert_eq!(
+            executor.execute(&block, U256::ZERO, Some(vec![sender])).unwrap_err(),
+            BlockValidationError::BlockBlobGasUsed { got: DATA_GAS_PER_BLOB, expected: 0 }.into()
+        );
+    }
+
     #[test]
     fn test_selfdestruct() {
         // Modified version of eth test. Storage is added for selfdestructed account to see
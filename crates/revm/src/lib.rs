@@ -33,3 +33,5 @@ pub use revm;
 
 /// Etereum DAO hardfork state change data.
 pub mod eth_dao_fork;
+
+pub mod system_calls;
@@ -0,0 +1,134 @@
+//! System calls that are applied before the transactions of a block are executed.
+//!
+//! The system contracts only store the values they are called with, so instead of executing their
+//! code the calls are applied as direct storage writes. The returned changes can be committed like
+//! the changes of a transaction, which makes them part of the block's [PostState] and of the state
+//! that transactions are traced on.
+//!
+//! [PostState]: reth_provider::PostState
+use reth_primitives::{
+    hex_literal::hex, Address, BlockNumber, ChainSpec, Hardfork, Header, H160, H256, KECCAK_EMPTY,
+    U256,
+};
+use revm::{
+    db::{CacheDB, DatabaseRef},
+    primitives::{hash_map, Account as RevmAccount, StorageSlot},
+    Database,
+};
+
+/// The address of the beacon roots contract, see
+/// [EIP-4788](https://eips.ethereum.org/EIPS/eip-4788).
+pub const BEACON_ROOTS_ADDRESS: H160 = H160(hex!("000f3df6d732807ef1319fb7b8bb8522d0beac02"));
+
+/// The length of the ring buffers of the beacon roots contract.
+pub const HISTORY_BUFFER_LENGTH: u64 = 8191;
+
+/// The address of the block hash history contract, see
+/// [EIP-2935](https://eips.ethereum.org/EIPS/eip-2935).
+pub const HISTORY_STORAGE_ADDRESS: H160 = H160(hex!("0000f90827f1c53a10cb7a02335b175320002935"));
+
+/// The number of block hashes served by the block hash history contract.
+pub const HISTORY_SERVE_WINDOW: u64 = 8191;
+
+/// Returns the state changes of all system calls that are active for the block with the given
+/// header.
+///
+/// The changes are _not_ committed to the database.
+pub fn pre_block_system_calls<DB>(
+    db: &mut CacheDB<DB>,
+    chain_spec: &ChainSpec,
+    header: &Header,
+) -> Result<hash_map::HashMap<Address, RevmAccount>, DB::Error>
+where
+    DB: DatabaseRef,
+{
+    pre_block_system_calls_with(
+        db,
+        chain_spec,
+        header.number,
+        header.timestamp,
+        header.parent_hash,
+        header.parent_beacon_block_root,
+    )
+}
+
+/// Returns the state changes of all system calls that are active for a block with the given
+/// number and timestamp.
+///
+/// This is useful if the header of the block doesn't exist yet, like when building a payload.
+pub fn pre_block_system_calls_with<DB>(
+    db: &mut CacheDB<DB>,
+    chain_spec: &ChainSpec,
+    block_number: BlockNumber,
+    timestamp: u64,
+    parent_hash: H256,
+    parent_beacon_block_root: Option<H256>,
+) -> Result<hash_map::HashMap<Address, RevmAccount>, DB::Error>
+where
+    DB: DatabaseRef,
+{
+    let mut changes = hash_map::HashMap::new();
+
+    // EIP-4788: the genesis block has no parent beacon block
+    if chain_spec.fork(Hardfork::Cancun).active_at_timestamp(timestamp) && block_number > 0 {
+        if let Some(root) = parent_beacon_block_root {
+            let timestamp_index = timestamp % HISTORY_BUFFER_LENGTH;
+            let slots = [
+                (U256::from(timestamp_index), U256::from(timestamp)),
+                (U256::from(timestamp_index + HISTORY_BUFFER_LENGTH), h256_to_u256(root)),
+            ];
+            system_call(db, BEACON_ROOTS_ADDRESS, slots, &mut changes)?;
+        }
+    }
+
+    // EIP-2935
+    if chain_spec.fork(Hardfork::Prague).active_at_timestamp(timestamp) && block_number > 0 {
+        let slot = U256::from((block_number - 1) % HISTORY_SERVE_WINDOW);
+        let slots = [(slot, h256_to_u256(parent_hash))];
+        system_call(db, HISTORY_STORAGE_ADDRESS, slots, &mut changes)?;
+    }
+
+    Ok(changes)
+}
+
+/// Writes the given storage slots of the system contract.
+///
+/// If the contract is not deployed, the call is a no-op.
+fn system_call<DB, const N: usize>(
+    db: &mut CacheDB<DB>,
+    address: Address,
+    slots: [(U256, U256); N],
+    changes: &mut hash_map::HashMap<Address, RevmAccount>,
+) -> Result<(), DB::Error>
+where
+    DB: DatabaseRef,
+{
+    let account = db.load_account(address)?;
+    if account.info.code_hash == KECCAK_EMPTY {
+        return Ok(())
+    }
+    let info = account.info.clone();
+
+    let mut storage = hash_map::HashMap::with_capacity(N);
+    for (slot, value) in slots {
+        let original_value = db.storage(address, slot)?;
+        storage.insert(slot, StorageSlot { original_value, present_value: value });
+    }
+
+    changes.insert(
+        address,
+        RevmAccount {
+            info,
+            storage,
+            is_destroyed: false,
+            is_touched: true,
+            storage_cleared: false,
+            is_not_existing: false,
+        },
+    );
+    Ok(())
+}
+
+fn h256_to_u256(value: H256) -> U256 {
+    U256::from_be_bytes(value.0)
+}
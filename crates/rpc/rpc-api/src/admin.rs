@@ -47,3 +47,28 @@ pub trait AdminApi {
     #[method(name = "nodeInfo")]
     async fn node_info(&self) -> RpcResult<NodeInfo>;
 }
+
+/// Admin namespace rpc interface to switch rpc namespaces of the http and ws servers on and off at
+/// runtime.
+///
+/// This should only be exposed on authenticated transports.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "admin"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "admin"))]
+pub trait AdminRpcNamespacesApi {
+    /// Enables a previously disabled rpc namespace, e.g. `trace`.
+    ///
+    /// Returns false if the namespace was not disabled.
+    #[method(name = "enableRpcNamespace")]
+    fn enable_rpc_namespace(&self, namespace: String) -> RpcResult<bool>;
+
+    /// Disables an rpc namespace, e.g. `trace`. Calls to methods of a disabled namespace are
+    /// rejected until it is enabled again.
+    ///
+    /// Returns false if the namespace was already disabled.
+    #[method(name = "disableRpcNamespace")]
+    fn disable_rpc_namespace(&self, namespace: String) -> RpcResult<bool>;
+
+    /// Returns all currently disabled rpc namespaces.
+    #[method(name = "disabledRpcNamespaces")]
+    fn disabled_rpc_namespaces(&self) -> RpcResult<Vec<String>>;
+}
@@ -249,8 +249,11 @@ pub trait DebugApi {
     async fn debug_mutex_profile(&self, file: String, nsec: u64) -> RpcResult<()>;
 
     /// Returns the preimage for a sha3 hash, if known.
+    ///
+    /// Preimages of hashed addresses and storage keys are only known if preimage recording is
+    /// enabled, and they haven't been pruned.
     #[method(name = "preimage")]
-    async fn debug_preimage(&self, hash: H256) -> RpcResult<()>;
+    async fn debug_preimage(&self, hash: H256) -> RpcResult<Option<Bytes>>;
 
     /// Retrieves a block and returns its pretty printed form.
     #[method(name = "printBlock")]
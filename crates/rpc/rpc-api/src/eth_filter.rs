@@ -1,5 +1,5 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
-use reth_rpc_types::{Filter, FilterChanges, FilterId, Log};
+use reth_rpc_types::{Filter, FilterChanges, FilterId, Log, LogsPage, LogsPageCursor};
 
 /// Rpc Interface for poll-based ethereum filter API.
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "eth"))]
@@ -32,4 +32,16 @@ pub trait EthFilterApi {
     /// Returns logs matching given filter object.
     #[method(name = "getLogs")]
     async fn logs(&self, filter: Filter) -> RpcResult<Vec<Log>>;
+
+    /// Returns a page of the logs matching given filter object, starting at the given cursor.
+    ///
+    /// This is a non-standard extension of `eth_getLogs` for queries that match too many logs for
+    /// a single response: the returned cursor resumes the query at the first log that was not
+    /// returned.
+    #[method(name = "getLogsPage")]
+    async fn logs_page(
+        &self,
+        filter: Filter,
+        cursor: Option<LogsPageCursor>,
+    ) -> RpcResult<LogsPage>;
 }
@@ -27,7 +27,10 @@ mod eth_filter;
 mod eth_pubsub;
 mod net;
 mod otterscan;
+mod personal;
+mod provider;
 mod reth;
+mod reth_pubsub;
 mod rpc;
 mod trace;
 mod txpool;
@@ -39,7 +42,7 @@ pub use servers::*;
 /// Aggregates all server traits.
 pub mod servers {
     pub use crate::{
-        admin::AdminApiServer,
+        admin::{AdminApiServer, AdminRpcNamespacesApiServer},
         debug::DebugApiServer,
         engine::{EngineApiServer, EngineEthApiServer},
         eth::EthApiServer,
@@ -47,7 +50,10 @@ pub mod servers {
         eth_pubsub::EthPubSubApiServer,
         net::NetApiServer,
         otterscan::OtterscanServer,
+        personal::{EthSigningApiServer, PersonalApiServer},
+        provider::ProviderApiServer,
         reth::RethApiServer,
+        reth_pubsub::RethPubSubApiServer,
         rpc::RpcApiServer,
         trace::TraceApiServer,
         txpool::TxPoolApiServer,
@@ -63,13 +69,15 @@ pub use clients::*;
 #[cfg(feature = "client")]
 pub mod clients {
     pub use crate::{
-        admin::AdminApiClient,
+        admin::{AdminApiClient, AdminRpcNamespacesApiClient},
         debug::DebugApiClient,
         engine::{EngineApiClient, EngineEthApiClient},
         eth::EthApiClient,
         eth_filter::EthFilterApiClient,
         net::NetApiClient,
         otterscan::OtterscanClient,
+        personal::{EthSigningApiClient, PersonalApiClient},
+        provider::ProviderApiClient,
         rpc::RpcApiServer,
         trace::TraceApiClient,
         txpool::TxPoolApiClient,
@@ -0,0 +1,79 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use reth_primitives::{Address, Bytes};
+use reth_rpc_types::TransactionRequest;
+
+/// Personal namespace rpc interface to manage the accounts of the node's keystore.
+///
+/// This should only be exposed on authenticated transports.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "personal"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "personal"))]
+pub trait PersonalApi {
+    /// Returns the addresses of all accounts in the keystore.
+    #[method(name = "listAccounts")]
+    async fn list_accounts(&self) -> RpcResult<Vec<Address>>;
+
+    /// Creates a new account with a random key that is encrypted with the given password.
+    #[method(name = "newAccount")]
+    async fn new_account(&self, password: String) -> RpcResult<Address>;
+
+    /// Imports the given hex encoded secret key and encrypts it with the given password.
+    #[method(name = "importRawKey")]
+    async fn import_raw_key(&self, key: String, password: String) -> RpcResult<Address>;
+
+    /// Decrypts the key of the account so it can be used by the `eth_sign` and
+    /// `eth_signTransaction` methods.
+    ///
+    /// The account is locked again after `duration` seconds, or 300 seconds if not set. A duration
+    /// of 0 keeps the account unlocked until it is locked explicitly.
+    #[method(name = "unlockAccount")]
+    async fn unlock_account(
+        &self,
+        address: Address,
+        password: String,
+        duration: Option<u64>,
+    ) -> RpcResult<bool>;
+
+    /// Removes the decrypted key of the account from memory.
+    ///
+    /// Returns false if the account was not unlocked.
+    #[method(name = "lockAccount")]
+    async fn lock_account(&self, address: Address) -> RpcResult<bool>;
+
+    /// Signs the message according to EIP-191 with the account, which is decrypted with the given
+    /// password for this request only.
+    #[method(name = "sign")]
+    async fn sign(&self, message: Bytes, address: Address, password: String) -> RpcResult<Bytes>;
+
+    /// Signs the transaction with its `from` account, which is decrypted with the given password
+    /// for this request only, and returns the raw signed transaction.
+    ///
+    /// The nonce and gas limit of the transaction must be set.
+    #[method(name = "signTransaction")]
+    async fn sign_transaction(
+        &self,
+        request: TransactionRequest,
+        password: String,
+    ) -> RpcResult<Bytes>;
+}
+
+/// Eth signing methods that use the unlocked accounts of the node's keystore.
+///
+/// This should only be exposed on authenticated transports.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "eth"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "eth"))]
+pub trait EthSigningApi {
+    /// Returns the addresses of all accounts in the keystore.
+    #[method(name = "accounts")]
+    async fn accounts(&self) -> RpcResult<Vec<Address>>;
+
+    /// Signs the message according to EIP-191 with the unlocked account.
+    #[method(name = "sign")]
+    async fn sign(&self, address: Address, message: Bytes) -> RpcResult<Bytes>;
+
+    /// Signs the transaction with its unlocked `from` account and returns the raw signed
+    /// transaction.
+    ///
+    /// The nonce and gas limit of the transaction must be set.
+    #[method(name = "signTransaction")]
+    async fn sign_transaction(&self, request: TransactionRequest) -> RpcResult<Bytes>;
+}
@@ -0,0 +1,53 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use reth_primitives::Bytes;
+use reth_rpc_types::{CursorOp, TableEntry};
+
+/// Provider rpc interface, which serves read-only access to the raw tables of the database.
+///
+/// This allows stateless RPC front-ends to serve requests from the storage of a backend node.
+/// Keys and values are exchanged in their database encoding.
+///
+/// Reads without a snapshot see the latest state of the database. Reads that are pinned to a
+/// snapshot all see the state of the database at the time the snapshot was opened.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "provider"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "provider"))]
+pub trait ProviderApi {
+    /// Opens a snapshot of the database and returns its id.
+    ///
+    /// The snapshot is released by `provider_releaseSnapshot`, or once it has not been read for a
+    /// while.
+    #[method(name = "openSnapshot")]
+    async fn open_snapshot(&self) -> RpcResult<u64>;
+
+    /// Releases the snapshot with the given id.
+    ///
+    /// Returns `false` if the snapshot was already released.
+    #[method(name = "releaseSnapshot")]
+    async fn release_snapshot(&self, snapshot: u64) -> RpcResult<bool>;
+
+    /// Returns the value of the given key in the given table.
+    #[method(name = "get")]
+    async fn get(
+        &self,
+        table: String,
+        key: Bytes,
+        snapshot: Option<u64>,
+    ) -> RpcResult<Option<Bytes>>;
+
+    /// Positions a cursor on the given table and returns the entry at the position, followed by
+    /// up to `limit - 1` subsequent entries in the direction of the operation.
+    ///
+    /// Returns no entries if the position does not exist.
+    #[method(name = "cursor")]
+    async fn cursor(
+        &self,
+        table: String,
+        op: CursorOp,
+        limit: Option<usize>,
+        snapshot: Option<u64>,
+    ) -> RpcResult<Vec<TableEntry>>;
+
+    /// Returns the number of entries in the given table.
+    #[method(name = "entries")]
+    async fn entries(&self, table: String, snapshot: Option<u64>) -> RpcResult<usize>;
+}
@@ -1,5 +1,6 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use reth_primitives::{Address, BlockId, U256};
+use reth_rpc_types::{AccountSummary, ChainSpecInfo};
 use std::collections::HashMap;
 
 /// Reth API namespace for reth-specific methods
@@ -12,4 +13,30 @@ pub trait RethApi {
         &self,
         block_id: BlockId,
     ) -> RpcResult<HashMap<Address, U256>>;
+
+    /// Returns the balances of the given accounts at the given block, in the order of the
+    /// addresses.
+    #[method(name = "getBalances")]
+    async fn reth_get_balances(
+        &self,
+        addresses: Vec<Address>,
+        block_id: Option<BlockId>,
+    ) -> RpcResult<Vec<U256>>;
+
+    /// Returns the balance, nonce and code hash of the given accounts at the given block, in the
+    /// order of the addresses. The code of the accounts is included if `include_code` is set.
+    ///
+    /// Non-existent accounts are returned as empty accounts.
+    #[method(name = "getAccounts")]
+    async fn reth_get_accounts(
+        &self,
+        addresses: Vec<Address>,
+        block_id: Option<BlockId>,
+        include_code: Option<bool>,
+    ) -> RpcResult<Vec<AccountSummary>>;
+
+    /// Returns the chain spec of the node: the activation conditions of the hardforks, the deposit
+    /// contract, the fork ids and the base fee parameters.
+    #[method(name = "chainSpec")]
+    fn reth_chain_spec(&self) -> RpcResult<ChainSpecInfo>;
 }
@@ -0,0 +1,34 @@
+use jsonrpsee::proc_macros::rpc;
+use reth_rpc_types::pubsub::{BlocksSubscriptionParams, StateChangesParams};
+
+/// Reth pub-sub rpc interface.
+#[rpc(server, namespace = "reth")]
+pub trait RethPubSubApi {
+    /// Streams every canonical block together with the receipts of its transactions.
+    ///
+    /// Blocks that are removed by a reorg are streamed with `removed` set before the blocks of the
+    /// new chain, so the stream can be applied without fetching blocks separately.
+    #[subscription(
+        name = "subscribeBlocks" => "blocksSubscription",
+        unsubscribe = "unsubscribeBlocks",
+        item = reth_rpc_types::pubsub::BlockWithReceipts
+    )]
+    async fn subscribe_blocks(
+        &self,
+        params: Option<BlocksSubscriptionParams>,
+    ) -> jsonrpsee::core::SubscriptionResult;
+
+    /// Streams the changes of the given accounts and storage slots in new canonical blocks.
+    ///
+    /// Only blocks that change a watched account or slot are streamed. The subscription is closed
+    /// if it falls behind the canonical chain, since the missed changes can not be recovered.
+    #[subscription(
+        name = "subscribeStateChanges" => "stateChangesSubscription",
+        unsubscribe = "unsubscribeStateChanges",
+        item = reth_rpc_types::pubsub::StateChanges
+    )]
+    async fn subscribe_state_changes(
+        &self,
+        params: StateChangesParams,
+    ) -> jsonrpsee::core::SubscriptionResult;
+}
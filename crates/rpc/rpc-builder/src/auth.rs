@@ -1,7 +1,6 @@
 use crate::{
-    constants,
+    constants::{self, DEFAULT_MAX_BLOCKS_PER_FILTER, DEFAULT_MAX_LOGS_PER_RESPONSE},
     error::{RpcError, ServerKind},
-    eth::DEFAULT_MAX_LOGS_PER_RESPONSE,
     EthConfig,
 };
 use hyper::header::AUTHORIZATION;
@@ -73,6 +72,7 @@ where
         pool,
         eth_cache.clone(),
         DEFAULT_MAX_LOGS_PER_RESPONSE,
+        DEFAULT_MAX_BLOCKS_PER_FILTER,
         Box::new(executor.clone()),
     );
     launch_with_eth_api(eth_api, eth_filter, engine_api, socket_addr, secret).await
@@ -7,6 +7,12 @@ pub const DEFAULT_WS_RPC_PORT: u16 = 8546;
 /// The default port for the auth server.
 pub const DEFAULT_AUTH_PORT: u16 = 8551;
 
+/// The default maximum of logs in a single response.
+pub const DEFAULT_MAX_LOGS_PER_RESPONSE: usize = 20_000;
+
+/// The default maximum number of blocks a single `eth_getLogs` query may span, `0` is unlimited.
+pub const DEFAULT_MAX_BLOCKS_PER_FILTER: u64 = 0;
+
 /// The default IPC endpoint
 #[cfg(windows)]
 pub const DEFAULT_IPC_ENDPOINT: &str = r"\\.\pipe\reth.ipc";
@@ -1,16 +1,14 @@
+use crate::constants::{DEFAULT_MAX_BLOCKS_PER_FILTER, DEFAULT_MAX_LOGS_PER_RESPONSE};
 use reth_rpc::{
     eth::{
         cache::{EthStateCache, EthStateCacheConfig},
         gas_oracle::GasPriceOracleConfig,
         RPC_DEFAULT_GAS_CAP,
     },
-    EthApi, EthFilter, EthPubSub, TracingCallPool,
+    BlockTraceCacheConfig, EthApi, EthFilter, EthPubSub, TracingCallPool,
 };
 use serde::{Deserialize, Serialize};
 
-/// The default maximum of logs in a single response.
-pub(crate) const DEFAULT_MAX_LOGS_PER_RESPONSE: usize = 20_000;
-
 /// The default maximum number of concurrently executed tracing calls
 pub(crate) const DEFAULT_MAX_TRACING_REQUESTS: u32 = 25;
 
@@ -38,8 +36,12 @@ pub struct EthConfig {
     pub gas_oracle: GasPriceOracleConfig,
     /// The maximum number of tracing calls that can be executed in concurrently.
     pub max_tracing_requests: u32,
+    /// Settings for the cache of `debug_traceBlock*` results
+    pub trace_cache: BlockTraceCacheConfig,
     /// Maximum number of logs that can be returned in a single response in `eth_getLogs` calls.
     pub max_logs_per_response: usize,
+    /// Maximum number of blocks that a single `eth_getLogs` query may span.
+    pub max_blocks_per_filter: u64,
     /// Gas limit for `eth_call` and call tracing RPC methods.
     ///
     /// Defaults to [RPC_DEFAULT_GAS_CAP]
@@ -52,7 +54,9 @@ impl Default for EthConfig {
             cache: EthStateCacheConfig::default(),
             gas_oracle: GasPriceOracleConfig::default(),
             max_tracing_requests: DEFAULT_MAX_TRACING_REQUESTS,
+            trace_cache: BlockTraceCacheConfig::default(),
             max_logs_per_response: DEFAULT_MAX_LOGS_PER_RESPONSE,
+            max_blocks_per_filter: DEFAULT_MAX_BLOCKS_PER_FILTER,
             rpc_gas_cap: RPC_DEFAULT_GAS_CAP.into(),
         }
     }
@@ -77,12 +81,24 @@ impl EthConfig {
         self
     }
 
+    /// Configures the cache of `debug_traceBlock*` results
+    pub fn trace_cache(mut self, trace_cache: BlockTraceCacheConfig) -> Self {
+        self.trace_cache = trace_cache;
+        self
+    }
+
     /// Configures the maximum number of logs per response
     pub fn max_logs_per_response(mut self, max_logs: usize) -> Self {
         self.max_logs_per_response = max_logs;
         self
     }
 
+    /// Configures the maximum number of blocks a single `eth_getLogs` query may span
+    pub fn max_blocks_per_filter(mut self, max_blocks: u64) -> Self {
+        self.max_blocks_per_filter = max_blocks;
+        self
+    }
+
     /// Configures the maximum gas limit for `eth_call` and call tracing RPC methods
     pub fn rpc_gas_cap(mut self, rpc_gas_cap: u64) -> Self {
         self.rpc_gas_cap = rpc_gas_cap;
@@ -31,13 +31,13 @@
 //!
 //! ```
 //! use reth_network_api::{NetworkInfo, Peers};
-//! use reth_provider::{BlockReaderIdExt, ChainSpecProvider, CanonStateSubscriptions, StateProviderFactory, EvmEnvProvider, ChangeSetReader};
+//! use reth_provider::{BlockReaderIdExt, ChainSpecProvider, CanonStateSubscriptions, StateProviderFactory, EvmEnvProvider, ChangeSetReader, PreimageReader, StageCheckpointReader};
 //! use reth_rpc_builder::{RethRpcModule, RpcModuleBuilder, RpcServerConfig, ServerBuilder, TransportRpcModuleConfig};
 //! use reth_tasks::TokioTaskExecutor;
 //! use reth_transaction_pool::TransactionPool;
 //! pub async fn launch<Provider, Pool, Network, Events>(provider: Provider, pool: Pool, network: Network, events: Events)
 //! where
-//!     Provider: BlockReaderIdExt + ChainSpecProvider + ChangeSetReader + StateProviderFactory + EvmEnvProvider + Clone + Unpin + 'static,
+//!     Provider: BlockReaderIdExt + ChainSpecProvider + ChangeSetReader + StageCheckpointReader + PreimageReader + StateProviderFactory + EvmEnvProvider + Clone + Unpin + 'static,
 //!     Pool: TransactionPool + Clone + 'static,
 //!     Network: NetworkInfo + Peers + Clone + 'static,
 //!     Events: CanonStateSubscriptions +  Clone + 'static,
@@ -64,7 +64,7 @@
 //! ```
 //! use tokio::try_join;
 //! use reth_network_api::{NetworkInfo, Peers};
-//! use reth_provider::{BlockReaderIdExt, ChainSpecProvider, CanonStateSubscriptions, StateProviderFactory, EvmEnvProvider, ChangeSetReader};
+//! use reth_provider::{BlockReaderIdExt, ChainSpecProvider, CanonStateSubscriptions, StateProviderFactory, EvmEnvProvider, ChangeSetReader, PreimageReader, StageCheckpointReader};
 //! use reth_rpc::JwtSecret;
 //! use reth_rpc_builder::{RethRpcModule, RpcModuleBuilder, RpcServerConfig, TransportRpcModuleConfig};
 //! use reth_tasks::TokioTaskExecutor;
@@ -73,7 +73,7 @@
 //! use reth_rpc_builder::auth::AuthServerConfig;
 //! pub async fn launch<Provider, Pool, Network, Events, EngineApi>(provider: Provider, pool: Pool, network: Network, events: Events, engine_api: EngineApi)
 //! where
-//!     Provider: BlockReaderIdExt + ChainSpecProvider + ChangeSetReader + StateProviderFactory + EvmEnvProvider + Clone + Unpin + 'static,
+//!     Provider: BlockReaderIdExt + ChainSpecProvider + ChangeSetReader + StageCheckpointReader + PreimageReader + StateProviderFactory + EvmEnvProvider + Clone + Unpin + 'static,
 //!     Pool: TransactionPool + Clone + 'static,
 //!     Network: NetworkInfo + Peers + Clone + 'static,
 //!     Events: CanonStateSubscriptions +  Clone + 'static,
@@ -110,20 +110,23 @@ use jsonrpsee::{
     server::{IdProvider, Server, ServerHandle},
     Methods, RpcModule,
 };
+use namespace_gate::NamespaceGate;
+use rate_limit::{RateLimitConfig, RateLimitLayer};
+use response_cache::ResponseCache;
 use reth_ipc::server::IpcServer;
 use reth_network_api::{NetworkInfo, Peers};
 use reth_provider::{
     BlockReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader,
-    EvmEnvProvider, StateProviderFactory,
+    EvmEnvProvider, PreimageReader, StageCheckpointReader, StateProviderFactory,
 };
 use reth_rpc::{
     eth::{
-        cache::{cache_new_blocks_task, EthStateCache},
+        cache::{blob_fee_cache_new_blocks_task, cache_new_blocks_task, EthStateCache},
         gas_oracle::GasPriceOracle,
     },
-    AdminApi, DebugApi, EngineEthApi, EthApi, EthFilter, EthPubSub, EthSubscriptionIdProvider,
-    NetApi, OtterscanApi, RPCApi, RethApi, TraceApi, TracingCallGuard, TracingCallPool, TxPoolApi,
-    Web3Api,
+    AdminApi, BlockTraceCache, DebugApi, EngineEthApi, EthApi, EthFilter, EthPubSub,
+    EthSubscriptionIdProvider, NetApi, OtterscanApi, RPCApi, RethApi, RethPubSub, TraceApi,
+    TracingCallGuard, TracingCallPool, TxPoolApi, Web3Api,
 };
 use reth_rpc_api::{servers::*, EngineApiServer};
 use reth_tasks::{TaskSpawner, TokioTaskExecutor};
@@ -136,7 +139,10 @@ use std::{
     str::FromStr,
 };
 use strum::{AsRefStr, EnumString, EnumVariantNames, ParseError, VariantNames};
-use tower::layer::util::{Identity, Stack};
+use tower::{
+    layer::util::{Identity, Stack},
+    util::Either,
+};
 use tower_http::cors::CorsLayer;
 use tracing::{instrument, trace};
 
@@ -158,6 +164,15 @@ pub mod constants;
 // Rpc server metrics
 mod metrics;
 
+/// Per-IP rate limiting.
+pub mod rate_limit;
+
+/// Runtime toggling of rpc namespaces.
+pub mod namespace_gate;
+
+/// Caching of idempotent read responses.
+pub mod response_cache;
+
 // re-export for convenience
 pub use crate::eth::{EthConfig, EthHandlers};
 pub use jsonrpsee::server::ServerBuilder;
@@ -181,6 +196,8 @@ where
         + EvmEnvProvider
         + ChainSpecProvider
         + ChangeSetReader
+        + StageCheckpointReader
+        + PreimageReader
         + Clone
         + Unpin
         + 'static,
@@ -326,6 +343,8 @@ where
         + EvmEnvProvider
         + ChainSpecProvider
         + ChangeSetReader
+        + StageCheckpointReader
+        + PreimageReader
         + Clone
         + Unpin
         + 'static,
@@ -554,6 +573,8 @@ impl RpcModuleSelection {
             + EvmEnvProvider
             + ChainSpecProvider
             + ChangeSetReader
+            + StageCheckpointReader
+            + PreimageReader
             + Clone
             + Unpin
             + 'static,
@@ -700,6 +721,8 @@ pub struct RethModuleRegistry<Provider, Pool, Network, Tasks, Events> {
     eth: Option<EthHandlers<Provider, Pool, Network, Events>>,
     /// to put trace calls behind semaphore
     tracing_call_guard: TracingCallGuard,
+    /// Cache of the traces of recently traced blocks
+    block_trace_cache: BlockTraceCache,
     /// Contains the [Methods] of a module
     modules: HashMap<RethRpcModule, Methods>,
 }
@@ -726,6 +749,7 @@ impl<Provider, Pool, Network, Tasks, Events>
             executor,
             modules: Default::default(),
             tracing_call_guard: TracingCallGuard::new(config.eth.max_tracing_requests),
+            block_trace_cache: BlockTraceCache::new(config.eth.trace_cache.clone()),
             config,
             events,
         }
@@ -794,6 +818,8 @@ where
         + EvmEnvProvider
         + ChainSpecProvider
         + ChangeSetReader
+        + StageCheckpointReader
+        + PreimageReader
         + Clone
         + Unpin
         + 'static,
@@ -826,6 +852,7 @@ where
                 eth_api,
                 Box::new(self.executor.clone()),
                 self.tracing_call_guard.clone(),
+                self.block_trace_cache.clone(),
             )
             .into_rpc()
             .into(),
@@ -937,6 +964,7 @@ where
                             eth_api.clone(),
                             Box::new(self.executor.clone()),
                             self.tracing_call_guard.clone(),
+                            self.block_trace_cache.clone(),
                         )
                         .into_rpc()
                         .into(),
@@ -972,9 +1000,26 @@ where
                         .into(),
                         RethRpcModule::Ots => OtterscanApi::new(eth_api.clone()).into_rpc().into(),
                         RethRpcModule::Reth => {
-                            RethApi::new(self.provider.clone(), Box::new(self.executor.clone()))
-                                .into_rpc()
-                                .into()
+                            // merge all reth handlers
+                            let mut module = RethApi::new(
+                                self.provider.clone(),
+                                Box::new(self.executor.clone()),
+                            )
+                            .into_rpc();
+                            let trace_api = TraceApi::new(
+                                self.provider.clone(),
+                                eth_api.clone(),
+                                self.tracing_call_guard.clone(),
+                            );
+                            let pubsub = RethPubSub::with_spawner(
+                                self.provider.clone(),
+                                self.events.clone(),
+                                trace_api,
+                                Box::new(self.executor.clone()),
+                            );
+                            module.merge(pubsub.into_rpc()).expect("No conflicts");
+
+                            module.into()
                         }
                     })
                     .clone()
@@ -1027,11 +1072,22 @@ where
                 executor.clone(),
                 tracing_call_pool.clone(),
             );
+
+            let new_canonical_blocks = self.events.canonical_state_stream();
+            let blob_fee_cache = api.blob_fee_cache().clone();
+            self.executor.spawn_critical(
+                "cache canonical blob fees task",
+                Box::pin(async move {
+                    blob_fee_cache_new_blocks_task(blob_fee_cache, new_canonical_blocks).await;
+                }),
+            );
+
             let filter = EthFilter::new(
                 self.provider.clone(),
                 self.pool.clone(),
                 cache.clone(),
                 self.config.eth.max_logs_per_response,
+                self.config.eth.max_blocks_per_filter,
                 executor.clone(),
             );
 
@@ -1089,6 +1145,12 @@ pub struct RpcServerConfig {
     ipc_server_config: Option<IpcServerBuilder>,
     /// The Endpoint where to launch the ipc server
     ipc_endpoint: Option<Endpoint>,
+    /// Per-IP rate limit shared by the http and ws servers
+    rate_limit: Option<RateLimitConfig>,
+    /// Namespaces of the http and ws servers that can be disabled at runtime
+    namespace_gate: Option<NamespaceGate>,
+    /// Cache of the responses of idempotent read methods of the http server
+    response_cache: Option<ResponseCache>,
 }
 
 impl fmt::Debug for RpcServerConfig {
@@ -1101,6 +1163,9 @@ impl fmt::Debug for RpcServerConfig {
             .field("ws_addr", &self.ws_addr)
             .field("ipc_server_config", &self.ipc_server_config)
             .field("ipc_endpoint", &self.ipc_endpoint.as_ref().map(|endpoint| endpoint.path()))
+            .field("rate_limit", &self.rate_limit)
+            .field("namespace_gate", &self.namespace_gate)
+            .field("response_cache", &self.response_cache)
             .finish()
     }
 }
@@ -1150,6 +1215,30 @@ impl RpcServerConfig {
         self
     }
 
+    /// Configures a per-IP rate limit for the http and ws servers.
+    ///
+    /// See [RateLimitLayer] for how clients are identified.
+    pub fn with_rate_limit(mut self, rate_limit: Option<RateLimitConfig>) -> Self {
+        self.rate_limit = rate_limit;
+        self
+    }
+
+    /// Configures a [NamespaceGate] for the http and ws servers that can be used to disable rpc
+    /// namespaces at runtime.
+    pub fn with_namespace_gate(mut self, namespace_gate: Option<NamespaceGate>) -> Self {
+        self.namespace_gate = namespace_gate;
+        self
+    }
+
+    /// Configures a [ResponseCache] for the http server.
+    ///
+    /// The head of the cache must be kept up to date, see
+    /// [response_cache_head_task](response_cache::response_cache_head_task).
+    pub fn with_response_cache(mut self, response_cache: Option<ResponseCache>) -> Self {
+        self.response_cache = response_cache;
+        self
+    }
+
     /// Configures the ws server
     ///
     /// Note: this always configures an [EthSubscriptionIdProvider] [IdProvider] for convenience.
@@ -1204,6 +1293,23 @@ impl RpcServerConfig {
         self
     }
 
+    /// Runs all configured servers and their connections on the runtime of the given handle.
+    ///
+    /// By default the servers are spawned onto the runtime they are started from.
+    pub fn with_tokio_runtime(mut self, handle: tokio::runtime::Handle) -> Self {
+        if let Some(http) = self.http_server_config {
+            self.http_server_config = Some(http.custom_tokio_runtime(handle.clone()));
+        }
+        if let Some(ws) = self.ws_server_config {
+            self.ws_server_config = Some(ws.custom_tokio_runtime(handle.clone()));
+        }
+        if let Some(ipc) = self.ipc_server_config {
+            self.ipc_server_config = Some(ipc.custom_tokio_runtime(handle));
+        }
+
+        self
+    }
+
     /// Configures the endpoint of the ipc server
     ///
     /// Default is [DEFAULT_IPC_ENDPOINT]
@@ -1254,6 +1360,8 @@ impl RpcServerConfig {
             .ws_addr
             .unwrap_or(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, DEFAULT_WS_RPC_PORT)));
         let metrics = RpcServerMetrics::default();
+        // the http and ws servers share the buckets of the clients
+        let rate_limit = self.rate_limit.map(RateLimitLayer::new);
         // If both are configured on the same port, we combine them into one server.
         if self.http_addr == self.ws_addr &&
             self.http_server_config.is_some() &&
@@ -1284,6 +1392,9 @@ impl RpcServerConfig {
                 builder,
                 http_socket_addr,
                 cors,
+                rate_limit,
+                self.namespace_gate.clone(),
+                self.response_cache.clone(),
                 ServerKind::WsHttp(http_socket_addr),
                 metrics.clone(),
             )
@@ -1306,6 +1417,9 @@ impl RpcServerConfig {
                 builder,
                 ws_socket_addr,
                 self.ws_cors_domains.take(),
+                rate_limit.clone(),
+                self.namespace_gate.clone(),
+                None,
                 ServerKind::WS(ws_socket_addr),
                 metrics.clone(),
             )
@@ -1320,6 +1434,9 @@ impl RpcServerConfig {
                 builder,
                 http_socket_addr,
                 self.http_cors_domains.take(),
+                rate_limit,
+                self.namespace_gate.clone(),
+                self.response_cache.clone(),
                 ServerKind::Http(http_socket_addr),
                 metrics.clone(),
             )
@@ -1536,6 +1653,18 @@ impl TransportRpcModules {
         Ok(())
     }
 
+    /// Removes all methods from the http and ws modules for which the predicate returns `false`.
+    ///
+    /// The ipc module is left untouched, since it can only be reached locally.
+    pub fn retain_http_ws_methods(&mut self, mut f: impl FnMut(&str) -> bool) {
+        for module in [self.http.as_mut(), self.ws.as_mut()].into_iter().flatten() {
+            let removed = module.method_names().filter(|method| !f(method)).collect::<Vec<_>>();
+            for method in removed {
+                module.remove_method(method);
+            }
+        }
+    }
+
     /// Convenience function for starting a server
     pub async fn start_server(self, builder: RpcServerConfig) -> Result<RpcServerHandle, RpcError> {
         builder.start(self).await
@@ -1615,6 +1744,22 @@ enum WsHttpServerKind {
     Plain(Server<Identity, RpcServerMetrics>),
     /// Http server with cors
     WithCors(Server<Stack<CorsLayer, Identity>, RpcServerMetrics>),
+    /// Http server with optional cors, rate limiting, namespace gate and response cache
+    WithMiddleware(
+        Server<
+            Stack<
+                Either<ResponseCache, Identity>,
+                Stack<
+                    Either<NamespaceGate, Identity>,
+                    Stack<
+                        Either<RateLimitLayer, Identity>,
+                        Stack<Either<CorsLayer, Identity>, Identity>,
+                    >,
+                >,
+            >,
+            RpcServerMetrics,
+        >,
+    ),
 }
 
 // === impl WsHttpServerKind ===
@@ -1625,6 +1770,7 @@ impl WsHttpServerKind {
         match self {
             WsHttpServerKind::Plain(server) => server.start(module),
             WsHttpServerKind::WithCors(server) => server.start(module),
+            WsHttpServerKind::WithMiddleware(server) => server.start(module),
         }
     }
 
@@ -1633,10 +1779,33 @@ impl WsHttpServerKind {
         builder: ServerBuilder,
         socket_addr: SocketAddr,
         cors_domains: Option<String>,
+        rate_limit: Option<RateLimitLayer>,
+        namespace_gate: Option<NamespaceGate>,
+        response_cache: Option<ResponseCache>,
         server_kind: ServerKind,
         metrics: RpcServerMetrics,
     ) -> Result<(Self, SocketAddr), RpcError> {
-        if let Some(cors) = cors_domains.as_deref().map(cors::create_cors_layer) {
+        if rate_limit.is_some() || namespace_gate.is_some() || response_cache.is_some() {
+            let cors = cors_domains
+                .as_deref()
+                .map(cors::create_cors_layer)
+                .transpose()
+                .map_err(|err| RpcError::Custom(err.to_string()))?;
+            let middleware = tower::ServiceBuilder::new()
+                .option_layer(cors)
+                .option_layer(rate_limit)
+                .option_layer(namespace_gate)
+                .option_layer(response_cache);
+            let server = builder
+                .set_middleware(middleware)
+                .set_logger(metrics)
+                .build(socket_addr)
+                .await
+                .map_err(|err| RpcError::from_jsonrpsee_error(err, server_kind))?;
+            let local_addr = server.local_addr()?;
+            let server = WsHttpServerKind::WithMiddleware(server);
+            Ok((server, local_addr))
+        } else if let Some(cors) = cors_domains.as_deref().map(cors::create_cors_layer) {
             let cors = cors.map_err(|err| RpcError::Custom(err.to_string()))?;
             let middleware = tower::ServiceBuilder::new().layer(cors);
             let server = builder
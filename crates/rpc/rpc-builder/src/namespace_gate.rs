@@ -0,0 +1,249 @@
+use crate::RethRpcModule;
+use hyper::{
+    body::{Bytes, HttpBody},
+    header::{CONTENT_LENGTH, CONTENT_TYPE},
+    Body, Method, Request, Response, StatusCode,
+};
+use jsonrpsee::{
+    core::RpcResult,
+    types::error::{ErrorObject, INVALID_PARAMS_CODE, METHOD_NOT_FOUND_CODE},
+};
+use reth_rpc_api::AdminRpcNamespacesApiServer;
+use serde_json::Value;
+use std::{
+    collections::HashSet,
+    future::Future,
+    pin::Pin,
+    str::FromStr,
+    sync::{Arc, RwLock},
+    task::{Context, Poll},
+};
+use tower::{Layer, Service};
+
+/// Requests with a larger body are not inspected and passed to the server as is, which rejects
+/// them if they exceed its own limit.
+const MAX_INSPECTED_BODY_SIZE: usize = 100 * 1024 * 1024;
+
+/// Tracks rpc namespaces that were disabled at runtime.
+///
+/// This is a [Layer] for the http and ws servers that rejects calls to methods of disabled
+/// namespaces with a "method not found" error. Namespaces are switched on and off via the
+/// [AdminRpcNamespacesApiServer] methods, which should only be exposed on the authenticated
+/// server.
+///
+/// Note: Only http requests and ws handshakes pass through the middleware, messages of already
+/// established ws connections are not affected.
+#[derive(Debug, Clone, Default)]
+pub struct NamespaceGate {
+    disabled: Arc<RwLock<HashSet<RethRpcModule>>>,
+}
+
+impl NamespaceGate {
+    /// Disables the given namespace, returns `false` if it was already disabled.
+    pub fn disable(&self, module: RethRpcModule) -> bool {
+        self.disabled.write().expect("not poisoned; qed").insert(module)
+    }
+
+    /// Enables the given namespace, returns `false` if it was not disabled.
+    pub fn enable(&self, module: RethRpcModule) -> bool {
+        self.disabled.write().expect("not poisoned; qed").remove(&module)
+    }
+
+    /// Returns all disabled namespaces.
+    pub fn disabled(&self) -> Vec<RethRpcModule> {
+        self.disabled.read().expect("not poisoned; qed").iter().copied().collect()
+    }
+
+    /// Returns `true` if the namespace of the given method, e.g. `trace` for
+    /// `trace_replayTransaction`, is not disabled.
+    pub fn is_method_enabled(&self, method: &str) -> bool {
+        let namespace = method.split_once('_').map(|(namespace, _)| namespace).unwrap_or(method);
+        !self
+            .disabled
+            .read()
+            .expect("not poisoned; qed")
+            .iter()
+            .any(|module| module.as_ref() == namespace)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.disabled.read().expect("not poisoned; qed").is_empty()
+    }
+
+    /// Returns an error response if the request calls a method of a disabled namespace.
+    ///
+    /// Batches are rejected as a whole.
+    fn check_request(&self, body: &[u8]) -> Option<Response<Body>> {
+        let (method, id) = match serde_json::from_slice::<Value>(body).ok()? {
+            Value::Object(call) => {
+                let method = call.get("method")?.as_str()?;
+                (method.to_string(), call.get("id").cloned().unwrap_or(Value::Null))
+            }
+            Value::Array(batch) => {
+                let method = batch
+                    .iter()
+                    .filter_map(|call| call.get("method")?.as_str())
+                    .find(|method| !self.is_method_enabled(method))?;
+                return Some(disabled_method_response(method, Value::Null))
+            }
+            _ => return None,
+        };
+        (!self.is_method_enabled(&method)).then(|| disabled_method_response(&method, id))
+    }
+}
+
+impl<S> Layer<S> for NamespaceGate {
+    type Service = NamespaceGateService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        NamespaceGateService { inner, gate: self.clone() }
+    }
+}
+
+impl AdminRpcNamespacesApiServer for NamespaceGate {
+    /// Handler for `admin_enableRpcNamespace`
+    fn enable_rpc_namespace(&self, namespace: String) -> RpcResult<bool> {
+        Ok(self.enable(parse_namespace(&namespace)?))
+    }
+
+    /// Handler for `admin_disableRpcNamespace`
+    fn disable_rpc_namespace(&self, namespace: String) -> RpcResult<bool> {
+        Ok(self.disable(parse_namespace(&namespace)?))
+    }
+
+    /// Handler for `admin_disabledRpcNamespaces`
+    fn disabled_rpc_namespaces(&self) -> RpcResult<Vec<String>> {
+        let mut namespaces: Vec<_> = self.disabled().iter().map(ToString::to_string).collect();
+        namespaces.sort_unstable();
+        Ok(namespaces)
+    }
+}
+
+/// The [Service] created by [NamespaceGate].
+#[derive(Debug, Clone)]
+pub struct NamespaceGateService<S> {
+    inner: S,
+    gate: NamespaceGate,
+}
+
+impl<S> Service<Request<Body>> for NamespaceGateService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let exceeds_limit = request
+            .headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok()?.parse::<usize>().ok())
+            .map_or(false, |len| len > MAX_INSPECTED_BODY_SIZE);
+        if self.gate.is_empty() || request.method() != Method::POST || exceeds_limit {
+            return Box::pin(self.inner.call(request))
+        }
+
+        // use the service that was driven to readiness
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let gate = self.gate.clone();
+        Box::pin(async move {
+            let (parts, body) = request.into_parts();
+            let body = match read_body(body).await {
+                Ok(body) => body,
+                Err(response) => return Ok(response),
+            };
+            if let Some(response) = gate.check_request(&body) {
+                return Ok(response)
+            }
+            inner.call(Request::from_parts(parts, Body::from(body))).await
+        })
+    }
+}
+
+/// Buffers the body of a request, returns an error response if it can't be read or is too large.
+pub(crate) async fn read_body(mut body: Body) -> Result<Bytes, Response<Body>> {
+    let mut buf = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(|_| status_response(StatusCode::BAD_REQUEST))?;
+        if buf.len() + chunk.len() > MAX_INSPECTED_BODY_SIZE {
+            return Err(status_response(StatusCode::PAYLOAD_TOO_LARGE))
+        }
+        buf.extend_from_slice(&chunk);
+    }
+    Ok(buf.into())
+}
+
+pub(crate) fn status_response(status: StatusCode) -> Response<Body> {
+    Response::builder().status(status).body(Body::empty()).expect("valid response; qed")
+}
+
+fn disabled_method_response(method: &str, id: Value) -> Response<Body> {
+    let body = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": {
+            "code": METHOD_NOT_FOUND_CODE,
+            "message": format!("method {method} is disabled"),
+        },
+    });
+    Response::builder()
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .expect("valid response; qed")
+}
+
+fn parse_namespace(namespace: &str) -> RpcResult<RethRpcModule> {
+    RethRpcModule::from_str(namespace).map_err(|_| {
+        ErrorObject::owned(
+            INVALID_PARAMS_CODE,
+            format!("unknown rpc namespace {namespace}"),
+            None::<()>,
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disable_namespace() {
+        let gate = NamespaceGate::default();
+        assert!(gate.is_method_enabled("trace_block"));
+
+        assert!(gate.disable(RethRpcModule::Trace));
+        assert!(!gate.disable(RethRpcModule::Trace));
+        assert!(!gate.is_method_enabled("trace_block"));
+        assert!(gate.is_method_enabled("eth_blockNumber"));
+        assert_eq!(gate.disabled(), vec![RethRpcModule::Trace]);
+
+        assert!(gate.enable(RethRpcModule::Trace));
+        assert!(!gate.enable(RethRpcModule::Trace));
+        assert!(gate.is_method_enabled("trace_block"));
+    }
+
+    #[test]
+    fn check_requests() {
+        let gate = NamespaceGate::default();
+        gate.disable(RethRpcModule::Debug);
+
+        let call = br#"{"jsonrpc":"2.0","id":1,"method":"debug_traceTransaction","params":[]}"#;
+        assert!(gate.check_request(call).is_some());
+
+        let call = br#"{"jsonrpc":"2.0","id":1,"method":"eth_chainId","params":[]}"#;
+        assert!(gate.check_request(call).is_none());
+
+        let batch = br#"[{"jsonrpc":"2.0","id":1,"method":"eth_chainId"},{"jsonrpc":"2.0","id":2,"method":"debug_getRawBlock"}]"#;
+        assert!(gate.check_request(batch).is_some());
+
+        assert!(gate.check_request(b"not json").is_none());
+    }
+}
@@ -0,0 +1,197 @@
+use hyper::{
+    header::{HeaderName, RETRY_AFTER},
+    Body, HeaderMap, Request, Response, StatusCode,
+};
+use std::{
+    collections::HashMap,
+    future::Future,
+    net::{IpAddr, Ipv4Addr},
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use tower::{Layer, Service};
+
+/// The number of tracked clients after which idle clients are evicted.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// Clients that haven't sent a request for this long are evicted once the limit of tracked
+/// clients is reached.
+const CLIENT_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Configuration for per-IP rate limiting of http requests and ws connection attempts.
+///
+/// Every client is given a token bucket that holds up to `burst` requests and is refilled at
+/// `requests_per_second`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitConfig {
+    /// The sustained number of requests per second allowed for a single client.
+    pub requests_per_second: u32,
+    /// The number of requests a single client can send at once.
+    pub burst: u32,
+}
+
+impl RateLimitConfig {
+    /// Creates a new config with a burst of twice the sustained rate.
+    pub fn new(requests_per_second: u32) -> Self {
+        Self { requests_per_second, burst: requests_per_second.saturating_mul(2) }
+    }
+
+    /// Sets the number of requests a single client can send at once.
+    pub fn with_burst(mut self, burst: u32) -> Self {
+        self.burst = burst;
+        self
+    }
+}
+
+/// A [Layer] that rate limits requests by client IP.
+///
+/// The servers don't expose the remote address to the middleware, so the client is identified by
+/// the `X-Forwarded-For` or `X-Real-IP` header that is set by reverse proxies. Requests without
+/// these headers share a single bucket, which makes this a global limit for servers that are
+/// exposed directly.
+#[derive(Debug, Clone)]
+pub struct RateLimitLayer {
+    limiter: Arc<RateLimiter>,
+}
+
+impl RateLimitLayer {
+    /// Creates a new layer with the given config.
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self { limiter: Arc::new(RateLimiter::new(config)) }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitService { inner, limiter: Arc::clone(&self.limiter) }
+    }
+}
+
+/// The [Service] created by [RateLimitLayer].
+#[derive(Debug, Clone)]
+pub struct RateLimitService<S> {
+    inner: S,
+    limiter: Arc<RateLimiter>,
+}
+
+impl<S> Service<Request<Body>> for RateLimitService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>>,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let client = client_ip(request.headers());
+        if !self.limiter.try_acquire(client, Instant::now()) {
+            let response = Response::builder()
+                .status(StatusCode::TOO_MANY_REQUESTS)
+                .header(RETRY_AFTER, "1")
+                .body(Body::from("rate limit exceeded"))
+                .expect("valid response; qed");
+            return Box::pin(async move { Ok(response) })
+        }
+        Box::pin(self.inner.call(request))
+    }
+}
+
+/// Returns the client IP set by a reverse proxy, if any.
+fn client_ip(headers: &HeaderMap) -> IpAddr {
+    const X_FORWARDED_FOR: HeaderName = HeaderName::from_static("x-forwarded-for");
+    const X_REAL_IP: HeaderName = HeaderName::from_static("x-real-ip");
+
+    headers
+        .get(X_FORWARDED_FOR)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(',').next())
+        .or_else(|| headers.get(X_REAL_IP).and_then(|value| value.to_str().ok()))
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED))
+}
+
+/// Token buckets of all clients.
+#[derive(Debug)]
+struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<HashMap<IpAddr, Bucket>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    fn new(config: RateLimitConfig) -> Self {
+        Self { config, buckets: Default::default() }
+    }
+
+    /// Takes a token from the bucket of the client, returns `false` if the bucket is empty.
+    fn try_acquire(&self, client: IpAddr, now: Instant) -> bool {
+        let burst = self.config.burst.max(1) as f64;
+        let mut buckets = self.buckets.lock().expect("not poisoned; qed");
+
+        if buckets.len() >= MAX_TRACKED_CLIENTS && !buckets.contains_key(&client) {
+            buckets
+                .retain(|_, bucket| now.duration_since(bucket.last_refill) < CLIENT_IDLE_TIMEOUT);
+        }
+
+        let bucket = buckets.entry(client).or_insert(Bucket { tokens: burst, last_refill: now });
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens =
+            (bucket.tokens + elapsed * self.config.requests_per_second as f64).min(burst);
+        bucket.last_refill = now;
+
+        if bucket.tokens < 1.0 {
+            return false
+        }
+        bucket.tokens -= 1.0;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits_per_client() {
+        let limiter = RateLimiter::new(RateLimitConfig::new(1).with_burst(2));
+        let now = Instant::now();
+        let (a, b): (IpAddr, IpAddr) = ([1, 1, 1, 1].into(), [2, 2, 2, 2].into());
+
+        assert!(limiter.try_acquire(a, now));
+        assert!(limiter.try_acquire(a, now));
+        assert!(!limiter.try_acquire(a, now));
+
+        // other clients have their own bucket
+        assert!(limiter.try_acquire(b, now));
+
+        // refilled after a second
+        assert!(limiter.try_acquire(a, now + Duration::from_secs(1)));
+        assert!(!limiter.try_acquire(a, now + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn client_ip_from_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(client_ip(&headers), IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+
+        headers.insert("x-real-ip", "10.0.0.2".parse().unwrap());
+        assert_eq!(client_ip(&headers), "10.0.0.2".parse::<IpAddr>().unwrap());
+
+        headers.insert("x-forwarded-for", "10.0.0.1, 192.168.0.1".parse().unwrap());
+        assert_eq!(client_ip(&headers), "10.0.0.1".parse::<IpAddr>().unwrap());
+    }
+}
@@ -0,0 +1,320 @@
+use crate::namespace_gate::{read_body, status_response};
+use futures::{Stream, StreamExt};
+use hyper::{body::Bytes, header::CONTENT_TYPE, Body, Method, Request, Response, StatusCode};
+use reth_primitives::{BlockNumber, H256};
+use reth_provider::{BlockIdReader, CanonStateNotification};
+use schnellru::{ByLength, LruMap};
+use serde_json::Value;
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex, RwLock},
+    task::{Context, Poll},
+};
+use tower::{Layer, Service};
+
+/// The default number of responses kept in the [ResponseCache].
+pub const DEFAULT_RESPONSE_CACHE_MAX_LEN: u32 = 2_000;
+
+/// Larger results are not cached, which bounds the memory of the cache to 512MB by default.
+const MAX_CACHED_RESULT_SIZE: usize = 256 * 1024;
+
+/// Methods whose result never changes.
+const STATIC_METHODS: &[&str] = &["eth_chainId", "net_version", "web3_clientVersion"];
+
+/// Methods addressed by block number whose result never changes for finalized blocks.
+///
+/// Only calls with an explicit block number or hash are cached, block tags like `latest` or
+/// `pending` are passed to the server.
+const BLOCK_NUMBER_METHODS: &[&str] = &[
+    "eth_getBlockByNumber",
+    "eth_getBlockReceipts",
+    "eth_getBlockTransactionCountByNumber",
+    "eth_getTransactionByBlockNumberAndIndex",
+    "eth_getUncleByBlockNumberAndIndex",
+    "eth_getUncleCountByBlockNumber",
+];
+
+/// Methods whose result can change with the canonical chain, e.g. receipts of transactions that
+/// were reorged out.
+const HEAD_METHODS: &[&str] = &[
+    "eth_getBlockByHash",
+    "eth_getBlockTransactionCountByHash",
+    "eth_getTransactionByBlockHashAndIndex",
+    "eth_getTransactionByHash",
+    "eth_getTransactionReceipt",
+];
+
+/// A [Layer] for the http server that caches the responses of idempotent read methods.
+///
+/// Responses are keyed by the method, its params and, for methods whose result depends on the
+/// canonical chain, the hash of the current head. Entries of previous heads are never served and
+/// age out of the LRU. Blocks at or below the finalized block are cached independently of the
+/// head, as are methods whose result never changes. Calls addressed by a block tag (e.g. `latest`)
+/// are never cached. Only successful, non-null results of single calls are cached, batches are
+/// passed to the server as is.
+///
+/// The head is updated by [response_cache_head_task].
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+    inner: Arc<ResponseCacheInner>,
+}
+
+#[derive(Debug)]
+struct ResponseCacheInner {
+    /// Cached results by request.
+    entries: Mutex<LruMap<CacheKey, Bytes, ByLength>>,
+    /// The current head of the canonical chain.
+    head: RwLock<ChainHead>,
+}
+
+/// The chain state the cached responses depend on.
+#[derive(Debug, Default, Clone, Copy)]
+struct ChainHead {
+    hash: H256,
+    finalized: Option<BlockNumber>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    method: String,
+    params: String,
+    /// The head the response was served at, `None` if it does not depend on the head.
+    head: Option<H256>,
+}
+
+impl ResponseCache {
+    /// Creates a new cache that keeps at most `max_len` responses.
+    pub fn new(max_len: u32) -> Self {
+        let inner = ResponseCacheInner {
+            entries: Mutex::new(LruMap::new(ByLength::new(max_len))),
+            head: Default::default(),
+        };
+        Self { inner: Arc::new(inner) }
+    }
+
+    /// Sets the current head and finalized block of the canonical chain.
+    pub fn on_new_head(&self, hash: H256, finalized: Option<BlockNumber>) {
+        *self.inner.head.write().expect("not poisoned; qed") = ChainHead { hash, finalized };
+    }
+
+    /// Returns the number of cached responses.
+    pub fn len(&self) -> usize {
+        self.inner.entries.lock().expect("not poisoned; qed").len()
+    }
+
+    /// Returns `true` if no responses are cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the key of a call, `None` if the response of the call can't be cached.
+    fn cache_key(&self, method: &str, params: &Value) -> Option<CacheKey> {
+        let head = *self.inner.head.read().expect("not poisoned; qed");
+        let head = if STATIC_METHODS.contains(&method) {
+            None
+        } else if BLOCK_NUMBER_METHODS.contains(&method) {
+            match explicit_block(params.get(0)?)? {
+                ExplicitBlock::Number(number)
+                    if head.finalized.map_or(false, |finalized| number <= finalized) =>
+                {
+                    None
+                }
+                _ => Some(head.hash),
+            }
+        } else if HEAD_METHODS.contains(&method) {
+            Some(head.hash)
+        } else {
+            return None
+        };
+        Some(CacheKey { method: method.to_string(), params: params.to_string(), head })
+    }
+
+    fn get(&self, key: &CacheKey) -> Option<Bytes> {
+        self.inner.entries.lock().expect("not poisoned; qed").get(key).cloned()
+    }
+
+    fn insert(&self, key: CacheKey, result: Bytes) {
+        self.inner.entries.lock().expect("not poisoned; qed").insert(key, result);
+    }
+}
+
+impl Default for ResponseCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_RESPONSE_CACHE_MAX_LEN)
+    }
+}
+
+impl<S> Layer<S> for ResponseCache {
+    type Service = ResponseCacheService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ResponseCacheService { inner, cache: self.clone() }
+    }
+}
+
+/// The [Service] created by [ResponseCache].
+#[derive(Debug, Clone)]
+pub struct ResponseCacheService<S> {
+    inner: S,
+    cache: ResponseCache,
+}
+
+impl<S> Service<Request<Body>> for ResponseCacheService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        if request.method() != Method::POST {
+            return Box::pin(self.inner.call(request))
+        }
+
+        // use the service that was driven to readiness
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let cache = self.cache.clone();
+        Box::pin(async move {
+            let (parts, body) = request.into_parts();
+            let body = match read_body(body).await {
+                Ok(body) => body,
+                Err(response) => return Ok(response),
+            };
+
+            let call = serde_json::from_slice::<Value>(&body).ok().and_then(|call| {
+                let key = cache.cache_key(
+                    call.get("method")?.as_str()?,
+                    call.get("params").unwrap_or(&Value::Null),
+                )?;
+                Some((key, call.get("id").cloned().unwrap_or(Value::Null)))
+            });
+            let Some((key, id)) = call else {
+                return inner.call(Request::from_parts(parts, Body::from(body))).await
+            };
+
+            if let Some(result) = cache.get(&key) {
+                return Ok(result_response(&id, &result))
+            }
+
+            let response = inner.call(Request::from_parts(parts, Body::from(body))).await?;
+            if response.status() != StatusCode::OK {
+                return Ok(response)
+            }
+            let (parts, body) = response.into_parts();
+            let body = match hyper::body::to_bytes(body).await {
+                Ok(body) => body,
+                Err(_) => return Ok(status_response(StatusCode::INTERNAL_SERVER_ERROR)),
+            };
+            if body.len() <= MAX_CACHED_RESULT_SIZE {
+                let result = serde_json::from_slice::<Value>(&body)
+                    .ok()
+                    .and_then(|mut response| response.get_mut("result").map(Value::take))
+                    .filter(|result| !result.is_null());
+                if let Some(result) = result {
+                    cache.insert(key, result.to_string().into());
+                }
+            }
+            Ok(Response::from_parts(parts, Body::from(body)))
+        })
+    }
+}
+
+/// A block that is addressed explicitly, as opposed to a block tag.
+enum ExplicitBlock {
+    Number(BlockNumber),
+    Hash,
+}
+
+/// Returns the block a call is addressed to if it is given by number or hash, `None` for block tags
+/// like `latest` or `pending` whose block changes over time.
+fn explicit_block(block: &Value) -> Option<ExplicitBlock> {
+    // block ids can also be given as `{"blockHash": ..}` or `{"blockNumber": ..}`
+    if let Some(object) = block.as_object() {
+        if object.contains_key("blockHash") {
+            return Some(ExplicitBlock::Hash)
+        }
+        return explicit_block(object.get("blockNumber")?)
+    }
+    let hex = block.as_str()?.strip_prefix("0x")?;
+    if hex.len() == 64 {
+        return Some(ExplicitBlock::Hash)
+    }
+    u64::from_str_radix(hex, 16).ok().map(ExplicitBlock::Number)
+}
+
+fn result_response(id: &Value, result: &[u8]) -> Response<Body> {
+    let mut body = format!(r#"{{"jsonrpc":"2.0","id":{id},"result":"#).into_bytes();
+    body.extend_from_slice(result);
+    body.push(b'}');
+    Response::builder()
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(body))
+        .expect("valid response; qed")
+}
+
+/// Awaits new canonical chain events and updates the head of the [ResponseCache].
+pub async fn response_cache_head_task<Provider, St>(
+    cache: ResponseCache,
+    provider: Provider,
+    mut events: St,
+) where
+    Provider: BlockIdReader,
+    St: Stream<Item = CanonStateNotification> + Unpin + 'static,
+{
+    while let Some(event) = events.next().await {
+        let finalized = provider.finalized_block_number().ok().flatten();
+        cache.on_new_head(event.tip().hash, finalized);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn response_cache_keys() {
+        let cache = ResponseCache::new(10);
+        cache.on_new_head(H256::repeat_byte(1), Some(100));
+
+        let key = cache.cache_key("eth_chainId", &json!([])).unwrap();
+        assert_eq!(key.head, None);
+        assert!(cache.cache_key("eth_blockNumber", &json!([])).is_none());
+
+        // finalized blocks don't depend on the head
+        let finalized = cache.cache_key("eth_getBlockByNumber", &json!(["0x64", false])).unwrap();
+        assert_eq!(finalized.head, None);
+        let unfinalized = cache.cache_key("eth_getBlockByNumber", &json!(["0x65", false])).unwrap();
+        assert_eq!(unfinalized.head, Some(H256::repeat_byte(1)));
+
+        // block tags are never cached
+        for tag in ["latest", "pending", "safe", "finalized", "earliest"] {
+            assert!(cache.cache_key("eth_getBlockByNumber", &json!([tag, false])).is_none());
+        }
+        assert!(cache.cache_key("eth_getBlockReceipts", &json!(["pending"])).is_none());
+        assert!(cache.cache_key("eth_getBlockByNumber", &json!([])).is_none());
+
+        // block hashes depend on the head
+        let hash = format!("{:?}", H256::repeat_byte(3));
+        let by_hash = cache.cache_key("eth_getBlockReceipts", &json!([hash])).unwrap();
+        assert_eq!(by_hash.head, Some(H256::repeat_byte(1)));
+        let by_hash = cache.cache_key("eth_getBlockReceipts", &json!([{ "blockHash": hash }]));
+        assert_eq!(by_hash.unwrap().head, Some(H256::repeat_byte(1)));
+
+        // entries of a previous head are not served
+        cache.insert(unfinalized.clone(), Bytes::from_static(b"{}"));
+        assert!(cache.get(&unfinalized).is_some());
+        cache.on_new_head(H256::repeat_byte(2), Some(100));
+        let unfinalized = cache.cache_key("eth_getBlockByNumber", &json!(["0x65", false])).unwrap();
+        assert!(cache.get(&unfinalized).is_none());
+    }
+}
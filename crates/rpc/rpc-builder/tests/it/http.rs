@@ -40,6 +40,7 @@ where
     let id = EthFilterApiClient::new_block_filter(client).await.unwrap();
     EthFilterApiClient::filter_changes(client, id.clone()).await.unwrap();
     EthFilterApiClient::logs(client, Filter::default()).await.unwrap();
+    EthFilterApiClient::logs_page(client, Filter::default(), None).await.unwrap();
     let id = EthFilterApiClient::new_filter(client, Filter::default()).await.unwrap();
     EthFilterApiClient::filter_logs(client, id.clone()).await.unwrap();
     EthFilterApiClient::uninstall_filter(client, id).await.unwrap();
@@ -84,6 +85,7 @@ where
     EthApiClient::balance(client, address, None).await.unwrap();
     EthApiClient::transaction_count(client, address, None).await.unwrap();
     EthApiClient::storage_at(client, address, U256::default().into(), None).await.unwrap();
+    EthApiClient::get_account(client, address, None).await.unwrap();
     EthApiClient::block_by_hash(client, hash, false).await.unwrap();
     EthApiClient::block_by_number(client, block_number, false).await.unwrap();
     EthApiClient::block_transaction_count_by_number(client, block_number).await.unwrap();
@@ -109,11 +111,13 @@ where
         .await
         .unwrap();
     EthApiClient::syncing(client).await.unwrap();
+    EthApiClient::sign_transaction(client, transaction_request.clone()).await.unwrap_err();
     EthApiClient::send_transaction(client, transaction_request).await.unwrap_err();
     EthApiClient::hashrate(client).await.unwrap();
     EthApiClient::submit_hashrate(client, U256::default(), H256::default()).await.unwrap();
     EthApiClient::gas_price(client).await.unwrap_err();
     EthApiClient::max_priority_fee_per_gas(client).await.unwrap_err();
+    EthApiClient::blob_base_fee(client).await.unwrap_err();
 
     // Unimplemented
     assert!(is_unimplemented(
@@ -128,9 +132,6 @@ where
             .err()
             .unwrap()
     ));
-    assert!(is_unimplemented(
-        EthApiClient::sign_transaction(client, call_request.clone()).await.err().unwrap()
-    ));
 }
 
 async fn test_basic_debug_calls<C>(client: &C)
@@ -143,6 +144,7 @@ where
     DebugApiClient::raw_block(client, block_id).await.unwrap();
     DebugApiClient::raw_transaction(client, H256::default()).await.unwrap();
     DebugApiClient::raw_receipts(client, block_id).await.unwrap();
+    DebugApiClient::debug_preimage(client, H256::default()).await.unwrap();
     assert!(is_unimplemented(DebugApiClient::bad_blocks(client).await.err().unwrap()));
 }
 
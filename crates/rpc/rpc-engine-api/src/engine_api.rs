@@ -1,5 +1,6 @@
 use crate::{
-    payload::PayloadOrAttributes, EngineApiError, EngineApiMessageVersion, EngineApiResult,
+    metrics::EngineApiMetrics, payload::PayloadOrAttributes, EngineApiError,
+    EngineApiMessageVersion, EngineApiResult,
 };
 use async_trait::async_trait;
 use jsonrpsee_core::RpcResult;
@@ -18,7 +19,7 @@ use reth_rpc_types::engine::{
 use reth_tasks::TaskSpawner;
 use std::sync::Arc;
 use tokio::sync::oneshot;
-use tracing::trace;
+use tracing::{debug, instrument, trace};
 
 /// The Engine API response sender.
 pub type EngineApiSender<Ok> = oneshot::Sender<EngineApiResult<Ok>>;
@@ -43,6 +44,8 @@ struct EngineApiInner<Provider> {
     payload_store: PayloadStore,
     /// For spawning and executing async tasks
     task_spawner: Box<dyn TaskSpawner>,
+    /// Engine API metrics
+    metrics: EngineApiMetrics,
 }
 
 impl<Provider> EngineApi<Provider>
@@ -63,12 +66,14 @@ where
             beacon_consensus,
             payload_store,
             task_spawner,
+            metrics: EngineApiMetrics::default(),
         });
         Self { inner }
     }
 
     /// See also <https://github.com/ethereum/execution-apis/blob/3d627c95a4d3510a8187dd02e0250ecb4331d27e/src/engine/paris.md#engine_newpayloadv1>
     /// Caution: This should not accept the `withdrawals` field
+    #[instrument(level = "debug", skip_all, fields(block_hash = ?payload.block_hash), target = "rpc::engine")]
     pub async fn new_payload_v1(
         &self,
         payload: ExecutionPayloadV1,
@@ -80,6 +85,7 @@ where
     }
 
     /// See also <https://github.com/ethereum/execution-apis/blob/3d627c95a4d3510a8187dd02e0250ecb4331d27e/src/engine/shanghai.md#engine_newpayloadv2>
+    #[instrument(level = "debug", skip_all, fields(block_hash = ?payload.payload_inner.block_hash), target = "rpc::engine")]
     pub async fn new_payload_v2(
         &self,
         payload: ExecutionPayloadV2,
@@ -91,6 +97,7 @@ where
     }
 
     /// See also <https://github.com/ethereum/execution-apis/blob/fe8e13c288c592ec154ce25c534e26cb7ce0530d/src/engine/cancun.md#engine_newpayloadv3>
+    #[instrument(level = "debug", skip_all, fields(block_hash = ?payload.payload_inner.payload_inner.block_hash), target = "rpc::engine")]
     pub async fn new_payload_v3(
         &self,
         payload: ExecutionPayloadV3,
@@ -113,6 +120,7 @@ where
     /// See also <https://github.com/ethereum/execution-apis/blob/3d627c95a4d3510a8187dd02e0250ecb4331d27e/src/engine/paris.md#engine_forkchoiceUpdatedV1>
     ///
     /// Caution: This should not accept the `withdrawals` field
+    #[instrument(level = "debug", skip_all, fields(block_hash = ?state.head_block_hash), target = "rpc::engine")]
     pub async fn fork_choice_updated_v1(
         &self,
         state: ForkchoiceState,
@@ -128,6 +136,7 @@ where
     /// but only _after_ shanghai.
     ///
     /// See also <https://github.com/ethereum/execution-apis/blob/3d627c95a4d3510a8187dd02e0250ecb4331d27e/src/engine/shanghai.md#engine_forkchoiceupdatedv2>
+    #[instrument(level = "debug", skip_all, fields(block_hash = ?state.head_block_hash), target = "rpc::engine")]
     pub async fn fork_choice_updated_v2(
         &self,
         state: ForkchoiceState,
@@ -143,6 +152,7 @@ where
     /// but only _after_ cancun.
     ///
     /// See also  <https://github.com/ethereum/execution-apis/blob/main/src/engine/cancun.md#engine_forkchoiceupdatedv3>
+    #[instrument(level = "debug", skip_all, fields(block_hash = ?state.head_block_hash), target = "rpc::engine")]
     pub async fn fork_choice_updated_v3(
         &self,
         state: ForkchoiceState,
@@ -376,6 +386,23 @@ where
         }
     }
 
+    /// Returns the engine methods supported by this node, see [CAPABILITIES].
+    ///
+    /// The capabilities announced by the consensus layer are compared against the supported
+    /// methods, so that methods the consensus layer won't call can be detected early.
+    pub fn exchange_capabilities(&self, capabilities: Vec<String>) -> Vec<String> {
+        let missing = CAPABILITIES
+            .into_iter()
+            .filter(|method| !capabilities.iter().any(|cap| cap == method))
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            debug!(target: "rpc::engine", ?missing, "Consensus layer does not support all engine methods");
+        }
+        self.inner.metrics.missing_cl_capabilities.set(missing.len() as f64);
+
+        CAPABILITIES.into_iter().map(str::to_owned).collect()
+    }
+
     /// Validates the presence of the `withdrawals` field according to the payload timestamp.
     /// After Shanghai, withdrawals field must be [Some].
     /// Before Shanghai, withdrawals field must be [None];
@@ -493,6 +520,7 @@ where
     /// Caution: This should not accept the `withdrawals` field
     async fn new_payload_v1(&self, payload: ExecutionPayloadV1) -> RpcResult<PayloadStatus> {
         trace!(target: "rpc::engine", "Serving engine_newPayloadV1");
+        self.inner.metrics.new_payload_v1.increment(1);
         Ok(EngineApi::new_payload_v1(self, payload).await?)
     }
 
@@ -500,6 +528,7 @@ where
     /// See also <https://github.com/ethereum/execution-apis/blob/3d627c95a4d3510a8187dd02e0250ecb4331d27e/src/engine/shanghai.md#engine_newpayloadv2>
     async fn new_payload_v2(&self, payload: ExecutionPayloadV2) -> RpcResult<PayloadStatus> {
         trace!(target: "rpc::engine", "Serving engine_newPayloadV2");
+        self.inner.metrics.new_payload_v2.increment(1);
         Ok(EngineApi::new_payload_v2(self, payload).await?)
     }
 
@@ -512,6 +541,7 @@ where
         parent_beacon_block_root: H256,
     ) -> RpcResult<PayloadStatus> {
         trace!(target: "rpc::engine", "Serving engine_newPayloadV3");
+        self.inner.metrics.new_payload_v3.increment(1);
         Ok(EngineApi::new_payload_v3(self, payload, versioned_hashes, parent_beacon_block_root)
             .await?)
     }
@@ -526,6 +556,7 @@ where
         payload_attributes: Option<PayloadAttributes>,
     ) -> RpcResult<ForkchoiceUpdated> {
         trace!(target: "rpc::engine", "Serving engine_forkchoiceUpdatedV1");
+        self.inner.metrics.fork_choice_updated_v1.increment(1);
         Ok(EngineApi::fork_choice_updated_v1(self, fork_choice_state, payload_attributes).await?)
     }
 
@@ -537,6 +568,7 @@ where
         payload_attributes: Option<PayloadAttributes>,
     ) -> RpcResult<ForkchoiceUpdated> {
         trace!(target: "rpc::engine", "Serving engine_forkchoiceUpdatedV2");
+        self.inner.metrics.fork_choice_updated_v2.increment(1);
         Ok(EngineApi::fork_choice_updated_v2(self, fork_choice_state, payload_attributes).await?)
     }
 
@@ -549,6 +581,7 @@ where
         payload_attributes: Option<PayloadAttributes>,
     ) -> RpcResult<ForkchoiceUpdated> {
         trace!(target: "rpc::engine", "Serving engine_forkchoiceUpdatedV3");
+        self.inner.metrics.fork_choice_updated_v3.increment(1);
         Ok(EngineApi::fork_choice_updated_v3(self, fork_choice_state, payload_attributes).await?)
     }
 
@@ -565,6 +598,7 @@ where
     /// > Provider software MAY stop the corresponding build process after serving this call.
     async fn get_payload_v1(&self, payload_id: PayloadId) -> RpcResult<ExecutionPayloadV1> {
         trace!(target: "rpc::engine", "Serving engine_getPayloadV1");
+        self.inner.metrics.get_payload_v1.increment(1);
         Ok(EngineApi::get_payload_v1(self, payload_id).await?)
     }
 
@@ -579,6 +613,7 @@ where
     /// > Provider software MAY stop the corresponding build process after serving this call.
     async fn get_payload_v2(&self, payload_id: PayloadId) -> RpcResult<ExecutionPayloadEnvelopeV2> {
         trace!(target: "rpc::engine", "Serving engine_getPayloadV2");
+        self.inner.metrics.get_payload_v2.increment(1);
         Ok(EngineApi::get_payload_v2(self, payload_id).await?)
     }
 
@@ -593,6 +628,7 @@ where
     /// > Provider software MAY stop the corresponding build process after serving this call.
     async fn get_payload_v3(&self, payload_id: PayloadId) -> RpcResult<ExecutionPayloadEnvelopeV3> {
         trace!(target: "rpc::engine", "Serving engine_getPayloadV3");
+        self.inner.metrics.get_payload_v3.increment(1);
         Ok(EngineApi::get_payload_v3(self, payload_id).await?)
     }
 
@@ -603,6 +639,7 @@ where
         block_hashes: Vec<BlockHash>,
     ) -> RpcResult<ExecutionPayloadBodiesV1> {
         trace!(target: "rpc::engine", "Serving engine_getPayloadBodiesByHashV1");
+        self.inner.metrics.get_payload_bodies_by_hash_v1.increment(1);
         Ok(EngineApi::get_payload_bodies_by_hash(self, block_hashes)?)
     }
 
@@ -627,6 +664,7 @@ where
         count: U64,
     ) -> RpcResult<ExecutionPayloadBodiesV1> {
         trace!(target: "rpc::engine", "Serving engine_getPayloadBodiesByRangeV1");
+        self.inner.metrics.get_payload_bodies_by_range_v1.increment(1);
         Ok(EngineApi::get_payload_bodies_by_range(self, start.as_u64(), count.as_u64()).await?)
     }
 
@@ -637,13 +675,16 @@ where
         config: TransitionConfiguration,
     ) -> RpcResult<TransitionConfiguration> {
         trace!(target: "rpc::engine", "Serving engine_exchangeTransitionConfigurationV1");
+        self.inner.metrics.exchange_transition_configuration_v1.increment(1);
         Ok(EngineApi::exchange_transition_configuration(self, config).await?)
     }
 
     /// Handler for `engine_exchangeCapabilitiesV1`
     /// See also <https://github.com/ethereum/execution-apis/blob/6452a6b194d7db269bf1dbd087a267251d3cc7f8/src/engine/common.md#capabilities>
-    async fn exchange_capabilities(&self, _capabilities: Vec<String>) -> RpcResult<Vec<String>> {
-        Ok(CAPABILITIES.into_iter().map(str::to_owned).collect())
+    async fn exchange_capabilities(&self, capabilities: Vec<String>) -> RpcResult<Vec<String>> {
+        trace!(target: "rpc::engine", "Serving engine_exchangeCapabilities");
+        self.inner.metrics.exchange_capabilities.increment(1);
+        Ok(EngineApi::exchange_capabilities(self, capabilities))
     }
 }
 
@@ -699,6 +740,19 @@ mod tests {
         assert_matches!(handle.from_api.recv().await, Some(BeaconEngineMessage::NewPayload { .. }));
     }
 
+    #[tokio::test]
+    async fn advertised_capabilities_are_served() {
+        let (_handle, api) = setup_engine_api();
+
+        let capabilities = api.exchange_capabilities(vec![]);
+        assert!(!capabilities.iter().any(|cap| cap == "engine_exchangeCapabilities"));
+
+        let module = api.into_rpc();
+        for capability in &capabilities {
+            assert!(module.method_names().any(|name| name == capability), "{capability}");
+        }
+    }
+
     // tests covering `engine_getPayloadBodiesByRange` and `engine_getPayloadBodiesByHash`
     mod get_payload_bodies {
         use super::*;
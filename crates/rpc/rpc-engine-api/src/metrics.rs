@@ -0,0 +1,39 @@
+use reth_metrics::{
+    metrics::{Counter, Gauge},
+    Metrics,
+};
+
+/// Beacon consensus engine API metrics, tracking how often each method version is called.
+#[derive(Metrics)]
+#[metrics(scope = "engine.rpc")]
+pub(crate) struct EngineApiMetrics {
+    /// The number of `engine_newPayloadV1` calls.
+    pub(crate) new_payload_v1: Counter,
+    /// The number of `engine_newPayloadV2` calls.
+    pub(crate) new_payload_v2: Counter,
+    /// The number of `engine_newPayloadV3` calls.
+    pub(crate) new_payload_v3: Counter,
+    /// The number of `engine_forkchoiceUpdatedV1` calls.
+    pub(crate) fork_choice_updated_v1: Counter,
+    /// The number of `engine_forkchoiceUpdatedV2` calls.
+    pub(crate) fork_choice_updated_v2: Counter,
+    /// The number of `engine_forkchoiceUpdatedV3` calls.
+    pub(crate) fork_choice_updated_v3: Counter,
+    /// The number of `engine_getPayloadV1` calls.
+    pub(crate) get_payload_v1: Counter,
+    /// The number of `engine_getPayloadV2` calls.
+    pub(crate) get_payload_v2: Counter,
+    /// The number of `engine_getPayloadV3` calls.
+    pub(crate) get_payload_v3: Counter,
+    /// The number of `engine_getPayloadBodiesByHashV1` calls.
+    pub(crate) get_payload_bodies_by_hash_v1: Counter,
+    /// The number of `engine_getPayloadBodiesByRangeV1` calls.
+    pub(crate) get_payload_bodies_by_range_v1: Counter,
+    /// The number of `engine_exchangeTransitionConfigurationV1` calls.
+    pub(crate) exchange_transition_configuration_v1: Counter,
+    /// The number of `engine_exchangeCapabilities` calls.
+    pub(crate) exchange_capabilities: Counter,
+    /// The number of supported engine methods that the consensus layer did not announce in the
+    /// last capabilities exchange.
+    pub(crate) missing_cl_capabilities: Gauge,
+}
@@ -7,26 +7,51 @@ use reth_interfaces::test_utils::generators::{
 use reth_primitives::{
     bytes::{Bytes, BytesMut},
     proofs::{self},
-    Block, SealedBlock, TransactionSigned, H256, U256,
+    Address, Block, SealedBlock, Signature, Transaction, TransactionSigned, TxEip4844, Withdrawal,
+    H256, U256,
 };
 use reth_rlp::{Decodable, DecodeError};
 use reth_rpc_types::engine::{
-    ExecutionPayload, ExecutionPayloadBodyV1, ExecutionPayloadV1, PayloadError,
+    validate_blob_versioned_hashes, CancunPayloadFields, ExecutionPayload, ExecutionPayloadBodyV1,
+    ExecutionPayloadV1, ExecutionPayloadV2, ExecutionPayloadV3, PayloadError,
 };
 
-fn transform_block<F: FnOnce(Block) -> Block>(src: SealedBlock, f: F) -> ExecutionPayload {
+fn transform_sealed_block<F: FnOnce(Block) -> Block>(src: SealedBlock, f: F) -> SealedBlock {
     let unsealed = src.unseal();
     let mut transformed: Block = f(unsealed);
     // Recalculate roots
     transformed.header.transactions_root = proofs::calculate_transaction_root(&transformed.body);
     transformed.header.ommers_hash = proofs::calculate_ommers_root(&transformed.ommers);
+    transformed.header.withdrawals_root =
+        transformed.withdrawals.as_ref().map(|w| proofs::calculate_withdrawals_root(w));
     SealedBlock {
         header: transformed.header.seal_slow(),
         body: transformed.body,
         ommers: transformed.ommers,
         withdrawals: transformed.withdrawals,
     }
-    .into()
+}
+
+fn transform_block<F: FnOnce(Block) -> Block>(src: SealedBlock, f: F) -> ExecutionPayload {
+    transform_sealed_block(src, f).into()
+}
+
+fn with_withdrawals(mut block: Block) -> Block {
+    block.withdrawals = Some(vec![Withdrawal {
+        index: 1,
+        validator_index: 2,
+        address: Address::random(),
+        amount: 3,
+    }]);
+    block
+}
+
+fn blob_transaction(blob_versioned_hashes: Vec<H256>) -> TransactionSigned {
+    let tx = TxEip4844 { blob_versioned_hashes, ..Default::default() };
+    TransactionSigned::from_transaction_and_signature(
+        Transaction::Eip4844(tx),
+        Signature::default(),
+    )
 }
 
 #[test]
@@ -128,3 +153,95 @@ fn payload_validation() {
     let valid_block = block;
     assert_matches!(TryInto::<SealedBlock>::try_into(valid_block), Ok(_));
 }
+
+#[test]
+fn payload_sealed_block_roundtrip() {
+    let mut rng = generators::rng();
+    let block = random_block(&mut rng, 100, Some(H256::random()), Some(3), Some(0));
+
+    let v1 = ExecutionPayloadV1::from(block.clone());
+    assert_eq!(v1.try_into_sealed_block().unwrap(), block);
+
+    let block = transform_sealed_block(block, with_withdrawals);
+    let v2 = ExecutionPayloadV2::from(block.clone());
+    assert_eq!(v2.try_into_sealed_block().unwrap(), block);
+
+    let parent_beacon_block_root = H256::random();
+    let block = transform_sealed_block(block, |mut b| {
+        b.header.parent_beacon_block_root = Some(parent_beacon_block_root);
+        b.header.blob_gas_used = Some(0);
+        b.header.excess_blob_gas = Some(0);
+        b
+    });
+    let v3 = ExecutionPayloadV3::from(block.clone());
+    assert_eq!(v3.clone().try_into_sealed_block(parent_beacon_block_root).unwrap(), block);
+
+    // the parent beacon block root is part of the block hash
+    assert_matches!(
+        v3.try_into_sealed_block(H256::random()),
+        Err(PayloadError::BlockHash { consensus, .. }) if consensus == block.hash()
+    );
+
+    // withdrawals are part of the block hash
+    let mut v2 = ExecutionPayloadV2::from(block.clone());
+    v2.withdrawals.clear();
+    assert_matches!(v2.try_into_sealed_block(), Err(PayloadError::BlockHash { .. }));
+}
+
+#[test]
+fn payload_blob_versioned_hashes() {
+    let mut rng = generators::rng();
+    let versioned_hashes = vec![H256::random(), H256::random()];
+    let parent_beacon_block_root = H256::random();
+
+    let block = random_block(&mut rng, 100, Some(H256::random()), Some(1), Some(0));
+    let block = transform_sealed_block(block, |b| {
+        let mut b = with_withdrawals(b);
+        b.body.push(blob_transaction(versioned_hashes[..1].to_vec()));
+        b.body.push(blob_transaction(versioned_hashes[1..].to_vec()));
+        b.header.parent_beacon_block_root = Some(parent_beacon_block_root);
+        b.header.blob_gas_used = Some(0);
+        b.header.excess_blob_gas = Some(0);
+        b
+    });
+    let payload = ExecutionPayload::from(block.clone());
+
+    let fields = CancunPayloadFields {
+        parent_beacon_block_root,
+        versioned_hashes: versioned_hashes.clone(),
+    };
+    assert_eq!(
+        payload.clone().try_into_sealed_block_with_cancun_fields(Some(&fields)).unwrap(),
+        block
+    );
+
+    // wrong order
+    let reversed = CancunPayloadFields {
+        parent_beacon_block_root,
+        versioned_hashes: versioned_hashes.iter().rev().copied().collect(),
+    };
+    assert_matches!(
+        payload.clone().try_into_sealed_block_with_cancun_fields(Some(&reversed)),
+        Err(PayloadError::InvalidVersionedHashes)
+    );
+
+    // missing hash
+    let missing = CancunPayloadFields {
+        parent_beacon_block_root,
+        versioned_hashes: versioned_hashes[..1].to_vec(),
+    };
+    assert_matches!(
+        payload.try_into_sealed_block_with_cancun_fields(Some(&missing)),
+        Err(PayloadError::InvalidVersionedHashes)
+    );
+
+    assert_matches!(validate_blob_versioned_hashes(&block, Some(&versioned_hashes)), Ok(()));
+    assert_matches!(
+        validate_blob_versioned_hashes(&block, None),
+        Err(PayloadError::InvalidVersionedHashes)
+    );
+    assert_matches!(
+        validate_blob_versioned_hashes(&block, Some(&[])),
+        Err(PayloadError::InvalidVersionedHashes)
+    );
+}
@@ -34,6 +34,37 @@ pub struct EIP1186AccountProofResponse {
     pub storage_proof: Vec<StorageProof>,
 }
 
+/// Response for `eth_getAccount`
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountResponse {
+    /// The balance of the account.
+    pub balance: U256,
+    /// The nonce of the account.
+    pub nonce: U64,
+    /// The hash of the account's code, the hash of the empty string for accounts without code.
+    pub code_hash: H256,
+    /// The root of the account's storage trie.
+    pub storage_root: H256,
+}
+
+/// An account returned by `reth_getAccounts`
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountSummary {
+    /// The address of the account.
+    pub address: Address,
+    /// The balance of the account.
+    pub balance: U256,
+    /// The nonce of the account.
+    pub nonce: U64,
+    /// The hash of the account's code, the hash of the empty string for accounts without code.
+    pub code_hash: H256,
+    /// The code of the account, if requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+}
+
 /// Extended account information (used by `parity_allAccountInfo`).
 #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ExtAccountInfo {
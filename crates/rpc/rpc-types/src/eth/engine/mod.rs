@@ -5,6 +5,8 @@
 mod cancun;
 mod forkchoice;
 mod payload;
+#[cfg(feature = "ssz")]
+mod ssz;
 mod transition;
 
 pub use self::{cancun::*, forkchoice::*, payload::*, transition::*};
@@ -8,6 +8,8 @@ use reth_primitives::{
 use reth_rlp::Decodable;
 use serde::{ser::SerializeMap, Deserialize, Serialize, Serializer};
 
+use super::CancunPayloadFields;
+
 /// The execution payload body response that allows for `null` values.
 pub type ExecutionPayloadBodiesV1 = Vec<Option<ExecutionPayloadBodyV1>>;
 
@@ -169,6 +171,15 @@ impl From<SealedBlock> for ExecutionPayloadV1 {
     }
 }
 
+impl ExecutionPayloadV1 {
+    /// Tries to convert the payload into a [SealedBlock] and verifies that the hash of the block
+    /// matches the `block_hash` of the payload.
+    pub fn try_into_sealed_block(self) -> Result<SealedBlock, PayloadError> {
+        let block_hash = self.block_hash;
+        seal_payload_block(Block::try_from(self)?, block_hash)
+    }
+}
+
 /// Try to construct a block from given payload. Perform addition validation of `extra_data` and
 /// `base_fee_per_gas` fields.
 ///
@@ -249,6 +260,13 @@ impl ExecutionPayloadV2 {
     pub fn timestamp(&self) -> u64 {
         self.payload_inner.timestamp.as_u64()
     }
+
+    /// Tries to convert the payload into a [SealedBlock] with withdrawals and verifies that the
+    /// hash of the block matches the `block_hash` of the payload.
+    pub fn try_into_sealed_block(self) -> Result<SealedBlock, PayloadError> {
+        let block_hash = self.payload_inner.block_hash;
+        seal_payload_block(Block::try_from(self)?, block_hash)
+    }
 }
 
 impl From<SealedBlock> for ExecutionPayloadV2 {
@@ -328,6 +346,21 @@ impl ExecutionPayloadV3 {
     pub fn timestamp(&self) -> u64 {
         self.payload_inner.payload_inner.timestamp.as_u64()
     }
+
+    /// Tries to convert the payload into a [SealedBlock] with the given parent beacon block root
+    /// and verifies that the hash of the block matches the `block_hash` of the payload.
+    ///
+    /// This does not validate the blob versioned hashes of the block, see
+    /// [ExecutionPayload::try_into_sealed_block_with_cancun_fields].
+    pub fn try_into_sealed_block(
+        self,
+        parent_beacon_block_root: H256,
+    ) -> Result<SealedBlock, PayloadError> {
+        let block_hash = self.payload_inner.payload_inner.block_hash;
+        let mut block = Block::try_from(self)?;
+        block.header.parent_beacon_block_root = Some(parent_beacon_block_root);
+        seal_payload_block(block, block_hash)
+    }
 }
 
 impl From<SealedBlock> for ExecutionPayloadV3 {
@@ -490,14 +523,66 @@ impl ExecutionPayload {
 
         base_payload.header.parent_beacon_block_root = parent_beacon_block_root;
 
-        let payload = base_payload.seal_slow();
+        seal_payload_block(base_payload, block_hash)
+    }
 
-        if block_hash != payload.hash() {
-            return Err(PayloadError::BlockHash { execution: payload.hash(), consensus: block_hash })
-        }
+    /// Tries to create a new block from the given payload and the [CancunPayloadFields] of the
+    /// `engine_newPayload` request.
+    ///
+    /// In addition to [ExecutionPayload::try_into_sealed_block], this validates that the blob
+    /// versioned hashes of the block's transactions match the expected versioned hashes, see
+    /// [validate_blob_versioned_hashes].
+    pub fn try_into_sealed_block_with_cancun_fields(
+        self,
+        cancun_fields: Option<&CancunPayloadFields>,
+    ) -> Result<SealedBlock, PayloadError> {
+        let block = self
+            .try_into_sealed_block(cancun_fields.map(|fields| fields.parent_beacon_block_root))?;
+        validate_blob_versioned_hashes(
+            &block,
+            cancun_fields.map(|fields| fields.versioned_hashes.as_slice()),
+        )?;
+        Ok(block)
+    }
+}
 
-        Ok(payload)
+/// Seals the block that was constructed from a payload and verifies that its hash matches the
+/// `block_hash` of the payload.
+fn seal_payload_block(block: Block, block_hash: H256) -> Result<SealedBlock, PayloadError> {
+    let block = block.seal_slow();
+    if block_hash != block.hash() {
+        return Err(PayloadError::BlockHash { execution: block.hash(), consensus: block_hash })
     }
+    Ok(block)
+}
+
+/// Validates that the blob versioned hashes of the block's transactions match the expected
+/// versioned hashes of the `engine_newPayloadV3` request.
+///
+/// The actual versioned hashes are the concatenated `blob_versioned_hashes` of all blob
+/// transactions, in the order of inclusion. If no expected versioned hashes are provided, the
+/// block must not contain any blob versioned hashes.
+///
+/// See <https://github.com/ethereum/execution-apis/blob/fe8e13c288c592ec154ce25c534e26cb7ce0530d/src/engine/cancun.md#specification>
+pub fn validate_blob_versioned_hashes(
+    block: &SealedBlock,
+    expected: Option<&[H256]>,
+) -> Result<(), PayloadError> {
+    let mut actual = block
+        .body
+        .iter()
+        .filter_map(|tx| tx.as_eip4844())
+        .flat_map(|tx| tx.blob_versioned_hashes.iter());
+
+    let matches = match expected {
+        Some(expected) => actual.by_ref().eq(expected.iter()),
+        None => actual.next().is_none(),
+    };
+
+    if !matches {
+        return Err(PayloadError::InvalidVersionedHashes)
+    }
+    Ok(())
 }
 
 impl From<ExecutionPayloadV1> for ExecutionPayload {
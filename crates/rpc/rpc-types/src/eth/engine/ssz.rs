@@ -0,0 +1,235 @@
+//! SSZ encoding of the execution payloads, see the `ExecutionPayload` containers of the
+//! [consensus specs](https://github.com/ethereum/consensus-specs/blob/dev/specs/deneb/beacon-chain.md#executionpayload).
+
+use super::{ExecutionPayloadV1, ExecutionPayloadV2, ExecutionPayloadV3};
+use reth_primitives::{
+    ssz::{Decode, DecodeError, Encode, SszDecoder, SszDecoderBuilder, SszEncoder, SszFixed},
+    Address, Bloom, Bytes, Withdrawal, H256, U256, U64,
+};
+
+/// Length of the fixed part of [ExecutionPayloadV1], the `extra_data` and `transactions` fields
+/// are variable size and encoded as 4 byte offsets.
+const EXECUTION_PAYLOAD_V1_FIXED_LEN: usize =
+    32 + 20 + 32 + 32 + 256 + 32 + 4 * 8 + 4 + 32 + 32 + 4;
+
+/// [ExecutionPayloadV2] adds the offset of the `withdrawals`.
+const EXECUTION_PAYLOAD_V2_FIXED_LEN: usize = EXECUTION_PAYLOAD_V1_FIXED_LEN + 4;
+
+/// [ExecutionPayloadV3] adds `blob_gas_used` and `excess_blob_gas`.
+const EXECUTION_PAYLOAD_V3_FIXED_LEN: usize = EXECUTION_PAYLOAD_V2_FIXED_LEN + 2 * 8;
+
+impl ExecutionPayloadV1 {
+    fn ssz_append_fields(&self, encoder: &mut SszEncoder<'_>) {
+        self.parent_hash.ssz_append_field(encoder);
+        self.fee_recipient.ssz_append_field(encoder);
+        self.state_root.ssz_append_field(encoder);
+        self.receipts_root.ssz_append_field(encoder);
+        encoder.append(&self.logs_bloom);
+        self.prev_randao.ssz_append_field(encoder);
+        self.block_number.ssz_append_field(encoder);
+        self.gas_limit.ssz_append_field(encoder);
+        self.gas_used.ssz_append_field(encoder);
+        self.timestamp.ssz_append_field(encoder);
+        encoder.append(&self.extra_data);
+        self.base_fee_per_gas.ssz_append_field(encoder);
+        self.block_hash.ssz_append_field(encoder);
+        encoder.append(&self.transactions);
+    }
+
+    fn ssz_variable_len(&self) -> usize {
+        self.extra_data.ssz_bytes_len() + self.transactions.ssz_bytes_len()
+    }
+
+    fn ssz_register_fields(builder: &mut SszDecoderBuilder<'_>) -> Result<(), DecodeError> {
+        H256::ssz_register_field(builder)?;
+        Address::ssz_register_field(builder)?;
+        H256::ssz_register_field(builder)?;
+        H256::ssz_register_field(builder)?;
+        builder.register_type::<Bloom>()?;
+        H256::ssz_register_field(builder)?;
+        U64::ssz_register_field(builder)?;
+        U64::ssz_register_field(builder)?;
+        U64::ssz_register_field(builder)?;
+        U64::ssz_register_field(builder)?;
+        builder.register_type::<Bytes>()?;
+        U256::ssz_register_field(builder)?;
+        H256::ssz_register_field(builder)?;
+        builder.register_type::<Vec<Bytes>>()
+    }
+
+    fn ssz_decode_fields(decoder: &mut SszDecoder<'_>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            parent_hash: H256::ssz_decode_field(decoder)?,
+            fee_recipient: Address::ssz_decode_field(decoder)?,
+            state_root: H256::ssz_decode_field(decoder)?,
+            receipts_root: H256::ssz_decode_field(decoder)?,
+            logs_bloom: decoder.decode_next()?,
+            prev_randao: H256::ssz_decode_field(decoder)?,
+            block_number: U64::ssz_decode_field(decoder)?,
+            gas_limit: U64::ssz_decode_field(decoder)?,
+            gas_used: U64::ssz_decode_field(decoder)?,
+            timestamp: U64::ssz_decode_field(decoder)?,
+            extra_data: decoder.decode_next()?,
+            base_fee_per_gas: U256::ssz_decode_field(decoder)?,
+            block_hash: H256::ssz_decode_field(decoder)?,
+            transactions: decoder.decode_next()?,
+        })
+    }
+}
+
+impl ExecutionPayloadV2 {
+    fn ssz_append_fields(&self, encoder: &mut SszEncoder<'_>) {
+        self.payload_inner.ssz_append_fields(encoder);
+        encoder.append(&self.withdrawals);
+    }
+
+    fn ssz_variable_len(&self) -> usize {
+        self.payload_inner.ssz_variable_len() + self.withdrawals.ssz_bytes_len()
+    }
+
+    fn ssz_register_fields(builder: &mut SszDecoderBuilder<'_>) -> Result<(), DecodeError> {
+        ExecutionPayloadV1::ssz_register_fields(builder)?;
+        builder.register_type::<Vec<Withdrawal>>()
+    }
+
+    fn ssz_decode_fields(decoder: &mut SszDecoder<'_>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            payload_inner: ExecutionPayloadV1::ssz_decode_fields(decoder)?,
+            withdrawals: decoder.decode_next()?,
+        })
+    }
+}
+
+impl ExecutionPayloadV3 {
+    fn ssz_append_fields(&self, encoder: &mut SszEncoder<'_>) {
+        self.payload_inner.ssz_append_fields(encoder);
+        self.blob_gas_used.ssz_append_field(encoder);
+        self.excess_blob_gas.ssz_append_field(encoder);
+    }
+
+    fn ssz_variable_len(&self) -> usize {
+        self.payload_inner.ssz_variable_len()
+    }
+
+    fn ssz_register_fields(builder: &mut SszDecoderBuilder<'_>) -> Result<(), DecodeError> {
+        ExecutionPayloadV2::ssz_register_fields(builder)?;
+        U64::ssz_register_field(builder)?;
+        U64::ssz_register_field(builder)
+    }
+
+    fn ssz_decode_fields(decoder: &mut SszDecoder<'_>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            payload_inner: ExecutionPayloadV2::ssz_decode_fields(decoder)?,
+            blob_gas_used: U64::ssz_decode_field(decoder)?,
+            excess_blob_gas: U64::ssz_decode_field(decoder)?,
+        })
+    }
+}
+
+macro_rules! impl_ssz_payload {
+    ($(($name:ty, $fixed_len:ident)),+) => {
+        $(
+            impl Encode for $name {
+                fn is_ssz_fixed_len() -> bool {
+                    false
+                }
+
+                fn ssz_append(&self, buf: &mut Vec<u8>) {
+                    let mut encoder = SszEncoder::container(buf, $fixed_len);
+                    self.ssz_append_fields(&mut encoder);
+                    encoder.finalize();
+                }
+
+                fn ssz_bytes_len(&self) -> usize {
+                    $fixed_len + self.ssz_variable_len()
+                }
+            }
+
+            impl Decode for $name {
+                fn is_ssz_fixed_len() -> bool {
+                    false
+                }
+
+                fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+                    let mut builder = SszDecoderBuilder::new(bytes);
+                    Self::ssz_register_fields(&mut builder)?;
+                    let mut decoder = builder.build()?;
+                    Self::ssz_decode_fields(&mut decoder)
+                }
+            }
+        )+
+    };
+}
+
+impl_ssz_payload!(
+    (ExecutionPayloadV1, EXECUTION_PAYLOAD_V1_FIXED_LEN),
+    (ExecutionPayloadV2, EXECUTION_PAYLOAD_V2_FIXED_LEN),
+    (ExecutionPayloadV3, EXECUTION_PAYLOAD_V3_FIXED_LEN)
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload_v3() -> ExecutionPayloadV3 {
+        ExecutionPayloadV3 {
+            payload_inner: ExecutionPayloadV2 {
+                payload_inner: ExecutionPayloadV1 {
+                    parent_hash: H256::repeat_byte(1),
+                    fee_recipient: Address::repeat_byte(2),
+                    state_root: H256::repeat_byte(3),
+                    receipts_root: H256::repeat_byte(4),
+                    logs_bloom: Bloom::repeat_byte(5),
+                    prev_randao: H256::repeat_byte(6),
+                    block_number: U64::from(7),
+                    gas_limit: U64::from(30_000_000),
+                    gas_used: U64::from(21_000),
+                    timestamp: U64::from(1_700_000_000),
+                    extra_data: Bytes::from(b"reth".to_vec()),
+                    base_fee_per_gas: U256::from(1_000_000_007),
+                    block_hash: H256::repeat_byte(8),
+                    transactions: vec![Bytes::from(vec![0x02, 0xc0]), Bytes::from(vec![0x01])],
+                },
+                withdrawals: vec![Withdrawal {
+                    index: 1,
+                    validator_index: 2,
+                    address: Address::repeat_byte(9),
+                    amount: 3,
+                }],
+            },
+            blob_gas_used: U64::from(131_072),
+            excess_blob_gas: U64::from(0),
+        }
+    }
+
+    #[test]
+    fn execution_payload_ssz_roundtrip() {
+        let v3 = payload_v3();
+        let encoded = v3.as_ssz_bytes();
+        assert_eq!(encoded.len(), v3.ssz_bytes_len());
+        assert_eq!(ExecutionPayloadV3::from_ssz_bytes(&encoded).unwrap(), v3);
+
+        let v2 = v3.payload_inner;
+        let encoded = v2.as_ssz_bytes();
+        assert_eq!(encoded.len(), v2.ssz_bytes_len());
+        assert_eq!(ExecutionPayloadV2::from_ssz_bytes(&encoded).unwrap(), v2);
+
+        let v1 = v2.payload_inner;
+        let encoded = v1.as_ssz_bytes();
+        assert_eq!(encoded.len(), v1.ssz_bytes_len());
+        assert_eq!(ExecutionPayloadV1::from_ssz_bytes(&encoded).unwrap(), v1);
+
+        // the offset of `extra_data` points right after the fixed part
+        let offset = u32::from_le_bytes(encoded[436..440].try_into().unwrap());
+        assert_eq!(offset as usize, EXECUTION_PAYLOAD_V1_FIXED_LEN);
+        assert_eq!(&encoded[EXECUTION_PAYLOAD_V1_FIXED_LEN..][..4], b"reth");
+    }
+
+    #[test]
+    fn execution_payload_ssz_invalid() {
+        let encoded = payload_v3().as_ssz_bytes();
+        assert!(ExecutionPayloadV3::from_ssz_bytes(&encoded[..EXECUTION_PAYLOAD_V3_FIXED_LEN - 1])
+            .is_err());
+        assert!(ExecutionPayloadV1::from_ssz_bytes(&[]).is_err());
+    }
+}
@@ -13,6 +13,9 @@ pub enum EthRpcErrorCode {
     /// > If the block is not found, the callee SHOULD raise a JSON-RPC error (the recommended
     /// > error code is -32001: Resource not found).
     ResourceNotFound,
+    /// Thrown when the requested resource exists but is currently not available, for example
+    /// because it was pruned, see <https://eips.ethereum.org/EIPS/eip-1474>
+    ResourceUnavailable,
     /// Thrown when querying for `finalized` or `safe` block before the merge transition is
     /// finalized, <https://github.com/ethereum/execution-apis/blob/6d17705a875e52c26826124c2a8a15ed542aeca2/src/schemas/block.yaml#L109>
     UnknownBlock,
@@ -26,6 +29,7 @@ impl EthRpcErrorCode {
             EthRpcErrorCode::ExecutionError => 3,
             EthRpcErrorCode::InvalidInput => -32000,
             EthRpcErrorCode::ResourceNotFound => -32001,
+            EthRpcErrorCode::ResourceUnavailable => -32002,
             EthRpcErrorCode::UnknownBlock => -39001,
         }
     }
@@ -873,6 +873,28 @@ impl<'de> Deserialize<'de> for FilterChanges {
     }
 }
 
+/// The position of the first log that was not returned by a paginated `eth_getLogsPage` query.
+///
+/// Passing the cursor to the next query resumes the query at that log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogsPageCursor {
+    /// The number of the block the next log is in.
+    pub block_number: U64,
+    /// The index of the next log in its block.
+    pub log_index: U64,
+}
+
+/// Response of the `eth_getLogsPage` RPC.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogsPage {
+    /// The matching logs of this page.
+    pub logs: Vec<RpcLog>,
+    /// The cursor to query the next page with, `None` if all logs were returned.
+    pub next: Option<LogsPageCursor>,
+}
+
 /// Owned equivalent of a `SubscriptionId`
 #[derive(Debug, PartialEq, Clone, Hash, Eq, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
@@ -914,6 +936,20 @@ mod tests {
         serde_json::to_value(t).expect("Failed to serialize value")
     }
 
+    #[test]
+    fn serde_logs_page() {
+        let page = LogsPage {
+            logs: Vec::new(),
+            next: Some(LogsPageCursor { block_number: U64::from(16), log_index: U64::from(2) }),
+        };
+        let json = serialize(&page);
+        assert_eq!(json, json!({"logs": [], "next": {"blockNumber": "0x10", "logIndex": "0x2"}}));
+        assert_eq!(serde_json::from_value::<LogsPage>(json).unwrap(), page);
+
+        let last = serialize(&LogsPage::default());
+        assert_eq!(last, json!({"logs": [], "next": null}));
+    }
+
     #[test]
     fn test_empty_filter_topics_list() {
         let s = r#"{"fromBlock": "0xfc359e", "toBlock": "0xfc359e", "topics": [["0x8c5be1e5ebec7d5bd14f71427d1e84f3dd0314c0f7b2291e5b200ac8c7c3b925"], [], ["0x0000000000000000000000000c17e776cd218252adfca8d4e761d3fe757e9778"]]}"#;
@@ -1,12 +1,13 @@
 //! Ethereum types for pub-sub
 
 use crate::{
-    eth::{Filter, Transaction},
-    Log, RichHeader,
+    eth::{trace::parity::LocalizedTransactionTrace, Filter, Transaction},
+    Block, Log, RichHeader, TransactionReceipt,
 };
 
-use reth_primitives::H256;
+use reth_primitives::{Address, BlockNumberOrTag, H256, U256, U64};
 use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
 
 /// Subscription result.
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
@@ -156,6 +157,119 @@ impl<'a> Deserialize<'a> for Params {
     }
 }
 
+/// Parameters of the `reth_subscribeBlocks` subscription.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct BlocksSubscriptionParams {
+    /// The first block to stream.
+    ///
+    /// The canonical blocks from this block up to the current tip are streamed before the new
+    /// blocks. If not set, only new blocks are streamed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_block: Option<BlockNumberOrTag>,
+    /// Whether to include the parity traces of the transactions.
+    #[serde(default)]
+    pub include_traces: bool,
+}
+
+/// A canonical block with the receipts of its transactions, as streamed by the
+/// `reth_subscribeBlocks` subscription.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockWithReceipts {
+    /// The block with full transactions.
+    pub block: Block,
+    /// The receipts of the transactions, in transaction order.
+    pub receipts: Vec<TransactionReceipt>,
+    /// The parity traces of the transactions, if requested.
+    ///
+    /// Traces are not included for removed blocks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub traces: Option<Vec<LocalizedTransactionTrace>>,
+    /// Whether the block was removed from the canonical chain by a reorg.
+    ///
+    /// Removed blocks are streamed from the highest block down, before the blocks of the new
+    /// chain.
+    pub removed: bool,
+}
+
+/// Parameters of the `reth_subscribeStateChanges` subscription.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct StateChangesParams {
+    /// The accounts whose balance, nonce or code changes are notified.
+    #[serde(default)]
+    pub accounts: Vec<Address>,
+    /// The storage slots whose changes are notified, by account.
+    ///
+    /// An empty list of slots watches all slots of the account.
+    #[serde(default)]
+    pub storage: BTreeMap<Address, Vec<H256>>,
+}
+
+impl StateChangesParams {
+    /// Returns `true` if no account or storage slot is watched.
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty() && self.storage.is_empty()
+    }
+}
+
+/// The changes of the watched accounts and storage slots in a canonical block, as streamed by the
+/// `reth_subscribeStateChanges` subscription.
+///
+/// Only blocks that change at least one watched account or slot are streamed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateChanges {
+    /// The number of the block.
+    pub block_number: U64,
+    /// The hash of the block.
+    pub block_hash: H256,
+    /// The changed accounts with their values after the block.
+    pub accounts: Vec<AccountChange>,
+    /// The changed storage slots with their values after the block.
+    pub storage: Vec<StorageChange>,
+    /// The watched accounts whose storage was wiped by the block, for example by a selfdestruct.
+    ///
+    /// All slots of these accounts that are not included in `storage` are zero after the block.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub storage_wiped: Vec<Address>,
+    /// Whether the block was removed from the canonical chain by a reorg.
+    ///
+    /// The changes of removed blocks contain the values before the block, and are streamed from
+    /// the highest block down, before the changes of the new chain. The storage of accounts in
+    /// `storageWiped` is restored, but only the slots in `storage` are included.
+    pub removed: bool,
+}
+
+/// The state of a changed account.
+///
+/// Accounts that do not exist are reported with zero balance and nonce, and the empty code hash.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountChange {
+    /// The address of the account.
+    pub address: Address,
+    /// The balance of the account.
+    pub balance: U256,
+    /// The nonce of the account.
+    pub nonce: U64,
+    /// The hash of the code of the account.
+    pub code_hash: H256,
+}
+
+/// The value of a changed storage slot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageChange {
+    /// The address of the account.
+    pub address: Address,
+    /// The storage slot.
+    pub slot: H256,
+    /// The value of the slot.
+    pub value: U256,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -167,4 +281,32 @@ mod tests {
         let s: Params = serde_json::from_str("null").unwrap();
         assert_eq!(s, Params::None);
     }
+
+    #[test]
+    fn blocks_subscription_params_serde() {
+        let s: BlocksSubscriptionParams =
+            serde_json::from_str(r#"{"fromBlock":"0x64","includeTraces":true}"#).unwrap();
+        assert_eq!(
+            s,
+            BlocksSubscriptionParams {
+                from_block: Some(BlockNumberOrTag::Number(100)),
+                include_traces: true
+            }
+        );
+        let s: BlocksSubscriptionParams = serde_json::from_str("{}").unwrap();
+        assert_eq!(s, BlocksSubscriptionParams::default());
+        assert!(serde_json::from_str::<BlocksSubscriptionParams>(r#"{"address":"0x"}"#).is_err());
+    }
+
+    #[test]
+    fn state_changes_params_serde() {
+        let s: StateChangesParams = serde_json::from_str(
+            r#"{"accounts":["0x0000000000000000000000000000000000000001"],"storage":{"0x0000000000000000000000000000000000000002":[]}}"#,
+        )
+        .unwrap();
+        assert_eq!(s.accounts, vec![Address::from_low_u64_be(1)]);
+        assert_eq!(s.storage, BTreeMap::from([(Address::from_low_u64_be(2), vec![])]));
+        assert!(!s.is_empty());
+        assert!(serde_json::from_str::<StateChangesParams>("{}").unwrap().is_empty());
+    }
 }
@@ -18,9 +18,13 @@
 mod admin;
 mod eth;
 mod otterscan;
+mod provider;
+mod reth;
 mod rpc;
 
 pub use admin::*;
 pub use eth::*;
 pub use otterscan::*;
+pub use provider::*;
+pub use reth::*;
 pub use rpc::*;
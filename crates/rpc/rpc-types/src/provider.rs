@@ -0,0 +1,84 @@
+use reth_primitives::Bytes;
+use serde::{Deserialize, Serialize};
+
+/// A raw entry of a database table, as it is stored in the database.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableEntry {
+    /// The encoded key.
+    pub key: Bytes,
+    /// The compressed value.
+    pub value: Bytes,
+}
+
+/// The operation of a `provider_cursor` request, which positions a cursor on a table.
+///
+/// Cursors are stateless: operations that move relative to the current position of a cursor
+/// include the entry the cursor is positioned at.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "camelCase")]
+pub enum CursorOp {
+    /// Positions at the first entry of the table.
+    First,
+    /// Positions at the last entry of the table.
+    Last,
+    /// Positions at the first entry with the given key.
+    SeekExact {
+        /// The encoded key.
+        key: Bytes,
+    },
+    /// Positions at the first entry with a key greater than or equal to the given key.
+    Seek {
+        /// The encoded key.
+        key: Bytes,
+    },
+    /// Positions at the entry after the given entry.
+    Next {
+        /// The encoded key of the current entry.
+        key: Bytes,
+        /// The compressed value of the current entry.
+        value: Bytes,
+    },
+    /// Positions at the entry before the given entry.
+    Prev {
+        /// The encoded key of the current entry.
+        key: Bytes,
+        /// The compressed value of the current entry.
+        value: Bytes,
+    },
+    /// Positions at the first entry with a key greater than the given key.
+    NextNoDup {
+        /// The encoded key of the current entry.
+        key: Bytes,
+    },
+    /// Positions at the first duplicate of the given key whose value is greater than or equal to
+    /// the subkey. Only supported by tables with duplicate keys.
+    SeekBySubkey {
+        /// The encoded key.
+        key: Bytes,
+        /// The encoded subkey.
+        subkey: Bytes,
+    },
+}
+
+impl CursorOp {
+    /// Returns `true` if the entries are read towards the start of the table.
+    pub fn is_backward(&self) -> bool {
+        matches!(self, CursorOp::Last | CursorOp::Prev { .. })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_cursor_op() {
+        let op = CursorOp::Next { key: Bytes::from(vec![1]), value: Bytes::from(vec![2, 3]) };
+        let s = serde_json::to_string(&op).unwrap();
+        assert_eq!(s, r#"{"op":"next","key":"0x01","value":"0x0203"}"#);
+        assert_eq!(serde_json::from_str::<CursorOp>(&s).unwrap(), op);
+
+        let s = serde_json::to_string(&CursorOp::First).unwrap();
+        assert_eq!(s, r#"{"op":"first"}"#);
+    }
+}
@@ -0,0 +1,150 @@
+use reth_primitives::{
+    Address, Bytes, ChainSpec, ForkCondition, ForkId, Hardfork, H256, U256, U64,
+};
+use serde::{Deserialize, Serialize};
+
+/// The chain spec of the node, returned by `reth_chainSpec`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainSpecInfo {
+    /// The chain id.
+    pub chain_id: U64,
+    /// The hash of the genesis block.
+    pub genesis_hash: H256,
+    /// The hardforks of the chain and their activation conditions, in the order of activation.
+    pub hardforks: Vec<HardforkInfo>,
+    /// The deposit contract deployed for PoS, if any.
+    pub deposit_contract: Option<DepositContractInfo>,
+    /// The fork ids of the chain in the order of activation, starting with the fork id of the
+    /// genesis block, see [EIP-2124](https://eips.ethereum.org/EIPS/eip-2124).
+    pub fork_ids: Vec<ForkIdInfo>,
+    /// The parameters that configure how the base fee of a block is computed.
+    pub base_fee_params: BaseFeeParamsInfo,
+}
+
+impl From<&ChainSpec> for ChainSpecInfo {
+    fn from(spec: &ChainSpec) -> Self {
+        Self {
+            chain_id: U64::from(spec.chain.id()),
+            genesis_hash: spec.genesis_hash(),
+            hardforks: spec
+                .forks_iter()
+                .filter(|(_, condition)| *condition != ForkCondition::Never)
+                .map(|(fork, condition)| HardforkInfo::new(fork, condition))
+                .collect(),
+            deposit_contract: spec.deposit_contract.as_ref().map(|contract| DepositContractInfo {
+                address: contract.address,
+                block: U64::from(contract.block),
+                topic: contract.topic,
+            }),
+            fork_ids: spec.fork_ids().into_iter().map(ForkIdInfo::from).collect(),
+            base_fee_params: BaseFeeParamsInfo {
+                max_change_denominator: U64::from(spec.base_fee_params.max_change_denominator),
+                elasticity_multiplier: U64::from(spec.base_fee_params.elasticity_multiplier),
+            },
+        }
+    }
+}
+
+/// A hardfork and its activation condition.
+///
+/// Exactly one of `block` and `timestamp` is set, unless the fork is activated at a terminal total
+/// difficulty without a known block.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HardforkInfo {
+    /// The name of the hardfork.
+    pub name: String,
+    /// The block at which the hardfork is activated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block: Option<U64>,
+    /// The timestamp at which the hardfork is activated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<U64>,
+    /// The total difficulty after which the hardfork is activated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub terminal_total_difficulty: Option<U256>,
+}
+
+impl HardforkInfo {
+    /// Creates the info of the hardfork with the given activation condition.
+    pub fn new(fork: Hardfork, condition: ForkCondition) -> Self {
+        let (block, timestamp, terminal_total_difficulty) = match condition {
+            ForkCondition::Block(block) => (Some(block), None, None),
+            ForkCondition::Timestamp(timestamp) => (None, Some(timestamp), None),
+            ForkCondition::TTD { fork_block, total_difficulty } => {
+                (fork_block, None, Some(total_difficulty))
+            }
+            ForkCondition::Never => (None, None, None),
+        };
+        Self {
+            name: fork.to_string(),
+            block: block.map(U64::from),
+            timestamp: timestamp.map(U64::from),
+            terminal_total_difficulty,
+        }
+    }
+}
+
+/// The deposit contract deployed for PoS.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositContractInfo {
+    /// The address of the contract.
+    pub address: Address,
+    /// The block the contract was deployed in.
+    pub block: U64,
+    /// The signature of the `DepositEvent` event.
+    pub topic: H256,
+}
+
+/// A fork id, see [EIP-2124](https://eips.ethereum.org/EIPS/eip-2124).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForkIdInfo {
+    /// The CRC32 checksum of the genesis hash and the activations of all past forks.
+    pub hash: Bytes,
+    /// The block number or timestamp of the next fork, zero if no fork is scheduled.
+    pub next: U64,
+}
+
+impl From<ForkId> for ForkIdInfo {
+    fn from(fork_id: ForkId) -> Self {
+        Self { hash: Bytes::from(fork_id.hash.0.to_vec()), next: U64::from(fork_id.next) }
+    }
+}
+
+/// The parameters that configure how the base fee of a block is computed, see
+/// [EIP-1559](https://eips.ethereum.org/EIPS/eip-1559).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaseFeeParamsInfo {
+    /// The maximum change of the base fee from one block to the next is `1/denominator`.
+    pub max_change_denominator: U64,
+    /// The gas target of a block is the gas limit divided by the elasticity multiplier.
+    pub elasticity_multiplier: U64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::MAINNET;
+
+    #[test]
+    fn mainnet_chain_spec_info() {
+        let info = ChainSpecInfo::from(&**MAINNET);
+        assert_eq!(info.chain_id, U64::from(1));
+        assert_eq!(info.fork_ids.len(), MAINNET.fork_ids().len());
+        assert_eq!(info.fork_ids[0].hash, Bytes::from(vec![0xfc, 0x64, 0xec, 0x04]));
+        assert_eq!(info.fork_ids[0].next, U64::from(1150000));
+
+        let shanghai = info.hardforks.iter().find(|fork| fork.name == "Shanghai").unwrap();
+        assert_eq!(shanghai.timestamp, Some(U64::from(1681338455)));
+        assert_eq!(shanghai.block, None);
+
+        let json = serde_json::to_value(&info).unwrap();
+        assert!(json["depositContract"]["address"].is_string());
+        assert_eq!(json["baseFeeParams"]["elasticityMultiplier"], "0x2");
+        assert_eq!(serde_json::from_value::<ChainSpecInfo>(json).unwrap(), info);
+    }
+}
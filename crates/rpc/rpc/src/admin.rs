@@ -46,7 +46,7 @@ where
 
     /// Handler for `admin_removeTrustedPeer`
     fn remove_trusted_peer(&self, record: NodeRecord) -> RpcResult<bool> {
-        self.network.remove_peer(record.id, PeerKind::Trusted);
+        self.network.remove_trusted_peer(record.id);
         Ok(true)
     }
 
@@ -0,0 +1,190 @@
+//! Cache of the traces of recently traced blocks.
+
+use parking_lot::Mutex;
+use reth_primitives::H256;
+use reth_rpc_types::trace::geth::{GethDebugTracingOptions, TraceResult};
+use schnellru::{ByLength, LruMap};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Default number of block traces in the cache: 128 blocks.
+pub const DEFAULT_TRACE_CACHE_MAX_BLOCKS: u32 = 128;
+
+/// Default size of the cached block traces: 512MB.
+pub const DEFAULT_TRACE_CACHE_MAX_SIZE_MB: usize = 512;
+
+/// Settings for the [BlockTraceCache].
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockTraceCacheConfig {
+    /// Max number of block traces in the cache, `0` disables the cache.
+    ///
+    /// Default is 128.
+    pub max_blocks: u32,
+    /// Max size of all cached traces in MB, measured by the size of their JSON encoding.
+    ///
+    /// Default is 512.
+    pub max_size_mb: usize,
+}
+
+impl Default for BlockTraceCacheConfig {
+    fn default() -> Self {
+        Self {
+            max_blocks: DEFAULT_TRACE_CACHE_MAX_BLOCKS,
+            max_size_mb: DEFAULT_TRACE_CACHE_MAX_SIZE_MB,
+        }
+    }
+}
+
+/// An LRU cache of the results of `debug_traceBlock*` calls, keyed by the block hash and the
+/// tracing options.
+///
+/// The trace of a block with a given hash never changes, so entries are only evicted when the
+/// cache is full. Explorers and debugging UIs that fetch the same traces repeatedly are served
+/// without re-executing the block.
+#[derive(Debug, Clone)]
+pub struct BlockTraceCache {
+    inner: Arc<Mutex<BlockTraceCacheInner>>,
+}
+
+#[derive(Debug)]
+struct BlockTraceCacheInner {
+    /// Cached traces with their size.
+    traces: LruMap<BlockTraceKey, (Arc<Vec<TraceResult>>, usize), ByLength>,
+    /// The max number of cached traces.
+    max_blocks: u32,
+    /// The max size of all cached traces in bytes.
+    max_size: usize,
+    /// The size of all cached traces in bytes.
+    size: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct BlockTraceKey {
+    block_hash: H256,
+    /// The JSON encoding of the tracing options.
+    opts: String,
+}
+
+impl BlockTraceCache {
+    /// Creates a new cache with the given config.
+    pub fn new(config: BlockTraceCacheConfig) -> Self {
+        let inner = BlockTraceCacheInner {
+            traces: LruMap::new(ByLength::new(config.max_blocks)),
+            max_blocks: config.max_blocks,
+            max_size: config.max_size_mb * 1024 * 1024,
+            size: 0,
+        };
+        Self { inner: Arc::new(Mutex::new(inner)) }
+    }
+
+    /// Returns the cached traces of the block traced with the given options.
+    pub fn get(
+        &self,
+        block_hash: H256,
+        opts: &GethDebugTracingOptions,
+    ) -> Option<Arc<Vec<TraceResult>>> {
+        let key = BlockTraceKey::new(block_hash, opts)?;
+        self.inner.lock().traces.get(&key).map(|(traces, _)| traces.clone())
+    }
+
+    /// Caches the traces of the block traced with the given options.
+    ///
+    /// Traces that exceed the max size of the cache on their own are not cached.
+    pub fn insert(
+        &self,
+        block_hash: H256,
+        opts: &GethDebugTracingOptions,
+        traces: Arc<Vec<TraceResult>>,
+    ) {
+        let Some(key) = BlockTraceKey::new(block_hash, opts) else { return };
+        let mut inner = self.inner.lock();
+        if inner.max_blocks == 0 {
+            return
+        }
+        let Ok(size) = serde_json::to_vec(traces.as_ref()).map(|encoded| encoded.len()) else {
+            return
+        };
+        if size > inner.max_size {
+            return
+        }
+
+        if let Some((_, replaced)) = inner.traces.remove(&key) {
+            inner.size -= replaced;
+        }
+        // evict the least recently used traces until the new traces fit
+        while inner.size + size > inner.max_size || inner.traces.len() >= inner.max_blocks as usize
+        {
+            match inner.traces.pop_oldest() {
+                Some((_, (_, evicted))) => inner.size -= evicted,
+                None => break,
+            }
+        }
+        inner.size += size;
+        inner.traces.insert(key, (traces, size));
+    }
+
+    /// Returns the number of cached block traces.
+    pub fn len(&self) -> usize {
+        self.inner.lock().traces.len()
+    }
+
+    /// Returns `true` if no traces are cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for BlockTraceCache {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl BlockTraceKey {
+    fn new(block_hash: H256, opts: &GethDebugTracingOptions) -> Option<Self> {
+        Some(Self { block_hash, opts: serde_json::to_string(opts).ok()? })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_rpc_types::trace::geth::{
+        GethDebugBuiltInTracerType, GethDebugTracerType, GethTrace, NoopFrame,
+    };
+
+    fn traces(len: usize) -> Arc<Vec<TraceResult>> {
+        Arc::new(vec![
+            TraceResult::Success { result: GethTrace::NoopTracer(NoopFrame::default()) };
+            len
+        ])
+    }
+
+    #[test]
+    fn cache_block_traces() {
+        let cache = BlockTraceCache::new(BlockTraceCacheConfig { max_blocks: 2, max_size_mb: 1 });
+        let opts = GethDebugTracingOptions::default();
+        let noop = GethDebugTracingOptions {
+            tracer: Some(GethDebugTracerType::BuiltInTracer(
+                GethDebugBuiltInTracerType::NoopTracer,
+            )),
+            ..Default::default()
+        };
+
+        cache.insert(H256::repeat_byte(1), &opts, traces(1));
+        assert_eq!(cache.get(H256::repeat_byte(1), &opts), Some(traces(1)));
+        assert!(cache.get(H256::repeat_byte(1), &noop).is_none());
+
+        // the least recently used traces are evicted
+        cache.insert(H256::repeat_byte(1), &noop, traces(2));
+        cache.insert(H256::repeat_byte(2), &opts, traces(3));
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(H256::repeat_byte(1), &opts).is_none());
+
+        // traces larger than the cache are not cached
+        cache.insert(H256::repeat_byte(3), &opts, traces(200_000));
+        assert!(cache.get(H256::repeat_byte(3), &opts).is_none());
+        assert_eq!(cache.len(), 2);
+    }
+}
@@ -1,9 +1,10 @@
 use crate::{
+    block_trace_cache::BlockTraceCache,
     eth::{
         error::{EthApiError, EthResult},
         revm_utils::{
-            clone_into_empty_db, inspect, inspect_and_return_db, prepare_call_env,
-            replay_transactions_until, result_output, transact, EvmOverrides,
+            apply_pre_block_system_calls, clone_into_empty_db, inspect, inspect_and_return_db,
+            prepare_call_env, replay_transactions_until, result_output, transact, EvmOverrides,
         },
         EthTransactions, TransactionSource,
     },
@@ -13,9 +14,11 @@ use crate::{
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
 use reth_primitives::{
-    Account, Block, BlockId, BlockNumberOrTag, Bytes, TransactionSigned, H160, H256,
+    Account, Block, BlockId, BlockNumberOrTag, Bytes, Header, TransactionSigned, H160, H256,
+};
+use reth_provider::{
+    BlockReaderIdExt, ChainSpecProvider, HeaderProvider, PreimageReader, StateProviderBox,
 };
-use reth_provider::{BlockReaderIdExt, HeaderProvider, StateProviderBox};
 use reth_revm::{
     database::{State, SubState},
     env::tx_env_with_recovered,
@@ -62,9 +65,15 @@ impl<Provider, Eth> DebugApi<Provider, Eth> {
         eth: Eth,
         task_spawner: Box<dyn TaskSpawner>,
         tracing_call_guard: TracingCallGuard,
+        trace_cache: BlockTraceCache,
     ) -> Self {
-        let inner =
-            Arc::new(DebugApiInner { provider, eth_api: eth, task_spawner, tracing_call_guard });
+        let inner = Arc::new(DebugApiInner {
+            provider,
+            eth_api: eth,
+            task_spawner,
+            tracing_call_guard,
+            trace_cache,
+        });
         Self { inner }
     }
 }
@@ -73,7 +82,7 @@ impl<Provider, Eth> DebugApi<Provider, Eth> {
 
 impl<Provider, Eth> DebugApi<Provider, Eth>
 where
-    Provider: BlockReaderIdExt + HeaderProvider + 'static,
+    Provider: BlockReaderIdExt + HeaderProvider + ChainSpecProvider + 'static,
     Eth: EthTransactions + 'static,
 {
     /// Acquires a permit to execute a tracing call.
@@ -85,6 +94,7 @@ where
     async fn trace_block_with(
         &self,
         at: BlockId,
+        header: Header,
         transactions: Vec<TransactionSigned>,
         cfg: CfgEnv,
         block_env: BlockEnv,
@@ -92,11 +102,13 @@ where
     ) -> EthResult<Vec<TraceResult>> {
         // replay all transactions of the block
         let this = self.clone();
+        let chain_spec = self.inner.provider.chain_spec();
         self.inner
             .eth_api
             .spawn_with_state_at_block(at, move |state| {
                 let mut results = Vec::with_capacity(transactions.len());
                 let mut db = SubState::new(State::new(state));
+                apply_pre_block_system_calls(&mut db, &chain_spec, &header)?;
 
                 let mut transactions = transactions.into_iter().peekable();
                 while let Some(tx) = transactions.next() {
@@ -136,10 +148,12 @@ where
 
         // we trace on top the block's parent block
         let parent = block.parent_hash;
-        self.trace_block_with(parent.into(), block.body, cfg, block_env, opts).await
+        self.trace_block_with(parent.into(), block.header, block.body, cfg, block_env, opts).await
     }
 
     /// Replays a block and returns the trace of each transaction.
+    ///
+    /// The traces are cached by block hash and tracing options.
     pub async fn debug_trace_block(
         &self,
         block_id: BlockId,
@@ -151,6 +165,10 @@ where
             .block_hash_for_id(block_id)?
             .ok_or_else(|| EthApiError::UnknownBlockNumber)?;
 
+        if let Some(traces) = self.inner.trace_cache.get(block_hash, &opts) {
+            return Ok(traces.as_ref().clone())
+        }
+
         let ((cfg, block_env, _), block) = futures::try_join!(
             self.inner.eth_api.evm_env_at(block_hash.into()),
             self.inner.eth_api.block_by_id(block_id),
@@ -161,7 +179,18 @@ where
         // its parent block's state
         let state_at = block.parent_hash;
 
-        self.trace_block_with(state_at.into(), block.body, cfg, block_env, opts).await
+        let traces = self
+            .trace_block_with(
+                state_at.into(),
+                block.header.unseal(),
+                block.body,
+                cfg,
+                block_env,
+                opts.clone(),
+            )
+            .await?;
+        self.inner.trace_cache.insert(block_hash, &opts, Arc::new(traces.clone()));
+        Ok(traces)
     }
 
     /// Trace the transaction according to the provided options.
@@ -181,9 +210,11 @@ where
         // we need to get the state of the parent block because we're essentially replaying the
         // block the transaction is included in
         let state_at: BlockId = block.parent_hash.into();
+        let header = block.header.unseal();
         let block_txs = block.body;
 
         let this = self.clone();
+        let chain_spec = self.inner.provider.chain_spec();
         self.inner
             .eth_api
             .spawn_with_state_at_block(state_at, move |state| {
@@ -191,6 +222,7 @@ where
                 let tx = transaction.into_recovered();
 
                 let mut db = SubState::new(State::new(state));
+                apply_pre_block_system_calls(&mut db, &chain_spec, &header)?;
                 // replay all transactions prior to the targeted transaction
                 replay_transactions_until(
                     &mut db,
@@ -625,23 +657,12 @@ where
 #[async_trait]
 impl<Provider, Eth> DebugApiServer for DebugApi<Provider, Eth>
 where
-    Provider: BlockReaderIdExt + HeaderProvider + 'static,
+    Provider: BlockReaderIdExt + HeaderProvider + ChainSpecProvider + PreimageReader + 'static,
     Eth: EthApiSpec + 'static,
 {
     /// Handler for `debug_getRawHeader`
     async fn raw_header(&self, block_id: BlockId) -> RpcResult<Bytes> {
-        let header = match block_id {
-            BlockId::Hash(hash) => self.inner.provider.header(&hash.into()).to_rpc_result()?,
-            BlockId::Number(number_or_tag) => {
-                let number = self
-                    .inner
-                    .provider
-                    .convert_block_number(number_or_tag)
-                    .to_rpc_result()?
-                    .ok_or_else(|| internal_rpc_err("Pending block not supported".to_string()))?;
-                self.inner.provider.header_by_number(number).to_rpc_result()?
-            }
-        };
+        let header = self.inner.provider.header_by_id(block_id).to_rpc_result()?;
 
         let mut res = Vec::new();
         if let Some(header) = header {
@@ -755,8 +776,9 @@ where
         Ok(())
     }
 
-    async fn debug_preimage(&self, _hash: H256) -> RpcResult<()> {
-        Ok(())
+    /// Handler for `debug_preimage`
+    async fn debug_preimage(&self, hash: H256) -> RpcResult<Option<Bytes>> {
+        self.inner.provider.preimage(hash).to_rpc_result()
     }
 
     async fn debug_print_block(&self, _number: u64) -> RpcResult<()> {
@@ -1005,4 +1027,6 @@ struct DebugApiInner<Provider, Eth> {
     tracing_call_guard: TracingCallGuard,
     /// The type that can spawn tasks which would otherwise block.
     task_spawner: Box<dyn TaskSpawner>,
+    /// Cache of the traces of recently traced blocks.
+    trace_cache: BlockTraceCache,
 }
@@ -111,6 +111,14 @@ where
             return Ok(self.provider().pending_block()?.map(|block| block.body.len()))
         }
 
+        // The number of transactions of a canonical block is stored in its body indices, so the
+        // transactions don't need to be loaded
+        if let Some(number) = self.provider().block_number_for_id(block_id)? {
+            if let Some(count) = self.provider().block_transaction_count(number.into())? {
+                return Ok(Some(count as usize))
+            }
+        }
+
         let block_hash = match self.provider().block_hash_for_id(block_id)? {
             Some(block_hash) => block_hash,
             None => return Ok(None),
@@ -1,7 +1,10 @@
 //! Contains RPC handler implementations for fee history.
 
 use crate::{
-    eth::error::{EthApiError, EthResult},
+    eth::{
+        cache::BlockBlobFee,
+        error::{EthApiError, EthResult},
+    },
     EthApi,
 };
 use reth_network_api::NetworkInfo;
@@ -36,6 +39,23 @@ where
         self.gas_oracle().suggest_tip_cap().await
     }
 
+    /// Returns the base fee per blob gas of the next block.
+    ///
+    /// Served from the blob fee cache if it contains the latest block, otherwise the latest header
+    /// is fetched.
+    pub(crate) async fn blob_base_fee(&self) -> EthResult<U256> {
+        let latest = self.provider().best_block_number()?;
+        let fee = match self.blob_fee_cache().latest() {
+            Some((number, fee)) if number == latest => Some(fee),
+            _ => self
+                .provider()
+                .latest_header()?
+                .and_then(|header| BlockBlobFee::from_header(&header)),
+        };
+        let fee = fee.ok_or(EthApiError::ExcessBlobGasNotSet)?;
+        Ok(U256::from(fee.next_block_blob_fee()))
+    }
+
     /// Reports the fee history, for the given amount of blocks, up until the newest block
     /// provided.
     pub(crate) async fn fee_history(
@@ -99,12 +119,22 @@ where
         // Collect base fees, gas usage ratios and (optionally) reward percentile data
         let mut base_fee_per_gas: Vec<U256> = Vec::new();
         let mut gas_used_ratio: Vec<f64> = Vec::new();
+        let mut base_fee_per_blob_gas: Vec<U256> = Vec::new();
+        let mut blob_gas_used_ratio: Vec<f64> = Vec::new();
         let mut rewards: Vec<Vec<U256>> = Vec::new();
+        let mut last_blob_fee = None;
         for header in &headers {
             base_fee_per_gas
                 .push(U256::try_from(header.base_fee_per_gas.unwrap_or_default()).unwrap());
             gas_used_ratio.push(header.gas_used as f64 / header.gas_limit as f64);
 
+            // Pre-Cancun blocks have no blob fee, which is reported as zero
+            last_blob_fee = BlockBlobFee::from_header(header);
+            base_fee_per_blob_gas
+                .push(U256::from(last_blob_fee.map(|fee| fee.blob_fee()).unwrap_or_default()));
+            blob_gas_used_ratio
+                .push(last_blob_fee.map(|fee| fee.blob_gas_used_ratio()).unwrap_or_default());
+
             // Percentiles were specified, so we need to collect reward percentile ino
             if let Some(percentiles) = &reward_percentiles {
                 rewards.push(self.calculate_reward_percentiles(percentiles, header).await?);
@@ -124,9 +154,20 @@ where
             chain_spec.base_fee_params,
         )));
 
+        // The blob fields are only returned if the range includes post-Cancun blocks, in which case
+        // the newest block is a post-Cancun block.
+        if let Some(fee) = last_blob_fee {
+            base_fee_per_blob_gas.push(U256::from(fee.next_block_blob_fee()));
+        } else {
+            base_fee_per_blob_gas.clear();
+            blob_gas_used_ratio.clear();
+        }
+
         Ok(FeeHistory {
             base_fee_per_gas,
             gas_used_ratio,
+            base_fee_per_blob_gas,
+            blob_gas_used_ratio,
             oldest_block: U256::from(start_block),
             reward: reward_percentiles.map(|_| rewards),
         })
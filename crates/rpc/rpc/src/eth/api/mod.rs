@@ -5,12 +5,13 @@
 
 use crate::eth::{
     api::pending_block::{PendingBlock, PendingBlockEnv, PendingBlockEnvOrigin},
-    cache::EthStateCache,
+    cache::{BlobFeeCache, EthStateCache},
     error::{EthApiError, EthResult},
     gas_oracle::GasPriceOracle,
-    signer::EthSigner,
+    signer::{DevSigner, EthSigner},
 };
 use async_trait::async_trait;
+use parking_lot::RwLock;
 use reth_interfaces::Result;
 use reth_network_api::NetworkInfo;
 use reth_primitives::{
@@ -40,6 +41,7 @@ mod state;
 mod transactions;
 
 use crate::TracingCallPool;
+pub(crate) use transactions::build_transaction_receipt_with_block_receipts;
 pub use transactions::{EthTransactions, TransactionSource};
 
 /// `Eth` API trait.
@@ -131,6 +133,7 @@ where
             network,
             signers: Default::default(),
             eth_cache,
+            blob_fee_cache: Default::default(),
             gas_oracle,
             gas_cap,
             starting_block: U256::from(latest_block),
@@ -166,6 +169,14 @@ where
         &self.inner.eth_cache
     }
 
+    /// Returns the cache of the blob gas fields of recent canonical blocks.
+    ///
+    /// The cache is updated by
+    /// [blob_fee_cache_new_blocks_task](crate::eth::cache::blob_fee_cache_new_blocks_task).
+    pub fn blob_fee_cache(&self) -> &BlobFeeCache {
+        &self.inner.blob_fee_cache
+    }
+
     /// Returns the gas oracle frontend
     pub(crate) fn gas_oracle(&self) -> &GasPriceOracle<Provider> {
         &self.inner.gas_oracle
@@ -190,6 +201,15 @@ where
     pub fn pool(&self) -> &Pool {
         &self.inner.pool
     }
+
+    /// Adds the prefunded accounts of the dev chain as signers, so `eth_sign`,
+    /// `eth_signTypedData_v4`, `eth_signTransaction` and `eth_sendTransaction` can be used with
+    /// them.
+    ///
+    /// This must only be used for the dev chain, since the keys of these accounts are public.
+    pub fn with_dev_accounts(&self) {
+        self.inner.signers.write().push(Arc::new(DevSigner::dev_accounts()));
+    }
 }
 
 // === State access helpers ===
@@ -357,7 +377,7 @@ where
     }
 
     fn accounts(&self) -> Vec<Address> {
-        self.inner.signers.iter().flat_map(|s| s.accounts()).collect()
+        self.inner.signers.read().iter().flat_map(|s| s.accounts()).collect()
     }
 
     fn is_syncing(&self) -> bool {
@@ -422,9 +442,11 @@ struct EthApiInner<Provider, Pool, Network> {
     /// An interface to interact with the network
     network: Network,
     /// All configured Signers
-    signers: Vec<Box<dyn EthSigner>>,
+    signers: RwLock<Vec<Arc<dyn EthSigner>>>,
     /// The async cache frontend for eth related data
     eth_cache: EthStateCache,
+    /// The blob gas fields of recent canonical blocks
+    blob_fee_cache: BlobFeeCache,
     /// The async gas oracle frontend for gas price suggestions
     gas_oracle: GasPriceOracle<Provider>,
     /// Maximum gas limit for `eth_call` and call tracing RPC methods.
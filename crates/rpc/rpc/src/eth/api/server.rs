@@ -21,9 +21,9 @@ use reth_provider::{
 };
 use reth_rpc_api::EthApiServer;
 use reth_rpc_types::{
-    state::StateOverride, BlockOverrides, Bundle, CallRequest, EIP1186AccountProofResponse,
-    EthCallResponse, FeeHistory, Index, RichBlock, StateContext, SyncStatus, TransactionReceipt,
-    TransactionRequest, Work,
+    state::StateOverride, AccountResponse, BlockOverrides, Bundle, CallRequest,
+    EIP1186AccountProofResponse, EthCallResponse, FeeHistory, Index, RichBlock, StateContext,
+    SyncStatus, TransactionReceipt, TransactionRequest, Work,
 };
 use reth_transaction_pool::TransactionPool;
 use serde_json::Value;
@@ -292,6 +292,12 @@ where
         return Ok(EthApi::suggested_priority_fee(self).await?)
     }
 
+    /// Handler for: `eth_blobBaseFee`
+    async fn blob_base_fee(&self) -> Result<U256> {
+        trace!(target: "rpc::eth", "Serving eth_blobBaseFee");
+        return Ok(EthApi::blob_base_fee(self).await?)
+    }
+
     // FeeHistory is calculated based on lazy evaluation of fees for historical blocks, and further
     // caching of it in the LRU cache.
     // When new RPC call is executed, the cache gets locked, we check it for the historical fees
@@ -356,8 +362,10 @@ where
     }
 
     /// Handler for: `eth_signTransaction`
-    async fn sign_transaction(&self, _transaction: CallRequest) -> Result<Bytes> {
-        Err(internal_rpc_err("unimplemented"))
+    async fn sign_transaction(&self, request: TransactionRequest) -> Result<Bytes> {
+        trace!(target: "rpc::eth", ?request, "Serving eth_signTransaction");
+        let transaction = EthTransactions::sign_transaction_request(self, request).await?;
+        Ok(transaction.envelope_encoded().into())
     }
 
     /// Handler for: `eth_signTypedData`
@@ -385,6 +393,18 @@ where
         // })?)
         Err(internal_rpc_err("unimplemented"))
     }
+
+    /// Handler for: `eth_getAccount`
+    async fn get_account(
+        &self,
+        address: Address,
+        block_number: Option<BlockId>,
+    ) -> Result<AccountResponse> {
+        trace!(target: "rpc::eth", ?address, ?block_number, "Serving eth_getAccount");
+        Ok(self
+            .on_blocking_task(|this| async move { this.get_account(address, block_number) })
+            .await?)
+    }
 }
 
 #[cfg(test)]
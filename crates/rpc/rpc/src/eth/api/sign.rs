@@ -9,33 +9,29 @@ use crate::{
 use ethers_core::types::transaction::eip712::TypedData;
 use reth_primitives::{Address, Bytes};
 use serde_json::Value;
-use std::ops::Deref;
+use std::sync::Arc;
 
 impl<Provider, Pool, Network> EthApi<Provider, Pool, Network> {
     pub(crate) async fn sign(&self, account: Address, message: Bytes) -> EthResult<Bytes> {
         let signer = self.find_signer(&account)?;
         let signature = signer.sign(account, &message).await?;
-        let bytes = hex::encode(signature.to_bytes()).as_bytes().into();
-        Ok(bytes)
+        Ok(signature.to_bytes().to_vec().into())
     }
 
     pub(crate) async fn sign_typed_data(&self, data: Value, account: Address) -> EthResult<Bytes> {
         let signer = self.find_signer(&account)?;
         let data = serde_json::from_value::<TypedData>(data).map_err(|_| SignError::TypedData)?;
         let signature = signer.sign_typed_data(account, &data)?;
-        let bytes = hex::encode(signature.to_bytes()).as_bytes().into();
-        Ok(bytes)
+        Ok(signature.to_bytes().to_vec().into())
     }
 
-    pub(crate) fn find_signer(
-        &self,
-        account: &Address,
-    ) -> Result<&(dyn EthSigner + 'static), SignError> {
+    pub(crate) fn find_signer(&self, account: &Address) -> Result<Arc<dyn EthSigner>, SignError> {
         self.inner
             .signers
+            .read()
             .iter()
             .find(|signer| signer.is_signer_for(account))
-            .map(|signer| signer.deref())
+            .cloned()
             .ok_or(SignError::NoAccount)
     }
 }
@@ -5,14 +5,14 @@ use crate::{
     EthApi,
 };
 use reth_primitives::{
-    serde_helper::JsonStorageKey, Address, BlockId, BlockNumberOrTag, Bytes, H256, KECCAK_EMPTY,
-    U256,
+    proofs::EMPTY_ROOT, serde_helper::JsonStorageKey, Address, BlockId, BlockNumberOrTag, Bytes,
+    H256, KECCAK_EMPTY, U256,
 };
 use reth_provider::{
-    AccountReader, BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProvider,
-    StateProviderFactory,
+    AccountReader, BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, PostState, StateProvider,
+    StateProviderFactory, StateRootProvider,
 };
-use reth_rpc_types::{EIP1186AccountProofResponse, StorageProof};
+use reth_rpc_types::{AccountResponse, EIP1186AccountProofResponse, StorageProof};
 use reth_transaction_pool::{PoolTransaction, TransactionPool};
 
 impl<Provider, Pool, Network> EthApi<Provider, Pool, Network>
@@ -84,6 +84,30 @@ where
         Ok(H256(value.to_be_bytes()))
     }
 
+    /// Returns the account at the given block identifier, with the storage root read from the
+    /// trie tables.
+    pub(crate) fn get_account(
+        &self,
+        address: Address,
+        block_id: Option<BlockId>,
+    ) -> EthResult<AccountResponse> {
+        let state = self.state_at_block_id_or_latest(block_id)?;
+        let Some(account) = state.basic_account(address)? else {
+            return Ok(AccountResponse {
+                code_hash: KECCAK_EMPTY,
+                storage_root: EMPTY_ROOT,
+                ..Default::default()
+            })
+        };
+
+        Ok(AccountResponse {
+            balance: account.balance,
+            nonce: account.nonce.into(),
+            code_hash: account.get_bytecode_hash(),
+            storage_root: state.storage_root(address, PostState::default())?,
+        })
+    }
+
     #[allow(unused)]
     pub(crate) fn get_proof(
         &self,
@@ -4,8 +4,8 @@ use crate::{
         api::pending_block::PendingBlockEnv,
         error::{EthApiError, EthResult, SignError},
         revm_utils::{
-            inspect, inspect_and_return_db, prepare_call_env, replay_transactions_until, transact,
-            EvmOverrides,
+            apply_pre_block_system_calls, inspect, inspect_and_return_db, prepare_call_env,
+            replay_transactions_until, transact, EvmOverrides,
         },
         utils::recover_raw_transaction,
     },
@@ -42,6 +42,10 @@ use revm::{
 };
 use revm_primitives::{utilities::create_address, Env, ResultAndState, SpecId};
 
+/// The maximum time to wait for connected peers to respond to a transaction lookup.
+#[cfg(feature = "peer-transactions")]
+pub const PEER_TRANSACTION_LOOKUP_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
 /// Helper alias type for the state's [CacheDB]
 pub(crate) type StateCacheDB<'r> = CacheDB<State<StateProviderBox<'r>>>;
 
@@ -134,6 +138,13 @@ pub trait EthTransactions: Send + Sync {
     /// Returns the hash of the signed transaction.
     async fn send_transaction(&self, request: TransactionRequest) -> EthResult<H256>;
 
+    /// Fills the missing nonce and gas limit of the request and signs it with a matching signer.
+    /// Returns the signed transaction without submitting it to the pool.
+    async fn sign_transaction_request(
+        &self,
+        request: TransactionRequest,
+    ) -> EthResult<TransactionSigned>;
+
     /// Prepares the state and env for the given [CallRequest] at the given [BlockId] and executes
     /// the closure on a new task returning the result of the closure.
     async fn spawn_with_call_at<F, R>(
@@ -353,6 +364,12 @@ where
             }
         }
 
+        #[cfg(feature = "peer-transactions")]
+        if resp.is_none() {
+            // tx not known locally, it may have just been broadcast to other peers
+            resp = self.transaction_by_hash_from_peers(hash).await;
+        }
+
         Ok(resp)
     }
 
@@ -413,6 +430,12 @@ where
                     None => return Ok(None),
                 };
 
+                // the lookups above are not atomic, so make sure the block wasn't unwound in
+                // between and the receipt still belongs to the transaction's block
+                if !this.provider().block_contains_transaction(meta.block_number, hash)? {
+                    return Ok(None)
+                }
+
                 Ok(Some((tx, meta, receipt)))
             })
             .await?;
@@ -436,7 +459,24 @@ where
         Ok(hash)
     }
 
-    async fn send_transaction(&self, mut request: TransactionRequest) -> EthResult<H256> {
+    async fn send_transaction(&self, request: TransactionRequest) -> EthResult<H256> {
+        let signed_tx = self.sign_transaction_request(request).await?;
+
+        let recovered =
+            signed_tx.into_ecrecovered().ok_or(EthApiError::InvalidTransactionSignature)?;
+
+        let pool_transaction = <Pool::Transaction>::from_recovered_transaction(recovered.into());
+
+        // submit the transaction to the pool with a `Local` origin
+        let hash = self.pool().add_transaction(TransactionOrigin::Local, pool_transaction).await?;
+
+        Ok(hash)
+    }
+
+    async fn sign_transaction_request(
+        &self,
+        mut request: TransactionRequest,
+    ) -> EthResult<TransactionSigned> {
         let from = match request.from {
             Some(from) => from,
             None => return Err(SignError::NoAccount.into()),
@@ -500,17 +540,7 @@ where
             None => return Err(EthApiError::ConflictingFeeFieldsInRequest),
         };
 
-        let signed_tx = self.sign_request(&from, transaction)?;
-
-        let recovered =
-            signed_tx.into_ecrecovered().ok_or(EthApiError::InvalidTransactionSignature)?;
-
-        let pool_transaction = <Pool::Transaction>::from_recovered_transaction(recovered.into());
-
-        // submit the transaction to the pool with a `Local` origin
-        let hash = self.pool().add_transaction(TransactionOrigin::Local, pool_transaction).await?;
-
-        Ok(hash)
+        self.sign_request(&from, transaction)
     }
 
     async fn spawn_with_call_at<F, R>(
@@ -659,10 +689,13 @@ where
         // we need to get the state of the parent block because we're essentially replaying the
         // block the transaction is included in
         let parent_block = block.parent_hash;
+        let header = block.header.unseal();
         let block_txs = block.body;
+        let chain_spec = self.provider().chain_spec();
 
         self.spawn_with_state_at_block(parent_block.into(), move |state| {
             let mut db = SubState::new(State::new(state));
+            apply_pre_block_system_calls(&mut db, &chain_spec, &header)?;
 
             // replay all transactions prior to the targeted transaction
             replay_transactions_until(&mut db, cfg.clone(), block_env.clone(), block_txs, tx.hash)?;
@@ -716,7 +749,7 @@ where
         from: &Address,
         request: TypedTransactionRequest,
     ) -> EthResult<TransactionSigned> {
-        for signer in self.inner.signers.iter() {
+        for signer in self.inner.signers.read().iter() {
             if signer.is_signer_for(from) {
                 return match signer.sign_transaction(request, from) {
                     Ok(tx) => Ok(tx),
@@ -755,7 +788,35 @@ where
 
         Ok(None)
     }
+
+    /// Requests the transaction from the pool of a connected peer.
+    ///
+    /// Returns `None` if no peer responded with the transaction within
+    /// [PEER_TRANSACTION_LOOKUP_TIMEOUT].
+    #[cfg(feature = "peer-transactions")]
+    async fn transaction_by_hash_from_peers(&self, hash: H256) -> Option<TransactionSource> {
+        let request = self.network().get_pooled_transactions(vec![hash]);
+        let transactions = match tokio::time::timeout(PEER_TRANSACTION_LOOKUP_TIMEOUT, request)
+            .await
+        {
+            Ok(Ok(transactions)) => transactions,
+            Ok(Err(err)) => {
+                tracing::trace!(target: "rpc::eth", ?err, ?hash, "Failed to request transaction from peers");
+                return None
+            }
+            Err(_) => {
+                tracing::trace!(target: "rpc::eth", ?hash, "Timed out requesting transaction from peers");
+                return None
+            }
+        };
+
+        // peers may respond with unrelated transactions
+        let transaction = transactions.into_iter().find(|tx| *tx.hash() == hash)?;
+        let transaction = transaction.try_into_ecrecovered().ok()?;
+        Some(TransactionSource::Pool(transaction.into_ecrecovered_transaction()))
+    }
 }
+
 /// Represents from where a transaction was fetched.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum TransactionSource {
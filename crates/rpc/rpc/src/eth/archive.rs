@@ -0,0 +1,269 @@
+//! Fallback to a remote archive node for blocks and receipts that are not in the database.
+
+use jsonrpsee::{
+    core::client::ClientT,
+    http_client::{HttpClient, HttpClientBuilder},
+    rpc_params,
+};
+use reth_primitives::{
+    proofs, Block, BlockNumber, BlockNumberOrTag, Log, Receipt, SealedHeader, TxType, H256,
+};
+use reth_rlp::Decodable;
+use reth_rpc_api::DebugApiClient;
+use reth_rpc_types::TransactionReceipt;
+use std::time::Duration;
+
+/// The timeout of requests to the archive node.
+const ARCHIVE_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Errors that can occur when fetching data from the archive node.
+#[derive(Debug, thiserror::Error)]
+pub enum ArchiveError {
+    /// The request to the archive node failed.
+    #[error(transparent)]
+    Rpc(#[from] jsonrpsee::core::Error),
+    /// The archive node doesn't have the block.
+    #[error("archive node has no block {0}")]
+    MissingBlock(BlockNumber),
+    /// The archive node doesn't have the receipts of the block.
+    #[error("archive node has no receipts for block {0}")]
+    MissingReceipts(BlockNumber),
+    /// The block of the archive node can't be decoded.
+    #[error("failed to decode block {number} of the archive node: {err}")]
+    Decode {
+        /// The number of the block.
+        number: BlockNumber,
+        /// The decoding error.
+        err: reth_rlp::DecodeError,
+    },
+    /// The archive node returned a block with a different hash.
+    #[error("archive node returned block {got:?}, expected canonical block {expected:?}")]
+    HashMismatch {
+        /// The hash of the local canonical block.
+        expected: H256,
+        /// The hash of the block of the archive node.
+        got: H256,
+    },
+    /// The body of the block doesn't match the local header.
+    #[error("body of block {0} of the archive node does not match its header")]
+    BodyMismatch(BlockNumber),
+    /// The receipts don't match the receipts root of the local header.
+    #[error(
+        "receipts root {got:?} of the archive node does not match the header of block {number}"
+    )]
+    ReceiptsRootMismatch {
+        /// The number of the block.
+        number: BlockNumber,
+        /// The receipts root of the receipts of the archive node.
+        got: H256,
+    },
+    /// The receipt of the archive node belongs to a different block.
+    #[error(
+        "archive node receipt belongs to block {got:?}, expected canonical block {expected:?}"
+    )]
+    ReceiptBlockMismatch {
+        /// The hash of the local canonical block.
+        expected: H256,
+        /// The block hash of the receipt.
+        got: Option<H256>,
+    },
+    /// The receipt has an unknown transaction type.
+    #[error("unsupported transaction type {0}")]
+    UnsupportedTxType(u8),
+    /// The receipt has no status, receipts before Byzantium contain the intermediate state root
+    /// instead.
+    #[error("pre-byzantium receipts without status are not supported")]
+    PreByzantiumReceipt,
+}
+
+/// A client for a remote archive node that serves the blocks and receipts that are not in the
+/// local database, for example because the receipts were pruned or the node was synced from a
+/// checkpoint.
+///
+/// Everything that is fetched from the archive node is verified against the local canonical
+/// header before it is returned.
+#[derive(Debug, Clone)]
+pub struct ArchiveClient {
+    client: HttpClient,
+}
+
+impl ArchiveClient {
+    /// Creates a client for the archive node with the given HTTP endpoint.
+    pub fn new(url: impl AsRef<str>) -> Result<Self, ArchiveError> {
+        let client =
+            HttpClientBuilder::default().request_timeout(ARCHIVE_REQUEST_TIMEOUT).build(url)?;
+        Ok(Self { client })
+    }
+
+    /// Fetches the block of the given canonical header with `debug_getRawBlock`.
+    ///
+    /// The block is verified against the hash of the header and its body against the
+    /// transactions, ommers and withdrawals roots of the header.
+    pub async fn block(&self, header: &SealedHeader) -> Result<Block, ArchiveError> {
+        let raw = DebugApiClient::raw_block(&self.client, header.hash.into()).await?;
+        if raw.is_empty() {
+            return Err(ArchiveError::MissingBlock(header.number))
+        }
+        let block = Block::decode(&mut raw.as_ref())
+            .map_err(|err| ArchiveError::Decode { number: header.number, err })?;
+        verify_block(header, &block)?;
+        Ok(block)
+    }
+
+    /// Fetches the receipts of the block of the given canonical header with
+    /// `eth_getBlockReceipts`.
+    ///
+    /// The receipts are verified against the receipts root of the header.
+    pub async fn receipts(&self, header: &SealedHeader) -> Result<Vec<Receipt>, ArchiveError> {
+        let remote: Option<Vec<TransactionReceipt>> = self
+            .client
+            .request("eth_getBlockReceipts", rpc_params![BlockNumberOrTag::Number(header.number)])
+            .await?;
+        let remote = remote.ok_or(ArchiveError::MissingReceipts(header.number))?;
+
+        let receipts = remote
+            .into_iter()
+            .map(|receipt| convert_receipt(receipt, header.hash))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let root = proofs::calculate_receipt_root(
+            &receipts.iter().cloned().map(Receipt::with_bloom).collect::<Vec<_>>(),
+        );
+        if root != header.receipts_root {
+            return Err(ArchiveError::ReceiptsRootMismatch { number: header.number, got: root })
+        }
+
+        Ok(receipts)
+    }
+}
+
+/// Verifies that the block of the archive node is the block of the given canonical header.
+fn verify_block(header: &SealedHeader, block: &Block) -> Result<(), ArchiveError> {
+    let hash = block.header.hash_slow();
+    if hash != header.hash {
+        return Err(ArchiveError::HashMismatch { expected: header.hash, got: hash })
+    }
+
+    let withdrawals_root = block.withdrawals.as_deref().map(proofs::calculate_withdrawals_root);
+    if proofs::calculate_transaction_root(&block.body) != header.transactions_root ||
+        proofs::calculate_ommers_root(&block.ommers) != header.ommers_hash ||
+        withdrawals_root != header.withdrawals_root
+    {
+        return Err(ArchiveError::BodyMismatch(header.number))
+    }
+
+    Ok(())
+}
+
+/// Converts a receipt of the archive node for the canonical block with the given hash.
+fn convert_receipt(receipt: TransactionReceipt, block_hash: H256) -> Result<Receipt, ArchiveError> {
+    if receipt.block_hash != Some(block_hash) {
+        return Err(ArchiveError::ReceiptBlockMismatch {
+            expected: block_hash,
+            got: receipt.block_hash,
+        })
+    }
+
+    let tx_type = match receipt.transaction_type.to::<u8>() {
+        0 => TxType::Legacy,
+        1 => TxType::EIP2930,
+        2 => TxType::EIP1559,
+        3 => TxType::EIP4844,
+        ty => return Err(ArchiveError::UnsupportedTxType(ty)),
+    };
+    // receipts before byzantium contain the intermediate state root instead of the status
+    let status = receipt.status_code.ok_or(ArchiveError::PreByzantiumReceipt)?;
+
+    Ok(Receipt {
+        tx_type,
+        success: !status.is_zero(),
+        cumulative_gas_used: receipt.cumulative_gas_used.to(),
+        logs: receipt
+            .logs
+            .into_iter()
+            .map(|log| Log { address: log.address, topics: log.topics, data: log.data })
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+    use reth_interfaces::test_utils::generators::{self, random_block};
+    use reth_primitives::{Address, Bloom, U256, U64, U8};
+
+    fn remote_receipt(block_hash: H256) -> TransactionReceipt {
+        TransactionReceipt {
+            transaction_hash: Some(H256::random()),
+            transaction_index: U64::zero(),
+            block_hash: Some(block_hash),
+            block_number: Some(U256::from(1)),
+            cumulative_gas_used: U256::from(21_000),
+            gas_used: Some(U256::from(21_000)),
+            effective_gas_price: Default::default(),
+            blob_gas_used: None,
+            blob_gas_price: None,
+            from: Address::random(),
+            to: Some(Address::random()),
+            contract_address: None,
+            logs: vec![],
+            logs_bloom: Bloom::zero(),
+            state_root: None,
+            status_code: Some(U64::one()),
+            transaction_type: U8::from(2),
+        }
+    }
+
+    #[test]
+    fn convert_remote_receipt() {
+        let block_hash = H256::random();
+        let receipt = convert_receipt(remote_receipt(block_hash), block_hash).unwrap();
+        assert_eq!(
+            receipt,
+            Receipt {
+                tx_type: TxType::EIP1559,
+                success: true,
+                cumulative_gas_used: 21_000,
+                logs: vec![]
+            }
+        );
+
+        // receipt of a different block
+        assert_matches!(
+            convert_receipt(remote_receipt(H256::random()), block_hash),
+            Err(ArchiveError::ReceiptBlockMismatch { .. })
+        );
+
+        // pre-byzantium receipt
+        let mut receipt = remote_receipt(block_hash);
+        receipt.status_code = None;
+        receipt.state_root = Some(H256::random());
+        assert_matches!(
+            convert_receipt(receipt, block_hash),
+            Err(ArchiveError::PreByzantiumReceipt)
+        );
+    }
+
+    #[test]
+    fn verify_remote_block() {
+        let mut rng = generators::rng();
+        let block = random_block(&mut rng, 1, None, Some(3), Some(1));
+        let header = block.header.clone();
+        let block = block.unseal();
+        assert_matches!(verify_block(&header, &block), Ok(()));
+
+        // block with a different header
+        let mut other = block.clone();
+        other.header.gas_used += 1;
+        assert_matches!(verify_block(&header, &other), Err(ArchiveError::HashMismatch { .. }));
+
+        // body that doesn't match the header
+        let mut other = block.clone();
+        other.body.pop();
+        assert_matches!(verify_block(&header, &other), Err(ArchiveError::BodyMismatch(1)));
+        let mut other = block;
+        other.ommers.clear();
+        assert_matches!(verify_block(&header, &other), Err(ArchiveError::BodyMismatch(1)));
+    }
+}
@@ -0,0 +1,149 @@
+//! A small cache for the blob gas fields of recent canonical blocks.
+
+use futures::{Stream, StreamExt};
+use parking_lot::RwLock;
+use reth_primitives::{
+    calculate_excess_blob_gas,
+    constants::eip4844::{blob_fee, MAX_DATA_GAS_PER_BLOCK},
+    BlockNumber, Header,
+};
+use reth_provider::CanonStateNotification;
+use std::{collections::BTreeMap, sync::Arc};
+
+/// The default number of blocks kept in the [BlobFeeCache].
+pub const DEFAULT_BLOB_FEE_CACHE_MAX_BLOCKS: usize = 1024;
+
+/// The blob gas fields of a post-Cancun block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockBlobFee {
+    /// The total blob gas consumed by the transactions of the block.
+    pub blob_gas_used: u64,
+    /// The excess blob gas of the block.
+    pub excess_blob_gas: u64,
+}
+
+impl BlockBlobFee {
+    /// Returns the blob fields of the header, or `None` if the header is pre-Cancun.
+    pub fn from_header(header: &Header) -> Option<Self> {
+        Some(Self {
+            blob_gas_used: header.blob_gas_used?,
+            excess_blob_gas: header.excess_blob_gas?,
+        })
+    }
+
+    /// Returns the blob base fee of the block.
+    pub fn blob_fee(&self) -> u128 {
+        blob_fee(self.excess_blob_gas).to::<u128>()
+    }
+
+    /// Returns the blob base fee of the block following this block.
+    pub fn next_block_blob_fee(&self) -> u128 {
+        blob_fee(calculate_excess_blob_gas(self.excess_blob_gas, self.blob_gas_used)).to::<u128>()
+    }
+
+    /// Returns the ratio of blob gas used to the max blob gas per block.
+    pub fn blob_gas_used_ratio(&self) -> f64 {
+        self.blob_gas_used as f64 / MAX_DATA_GAS_PER_BLOCK as f64
+    }
+}
+
+/// Keeps the [BlockBlobFee] of the most recent canonical blocks, keyed by block number.
+///
+/// The cache is updated by [blob_fee_cache_new_blocks_task] whenever new blocks are committed to
+/// the canonical chain. Lookups for blocks that are not cached should fall back to the header.
+#[derive(Debug, Clone)]
+pub struct BlobFeeCache {
+    inner: Arc<RwLock<BTreeMap<BlockNumber, BlockBlobFee>>>,
+    max_blocks: usize,
+}
+
+impl BlobFeeCache {
+    /// Creates a new cache that keeps at most `max_blocks` entries.
+    pub fn new(max_blocks: usize) -> Self {
+        Self { inner: Default::default(), max_blocks }
+    }
+
+    /// Returns the cached entry of the given block.
+    pub fn get(&self, number: BlockNumber) -> Option<BlockBlobFee> {
+        self.inner.read().get(&number).copied()
+    }
+
+    /// Returns the number and entry of the most recent cached block.
+    pub fn latest(&self) -> Option<(BlockNumber, BlockBlobFee)> {
+        self.inner.read().last_key_value().map(|(number, fee)| (*number, *fee))
+    }
+
+    /// Inserts the blob fields of the given canonical headers.
+    ///
+    /// All cached entries at or above the first inserted block are removed first, since they are
+    /// no longer canonical.
+    pub fn insert_canonical<'a>(&self, headers: impl IntoIterator<Item = &'a Header>) {
+        let mut headers = headers.into_iter().peekable();
+        let Some(first) = headers.peek() else { return };
+
+        let mut inner = self.inner.write();
+        inner.split_off(&first.number);
+        for header in headers {
+            if let Some(fee) = BlockBlobFee::from_header(header) {
+                inner.insert(header.number, fee);
+            }
+        }
+        while inner.len() > self.max_blocks {
+            inner.pop_first();
+        }
+    }
+}
+
+impl Default for BlobFeeCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_BLOB_FEE_CACHE_MAX_BLOCKS)
+    }
+}
+
+/// Awaits for new chain events and inserts the blob fields of newly committed blocks into the
+/// cache.
+pub async fn blob_fee_cache_new_blocks_task<St>(cache: BlobFeeCache, mut events: St)
+where
+    St: Stream<Item = CanonStateNotification> + Unpin + 'static,
+{
+    while let Some(event) = events.next().await {
+        if let Some(committed) = event.committed() {
+            cache.insert_canonical(
+                committed.blocks().values().map(|block| &block.block.header.header),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(number: BlockNumber, blob_gas_used: u64, excess_blob_gas: u64) -> Header {
+        Header {
+            number,
+            blob_gas_used: Some(blob_gas_used),
+            excess_blob_gas: Some(excess_blob_gas),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn insert_canonical_replaces_reorged_blocks() {
+        let cache = BlobFeeCache::new(2);
+        cache.insert_canonical(&[header(1, 0, 0), header(2, 0, 0), header(3, 0, 0)]);
+        assert_eq!(cache.get(1), None);
+        assert_eq!(cache.latest().map(|(number, _)| number), Some(3));
+
+        let fee =
+            BlockBlobFee { blob_gas_used: MAX_DATA_GAS_PER_BLOCK, excess_blob_gas: 10_000_000 };
+        cache.insert_canonical(&[header(3, fee.blob_gas_used, fee.excess_blob_gas)]);
+        assert_eq!(cache.latest(), Some((3, fee)));
+        assert_eq!(fee.blob_gas_used_ratio(), 1.);
+        assert!(fee.next_block_blob_fee() > fee.blob_fee());
+
+        // pre-Cancun headers are not cached
+        cache.insert_canonical(&[Header { number: 3, ..Default::default() }]);
+        assert_eq!(cache.latest().map(|(number, _)| number), Some(2));
+    }
+}
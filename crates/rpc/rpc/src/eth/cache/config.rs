@@ -21,6 +21,9 @@ pub const DEFAULT_RECEIPT_CACHE_MAX_LEN: u32 = 2000;
 /// Default cache size for the env cache: 1000 envs.
 pub const DEFAULT_ENV_CACHE_MAX_LEN: u32 = 1000;
 
+/// Default number of latest canonical blocks that are kept in memory: 64 blocks.
+pub const DEFAULT_LATEST_BLOCK_CACHE_MAX_LEN: u32 = 64;
+
 /// Default number of concurrent database requests.
 pub const DEFAULT_CONCURRENT_DB_REQUESTS: usize = 512;
 
@@ -40,10 +43,20 @@ pub struct EthStateCacheConfig {
     ///
     /// Default is 1000.
     pub max_envs: u32,
+    /// Max number of latest canonical blocks, with their receipts, that are kept in memory
+    /// regardless of the reads of older blocks.
+    ///
+    /// Default is 64.
+    pub max_latest_blocks: u32,
     /// Max number of concurrent database requests.
     ///
     /// Default is 512.
     pub max_concurrent_db_requests: usize,
+    /// The HTTP endpoint of an archive node that serves the blocks and receipts that are not in
+    /// the database.
+    ///
+    /// Default is no archive node.
+    pub archive_url: Option<String>,
 }
 
 impl Default for EthStateCacheConfig {
@@ -52,7 +65,9 @@ impl Default for EthStateCacheConfig {
             max_blocks: DEFAULT_BLOCK_CACHE_MAX_LEN,
             max_receipts: DEFAULT_RECEIPT_CACHE_MAX_LEN,
             max_envs: DEFAULT_ENV_CACHE_MAX_LEN,
+            max_latest_blocks: DEFAULT_LATEST_BLOCK_CACHE_MAX_LEN,
             max_concurrent_db_requests: DEFAULT_CONCURRENT_DB_REQUESTS,
+            archive_url: None,
         }
     }
 }
@@ -0,0 +1,121 @@
+//! An in-memory cache of the latest canonical blocks.
+
+use reth_primitives::{Block, BlockNumber, Receipt, SealedBlock, H256};
+use std::collections::{BTreeMap, HashMap};
+
+/// A canonical block with its receipts.
+#[derive(Debug)]
+struct LatestBlock {
+    hash: H256,
+    block: Block,
+    receipts: Vec<Receipt>,
+}
+
+/// Keeps the latest `max_blocks` canonical blocks and their receipts in memory.
+///
+/// Unlike the LRU caches, entries are not evicted by reads of older blocks, so the tip of the chain
+/// is always served from memory. The cache is only maintained by canonical state notifications:
+/// new canonical blocks replace blocks with the same number and reorged blocks are removed.
+#[derive(Debug)]
+pub(crate) struct LatestBlocks {
+    /// The maximum number of blocks to keep, `0` disables the cache.
+    max_blocks: usize,
+    /// The cached blocks by number.
+    blocks: BTreeMap<BlockNumber, LatestBlock>,
+    /// The numbers of the cached blocks by hash.
+    numbers: HashMap<H256, BlockNumber>,
+}
+
+impl LatestBlocks {
+    /// Creates a new cache that keeps at most `max_blocks` blocks.
+    pub(crate) fn new(max_blocks: u32) -> Self {
+        Self {
+            max_blocks: max_blocks as usize,
+            blocks: Default::default(),
+            numbers: Default::default(),
+        }
+    }
+
+    /// Returns the cached block with the given hash.
+    pub(crate) fn block(&self, hash: &H256) -> Option<&Block> {
+        self.get(hash).map(|cached| &cached.block)
+    }
+
+    /// Returns the receipts of the cached block with the given hash.
+    pub(crate) fn receipts(&self, hash: &H256) -> Option<&Vec<Receipt>> {
+        self.get(hash).map(|cached| &cached.receipts)
+    }
+
+    fn get(&self, hash: &H256) -> Option<&LatestBlock> {
+        self.numbers.get(hash).and_then(|number| self.blocks.get(number))
+    }
+
+    /// Inserts a new canonical block, replacing the block with the same number and evicting the
+    /// oldest blocks if the cache is full.
+    pub(crate) fn insert(&mut self, block: SealedBlock, receipts: Vec<Receipt>) {
+        if self.max_blocks == 0 {
+            return
+        }
+
+        let (number, hash) = (block.number, block.hash);
+        let cached = LatestBlock { hash, block: block.unseal(), receipts };
+        if let Some(replaced) = self.blocks.insert(number, cached) {
+            self.numbers.remove(&replaced.hash);
+        }
+        self.numbers.insert(hash, number);
+
+        while self.blocks.len() > self.max_blocks {
+            if let Some((_, evicted)) = self.blocks.pop_first() {
+                self.numbers.remove(&evicted.hash);
+            }
+        }
+    }
+
+    /// Removes the block with the given hash, if it was reorged out of the canonical chain.
+    pub(crate) fn remove(&mut self, hash: &H256) {
+        if let Some(number) = self.numbers.remove(hash) {
+            self.blocks.remove(&number);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::Header;
+
+    fn block(number: BlockNumber, extra_data: u8) -> SealedBlock {
+        let header = Header { number, extra_data: vec![extra_data].into(), ..Default::default() }
+            .seal_slow();
+        SealedBlock { header, ..Default::default() }
+    }
+
+    #[test]
+    fn keeps_latest_blocks() {
+        let mut cache = LatestBlocks::new(2);
+        let blocks = (1..=3).map(|number| block(number, 0)).collect::<Vec<_>>();
+        for block in blocks.clone() {
+            cache.insert(block, vec![Receipt::default()]);
+        }
+        assert_eq!(cache.blocks.len(), 2);
+        assert!(cache.block(&blocks[0].hash).is_none());
+        assert_eq!(cache.block(&blocks[2].hash).map(|block| block.number), Some(3));
+        assert_eq!(cache.receipts(&blocks[1].hash).map(Vec::len), Some(1));
+
+        // a reorg replaces the tip
+        let reorged = block(3, 1);
+        cache.remove(&blocks[2].hash);
+        cache.insert(reorged.clone(), Vec::new());
+        assert_eq!(cache.blocks.len(), 2);
+        assert!(cache.block(&blocks[2].hash).is_none());
+        assert_eq!(cache.receipts(&reorged.hash).map(Vec::len), Some(0));
+    }
+
+    #[test]
+    fn disabled_cache() {
+        let mut cache = LatestBlocks::new(0);
+        let block = block(1, 0);
+        cache.insert(block.clone(), Vec::new());
+        assert!(cache.block(&block.hash).is_none());
+    }
+}
@@ -1,8 +1,9 @@
 //! Async caching support for eth RPC
 
+use crate::eth::archive::ArchiveClient;
 use futures::{future::Either, Stream, StreamExt};
 use reth_interfaces::{provider::ProviderError, Result};
-use reth_primitives::{Block, Receipt, SealedBlock, TransactionSigned, H256};
+use reth_primitives::{Block, Receipt, SealedBlock, SealedHeader, TransactionSigned, H256};
 use reth_provider::{
     BlockReader, BlockSource, CanonStateNotification, EvmEnvProvider, StateProviderFactory,
 };
@@ -20,10 +21,17 @@ use tokio::sync::{
     oneshot, Semaphore,
 };
 use tokio_stream::wrappers::UnboundedReceiverStream;
+use tracing::{debug, error};
+
+mod blob_fee;
+pub use blob_fee::*;
 
 mod config;
 pub use config::*;
 
+mod latest;
+use latest::LatestBlocks;
+
 mod metrics;
 
 mod multi_consumer;
@@ -69,7 +77,9 @@ impl EthStateCache {
         max_blocks: u32,
         max_receipts: u32,
         max_envs: u32,
+        max_latest_blocks: u32,
         max_concurrent_db_operations: usize,
+        archive: Option<ArchiveClient>,
     ) -> (Self, EthStateCacheService<Provider, Tasks>) {
         let (to_service, rx) = unbounded_channel();
         let service = EthStateCacheService {
@@ -77,10 +87,12 @@ impl EthStateCache {
             full_block_cache: BlockLruCache::new(max_blocks, "blocks"),
             receipts_cache: ReceiptsLruCache::new(max_receipts, "receipts"),
             evm_env_cache: EnvLruCache::new(max_envs, "evm_env"),
+            latest_blocks: LatestBlocks::new(max_latest_blocks),
             action_tx: to_service.clone(),
             action_rx: UnboundedReceiverStream::new(rx),
             action_task_spawner,
             rate_limiter: Arc::new(Semaphore::new(max_concurrent_db_operations)),
+            archive,
         };
         let cache = EthStateCache { to_service };
         (cache, service)
@@ -110,15 +122,30 @@ impl EthStateCache {
         Provider: StateProviderFactory + BlockReader + EvmEnvProvider + Clone + Unpin + 'static,
         Tasks: TaskSpawner + Clone + 'static,
     {
-        let EthStateCacheConfig { max_blocks, max_receipts, max_envs, max_concurrent_db_requests } =
-            config;
+        let EthStateCacheConfig {
+            max_blocks,
+            max_receipts,
+            max_envs,
+            max_latest_blocks,
+            max_concurrent_db_requests,
+            archive_url,
+        } = config;
+        let archive = archive_url.and_then(|url| match ArchiveClient::new(&url) {
+            Ok(archive) => Some(archive),
+            Err(err) => {
+                error!(target: "rpc::eth::cache", %url, %err, "Invalid archive node url, only blocks and receipts in the database are served");
+                None
+            }
+        });
         let (this, service) = Self::create(
             provider,
             executor.clone(),
             max_blocks,
             max_receipts,
             max_envs,
+            max_latest_blocks,
             max_concurrent_db_requests,
+            archive,
         );
         executor.spawn_critical("eth state cache", Box::pin(service));
         this
@@ -210,6 +237,10 @@ impl EthStateCache {
 /// that does the IO and sends the result back to it. This way the caching service only
 /// handles messages and does LRU lookups and never blocking IO.
 ///
+/// If an [ArchiveClient] is configured, blocks whose body is not in the database and receipts that
+/// were pruned are fetched from the archive node on demand, verified against the local header and
+/// cached like the data from disk.
+///
 /// Caution: The channel for the data is _unbounded_ it is assumed that this is mainly used by the
 /// [EthApi](crate::EthApi) which is typically invoked by the RPC server, which already uses permits
 /// to limit concurrent requests.
@@ -233,6 +264,8 @@ pub(crate) struct EthStateCacheService<
     receipts_cache: ReceiptsLruCache<LimitReceipts>,
     /// The LRU cache for revm environments
     evm_env_cache: EnvLruCache<LimitEnvs>,
+    /// The latest canonical blocks and their receipts, which are checked before the LRU caches.
+    latest_blocks: LatestBlocks,
     /// Sender half of the action channel.
     action_tx: UnboundedSender<CacheAction>,
     /// Receiver half of the action channel.
@@ -241,6 +274,8 @@ pub(crate) struct EthStateCacheService<
     action_task_spawner: Tasks,
     /// Rate limiter
     rate_limiter: Arc<Semaphore>,
+    /// The archive node for the blocks and receipts that are not in the database.
+    archive: Option<ArchiveClient>,
 }
 
 impl<Provider, Tasks> EthStateCacheService<Provider, Tasks>
@@ -248,6 +283,99 @@ where
     Provider: StateProviderFactory + BlockReader + EvmEnvProvider + Clone + Unpin + 'static,
     Tasks: TaskSpawner + Clone + 'static,
 {
+    /// Returns the block if it is one of the latest blocks or in the LRU cache.
+    fn cached_block(&mut self, block_hash: &H256) -> Option<&Block> {
+        // the latest blocks are not in the LRU cache, so they are checked first
+        if let Some(block) = self.latest_blocks.block(block_hash) {
+            return Some(block)
+        }
+        self.full_block_cache.get(block_hash).map(|block| &*block)
+    }
+
+    /// Returns the receipts of the block if it is one of the latest blocks or they are in the LRU
+    /// cache.
+    fn cached_receipts(&mut self, block_hash: &H256) -> Option<&Vec<Receipt>> {
+        if let Some(receipts) = self.latest_blocks.receipts(block_hash) {
+            return Some(receipts)
+        }
+        self.receipts_cache.get(block_hash).map(|receipts| &*receipts)
+    }
+
+    /// Spawns a task that loads the block from the database, or from the archive node if its body
+    /// is not in the database.
+    fn fetch_block(&self, block_hash: H256) {
+        let provider = self.provider.clone();
+        let action_tx = self.action_tx.clone();
+        let rate_limiter = self.rate_limiter.clone();
+        let archive = self.archive.clone();
+        let task_spawner = self.action_task_spawner.clone();
+        self.action_task_spawner.spawn_blocking(Box::pin(async move {
+            let (res, missing) = {
+                // Acquire permit
+                let _permit = rate_limiter.acquire().await;
+                // Only look in the database to prevent situations where we
+                // looking up the tree is blocking
+                let res = provider.find_block_by_hash(block_hash, BlockSource::Database);
+                let missing = archive.and_then(|archive| {
+                    missing_block_header(&provider, block_hash, &res).map(|header| (archive, header))
+                });
+                (res, missing)
+            };
+
+            let Some((archive, header)) = missing else {
+                let _ = action_tx.send(CacheAction::BlockResult { block_hash, res });
+                return
+            };
+            task_spawner.spawn(Box::pin(async move {
+                let res = match archive.block(&header).await {
+                    Ok(block) => Ok(Some(block)),
+                    Err(err) => {
+                        debug!(target: "rpc::eth::cache", ?block_hash, %err, "Failed to fetch block from archive node");
+                        res
+                    }
+                };
+                let _ = action_tx.send(CacheAction::BlockResult { block_hash, res });
+            }));
+        }));
+    }
+
+    /// Spawns a task that loads the receipts of the block from the database, or from the archive
+    /// node if they are not in the database.
+    fn fetch_receipts(&self, block_hash: H256) {
+        let provider = self.provider.clone();
+        let action_tx = self.action_tx.clone();
+        let rate_limiter = self.rate_limiter.clone();
+        let archive = self.archive.clone();
+        let task_spawner = self.action_task_spawner.clone();
+        self.action_task_spawner.spawn_blocking(Box::pin(async move {
+            let (res, missing) = {
+                // Acquire permit
+                let _permit = rate_limiter.acquire().await;
+                let res = provider.receipts_by_block(block_hash.into());
+                let missing = archive.and_then(|archive| {
+                    missing_receipts_header(&provider, block_hash, &res)
+                        .map(|header| (archive, header))
+                });
+                (res, missing)
+            };
+
+            let Some((archive, header)) = missing else {
+                let _ = action_tx.send(CacheAction::ReceiptsResult { block_hash, res });
+                return
+            };
+            task_spawner.spawn(Box::pin(async move {
+                let res = match archive.receipts(&header).await {
+                    Ok(receipts) => Ok(Some(receipts)),
+                    Err(err) => {
+                        debug!(target: "rpc::eth::cache", ?block_hash, %err, "Failed to fetch receipts from archive node");
+                        res
+                    }
+                };
+                let _ = action_tx.send(CacheAction::ReceiptsResult { block_hash, res });
+            }));
+        }));
+    }
+
     fn on_new_block(&mut self, block_hash: H256, res: Result<Option<Block>>) {
         if let Some(queued) = self.full_block_cache.remove(&block_hash) {
             // send the response to queued senders
@@ -311,71 +439,38 @@ where
                     match action {
                         CacheAction::GetBlock { block_hash, response_tx } => {
                             // check if block is cached
-                            if let Some(block) = this.full_block_cache.get(&block_hash).cloned() {
-                                let _ = response_tx.send(Ok(Some(block)));
+                            if let Some(block) = this.cached_block(&block_hash) {
+                                let _ = response_tx.send(Ok(Some(block.clone())));
                                 continue
                             }
 
                             // block is not in the cache, request it if this is the first consumer
                             if this.full_block_cache.queue(block_hash, Either::Left(response_tx)) {
-                                let provider = this.provider.clone();
-                                let action_tx = this.action_tx.clone();
-                                let rate_limiter = this.rate_limiter.clone();
-                                this.action_task_spawner.spawn_blocking(Box::pin(async move {
-                                    // Acquire permit
-                                    let _permit = rate_limiter.acquire().await;
-                                    // Only look in the database to prevent situations where we
-                                    // looking up the tree is blocking
-                                    let res = provider
-                                        .find_block_by_hash(block_hash, BlockSource::Database);
-                                    let _ = action_tx
-                                        .send(CacheAction::BlockResult { block_hash, res });
-                                }));
+                                this.fetch_block(block_hash);
                             }
                         }
                         CacheAction::GetBlockTransactions { block_hash, response_tx } => {
                             // check if block is cached
-                            if let Some(block) = this.full_block_cache.get(&block_hash) {
+                            if let Some(block) = this.cached_block(&block_hash) {
                                 let _ = response_tx.send(Ok(Some(block.body.clone())));
                                 continue
                             }
 
                             // block is not in the cache, request it if this is the first consumer
                             if this.full_block_cache.queue(block_hash, Either::Right(response_tx)) {
-                                let provider = this.provider.clone();
-                                let action_tx = this.action_tx.clone();
-                                let rate_limiter = this.rate_limiter.clone();
-                                this.action_task_spawner.spawn_blocking(Box::pin(async move {
-                                    // Acquire permit
-                                    let _permit = rate_limiter.acquire().await;
-                                    // Only look in the database to prevent situations where we
-                                    // looking up the tree is blocking
-                                    let res = provider
-                                        .find_block_by_hash(block_hash, BlockSource::Database);
-                                    let _ = action_tx
-                                        .send(CacheAction::BlockResult { block_hash, res });
-                                }));
+                                this.fetch_block(block_hash);
                             }
                         }
                         CacheAction::GetReceipts { block_hash, response_tx } => {
                             // check if block is cached
-                            if let Some(receipts) = this.receipts_cache.get(&block_hash).cloned() {
-                                let _ = response_tx.send(Ok(Some(receipts)));
+                            if let Some(receipts) = this.cached_receipts(&block_hash) {
+                                let _ = response_tx.send(Ok(Some(receipts.clone())));
                                 continue
                             }
 
                             // block is not in the cache, request it if this is the first consumer
                             if this.receipts_cache.queue(block_hash, response_tx) {
-                                let provider = this.provider.clone();
-                                let action_tx = this.action_tx.clone();
-                                let rate_limiter = this.rate_limiter.clone();
-                                this.action_task_spawner.spawn_blocking(Box::pin(async move {
-                                    // Acquire permit
-                                    let _permit = rate_limiter.acquire().await;
-                                    let res = provider.receipts_by_block(block_hash.into());
-                                    let _ = action_tx
-                                        .send(CacheAction::ReceiptsResult { block_hash, res });
-                                }));
+                                this.fetch_receipts(block_hash);
                             }
                         }
                         CacheAction::GetEnv { block_hash, response_tx } => {
@@ -427,6 +522,11 @@ where
                             }
                         }
                         CacheAction::CacheNewCanonicalChain { blocks, receipts } => {
+                            for (block, block_receipts) in blocks.iter().zip(&receipts) {
+                                this.latest_blocks
+                                    .insert(block.clone(), block_receipts.receipts.clone());
+                            }
+
                             for block in blocks {
                                 this.on_new_block(block.hash, Ok(Some(block.unseal())));
                             }
@@ -438,6 +538,11 @@ where
                                 );
                             }
                         }
+                        CacheAction::RemoveReorgedChain { block_hashes } => {
+                            for block_hash in block_hashes {
+                                this.latest_blocks.remove(&block_hash);
+                            }
+                        }
                     };
                     this.update_cached_metrics();
                 }
@@ -456,6 +561,7 @@ enum CacheAction {
     ReceiptsResult { block_hash: H256, res: Result<Option<Vec<Receipt>>> },
     EnvResult { block_hash: H256, res: Box<Result<(CfgEnv, BlockEnv)>> },
     CacheNewCanonicalChain { blocks: Vec<SealedBlock>, receipts: Vec<BlockReceipts> },
+    RemoveReorgedChain { block_hashes: Vec<H256> },
 }
 
 struct BlockReceipts {
@@ -463,6 +569,37 @@ struct BlockReceipts {
     receipts: Vec<Receipt>,
 }
 
+/// Returns the canonical header of the block if the block is known but its body is not in the
+/// database.
+fn missing_block_header<Provider: BlockReader>(
+    provider: &Provider,
+    block_hash: H256,
+    block: &Result<Option<Block>>,
+) -> Option<SealedHeader> {
+    if !matches!(block, Ok(None)) {
+        return None
+    }
+    provider.header(&block_hash).ok().flatten().map(|header| header.seal(block_hash))
+}
+
+/// Returns the canonical header of the block if the block is known but its receipts are not in the
+/// database, because the body of the block is missing or the receipts were pruned.
+fn missing_receipts_header<Provider: BlockReader>(
+    provider: &Provider,
+    block_hash: H256,
+    receipts: &Result<Option<Vec<Receipt>>>,
+) -> Option<SealedHeader> {
+    let Ok(receipts) = receipts else { return None };
+    let number = provider.block_number(block_hash).ok().flatten()?;
+    if let Some(receipts) = receipts {
+        let body = provider.block_body_indices(number).ok().flatten()?;
+        if receipts.len() as u64 == body.tx_count {
+            return None
+        }
+    }
+    provider.header_by_number(number).ok().flatten().map(|header| header.seal(block_hash))
+}
+
 /// Awaits for new chain events and directly inserts them into the cache so they're available
 /// immediately before they need to be fetched from disk.
 pub async fn cache_new_blocks_task<St>(eth_state_cache: EthStateCache, mut events: St)
@@ -470,6 +607,13 @@ where
     St: Stream<Item = CanonStateNotification> + Unpin + 'static,
 {
     while let Some(event) = events.next().await {
+        if let Some(reverted) = event.reverted() {
+            // the reorged blocks are no longer among the latest canonical blocks
+            let block_hashes = reverted.blocks().values().map(|block| block.hash).collect();
+            let _ =
+                eth_state_cache.to_service.send(CacheAction::RemoveReorgedChain { block_hashes });
+        }
+
         if let Some(committed) = event.committed() {
             // we're only interested in new committed blocks
             let (blocks, state) = committed.inner();
@@ -5,6 +5,7 @@ use jsonrpsee::{
     core::Error as RpcError,
     types::{error::CALL_EXECUTION_FAILED_CODE, ErrorObject},
 };
+use reth_interfaces::ErrorKind;
 use reth_primitives::{abi::decode_revert_reason, Address, Bytes, U256};
 use reth_revm::tracing::js::JsInspectorError;
 use reth_rpc_types::{error::EthRpcErrorCode, BlockError, CallInputError};
@@ -43,6 +44,9 @@ pub enum EthApiError {
     /// An internal error where prevrandao is not set in the evm's environment
     #[error("Prevrandao not in th EVM's environment after merge")]
     PrevrandaoNotSet,
+    /// Thrown when the blob base fee is requested before Cancun is active
+    #[error("excess blob gas missing in the latest block header")]
+    ExcessBlobGasNotSet,
     /// Thrown when a call or transaction request (`eth_call`, `eth_estimateGas`,
     /// `eth_sendTransaction`) contains conflicting fields (legacy, EIP-1559)
     #[error("both gasPrice and (maxFeePerGas or maxPriorityFeePerGas) specified")]
@@ -110,9 +114,19 @@ impl From<EthApiError> for ErrorObject<'static> {
             EthApiError::InvalidTransaction(err) => err.into(),
             EthApiError::PoolError(err) => err.into(),
             EthApiError::PrevrandaoNotSet |
+            EthApiError::ExcessBlobGasNotSet |
             EthApiError::InvalidBlockData(_) |
-            EthApiError::Internal(_) |
             EthApiError::TransactionNotFound => internal_rpc_err(error.to_string()),
+            EthApiError::Internal(ref err) => match err.kind() {
+                ErrorKind::NotFound => {
+                    rpc_error_with_code(EthRpcErrorCode::ResourceNotFound.code(), error.to_string())
+                }
+                ErrorKind::Pruned | ErrorKind::Transient => rpc_error_with_code(
+                    EthRpcErrorCode::ResourceUnavailable.code(),
+                    error.to_string(),
+                ),
+                ErrorKind::Corruption | ErrorKind::Other => internal_rpc_err(error.to_string()),
+            },
             EthApiError::UnknownBlockNumber | EthApiError::UnknownBlockOrTxIndex => {
                 rpc_error_with_code(EthRpcErrorCode::ResourceNotFound.code(), error.to_string())
             }
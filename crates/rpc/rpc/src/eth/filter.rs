@@ -9,10 +9,13 @@ use crate::{
 };
 use async_trait::async_trait;
 use jsonrpsee::{core::RpcResult, server::IdProvider};
-use reth_primitives::{BlockHashOrNumber, Receipt, SealedBlock};
+use reth_primitives::{BlockHashOrNumber, BlockNumberOrTag, Receipt, SealedBlock, U64};
 use reth_provider::{BlockIdReader, BlockReader, EvmEnvProvider};
 use reth_rpc_api::EthFilterApiServer;
-use reth_rpc_types::{Filter, FilterBlockOption, FilterChanges, FilterId, FilteredParams, Log};
+use reth_rpc_types::{
+    Filter, FilterBlockOption, FilterChanges, FilterId, FilteredParams, Log, LogsPage,
+    LogsPageCursor,
+};
 use reth_tasks::TaskSpawner;
 use reth_transaction_pool::TransactionPool;
 use std::{collections::HashMap, iter::StepBy, ops::RangeInclusive, sync::Arc, time::Instant};
@@ -22,6 +25,10 @@ use tracing::trace;
 /// The maximum number of headers we read at once when handling a range filter.
 const MAX_HEADERS_RANGE: u64 = 1_000; // with ~530bytes per header this is ~500kb
 
+/// The maximum number of blocks scanned for a single page of `eth_getLogsPage`, so that a page of
+/// a sparse filter doesn't take as long as an unbounded `eth_getLogs` query.
+const MAX_BLOCKS_PER_LOGS_PAGE: u64 = 10_000;
+
 /// `Eth` filter RPC implementation.
 pub struct EthFilter<Provider, Pool> {
     /// All nested fields bundled together.
@@ -33,13 +40,15 @@ impl<Provider, Pool> EthFilter<Provider, Pool> {
     ///
     /// This uses the given pool to get notified about new transactions, the provider to interact
     /// with the blockchain, the cache to fetch cacheable data, like the logs and the
-    /// max_logs_per_response to limit the amount of logs returned in a single response
-    /// `eth_getLogs`
+    /// max_logs_per_response and max_blocks_per_filter to limit the amount of logs returned in a
+    /// single response and the range of blocks a single `eth_getLogs` query may span, `0` disables
+    /// the limit.
     pub fn new(
         provider: Provider,
         pool: Pool,
         eth_cache: EthStateCache,
         max_logs_per_response: usize,
+        max_blocks_per_filter: u64,
         task_spawner: Box<dyn TaskSpawner>,
     ) -> Self {
         let inner = EthFilterInner {
@@ -48,6 +57,7 @@ impl<Provider, Pool> EthFilter<Provider, Pool> {
             pool,
             id_provider: Arc::new(EthSubscriptionIdProvider::default()),
             max_logs_per_response,
+            max_blocks_per_filter,
             eth_cache,
             max_headers_range: MAX_HEADERS_RANGE,
             task_spawner,
@@ -212,6 +222,18 @@ where
         trace!(target: "rpc::eth", "Serving eth_getLogs");
         Ok(self.inner.logs_for_filter(filter).await?)
     }
+
+    /// Returns a page of the logs matching given filter object.
+    ///
+    /// Handler for `eth_getLogsPage`
+    async fn logs_page(
+        &self,
+        filter: Filter,
+        cursor: Option<LogsPageCursor>,
+    ) -> RpcResult<LogsPage> {
+        trace!(target: "rpc::eth", ?cursor, "Serving eth_getLogsPage");
+        Ok(self.inner.logs_page_for_filter(filter, cursor).await?)
+    }
 }
 
 impl<Provider, Pool> std::fmt::Debug for EthFilter<Provider, Pool> {
@@ -240,6 +262,8 @@ struct EthFilterInner<Provider, Pool> {
     id_provider: Arc<dyn IdProvider>,
     /// Maximum number of logs that can be returned in a response
     max_logs_per_response: usize,
+    /// Maximum number of blocks a range filter may span
+    max_blocks_per_filter: u64,
     /// The async cache frontend for eth related data
     eth_cache: EthStateCache,
     /// maximum number of headers to read at once for range filter
@@ -275,26 +299,56 @@ where
                 Ok(all_logs)
             }
             FilterBlockOption::Range { from_block, to_block } => {
-                // compute the range
-                let info = self.provider.chain_info()?;
-
-                // we start at the most recent block if unset in filter
-                let start_block = info.best_number;
-                let from = from_block
-                    .map(|num| self.provider.convert_block_number(num))
-                    .transpose()?
-                    .flatten();
-                let to = to_block
-                    .map(|num| self.provider.convert_block_number(num))
-                    .transpose()?
-                    .flatten();
                 let (from_block_number, to_block_number) =
-                    logs_utils::get_filter_block_range(from, to, start_block, info);
+                    self.filter_block_range(from_block, to_block)?;
                 self.get_logs_in_block_range(&filter, from_block_number, to_block_number).await
             }
         }
     }
 
+    /// Returns a page of at most `max_logs_per_response` logs matching given filter object,
+    /// starting at the given cursor.
+    ///
+    /// All logs of a block hash filter are returned in a single page.
+    async fn logs_page_for_filter(
+        &self,
+        filter: Filter,
+        cursor: Option<LogsPageCursor>,
+    ) -> Result<LogsPage, FilterError> {
+        match filter.block_option {
+            FilterBlockOption::AtBlockHash(_) => {
+                Ok(LogsPage { logs: self.logs_for_filter(filter).await?, next: None })
+            }
+            FilterBlockOption::Range { from_block, to_block } => {
+                let (from_block_number, to_block_number) =
+                    self.filter_block_range(from_block, to_block)?;
+                self.get_logs_page_in_block_range(
+                    &filter,
+                    from_block_number,
+                    to_block_number,
+                    cursor,
+                )
+                .await
+            }
+        }
+    }
+
+    /// Resolves the _inclusive_ block range of a range filter.
+    fn filter_block_range(
+        &self,
+        from_block: Option<BlockNumberOrTag>,
+        to_block: Option<BlockNumberOrTag>,
+    ) -> Result<(u64, u64), FilterError> {
+        let info = self.provider.chain_info()?;
+
+        // we start at the most recent block if unset in filter
+        let start_block = info.best_number;
+        let from =
+            from_block.map(|num| self.provider.convert_block_number(num)).transpose()?.flatten();
+        let to = to_block.map(|num| self.provider.convert_block_number(num)).transpose()?.flatten();
+        Ok(logs_utils::get_filter_block_range(from, to, start_block, info))
+    }
+
     /// Installs a new filter and returns the new identifier.
     async fn install_filter(&self, kind: FilterKind) -> RpcResult<FilterId> {
         let last_poll_block_number = self.provider.best_block_number().to_rpc_result()?;
@@ -328,7 +382,10 @@ where
     ///
     /// Returns an error if:
     ///  - underlying database error
+    ///  - the range exceeds the configured limit
     ///  - amount of matches exceeds configured limit
+    ///
+    /// Both limit errors include the range the client may query instead.
     async fn get_logs_in_block_range(
         &self,
         filter: &Filter,
@@ -337,6 +394,16 @@ where
     ) -> Result<Vec<Log>, FilterError> {
         trace!(target: "rpc::eth::filter", from=from_block, to=to_block, ?filter, "finding logs in range");
 
+        if self.max_blocks_per_filter > 0 &&
+            to_block.saturating_sub(from_block) >= self.max_blocks_per_filter
+        {
+            return Err(FilterError::QueryExceedsMaxBlocks {
+                max_blocks: self.max_blocks_per_filter,
+                from_block,
+                to_block: from_block + self.max_blocks_per_filter - 1,
+            })
+        }
+
         let mut all_logs = Vec::new();
         let filter_params = FilteredParams::new(Some(filter.clone()));
 
@@ -380,10 +447,16 @@ where
 
                         // size check but only if range is multiple blocks, so we always return all
                         // logs of a single block
-                        if is_multi_block_range && all_logs.len() > self.max_logs_per_response {
-                            return Err(FilterError::QueryExceedsMaxResults(
-                                self.max_logs_per_response,
-                            ))
+                        if is_multi_block_range &&
+                            self.max_logs_per_response > 0 &&
+                            all_logs.len() > self.max_logs_per_response
+                        {
+                            // all blocks before this one fit into the response
+                            return Err(FilterError::QueryExceedsMaxResults {
+                                max_logs: self.max_logs_per_response,
+                                from_block,
+                                to_block: block.number.saturating_sub(1).max(from_block),
+                            })
                         }
                     }
                 }
@@ -392,6 +465,98 @@ where
 
         Ok(all_logs)
     }
+
+    /// Returns a page of the logs in the given _inclusive_ range that match the filter, starting
+    /// at the given cursor.
+    ///
+    /// A page ends after `max_logs_per_response` logs or [MAX_BLOCKS_PER_LOGS_PAGE] blocks,
+    /// whichever comes first, so the memory and time spent on a page are bounded. The returned
+    /// cursor points at the first log, or block, that was not part of the page.
+    async fn get_logs_page_in_block_range(
+        &self,
+        filter: &Filter,
+        from_block: u64,
+        to_block: u64,
+        cursor: Option<LogsPageCursor>,
+    ) -> Result<LogsPage, FilterError> {
+        // resume at the cursor, skipping the logs of its block that were already returned
+        let (from_block, skip_logs) = match cursor {
+            Some(cursor) if cursor.block_number.to::<u64>() >= from_block => {
+                (cursor.block_number.to::<u64>(), cursor.log_index.to::<u64>())
+            }
+            _ => (from_block, 0),
+        };
+        if from_block > to_block {
+            return Ok(LogsPage::default())
+        }
+        let page_to_block = to_block.min(from_block.saturating_add(MAX_BLOCKS_PER_LOGS_PAGE - 1));
+        let max_logs = match self.max_logs_per_response {
+            0 => usize::MAX,
+            max_logs => max_logs,
+        };
+
+        trace!(target: "rpc::eth::filter", from=from_block, to=page_to_block, ?filter, "finding logs page in range");
+
+        let mut logs = Vec::new();
+        let filter_params = FilteredParams::new(Some(filter.clone()));
+        let address_filter = FilteredParams::address_filter(&filter.address);
+        let topics_filter = FilteredParams::topics_filter(&filter.topics);
+
+        for (from, to) in
+            BlockRangeInclusiveIter::new(from_block..=page_to_block, self.max_headers_range)
+        {
+            let headers = self.provider.headers_range(from..=to)?;
+
+            for (idx, header) in headers.iter().enumerate() {
+                let num_hash: BlockHashOrNumber = headers
+                    .get(idx + 1)
+                    .map(|h| h.parent_hash.into())
+                    .unwrap_or_else(|| header.number.into());
+
+                if !(FilteredParams::matches_address(header.logs_bloom, &address_filter) &&
+                    FilteredParams::matches_topics(header.logs_bloom, &topics_filter))
+                {
+                    continue
+                }
+
+                if let Some((block, receipts)) = self.block_and_receipts_by_number(num_hash).await?
+                {
+                    logs_utils::append_matching_block_logs(
+                        &mut logs,
+                        &filter_params,
+                        (block.number, block.hash).into(),
+                        block.body.into_iter().map(|tx| tx.hash()).zip(receipts),
+                        false,
+                    );
+                    if block.number == from_block && skip_logs > 0 {
+                        logs.retain(|log| {
+                            log.log_index.map(|idx| idx.to::<u64>()).unwrap_or_default() >=
+                                skip_logs
+                        });
+                    }
+
+                    if logs.len() > max_logs {
+                        let next = &logs[max_logs];
+                        let next = LogsPageCursor {
+                            block_number: U64::from(block.number),
+                            log_index: U64::from(
+                                next.log_index.map(|idx| idx.to::<u64>()).unwrap_or_default(),
+                            ),
+                        };
+                        logs.truncate(max_logs);
+                        return Ok(LogsPage { logs, next: Some(next) })
+                    }
+                }
+            }
+        }
+
+        // the page ended at the block limit, continue with the next block
+        let next = (page_to_block < to_block).then(|| LogsPageCursor {
+            block_number: U64::from(page_to_block + 1),
+            log_index: U64::ZERO,
+        });
+        Ok(LogsPage { logs, next })
+    }
 }
 
 /// All active filters
@@ -423,8 +588,28 @@ enum FilterKind {
 pub enum FilterError {
     #[error("filter not found")]
     FilterNotFound(FilterId),
-    #[error("Query exceeds max results {0}")]
-    QueryExceedsMaxResults(usize),
+    /// The query matched more logs than allowed in a single response.
+    #[error("query exceeds max results {max_logs}, retry with the range {from_block}-{to_block}")]
+    QueryExceedsMaxResults {
+        /// The maximum number of logs in a response.
+        max_logs: usize,
+        /// The first block of the range that can be queried instead.
+        from_block: u64,
+        /// The last block of the range that can be queried instead.
+        to_block: u64,
+    },
+    /// The range of the query spans more blocks than allowed.
+    #[error(
+        "query exceeds max block range {max_blocks}, retry with the range {from_block}-{to_block}"
+    )]
+    QueryExceedsMaxBlocks {
+        /// The maximum number of blocks a query may span.
+        max_blocks: u64,
+        /// The first block of the range that can be queried instead.
+        from_block: u64,
+        /// The last block of the range that can be queried instead.
+        to_block: u64,
+    },
     #[error(transparent)]
     EthAPIError(#[from] EthApiError),
     /// Error thrown when a spawned task failed to deliver a response.
@@ -444,13 +629,29 @@ impl From<FilterError> for jsonrpsee::types::error::ErrorObject<'static> {
                 rpc_error_with_code(jsonrpsee::types::error::INTERNAL_ERROR_CODE, err.to_string())
             }
             FilterError::EthAPIError(err) => err.into(),
-            err @ FilterError::QueryExceedsMaxResults(_) => {
-                rpc_error_with_code(jsonrpsee::types::error::INVALID_PARAMS_CODE, err.to_string())
+            FilterError::QueryExceedsMaxResults { from_block, to_block, .. } |
+            FilterError::QueryExceedsMaxBlocks { from_block, to_block, .. } => {
+                // the allowed range is included as structured data, so clients can retry with it
+                let range =
+                    AllowedRange { from_block: from_block.into(), to_block: to_block.into() };
+                jsonrpsee::types::error::ErrorObject::owned(
+                    jsonrpsee::types::error::INVALID_PARAMS_CODE,
+                    err.to_string(),
+                    Some(range),
+                )
             }
         }
     }
 }
 
+/// The block range a client may query instead of a range that exceeded a limit.
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AllowedRange {
+    from_block: U64,
+    to_block: U64,
+}
+
 impl From<reth_interfaces::Error> for FilterError {
     fn from(err: reth_interfaces::Error) -> Self {
         FilterError::EthAPIError(err.into())
@@ -488,6 +689,78 @@ impl Iterator for BlockRangeInclusiveIter {
 mod tests {
     use super::*;
     use rand::{thread_rng, Rng};
+    use reth_primitives::{Block, Header, TransactionSigned, H256};
+    use reth_provider::test_utils::MockEthProvider;
+    use reth_tasks::TokioTaskExecutor;
+    use reth_transaction_pool::test_utils::testing_pool;
+
+    /// Returns a filter over a chain of `blocks` blocks with one log each.
+    fn filter_with_limits(
+        blocks: u64,
+        max_logs_per_response: usize,
+        max_blocks_per_filter: u64,
+    ) -> EthFilter<MockEthProvider, impl TransactionPool> {
+        let provider = MockEthProvider::default();
+        for number in 0..blocks {
+            let hash = H256::from_low_u64_be(number + 1);
+            let header =
+                Header { number, parent_hash: H256::from_low_u64_be(number), ..Default::default() };
+            provider.add_block(
+                hash,
+                Block { header, body: vec![TransactionSigned::default()], ..Default::default() },
+            );
+            provider.add_receipts(
+                hash,
+                vec![Receipt { logs: vec![Default::default()], ..Default::default() }],
+            );
+        }
+        let cache = EthStateCache::spawn(provider.clone(), Default::default());
+        EthFilter::new(
+            provider,
+            testing_pool(),
+            cache,
+            max_logs_per_response,
+            max_blocks_per_filter,
+            Box::<TokioTaskExecutor>::default(),
+        )
+    }
+
+    #[tokio::test]
+    async fn get_logs_max_blocks() {
+        let filter = filter_with_limits(10, 0, 4);
+        let err = filter.inner.get_logs_in_block_range(&Filter::default(), 2, 9).await.unwrap_err();
+        assert!(matches!(
+            err,
+            FilterError::QueryExceedsMaxBlocks { max_blocks: 4, from_block: 2, to_block: 5 }
+        ));
+
+        let logs = filter.inner.get_logs_in_block_range(&Filter::default(), 2, 5).await.unwrap();
+        assert_eq!(logs.len(), 4);
+
+        // no limit
+        let filter = filter_with_limits(10, 0, 0);
+        let logs = filter.inner.get_logs_in_block_range(&Filter::default(), 0, 9).await.unwrap();
+        assert_eq!(logs.len(), 10);
+    }
+
+    #[tokio::test]
+    async fn get_logs_max_results() {
+        let filter = filter_with_limits(10, 3, 0);
+        let err = filter.inner.get_logs_in_block_range(&Filter::default(), 2, 9).await.unwrap_err();
+        assert!(matches!(
+            err,
+            FilterError::QueryExceedsMaxResults { max_logs: 3, from_block: 2, to_block: 4 }
+        ));
+
+        // the suggested range fits into a response
+        let logs = filter.inner.get_logs_in_block_range(&Filter::default(), 2, 4).await.unwrap();
+        assert_eq!(logs.len(), 3);
+
+        // all logs of a single block are always returned
+        let filter = filter_with_limits(10, 0, 0);
+        let logs = filter.inner.get_logs_in_block_range(&Filter::default(), 3, 3).await.unwrap();
+        assert_eq!(logs.len(), 1);
+    }
 
     #[test]
     fn test_block_range_iter() {
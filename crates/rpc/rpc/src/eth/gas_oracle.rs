@@ -19,10 +19,37 @@ pub const DEFAULT_MAX_PRICE: U256 = U256::from_limbs([500_000_000_000u64, 0, 0,
 /// The default minimum gas price, under which the sample will be ignored
 pub const DEFAULT_IGNORE_PRICE: U256 = U256::from_limbs([2u64, 0, 0, 0]);
 
+/// The strategy the [GasPriceOracle] uses to suggest a priority fee.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GasPriceOracleStrategy {
+    /// Suggest the configured percentile of the lowest tips paid in recent blocks.
+    #[default]
+    Percentile,
+    /// Always suggest the configured default price, useful for private and dev chains.
+    Fixed,
+}
+
+impl std::str::FromStr for GasPriceOracleStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "percentile" => Ok(GasPriceOracleStrategy::Percentile),
+            "fixed" => Ok(GasPriceOracleStrategy::Fixed),
+            _ => Err(format!("unknown gas price oracle strategy: {s}")),
+        }
+    }
+}
+
 /// Settings for the [GasPriceOracle]
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GasPriceOracleConfig {
+    /// The strategy to use for the estimate
+    #[serde(default)]
+    pub strategy: GasPriceOracleStrategy,
+
     /// The number of populated blocks to produce the gas price estimate
     pub blocks: u32,
 
@@ -38,6 +65,10 @@ pub struct GasPriceOracleConfig {
     /// The default gas price to use if there are no blocks to use
     pub default: Option<U256>,
 
+    /// The minimum gas price to suggest
+    #[serde(default)]
+    pub min_price: Option<U256>,
+
     /// The maximum gas price to use for the estimate
     pub max_price: Option<U256>,
 
@@ -48,11 +79,13 @@ pub struct GasPriceOracleConfig {
 impl Default for GasPriceOracleConfig {
     fn default() -> Self {
         GasPriceOracleConfig {
+            strategy: GasPriceOracleStrategy::Percentile,
             blocks: 20,
             percentile: 60,
             max_header_history: 1024,
             max_block_history: 1024,
             default: None,
+            min_price: None,
             max_price: Some(DEFAULT_MAX_PRICE),
             ignore_price: Some(DEFAULT_IGNORE_PRICE),
         }
@@ -68,15 +101,52 @@ impl GasPriceOracleConfig {
         percentile: Option<u32>,
     ) -> Self {
         Self {
+            strategy: GasPriceOracleStrategy::Percentile,
             blocks: blocks.unwrap_or(20),
             percentile: percentile.unwrap_or(60),
             max_header_history: 1024,
             max_block_history: 1024,
             default: None,
+            min_price: None,
             max_price: max_price.map(U256::from).or(Some(DEFAULT_MAX_PRICE)),
             ignore_price: ignore_price.map(U256::from).or(Some(DEFAULT_IGNORE_PRICE)),
         }
     }
+
+    /// Sets the strategy to use for the estimate.
+    pub fn with_strategy(mut self, strategy: GasPriceOracleStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Sets the gas price to use if there are no blocks to use, or the price to always suggest
+    /// with [GasPriceOracleStrategy::Fixed].
+    pub fn with_default(mut self, default: Option<u64>) -> Self {
+        self.default = default.map(U256::from);
+        self
+    }
+
+    /// Sets the minimum gas price to suggest.
+    pub fn with_min_price(mut self, min_price: Option<u64>) -> Self {
+        self.min_price = min_price.map(U256::from);
+        self
+    }
+
+    /// Returns the price that is suggested if there is no data to base the estimate on.
+    fn default_price(&self) -> U256 {
+        self.default.unwrap_or(U256::from(GWEI_TO_WEI))
+    }
+
+    /// Constrains the price to the configured minimum and maximum.
+    fn clamp(&self, mut price: U256) -> U256 {
+        if let Some(min_price) = self.min_price {
+            price = price.max(min_price);
+        }
+        if let Some(max_price) = self.max_price {
+            price = price.min(max_price);
+        }
+        price
+    }
 }
 
 /// Calculates a gas price depending on recent blocks.
@@ -108,7 +178,9 @@ where
             oracle_config.percentile = 100;
         }
 
-        Self { provider, oracle_config, last_price: Default::default(), cache }
+        let last_price =
+            GasPriceOracleResult { block_hash: H256::zero(), price: oracle_config.default_price() };
+        Self { provider, oracle_config, last_price: Mutex::new(last_price), cache }
     }
 
     /// Returns the configuration of the gas price oracle.
@@ -116,8 +188,12 @@ where
         &self.oracle_config
     }
 
-    /// Suggests a gas price estimate based on recent blocks, using the configured percentile.
+    /// Suggests a gas price estimate based on recent blocks, using the configured strategy.
     pub async fn suggest_tip_cap(&self) -> EthResult<U256> {
+        if self.oracle_config.strategy == GasPriceOracleStrategy::Fixed {
+            return Ok(self.oracle_config.clamp(self.oracle_config.default_price()))
+        }
+
         let header = self
             .provider
             .sealed_header_by_number_or_tag(BlockNumberOrTag::Latest)?
@@ -176,12 +252,8 @@ where
                 .expect("gas price index is a percent of nonzero array length, so a value always exists; qed");
         }
 
-        // constrain to the max price
-        if let Some(max_price) = self.oracle_config.max_price {
-            if price > max_price {
-                price = max_price;
-            }
-        }
+        // constrain to the min and max price
+        price = self.oracle_config.clamp(price);
 
         *last_price = GasPriceOracleResult { block_hash: header.hash, price };
 
@@ -267,6 +339,7 @@ impl Default for GasPriceOracleResult {
 #[cfg(test)]
 mod tests {
     use reth_primitives::constants::GWEI_TO_WEI;
+    use reth_provider::test_utils::NoopProvider;
 
     use super::*;
 
@@ -280,4 +353,24 @@ mod tests {
     fn ignore_price_sanity() {
         assert_eq!(DEFAULT_IGNORE_PRICE, U256::from(2u64));
     }
+
+    #[test]
+    fn clamp_to_min_and_max_price() {
+        let config =
+            GasPriceOracleConfig::new(None, None, Some(100), None).with_min_price(Some(10));
+        assert_eq!(config.clamp(U256::from(1)), U256::from(10));
+        assert_eq!(config.clamp(U256::from(50)), U256::from(50));
+        assert_eq!(config.clamp(U256::from(1000)), U256::from(100));
+    }
+
+    #[tokio::test]
+    async fn fixed_strategy() {
+        let provider = NoopProvider::default();
+        let cache = EthStateCache::spawn(provider.clone(), Default::default());
+        let config = GasPriceOracleConfig::default()
+            .with_strategy(GasPriceOracleStrategy::Fixed)
+            .with_default(Some(7 * GWEI_TO_WEI));
+        let oracle = GasPriceOracle::new(provider, config, cache);
+        assert_eq!(oracle.suggest_tip_cap().await.unwrap(), U256::from(7 * GWEI_TO_WEI));
+    }
 }
@@ -0,0 +1,234 @@
+//! An encrypted keystore for node-side signing.
+
+use crate::eth::{
+    error::SignError,
+    signer::{DevSigner, EthSigner},
+};
+use ethers_core::types::transaction::eip712::TypedData;
+use parking_lot::RwLock;
+use reth_primitives::{public_key_to_address, Address, Signature, TransactionSigned};
+use reth_rpc_types::TypedTransactionRequest;
+use secp256k1::{SecretKey, SECP256K1};
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+/// The duration an account stays unlocked if no duration is given.
+pub const DEFAULT_UNLOCK_DURATION: Duration = Duration::from_secs(300);
+
+/// Errors returned by the [Keystore].
+#[derive(Debug, thiserror::Error)]
+pub enum KeystoreError {
+    /// Error while accessing the keystore directory.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// Error while encrypting or decrypting a key file, e.g. due to a wrong password.
+    #[error(transparent)]
+    KeyFile(#[from] eth_keystore::KeystoreError),
+    /// The account is not in the keystore.
+    #[error("unknown account {0:?}")]
+    UnknownAccount(Address),
+    /// The account is already in the keystore.
+    #[error("account {0:?} already exists")]
+    AccountExists(Address),
+    /// The key is not a valid secp256k1 secret key.
+    #[error("invalid secret key")]
+    InvalidKey,
+}
+
+/// A directory of password encrypted keys in the [Web3 Secret Storage](https://ethereum.org/en/developers/docs/data-structures-and-encoding/web3-secret-storage/)
+/// format.
+///
+/// Every key is stored in a file named after the address of the account. Accounts must be
+/// unlocked with their password before they can be used for signing, the decrypted keys are only
+/// held in memory.
+#[derive(Debug)]
+pub struct Keystore {
+    dir: PathBuf,
+    unlocked: RwLock<HashMap<Address, UnlockedAccount>>,
+}
+
+#[derive(Debug)]
+struct UnlockedAccount {
+    key: SecretKey,
+    expires_at: Option<Instant>,
+}
+
+impl Keystore {
+    /// Opens the keystore in the given directory, the directory is created if it doesn't exist.
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self, KeystoreError> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir, unlocked: Default::default() })
+    }
+
+    /// Returns the keystore directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the addresses of all accounts in the keystore.
+    pub fn accounts(&self) -> Result<Vec<Address>, KeystoreError> {
+        let mut accounts = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            if !entry.file_type()?.is_file() {
+                continue
+            }
+            if let Some(address) =
+                entry.file_name().to_str().and_then(|name| Address::from_str(name).ok())
+            {
+                accounts.push(address);
+            }
+        }
+        accounts.sort_unstable();
+        Ok(accounts)
+    }
+
+    /// Returns `true` if the account is in the keystore.
+    pub fn contains(&self, address: Address) -> bool {
+        self.key_path(address).is_file()
+    }
+
+    /// Creates a new account with a random key and returns its address.
+    pub fn new_account(&self, password: &str) -> Result<Address, KeystoreError> {
+        self.import(&SecretKey::new(&mut rand::thread_rng()), password)
+    }
+
+    /// Encrypts the key with the password and adds it to the keystore.
+    pub fn import(&self, key: &SecretKey, password: &str) -> Result<Address, KeystoreError> {
+        let address = public_key_to_address(key.public_key(SECP256K1));
+        if self.contains(address) {
+            return Err(KeystoreError::AccountExists(address))
+        }
+        eth_keystore::encrypt_key(
+            &self.dir,
+            &mut rand::thread_rng(),
+            key.secret_bytes(),
+            password,
+            Some(&format!("{address:x}")),
+        )?;
+        Ok(address)
+    }
+
+    /// Decrypts the key of the account with the password.
+    pub fn export(&self, address: Address, password: &str) -> Result<SecretKey, KeystoreError> {
+        if !self.contains(address) {
+            return Err(KeystoreError::UnknownAccount(address))
+        }
+        let key = eth_keystore::decrypt_key(self.key_path(address), password)?;
+        SecretKey::from_slice(&key).map_err(|_| KeystoreError::InvalidKey)
+    }
+
+    /// Decrypts the key of the account and keeps it in memory for the given duration, or until
+    /// the account is locked if no duration is given.
+    pub fn unlock(
+        &self,
+        address: Address,
+        password: &str,
+        duration: Option<Duration>,
+    ) -> Result<(), KeystoreError> {
+        let key = self.export(address, password)?;
+        let expires_at = duration.map(|duration| Instant::now() + duration);
+        self.unlocked.write().insert(address, UnlockedAccount { key, expires_at });
+        Ok(())
+    }
+
+    /// Removes the decrypted key of the account from memory, returns `false` if the account was
+    /// not unlocked.
+    pub fn lock(&self, address: Address) -> bool {
+        self.unlocked.write().remove(&address).is_some()
+    }
+
+    /// Returns `true` if the account is unlocked.
+    pub fn is_unlocked(&self, address: Address) -> bool {
+        self.unlocked_key(address).is_some()
+    }
+
+    /// Returns the decrypted key of the account if it is unlocked, expired accounts are locked.
+    fn unlocked_key(&self, address: Address) -> Option<SecretKey> {
+        let mut unlocked = self.unlocked.write();
+        let account = unlocked.get(&address)?;
+        if account.expires_at.map_or(false, |expires_at| expires_at <= Instant::now()) {
+            unlocked.remove(&address);
+            return None
+        }
+        Some(account.key)
+    }
+
+    /// Returns a signer for the account if it is unlocked.
+    fn signer(&self, address: Address) -> Result<DevSigner, SignError> {
+        self.unlocked_key(address)
+            .map(|key| DevSigner::from_keys([key]))
+            .ok_or(SignError::NoAccount)
+    }
+
+    fn key_path(&self, address: Address) -> PathBuf {
+        self.dir.join(format!("{address:x}"))
+    }
+}
+
+#[async_trait::async_trait]
+impl EthSigner for Keystore {
+    fn accounts(&self) -> Vec<Address> {
+        Keystore::accounts(self).unwrap_or_default()
+    }
+
+    fn is_signer_for(&self, addr: &Address) -> bool {
+        self.is_unlocked(*addr)
+    }
+
+    async fn sign(&self, address: Address, message: &[u8]) -> Result<Signature, SignError> {
+        self.signer(address)?.sign(address, message).await
+    }
+
+    fn sign_transaction(
+        &self,
+        request: TypedTransactionRequest,
+        address: &Address,
+    ) -> Result<TransactionSigned, SignError> {
+        self.signer(*address)?.sign_transaction(request, address)
+    }
+
+    fn sign_typed_data(
+        &self,
+        address: Address,
+        payload: &TypedData,
+    ) -> Result<Signature, SignError> {
+        self.signer(address)?.sign_typed_data(address, payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn import_unlock_and_sign() {
+        let dir = tempfile::tempdir().unwrap();
+        let keystore = Keystore::open(dir.path()).unwrap();
+        let key =
+            SecretKey::from_str("4646464646464646464646464646464646464646464646464646464646464646")
+                .unwrap();
+
+        let address = keystore.import(&key, "password").unwrap();
+        assert_eq!(keystore.accounts().unwrap(), vec![address]);
+        assert!(matches!(keystore.import(&key, "password"), Err(KeystoreError::AccountExists(_))));
+
+        assert!(keystore.export(address, "wrong").is_err());
+        assert_eq!(keystore.export(address, "password").unwrap(), key);
+
+        assert!(!keystore.is_signer_for(&address));
+        keystore.unlock(address, "password", None).unwrap();
+        assert!(keystore.is_signer_for(&address));
+        assert!(keystore.lock(address));
+        assert!(!keystore.is_signer_for(&address));
+
+        keystore.unlock(address, "password", Some(Duration::ZERO)).unwrap();
+        assert!(!keystore.is_signer_for(&address));
+    }
+}
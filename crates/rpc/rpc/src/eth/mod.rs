@@ -1,17 +1,20 @@
 //! `eth` namespace handler implementation.
 
 mod api;
+pub mod archive;
 pub mod cache;
 pub mod error;
 mod filter;
 pub mod gas_oracle;
 mod id_provider;
+pub mod keystore;
 mod logs_utils;
 mod pubsub;
 pub mod revm_utils;
 mod signer;
 pub(crate) mod utils;
 
+pub(crate) use api::build_transaction_receipt_with_block_receipts;
 pub use api::{EthApi, EthApiSpec, EthTransactions, TransactionSource, RPC_DEFAULT_GAS_CAP};
 pub use filter::EthFilter;
 pub use id_provider::EthSubscriptionIdProvider;
@@ -3,8 +3,8 @@ use crate::{eth::logs_utils, result::invalid_params_rpc_err};
 use futures::StreamExt;
 use jsonrpsee::{server::SubscriptionMessage, PendingSubscriptionSink, SubscriptionSink};
 use reth_network_api::NetworkInfo;
-use reth_primitives::{IntoRecoveredTransaction, TxHash};
-use reth_provider::{BlockReader, CanonStateSubscriptions, EvmEnvProvider};
+use reth_primitives::{stage::StageId, IntoRecoveredTransaction, TxHash};
+use reth_provider::{BlockReader, CanonStateSubscriptions, EvmEnvProvider, StageCheckpointReader};
 use reth_rpc_api::EthPubSubApiServer;
 use reth_rpc_types::{
     pubsub::{
@@ -16,12 +16,15 @@ use reth_rpc_types::{
 use reth_tasks::{TaskSpawner, TokioTaskExecutor};
 use reth_transaction_pool::{NewTransactionEvent, TransactionPool};
 use serde::Serialize;
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 use tokio_stream::{
     wrappers::{BroadcastStream, ReceiverStream},
     Stream,
 };
 
+/// The interval in which the sync progress is checked for the `syncing` subscription.
+const SYNC_STATUS_INTERVAL: Duration = Duration::from_secs(2);
+
 /// `Eth` pubsub RPC implementation.
 ///
 /// This handles `eth_subscribe` RPC calls.
@@ -66,7 +69,7 @@ impl<Provider, Pool, Events, Network> EthPubSub<Provider, Pool, Events, Network>
 impl<Provider, Pool, Events, Network> EthPubSubApiServer
     for EthPubSub<Provider, Pool, Events, Network>
 where
-    Provider: BlockReader + EvmEnvProvider + Clone + 'static,
+    Provider: BlockReader + EvmEnvProvider + StageCheckpointReader + Clone + 'static,
     Pool: TransactionPool + 'static,
     Events: CanonStateSubscriptions + Clone + 'static,
     Network: NetworkInfo + Clone + 'static,
@@ -96,7 +99,7 @@ async fn handle_accepted<Provider, Pool, Events, Network>(
     params: Option<Params>,
 ) -> Result<(), jsonrpsee::core::Error>
 where
-    Provider: BlockReader + EvmEnvProvider + Clone + 'static,
+    Provider: BlockReader + EvmEnvProvider + StageCheckpointReader + Clone + 'static,
     Pool: TransactionPool + 'static,
     Events: CanonStateSubscriptions + Clone + 'static,
     Network: NetworkInfo + Clone + 'static,
@@ -156,30 +159,29 @@ where
             // get new block subscription
             let mut canon_state =
                 BroadcastStream::new(pubsub.chain_events.subscribe_to_canonical_state());
-            // get current sync status
-            let mut initial_sync_status = pubsub.network.is_syncing();
-            let current_sub_res = pubsub.sync_status(initial_sync_status).await;
-
-            // send the current status immediately
-            let msg = SubscriptionMessage::from_json(&current_sub_res)?;
-            if accepted_sink.send(msg).await.is_err() {
-                return Ok(())
-            }
-
-            while (canon_state.next().await).is_some() {
-                let current_syncing = pubsub.network.is_syncing();
-                // Only send a new response if the sync status has changed
-                if current_syncing != initial_sync_status {
-                    // Update the sync status on each new block
-                    initial_sync_status = current_syncing;
+            // the pipeline does not emit canonical state notifications, so the progress is also
+            // checked periodically
+            let mut interval = tokio::time::interval(SYNC_STATUS_INTERVAL);
+            let mut tracker = SyncStatusTracker::default();
 
-                    // send a new message now that the status changed
-                    let sync_status = pubsub.sync_status(current_syncing).await;
-                    let msg = SubscriptionMessage::from_json(&sync_status)?;
-                    if accepted_sink.send(msg).await.is_err() {
-                        break
+            loop {
+                // the first tick completes immediately, so the current status is sent right away
+                tokio::select! {
+                    _ = accepted_sink.closed() => break,
+                    _ = interval.tick() => {}
+                    new_block = canon_state.next() => {
+                        if new_block.is_none() {
+                            break
+                        }
                     }
                 }
+
+                let Some(status) = pubsub.sync_status(&mut tracker) else { continue };
+                let msg =
+                    SubscriptionMessage::from_json(&EthSubscriptionResult::SyncState(status))?;
+                if accepted_sink.send(msg).await.is_err() {
+                    break
+                }
             }
 
             Ok(())
@@ -244,22 +246,69 @@ struct EthPubSubInner<Provider, Pool, Events, Network> {
 
 impl<Provider, Pool, Events, Network> EthPubSubInner<Provider, Pool, Events, Network>
 where
-    Provider: BlockReader + 'static,
+    Provider: BlockReader + StageCheckpointReader + 'static,
+    Network: NetworkInfo + 'static,
 {
-    /// Returns the current sync status for the `syncing` subscription
-    async fn sync_status(&self, is_syncing: bool) -> EthSubscriptionResult {
-        if is_syncing {
-            let current_block =
-                self.provider.chain_info().map(|info| info.best_number).unwrap_or_default();
-            EthSubscriptionResult::SyncState(PubSubSyncStatus::Detailed(SyncStatusMetadata {
-                syncing: true,
-                starting_block: 0,
-                current_block,
-                highest_block: Some(current_block),
-            }))
-        } else {
-            EthSubscriptionResult::SyncState(PubSubSyncStatus::Simple(false))
+    /// Returns the sync status for the `syncing` subscription if it changed since the last call.
+    ///
+    /// While the pipeline is running, the current block is the block that was fully synced by the
+    /// pipeline and the highest block is the highest downloaded header.
+    fn sync_status(&self, tracker: &mut SyncStatusTracker) -> Option<PubSubSyncStatus> {
+        if !self.network.is_syncing() {
+            return tracker.update(None)
+        }
+
+        let best_number =
+            self.provider.chain_info().map(|info| info.best_number).unwrap_or_default();
+        let finished = self
+            .provider
+            .get_stage_checkpoint(StageId::Finish)
+            .ok()
+            .flatten()
+            .map(|checkpoint| checkpoint.block_number)
+            .unwrap_or_default();
+        let current_block = best_number.max(finished);
+        let highest_block =
+            self.provider.last_block_number().unwrap_or_default().max(current_block);
+        tracker.update(Some((current_block, highest_block)))
+    }
+}
+
+/// Tracks the sync status that was last sent to a `syncing` subscription.
+///
+/// A sync is reported as a transition to syncing with the block it started at, followed by
+/// progress snapshots whenever the current or highest block changed, and a transition to `false`
+/// once the sync finished.
+#[derive(Debug, Default)]
+struct SyncStatusTracker {
+    /// The last sent status, `None` if nothing was sent yet.
+    last: Option<PubSubSyncStatus>,
+}
+
+impl SyncStatusTracker {
+    /// Updates the tracker with the current and highest block if syncing, and returns the status
+    /// to send if it changed.
+    fn update(&mut self, progress: Option<(u64, u64)>) -> Option<PubSubSyncStatus> {
+        let status = match progress {
+            Some((current_block, highest_block)) => {
+                let starting_block = match &self.last {
+                    Some(PubSubSyncStatus::Detailed(status)) => status.starting_block,
+                    _ => current_block,
+                };
+                PubSubSyncStatus::Detailed(SyncStatusMetadata {
+                    syncing: true,
+                    starting_block,
+                    current_block,
+                    highest_block: Some(highest_block),
+                })
+            }
+            None => PubSubSyncStatus::Simple(false),
+        };
+        if self.last.as_ref() == Some(&status) {
+            return None
         }
+        self.last = Some(status.clone());
+        Some(status)
     }
 }
 
@@ -316,3 +365,36 @@ where
             })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sync_status_transitions() {
+        let mut tracker = SyncStatusTracker::default();
+        // the initial status is always sent
+        assert_eq!(tracker.update(None), Some(PubSubSyncStatus::Simple(false)));
+        assert_eq!(tracker.update(None), None);
+
+        let syncing = |starting_block, current_block, highest_block| {
+            Some(PubSubSyncStatus::Detailed(SyncStatusMetadata {
+                syncing: true,
+                starting_block,
+                current_block,
+                highest_block: Some(highest_block),
+            }))
+        };
+        // started
+        assert_eq!(tracker.update(Some((10, 10))), syncing(10, 10, 10));
+        // progress
+        assert_eq!(tracker.update(Some((10, 100))), syncing(10, 10, 100));
+        assert_eq!(tracker.update(Some((10, 100))), None);
+        assert_eq!(tracker.update(Some((50, 100))), syncing(10, 50, 100));
+        // finished
+        assert_eq!(tracker.update(None), Some(PubSubSyncStatus::Simple(false)));
+
+        // a new sync starts at the current block
+        assert_eq!(tracker.update(Some((100, 101))), syncing(100, 100, 101));
+    }
+}
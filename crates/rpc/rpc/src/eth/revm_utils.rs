@@ -2,9 +2,13 @@
 
 use crate::eth::error::{EthApiError, EthResult, RpcInvalidTransactionError};
 use reth_primitives::{
-    AccessList, Address, TransactionSigned, TransactionSignedEcRecovered, TxHash, H256, U256,
+    AccessList, Address, ChainSpec, Header, TransactionSigned, TransactionSignedEcRecovered,
+    TxHash, H256, U256,
+};
+use reth_revm::{
+    env::{fill_tx_env, fill_tx_env_with_recovered},
+    system_calls::pre_block_system_calls,
 };
-use reth_revm::env::{fill_tx_env, fill_tx_env_with_recovered};
 use reth_rpc_types::{
     state::{AccountOverride, StateOverride},
     BlockOverrides, CallRequest,
@@ -195,6 +199,24 @@ where
     Ok(())
 }
 
+/// Applies the pre-block system calls of the block to the _runtime_ db ([CacheDB]).
+///
+/// This must be done before the transactions of the block are replayed, so they observe the same
+/// state they were executed on.
+pub(crate) fn apply_pre_block_system_calls<DB>(
+    db: &mut CacheDB<DB>,
+    chain_spec: &ChainSpec,
+    header: &Header,
+) -> EthResult<()>
+where
+    DB: DatabaseRef,
+    EthApiError: From<<DB as DatabaseRef>::Error>,
+{
+    let changes = pre_block_system_calls(db, chain_spec, header)?;
+    db.commit(changes);
+    Ok(())
+}
+
 /// Prepares the [Env] for execution.
 ///
 /// Does not commit any changes to the underlying database.
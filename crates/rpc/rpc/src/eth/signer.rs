@@ -5,14 +5,41 @@ use ethers_core::{
     types::transaction::eip712::{Eip712, TypedData},
     utils::hash_message,
 };
-use reth_primitives::{sign_message, Address, Signature, TransactionSigned, H256};
+use reth_primitives::{
+    public_key_to_address, sign_message, Address, Signature, TransactionSigned, H256,
+};
 use reth_rpc_types::TypedTransactionRequest;
 
-use secp256k1::SecretKey;
-use std::collections::HashMap;
+use secp256k1::{SecretKey, SECP256K1};
+use std::{collections::HashMap, str::FromStr};
 
 type Result<T> = std::result::Result<T, SignError>;
 
+/// The secret keys of the prefunded accounts of the dev chain, derived from the mnemonic "test test
+/// test test test test test test test test test junk".
+const DEV_SECRET_KEYS: [&str; 20] = [
+    "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+    "59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d",
+    "5de4111afa1a4b94908f83103eb1f1706367c2e68ca870fc3fb9a804cdab365a",
+    "7c852118294e51e653712a81e05800f419141751be58f605c371e15141b007a6",
+    "47e179ec197488593b187f80a00eb0da91f1b9d0b13f8733639f19c30a34926a",
+    "8b3a350cf5c34c9194ca85829a2df0ec3153be0318b5e2d3348e872092edffba",
+    "92db14e403b83dfe3df233f83dfa3a0d7096f21ca9b0d6d6b8d88b2b4ec1564e",
+    "4bbbf85ce3377467afe5d46f804f221813b2bb87f24d81f60f1fcdbf7cbf4356",
+    "dbda1821b80551c9d65939329250298aa3472ba22feea921c0cf5d620ea67b97",
+    "2a871d0798f97d79848a013d4936a73bf4cc922c825d33c1cf7073dff6d409c6",
+    "f214f2b2cd398c806f84e317254e0f0b801d0643303237d97a22a48e01628897",
+    "701b615bbdfb9de65240bc28bd21bbc0d996645a3dd57e7b12bc2bdf6f192c82",
+    "a267530f49f8280200edf313ee7af6b827f2a8bce2897751d06a843f644967b1",
+    "47c99abed3324a2707c28affff1267e45918ec8c3f20b8aa892e8b065d2942dd",
+    "c526ee95bf44d8fc405a158bb884d9d1238d99f0612e9f33d006bb0789009aaa",
+    "8166f546bab6da521a8369cab06c5d2b9e46670292d85c875ee9ec20e84ffb61",
+    "ea6c44ac03bff858b476bba40716402b03e41b8e97e276d1baec7c37d42484a0",
+    "689af8efa8c651a91ad287602527f3af2fe9f6501a7ac4b061667b5a93e037fd",
+    "de9be858da4a475276426320d5e9262ecfc3ba460bfac56360bfa6c4c28b4ee0",
+    "df57089febbacf7ba0bc227dafbffa9fc08a93fdc68e1e42411a14efcf23656e",
+];
+
 /// An Ethereum Signer used via RPC.
 #[async_trait::async_trait]
 pub(crate) trait EthSigner: Send + Sync {
@@ -45,6 +72,26 @@ pub(crate) struct DevSigner {
 }
 
 impl DevSigner {
+    /// Creates a signer for the given keys.
+    pub(crate) fn from_keys(keys: impl IntoIterator<Item = SecretKey>) -> Self {
+        let mut addresses = Vec::new();
+        let mut accounts = HashMap::new();
+        for key in keys {
+            let address = public_key_to_address(key.public_key(SECP256K1));
+            if accounts.insert(address, key).is_none() {
+                addresses.push(address);
+            }
+        }
+        Self { addresses, accounts }
+    }
+
+    /// Creates a signer for the prefunded accounts of the dev chain.
+    pub(crate) fn dev_accounts() -> Self {
+        Self::from_keys(
+            DEV_SECRET_KEYS.iter().map(|key| SecretKey::from_str(key).expect("valid key; qed")),
+        )
+    }
+
     fn get_key(&self, account: Address) -> Result<&SecretKey> {
         self.accounts.get(&account).ok_or(SignError::NoAccount)
     }
@@ -92,8 +139,7 @@ impl EthSigner for DevSigner {
 #[cfg(test)]
 mod test {
     use super::*;
-    use reth_primitives::U256;
-    use std::str::FromStr;
+    use reth_primitives::{DEV, U256};
     fn build_signer() -> DevSigner {
         let addresses = vec![];
         let secret =
@@ -190,6 +236,16 @@ mod test {
         assert_eq!(sig, expected)
     }
 
+    #[test]
+    fn test_dev_accounts() {
+        let signer = DevSigner::dev_accounts();
+        let mut accounts = signer.accounts();
+        accounts.sort();
+        let mut expected: Vec<_> = DEV.genesis.alloc.keys().copied().collect();
+        expected.sort();
+        assert_eq!(accounts, expected);
+    }
+
     #[tokio::test]
     async fn test_signer() {
         let message = b"Test message";
@@ -31,13 +31,17 @@
 //! disk-io, hence these calls are spawned as futures to a blocking task manually.
 
 mod admin;
+pub mod block_trace_cache;
 mod debug;
 mod engine;
 pub mod eth;
 mod layers;
 mod net;
 mod otterscan;
+mod personal;
+mod provider;
 mod reth;
+mod reth_pubsub;
 mod rpc;
 mod trace;
 pub mod tracing_call;
@@ -45,13 +49,17 @@ mod txpool;
 mod web3;
 
 pub use admin::AdminApi;
+pub use block_trace_cache::{BlockTraceCache, BlockTraceCacheConfig};
 pub use debug::DebugApi;
 pub use engine::{EngineApi, EngineEthApi};
 pub use eth::{EthApi, EthApiSpec, EthFilter, EthPubSub, EthSubscriptionIdProvider};
 pub use layers::{AuthLayer, AuthValidator, Claims, JwtAuthValidator, JwtError, JwtSecret};
 pub use net::NetApi;
 pub use otterscan::OtterscanApi;
+pub use personal::PersonalApi;
+pub use provider::{ProviderApi, RemoteCursor, RemoteDatabase, RemoteTx, MAX_CURSOR_ENTRIES};
 pub use reth::RethApi;
+pub use reth_pubsub::{RethPubSub, MAX_BLOCKS_CATCH_UP};
 pub use rpc::RPCApi;
 pub use trace::TraceApi;
 pub use tracing_call::{TracingCallGuard, TracingCallPool};
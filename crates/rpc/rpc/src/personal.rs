@@ -0,0 +1,199 @@
+use crate::{
+    eth::{
+        error::{EthApiError, EthResult, SignError},
+        keystore::{Keystore, KeystoreError, DEFAULT_UNLOCK_DURATION},
+        signer::{DevSigner, EthSigner},
+    },
+    result::{internal_rpc_err, invalid_params_rpc_err},
+};
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use reth_primitives::{Address, Bytes};
+use reth_rpc_api::{EthSigningApiServer, PersonalApiServer};
+use reth_rpc_types::{TransactionRequest, TypedTransactionRequest};
+use reth_tasks::TaskSpawner;
+use secp256k1::SecretKey;
+use std::{str::FromStr, sync::Arc, time::Duration};
+use tokio::sync::oneshot;
+
+/// `personal` API implementation.
+///
+/// This type provides the functionality for handling `personal` requests and the `eth` signing
+/// requests that use the accounts of the node's [Keystore].
+#[derive(Clone)]
+pub struct PersonalApi {
+    inner: Arc<PersonalApiInner>,
+}
+
+struct PersonalApiInner {
+    keystore: Keystore,
+    chain_id: u64,
+    task_spawner: Box<dyn TaskSpawner>,
+}
+
+impl PersonalApi {
+    /// Creates a new instance of `PersonalApi` that signs transactions for the given chain.
+    pub fn new(keystore: Keystore, chain_id: u64, task_spawner: Box<dyn TaskSpawner>) -> Self {
+        Self { inner: Arc::new(PersonalApiInner { keystore, chain_id, task_spawner }) }
+    }
+
+    /// Returns the keystore.
+    pub fn keystore(&self) -> &Keystore {
+        &self.inner.keystore
+    }
+
+    /// Runs the keystore operation on a new blocking task, since key derivation is CPU
+    /// intensive.
+    async fn on_blocking_task<F, R>(&self, f: F) -> RpcResult<R>
+    where
+        F: FnOnce(&Keystore) -> Result<R, KeystoreError> + Send + 'static,
+        R: Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        let this = self.clone();
+        self.inner.task_spawner.spawn_blocking(Box::pin(async move {
+            let _ = tx.send(f(this.keystore()));
+        }));
+        rx.await
+            .map_err(|_| internal_rpc_err("keystore task failed"))?
+            .map_err(|err| internal_rpc_err(err.to_string()))
+    }
+
+    /// Converts the request into a typed transaction request for the configured chain.
+    ///
+    /// Returns the `from` address of the request.
+    fn typed_request(
+        &self,
+        request: TransactionRequest,
+    ) -> EthResult<(Address, TypedTransactionRequest)> {
+        let from = request.from.ok_or(SignError::NoAccount)?;
+        if request.nonce.is_none() {
+            return Err(EthApiError::InvalidParams("nonce not specified".to_string()))
+        }
+        if request.gas.is_none() {
+            return Err(EthApiError::InvalidParams("gas not specified".to_string()))
+        }
+
+        let chain_id = self.inner.chain_id;
+        let request = match request.into_typed_request() {
+            Some(TypedTransactionRequest::Legacy(mut m)) => {
+                m.chain_id = Some(chain_id);
+                TypedTransactionRequest::Legacy(m)
+            }
+            Some(TypedTransactionRequest::EIP2930(mut m)) => {
+                m.chain_id = chain_id;
+                TypedTransactionRequest::EIP2930(m)
+            }
+            Some(TypedTransactionRequest::EIP1559(mut m)) => {
+                m.chain_id = chain_id;
+                TypedTransactionRequest::EIP1559(m)
+            }
+            None => return Err(EthApiError::ConflictingFeeFieldsInRequest),
+        };
+        Ok((from, request))
+    }
+
+    /// Signs the transaction request with the signer and returns the raw signed transaction.
+    fn sign_request(
+        &self,
+        signer: &dyn EthSigner,
+        request: TransactionRequest,
+    ) -> EthResult<Bytes> {
+        let (from, request) = self.typed_request(request)?;
+        let transaction = signer.sign_transaction(request, &from)?;
+        Ok(transaction.envelope_encoded().into())
+    }
+
+    /// Decrypts the key of the account for a single request.
+    async fn decrypt(&self, address: Address, password: String) -> RpcResult<DevSigner> {
+        let key =
+            self.on_blocking_task(move |keystore| keystore.export(address, &password)).await?;
+        Ok(DevSigner::from_keys([key]))
+    }
+}
+
+#[async_trait]
+impl PersonalApiServer for PersonalApi {
+    /// Handler for `personal_listAccounts`
+    async fn list_accounts(&self) -> RpcResult<Vec<Address>> {
+        self.on_blocking_task(|keystore| keystore.accounts()).await
+    }
+
+    /// Handler for `personal_newAccount`
+    async fn new_account(&self, password: String) -> RpcResult<Address> {
+        self.on_blocking_task(move |keystore| keystore.new_account(&password)).await
+    }
+
+    /// Handler for `personal_importRawKey`
+    async fn import_raw_key(&self, key: String, password: String) -> RpcResult<Address> {
+        let key = SecretKey::from_str(key.trim_start_matches("0x"))
+            .map_err(|_| invalid_params_rpc_err("invalid secret key"))?;
+        self.on_blocking_task(move |keystore| keystore.import(&key, &password)).await
+    }
+
+    /// Handler for `personal_unlockAccount`
+    async fn unlock_account(
+        &self,
+        address: Address,
+        password: String,
+        duration: Option<u64>,
+    ) -> RpcResult<bool> {
+        let duration = match duration {
+            None => Some(DEFAULT_UNLOCK_DURATION),
+            Some(0) => None,
+            Some(secs) => Some(Duration::from_secs(secs)),
+        };
+        self.on_blocking_task(move |keystore| keystore.unlock(address, &password, duration))
+            .await?;
+        Ok(true)
+    }
+
+    /// Handler for `personal_lockAccount`
+    async fn lock_account(&self, address: Address) -> RpcResult<bool> {
+        Ok(self.keystore().lock(address))
+    }
+
+    /// Handler for `personal_sign`
+    async fn sign(&self, message: Bytes, address: Address, password: String) -> RpcResult<Bytes> {
+        let signer = self.decrypt(address, password).await?;
+        let signature = signer.sign(address, &message).await.map_err(EthApiError::from)?;
+        Ok(signature.to_bytes().to_vec().into())
+    }
+
+    /// Handler for `personal_signTransaction`
+    async fn sign_transaction(
+        &self,
+        request: TransactionRequest,
+        password: String,
+    ) -> RpcResult<Bytes> {
+        let from = request.from.ok_or(EthApiError::from(SignError::NoAccount))?;
+        let signer = self.decrypt(from, password).await?;
+        Ok(self.sign_request(&signer, request)?)
+    }
+}
+
+#[async_trait]
+impl EthSigningApiServer for PersonalApi {
+    /// Handler for `eth_accounts`
+    async fn accounts(&self) -> RpcResult<Vec<Address>> {
+        self.on_blocking_task(|keystore| keystore.accounts()).await
+    }
+
+    /// Handler for `eth_sign`
+    async fn sign(&self, address: Address, message: Bytes) -> RpcResult<Bytes> {
+        let signature =
+            EthSigner::sign(self.keystore(), address, &message).await.map_err(EthApiError::from)?;
+        Ok(signature.to_bytes().to_vec().into())
+    }
+
+    /// Handler for `eth_signTransaction`
+    async fn sign_transaction(&self, request: TransactionRequest) -> RpcResult<Bytes> {
+        Ok(self.sign_request(self.keystore(), request)?)
+    }
+}
+
+impl std::fmt::Debug for PersonalApi {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PersonalApi").field("keystore", self.keystore()).finish_non_exhaustive()
+    }
+}
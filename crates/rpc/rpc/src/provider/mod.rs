@@ -0,0 +1,502 @@
+//! `provider` namespace, which serves the raw tables of the database to remote replicas.
+use crate::result::{internal_rpc_err, invalid_params_rpc_err};
+use async_trait::async_trait;
+use jsonrpsee::{core::RpcResult, types::error::ErrorObject};
+use parking_lot::Mutex;
+use reth_db::{
+    common::PairResult,
+    cursor::{DbCursorRO, DbDupCursorRO},
+    database::Database,
+    table::{Compress, Decode, DupSort, Encode, Key, Table},
+    tables,
+    transaction::DbTx,
+    RawDupSort, RawKey, RawTable, TableViewer, Tables,
+};
+use reth_primitives::Bytes;
+use reth_rpc_api::ProviderApiServer;
+use reth_rpc_types::{CursorOp, TableEntry};
+use reth_tasks::TaskSpawner;
+use std::{collections::HashMap, str::FromStr, sync::Arc, time::Duration};
+use tokio::sync::oneshot;
+
+mod remote;
+pub use remote::{RemoteCursor, RemoteDatabase, RemoteTx};
+
+/// The maximum number of entries that are returned by a single `provider_cursor` request.
+pub const MAX_CURSOR_ENTRIES: usize = 1_000;
+
+/// The maximum number of snapshots that can be open at the same time.
+///
+/// Every snapshot keeps a read transaction open, which keeps the database from reusing the pages
+/// that were freed since the snapshot was opened.
+pub const MAX_OPEN_SNAPSHOTS: usize = 64;
+
+/// The time after which a snapshot that is not read is released.
+pub const SNAPSHOT_IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// `provider` API implementation.
+///
+/// This type serves read-only access to the raw tables of the database, so a [RemoteDatabase] can
+/// be used by a stateless RPC front-end on another machine. The keys and values are not decoded.
+///
+/// The reads of a snapshot are served by a blocking task that holds the read transaction of the
+/// snapshot, so they all see the same state of the database.
+///
+/// This gives unrestricted read access to the database and should only be exposed to trusted
+/// replicas.
+#[derive(Clone)]
+pub struct ProviderApi<DB> {
+    inner: Arc<ProviderApiInner<DB>>,
+}
+
+struct ProviderApiInner<DB> {
+    db: DB,
+    task_spawner: Box<dyn TaskSpawner>,
+    snapshots: Mutex<Snapshots>,
+}
+
+/// The open snapshots.
+#[derive(Default)]
+struct Snapshots {
+    /// The id of the next snapshot.
+    next_id: u64,
+    /// The channels to the tasks that serve the reads of the open snapshots.
+    open: HashMap<u64, std::sync::mpsc::Sender<ReadRequest>>,
+}
+
+impl<DB> ProviderApi<DB> {
+    /// Creates a new instance of `ProviderApi` that serves the given database.
+    pub fn new(db: DB, task_spawner: Box<dyn TaskSpawner>) -> Self {
+        Self {
+            inner: Arc::new(ProviderApiInner {
+                db,
+                task_spawner,
+                snapshots: Mutex::new(Snapshots::default()),
+            }),
+        }
+    }
+}
+
+impl<DB> ProviderApi<DB>
+where
+    DB: Database + 'static,
+{
+    /// Serves the read from the snapshot with the given id, or from a new read transaction on a
+    /// blocking task.
+    async fn read<R>(
+        &self,
+        snapshot: Option<u64>,
+        request: impl FnOnce(oneshot::Sender<ReadResult<R>>) -> ReadRequest,
+    ) -> RpcResult<R> {
+        let (tx, rx) = oneshot::channel();
+        let request = request(tx);
+        match snapshot {
+            Some(id) => {
+                let sent = self
+                    .inner
+                    .snapshots
+                    .lock()
+                    .open
+                    .get(&id)
+                    .map_or(false, |to_snapshot| to_snapshot.send(request).is_ok());
+                if !sent {
+                    return Err(invalid_params_rpc_err(format!("unknown snapshot {id}")))
+                }
+            }
+            None => {
+                let this = self.clone();
+                self.inner.task_spawner.spawn_blocking(Box::pin(async move {
+                    match this.inner.db.tx() {
+                        Ok(tx) => request.serve(&tx),
+                        Err(err) => request.fail(db_err(err)),
+                    }
+                }));
+            }
+        }
+        Ok(rx.await.map_err(|_| internal_rpc_err("database task failed"))??)
+    }
+}
+
+#[async_trait]
+impl<DB> ProviderApiServer for ProviderApi<DB>
+where
+    DB: Database + 'static,
+{
+    /// Handler for `provider_openSnapshot`
+    async fn open_snapshot(&self) -> RpcResult<u64> {
+        let (to_snapshot, requests) = std::sync::mpsc::channel::<ReadRequest>();
+        let id = {
+            let mut snapshots = self.inner.snapshots.lock();
+            if snapshots.open.len() >= MAX_OPEN_SNAPSHOTS {
+                return Err(internal_rpc_err("too many open snapshots"))
+            }
+            let id = snapshots.next_id;
+            snapshots.next_id += 1;
+            snapshots.open.insert(id, to_snapshot);
+            id
+        };
+
+        let (opened_tx, opened_rx) = oneshot::channel();
+        let this = self.clone();
+        self.inner.task_spawner.spawn_blocking(Box::pin(async move {
+            match this.inner.db.tx() {
+                Ok(tx) => {
+                    let _ = opened_tx.send(Ok(()));
+                    // serve reads until the snapshot is released or idle
+                    while let Ok(request) = requests.recv_timeout(SNAPSHOT_IDLE_TIMEOUT) {
+                        request.serve(&tx);
+                    }
+                }
+                Err(err) => {
+                    let _ = opened_tx.send(Err(db_err(err)));
+                }
+            }
+            this.inner.snapshots.lock().open.remove(&id);
+        }));
+
+        opened_rx.await.map_err(|_| internal_rpc_err("database task failed"))??;
+        Ok(id)
+    }
+
+    /// Handler for `provider_releaseSnapshot`
+    async fn release_snapshot(&self, snapshot: u64) -> RpcResult<bool> {
+        // the task of the snapshot exits once the channel is closed
+        Ok(self.inner.snapshots.lock().open.remove(&snapshot).is_some())
+    }
+
+    /// Handler for `provider_get`
+    async fn get(
+        &self,
+        table: String,
+        key: Bytes,
+        snapshot: Option<u64>,
+    ) -> RpcResult<Option<Bytes>> {
+        let table = parse_table(&table)?;
+        self.read(snapshot, |response| ReadRequest::Get { table, key, response }).await
+    }
+
+    /// Handler for `provider_cursor`
+    async fn cursor(
+        &self,
+        table: String,
+        op: CursorOp,
+        limit: Option<usize>,
+        snapshot: Option<u64>,
+    ) -> RpcResult<Vec<TableEntry>> {
+        let table = parse_table(&table)?;
+        let limit = limit.unwrap_or(1).clamp(1, MAX_CURSOR_ENTRIES);
+        self.read(snapshot, |response| ReadRequest::Cursor { table, op, limit, response }).await
+    }
+
+    /// Handler for `provider_entries`
+    async fn entries(&self, table: String, snapshot: Option<u64>) -> RpcResult<usize> {
+        let table = parse_table(&table)?;
+        self.read(snapshot, |response| ReadRequest::Entries { table, response }).await
+    }
+}
+
+impl<DB> std::fmt::Debug for ProviderApi<DB> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProviderApi").finish_non_exhaustive()
+    }
+}
+
+type ReadResult<R> = Result<R, ErrorObject<'static>>;
+
+/// A read of the `provider` namespace and the channel of its response.
+enum ReadRequest {
+    Get {
+        table: Tables,
+        key: Bytes,
+        response: oneshot::Sender<ReadResult<Option<Bytes>>>,
+    },
+    Cursor {
+        table: Tables,
+        op: CursorOp,
+        limit: usize,
+        response: oneshot::Sender<ReadResult<Vec<TableEntry>>>,
+    },
+    Entries {
+        table: Tables,
+        response: oneshot::Sender<ReadResult<usize>>,
+    },
+}
+
+impl ReadRequest {
+    /// Serves the read from the given transaction.
+    fn serve<'tx, TX: DbTx<'tx>>(self, tx: &TX) {
+        match self {
+            ReadRequest::Get { table, key, response } => {
+                let _ = response.send(table.view(&GetViewer { tx, key }));
+            }
+            ReadRequest::Cursor { table, op, limit, response } => {
+                let _ = response.send(read_cursor(tx, table, op, limit));
+            }
+            ReadRequest::Entries { table, response } => {
+                let _ = response.send(table.view(&EntriesViewer { tx }));
+            }
+        }
+    }
+
+    /// Responds with the given error.
+    fn fail(self, err: ErrorObject<'static>) {
+        let _ = match self {
+            ReadRequest::Get { response, .. } => response.send(Err(err)).is_ok(),
+            ReadRequest::Cursor { response, .. } => response.send(Err(err)).is_ok(),
+            ReadRequest::Entries { response, .. } => response.send(Err(err)).is_ok(),
+        };
+    }
+}
+
+fn parse_table(table: &str) -> Result<Tables, ErrorObject<'static>> {
+    Tables::from_str(table).map_err(|_| invalid_params_rpc_err(format!("unknown table {table}")))
+}
+
+fn db_err(err: reth_db::DatabaseError) -> ErrorObject<'static> {
+    internal_rpc_err(err.to_string())
+}
+
+/// Reads the raw value of a key.
+struct GetViewer<'a, TX> {
+    tx: &'a TX,
+    key: Bytes,
+}
+
+impl<'tx, TX: DbTx<'tx>> TableViewer<Option<Bytes>> for GetViewer<'_, TX> {
+    type Error = ErrorObject<'static>;
+
+    fn view<T: Table>(&self) -> Result<Option<Bytes>, Self::Error> {
+        let key = RawKey::<T::Key>::decode(&self.key).map_err(db_err)?;
+        let value = self.tx.get::<RawTable<T>>(key).map_err(db_err)?;
+        Ok(value.map(|value| value.raw_value().clone().into()))
+    }
+}
+
+/// Counts the entries of a table.
+struct EntriesViewer<'a, TX> {
+    tx: &'a TX,
+}
+
+impl<'tx, TX: DbTx<'tx>> TableViewer<usize> for EntriesViewer<'_, TX> {
+    type Error = ErrorObject<'static>;
+
+    fn view<T: Table>(&self) -> Result<usize, Self::Error> {
+        self.tx.entries::<RawTable<T>>().map_err(db_err)
+    }
+}
+
+/// Reads the entries of a table at the position of the cursor operation.
+fn read_cursor<'tx, TX: DbTx<'tx>>(
+    tx: &TX,
+    table: Tables,
+    op: CursorOp,
+    limit: usize,
+) -> ReadResult<Vec<TableEntry>> {
+    match table {
+        Tables::PlainStorageState => dup_cursor::<_, tables::PlainStorageState>(tx, op, limit),
+        Tables::AccountChangeSet => dup_cursor::<_, tables::AccountChangeSet>(tx, op, limit),
+        Tables::StorageChangeSet => dup_cursor::<_, tables::StorageChangeSet>(tx, op, limit),
+        Tables::HashedStorage => dup_cursor::<_, tables::HashedStorage>(tx, op, limit),
+        Tables::StoragesTrie => dup_cursor::<_, tables::StoragesTrie>(tx, op, limit),
+        table => table.view(&CursorViewer { tx, op, limit }),
+    }
+}
+
+/// Reads the entries of a table without duplicate keys.
+struct CursorViewer<'a, TX> {
+    tx: &'a TX,
+    op: CursorOp,
+    limit: usize,
+}
+
+impl<'tx, TX: DbTx<'tx>> TableViewer<Vec<TableEntry>> for CursorViewer<'_, TX> {
+    type Error = ErrorObject<'static>;
+
+    fn view<T: Table>(&self) -> Result<Vec<TableEntry>, Self::Error> {
+        let mut cursor = self.tx.cursor_read::<RawTable<T>>().map_err(db_err)?;
+        let start = match self.op.clone() {
+            CursorOp::Next { key, .. } | CursorOp::NextNoDup { key } => next_key(&mut cursor, key),
+            CursorOp::Prev { key, .. } => match cursor.seek(raw_key(&key)?).map_err(db_err)? {
+                Some(_) => cursor.prev(),
+                None => cursor.last(),
+            },
+            CursorOp::SeekBySubkey { .. } => {
+                return Err(invalid_params_rpc_err(format!("table {} has no subkeys", T::NAME)))
+            }
+            op => seek(&mut cursor, op)?,
+        };
+        collect(&mut cursor, start, self.op.is_backward(), self.limit)
+    }
+}
+
+/// Reads the entries of a table with duplicate keys.
+///
+/// The cursor is positioned at exact `(key, value)` pairs by seeking to the value as subkey.
+fn dup_cursor<'tx, TX, T>(tx: &TX, op: CursorOp, limit: usize) -> ReadResult<Vec<TableEntry>>
+where
+    TX: DbTx<'tx>,
+    T: DupSort,
+{
+    let backward = op.is_backward();
+    let mut cursor = tx.cursor_dup_read::<RawDupSort<T>>().map_err(db_err)?;
+    let start = match op {
+        CursorOp::Next { key, value } => {
+            match cursor.seek_by_key_subkey(raw_key(&key)?, raw_key(&value)?).map_err(db_err)? {
+                Some(found) if found.raw_value().as_slice() == value.as_ref() => cursor.next(),
+                Some(found) => Ok(Some((raw_key(&key)?, found))),
+                None => next_dup_key(&mut cursor, key),
+            }
+        }
+        CursorOp::Prev { key, value } => {
+            match cursor.seek_by_key_subkey(raw_key(&key)?, raw_key(&value)?).map_err(db_err)? {
+                Some(_) => cursor.prev(),
+                // all duplicates of the key are smaller, so the entry before the next key is the
+                // last duplicate of the key
+                None => match next_dup_key(&mut cursor, key).map_err(db_err)? {
+                    Some(_) => cursor.prev(),
+                    None => cursor.last(),
+                },
+            }
+        }
+        CursorOp::NextNoDup { key } => next_dup_key(&mut cursor, key),
+        CursorOp::SeekBySubkey { key, subkey } => {
+            let key = raw_key(&key)?;
+            cursor
+                .seek_by_key_subkey(key.clone(), raw_key(&subkey)?)
+                .map(|value| value.map(|value| (key, value)))
+        }
+        op => seek(&mut cursor, op)?,
+    };
+    collect(&mut cursor, start, backward, limit)
+}
+
+fn raw_key<K: Key>(key: &Bytes) -> Result<RawKey<K>, ErrorObject<'static>> {
+    RawKey::decode(key).map_err(db_err)
+}
+
+/// Positions the cursor at the operations that do not depend on the current entry.
+fn seek<'tx, K, R, C>(cursor: &mut C, op: CursorOp) -> Result<PairResult<R>, ErrorObject<'static>>
+where
+    K: Key,
+    R: Table<Key = RawKey<K>>,
+    C: DbCursorRO<'tx, R>,
+{
+    Ok(match op {
+        CursorOp::First => cursor.first(),
+        CursorOp::Last => cursor.last(),
+        CursorOp::SeekExact { key } => cursor.seek_exact(raw_key(&key)?),
+        CursorOp::Seek { key } => cursor.seek(raw_key(&key)?),
+        op => return Err(invalid_params_rpc_err(format!("unexpected cursor operation {op:?}"))),
+    })
+}
+
+/// Positions the cursor at the first entry with a key greater than the given key.
+fn next_key<'tx, T, C>(cursor: &mut C, key: Bytes) -> PairResult<RawTable<T>>
+where
+    T: Table,
+    C: DbCursorRO<'tx, RawTable<T>>,
+{
+    let key = RawKey::decode(&key)?;
+    match cursor.seek(key.clone())? {
+        Some((found, _)) if found == key => cursor.next(),
+        entry => Ok(entry),
+    }
+}
+
+/// Positions the cursor at the first entry with a key greater than the given key, skipping the
+/// duplicates of the key.
+fn next_dup_key<'tx, T, C>(cursor: &mut C, key: Bytes) -> PairResult<RawDupSort<T>>
+where
+    T: DupSort,
+    C: DbCursorRO<'tx, RawDupSort<T>> + DbDupCursorRO<'tx, RawDupSort<T>>,
+{
+    let key = RawKey::decode(&key)?;
+    match cursor.seek_exact(key.clone())? {
+        Some(_) => cursor.next_no_dup(),
+        None => cursor.seek(key),
+    }
+}
+
+/// Collects the entry at the position and the following entries in the given direction.
+fn collect<'tx, R, C>(
+    cursor: &mut C,
+    start: PairResult<R>,
+    backward: bool,
+    limit: usize,
+) -> RpcResult<Vec<TableEntry>>
+where
+    R: Table,
+    C: DbCursorRO<'tx, R>,
+{
+    let mut entries = Vec::new();
+    let mut entry = start.map_err(db_err)?;
+    while let Some((key, value)) = entry {
+        entries.push(TableEntry {
+            key: key.encode().as_ref().to_vec().into(),
+            value: value.compress().as_ref().to_vec().into(),
+        });
+        if entries.len() >= limit {
+            break
+        }
+        entry = if backward { cursor.prev() } else { cursor.next() }.map_err(db_err)?;
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::{test_utils::create_test_rw_db, transaction::DbTxMut};
+    use reth_primitives::{Address, StorageEntry, H256, U256};
+    use reth_tasks::TokioTaskExecutor;
+
+    fn entry(address: Address, slot: u64) -> TableEntry {
+        let entry = StorageEntry { key: H256::from_low_u64_be(slot), value: U256::from(slot) };
+        TableEntry {
+            key: address.encode().as_ref().to_vec().into(),
+            value: entry.compress().as_ref().to_vec().into(),
+        }
+    }
+
+    #[test]
+    fn dup_cursor_moves_along_duplicates() {
+        let db = create_test_rw_db();
+        let (a, b) = (Address::from_low_u64_be(1), Address::from_low_u64_be(2));
+        db.update(|tx| {
+            for (address, slot) in [(a, 1), (a, 2), (a, 3), (b, 1)] {
+                let entry =
+                    StorageEntry { key: H256::from_low_u64_be(slot), value: U256::from(slot) };
+                tx.put::<tables::PlainStorageState>(address, entry).unwrap();
+            }
+        })
+        .unwrap();
+
+        let tx = db.tx().unwrap();
+        let cursor =
+            |op, limit| dup_cursor::<_, tables::PlainStorageState>(&tx, op, limit).unwrap();
+
+        let TableEntry { key, value } = entry(a, 2);
+        assert_eq!(
+            cursor(CursorOp::Next { key: key.clone(), value: value.clone() }, 10),
+            vec![entry(a, 3), entry(b, 1)]
+        );
+        assert_eq!(cursor(CursorOp::Prev { key: key.clone(), value }, 10), vec![entry(a, 1)]);
+        assert_eq!(cursor(CursorOp::NextNoDup { key }, 10), vec![entry(b, 1)]);
+        assert_eq!(cursor(CursorOp::Last, 2), vec![entry(b, 1), entry(a, 3)]);
+    }
+
+    #[tokio::test]
+    async fn snapshot_reads_see_the_same_state() {
+        let db = create_test_rw_db();
+        let api = ProviderApi::new(db.clone(), Box::<TokioTaskExecutor>::default());
+        let snapshot = api.open_snapshot().await.unwrap();
+
+        db.update(|tx| tx.put::<tables::CanonicalHeaders>(0, H256::random()).unwrap()).unwrap();
+        let table = tables::CanonicalHeaders::NAME.to_string();
+        assert_eq!(api.entries(table.clone(), Some(snapshot)).await.unwrap(), 0);
+        assert_eq!(api.entries(table.clone(), None).await.unwrap(), 1);
+
+        assert!(api.release_snapshot(snapshot).await.unwrap());
+        assert!(api.entries(table, Some(snapshot)).await.is_err());
+    }
+}
@@ -0,0 +1,592 @@
+//! A [Database] that reads the tables of a node over the `provider` namespace.
+use jsonrpsee::core::client::ClientT;
+use reth_db::{
+    common::{PairResult, ValueOnlyResult},
+    cursor::{
+        DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW, DupWalker, RangeWalker,
+        ReverseWalker, Walker,
+    },
+    database::{Database, DatabaseGAT},
+    table::{Decode, Decompress, DupSort, Encode, Table, TableImporter},
+    transaction::{DbTx, DbTxGAT, DbTxMut, DbTxMutGAT},
+    DatabaseError,
+};
+use reth_primitives::Bytes;
+use reth_rpc_api::ProviderApiClient;
+use reth_rpc_types::{CursorOp, TableEntry};
+use std::{
+    collections::VecDeque,
+    fmt::Display,
+    future::Future,
+    marker::PhantomData,
+    ops::{Bound, RangeBounds},
+    sync::Arc,
+};
+use tokio::runtime::{Handle, RuntimeFlavor};
+
+/// The default number of entries that are fetched when a cursor moves to the next or previous
+/// entry.
+const DEFAULT_PREFETCH: usize = 64;
+
+/// A read-only [Database] that is served by the `provider` namespace of a remote node.
+///
+/// Every read blocks the calling thread until the request is finished. The requests are driven by
+/// the given runtime, which should be dedicated to the client. When a read is called from a worker
+/// thread of a multi-threaded runtime, the thread is handed off with
+/// [tokio::task::block_in_place], so the runtime keeps running its other tasks.
+///
+/// Every [RemoteTx] opens a snapshot of the remote database, so all its reads see the same state.
+/// The snapshot is released once the transaction and its cursors are dropped. A snapshot that is
+/// not read for a while is released by the remote node, the reads of the transaction fail after
+/// that.
+///
+/// Write transactions are not supported and fail with [DatabaseError::ReadOnly].
+pub struct RemoteDatabase<C> {
+    inner: Arc<RemoteDatabaseInner<C>>,
+    /// The number of entries that are fetched when a cursor moves.
+    prefetch: usize,
+}
+
+struct RemoteDatabaseInner<C> {
+    client: C,
+    handle: Handle,
+}
+
+impl<C> RemoteDatabase<C> {
+    /// Creates a new database that sends its requests with the client on the given runtime.
+    pub fn new(client: C, handle: Handle) -> Self {
+        Self { inner: Arc::new(RemoteDatabaseInner { client, handle }), prefetch: DEFAULT_PREFETCH }
+    }
+
+    /// Sets the number of entries that are fetched when a cursor moves to the next or previous
+    /// entry.
+    pub fn with_prefetch(mut self, prefetch: usize) -> Self {
+        self.prefetch = prefetch.max(1);
+        self
+    }
+}
+
+impl<C> RemoteDatabase<C>
+where
+    C: ClientT + Send + Sync + 'static,
+{
+    /// Drives the request on the runtime and blocks until it is finished.
+    fn request<R, E: Display>(
+        &self,
+        request: impl Future<Output = Result<R, E>>,
+    ) -> Result<R, DatabaseError> {
+        let res = match Handle::try_current() {
+            Ok(current) if current.runtime_flavor() == RuntimeFlavor::MultiThread => {
+                tokio::task::block_in_place(|| self.inner.handle.block_on(request))
+            }
+            Ok(_) => {
+                return Err(DatabaseError::Remote(
+                    "remote database can't be read from a current-thread runtime".to_string(),
+                ))
+            }
+            Err(_) => self.inner.handle.block_on(request),
+        };
+        res.map_err(|err| DatabaseError::Remote(err.to_string()))
+    }
+
+    /// Opens a snapshot of the remote database, which is released when the returned guard is
+    /// dropped.
+    fn open_snapshot(&self) -> Result<Arc<SnapshotGuard>, DatabaseError> {
+        let id = self.request(ProviderApiClient::open_snapshot(&self.inner.client))?;
+        let inner = Arc::clone(&self.inner);
+        let release = move || {
+            let handle = inner.handle.clone();
+            handle.spawn(async move {
+                let _ = ProviderApiClient::release_snapshot(&inner.client, id).await;
+            });
+        };
+        Ok(Arc::new(SnapshotGuard { id, release: Some(Box::new(release)) }))
+    }
+
+    fn get(
+        &self,
+        snapshot: u64,
+        table: &'static str,
+        key: Bytes,
+    ) -> Result<Option<Bytes>, DatabaseError> {
+        self.request(ProviderApiClient::get(
+            &self.inner.client,
+            table.to_string(),
+            key,
+            Some(snapshot),
+        ))
+    }
+
+    fn cursor(
+        &self,
+        snapshot: u64,
+        table: &'static str,
+        op: CursorOp,
+        limit: usize,
+    ) -> Result<Vec<TableEntry>, DatabaseError> {
+        self.request(ProviderApiClient::cursor(
+            &self.inner.client,
+            table.to_string(),
+            op,
+            Some(limit),
+            Some(snapshot),
+        ))
+    }
+
+    fn entries(&self, snapshot: u64, table: &'static str) -> Result<usize, DatabaseError> {
+        self.request(ProviderApiClient::entries(
+            &self.inner.client,
+            table.to_string(),
+            Some(snapshot),
+        ))
+    }
+}
+
+/// Releases a snapshot of the remote database when it is dropped.
+struct SnapshotGuard {
+    id: u64,
+    release: Option<Box<dyn FnOnce() + Send + Sync>>,
+}
+
+impl Drop for SnapshotGuard {
+    fn drop(&mut self) {
+        if let Some(release) = self.release.take() {
+            release()
+        }
+    }
+}
+
+impl<C> Clone for RemoteDatabase<C> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner), prefetch: self.prefetch }
+    }
+}
+
+impl<C> std::fmt::Debug for RemoteDatabase<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RemoteDatabase").field("prefetch", &self.prefetch).finish_non_exhaustive()
+    }
+}
+
+impl<C> Database for RemoteDatabase<C>
+where
+    C: ClientT + Send + Sync + 'static,
+{
+    fn tx(&self) -> Result<<Self as DatabaseGAT<'_>>::TX, DatabaseError> {
+        Ok(RemoteTx { db: self.clone(), snapshot: self.open_snapshot()? })
+    }
+
+    fn tx_mut(&self) -> Result<<Self as DatabaseGAT<'_>>::TXMut, DatabaseError> {
+        Err(DatabaseError::ReadOnly)
+    }
+}
+
+impl<'a, C> DatabaseGAT<'a> for RemoteDatabase<C>
+where
+    C: ClientT + Send + Sync + 'static,
+{
+    type TX = RemoteTx<C>;
+    type TXMut = RemoteTx<C>;
+}
+
+/// A read-only transaction of a [RemoteDatabase], which reads from a snapshot of the remote
+/// database.
+pub struct RemoteTx<C> {
+    db: RemoteDatabase<C>,
+    snapshot: Arc<SnapshotGuard>,
+}
+
+impl<C> Clone for RemoteTx<C> {
+    fn clone(&self) -> Self {
+        Self { db: self.db.clone(), snapshot: Arc::clone(&self.snapshot) }
+    }
+}
+
+impl<C> std::fmt::Debug for RemoteTx<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RemoteTx")
+            .field("db", &self.db)
+            .field("snapshot", &self.snapshot.id)
+            .finish()
+    }
+}
+
+impl<'a, C> DbTxGAT<'a> for RemoteTx<C>
+where
+    C: ClientT + Send + Sync + 'static,
+{
+    type Cursor<T: Table> = RemoteCursor<C, T>;
+    type DupCursor<T: DupSort> = RemoteCursor<C, T>;
+}
+
+impl<'a, C> DbTxMutGAT<'a> for RemoteTx<C>
+where
+    C: ClientT + Send + Sync + 'static,
+{
+    type CursorMut<T: Table> = RemoteCursor<C, T>;
+    type DupCursorMut<T: DupSort> = RemoteCursor<C, T>;
+}
+
+impl<'a, C> DbTx<'a> for RemoteTx<C>
+where
+    C: ClientT + Send + Sync + 'static,
+{
+    fn get<T: Table>(&self, key: T::Key) -> Result<Option<T::Value>, DatabaseError> {
+        self.db
+            .get(self.snapshot.id, T::NAME, encode_key::<T>(key))?
+            .map(T::Value::decompress)
+            .transpose()
+    }
+
+    fn commit(self) -> Result<bool, DatabaseError> {
+        Ok(true)
+    }
+
+    fn drop(self) {}
+
+    fn cursor_read<T: Table>(&self) -> Result<<Self as DbTxGAT<'_>>::Cursor<T>, DatabaseError> {
+        Ok(RemoteCursor::new(self.clone()))
+    }
+
+    fn cursor_dup_read<T: DupSort>(
+        &self,
+    ) -> Result<<Self as DbTxGAT<'_>>::DupCursor<T>, DatabaseError> {
+        Ok(RemoteCursor::new(self.clone()))
+    }
+
+    fn entries<T: Table>(&self) -> Result<usize, DatabaseError> {
+        self.db.entries(self.snapshot.id, T::NAME)
+    }
+}
+
+impl<'a, C> DbTxMut<'a> for RemoteTx<C>
+where
+    C: ClientT + Send + Sync + 'static,
+{
+    fn put<T: Table>(&self, _key: T::Key, _value: T::Value) -> Result<(), DatabaseError> {
+        Err(DatabaseError::ReadOnly)
+    }
+
+    fn delete<T: Table>(
+        &self,
+        _key: T::Key,
+        _value: Option<T::Value>,
+    ) -> Result<bool, DatabaseError> {
+        Err(DatabaseError::ReadOnly)
+    }
+
+    fn clear<T: Table>(&self) -> Result<(), DatabaseError> {
+        Err(DatabaseError::ReadOnly)
+    }
+
+    fn cursor_write<T: Table>(
+        &self,
+    ) -> Result<<Self as DbTxMutGAT<'_>>::CursorMut<T>, DatabaseError> {
+        Err(DatabaseError::ReadOnly)
+    }
+
+    fn cursor_dup_write<T: DupSort>(
+        &self,
+    ) -> Result<<Self as DbTxMutGAT<'_>>::DupCursorMut<T>, DatabaseError> {
+        Err(DatabaseError::ReadOnly)
+    }
+}
+
+impl<'a, C> TableImporter<'a> for RemoteTx<C> where C: ClientT + Send + Sync + 'static {}
+
+/// A cursor of a [RemoteTx].
+///
+/// The cursor keeps the raw entry it is positioned at and sends it along with relative moves.
+/// Moving to the next or previous entry fetches the following entries in that direction, so walks
+/// only need a request per batch of entries.
+pub struct RemoteCursor<C, T> {
+    tx: RemoteTx<C>,
+    /// The entry the cursor is positioned at.
+    current: Option<TableEntry>,
+    /// The prefetched entries after the current entry, in the direction of `backward`.
+    buffer: VecDeque<TableEntry>,
+    /// Whether the buffer contains the entries before the current entry.
+    backward: bool,
+    _table: PhantomData<T>,
+}
+
+impl<C, T> RemoteCursor<C, T>
+where
+    C: ClientT + Send + Sync + 'static,
+    T: Table,
+{
+    fn new(tx: RemoteTx<C>) -> Self {
+        Self { tx, current: None, buffer: VecDeque::new(), backward: false, _table: PhantomData }
+    }
+
+    /// Reads up to `limit` entries at the position of the operation.
+    fn read(&self, op: CursorOp, limit: usize) -> Result<Vec<TableEntry>, DatabaseError> {
+        self.tx.db.cursor(self.tx.snapshot.id, T::NAME, op, limit)
+    }
+
+    /// Positions the cursor at the entry of an operation that does not depend on the current
+    /// entry.
+    fn position(&mut self, op: CursorOp) -> PairResult<T> {
+        self.current = self.read(op, 1)?.into_iter().next();
+        self.buffer.clear();
+        self.decode_current()
+    }
+
+    /// Moves to the next entry in the given direction, from the buffer if possible.
+    fn step(&mut self, backward: bool) -> PairResult<T> {
+        let Some(current) = self.current.clone() else {
+            return if backward { self.last() } else { self.first() }
+        };
+        if self.backward == backward {
+            if let Some(entry) = self.buffer.pop_front() {
+                self.current = Some(entry);
+                return self.decode_current()
+            }
+        }
+
+        let TableEntry { key, value } = current;
+        let op =
+            if backward { CursorOp::Prev { key, value } } else { CursorOp::Next { key, value } };
+        self.step_with(op)
+    }
+
+    /// Sends a relative operation and moves to the first returned entry.
+    fn step_with(&mut self, op: CursorOp) -> PairResult<T> {
+        let backward = op.is_backward();
+        let mut entries = VecDeque::from(self.read(op, self.tx.db.prefetch)?);
+        self.backward = backward;
+        match entries.pop_front() {
+            Some(entry) => {
+                self.current = Some(entry);
+                self.buffer = entries;
+                self.decode_current()
+            }
+            None => {
+                self.buffer.clear();
+                Ok(None)
+            }
+        }
+    }
+
+    /// Returns the next entry without moving the cursor.
+    fn peek_next(&mut self) -> Result<Option<TableEntry>, DatabaseError> {
+        let Some(TableEntry { key, value }) = self.current.clone() else { return Ok(None) };
+        if self.backward || self.buffer.is_empty() {
+            self.buffer = self.read(CursorOp::Next { key, value }, self.tx.db.prefetch)?.into();
+            self.backward = false;
+        }
+        Ok(self.buffer.front().cloned())
+    }
+
+    fn decode_current(&self) -> PairResult<T> {
+        self.current.as_ref().map(decode_entry::<T>).transpose()
+    }
+}
+
+impl<'tx, C, T> DbCursorRO<'tx, T> for RemoteCursor<C, T>
+where
+    C: ClientT + Send + Sync + 'static,
+    T: Table,
+{
+    fn first(&mut self) -> PairResult<T> {
+        self.position(CursorOp::First)
+    }
+
+    fn seek_exact(&mut self, key: T::Key) -> PairResult<T> {
+        self.position(CursorOp::SeekExact { key: encode_key::<T>(key) })
+    }
+
+    fn seek(&mut self, key: T::Key) -> PairResult<T> {
+        self.position(CursorOp::Seek { key: encode_key::<T>(key) })
+    }
+
+    fn next(&mut self) -> PairResult<T> {
+        self.step(false)
+    }
+
+    fn prev(&mut self) -> PairResult<T> {
+        self.step(true)
+    }
+
+    fn last(&mut self) -> PairResult<T> {
+        self.position(CursorOp::Last)
+    }
+
+    fn current(&mut self) -> PairResult<T> {
+        self.decode_current()
+    }
+
+    fn walk<'cursor>(
+        &'cursor mut self,
+        start_key: Option<T::Key>,
+    ) -> Result<Walker<'cursor, 'tx, T, Self>, DatabaseError>
+    where
+        Self: Sized,
+    {
+        let start = match start_key {
+            Some(key) => self.seek(key),
+            None => self.first(),
+        }
+        .transpose();
+
+        Ok(Walker::new(self, start))
+    }
+
+    fn walk_range<'cursor>(
+        &'cursor mut self,
+        range: impl RangeBounds<T::Key>,
+    ) -> Result<RangeWalker<'cursor, 'tx, T, Self>, DatabaseError>
+    where
+        Self: Sized,
+    {
+        let start = match range.start_bound().cloned() {
+            Bound::Included(key) => self.seek(key),
+            Bound::Excluded(_key) => {
+                unreachable!("Rust doesn't allow for Bound::Excluded in starting bounds");
+            }
+            Bound::Unbounded => self.first(),
+        }
+        .transpose();
+
+        Ok(RangeWalker::new(self, start, range.end_bound().cloned()))
+    }
+
+    fn walk_back<'cursor>(
+        &'cursor mut self,
+        start_key: Option<T::Key>,
+    ) -> Result<ReverseWalker<'cursor, 'tx, T, Self>, DatabaseError>
+    where
+        Self: Sized,
+    {
+        let start = match start_key {
+            Some(key) => self.seek(key),
+            None => self.last(),
+        }
+        .transpose();
+
+        Ok(ReverseWalker::new(self, start))
+    }
+}
+
+impl<'tx, C, T> DbDupCursorRO<'tx, T> for RemoteCursor<C, T>
+where
+    C: ClientT + Send + Sync + 'static,
+    T: DupSort,
+{
+    fn next_dup(&mut self) -> PairResult<T> {
+        let Some(current) = self.current.as_ref().map(|entry| entry.key.clone()) else {
+            return Ok(None)
+        };
+        match self.peek_next()? {
+            Some(next) if next.key == current => self.step(false),
+            _ => Ok(None),
+        }
+    }
+
+    fn next_no_dup(&mut self) -> PairResult<T> {
+        let Some(current) = self.current.as_ref().map(|entry| entry.key.clone()) else {
+            return self.first()
+        };
+        if !self.backward {
+            while self.buffer.front().map_or(false, |entry| entry.key == current) {
+                self.buffer.pop_front();
+            }
+            if let Some(entry) = self.buffer.pop_front() {
+                self.current = Some(entry);
+                return self.decode_current()
+            }
+        }
+        self.step_with(CursorOp::NextNoDup { key: current })
+    }
+
+    fn next_dup_val(&mut self) -> ValueOnlyResult<T> {
+        Ok(self.next_dup()?.map(|(_, value)| value))
+    }
+
+    fn seek_by_key_subkey(
+        &mut self,
+        key: <T as Table>::Key,
+        subkey: <T as DupSort>::SubKey,
+    ) -> ValueOnlyResult<T> {
+        let op = CursorOp::SeekBySubkey {
+            key: encode_key::<T>(key),
+            subkey: subkey.encode().as_ref().to_vec().into(),
+        };
+        Ok(self.position(op)?.map(|(_, value)| value))
+    }
+
+    fn walk_dup<'cursor>(
+        &'cursor mut self,
+        key: Option<T::Key>,
+        subkey: Option<T::SubKey>,
+    ) -> Result<DupWalker<'cursor, 'tx, T, Self>, DatabaseError> {
+        let start = match (key, subkey) {
+            (Some(key), Some(subkey)) => {
+                self.seek_by_key_subkey(key, subkey)?;
+                self.decode_current()
+            }
+            (Some(key), None) => self.seek_exact(key),
+            (None, Some(subkey)) => match self.first()? {
+                Some((key, _)) => {
+                    self.seek_by_key_subkey(key, subkey)?;
+                    self.decode_current()
+                }
+                None => Ok(None),
+            },
+            (None, None) => self.first(),
+        }
+        .transpose();
+
+        Ok(DupWalker::<'cursor, 'tx, T, Self> { cursor: self, start, _tx_phantom: PhantomData {} })
+    }
+}
+
+impl<'tx, C, T> DbCursorRW<'tx, T> for RemoteCursor<C, T>
+where
+    C: ClientT + Send + Sync + 'static,
+    T: Table,
+{
+    fn upsert(&mut self, _key: T::Key, _value: T::Value) -> Result<(), DatabaseError> {
+        Err(DatabaseError::ReadOnly)
+    }
+
+    fn insert(&mut self, _key: T::Key, _value: T::Value) -> Result<(), DatabaseError> {
+        Err(DatabaseError::ReadOnly)
+    }
+
+    fn append(&mut self, _key: T::Key, _value: T::Value) -> Result<(), DatabaseError> {
+        Err(DatabaseError::ReadOnly)
+    }
+
+    fn delete_current(&mut self) -> Result<(), DatabaseError> {
+        Err(DatabaseError::ReadOnly)
+    }
+}
+
+impl<'tx, C, T> DbDupCursorRW<'tx, T> for RemoteCursor<C, T>
+where
+    C: ClientT + Send + Sync + 'static,
+    T: DupSort,
+{
+    fn delete_current_duplicates(&mut self) -> Result<(), DatabaseError> {
+        Err(DatabaseError::ReadOnly)
+    }
+
+    fn append_dup(&mut self, _key: T::Key, _value: T::Value) -> Result<(), DatabaseError> {
+        Err(DatabaseError::ReadOnly)
+    }
+}
+
+impl<C, T> std::fmt::Debug for RemoteCursor<C, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RemoteCursor").field("current", &self.current).finish_non_exhaustive()
+    }
+}
+
+fn encode_key<T: Table>(key: T::Key) -> Bytes {
+    key.encode().as_ref().to_vec().into()
+}
+
+fn decode_entry<T: Table>(entry: &TableEntry) -> Result<(T::Key, T::Value), DatabaseError> {
+    Ok((T::Key::decode(&entry.key)?, T::Value::decompress(&entry.value)?))
+}
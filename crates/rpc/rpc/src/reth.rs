@@ -2,13 +2,17 @@ use crate::eth::error::{EthApiError, EthResult};
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
 use reth_interfaces::Result;
-use reth_primitives::{Address, BlockId, U256};
-use reth_provider::{BlockReaderIdExt, ChangeSetReader, StateProviderFactory};
+use reth_primitives::{Address, BlockId, BlockNumberOrTag, KECCAK_EMPTY, U256};
+use reth_provider::{BlockReaderIdExt, ChainSpecProvider, ChangeSetReader, StateProviderFactory};
 use reth_rpc_api::RethApiServer;
+use reth_rpc_types::{AccountSummary, ChainSpecInfo};
 use reth_tasks::TaskSpawner;
 use std::{collections::HashMap, future::Future, sync::Arc};
 use tokio::sync::oneshot;
 
+/// The maximum number of addresses that can be queried in a single batch state request.
+const MAX_BATCH_ACCOUNTS: usize = 10_000;
+
 /// `reth` API implementation.
 ///
 /// This type provides the functionality for handling `reth` prototype RPC requests.
@@ -81,12 +85,73 @@ where
         )?;
         Ok(hash_map)
     }
+
+    /// Returns the balances of the given accounts, read from a single state at the given block.
+    pub async fn balances(
+        &self,
+        addresses: Vec<Address>,
+        block_id: Option<BlockId>,
+    ) -> EthResult<Vec<U256>> {
+        let accounts = self.accounts(addresses, block_id, false).await?;
+        Ok(accounts.into_iter().map(|account| account.balance).collect())
+    }
+
+    /// Returns the given accounts, read from a single state at the given block.
+    pub async fn accounts(
+        &self,
+        addresses: Vec<Address>,
+        block_id: Option<BlockId>,
+        include_code: bool,
+    ) -> EthResult<Vec<AccountSummary>> {
+        if addresses.len() > MAX_BATCH_ACCOUNTS {
+            return Err(EthApiError::InvalidParams(format!(
+                "too many addresses, at most {MAX_BATCH_ACCOUNTS} are allowed"
+            )))
+        }
+        self.on_blocking_task(
+            |this| async move { this.try_accounts(addresses, block_id, include_code) },
+        )
+        .await
+    }
+
+    fn try_accounts(
+        &self,
+        addresses: Vec<Address>,
+        block_id: Option<BlockId>,
+        include_code: bool,
+    ) -> EthResult<Vec<AccountSummary>> {
+        let block_id = block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
+        let state = self.provider().state_by_block_id(block_id)?;
+        let accounts = state.basic_accounts_with_code(&addresses, include_code)?;
+
+        Ok(addresses
+            .into_iter()
+            .zip(accounts)
+            .map(|(address, account)| match account {
+                Some((account, code)) => AccountSummary {
+                    address,
+                    balance: account.balance,
+                    nonce: account.nonce.into(),
+                    code_hash: account.get_bytecode_hash(),
+                    code: include_code
+                        .then(|| code.map(|code| code.original_bytes().into()).unwrap_or_default()),
+                },
+                None => AccountSummary {
+                    address,
+                    code_hash: KECCAK_EMPTY,
+                    code: include_code.then(Default::default),
+                    ..Default::default()
+                },
+            })
+            .collect())
+    }
 }
 
 #[async_trait]
 impl<Provider> RethApiServer for RethApi<Provider>
 where
-    Provider: BlockReaderIdExt + ChangeSetReader + StateProviderFactory + 'static,
+    Provider:
+        BlockReaderIdExt + ChainSpecProvider + ChangeSetReader + StateProviderFactory + 'static,
 {
     /// Handler for `reth_getBalanceChangesInBlock`
     async fn reth_get_balance_changes_in_block(
@@ -95,6 +160,30 @@ where
     ) -> RpcResult<HashMap<Address, U256>> {
         Ok(RethApi::balance_changes_in_block(self, block_id).await?)
     }
+
+    /// Handler for `reth_getBalances`
+    async fn reth_get_balances(
+        &self,
+        addresses: Vec<Address>,
+        block_id: Option<BlockId>,
+    ) -> RpcResult<Vec<U256>> {
+        Ok(RethApi::balances(self, addresses, block_id).await?)
+    }
+
+    /// Handler for `reth_getAccounts`
+    async fn reth_get_accounts(
+        &self,
+        addresses: Vec<Address>,
+        block_id: Option<BlockId>,
+        include_code: Option<bool>,
+    ) -> RpcResult<Vec<AccountSummary>> {
+        Ok(RethApi::accounts(self, addresses, block_id, include_code.unwrap_or_default()).await?)
+    }
+
+    /// Handler for `reth_chainSpec`
+    fn reth_chain_spec(&self) -> RpcResult<ChainSpecInfo> {
+        Ok(ChainSpecInfo::from(&*self.provider().chain_spec()))
+    }
 }
 
 impl<Provider> std::fmt::Debug for RethApi<Provider> {
@@ -0,0 +1,572 @@
+//! `reth_` PubSub RPC handler implementation
+use crate::{
+    eth::{
+        build_transaction_receipt_with_block_receipts,
+        error::{EthApiError, EthResult},
+        EthTransactions,
+    },
+    result::invalid_params_rpc_err,
+    TraceApi,
+};
+use jsonrpsee::{server::SubscriptionMessage, PendingSubscriptionSink, SubscriptionSink};
+use reth_primitives::{
+    Account, Address, BlockId, BlockNumber, Receipt, SealedBlock, TransactionMeta, H256,
+    KECCAK_EMPTY, U256, U64,
+};
+use reth_provider::{
+    BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
+    CanonStateNotification, CanonStateNotifications, CanonStateSubscriptions, ChainSpecProvider,
+    EvmEnvProvider, HeaderProvider, PostState, ReceiptProvider, StateProviderFactory,
+};
+use reth_rpc_api::RethPubSubApiServer;
+use reth_rpc_types::{
+    pubsub::{
+        AccountChange, BlockWithReceipts, BlocksSubscriptionParams, StateChanges,
+        StateChangesParams, StorageChange,
+    },
+    BlockTransactionsKind,
+};
+use reth_rpc_types_compat::block::from_block;
+use reth_tasks::{TaskSpawner, TokioTaskExecutor};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::Arc,
+};
+use tokio::sync::broadcast::error::RecvError;
+
+/// The maximum number of blocks a subscription can request to catch up with.
+pub const MAX_BLOCKS_CATCH_UP: u64 = 1_000;
+
+/// The number of sent blocks that are kept per subscription to stream them as removed on reorgs.
+const MAX_SENT_BLOCKS: usize = 64;
+
+/// `reth` pubsub RPC implementation.
+///
+/// This handles `reth_subscribeBlocks` and `reth_subscribeStateChanges` RPC calls.
+#[derive(Clone)]
+pub struct RethPubSub<Provider, Events, Eth> {
+    /// All nested fields bundled together.
+    inner: Arc<RethPubSubInner<Provider, Events, Eth>>,
+    /// The type that's used to spawn subscription tasks.
+    subscription_task_spawner: Box<dyn TaskSpawner>,
+}
+
+// === impl RethPubSub ===
+
+impl<Provider, Events, Eth> RethPubSub<Provider, Events, Eth> {
+    /// Creates a new, shareable instance.
+    ///
+    /// Subscription tasks are spawned via [tokio::task::spawn]
+    pub fn new(
+        provider: Provider,
+        chain_events: Events,
+        trace_api: TraceApi<Provider, Eth>,
+    ) -> Self {
+        Self::with_spawner(provider, chain_events, trace_api, Box::<TokioTaskExecutor>::default())
+    }
+
+    /// Creates a new, shareable instance.
+    pub fn with_spawner(
+        provider: Provider,
+        chain_events: Events,
+        trace_api: TraceApi<Provider, Eth>,
+        subscription_task_spawner: Box<dyn TaskSpawner>,
+    ) -> Self {
+        let inner = RethPubSubInner { provider, chain_events, trace_api };
+        Self { inner: Arc::new(inner), subscription_task_spawner }
+    }
+}
+
+#[async_trait::async_trait]
+impl<Provider, Events, Eth> RethPubSubApiServer for RethPubSub<Provider, Events, Eth>
+where
+    Provider: BlockReaderIdExt
+        + StateProviderFactory
+        + EvmEnvProvider
+        + ChainSpecProvider
+        + Clone
+        + 'static,
+    Events: CanonStateSubscriptions + Clone + 'static,
+    Eth: EthTransactions + 'static,
+{
+    /// Handler for `reth_subscribeBlocks`
+    async fn subscribe_blocks(
+        &self,
+        pending: PendingSubscriptionSink,
+        params: Option<BlocksSubscriptionParams>,
+    ) -> jsonrpsee::core::SubscriptionResult {
+        let params = params.unwrap_or_default();
+
+        // subscribe before reading the tip, so no block is missed while catching up
+        let notifications = self.inner.chain_events.subscribe_to_canonical_state();
+
+        let from = match params.from_block {
+            Some(block) => {
+                let tip = self.inner.provider.best_block_number()?;
+                let Some(from) = self.inner.provider.convert_block_number(block)? else {
+                    pending.reject(invalid_params_rpc_err("unknown block")).await;
+                    return Ok(())
+                };
+                if from > tip + 1 {
+                    pending.reject(invalid_params_rpc_err("block is beyond the tip")).await;
+                    return Ok(())
+                }
+                if tip + 1 - from > MAX_BLOCKS_CATCH_UP {
+                    pending
+                        .reject(invalid_params_rpc_err(format!(
+                            "can not catch up with more than {MAX_BLOCKS_CATCH_UP} blocks"
+                        )))
+                        .await;
+                    return Ok(())
+                }
+                Some(from)
+            }
+            None => None,
+        };
+
+        let sink = pending.accept().await?;
+        let streamer = BlockStreamer {
+            pubsub: self.inner.clone(),
+            sink,
+            include_traces: params.include_traces,
+            sent: VecDeque::new(),
+        };
+        self.subscription_task_spawner.spawn(Box::pin(async move {
+            let _ = streamer.run(notifications, from).await;
+        }));
+
+        Ok(())
+    }
+
+    /// Handler for `reth_subscribeStateChanges`
+    async fn subscribe_state_changes(
+        &self,
+        pending: PendingSubscriptionSink,
+        params: StateChangesParams,
+    ) -> jsonrpsee::core::SubscriptionResult {
+        if params.is_empty() {
+            pending.reject(invalid_params_rpc_err("no accounts or storage slots to watch")).await;
+            return Ok(())
+        }
+
+        let notifications = self.inner.chain_events.subscribe_to_canonical_state();
+        let sink = pending.accept().await?;
+        let watch = StateWatch::new(params);
+        self.subscription_task_spawner.spawn(Box::pin(async move {
+            let _ = watch.run(sink, notifications).await;
+        }));
+
+        Ok(())
+    }
+}
+
+impl<Provider, Events, Eth> std::fmt::Debug for RethPubSub<Provider, Events, Eth> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RethPubSub").finish_non_exhaustive()
+    }
+}
+
+/// Container type `RethPubSub`
+struct RethPubSubInner<Provider, Events, Eth> {
+    /// The provider that can interact with the chain.
+    provider: Provider,
+    /// A type that allows to create new event subscriptions.
+    chain_events: Events,
+    /// The trace handler, used if traces are requested.
+    trace_api: TraceApi<Provider, Eth>,
+}
+
+/// Streams the canonical chain to a single `reth_subscribeBlocks` subscription.
+struct BlockStreamer<Provider, Events, Eth> {
+    pubsub: Arc<RethPubSubInner<Provider, Events, Eth>>,
+    sink: SubscriptionSink,
+    include_traces: bool,
+    /// The most recently sent blocks without traces, with the last sent block as the last entry.
+    sent: VecDeque<BlockWithReceipts>,
+}
+
+impl<Provider, Events, Eth> BlockStreamer<Provider, Events, Eth>
+where
+    Provider: BlockReaderIdExt
+        + StateProviderFactory
+        + EvmEnvProvider
+        + ChainSpecProvider
+        + Clone
+        + 'static,
+    Eth: EthTransactions + 'static,
+{
+    /// Streams the blocks starting at `from`, then the blocks of the notifications.
+    async fn run(
+        mut self,
+        mut notifications: CanonStateNotifications,
+        from: Option<BlockNumber>,
+    ) -> Result<(), jsonrpsee::core::Error> {
+        if let Some(from) = from {
+            self.send_canonical_range(from).await?;
+        }
+
+        loop {
+            let notification = tokio::select! {
+                _ = self.sink.closed() => {
+                    // connection dropped
+                    break Ok(())
+                },
+                notification = notifications.recv() => notification,
+            };
+            match notification {
+                Ok(notification) => self.on_notification(notification).await?,
+                // the missed blocks are read from the database
+                Err(RecvError::Lagged(_)) => self.catch_up().await?,
+                Err(RecvError::Closed) => break Ok(()),
+            }
+        }
+    }
+
+    /// Streams the reverted and committed blocks of a notification.
+    async fn on_notification(
+        &mut self,
+        notification: CanonStateNotification,
+    ) -> Result<(), jsonrpsee::core::Error> {
+        if let Some(old) = notification.reverted() {
+            for block in old.blocks().values().rev() {
+                if self.last_sent_hash() == Some(block.hash) {
+                    self.send_removed().await?;
+                }
+            }
+        }
+
+        let Some(new) = notification.committed() else { return Ok(()) };
+        for (number, block) in new.blocks() {
+            if let Some(last) = self.sent.back() {
+                let last_number = last.block.header.number.unwrap_or_default().to::<u64>();
+                if *number <= last_number {
+                    // already sent while catching up
+                    continue
+                }
+                if *number != last_number + 1 || Some(block.parent_hash) != last.block.header.hash {
+                    // the notification does not connect to the sent blocks
+                    return self.catch_up().await
+                }
+            }
+            let receipts = new.state().receipts(*number).to_vec();
+            self.send_block(block.block.clone(), receipts).await?;
+        }
+        Ok(())
+    }
+
+    /// Streams the sent blocks that are no longer canonical as removed, and all canonical blocks
+    /// after the last sent block.
+    async fn catch_up(&mut self) -> Result<(), jsonrpsee::core::Error> {
+        while let Some(hash) = self.last_sent_hash() {
+            let number = self.sent.back().and_then(|last| last.block.header.number);
+            let number = number.unwrap_or_default().to::<u64>();
+            if self.pubsub.provider.block_hash(number).map_err(EthApiError::from)? == Some(hash) {
+                return self.send_canonical_range(number + 1).await
+            }
+            self.send_removed().await?;
+        }
+        Ok(())
+    }
+
+    /// Streams the canonical blocks of the database from `from` up to the current tip.
+    async fn send_canonical_range(
+        &mut self,
+        from: BlockNumber,
+    ) -> Result<(), jsonrpsee::core::Error> {
+        let provider = &self.pubsub.provider;
+        let mut number = from;
+        while let Some(hash) = provider.block_hash(number).map_err(EthApiError::from)? {
+            let block = provider.block(hash.into()).map_err(EthApiError::from)?;
+            let receipts = provider.receipts_by_block(hash.into()).map_err(EthApiError::from)?;
+            let Some(block) = block else { break };
+            self.send_block(block.seal(hash), receipts.unwrap_or_default()).await?;
+            number += 1;
+        }
+        Ok(())
+    }
+
+    /// Sends a canonical block.
+    async fn send_block(
+        &mut self,
+        block: SealedBlock,
+        receipts: Vec<Receipt>,
+    ) -> Result<(), jsonrpsee::core::Error> {
+        let hash = block.hash;
+        let mut message = self.block_with_receipts(block, receipts)?;
+        if self.include_traces {
+            message.traces = self.pubsub.trace_api.trace_block(BlockId::Hash(hash.into())).await?;
+        }
+        self.send(&message).await?;
+
+        message.traces = None;
+        self.sent.push_back(message);
+        if self.sent.len() > MAX_SENT_BLOCKS {
+            self.sent.pop_front();
+        }
+        Ok(())
+    }
+
+    /// Sends the last sent block as removed.
+    async fn send_removed(&mut self) -> Result<(), jsonrpsee::core::Error> {
+        let Some(mut message) = self.sent.pop_back() else { return Ok(()) };
+        message.removed = true;
+        self.send(&message).await
+    }
+
+    async fn send(&self, message: &BlockWithReceipts) -> Result<(), jsonrpsee::core::Error> {
+        let msg = SubscriptionMessage::from_json(message)?;
+        self.sink
+            .send(msg)
+            .await
+            .map_err(|_| jsonrpsee::core::Error::Custom("subscription closed".to_string()))
+    }
+
+    fn last_sent_hash(&self) -> Option<H256> {
+        self.sent.back().and_then(|last| last.block.header.hash)
+    }
+
+    /// Converts the block and its receipts into the RPC message.
+    fn block_with_receipts(
+        &self,
+        block: SealedBlock,
+        receipts: Vec<Receipt>,
+    ) -> EthResult<BlockWithReceipts> {
+        let block_hash = block.hash;
+        let total_difficulty = self.pubsub.provider.header_td(&block_hash)?.unwrap_or_default();
+        let receipts = block
+            .body
+            .iter()
+            .cloned()
+            .zip(receipts.iter().cloned())
+            .enumerate()
+            .map(|(idx, (tx, receipt))| {
+                let meta = TransactionMeta {
+                    tx_hash: tx.hash,
+                    index: idx as u64,
+                    block_hash,
+                    block_number: block.number,
+                    base_fee: block.base_fee_per_gas,
+                    excess_blob_gas: block.excess_blob_gas,
+                };
+                build_transaction_receipt_with_block_receipts(tx, meta, receipt, &receipts)
+            })
+            .collect::<EthResult<Vec<_>>>()?;
+        let block = from_block(
+            block.into(),
+            total_difficulty,
+            BlockTransactionsKind::Full,
+            Some(block_hash),
+        )?;
+        Ok(BlockWithReceipts { block, receipts, traces: None, removed: false })
+    }
+}
+
+/// The accounts and storage slots watched by a `reth_subscribeStateChanges` subscription.
+#[derive(Debug, Default)]
+struct StateWatch {
+    accounts: HashSet<Address>,
+    /// The watched slots by account, all slots of the account are watched if empty.
+    storage: HashMap<Address, HashSet<U256>>,
+}
+
+impl StateWatch {
+    fn new(params: StateChangesParams) -> Self {
+        let storage = params
+            .storage
+            .into_iter()
+            .map(|(address, slots)| {
+                (address, slots.into_iter().map(|slot| U256::from_be_bytes(slot.0)).collect())
+            })
+            .collect();
+        Self { accounts: params.accounts.into_iter().collect(), storage }
+    }
+
+    /// Streams the changes of the watched state in the blocks of the notifications.
+    async fn run(
+        self,
+        sink: SubscriptionSink,
+        mut notifications: CanonStateNotifications,
+    ) -> Result<(), jsonrpsee::core::Error> {
+        loop {
+            let notification = tokio::select! {
+                _ = sink.closed() => {
+                    // connection dropped
+                    break Ok(())
+                },
+                notification = notifications.recv() => notification,
+            };
+            // the changes of missed blocks can not be recovered, so the subscription is closed
+            let Ok(notification) = notification else { break Ok(()) };
+            for changes in self.notification_changes(&notification) {
+                let msg = SubscriptionMessage::from_json(&changes)?;
+                if sink.send(msg).await.is_err() {
+                    return Ok(())
+                }
+            }
+        }
+    }
+
+    /// Returns the changes of the watched state in the reverted and committed blocks of a
+    /// notification.
+    fn notification_changes(&self, notification: &CanonStateNotification) -> Vec<StateChanges> {
+        let mut changes = Vec::new();
+        if let Some(old) = notification.reverted() {
+            for (number, block) in old.blocks().iter().rev() {
+                let matched = self.matched_changes(old.state(), *number);
+                if !matched.is_empty() {
+                    changes.push(matched.into_state_changes(block.hash, None));
+                }
+            }
+        }
+        if let Some(new) = notification.committed() {
+            for (number, block) in new.blocks() {
+                let matched = self.matched_changes(new.state(), *number);
+                if !matched.is_empty() {
+                    let state = new.state_at_block(*number).unwrap_or_default();
+                    changes.push(matched.into_state_changes(block.hash, Some(&state)));
+                }
+            }
+        }
+        changes
+    }
+
+    /// Returns the watched accounts and slots that were changed by the block.
+    fn matched_changes(&self, state: &PostState, number: BlockNumber) -> MatchedChanges {
+        let mut matched = MatchedChanges { number, ..Default::default() };
+        if let Some(accounts) = state.account_changes().inner.get(&number) {
+            matched.accounts = accounts
+                .iter()
+                .filter(|(address, _)| self.accounts.contains(*address))
+                .map(|(address, before)| (*address, *before))
+                .collect();
+        }
+        for (address, transition) in
+            state.storage_changes().inner.get(&number).into_iter().flatten()
+        {
+            let Some(slots) = self.storage.get(address) else { continue };
+            if transition.wipe.is_wiped() {
+                matched.storage_wiped.push(*address);
+            }
+            for (slot, before) in &transition.storage {
+                if slots.is_empty() || slots.contains(slot) {
+                    matched.storage.push((*address, *slot, *before));
+                }
+            }
+        }
+        matched
+    }
+}
+
+/// The watched accounts and slots that were changed by a block, with their values before the
+/// block.
+#[derive(Debug, Default)]
+struct MatchedChanges {
+    number: BlockNumber,
+    accounts: Vec<(Address, Option<Account>)>,
+    storage: Vec<(Address, U256, U256)>,
+    storage_wiped: Vec<Address>,
+}
+
+impl MatchedChanges {
+    fn is_empty(&self) -> bool {
+        self.accounts.is_empty() && self.storage.is_empty() && self.storage_wiped.is_empty()
+    }
+
+    /// Converts the changes into the RPC message, with the values of the state after the block, or
+    /// with the values before the block if the block was removed.
+    fn into_state_changes(self, block_hash: H256, after: Option<&PostState>) -> StateChanges {
+        let accounts = self
+            .accounts
+            .into_iter()
+            .map(|(address, before)| {
+                let account = match after {
+                    Some(state) => state.account(&address).copied().flatten(),
+                    None => before,
+                }
+                .unwrap_or_default();
+                AccountChange {
+                    address,
+                    balance: account.balance,
+                    nonce: U64::from(account.nonce),
+                    code_hash: account.bytecode_hash.unwrap_or(KECCAK_EMPTY),
+                }
+            })
+            .collect();
+        let storage = self
+            .storage
+            .into_iter()
+            .map(|(address, slot, before)| {
+                let value = match after {
+                    Some(state) => state
+                        .account_storage(&address)
+                        .and_then(|storage| storage.storage.get(&slot))
+                        .copied()
+                        .unwrap_or_default(),
+                    None => before,
+                };
+                StorageChange { address, slot: H256(slot.to_be_bytes()), value }
+            })
+            .collect();
+        StateChanges {
+            block_number: U64::from(self.number),
+            block_hash,
+            accounts,
+            storage,
+            storage_wiped: self.storage_wiped,
+            removed: after.is_none(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn state_watch_filters_changes() {
+        let watched = Address::from_low_u64_be(1);
+        let other = Address::from_low_u64_be(2);
+        let watch = StateWatch::new(StateChangesParams {
+            accounts: vec![watched],
+            storage: BTreeMap::from([(watched, vec![H256::from_low_u64_be(1)])]),
+        });
+
+        let mut state = PostState::new();
+        let old = Account { nonce: 1, ..Default::default() };
+        let new = Account { nonce: 2, ..Default::default() };
+        state.change_account(1, watched, old, new);
+        state.change_account(1, other, old, new);
+        state.change_storage(
+            1,
+            watched,
+            BTreeMap::from([
+                (U256::from(1), (U256::ZERO, U256::from(10))),
+                (U256::from(2), (U256::ZERO, U256::from(20))),
+            ]),
+        );
+
+        assert!(watch.matched_changes(&state, 2).is_empty());
+
+        let changes =
+            watch.matched_changes(&state, 1).into_state_changes(H256::zero(), Some(&state));
+        assert_eq!(changes.accounts.len(), 1);
+        assert_eq!(changes.accounts[0].address, watched);
+        assert_eq!(changes.accounts[0].nonce, U64::from(2));
+        assert_eq!(
+            changes.storage,
+            vec![StorageChange {
+                address: watched,
+                slot: H256::from_low_u64_be(1),
+                value: U256::from(10)
+            }]
+        );
+        assert!(!changes.removed);
+
+        // removed blocks are streamed with the values before the block
+        let changes = watch.matched_changes(&state, 1).into_state_changes(H256::zero(), None);
+        assert_eq!(changes.accounts[0].nonce, U64::from(1));
+        assert_eq!(changes.storage[0].value, U256::ZERO);
+        assert!(changes.removed);
+    }
+}
@@ -1,7 +1,10 @@
 use crate::{
     eth::{
         error::{EthApiError, EthResult},
-        revm_utils::{inspect, inspect_and_return_db, prepare_call_env, EvmOverrides},
+        revm_utils::{
+            apply_pre_block_system_calls, inspect, inspect_and_return_db, prepare_call_env,
+            EvmOverrides,
+        },
         utils::recover_raw_transaction,
         EthTransactions,
     },
@@ -314,7 +317,9 @@ where
         let state_at = block.parent_hash;
 
         let block_hash = block.hash;
+        let header = block.header.unseal();
         let transactions = block.body;
+        let chain_spec = self.provider().chain_spec();
 
         // replay all transactions of the block
         self.inner
@@ -322,6 +327,7 @@ where
             .spawn_with_state_at_block(state_at.into(), move |state| {
                 let mut results = Vec::with_capacity(transactions.len());
                 let mut db = SubState::new(State::new(state));
+                apply_pre_block_system_calls(&mut db, &chain_spec, &header)?;
 
                 let mut transactions = transactions.into_iter().enumerate().peekable();
 
@@ -30,7 +30,7 @@ impl<Pool> TxPoolApi<Pool>
 where
     Pool: TransactionPool + 'static,
 {
-    fn content(&self) -> TxpoolContent {
+    fn content(&self, transactions: AllPoolTransactions<Pool::Transaction>) -> TxpoolContent {
         #[inline]
         fn insert<T: PoolTransaction>(
             tx: &T,
@@ -43,7 +43,7 @@ where
             entry.insert(key, tx);
         }
 
-        let AllPoolTransactions { pending, queued } = self.pool.all_transactions();
+        let AllPoolTransactions { pending, queued } = transactions;
 
         let mut content = TxpoolContent::default();
         for pending in pending {
@@ -126,7 +126,8 @@ where
     /// Handler for `txpool_contentFrom`
     async fn txpool_content_from(&self, from: Address) -> Result<TxpoolContentFrom> {
         trace!(target: "rpc::eth", ?from, "Serving txpool_contentFrom");
-        Ok(self.content().remove_from(&from))
+        let transactions = self.pool.get_transactions_by_sender_grouped(from);
+        Ok(self.content(transactions).remove_from(&from))
     }
 
     /// Returns the details of all transactions currently pending for inclusion in the next
@@ -136,7 +137,7 @@ where
     /// Handler for `txpool_inspect`
     async fn txpool_content(&self) -> Result<TxpoolContent> {
         trace!(target: "rpc::eth", "Serving txpool_inspect");
-        Ok(self.content())
+        Ok(self.content(self.pool.all_transactions()))
     }
 }
 
@@ -1,7 +1,7 @@
 use crate::pipeline::PipelineEvent;
 use reth_interfaces::{
     consensus, db::DatabaseError as DbError, executor, p2p::error::DownloadError,
-    provider::ProviderError,
+    provider::ProviderError, ErrorKind,
 };
 use reth_primitives::SealedHeader;
 use thiserror::Error;
@@ -83,16 +83,20 @@ pub enum StageError {
 
 impl StageError {
     /// If the error is fatal the pipeline will stop.
+    ///
+    /// Database errors are fatal unless they are [retryable](ErrorKind::is_retryable), internal
+    /// errors are fatal if they indicate a [corrupted](ErrorKind::Corruption) database.
     pub fn is_fatal(&self) -> bool {
-        matches!(
-            self,
-            StageError::Database(_) |
-                StageError::Download(_) |
-                StageError::DatabaseIntegrity(_) |
-                StageError::StageCheckpoint(_) |
-                StageError::ChannelClosed |
-                StageError::Fatal(_)
-        )
+        match self {
+            StageError::Database(err) => !err.is_retryable(),
+            StageError::DatabaseIntegrity(err) => !err.is_retryable(),
+            StageError::Internal(err) => err.kind() == ErrorKind::Corruption,
+            StageError::Download(_) |
+            StageError::StageCheckpoint(_) |
+            StageError::ChannelClosed |
+            StageError::Fatal(_) => true,
+            _ => false,
+        }
     }
 }
 
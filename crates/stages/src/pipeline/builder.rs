@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use crate::{pipeline::BoxedStage, MetricEventsSender, Pipeline, Stage, StageSet};
+use crate::{pipeline::BoxedStage, MetricEventsSender, Pipeline, Stage, StageHook, StageSet};
 use reth_db::database::Database;
 use reth_primitives::{stage::StageId, BlockNumber, ChainSpec, H256};
 use tokio::sync::watch;
@@ -18,6 +18,8 @@ where
     /// A receiver for the current chain tip to sync to.
     tip_tx: Option<watch::Sender<H256>>,
     metrics_tx: Option<MetricEventsSender>,
+    /// Hooks invoked before and after every stage run.
+    hooks: Vec<Arc<dyn StageHook>>,
 }
 
 impl<DB> PipelineBuilder<DB>
@@ -67,11 +69,19 @@ where
         self
     }
 
+    /// Add a hook that is invoked before and after every stage run.
+    ///
+    /// Hooks are invoked in the order they were added.
+    pub fn with_stage_hook(mut self, hook: Arc<dyn StageHook>) -> Self {
+        self.hooks.push(hook);
+        self
+    }
+
     /// Builds the final [`Pipeline`] using the given database.
     ///
     /// Note: it's expected that this is either an [Arc](std::sync::Arc) or an Arc wrapper type.
     pub fn build(self, db: DB, chain_spec: Arc<ChainSpec>) -> Pipeline<DB> {
-        let Self { stages, max_block, tip_tx, metrics_tx } = self;
+        let Self { stages, max_block, tip_tx, metrics_tx, hooks } = self;
         Pipeline {
             db,
             chain_spec,
@@ -81,13 +91,20 @@ where
             listeners: Default::default(),
             progress: Default::default(),
             metrics_tx,
+            hooks,
         }
     }
 }
 
 impl<DB: Database> Default for PipelineBuilder<DB> {
     fn default() -> Self {
-        Self { stages: Vec::new(), max_block: None, tip_tx: None, metrics_tx: None }
+        Self {
+            stages: Vec::new(),
+            max_block: None,
+            tip_tx: None,
+            metrics_tx: None,
+            hooks: Vec::new(),
+        }
     }
 }
 
@@ -96,6 +113,7 @@ impl<DB: Database> std::fmt::Debug for PipelineBuilder<DB> {
         f.debug_struct("PipelineBuilder")
             .field("stages", &self.stages.iter().map(|stage| stage.id()).collect::<Vec<StageId>>())
             .field("max_block", &self.max_block)
+            .field("hooks", &self.hooks.len())
             .finish()
     }
 }
@@ -0,0 +1,46 @@
+use crate::{ExecInput, ExecOutput, StageError, UnwindInput, UnwindOutput};
+use reth_primitives::stage::StageId;
+
+/// A hook that is invoked before and after every run and every unwind step of a stage.
+///
+/// The pipeline awaits the hooks, so it is paused until a hook returns. This can be used to
+/// coordinate external systems with the pipeline, for example to pause backups of the database
+/// while a stage runs and commits its progress, or to notify a monitoring system.
+///
+/// The pipeline doesn't hold a database transaction while a hook is invoked, so hooks can safely
+/// open their own transactions.
+///
+/// The block range of a run can be derived from its [ExecInput], see
+/// [ExecInput::next_block_range].
+#[async_trait::async_trait]
+pub trait StageHook: Send + Sync + 'static {
+    /// Invoked before the stage is executed with the given input.
+    async fn pre_stage(&self, _stage_id: StageId, _input: ExecInput) {}
+
+    /// Invoked after the stage was executed with the given input.
+    ///
+    /// If the run succeeded, its progress was committed to the database before this is invoked.
+    /// If it failed, its changes were discarded.
+    async fn post_stage(
+        &self,
+        _stage_id: StageId,
+        _input: ExecInput,
+        _result: Result<&ExecOutput, &StageError>,
+    ) {
+    }
+
+    /// Invoked before the stage is unwound with the given input.
+    async fn pre_unwind(&self, _stage_id: StageId, _input: UnwindInput) {}
+
+    /// Invoked after the stage was unwound with the given input.
+    ///
+    /// If the unwind succeeded, it was committed to the database before this is invoked. If it
+    /// failed, its changes were discarded.
+    async fn post_unwind(
+        &self,
+        _stage_id: StageId,
+        _input: UnwindInput,
+        _result: Result<&UnwindOutput, &StageError>,
+    ) {
+    }
+}
@@ -18,12 +18,14 @@ use tracing::*;
 mod builder;
 mod ctrl;
 mod event;
+mod hooks;
 mod progress;
 mod set;
 
 pub use crate::pipeline::ctrl::ControlFlow;
 pub use builder::*;
 pub use event::*;
+pub use hooks::*;
 use progress::*;
 pub use set::*;
 
@@ -107,6 +109,8 @@ pub struct Pipeline<DB: Database> {
     /// A receiver for the current chain tip to sync to.
     tip_tx: Option<watch::Sender<H256>>,
     metrics_tx: Option<MetricEventsSender>,
+    /// Hooks invoked before and after every stage run.
+    hooks: Vec<Arc<dyn StageHook>>,
 }
 
 impl<DB> Pipeline<DB>
@@ -264,14 +268,14 @@ where
         let unwind_pipeline = self.stages.iter_mut().rev();
 
         let factory = ProviderFactory::new(&self.db, self.chain_spec.clone());
-        let mut provider_rw = factory.provider_rw().map_err(PipelineError::Interface)?;
 
         for stage in unwind_pipeline {
             let stage_id = stage.id();
             let span = info_span!("Unwinding", stage = %stage_id);
             let _enter = span.enter();
 
-            let mut checkpoint = provider_rw.get_stage_checkpoint(stage_id)?.unwrap_or_default();
+            let mut checkpoint =
+                factory.provider()?.get_stage_checkpoint(stage_id)?.unwrap_or_default();
             if checkpoint.block_number < to {
                 debug!(target: "sync::pipeline", from = %checkpoint, %to, "Unwind point too far for stage");
                 self.listeners.notify(PipelineEvent::Skipped { stage_id });
@@ -282,7 +286,11 @@ where
             while checkpoint.block_number > to {
                 let input = UnwindInput { checkpoint, unwind_to: to, bad_block };
                 self.listeners.notify(PipelineEvent::Unwinding { stage_id, input });
+                for hook in &self.hooks {
+                    hook.pre_unwind(stage_id, input).await;
+                }
 
+                let provider_rw = factory.provider_rw().map_err(PipelineError::Interface)?;
                 let output = stage.unwind(&provider_rw, input).await;
                 match output {
                     Ok(unwind_output) => {
@@ -306,14 +314,23 @@ where
                         }
                         provider_rw.save_stage_checkpoint(stage_id, checkpoint)?;
 
-                        self.listeners
-                            .notify(PipelineEvent::Unwound { stage_id, result: unwind_output });
+                        self.listeners.notify(PipelineEvent::Unwound {
+                            stage_id,
+                            result: unwind_output.clone(),
+                        });
 
                         provider_rw.commit()?;
-                        provider_rw = factory.provider_rw().map_err(PipelineError::Interface)?;
+
+                        for hook in &self.hooks {
+                            hook.post_unwind(stage_id, input, Ok(&unwind_output)).await;
+                        }
                     }
                     Err(err) => {
+                        drop(provider_rw);
                         self.listeners.notify(PipelineEvent::Error { stage_id });
+                        for hook in &self.hooks {
+                            hook.post_unwind(stage_id, input, Err(&err)).await;
+                        }
                         return Err(PipelineError::Stage(StageError::Fatal(Box::new(err))))
                     }
                 }
@@ -336,10 +353,9 @@ where
         let target = self.max_block.or(previous_stage);
 
         let factory = ProviderFactory::new(&self.db, self.chain_spec.clone());
-        let mut provider_rw = factory.provider_rw().map_err(PipelineError::Interface)?;
 
         loop {
-            let prev_checkpoint = provider_rw.get_stage_checkpoint(stage_id)?;
+            let prev_checkpoint = factory.provider()?.get_stage_checkpoint(stage_id)?;
 
             let stage_reached_max_block = prev_checkpoint
                 .zip(self.max_block)
@@ -367,10 +383,22 @@ where
                 checkpoint: prev_checkpoint,
             });
 
-            match stage
-                .execute(&provider_rw, ExecInput { target, checkpoint: prev_checkpoint })
-                .await
-            {
+            let span = debug_span!(
+                target: "sync::pipeline",
+                "stage_run",
+                stage = %stage_id,
+                checkpoint = prev_checkpoint.map(|progress| progress.block_number),
+                target_block = target
+            );
+            let input = ExecInput { target, checkpoint: prev_checkpoint };
+            for hook in &self.hooks {
+                hook.pre_stage(stage_id, input).await;
+            }
+
+            // the write transaction is only opened after the hooks returned, so that it isn't held
+            // while they run
+            let provider_rw = factory.provider_rw().map_err(PipelineError::Interface)?;
+            match stage.execute(&provider_rw, input).instrument(span).await {
                 Ok(out @ ExecOutput { checkpoint, done }) => {
                     made_progress |=
                         checkpoint.block_number != prev_checkpoint.unwrap_or_default().block_number;
@@ -399,8 +427,17 @@ where
                     });
 
                     // TODO: Make the commit interval configurable
-                    provider_rw.commit()?;
-                    provider_rw = factory.provider_rw().map_err(PipelineError::Interface)?;
+                    debug_span!(
+                        target: "sync::pipeline",
+                        "commit",
+                        stage = %stage_id,
+                        block_number = checkpoint.block_number
+                    )
+                    .in_scope(|| provider_rw.commit())?;
+
+                    for hook in &self.hooks {
+                        hook.post_stage(stage_id, input, Ok(&out)).await;
+                    }
 
                     if done {
                         let block_number = checkpoint.block_number;
@@ -412,7 +449,12 @@ where
                     }
                 }
                 Err(err) => {
+                    // discard the changes of the failed run before the hooks are invoked
+                    drop(provider_rw);
                     self.listeners.notify(PipelineEvent::Error { stage_id });
+                    for hook in &self.hooks {
+                        hook.post_stage(stage_id, input, Err(&err)).await;
+                    }
 
                     let out = if let StageError::DetachedHead { local_head, header, error } = err {
                         warn!(target: "sync::pipeline", stage = %stage_id, ?local_head, ?header, ?error, "Stage encountered detached head");
@@ -434,8 +476,8 @@ where
                         // FIXME: When handling errors, we do not commit the database transaction.
                         // This leads to the Merkle stage not clearing its
                         // checkpoint, and restarting from an invalid place.
-                        drop(provider_rw);
-                        provider_rw = factory.provider_rw().map_err(PipelineError::Interface)?;
+                        let provider_rw =
+                            factory.provider_rw().map_err(PipelineError::Interface)?;
                         provider_rw
                             .save_stage_checkpoint_progress(StageId::MerkleExecute, vec![])?;
                         provider_rw.save_stage_checkpoint(
@@ -500,6 +542,7 @@ impl<DB: Database> std::fmt::Debug for Pipeline<DB> {
             .field("stages", &self.stages.iter().map(|stage| stage.id()).collect::<Vec<StageId>>())
             .field("max_block", &self.max_block)
             .field("listeners", &self.listeners)
+            .field("hooks", &self.hooks.len())
             .finish()
     }
 }
@@ -600,6 +643,86 @@ mod tests {
         );
     }
 
+    /// Runs and unwinds a pipeline with a hook that opens its own write transactions.
+    #[tokio::test]
+    async fn run_pipeline_with_hooks() {
+        use reth_db::transaction::DbTx;
+
+        #[derive(Debug)]
+        struct RecordingHook {
+            db: Arc<reth_db::DatabaseEnv>,
+            calls: std::sync::Mutex<Vec<(&'static str, StageId, bool)>>,
+        }
+
+        impl RecordingHook {
+            fn record(&self, call: &'static str, stage_id: StageId, ok: bool) {
+                // only one write transaction can be open at a time, so this would block forever
+                // if the pipeline held one while invoking the hook
+                self.db.tx_mut().unwrap().commit().unwrap();
+                self.calls.lock().unwrap().push((call, stage_id, ok));
+            }
+        }
+
+        #[async_trait::async_trait]
+        impl StageHook for RecordingHook {
+            async fn pre_stage(&self, stage_id: StageId, _input: ExecInput) {
+                self.record("pre_stage", stage_id, true);
+            }
+
+            async fn post_stage(
+                &self,
+                stage_id: StageId,
+                _input: ExecInput,
+                result: Result<&ExecOutput, &StageError>,
+            ) {
+                self.record("post_stage", stage_id, result.is_ok());
+            }
+
+            async fn pre_unwind(&self, stage_id: StageId, _input: UnwindInput) {
+                self.record("pre_unwind", stage_id, true);
+            }
+
+            async fn post_unwind(
+                &self,
+                stage_id: StageId,
+                _input: UnwindInput,
+                result: Result<&UnwindOutput, &StageError>,
+            ) {
+                self.record("post_unwind", stage_id, result.is_ok());
+            }
+        }
+
+        let db = create_test_rw_db();
+        let hook = Arc::new(RecordingHook { db: db.clone(), calls: Default::default() });
+
+        let mut pipeline = Pipeline::builder()
+            .add_stage(
+                TestStage::new(StageId::Other("A"))
+                    .add_exec(Ok(ExecOutput { checkpoint: StageCheckpoint::new(10), done: true }))
+                    .add_unwind(Ok(UnwindOutput { checkpoint: StageCheckpoint::new(1) })),
+            )
+            .add_stage(TestStage::new(StageId::Other("B")).add_exec(Err(
+                StageError::DatabaseIntegrity(ProviderError::BlockBodyIndicesNotFound(5)),
+            )))
+            .with_max_block(10)
+            .with_stage_hook(hook.clone())
+            .build(db, MAINNET.clone());
+
+        assert!(pipeline.run().await.is_err());
+        pipeline.unwind(1, None).await.expect("Could not unwind pipeline");
+        assert_eq!(
+            *hook.calls.lock().unwrap(),
+            vec![
+                ("pre_stage", StageId::Other("A"), true),
+                ("post_stage", StageId::Other("A"), true),
+                ("pre_stage", StageId::Other("B"), true),
+                ("post_stage", StageId::Other("B"), false),
+                ("pre_unwind", StageId::Other("A"), true),
+                ("post_unwind", StageId::Other("A"), true),
+            ]
+        );
+    }
+
     /// Unwinds a simple pipeline.
     #[tokio::test]
     async fn unwind_pipeline() {
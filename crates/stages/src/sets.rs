@@ -49,6 +49,7 @@ use reth_interfaces::{
     consensus::Consensus,
     p2p::{bodies::downloader::BodyDownloader, headers::downloader::HeaderDownloader},
 };
+use reth_primitives::BlockNumHash;
 use reth_provider::ExecutorFactory;
 use std::sync::Arc;
 
@@ -99,6 +100,17 @@ impl<H, B, EF> DefaultStages<H, B, EF> {
             executor_factory,
         }
     }
+
+    /// Sets the trusted checkpoints the downloaded header chain must pass through.
+    ///
+    /// See [HeaderStage::with_trusted_checkpoints].
+    pub fn with_trusted_header_checkpoints(
+        mut self,
+        checkpoints: impl IntoIterator<Item = BlockNumHash>,
+    ) -> Self {
+        self.online = self.online.with_trusted_header_checkpoints(checkpoints);
+        self
+    }
 }
 
 impl<H, B, EF> DefaultStages<H, B, EF>
@@ -140,6 +152,8 @@ pub struct OnlineStages<H, B> {
     header_downloader: H,
     /// The block body downloader
     body_downloader: B,
+    /// The trusted checkpoints for the headers stage.
+    header_checkpoints: Vec<BlockNumHash>,
 }
 
 impl<H, B> OnlineStages<H, B> {
@@ -150,7 +164,24 @@ impl<H, B> OnlineStages<H, B> {
         header_downloader: H,
         body_downloader: B,
     ) -> Self {
-        Self { header_mode, consensus, header_downloader, body_downloader }
+        Self {
+            header_mode,
+            consensus,
+            header_downloader,
+            body_downloader,
+            header_checkpoints: Vec::new(),
+        }
+    }
+
+    /// Sets the trusted checkpoints the downloaded header chain must pass through.
+    ///
+    /// See [HeaderStage::with_trusted_checkpoints].
+    pub fn with_trusted_header_checkpoints(
+        mut self,
+        checkpoints: impl IntoIterator<Item = BlockNumHash>,
+    ) -> Self {
+        self.header_checkpoints = checkpoints.into_iter().collect();
+        self
     }
 }
 
@@ -193,7 +224,10 @@ where
 {
     fn builder(self) -> StageSetBuilder<DB> {
         StageSetBuilder::default()
-            .add_stage(HeaderStage::new(self.header_downloader, self.header_mode))
+            .add_stage(
+                HeaderStage::new(self.header_downloader, self.header_mode)
+                    .with_trusted_checkpoints(self.header_checkpoints),
+            )
             .add_stage(TotalDifficultyStage::new(self.consensus.clone()))
             .add_stage(BodyStage { downloader: self.body_downloader, consensus: self.consensus })
     }
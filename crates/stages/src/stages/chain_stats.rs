@@ -0,0 +1,244 @@
+use crate::{ExecInput, ExecOutput, Stage, StageError, UnwindInput, UnwindOutput};
+use reth_db::{
+    cursor::{DbCursorRO, DbCursorRW},
+    database::Database,
+    tables,
+    transaction::{DbTx, DbTxMut},
+    DatabaseError,
+};
+use reth_primitives::stage::{EntitiesCheckpoint, StageCheckpoint, StageId};
+use reth_provider::DatabaseProviderRW;
+use tracing::*;
+
+/// The chain stats stage.
+///
+/// This stage walks over inserted headers and maintains the cumulative gas used of the chain at
+/// each block in the [`CumulativeGasUsed`][reth_db::tables::CumulativeGasUsed] table, which backs
+/// the range queries of [`ChainStatsReader`][reth_provider::ChainStatsReader].
+///
+/// If the entry for the checkpoint block is missing, e.g. because the stage was enabled on an
+/// already synced node, the table is rebuilt from genesis.
+#[derive(Debug, Clone)]
+pub struct ChainStatsStage {
+    /// The number of table entries to commit at once
+    commit_threshold: u64,
+}
+
+impl ChainStatsStage {
+    /// Create a new chain stats stage
+    pub fn new(commit_threshold: u64) -> Self {
+        Self { commit_threshold }
+    }
+
+    /// The [StageId] of the chain stats stage.
+    pub const ID: StageId = StageId::Other("ChainStats");
+}
+
+impl Default for ChainStatsStage {
+    fn default() -> Self {
+        Self { commit_threshold: 100_000 }
+    }
+}
+
+#[async_trait::async_trait]
+impl<DB: Database> Stage<DB> for ChainStatsStage {
+    /// Return the id of the stage
+    fn id(&self) -> StageId {
+        Self::ID
+    }
+
+    /// Write cumulative gas used entries
+    async fn execute(
+        &mut self,
+        provider: &DatabaseProviderRW<'_, &DB>,
+        input: ExecInput,
+    ) -> Result<ExecOutput, StageError> {
+        let tx = provider.tx_ref();
+        if input.target_reached() {
+            return Ok(ExecOutput::done(input.checkpoint()))
+        }
+
+        let (range, is_final_range) = input.next_block_range_with_threshold(self.commit_threshold);
+        let end_block = *range.end();
+
+        let mut cursor_gas = tx.cursor_write::<tables::CumulativeGasUsed>()?;
+        let mut cursor_headers = tx.cursor_read::<tables::Headers>()?;
+
+        // Entries past the checkpoint may already have been written by the blockchain tree, so
+        // they are overwritten rather than appended.
+        let last_block = input.checkpoint().block_number;
+        let (mut cumulative_gas_used, range) = match cursor_gas.seek_exact(last_block)? {
+            Some((_, cumulative_gas_used)) => (cumulative_gas_used, range),
+            None => (0, 0..=end_block),
+        };
+        debug!(target: "sync::stages::chain_stats", start_block = range.start(), end_block, cumulative_gas_used, "Commencing sync");
+
+        for entry in cursor_headers.walk_range(range)? {
+            let (block_number, header) = entry?;
+            cumulative_gas_used += header.gas_used;
+            cursor_gas.upsert(block_number, cumulative_gas_used)?;
+        }
+
+        Ok(ExecOutput {
+            checkpoint: StageCheckpoint::new(end_block)
+                .with_entities_stage_checkpoint(stage_checkpoint(provider)?),
+            done: is_final_range,
+        })
+    }
+
+    /// Unwind the stage.
+    async fn unwind(
+        &mut self,
+        provider: &DatabaseProviderRW<'_, &DB>,
+        input: UnwindInput,
+    ) -> Result<UnwindOutput, StageError> {
+        let (_, unwind_to, _) = input.unwind_block_range_with_threshold(self.commit_threshold);
+
+        provider.unwind_table_by_num::<tables::CumulativeGasUsed>(unwind_to)?;
+
+        Ok(UnwindOutput {
+            checkpoint: StageCheckpoint::new(unwind_to)
+                .with_entities_stage_checkpoint(stage_checkpoint(provider)?),
+        })
+    }
+}
+
+fn stage_checkpoint<DB: Database>(
+    provider: &DatabaseProviderRW<'_, DB>,
+) -> Result<EntitiesCheckpoint, DatabaseError> {
+    Ok(EntitiesCheckpoint {
+        processed: provider.tx_ref().entries::<tables::CumulativeGasUsed>()? as u64,
+        total: provider.tx_ref().entries::<tables::Headers>()? as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        stage_test_suite_ext, ExecuteStageTestRunner, StageTestRunner, TestRunnerError,
+        TestTransaction, UnwindStageTestRunner,
+    };
+    use reth_interfaces::test_utils::{
+        generators,
+        generators::{random_header, random_header_range},
+    };
+    use reth_primitives::{BlockNumber, SealedHeader};
+    use reth_provider::ChainStatsReader;
+
+    stage_test_suite_ext!(ChainStatsTestRunner, chain_stats);
+
+    #[tokio::test]
+    async fn execute_rebuilds_from_genesis() {
+        let runner = ChainStatsTestRunner::default();
+        let mut rng = generators::rng();
+        let headers = random_header_range(&mut rng, 0..100, Default::default());
+        runner.tx.insert_headers(headers.iter()).expect("failed to insert headers");
+
+        // the checkpoint is ahead of the table
+        let input = ExecInput { target: Some(99), checkpoint: Some(StageCheckpoint::new(50)) };
+        let result = runner.execute(input).await.unwrap();
+        assert!(matches!(result, Ok(ExecOutput { done: true, .. })));
+
+        let provider = runner.tx.inner();
+        let total = headers.iter().map(|header| header.gas_used).sum::<u64>();
+        assert_eq!(provider.cumulative_gas_used(99).unwrap(), Some(total));
+        assert_eq!(
+            provider.gas_used_in_range(10..=20).unwrap(),
+            Some(headers[10..=20].iter().map(|header| header.gas_used).sum())
+        );
+    }
+
+    struct ChainStatsTestRunner {
+        tx: TestTransaction,
+        commit_threshold: u64,
+    }
+
+    impl Default for ChainStatsTestRunner {
+        fn default() -> Self {
+            Self { tx: Default::default(), commit_threshold: 500 }
+        }
+    }
+
+    impl StageTestRunner for ChainStatsTestRunner {
+        type S = ChainStatsStage;
+
+        fn tx(&self) -> &TestTransaction {
+            &self.tx
+        }
+
+        fn stage(&self) -> Self::S {
+            ChainStatsStage::new(self.commit_threshold)
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ExecuteStageTestRunner for ChainStatsTestRunner {
+        type Seed = Vec<SealedHeader>;
+
+        fn seed_execution(&mut self, input: ExecInput) -> Result<Self::Seed, TestRunnerError> {
+            let mut rng = generators::rng();
+            let start = input.checkpoint().block_number;
+            let head = random_header(&mut rng, start, None);
+            self.tx.insert_headers(std::iter::once(&head))?;
+            self.tx.commit(|tx| {
+                let gas_used = tx
+                    .cursor_read::<tables::CumulativeGasUsed>()?
+                    .last()?
+                    .map(|(_, v)| v)
+                    .unwrap_or_default();
+                tx.put::<tables::CumulativeGasUsed>(head.number, gas_used + head.gas_used)
+            })?;
+
+            let end = input.target.unwrap_or_default() + 1;
+            if start + 1 >= end {
+                return Ok(Vec::default())
+            }
+
+            let mut headers = random_header_range(&mut rng, start + 1..end, head.hash());
+            self.tx.insert_headers(headers.iter())?;
+            headers.insert(0, head);
+            Ok(headers)
+        }
+
+        fn validate_execution(
+            &self,
+            input: ExecInput,
+            output: Option<ExecOutput>,
+        ) -> Result<(), TestRunnerError> {
+            let initial_checkpoint = input.checkpoint().block_number;
+            match output {
+                Some(output) if output.checkpoint.block_number > initial_checkpoint => {
+                    let tx = self.tx.inner();
+                    let mut cumulative_gas_used = tx
+                        .cumulative_gas_used(initial_checkpoint)?
+                        .expect("no initial cumulative gas used");
+                    for block in initial_checkpoint + 1..=output.checkpoint.block_number {
+                        let (_, header) = tx
+                            .tx_ref()
+                            .cursor_read::<tables::Headers>()?
+                            .seek_exact(block)?
+                            .expect("no header");
+                        cumulative_gas_used += header.gas_used;
+                        assert_eq!(tx.cumulative_gas_used(block)?, Some(cumulative_gas_used));
+                    }
+                }
+                _ => self.check_no_entry_above(initial_checkpoint)?,
+            };
+            Ok(())
+        }
+    }
+
+    impl UnwindStageTestRunner for ChainStatsTestRunner {
+        fn validate_unwind(&self, input: UnwindInput) -> Result<(), TestRunnerError> {
+            self.check_no_entry_above(input.unwind_to)
+        }
+    }
+
+    impl ChainStatsTestRunner {
+        fn check_no_entry_above(&self, block: BlockNumber) -> Result<(), TestRunnerError> {
+            self.tx.ensure_no_entry_above::<tables::CumulativeGasUsed, _>(block, |num| num)?;
+            Ok(())
+        }
+    }
+}
@@ -67,6 +67,8 @@ pub struct ExecutionStage<EF: ExecutorFactory> {
     external_clean_threshold: u64,
     /// Pruning configuration.
     prune_modes: PruneModes,
+    /// Whether to record the preimages of hashed addresses and storage keys.
+    record_preimages: bool,
 }
 
 impl<EF: ExecutorFactory> ExecutionStage<EF> {
@@ -83,6 +85,7 @@ impl<EF: ExecutorFactory> ExecutionStage<EF> {
             executor_factory,
             thresholds,
             prune_modes,
+            record_preimages: false,
         }
     }
 
@@ -104,6 +107,13 @@ impl<EF: ExecutorFactory> ExecutionStage<EF> {
         self
     }
 
+    /// Record the preimages of hashed addresses and storage keys of executed blocks in the
+    /// [`Preimages`](reth_db::tables::Preimages) table.
+    pub fn with_preimages(mut self, record_preimages: bool) -> Self {
+        self.record_preimages = record_preimages;
+        self
+    }
+
     /// Execute the stage.
     pub fn execute_inner<DB: Database>(
         &mut self,
@@ -130,6 +140,9 @@ impl<EF: ExecutorFactory> ExecutionStage<EF> {
         // Execute block range
         let mut state = PostState::default();
         state.add_prune_modes(prune_modes);
+        if self.record_preimages {
+            state.record_preimages();
+        }
 
         for block_number in start_block..=max_block {
             let td = provider
@@ -7,6 +7,7 @@ use reth_db::{
     transaction::{DbTx, DbTxMut},
 };
 use reth_interfaces::{
+    consensus::ConsensusError,
     p2p::headers::{
         downloader::{HeaderDownloader, SyncTarget},
         error::HeadersDownloaderError,
@@ -17,9 +18,10 @@ use reth_primitives::{
     stage::{
         CheckpointBlockRange, EntitiesCheckpoint, HeadersCheckpoint, StageCheckpoint, StageId,
     },
-    BlockHashOrNumber, BlockNumber, SealedHeader, H256,
+    BlockHashOrNumber, BlockNumHash, BlockNumber, SealedHeader, H256,
 };
 use reth_provider::DatabaseProviderRW;
+use std::collections::BTreeMap;
 use tokio::sync::watch;
 use tracing::*;
 
@@ -47,12 +49,19 @@ pub enum HeaderSyncMode {
 ///
 /// NOTE: This stage downloads headers in reverse. Upon returning the control flow to the pipeline,
 /// the stage checkpoint is not updated until this stage is done.
+///
+/// Optionally, the stage can be given trusted checkpoints, i.e. block hashes of the canonical chain
+/// obtained from the consensus layer or the config. Every downloaded batch of headers is checked
+/// against the checkpoints in its range, so a fake chain is rejected as soon as it diverges from a
+/// checkpoint instead of only after it was downloaded and validated entirely.
 #[derive(Debug)]
 pub struct HeaderStage<D: HeaderDownloader> {
     /// Strategy for downloading the headers
     downloader: D,
     /// The sync mode for the stage.
     mode: HeaderSyncMode,
+    /// Trusted block hashes by number that the downloaded chain must pass through.
+    trusted_checkpoints: BTreeMap<BlockNumber, H256>,
 }
 
 // === impl HeaderStage ===
@@ -63,7 +72,41 @@ where
 {
     /// Create a new header stage
     pub fn new(downloader: D, mode: HeaderSyncMode) -> Self {
-        Self { downloader, mode }
+        Self { downloader, mode, trusted_checkpoints: BTreeMap::new() }
+    }
+
+    /// Sets the trusted checkpoints the downloaded header chain must pass through.
+    pub fn with_trusted_checkpoints(
+        mut self,
+        checkpoints: impl IntoIterator<Item = BlockNumHash>,
+    ) -> Self {
+        self.trusted_checkpoints =
+            checkpoints.into_iter().map(|checkpoint| checkpoint.into_components()).collect();
+        self
+    }
+
+    /// Checks the downloaded headers against the trusted checkpoints in their range.
+    ///
+    /// The headers are expected in descending order and connected, as returned by the downloader.
+    fn verify_trusted_checkpoints(&self, headers: &[SealedHeader]) -> Result<(), StageError> {
+        let (Some(highest), Some(lowest)) = (headers.first(), headers.last()) else {
+            return Ok(())
+        };
+        for (&number, &expected) in self.trusted_checkpoints.range(lowest.number..=highest.number) {
+            let header = &headers[(highest.number - number) as usize];
+            if header.hash() != expected {
+                warn!(target: "sync::stages::headers", number, got = ?header.hash(), ?expected, "Downloaded header does not match trusted checkpoint");
+                return Err(StageError::Validation {
+                    block: header.clone(),
+                    error: ConsensusError::TrustedCheckpointMismatch {
+                        number,
+                        got: header.hash(),
+                        expected,
+                    },
+                })
+            }
+        }
+        Ok(())
     }
 
     fn is_stage_done<DB: Database>(
@@ -230,6 +273,8 @@ where
 
         info!(target: "sync::stages::headers", len = downloaded_headers.len(), "Received headers");
 
+        self.verify_trusted_checkpoints(&downloaded_headers)?;
+
         let tip_block_number = match tip {
             // If tip is hash and it equals to the first downloaded header's hash, we can use
             // the block number of this header as tip.
@@ -438,10 +483,10 @@ mod tests {
             }
 
             fn stage(&self) -> Self::S {
-                HeaderStage {
-                    mode: HeaderSyncMode::Tip(self.channel.1.clone()),
-                    downloader: (*self.downloader_factory)(),
-                }
+                HeaderStage::new(
+                    (*self.downloader_factory)(),
+                    HeaderSyncMode::Tip(self.channel.1.clone()),
+                )
             }
         }
 
@@ -713,4 +758,40 @@ mod tests {
             processed == checkpoint + headers.len() as u64 - 1 && total == tip.number);
         assert!(runner.validate_execution(input, result.ok()).is_ok(), "validation failed");
     }
+
+    /// Test that downloaded headers are checked against the trusted checkpoints in their range
+    #[test]
+    fn verify_trusted_checkpoints() {
+        let runner = HeadersTestRunner::default();
+        let mut rng = generators::rng();
+        let headers =
+            generators::random_header_range(&mut rng, 10..20, H256::zero()).into_iter().rev();
+        let headers = headers.collect::<Vec<_>>();
+        let checkpoint = headers[3].num_hash();
+
+        // no checkpoints
+        assert!(runner.stage().verify_trusted_checkpoints(&headers).is_ok());
+
+        // matching checkpoint and checkpoints out of range
+        let stage = runner.stage().with_trusted_checkpoints([
+            BlockNumHash::new(5, H256::random()),
+            checkpoint,
+            BlockNumHash::new(25, H256::random()),
+        ]);
+        assert!(stage.verify_trusted_checkpoints(&headers).is_ok());
+
+        // mismatching checkpoint
+        let expected = H256::random();
+        let stage = runner
+            .stage()
+            .with_trusted_checkpoints([BlockNumHash::new(checkpoint.number, expected)]);
+        assert_matches!(
+            stage.verify_trusted_checkpoints(&headers),
+            Err(StageError::Validation {
+                block,
+                error: ConsensusError::TrustedCheckpointMismatch { number, got, expected: _expected }
+            }) if block.num_hash() == checkpoint && number == checkpoint.number &&
+                got == checkpoint.hash && _expected == expected
+        );
+    }
 }
@@ -1,5 +1,7 @@
 /// The bodies stage.
 mod bodies;
+/// The chain stats stage.
+mod chain_stats;
 /// The execution stage that generates state diff.
 mod execution;
 /// The finish stage
@@ -24,6 +26,7 @@ mod total_difficulty;
 mod tx_lookup;
 
 pub use bodies::*;
+pub use chain_stats::*;
 pub use execution::*;
 pub use finish::*;
 pub use hashing_account::*;
@@ -11,10 +11,11 @@ use reth_interfaces::consensus;
 use reth_primitives::{
     keccak256,
     stage::{EntitiesCheckpoint, StageCheckpoint, StageId},
-    PrunePart, TransactionSignedNoHash, TxNumber, H160,
+    PruneCheckpoint, PruneModes, PrunePart, TransactionSignedNoHash, TxNumber, H160,
 };
 use reth_provider::{
     BlockReader, DatabaseProviderRW, HeaderProvider, ProviderError, PruneCheckpointReader,
+    PruneCheckpointWriter,
 };
 use std::fmt::Debug;
 use thiserror::Error;
@@ -24,23 +25,28 @@ use tracing::*;
 /// The sender recovery stage iterates over existing transactions,
 /// recovers the transaction signer and stores them
 /// in [`TxSenders`][reth_db::tables::TxSenders] table.
+///
+/// Senders of blocks that would be pruned right away are not recovered, they are recovered from
+/// the signatures when the blocks are executed instead.
 #[derive(Clone, Debug)]
 pub struct SenderRecoveryStage {
     /// The size of inserted items after which the control
     /// flow will be returned to the pipeline for commit
     pub commit_threshold: u64,
+    /// Pruning configuration.
+    pub prune_modes: PruneModes,
 }
 
 impl SenderRecoveryStage {
     /// Create new instance of [SenderRecoveryStage].
-    pub fn new(commit_threshold: u64) -> Self {
-        Self { commit_threshold }
+    pub fn new(commit_threshold: u64, prune_modes: PruneModes) -> Self {
+        Self { commit_threshold, prune_modes }
     }
 }
 
 impl Default for SenderRecoveryStage {
     fn default() -> Self {
-        Self { commit_threshold: 5_000_000 }
+        Self { commit_threshold: 5_000_000, prune_modes: PruneModes::none() }
     }
 }
 
@@ -59,8 +65,33 @@ impl<DB: Database> Stage<DB> for SenderRecoveryStage {
     async fn execute(
         &mut self,
         provider: &DatabaseProviderRW<'_, &DB>,
-        input: ExecInput,
+        mut input: ExecInput,
     ) -> Result<ExecOutput, StageError> {
+        if let Some((target_prunable_block, prune_mode)) =
+            self.prune_modes.prune_target_block_sender_recovery(input.target())?
+        {
+            if target_prunable_block > input.checkpoint().block_number {
+                input.checkpoint = Some(StageCheckpoint::new(target_prunable_block));
+
+                // Save prune checkpoint only if we don't have one already.
+                // Otherwise, pruner may skip the unpruned range of blocks.
+                if provider.get_prune_checkpoint(PrunePart::SenderRecovery)?.is_none() {
+                    let target_prunable_tx_number = provider
+                        .block_body_indices(target_prunable_block)?
+                        .ok_or(ProviderError::BlockBodyIndicesNotFound(target_prunable_block))?
+                        .last_tx_num();
+
+                    provider.save_prune_checkpoint(
+                        PrunePart::SenderRecovery,
+                        PruneCheckpoint {
+                            block_number: Some(target_prunable_block),
+                            tx_number: Some(target_prunable_tx_number),
+                            prune_mode,
+                        },
+                    )?;
+                }
+            }
+        }
         if input.target_reached() {
             return Ok(ExecOutput::done(input.checkpoint()))
         }
@@ -213,6 +244,8 @@ fn stage_checkpoint<DB: Database>(
     let pruned_entries = provider
         .get_prune_checkpoint(PrunePart::SenderRecovery)?
         .and_then(|checkpoint| checkpoint.tx_number)
+        // `+1` is needed because `TxNumber` is 0-indexed
+        .map(|tx_number| tx_number + 1)
         .unwrap_or_default();
     Ok(EntitiesCheckpoint {
         // If `TxSenders` table was pruned, we will have a number of entries in it not matching
@@ -252,6 +285,7 @@ mod tests {
         TransactionSigned, H256, MAINNET,
     };
     use reth_provider::{ProviderFactory, PruneCheckpointWriter, TransactionsProvider};
+    use std::ops::Sub;
 
     use super::*;
     use crate::test_utils::{
@@ -376,6 +410,62 @@ mod tests {
         assert!(runner.validate_execution(first_input, result.ok()).is_ok(), "validation failed");
     }
 
+    #[tokio::test]
+    async fn execute_pruned_sender_recovery() {
+        let (previous_stage, prune_target, stage_progress) = (500, 400, 100);
+        let mut rng = generators::rng();
+
+        // Set up the runner
+        let mut runner = SenderRecoveryTestRunner::default();
+        let input = ExecInput {
+            target: Some(previous_stage),
+            checkpoint: Some(StageCheckpoint::new(stage_progress)),
+        };
+
+        // Seed only once with full input range
+        let seed =
+            random_block_range(&mut rng, stage_progress + 1..=previous_stage, H256::zero(), 0..2);
+        runner.tx.insert_blocks(seed.iter(), None).expect("failed to seed execution");
+
+        runner.set_prune_modes(PruneModes {
+            sender_recovery: Some(PruneMode::Before(prune_target)),
+            ..Default::default()
+        });
+
+        let rx = runner.execute(input);
+
+        // Assert the successful result
+        let result = rx.await.unwrap();
+        assert_matches!(
+            result,
+            Ok(ExecOutput {
+                checkpoint: StageCheckpoint {
+                block_number,
+                stage_checkpoint: Some(StageUnitCheckpoint::Entities(EntitiesCheckpoint {
+                    processed,
+                    total
+                }))
+            }, done: true }) if block_number == previous_stage && processed == total &&
+                total == runner.tx.table::<tables::Transactions>().unwrap().len() as u64
+        );
+
+        // The senders of the pruned blocks were not recovered
+        let last_pruned_tx = seed
+            .iter()
+            .take_while(|block| block.number < prune_target)
+            .map(|block| block.body.len() as u64)
+            .sum::<u64>();
+        assert!(runner
+            .tx
+            .table::<tables::TxSenders>()
+            .unwrap()
+            .iter()
+            .all(|(tx_number, _)| *tx_number >= last_pruned_tx));
+
+        // Validate the stage execution
+        assert!(runner.validate_execution(input, result.ok()).is_ok(), "execution validation");
+    }
+
     #[test]
     fn stage_checkpoint_pruned() {
         let tx = TestTransaction::default();
@@ -410,7 +500,8 @@ mod tests {
                         blocks[..=max_pruned_block as usize]
                             .iter()
                             .map(|block| block.body.len() as u64)
-                            .sum::<u64>(),
+                            .sum::<u64>()
+                            .sub(1), // `TxNumber` is 0-indexed
                     ),
                     prune_mode: PruneMode::Full,
                 },
@@ -437,11 +528,16 @@ mod tests {
     struct SenderRecoveryTestRunner {
         tx: TestTransaction,
         threshold: u64,
+        prune_modes: PruneModes,
     }
 
     impl Default for SenderRecoveryTestRunner {
         fn default() -> Self {
-            Self { threshold: 1000, tx: TestTransaction::default() }
+            Self {
+                threshold: 1000,
+                tx: TestTransaction::default(),
+                prune_modes: PruneModes::none(),
+            }
         }
     }
 
@@ -450,6 +546,10 @@ mod tests {
             self.threshold = threshold;
         }
 
+        fn set_prune_modes(&mut self, prune_modes: PruneModes) {
+            self.prune_modes = prune_modes;
+        }
+
         /// # Panics
         ///
         /// 1. If there are any entries in the [tables::TxSenders] table above a given block number.
@@ -483,7 +583,10 @@ mod tests {
         }
 
         fn stage(&self) -> Self::S {
-            SenderRecoveryStage { commit_threshold: self.threshold }
+            SenderRecoveryStage {
+                commit_threshold: self.threshold,
+                prune_modes: self.prune_modes.clone(),
+            }
         }
     }
 
@@ -502,12 +605,22 @@ mod tests {
 
         fn validate_execution(
             &self,
-            input: ExecInput,
+            mut input: ExecInput,
             output: Option<ExecOutput>,
         ) -> Result<(), TestRunnerError> {
             match output {
                 Some(output) => {
                     let provider = self.tx.inner();
+
+                    if let Some((target_prunable_block, _)) = self
+                        .prune_modes
+                        .prune_target_block_sender_recovery(input.target())
+                        .expect("prune target block for sender recovery")
+                    {
+                        if target_prunable_block > input.checkpoint().block_number {
+                            input.checkpoint = Some(StageCheckpoint::new(target_prunable_block));
+                        }
+                    }
                     let start_block = input.next_block();
                     let end_block = output.checkpoint.block_number;
 
@@ -9,7 +9,7 @@ use reth_db::{
 use reth_interfaces::{consensus::Consensus, provider::ProviderError};
 use reth_primitives::{
     stage::{EntitiesCheckpoint, StageCheckpoint, StageId},
-    U256,
+    BlockNumber, ChainSpec, U256,
 };
 use reth_provider::DatabaseProviderRW;
 use std::sync::Arc;
@@ -20,18 +20,23 @@ use tracing::*;
 /// This stage walks over inserted headers and computes total difficulty
 /// at each block. The entries are inserted into [`HeaderTD`][reth_db::tables::HeaderTD]
 /// table.
+///
+/// If configured with [TotalDifficultyStage::with_post_merge_elision], entries are only inserted
+/// for blocks before the merge, since the total difficulty is constant afterwards.
 #[derive(Debug, Clone)]
 pub struct TotalDifficultyStage {
     /// Consensus client implementation
     consensus: Arc<dyn Consensus>,
     /// The number of table entries to commit at once
     commit_threshold: u64,
+    /// The merge block and the final total difficulty, if entries after the merge are elided.
+    paris_block_and_final_difficulty: Option<(BlockNumber, U256)>,
 }
 
 impl TotalDifficultyStage {
     /// Create a new total difficulty stage
     pub fn new(consensus: Arc<dyn Consensus>) -> Self {
-        Self { consensus, commit_threshold: 100_000 }
+        Self { consensus, commit_threshold: 100_000, paris_block_and_final_difficulty: None }
     }
 
     /// Set a commit threshold on total difficulty stage
@@ -39,6 +44,23 @@ impl TotalDifficultyStage {
         self.commit_threshold = commit_threshold;
         self
     }
+
+    /// Don't store total difficulty entries for blocks at or above the merge block of the given
+    /// chain.
+    ///
+    /// The total difficulty of these blocks is the final total difficulty of the chain, which the
+    /// providers return without consulting the table. This has no effect if the merge block of the
+    /// chain is unknown.
+    pub fn with_post_merge_elision(mut self, chain_spec: &ChainSpec) -> Self {
+        self.paris_block_and_final_difficulty = chain_spec.paris_block_and_final_difficulty;
+        self
+    }
+
+    /// Returns the final total difficulty if the entry for the given block is elided.
+    fn elided_total_difficulty(&self, block_number: BlockNumber) -> Option<U256> {
+        self.paris_block_and_final_difficulty
+            .and_then(|(paris_block, td)| (block_number >= paris_block).then_some(td))
+    }
 }
 
 #[async_trait::async_trait]
@@ -70,11 +92,14 @@ impl<DB: Database> Stage<DB> for TotalDifficultyStage {
 
         // Get latest total difficulty
         let last_header_number = input.checkpoint().block_number;
-        let last_entry = cursor_td
-            .seek_exact(last_header_number)?
-            .ok_or(ProviderError::TotalDifficultyNotFound { number: last_header_number })?;
-
-        let mut td: U256 = last_entry.1.into();
+        let mut td: U256 = match self.elided_total_difficulty(last_header_number) {
+            Some(td) => td,
+            None => cursor_td
+                .seek_exact(last_header_number)?
+                .ok_or(ProviderError::TotalDifficultyNotFound { number: last_header_number })?
+                .1
+                .into(),
+        };
         debug!(target: "sync::stages::total_difficulty", ?td, block_number = last_header_number, "Last total difficulty entry");
 
         // Walk over newly inserted headers, update & insert td
@@ -85,12 +110,14 @@ impl<DB: Database> Stage<DB> for TotalDifficultyStage {
             self.consensus
                 .validate_header_with_total_difficulty(&header, td)
                 .map_err(|error| StageError::Validation { block: header.seal_slow(), error })?;
-            cursor_td.append(block_number, td.into())?;
+            if self.elided_total_difficulty(block_number).is_none() {
+                cursor_td.append(block_number, td.into())?;
+            }
         }
 
         Ok(ExecOutput {
             checkpoint: StageCheckpoint::new(end_block)
-                .with_entities_stage_checkpoint(stage_checkpoint(provider)?),
+                .with_entities_stage_checkpoint(self.stage_checkpoint(provider, end_block)?),
             done: is_final_range,
         })
     }
@@ -107,18 +134,40 @@ impl<DB: Database> Stage<DB> for TotalDifficultyStage {
 
         Ok(UnwindOutput {
             checkpoint: StageCheckpoint::new(unwind_to)
-                .with_entities_stage_checkpoint(stage_checkpoint(provider)?),
+                .with_entities_stage_checkpoint(self.stage_checkpoint(provider, unwind_to)?),
         })
     }
 }
 
-fn stage_checkpoint<DB: Database>(
-    provider: &DatabaseProviderRW<'_, DB>,
-) -> Result<EntitiesCheckpoint, DatabaseError> {
-    Ok(EntitiesCheckpoint {
-        processed: provider.tx_ref().entries::<tables::HeaderTD>()? as u64,
-        total: provider.tx_ref().entries::<tables::Headers>()? as u64,
-    })
+impl TotalDifficultyStage {
+    fn stage_checkpoint<DB: Database>(
+        &self,
+        provider: &DatabaseProviderRW<'_, DB>,
+        block_number: BlockNumber,
+    ) -> Result<EntitiesCheckpoint, DatabaseError> {
+        // elided entries are processed as well, but entries after the merge may still be stored,
+        // e.g. if they were written before the elision was enabled
+        let elided = match self.paris_block_and_final_difficulty {
+            Some((paris_block, _)) if block_number >= paris_block => {
+                let mut stored = 0;
+                for entry in provider
+                    .tx_ref()
+                    .cursor_read::<tables::HeaderTD>()?
+                    .walk_range(paris_block..=block_number)?
+                {
+                    entry?;
+                    stored += 1;
+                }
+                (block_number + 1 - paris_block).saturating_sub(stored)
+            }
+            _ => 0,
+        };
+
+        Ok(EntitiesCheckpoint {
+            processed: provider.tx_ref().entries::<tables::HeaderTD>()? as u64 + elided,
+            total: provider.tx_ref().entries::<tables::Headers>()? as u64,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -197,6 +246,7 @@ mod tests {
         tx: TestTransaction,
         consensus: Arc<TestConsensus>,
         commit_threshold: u64,
+        paris_block_and_final_difficulty: Option<(BlockNumber, U256)>,
     }
 
     impl Default for TotalDifficultyTestRunner {
@@ -205,6 +255,7 @@ mod tests {
                 tx: Default::default(),
                 consensus: Arc::new(TestConsensus::default()),
                 commit_threshold: 500,
+                paris_block_and_final_difficulty: None,
             }
         }
     }
@@ -220,6 +271,7 @@ mod tests {
             TotalDifficultyStage {
                 consensus: self.consensus.clone(),
                 commit_threshold: self.commit_threshold,
+                paris_block_and_final_difficulty: self.paris_block_and_final_difficulty,
             }
         }
     }
@@ -307,4 +359,63 @@ mod tests {
             self.commit_threshold = new_threshold;
         }
     }
+
+    #[tokio::test]
+    async fn execute_with_post_merge_elision() {
+        let (stage_progress, previous_stage) = (100, 200);
+        let paris_block = 150;
+
+        let mut runner = TotalDifficultyTestRunner::default();
+        let input = ExecInput {
+            target: Some(previous_stage),
+            checkpoint: Some(StageCheckpoint::new(stage_progress)),
+        };
+        runner.seed_execution(input).expect("failed to seed execution");
+
+        let final_td: U256 = runner
+            .tx
+            .query(|tx| {
+                let (_, td) = tx.cursor_read::<tables::HeaderTD>()?.last()?.expect("seeded");
+                let headers = tx
+                    .cursor_read::<tables::Headers>()?
+                    .walk_range(stage_progress + 1..paris_block)?
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(headers.into_iter().fold(td.into(), |td: U256, (_, h)| td + h.difficulty))
+            })
+            .unwrap();
+
+        runner.paris_block_and_final_difficulty = Some((paris_block, final_td));
+        let result = runner.execute(input).await.unwrap();
+
+        assert_matches!(
+            result,
+            Ok(ExecOutput { checkpoint: StageCheckpoint {
+                block_number,
+                stage_checkpoint: Some(StageUnitCheckpoint::Entities(EntitiesCheckpoint {
+                    processed,
+                    total
+                }))
+            }, done: true }) if block_number == previous_stage && processed == total
+        );
+
+        // no entries at or above the merge block
+        runner.check_no_td_above(paris_block - 1).unwrap();
+        assert_eq!(
+            runner.tx.table::<tables::HeaderTD>().unwrap().len() as u64,
+            paris_block - stage_progress
+        );
+
+        // entries after the merge, e.g. written before the elision was enabled, are not counted
+        // twice
+        runner
+            .tx
+            .commit(|tx| {
+                tx.put::<tables::HeaderTD>(paris_block + 10, final_td.into())?;
+                tx.put::<tables::HeaderTD>(paris_block + 20, final_td.into())
+            })
+            .unwrap();
+        let provider = runner.tx.inner_rw();
+        let checkpoint = runner.stage().stage_checkpoint(&provider, previous_stage).unwrap();
+        assert_eq!(checkpoint.processed, checkpoint.total);
+    }
 }
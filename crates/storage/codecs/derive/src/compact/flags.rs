@@ -51,6 +51,8 @@ pub(crate) fn generate_flag_struct(
 
     let docs =
         format!("Fieldset that facilitates compacting the parent type. Used bytes: {total_bytes} | Unused bits: {unused_bits}");
+    let bitflag_encoded_bytes = total_bytes as usize;
+    let bitflag_unused_bits = unused_bits as usize;
 
     // Generate the flag struct.
     quote! {
@@ -74,6 +76,16 @@ pub(crate) fn generate_flag_struct(
                         #(#readable_bytes)*
                     ]), buf)
                 }
+
+                /// Returns the number of bytes used by this fieldset.
+                pub const fn bitflag_encoded_bytes() -> usize {
+                    #bitflag_encoded_bytes
+                }
+
+                /// Returns the number of unused bits of this fieldset, they are reserved for fields that are added to the parent type in the future.
+                pub const fn bitflag_unused_bits() -> usize {
+                    #bitflag_unused_bits
+                }
             }
         }
     }
@@ -162,6 +174,14 @@ fn placeholder_flag_struct(flags: &Ident) -> TokenStream2 {
             pub fn into_bytes(self) -> [u8; 0] {
                 []
             }
+            /// Placeholder: does not use any bytes.
+            pub const fn bitflag_encoded_bytes() -> usize {
+                0
+            }
+            /// Placeholder: there are no unused bits.
+            pub const fn bitflag_unused_bits() -> usize {
+                0
+            }
         }
     }
 }
@@ -233,6 +233,14 @@ mod tests {
                             buf
                         )
                     }
+                    #[doc=r" Returns the number of bytes used by this fieldset."]
+                    pub const fn bitflag_encoded_bytes() -> usize {
+                        2usize
+                    }
+                    #[doc=r" Returns the number of unused bits of this fieldset, they are reserved for fields that are added to the parent type in the future."]
+                    pub const fn bitflag_unused_bits() -> usize {
+                        1usize
+                    }
                 }
             }
             #[cfg(test)]
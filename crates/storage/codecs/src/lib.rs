@@ -8,6 +8,9 @@ use bytes::{Buf, Bytes};
 pub use codecs_derive::*;
 use revm_primitives::{B160 as H160, B256 as H256, U256};
 
+mod versioned;
+pub use versioned::{migrate_compact, upgrade_compact, Versioned, VersionedCompact};
+
 /// Trait that implements the `Compact` codec.
 ///
 /// When deriving the trait for custom structs, be aware of certain limitations/recommendations:
@@ -22,6 +25,9 @@ use revm_primitives::{B160 as H160, B256 as H256, U256};
 /// `StructFlags`. It will fail compilation if it's not respected. If they're alias to known types,
 /// add their definitions to `get_bit_size()` or `known_types` in `generator.rs`.
 ///
+/// Since the encoding is stored in the database, new fields must be added in a backwards compatible
+/// way, see [VersionedCompact].
+///
 /// Regarding the `specialized_to/from_compact` methods: Mainly used as a workaround for not being
 /// able to specialize an impl over certain types like `Vec<T>`/`Option<T>` where `T` is a fixed
 /// size array like `Vec<H256>`.
@@ -0,0 +1,207 @@
+use crate::Compact;
+use bytes::Buf;
+
+/// A type whose [Compact] encoding has multiple versions.
+///
+/// Fields can be added to a type without a new version, as long as they fit into the unused bits
+/// of its fieldset (see `bitflag_unused_bits` of the generated `Flags` struct) and are placed after
+/// all existing fields: values that were encoded before the field existed decode it as `None` or
+/// zero. Any other change of the layout requires a new version.
+///
+/// Wrapped in [Versioned], the encoding is prefixed with the [VersionedCompact::VERSION] byte and
+/// values of older versions are decoded and upgraded by [VersionedCompact::from_compact_version].
+pub trait VersionedCompact: Compact + Sized {
+    /// The version of the current encoding.
+    const VERSION: u8;
+
+    /// Decodes a value that was encoded with an older `version` of the type and upgrades it to
+    /// the current version, see also [upgrade_compact].
+    ///
+    /// Returns `None` if the version is unknown.
+    fn from_compact_version(version: u8, buf: &[u8], len: usize) -> Option<(Self, &[u8])>;
+}
+
+/// Wrapper that prefixes the [Compact] encoding of the inner type with its version.
+///
+/// Decoding panics if the version is unknown, e.g. if the value was written by a newer release.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Versioned<T>(pub T);
+
+impl<T> Versioned<T> {
+    /// Returns the inner value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: VersionedCompact> Compact for Versioned<T> {
+    fn to_compact<B>(self, buf: &mut B) -> usize
+    where
+        B: bytes::BufMut + AsMut<[u8]>,
+    {
+        buf.put_u8(T::VERSION);
+        1 + self.0.to_compact(buf)
+    }
+
+    fn from_compact(mut buf: &[u8], len: usize) -> (Self, &[u8]) {
+        let version = buf.get_u8();
+        let len = len - 1;
+
+        let (value, buf) = if version == T::VERSION {
+            T::from_compact(buf, len)
+        } else {
+            T::from_compact_version(version, buf, len)
+                .unwrap_or_else(|| panic!("unknown compact encoding version {version}"))
+        };
+        (Self(value), buf)
+    }
+}
+
+/// Decodes the [Compact] encoding of `Old` and converts it into `New`.
+///
+/// Helper to implement [VersionedCompact::from_compact_version] with the type of a previous
+/// version.
+pub fn upgrade_compact<Old, New>(buf: &[u8], len: usize) -> (New, &[u8])
+where
+    Old: Compact,
+    New: From<Old>,
+{
+    let (old, buf) = Old::from_compact(buf, len);
+    (old.into(), buf)
+}
+
+/// Re-encodes the unversioned [Compact] encoding of `Old` as the [Versioned] encoding of `New`.
+///
+/// Helper to migrate the values of a database table to a versioned encoding.
+pub fn migrate_compact<Old, New>(buf: &[u8]) -> Vec<u8>
+where
+    Old: Compact,
+    New: VersionedCompact + From<Old>,
+{
+    let (new, _) = upgrade_compact::<Old, New>(buf, buf.len());
+    let mut out = Vec::with_capacity(buf.len() + 1);
+    Versioned(new).to_compact(&mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::main_codec;
+
+    #[main_codec]
+    #[derive(Debug, PartialEq, Clone, Default)]
+    pub struct ValueV1 {
+        a: u64,
+        b: Option<u64>,
+    }
+
+    /// Adds a field that fits into the unused bits of [ValueV1].
+    #[main_codec]
+    #[derive(Debug, PartialEq, Clone, Default)]
+    pub struct ValueV2 {
+        a: u64,
+        b: Option<u64>,
+        c: Option<u64>,
+    }
+
+    /// Adds a field that doesn't fit into the unused bits of [ValueV2].
+    #[main_codec]
+    #[derive(Debug, PartialEq, Clone, Default)]
+    pub struct ValueV3 {
+        a: u64,
+        b: Option<u64>,
+        c: Option<u64>,
+        d: u128,
+    }
+
+    impl VersionedCompact for ValueV2 {
+        const VERSION: u8 = 1;
+
+        fn from_compact_version(_: u8, _: &[u8], _: usize) -> Option<(Self, &[u8])> {
+            None
+        }
+    }
+
+    impl VersionedCompact for ValueV3 {
+        const VERSION: u8 = 2;
+
+        fn from_compact_version(version: u8, buf: &[u8], len: usize) -> Option<(Self, &[u8])> {
+            match version {
+                1 => Some(upgrade_compact::<ValueV2, _>(buf, len)),
+                _ => None,
+            }
+        }
+    }
+
+    impl From<ValueV2> for ValueV3 {
+        fn from(value: ValueV2) -> Self {
+            let ValueV2 { a, b, c } = value;
+            Self { a, b, c, d: 0 }
+        }
+    }
+
+    #[test]
+    fn bitflag_unused_bits() {
+        assert_eq!(ValueV1Flags::bitflag_encoded_bytes(), 1);
+        assert_eq!(ValueV1Flags::bitflag_unused_bits(), 3);
+        assert_eq!(ValueV2Flags::bitflag_encoded_bytes(), 1);
+        assert_eq!(ValueV2Flags::bitflag_unused_bits(), 2);
+        assert_eq!(ValueV3Flags::bitflag_encoded_bytes(), 2);
+    }
+
+    #[test]
+    fn field_in_unused_bits_is_backwards_compatible() {
+        proptest::proptest!(|(value: ValueV1)| {
+            let mut buf = vec![];
+            let len = value.clone().to_compact(&mut buf);
+
+            let (decoded, rest) = ValueV2::from_compact(&buf, len);
+            assert_eq!(decoded, ValueV2 { a: value.a, b: value.b, c: None });
+            assert!(rest.is_empty());
+        });
+    }
+
+    #[test]
+    fn versioned_roundtrip_across_versions() {
+        proptest::proptest!(|(value: ValueV2)| {
+            let mut buf = vec![];
+            let len = Versioned(value.clone()).to_compact(&mut buf);
+            assert_eq!(buf[0], ValueV2::VERSION);
+
+            let (decoded, rest) = Versioned::<ValueV3>::from_compact(&buf, len);
+            assert_eq!(decoded.into_inner(), ValueV3::from(value.clone()));
+            assert!(rest.is_empty());
+
+            let (decoded, _) = Versioned::<ValueV2>::from_compact(&buf, len);
+            assert_eq!(decoded.into_inner(), value);
+        });
+
+        proptest::proptest!(|(value: ValueV3)| {
+            let mut buf = vec![];
+            let len = Versioned(value.clone()).to_compact(&mut buf);
+            let (decoded, _) = Versioned::<ValueV3>::from_compact(&buf, len);
+            assert_eq!(decoded.into_inner(), value);
+        });
+    }
+
+    #[test]
+    fn migrate_to_versioned() {
+        proptest::proptest!(|(value: ValueV2)| {
+            let mut buf = vec![];
+            value.clone().to_compact(&mut buf);
+
+            let migrated = migrate_compact::<ValueV2, ValueV3>(&buf);
+            let (decoded, _) = Versioned::<ValueV3>::from_compact(&migrated, migrated.len());
+            assert_eq!(decoded.into_inner(), ValueV3::from(value));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "unknown compact encoding version")]
+    fn unknown_version() {
+        let mut buf = vec![];
+        Versioned(ValueV3::default()).to_compact(&mut buf);
+        Versioned::<ValueV2>::from_compact(&buf, buf.len());
+    }
+}
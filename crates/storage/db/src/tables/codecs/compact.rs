@@ -49,7 +49,8 @@ impl_compression_for_compact!(
     TransactionSignedNoHash,
     CompactU256,
     StageCheckpoint,
-    PruneCheckpoint
+    PruneCheckpoint,
+    StoredPreimage
 );
 
 macro_rules! impl_compression_fixed_compact {
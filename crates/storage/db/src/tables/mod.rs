@@ -30,7 +30,8 @@ use crate::{
             accounts::{AccountBeforeTx, BlockNumberAddress},
             blocks::{HeaderHash, StoredBlockOmmers},
             storage_sharded_key::StorageShardedKey,
-            ShardedKey, StoredBlockBodyIndices, StoredBlockWithdrawals,
+            ChainStateKey, ShardedKey, StoredBlockBodyIndices, StoredBlockWithdrawals,
+            StoredPreimage,
         },
     },
 };
@@ -51,7 +52,7 @@ pub enum TableType {
 }
 
 /// Number of tables that should be present inside database.
-pub const NUM_TABLES: usize = 26;
+pub const NUM_TABLES: usize = 30;
 
 /// The general purpose of this is to use with a combination of Tables enum,
 /// by implementing a `TableViewer` trait you can operate on db tables in an abstract way.
@@ -184,7 +185,11 @@ tables!([
     (TxSenders, TableType::Table),
     (SyncStage, TableType::Table),
     (SyncStageProgress, TableType::Table),
-    (PruneCheckpoints, TableType::Table)
+    (PruneCheckpoints, TableType::Table),
+    (ChainState, TableType::Table),
+    (Preimages, TableType::Table),
+    (PreimageChangeSet, TableType::DupSort),
+    (CumulativeGasUsed, TableType::Table)
 ]);
 
 #[macro_export]
@@ -421,6 +426,34 @@ table!(
     ( PruneCheckpoints ) PrunePart | PruneCheckpoint
 );
 
+table!(
+    /// Stores the block hashes of the last valid forkchoice state received from the consensus
+    /// layer.
+    ( ChainState ) ChainStateKey | H256
+);
+
+table!(
+    /// Stores the preimages of hashed addresses and storage keys, keyed by their keccak256 hash.
+    ///
+    /// Only written if preimage recording is enabled, see
+    /// `PostState::record_preimages`.
+    ( Preimages ) H256 | StoredPreimage
+);
+
+dupsort!(
+    /// Stores the hashes of the preimages that were written in each block.
+    ///
+    /// Used to prune the [`Preimages`] table by block.
+    ( PreimageChangeSet ) BlockNumber | [H256] H256
+);
+
+table!(
+    /// Stores the total gas used by all blocks up to and including each block.
+    ///
+    /// Only maintained if the chain stats stage is enabled.
+    ( CumulativeGasUsed ) BlockNumber | u64
+);
+
 /// Alias Types
 
 /// List with transaction numbers.
@@ -461,6 +494,10 @@ mod tests {
         (TableType::Table, SyncStage::const_name()),
         (TableType::Table, SyncStageProgress::const_name()),
         (TableType::Table, PruneCheckpoints::const_name()),
+        (TableType::Table, ChainState::const_name()),
+        (TableType::Table, Preimages::const_name()),
+        (TableType::DupSort, PreimageChangeSet::const_name()),
+        (TableType::Table, CumulativeGasUsed::const_name()),
     ];
 
     #[test]
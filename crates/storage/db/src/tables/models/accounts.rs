@@ -27,6 +27,12 @@ pub struct AccountBeforeTx {
 // NOTE: Removing main_codec and manually encode subkey
 // and compress second part of the value. If we have compression
 // over whole value (Even SubKey) that would mess up fetching of values with seek_by_key_subkey
+//
+// Delta or dictionary encoding of the account is not possible at this layer: every dupsort value
+// is decoded on its own, so there is no previous entry to delta against, and the compact
+// [Account] already strips the leading zeros of the nonce and balance and omits the bytecode hash
+// of accounts without code. A bytecode-less account takes at most 2 flag bytes + 8 nonce bytes +
+// 32 balance bytes after the address, and an account that did not exist takes none.
 impl Compact for AccountBeforeTx {
     fn to_compact<B>(self, buf: &mut B) -> usize
     where
@@ -131,6 +137,7 @@ impl_fixed_arbitrary!(BlockNumberAddress, 28);
 mod test {
     use super::*;
     use rand::{thread_rng, Rng};
+    use reth_primitives::U256;
     use std::str::FromStr;
 
     #[test]
@@ -150,6 +157,27 @@ mod test {
         assert_eq!(decoded, key);
     }
 
+    #[test]
+    fn account_before_tx_compact_size() {
+        let address = Address::repeat_byte(1);
+        let mut buf = Vec::new();
+        let len = AccountBeforeTx { address, info: None }.to_compact(&mut buf);
+        assert_eq!(len, 20);
+
+        // flags, one nonce byte and eight balance bytes, no bytecode hash
+        let account = Account {
+            nonce: 1,
+            balance: U256::from(10u64).pow(U256::from(18)),
+            ..Default::default()
+        };
+        let mut buf = Vec::new();
+        let len = AccountBeforeTx { address, info: Some(account) }.to_compact(&mut buf);
+        assert_eq!(len, 20 + 2 + 1 + 8);
+
+        let (decoded, _) = AccountBeforeTx::from_compact(&buf, len);
+        assert_eq!(decoded, AccountBeforeTx { address, info: Some(account) });
+    }
+
     #[test]
     fn test_tx_number_address_rand() {
         let mut bytes = [0u8; 28];
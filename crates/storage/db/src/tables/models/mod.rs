@@ -3,11 +3,12 @@ use crate::{
     table::{Decode, Encode},
     DatabaseError,
 };
-use reth_codecs::Compact;
+use reth_codecs::{main_codec, Compact};
 use reth_primitives::{
     trie::{StoredNibbles, StoredNibblesSubKey},
-    Address, PrunePart, H256,
+    Address, BlockNumber, Bytes, PrunePart, H256,
 };
+use serde::{Deserialize, Serialize};
 
 pub mod accounts;
 pub mod blocks;
@@ -152,3 +153,48 @@ impl Decode for PrunePart {
         Ok(Self::from_compact(buf, buf.len()).0)
     }
 }
+
+/// Keys of the [`ChainState`](crate::tables::ChainState) table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ChainStateKey {
+    /// The head block of the last valid forkchoice state.
+    LastHeadBlock,
+    /// The safe block of the last valid forkchoice state.
+    LastSafeBlock,
+    /// The finalized block of the last valid forkchoice state.
+    LastFinalizedBlock,
+}
+
+impl Encode for ChainStateKey {
+    type Encoded = [u8; 1];
+
+    fn encode(self) -> Self::Encoded {
+        match self {
+            ChainStateKey::LastHeadBlock => [0],
+            ChainStateKey::LastSafeBlock => [1],
+            ChainStateKey::LastFinalizedBlock => [2],
+        }
+    }
+}
+
+impl Decode for ChainStateKey {
+    fn decode<B: AsRef<[u8]>>(value: B) -> Result<Self, DatabaseError> {
+        match value.as_ref() {
+            [0] => Ok(ChainStateKey::LastHeadBlock),
+            [1] => Ok(ChainStateKey::LastSafeBlock),
+            [2] => Ok(ChainStateKey::LastFinalizedBlock),
+            _ => Err(DatabaseError::DecodeError),
+        }
+    }
+}
+
+/// The preimage of a hashed address or storage key, stored in the
+/// [`Preimages`](crate::tables::Preimages) table.
+#[main_codec]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StoredPreimage {
+    /// The last block in which the address or storage key was changed.
+    pub block_number: BlockNumber,
+    /// The address or storage key bytes.
+    pub preimage: Bytes,
+}
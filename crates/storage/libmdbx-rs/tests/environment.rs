@@ -83,6 +83,25 @@ fn test_sync() {
     }
 }
 
+#[test]
+fn test_copy() {
+    let dir = tempdir().unwrap();
+    let env = Environment::new().open(dir.path()).unwrap();
+    let tx = env.begin_rw_txn().unwrap();
+    tx.put(tx.open_db(None).unwrap().dbi(), b"key", b"val", WriteFlags::empty()).unwrap();
+    tx.commit().unwrap();
+
+    let copy_dir = tempdir().unwrap();
+    env.copy(&copy_dir.path().join("mdbx.dat"), true).unwrap();
+    // the destination must not exist
+    assert!(env.copy(&copy_dir.path().join("mdbx.dat"), true).is_err());
+
+    let copy = Environment::new().open(copy_dir.path()).unwrap();
+    let tx = copy.begin_ro_txn().unwrap();
+    let db = tx.open_db(None).unwrap();
+    assert_eq!(tx.get(db.dbi(), b"key").unwrap(), Some(*b"val"));
+}
+
 #[test]
 fn test_stat() {
     let dir = tempdir().unwrap();
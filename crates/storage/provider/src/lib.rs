@@ -25,18 +25,21 @@ pub use traits::{
     BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt, BlockSource, BlockWriter,
     BlockchainTreePendingStateProvider, CanonChainTracker, CanonStateNotification,
     CanonStateNotificationSender, CanonStateNotifications, CanonStateSubscriptions,
-    ChainSpecProvider, ChangeSetReader, EvmEnvProvider, ExecutorFactory, HashingWriter,
-    HeaderProvider, HistoryWriter, PostStateDataProvider, PruneCheckpointReader,
-    PruneCheckpointWriter, ReceiptProvider, ReceiptProviderIdExt, StageCheckpointReader,
-    StageCheckpointWriter, StateProvider, StateProviderBox, StateProviderFactory,
-    StateRootProvider, StorageReader, TransactionsProvider, WithdrawalsProvider,
+    ChainSpecProvider, ChainStateReader, ChainStateWriter, ChainStatsReader, ChangeSetReader,
+    EvmEnvProvider, ExecutorFactory, HashingWriter, HeaderProvider, HistoryWriter,
+    PostStateDataProvider, PreimageReader, PruneCheckpointReader, PruneCheckpointWriter,
+    ReceiptProvider, ReceiptProviderIdExt, SnapStateProvider, SnapStateProviderBox,
+    SnapStateProviderFactory, StageCheckpointReader, StageCheckpointWriter, StateProvider,
+    StateProviderBox, StateProviderFactory, StateRootProvider, StorageReader, TransactionsProvider,
+    WithdrawalsProvider, SNAP_SERVED_STATES,
 };
 
 /// Provider trait implementations.
 pub mod providers;
 pub use providers::{
-    DatabaseProvider, DatabaseProviderRO, DatabaseProviderRW, HistoricalStateProvider,
-    HistoricalStateProviderRef, LatestStateProvider, LatestStateProviderRef, ProviderFactory,
+    DatabaseProvider, DatabaseProviderRO, DatabaseProviderRW, HashedStateProvider,
+    HistoricalStateProvider, HistoricalStateProviderRef, LatestStateProvider,
+    LatestStateProviderRef, ProviderFactory,
 };
 
 /// Execution result
@@ -1,7 +1,7 @@
 //! Output of execution.
 use reth_db::{
     cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW},
-    models::{AccountBeforeTx, BlockNumberAddress},
+    models::{AccountBeforeTx, BlockNumberAddress, StoredPreimage},
     tables,
     transaction::{DbTx, DbTxMut},
     DatabaseError as DbError,
@@ -82,6 +82,8 @@ pub struct PostState {
     receipts: BTreeMap<BlockNumber, Vec<Receipt>>,
     /// Pruning configuration.
     prune_modes: PruneModes,
+    /// Whether to write the preimages of the hashed addresses and storage keys.
+    record_preimages: bool,
 }
 
 impl PostState {
@@ -100,6 +102,12 @@ impl PostState {
         self.prune_modes = prune_modes;
     }
 
+    /// Write the preimages of all changed addresses and storage keys to the
+    /// [Preimages](tables::Preimages) table when writing to the database.
+    pub fn record_preimages(&mut self) {
+        self.record_preimages = true;
+    }
+
     /// Return the current size of the poststate.
     ///
     /// Size is the sum of individual changes to accounts, storage, bytecode and receipts.
@@ -597,12 +605,60 @@ impl PostState {
         Ok(())
     }
 
+    /// Write the preimages of the changed addresses and storage keys to the database.
+    ///
+    /// Each preimage is stored with the last block it was changed in, and indexed by that block
+    /// in the [PreimageChangeSet](tables::PreimageChangeSet) table so it can be pruned.
+    fn write_preimages_to_db<'a, TX: DbTxMut<'a> + DbTx<'a>>(
+        &self,
+        tx: &TX,
+    ) -> Result<(), DbError> {
+        tracing::trace!(target: "provider::post_state", "Writing preimages");
+        let mut preimages_cursor = tx.cursor_write::<tables::Preimages>()?;
+        let mut changeset_cursor = tx.cursor_dup_write::<tables::PreimageChangeSet>()?;
+        let mut write = |block_number: BlockNumber, preimage: &[u8]| -> Result<(), DbError> {
+            let hash = keccak256(preimage);
+            if preimages_cursor
+                .seek_exact(hash)?
+                .map_or(true, |(_, stored)| stored.block_number < block_number)
+            {
+                preimages_cursor.upsert(
+                    hash,
+                    StoredPreimage { block_number, preimage: preimage.to_vec().into() },
+                )?;
+                changeset_cursor.upsert(block_number, hash)?;
+            }
+            Ok(())
+        };
+
+        for (block_number, account_changes) in self.account_changes.iter() {
+            for address in account_changes.keys() {
+                write(*block_number, address.as_bytes())?;
+            }
+        }
+        for (block_number, storage_changes) in self.storage_changes.iter() {
+            for (address, storage) in storage_changes {
+                write(*block_number, address.as_bytes())?;
+                for key in storage.storage.keys() {
+                    write(*block_number, H256::from(*key).as_bytes())?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Write the post state to the database.
     pub fn write_to_db<'a, TX: DbTxMut<'a> + DbTx<'a>>(
         mut self,
         tx: &TX,
         tip: BlockNumber,
     ) -> Result<(), Error> {
+        // Preimages are written first, because writing the history consumes the changes.
+        if self.record_preimages {
+            self.write_preimages_to_db(tx)?;
+        }
+
         self.write_history_to_db(tx, tip)?;
 
         // Write new storage state
@@ -646,11 +702,14 @@ impl PostState {
             }
         }
 
-        // Write bytecode
+        // Write bytecode. Bytecode is content-addressed, so contracts that are already stored are
+        // skipped.
         tracing::trace!(target: "provider::post_state", len = self.bytecode.len(), "Writing bytecodes");
         let mut bytecodes_cursor = tx.cursor_write::<tables::Bytecodes>()?;
         for (hash, bytecode) in self.bytecode.into_iter() {
-            bytecodes_cursor.upsert(hash, bytecode)?;
+            if bytecodes_cursor.seek_exact(hash)?.is_none() {
+                bytecodes_cursor.upsert(hash, bytecode)?;
+            }
         }
 
         // Write the receipts of the transactions if not pruned
@@ -1217,6 +1276,59 @@ mod tests {
         );
     }
 
+    #[test]
+    fn write_to_db_preimages() {
+        let db: Arc<DatabaseEnv> = create_test_rw_db();
+        let factory = ProviderFactory::new(db, MAINNET.clone());
+        let provider = factory.provider_rw().unwrap();
+
+        let address_a = Address::zero();
+        let address_b = Address::repeat_byte(0xff);
+        let slot = U256::from(1);
+
+        let mut post_state = PostState::new();
+        post_state.create_account(1, address_a, Account::default());
+        post_state.write_to_db(provider.tx_ref(), 0).unwrap();
+        assert_eq!(provider.tx_ref().entries::<tables::Preimages>().unwrap(), 0);
+
+        let mut post_state = PostState::new();
+        post_state.record_preimages();
+        post_state.create_account(2, address_b, Account::default());
+        post_state.change_storage(2, address_b, BTreeMap::from([(slot, (U256::ZERO, slot))]));
+        post_state.write_to_db(provider.tx_ref(), 0).unwrap();
+
+        let mut post_state = PostState::new();
+        post_state.record_preimages();
+        post_state.change_account(3, address_b, Account::default(), Account::default());
+        post_state.write_to_db(provider.tx_ref(), 0).unwrap();
+
+        let tx = provider.tx_ref();
+        assert_eq!(tx.entries::<tables::Preimages>().unwrap(), 2);
+        assert_eq!(
+            tx.get::<tables::Preimages>(keccak256(address_b)).unwrap(),
+            Some(StoredPreimage {
+                block_number: 3,
+                preimage: address_b.as_bytes().to_vec().into()
+            })
+        );
+        let slot = H256::from(slot);
+        assert_eq!(
+            tx.get::<tables::Preimages>(keccak256(slot)).unwrap(),
+            Some(StoredPreimage { block_number: 2, preimage: slot.as_bytes().to_vec().into() })
+        );
+
+        let changesets = tx
+            .cursor_dup_read::<tables::PreimageChangeSet>()
+            .unwrap()
+            .walk(None)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(changesets.len(), 3);
+        assert!(changesets.contains(&(2, keccak256(slot))));
+        assert!(changesets.contains(&(3, keccak256(address_b))));
+    }
+
     #[test]
     fn write_to_db_storage() {
         let db: Arc<DatabaseEnv> = create_test_rw_db();
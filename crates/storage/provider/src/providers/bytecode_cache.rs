@@ -0,0 +1,237 @@
+//! A shared in-memory cache for contract bytecode.
+use crate::{
+    AccountReader, BlockHashReader, PostState, StateProvider, StateProviderBox, StateRootProvider,
+};
+use parking_lot::Mutex;
+use reth_interfaces::Result;
+use reth_primitives::{
+    Account, Address, BlockNumber, Bytecode, Bytes, StorageKey, StorageValue, H256,
+};
+use schnellru::{LruMap, Unlimited};
+use std::sync::Arc;
+
+/// The default total size in bytes of the bytecode kept in the [BytecodeCache] (128MB).
+pub const DEFAULT_BYTECODE_CACHE_MAX_BYTES: usize = 128 * 1024 * 1024;
+
+/// An LRU cache of decoded contract bytecode, keyed by code hash.
+///
+/// Bytecode is stored content-addressed in the `Bytecodes` table, so an entry never becomes stale
+/// and the cache can be shared by all state providers, regardless of the block they read from.
+///
+/// The cache is bounded by the total size of the cached bytecode, since contract sizes vary widely.
+#[derive(Clone)]
+pub struct BytecodeCache {
+    inner: Arc<Mutex<BytecodeCacheInner>>,
+}
+
+impl BytecodeCache {
+    /// Creates a new cache that keeps at most `max_bytes` of bytecode.
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(BytecodeCacheInner {
+                entries: LruMap::new(Unlimited),
+                size: 0,
+                max_size: max_bytes,
+            })),
+        }
+    }
+
+    /// Returns the cached bytecode for the given code hash.
+    pub fn get(&self, code_hash: &H256) -> Option<Bytecode> {
+        self.inner.lock().entries.get(code_hash).cloned()
+    }
+
+    /// Inserts the bytecode for the given code hash.
+    ///
+    /// The least recently used contracts are evicted until the bytecode fits, bytecode larger than
+    /// the cache is not cached.
+    pub fn insert(&self, code_hash: H256, bytecode: Bytecode) {
+        self.inner.lock().insert(code_hash, bytecode)
+    }
+
+    /// Returns the number of cached contracts.
+    pub fn len(&self) -> usize {
+        self.inner.lock().entries.len()
+    }
+
+    /// Returns the total size in bytes of the cached bytecode.
+    pub fn size(&self) -> usize {
+        self.inner.lock().size
+    }
+
+    /// Returns `true` if the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for BytecodeCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_BYTECODE_CACHE_MAX_BYTES)
+    }
+}
+
+impl std::fmt::Debug for BytecodeCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let inner = self.inner.lock();
+        f.debug_struct("BytecodeCache")
+            .field("len", &inner.entries.len())
+            .field("size", &inner.size)
+            .field("max_size", &inner.max_size)
+            .finish()
+    }
+}
+
+/// The entries of the [BytecodeCache] and their total size.
+struct BytecodeCacheInner {
+    entries: LruMap<H256, Bytecode, Unlimited>,
+    /// Total size in bytes of the cached bytecode.
+    size: usize,
+    /// Maximum total size in bytes of the cached bytecode.
+    max_size: usize,
+}
+
+impl BytecodeCacheInner {
+    fn insert(&mut self, code_hash: H256, bytecode: Bytecode) {
+        let bytecode_size = bytecode.0.bytecode.len();
+        // bytecode is content-addressed, so an existing entry is identical
+        if bytecode_size > self.max_size || self.entries.peek(&code_hash).is_some() {
+            return
+        }
+
+        while self.size + bytecode_size > self.max_size {
+            let Some((_, evicted)) = self.entries.pop_oldest() else { break };
+            self.size -= evicted.0.bytecode.len();
+        }
+
+        self.entries.insert(code_hash, bytecode);
+        self.size += bytecode_size;
+    }
+}
+
+/// A [StateProvider] that serves bytecode lookups from a [BytecodeCache] before falling back to
+/// the wrapped provider.
+pub struct CachedBytecodeStateProvider<'a> {
+    inner: StateProviderBox<'a>,
+    cache: BytecodeCache,
+}
+
+impl<'a> CachedBytecodeStateProvider<'a> {
+    /// Wraps the given state provider.
+    pub fn new(inner: StateProviderBox<'a>, cache: BytecodeCache) -> Self {
+        Self { inner, cache }
+    }
+}
+
+impl<'a> BlockHashReader for CachedBytecodeStateProvider<'a> {
+    fn block_hash(&self, number: u64) -> Result<Option<H256>> {
+        self.inner.block_hash(number)
+    }
+
+    fn canonical_hashes_range(&self, start: BlockNumber, end: BlockNumber) -> Result<Vec<H256>> {
+        self.inner.canonical_hashes_range(start, end)
+    }
+}
+
+impl<'a> AccountReader for CachedBytecodeStateProvider<'a> {
+    fn basic_account(&self, address: Address) -> Result<Option<Account>> {
+        self.inner.basic_account(address)
+    }
+}
+
+impl<'a> StateRootProvider for CachedBytecodeStateProvider<'a> {
+    fn state_root(&self, post_state: PostState) -> Result<H256> {
+        self.inner.state_root(post_state)
+    }
+
+    fn storage_root(&self, address: Address, post_state: PostState) -> Result<H256> {
+        self.inner.storage_root(address, post_state)
+    }
+}
+
+impl<'a> StateProvider for CachedBytecodeStateProvider<'a> {
+    fn storage(&self, account: Address, storage_key: StorageKey) -> Result<Option<StorageValue>> {
+        self.inner.storage(account, storage_key)
+    }
+
+    fn bytecode_by_hash(&self, code_hash: H256) -> Result<Option<Bytecode>> {
+        if let Some(bytecode) = self.cache.get(&code_hash) {
+            return Ok(Some(bytecode))
+        }
+        let bytecode = self.inner.bytecode_by_hash(code_hash)?;
+        if let Some(bytecode) = &bytecode {
+            self.cache.insert(code_hash, bytecode.clone());
+        }
+        Ok(bytecode)
+    }
+
+    fn proof(
+        &self,
+        address: Address,
+        keys: &[H256],
+    ) -> Result<(Vec<Bytes>, H256, Vec<Vec<Bytes>>)> {
+        self.inner.proof(address, keys)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_primitives::keccak256;
+
+    #[test]
+    fn serves_bytecode_from_cache() {
+        let provider = MockEthProvider::default();
+        let address = Address::random();
+        let code = Bytes::from(vec![0x60, 0x00]);
+        let code_hash = keccak256(&code);
+        let bytecode = Bytecode::new_raw(code.clone().into());
+        provider
+            .add_account(address, ExtendedAccount::new(0, Default::default()).with_bytecode(code));
+
+        let cache = BytecodeCache::new(1024);
+        let state = CachedBytecodeStateProvider::new(Box::new(provider.clone()), cache.clone());
+        assert!(state.bytecode_by_hash(H256::random()).unwrap().is_none());
+        assert!(cache.is_empty());
+
+        assert_eq!(state.bytecode_by_hash(code_hash).unwrap(), Some(bytecode.clone()));
+        assert_eq!(cache.get(&code_hash), Some(bytecode));
+    }
+
+    #[test]
+    fn evicts_by_size() {
+        let bytecode = |len: usize| {
+            let code = Bytes::from(vec![0x00; len]);
+            (keccak256(&code), Bytecode::new_raw(code.into()))
+        };
+        let (small_hash, small) = bytecode(100);
+        let size = small.0.bytecode.len();
+        let cache = BytecodeCache::new(2 * size + 10);
+
+        cache.insert(small_hash, small.clone());
+        assert_eq!(cache.size(), size);
+
+        // larger than the cache
+        let (large_hash, large) = bytecode(2 * size + 11);
+        cache.insert(large_hash, large);
+        assert_eq!(cache.get(&large_hash), None);
+        assert_eq!(cache.len(), 1);
+
+        // fits next to the first entry
+        let (second_hash, second) = bytecode(101);
+        let second_size = second.0.bytecode.len();
+        cache.insert(second_hash, second);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.size(), size + second_size);
+
+        // evicts the least recently used entry
+        assert!(cache.get(&small_hash).is_some());
+        let (third_hash, third) = bytecode(102);
+        let third_size = third.0.bytecode.len();
+        cache.insert(third_hash, third);
+        assert!(cache.get(&second_hash).is_none());
+        assert!(cache.get(&small_hash).is_some());
+        assert_eq!(cache.size(), size + third_size);
+    }
+}
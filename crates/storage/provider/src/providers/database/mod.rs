@@ -1,15 +1,27 @@
 use crate::{
-    providers::state::{historical::HistoricalStateProvider, latest::LatestStateProvider},
+    providers::{
+        state::{
+            historical::HistoricalStateProvider, latest::LatestStateProvider,
+            snap::HashedStateProvider,
+        },
+        BytecodeCache, CachedBytecodeStateProvider,
+    },
     traits::{BlockSource, ReceiptProvider},
-    BlockHashReader, BlockNumReader, BlockReader, ChainSpecProvider, EvmEnvProvider,
-    HeaderProvider, ProviderError, PruneCheckpointReader, StageCheckpointReader, StateProviderBox,
-    TransactionsProvider, WithdrawalsProvider,
+    BlockHashReader, BlockNumReader, BlockReader, ChainSpecProvider, ChainStateReader,
+    ChainStateWriter, ChainStatsReader, EvmEnvProvider, HeaderProvider, PreimageReader,
+    ProviderError, PruneCheckpointReader, SnapStateProviderBox, SnapStateProviderFactory,
+    StageCheckpointReader, StateProviderBox, TransactionsProvider, WithdrawalsProvider,
+};
+use reth_db::{
+    database::Database,
+    init_db,
+    models::{ChainStateKey, StoredBlockBodyIndices},
+    DatabaseEnv,
 };
-use reth_db::{database::Database, init_db, models::StoredBlockBodyIndices, DatabaseEnv};
 use reth_interfaces::Result;
 use reth_primitives::{
     stage::{StageCheckpoint, StageId},
-    Address, Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithSenders, ChainInfo,
+    Address, Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithSenders, Bytes, ChainInfo,
     ChainSpec, Header, PruneCheckpoint, PrunePart, Receipt, SealedBlock, SealedHeader,
     TransactionMeta, TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, Withdrawal,
     H256, U256,
@@ -31,6 +43,10 @@ pub struct ProviderFactory<DB> {
     db: DB,
     /// Chain spec
     chain_spec: Arc<ChainSpec>,
+    /// Optional cache for contract bytecode, shared by all state providers.
+    bytecode_cache: Option<BytecodeCache>,
+    /// Whether the read-write providers don't write total difficulty entries after the merge.
+    elide_post_merge_td: bool,
 }
 
 impl<DB: Database> ProviderFactory<DB> {
@@ -46,14 +62,47 @@ impl<DB: Database> ProviderFactory<DB> {
     /// [`BlockHashReader`].  This may fail if the inner read/write database transaction fails to
     /// open.
     pub fn provider_rw(&self) -> Result<DatabaseProviderRW<'_, DB>> {
-        Ok(DatabaseProviderRW(DatabaseProvider::new_rw(self.db.tx_mut()?, self.chain_spec.clone())))
+        let provider = DatabaseProvider::new_rw(self.db.tx_mut()?, self.chain_spec.clone());
+        if self.elide_post_merge_td {
+            return Ok(DatabaseProviderRW(provider.with_post_merge_td_elision()))
+        }
+        Ok(DatabaseProviderRW(provider))
     }
 }
 
 impl<DB> ProviderFactory<DB> {
     /// create new database provider
     pub fn new(db: DB, chain_spec: Arc<ChainSpec>) -> Self {
-        Self { db, chain_spec }
+        Self { db, chain_spec, bytecode_cache: None, elide_post_merge_td: false }
+    }
+
+    /// Serves the bytecode lookups of all state providers created by this factory from the given
+    /// cache.
+    pub fn with_bytecode_cache(mut self, bytecode_cache: BytecodeCache) -> Self {
+        self.bytecode_cache = Some(bytecode_cache);
+        self
+    }
+
+    /// Don't write total difficulty entries for blocks at or above the merge block with the
+    /// read-write providers of this factory.
+    ///
+    /// See [DatabaseProvider::with_post_merge_td_elision].
+    pub fn with_post_merge_td_elision(mut self) -> Self {
+        self.elide_post_merge_td = true;
+        self
+    }
+
+    /// Returns the bytecode cache, if configured.
+    pub fn bytecode_cache(&self) -> Option<&BytecodeCache> {
+        self.bytecode_cache.as_ref()
+    }
+
+    /// Wraps the state provider with the bytecode cache, if configured.
+    fn with_cached_bytecode<'a>(&self, provider: StateProviderBox<'a>) -> StateProviderBox<'a> {
+        match &self.bytecode_cache {
+            Some(cache) => Box::new(CachedBytecodeStateProvider::new(provider, cache.clone())),
+            None => provider,
+        }
     }
 }
 
@@ -69,13 +118,20 @@ impl<DB: Database> ProviderFactory<DB> {
             db: init_db(path, log_level)
                 .map_err(|e| reth_interfaces::Error::Custom(e.to_string()))?,
             chain_spec,
+            bytecode_cache: None,
+            elide_post_merge_td: false,
         })
     }
 }
 
 impl<DB: Clone> Clone for ProviderFactory<DB> {
     fn clone(&self) -> Self {
-        Self { db: self.db.clone(), chain_spec: Arc::clone(&self.chain_spec) }
+        Self {
+            db: self.db.clone(),
+            chain_spec: Arc::clone(&self.chain_spec),
+            bytecode_cache: self.bytecode_cache.clone(),
+            elide_post_merge_td: self.elide_post_merge_td,
+        }
     }
 }
 
@@ -83,7 +139,7 @@ impl<DB: Database> ProviderFactory<DB> {
     /// Storage provider for latest block
     pub fn latest(&self) -> Result<StateProviderBox<'_>> {
         trace!(target: "providers::db", "Returning latest state provider");
-        Ok(Box::new(LatestStateProvider::new(self.db.tx()?)))
+        Ok(self.with_cached_bytecode(Box::new(LatestStateProvider::new(self.db.tx()?))))
     }
 
     /// Storage provider for state at that given block
@@ -96,7 +152,9 @@ impl<DB: Database> ProviderFactory<DB> {
         if block_number == provider.best_block_number().unwrap_or_default() &&
             block_number == provider.last_block_number().unwrap_or_default()
         {
-            return Ok(Box::new(LatestStateProvider::new(provider.into_tx())))
+            return Ok(
+                self.with_cached_bytecode(Box::new(LatestStateProvider::new(provider.into_tx())))
+            )
         }
 
         // +1 as the changeset that we want is the one that was applied after this block.
@@ -126,7 +184,7 @@ impl<DB: Database> ProviderFactory<DB> {
             );
         }
 
-        Ok(Box::new(state_provider))
+        Ok(self.with_cached_bytecode(Box::new(state_provider)))
     }
 
     /// Storage provider for state at that given block
@@ -384,27 +442,80 @@ where
     }
 }
 
+impl<DB: Database> ChainStatsReader for ProviderFactory<DB> {
+    fn total_transactions(&self, block: BlockNumber) -> Result<Option<u64>> {
+        self.provider()?.total_transactions(block)
+    }
+
+    fn cumulative_gas_used(&self, block: BlockNumber) -> Result<Option<u64>> {
+        self.provider()?.cumulative_gas_used(block)
+    }
+}
+
+impl<DB: Database> PreimageReader for ProviderFactory<DB> {
+    fn preimage(&self, hash: H256) -> Result<Option<Bytes>> {
+        self.provider()?.preimage(hash)
+    }
+}
+
 impl<DB: Database> PruneCheckpointReader for ProviderFactory<DB> {
     fn get_prune_checkpoint(&self, part: PrunePart) -> Result<Option<PruneCheckpoint>> {
         self.provider()?.get_prune_checkpoint(part)
     }
 }
 
+impl<DB: Database> ChainStateReader for ProviderFactory<DB> {
+    fn get_chain_state(&self, key: ChainStateKey) -> Result<Option<H256>> {
+        self.provider()?.get_chain_state(key)
+    }
+}
+
+impl<DB: Database> SnapStateProviderFactory for ProviderFactory<DB> {
+    fn snap_state_by_root(&self, root: H256) -> Result<Option<SnapStateProviderBox<'_>>> {
+        let provider = self.provider()?;
+        let Some((block, tip)) = provider.snap_state_block(root)? else { return Ok(None) };
+        trace!(target: "providers::db", ?root, block, tip, "Returning snap state provider");
+        Ok(Some(Box::new(HashedStateProvider::at_block(provider.into_tx(), block, tip)?)))
+    }
+}
+
+impl<DB: Database> ChainStateWriter for ProviderFactory<DB> {
+    fn save_chain_state(&self, entries: &[(ChainStateKey, H256)]) -> Result<()> {
+        let provider = self.provider_rw()?;
+        provider.save_chain_state(entries)?;
+        provider.commit()?;
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::ProviderFactory;
-    use crate::{BlockHashReader, BlockNumReader, BlockWriter, TransactionsProvider};
+    use crate::{
+        BlockHashReader, BlockNumReader, BlockReader, BlockWriter, HashingWriter, HeaderProvider,
+        TransactionsProvider,
+    };
     use assert_matches::assert_matches;
     use reth_db::{
+        cursor::DbCursorRO,
         tables,
         test_utils::{create_test_rw_db, ERROR_TEMPDIR},
+        transaction::DbTx,
         DatabaseEnv,
     };
-    use reth_interfaces::test_utils::{generators, generators::random_block};
+    use reth_interfaces::{
+        provider::ProviderError,
+        test_utils::{generators, generators::random_block},
+    };
     use reth_primitives::{
-        hex_literal::hex, ChainSpecBuilder, PruneMode, PruneModes, SealedBlock, TxNumber, H256,
+        hex_literal::hex, Account, ChainSpec, ChainSpecBuilder, PruneMode, PruneModes, SealedBlock,
+        StorageEntry, TxNumber, H256, U256,
     };
     use reth_rlp::Decodable;
+    use reth_trie::{
+        hashed_cursor::{HashedPostState, HashedStorage},
+        test_utils::state_root_prehashed,
+    };
     use std::{ops::RangeInclusive, sync::Arc};
 
     #[test]
@@ -494,6 +605,135 @@ mod tests {
         }
     }
 
+    #[test]
+    fn insert_block_post_merge_td_elision() {
+        let final_td = U256::from(100);
+        let chain_spec = Arc::new(ChainSpec {
+            paris_block_and_final_difficulty: Some((1, final_td)),
+            ..ChainSpecBuilder::mainnet().build()
+        });
+
+        let mut rng = generators::rng();
+        let genesis = random_block(&mut rng, 0, None, Some(0), Some(0));
+        let block1 = random_block(&mut rng, 1, Some(genesis.hash), Some(0), Some(0));
+        let block2 = random_block(&mut rng, 2, Some(block1.hash), Some(0), Some(0));
+
+        for elide in [false, true] {
+            let mut factory = ProviderFactory::new(create_test_rw_db(), chain_spec.clone());
+            if elide {
+                factory = factory.with_post_merge_td_elision();
+            }
+
+            let provider = factory.provider_rw().unwrap();
+            for block in [&genesis, &block1, &block2] {
+                assert_matches!(provider.insert_block(block.clone(), None, None), Ok(_));
+            }
+
+            // only the entries of blocks before the merge are stored with elision
+            let stored = if elide { 1 } else { 3 };
+            assert_eq!(provider.tx_ref().entries::<tables::HeaderTD>().unwrap(), stored);
+            assert_eq!(provider.header_td_by_number(0).unwrap(), Some(genesis.difficulty));
+            assert_eq!(provider.header_td_by_number(2).unwrap(), Some(final_td));
+        }
+    }
+
+    #[test]
+    fn insert_hashed_state() {
+        let chain_spec = ChainSpecBuilder::mainnet().build();
+        let db = create_test_rw_db();
+        let factory = ProviderFactory::new(db, Arc::new(chain_spec));
+        let provider = factory.provider_rw().unwrap();
+
+        let (address1, address2) = (H256::random(), H256::random());
+        let (slot1, slot2, slot3) = (H256::random(), H256::random(), H256::random());
+        let account1 = Account { nonce: 1, balance: U256::from(10), bytecode_hash: None };
+        let account2 = Account { nonce: 2, balance: U256::from(20), bytecode_hash: None };
+
+        // new accounts and storage
+        let mut hashed_state = HashedPostState::default();
+        hashed_state.insert_account(address1, account1);
+        hashed_state.insert_account(address2, account2);
+        let mut storage = HashedStorage::new(false);
+        storage.insert_non_zero_valued_storage(slot1, U256::from(1));
+        storage.insert_non_zero_valued_storage(slot2, U256::from(2));
+        hashed_state.insert_hashed_storage(address1, storage);
+
+        let expected_root = state_root_prehashed(
+            [
+                (address1, (account1, vec![(slot1, U256::from(1)), (slot2, U256::from(2))])),
+                (address2, (account2, vec![])),
+            ]
+            .into_iter(),
+        );
+        assert_matches!(
+            provider.insert_hashed_state(&hashed_state.sorted(), 1, H256::zero(), expected_root),
+            Ok(())
+        );
+        let tx = provider.tx_ref();
+        assert_eq!(tx.get::<tables::HashedAccount>(address2).unwrap(), Some(account2));
+        assert_eq!(tx.entries::<tables::HashedStorage>().unwrap(), 2);
+
+        // wiped storage and cleared account
+        let mut hashed_state = HashedPostState::default();
+        hashed_state.insert_cleared_account(address2);
+        let mut storage = HashedStorage::new(true);
+        storage.insert_non_zero_valued_storage(slot3, U256::from(3));
+        hashed_state.insert_hashed_storage(address1, storage);
+
+        let expected_root = state_root_prehashed(
+            [(address1, (account1, vec![(slot3, U256::from(3))]))].into_iter(),
+        );
+        assert_matches!(
+            provider.insert_hashed_state(&hashed_state.sorted(), 2, H256::zero(), expected_root),
+            Ok(())
+        );
+        let tx = provider.tx_ref();
+        assert_eq!(tx.get::<tables::HashedAccount>(address2).unwrap(), None);
+        let storage = tx
+            .cursor_read::<tables::HashedStorage>()
+            .unwrap()
+            .walk(None)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(storage, vec![(address1, StorageEntry { key: slot3, value: U256::from(3) })]);
+
+        // mismatching state root
+        let mut hashed_state = HashedPostState::default();
+        hashed_state.insert_account(address2, account2);
+        assert_matches!(
+            provider.insert_hashed_state(&hashed_state.sorted(), 3, H256::zero(), H256::random()),
+            Err(reth_interfaces::Error::Provider(ProviderError::StateRootMismatch {
+                block_number: 3,
+                ..
+            }))
+        );
+    }
+
+    #[test]
+    fn block_transaction_count_and_existence() {
+        let chain_spec = ChainSpecBuilder::mainnet().build();
+        let db = create_test_rw_db();
+        let factory = ProviderFactory::new(db, Arc::new(chain_spec));
+
+        let mut rng = generators::rng();
+        let block = random_block(&mut rng, 0, None, Some(3), None);
+        let other = random_block(&mut rng, 1, Some(block.hash), Some(2), None);
+
+        let provider = factory.provider_rw().unwrap();
+        assert_matches!(provider.insert_block(block.clone(), None, None), Ok(_));
+        assert_matches!(provider.insert_block(other.clone(), None, None), Ok(_));
+
+        assert_matches!(provider.block_transaction_count(0.into()), Ok(Some(3)));
+        assert_matches!(provider.block_transaction_count(other.hash.into()), Ok(Some(2)));
+        assert_matches!(provider.block_transaction_count(2.into()), Ok(None));
+
+        assert_matches!(provider.block_contains_transaction(0, block.body[2].hash), Ok(true));
+        assert_matches!(provider.block_contains_transaction(1, block.body[2].hash), Ok(false));
+        assert_matches!(provider.block_contains_transaction(1, other.body[0].hash), Ok(true));
+        assert_matches!(provider.block_contains_transaction(0, H256::random()), Ok(false));
+    }
+
     #[test]
     fn get_take_block_transaction_range_recover_senders() {
         let chain_spec = ChainSpecBuilder::mainnet().build();
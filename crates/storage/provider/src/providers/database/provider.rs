@@ -4,18 +4,20 @@ use crate::{
         AccountExtReader, BlockSource, ChangeSetReader, ReceiptProvider, StageCheckpointWriter,
     },
     AccountReader, BlockExecutionWriter, BlockHashReader, BlockNumReader, BlockReader, BlockWriter,
-    EvmEnvProvider, HashingWriter, HeaderProvider, HistoryWriter, PostState, ProviderError,
-    PruneCheckpointReader, PruneCheckpointWriter, StageCheckpointReader, StorageReader,
-    TransactionsProvider, WithdrawalsProvider,
+    ChainStateReader, ChainStateWriter, ChainStatsReader, EvmEnvProvider, HashingWriter,
+    HeaderProvider, HistoryWriter, PostState, PreimageReader, ProviderError, PruneCheckpointReader,
+    PruneCheckpointWriter, StageCheckpointReader, StorageReader, TransactionsProvider,
+    WithdrawalsProvider, SNAP_SERVED_STATES,
 };
 use itertools::{izip, Itertools};
 use reth_db::{
     common::KeyValue,
-    cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO},
+    cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW},
     database::{Database, DatabaseGAT},
     models::{
         sharded_key, storage_sharded_key::StorageShardedKey, AccountBeforeTx, BlockNumberAddress,
-        ShardedKey, StoredBlockBodyIndices, StoredBlockOmmers, StoredBlockWithdrawals,
+        ChainStateKey, ShardedKey, StoredBlockBodyIndices, StoredBlockOmmers,
+        StoredBlockWithdrawals,
     },
     table::{Table, TableRow},
     tables,
@@ -28,9 +30,9 @@ use reth_interfaces::{
 };
 use reth_primitives::{
     keccak256,
-    stage::{StageCheckpoint, StageId},
+    stage::{StageCheckpoint, StageId, StageUnitCheckpoint},
     trie::Nibbles,
-    Account, Address, Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithSenders,
+    Account, Address, Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithSenders, Bytes,
     ChainInfo, ChainSpec, Hardfork, Head, Header, PruneCheckpoint, PruneModes, PrunePart, Receipt,
     SealedBlock, SealedBlockWithSenders, SealedHeader, StorageEntry, TransactionMeta,
     TransactionSigned, TransactionSignedEcRecovered, TransactionSignedNoHash, TxHash, TxNumber,
@@ -41,7 +43,7 @@ use reth_revm_primitives::{
     env::{fill_block_env, fill_cfg_and_block_env, fill_cfg_env},
     primitives::{BlockEnv, CfgEnv, SpecId},
 };
-use reth_trie::{prefix_set::PrefixSetMut, StateRoot};
+use reth_trie::{hashed_cursor::HashedPostState, prefix_set::PrefixSetMut, StateRoot};
 use std::{
     collections::{btree_map::Entry, BTreeMap, BTreeSet, HashMap, HashSet},
     fmt::Debug,
@@ -98,13 +100,25 @@ where
     tx: TX,
     /// Chain spec
     chain_spec: Arc<ChainSpec>,
+    /// Whether total difficulty entries of blocks after the merge are not written.
+    elide_post_merge_td: bool,
     _phantom_data: std::marker::PhantomData<&'this TX>,
 }
 
 impl<'this, TX: DbTxMut<'this>> DatabaseProvider<'this, TX> {
     /// Creates a provider with an inner read-write transaction.
     pub fn new_rw(tx: TX, chain_spec: Arc<ChainSpec>) -> Self {
-        Self { tx, chain_spec, _phantom_data: std::marker::PhantomData }
+        Self { tx, chain_spec, elide_post_merge_td: false, _phantom_data: std::marker::PhantomData }
+    }
+
+    /// Don't write total difficulty entries for inserted blocks at or above the merge block.
+    ///
+    /// Their total difficulty is the final total difficulty of the chain, which is returned without
+    /// consulting the table, see [HeaderProvider::header_td_by_number]. This should match the
+    /// post-merge elision of the total difficulty stage.
+    pub fn with_post_merge_td_elision(mut self) -> Self {
+        self.elide_post_merge_td = true;
+        self
     }
 }
 
@@ -156,10 +170,17 @@ where
     Ok(Vec::new())
 }
 
+/// The state of accounts and their storage before a range of blocks, recreated from the
+/// changesets.
+///
+/// Double option around Account represent if Account state is know (first option) and account is
+/// removed (Second Option).
+type LocalPlainState = BTreeMap<Address, (Option<Option<Account>>, BTreeMap<H256, U256>)>;
+
 impl<'this, TX: DbTx<'this>> DatabaseProvider<'this, TX> {
     /// Creates a provider with an inner read-only transaction.
     pub fn new(tx: TX, chain_spec: Arc<ChainSpec>) -> Self {
-        Self { tx, chain_spec, _phantom_data: std::marker::PhantomData }
+        Self { tx, chain_spec, elide_post_merge_td: false, _phantom_data: std::marker::PhantomData }
     }
 
     /// Consume `DbTx` or `DbTxMut`.
@@ -187,15 +208,42 @@ impl<'this, TX: DbTx<'this>> DatabaseProvider<'this, TX> {
             .walk(Some(T::Key::default()))?
             .collect::<std::result::Result<Vec<_>, DatabaseError>>()
     }
-}
 
-impl<'this, TX: DbTxMut<'this> + DbTx<'this>> DatabaseProvider<'this, TX> {
-    /// Commit database transaction.
-    pub fn commit(self) -> Result<bool> {
-        Ok(self.tx.commit()?)
-    }
+    /// Returns the block of the state with the given root, if it is the state of one of the last
+    /// [SNAP_SERVED_STATES] blocks, together with the block of the hashed state.
+    ///
+    /// Returns `None` while the hashed state and the tries are being updated by the pipeline, in
+    /// which case they don't match the state of any block.
+    pub fn snap_state_block(&self, root: H256) -> Result<Option<(BlockNumber, BlockNumber)>> {
+        let tip = self.get_stage_checkpoint(StageId::Finish)?.unwrap_or_default().block_number;
+        // the hashed state and the tries are updated by consecutive stages of the pipeline
+        for stage in [StageId::AccountHashing, StageId::StorageHashing, StageId::MerkleExecute] {
+            let checkpoint = self.get_stage_checkpoint(stage)?.unwrap_or_default();
+            let in_progress = match checkpoint.stage_checkpoint {
+                Some(StageUnitCheckpoint::Account(checkpoint)) => checkpoint.address.is_some(),
+                Some(StageUnitCheckpoint::Storage(checkpoint)) => checkpoint.address.is_some(),
+                _ => false,
+            };
+            if checkpoint.block_number != tip || in_progress {
+                return Ok(None)
+            }
+        }
+        if self
+            .get_stage_checkpoint_progress(StageId::MerkleExecute)?
+            .map_or(false, |progress| !progress.is_empty())
+        {
+            return Ok(None)
+        }
 
-    // TODO(joshie) TEMPORARY should be moved to trait providers
+        let mut cursor = self.tx.cursor_read::<tables::Headers>()?;
+        for entry in cursor.walk_back(Some(tip))?.take(SNAP_SERVED_STATES as usize) {
+            let (number, header) = entry?;
+            if header.state_root == root {
+                return Ok(Some((number, tip)))
+            }
+        }
+        Ok(None)
+    }
 
     /// Traverse over changesets and plain state and recreate the [`PostState`]s for the given range
     /// of blocks.
@@ -217,10 +265,10 @@ impl<'this, TX: DbTxMut<'this> + DbTx<'this>> DatabaseProvider<'this, TX> {
     ///     1. Take the old value from the changeset
     ///     2. Take the new value from the local state
     ///     3. Set the local state to the value in the changeset
-    ///
-    /// If `TAKE` is `true`, the local state will be written to the plain state tables.
     /// 5. Get all receipts from table
-    fn get_take_block_execution_result_range<const TAKE: bool>(
+    ///
+    /// Only reads from the database, so it can be used with a read-only transaction.
+    pub fn block_execution_result_range(
         &self,
         range: RangeInclusive<BlockNumber>,
     ) -> Result<Vec<PostState>> {
@@ -228,8 +276,7 @@ impl<'this, TX: DbTxMut<'this> + DbTx<'this>> DatabaseProvider<'this, TX> {
             return Ok(Vec::new())
         }
 
-        // We are not removing block meta as it is used to get block changesets.
-        let block_bodies = self.get_or_take::<tables::BlockBodyIndices, false>(range.clone())?;
+        let block_bodies = self.read_range::<tables::BlockBodyIndices>(range.clone())?;
 
         // get transaction receipts
         let from_transaction_num =
@@ -237,19 +284,35 @@ impl<'this, TX: DbTxMut<'this> + DbTx<'this>> DatabaseProvider<'this, TX> {
         let to_transaction_num =
             block_bodies.last().expect("already checked if there are blocks").1.last_tx_num();
         let receipts =
-            self.get_or_take::<tables::Receipts, TAKE>(from_transaction_num..=to_transaction_num)?;
-
-        let storage_range = BlockNumberAddress::range(range.clone());
+            self.read_range::<tables::Receipts>(from_transaction_num..=to_transaction_num)?;
 
         let storage_changeset =
-            self.get_or_take::<tables::StorageChangeSet, TAKE>(storage_range)?;
-        let account_changeset = self.get_or_take::<tables::AccountChangeSet, TAKE>(range)?;
+            self.read_range::<tables::StorageChangeSet>(BlockNumberAddress::range(range.clone()))?;
+        let account_changeset = self.read_range::<tables::AccountChangeSet>(range)?;
 
-        // iterate previous value and get plain state value to create changeset
-        // Double option around Account represent if Account state is know (first option) and
-        // account is removed (Second Option)
-        type LocalPlainState = BTreeMap<Address, (Option<Option<Account>>, BTreeMap<H256, U256>)>;
+        Ok(self
+            .recreate_block_execution_results(
+                block_bodies,
+                receipts,
+                account_changeset,
+                storage_changeset,
+            )?
+            .0)
+    }
 
+    /// Recreates the [`PostState`]s of a range of blocks from their block body indices, receipts
+    /// and changesets, see [`DatabaseProvider::block_execution_result_range`].
+    ///
+    /// Also returns the plain state before the range of blocks, for all accounts and storage
+    /// slots that were changed.
+    fn recreate_block_execution_results(
+        &self,
+        block_bodies: Vec<KeyValue<tables::BlockBodyIndices>>,
+        receipts: Vec<KeyValue<tables::Receipts>>,
+        account_changeset: Vec<KeyValue<tables::AccountChangeSet>>,
+        storage_changeset: Vec<KeyValue<tables::StorageChangeSet>>,
+    ) -> Result<(Vec<PostState>, LocalPlainState)> {
+        // iterate previous value and get plain state value to create changeset
         let mut local_plain_state: LocalPlainState = BTreeMap::new();
 
         // iterate in reverse and get plain state.
@@ -258,8 +321,8 @@ impl<'this, TX: DbTxMut<'this> + DbTx<'this>> DatabaseProvider<'this, TX> {
         let mut block_states =
             BTreeMap::from_iter(block_bodies.iter().map(|(num, _)| (*num, PostState::default())));
 
-        let mut plain_accounts_cursor = self.tx.cursor_write::<tables::PlainAccountState>()?;
-        let mut plain_storage_cursor = self.tx.cursor_dup_write::<tables::PlainStorageState>()?;
+        let mut plain_accounts_cursor = self.tx.cursor_read::<tables::PlainAccountState>()?;
+        let mut plain_storage_cursor = self.tx.cursor_dup_read::<tables::PlainStorageState>()?;
 
         // add account changeset changes
         for (block_number, account_before) in account_changeset.into_iter().rev() {
@@ -326,7 +389,82 @@ impl<'this, TX: DbTxMut<'this> + DbTx<'this>> DatabaseProvider<'this, TX> {
             );
         }
 
+        // iterate over block body and create ExecutionResult
+        let mut receipt_iter = receipts.into_iter();
+
+        // loop break if we are at the end of the blocks.
+        for (block_number, block_body) in block_bodies.into_iter() {
+            for _ in block_body.tx_num_range() {
+                if let Some((_, receipt)) = receipt_iter.next() {
+                    block_states
+                        .entry(block_number)
+                        .or_default()
+                        .add_receipt(block_number, receipt);
+                }
+            }
+        }
+        Ok((block_states.into_values().collect(), local_plain_state))
+    }
+
+    /// Return list of entries from table in the given range.
+    pub fn read_range<T: Table>(
+        &self,
+        range: impl RangeBounds<T::Key>,
+    ) -> std::result::Result<Vec<KeyValue<T>>, DatabaseError> {
+        self.tx.cursor_read::<T>()?.walk_range(range)?.collect::<std::result::Result<Vec<_>, _>>()
+    }
+}
+
+impl<'this, TX: DbTxMut<'this> + DbTx<'this>> DatabaseProvider<'this, TX> {
+    /// Commit database transaction.
+    pub fn commit(self) -> Result<bool> {
+        Ok(self.tx.commit()?)
+    }
+
+    // TODO(joshie) TEMPORARY should be moved to trait providers
+
+    /// Recreates the [`PostState`]s for the given range of blocks, see
+    /// [`DatabaseProvider::block_execution_result_range`].
+    ///
+    /// If `TAKE` is `true`, the receipts and changesets are removed, and the local state is
+    /// written to the plain state tables.
+    fn get_take_block_execution_result_range<const TAKE: bool>(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<Vec<PostState>> {
+        if range.is_empty() {
+            return Ok(Vec::new())
+        }
+
+        // We are not removing block meta as it is used to get block changesets.
+        let block_bodies = self.get_or_take::<tables::BlockBodyIndices, false>(range.clone())?;
+
+        // get transaction receipts
+        let from_transaction_num =
+            block_bodies.first().expect("already checked if there are blocks").1.first_tx_num();
+        let to_transaction_num =
+            block_bodies.last().expect("already checked if there are blocks").1.last_tx_num();
+        let receipts =
+            self.get_or_take::<tables::Receipts, TAKE>(from_transaction_num..=to_transaction_num)?;
+
+        let storage_range = BlockNumberAddress::range(range.clone());
+
+        let storage_changeset =
+            self.get_or_take::<tables::StorageChangeSet, TAKE>(storage_range)?;
+        let account_changeset = self.get_or_take::<tables::AccountChangeSet, TAKE>(range)?;
+
+        let (block_states, local_plain_state) = self.recreate_block_execution_results(
+            block_bodies,
+            receipts,
+            account_changeset,
+            storage_changeset,
+        )?;
+
         if TAKE {
+            let mut plain_accounts_cursor = self.tx.cursor_write::<tables::PlainAccountState>()?;
+            let mut plain_storage_cursor =
+                self.tx.cursor_dup_write::<tables::PlainStorageState>()?;
+
             // iterate over local plain state remove all account and all storages.
             for (address, (account, storage)) in local_plain_state.into_iter() {
                 // revert account
@@ -361,21 +499,7 @@ impl<'this, TX: DbTxMut<'this> + DbTx<'this>> DatabaseProvider<'this, TX> {
             }
         }
 
-        // iterate over block body and create ExecutionResult
-        let mut receipt_iter = receipts.into_iter();
-
-        // loop break if we are at the end of the blocks.
-        for (block_number, block_body) in block_bodies.into_iter() {
-            for _ in block_body.tx_num_range() {
-                if let Some((_, receipt)) = receipt_iter.next() {
-                    block_states
-                        .entry(block_number)
-                        .or_default()
-                        .add_receipt(block_number, receipt);
-                }
-            }
-        }
-        Ok(block_states.into_values().collect())
+        Ok(block_states)
     }
 
     /// Return list of entries from table
@@ -396,10 +520,7 @@ impl<'this, TX: DbTxMut<'this> + DbTx<'this>> DatabaseProvider<'this, TX> {
             }
             Ok(items)
         } else {
-            self.tx
-                .cursor_read::<T>()?
-                .walk_range(range)?
-                .collect::<std::result::Result<Vec<_>, _>>()
+            self.read_range::<T>(range)
         }
     }
 
@@ -563,7 +684,9 @@ impl<'this, TX: DbTxMut<'this> + DbTx<'this>> DatabaseProvider<'this, TX> {
 
         if TAKE {
             // rm HeaderTD
-            self.get_or_take::<tables::HeaderTD, TAKE>(range)?;
+            self.get_or_take::<tables::HeaderTD, TAKE>(range.clone())?;
+            // rm CumulativeGasUsed
+            self.get_or_take::<tables::CumulativeGasUsed, TAKE>(range)?;
             // rm HeaderNumbers
             let mut header_number_cursor = self.tx.cursor_write::<tables::HeaderNumbers>()?;
             for (_, hash) in block_header_hashes.iter() {
@@ -1077,7 +1200,17 @@ impl<'this, TX: DbTx<'this>> BlockReader for DatabaseProvider<'this, TX> {
                     transaction: tx.transaction,
                 }
             })
-            .collect();
+            .collect::<Vec<_>>();
+
+        // senders of blocks below the prune target of the sender recovery are not stored
+        let senders = if senders.len() == body.len() {
+            senders
+        } else {
+            body.iter()
+                .map(TransactionSigned::recover_signer)
+                .collect::<Option<Vec<_>>>()
+                .ok_or(ProviderError::SenderRecoveryError(block_number))?
+        };
 
         Ok(Some(Block { header, body, ommers, withdrawals }.with_senders(senders)))
     }
@@ -1464,6 +1597,42 @@ impl<'this, TX: DbTx<'this>> StorageReader for DatabaseProvider<'this, TX> {
     }
 }
 
+impl<'this, TX: DbTxMut<'this> + DbTx<'this>> DatabaseProvider<'this, TX> {
+    /// Calculates the state root from the changed prefixes, compares it with the expected state
+    /// root and writes the trie updates.
+    fn update_trie(
+        &self,
+        account_prefix_set: PrefixSetMut,
+        storage_prefix_set: HashMap<H256, PrefixSetMut>,
+        destroyed_accounts: HashSet<H256>,
+        end_block_number: BlockNumber,
+        end_block_hash: H256,
+        expected_state_root: H256,
+    ) -> Result<()> {
+        // This is the same as `StateRoot::incremental_root_with_updates`, only the prefix sets
+        // are pre-loaded.
+        let (state_root, trie_updates) = StateRoot::new(&self.tx)
+            .with_changed_account_prefixes(account_prefix_set.freeze())
+            .with_changed_storage_prefixes(
+                storage_prefix_set.into_iter().map(|(k, v)| (k, v.freeze())).collect(),
+            )
+            .with_destroyed_accounts(destroyed_accounts)
+            .root_with_updates()
+            .map_err(Into::<reth_db::DatabaseError>::into)?;
+        if state_root != expected_state_root {
+            return Err(ProviderError::StateRootMismatch {
+                got: state_root,
+                expected: expected_state_root,
+                block_number: end_block_number,
+                block_hash: end_block_hash,
+            }
+            .into())
+        }
+        trie_updates.flush(&self.tx)?;
+        Ok(())
+    }
+}
+
 impl<'this, TX: DbTxMut<'this> + DbTx<'this>> HashingWriter for DatabaseProvider<'this, TX> {
     fn insert_hashes(
         &self,
@@ -1506,29 +1675,100 @@ impl<'this, TX: DbTxMut<'this> + DbTx<'this>> HashingWriter for DatabaseProvider
         }
 
         // merkle tree
+        self.update_trie(
+            account_prefix_set,
+            storage_prefix_set,
+            destroyed_accounts,
+            *range.end(),
+            end_block_hash,
+            expected_state_root,
+        )
+    }
+
+    fn insert_hashed_state(
+        &self,
+        hashed_state: &HashedPostState,
+        end_block_number: BlockNumber,
+        end_block_hash: H256,
+        expected_state_root: H256,
+    ) -> Result<()> {
+        let mut account_prefix_set = PrefixSetMut::default();
+        let mut storage_prefix_set: HashMap<H256, PrefixSetMut> = HashMap::default();
+        let mut destroyed_accounts = HashSet::default();
+
+        // hashed accounts
         {
-            // This is the same as `StateRoot::incremental_root_with_updates`, only the prefix sets
-            // are pre-loaded.
-            let (state_root, trie_updates) = StateRoot::new(&self.tx)
-                .with_changed_account_prefixes(account_prefix_set.freeze())
-                .with_changed_storage_prefixes(
-                    storage_prefix_set.into_iter().map(|(k, v)| (k, v.freeze())).collect(),
-                )
-                .with_destroyed_accounts(destroyed_accounts)
-                .root_with_updates()
-                .map_err(Into::<reth_db::DatabaseError>::into)?;
-            if state_root != expected_state_root {
-                return Err(ProviderError::StateRootMismatch {
-                    got: state_root,
-                    expected: expected_state_root,
-                    block_number: *range.end(),
-                    block_hash: end_block_hash,
+            let mut hashed_accounts_cursor = self.tx.cursor_write::<tables::HashedAccount>()?;
+            for (hashed_address, account) in hashed_state.accounts() {
+                account_prefix_set.insert(Nibbles::unpack(hashed_address));
+                hashed_accounts_cursor.upsert(*hashed_address, *account)?;
+            }
+            for hashed_address in hashed_state.cleared_accounts() {
+                account_prefix_set.insert(Nibbles::unpack(hashed_address));
+                destroyed_accounts.insert(*hashed_address);
+                if hashed_accounts_cursor.seek_exact(*hashed_address)?.is_some() {
+                    hashed_accounts_cursor.delete_current()?;
                 }
-                .into())
             }
-            trie_updates.flush(&self.tx)?;
         }
-        Ok(())
+
+        // hashed storages
+        {
+            let mut hashed_storage_cursor = self.tx.cursor_dup_write::<tables::HashedStorage>()?;
+            for (hashed_address, hashed_storage) in hashed_state.storages() {
+                account_prefix_set.insert(Nibbles::unpack(hashed_address));
+                let prefix_set = storage_prefix_set.entry(*hashed_address).or_default();
+
+                // The slots of a wiped storage are not part of the hashed state, so they have to
+                // be added to the prefix set before they are removed.
+                if hashed_storage.wiped() {
+                    if let Some((_, entry)) = hashed_storage_cursor.seek_exact(*hashed_address)? {
+                        prefix_set.insert(Nibbles::unpack(entry.key));
+                        while let Some((_, entry)) = hashed_storage_cursor.next_dup()? {
+                            prefix_set.insert(Nibbles::unpack(entry.key));
+                        }
+                        hashed_storage_cursor.seek_exact(*hashed_address)?;
+                        hashed_storage_cursor.delete_current_duplicates()?;
+                    }
+                }
+
+                for (hashed_slot, value) in hashed_storage.non_zero_valued_storage() {
+                    prefix_set.insert(Nibbles::unpack(hashed_slot));
+                    if hashed_storage_cursor
+                        .seek_by_key_subkey(*hashed_address, *hashed_slot)?
+                        .filter(|entry| entry.key == *hashed_slot)
+                        .is_some()
+                    {
+                        hashed_storage_cursor.delete_current()?;
+                    }
+                    hashed_storage_cursor.upsert(
+                        *hashed_address,
+                        StorageEntry { key: *hashed_slot, value: *value },
+                    )?;
+                }
+
+                for hashed_slot in hashed_storage.zero_valued_slots() {
+                    prefix_set.insert(Nibbles::unpack(hashed_slot));
+                    if hashed_storage_cursor
+                        .seek_by_key_subkey(*hashed_address, *hashed_slot)?
+                        .filter(|entry| entry.key == *hashed_slot)
+                        .is_some()
+                    {
+                        hashed_storage_cursor.delete_current()?;
+                    }
+                }
+            }
+        }
+
+        // merkle tree
+        self.update_trie(
+            account_prefix_set,
+            storage_prefix_set,
+            destroyed_accounts,
+            end_block_number,
+            end_block_hash,
+            expected_state_root,
+        )
     }
 
     fn unwind_storage_hashing(
@@ -1939,16 +2179,32 @@ impl<'this, TX: DbTxMut<'this> + DbTx<'this>> BlockWriter for DatabaseProvider<'
         self.tx.put::<tables::Headers>(block.number, block.header.as_ref().clone())?;
         self.tx.put::<tables::HeaderNumbers>(block.hash(), block.number)?;
 
-        // total difficulty
-        let ttd = if block.number == 0 {
-            block.difficulty
-        } else {
-            let parent_block_number = block.number - 1;
-            let parent_ttd = self.header_td_by_number(parent_block_number)?.unwrap_or_default();
-            parent_ttd + block.difficulty
-        };
+        // total difficulty, unless it is elided after the merge
+        if !self.elide_post_merge_td ||
+            self.chain_spec.final_paris_total_difficulty(block.number).is_none()
+        {
+            let ttd = if block.number == 0 {
+                block.difficulty
+            } else {
+                let parent_block_number = block.number - 1;
+                let parent_ttd = self.header_td_by_number(parent_block_number)?.unwrap_or_default();
+                parent_ttd + block.difficulty
+            };
+
+            self.tx.put::<tables::HeaderTD>(block.number, ttd.into())?;
+        }
 
-        self.tx.put::<tables::HeaderTD>(block.number, ttd.into())?;
+        // cumulative gas used, only maintained if the table is populated up to the parent block
+        if block.number > 0 {
+            if let Some(parent_gas_used) =
+                self.tx.get::<tables::CumulativeGasUsed>(block.number - 1)?
+            {
+                self.tx.put::<tables::CumulativeGasUsed>(
+                    block.number,
+                    parent_gas_used + block.gas_used,
+                )?;
+            }
+        }
 
         // insert body ommers data
         if !block.ommers.is_empty() {
@@ -2046,11 +2302,21 @@ impl<'this, TX: DbTxMut<'this> + DbTx<'this>> BlockWriter for DatabaseProvider<'
             self.insert_block(block, Some(senders), prune_modes)?;
         }
 
+        // Hash the changed state of the whole batch at once, before the post state is consumed.
+        let hashed_state = state.hash_state_slow().sorted();
+
         // Write state and changesets to the database.
         // Must be written after blocks because of the receipt lookup.
         state.write_to_db(self.tx_ref(), new_tip_number)?;
 
-        self.insert_hashes(first_number..=last_block_number, last_block_hash, expected_state_root)?;
+        // Write the hashed state and update the trie in a single pass for all blocks, instead of
+        // reading the changes back from the changesets.
+        self.insert_hashed_state(
+            &hashed_state,
+            last_block_number,
+            last_block_hash,
+            expected_state_root,
+        )?;
 
         self.calculate_history_indices(first_number..=last_block_number)?;
 
@@ -2061,6 +2327,22 @@ impl<'this, TX: DbTxMut<'this> + DbTx<'this>> BlockWriter for DatabaseProvider<'
     }
 }
 
+impl<'this, TX: DbTx<'this>> ChainStatsReader for DatabaseProvider<'this, TX> {
+    fn total_transactions(&self, block: BlockNumber) -> Result<Option<u64>> {
+        Ok(self.block_body_indices(block)?.map(|indices| indices.next_tx_num()))
+    }
+
+    fn cumulative_gas_used(&self, block: BlockNumber) -> Result<Option<u64>> {
+        Ok(self.tx.get::<tables::CumulativeGasUsed>(block)?)
+    }
+}
+
+impl<'this, TX: DbTx<'this>> PreimageReader for DatabaseProvider<'this, TX> {
+    fn preimage(&self, hash: H256) -> Result<Option<Bytes>> {
+        Ok(self.tx.get::<tables::Preimages>(hash)?.map(|stored| stored.preimage))
+    }
+}
+
 impl<'this, TX: DbTx<'this>> PruneCheckpointReader for DatabaseProvider<'this, TX> {
     fn get_prune_checkpoint(&self, part: PrunePart) -> Result<Option<PruneCheckpoint>> {
         Ok(self.tx.get::<tables::PruneCheckpoints>(part)?)
@@ -2072,3 +2354,18 @@ impl<'this, TX: DbTxMut<'this>> PruneCheckpointWriter for DatabaseProvider<'this
         Ok(self.tx.put::<tables::PruneCheckpoints>(part, checkpoint)?)
     }
 }
+
+impl<'this, TX: DbTx<'this>> ChainStateReader for DatabaseProvider<'this, TX> {
+    fn get_chain_state(&self, key: ChainStateKey) -> Result<Option<H256>> {
+        Ok(self.tx.get::<tables::ChainState>(key)?)
+    }
+}
+
+impl<'this, TX: DbTxMut<'this>> ChainStateWriter for DatabaseProvider<'this, TX> {
+    fn save_chain_state(&self, entries: &[(ChainStateKey, H256)]) -> Result<()> {
+        for (key, hash) in entries {
+            self.tx.put::<tables::ChainState>(*key, *hash)?;
+        }
+        Ok(())
+    }
+}
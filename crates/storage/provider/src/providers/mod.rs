@@ -1,12 +1,16 @@
 use crate::{
     BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
     BlockchainTreePendingStateProvider, CanonChainTracker, CanonStateNotifications,
-    CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader, EvmEnvProvider, HeaderProvider,
-    PostStateDataProvider, ProviderError, PruneCheckpointReader, ReceiptProvider,
-    ReceiptProviderIdExt, StageCheckpointReader, StateProviderBox, StateProviderFactory,
-    TransactionsProvider, WithdrawalsProvider,
+    CanonStateSubscriptions, ChainSpecProvider, ChainStateReader, ChainStateWriter,
+    ChainStatsReader, ChangeSetReader, EvmEnvProvider, HeaderProvider, PostStateDataProvider,
+    PreimageReader, ProviderError, PruneCheckpointReader, ReceiptProvider, ReceiptProviderIdExt,
+    SnapStateProviderBox, SnapStateProviderFactory, StageCheckpointReader, StateProviderBox,
+    StateProviderFactory, TransactionsProvider, WithdrawalsProvider,
+};
+use reth_db::{
+    database::Database,
+    models::{ChainStateKey, StoredBlockBodyIndices},
 };
-use reth_db::{database::Database, models::StoredBlockBodyIndices};
 use reth_interfaces::{
     blockchain_tree::{BlockchainTreeEngine, BlockchainTreeViewer},
     consensus::ForkchoiceState,
@@ -15,14 +19,15 @@ use reth_interfaces::{
 use reth_primitives::{
     stage::{StageCheckpoint, StageId},
     Address, Block, BlockHash, BlockHashOrNumber, BlockId, BlockNumHash, BlockNumber,
-    BlockNumberOrTag, BlockWithSenders, ChainInfo, ChainSpec, Header, PruneCheckpoint, PrunePart,
-    Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader, TransactionMeta, TransactionSigned,
-    TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, H256, U256,
+    BlockNumberOrTag, BlockWithSenders, Bytes, ChainInfo, ChainSpec, Header, PruneCheckpoint,
+    PrunePart, Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader, TransactionMeta,
+    TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, H256, U256,
 };
 use reth_revm_primitives::primitives::{BlockEnv, CfgEnv};
 pub use state::{
     historical::{HistoricalStateProvider, HistoricalStateProviderRef},
     latest::{LatestStateProvider, LatestStateProviderRef},
+    snap::{revert_hashed_state, HashedStateProvider},
 };
 use std::{
     collections::{BTreeMap, HashSet},
@@ -32,11 +37,15 @@ use std::{
 };
 use tracing::trace;
 
+mod bytecode_cache;
 mod chain_info;
 mod database;
 mod post_state_provider;
 mod state;
 use crate::{providers::chain_info::ChainInfoTracker, traits::BlockSource};
+pub use bytecode_cache::{
+    BytecodeCache, CachedBytecodeStateProvider, DEFAULT_BYTECODE_CACHE_MAX_BYTES,
+};
 pub use database::*;
 pub use post_state_provider::PostStateProvider;
 use reth_db::models::AccountBeforeTx;
@@ -438,6 +447,30 @@ where
     }
 }
 
+impl<DB, Tree> ChainStatsReader for BlockchainProvider<DB, Tree>
+where
+    DB: Database,
+    Tree: Send + Sync,
+{
+    fn total_transactions(&self, block: BlockNumber) -> Result<Option<u64>> {
+        self.database.provider()?.total_transactions(block)
+    }
+
+    fn cumulative_gas_used(&self, block: BlockNumber) -> Result<Option<u64>> {
+        self.database.provider()?.cumulative_gas_used(block)
+    }
+}
+
+impl<DB, Tree> PreimageReader for BlockchainProvider<DB, Tree>
+where
+    DB: Database,
+    Tree: Send + Sync,
+{
+    fn preimage(&self, hash: H256) -> Result<Option<Bytes>> {
+        self.database.provider()?.preimage(hash)
+    }
+}
+
 impl<DB, Tree> PruneCheckpointReader for BlockchainProvider<DB, Tree>
 where
     DB: Database,
@@ -448,6 +481,36 @@ where
     }
 }
 
+impl<DB, Tree> ChainStateReader for BlockchainProvider<DB, Tree>
+where
+    DB: Database,
+    Tree: Send + Sync,
+{
+    fn get_chain_state(&self, key: ChainStateKey) -> Result<Option<H256>> {
+        self.database.get_chain_state(key)
+    }
+}
+
+impl<DB, Tree> SnapStateProviderFactory for BlockchainProvider<DB, Tree>
+where
+    DB: Database,
+    Tree: Send + Sync,
+{
+    fn snap_state_by_root(&self, root: H256) -> Result<Option<SnapStateProviderBox<'_>>> {
+        self.database.snap_state_by_root(root)
+    }
+}
+
+impl<DB, Tree> ChainStateWriter for BlockchainProvider<DB, Tree>
+where
+    DB: Database,
+    Tree: Send + Sync,
+{
+    fn save_chain_state(&self, entries: &[(ChainStateKey, H256)]) -> Result<()> {
+        self.database.save_chain_state(entries)
+    }
+}
+
 impl<DB, Tree> ChainSpecProvider for BlockchainProvider<DB, Tree>
 where
     DB: Send + Sync,
@@ -567,6 +630,10 @@ where
     fn unwind(&self, unwind_to: BlockNumber) -> Result<()> {
         self.tree.unwind(unwind_to)
     }
+
+    fn take_invalid_buffered_blocks(&self) -> Vec<SealedHeader> {
+        self.tree.take_invalid_buffered_blocks()
+    }
 }
 
 impl<DB, Tree> BlockchainTreeViewer for BlockchainProvider<DB, Tree>
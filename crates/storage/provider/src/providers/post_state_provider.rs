@@ -57,6 +57,12 @@ impl<SP: StateProvider, PSDP: PostStateDataProvider> StateRootProvider
         state.extend(post_state);
         self.state_provider.state_root(state)
     }
+
+    fn storage_root(&self, address: Address, post_state: PostState) -> Result<H256> {
+        let mut state = self.post_state_data_provider.state().clone();
+        state.extend(post_state);
+        self.state_provider.storage_root(address, state)
+    }
 }
 
 impl<SP: StateProvider, PSDP: PostStateDataProvider> StateProvider for PostStateProvider<SP, PSDP> {
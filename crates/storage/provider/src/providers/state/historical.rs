@@ -1,6 +1,6 @@
 use crate::{
-    providers::state::macros::delegate_provider_impls, AccountReader, BlockHashReader, PostState,
-    ProviderError, StateProvider, StateRootProvider,
+    providers::state::{macros::delegate_provider_impls, storage_root_with_changes},
+    AccountReader, BlockHashReader, PostState, ProviderError, StateProvider, StateRootProvider,
 };
 use reth_db::{
     cursor::{DbCursorRO, DbDupCursorRO},
@@ -14,7 +14,7 @@ use reth_interfaces::Result;
 use reth_primitives::{
     Account, Address, BlockNumber, Bytecode, Bytes, StorageKey, StorageValue, H256,
 };
-use std::marker::PhantomData;
+use std::{collections::BTreeMap, marker::PhantomData};
 
 /// State provider for a given block number which takes a tx reference.
 ///
@@ -204,6 +204,47 @@ impl<'a, 'b, TX: DbTx<'a>> StateRootProvider for HistoricalStateProviderRef<'a,
     fn state_root(&self, _post_state: PostState) -> Result<H256> {
         Err(ProviderError::StateRootNotAvailableForHistoricalBlock.into())
     }
+
+    /// Reverts the slots of the account that changed at or after the block before applying the
+    /// changes of the post state. Every slot the account ever wrote is looked up in the history
+    /// index, so this is proportional to the size of the account's storage history.
+    fn storage_root(&self, address: Address, post_state: PostState) -> Result<H256> {
+        let mut changes = BTreeMap::new();
+        let mut last_slot = None;
+        let mut cursor = self.tx.cursor_read::<tables::StorageHistory>()?;
+        for entry in cursor.walk(Some(StorageShardedKey::new(address, H256::zero(), 0)))? {
+            let (key, _) = entry?;
+            if key.address != address {
+                break
+            }
+
+            // history of a slot may be split into multiple shards
+            let slot = key.sharded_key.key;
+            if last_slot.replace(slot) == Some(slot) {
+                continue
+            }
+
+            match self.storage_history_lookup(address, slot)? {
+                HistoryInfo::InPlainState | HistoryInfo::MaybeInPlainState => {}
+                HistoryInfo::NotYetWritten | HistoryInfo::InChangeset(_) => {
+                    changes.insert(slot, self.storage(address, slot)?.unwrap_or_default());
+                }
+            }
+        }
+
+        let mut wiped = false;
+        if let Some(storage) = post_state.account_storage(&address) {
+            if storage.wiped() {
+                wiped = true;
+                changes.clear();
+            }
+            changes.extend(
+                storage.storage.iter().map(|(slot, value)| (H256(slot.to_be_bytes()), *value)),
+            );
+        }
+
+        storage_root_with_changes(self.tx, address, changes, wiped)
+    }
 }
 
 impl<'a, 'b, TX: DbTx<'a>> StateProvider for HistoricalStateProviderRef<'a, 'b, TX> {
@@ -336,7 +377,8 @@ impl LowestAvailableBlocks {
 mod tests {
     use crate::{
         providers::state::historical::{HistoryInfo, LowestAvailableBlocks},
-        AccountReader, HistoricalStateProvider, HistoricalStateProviderRef, StateProvider,
+        AccountReader, HistoricalStateProvider, HistoricalStateProviderRef, PostState,
+        StateProvider, StateRootProvider,
     };
     use reth_db::{
         database::Database,
@@ -347,7 +389,9 @@ mod tests {
         BlockNumberList,
     };
     use reth_interfaces::provider::ProviderError;
-    use reth_primitives::{hex_literal::hex, Account, StorageEntry, H160, H256, U256};
+    use reth_primitives::{hex_literal::hex, keccak256, Account, StorageEntry, H160, H256, U256};
+    use reth_trie::test_utils::storage_root;
+    use std::collections::BTreeMap;
 
     const ADDRESS: H160 = H160(hex!("0000000000000000000000000000000000000001"));
     const HIGHER_ADDRESS: H160 = H160(hex!("0000000000000000000000000000000000000005"));
@@ -615,4 +659,106 @@ mod tests {
             Ok(HistoryInfo::MaybeInPlainState)
         );
     }
+
+    #[test]
+    fn history_provider_storage_root() {
+        let db = create_test_rw_db();
+        let tx = db.tx_mut().unwrap();
+
+        let slot = H256::from_low_u64_be;
+        // the storage changes of blocks 1 to 3
+        let blocks = [
+            vec![(slot(1), U256::from(1)), (slot(2), U256::from(2))],
+            vec![(slot(1), U256::from(10)), (slot(3), U256::from(3))],
+            vec![(slot(2), U256::ZERO), (slot(3), U256::from(30))],
+        ];
+
+        // apply the blocks, writing the changesets and the history index, and record the storage
+        // root after every block
+        let mut storage = BTreeMap::<H256, U256>::new();
+        let mut history = BTreeMap::<H256, Vec<u64>>::new();
+        let mut roots = vec![storage_root(std::iter::empty())];
+        for (number, changes) in (1..).zip(&blocks) {
+            for (key, value) in changes {
+                let old = storage.insert(*key, *value).unwrap_or_default();
+                tx.put::<tables::StorageChangeSet>(
+                    (number, ADDRESS).into(),
+                    StorageEntry { key: *key, value: old },
+                )
+                .unwrap();
+                history.entry(*key).or_default().push(number);
+            }
+            storage.retain(|_, value| *value != U256::ZERO);
+            roots.push(storage_root(storage.clone().into_iter()));
+        }
+        for (key, blocks) in history {
+            tx.put::<tables::StorageHistory>(
+                StorageShardedKey {
+                    address: ADDRESS,
+                    sharded_key: ShardedKey { key, highest_block_number: u64::MAX },
+                },
+                BlockNumberList::new(blocks).unwrap(),
+            )
+            .unwrap();
+        }
+
+        // the current state, next to the storage of another account
+        for (key, value) in &storage {
+            tx.put::<tables::PlainStorageState>(ADDRESS, StorageEntry { key: *key, value: *value })
+                .unwrap();
+            tx.put::<tables::HashedStorage>(
+                keccak256(ADDRESS),
+                StorageEntry { key: keccak256(key), value: *value },
+            )
+            .unwrap();
+        }
+        let higher_entry = StorageEntry { key: STORAGE, value: U256::from(1000) };
+        tx.put::<tables::StorageHistory>(
+            StorageShardedKey {
+                address: HIGHER_ADDRESS,
+                sharded_key: ShardedKey { key: STORAGE, highest_block_number: u64::MAX },
+            },
+            BlockNumberList::new([2]).unwrap(),
+        )
+        .unwrap();
+        tx.put::<tables::StorageChangeSet>(
+            (2, HIGHER_ADDRESS).into(),
+            StorageEntry { key: STORAGE, value: U256::ZERO },
+        )
+        .unwrap();
+        tx.put::<tables::PlainStorageState>(HIGHER_ADDRESS, higher_entry).unwrap();
+        tx.put::<tables::HashedStorage>(
+            keccak256(HIGHER_ADDRESS),
+            StorageEntry { key: keccak256(STORAGE), value: higher_entry.value },
+        )
+        .unwrap();
+        tx.commit().unwrap();
+
+        let tx = db.tx().unwrap();
+
+        // the state at block `n` is the state after block `n - 1`
+        for (number, root) in (1..).zip(&roots) {
+            assert_eq!(
+                HistoricalStateProviderRef::new(&tx, number)
+                    .storage_root(ADDRESS, PostState::default()),
+                Ok(*root),
+                "storage root at block {number}"
+            );
+        }
+
+        // the changes of the post state are applied on top of the historical storage
+        let mut post_state = PostState::default();
+        post_state.change_storage(
+            2,
+            ADDRESS,
+            blocks[1]
+                .iter()
+                .map(|(key, value)| (U256::from_be_bytes(key.0), (U256::ZERO, *value)))
+                .collect(),
+        );
+        assert_eq!(
+            HistoricalStateProviderRef::new(&tx, 2).storage_root(ADDRESS, post_state),
+            Ok(roots[2])
+        );
+    }
 }
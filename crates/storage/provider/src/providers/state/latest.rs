@@ -1,6 +1,6 @@
 use crate::{
-    providers::state::macros::delegate_provider_impls, AccountReader, BlockHashReader, PostState,
-    StateProvider, StateRootProvider,
+    providers::state::{macros::delegate_provider_impls, storage_root_with_changes},
+    AccountReader, BlockHashReader, PostState, StateProvider, StateRootProvider,
 };
 use reth_db::{
     cursor::{DbCursorRO, DbDupCursorRO},
@@ -61,6 +61,15 @@ impl<'a, 'b, TX: DbTx<'a>> StateRootProvider for LatestStateProviderRef<'a, 'b,
             .state_root_slow(self.db)
             .map_err(|err| reth_interfaces::Error::Database(err.into()))
     }
+
+    fn storage_root(&self, address: Address, post_state: PostState) -> Result<H256> {
+        let Some(storage) = post_state.account_storage(&address) else {
+            return storage_root_with_changes(self.db, address, [], false)
+        };
+        let changes =
+            storage.storage.iter().map(|(slot, value)| (H256(slot.to_be_bytes()), *value));
+        storage_root_with_changes(self.db, address, changes, storage.wiped())
+    }
 }
 
 impl<'a, 'b, TX: DbTx<'a>> StateProvider for LatestStateProviderRef<'a, 'b, TX> {
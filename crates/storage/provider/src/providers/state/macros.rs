@@ -32,6 +32,7 @@ macro_rules! delegate_provider_impls {
             for $target =>
             StateRootProvider $(where [$($generics)*])? {
                 fn state_root(&self, state: crate::PostState) -> reth_interfaces::Result<reth_primitives::H256>;
+                fn storage_root(&self, address: reth_primitives::Address, state: crate::PostState) -> reth_interfaces::Result<reth_primitives::H256>;
             }
             AccountReader $(where [$($generics)*])? {
                 fn basic_account(&self, address: reth_primitives::Address) -> reth_interfaces::Result<Option<reth_primitives::Account>>;
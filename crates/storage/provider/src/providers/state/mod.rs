@@ -2,3 +2,57 @@
 pub(crate) mod historical;
 pub(crate) mod latest;
 pub(crate) mod macros;
+pub(crate) mod snap;
+
+use reth_db::{cursor::DbDupCursorRO, tables, transaction::DbTx};
+use reth_interfaces::Result;
+use reth_primitives::{keccak256, trie::Nibbles, Address, H256, U256};
+use reth_trie::{
+    hashed_cursor::{HashedPostState, HashedPostStateCursorFactory, HashedStorage},
+    prefix_set::PrefixSetMut,
+    StorageRoot, StorageRootError,
+};
+
+/// Computes the storage root of the account with the given slot changes applied on top of the
+/// hashed storage and storage trie tables.
+///
+/// If `wiped` is set, the stored slots of the account are ignored.
+pub(crate) fn storage_root_with_changes<'a, TX: DbTx<'a>>(
+    tx: &TX,
+    address: Address,
+    changes: impl IntoIterator<Item = (H256, U256)>,
+    wiped: bool,
+) -> Result<H256> {
+    let hashed_address = keccak256(address);
+
+    let mut prefix_set = PrefixSetMut::default();
+    let mut hashed_storage = HashedStorage::new(wiped);
+    for (slot, value) in changes {
+        let hashed_slot = keccak256(slot);
+        prefix_set.insert(Nibbles::unpack(hashed_slot));
+        if value == U256::ZERO {
+            hashed_storage.insert_zero_valued_slot(hashed_slot);
+        } else {
+            hashed_storage.insert_non_zero_valued_storage(hashed_slot, value);
+        }
+    }
+
+    // none of the stored intermediate nodes can be reused if the storage was wiped
+    if wiped {
+        let mut cursor = tx.cursor_dup_read::<tables::HashedStorage>()?;
+        for entry in cursor.walk_dup(Some(hashed_address), None)? {
+            let (_, entry) = entry?;
+            prefix_set.insert(Nibbles::unpack(entry.key));
+        }
+    }
+
+    let mut state = HashedPostState::default();
+    state.insert_hashed_storage(hashed_address, hashed_storage);
+    let state = state.sorted();
+
+    let hashed_cursor_factory = HashedPostStateCursorFactory::new(tx, &state);
+    StorageRoot::new_hashed_with_factory(tx, &hashed_cursor_factory, hashed_address)
+        .with_changed_prefixes(prefix_set.freeze())
+        .root()
+        .map_err(|StorageRootError::DB(err)| err.into())
+}
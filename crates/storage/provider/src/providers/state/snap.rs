@@ -0,0 +1,215 @@
+use crate::SnapStateProvider;
+use reth_db::{
+    cursor::DbCursorRO,
+    models::{AccountBeforeTx, BlockNumberAddress},
+    tables,
+    transaction::DbTx,
+};
+use reth_interfaces::Result;
+use reth_primitives::{
+    keccak256, trie::Nibbles, Account, Address, BlockNumber, Bytes, StorageEntry, H256, U256,
+};
+use reth_trie::{
+    hashed_cursor::{
+        HashedAccountCursor, HashedCursorFactory, HashedPostState, HashedPostStateCursorFactory,
+        HashedStorage, HashedStorageCursor,
+    },
+    prefix_set::PrefixSet,
+    proof::Proof,
+    ProofError, StorageRoot, StorageRootError,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    marker::PhantomData,
+    ops::RangeInclusive,
+};
+
+/// Provider of a recent state by hashed keys.
+///
+/// The state is read from the hashed state and the tries of the latest block, with the changes of
+/// the blocks after the state reverted in memory.
+pub struct HashedStateProvider<'a, TX: DbTx<'a>> {
+    /// database transaction
+    tx: TX,
+    /// The changes that revert the latest hashed state to this state.
+    reverts: HashedPostState,
+    /// The prefixes of the state trie that are changed by the reverts.
+    account_prefixes: PrefixSet,
+    /// The prefixes of the storage tries that are changed by the reverts.
+    storage_prefixes: HashMap<H256, PrefixSet>,
+    /// Phantom lifetime `'a`
+    _phantom: PhantomData<&'a TX>,
+}
+
+impl<'a, TX: DbTx<'a>> HashedStateProvider<'a, TX> {
+    /// Create new provider of the latest state.
+    pub fn new(tx: TX) -> Self {
+        Self::with_reverts(tx, HashedPostState::default())
+    }
+
+    /// Create new provider of the latest state with the given changes reverted.
+    pub fn with_reverts(tx: TX, reverts: HashedPostState) -> Self {
+        let reverts = reverts.sorted();
+        let (account_prefixes, storage_prefixes) = reverts.construct_prefix_sets();
+        Self { tx, reverts, account_prefixes, storage_prefixes, _phantom: PhantomData {} }
+    }
+
+    /// Create new provider of the state at the end of the given block, with the changes of the
+    /// blocks up to the given tip reverted.
+    pub fn at_block(tx: TX, block: BlockNumber, tip: BlockNumber) -> Result<Self> {
+        if block >= tip {
+            return Ok(Self::new(tx))
+        }
+        let reverts = revert_hashed_state(&tx, block + 1..=tip)?;
+        Ok(Self::with_reverts(tx, reverts))
+    }
+
+    fn account_multiproof(&self, targets: Vec<Nibbles>) -> Result<BTreeMap<Nibbles, Bytes>> {
+        let hashed_cursor_factory = HashedPostStateCursorFactory::new(&self.tx, &self.reverts);
+        let (_, proofs) = Proof::new(&self.tx)
+            .with_hashed_cursor_factory(&hashed_cursor_factory)
+            .with_changed_account_prefixes(self.account_prefixes.clone())
+            .with_changed_storage_prefixes(self.storage_prefixes.clone())
+            .account_multiproof(targets)
+            .map_err(into_provider_error)?;
+        Ok(proofs)
+    }
+
+    fn storage_multiproof(
+        &self,
+        hashed_address: H256,
+        targets: Vec<Nibbles>,
+    ) -> Result<BTreeMap<Nibbles, Bytes>> {
+        let hashed_cursor_factory = HashedPostStateCursorFactory::new(&self.tx, &self.reverts);
+        let (_, proofs) = Proof::new(&self.tx)
+            .with_hashed_cursor_factory(&hashed_cursor_factory)
+            .with_changed_storage_prefixes(self.storage_prefixes.clone())
+            .storage_multiproof(hashed_address, targets)
+            .map_err(into_provider_error)?;
+        Ok(proofs)
+    }
+}
+
+impl<'a, TX: DbTx<'a>> SnapStateProvider for HashedStateProvider<'a, TX> {
+    fn hashed_accounts(&self, start: H256, limit: usize) -> Result<Vec<(H256, Account)>> {
+        let hashed_cursor_factory = HashedPostStateCursorFactory::new(&self.tx, &self.reverts);
+        let mut cursor = hashed_cursor_factory.hashed_account_cursor()?;
+
+        let mut accounts = Vec::new();
+        let mut entry = cursor.seek(start)?;
+        while let Some(account) = entry {
+            if accounts.len() >= limit {
+                break
+            }
+            accounts.push(account);
+            entry = cursor.next()?;
+        }
+        Ok(accounts)
+    }
+
+    fn storage_root(&self, hashed_address: H256) -> Result<H256> {
+        let hashed_cursor_factory = HashedPostStateCursorFactory::new(&self.tx, &self.reverts);
+        StorageRoot::new_hashed_with_factory(&self.tx, &hashed_cursor_factory, hashed_address)
+            .with_changed_prefixes(
+                self.storage_prefixes.get(&hashed_address).cloned().unwrap_or_default(),
+            )
+            .root()
+            .map_err(|StorageRootError::DB(err)| err.into())
+    }
+
+    fn hashed_storage(
+        &self,
+        hashed_address: H256,
+        start: H256,
+        limit: usize,
+    ) -> Result<Vec<StorageEntry>> {
+        let hashed_cursor_factory = HashedPostStateCursorFactory::new(&self.tx, &self.reverts);
+        let mut cursor = hashed_cursor_factory.hashed_storage_cursor()?;
+
+        let mut slots = Vec::new();
+        let mut entry = cursor.seek(hashed_address, start)?;
+        while let Some(slot) = entry {
+            if slots.len() >= limit {
+                break
+            }
+            slots.push(slot);
+            entry = cursor.next()?;
+        }
+        Ok(slots)
+    }
+
+    fn account_proof(&self, hashed_addresses: Vec<H256>) -> Result<Vec<Bytes>> {
+        let targets = hashed_addresses.into_iter().map(Nibbles::unpack).collect();
+        Ok(self.account_multiproof(targets)?.into_values().collect())
+    }
+
+    fn storage_proof(&self, hashed_address: H256, hashed_slots: Vec<H256>) -> Result<Vec<Bytes>> {
+        let targets = hashed_slots.into_iter().map(Nibbles::unpack).collect();
+        Ok(self.storage_multiproof(hashed_address, targets)?.into_values().collect())
+    }
+
+    fn account_trie_nodes(&self, paths: Vec<Nibbles>) -> Result<Vec<Option<Bytes>>> {
+        let mut nodes = self.account_multiproof(paths.clone())?;
+        Ok(paths.iter().map(|path| nodes.get(path).cloned()).collect())
+    }
+
+    fn storage_trie_nodes(
+        &self,
+        hashed_address: H256,
+        paths: Vec<Nibbles>,
+    ) -> Result<Vec<Option<Bytes>>> {
+        let mut nodes = self.storage_multiproof(hashed_address, paths.clone())?;
+        Ok(paths.iter().map(|path| nodes.get(path).cloned()).collect())
+    }
+}
+
+fn into_provider_error(err: ProofError) -> reth_interfaces::Error {
+    match err {
+        ProofError::DB(err) | ProofError::StorageRootError(StorageRootError::DB(err)) => err.into(),
+        err => reth_interfaces::Error::Custom(err.to_string()),
+    }
+}
+
+/// Returns the changes that revert the hashed state at the end of the given block range to the
+/// state before the first block of the range.
+pub fn revert_hashed_state<'a, TX: DbTx<'a>>(
+    tx: &TX,
+    range: RangeInclusive<BlockNumber>,
+) -> Result<HashedPostState> {
+    // the first change of every key in the range holds its value before the range
+    let mut accounts = HashMap::<Address, Option<Account>>::new();
+    for entry in tx.cursor_read::<tables::AccountChangeSet>()?.walk_range(range.clone())? {
+        let (_, AccountBeforeTx { address, info }) = entry?;
+        accounts.entry(address).or_insert(info);
+    }
+
+    let mut storages = HashMap::<Address, HashMap<H256, U256>>::new();
+    for entry in tx
+        .cursor_read::<tables::StorageChangeSet>()?
+        .walk_range(BlockNumberAddress::range(range))?
+    {
+        let (BlockNumberAddress((_, address)), StorageEntry { key, value }) = entry?;
+        storages.entry(address).or_default().entry(key).or_insert(value);
+    }
+
+    let mut state = HashedPostState::default();
+    for (address, account) in accounts {
+        match account {
+            Some(account) => state.insert_account(keccak256(address), account),
+            None => state.insert_cleared_account(keccak256(address)),
+        }
+    }
+    for (address, storage) in storages {
+        let mut hashed_storage = HashedStorage::new(false);
+        for (slot, value) in storage {
+            if value == U256::ZERO {
+                hashed_storage.insert_zero_valued_slot(keccak256(slot));
+            } else {
+                hashed_storage.insert_non_zero_valued_storage(keccak256(slot), value);
+            }
+        }
+        state.insert_hashed_storage(keccak256(address), hashed_storage);
+    }
+
+    Ok(state.sorted())
+}
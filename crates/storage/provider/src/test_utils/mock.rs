@@ -28,6 +28,8 @@ pub struct MockEthProvider {
     pub blocks: Arc<Mutex<HashMap<H256, Block>>>,
     /// Local header store
     pub headers: Arc<Mutex<HashMap<H256, Header>>>,
+    /// Local receipt store, keyed by block hash
+    pub receipts: Arc<Mutex<HashMap<H256, Vec<Receipt>>>>,
     /// Local account store
     pub accounts: Arc<Mutex<HashMap<Address, ExtendedAccount>>>,
     /// Local chain spec
@@ -39,6 +41,7 @@ impl Default for MockEthProvider {
         MockEthProvider {
             blocks: Default::default(),
             headers: Default::default(),
+            receipts: Default::default(),
             accounts: Default::default(),
             chain_spec: Arc::new(reth_primitives::ChainSpecBuilder::mainnet().build()),
         }
@@ -109,6 +112,11 @@ impl MockEthProvider {
         }
     }
 
+    /// Add the receipts of a block to local receipt store
+    pub fn add_receipts(&self, hash: H256, receipts: Vec<Receipt>) {
+        self.receipts.lock().insert(hash, receipts);
+    }
+
     /// Add account to local account store
     pub fn add_account(&self, address: Address, account: ExtendedAccount) {
         self.accounts.lock().insert(address, account);
@@ -258,8 +266,9 @@ impl ReceiptProvider for MockEthProvider {
         Ok(None)
     }
 
-    fn receipts_by_block(&self, _block: BlockHashOrNumber) -> Result<Option<Vec<Receipt>>> {
-        Ok(None)
+    fn receipts_by_block(&self, block: BlockHashOrNumber) -> Result<Option<Vec<Receipt>>> {
+        let Some(hash) = self.convert_block_hash(block)? else { return Ok(None) };
+        Ok(self.receipts.lock().get(&hash).cloned())
     }
 }
 
@@ -402,6 +411,10 @@ impl StateRootProvider for MockEthProvider {
     fn state_root(&self, _post_state: PostState) -> Result<H256> {
         todo!()
     }
+
+    fn storage_root(&self, _address: Address, _post_state: PostState) -> Result<H256> {
+        todo!()
+    }
 }
 
 impl StateProvider for MockEthProvider {
@@ -1,12 +1,12 @@
 use crate::{
     traits::{BlockSource, ReceiptProvider},
     AccountReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
-    ChainSpecProvider, ChangeSetReader, EvmEnvProvider, HeaderProvider, PostState,
-    PruneCheckpointReader, ReceiptProviderIdExt, StageCheckpointReader, StateProvider,
-    StateProviderBox, StateProviderFactory, StateRootProvider, TransactionsProvider,
-    WithdrawalsProvider,
+    ChainSpecProvider, ChainStateReader, ChangeSetReader, EvmEnvProvider, HeaderProvider,
+    PostState, PreimageReader, PruneCheckpointReader, ReceiptProviderIdExt, SnapStateProviderBox,
+    SnapStateProviderFactory, StageCheckpointReader, StateProvider, StateProviderBox,
+    StateProviderFactory, StateRootProvider, TransactionsProvider, WithdrawalsProvider,
 };
-use reth_db::models::{AccountBeforeTx, StoredBlockBodyIndices};
+use reth_db::models::{AccountBeforeTx, ChainStateKey, StoredBlockBodyIndices};
 use reth_interfaces::Result;
 use reth_primitives::{
     stage::{StageCheckpoint, StageId},
@@ -246,6 +246,10 @@ impl StateRootProvider for NoopProvider {
     fn state_root(&self, _post_state: PostState) -> Result<H256> {
         todo!()
     }
+
+    fn storage_root(&self, _address: Address, _post_state: PostState) -> Result<H256> {
+        todo!()
+    }
 }
 
 impl StateProvider for NoopProvider {
@@ -339,6 +343,12 @@ impl StateProviderFactory for NoopProvider {
     }
 }
 
+impl PreimageReader for NoopProvider {
+    fn preimage(&self, _hash: H256) -> Result<Option<Bytes>> {
+        Ok(None)
+    }
+}
+
 impl StageCheckpointReader for NoopProvider {
     fn get_stage_checkpoint(&self, _id: StageId) -> Result<Option<StageCheckpoint>> {
         Ok(None)
@@ -367,3 +377,15 @@ impl PruneCheckpointReader for NoopProvider {
         Ok(None)
     }
 }
+
+impl ChainStateReader for NoopProvider {
+    fn get_chain_state(&self, _key: ChainStateKey) -> Result<Option<H256>> {
+        Ok(None)
+    }
+}
+
+impl SnapStateProviderFactory for NoopProvider {
+    fn snap_state_by_root(&self, _root: H256) -> Result<Option<SnapStateProviderBox<'_>>> {
+        Ok(None)
+    }
+}
@@ -8,7 +8,7 @@ use reth_interfaces::Result;
 use reth_primitives::{
     Address, Block, BlockHashOrNumber, BlockId, BlockNumber, BlockNumberOrTag, BlockWithSenders,
     ChainSpec, Header, PruneModes, Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader,
-    H256,
+    TxHash, H256,
 };
 use std::ops::RangeInclusive;
 
@@ -102,6 +102,28 @@ pub trait BlockReader:
     /// Returns `None` if block is not found.
     fn block_body_indices(&self, num: u64) -> Result<Option<StoredBlockBodyIndices>>;
 
+    /// Returns the number of transactions in the given block.
+    ///
+    /// The count is read from the block body indices, without loading the transactions.
+    ///
+    /// Returns `None` if block is not found.
+    fn block_transaction_count(&self, id: BlockHashOrNumber) -> Result<Option<u64>> {
+        let Some(number) = self.convert_hash_or_number(id)? else { return Ok(None) };
+        Ok(self.block_body_indices(number)?.map(|indices| indices.tx_count))
+    }
+
+    /// Returns whether the block with the given number contains the transaction with the given
+    /// hash.
+    ///
+    /// This is answered from the transaction lookup table and the block body indices, without
+    /// loading the transactions, so it returns `false` if the transaction lookup is pruned.
+    fn block_contains_transaction(&self, number: BlockNumber, hash: TxHash) -> Result<bool> {
+        let Some(tx_number) = self.transaction_id(hash)? else { return Ok(false) };
+        Ok(self
+            .block_body_indices(number)?
+            .map_or(false, |indices| indices.tx_num_range().contains(&tx_number)))
+    }
+
     /// Returns the block with senders with matching number from database.
     ///
     /// Returns `None` if block is not found.
@@ -250,7 +272,7 @@ pub trait BlockWriter: Send + Sync {
     /// updates the post-state.
     ///
     /// Inserts the blocks into the database and updates the state with
-    /// provided `PostState`.
+    /// provided `PostState`. The hashed state and the trie are updated once for the whole batch.
     ///
     /// # Parameters
     ///
@@ -0,0 +1,18 @@
+use reth_db::models::ChainStateKey;
+use reth_interfaces::Result;
+use reth_primitives::H256;
+
+/// The trait for fetching the persisted state of the canonical chain, like the last valid
+/// forkchoice state.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait ChainStateReader: Send + Sync {
+    /// Fetch the block hash stored for the given key.
+    fn get_chain_state(&self, key: ChainStateKey) -> Result<Option<H256>>;
+}
+
+/// The trait for persisting the state of the canonical chain.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait ChainStateWriter: Send + Sync {
+    /// Save the block hashes for the given keys, all entries are written atomically.
+    fn save_chain_state(&self, entries: &[(ChainStateKey, H256)]) -> Result<()>;
+}
@@ -0,0 +1,37 @@
+use reth_interfaces::Result;
+use reth_primitives::BlockNumber;
+use std::ops::RangeInclusive;
+
+/// The trait for aggregate queries over the canonical chain that don't require scanning blocks.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait ChainStatsReader: Send + Sync {
+    /// Returns the total number of transactions in all blocks up to and including the given
+    /// block, or `None` if the block is not available.
+    fn total_transactions(&self, block: BlockNumber) -> Result<Option<u64>>;
+
+    /// Returns the total gas used by all blocks up to and including the given block, or `None`
+    /// if the block is not available.
+    ///
+    /// Requires the cumulative gas used to be indexed by the chain stats stage.
+    fn cumulative_gas_used(&self, block: BlockNumber) -> Result<Option<u64>>;
+
+    /// Returns the total gas used by the blocks in the given range, or `None` if any block of the
+    /// range is not available.
+    fn gas_used_in_range(&self, range: RangeInclusive<BlockNumber>) -> Result<Option<u64>> {
+        let Some(end) = self.cumulative_gas_used(*range.end())? else { return Ok(None) };
+        if *range.start() == 0 {
+            return Ok(Some(end))
+        }
+        Ok(self.cumulative_gas_used(range.start() - 1)?.map(|start| end.saturating_sub(start)))
+    }
+
+    /// Returns the number of transactions in the blocks of the given range, or `None` if any block
+    /// of the range is not available.
+    fn transactions_in_range(&self, range: RangeInclusive<BlockNumber>) -> Result<Option<u64>> {
+        let Some(end) = self.total_transactions(*range.end())? else { return Ok(None) };
+        if *range.start() == 0 {
+            return Ok(Some(end))
+        }
+        Ok(self.total_transactions(range.start() - 1)?.map(|start| end.saturating_sub(start)))
+    }
+}
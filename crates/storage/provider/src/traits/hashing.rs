@@ -2,6 +2,7 @@ use auto_impl::auto_impl;
 use reth_db::models::BlockNumberAddress;
 use reth_interfaces::Result;
 use reth_primitives::{Account, Address, BlockNumber, StorageEntry, H256};
+use reth_trie::hashed_cursor::HashedPostState;
 use std::{
     collections::{BTreeMap, BTreeSet, HashMap},
     ops::{Range, RangeInclusive},
@@ -62,4 +63,19 @@ pub trait HashingWriter: Send + Sync {
         end_block_hash: H256,
         expected_state_root: H256,
     ) -> Result<()>;
+
+    /// Write the hashed state of a batch of blocks and update the trie for the whole batch in a
+    /// single pass.
+    ///
+    /// Unlike [Self::insert_hashes], the changed accounts and storages are taken from the given
+    /// [HashedPostState] instead of being read back from the changesets of the batch.
+    ///
+    /// The resulting state root is compared with `expected_state_root`.
+    fn insert_hashed_state(
+        &self,
+        hashed_state: &HashedPostState,
+        end_block_number: BlockNumber,
+        end_block_hash: H256,
+        expected_state_root: H256,
+    ) -> Result<()>;
 }
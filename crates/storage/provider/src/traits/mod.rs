@@ -60,5 +60,19 @@ pub use hashing::HashingWriter;
 mod history;
 pub use history::HistoryWriter;
 
+mod chain_stats;
+pub use chain_stats::ChainStatsReader;
+
+mod preimage;
+pub use preimage::PreimageReader;
+
 mod prune_checkpoint;
 pub use prune_checkpoint::{PruneCheckpointReader, PruneCheckpointWriter};
+
+mod chain_state;
+pub use chain_state::{ChainStateReader, ChainStateWriter};
+
+mod snap;
+pub use snap::{
+    SnapStateProvider, SnapStateProviderBox, SnapStateProviderFactory, SNAP_SERVED_STATES,
+};
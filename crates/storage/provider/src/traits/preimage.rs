@@ -0,0 +1,11 @@
+use reth_interfaces::Result;
+use reth_primitives::{Bytes, H256};
+
+/// The trait for fetching the preimages of hashed addresses and storage keys.
+///
+/// Preimages are only available if they were recorded during execution.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait PreimageReader: Send + Sync {
+    /// Get the preimage of the given keccak256 hash.
+    fn preimage(&self, hash: H256) -> Result<Option<Bytes>>;
+}
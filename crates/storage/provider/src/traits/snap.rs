@@ -0,0 +1,60 @@
+use reth_interfaces::Result;
+use reth_primitives::{trie::Nibbles, Account, Bytes, StorageEntry, H256};
+
+/// Type alias of boxed [SnapStateProvider].
+pub type SnapStateProviderBox<'a> = Box<dyn SnapStateProvider + 'a>;
+
+/// The number of recent states that are served by the `snap` protocol.
+pub const SNAP_SERVED_STATES: u64 = 128;
+
+/// The trait for creating providers of the recent states served by the `snap` protocol.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait SnapStateProviderFactory: Send + Sync {
+    /// Returns a provider of the state with the given root, if it is the state of one of the last
+    /// [SNAP_SERVED_STATES] blocks.
+    ///
+    /// Returns `None` if the root is unknown or too old, or while the hashed state and the tries
+    /// are being updated by the pipeline.
+    fn snap_state_by_root(&self, root: H256) -> Result<Option<SnapStateProviderBox<'_>>>;
+}
+
+/// The state of a block by hashed keys, as served by the `snap` protocol.
+///
+/// All reads of a provider are served from the same database transaction.
+pub trait SnapStateProvider: Send + Sync {
+    /// Returns up to `limit` accounts with a hashed address of at least `start`, ordered by
+    /// hashed address.
+    fn hashed_accounts(&self, start: H256, limit: usize) -> Result<Vec<(H256, Account)>>;
+
+    /// Returns the root of the storage trie of the account with the hashed address.
+    fn storage_root(&self, hashed_address: H256) -> Result<H256>;
+
+    /// Returns up to `limit` storage slots of the account with a hashed key of at least `start`,
+    /// ordered by hashed key.
+    fn hashed_storage(
+        &self,
+        hashed_address: H256,
+        start: H256,
+        limit: usize,
+    ) -> Result<Vec<StorageEntry>>;
+
+    /// Returns the nodes of the state trie that prove the inclusion or exclusion of the accounts
+    /// with the hashed addresses, ordered by path.
+    fn account_proof(&self, hashed_addresses: Vec<H256>) -> Result<Vec<Bytes>>;
+
+    /// Returns the nodes of the storage trie of the account that prove the inclusion or exclusion
+    /// of the slots with the hashed keys, ordered by path.
+    fn storage_proof(&self, hashed_address: H256, hashed_slots: Vec<H256>) -> Result<Vec<Bytes>>;
+
+    /// Returns the nodes of the state trie at the given paths, `None` if there is no node at a
+    /// path.
+    fn account_trie_nodes(&self, paths: Vec<Nibbles>) -> Result<Vec<Option<Bytes>>>;
+
+    /// Returns the nodes of the storage trie of the account at the given paths, `None` if there
+    /// is no node at a path.
+    fn storage_trie_nodes(
+        &self,
+        hashed_address: H256,
+        paths: Vec<Nibbles>,
+    ) -> Result<Vec<Option<Bytes>>>;
+}
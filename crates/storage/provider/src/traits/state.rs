@@ -3,8 +3,8 @@ use crate::{post_state::PostState, BlockHashReader, BlockIdReader};
 use auto_impl::auto_impl;
 use reth_interfaces::{provider::ProviderError, Result};
 use reth_primitives::{
-    Address, BlockHash, BlockId, BlockNumHash, BlockNumber, BlockNumberOrTag, Bytecode, Bytes,
-    StorageKey, StorageValue, H256, KECCAK_EMPTY, U256,
+    Account, Address, BlockHash, BlockId, BlockNumHash, BlockNumber, BlockNumberOrTag, Bytecode,
+    Bytes, StorageKey, StorageValue, H256, KECCAK_EMPTY, U256,
 };
 
 /// Type alias of boxed [StateProvider].
@@ -69,6 +69,30 @@ pub trait StateProvider: BlockHashReader + AccountReader + StateRootProvider + S
             None => Ok(None),
         }
     }
+
+    /// Get basic account information of multiple accounts, along with their code if `with_code`
+    /// is set.
+    ///
+    /// All accounts are read from the same state. Returns `None` for accounts that don't exist.
+    fn basic_accounts_with_code(
+        &self,
+        addresses: &[Address],
+        with_code: bool,
+    ) -> Result<Vec<Option<(Account, Option<Bytecode>)>>> {
+        addresses
+            .iter()
+            .map(|address| {
+                let Some(account) = self.basic_account(*address)? else { return Ok(None) };
+                let code = match account.bytecode_hash {
+                    Some(code_hash) if with_code && code_hash != KECCAK_EMPTY => {
+                        self.bytecode_by_hash(code_hash)?
+                    }
+                    _ => None,
+                };
+                Ok(Some((account, code)))
+            })
+            .collect()
+    }
 }
 
 /// Light wrapper that returns `StateProvider` implementations that correspond to the given
@@ -229,4 +253,8 @@ pub trait StateRootProvider: Send + Sync {
     /// Returns the state root of the PostState on top of the current state.
     /// See [PostState::state_root_slow] for more info.
     fn state_root(&self, post_state: PostState) -> Result<H256>;
+
+    /// Returns the storage root of the account with the storage changes of the PostState applied
+    /// on top of the current state.
+    fn storage_root(&self, address: Address, post_state: PostState) -> Result<H256>;
 }
@@ -27,6 +27,7 @@ use std::{
     fmt::{Display, Formatter},
     pin::Pin,
     task::{ready, Context, Poll},
+    time::Duration,
 };
 use tokio::{
     runtime::Handle,
@@ -38,6 +39,7 @@ use tracing_futures::Instrument;
 
 pub mod metrics;
 pub mod shutdown;
+pub mod watchdog;
 
 /// A type that can spawn tasks.
 ///
@@ -195,16 +197,21 @@ impl Future for TaskManager {
 pub struct PanickedTaskError {
     task_name: &'static str,
     error: Option<String>,
+    /// How often the task was restarted before it panicked for the last time.
+    restarts: usize,
 }
 
 impl Display for PanickedTaskError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let task_name = self.task_name;
+        write!(f, "Critical task `{task_name}` panicked")?;
+        if self.restarts > 0 {
+            write!(f, " after {} restarts", self.restarts)?;
+        }
         if let Some(error) = &self.error {
-            write!(f, "Critical task `{task_name}` panicked: `{error}`")
-        } else {
-            write!(f, "Critical task `{task_name}` panicked")
+            write!(f, ": `{error}`")?;
         }
+        Ok(())
     }
 }
 
@@ -218,10 +225,34 @@ impl PanickedTaskError {
             },
         };
 
-        Self { task_name, error }
+        Self { task_name, error, restarts: 0 }
+    }
+
+    /// Returns the name of the task that panicked.
+    pub fn task_name(&self) -> &'static str {
+        self.task_name
     }
 }
 
+/// Determines what happens if a critical task panics.
+///
+/// See [`TaskExecutor::spawn_critical_with_restart`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RestartPolicy {
+    /// Notify the [`TaskManager`], which shuts down the node gracefully.
+    #[default]
+    Shutdown,
+    /// Restart the task after the given backoff.
+    ///
+    /// Once the task panicked more than `max_restarts` times, the [`TaskManager`] is notified.
+    Restart {
+        /// How often the task is restarted at most.
+        max_restarts: usize,
+        /// How long to wait before restarting the task.
+        backoff: Duration,
+    },
+}
+
 /// A type that can spawn new tokio tasks
 #[derive(Debug, Clone)]
 pub struct TaskExecutor {
@@ -250,6 +281,15 @@ impl TaskExecutor {
         &self.on_shutdown
     }
 
+    /// Returns a new [TaskExecutor] that spawns tasks onto the runtime of the given [Handle].
+    ///
+    /// The new executor shares the shutdown signal and the monitoring of critical tasks with this
+    /// executor, so subsystems can run on dedicated runtimes while still being managed by the same
+    /// [TaskManager].
+    pub fn with_handle(&self, handle: Handle) -> Self {
+        Self { handle, ..self.clone() }
+    }
+
     /// Spawns a future on the tokio runtime depending on the [TaskKind]
     fn spawn_on_rt<F>(&self, fut: F, task_kind: TaskKind) -> JoinHandle<()>
     where
@@ -382,6 +422,64 @@ impl TaskExecutor {
         self.spawn_critical_as(name, fut, TaskKind::Default)
     }
 
+    /// This spawns a critical task onto the runtime that is created by `make_task` and restarted
+    /// according to the [`RestartPolicy`] if it panics.
+    /// The task resolves as soon as the [Shutdown] signal is received.
+    ///
+    /// Once the policy gives up on the task, the [`TaskManager`] is notified.
+    pub fn spawn_critical_with_restart<F, Fut>(
+        &self,
+        name: &'static str,
+        policy: RestartPolicy,
+        mut make_task: F,
+    ) -> JoinHandle<()>
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let panicked_tasks_tx = self.panicked_tasks_tx.clone();
+        let on_shutdown = self.on_shutdown.clone();
+        let restarted_critical_tasks_metrics = self.metrics.restarted_critical_tasks.clone();
+
+        let task = async move {
+            let mut restarts = 0;
+            loop {
+                let Err(error) = std::panic::AssertUnwindSafe(make_task()).catch_unwind().await
+                else {
+                    return
+                };
+                let mut task_error = PanickedTaskError::new(name, error);
+                match policy {
+                    RestartPolicy::Restart { max_restarts, backoff } if restarts < max_restarts => {
+                        restarts += 1;
+                        error!(target: "tasks", restarts, max_restarts, ?backoff, "{task_error}, restarting");
+                        restarted_critical_tasks_metrics.increment(1);
+                        tokio::time::sleep(backoff).await;
+                    }
+                    _ => {
+                        task_error.restarts = restarts;
+                        error!("{task_error}");
+                        let _ = panicked_tasks_tx.send(task_error);
+                        return
+                    }
+                }
+            }
+        }
+        .in_current_span();
+
+        // Clone only the specific counter that we need.
+        let finished_critical_tasks_metrics = self.metrics.finished_critical_tasks.clone();
+        let task = async move {
+            // Create an instance of IncCounterOnDrop with the counter to increment
+            let _inc_counter_on_drop = IncCounterOnDrop::new(finished_critical_tasks_metrics);
+            pin_mut!(task);
+            let _ = select(on_shutdown, task).await;
+        };
+
+        self.metrics.inc_critical_tasks();
+        self.spawn_on_rt(task, TaskKind::Default)
+    }
+
     /// This spawns a critical task onto the runtime.
     ///
     /// If this task panics, the [`TaskManager`] is notified.
@@ -482,6 +580,86 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_critical_on_other_runtime() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let other = tokio::runtime::Runtime::new().unwrap();
+        let manager = TaskManager::new(runtime.handle().clone());
+        let executor = manager.executor().with_handle(other.handle().clone());
+
+        executor.spawn_critical(
+            "this is a critical task",
+            Box::pin(async { panic!("intentionally panic") }),
+        );
+
+        runtime.block_on(async move {
+            let err = manager.await;
+            assert_eq!(err.task_name, "this is a critical task");
+        })
+    }
+
+    #[test]
+    fn test_critical_restart() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let manager = TaskManager::new(runtime.handle().clone());
+        let executor = manager.executor();
+
+        let (tx, mut rx) = unbounded_channel();
+        executor.spawn_critical_with_restart(
+            "this is a critical task",
+            RestartPolicy::Restart { max_restarts: 2, backoff: Duration::from_millis(10) },
+            move || {
+                let tx = tx.clone();
+                async move {
+                    let _ = tx.send(());
+                    panic!("intentionally panic")
+                }
+            },
+        );
+
+        runtime.block_on(async move {
+            let err = manager.await;
+            assert_eq!(err.task_name, "this is a critical task");
+            assert_eq!(err.restarts, 2);
+            assert_eq!(
+                err.to_string(),
+                "Critical task `this is a critical task` panicked after 2 restarts: `intentionally panic`"
+            );
+            // initial run and two restarts
+            for _ in 0..3 {
+                rx.recv().await.unwrap();
+            }
+            assert!(rx.try_recv().is_err());
+        })
+    }
+
+    #[test]
+    fn test_critical_restart_recovers() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let manager = TaskManager::new(runtime.handle().clone());
+        let executor = manager.executor();
+
+        let mut attempt = 0;
+        let task = executor.spawn_critical_with_restart(
+            "this is a critical task",
+            RestartPolicy::Restart { max_restarts: 1, backoff: Duration::ZERO },
+            move || {
+                attempt += 1;
+                let attempt = attempt;
+                async move {
+                    if attempt == 1 {
+                        panic!("intentionally panic")
+                    }
+                }
+            },
+        );
+
+        runtime.block_on(task).unwrap();
+        let mut manager = std::pin::pin!(manager);
+        let waker = futures_util::task::noop_waker();
+        assert!(manager.as_mut().poll(&mut Context::from_waker(&waker)).is_pending());
+    }
+
     // Tests that spawned tasks are terminated if the `TaskManager` drops
     #[test]
     fn test_manager_shutdown_critical() {
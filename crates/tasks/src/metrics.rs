@@ -1,5 +1,8 @@
 //! Task Executor Metrics
-use reth_metrics::{metrics::Counter, Metrics};
+use reth_metrics::{
+    metrics::{Counter, Gauge},
+    Metrics,
+};
 
 /// Task Executor Metrics
 #[derive(Metrics, Clone)]
@@ -9,12 +12,26 @@ pub struct TaskExecutorMetrics {
     pub(crate) critical_tasks: Counter,
     /// Number of finished spawned critical tasks
     pub(crate) finished_critical_tasks: Counter,
+    /// Number of times a critical task was restarted after it panicked
+    pub(crate) restarted_critical_tasks: Counter,
     /// Number of spawned regular tasks
     pub(crate) regular_tasks: Counter,
     /// Number of finished spawned regular tasks
     pub(crate) finished_regular_tasks: Counter,
 }
 
+/// Metrics of a future monitored by the [`StallDetector`](crate::watchdog::StallDetector)
+#[derive(Metrics)]
+#[metrics(scope = "executor.watchdog")]
+pub struct StallDetectorMetrics {
+    /// Number of times the future stopped making progress
+    pub(crate) stalls: Counter,
+    /// Whether the future is currently stalled
+    pub(crate) stalled: Gauge,
+    /// Number of messages that were sent to the future, but not yet received by it
+    pub(crate) queued_messages: Gauge,
+}
+
 impl TaskExecutorMetrics {
     pub(crate) fn inc_critical_tasks(&self) {
         self.critical_tasks.increment(1);
@@ -0,0 +1,345 @@
+//! Stall detection for long running futures.
+//!
+//! A future reports its progress via a [Heartbeat], the [StallDetector] periodically checks all
+//! heartbeats and reports futures that have been busy without making progress for longer than the
+//! configured threshold, for example because they are blocked on a lock. Futures that track their
+//! inbound channel via [QueuedMessages] are also reported if messages are queued for longer than
+//! the threshold without the future making progress.
+
+use crate::metrics::StallDetectorMetrics;
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+    time::{Duration, Instant},
+};
+use tracing::{error, info};
+
+/// Tracks the progress of a future, see [StallDetector].
+#[derive(Debug, Clone)]
+pub struct Heartbeat {
+    inner: Arc<HeartbeatInner>,
+}
+
+#[derive(Debug)]
+struct HeartbeatInner {
+    /// The name of the monitored future.
+    name: &'static str,
+    /// All timestamps are relative to this instant.
+    created: Instant,
+    /// Milliseconds since `created` when the future last made progress.
+    last_beat: AtomicU64,
+    /// Whether the future is currently being polled.
+    busy: AtomicBool,
+    /// Number of messages the future started processing.
+    processed: AtomicU64,
+    /// The kind of the message the future started processing last.
+    last_message: Mutex<Option<&'static str>>,
+    /// The messages queued for the future, if tracked.
+    queue: OnceLock<QueuedMessages>,
+}
+
+// === impl Heartbeat ===
+
+impl Heartbeat {
+    /// Creates a new heartbeat for the future with the given name.
+    pub fn new(name: &'static str) -> Self {
+        let inner = HeartbeatInner {
+            name,
+            created: Instant::now(),
+            last_beat: AtomicU64::new(0),
+            busy: AtomicBool::new(false),
+            processed: AtomicU64::new(0),
+            last_message: Mutex::new(None),
+            queue: OnceLock::new(),
+        };
+        Self { inner: Arc::new(inner) }
+    }
+
+    /// Returns the name of the monitored future.
+    pub fn name(&self) -> &'static str {
+        self.inner.name
+    }
+
+    /// Marks the start of a poll, the returned guard marks the end of the poll when dropped.
+    pub fn enter(&self) -> HeartbeatGuard {
+        self.beat();
+        self.inner.busy.store(true, Ordering::Relaxed);
+        HeartbeatGuard(self.clone())
+    }
+
+    /// Records that the future started processing a message of the given kind.
+    pub fn on_message(&self, message: &'static str) {
+        self.beat();
+        self.inner.processed.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut last_message) = self.inner.last_message.lock() {
+            *last_message = Some(message);
+        }
+    }
+
+    /// Tracks the messages queued for the future, so that a future that isn't woken up to receive
+    /// them can be detected.
+    ///
+    /// Only the first tracked queue is used.
+    pub fn track_queue(&self, queue: QueuedMessages) {
+        let _ = self.inner.queue.set(queue);
+    }
+
+    fn beat(&self) {
+        let now = self.inner.created.elapsed().as_millis() as u64;
+        self.inner.last_beat.store(now, Ordering::Relaxed);
+    }
+
+    /// Returns for how long the future has been busy without making progress.
+    ///
+    /// Returns `None` if the future is idle, waiting to be woken up.
+    fn stalled_for(&self) -> Option<Duration> {
+        if !self.inner.busy.load(Ordering::Relaxed) {
+            return None
+        }
+        Some(self.since_last_beat())
+    }
+
+    /// Returns the time since the future last made progress.
+    fn since_last_beat(&self) -> Duration {
+        let last_beat = Duration::from_millis(self.inner.last_beat.load(Ordering::Relaxed));
+        self.inner.created.elapsed().saturating_sub(last_beat)
+    }
+
+    /// Returns the number of messages queued for the future.
+    fn queued(&self) -> u64 {
+        self.inner.queue.get().map_or(0, QueuedMessages::count)
+    }
+
+    fn last_message(&self) -> Option<&'static str> {
+        self.inner.last_message.lock().ok().and_then(|last_message| *last_message)
+    }
+}
+
+/// Marks the end of a poll when dropped, see [Heartbeat::enter].
+#[derive(Debug)]
+pub struct HeartbeatGuard(Heartbeat);
+
+impl Drop for HeartbeatGuard {
+    fn drop(&mut self) {
+        self.0.beat();
+        self.0.inner.busy.store(false, Ordering::Relaxed);
+    }
+}
+
+/// Counts the messages that were sent to a monitored future, but not yet received by it, see
+/// [Heartbeat::track_queue].
+#[derive(Debug, Clone, Default)]
+pub struct QueuedMessages(Arc<AtomicU64>);
+
+// === impl QueuedMessages ===
+
+impl QueuedMessages {
+    /// Records that a message was sent to the future.
+    pub fn on_send(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records that the future received a message.
+    pub fn on_recv(&self) {
+        // messages can also be sent via untracked senders
+        let _ =
+            self.0.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |count| count.checked_sub(1));
+    }
+
+    /// Returns the number of messages that were sent, but not yet received.
+    pub fn count(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Periodically checks the registered [Heartbeat]s and reports futures that stopped making
+/// progress.
+///
+/// A future is stalled if it has been busy without making progress, or if messages have been
+/// queued for it without it making progress, for longer than the threshold.
+///
+/// Stalls are logged with the number of processed and queued messages and the last processed
+/// message and are reported via metrics. If the node is built with `tokio_unstable` and
+/// `tokio_taskdump`, a dump of all tasks is logged as well.
+#[derive(Debug)]
+#[must_use = "StallDetector does nothing unless run"]
+pub struct StallDetector {
+    threshold: Duration,
+    monitored: Vec<Monitored>,
+}
+
+#[derive(Debug)]
+struct Monitored {
+    heartbeat: Heartbeat,
+    metrics: StallDetectorMetrics,
+    stalled: bool,
+    /// When messages were first seen queued for the future, reset once the queue is empty.
+    queued_since: Option<Instant>,
+}
+
+// === impl StallDetector ===
+
+impl StallDetector {
+    /// Creates a new detector that reports futures that made no progress for the given duration.
+    pub fn new(threshold: Duration) -> Self {
+        Self { threshold, monitored: Vec::new() }
+    }
+
+    /// Returns a new [Heartbeat] for the future with the given name that is monitored by this
+    /// detector.
+    pub fn heartbeat(&mut self, name: &'static str) -> Heartbeat {
+        let heartbeat = Heartbeat::new(name);
+        self.monitored.push(Monitored {
+            heartbeat: heartbeat.clone(),
+            metrics: StallDetectorMetrics::new_with_labels(&[("task", name)]),
+            stalled: false,
+            queued_since: None,
+        });
+        heartbeat
+    }
+
+    /// Checks the heartbeats every half of the threshold, forever.
+    pub async fn run(mut self) {
+        let mut interval = tokio::time::interval(self.threshold / 2);
+        loop {
+            interval.tick().await;
+            if self.check() > 0 {
+                dump_tasks().await;
+            }
+        }
+    }
+
+    /// Checks all heartbeats and returns the number of futures that stalled since the last check.
+    fn check(&mut self) -> usize {
+        let mut stalled = 0;
+        for monitored in &mut self.monitored {
+            let heartbeat = &monitored.heartbeat;
+            let queued = heartbeat.queued();
+            monitored.metrics.queued_messages.set(queued as f64);
+
+            let stalled_for = match heartbeat.stalled_for() {
+                Some(stalled_for) => Some(stalled_for),
+                // an idle future with queued messages may not be woken up to receive them
+                None if queued > 0 => {
+                    let queued_for =
+                        monitored.queued_since.get_or_insert_with(Instant::now).elapsed();
+                    Some(queued_for.min(heartbeat.since_last_beat()))
+                }
+                None => None,
+            };
+            if queued == 0 {
+                monitored.queued_since = None;
+            }
+
+            match stalled_for.filter(|stalled_for| *stalled_for >= self.threshold) {
+                Some(stalled_for) => {
+                    if !monitored.stalled {
+                        monitored.stalled = true;
+                        monitored.metrics.stalls.increment(1);
+                        monitored.metrics.stalled.set(1.0);
+                        stalled += 1;
+                        error!(
+                            target: "tasks::watchdog",
+                            task = heartbeat.name(),
+                            ?stalled_for,
+                            processed = heartbeat.inner.processed.load(Ordering::Relaxed),
+                            queued,
+                            last_message = ?heartbeat.last_message(),
+                            "Task stopped making progress"
+                        );
+                    }
+                }
+                None => {
+                    if monitored.stalled {
+                        monitored.stalled = false;
+                        monitored.metrics.stalled.set(0.0);
+                        info!(target: "tasks::watchdog", task = heartbeat.name(), "Task made progress again");
+                    }
+                }
+            }
+        }
+        stalled
+    }
+}
+
+/// Logs a dump of all tasks on the current runtime.
+#[cfg(all(tokio_unstable, tokio_taskdump))]
+async fn dump_tasks() {
+    // tasks that are blocked themselves can keep the dump from completing
+    const DUMP_TIMEOUT: Duration = Duration::from_secs(5);
+
+    let handle = tokio::runtime::Handle::current();
+    match tokio::time::timeout(DUMP_TIMEOUT, handle.dump()).await {
+        Ok(dump) => {
+            for (id, task) in dump.tasks().iter().enumerate() {
+                error!(target: "tasks::watchdog", id, trace = %task.trace(), "Task dump");
+            }
+        }
+        Err(_) => error!(target: "tasks::watchdog", "Timed out dumping tasks"),
+    }
+}
+
+/// Task dumps are only available with `tokio_unstable` and `tokio_taskdump`.
+#[cfg(not(all(tokio_unstable, tokio_taskdump)))]
+async fn dump_tasks() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_stall() {
+        let mut detector = StallDetector::new(Duration::from_millis(20));
+        let heartbeat = detector.heartbeat("engine");
+
+        // idle futures are not stalled
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(detector.check(), 0);
+
+        let guard = heartbeat.enter();
+        heartbeat.on_message("forkchoice_updated");
+        assert_eq!(detector.check(), 0);
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(detector.check(), 1);
+        assert_eq!(heartbeat.last_message(), Some("forkchoice_updated"));
+        // only reported once
+        assert_eq!(detector.check(), 0);
+
+        drop(guard);
+        assert_eq!(detector.check(), 0);
+        assert!(!detector.monitored[0].stalled);
+    }
+
+    #[test]
+    fn detects_stall_with_queued_messages() {
+        let mut detector = StallDetector::new(Duration::from_millis(20));
+        let heartbeat = detector.heartbeat("network");
+        let queue = QueuedMessages::default();
+        heartbeat.track_queue(queue.clone());
+
+        // messages that were just queued are not stalled
+        std::thread::sleep(Duration::from_millis(30));
+        queue.on_send();
+        assert_eq!(detector.check(), 0);
+
+        // the idle future doesn't receive the queued message
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(detector.check(), 1);
+
+        // receiving the message is progress
+        let guard = heartbeat.enter();
+        queue.on_recv();
+        heartbeat.on_message("handle message");
+        drop(guard);
+        assert_eq!(detector.check(), 0);
+        assert!(!detector.monitored[0].stalled);
+        assert_eq!(queue.count(), 0);
+
+        // untracked messages don't underflow the count
+        queue.on_recv();
+        assert_eq!(queue.count(), 0);
+    }
+}
@@ -17,6 +17,7 @@
 //! - [`stdout()`]
 //! - [`file()`]
 //! - [`journald()`]
+//! - `otlp()`, if the `otlp` feature is enabled
 //!
 //! As well as a simple way to initialize a subscriber: [`init`].
 use std::path::Path;
@@ -109,6 +110,71 @@ where
     Ok(tracing_journald::layer()?.with_filter(filter).boxed())
 }
 
+/// Builds a new tracing layer that exports spans to an OpenTelemetry collector via OTLP/gRPC.
+///
+/// The spans are filtered by `filter` and exported in batches by a dedicated runtime, so the layer
+/// can be installed before any other runtime is started.
+///
+/// The boxed layer and a guard is returned. When the guard is dropped all pending spans are
+/// exported.
+#[cfg(feature = "otlp")]
+#[must_use = "tracing guard must be kept alive to export spans"]
+pub fn otlp<S>(
+    filter: EnvFilter,
+    endpoint: impl Into<String>,
+) -> Result<(BoxedLayer<S>, OtlpGuard), opentelemetry::trace::TraceError>
+where
+    S: Subscriber,
+    for<'a> S: LookupSpan<'a>,
+{
+    use opentelemetry::{trace::TraceError, KeyValue};
+    use opentelemetry_otlp::WithExportConfig;
+    use opentelemetry_sdk::{trace, Resource};
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .thread_name("otlp-exporter")
+        .enable_all()
+        .build()
+        .map_err(|err| TraceError::Other(err.into()))?;
+
+    // the batch processor and the gRPC channel are spawned on the current runtime
+    let tracer = {
+        let _enter = runtime.enter();
+        opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(opentelemetry_otlp::new_exporter().tonic().with_endpoint(endpoint))
+            .with_trace_config(
+                trace::config()
+                    .with_resource(Resource::new([KeyValue::new("service.name", "reth")])),
+            )
+            .install_batch(opentelemetry_sdk::runtime::Tokio)?
+    };
+
+    let layer = tracing_opentelemetry::layer().with_tracer(tracer).with_filter(filter).boxed();
+    Ok((layer, OtlpGuard { runtime: Some(runtime) }))
+}
+
+/// A guard returned by `otlp()`.
+///
+/// When the guard is dropped, all pending spans are exported and the exporter is shut down.
+#[cfg(feature = "otlp")]
+#[derive(Debug)]
+pub struct OtlpGuard {
+    runtime: Option<tokio::runtime::Runtime>,
+}
+
+#[cfg(feature = "otlp")]
+impl Drop for OtlpGuard {
+    fn drop(&mut self) {
+        // flushes the batch processor, which is driven by the runtime
+        opentelemetry::global::shutdown_tracer_provider();
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
 /// Initializes a tracing subscriber for tests.
 ///
 /// The filter is configurable via `RUST_LOG`.
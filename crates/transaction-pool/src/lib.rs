@@ -160,6 +160,7 @@ use reth_primitives::{Address, BlobTransactionSidecar, PooledTransactionsElement
 use reth_provider::StateProviderFactory;
 use std::{
     collections::{HashMap, HashSet},
+    ops::RangeInclusive,
     sync::Arc,
 };
 use tokio::sync::mpsc::Receiver;
@@ -179,15 +180,16 @@ pub use crate::{
         TransactionEvents,
     },
     traits::{
-        AllPoolTransactions, BestTransactions, BlockInfo, CanonicalStateUpdate, ChangedAccount,
-        EthBlobTransactionSidecar, EthPoolTransaction, EthPooledTransaction,
-        GetPooledTransactionLimit, NewTransactionEvent, PoolSize, PoolTransaction, PropagateKind,
-        PropagatedTransactions, TransactionListenerKind, TransactionOrigin, TransactionPool,
-        TransactionPoolExt,
+        AllPoolTransactions, BestTransactions, BestTransactionsCursor, BestTransactionsPage,
+        BlockInfo, CanonicalStateUpdate, ChangedAccount, EthBlobTransactionSidecar,
+        EthPoolTransaction, EthPooledTransaction, GetPooledTransactionLimit, NewTransactionEvent,
+        PoolSize, PoolTransaction, PropagateKind, PropagatedTransactions, TransactionListenerKind,
+        TransactionOrigin, TransactionPool, TransactionPoolExt,
     },
     validate::{
-        EthTransactionValidator, TransactionValidationOutcome, TransactionValidationTaskExecutor,
-        TransactionValidator, ValidPoolTransaction,
+        DynamicFeeFloor, DynamicFeeFloorConfig, EthTransactionValidator,
+        TransactionValidationOutcome, TransactionValidationTaskExecutor, TransactionValidator,
+        ValidPoolTransaction,
     },
 };
 
@@ -467,6 +469,28 @@ where
         self.pool.get_transactions_by_sender(sender)
     }
 
+    fn get_transactions_by_sender_grouped(
+        &self,
+        sender: Address,
+    ) -> AllPoolTransactions<Self::Transaction> {
+        self.pool.get_transactions_by_sender_grouped(sender)
+    }
+
+    fn get_transactions_by_effective_tip(
+        &self,
+        range: RangeInclusive<u128>,
+    ) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>> {
+        self.pool.get_transactions_by_effective_tip(range)
+    }
+
+    fn best_transactions_page(
+        &self,
+        cursor: Option<BestTransactionsCursor>,
+        limit: usize,
+    ) -> BestTransactionsPage<Self::Transaction> {
+        self.pool.best_transactions_page(cursor, limit)
+    }
+
     fn unique_senders(&self) -> HashSet<Address> {
         self.pool.unique_senders()
     }
@@ -8,13 +8,13 @@ use crate::{
     error::PoolError,
     traits::{GetPooledTransactionLimit, TransactionListenerKind},
     validate::ValidTransaction,
-    AllPoolTransactions, AllTransactionsEvents, BestTransactions, BlockInfo, EthPooledTransaction,
-    NewTransactionEvent, PoolResult, PoolSize, PoolTransaction, PropagatedTransactions,
-    TransactionEvents, TransactionOrigin, TransactionPool, TransactionValidationOutcome,
-    TransactionValidator, ValidPoolTransaction,
+    AllPoolTransactions, AllTransactionsEvents, BestTransactions, BestTransactionsCursor,
+    BestTransactionsPage, BlockInfo, EthPooledTransaction, NewTransactionEvent, PoolResult,
+    PoolSize, PoolTransaction, PropagatedTransactions, TransactionEvents, TransactionOrigin,
+    TransactionPool, TransactionValidationOutcome, TransactionValidator, ValidPoolTransaction,
 };
 use reth_primitives::{Address, BlobTransactionSidecar, PooledTransactionsElement, TxHash};
-use std::{collections::HashSet, marker::PhantomData, sync::Arc};
+use std::{collections::HashSet, marker::PhantomData, ops::RangeInclusive, sync::Arc};
 use tokio::sync::{mpsc, mpsc::Receiver};
 
 /// A [`TransactionPool`] implementation that does nothing.
@@ -178,6 +178,28 @@ impl TransactionPool for NoopTransactionPool {
         vec![]
     }
 
+    fn get_transactions_by_sender_grouped(
+        &self,
+        _sender: Address,
+    ) -> AllPoolTransactions<Self::Transaction> {
+        AllPoolTransactions::default()
+    }
+
+    fn get_transactions_by_effective_tip(
+        &self,
+        _range: RangeInclusive<u128>,
+    ) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>> {
+        vec![]
+    }
+
+    fn best_transactions_page(
+        &self,
+        _cursor: Option<BestTransactionsCursor>,
+        _limit: usize,
+    ) -> BestTransactionsPage<Self::Transaction> {
+        BestTransactionsPage::default()
+    }
+
     fn unique_senders(&self) -> HashSet<Address> {
         Default::default()
     }
@@ -74,8 +74,8 @@ use crate::{
         txpool::{SenderInfo, TxPool},
     },
     traits::{
-        AllPoolTransactions, BlockInfo, NewTransactionEvent, PoolSize, PoolTransaction,
-        PropagatedTransactions, TransactionOrigin,
+        AllPoolTransactions, BestTransactionsCursor, BestTransactionsPage, BlockInfo,
+        NewTransactionEvent, PoolSize, PoolTransaction, PropagatedTransactions, TransactionOrigin,
     },
     validate::{TransactionValidationOutcome, ValidPoolTransaction},
     CanonicalStateUpdate, ChangedAccount, PoolConfig, TransactionOrdering, TransactionValidator,
@@ -89,6 +89,7 @@ use reth_primitives::{
 use std::{
     collections::{HashMap, HashSet},
     fmt,
+    ops::RangeInclusive,
     sync::Arc,
     time::Instant,
 };
@@ -298,14 +299,13 @@ where
         let mut size = 0;
         for transaction in transactions {
             let tx = transaction.to_recovered_transaction().into_signed();
-            let pooled = if tx.is_eip4844() {
-                if let Some(blob) = self.get_blob_transaction(tx) {
-                    PooledTransactionsElement::BlobTransaction(blob)
-                } else {
-                    continue
-                }
-            } else {
-                PooledTransactionsElement::from(tx)
+            let pooled = match PooledTransactionsElement::try_from_broadcast(tx) {
+                Ok(pooled) => pooled,
+                // blob transactions are only served with their sidecar
+                Err(tx) => match self.get_blob_transaction(tx) {
+                    Some(blob) => PooledTransactionsElement::BlobTransaction(blob),
+                    None => continue,
+                },
             };
 
             size += pooled.length();
@@ -684,6 +684,32 @@ where
         self.pool.read().get_transactions_by_sender(sender_id)
     }
 
+    /// Returns all transactions sent by the given sender, grouped by pending and queued.
+    pub(crate) fn get_transactions_by_sender_grouped(
+        &self,
+        sender: Address,
+    ) -> AllPoolTransactions<T::Transaction> {
+        let sender_id = self.get_sender_id(sender);
+        self.pool.read().get_transactions_by_sender_grouped(sender_id)
+    }
+
+    /// Returns all transactions with an effective tip at the pending base fee within the range.
+    pub(crate) fn get_transactions_by_effective_tip(
+        &self,
+        range: RangeInclusive<u128>,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        self.pool.read().get_transactions_by_effective_tip(range)
+    }
+
+    /// Returns a page of the pending transactions ordered by their effective tip.
+    pub(crate) fn best_transactions_page(
+        &self,
+        cursor: Option<BestTransactionsCursor>,
+        limit: usize,
+    ) -> BestTransactionsPage<T::Transaction> {
+        self.pool.read().best_transactions_page(cursor, limit)
+    }
+
     /// Returns all the transactions belonging to the hashes.
     ///
     /// If no transaction exists, it is skipped.
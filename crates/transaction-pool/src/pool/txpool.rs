@@ -12,7 +12,9 @@ use crate::{
         update::{Destination, PoolUpdate},
         AddedPendingTransaction, AddedTransaction, OnNewCanonicalStateOutcome,
     },
-    traits::{BlockInfo, PoolSize},
+    traits::{
+        AllPoolTransactions, BestTransactionsCursor, BestTransactionsPage, BlockInfo, PoolSize,
+    },
     PoolConfig, PoolResult, PoolTransaction, PriceBumpConfig, TransactionOrdering,
     ValidPoolTransaction, U256,
 };
@@ -24,10 +26,13 @@ use reth_primitives::{
     Address, TxHash, H256,
 };
 use std::{
-    cmp::Ordering,
+    cmp::{Ordering, Reverse},
     collections::{btree_map::Entry, hash_map, BTreeMap, HashMap, HashSet},
     fmt,
-    ops::Bound::{Excluded, Unbounded},
+    ops::{
+        Bound::{Excluded, Unbounded},
+        RangeInclusive,
+    },
     sync::Arc,
 };
 
@@ -291,6 +296,70 @@ impl<T: TransactionOrdering> TxPool<T> {
         self.all_transactions.txs_iter(sender).map(|(_, tx)| Arc::clone(&tx.transaction)).collect()
     }
 
+    /// Returns all transactions sent from the given sender, grouped by pending and queued.
+    pub(crate) fn get_transactions_by_sender_grouped(
+        &self,
+        sender: SenderId,
+    ) -> AllPoolTransactions<T::Transaction> {
+        let mut transactions = AllPoolTransactions::default();
+        for (_, tx) in self.all_transactions.txs_iter(sender) {
+            if tx.subpool.is_pending() {
+                transactions.pending.push(Arc::clone(&tx.transaction));
+            } else {
+                transactions.queued.push(Arc::clone(&tx.transaction));
+            }
+        }
+        transactions
+    }
+
+    /// Returns all transactions whose effective tip at the pending base fee is within the range.
+    pub(crate) fn get_transactions_by_effective_tip(
+        &self,
+        range: RangeInclusive<u128>,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        let base_fee = self.all_transactions.pending_basefee;
+        self.all_transactions
+            .txs
+            .values()
+            .filter(|tx| {
+                tx.transaction
+                    .effective_tip_per_gas(base_fee)
+                    .map_or(false, |tip| range.contains(&tip))
+            })
+            .map(|tx| Arc::clone(&tx.transaction))
+            .collect()
+    }
+
+    /// Returns up to `limit` pending transactions after the cursor, ordered by their effective tip
+    /// at the base fee of the cursor, highest first, and then by hash.
+    ///
+    /// Only the transactions of the page are kept while scanning the pending pool.
+    pub(crate) fn best_transactions_page(
+        &self,
+        cursor: Option<BestTransactionsCursor>,
+        limit: usize,
+    ) -> BestTransactionsPage<T::Transaction> {
+        let base_fee = cursor.map_or(self.all_transactions.pending_basefee, |c| c.base_fee);
+        let mut page = BTreeMap::new();
+        let mut truncated = false;
+        for tx in self.pending_pool.all() {
+            let Some(tip) = tx.effective_tip_per_gas(base_fee) else { continue };
+            if cursor.map_or(false, |cursor| !cursor.is_before(tip, tx.hash())) {
+                continue
+            }
+            page.insert((Reverse(tip), *tx.hash()), tx);
+            if page.len() > limit {
+                page.pop_last();
+                truncated = true;
+            }
+        }
+
+        let next = page.keys().next_back().filter(|_| truncated).map(|(Reverse(tip), hash)| {
+            BestTransactionsCursor { base_fee, effective_tip: *tip, hash: *hash }
+        });
+        BestTransactionsPage { transactions: page.into_values().collect(), next }
+    }
+
     /// Updates the transactions for the changed senders.
     pub(crate) fn update_accounts(
         &mut self,
@@ -1695,6 +1764,41 @@ mod tests {
         assert!(inserted.state.intersects(expected_state));
     }
 
+    #[test]
+    fn best_transactions_pages() {
+        let on_chain_balance = U256::MAX;
+        let on_chain_nonce = 0;
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+        let mut add = |pool: &mut TxPool<MockOrdering>, tip: u128| {
+            let tx = MockTransaction::eip1559().with_max_fee(100).with_priority_fee(tip);
+            let tx = f.validated(tx);
+            pool.add_transaction(tx, on_chain_balance, on_chain_nonce).unwrap();
+        };
+        for tip in [10, 30, 20] {
+            add(&mut pool, tip);
+        }
+
+        let tips = |page: &BestTransactionsPage<MockTransaction>| {
+            page.transactions
+                .iter()
+                .map(|tx| tx.effective_tip_per_gas(0).unwrap())
+                .collect::<Vec<_>>()
+        };
+        let page = pool.best_transactions_page(None, 2);
+        assert_eq!(tips(&page), vec![30, 20]);
+        let cursor = page.next.unwrap();
+
+        // transactions added before the cursor are not listed on the next pages
+        add(&mut pool, 25);
+        add(&mut pool, 15);
+        let page = pool.best_transactions_page(Some(cursor), 2);
+        assert_eq!(tips(&page), vec![15, 10]);
+        assert!(page.next.is_none());
+
+        assert_eq!(pool.get_transactions_by_effective_tip(15..=25).len(), 3);
+    }
+
     #[test]
     fn insert_already_imported() {
         let on_chain_balance = U256::ZERO;
@@ -14,8 +14,10 @@ use reth_primitives::{
 };
 use reth_rlp::Encodable;
 use std::{
+    cmp::Reverse,
     collections::{HashMap, HashSet},
     fmt,
+    ops::RangeInclusive,
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
@@ -294,6 +296,44 @@ pub trait TransactionPool: Send + Sync + Clone {
         sender: Address,
     ) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>>;
 
+    /// Returns all transactions sent by the given sender, grouped by whether they are pending or
+    /// queued.
+    ///
+    /// Consumer: RPC
+    fn get_transactions_by_sender_grouped(
+        &self,
+        sender: Address,
+    ) -> AllPoolTransactions<Self::Transaction>;
+
+    /// Returns all transactions whose effective tip per gas at the pending base fee is within the
+    /// given range.
+    ///
+    /// Transactions with a max fee per gas below the pending base fee have no effective tip and
+    /// are not included.
+    ///
+    /// Consumer: RPC
+    fn get_transactions_by_effective_tip(
+        &self,
+        range: RangeInclusive<u128>,
+    ) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>>;
+
+    /// Returns up to `limit` pending transactions, starting after the given cursor.
+    ///
+    /// The listing is ordered by the effective tip per gas at the base fee of the first page,
+    /// highest first, and then by hash. Continuing with the [BestTransactionsPage::next] cursor
+    /// lists every transaction that stays in the pool exactly once, even if the pool changes
+    /// between the pages, without cloning the entire pool for every page.
+    ///
+    /// Note: unlike [Self::best_transactions], the listing does not respect the nonce order of the
+    /// transactions of a sender.
+    ///
+    /// Consumer: RPC, Block production
+    fn best_transactions_page(
+        &self,
+        cursor: Option<BestTransactionsCursor>,
+        limit: usize,
+    ) -> BestTransactionsPage<Self::Transaction>;
+
     /// Returns a set of all senders of transactions in the pool
     fn unique_senders(&self) -> HashSet<Address>;
 
@@ -396,6 +436,42 @@ impl<T: PoolTransaction> Default for AllPoolTransactions<T> {
     }
 }
 
+/// The position in the listing of [TransactionPool::best_transactions_page] after which a page
+/// starts.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BestTransactionsCursor {
+    /// The base fee the effective tips of the listing are computed with.
+    pub base_fee: u64,
+    /// The effective tip per gas of the last listed transaction.
+    pub effective_tip: u128,
+    /// The hash of the last listed transaction.
+    pub hash: TxHash,
+}
+
+impl BestTransactionsCursor {
+    /// Returns `true` if a transaction with the given effective tip and hash is listed after the
+    /// cursor.
+    pub fn is_before(&self, effective_tip: u128, hash: &TxHash) -> bool {
+        (Reverse(self.effective_tip), self.hash) < (Reverse(effective_tip), *hash)
+    }
+}
+
+/// A page of pending transactions, see [TransactionPool::best_transactions_page].
+#[derive(Debug, Clone)]
+pub struct BestTransactionsPage<T: PoolTransaction> {
+    /// The transactions of the page, in listing order.
+    pub transactions: Vec<Arc<ValidPoolTransaction<T>>>,
+    /// The cursor of the next page, `None` if there are no more transactions.
+    pub next: Option<BestTransactionsCursor>,
+}
+
+impl<T: PoolTransaction> Default for BestTransactionsPage<T> {
+    fn default() -> Self {
+        Self { transactions: Default::default(), next: None }
+    }
+}
+
 /// Represents a transaction that was propagated over the network.
 #[derive(Debug, Clone, Eq, PartialEq, Default)]
 pub struct PropagatedTransactions(pub HashMap<TxHash, Vec<PropagateKind>>);
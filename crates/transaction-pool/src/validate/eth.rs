@@ -4,24 +4,30 @@ use crate::{
     blobstore::BlobStore,
     error::{Eip4844PoolTransactionError, InvalidPoolTransactionError},
     traits::TransactionOrigin,
-    validate::{ValidTransaction, ValidationTask, MAX_INIT_CODE_SIZE, TX_MAX_SIZE},
+    validate::{
+        DynamicFeeFloor, ValidTransaction, ValidationTask, MAX_INIT_CODE_SIZE, TX_MAX_SIZE,
+    },
     EthBlobTransactionSidecar, EthPoolTransaction, TransactionValidationOutcome,
     TransactionValidationTaskExecutor, TransactionValidator,
 };
 use reth_primitives::{
     constants::{
         eip4844::{MAINNET_KZG_TRUSTED_SETUP, MAX_BLOBS_PER_BLOCK},
-        ETHEREUM_BLOCK_GAS_LIMIT,
+        ETHEREUM_BLOCK_GAS_LIMIT, SLOT_DURATION,
     },
     kzg::KzgSettings,
-    ChainSpec, InvalidTransactionError, SealedBlock, EIP1559_TX_TYPE_ID, EIP2930_TX_TYPE_ID,
-    EIP4844_TX_TYPE_ID, LEGACY_TX_TYPE_ID,
+    ChainSpec, InvalidTransactionError, SealedBlock, SenderRecoveryCache, EIP1559_TX_TYPE_ID,
+    EIP2930_TX_TYPE_ID, EIP4844_TX_TYPE_ID, LEGACY_TX_TYPE_ID,
 };
 use reth_provider::{AccountReader, StateProviderFactory};
 use reth_tasks::TaskSpawner;
 use std::{
     marker::PhantomData,
-    sync::{atomic::AtomicBool, Arc},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
 };
 use tokio::sync::Mutex;
 
@@ -74,10 +80,14 @@ pub(crate) struct EthTransactionValidatorInner<Client, T> {
     block_gas_limit: u64,
     /// Minimum priority fee to enforce for acceptance into the pool.
     minimum_priority_fee: Option<u128>,
+    /// Dynamic minimum fee that rises during spam waves.
+    fee_floor: Option<Arc<DynamicFeeFloor>>,
     /// Toggle to determine if a local transaction should be propagated
     propagate_local_transactions: bool,
     /// Stores the setup and parameters needed for validating KZG proofs.
     kzg_settings: Arc<KzgSettings>,
+    /// Cache the senders of valid transactions are inserted into.
+    sender_cache: Option<SenderRecoveryCache>,
     /// Marker for the transaction type
     _marker: PhantomData<T>,
 }
@@ -104,6 +114,17 @@ where
         origin: TransactionOrigin,
         mut transaction: Self::Transaction,
     ) -> TransactionValidationOutcome<Self::Transaction> {
+        // Drop non-local transactions below the dynamic fee floor before doing any other work, this
+        // keeps the cost of spam waves low
+        if let Some(fee_floor) = &self.fee_floor {
+            if !origin.is_local() && !fee_floor.check(transaction.priority_fee_or_price()) {
+                return TransactionValidationOutcome::Invalid(
+                    transaction,
+                    InvalidPoolTransactionError::Underpriced,
+                )
+            }
+        }
+
         // Checks for tx_type
         match transaction.tx_type() {
             LEGACY_TX_TYPE_ID => {
@@ -322,6 +343,12 @@ where
             )
         }
 
+        // Remember the sender, so its signature isn't recovered again when the transaction is
+        // included in a block
+        if let Some(sender_cache) = &self.sender_cache {
+            sender_cache.insert(*transaction.hash(), transaction.sender());
+        }
+
         // Return the valid transaction
         TransactionValidationOutcome::Valid {
             balance: account.balance,
@@ -338,13 +365,7 @@ where
 
     fn on_new_head_block(&self, new_tip_block: &SealedBlock) {
         // update all forks
-        if self.chain_spec.is_cancun_activated_at_timestamp(new_tip_block.timestamp) {
-            self.fork_tracker.cancun.store(true, std::sync::atomic::Ordering::Relaxed);
-        }
-
-        if self.chain_spec.is_shanghai_activated_at_timestamp(new_tip_block.timestamp) {
-            self.fork_tracker.shanghai.store(true, std::sync::atomic::Ordering::Relaxed);
-        }
+        self.fork_tracker.on_new_head(&self.chain_spec, new_tip_block.timestamp);
     }
 }
 
@@ -356,6 +377,15 @@ pub struct EthTransactionValidatorBuilder {
     shanghai: bool,
     /// Fork indicator whether we are in the Cancun hardfork.
     cancun: bool,
+    /// Whether the Shanghai indicator was set explicitly and is not derived from the head.
+    shanghai_fixed: bool,
+    /// Whether the Cancun indicator was set explicitly and is not derived from the head.
+    cancun_fixed: bool,
+    /// The timestamp of the latest block, derives the fork indicators that weren't set
+    /// explicitly.
+    head_timestamp: Option<u64>,
+    /// How long before its activation a fork is enabled for validation.
+    fork_lookahead: Duration,
     /// Whether using EIP-2718 type transactions is allowed
     eip2718: bool,
     /// Whether using EIP-1559 type transactions is allowed
@@ -366,6 +396,8 @@ pub struct EthTransactionValidatorBuilder {
     block_gas_limit: u64,
     /// Minimum priority fee to enforce for acceptance into the pool.
     minimum_priority_fee: Option<u128>,
+    /// Dynamic minimum fee that rises during spam waves.
+    fee_floor: Option<Arc<DynamicFeeFloor>>,
     /// Determines how many additional tasks to spawn
     ///
     /// Default is 1
@@ -375,6 +407,8 @@ pub struct EthTransactionValidatorBuilder {
 
     /// Stores the setup and parameters needed for validating KZG proofs.
     kzg_settings: Arc<KzgSettings>,
+    /// Cache the senders of valid transactions are inserted into.
+    sender_cache: Option<SenderRecoveryCache>,
 }
 
 impl EthTransactionValidatorBuilder {
@@ -387,10 +421,12 @@ impl EthTransactionValidatorBuilder {
             chain_spec,
             block_gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
             minimum_priority_fee: None,
+            fee_floor: None,
             additional_tasks: 1,
             // default to true, can potentially take this as a param in the future
             propagate_local_transactions: true,
             kzg_settings: Arc::clone(&MAINNET_KZG_TRUSTED_SETUP),
+            sender_cache: None,
 
             // by default all transaction types are allowed
             eip2718: true,
@@ -402,6 +438,11 @@ impl EthTransactionValidatorBuilder {
 
             // TODO: can hard enable by default once mainnet transitioned
             cancun,
+
+            shanghai_fixed: false,
+            cancun_fixed: false,
+            head_timestamp: None,
+            fork_lookahead: SLOT_DURATION,
         }
     }
 
@@ -413,6 +454,7 @@ impl EthTransactionValidatorBuilder {
     /// Set the Cancun fork.
     pub fn set_cancun(mut self, cancun: bool) -> Self {
         self.cancun = cancun;
+        self.cancun_fixed = true;
         self
     }
 
@@ -424,6 +466,25 @@ impl EthTransactionValidatorBuilder {
     /// Set the Shanghai fork.
     pub fn set_shanghai(mut self, shanghai: bool) -> Self {
         self.shanghai = shanghai;
+        self.shanghai_fixed = true;
+        self
+    }
+
+    /// Sets the timestamp of the latest block.
+    ///
+    /// The forks are then enabled according to the chain spec for the block that follows, unless
+    /// their fork indicators were set explicitly.
+    pub fn with_head_timestamp(mut self, timestamp: u64) -> Self {
+        self.head_timestamp = Some(timestamp);
+        self
+    }
+
+    /// Sets how long before its activation a fork is enabled for validation.
+    ///
+    /// Transactions are validated against the rules of the next block, which is expected one slot
+    /// after the latest block by default.
+    pub fn with_fork_lookahead(mut self, fork_lookahead: Duration) -> Self {
+        self.fork_lookahead = fork_lookahead;
         self
     }
 
@@ -479,6 +540,24 @@ impl EthTransactionValidatorBuilder {
         self
     }
 
+    /// Sets a [DynamicFeeFloor] that's enforced for non-local transactions.
+    ///
+    /// The floor is shared, so it can also be checked before the signature of a transaction
+    /// received over the network is recovered.
+    pub fn with_dynamic_fee_floor(mut self, fee_floor: Arc<DynamicFeeFloor>) -> Self {
+        self.fee_floor = Some(fee_floor);
+        self
+    }
+
+    /// Sets a [SenderRecoveryCache] that the senders of valid transactions are inserted into.
+    ///
+    /// The cache is shared with the block executor, so the signatures of pooled transactions
+    /// aren't recovered again when they are included in a block.
+    pub fn with_sender_cache(mut self, sender_cache: SenderRecoveryCache) -> Self {
+        self.sender_cache = Some(sender_cache);
+        self
+    }
+
     /// Sets the number of additional tasks to spawn.
     pub fn with_additional_tasks(mut self, additional_tasks: usize) -> Self {
         self.additional_tasks = additional_tasks;
@@ -505,18 +584,32 @@ impl EthTransactionValidatorBuilder {
             chain_spec,
             shanghai,
             cancun,
+            shanghai_fixed,
+            cancun_fixed,
+            head_timestamp,
+            fork_lookahead,
             eip2718,
             eip1559,
             eip4844,
             block_gas_limit,
             minimum_priority_fee,
+            fee_floor,
             additional_tasks,
             propagate_local_transactions,
             kzg_settings,
+            sender_cache,
         } = self;
 
-        let fork_tracker =
-            ForkTracker { shanghai: AtomicBool::new(shanghai), cancun: AtomicBool::new(cancun) };
+        let fork_tracker = ForkTracker {
+            shanghai: AtomicBool::new(shanghai),
+            cancun: AtomicBool::new(cancun),
+            shanghai_fixed,
+            cancun_fixed,
+            lookahead: fork_lookahead,
+        };
+        if let Some(timestamp) = head_timestamp {
+            fork_tracker.on_new_head(&chain_spec, timestamp);
+        }
 
         let inner = EthTransactionValidatorInner {
             chain_spec,
@@ -527,9 +620,11 @@ impl EthTransactionValidatorBuilder {
             eip4844,
             block_gas_limit,
             minimum_priority_fee,
+            fee_floor,
             propagate_local_transactions,
             blob_store: Box::new(blob_store),
             kzg_settings,
+            sender_cache,
             _marker: Default::default(),
         };
 
@@ -562,20 +657,98 @@ impl EthTransactionValidatorBuilder {
 /// Keeps track of whether certain forks are activated
 #[derive(Debug)]
 pub(crate) struct ForkTracker {
-    /// Tracks if shanghai is activated at the block's timestamp.
+    /// Tracks if shanghai is activated at the next block's timestamp.
     pub(crate) shanghai: AtomicBool,
-    /// Tracks if cancun is activated at the block's timestamp.
+    /// Tracks if cancun is activated at the next block's timestamp.
     pub(crate) cancun: AtomicBool,
+    /// Whether the Shanghai indicator was set explicitly and is not updated on new heads.
+    pub(crate) shanghai_fixed: bool,
+    /// Whether the Cancun indicator was set explicitly and is not updated on new heads.
+    pub(crate) cancun_fixed: bool,
+    /// The expected time between the latest and the next block.
+    pub(crate) lookahead: Duration,
 }
 
 impl ForkTracker {
     /// Returns true if the Shanghai fork is activated.
     pub(crate) fn is_shanghai_activated(&self) -> bool {
-        self.shanghai.load(std::sync::atomic::Ordering::Relaxed)
+        self.shanghai.load(Ordering::Relaxed)
     }
 
-    /// Returns true if the Shanghai fork is activated.
+    /// Returns true if the Cancun fork is activated.
     pub(crate) fn is_cancun_activated(&self) -> bool {
-        self.cancun.load(std::sync::atomic::Ordering::Relaxed)
+        self.cancun.load(Ordering::Relaxed)
+    }
+
+    /// Updates the forks for the block that follows the head with the given timestamp.
+    ///
+    /// Transactions in the pool are included in upcoming blocks, so they are validated against
+    /// the rules of the fork of the next block: e.g. blob transactions are accepted shortly before
+    /// Cancun activates, and rejected if the next block is still before Cancun.
+    ///
+    /// Forks that were set explicitly are left untouched.
+    pub(crate) fn on_new_head(&self, chain_spec: &ChainSpec, head_timestamp: u64) {
+        let timestamp = head_timestamp.saturating_add(self.lookahead.as_secs());
+        if !self.shanghai_fixed {
+            self.shanghai
+                .store(chain_spec.is_shanghai_activated_at_timestamp(timestamp), Ordering::Relaxed);
+        }
+        if !self.cancun_fixed {
+            self.cancun
+                .store(chain_spec.is_cancun_activated_at_timestamp(timestamp), Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{ChainSpecBuilder, ForkCondition, Hardfork};
+
+    #[test]
+    fn enable_forks_for_next_block() {
+        let chain_spec = ChainSpecBuilder::mainnet()
+            .with_fork(Hardfork::Shanghai, ForkCondition::Timestamp(0))
+            .with_fork(Hardfork::Cancun, ForkCondition::Timestamp(1000))
+            .build();
+        let tracker = ForkTracker {
+            shanghai: AtomicBool::new(true),
+            cancun: AtomicBool::new(true),
+            shanghai_fixed: false,
+            cancun_fixed: false,
+            lookahead: SLOT_DURATION,
+        };
+
+        // the next block is before Cancun
+        tracker.on_new_head(&chain_spec, 1000 - 13);
+        assert!(tracker.is_shanghai_activated());
+        assert!(!tracker.is_cancun_activated());
+
+        // the next block is the first Cancun block
+        tracker.on_new_head(&chain_spec, 1000 - 12);
+        assert!(tracker.is_cancun_activated());
+
+        tracker.on_new_head(&chain_spec, 1000);
+        assert!(tracker.is_cancun_activated());
+    }
+
+    #[test]
+    fn keep_explicit_forks() {
+        let chain_spec = ChainSpecBuilder::mainnet()
+            .with_fork(Hardfork::Shanghai, ForkCondition::Timestamp(0))
+            .with_fork(Hardfork::Cancun, ForkCondition::Timestamp(1000))
+            .build();
+        let tracker = ForkTracker {
+            shanghai: AtomicBool::new(true),
+            cancun: AtomicBool::new(false),
+            shanghai_fixed: false,
+            cancun_fixed: true,
+            lookahead: SLOT_DURATION,
+        };
+
+        // the explicitly disabled Cancun fork stays disabled
+        tracker.on_new_head(&chain_spec, 2000);
+        assert!(tracker.is_shanghai_activated());
+        assert!(!tracker.is_cancun_activated());
     }
 }
@@ -0,0 +1,181 @@
+//! A dynamic minimum fee that protects the pool against spam waves.
+
+use parking_lot::Mutex;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// The default ingress rate of external transactions per second above which the floor rises.
+pub const DEFAULT_SPAM_RATE_THRESHOLD: u64 = 2_000;
+
+/// The default floor in wei that is enforced once the ingress rate exceeds the threshold: 1 gwei.
+pub const DEFAULT_FEE_FLOOR_STEP: u128 = 1_000_000_000;
+
+/// The default maximum floor in wei: 100 gwei.
+pub const DEFAULT_MAX_FEE_FLOOR: u128 = 100_000_000_000;
+
+/// Configuration of the [DynamicFeeFloor].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DynamicFeeFloorConfig {
+    /// The number of external transactions per second above which the floor rises.
+    pub rate_threshold: u64,
+    /// The floor that is enforced the first time the threshold is exceeded, the floor doubles
+    /// for every following interval the threshold is exceeded.
+    pub step: u128,
+    /// The upper bound of the floor.
+    pub max_floor: u128,
+    /// The interval over which the ingress rate is measured.
+    pub interval: Duration,
+}
+
+impl Default for DynamicFeeFloorConfig {
+    fn default() -> Self {
+        Self {
+            rate_threshold: DEFAULT_SPAM_RATE_THRESHOLD,
+            step: DEFAULT_FEE_FLOOR_STEP,
+            max_floor: DEFAULT_MAX_FEE_FLOOR,
+            interval: Duration::from_secs(1),
+        }
+    }
+}
+
+/// A minimum priority fee that rises while the ingress of external transactions exceeds a rate
+/// threshold and decays back once the ingress calms down.
+///
+/// The ingress rate is measured over fixed intervals. At the end of every interval in which the
+/// rate exceeded the threshold, the floor doubles (starting at [DynamicFeeFloorConfig::step]) up
+/// to [DynamicFeeFloorConfig::max_floor]. For every interval below the threshold, the floor is
+/// halved until it drops below the step, at which point it is disabled again.
+///
+/// The floor is cheap to check, so it is supposed to be checked before any expensive work, like
+/// the recovery of the signature, is done for a transaction.
+#[derive(Debug)]
+pub struct DynamicFeeFloor {
+    config: DynamicFeeFloorConfig,
+    state: Mutex<FeeFloorState>,
+}
+
+#[derive(Debug)]
+struct FeeFloorState {
+    /// The currently enforced floor.
+    floor: u128,
+    /// The start of the current measurement interval.
+    interval_start: Instant,
+    /// The number of transactions received in the current interval.
+    received: u64,
+}
+
+// === impl DynamicFeeFloor ===
+
+impl DynamicFeeFloor {
+    /// Creates a new floor with the given configuration, which starts disabled.
+    pub fn new(config: DynamicFeeFloorConfig) -> Self {
+        let state = FeeFloorState { floor: 0, interval_start: Instant::now(), received: 0 };
+        Self { config, state: Mutex::new(state) }
+    }
+
+    /// Returns the configuration of the floor.
+    pub fn config(&self) -> &DynamicFeeFloorConfig {
+        &self.config
+    }
+
+    /// Returns the currently enforced floor, `0` if the floor is disabled.
+    ///
+    /// This does not record the transaction, see [Self::check].
+    pub fn floor(&self) -> u128 {
+        self.state.lock().floor
+    }
+
+    /// Records the ingress of an external transaction with the given priority fee and returns
+    /// whether the fee is above the current floor.
+    pub fn check(&self, priority_fee: u128) -> bool {
+        priority_fee >= self.record(Instant::now())
+    }
+
+    /// Returns whether the fee is above the current floor, for checks before validation.
+    ///
+    /// Only transactions below the floor are recorded, transactions that pass are recorded when
+    /// they are validated with [Self::check]. Recording the dropped transactions here keeps the
+    /// measured ingress rate from falling while the floor is filtering a spam wave.
+    pub fn precheck(&self, priority_fee: u128) -> bool {
+        let mut state = self.state.lock();
+        if priority_fee >= state.floor {
+            return true
+        }
+        priority_fee >= self.record_locked(&mut state, Instant::now())
+    }
+
+    /// Records a received transaction at the given time and returns the floor it is checked
+    /// against.
+    fn record(&self, now: Instant) -> u128 {
+        self.record_locked(&mut self.state.lock(), now)
+    }
+
+    fn record_locked(&self, state: &mut FeeFloorState, now: Instant) -> u128 {
+        state.received += 1;
+
+        let elapsed = now.saturating_duration_since(state.interval_start);
+        if elapsed >= self.config.interval {
+            let rate = state.received as u128 * 1_000 / elapsed.as_millis().max(1);
+            let previous = state.floor;
+            state.floor = if rate > self.config.rate_threshold as u128 {
+                previous.saturating_mul(2).max(self.config.step).min(self.config.max_floor)
+            } else if previous / 2 < self.config.step {
+                0
+            } else {
+                previous / 2
+            };
+            if state.floor != previous {
+                debug!(target: "txpool", rate, previous, floor = state.floor, "Updated dynamic fee floor");
+            }
+            state.interval_start = now;
+            state.received = 0;
+        }
+
+        state.floor
+    }
+}
+
+impl Default for DynamicFeeFloor {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn floor_rises_and_decays() {
+        let config = DynamicFeeFloorConfig {
+            rate_threshold: 10,
+            step: 100,
+            max_floor: 300,
+            interval: Duration::from_secs(1),
+        };
+        let floor = DynamicFeeFloor::new(config);
+        let mut now = floor.state.lock().interval_start;
+
+        // a spam wave of 20 transactions per second
+        let spam_interval = |now: &mut Instant| {
+            for _ in 0..19 {
+                floor.record(*now);
+            }
+            *now += Duration::from_secs(1);
+            floor.record(*now)
+        };
+        assert_eq!(spam_interval(&mut now), 100);
+        assert_eq!(spam_interval(&mut now), 200);
+        assert_eq!(spam_interval(&mut now), 300);
+        assert_eq!(spam_interval(&mut now), 300);
+        assert!(!floor.check(299));
+        assert!(floor.check(300));
+
+        // the wave is over
+        now += Duration::from_secs(1);
+        assert_eq!(floor.record(now), 150);
+        now += Duration::from_secs(1);
+        assert_eq!(floor.record(now), 0);
+        assert_eq!(floor.floor(), 0);
+    }
+}
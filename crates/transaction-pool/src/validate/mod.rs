@@ -13,11 +13,18 @@ use std::{fmt, time::Instant};
 
 mod constants;
 mod eth;
+mod fee_floor;
 mod task;
 
 /// A `TransactionValidator` implementation that validates ethereum transaction.
 pub use eth::{EthTransactionValidator, EthTransactionValidatorBuilder};
 
+/// A dynamic minimum fee that protects the pool against spam waves.
+pub use fee_floor::{
+    DynamicFeeFloor, DynamicFeeFloorConfig, DEFAULT_FEE_FLOOR_STEP, DEFAULT_MAX_FEE_FLOOR,
+    DEFAULT_SPAM_RATE_THRESHOLD,
+};
+
 /// A spawnable task that performs transaction validation.
 pub use task::{TransactionValidationTaskExecutor, ValidationTask};
 
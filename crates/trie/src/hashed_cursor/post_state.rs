@@ -51,6 +51,21 @@ impl HashedStorage {
     pub fn insert_zero_valued_slot(&mut self, slot: H256) {
         self.zero_valued_slots.insert(slot);
     }
+
+    /// Returns `true` if the storage was wiped.
+    pub fn wiped(&self) -> bool {
+        self.wiped
+    }
+
+    /// Returns the non zero-valued storage entries.
+    pub fn non_zero_valued_storage(&self) -> impl Iterator<Item = &(H256, U256)> {
+        self.non_zero_valued_storage.iter()
+    }
+
+    /// Returns the zero-valued storage slots.
+    pub fn zero_valued_slots(&self) -> impl Iterator<Item = &H256> {
+        self.zero_valued_slots.iter()
+    }
 }
 
 /// The post state with hashed addresses as keys.
@@ -113,6 +128,21 @@ impl HashedPostState {
         self.storages.insert(hashed_address, hashed_storage);
     }
 
+    /// Returns the changed accounts.
+    pub fn accounts(&self) -> impl Iterator<Item = &(H256, Account)> {
+        self.accounts.iter()
+    }
+
+    /// Returns the hashed addresses of the cleared accounts.
+    pub fn cleared_accounts(&self) -> impl Iterator<Item = &H256> {
+        self.cleared_accounts.iter()
+    }
+
+    /// Returns the changed storages.
+    pub fn storages(&self) -> impl Iterator<Item = (&H256, &HashedStorage)> {
+        self.storages.iter()
+    }
+
     /// Construct (PrefixSet)[PrefixSet] from hashed post state.
     /// The prefix sets contain the hashed account and storage keys that have been changed in the
     /// post state.
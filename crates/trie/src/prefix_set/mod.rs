@@ -1,5 +1,5 @@
 use reth_primitives::trie::Nibbles;
-use std::rc::Rc;
+use std::sync::Arc;
 
 mod loader;
 pub use loader::{LoadedPrefixSets, PrefixSetLoader};
@@ -35,12 +35,25 @@ pub struct PrefixSetMut {
     keys: Vec<Nibbles>,
     sorted: bool,
     index: usize,
+    all: bool,
 }
 
 impl PrefixSetMut {
+    /// Returns a set that contains every prefix.
+    ///
+    /// A trie walked with this set is recomputed from its leaves, without using any of its stored
+    /// nodes.
+    pub fn all() -> Self {
+        Self { all: true, ..Default::default() }
+    }
+
     /// Returns `true` if any of the keys in the set has the given prefix or
     /// if the given prefix is a prefix of any key in the set.
     pub fn contains<T: Into<Nibbles>>(&mut self, prefix: T) -> bool {
+        if self.all {
+            return true
+        }
+
         if !self.sorted {
             self.keys.sort();
             self.keys.dedup();
@@ -74,6 +87,13 @@ impl PrefixSetMut {
         self.keys.push(nibbles.into());
     }
 
+    /// Inserts all keys of the given set.
+    pub fn extend(&mut self, other: &PrefixSet) {
+        self.sorted = false;
+        self.all |= other.all;
+        self.keys.extend(other.keys.iter().cloned());
+    }
+
     /// Returns the number of elements in the set.
     pub fn len(&self) -> usize {
         self.keys.len()
@@ -81,7 +101,7 @@ impl PrefixSetMut {
 
     /// Returns `true` if the set is empty.
     pub fn is_empty(&self) -> bool {
-        self.keys.is_empty()
+        !self.all && self.keys.is_empty()
     }
 
     /// Returns a `PrefixSet` with the same elements as this set.
@@ -93,7 +113,7 @@ impl PrefixSetMut {
             self.keys.dedup();
         }
 
-        PrefixSet { keys: Rc::new(self.keys), index: self.index }
+        PrefixSet { keys: Arc::new(self.keys), index: self.index, all: self.all }
     }
 }
 
@@ -102,8 +122,9 @@ impl PrefixSetMut {
 /// See also [PrefixSetMut::freeze].
 #[derive(Debug, Default, Clone)]
 pub struct PrefixSet {
-    keys: Rc<Vec<Nibbles>>,
+    keys: Arc<Vec<Nibbles>>,
     index: usize,
+    all: bool,
 }
 
 impl PrefixSet {
@@ -111,6 +132,10 @@ impl PrefixSet {
     /// if the given prefix is a prefix of any key in the set.
     #[inline]
     pub fn contains<T: Into<Nibbles>>(&mut self, prefix: T) -> bool {
+        if self.all {
+            return true
+        }
+
         let prefix = prefix.into();
 
         while self.index > 0 && self.keys[self.index] > prefix {
@@ -139,7 +164,7 @@ impl PrefixSet {
 
     /// Returns `true` if the set is empty.
     pub fn is_empty(&self) -> bool {
-        self.keys.is_empty()
+        !self.all && self.keys.is_empty()
     }
 }
 
@@ -1,21 +1,23 @@
 use crate::{
     account::EthAccount,
-    hashed_cursor::{HashedAccountCursor, HashedCursorFactory},
-    prefix_set::PrefixSet,
-    trie_cursor::{AccountTrieCursor, TrieCursor},
+    hashed_cursor::{HashedAccountCursor, HashedCursorFactory, HashedStorageCursor},
+    prefix_set::{PrefixSet, PrefixSetMut},
+    trie_cursor::{AccountTrieCursor, StorageTrieCursor, TrieCursor},
     walker::TrieWalker,
     ProofError, StorageRoot,
 };
 use reth_db::{cursor::DbCursorRO, tables, transaction::DbTx};
 use reth_primitives::{
     keccak256,
+    proofs::EMPTY_ROOT,
     trie::{
         nodes::{rlp_hash, BranchNode, LeafNode, CHILD_INDEX_RANGE},
         BranchNodeCompact, HashBuilder, Nibbles,
     },
-    Address, Bytes, H256,
+    Address, Bytes, StorageEntry, H256,
 };
 use reth_rlp::Encodable;
+use std::collections::{BTreeMap, HashMap};
 
 /// A struct for generating merkle proofs.
 ///
@@ -34,17 +36,59 @@ use reth_rlp::Encodable;
 ///
 /// After traversing the path, the proof generator continues to restore the root node of the trie
 /// until completion. The root node is then inserted at the start of the proof.
+///
+/// The multiproofs are instead generated by recomputing the root with a hash builder that retains
+/// the nodes on the paths to the targets. The stored nodes are only used for the subtries that
+/// neither contain a target nor a changed prefix, so the multiproofs match the hashed state of the
+/// hashed cursor factory.
 pub struct Proof<'a, 'b, TX, H> {
     /// A reference to the database transaction.
     tx: &'a TX,
     /// The factory for hashed cursors.
     hashed_cursor_factory: &'b H,
+    /// A set of account prefixes that differ between the hashed state and the stored trie.
+    changed_account_prefixes: PrefixSet,
+    /// A map containing the storage prefixes that differ between the hashed state and the stored
+    /// trie with the hashed address as key.
+    changed_storage_prefixes: HashMap<H256, PrefixSet>,
 }
 
 impl<'a, TX> Proof<'a, 'a, TX, TX> {
     /// Create a new [Proof] instance.
     pub fn new(tx: &'a TX) -> Self {
-        Self { tx, hashed_cursor_factory: tx }
+        Self {
+            tx,
+            hashed_cursor_factory: tx,
+            changed_account_prefixes: PrefixSet::default(),
+            changed_storage_prefixes: HashMap::default(),
+        }
+    }
+}
+
+impl<'a, 'b, TX, H> Proof<'a, 'b, TX, H> {
+    /// Set the hashed cursor factory.
+    pub fn with_hashed_cursor_factory<'c, HF>(
+        self,
+        hashed_cursor_factory: &'c HF,
+    ) -> Proof<'a, 'c, TX, HF> {
+        Proof {
+            tx: self.tx,
+            hashed_cursor_factory,
+            changed_account_prefixes: self.changed_account_prefixes,
+            changed_storage_prefixes: self.changed_storage_prefixes,
+        }
+    }
+
+    /// Set the changed account prefixes.
+    pub fn with_changed_account_prefixes(mut self, prefixes: PrefixSet) -> Self {
+        self.changed_account_prefixes = prefixes;
+        self
+    }
+
+    /// Set the changed storage prefixes.
+    pub fn with_changed_storage_prefixes(mut self, prefixes: HashMap<H256, PrefixSet>) -> Self {
+        self.changed_storage_prefixes = prefixes;
+        self
     }
 }
 
@@ -97,6 +141,135 @@ where
         Ok(proofs)
     }
 
+    /// Generate the nodes of the account trie on the paths to the given targets.
+    ///
+    /// A target is either the unpacked hashed address of an account, to prove its inclusion or
+    /// exclusion, or the path of a single node.
+    ///
+    /// # Returns
+    ///
+    /// The state root and the RLP encoded nodes keyed by their path.
+    pub fn account_multiproof(
+        &self,
+        targets: Vec<Nibbles>,
+    ) -> Result<(H256, BTreeMap<Nibbles, Bytes>), ProofError> {
+        let mut prefix_set = PrefixSetMut::default();
+        prefix_set.extend(&self.changed_account_prefixes);
+        for target in &targets {
+            prefix_set.insert(target.clone());
+        }
+
+        let mut hashed_account_cursor = self.hashed_cursor_factory.hashed_account_cursor()?;
+        let mut trie_cursor =
+            AccountTrieCursor::new(self.tx.cursor_read::<tables::AccountsTrie>()?);
+        let mut walker = TrieWalker::new(&mut trie_cursor, prefix_set.freeze());
+        let mut hash_builder = HashBuilder::default().with_proof_retainer(targets);
+
+        let mut account_rlp = Vec::with_capacity(128);
+        while let Some(key) = walker.key() {
+            if walker.can_skip_current_node {
+                let value = walker.hash().unwrap();
+                let is_in_db_trie = walker.children_are_in_trie();
+                hash_builder.add_branch(key, value, is_in_db_trie);
+            }
+
+            let seek_key = match walker.next_unprocessed_key() {
+                Some(key) => key,
+                None => break, // no more keys
+            };
+
+            let next_key = walker.advance()?;
+            let mut account_entry = hashed_account_cursor.seek(seek_key)?;
+            while let Some((hashed_address, account)) = account_entry {
+                let account_nibbles = Nibbles::unpack(hashed_address);
+                if next_key.as_ref().map_or(false, |key| key < &account_nibbles) {
+                    break
+                }
+
+                let storage_root = StorageRoot::new_hashed_with_factory(
+                    self.tx,
+                    self.hashed_cursor_factory,
+                    hashed_address,
+                )
+                .with_changed_prefixes(
+                    self.changed_storage_prefixes.get(&hashed_address).cloned().unwrap_or_default(),
+                )
+                .root()?;
+
+                account_rlp.clear();
+                EthAccount::from(account).with_storage_root(storage_root).encode(&mut account_rlp);
+                hash_builder.add_leaf(account_nibbles, &account_rlp);
+
+                account_entry = hashed_account_cursor.next()?;
+            }
+        }
+
+        let root = hash_builder.root();
+        Ok((root, hash_builder.take_proofs()))
+    }
+
+    /// Generate the nodes of the storage trie of the account on the paths to the given targets.
+    ///
+    /// A target is either the unpacked hashed key of a storage slot, to prove its inclusion or
+    /// exclusion, or the path of a single node.
+    ///
+    /// # Returns
+    ///
+    /// The storage root and the RLP encoded nodes keyed by their path.
+    pub fn storage_multiproof(
+        &self,
+        hashed_address: H256,
+        targets: Vec<Nibbles>,
+    ) -> Result<(H256, BTreeMap<Nibbles, Bytes>), ProofError> {
+        let mut hashed_storage_cursor = self.hashed_cursor_factory.hashed_storage_cursor()?;
+
+        // an empty trie has no nodes
+        if hashed_storage_cursor.is_storage_empty(hashed_address)? {
+            return Ok((EMPTY_ROOT, BTreeMap::default()))
+        }
+
+        let mut prefix_set = PrefixSetMut::default();
+        if let Some(changed_prefixes) = self.changed_storage_prefixes.get(&hashed_address) {
+            prefix_set.extend(changed_prefixes);
+        }
+        for target in &targets {
+            prefix_set.insert(target.clone());
+        }
+
+        let mut trie_cursor = StorageTrieCursor::new(
+            self.tx.cursor_dup_read::<tables::StoragesTrie>()?,
+            hashed_address,
+        );
+        let mut walker = TrieWalker::new(&mut trie_cursor, prefix_set.freeze());
+        let mut hash_builder = HashBuilder::default().with_proof_retainer(targets);
+
+        while let Some(key) = walker.key() {
+            if walker.can_skip_current_node {
+                hash_builder.add_branch(key, walker.hash().unwrap(), walker.children_are_in_trie());
+            }
+
+            let seek_key = match walker.next_unprocessed_key() {
+                Some(key) => key,
+                None => break, // no more keys
+            };
+
+            let next_key = walker.advance()?;
+            let mut storage = hashed_storage_cursor.seek(hashed_address, seek_key)?;
+            while let Some(StorageEntry { key: hashed_key, value }) = storage {
+                let storage_key_nibbles = Nibbles::unpack(hashed_key);
+                if next_key.as_ref().map_or(false, |key| key < &storage_key_nibbles) {
+                    break
+                }
+                hash_builder
+                    .add_leaf(storage_key_nibbles, reth_rlp::encode_fixed_size(&value).as_ref());
+                storage = hashed_storage_cursor.next()?;
+            }
+        }
+
+        let root = hash_builder.root();
+        Ok((root, hash_builder.take_proofs()))
+    }
+
     fn traverse_path<T: DbCursorRO<'a, tables::AccountsTrie>>(
         &self,
         trie_cursor: &mut AccountTrieCursor<T>,
@@ -331,6 +504,14 @@ mod tests {
         let tx = db.tx().unwrap();
         let proof = Proof::new(&tx).account_proof(target).unwrap();
         pretty_assertions::assert_eq!(proof, expected_account_proof);
+
+        let (root, proofs) =
+            Proof::new(&tx).account_multiproof(vec![Nibbles::unpack(keccak256(target))]).unwrap();
+        assert_eq!(root, StateRoot::new(&tx).root().unwrap());
+        pretty_assertions::assert_eq!(
+            proofs.into_values().collect::<Vec<_>>(),
+            expected_account_proof
+        );
     }
 
     #[test]
@@ -38,6 +38,8 @@ pub struct StateRoot<'a, 'b, TX, H> {
     previous_state: Option<IntermediateStateRootState>,
     /// The number of updates after which the intermediate progress should be returned.
     threshold: u64,
+    /// Whether the stored account and storage tries are ignored.
+    ignore_stored_trie: bool,
 }
 
 impl<'a, 'b, TX, H> StateRoot<'a, 'b, TX, H> {
@@ -77,6 +79,16 @@ impl<'a, 'b, TX, H> StateRoot<'a, 'b, TX, H> {
         self
     }
 
+    /// Recompute the root from the hashed state only, without using the hashes of the stored
+    /// account and storage tries.
+    ///
+    /// Unlike clearing the trie tables, this works with a read-only transaction.
+    pub fn with_stored_trie_ignored(mut self) -> Self {
+        self.changed_account_prefixes = PrefixSetMut::all().freeze();
+        self.ignore_stored_trie = true;
+        self
+    }
+
     /// Set the hashed cursor factory.
     pub fn with_hashed_cursor_factory<'c, HF>(
         self,
@@ -89,6 +101,7 @@ impl<'a, 'b, TX, H> StateRoot<'a, 'b, TX, H> {
             destroyed_accounts: self.destroyed_accounts,
             threshold: self.threshold,
             previous_state: self.previous_state,
+            ignore_stored_trie: self.ignore_stored_trie,
             hashed_cursor_factory,
         }
     }
@@ -107,6 +120,7 @@ where
             destroyed_accounts: HashSet::default(),
             previous_state: None,
             threshold: 100_000,
+            ignore_stored_trie: false,
             hashed_cursor_factory: tx,
         }
     }
@@ -298,14 +312,14 @@ where
                 // progress.
                 // TODO: We can consider introducing the TrieProgress::Progress/Complete
                 // abstraction inside StorageRoot, but let's give it a try as-is for now.
+                let changed_storage_prefixes = if self.ignore_stored_trie {
+                    PrefixSetMut::all().freeze()
+                } else {
+                    self.changed_storage_prefixes.get(&hashed_address).cloned().unwrap_or_default()
+                };
                 let storage_root_calculator = StorageRoot::new_hashed(self.tx, hashed_address)
                     .with_hashed_cursor_factory(self.hashed_cursor_factory)
-                    .with_changed_prefixes(
-                        self.changed_storage_prefixes
-                            .get(&hashed_address)
-                            .cloned()
-                            .unwrap_or_default(),
-                    );
+                    .with_changed_prefixes(changed_storage_prefixes);
 
                 let storage_root = if retain_updates {
                     let (root, storage_slots_walked, updates) =
@@ -812,6 +826,56 @@ mod tests {
         );
     }
 
+    #[test]
+    fn state_root_with_stored_trie_ignored() {
+        let db = create_test_rw_db();
+        let factory = ProviderFactory::new(db.as_ref(), MAINNET.clone());
+        let tx = factory.provider_rw().unwrap();
+
+        let mut state = State::default();
+        for i in 0..100u64 {
+            let account = Account { nonce: i, balance: U256::from(i), bytecode_hash: None };
+            let storage = BTreeMap::from([(H256::from_low_u64_be(i), U256::from(i + 1))]);
+            state.insert(Address::from_low_u64_be(i), (account, storage));
+        }
+        for (address, (account, storage)) in &state {
+            insert_account(tx.tx_ref(), *address, *account, storage);
+        }
+
+        let (root, updates) = StateRoot::new(tx.tx_ref()).root_with_updates().unwrap();
+        assert_eq!(root, state_root(state.clone().into_iter()));
+        updates.flush(tx.tx_ref()).unwrap();
+
+        // change an account and a storage slot without updating the stored trie
+        let address = Address::from_low_u64_be(7);
+        let account = Account { nonce: 8, balance: U256::from(1000), bytecode_hash: None };
+        tx.tx_ref().put::<tables::HashedAccount>(keccak256(address), account).unwrap();
+        state.get_mut(&address).unwrap().0 = account;
+
+        let address = Address::from_low_u64_be(9);
+        let slot = H256::from_low_u64_be(9);
+        tx.tx_ref()
+            .delete::<tables::HashedStorage>(
+                keccak256(address),
+                Some(StorageEntry { key: keccak256(slot), value: U256::from(10) }),
+            )
+            .unwrap();
+        tx.tx_ref()
+            .put::<tables::HashedStorage>(
+                keccak256(address),
+                StorageEntry { key: keccak256(slot), value: U256::from(1000) },
+            )
+            .unwrap();
+        state.get_mut(&address).unwrap().1.insert(slot, U256::from(1000));
+
+        // the stored trie still yields the previous root
+        assert_eq!(StateRoot::new(tx.tx_ref()).root().unwrap(), root);
+        assert_eq!(
+            StateRoot::new(tx.tx_ref()).with_stored_trie_ignored().root().unwrap(),
+            state_root(state.into_iter())
+        );
+    }
+
     fn test_state_root_with_state(state: State) {
         let db = create_test_rw_db();
         let factory = ProviderFactory::new(db.as_ref(), MAINNET.clone());